@@ -132,6 +132,8 @@ export interface SearchResponse {
   total: number
   query_time_ms: number
   suggestions: string[]
+  capped?: boolean
+  total_is_estimate?: boolean
 }
 
 export interface FacetItem {
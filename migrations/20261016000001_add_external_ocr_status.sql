@@ -0,0 +1,9 @@
+-- Allow documents whose text was extracted by an external system.
+-- 'external' documents are created through the bulk ingest API with their
+-- text already populated and never enter the OCR queue.
+ALTER TABLE documents DROP CONSTRAINT IF EXISTS check_ocr_status;
+
+ALTER TABLE documents ADD CONSTRAINT check_ocr_status
+CHECK (ocr_status IN ('pending', 'processing', 'completed', 'failed', 'external'));
+
+COMMENT ON CONSTRAINT check_ocr_status ON documents IS 'Ensures OCR status is one of the valid values';
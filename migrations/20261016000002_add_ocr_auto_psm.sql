@@ -0,0 +1,8 @@
+-- Add automatic page segmentation mode selection to OCR settings.
+-- When enabled, the PSM is chosen per image from its text density instead of
+-- using the fixed ocr_page_segmentation_mode.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS ocr_auto_psm BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN settings.ocr_auto_psm IS
+'Pick the Tesseract page segmentation mode from image text density (default: false)';
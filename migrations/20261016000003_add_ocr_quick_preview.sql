@@ -0,0 +1,20 @@
+-- Optional quick OCR preview for large PDFs.
+-- When ocr_quick_preview_pages > 0, the OCR queue first produces a fast, low-DPI
+-- OCR of the first N pages and stores it with ocr_status = 'preview' before
+-- running full-quality OCR, which replaces the preview text.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS ocr_quick_preview_pages INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE settings DROP CONSTRAINT IF EXISTS check_ocr_quick_preview_pages;
+ALTER TABLE settings ADD CONSTRAINT check_ocr_quick_preview_pages
+CHECK (ocr_quick_preview_pages >= 0 AND ocr_quick_preview_pages <= 50);
+
+COMMENT ON COLUMN settings.ocr_quick_preview_pages IS
+'Number of leading PDF pages to OCR for a quick preview before full processing (0 disables)';
+
+ALTER TABLE documents DROP CONSTRAINT IF EXISTS check_ocr_status;
+
+ALTER TABLE documents ADD CONSTRAINT check_ocr_status
+CHECK (ocr_status IN ('pending', 'processing', 'preview', 'completed', 'failed', 'external'));
+
+COMMENT ON CONSTRAINT check_ocr_status ON documents IS 'Ensures OCR status is one of the valid values';
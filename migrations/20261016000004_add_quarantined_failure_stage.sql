@@ -0,0 +1,7 @@
+-- Files flagged by the malware scanner are never stored as documents; they are
+-- recorded in failed_documents with failure_reason 'virus_detected' and the
+-- 'quarantined' stage.
+ALTER TABLE failed_documents DROP CONSTRAINT IF EXISTS check_failure_stage;
+
+ALTER TABLE failed_documents ADD CONSTRAINT check_failure_stage
+CHECK (failure_stage IN ('ingestion', 'validation', 'ocr', 'storage', 'processing', 'sync', 'quarantined'));
@@ -0,0 +1,10 @@
+-- Manual OCR text corrections.
+-- ocr_text holds the corrected text so search picks it up through the existing
+-- expression indexes; the OCR engine's output is kept in ocr_original_text.
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS ocr_manually_corrected BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS ocr_original_text TEXT,
+    ADD COLUMN IF NOT EXISTS ocr_corrected_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN documents.ocr_manually_corrected IS 'OCR text was corrected by a user; automatic reprocessing skips the document unless forced';
+COMMENT ON COLUMN documents.ocr_original_text IS 'OCR output before the first manual correction';
@@ -0,0 +1,33 @@
+-- Notification digest mode.
+-- With notification_delivery_mode = 'digest', non-error notifications are held in
+-- pending_notifications and coalesced per (user, notification_type) into one
+-- summary notification once the user's digest interval has passed. Errors are
+-- always delivered immediately.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS notification_delivery_mode TEXT NOT NULL DEFAULT 'immediate',
+ADD COLUMN IF NOT EXISTS notification_digest_interval_minutes INTEGER NOT NULL DEFAULT 15;
+
+ALTER TABLE settings DROP CONSTRAINT IF EXISTS check_notification_delivery_mode;
+ALTER TABLE settings ADD CONSTRAINT check_notification_delivery_mode
+CHECK (notification_delivery_mode IN ('immediate', 'digest'));
+
+ALTER TABLE settings DROP CONSTRAINT IF EXISTS check_notification_digest_interval_minutes;
+ALTER TABLE settings ADD CONSTRAINT check_notification_digest_interval_minutes
+CHECK (notification_digest_interval_minutes >= 1 AND notification_digest_interval_minutes <= 1440);
+
+CREATE TABLE IF NOT EXISTS pending_notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    notification_type VARCHAR(50) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    message TEXT NOT NULL,
+    action_url VARCHAR(500),
+    metadata JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_notifications_user_type
+ON pending_notifications(user_id, notification_type, created_at);
+
+COMMENT ON TABLE pending_notifications IS
+'Notifications held for a digest; flushed into a single summary notification per user and type';
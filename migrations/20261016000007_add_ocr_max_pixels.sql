@@ -0,0 +1,11 @@
+-- Upper bound on image size for OCR.
+-- Images with more pixels are downscaled (keeping their aspect ratio) before
+-- OCR so a single huge scan cannot exhaust the OCR runtime's memory.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS ocr_max_pixels BIGINT NOT NULL DEFAULT 40000000;
+
+ALTER TABLE settings DROP CONSTRAINT IF EXISTS check_ocr_max_pixels;
+ALTER TABLE settings ADD CONSTRAINT check_ocr_max_pixels
+CHECK (ocr_max_pixels >= 1000000);
+
+COMMENT ON COLUMN settings.ocr_max_pixels IS 'Images larger than this many pixels are downscaled before OCR';
@@ -0,0 +1,12 @@
+-- Hybrid PDF extraction.
+-- With ocr_pdf_hybrid enabled, PDFs that have both a text layer and embedded
+-- images keep the text layer and get text found only in the images (scanned
+-- figures, screenshots) appended from OCR.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS ocr_pdf_hybrid BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS ocr_hybrid_extraction BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.ocr_pdf_hybrid IS 'Combine the PDF text layer with OCR of embedded images';
+COMMENT ON COLUMN documents.ocr_hybrid_extraction IS 'OCR text was built from the PDF text layer plus OCR of embedded images';
@@ -0,0 +1,17 @@
+-- Sharing documents with other users.
+-- A row grants shared_with_user_id read access to a document owned by
+-- shared_by; search can then include documents shared with the caller.
+CREATE TABLE IF NOT EXISTS document_shares (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    shared_with_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    shared_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_document_share UNIQUE (document_id, shared_with_user_id)
+);
+
+-- Search filters by recipient, so look shares up by user first
+CREATE INDEX IF NOT EXISTS idx_document_shares_shared_with
+ON document_shares(shared_with_user_id, document_id);
+
+COMMENT ON TABLE document_shares IS 'Documents shared read-only with other users';
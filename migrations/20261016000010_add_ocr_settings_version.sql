@@ -0,0 +1,18 @@
+-- Reprocessing documents after OCR settings change.
+-- settings.ocr_settings_version is bumped whenever OCR-relevant settings
+-- change; each document records the version it was processed under, so
+-- documents processed under older settings can be counted and requeued.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS ocr_reprocess_on_settings_change BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN IF NOT EXISTS ocr_settings_version INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS ocr_settings_version INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_documents_user_ocr_settings_version
+ON documents(user_id, ocr_settings_version)
+WHERE ocr_manually_corrected = FALSE;
+
+COMMENT ON COLUMN settings.ocr_reprocess_on_settings_change IS 'Queue affected documents for OCR when OCR-relevant settings change';
+COMMENT ON COLUMN settings.ocr_settings_version IS 'Incremented whenever OCR-relevant settings change';
+COMMENT ON COLUMN documents.ocr_settings_version IS 'Owner''s OCR settings version the document was last processed under; NULL if processed before tracking';
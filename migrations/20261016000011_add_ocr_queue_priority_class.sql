@@ -0,0 +1,16 @@
+-- Scheduling classes for OCR queue items.
+-- Workers drain higher classes first (interactive uploads before normal work
+-- before bulk backfill); the existing priority column only orders items
+-- within a class.
+ALTER TABLE ocr_queue
+ADD COLUMN IF NOT EXISTS priority_class SMALLINT NOT NULL DEFAULT 1;
+
+ALTER TABLE ocr_queue DROP CONSTRAINT IF EXISTS check_ocr_queue_priority_class;
+ALTER TABLE ocr_queue
+ADD CONSTRAINT check_ocr_queue_priority_class CHECK (priority_class BETWEEN 0 AND 2);
+
+CREATE INDEX IF NOT EXISTS idx_ocr_queue_pending_priority_class
+ON ocr_queue(priority_class DESC, priority DESC, created_at)
+WHERE status = 'pending';
+
+COMMENT ON COLUMN ocr_queue.priority_class IS 'Scheduling class: 0 = backfill, 1 = normal, 2 = interactive; higher classes are processed first';
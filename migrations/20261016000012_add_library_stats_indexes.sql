@@ -0,0 +1,11 @@
+-- Indexes backing the library statistics endpoint.
+-- Each breakdown groups a user's documents by one column; leading with user_id
+-- lets Postgres answer per-user aggregates from the index instead of scanning
+-- every document in the table.
+CREATE INDEX IF NOT EXISTS idx_documents_user_mime_type ON documents(user_id, mime_type);
+CREATE INDEX IF NOT EXISTS idx_documents_user_ocr_status ON documents(user_id, ocr_status);
+CREATE INDEX IF NOT EXISTS idx_documents_user_source_id ON documents(user_id, source_id);
+CREATE INDEX IF NOT EXISTS idx_documents_user_created_at ON documents(user_id, created_at);
+
+-- Admins see the whole library, where only the ingestion window is selective
+CREATE INDEX IF NOT EXISTS idx_documents_created_at ON documents(created_at);
@@ -0,0 +1,10 @@
+-- Allow documents that were deliberately stored without text extraction.
+-- Sources can set "processing_mode" in their config to 'text_only' or 'none';
+-- the OCR queue marks their documents 'skipped' when they have no text layer
+-- or text extraction is disabled entirely.
+ALTER TABLE documents DROP CONSTRAINT IF EXISTS check_ocr_status;
+
+ALTER TABLE documents ADD CONSTRAINT check_ocr_status
+CHECK (ocr_status IN ('pending', 'processing', 'preview', 'completed', 'failed', 'external', 'skipped'));
+
+COMMENT ON CONSTRAINT check_ocr_status ON documents IS 'Ensures OCR status is one of the valid values';
@@ -0,0 +1,7 @@
+-- Automatic language labels.
+-- When enabled, documents get a system label such as 'lang:de' once OCR
+-- completes, based on the language detected in the extracted text.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS auto_label_language BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.auto_label_language IS 'Label documents with their detected language (lang:xx system labels) after OCR';
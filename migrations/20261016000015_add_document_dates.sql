@@ -0,0 +1,34 @@
+-- Document dates.
+-- documents.document_date is the date the document was written, taken from
+-- its text (invoice date, letter date), its file or its ingestion, in the
+-- order the user prefers. document_date_source records which one was used.
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS document_date DATE,
+ADD COLUMN IF NOT EXISTS document_date_source TEXT;
+
+ALTER TABLE documents ADD CONSTRAINT check_document_date_source
+CHECK (document_date_source IS NULL OR document_date_source IN ('content', 'file', 'ingestion'));
+
+CREATE INDEX IF NOT EXISTS idx_documents_user_document_date ON documents(user_id, document_date);
+
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS extract_document_date BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN IF NOT EXISTS document_date_priority TEXT NOT NULL DEFAULT 'content,file,ingestion',
+ADD COLUMN IF NOT EXISTS document_date_rule TEXT NOT NULL DEFAULT 'most_frequent',
+ADD COLUMN IF NOT EXISTS document_date_order TEXT NOT NULL DEFAULT 'dmy';
+
+ALTER TABLE settings ADD CONSTRAINT check_document_date_priority
+CHECK (document_date_priority ~ '^(content|file|ingestion)(,(content|file|ingestion))*$');
+
+ALTER TABLE settings ADD CONSTRAINT check_document_date_rule
+CHECK (document_date_rule IN ('most_frequent', 'first', 'earliest', 'latest'));
+
+ALTER TABLE settings ADD CONSTRAINT check_document_date_order
+CHECK (document_date_order IN ('dmy', 'mdy'));
+
+COMMENT ON COLUMN documents.document_date IS 'Date the document was written, from its content, file or ingestion';
+COMMENT ON COLUMN documents.document_date_source IS 'Where document_date came from: content, file or ingestion';
+COMMENT ON COLUMN settings.extract_document_date IS 'Set document dates after OCR, looking for dates in the extracted text';
+COMMENT ON COLUMN settings.document_date_priority IS 'Comma separated order of document date sources to try';
+COMMENT ON COLUMN settings.document_date_rule IS 'How to pick among several dates in the text: most_frequent, first, earliest or latest';
+COMMENT ON COLUMN settings.document_date_order IS 'Day/month order of numeric dates in the text: dmy or mdy';
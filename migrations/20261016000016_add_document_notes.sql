@@ -0,0 +1,23 @@
+-- Private notes users attach to documents, kept apart from the document text.
+-- Each note belongs to its author: on a shared document, the owner and the
+-- users it is shared with each see only their own notes.
+CREATE TABLE IF NOT EXISTS document_notes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    -- Weighted above document text (which has the default weight D), so a
+    -- match in the user's own notes ranks a document higher
+    search_vector TSVECTOR GENERATED ALWAYS AS (setweight(to_tsvector('english', content), 'B')) STORED,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_notes_document_user ON document_notes(document_id, user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_document_notes_user_id ON document_notes(user_id);
+CREATE INDEX IF NOT EXISTS idx_document_notes_search_vector ON document_notes USING GIN(search_vector);
+
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS search_include_notes BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.search_include_notes IS 'Match documents by the user''s own notes in full-text search unless a search says otherwise';
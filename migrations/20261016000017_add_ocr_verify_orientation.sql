@@ -0,0 +1,4 @@
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS ocr_verify_orientation BOOLEAN NOT NULL DEFAULT TRUE;
+
+COMMENT ON COLUMN settings.ocr_verify_orientation IS 'OCR a sample before and after orientation correction and keep the rotation only if it reads at least as well';
@@ -0,0 +1,17 @@
+-- One row per finished source sync, with the files that failed in it, so a
+-- partially failed sync can be assessed and its failed files retried.
+CREATE TABLE IF NOT EXISTS source_sync_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    source_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'partial', 'failed')),
+    retry_of_failed BOOLEAN NOT NULL DEFAULT FALSE,
+    files_discovered BIGINT NOT NULL DEFAULT 0,
+    files_processed BIGINT NOT NULL DEFAULT 0,
+    files_failed BIGINT NOT NULL DEFAULT 0,
+    -- Array of {path, stage, error_category, error_message, failed_at, ...}
+    failures JSONB NOT NULL DEFAULT '[]'::jsonb,
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_source_sync_runs_source_completed ON source_sync_runs(source_id, completed_at DESC);
@@ -0,0 +1,11 @@
+-- Documents flagged as handwritten are OCR'd by the handwriting engine when one
+-- is configured. The engine that produced the text is recorded, with a note when
+-- a handwritten document had to fall back to Tesseract.
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS ocr_handwriting BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN IF NOT EXISTS ocr_engine TEXT,
+ADD COLUMN IF NOT EXISTS ocr_engine_note TEXT;
+
+COMMENT ON COLUMN documents.ocr_handwriting IS 'Route OCR of this document to the handwriting engine';
+COMMENT ON COLUMN documents.ocr_engine IS 'OCR engine that produced ocr_text (tesseract or handwriting)';
+COMMENT ON COLUMN documents.ocr_engine_note IS 'Caveat about how ocr_text was produced, e.g. a handwriting fallback to Tesseract';
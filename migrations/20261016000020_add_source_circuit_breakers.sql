@@ -0,0 +1,11 @@
+-- Circuit breaker state of each source's scheduled syncs. Sources without a
+-- row are closed.
+CREATE TABLE IF NOT EXISTS source_circuit_breakers (
+    source_id UUID PRIMARY KEY REFERENCES sources(id) ON DELETE CASCADE,
+    state TEXT NOT NULL DEFAULT 'closed' CHECK (state IN ('closed', 'open', 'half_open')),
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    trips INTEGER NOT NULL DEFAULT 0,
+    opened_at TIMESTAMPTZ,
+    cooldown_until TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,22 @@
+-- Multi-document scans can be split at blank or barcode separator pages. Each
+-- part becomes a document of its own that points at the original, which is kept
+-- as an archived parent.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS auto_split_on_separator BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN IF NOT EXISTS auto_split_barcode TEXT;
+
+COMMENT ON COLUMN settings.auto_split_on_separator IS 'Split ingested PDFs into separate documents at separator pages';
+COMMENT ON COLUMN settings.auto_split_barcode IS 'Value of the separator barcode; blank pages are separators when unset';
+
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS split_parent_id UUID REFERENCES documents(id) ON DELETE SET NULL,
+ADD COLUMN IF NOT EXISTS split_first_page INTEGER,
+ADD COLUMN IF NOT EXISTS split_last_page INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_documents_split_parent_id ON documents(split_parent_id) WHERE split_parent_id IS NOT NULL;
+
+COMMENT ON COLUMN documents.archived_at IS 'Set when the document is kept only for reference, e.g. the original of a split scan; hidden from document lists';
+COMMENT ON COLUMN documents.split_parent_id IS 'Original scan this document was split from';
+COMMENT ON COLUMN documents.split_first_page IS 'First page of the original scan in this part (1-based)';
+COMMENT ON COLUMN documents.split_last_page IS 'Last page of the original scan in this part (1-based)';
@@ -0,0 +1,16 @@
+-- Version of each source file as last stored by a sync, so re-syncs can download
+-- it conditionally (If-None-Match) and skip it when unchanged. A version is only
+-- kept while its document exists, so deleting the document lets the file be
+-- imported again.
+CREATE TABLE IF NOT EXISTS source_file_versions (
+    source_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    file_path TEXT NOT NULL,
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    etag TEXT NOT NULL,
+    file_size BIGINT NOT NULL,
+    last_modified TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (source_id, file_path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_source_file_versions_document_id ON source_file_versions(document_id);
@@ -0,0 +1,15 @@
+-- Documents can carry an expiry date (passports, contracts, warranties). Owners
+-- are reminded a configurable number of days before a document expires.
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS expiry_date DATE,
+ADD COLUMN IF NOT EXISTS expiry_reminded_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_documents_expiry_date ON documents(expiry_date) WHERE expiry_date IS NOT NULL;
+
+COMMENT ON COLUMN documents.expiry_date IS 'Day the document expires, set by its owner';
+COMMENT ON COLUMN documents.expiry_reminded_at IS 'When the owner was reminded of the expiry; cleared when the expiry date changes';
+
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS expiry_reminder_days INTEGER NOT NULL DEFAULT 30;
+
+COMMENT ON COLUMN settings.expiry_reminder_days IS 'Days before a document expires that its owner is reminded; 0 disables reminders';
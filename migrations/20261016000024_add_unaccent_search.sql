@@ -0,0 +1,45 @@
+-- Accent-insensitive full-text search.
+-- Every supported language gets a text search configuration <language>_unaccent
+-- that strips accents before stemming, so "café" and "cafe" produce the same
+-- lexeme at index and query time. Lowercasing is already done by the stemmers.
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+DO $$
+DECLARE
+    lang TEXT;
+BEGIN
+    FOREACH lang IN ARRAY ARRAY['simple', 'english', 'german', 'french', 'spanish', 'italian', 'portuguese', 'dutch'] LOOP
+        IF NOT EXISTS (SELECT 1 FROM pg_ts_config WHERE cfgname = lang || '_unaccent') THEN
+            EXECUTE format('CREATE TEXT SEARCH CONFIGURATION %I (COPY = %I)', lang || '_unaccent', lang);
+            EXECUTE format(
+                'ALTER TEXT SEARCH CONFIGURATION %I ALTER MAPPING FOR hword, hword_part, word WITH unaccent, %I',
+                lang || '_unaccent',
+                CASE WHEN lang = 'simple' THEN 'simple' ELSE lang || '_stem' END
+            );
+        END IF;
+    END LOOP;
+END $$;
+
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS search_language TEXT NOT NULL DEFAULT 'english',
+ADD COLUMN IF NOT EXISTS search_ignore_accents BOOLEAN NOT NULL DEFAULT TRUE;
+
+ALTER TABLE settings DROP CONSTRAINT IF EXISTS check_search_language;
+ALTER TABLE settings ADD CONSTRAINT check_search_language
+CHECK (search_language IN ('simple', 'english', 'german', 'french', 'spanish', 'italian', 'portuguese', 'dutch'));
+
+COMMENT ON COLUMN settings.search_language IS 'Language whose stemming full-text search uses';
+COMMENT ON COLUMN settings.search_ignore_accents IS 'Match search terms regardless of accents unless a search says otherwise';
+
+-- Rebuild the document text index with the default configuration
+-- (english_unaccent); searches in other configurations are not indexed
+DROP INDEX IF EXISTS idx_documents_content_search;
+CREATE INDEX IF NOT EXISTS idx_documents_content_search_unaccent ON documents
+USING GIN(to_tsvector('english_unaccent', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')));
+
+-- Note vectors follow the same default configuration
+DROP INDEX IF EXISTS idx_document_notes_search_vector;
+ALTER TABLE document_notes DROP COLUMN IF EXISTS search_vector;
+ALTER TABLE document_notes
+ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (setweight(to_tsvector('english_unaccent', content), 'B')) STORED;
+CREATE INDEX IF NOT EXISTS idx_document_notes_search_vector ON document_notes USING GIN(search_vector);
@@ -0,0 +1,14 @@
+-- Sync history: what each finished sync did with the files it found and how
+-- much it downloaded. Runs older than SYNC_HISTORY_RETENTION_DAYS are pruned.
+ALTER TABLE source_sync_runs
+ADD COLUMN IF NOT EXISTS files_added BIGINT NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS files_updated BIGINT NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS files_skipped BIGINT NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS bytes_transferred BIGINT NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN source_sync_runs.files_added IS 'Files stored as new documents';
+COMMENT ON COLUMN source_sync_runs.files_updated IS 'Files stored again because they changed since the previous sync';
+COMMENT ON COLUMN source_sync_runs.files_skipped IS 'Files left alone as unchanged, duplicates or quarantined';
+COMMENT ON COLUMN source_sync_runs.bytes_transferred IS 'Bytes downloaded from the source';
+
+CREATE INDEX IF NOT EXISTS idx_source_sync_runs_source_started ON source_sync_runs(source_id, started_at DESC);
@@ -0,0 +1,16 @@
+-- Uploaded ZIP archives can be extracted into one document per contained file.
+-- Each file points at the archive it came from, which is kept as an archived
+-- parent.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS extract_archives BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.extract_archives IS 'Extract the files of ingested archives as separate documents';
+
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS archive_parent_id UUID REFERENCES documents(id) ON DELETE SET NULL,
+ADD COLUMN IF NOT EXISTS archive_entry_path TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_documents_archive_parent_id ON documents(archive_parent_id) WHERE archive_parent_id IS NOT NULL;
+
+COMMENT ON COLUMN documents.archive_parent_id IS 'Archive this document was extracted from';
+COMMENT ON COLUMN documents.archive_entry_path IS 'Path of the file inside the archive, through any nested archives';
@@ -0,0 +1,14 @@
+-- Ingested PDFs can be rewritten by an optimizer to save storage. The stored
+-- file is then the optimized one; the original size, and the original itself
+-- when the user keeps it, are recorded on the document.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS keep_original_pdf BOOLEAN NOT NULL DEFAULT TRUE;
+
+COMMENT ON COLUMN settings.keep_original_pdf IS 'Keep the original file when an ingested PDF is optimized';
+
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS original_file_size BIGINT,
+ADD COLUMN IF NOT EXISTS original_file_path TEXT;
+
+COMMENT ON COLUMN documents.original_file_size IS 'Size before PDF optimization; NULL when the stored file was not optimized';
+COMMENT ON COLUMN documents.original_file_path IS 'Storage path of the original kept next to an optimized PDF';
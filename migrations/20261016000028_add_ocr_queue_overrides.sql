@@ -0,0 +1,7 @@
+-- OCR jobs requested on demand for a single document. Such jobs run the full
+-- OCR pipeline whatever the processing mode of the document's source, with
+-- the given settings overrides applied on top of the owner's settings.
+ALTER TABLE ocr_queue
+ADD COLUMN IF NOT EXISTS ocr_overrides JSONB;
+
+COMMENT ON COLUMN ocr_queue.ocr_overrides IS 'Settings overrides of an on-demand OCR job (NULL for regular jobs); set jobs ignore the source processing mode';
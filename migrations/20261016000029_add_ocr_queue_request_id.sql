@@ -0,0 +1,6 @@
+-- Id of the HTTP request that queued an OCR job, directly or through a manual
+-- sync it started, so the job's logs can be correlated with the request.
+ALTER TABLE ocr_queue
+ADD COLUMN IF NOT EXISTS request_id TEXT;
+
+COMMENT ON COLUMN ocr_queue.request_id IS 'x-request-id of the request that queued the job (NULL for jobs queued by background work)';
@@ -0,0 +1,6 @@
+-- Whether a failed sync run failed transiently (nothing stored, the source
+-- unreachable) and was retried by the scheduler, or permanently.
+ALTER TABLE source_sync_runs
+ADD COLUMN IF NOT EXISTS failure_kind TEXT CHECK (failure_kind IN ('transient', 'permanent'));
+
+COMMENT ON COLUMN source_sync_runs.failure_kind IS 'transient or permanent for failed runs, NULL otherwise';
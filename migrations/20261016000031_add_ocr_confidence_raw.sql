@@ -0,0 +1,7 @@
+-- OCR confidence as reported by Tesseract. ocr_confidence holds the value
+-- calibrated with the language's correction curve, which equals the raw value
+-- when no curves are configured.
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS ocr_confidence_raw REAL;
+
+COMMENT ON COLUMN documents.ocr_confidence_raw IS 'OCR confidence before calibration (NULL for documents OCRed before it was recorded)';
@@ -0,0 +1,3 @@
+-- OCR resolution for documents carrying the label, overriding the owner's OCR DPI setting
+ALTER TABLE labels ADD COLUMN IF NOT EXISTS ocr_dpi INTEGER
+    CHECK (ocr_dpi IS NULL OR ocr_dpi BETWEEN 72 AND 1200);
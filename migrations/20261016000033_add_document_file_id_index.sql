@@ -0,0 +1,5 @@
+-- Local folder sources with track_file_ids look up the document stored from a
+-- file by its file id (device and inode) to recognize renamed and moved files.
+CREATE INDEX IF NOT EXISTS idx_documents_source_file_id
+ON documents (source_id, (source_metadata->>'file_id'))
+WHERE source_metadata->>'file_id' IS NOT NULL;
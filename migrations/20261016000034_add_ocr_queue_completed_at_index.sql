@@ -0,0 +1,5 @@
+-- OCR statistics over time group finished jobs by when they finished; jobs
+-- that fail for good now record that time in completed_at as well
+CREATE INDEX IF NOT EXISTS idx_ocr_queue_finished_completed_at
+    ON ocr_queue(completed_at)
+    WHERE status IN ('completed', 'failed');
@@ -0,0 +1,8 @@
+-- Skew between each WebDAV source's clock and readur's, measured at the start
+-- of its last sync. Sources without a row haven't been measured.
+CREATE TABLE IF NOT EXISTS source_clock_skew (
+    source_id UUID PRIMARY KEY REFERENCES sources(id) ON DELETE CASCADE,
+    skew_seconds BIGINT NOT NULL,
+    exceeds_threshold BOOLEAN NOT NULL DEFAULT FALSE,
+    measured_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,7 @@
+-- Barcodes and QR codes found on a document can be decoded during OCR. Their
+-- values are appended to the OCR text, tagged with their symbology, and kept
+-- under "barcodes" in the document's source metadata.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS decode_barcodes BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.decode_barcodes IS 'Decode barcodes and QR codes into the searchable text of a document';
@@ -0,0 +1,13 @@
+-- Sources can be deleted with an undo window. A soft-deleted source is hidden
+-- and no longer synced; it is purged (optionally together with its documents)
+-- once purge_at has passed, unless it is restored first.
+ALTER TABLE sources
+ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS purge_at TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS purge_documents BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_sources_purge_at ON sources(purge_at) WHERE deleted_at IS NOT NULL;
+
+COMMENT ON COLUMN sources.deleted_at IS 'When the source was deleted; NULL for live sources';
+COMMENT ON COLUMN sources.purge_at IS 'When a deleted source is removed for good';
+COMMENT ON COLUMN sources.purge_documents IS 'Delete the source''s documents when it is purged instead of keeping them without a source';
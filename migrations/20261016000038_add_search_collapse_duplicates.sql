@@ -0,0 +1,8 @@
+-- Searches can show one result per file content: the most recent of the
+-- matching documents sharing a file hash, with the others listed as its
+-- duplicates. Duplicates come from documents shared with the user and, for
+-- admins, from other users' libraries.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS search_collapse_duplicates BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.search_collapse_duplicates IS 'Collapse search results with the same file hash unless a search says otherwise';
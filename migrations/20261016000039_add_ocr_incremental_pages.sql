@@ -0,0 +1,7 @@
+-- Files that changed on a source with versioning can be OCR'd page by page:
+-- only the pages whose renders differ from the previous version are OCR'd
+-- again, and the other pages keep the previous version's text.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS ocr_incremental_pages BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.ocr_incremental_pages IS 'Only OCR the changed pages of a new version of a synced PDF';
@@ -0,0 +1,7 @@
+-- Markdown and text files often start with a YAML front-matter block (note
+-- vault exports). When enabled, the block is kept as document metadata, its
+-- tags become labels, and only the text after it is indexed.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS parse_front_matter BOOLEAN NOT NULL DEFAULT TRUE;
+
+COMMENT ON COLUMN settings.parse_front_matter IS 'Parse YAML front-matter of markdown and text files into metadata and labels';
@@ -0,0 +1,10 @@
+-- Secrets in source configurations (WebDAV passwords, S3 secret keys) can be
+-- encrypted with a master key. The version of the master key each source's
+-- credentials are wrapped with is kept so rotating to a new key can find the
+-- sources still under an older one. NULL means the credentials are plain text.
+ALTER TABLE sources
+ADD COLUMN IF NOT EXISTS credentials_key_version INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_sources_credentials_key_version ON sources (credentials_key_version);
+
+COMMENT ON COLUMN sources.credentials_key_version IS 'Master key version the source''s credentials are encrypted with; NULL for plain text';
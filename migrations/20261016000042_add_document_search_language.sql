@@ -0,0 +1,28 @@
+-- Documents are indexed with the stemming of their own language, detected
+-- after OCR (or the configured default when detection is unavailable).
+-- Documents without a language are searched in the searcher's language only.
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS search_language TEXT;
+
+ALTER TABLE documents DROP CONSTRAINT IF EXISTS check_document_search_language;
+ALTER TABLE documents ADD CONSTRAINT check_document_search_language
+CHECK (search_language IS NULL OR search_language IN ('simple', 'english', 'german', 'french', 'spanish', 'italian', 'portuguese', 'dutch'));
+
+COMMENT ON COLUMN documents.search_language IS 'Language whose stemming the document text is searched with';
+
+-- Text search configuration of a document language. The language is always
+-- one of the configurations created by the add_unaccent_search migration, so
+-- the result only depends on the arguments and the function can be indexed.
+CREATE OR REPLACE FUNCTION document_search_config(language TEXT, ignore_accents BOOLEAN)
+RETURNS regconfig
+LANGUAGE sql IMMUTABLE
+AS $$
+    SELECT CASE
+        WHEN language IS NULL THEN NULL
+        WHEN ignore_accents THEN (language || '_unaccent')::regconfig
+        ELSE language::regconfig
+    END
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_documents_content_search_language ON documents
+USING GIN(to_tsvector(document_search_config(search_language, TRUE), COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')));
@@ -0,0 +1,13 @@
+-- Size, in pixels, of the thumbnail cached for each document. It versions the
+-- thumbnail: after THUMBNAIL_SIZE changes, documents whose thumbnail was made
+-- at another size are regenerated, and those already done are skipped when an
+-- interrupted regeneration resumes. NULL means no thumbnail was made yet.
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS thumbnail_size INTEGER;
+
+-- Thumbnails so far were always 200 pixels
+UPDATE documents SET thumbnail_size = 200 WHERE thumbnail_size IS NULL;
+
+COMMENT ON COLUMN documents.thumbnail_size IS 'Longest side in pixels of the cached thumbnail, NULL if none was generated';
+
+CREATE INDEX IF NOT EXISTS idx_documents_thumbnail_size ON documents(thumbnail_size);
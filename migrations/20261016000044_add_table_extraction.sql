@@ -0,0 +1,12 @@
+-- Tables detected in PDFs by the layout of their text, kept as JSON so they
+-- can be returned structured or exported as CSV. Extraction runs a second
+-- pdftotext pass, so it is opt-in per user.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS extract_tables BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN settings.extract_tables IS 'Extract tables from PDFs into structured data after OCR';
+
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS extracted_tables JSONB;
+
+COMMENT ON COLUMN documents.extracted_tables IS 'Tables extracted from the document as [{page, rows}], NULL if extraction did not run';
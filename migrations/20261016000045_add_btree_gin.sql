@@ -0,0 +1,5 @@
+-- btree_gin lets a GIN index start with user_id, so the per-user search
+-- indexes (SEARCH_INDEX_STRATEGY=per_user) narrow a search to its user inside
+-- the index. Those indexes are built concurrently at startup rather than here,
+-- so upgrading doesn't lock a large documents table while they are built.
+CREATE EXTENSION IF NOT EXISTS btree_gin;
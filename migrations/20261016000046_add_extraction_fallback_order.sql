@@ -0,0 +1,17 @@
+-- Per-user order of the text extraction methods tried for legacy Word files
+-- and PDFs, comma-separated. NULL uses the server order set by
+-- OFFICE_DOC_TOOL_ORDER, PDF_EXTRACTION_ORDER and EXTRACTION_AUTO_ORDER.
+ALTER TABLE settings
+ADD COLUMN IF NOT EXISTS doc_extraction_order TEXT,
+ADD COLUMN IF NOT EXISTS pdf_extraction_order TEXT,
+ADD COLUMN IF NOT EXISTS extraction_auto_order BOOLEAN;
+
+ALTER TABLE settings ADD CONSTRAINT check_doc_extraction_order
+CHECK (doc_extraction_order ~ '^(antiword|catdoc|wvText)(,(antiword|catdoc|wvText))*$');
+
+ALTER TABLE settings ADD CONSTRAINT check_pdf_extraction_order
+CHECK (pdf_extraction_order ~ '^(pdftotext|pdftoppm|ocrmypdf)(,(pdftotext|pdftoppm|ocrmypdf))*$');
+
+COMMENT ON COLUMN settings.doc_extraction_order IS 'Comma separated order of the legacy Word tools to try, NULL for the server order';
+COMMENT ON COLUMN settings.pdf_extraction_order IS 'Comma separated order of the PDF extraction methods to try, NULL for the server order';
+COMMENT ON COLUMN settings.extraction_auto_order IS 'Try the most successful extraction methods first, NULL for the server setting';
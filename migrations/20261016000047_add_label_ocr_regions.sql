@@ -0,0 +1,22 @@
+-- Named regions read from every document with a label, so a label can stand
+-- for a document type such as "Invoice" whose total is always in the same
+-- place. Coordinates are relative to the page, from 0 to 1.
+CREATE TABLE IF NOT EXISTS label_ocr_regions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    label_id UUID NOT NULL REFERENCES labels(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    page INTEGER NOT NULL DEFAULT 1 CHECK (page >= 1),
+    x DOUBLE PRECISION NOT NULL,
+    y DOUBLE PRECISION NOT NULL,
+    width DOUBLE PRECISION NOT NULL,
+    height DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (label_id, name)
+);
+
+COMMENT ON TABLE label_ocr_regions IS 'Named regions OCR''d from the documents with a label';
+
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS extracted_fields JSONB;
+
+COMMENT ON COLUMN documents.extracted_fields IS 'Text of the named regions of the document''s labels as [{name, label, page, text, confidence}], NULL if none were read';
@@ -0,0 +1,85 @@
+/*!
+ * Request Body Size Limit
+ *
+ * Rejects requests whose declared size exceeds `MAX_UPLOAD_BYTES` before any of
+ * the body is read, with a structured 413 instead of a dropped connection.
+ * Bodies without a declared size (chunked transfer encoding) are capped while
+ * they are read by axum's `DefaultBodyLimit`, applied alongside this middleware.
+ */
+
+use axum::{
+    extract::{Request, State},
+    http::{header::CONTENT_LENGTH, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum::body::HttpBody as _;
+use serde_json::json;
+
+/// Error code returned with every 413 caused by the request body size limit
+pub const REQUEST_TOO_LARGE_CODE: &str = "REQUEST_TOO_LARGE";
+
+/// Middleware rejecting requests that declare a body larger than `max_bytes`
+pub async fn reject_oversized_body(
+    State(max_bytes): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match declared_body_size(&request) {
+        Some(size) if size > max_bytes as u64 => {
+            tracing::warn!(
+                "Rejecting {} {}: body of {} bytes exceeds the {} byte limit",
+                request.method(),
+                request.uri().path(),
+                size,
+                max_bytes
+            );
+            payload_too_large(max_bytes, Some(size))
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Body size from the `Content-Length` header, or from the body itself when its size is known
+fn declared_body_size(request: &Request) -> Option<u64> {
+    request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .or_else(|| request.body().size_hint().exact())
+}
+
+/// Human readable message for a body over the limit
+pub fn limit_exceeded_message(max_bytes: usize, size: Option<u64>) -> String {
+    let limit_mb = max_bytes as f64 / (1024.0 * 1024.0);
+    match size {
+        Some(size) => format!(
+            "Request body of {} bytes exceeds the maximum upload size of {} bytes ({:.1}MB). \
+             Split the upload into smaller files or ask an administrator to raise MAX_UPLOAD_BYTES.",
+            size, max_bytes, limit_mb
+        ),
+        None => format!(
+            "Request body exceeds the maximum upload size of {} bytes ({:.1}MB). \
+             Split the upload into smaller files or ask an administrator to raise MAX_UPLOAD_BYTES.",
+            max_bytes, limit_mb
+        ),
+    }
+}
+
+/// Structured 413 response for a body over the limit
+pub fn payload_too_large(max_bytes: usize, size: Option<u64>) -> Response {
+    let status = StatusCode::PAYLOAD_TOO_LARGE;
+    (
+        status,
+        Json(json!({
+            "error": limit_exceeded_message(max_bytes, size),
+            "status": status.as_u16(),
+            "error_code": REQUEST_TOO_LARGE_CODE,
+            "max_bytes": max_bytes,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        })),
+    )
+        .into_response()
+}
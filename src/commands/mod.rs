@@ -1,3 +1,5 @@
 pub mod reset_admin;
+pub mod search_index;
 
 pub use reset_admin::reset_admin_password;
+pub use search_index::search_index_health;
@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+
+use crate::db::search_index::SearchIndexStrategy;
+use crate::db::Database;
+
+/// Report on the full-text indexes of the documents table
+///
+/// Prints the table's row and analyze statistics, the size, scans and validity
+/// of each full-text index, and the plan of a sample search in the largest
+/// user's documents, followed by advice for the problems found. Nothing is
+/// changed; the advice names the statements or settings that fix each problem.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `strategy` - The configured `SEARCH_INDEX_STRATEGY`
+pub async fn search_index_health(db: &Database, strategy: SearchIndexStrategy) -> Result<()> {
+    let health = db
+        .search_index_health(strategy)
+        .await
+        .context("Failed to collect search index statistics")?;
+
+    println!();
+    println!("Strategy: {}", health.strategy);
+    println!(
+        "Documents: {} live rows, {} dead rows, {} users (largest has {} documents)",
+        health.live_rows, health.dead_rows, health.users, health.largest_user_documents
+    );
+    match health.last_analyzed {
+        Some(analyzed) => println!("Last analyzed: {}", analyzed.format("%Y-%m-%d %H:%M:%S UTC")),
+        None => println!("Last analyzed: never"),
+    }
+
+    println!();
+    println!("Full-text indexes:");
+    for index in &health.indexes {
+        println!(
+            "  {} - {} MB, {} scans{}",
+            index.name,
+            index.size_bytes / (1024 * 1024),
+            index.scans,
+            if index.valid { "" } else { ", INVALID" }
+        );
+    }
+    for name in &health.missing_indexes {
+        println!("  {} - missing", name);
+    }
+
+    if let Some(plan) = &health.sample_plan {
+        println!();
+        println!("Plan of a sample search in the largest user's documents (sequential scans discouraged):");
+        for line in plan {
+            println!("  {}", line);
+        }
+    }
+
+    println!();
+    let advice = health.advice(chrono::Utc::now());
+    if advice.is_empty() {
+        println!("✅ No problems found");
+    } else {
+        println!("Advice:");
+        for item in advice {
+            println!("  ⚠️  {}", item);
+        }
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+/*!
+ * Response Compression
+ *
+ * Compresses responses with gzip or brotli when the client accepts it in
+ * `Accept-Encoding`, for text responses (JSON, plain text, XML, HTML) of at
+ * least `RESPONSE_COMPRESSION_MIN_BYTES`. Document downloads, thumbnails and
+ * other binary files are passed through untouched: they are mostly compressed
+ * formats already, and partial (`Range`) responses must keep their byte
+ * offsets. Static files served precompressed carry a `Content-Encoding` and
+ * are never compressed again.
+ */
+
+use axum::body::HttpBody;
+use axum::http::{header, Response, StatusCode};
+use tower_http::compression::predicate::{And, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+
+use crate::config::Config;
+
+/// Compression layer applied to the API router
+pub type ResponseCompressionLayer = CompressionLayer<And<SizeAbove, TextResponse>>;
+
+/// Whether responses of `content_type` are text worth compressing
+pub fn is_compressible_content_type(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    match essence.as_str() {
+        // Streamed to the client as events arrive, so never buffered by a compressor
+        "text/event-stream" => false,
+        "application/json" | "application/xml" | "application/javascript" | "application/x-ndjson"
+        | "image/svg+xml" => true,
+        other => other.starts_with("text/") || other.ends_with("+json") || other.ends_with("+xml"),
+    }
+}
+
+/// Predicate matching complete text responses
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextResponse;
+
+impl Predicate for TextResponse {
+    fn should_compress<B>(&self, response: &Response<B>) -> bool
+    where
+        B: HttpBody,
+    {
+        if response.status() == StatusCode::PARTIAL_CONTENT || response.headers().contains_key(header::CONTENT_RANGE) {
+            return false;
+        }
+        response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(is_compressible_content_type)
+    }
+}
+
+/// Layer compressing text responses of at least `min_bytes`
+pub fn compression_layer(min_bytes: u16) -> ResponseCompressionLayer {
+    CompressionLayer::new()
+        .gzip(true)
+        .br(true)
+        .compress_when(SizeAbove::new(min_bytes).and(TextResponse))
+}
+
+/// The compression layer configured with `RESPONSE_COMPRESSION_*`, or none when
+/// compression is disabled
+pub fn configured_compression_layer(config: &Config) -> Option<ResponseCompressionLayer> {
+    config
+        .response_compression_enabled
+        .then(|| compression_layer(config.response_compression_min_bytes))
+}
@@ -1,7 +1,17 @@
 use anyhow::Result;
 use std::env;
 
+use crate::mime_detection::MimeOverrides;
+use crate::ingestion::pipeline::IngestionPipelineConfig;
 use crate::models::S3SourceConfig;
+use crate::ocr::queue::OcrFairnessPolicy;
+use crate::error_envelope::ApiErrorFormat;
+use crate::services::credential_encryption::CredentialKeyring;
+use crate::services::file_validator::FileValidationMode;
+use crate::services::pdf_optimizer::PdfOptimizerTool;
+use crate::services::webdav::host_limits::DEFAULT_MAX_DOWNLOADS_PER_HOST;
+use crate::storage::compression::StorageCompression;
+use crate::db::search_index::SearchIndexStrategy;
 
 /// S3 storage is enabled by S3_ENABLED=true or the documented STORAGE_BACKEND=s3.
 fn s3_storage_enabled(s3_enabled: Option<&str>, storage_backend: Option<&str>) -> bool {
@@ -15,6 +25,30 @@ fn parse_force_path_style(primary: Option<&str>, legacy: Option<&str>) -> Option
     primary.or(legacy).map(|v| v.trim().eq_ignore_ascii_case("true"))
 }
 
+/// Reads a stage size of the ingestion pipeline, which must be at least 1.
+fn ingestion_stage_size(name: &str, default: usize) -> usize {
+    match env::var(name) {
+        Ok(val) => match val.trim().parse::<usize>() {
+            Ok(parsed) if parsed > 0 => {
+                println!("✅ {}: {} (loaded from env)", name, parsed);
+                parsed
+            }
+            Ok(_) => {
+                println!("❌ {}: Must be at least 1, using default {}", name, default);
+                default
+            }
+            Err(e) => {
+                println!("❌ {}: Invalid value '{}' - {}, using default {}", name, val, e, default);
+                default
+            }
+        },
+        Err(_) => {
+            println!("⚠️  {}: {} (using default - env var not set)", name, default);
+            default
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub database_url: String,
@@ -25,17 +59,42 @@ pub struct Config {
     pub user_watch_base_dir: String,
     pub enable_per_user_watch: bool,
     pub allowed_file_types: Vec<String>,
+    pub mime_type_overrides: MimeOverrides,
     pub watch_interval_seconds: Option<u64>,
     pub file_stability_check_ms: Option<u64>,
     pub max_file_age_hours: Option<u64>,
     
     // OCR Configuration
     pub ocr_language: String,
+    pub ocr_auto_download_langs: bool,
+    pub ocr_text_strip_control_chars: bool,
+    pub ocr_text_normalize_unicode: bool,
     pub concurrent_ocr_jobs: usize,
     pub ocr_timeout_seconds: u64,
+    // Initialized Tesseract instances kept per OCR worker thread; 0 = no reuse
+    pub ocr_model_cache_size: usize,
+    // Whether users or sources take turns in the OCR queue, or strict FIFO
+    pub ocr_scheduling_policy: OcrFairnessPolicy,
+    // Pick smaller files first among queue items of equal class and priority
+    pub ocr_smaller_files_first: bool,
+    // Files at or above this size OCR in their own lane; 0 = no large-file lane
+    pub ocr_large_file_threshold_mb: u64,
+    // Concurrent OCR jobs in the large-file lane
+    pub ocr_large_file_concurrency: usize,
     pub max_file_size_mb: u64,
+    // Largest accepted request body; None = max_file_size_mb
+    pub max_upload_bytes: Option<u64>,
     pub max_pdf_size_mb: u64,
     pub max_office_document_size_mb: u64,
+    // Order of the legacy Word (.doc) tools and PDF extraction methods; auto order
+    // tries the most successful first. Users can override these in their settings
+    pub office_doc_tool_order: Vec<String>,
+    pub pdf_extraction_order: Vec<String>,
+    pub extraction_auto_order: bool,
+    // Archive extraction: nesting levels extracted, and limits that stop zip bombs
+    pub archive_max_depth: usize,
+    pub archive_max_total_size_mb: u64,
+    pub archive_max_entries: usize,
 
     // Performance
     pub memory_limit_mb: usize,
@@ -56,8 +115,133 @@ pub struct Config {
     pub s3_enabled: bool,
     pub s3_config: Option<S3SourceConfig>,
 
+    // Compression applied to stored documents (none/gzip/zstd)
+    pub storage_compression: StorageCompression,
+
+    // Confine file reads and deletes on behalf of a user to that user's storage namespace
+    pub storage_user_isolation: bool,
+
+    // WebDAV downloads larger than this are streamed to a temporary file (0 = always in memory)
+    pub webdav_stream_threshold_mb: u64,
+
+    // Directory listings reused between WebDAV syncs while the directory's ETag is unchanged
+    pub webdav_discovery_cache_enabled: bool,
+    pub webdav_discovery_cache_max_entries: usize,
+    pub webdav_discovery_cache_ttl_minutes: u64,
+    pub webdav_discovery_cache_file: Option<String>,
+
+    // Clock skew with a WebDAV server that is warned about (0 = never), and how far
+    // before an incremental sync's cutoff a file's modification time may be
+    pub webdav_clock_skew_warning_seconds: u64,
+    pub webdav_clock_skew_tolerance_seconds: u64,
+
+    // Minutes a WebDAV server's checked capabilities are trusted before they are checked again
+    pub webdav_capability_ttl_minutes: u64,
+
+    // Downloads in flight at once from one WebDAV server across all sources (0 = no limit)
+    pub webdav_max_downloads_per_host: usize,
+
+    // Concurrency of the source sync discovery/download/store stages
+    pub ingestion_pipeline: IngestionPipelineConfig,
+
+    // Share of failed files (0-1] at which a sync counts as failed rather than partial
+    pub sync_failure_ratio_threshold: f64,
+
+    // Days a source's sync runs are kept; 0 keeps them forever
+    pub sync_history_retention_days: u32,
+
+    // Retries of a scheduled sync that failed because the source was unreachable
+    pub sync_transient_retries: u32,
+    pub sync_transient_retry_delay_seconds: u64,
+
+    // Source deletion: require the preview's confirmation token, and keep deleted
+    // sources restorable for this many minutes (0 deletes immediately)
+    pub source_delete_require_confirmation: bool,
+    pub source_delete_grace_period_minutes: u64,
+
+    // Malware scanning of ingested files
+    pub malware_scan_enabled: bool,
+    pub malware_scan_endpoint: Option<String>,
+    pub malware_scan_command: Option<String>,
+    pub malware_scan_fail_open: bool,
+    pub malware_scan_timeout_seconds: u64,
+
+    // Integrity checks of ingested files (off/lenient/strict); corrupt files are not stored
+    pub file_validation_mode: FileValidationMode,
+
+    // Optimization of ingested PDFs (none/ghostscript/ocrmypdf); smaller PDFs are stored as-is
+    pub pdf_optimizer: PdfOptimizerTool,
+    pub pdf_optimize_min_size_kb: u64,
+    pub pdf_optimize_timeout_seconds: u64,
+
+    // External OCR service for documents flagged as handwritten
+    pub handwriting_ocr_endpoint: Option<String>,
+    pub handwriting_ocr_api_key: Option<String>,
+    pub handwriting_ocr_timeout_seconds: u64,
+
+    // JSON file of per-language OCR confidence correction curves
+    pub ocr_confidence_calibration_file: Option<String>,
+
+    // Age after which leftover temporary OCR files are removed (0 disables)
+    pub temp_file_max_age_hours: u64,
+
+    // Pagination limits for list endpoints
+    pub pagination_default_per_page: i64,
+    pub pagination_max_per_page: i64,
+    pub pagination_clamp_out_of_range: bool,
+
+    // Most search results that can be paged through
+    pub search_max_results: i64,
+
+    // Layout of the full-text indexes: shared by all users, or also led by
+    // user_id so searches narrow to their user inside the index
+    pub search_index_strategy: SearchIndexStrategy,
+
+    // Gzip/brotli compression of text responses, above a size in bytes
+    pub response_compression_enabled: bool,
+    pub response_compression_min_bytes: u16,
+
     // Public URL for generating shared links
     pub public_url: Option<String>,
+
+    // Startup schema verification
+    pub schema_auto_repair: bool,
+
+    // pg_dump backup taken before pending migrations run
+    pub backup_before_migrate: bool,
+    pub migration_backup_dir: String,
+    pub migration_backup_include_data: bool,
+
+    // Admin-only endpoints for debugging sources, such as raw PROPFIND responses
+    pub source_debug_endpoints: bool,
+
+    // Admin-only endpoints for debugging OCR, such as the live log of a document's run
+    pub ocr_debug_endpoints: bool,
+
+    // Detect each document's language after OCR so it is searched with that
+    // language's stemming, and the language documents get when none is detected
+    pub detect_document_language: bool,
+    pub default_document_language: String,
+
+    // Shape of API error responses, and whether server errors keep their internals
+    pub api_error_format: ApiErrorFormat,
+    pub api_error_expose_details: bool,
+
+    // Master keys (`version:base64key`, comma separated) source credentials are
+    // encrypted with, from the env or CREDENTIALS_MASTER_KEYS_FILE; empty keeps
+    // them in plain text
+    pub credentials_master_keys: String,
+    // Re-encrypt credentials under an older master key at startup, and how many
+    // sources are re-encrypted per transaction
+    pub credentials_auto_rotate: bool,
+    pub credentials_rotation_batch_size: usize,
+
+    // Longest side of document thumbnails in pixels. When it changes, thumbnails
+    // made at the old size are regenerated in the background at startup if
+    // auto-regeneration is on, running this many at a time
+    pub thumbnail_size: u32,
+    pub thumbnail_auto_regenerate: bool,
+    pub thumbnail_regeneration_concurrency: usize,
 }
 
 impl Config {
@@ -270,6 +454,22 @@ impl Config {
                 println!("📄 Parsed file types: {:?}", types_vec);
                 types_vec
             },
+            mime_type_overrides: match env::var("MIME_TYPE_OVERRIDES") {
+                Ok(val) => match val.parse::<MimeOverrides>() {
+                    Ok(overrides) => {
+                        println!("✅ MIME_TYPE_OVERRIDES: {} override(s) (loaded from env)", overrides.len());
+                        overrides
+                    }
+                    Err(e) => {
+                        println!("❌ MIME_TYPE_OVERRIDES: {}", e);
+                        return Err(anyhow::anyhow!("Invalid MIME_TYPE_OVERRIDES: {}", e));
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  MIME_TYPE_OVERRIDES: none (using default - env var not set)");
+                    MimeOverrides::default()
+                }
+            },
             // Watcher Configuration
             watch_interval_seconds: {
                 match env::var("WATCH_INTERVAL_SECONDS") {
@@ -338,6 +538,39 @@ impl Config {
                     default_lang
                 }
             },
+            ocr_auto_download_langs: match env::var("OCR_AUTO_DOWNLOAD_LANGS") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ OCR_AUTO_DOWNLOAD_LANGS: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  OCR_AUTO_DOWNLOAD_LANGS: false (using default - env var not set)");
+                    false
+                }
+            },
+            ocr_text_strip_control_chars: match env::var("OCR_TEXT_STRIP_CONTROL_CHARS") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ OCR_TEXT_STRIP_CONTROL_CHARS: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  OCR_TEXT_STRIP_CONTROL_CHARS: true (using default - env var not set)");
+                    true
+                }
+            },
+            ocr_text_normalize_unicode: match env::var("OCR_TEXT_NORMALIZE_UNICODE") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ OCR_TEXT_NORMALIZE_UNICODE: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  OCR_TEXT_NORMALIZE_UNICODE: true (using default - env var not set)");
+                    true
+                }
+            },
             concurrent_ocr_jobs: {
                 match env::var("CONCURRENT_OCR_JOBS") {
                     Ok(val) => match val.parse::<usize>() {
@@ -378,6 +611,88 @@ impl Config {
                     }
                 }
             },
+            ocr_model_cache_size: {
+                let default_size = crate::ocr::model_cache::DEFAULT_MODEL_CACHE_SIZE;
+                match env::var("OCR_MODEL_CACHE_SIZE") {
+                    Ok(val) => match val.parse::<usize>() {
+                        Ok(parsed) => {
+                            println!("✅ OCR_MODEL_CACHE_SIZE: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            println!("❌ OCR_MODEL_CACHE_SIZE: Invalid value '{}' - {}, using default {}", val, e, default_size);
+                            default_size
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  OCR_MODEL_CACHE_SIZE: {} (using default - env var not set)", default_size);
+                        default_size
+                    }
+                }
+            },
+            ocr_scheduling_policy: match env::var("OCR_SCHEDULING_POLICY") {
+                Ok(val) => match val.parse::<OcrFairnessPolicy>() {
+                    Ok(policy) => {
+                        println!("✅ OCR_SCHEDULING_POLICY: {} (loaded from env)", policy);
+                        policy
+                    }
+                    Err(e) => {
+                        println!("⚠️  OCR_SCHEDULING_POLICY: {}, using default (fifo)", e);
+                        OcrFairnessPolicy::Fifo
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  OCR_SCHEDULING_POLICY: fifo (using default - env var not set)");
+                    OcrFairnessPolicy::Fifo
+                }
+            },
+            ocr_smaller_files_first: match env::var("OCR_SMALLER_FILES_FIRST") {
+                Ok(val) => {
+                    let enabled = val.trim().eq_ignore_ascii_case("true");
+                    println!("✅ OCR_SMALLER_FILES_FIRST: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  OCR_SMALLER_FILES_FIRST: false (using default - env var not set)");
+                    false
+                }
+            },
+            ocr_large_file_threshold_mb: match env::var("OCR_LARGE_FILE_THRESHOLD_MB") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ OCR_LARGE_FILE_THRESHOLD_MB: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ OCR_LARGE_FILE_THRESHOLD_MB: Invalid value '{}' - {}, using default 0", val, e);
+                        0
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  OCR_LARGE_FILE_THRESHOLD_MB: 0 (using default - env var not set)");
+                    0
+                }
+            },
+            ocr_large_file_concurrency: match env::var("OCR_LARGE_FILE_CONCURRENCY") {
+                Ok(val) => match val.trim().parse::<usize>() {
+                    Ok(parsed) if parsed > 0 => {
+                        println!("✅ OCR_LARGE_FILE_CONCURRENCY: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Ok(_) => {
+                        println!("❌ OCR_LARGE_FILE_CONCURRENCY: Must be at least 1, using default 1");
+                        1
+                    }
+                    Err(e) => {
+                        println!("❌ OCR_LARGE_FILE_CONCURRENCY: Invalid value '{}' - {}, using default 1", val, e);
+                        1
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  OCR_LARGE_FILE_CONCURRENCY: 1 (using default - env var not set)");
+                    1
+                }
+            },
             max_file_size_mb: {
                 match env::var("MAX_FILE_SIZE_MB") {
                     Ok(val) => match val.parse::<u64>() {
@@ -398,6 +713,19 @@ impl Config {
                     }
                 }
             },
+            max_upload_bytes: match env::var("MAX_UPLOAD_BYTES") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) if parsed > 0 => {
+                        println!("✅ MAX_UPLOAD_BYTES: {} (loaded from env)", parsed);
+                        Some(parsed)
+                    }
+                    _ => {
+                        println!("❌ MAX_UPLOAD_BYTES: Invalid value '{}', using MAX_FILE_SIZE_MB", val);
+                        None
+                    }
+                },
+                Err(_) => None,
+            },
             max_pdf_size_mb: {
                 match env::var("MAX_PDF_SIZE_MB") {
                     Ok(val) => match val.parse::<u64>() {
@@ -438,6 +766,115 @@ impl Config {
                     }
                 }
             },
+            office_doc_tool_order: {
+                let default_order = crate::ocr::fallback_order::FallbackConfig::default().doc_tool_order;
+                match env::var("OFFICE_DOC_TOOL_ORDER") {
+                    Ok(val) => match crate::ocr::fallback_order::FallbackStrategy::LegacyDoc.parse_order(&val) {
+                        Ok(order) => {
+                            println!("✅ OFFICE_DOC_TOOL_ORDER: {} (loaded from env)", order.join(","));
+                            order
+                        }
+                        Err(e) => {
+                            println!("❌ OFFICE_DOC_TOOL_ORDER: Invalid value '{}' - {}, using default {}", val, e, default_order.join(","));
+                            default_order
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  OFFICE_DOC_TOOL_ORDER: {} (using default - env var not set)", default_order.join(","));
+                        default_order
+                    }
+                }
+            },
+            pdf_extraction_order: {
+                let default_order = crate::ocr::fallback_order::FallbackConfig::default().pdf_method_order;
+                match env::var("PDF_EXTRACTION_ORDER") {
+                    Ok(val) => match crate::ocr::fallback_order::FallbackStrategy::Pdf.parse_order(&val) {
+                        Ok(order) => {
+                            println!("✅ PDF_EXTRACTION_ORDER: {} (loaded from env)", order.join(","));
+                            order
+                        }
+                        Err(e) => {
+                            println!("❌ PDF_EXTRACTION_ORDER: Invalid value '{}' - {}, using default {}", val, e, default_order.join(","));
+                            default_order
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  PDF_EXTRACTION_ORDER: {} (using default - env var not set)", default_order.join(","));
+                        default_order
+                    }
+                }
+            },
+            extraction_auto_order: match env::var("EXTRACTION_AUTO_ORDER") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ EXTRACTION_AUTO_ORDER: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  EXTRACTION_AUTO_ORDER: false (using default - env var not set)");
+                    false
+                }
+            },
+            archive_max_depth: match env::var("ARCHIVE_MAX_DEPTH") {
+                Ok(val) => match val.trim().parse::<usize>() {
+                    Ok(parsed) if parsed > 0 => {
+                        println!("✅ ARCHIVE_MAX_DEPTH: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Ok(_) => {
+                        println!("❌ ARCHIVE_MAX_DEPTH: Must be at least 1, using default 3");
+                        3
+                    }
+                    Err(e) => {
+                        println!("❌ ARCHIVE_MAX_DEPTH: Invalid value '{}' - {}, using default 3", val, e);
+                        3
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  ARCHIVE_MAX_DEPTH: 3 (using default - env var not set)");
+                    3
+                }
+            },
+            archive_max_total_size_mb: match env::var("ARCHIVE_MAX_TOTAL_SIZE_MB") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) if parsed > 0 => {
+                        println!("✅ ARCHIVE_MAX_TOTAL_SIZE_MB: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Ok(_) => {
+                        println!("❌ ARCHIVE_MAX_TOTAL_SIZE_MB: Must be at least 1, using default 1024");
+                        1024
+                    }
+                    Err(e) => {
+                        println!("❌ ARCHIVE_MAX_TOTAL_SIZE_MB: Invalid value '{}' - {}, using default 1024", val, e);
+                        1024
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  ARCHIVE_MAX_TOTAL_SIZE_MB: 1024 (using default - env var not set)");
+                    1024
+                }
+            },
+            archive_max_entries: match env::var("ARCHIVE_MAX_ENTRIES") {
+                Ok(val) => match val.trim().parse::<usize>() {
+                    Ok(parsed) if parsed > 0 => {
+                        println!("✅ ARCHIVE_MAX_ENTRIES: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Ok(_) => {
+                        println!("❌ ARCHIVE_MAX_ENTRIES: Must be at least 1, using default 1000");
+                        1000
+                    }
+                    Err(e) => {
+                        println!("❌ ARCHIVE_MAX_ENTRIES: Invalid value '{}' - {}, using default 1000", val, e);
+                        1000
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  ARCHIVE_MAX_ENTRIES: 1000 (using default - env var not set)");
+                    1000
+                }
+            },
 
             // Performance Configuration
             memory_limit_mb: {
@@ -606,6 +1043,43 @@ impl Config {
                     env::var("S3_FORCE_PATH_STYLE").ok().as_deref(),
                     env::var("S3_PATH_STYLE").ok().as_deref(),
                 );
+                let parse_s3_number = |name: &str, default: u64| -> u64 {
+                    match env::var(name) {
+                        Ok(val) => match val.parse::<u64>() {
+                            Ok(parsed) => {
+                                println!("✅ {}: {} (loaded from env)", name, parsed);
+                                parsed
+                            }
+                            Err(e) => {
+                                println!("❌ {}: Invalid value '{}' - {}, using default {}", name, val, e, default);
+                                default
+                            }
+                        },
+                        Err(_) => default,
+                    }
+                };
+                let connect_timeout_seconds = parse_s3_number(
+                    "S3_CONNECT_TIMEOUT_SECONDS",
+                    crate::models::DEFAULT_S3_CONNECT_TIMEOUT_SECONDS,
+                );
+                let read_timeout_seconds = parse_s3_number(
+                    "S3_READ_TIMEOUT_SECONDS",
+                    crate::models::DEFAULT_S3_READ_TIMEOUT_SECONDS,
+                );
+                let operation_timeout_seconds = parse_s3_number(
+                    "S3_OPERATION_TIMEOUT_SECONDS",
+                    crate::models::DEFAULT_S3_OPERATION_TIMEOUT_SECONDS,
+                );
+                let max_retries = parse_s3_number("S3_MAX_RETRIES", crate::models::DEFAULT_S3_MAX_RETRIES as u64)
+                    .min(u32::MAX as u64) as u32;
+                let create_bucket_if_missing = match env::var("S3_CREATE_BUCKET_IF_MISSING") {
+                    Ok(val) => {
+                        let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                        println!("✅ S3_CREATE_BUCKET_IF_MISSING: {} (loaded from env)", enabled);
+                        enabled
+                    }
+                    Err(_) => false,
+                };
 
                 if !bucket_name.is_empty() && !access_key_id.is_empty() && !secret_access_key.is_empty() {
                     println!("✅ S3_BUCKET_NAME: {} (loaded from env)", bucket_name);
@@ -634,6 +1108,16 @@ impl Config {
                         file_extensions: vec![], // Will be configured separately for sources
                         auto_sync: false, // Not used for general storage
                         sync_interval_minutes: 0, // Not used for general storage
+                        sync_newest_first: false,
+                        sync_on_startup: false,
+                        processing_mode: Default::default(),
+                        ocr_enabled: true,
+                        connect_timeout_seconds,
+                        read_timeout_seconds,
+                        operation_timeout_seconds,
+                        max_retries,
+                        create_bucket_if_missing,
+                        download_verification: Default::default(),
                     })
                 } else {
                     println!("❌ S3 enabled but missing required configuration (bucket_name, access_key_id, or secret_access_key)");
@@ -642,11 +1126,741 @@ impl Config {
             } else {
                 None
             },
+            storage_compression: match env::var("STORAGE_COMPRESSION") {
+                Ok(val) => match val.parse::<StorageCompression>() {
+                    Ok(compression) => {
+                        println!("✅ STORAGE_COMPRESSION: {} (loaded from env)", compression);
+                        compression
+                    }
+                    Err(e) => {
+                        println!("⚠️  STORAGE_COMPRESSION: {}, using default (none)", e);
+                        StorageCompression::None
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  STORAGE_COMPRESSION: none (using default - env var not set)");
+                    StorageCompression::None
+                }
+            },
+            storage_user_isolation: match env::var("STORAGE_USER_ISOLATION") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ STORAGE_USER_ISOLATION: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  STORAGE_USER_ISOLATION: true (using default - env var not set)");
+                    true
+                }
+            },
+            webdav_stream_threshold_mb: match env::var("WEBDAV_STREAM_THRESHOLD_MB") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ WEBDAV_STREAM_THRESHOLD_MB: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ WEBDAV_STREAM_THRESHOLD_MB: Invalid value '{}' - {}, using default 100", val, e);
+                        100
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  WEBDAV_STREAM_THRESHOLD_MB: 100 (using default - env var not set)");
+                    100
+                }
+            },
+            webdav_discovery_cache_enabled: match env::var("WEBDAV_DISCOVERY_CACHE_ENABLED") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ WEBDAV_DISCOVERY_CACHE_ENABLED: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  WEBDAV_DISCOVERY_CACHE_ENABLED: false (using default - env var not set)");
+                    false
+                }
+            },
+            webdav_discovery_cache_max_entries: match env::var("WEBDAV_DISCOVERY_CACHE_MAX_ENTRIES") {
+                Ok(val) => match val.trim().parse::<usize>() {
+                    Ok(parsed) => {
+                        println!("✅ WEBDAV_DISCOVERY_CACHE_MAX_ENTRIES: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ WEBDAV_DISCOVERY_CACHE_MAX_ENTRIES: Invalid value '{}' - {}, using default 10000", val, e);
+                        10000
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  WEBDAV_DISCOVERY_CACHE_MAX_ENTRIES: 10000 (using default - env var not set)");
+                    10000
+                }
+            },
+            webdav_discovery_cache_ttl_minutes: match env::var("WEBDAV_DISCOVERY_CACHE_TTL_MINUTES") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ WEBDAV_DISCOVERY_CACHE_TTL_MINUTES: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ WEBDAV_DISCOVERY_CACHE_TTL_MINUTES: Invalid value '{}' - {}, using default 1440", val, e);
+                        1440
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  WEBDAV_DISCOVERY_CACHE_TTL_MINUTES: 1440 (using default - env var not set)");
+                    1440
+                }
+            },
+            webdav_discovery_cache_file: env::var("WEBDAV_DISCOVERY_CACHE_FILE").ok().filter(|v| !v.trim().is_empty()).map(|path| {
+                println!("✅ WEBDAV_DISCOVERY_CACHE_FILE: {} (loaded from env)", path);
+                path
+            }),
+            webdav_clock_skew_warning_seconds: match env::var("WEBDAV_CLOCK_SKEW_WARNING_SECONDS") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ WEBDAV_CLOCK_SKEW_WARNING_SECONDS: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ WEBDAV_CLOCK_SKEW_WARNING_SECONDS: Invalid value '{}' - {}, using default 300", val, e);
+                        300
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  WEBDAV_CLOCK_SKEW_WARNING_SECONDS: 300 (using default - env var not set)");
+                    300
+                }
+            },
+            webdav_clock_skew_tolerance_seconds: match env::var("WEBDAV_CLOCK_SKEW_TOLERANCE_SECONDS") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ WEBDAV_CLOCK_SKEW_TOLERANCE_SECONDS: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ WEBDAV_CLOCK_SKEW_TOLERANCE_SECONDS: Invalid value '{}' - {}, using default 0", val, e);
+                        0
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  WEBDAV_CLOCK_SKEW_TOLERANCE_SECONDS: 0 (using default - env var not set)");
+                    0
+                }
+            },
+            webdav_capability_ttl_minutes: match env::var("WEBDAV_CAPABILITY_TTL_MINUTES") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ WEBDAV_CAPABILITY_TTL_MINUTES: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ WEBDAV_CAPABILITY_TTL_MINUTES: Invalid value '{}' - {}, using default 60", val, e);
+                        60
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  WEBDAV_CAPABILITY_TTL_MINUTES: 60 (using default - env var not set)");
+                    60
+                }
+            },
+            webdav_max_downloads_per_host: match env::var("WEBDAV_MAX_DOWNLOADS_PER_HOST") {
+                Ok(val) => match val.trim().parse::<usize>() {
+                    Ok(parsed) => {
+                        println!("✅ WEBDAV_MAX_DOWNLOADS_PER_HOST: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ WEBDAV_MAX_DOWNLOADS_PER_HOST: Invalid value '{}' - {}, using default {}",
+                            val, e, DEFAULT_MAX_DOWNLOADS_PER_HOST);
+                        DEFAULT_MAX_DOWNLOADS_PER_HOST
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  WEBDAV_MAX_DOWNLOADS_PER_HOST: {} (using default - env var not set)",
+                        DEFAULT_MAX_DOWNLOADS_PER_HOST);
+                    DEFAULT_MAX_DOWNLOADS_PER_HOST
+                }
+            },
+            ingestion_pipeline: {
+                let defaults = IngestionPipelineConfig::default();
+                IngestionPipelineConfig {
+                    download_concurrency: ingestion_stage_size("INGESTION_DOWNLOAD_CONCURRENCY", defaults.download_concurrency),
+                    store_concurrency: ingestion_stage_size("INGESTION_STORE_CONCURRENCY", defaults.store_concurrency),
+                    stage_queue_size: ingestion_stage_size("INGESTION_STAGE_QUEUE_SIZE", defaults.stage_queue_size),
+                }
+            },
+            sync_failure_ratio_threshold: match env::var("SYNC_FAILURE_RATIO_THRESHOLD") {
+                Ok(val) => match val.trim().parse::<f64>() {
+                    Ok(parsed) if parsed > 0.0 && parsed <= 1.0 => {
+                        println!("✅ SYNC_FAILURE_RATIO_THRESHOLD: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Ok(_) => {
+                        println!("❌ SYNC_FAILURE_RATIO_THRESHOLD: Must be above 0 and at most 1, using default 0.5");
+                        0.5
+                    }
+                    Err(e) => {
+                        println!("❌ SYNC_FAILURE_RATIO_THRESHOLD: Invalid value '{}' - {}, using default 0.5", val, e);
+                        0.5
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SYNC_FAILURE_RATIO_THRESHOLD: 0.5 (using default - env var not set)");
+                    0.5
+                }
+            },
+            sync_history_retention_days: match env::var("SYNC_HISTORY_RETENTION_DAYS") {
+                Ok(val) => match val.trim().parse::<u32>() {
+                    Ok(parsed) => {
+                        println!("✅ SYNC_HISTORY_RETENTION_DAYS: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ SYNC_HISTORY_RETENTION_DAYS: Invalid value '{}' - {}, using default 90", val, e);
+                        90
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SYNC_HISTORY_RETENTION_DAYS: 90 (using default - env var not set)");
+                    90
+                }
+            },
+            sync_transient_retries: match env::var("SYNC_TRANSIENT_RETRIES") {
+                Ok(val) => match val.trim().parse::<u32>() {
+                    Ok(parsed) => {
+                        println!("✅ SYNC_TRANSIENT_RETRIES: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ SYNC_TRANSIENT_RETRIES: Invalid value '{}' - {}, using default 2", val, e);
+                        2
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SYNC_TRANSIENT_RETRIES: 2 (using default - env var not set)");
+                    2
+                }
+            },
+            sync_transient_retry_delay_seconds: match env::var("SYNC_TRANSIENT_RETRY_DELAY_SECONDS") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ SYNC_TRANSIENT_RETRY_DELAY_SECONDS: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ SYNC_TRANSIENT_RETRY_DELAY_SECONDS: Invalid value '{}' - {}, using default 30", val, e);
+                        30
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SYNC_TRANSIENT_RETRY_DELAY_SECONDS: 30 (using default - env var not set)");
+                    30
+                }
+            },
+            source_delete_require_confirmation: match env::var("SOURCE_DELETE_REQUIRE_CONFIRMATION") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ SOURCE_DELETE_REQUIRE_CONFIRMATION: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  SOURCE_DELETE_REQUIRE_CONFIRMATION: false (using default - env var not set)");
+                    false
+                }
+            },
+            source_delete_grace_period_minutes: match env::var("SOURCE_DELETE_GRACE_PERIOD_MINUTES") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ SOURCE_DELETE_GRACE_PERIOD_MINUTES: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ SOURCE_DELETE_GRACE_PERIOD_MINUTES: Invalid value '{}' - {}, using default 0", val, e);
+                        0
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SOURCE_DELETE_GRACE_PERIOD_MINUTES: 0 (using default - env var not set)");
+                    0
+                }
+            },
+            malware_scan_enabled: match env::var("MALWARE_SCAN_ENABLED") {
+                Ok(val) => {
+                    let enabled = val.trim().eq_ignore_ascii_case("true");
+                    println!("✅ MALWARE_SCAN_ENABLED: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  MALWARE_SCAN_ENABLED: false (using default - env var not set)");
+                    false
+                }
+            },
+            malware_scan_endpoint: env::var("MALWARE_SCAN_ENDPOINT").ok().filter(|v| !v.trim().is_empty()).map(|endpoint| {
+                println!("✅ MALWARE_SCAN_ENDPOINT: {} (loaded from env)", endpoint);
+                endpoint
+            }),
+            malware_scan_command: env::var("MALWARE_SCAN_COMMAND").ok().filter(|v| !v.trim().is_empty()).map(|command| {
+                println!("✅ MALWARE_SCAN_COMMAND: {} (loaded from env)", command);
+                command
+            }),
+            malware_scan_fail_open: match env::var("MALWARE_SCAN_FAIL_OPEN") {
+                Ok(val) => {
+                    let fail_open = val.trim().eq_ignore_ascii_case("true");
+                    println!("✅ MALWARE_SCAN_FAIL_OPEN: {} (loaded from env)", fail_open);
+                    fail_open
+                }
+                Err(_) => {
+                    println!("⚠️  MALWARE_SCAN_FAIL_OPEN: false (using default - env var not set)");
+                    false
+                }
+            },
+            malware_scan_timeout_seconds: match env::var("MALWARE_SCAN_TIMEOUT_SECONDS") {
+                Ok(val) => match val.parse::<u64>() {
+                    Ok(seconds) if seconds > 0 => {
+                        println!("✅ MALWARE_SCAN_TIMEOUT_SECONDS: {} (loaded from env)", seconds);
+                        seconds
+                    }
+                    _ => {
+                        println!("❌ MALWARE_SCAN_TIMEOUT_SECONDS: Invalid value '{}', using default 60", val);
+                        60
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  MALWARE_SCAN_TIMEOUT_SECONDS: 60 (using default - env var not set)");
+                    60
+                }
+            },
+            file_validation_mode: match env::var("FILE_VALIDATION_MODE") {
+                Ok(val) => match val.parse::<FileValidationMode>() {
+                    Ok(mode) => {
+                        println!("✅ FILE_VALIDATION_MODE: {} (loaded from env)", mode);
+                        mode
+                    }
+                    Err(e) => {
+                        println!("⚠️  FILE_VALIDATION_MODE: {}, using default (lenient)", e);
+                        FileValidationMode::Lenient
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  FILE_VALIDATION_MODE: lenient (using default - env var not set)");
+                    FileValidationMode::Lenient
+                }
+            },
+            pdf_optimizer: match env::var("PDF_OPTIMIZER") {
+                Ok(val) => match val.parse::<PdfOptimizerTool>() {
+                    Ok(tool) => {
+                        println!("✅ PDF_OPTIMIZER: {} (loaded from env)", tool);
+                        tool
+                    }
+                    Err(e) => {
+                        println!("⚠️  PDF_OPTIMIZER: {}, using default (none)", e);
+                        PdfOptimizerTool::None
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  PDF_OPTIMIZER: none (using default - env var not set)");
+                    PdfOptimizerTool::None
+                }
+            },
+            pdf_optimize_min_size_kb: match env::var("PDF_OPTIMIZE_MIN_SIZE_KB") {
+                Ok(val) => match val.parse::<u64>() {
+                    Ok(size) => {
+                        println!("✅ PDF_OPTIMIZE_MIN_SIZE_KB: {} (loaded from env)", size);
+                        size
+                    }
+                    Err(_) => {
+                        println!("❌ PDF_OPTIMIZE_MIN_SIZE_KB: Invalid value '{}', using default 1024", val);
+                        1024
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  PDF_OPTIMIZE_MIN_SIZE_KB: 1024 (using default - env var not set)");
+                    1024
+                }
+            },
+            pdf_optimize_timeout_seconds: match env::var("PDF_OPTIMIZE_TIMEOUT_SECONDS") {
+                Ok(val) => match val.parse::<u64>() {
+                    Ok(seconds) if seconds > 0 => {
+                        println!("✅ PDF_OPTIMIZE_TIMEOUT_SECONDS: {} (loaded from env)", seconds);
+                        seconds
+                    }
+                    _ => {
+                        println!("❌ PDF_OPTIMIZE_TIMEOUT_SECONDS: Invalid value '{}', using default 300", val);
+                        300
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  PDF_OPTIMIZE_TIMEOUT_SECONDS: 300 (using default - env var not set)");
+                    300
+                }
+            },
+            handwriting_ocr_endpoint: env::var("HANDWRITING_OCR_ENDPOINT").ok().filter(|v| !v.trim().is_empty()).map(|endpoint| {
+                println!("✅ HANDWRITING_OCR_ENDPOINT: {} (loaded from env)", endpoint);
+                endpoint
+            }),
+            handwriting_ocr_api_key: env::var("HANDWRITING_OCR_API_KEY").ok().filter(|v| !v.trim().is_empty()).map(|api_key| {
+                println!("✅ HANDWRITING_OCR_API_KEY: ***hidden*** (loaded from env)");
+                api_key
+            }),
+            handwriting_ocr_timeout_seconds: match env::var("HANDWRITING_OCR_TIMEOUT_SECONDS") {
+                Ok(val) => match val.parse::<u64>() {
+                    Ok(seconds) if seconds > 0 => {
+                        println!("✅ HANDWRITING_OCR_TIMEOUT_SECONDS: {} (loaded from env)", seconds);
+                        seconds
+                    }
+                    _ => {
+                        println!("❌ HANDWRITING_OCR_TIMEOUT_SECONDS: Invalid value '{}', using default 120", val);
+                        120
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  HANDWRITING_OCR_TIMEOUT_SECONDS: 120 (using default - env var not set)");
+                    120
+                }
+            },
+            ocr_confidence_calibration_file: env::var("OCR_CONFIDENCE_CALIBRATION_FILE").ok().filter(|v| !v.trim().is_empty()).map(|path| {
+                println!("✅ OCR_CONFIDENCE_CALIBRATION_FILE: {} (loaded from env)", path);
+                path
+            }),
+            temp_file_max_age_hours: match env::var("TEMP_FILE_MAX_AGE_HOURS") {
+                Ok(val) => match val.trim().parse::<u64>() {
+                    Ok(parsed) => {
+                        println!("✅ TEMP_FILE_MAX_AGE_HOURS: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    Err(e) => {
+                        println!("❌ TEMP_FILE_MAX_AGE_HOURS: Invalid value '{}' - {}, using default 24", val, e);
+                        24
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  TEMP_FILE_MAX_AGE_HOURS: 24 (using default - env var not set)");
+                    24
+                }
+            },
+            pagination_max_per_page: match env::var("PAGINATION_MAX_PER_PAGE") {
+                Ok(val) => match val.parse::<i64>() {
+                    Ok(max) if max > 0 => {
+                        println!("✅ PAGINATION_MAX_PER_PAGE: {} (loaded from env)", max);
+                        max
+                    }
+                    _ => {
+                        println!("❌ PAGINATION_MAX_PER_PAGE: Invalid value '{}', using default 100", val);
+                        100
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  PAGINATION_MAX_PER_PAGE: 100 (using default - env var not set)");
+                    100
+                }
+            },
+            pagination_default_per_page: match env::var("PAGINATION_DEFAULT_PER_PAGE") {
+                Ok(val) => match val.parse::<i64>() {
+                    Ok(per_page) if per_page > 0 => {
+                        println!("✅ PAGINATION_DEFAULT_PER_PAGE: {} (loaded from env)", per_page);
+                        per_page
+                    }
+                    _ => {
+                        println!("❌ PAGINATION_DEFAULT_PER_PAGE: Invalid value '{}', using default 25", val);
+                        25
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  PAGINATION_DEFAULT_PER_PAGE: 25 (using default - env var not set)");
+                    25
+                }
+            },
+            pagination_clamp_out_of_range: match env::var("PAGINATION_CLAMP_OUT_OF_RANGE") {
+                Ok(val) => {
+                    let clamp = val.trim().eq_ignore_ascii_case("true");
+                    println!("✅ PAGINATION_CLAMP_OUT_OF_RANGE: {} (loaded from env)", clamp);
+                    clamp
+                }
+                Err(_) => {
+                    println!("⚠️  PAGINATION_CLAMP_OUT_OF_RANGE: false (using default - env var not set)");
+                    false
+                }
+            },
+            search_max_results: match env::var("SEARCH_MAX_RESULTS") {
+                Ok(val) => match val.trim().parse::<i64>() {
+                    Ok(max) if max > 0 => {
+                        println!("✅ SEARCH_MAX_RESULTS: {} (loaded from env)", max);
+                        max
+                    }
+                    _ => {
+                        println!("❌ SEARCH_MAX_RESULTS: Invalid value '{}', using default 10000", val);
+                        10000
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SEARCH_MAX_RESULTS: 10000 (using default - env var not set)");
+                    10000
+                }
+            },
+            search_index_strategy: match env::var("SEARCH_INDEX_STRATEGY") {
+                Ok(val) => match val.parse::<SearchIndexStrategy>() {
+                    Ok(strategy) => {
+                        println!("✅ SEARCH_INDEX_STRATEGY: {} (loaded from env)", strategy);
+                        strategy
+                    }
+                    Err(e) => {
+                        println!("⚠️  SEARCH_INDEX_STRATEGY: {}, using default (shared)", e);
+                        SearchIndexStrategy::Shared
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SEARCH_INDEX_STRATEGY: shared (using default - env var not set)");
+                    SearchIndexStrategy::Shared
+                }
+            },
+            response_compression_enabled: match env::var("RESPONSE_COMPRESSION_ENABLED") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ RESPONSE_COMPRESSION_ENABLED: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  RESPONSE_COMPRESSION_ENABLED: true (using default - env var not set)");
+                    true
+                }
+            },
+            response_compression_min_bytes: match env::var("RESPONSE_COMPRESSION_MIN_BYTES") {
+                Ok(val) => match val.trim().parse::<u16>() {
+                    Ok(min_bytes) => {
+                        println!("✅ RESPONSE_COMPRESSION_MIN_BYTES: {} (loaded from env)", min_bytes);
+                        min_bytes
+                    }
+                    Err(_) => {
+                        println!("❌ RESPONSE_COMPRESSION_MIN_BYTES: Invalid value '{}' (expected 0-65535), using default 1024", val);
+                        1024
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  RESPONSE_COMPRESSION_MIN_BYTES: 1024 (using default - env var not set)");
+                    1024
+                }
+            },
             public_url: env::var("PUBLIC_URL").ok().map(|url| {
                 let url = url.trim_end_matches('/').to_string();
                 println!("✅ PUBLIC_URL: {} (loaded from env)", url);
                 url
             }),
+            schema_auto_repair: match env::var("SCHEMA_AUTO_REPAIR") {
+                Ok(val) => {
+                    let repair = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ SCHEMA_AUTO_REPAIR: {} (loaded from env)", repair);
+                    repair
+                }
+                Err(_) => {
+                    println!("⚠️  SCHEMA_AUTO_REPAIR: false (using default - env var not set)");
+                    false
+                }
+            },
+            backup_before_migrate: match env::var("BACKUP_BEFORE_MIGRATE") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ BACKUP_BEFORE_MIGRATE: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  BACKUP_BEFORE_MIGRATE: false (using default - env var not set)");
+                    false
+                }
+            },
+            migration_backup_dir: match env::var("MIGRATION_BACKUP_DIR") {
+                Ok(val) => {
+                    println!("✅ MIGRATION_BACKUP_DIR: {} (loaded from env)", val);
+                    val
+                }
+                Err(_) => {
+                    let default_dir = "./backups".to_string();
+                    println!("⚠️  MIGRATION_BACKUP_DIR: {} (using default - env var not set)", default_dir);
+                    default_dir
+                }
+            },
+            migration_backup_include_data: match env::var("MIGRATION_BACKUP_INCLUDE_DATA") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ MIGRATION_BACKUP_INCLUDE_DATA: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  MIGRATION_BACKUP_INCLUDE_DATA: false (using default - env var not set)");
+                    false
+                }
+            },
+            source_debug_endpoints: match env::var("SOURCE_DEBUG_ENDPOINTS") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ SOURCE_DEBUG_ENDPOINTS: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  SOURCE_DEBUG_ENDPOINTS: false (using default - env var not set)");
+                    false
+                }
+            },
+            ocr_debug_endpoints: match env::var("OCR_DEBUG_ENDPOINTS") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ OCR_DEBUG_ENDPOINTS: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  OCR_DEBUG_ENDPOINTS: false (using default - env var not set)");
+                    false
+                }
+            },
+            detect_document_language: match env::var("DETECT_DOCUMENT_LANGUAGE") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ DETECT_DOCUMENT_LANGUAGE: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  DETECT_DOCUMENT_LANGUAGE: true (using default - env var not set)");
+                    true
+                }
+            },
+            default_document_language: match env::var("DEFAULT_DOCUMENT_LANGUAGE") {
+                Ok(val) if crate::utils::text_search::is_supported_search_language(&val) => {
+                    let language = val.trim().to_lowercase();
+                    println!("✅ DEFAULT_DOCUMENT_LANGUAGE: {} (loaded from env)", language);
+                    language
+                }
+                Ok(val) => {
+                    println!("⚠️  DEFAULT_DOCUMENT_LANGUAGE: unsupported language '{}', using default (english)", val);
+                    crate::utils::text_search::DEFAULT_SEARCH_LANGUAGE.to_string()
+                }
+                Err(_) => {
+                    println!("⚠️  DEFAULT_DOCUMENT_LANGUAGE: english (using default - env var not set)");
+                    crate::utils::text_search::DEFAULT_SEARCH_LANGUAGE.to_string()
+                }
+            },
+            api_error_format: match env::var("API_ERROR_FORMAT") {
+                Ok(val) => match val.parse::<ApiErrorFormat>() {
+                    Ok(format) => {
+                        println!("✅ API_ERROR_FORMAT: {} (loaded from env)", format);
+                        format
+                    }
+                    Err(e) => {
+                        println!("⚠️  API_ERROR_FORMAT: {}, using default (legacy)", e);
+                        ApiErrorFormat::Legacy
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  API_ERROR_FORMAT: legacy (using default - env var not set)");
+                    ApiErrorFormat::Legacy
+                }
+            },
+            api_error_expose_details: match env::var("API_ERROR_EXPOSE_DETAILS") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ API_ERROR_EXPOSE_DETAILS: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  API_ERROR_EXPOSE_DETAILS: false (using default - env var not set)");
+                    false
+                }
+            },
+            credentials_master_keys: match env::var("CREDENTIALS_MASTER_KEYS") {
+                Ok(keys) => {
+                    println!("✅ CREDENTIALS_MASTER_KEYS: ***hidden*** (loaded from env, {} keys)",
+                        keys.split(',').filter(|entry| !entry.trim().is_empty()).count());
+                    keys
+                }
+                Err(_) => match env::var("CREDENTIALS_MASTER_KEYS_FILE").ok().filter(|path| !path.trim().is_empty()) {
+                    // One `version:base64key` pair per line, e.g. a mounted Docker or Kubernetes secret
+                    Some(path) => {
+                        let keys = std::fs::read_to_string(path.trim())
+                            .map_err(|e| anyhow::anyhow!("Failed to read CREDENTIALS_MASTER_KEYS_FILE '{}': {}", path, e))?
+                            .lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                            .collect::<Vec<_>>()
+                            .join(",");
+                        println!("✅ CREDENTIALS_MASTER_KEYS: ***hidden*** (loaded from {}, {} keys)",
+                            path, keys.split(',').filter(|entry| !entry.trim().is_empty()).count());
+                        keys
+                    }
+                    None => {
+                        println!("⚠️  CREDENTIALS_MASTER_KEYS: not set (source credentials are stored in plain text)");
+                        String::new()
+                    }
+                },
+            },
+            credentials_auto_rotate: match env::var("CREDENTIALS_AUTO_ROTATE") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ CREDENTIALS_AUTO_ROTATE: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  CREDENTIALS_AUTO_ROTATE: true (using default - env var not set)");
+                    true
+                }
+            },
+            credentials_rotation_batch_size: match env::var("CREDENTIALS_ROTATION_BATCH_SIZE") {
+                Ok(val) => match val.trim().parse::<usize>() {
+                    Ok(parsed) if parsed > 0 => {
+                        println!("✅ CREDENTIALS_ROTATION_BATCH_SIZE: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    _ => {
+                        println!("❌ CREDENTIALS_ROTATION_BATCH_SIZE: Invalid value '{}' (must be at least 1), using default 100", val);
+                        100
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  CREDENTIALS_ROTATION_BATCH_SIZE: 100 (using default - env var not set)");
+                    100
+                }
+            },
+            thumbnail_size: match env::var("THUMBNAIL_SIZE") {
+                Ok(val) => match val.trim().parse::<u32>() {
+                    Ok(parsed) if (32..=2048).contains(&parsed) => {
+                        println!("✅ THUMBNAIL_SIZE: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    _ => {
+                        println!("❌ THUMBNAIL_SIZE: Invalid value '{}' (must be 32-2048), using default 200", val);
+                        crate::services::file_service::DEFAULT_THUMBNAIL_SIZE
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  THUMBNAIL_SIZE: 200 (using default - env var not set)");
+                    crate::services::file_service::DEFAULT_THUMBNAIL_SIZE
+                }
+            },
+            thumbnail_auto_regenerate: match env::var("THUMBNAIL_AUTO_REGENERATE") {
+                Ok(val) => {
+                    let enabled = matches!(val.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+                    println!("✅ THUMBNAIL_AUTO_REGENERATE: {} (loaded from env)", enabled);
+                    enabled
+                }
+                Err(_) => {
+                    println!("⚠️  THUMBNAIL_AUTO_REGENERATE: false (using default - env var not set)");
+                    false
+                }
+            },
+            thumbnail_regeneration_concurrency: match env::var("THUMBNAIL_REGENERATION_CONCURRENCY") {
+                Ok(val) => match val.trim().parse::<usize>() {
+                    Ok(parsed) if parsed > 0 => {
+                        println!("✅ THUMBNAIL_REGENERATION_CONCURRENCY: {} (loaded from env)", parsed);
+                        parsed
+                    }
+                    _ => {
+                        println!("❌ THUMBNAIL_REGENERATION_CONCURRENCY: Invalid value '{}' (must be at least 1), using default 2", val);
+                        2
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  THUMBNAIL_REGENERATION_CONCURRENCY: 2 (using default - env var not set)");
+                    2
+                }
+            },
         };
 
         println!("\n🔍 CONFIGURATION VALIDATION:");
@@ -686,10 +1900,104 @@ impl Config {
         println!("🧠 OCR language: {}", config.ocr_language);
         println!("⚙️  Concurrent OCR jobs: {}", config.concurrent_ocr_jobs);
         println!("⏱️  OCR timeout: {}s", config.ocr_timeout_seconds);
+        println!("⚖️  OCR scheduling policy: {}", config.ocr_scheduling_policy);
+        println!("🧠 OCR models cached per worker thread: {}", config.ocr_model_cache_size);
         println!("📏 Max file size: {}MB", config.max_file_size_mb);
+        println!("📦 Max request body: {} bytes", config.upload_body_limit());
         println!("📄 Max PDF size: {}MB", config.max_pdf_size_mb);
         println!("📑 Max Office document size: {}MB", config.max_office_document_size_mb);
+        println!("📝 Legacy Word tools: {}", config.office_doc_tool_order.join(", "));
+        println!("📄 PDF extraction methods: {}", config.pdf_extraction_order.join(", "));
+        println!("🔀 Extraction methods ordered by success: {}", config.extraction_auto_order);
         println!("💾 Memory limit: {}MB", config.memory_limit_mb);
+        println!("🗜️  Storage compression: {}", config.storage_compression);
+        println!("🔒 Storage user isolation: {}", if config.storage_user_isolation { "enabled" } else { "disabled" });
+        println!("🚚 Source sync pipeline: {} downloads, {} stores, {} queued per stage",
+            config.ingestion_pipeline.download_concurrency, config.ingestion_pipeline.store_concurrency,
+            config.ingestion_pipeline.stage_queue_size);
+        println!("🗑️  Source deletion: {}{}", if config.source_delete_grace_period_minutes == 0 {
+            "immediate".to_string()
+        } else {
+            format!("restorable for {} minutes", config.source_delete_grace_period_minutes)
+        }, if config.source_delete_require_confirmation { ", confirmation required" } else { "" });
+        println!("🛡️  Malware scanning: {}", if config.malware_scan_enabled {
+            if config.malware_scan_fail_open { "enabled (fail-open)" } else { "enabled (fail-closed)" }
+        } else {
+            "disabled"
+        });
+        println!("🩺 File validation: {}", if config.file_validation_mode == FileValidationMode::Off {
+            "disabled".to_string()
+        } else {
+            format!("{} (corrupt files are not stored)", config.file_validation_mode)
+        });
+        println!("📉 PDF optimization: {}", if config.pdf_optimizer == PdfOptimizerTool::None {
+            "disabled".to_string()
+        } else {
+            format!("{} for PDFs of {}KB and up", config.pdf_optimizer, config.pdf_optimize_min_size_kb)
+        });
+        println!("✍️  Handwriting OCR: {}", config.handwriting_ocr_endpoint.as_deref()
+            .unwrap_or("not configured (handwritten documents use Tesseract)"));
+        println!("🎯 OCR confidence calibration: {}", config.ocr_confidence_calibration_file.as_deref()
+            .unwrap_or("none (confidence stored as reported by Tesseract)"));
+        println!("🧹 Temporary OCR file cleanup: {}", if config.temp_file_max_age_hours == 0 {
+            "disabled".to_string()
+        } else {
+            format!("files older than {}h", config.temp_file_max_age_hours)
+        });
+        println!("📚 Page size: {} default, {} max ({})", config.pagination_default_per_page, config.pagination_max_per_page,
+            if config.pagination_clamp_out_of_range { "clamping out-of-range values" } else { "rejecting out-of-range values" });
+        println!("🔎 Search results: at most {} per search", config.search_max_results);
+        println!("🗂️  Search index strategy: {}", config.search_index_strategy);
+        println!("🗜️  Response compression: {}", if config.response_compression_enabled {
+            format!("gzip/brotli for text responses of {} bytes and up", config.response_compression_min_bytes)
+        } else {
+            "disabled".to_string()
+        });
+        println!("🗂️  WebDAV discovery cache: {}", if config.webdav_discovery_cache_enabled {
+            format!("up to {} directories for {} minutes{}", config.webdav_discovery_cache_max_entries,
+                config.webdav_discovery_cache_ttl_minutes,
+                config.webdav_discovery_cache_file.as_deref().map(|path| format!(", saved to {}", path)).unwrap_or_default())
+        } else {
+            "disabled".to_string()
+        });
+        println!("⏰ WebDAV clock skew: {}, tolerating {}s on incremental syncs",
+            if config.webdav_clock_skew_warning_seconds > 0 {
+                format!("warning beyond {}s", config.webdav_clock_skew_warning_seconds)
+            } else {
+                "warnings disabled".to_string()
+            },
+            config.webdav_clock_skew_tolerance_seconds);
+        println!("🧭 WebDAV server capabilities: checked again after {} minutes or when the server software changes",
+            config.webdav_capability_ttl_minutes);
+        if config.webdav_max_downloads_per_host > 0 {
+            println!("🚦 WebDAV downloads per host: at most {} at once across all sources",
+                config.webdav_max_downloads_per_host);
+        } else {
+            println!("🚦 WebDAV downloads per host: unlimited");
+        }
+        println!("🧾 API errors: {}{}", config.api_error_format,
+            if config.api_error_format == ApiErrorFormat::Structured && config.api_error_expose_details {
+                " (server errors keep their internal details)"
+            } else {
+                ""
+            });
+        if config.api_error_expose_details {
+            println!("⚠️  API_ERROR_EXPOSE_DETAILS is on: error responses can reveal SQL, file paths and server responses");
+        }
+        let keyring = CredentialKeyring::parse(&config.credentials_master_keys)
+            .map_err(|e| anyhow::anyhow!("Invalid CREDENTIALS_MASTER_KEYS: {}", e))?;
+        match keyring.current_version() {
+            Some(version) => println!("🔐 Source credentials: encrypted with master key v{} ({} keys configured{})",
+                version, keyring.versions().len(),
+                if config.credentials_auto_rotate { ", older keys rotated at startup" } else { "" }),
+            None => println!("🔐 Source credentials: stored in plain text"),
+        }
+        println!("💾 Backup before migrations: {}", if config.backup_before_migrate {
+            format!("schema{} to {}", if config.migration_backup_include_data { " and users/settings/sources rows" } else { "" },
+                config.migration_backup_dir)
+        } else {
+            "disabled".to_string()
+        });
         
         // Warning checks
         println!("\n⚠️  CONFIGURATION WARNINGS:");
@@ -703,6 +2011,10 @@ impl Config {
         if config.max_file_size_mb > 100 {
             println!("📏 INFO: Large file size limit ({}MB) may impact performance", config.max_file_size_mb);
         }
+        if config.pagination_default_per_page > config.pagination_max_per_page {
+            println!("⚠️  PAGINATION_DEFAULT_PER_PAGE ({}) exceeds PAGINATION_MAX_PER_PAGE ({}); the max will be used as the default",
+                config.pagination_default_per_page, config.pagination_max_per_page);
+        }
         if config.concurrent_ocr_jobs > 8 {
             println!("⚙️  INFO: High OCR concurrency ({}) may use significant CPU/memory", config.concurrent_ocr_jobs);
         }
@@ -745,6 +2057,12 @@ impl Config {
         
         Ok(config)
     }
+
+    /// Largest request body the server accepts, in bytes
+    pub fn upload_body_limit(&self) -> usize {
+        self.max_upload_bytes
+            .unwrap_or(self.max_file_size_mb * 1024 * 1024) as usize
+    }
     
     fn validate_paths(&self) -> Result<()> {
         use std::path::Path;
@@ -31,7 +31,7 @@ impl ConstraintValidator {
     /// Validates that a failure_stage value is allowed by the failed_documents table constraint
     pub fn validate_failure_stage(stage: &str) -> Result<(), String> {
         let valid_stages: HashSet<&str> = [
-            "ingestion", "validation", "ocr", "storage", "processing", "sync"
+            "ingestion", "validation", "ocr", "storage", "processing", "sync", "quarantined"
         ].iter().cloned().collect();
 
         if valid_stages.contains(stage) {
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::document_note::DocumentNote;
+
+/// Columns of a note with its author's username, selected from `document_notes n JOIN users u`
+const NOTE_COLUMNS: &str = "n.id, n.document_id, n.user_id, u.username AS author, n.content, n.created_at, n.updated_at";
+
+impl Database {
+    pub async fn create_document_note(&self, document_id: Uuid, user_id: Uuid, content: &str) -> Result<DocumentNote> {
+        let note = sqlx::query_as::<_, DocumentNote>(&format!(
+            r#"WITH n AS (
+                   INSERT INTO document_notes (document_id, user_id, content)
+                   VALUES ($1, $2, $3)
+                   RETURNING *
+               )
+               SELECT {} FROM n JOIN users u ON n.user_id = u.id"#,
+            NOTE_COLUMNS
+        ))
+        .bind(document_id)
+        .bind(user_id)
+        .bind(content)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Lists the notes `user_id` wrote on a document, oldest first
+    pub async fn get_document_notes(&self, document_id: Uuid, user_id: Uuid) -> Result<Vec<DocumentNote>> {
+        let notes = sqlx::query_as::<_, DocumentNote>(&format!(
+            r#"SELECT {} FROM document_notes n
+               JOIN users u ON n.user_id = u.id
+               WHERE n.document_id = $1 AND n.user_id = $2
+               ORDER BY n.created_at"#,
+            NOTE_COLUMNS
+        ))
+        .bind(document_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Replaces the text of a note. Returns None unless `user_id` wrote the note
+    /// on this document.
+    pub async fn update_document_note(
+        &self,
+        note_id: Uuid,
+        document_id: Uuid,
+        user_id: Uuid,
+        content: &str,
+    ) -> Result<Option<DocumentNote>> {
+        let note = sqlx::query_as::<_, DocumentNote>(&format!(
+            r#"WITH n AS (
+                   UPDATE document_notes SET content = $4, updated_at = NOW()
+                   WHERE id = $1 AND document_id = $2 AND user_id = $3
+                   RETURNING *
+               )
+               SELECT {} FROM n JOIN users u ON n.user_id = u.id"#,
+            NOTE_COLUMNS
+        ))
+        .bind(note_id)
+        .bind(document_id)
+        .bind(user_id)
+        .bind(content)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Deletes a note `user_id` wrote on this document
+    pub async fn delete_document_note(&self, note_id: Uuid, document_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"DELETE FROM document_notes WHERE id = $1 AND document_id = $2 AND user_id = $3"#,
+        )
+        .bind(note_id)
+        .bind(document_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
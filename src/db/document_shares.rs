@@ -0,0 +1,92 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::document_share::DocumentShare;
+use crate::models::UserRole;
+
+impl Database {
+    /// Shares a document owned by `owner_id` with another user. Returns None when the
+    /// owner does not own the document; sharing twice returns the existing share.
+    pub async fn share_document(
+        &self,
+        document_id: Uuid,
+        owner_id: Uuid,
+        shared_with_user_id: Uuid,
+    ) -> Result<Option<DocumentShare>> {
+        let share = sqlx::query_as::<_, DocumentShare>(
+            r#"INSERT INTO document_shares (document_id, shared_with_user_id, shared_by)
+               SELECT id, $3, user_id FROM documents WHERE id = $1 AND user_id = $2
+               ON CONFLICT (document_id, shared_with_user_id)
+               DO UPDATE SET shared_by = EXCLUDED.shared_by
+               RETURNING *"#,
+        )
+        .bind(document_id)
+        .bind(owner_id)
+        .bind(shared_with_user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(share)
+    }
+
+    /// Removes a share from a document owned by `owner_id`
+    pub async fn unshare_document(
+        &self,
+        document_id: Uuid,
+        owner_id: Uuid,
+        shared_with_user_id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"DELETE FROM document_shares ds
+               USING documents d
+               WHERE ds.document_id = d.id
+                 AND ds.document_id = $1 AND d.user_id = $2 AND ds.shared_with_user_id = $3"#,
+        )
+        .bind(document_id)
+        .bind(owner_id)
+        .bind(shared_with_user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Lists who a document owned by `owner_id` is shared with
+    pub async fn get_document_shares(&self, document_id: Uuid, owner_id: Uuid) -> Result<Vec<DocumentShare>> {
+        let shares = sqlx::query_as::<_, DocumentShare>(
+            r#"SELECT ds.* FROM document_shares ds
+               JOIN documents d ON ds.document_id = d.id
+               WHERE ds.document_id = $1 AND d.user_id = $2
+               ORDER BY ds.created_at"#,
+        )
+        .bind(document_id)
+        .bind(owner_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(shares)
+    }
+
+    /// Whether a user may read a document: they own it, it is shared with them,
+    /// or they are an admin
+    pub async fn can_read_document(&self, document_id: Uuid, user_id: Uuid, role: UserRole) -> Result<bool> {
+        let readable: bool = sqlx::query_scalar(
+            r#"SELECT EXISTS (
+                   SELECT 1 FROM documents d
+                   WHERE d.id = $1
+                     AND ($3 OR d.user_id = $2 OR EXISTS (
+                         SELECT 1 FROM document_shares ds
+                         WHERE ds.document_id = d.id AND ds.shared_with_user_id = $2
+                     ))
+               )"#,
+        )
+        .bind(document_id)
+        .bind(user_id)
+        .bind(role == UserRole::Admin)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(readable)
+    }
+}
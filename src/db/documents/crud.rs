@@ -2,10 +2,22 @@ use anyhow::Result;
 use sqlx::{QueryBuilder, Postgres};
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole};
-use super::helpers::{map_row_to_document, apply_role_based_filter, apply_pagination, DOCUMENT_FIELDS};
+use crate::models::{Document, DocumentDate, ExpiringDocument, OcrTextCorrection, UserRole};
+use super::helpers::{map_row_to_document, apply_role_based_filter, apply_pagination, apply_search_access_filter, DOCUMENT_FIELDS};
 use crate::db::Database;
 
+/// Documents of user `$1` last processed under OCR settings older than version `$2`
+const OCR_REPROCESS_FILTER: &str = r#"
+    user_id = $1
+    AND ocr_status IN ('completed', 'failed')
+    AND ocr_manually_corrected = FALSE
+    AND (ocr_settings_version IS NULL OR ocr_settings_version < $2)
+    AND NOT EXISTS (
+        SELECT 1 FROM ocr_queue q
+        WHERE q.document_id = documents.id AND q.status IN ('pending', 'processing')
+    )
+"#;
+
 impl Database {
     /// Creates a new document in the database
     pub async fn create_document(&self, document: Document) -> Result<Document> {
@@ -72,6 +84,25 @@ impl Database {
         Ok(row.map(|r| map_row_to_document(&r)))
     }
 
+    /// The documents among `document_ids` that a user may read: their own and
+    /// those shared with them, or any for admins. Fetched with a single query.
+    pub async fn get_readable_documents_by_ids(&self, document_ids: &[Uuid], user_id: Uuid, user_role: UserRole) -> Result<Vec<Document>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        query.push(DOCUMENT_FIELDS);
+        query.push(" FROM documents WHERE id = ANY(");
+        query.push_bind(document_ids);
+        query.push(")");
+
+        apply_search_access_filter(&mut query, user_id, user_role, true);
+
+        let rows = query
+            .build()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(map_row_to_document).collect())
+    }
+
     /// Gets documents for a user with role-based access and pagination
     pub async fn get_documents_by_user(&self, user_id: Uuid, limit: i64, offset: i64) -> Result<Vec<Document>> {
         let query_str = format!(
@@ -179,6 +210,704 @@ impl Database {
         Ok(())
     }
 
+    /// Stores externally extracted text for a document and marks it as `external`,
+    /// so it is searchable without going through the OCR queue
+    pub async fn update_document_external_text(&self, document_id: Uuid, text: &str) -> Result<()> {
+        let word_count = text.split_whitespace().count() as i32;
+
+        sqlx::query(
+            r#"
+            UPDATE documents 
+            SET ocr_text = $2, ocr_word_count = $3, ocr_confidence = NULL, ocr_confidence_raw = NULL, ocr_status = 'external',
+                ocr_completed_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .bind(text)
+        .bind(word_count)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replaces a document's OCR text with a user correction and marks it as manually
+    /// corrected. The first correction keeps the OCR output in `ocr_original_text`;
+    /// later corrections leave it untouched. Returns None if OCR is still in progress.
+    pub async fn correct_document_ocr_text(&self, document_id: Uuid, text: &str) -> Result<Option<OcrTextCorrection>> {
+        let word_count = text.split_whitespace().count() as i32;
+
+        let correction = sqlx::query_as::<_, OcrTextCorrection>(
+            r#"
+            UPDATE documents
+            SET ocr_original_text = CASE WHEN ocr_manually_corrected THEN ocr_original_text ELSE ocr_text END,
+                ocr_text = $2,
+                ocr_word_count = $3,
+                ocr_status = CASE WHEN ocr_status = 'external' THEN 'external' ELSE 'completed' END,
+                ocr_error = NULL,
+                ocr_failure_reason = NULL,
+                ocr_manually_corrected = TRUE,
+                ocr_corrected_at = NOW(),
+                ocr_hybrid_extraction = FALSE,
+                ocr_engine = NULL,
+                ocr_engine_note = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+              AND COALESCE(ocr_status, '') NOT IN ('pending', 'processing', 'preview')
+            RETURNING id AS document_id, ocr_manually_corrected AS manually_corrected,
+                      ocr_original_text AS original_ocr_text, ocr_corrected_at AS corrected_at
+            "#
+        )
+        .bind(document_id)
+        .bind(text)
+        .bind(word_count)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(correction)
+    }
+
+    /// Gets the manual correction state of a document's OCR text
+    pub async fn get_ocr_text_correction(&self, document_id: Uuid) -> Result<Option<OcrTextCorrection>> {
+        let correction = sqlx::query_as::<_, OcrTextCorrection>(
+            r#"
+            SELECT id AS document_id, ocr_manually_corrected AS manually_corrected,
+                   ocr_original_text AS original_ocr_text, ocr_corrected_at AS corrected_at
+            FROM documents
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(correction)
+    }
+
+    /// Returns the subset of `document_ids` whose OCR text was manually corrected
+    pub async fn get_manually_corrected_document_ids(&self, document_ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM documents WHERE id = ANY($1) AND ocr_manually_corrected"
+        )
+        .bind(document_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Drops a manual correction so the document can be reprocessed. The corrected
+    /// text stays in place until OCR replaces it.
+    pub async fn clear_ocr_text_correction(&self, document_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET ocr_manually_corrected = FALSE,
+                ocr_original_text = NULL,
+                ocr_corrected_at = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records whether a document's OCR text came from hybrid PDF extraction
+    pub async fn set_ocr_hybrid_extraction(&self, document_id: Uuid, hybrid: bool) -> Result<()> {
+        sqlx::query("UPDATE documents SET ocr_hybrid_extraction = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(hybrid)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether a document's OCR text came from hybrid PDF extraction
+    pub async fn get_ocr_hybrid_extraction(&self, document_id: Uuid) -> Result<bool> {
+        let hybrid = sqlx::query_scalar::<_, bool>("SELECT ocr_hybrid_extraction FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(hybrid.unwrap_or(false))
+    }
+
+    /// Records the language a document's text is searched in. `language` must be
+    /// one of the supported search languages.
+    pub async fn set_document_search_language(&self, document_id: Uuid, language: &str) -> Result<()> {
+        sqlx::query("UPDATE documents SET search_language = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(language)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Language a document's text is searched in, if one has been recorded
+    pub async fn get_document_search_language(&self, document_id: Uuid) -> Result<Option<String>> {
+        let language = sqlx::query_scalar::<_, Option<String>>("SELECT search_language FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(language.flatten())
+    }
+
+    /// Records the size a document's cached thumbnail was generated at
+    pub async fn set_document_thumbnail_size(&self, document_id: Uuid, size: i32) -> Result<()> {
+        sqlx::query("UPDATE documents SET thumbnail_size = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(size)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Size a document's cached thumbnail was generated at, if it has one
+    pub async fn get_document_thumbnail_size(&self, document_id: Uuid) -> Result<Option<i32>> {
+        let size = sqlx::query_scalar::<_, Option<i32>>("SELECT thumbnail_size FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(size.flatten())
+    }
+
+    /// Stores the tables extracted from a document, replacing those of an earlier run
+    pub async fn set_document_tables(&self, document_id: Uuid, tables: &[crate::ocr::table_extraction::ExtractedTable]) -> Result<()> {
+        sqlx::query("UPDATE documents SET extracted_tables = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(serde_json::to_value(tables)?)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Tables extracted from a document, or None if extraction has not run on it
+    pub async fn get_document_tables(&self, document_id: Uuid) -> Result<Option<Vec<crate::ocr::table_extraction::ExtractedTable>>> {
+        let tables = sqlx::query_scalar::<_, Option<serde_json::Value>>("SELECT extracted_tables FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match tables.flatten() {
+            Some(tables) => Ok(Some(serde_json::from_value(tables)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the fields read from the named OCR regions of a document's labels,
+    /// replacing those of an earlier run
+    pub async fn set_document_fields(&self, document_id: Uuid, fields: &[crate::ocr::region::ExtractedField]) -> Result<()> {
+        sqlx::query("UPDATE documents SET extracted_fields = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(serde_json::to_value(fields)?)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fields read from a document's named OCR regions, or None if none were read
+    pub async fn get_document_fields(&self, document_id: Uuid) -> Result<Option<Vec<crate::ocr::region::ExtractedField>>> {
+        let fields = sqlx::query_scalar::<_, Option<serde_json::Value>>("SELECT extracted_fields FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match fields.flatten() {
+            Some(fields) => Ok(Some(serde_json::from_value(fields)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Counts the documents whose thumbnail was generated at another size than `size`
+    pub async fn count_stale_thumbnails(&self, size: i32) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM documents WHERE thumbnail_size IS NOT NULL AND thumbnail_size <> $1",
+        )
+        .bind(size)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Up to `limit` documents whose thumbnail was generated at another size
+    /// than `size`, in ID order after `after`
+    pub async fn get_documents_with_stale_thumbnails(&self, size: i32, after: Option<Uuid>, limit: i64) -> Result<Vec<Document>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        query.push(DOCUMENT_FIELDS);
+        query.push(" FROM documents WHERE thumbnail_size IS NOT NULL AND thumbnail_size <> ");
+        query.push_bind(size);
+        if let Some(after) = after {
+            query.push(" AND id > ");
+            query.push_bind(after);
+        }
+        query.push(" ORDER BY id LIMIT ");
+        query.push_bind(limit);
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(map_row_to_document).collect())
+    }
+
+    /// Up to `limit` documents with a local file, as (id, user id, file path),
+    /// in ID order after `after`
+    pub async fn get_local_document_paths(&self, after: Option<Uuid>, limit: i64) -> Result<Vec<(Uuid, Uuid, String)>> {
+        let rows = sqlx::query_as::<_, (Uuid, Uuid, String)>(
+            r#"
+            SELECT id, user_id, file_path FROM documents
+            WHERE file_path NOT LIKE 's3://%' AND ($1::uuid IS NULL OR id > $1)
+            ORDER BY id
+            LIMIT $2
+            "#
+        )
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Flags a document as handwritten so OCR routes it to the handwriting engine.
+    /// Returns false if the document doesn't exist.
+    pub async fn set_document_handwriting(&self, document_id: Uuid, handwriting: bool) -> Result<bool> {
+        let result = sqlx::query("UPDATE documents SET ocr_handwriting = $2, updated_at = NOW() WHERE id = $1")
+            .bind(document_id)
+            .bind(handwriting)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Records which OCR engine produced a document's text (None when no OCR ran),
+    /// with an optional caveat
+    pub async fn set_ocr_engine(&self, document_id: Uuid, engine: Option<&str>, note: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE documents SET ocr_engine = $2, ocr_engine_note = $3 WHERE id = $1")
+            .bind(document_id)
+            .bind(engine)
+            .bind(note)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores the OCR confidence as reported by the engine, before calibration
+    pub async fn set_ocr_raw_confidence(&self, document_id: Uuid, raw_confidence: Option<f32>) -> Result<()> {
+        sqlx::query("UPDATE documents SET ocr_confidence_raw = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(raw_confidence)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The OCR confidence of a document before calibration, if recorded
+    pub async fn get_ocr_raw_confidence(&self, document_id: Uuid) -> Result<Option<f32>> {
+        let raw_confidence = sqlx::query_scalar::<_, Option<f32>>("SELECT ocr_confidence_raw FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(raw_confidence.flatten())
+    }
+
+    /// The handwriting flag of a document, the engine that produced its OCR text and the engine's note
+    pub async fn get_ocr_engine_info(&self, document_id: Uuid) -> Result<(bool, Option<String>, Option<String>)> {
+        let info = sqlx::query_as::<_, (bool, Option<String>, Option<String>)>(
+            "SELECT ocr_handwriting, ocr_engine, ocr_engine_note FROM documents WHERE id = $1"
+        )
+        .bind(document_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(info.unwrap_or((false, None, None)))
+    }
+
+    /// Records that a document is the part of `parent_id` covering the given pages
+    pub async fn link_split_part(&self, document_id: Uuid, parent_id: Uuid, first_page: i32, last_page: i32) -> Result<()> {
+        sqlx::query(
+            "UPDATE documents SET split_parent_id = $2, split_first_page = $3, split_last_page = $4 WHERE id = $1"
+        )
+        .bind(document_id)
+        .bind(parent_id)
+        .bind(first_page)
+        .bind(last_page)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Archives a document, which hides it from document lists unless archived
+    /// documents are asked for
+    pub async fn archive_document(&self, document_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE documents SET archived_at = NOW(), updated_at = NOW() WHERE id = $1 AND archived_at IS NULL")
+            .bind(document_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// When a document was archived, and the original it was split from with the pages it covers
+    pub async fn get_document_split_info(
+        &self,
+        document_id: Uuid,
+    ) -> Result<Option<(Option<chrono::DateTime<chrono::Utc>>, Option<Uuid>, Option<i32>, Option<i32>)>> {
+        let info = sqlx::query_as::<_, (Option<chrono::DateTime<chrono::Utc>>, Option<Uuid>, Option<i32>, Option<i32>)>(
+            "SELECT archived_at, split_parent_id, split_first_page, split_last_page FROM documents WHERE id = $1"
+        )
+        .bind(document_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(info)
+    }
+
+    /// The parts a document was split into as (id, filename, first page, last page), in page order
+    pub async fn get_split_parts(&self, parent_id: Uuid) -> Result<Vec<(Uuid, String, i32, i32)>> {
+        let parts = sqlx::query_as::<_, (Uuid, String, i32, i32)>(
+            r#"
+            SELECT id, filename, split_first_page, split_last_page
+            FROM documents
+            WHERE split_parent_id = $1
+            ORDER BY split_first_page
+            "#
+        )
+        .bind(parent_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(parts)
+    }
+
+    /// Records that a document was extracted from the archive `parent_id` at `entry_path`
+    pub async fn link_archive_entry(&self, document_id: Uuid, parent_id: Uuid, entry_path: &str) -> Result<()> {
+        sqlx::query("UPDATE documents SET archive_parent_id = $2, archive_entry_path = $3 WHERE id = $1")
+            .bind(document_id)
+            .bind(parent_id)
+            .bind(entry_path)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The archive a document was extracted from and its path inside it
+    pub async fn get_document_archive_info(&self, document_id: Uuid) -> Result<Option<(Option<Uuid>, Option<String>)>> {
+        let info = sqlx::query_as::<_, (Option<Uuid>, Option<String>)>(
+            "SELECT archive_parent_id, archive_entry_path FROM documents WHERE id = $1"
+        )
+        .bind(document_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(info)
+    }
+
+    /// The documents extracted from an archive as (id, filename, entry path), by path
+    pub async fn get_archive_entries(&self, parent_id: Uuid) -> Result<Vec<(Uuid, String, String)>> {
+        let entries = sqlx::query_as::<_, (Uuid, String, String)>(
+            r#"
+            SELECT id, filename, archive_entry_path
+            FROM documents
+            WHERE archive_parent_id = $1
+            ORDER BY archive_entry_path
+            "#
+        )
+        .bind(parent_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Records that a document's stored PDF was optimized from `original_size`
+    /// bytes, and where the original was kept, if it was
+    pub async fn record_pdf_optimization(&self, document_id: Uuid, original_size: i64, original_path: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE documents SET original_file_size = $2, original_file_path = $3 WHERE id = $1")
+            .bind(document_id)
+            .bind(original_size)
+            .bind(original_path)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The size before optimization and the kept original of an optimized PDF
+    pub async fn get_pdf_optimization(&self, document_id: Uuid) -> Result<Option<(i64, Option<String>)>> {
+        let optimization = sqlx::query_as::<_, (i64, Option<String>)>(
+            "SELECT original_file_size, original_file_path FROM documents WHERE id = $1 AND original_file_size IS NOT NULL"
+        )
+        .bind(document_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(optimization)
+    }
+
+    /// Records a document's date and where it came from
+    pub async fn set_document_date(&self, document_id: Uuid, date: chrono::NaiveDate, source: &str) -> Result<()> {
+        sqlx::query("UPDATE documents SET document_date = $2, document_date_source = $3 WHERE id = $1")
+            .bind(document_id)
+            .bind(date)
+            .bind(source)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Dates of the given documents; documents without a date are left out
+    pub async fn get_document_dates(&self, document_ids: &[Uuid]) -> Result<Vec<DocumentDate>> {
+        let dates = sqlx::query_as::<_, DocumentDate>(
+            r#"
+            SELECT id AS document_id, document_date, document_date_source
+            FROM documents
+            WHERE id = ANY($1) AND document_date IS NOT NULL AND document_date_source IS NOT NULL
+            "#
+        )
+        .bind(document_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(dates)
+    }
+
+    /// Sets or clears a document's expiry date. Changing it re-arms the reminder.
+    pub async fn set_document_expiry(&self, document_id: Uuid, expiry_date: Option<chrono::NaiveDate>) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE documents
+            SET expiry_reminded_at = CASE WHEN expiry_date IS NOT DISTINCT FROM $2 THEN expiry_reminded_at END,
+                expiry_date = $2,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .bind(expiry_date)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The expiry date of a document, if it has one
+    pub async fn get_document_expiry(&self, document_id: Uuid) -> Result<Option<chrono::NaiveDate>> {
+        let expiry = sqlx::query_scalar::<_, Option<chrono::NaiveDate>>("SELECT expiry_date FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(expiry.flatten())
+    }
+
+    /// A user's documents expiring between today and `within_days` from now,
+    /// soonest first
+    pub async fn get_expiring_documents(&self, user_id: Uuid, within_days: i32) -> Result<Vec<ExpiringDocument>> {
+        let documents = sqlx::query_as::<_, ExpiringDocument>(
+            r#"
+            SELECT id AS document_id, user_id, filename, expiry_date,
+                   expiry_reminded_at IS NOT NULL AS reminded
+            FROM documents
+            WHERE user_id = $1
+              AND expiry_date IS NOT NULL
+              AND expiry_date BETWEEN CURRENT_DATE AND CURRENT_DATE + $2
+            ORDER BY expiry_date, filename
+            "#
+        )
+        .bind(user_id)
+        .bind(within_days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Documents whose owners are due a reminder: not yet reminded, not yet
+    /// expired and within the owner's reminder window (30 days without settings)
+    pub async fn get_documents_due_expiry_reminder(&self) -> Result<Vec<ExpiringDocument>> {
+        let documents = sqlx::query_as::<_, ExpiringDocument>(
+            r#"
+            SELECT d.id AS document_id, d.user_id, d.filename, d.expiry_date, FALSE AS reminded
+            FROM documents d
+            LEFT JOIN settings s ON s.user_id = d.user_id
+            WHERE d.expiry_date IS NOT NULL
+              AND d.expiry_reminded_at IS NULL
+              AND COALESCE(s.expiry_reminder_days, 30) > 0
+              AND d.expiry_date BETWEEN CURRENT_DATE AND CURRENT_DATE + COALESCE(s.expiry_reminder_days, 30)
+            ORDER BY d.user_id, d.expiry_date
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Records that a document's owner was reminded of its expiry
+    pub async fn mark_expiry_reminded(&self, document_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE documents SET expiry_reminded_at = NOW() WHERE id = $1")
+            .bind(document_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records the owner's OCR settings version a document was processed under
+    pub async fn set_ocr_settings_version(&self, document_id: Uuid, version: i32) -> Result<()> {
+        sqlx::query("UPDATE documents SET ocr_settings_version = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(version)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Marks a document as deliberately stored without text extraction
+    pub async fn mark_ocr_skipped(&self, document_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE documents SET ocr_status = 'skipped', updated_at = NOW() WHERE id = $1")
+            .bind(document_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records the barcodes decoded from a document under `barcodes` in its source
+    /// metadata, replacing those of an earlier run
+    pub async fn set_document_barcodes(&self, document_id: Uuid, barcodes: &[crate::ocr::barcode::DecodedBarcode]) -> Result<()> {
+        sqlx::query(
+            "UPDATE documents SET source_metadata = jsonb_set(COALESCE(source_metadata, '{}'::jsonb), '{barcodes}', $2) WHERE id = $1",
+        )
+        .bind(document_id)
+        .bind(serde_json::to_value(barcodes)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Links a document stored from a changed file to the document holding the
+    /// file's previous version, under `previous_version_document_id` in its source metadata
+    pub async fn set_previous_version_document(&self, document_id: Uuid, previous_document_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE documents SET source_metadata = jsonb_set(COALESCE(source_metadata, '{}'::jsonb), '{previous_version_document_id}', to_jsonb($2::text)) WHERE id = $1",
+        )
+        .bind(document_id)
+        .bind(previous_document_id.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gives a new version of a synced file the location its previous version
+    /// was moved to, if the user moved it, so the new version doesn't reappear
+    /// at the remote path
+    pub async fn inherit_document_move(&self, document_id: Uuid, previous_document_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"UPDATE documents d
+               SET source_path = p.source_path,
+                   source_id = p.source_id,
+                   source_metadata = jsonb_set(COALESCE(d.source_metadata, '{}'::jsonb), '{moved_from}', p.source_metadata->'moved_from'),
+                   updated_at = NOW()
+               FROM documents p
+               WHERE d.id = $1 AND p.id = $2 AND p.source_metadata->'moved_from' IS NOT NULL"#
+        )
+        .bind(document_id)
+        .bind(previous_document_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records which pages an incremental OCR run OCR'd again under
+    /// `incremental_ocr` in the document's source metadata, or removes the
+    /// record after a full run
+    pub async fn set_incremental_ocr(&self, document_id: Uuid, record: Option<&crate::ocr::incremental::IncrementalOcrRecord>) -> Result<()> {
+        match record {
+            Some(record) => {
+                sqlx::query(
+                    "UPDATE documents SET source_metadata = jsonb_set(COALESCE(source_metadata, '{}'::jsonb), '{incremental_ocr}', $2) WHERE id = $1",
+                )
+                .bind(document_id)
+                .bind(serde_json::to_value(record)?)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("UPDATE documents SET source_metadata = source_metadata - 'incremental_ocr' WHERE id = $1 AND source_metadata ? 'incremental_ocr'")
+                    .bind(document_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts a user's documents processed under OCR settings older than `version`.
+    /// Manually corrected documents and documents already queued are left out.
+    pub async fn count_documents_for_ocr_reprocess(&self, user_id: Uuid, version: i32) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT COUNT(*) FROM documents WHERE {}",
+            OCR_REPROCESS_FILTER
+        ))
+        .bind(user_id)
+        .bind(version)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Lists `(id, file_size)` of the documents `count_documents_for_ocr_reprocess` counts
+    pub async fn get_documents_for_ocr_reprocess(&self, user_id: Uuid, version: i32) -> Result<Vec<(Uuid, i64)>> {
+        let documents = sqlx::query_as::<_, (Uuid, i64)>(&format!(
+            "SELECT id, file_size FROM documents WHERE {} ORDER BY created_at DESC",
+            OCR_REPROCESS_FILTER
+        ))
+        .bind(user_id)
+        .bind(version)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Moves documents back to `pending` so the queue worker will store new OCR results.
+    /// The previous text stays searchable until reprocessing replaces it.
+    pub async fn mark_documents_for_ocr_reprocess(&self, document_ids: &[Uuid]) -> Result<()> {
+        sqlx::query(
+            "UPDATE documents SET ocr_status = 'pending', ocr_error = NULL, updated_at = NOW() WHERE id = ANY($1)",
+        )
+        .bind(document_ids)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Gets recent documents for a specific source
     pub async fn get_recent_documents_for_source(&self, user_id: Uuid, source_id: Uuid, limit: i64) -> Result<Vec<Document>> {
         let query_str = format!(
@@ -1,7 +1,8 @@
 use sqlx::{Row, QueryBuilder, Postgres};
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole};
+use crate::models::{Document, DocumentListFilter, SearchMode, UserRole};
+use crate::utils::text_search::{supported_search_languages, text_search_config, DEFAULT_TEXT_SEARCH_CONFIG};
 
 /// Standard document fields for SELECT queries
 pub const DOCUMENT_FIELDS: &str = r#"
@@ -13,6 +14,9 @@ pub const DOCUMENT_FIELDS: &str = r#"
     file_owner, file_group, source_metadata
 "#;
 
+/// A document's searchable text: its content followed by its OCR text
+pub(crate) const DOCUMENT_TEXT: &str = "COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')";
+
 /// Maps a database row to a Document struct
 /// This eliminates the ~15+ instances of duplicate row mapping code
 pub fn map_row_to_document(row: &sqlx::postgres::PgRow) -> Document {
@@ -68,6 +72,197 @@ pub fn apply_role_based_filter(
     }
 }
 
+/// Restricts a search to the documents a user may read: their own, plus documents
+/// shared with them when `include_shared` is set. Admins can search every document.
+pub fn apply_search_access_filter(
+    query: &mut QueryBuilder<Postgres>,
+    user_id: Uuid,
+    role: UserRole,
+    include_shared: bool,
+) {
+    match role {
+        UserRole::Admin => {}
+        UserRole::User if include_shared => {
+            query.push(" AND (documents.user_id = ");
+            query.push_bind(user_id);
+            query.push(" OR documents.id IN (SELECT document_id FROM document_shares WHERE shared_with_user_id = ");
+            query.push_bind(user_id);
+            query.push("))");
+        }
+        UserRole::User => {
+            query.push(" AND documents.user_id = ");
+            query.push_bind(user_id);
+        }
+    }
+}
+
+/// The tsquery constructor a search mode uses; fuzzy search has none
+fn tsquery_function(mode: &SearchMode) -> Option<&'static str> {
+    match mode {
+        SearchMode::Simple => Some("plainto_tsquery"),
+        SearchMode::Phrase => Some("phraseto_tsquery"),
+        SearchMode::Boolean => Some("to_tsquery"),
+        SearchMode::Fuzzy => None,
+    }
+}
+
+/// A tsquery, in its text input form, that matches any of `lexemes`. Each lexeme
+/// is quoted with its quotes and backslashes escaped, so it is matched as-is
+/// however it is spelled. Empty when there are no lexemes.
+pub fn any_lexeme_tsquery(lexemes: &[String]) -> String {
+    lexemes
+        .iter()
+        .map(|lexeme| format!("'{}'", lexeme.replace('\\', "\\\\").replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Whether a text search configuration strips accents
+fn ignores_accents(config: &str) -> bool {
+    config.ends_with("_unaccent")
+}
+
+/// Wraps a text expression in `unaccent()` when the configuration ignores
+/// accents, so fuzzy matching agrees with full-text matching
+fn fuzzy_text(expression: &str, config: &str) -> String {
+    if ignores_accents(config) {
+        format!("unaccent({})", expression)
+    } else {
+        expression.to_string()
+    }
+}
+
+/// Pushes the search query as the second argument of a `similarity()` call
+fn push_fuzzy_query(query: &mut QueryBuilder<Postgres>, config: &str, search_query: &str) {
+    query.push(if ignores_accents(config) { "unaccent(" } else { "(" });
+    query.push_bind(search_query.to_string());
+    query.push(")");
+}
+
+/// A document's full-text vector in `config`. The document text index is built
+/// on this expression with [`DEFAULT_TEXT_SEARCH_CONFIG`].
+fn document_vector(config: &str) -> String {
+    format!("to_tsvector('{}', {})", config, DOCUMENT_TEXT)
+}
+
+/// A note's full-text vector in `config`, weighted like the stored
+/// `search_vector`, which is only valid for [`DEFAULT_TEXT_SEARCH_CONFIG`]
+fn note_vector(config: &str) -> String {
+    if config == DEFAULT_TEXT_SEARCH_CONFIG {
+        "n.search_vector".to_string()
+    } else {
+        format!("setweight(to_tsvector('{}', n.content), 'B')", config)
+    }
+}
+
+/// A document's full-text vector in the configuration of its own language,
+/// with accents handled like in `config`. It is NULL for documents without a
+/// language. The language index is built on this expression with accents ignored.
+fn document_language_vector(config: &str) -> String {
+    format!("to_tsvector({}, {})", document_language_config(config), DOCUMENT_TEXT)
+}
+
+fn document_language_config(config: &str) -> String {
+    format!("document_search_config(search_language, {})", ignores_accents(config))
+}
+
+/// Pushes the condition that a document's text matches the search, stemmed
+/// either in the document's language or in the searcher's. `config` must come
+/// from [`crate::utils::text_search::text_search_config`], as it is put into
+/// the SQL as a literal. The document's language is matched one language at a
+/// time, so each query has a constant configuration and can use the index.
+pub fn push_text_match(query: &mut QueryBuilder<Postgres>, mode: &SearchMode, config: &str, search_query: &str) {
+    match tsquery_function(mode) {
+        Some(function) => {
+            query.push("(");
+            for language in supported_search_languages() {
+                query.push(format!(
+                    "(search_language = '{}' AND {} @@ {}('{}', ",
+                    language,
+                    document_language_vector(config),
+                    function,
+                    text_search_config(language, ignores_accents(config))
+                ));
+                query.push_bind(search_query.to_string());
+                query.push(")) OR ");
+            }
+            query.push(format!("{} @@ {}('{}', ", document_vector(config), function, config));
+            query.push_bind(search_query.to_string());
+            query.push("))");
+        }
+        None => {
+            query.push(format!("similarity({}, ", fuzzy_text(DOCUMENT_TEXT, config)));
+            push_fuzzy_query(query, config, search_query);
+            query.push(") > 0.3");
+        }
+    }
+}
+
+/// Pushes the rank of a document's text for the search, the better of its
+/// ranks in the document's language and in the searcher's
+pub fn push_text_rank(query: &mut QueryBuilder<Postgres>, mode: &SearchMode, config: &str, search_query: &str) {
+    match tsquery_function(mode) {
+        Some(function) => {
+            // GREATEST ignores the NULL rank of documents without a language
+            query.push(format!(
+                "GREATEST(ts_rank({}, {}({}, ",
+                document_language_vector(config),
+                function,
+                document_language_config(config)
+            ));
+            query.push_bind(search_query.to_string());
+            query.push(format!(")), ts_rank({}, {}('{}', ", document_vector(config), function, config));
+            query.push_bind(search_query.to_string());
+            query.push(")))");
+        }
+        None => {
+            query.push(format!("similarity({}, ", fuzzy_text(DOCUMENT_TEXT, config)));
+            push_fuzzy_query(query, config, search_query);
+            query.push(")");
+        }
+    }
+}
+
+/// Extends a search condition with `OR <document has a matching note by user_id>`.
+/// Push it inside the parentheses of the text condition.
+pub fn push_note_match(query: &mut QueryBuilder<Postgres>, user_id: Uuid, mode: &SearchMode, config: &str, search_query: &str) {
+    query.push(" OR documents.id IN (SELECT n.document_id FROM document_notes n WHERE n.user_id = ");
+    query.push_bind(user_id);
+    match tsquery_function(mode) {
+        Some(function) => {
+            query.push(format!(" AND {} @@ {}('{}', ", note_vector(config), function, config));
+            query.push_bind(search_query.to_string());
+            query.push("))");
+        }
+        None => {
+            query.push(format!(" AND similarity({}, ", fuzzy_text("n.content", config)));
+            push_fuzzy_query(query, config, search_query);
+            query.push(") > 0.3)");
+        }
+    }
+}
+
+/// Adds the rank of the best matching note by `user_id` to a document's search rank.
+/// Notes are weighted above document text, so matching them lifts a document.
+pub fn push_note_rank(query: &mut QueryBuilder<Postgres>, user_id: Uuid, mode: &SearchMode, config: &str, search_query: &str) {
+    query.push(" + COALESCE((SELECT MAX(");
+    match tsquery_function(mode) {
+        Some(function) => {
+            query.push(format!("ts_rank({}, {}('{}', ", note_vector(config), function, config));
+            query.push_bind(search_query.to_string());
+            query.push("))");
+        }
+        None => {
+            query.push(format!("similarity({}, ", fuzzy_text("n.content", config)));
+            push_fuzzy_query(query, config, search_query);
+            query.push(")");
+        }
+    }
+    query.push(") FROM document_notes n WHERE n.document_id = documents.id AND n.user_id = ");
+    query.push_bind(user_id);
+    query.push("), 0)");
+}
+
 /// Applies pagination to a query builder
 pub fn apply_pagination(query: &mut QueryBuilder<Postgres>, limit: i64, offset: i64) {
     query.push(" LIMIT ");
@@ -76,6 +271,42 @@ pub fn apply_pagination(query: &mut QueryBuilder<Postgres>, limit: i64, offset:
     query.push_bind(offset);
 }
 
+/// Applies the OCR status and document date filters of a document listing
+pub fn apply_document_list_filter(query: &mut QueryBuilder<Postgres>, filter: &DocumentListFilter) {
+    match filter.ocr_status.as_deref() {
+        Some("pending") => {
+            query.push(" AND (ocr_status IS NULL OR ocr_status = 'pending')");
+        }
+        Some(status) => {
+            query.push(" AND ocr_status = ");
+            query.push_bind(status.to_string());
+        }
+        None => {}
+    }
+
+    apply_document_date_range(query, filter.document_date_from, filter.document_date_to);
+
+    if !filter.include_archived {
+        query.push(" AND archived_at IS NULL");
+    }
+}
+
+/// Restricts a query to documents dated within the given days, both inclusive
+pub fn apply_document_date_range(
+    query: &mut QueryBuilder<Postgres>,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+) {
+    if let Some(from) = from {
+        query.push(" AND document_date >= ");
+        query.push_bind(from);
+    }
+    if let Some(to) = to {
+        query.push(" AND document_date <= ");
+        query.push_bind(to);
+    }
+}
+
 /// Helper to determine if a character is a word boundary for snippet generation
 pub fn is_word_boundary(c: char) -> bool {
     c.is_whitespace() || c.is_ascii_punctuation()
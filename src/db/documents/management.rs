@@ -2,9 +2,10 @@ use anyhow::Result;
 use sqlx::{QueryBuilder, Postgres, Row};
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole, FacetItem};
+use crate::models::{Document, DocumentListFilter, UserRole, FacetItem, LibraryStats, SourceDocumentStats, DailyIngestionCount};
 use crate::routes::labels::Label;
-use super::helpers::{map_row_to_document, apply_role_based_filter, DOCUMENT_FIELDS};
+use crate::ocr::region::{NamedOcrRegion, OcrRegion};
+use super::helpers::{map_row_to_document, apply_document_list_filter, apply_pagination, apply_role_based_filter, DOCUMENT_FIELDS};
 use crate::db::Database;
 
 impl Database {
@@ -14,7 +15,7 @@ impl Database {
             r#"
             SELECT
                 l.id, l.user_id, l.name, l.description, l.color,
-                l.background_color, l.icon, l.is_system, l.created_at, l.updated_at,
+                l.background_color, l.icon, l.is_system, l.created_at, l.updated_at, l.ocr_dpi,
                 0::bigint as document_count, 0::bigint as source_count
             FROM labels l
             JOIN document_labels dl ON l.id = dl.label_id
@@ -29,6 +30,165 @@ impl Database {
         Ok(rows)
     }
 
+    /// Tags a document with the system label for its detected language, creating the
+    /// label on first use and replacing the document's previous language label
+    pub async fn set_document_language_label(&self, document_id: Uuid, label_name: &str) -> Result<Uuid> {
+        let mut tx = self.pool.begin().await?;
+
+        let label_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO labels (user_id, name, description, color, is_system)
+            VALUES (NULL, $1, 'Language detected from the document text', '#8250df', TRUE)
+            ON CONFLICT (name) WHERE user_id IS NULL DO UPDATE SET name = EXCLUDED.name
+            RETURNING id
+            "#
+        )
+        .bind(label_name)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM document_labels dl
+            USING labels l
+            WHERE dl.label_id = l.id
+              AND dl.document_id = $1
+              AND l.id <> $2
+              AND l.is_system = TRUE
+              AND l.user_id IS NULL
+              AND starts_with(l.name, $3)
+            "#
+        )
+        .bind(document_id)
+        .bind(label_id)
+        .bind(crate::ocr::language_detection::LANGUAGE_LABEL_PREFIX)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2) ON CONFLICT (document_id, label_id) DO NOTHING"
+        )
+        .bind(document_id)
+        .bind(label_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(label_id)
+    }
+
+    /// Tags a document with labels of its owner, creating the labels that don't
+    /// exist yet. Returns the number of labels the document was tagged with.
+    pub async fn add_document_user_labels(&self, document_id: Uuid, user_id: Uuid, label_names: &[String]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+
+        for label_name in label_names {
+            let label_id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO labels (user_id, name, description, color)
+                VALUES ($1, $2, 'Tag from the front-matter of a note', '#0969da')
+                ON CONFLICT (user_id, name) DO UPDATE SET name = EXCLUDED.name
+                RETURNING id
+                "#
+            )
+            .bind(user_id)
+            .bind(label_name)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2) ON CONFLICT (document_id, label_id) DO NOTHING"
+            )
+            .bind(document_id)
+            .bind(label_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(label_names.len())
+    }
+
+    /// Keeps the front-matter fields of a markdown or text document in its
+    /// `source_metadata` under `front_matter`
+    pub async fn set_document_front_matter(&self, document_id: Uuid, fields: &serde_json::Map<String, serde_json::Value>) -> Result<()> {
+        sqlx::query(
+            "UPDATE documents SET source_metadata = jsonb_set(COALESCE(source_metadata, '{}'::jsonb), '{front_matter}', $2) WHERE id = $1",
+        )
+        .bind(document_id)
+        .bind(serde_json::Value::Object(fields.clone()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Named OCR regions defined for a label, ordered by name
+    pub async fn get_label_ocr_regions(&self, label_id: Uuid) -> Result<Vec<NamedOcrRegion>> {
+        let rows = sqlx::query(
+            "SELECT name, page, x, y, width, height FROM label_ocr_regions WHERE label_id = $1 ORDER BY name"
+        )
+        .bind(label_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(named_ocr_region_from_row).collect())
+    }
+
+    /// Replaces the named OCR regions of a label
+    pub async fn set_label_ocr_regions(&self, label_id: Uuid, regions: &[NamedOcrRegion]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM label_ocr_regions WHERE label_id = $1")
+            .bind(label_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for region in regions {
+            sqlx::query(
+                r#"
+                INSERT INTO label_ocr_regions (label_id, name, page, x, y, width, height)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#
+            )
+            .bind(label_id)
+            .bind(region.name.trim())
+            .bind(region.page as i32)
+            .bind(region.region.x)
+            .bind(region.region.y)
+            .bind(region.region.width)
+            .bind(region.region.height)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Named OCR regions of every label of a document, with the label's name,
+    /// ordered by label and region name
+    pub async fn get_document_ocr_regions(&self, document_id: Uuid) -> Result<Vec<(String, NamedOcrRegion)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT l.name AS label_name, r.name, r.page, r.x, r.y, r.width, r.height
+            FROM label_ocr_regions r
+            JOIN labels l ON l.id = r.label_id
+            JOIN document_labels dl ON dl.label_id = r.label_id
+            WHERE dl.document_id = $1
+            ORDER BY l.name, r.name
+            "#
+        )
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("label_name"), named_ocr_region_from_row(row)))
+            .collect())
+    }
+
     /// Gets labels for multiple documents in batch
     pub async fn get_labels_for_documents(&self, document_ids: &[Uuid]) -> Result<Vec<(Uuid, Vec<Label>)>> {
         if document_ids.is_empty() {
@@ -37,7 +197,8 @@ impl Database {
 
         let rows = sqlx::query(
             r#"
-            SELECT dl.document_id, l.id as label_id, l.user_id, l.name, l.color, l.created_at, l.updated_at
+            SELECT dl.document_id, l.id as label_id, l.user_id, l.name, l.color, l.is_system, l.created_at, l.updated_at,
+                   l.ocr_dpi
             FROM labels l
             JOIN document_labels dl ON l.id = dl.label_id
             WHERE dl.document_id = ANY($1)
@@ -62,9 +223,10 @@ impl Database {
                 color: row.get("color"),
                 background_color: None,
                 icon: None,
-                is_system: false,
+                is_system: row.get("is_system"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
+                ocr_dpi: row.get("ocr_dpi"),
                 document_count: 0,
                 source_count: 0,
             };
@@ -166,6 +328,96 @@ impl Database {
         }).collect())
     }
 
+    /// Computes library statistics over the documents visible to the user
+    pub async fn get_library_stats(&self, user_id: Uuid, user_role: UserRole) -> Result<LibraryStats> {
+        const INGESTION_DAYS: u32 = 30;
+        let today = chrono::Utc::now().date_naive();
+        let window_start = today
+            .checked_sub_days(chrono::Days::new((INGESTION_DAYS - 1) as u64))
+            .unwrap_or(today);
+
+        let totals = async {
+            let mut query = QueryBuilder::<Postgres>::new(
+                r#"SELECT COUNT(*) as total_documents,
+                          COALESCE(SUM(file_size), 0)::BIGINT as total_storage_bytes,
+                          (AVG(ocr_confidence) FILTER (WHERE ocr_status = 'completed'))::DOUBLE PRECISION as average_ocr_confidence
+                   FROM documents WHERE 1=1"#
+            );
+            apply_role_based_filter(&mut query, user_id, user_role);
+            query.build().fetch_one(&self.pool).await
+        };
+
+        let by_ocr_status = async {
+            let mut query = QueryBuilder::<Postgres>::new(
+                "SELECT COALESCE(ocr_status, 'pending') as value, COUNT(*) as count FROM documents WHERE 1=1"
+            );
+            apply_role_based_filter(&mut query, user_id, user_role);
+            query.push(" GROUP BY 1 ORDER BY count DESC, value");
+            query.build().fetch_all(&self.pool).await
+        };
+
+        // Aggregate before joining so the role filter only ever sees documents.user_id
+        let by_source = async {
+            let mut query = QueryBuilder::<Postgres>::new(
+                r#"SELECT c.source_id, s.name as source_name, s.source_type, c.document_count, c.storage_bytes
+                   FROM (
+                       SELECT source_id, COUNT(*) as document_count, COALESCE(SUM(file_size), 0)::BIGINT as storage_bytes
+                       FROM documents WHERE 1=1"#
+            );
+            apply_role_based_filter(&mut query, user_id, user_role);
+            query.push(
+                r#" GROUP BY source_id
+                   ) c
+                   LEFT JOIN sources s ON s.id = c.source_id
+                   ORDER BY c.document_count DESC"#
+            );
+            query.build().fetch_all(&self.pool).await
+        };
+
+        let ingestion = async {
+            let mut query = QueryBuilder::<Postgres>::new(
+                "SELECT (created_at AT TIME ZONE 'UTC')::date as day, COUNT(*) as count FROM documents WHERE created_at >= "
+            );
+            query.push_bind(window_start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+            apply_role_based_filter(&mut query, user_id, user_role);
+            query.push(" GROUP BY 1");
+            query.build().fetch_all(&self.pool).await
+        };
+
+        let (totals, by_mime_type, by_ocr_status, by_source, ingestion) = tokio::try_join!(
+            async { totals.await.map_err(anyhow::Error::from) },
+            self.get_mime_type_facets(user_id, user_role),
+            async { by_ocr_status.await.map_err(anyhow::Error::from) },
+            async { by_source.await.map_err(anyhow::Error::from) },
+            async { ingestion.await.map_err(anyhow::Error::from) },
+        )?;
+
+        let daily_counts: Vec<(chrono::NaiveDate, i64)> = ingestion
+            .into_iter()
+            .map(|row| (row.get("day"), row.get("count")))
+            .collect();
+
+        Ok(LibraryStats {
+            total_documents: totals.get("total_documents"),
+            total_storage_bytes: totals.get("total_storage_bytes"),
+            by_mime_type,
+            by_ocr_status: by_ocr_status.into_iter().map(|row| FacetItem {
+                value: row.get("value"),
+                count: row.get("count"),
+            }).collect(),
+            by_source: by_source.into_iter().map(|row| SourceDocumentStats {
+                source_id: row.get("source_id"),
+                source_name: row.get("source_name"),
+                source_type: row.get("source_type"),
+                document_count: row.get("document_count"),
+                storage_bytes: row.get("storage_bytes"),
+            }).collect(),
+            ingestion_last_30_days: DailyIngestionCount::series(&daily_counts, today, INGESTION_DAYS),
+            average_ocr_confidence: totals.get("average_ocr_confidence"),
+            generated_at: chrono::Utc::now(),
+        })
+    }
+
     /// Gets tag facets (aggregated counts by label)
     pub async fn get_tag_facets(&self, user_id: Uuid, _user_role: UserRole) -> Result<Vec<FacetItem>> {
         let query = sqlx::query_as::<_, (String, i64)>(
@@ -238,13 +490,13 @@ impl Database {
         }).collect())
     }
 
-    /// Gets documents by user with role-based access and OCR status filtering
+    /// Gets documents with role-based access control, filtered and ordered by `filter`
     pub async fn get_documents_by_user_with_role_and_filter(
-        &self, 
-        user_id: Uuid, 
-        user_role: UserRole, 
-        ocr_status: Option<&str>, 
-        limit: i64, 
+        &self,
+        user_id: Uuid,
+        user_role: UserRole,
+        filter: &DocumentListFilter,
+        limit: i64,
         offset: i64
     ) -> Result<Vec<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
@@ -252,30 +504,14 @@ impl Database {
         query.push(" FROM documents WHERE 1=1");
 
         apply_role_based_filter(&mut query, user_id, user_role);
+        apply_document_list_filter(&mut query, filter);
 
-        if let Some(status) = ocr_status {
-            match status {
-                "pending" => {
-                    query.push(" AND (ocr_status IS NULL OR ocr_status = 'pending')");
-                }
-                "completed" => {
-                    query.push(" AND ocr_status = 'completed'");
-                }
-                "failed" => {
-                    query.push(" AND ocr_status = 'failed'");
-                }
-                _ => {
-                    query.push(" AND ocr_status = ");
-                    query.push_bind(status);
-                }
-            }
+        if filter.sort_by_document_date {
+            query.push(" ORDER BY document_date DESC NULLS LAST, created_at DESC");
+        } else {
+            query.push(" ORDER BY created_at DESC");
         }
-
-        query.push(" ORDER BY created_at DESC");
-        query.push(" LIMIT ");
-        query.push_bind(limit);
-        query.push(" OFFSET ");
-        query.push_bind(offset);
+        apply_pagination(&mut query, limit, offset);
 
         let rows = query.build().fetch_all(&self.pool).await?;
         Ok(rows.iter().map(map_row_to_document).collect())
@@ -313,4 +549,17 @@ impl Database {
         let row = query.build().fetch_one(&self.pool).await?;
         Ok(row.get(0))
     }
-}
\ No newline at end of file
+}
+
+fn named_ocr_region_from_row(row: &sqlx::postgres::PgRow) -> NamedOcrRegion {
+    NamedOcrRegion {
+        name: row.get("name"),
+        page: row.get::<i32, _>("page") as usize,
+        region: OcrRegion {
+            x: row.get("x"),
+            y: row.get("y"),
+            width: row.get("width"),
+            height: row.get("height"),
+        },
+    }
+}
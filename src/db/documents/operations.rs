@@ -2,8 +2,8 @@ use anyhow::Result;
 use sqlx::{QueryBuilder, Postgres, Row};
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole, FailedDocument};
-use super::helpers::{map_row_to_document, apply_role_based_filter, DOCUMENT_FIELDS};
+use crate::models::{Document, DocumentListFilter, UserRole, FailedDocument};
+use super::helpers::{map_row_to_document, apply_document_list_filter, apply_role_based_filter, DOCUMENT_FIELDS};
 use crate::db::Database;
 
 impl Database {
@@ -53,6 +53,49 @@ impl Database {
         Ok((deleted_ids, failed_ids))
     }
 
+    /// Moves a document to a new logical location and/or source, optionally pointing it
+    /// at a new storage path. The location a synced document was first ingested from is
+    /// preserved in `source_metadata.moved_from`. Syncs look files up by it, so a
+    /// renamed file doesn't undo the move, and a new version of the file is given the
+    /// moved location.
+    pub async fn move_document(
+        &self,
+        document_id: Uuid,
+        user_id: Uuid,
+        user_role: UserRole,
+        new_source_path: Option<&str>,
+        new_source_id: Option<Uuid>,
+        new_file_path: Option<&str>,
+    ) -> Result<Option<Document>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"UPDATE documents SET
+                source_metadata = CASE
+                    WHEN source_metadata->'moved_from' IS NOT NULL OR (source_path IS NULL AND source_id IS NULL)
+                        THEN source_metadata
+                    ELSE jsonb_set(
+                        COALESCE(source_metadata, '{}'::jsonb),
+                        '{moved_from}',
+                        jsonb_build_object('source_id', source_id, 'source_path', source_path)
+                    )
+                END,
+                source_path = COALESCE("#
+        );
+        query.push_bind(new_source_path);
+        query.push(", source_path), source_id = COALESCE(");
+        query.push_bind(new_source_id);
+        query.push(", source_id), file_path = COALESCE(");
+        query.push_bind(new_file_path);
+        query.push(", file_path), updated_at = NOW() WHERE id = ");
+        query.push_bind(document_id);
+
+        apply_role_based_filter(&mut query, user_id, user_role);
+        query.push(" RETURNING ");
+        query.push(DOCUMENT_FIELDS);
+
+        let row = query.build().fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| map_row_to_document(&r)))
+    }
+
     /// Finds documents with OCR confidence below threshold
     pub async fn find_documents_by_confidence_threshold(&self, user_id: Uuid, user_role: UserRole, max_confidence: f32, limit: i64, offset: i64) -> Result<Vec<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
@@ -280,21 +323,17 @@ impl Database {
         Ok(row.get("total"))
     }
 
-    /// Counts documents for a user with role-based access control and OCR status filtering
+    /// Counts documents for a user with role-based access control and list filtering
     pub async fn count_documents_by_user_with_role_and_filter(
-        &self, 
-        user_id: Uuid, 
-        user_role: UserRole, 
-        ocr_status: Option<&str>
+        &self,
+        user_id: Uuid,
+        user_role: UserRole,
+        filter: &DocumentListFilter
     ) -> Result<i64> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as total FROM documents WHERE 1=1");
         apply_role_based_filter(&mut query, user_id, user_role);
-        
-        if let Some(status) = ocr_status {
-            query.push(" AND ocr_status = ");
-            query.push_bind(status);
-        }
-        
+        apply_document_list_filter(&mut query, filter);
+
         let row = query.build().fetch_one(&self.pool).await?;
         Ok(row.get("total"))
     }
@@ -1,26 +1,48 @@
 use anyhow::Result;
+use futures::TryStreamExt;
 use sqlx::{QueryBuilder, Postgres, Row};
+use tokio::sync::mpsc;
+use tracing::warn;
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole, SearchRequest, SearchMode, SearchSnippet, HighlightRange, EnhancedDocumentResponse};
-use super::helpers::{map_row_to_document, apply_role_based_filter, apply_pagination, find_word_boundary, DOCUMENT_FIELDS};
+use crate::models::{Document, UserRole, SearchRequest, SearchMatchCount, SearchMode, SearchSnippet, HighlightRange, EnhancedDocumentResponse, RelatedDocument, SearchExportRow};
+use super::helpers::{map_row_to_document, any_lexeme_tsquery, apply_document_date_range, apply_search_access_filter, apply_pagination, find_word_boundary, push_note_match, push_note_rank, push_text_match, push_text_rank, DOCUMENT_FIELDS};
 use crate::db::Database;
+use crate::utils::text_search::FoldedText;
 
 impl Database {
     /// Performs basic document search with PostgreSQL full-text search
     pub async fn search_documents(&self, user_id: Uuid, search_request: &SearchRequest) -> Result<Vec<Document>> {
-        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        let results = self.search_documents_with_duplicates(user_id, search_request).await?;
+        Ok(results.into_iter().map(|(document, _)| document).collect())
+    }
+
+    /// Basic document search, returning each document with the IDs of the
+    /// duplicates collapsed into it (empty unless duplicates are collapsed)
+    pub async fn search_documents_with_duplicates(&self, user_id: Uuid, search_request: &SearchRequest) -> Result<Vec<(Document, Vec<Uuid>)>> {
+        let collapse = search_request.collapse_duplicates.unwrap_or(false);
+
+        let mut query = QueryBuilder::<Postgres>::new("");
+        if collapse {
+            push_collapse_start(&mut query);
+        }
+        query.push("SELECT ");
         query.push(DOCUMENT_FIELDS);
-        query.push(" FROM documents WHERE user_id = ");
-        query.push_bind(user_id);
+        if collapse {
+            query.push(CONTENT_KEY_FIELD);
+        }
+        query.push(" FROM documents WHERE 1=1");
+        apply_search_access_filter(&mut query, user_id, UserRole::User, search_request.include_shared.unwrap_or(false));
 
         // Add search conditions
         if !search_request.query.trim().is_empty() {
-            query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ plainto_tsquery('english', ");
-            query.push_bind(&search_request.query);
-            query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ plainto_tsquery('english', ");
-            query.push_bind(&search_request.query);
-            query.push("))");
+            let config = search_request.text_search_config();
+            query.push(" AND (");
+            push_text_match(&mut query, &SearchMode::Simple, config, &search_request.query);
+            if search_request.include_notes.unwrap_or(false) {
+                push_note_match(&mut query, user_id, &SearchMode::Simple, config, &search_request.query);
+            }
+            query.push(")");
         }
 
         // Add label filtering (tags param contains label names)
@@ -41,6 +63,11 @@ impl Database {
             }
         }
 
+        apply_document_date_range(&mut query, search_request.document_date_from, search_request.document_date_to);
+
+        if collapse {
+            push_collapse_end(&mut query);
+        }
         query.push(" ORDER BY created_at DESC");
         
         let limit = search_request.limit.unwrap_or(25);
@@ -48,7 +75,14 @@ impl Database {
         apply_pagination(&mut query, limit, offset);
 
         let rows = query.build().fetch_all(&self.pool).await?;
-        Ok(rows.iter().map(map_row_to_document).collect())
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let document = map_row_to_document(row);
+                let duplicates = collapsed_duplicate_ids(row, document.id);
+                (document, duplicates)
+            })
+            .collect())
     }
 
     /// Enhanced search with snippets and ranking
@@ -59,74 +93,47 @@ impl Database {
     /// Enhanced search with role-based access control
     pub async fn enhanced_search_documents_with_role(&self, user_id: Uuid, user_role: UserRole, search_request: &SearchRequest) -> Result<Vec<EnhancedDocumentResponse>> {
         let search_query = search_request.query.trim();
+        let search_mode = search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple);
+        let include_notes = search_request.include_notes.unwrap_or(false);
+        let config = search_request.text_search_config();
         let include_snippets = search_request.include_snippets.unwrap_or(true);
         let snippet_length = search_request.snippet_length.unwrap_or(200) as usize;
+        let collapse = search_request.collapse_duplicates.unwrap_or(false);
 
-        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        let mut query = QueryBuilder::<Postgres>::new("");
+        if collapse {
+            push_collapse_start(&mut query);
+        }
+        query.push("SELECT ");
         query.push(DOCUMENT_FIELDS);
+        if collapse {
+            query.push(CONTENT_KEY_FIELD);
+        }
         
         // Add search ranking if there's a query
         if !search_query.is_empty() {
-            match search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple) {
-                SearchMode::Simple => {
-                    query.push(", ts_rank(to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')), plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(")) as search_rank");
-                }
-                SearchMode::Phrase => {
-                    query.push(", ts_rank(to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')), phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(")) as search_rank");
-                }
-                SearchMode::Boolean => {
-                    query.push(", ts_rank(to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')), to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(")) as search_rank");
-                }
-                SearchMode::Fuzzy => {
-                    query.push(", similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), ");
-                    query.push_bind(search_query);
-                    query.push(") as search_rank");
-                }
+            query.push(", ");
+            push_text_rank(&mut query, search_mode, config, search_query);
+            if include_notes {
+                push_note_rank(&mut query, user_id, search_mode, config, search_query);
             }
+            query.push(" as search_rank");
         } else {
             query.push(", 0.0 as search_rank");
         }
 
         query.push(" FROM documents WHERE 1=1");
 
-        apply_role_based_filter(&mut query, user_id, user_role);
+        apply_search_access_filter(&mut query, user_id, user_role, search_request.include_shared.unwrap_or(false));
 
         // Add search conditions
         if !search_query.is_empty() {
-            match search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple) {
-                SearchMode::Simple => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Phrase => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Boolean => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Fuzzy => {
-                    query.push(" AND similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), ");
-                    query.push_bind(search_query);
-                    query.push(") > 0.3");
-                }
+            query.push(" AND (");
+            push_text_match(&mut query, search_mode, config, search_query);
+            if include_notes {
+                push_note_match(&mut query, user_id, search_mode, config, search_query);
             }
+            query.push(")");
         }
 
         // Add label filtering (tags param contains label names)
@@ -146,6 +153,11 @@ impl Database {
             }
         }
 
+        apply_document_date_range(&mut query, search_request.document_date_from, search_request.document_date_to);
+
+        if collapse {
+            push_collapse_end(&mut query);
+        }
         query.push(" ORDER BY search_rank DESC, created_at DESC");
         
         let limit = search_request.limit.unwrap_or(25);
@@ -158,9 +170,10 @@ impl Database {
         for row in rows {
             let document = map_row_to_document(&row);
             let search_rank: f32 = row.try_get("search_rank").unwrap_or(0.0);
+            let duplicate_ids = collapsed_duplicate_ids(&row, document.id);
 
             let snippets = if include_snippets && !search_query.is_empty() {
-                self.generate_snippets(&document, search_query, snippet_length).await
+                self.generate_snippets(&document, search_query, snippet_length, search_request.ignore_accents.unwrap_or(true)).await
             } else {
                 Vec::new()
             };
@@ -180,14 +193,18 @@ impl Database {
                 ocr_status: document.ocr_status,
                 search_rank: Some(search_rank),
                 snippets,
+                is_owner: document.user_id == user_id,
+                duplicate_count: duplicate_ids.len() as i64,
+                duplicate_ids,
             });
         }
 
         Ok(results)
     }
 
-    /// Generates search snippets with highlighted matches
-    pub async fn generate_snippets(&self, document: &Document, search_query: &str, snippet_length: usize) -> Vec<SearchSnippet> {
+    /// Generates search snippets with highlighted matches, matching terms
+    /// regardless of case and, with `ignore_accents`, of accents
+    pub async fn generate_snippets(&self, document: &Document, search_query: &str, snippet_length: usize, ignore_accents: bool) -> Vec<SearchSnippet> {
         let mut snippets = Vec::new();
         let search_terms: Vec<&str> = search_query.split_whitespace().collect();
 
@@ -202,13 +219,18 @@ impl Database {
                 continue;
             }
 
-            let text_lower = text.to_lowercase();
+            let folded = FoldedText::new(text, ignore_accents);
             for term in &search_terms {
-                let term_lower = term.to_lowercase();
+                let folded_term = FoldedText::new(term, ignore_accents).text;
+                if folded_term.is_empty() {
+                    continue;
+                }
                 let mut start_pos = 0;
 
-                while let Some(match_pos) = text_lower[start_pos..].find(&term_lower) {
-                    let absolute_match_pos = start_pos + match_pos;
+                while let Some(match_pos) = folded.text[start_pos..].find(&folded_term) {
+                    let folded_match_pos = start_pos + match_pos;
+                    let (absolute_match_pos, match_end) =
+                        folded.original_range(folded_match_pos, folded_match_pos + folded_term.len());
                     
                     // Calculate snippet boundaries
                     let snippet_start = if absolute_match_pos >= snippet_length / 2 {
@@ -230,7 +252,7 @@ impl Database {
                     
                     // Calculate highlight range relative to snippet
                     let highlight_start = absolute_match_pos - snippet_start;
-                    let highlight_end = highlight_start + term.len();
+                    let highlight_end = highlight_start + (match_end - absolute_match_pos);
 
                     let highlight_ranges = vec![HighlightRange {
                         start: highlight_start as i32,
@@ -244,7 +266,7 @@ impl Database {
                         highlight_ranges,
                     });
 
-                    start_pos = absolute_match_pos + term.len();
+                    start_pos = folded_match_pos + folded_term.len();
                     
                     // Limit snippets per term
                     if snippets.len() >= 3 {
@@ -261,42 +283,117 @@ impl Database {
 
     /// Counts total matching documents for pagination (without applying LIMIT/OFFSET)
     pub async fn count_search_documents(&self, user_id: Uuid, user_role: UserRole, search_request: &SearchRequest) -> Result<i64> {
-        let search_query = search_request.query.trim();
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM (SELECT ");
+        push_counted_result(&mut query, search_request);
+        query.push(" FROM documents WHERE 1=1");
+        push_search_conditions(&mut query, user_id, user_role, search_request);
+        query.push(") AS matches");
+
+        let row: (i64,) = query.build_query_as().fetch_one(&self.pool).await?;
+        Ok(row.0)
+    }
+
+    /// Counts matching documents up to `max_results`. Past that an exact count
+    /// isn't worth its cost, so the total is the planner's row estimate (at
+    /// least `max_results + 1`) and the count is marked capped and estimated.
+    pub async fn count_search_documents_capped(
+        &self,
+        user_id: Uuid,
+        user_role: UserRole,
+        search_request: &SearchRequest,
+        max_results: i64,
+    ) -> Result<SearchMatchCount> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM (SELECT ");
+        push_counted_result(&mut query, search_request);
+        query.push(" FROM documents WHERE 1=1");
+        push_search_conditions(&mut query, user_id, user_role, search_request);
+        query.push(" LIMIT ");
+        query.push_bind(max_results + 1);
+        query.push(") AS probe");
+
+        let (probed,): (i64,) = query.build_query_as().fetch_one(&self.pool).await?;
+        if probed <= max_results {
+            return Ok(SearchMatchCount { total: probed, capped: false, total_is_estimate: false });
+        }
+
+        let mut explain = QueryBuilder::<Postgres>::new("EXPLAIN SELECT ");
+        push_counted_result(&mut explain, search_request);
+        explain.push(" FROM documents WHERE 1=1");
+        push_search_conditions(&mut explain, user_id, user_role, search_request);
+        let estimate = match explain.build_query_scalar::<String>().fetch_optional(&self.pool).await {
+            Ok(plan) => plan.as_deref().and_then(plan_row_estimate).unwrap_or(0),
+            Err(e) => {
+                warn!("Failed to estimate search result count: {}", e);
+                0
+            }
+        };
 
-        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM documents WHERE 1=1");
+        Ok(SearchMatchCount {
+            total: estimate.max(max_results + 1),
+            capped: true,
+            total_is_estimate: true,
+        })
+    }
 
-        apply_role_based_filter(&mut query, user_id, user_role);
+    /// Lines of the `EXPLAIN` plan of a search's conditions. With
+    /// `prefer_indexes`, sequential scans are discouraged for the plan, which
+    /// shows which index a search would use once the table is large enough.
+    pub async fn explain_search(
+        &self,
+        user_id: Uuid,
+        user_role: UserRole,
+        search_request: &SearchRequest,
+        prefer_indexes: bool,
+    ) -> Result<Vec<String>> {
+        let mut explain = QueryBuilder::<Postgres>::new("EXPLAIN SELECT documents.id FROM documents WHERE 1=1");
+        push_search_conditions(&mut explain, user_id, user_role, search_request);
+
+        let mut tx = self.pool.begin().await?;
+        if prefer_indexes {
+            sqlx::query("SET LOCAL enable_seqscan = off").execute(&mut *tx).await?;
+        }
+        let plan = explain.build_query_scalar::<String>().fetch_all(&mut *tx).await?;
+        tx.rollback().await?;
+
+        Ok(plan)
+    }
+
+    /// Sends the metadata of every document a search matches to `rows`, one row
+    /// at a time in search order, without pagination. Stops early once the
+    /// receiver is dropped.
+    pub async fn export_search_documents(
+        &self,
+        user_id: Uuid,
+        user_role: UserRole,
+        search_request: &SearchRequest,
+        rows: &mpsc::Sender<Result<SearchExportRow>>,
+    ) -> Result<()> {
+        let search_query = search_request.query.trim();
+        let search_mode = search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple);
+        let include_notes = search_request.include_notes.unwrap_or(false);
+        let config = search_request.text_search_config();
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"SELECT documents.id, documents.filename, documents.original_filename, documents.mime_type,
+                      documents.file_size, documents.created_at, documents.document_date, documents.ocr_status,
+                      documents.ocr_confidence, documents.ocr_word_count, documents.source_type,
+                      (SELECT string_agg(l.name, '; ' ORDER BY l.name)
+                       FROM document_labels dl JOIN labels l ON l.id = dl.label_id
+                       WHERE dl.document_id = documents.id) AS labels,
+                      (SELECT s.name FROM sources s WHERE s.id = documents.source_id) AS source_name
+               FROM documents WHERE 1=1"#
+        );
+
+        apply_search_access_filter(&mut query, user_id, user_role, search_request.include_shared.unwrap_or(false));
 
         // Add search conditions (same as enhanced_search_documents_with_role)
         if !search_query.is_empty() {
-            match search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple) {
-                SearchMode::Simple => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Phrase => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Boolean => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Fuzzy => {
-                    query.push(" AND similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), ");
-                    query.push_bind(search_query);
-                    query.push(") > 0.3");
-                }
+            query.push(" AND (");
+            push_text_match(&mut query, search_mode, config, search_query);
+            if include_notes {
+                push_note_match(&mut query, user_id, search_mode, config, search_query);
             }
+            query.push(")");
         }
 
         // Add label filtering (tags param contains label names)
@@ -317,7 +414,184 @@ impl Database {
             }
         }
 
-        let row: (i64,) = query.build_query_as().fetch_one(&self.pool).await?;
-        Ok(row.0)
+        apply_document_date_range(&mut query, search_request.document_date_from, search_request.document_date_to);
+
+        // Same order as the search results
+        query.push(" ORDER BY ");
+        if !search_query.is_empty() {
+            push_text_rank(&mut query, search_mode, config, search_query);
+            if include_notes {
+                push_note_rank(&mut query, user_id, search_mode, config, search_query);
+            }
+            query.push(" DESC, ");
+        }
+        query.push("documents.created_at DESC");
+
+        let mut results = query.build_query_as::<SearchExportRow>().fetch(&self.pool);
+        while let Some(row) = results.try_next().await? {
+            if rows.send(Ok(row)).await.is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds documents similar to `document_id` within the same owner's library.
+    ///
+    /// The source document's `term_count` most frequent lexemes are read straight
+    /// from its tsvector and OR-ed into a tsquery, which matches other documents
+    /// through the full-text GIN index. The tsquery is bound as a parameter, so
+    /// lexemes holding quotes or backslashes are matched as they are. Documents
+    /// whose filenames are trigram-similar (at least `min_filename_similarity`)
+    /// are included even without shared terms.
+    pub async fn find_related_documents(
+        &self,
+        document_id: Uuid,
+        term_count: i64,
+        min_filename_similarity: f32,
+        limit: i64,
+    ) -> Result<Vec<RelatedDocument>> {
+        let terms: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT term.lexeme
+            FROM documents,
+                 unnest(to_tsvector('english_unaccent', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')))
+                     AS term(lexeme, positions, weights)
+            WHERE documents.id = $1 AND length(term.lexeme) > 2
+            ORDER BY COALESCE(array_length(term.positions, 1), 1) DESC, term.lexeme
+            LIMIT $2
+            "#
+        )
+        .bind(document_id)
+        .bind(term_count)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let related = sqlx::query_as::<_, RelatedDocument>(
+            r#"
+            WITH source AS (
+                SELECT id, user_id, original_filename
+                FROM documents
+                WHERE id = $1
+            ),
+            terms AS (
+                SELECT NULLIF($2, '')::tsquery AS query
+            ),
+            candidates AS (
+                SELECT d.id, d.filename, d.original_filename, d.mime_type, d.file_size, d.created_at,
+                       COALESCE(ts_rank(
+                           to_tsvector('english_unaccent', COALESCE(d.content, '') || ' ' || COALESCE(d.ocr_text, '')),
+                           terms.query,
+                           32
+                       ), 0)::real AS text_score,
+                       similarity(d.original_filename, source.original_filename)::real AS filename_similarity
+                FROM documents d
+                CROSS JOIN source
+                CROSS JOIN terms
+                WHERE d.user_id = source.user_id
+                  AND d.id <> source.id
+                  AND (
+                      to_tsvector('english_unaccent', COALESCE(d.content, '') || ' ' || COALESCE(d.ocr_text, '')) @@ terms.query
+                      OR similarity(d.original_filename, source.original_filename) >= $3
+                  )
+            )
+            SELECT id, filename, original_filename, mime_type, file_size, created_at,
+                   GREATEST(text_score, filename_similarity) AS score,
+                   text_score, filename_similarity
+            FROM candidates
+            ORDER BY score DESC, created_at DESC
+            LIMIT $4
+            "#
+        )
+        .bind(document_id)
+        .bind(any_lexeme_tsquery(&terms))
+        .bind(min_filename_similarity)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(related)
     }
-}
\ No newline at end of file
+}
+
+/// Groups documents with the same file content; documents without a hash stand alone
+const CONTENT_KEY_FIELD: &str = ", COALESCE(documents.file_hash, documents.id::text) AS content_key";
+
+/// Opens the wrapper that collapses duplicates: of each group of matches with
+/// the same content key only the most recent is kept, with the IDs of the whole
+/// group. The matching query follows, then [`push_collapse_end`].
+fn push_collapse_start(query: &mut QueryBuilder<Postgres>) {
+    query.push(
+        "SELECT * FROM (SELECT matches.*, \
+         ROW_NUMBER() OVER (PARTITION BY content_key ORDER BY created_at DESC, id) AS duplicate_position, \
+         array_agg(id) OVER (PARTITION BY content_key) AS duplicate_group_ids FROM (",
+    );
+}
+
+fn push_collapse_end(query: &mut QueryBuilder<Postgres>) {
+    query.push(") AS matches) AS collapsed WHERE duplicate_position = 1");
+}
+
+/// IDs of the duplicates collapsed into `document_id`, empty for uncollapsed results
+fn collapsed_duplicate_ids(row: &sqlx::postgres::PgRow, document_id: Uuid) -> Vec<Uuid> {
+    row.try_get::<Vec<Uuid>, _>("duplicate_group_ids")
+        .map(|ids| ids.into_iter().filter(|id| *id != document_id).collect())
+        .unwrap_or_default()
+}
+
+/// What a search count counts: documents, or distinct contents when duplicates are collapsed
+fn push_counted_result(query: &mut QueryBuilder<Postgres>, search_request: &SearchRequest) {
+    if search_request.collapse_duplicates.unwrap_or(false) {
+        query.push("DISTINCT COALESCE(documents.file_hash, documents.id::text)");
+    } else {
+        query.push("1");
+    }
+}
+
+/// Adds the access, text, label, MIME type and date conditions of a search
+fn push_search_conditions(query: &mut QueryBuilder<Postgres>, user_id: Uuid, user_role: UserRole, search_request: &SearchRequest) {
+    let search_query = search_request.query.trim();
+    let search_mode = search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple);
+    let include_notes = search_request.include_notes.unwrap_or(false);
+    let config = search_request.text_search_config();
+
+    apply_search_access_filter(query, user_id, user_role, search_request.include_shared.unwrap_or(false));
+
+    // Add search conditions (same as enhanced_search_documents_with_role)
+    if !search_query.is_empty() {
+        query.push(" AND (");
+        push_text_match(query, search_mode, config, search_query);
+        if include_notes {
+            push_note_match(query, user_id, search_mode, config, search_query);
+        }
+        query.push(")");
+    }
+
+    // Add label filtering (tags param contains label names)
+    if let Some(ref tags) = search_request.tags {
+        if !tags.is_empty() {
+            query.push(" AND documents.id IN (SELECT dl.document_id FROM document_labels dl JOIN labels l ON dl.label_id = l.id WHERE l.name = ANY(");
+            query.push_bind(tags);
+            query.push("))");
+        }
+    }
+
+    // Add MIME type filtering
+    if let Some(ref mime_types) = search_request.mime_types {
+        if !mime_types.is_empty() {
+            query.push(" AND mime_type = ANY(");
+            query.push_bind(mime_types);
+            query.push(")");
+        }
+    }
+
+    apply_document_date_range(query, search_request.document_date_from, search_request.document_date_to);
+}
+
+/// Row count the planner expects, read from the top line of a text `EXPLAIN`
+/// such as `Seq Scan on documents  (cost=0.00..35.50 rows=2550 width=4)`
+fn plan_row_estimate(plan_line: &str) -> Option<i64> {
+    let rows = plan_line.split("rows=").nth(1)?;
+    rows.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
@@ -0,0 +1,125 @@
+//! Optional backup taken with `pg_dump` right before pending migrations run.
+//!
+//! With `BACKUP_BEFORE_MIGRATE` the schema, and optionally the rows of a few
+//! small tables that are painful to recreate, are written to timestamped files
+//! in `MIGRATION_BACKUP_DIR`. They are a reference for rolling a failed upgrade
+//! back by hand, not a full backup: documents and stored files are left out.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+/// Tables whose rows are included with `MIGRATION_BACKUP_INCLUDE_DATA`
+pub const SNAPSHOT_TABLES: &[&str] = &["users", "settings", "sources"];
+
+/// Longest a single `pg_dump` run may take
+const PG_DUMP_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// What a backup file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupKind {
+    /// Every table, index, function and constraint, without rows
+    Schema,
+    /// Rows of [`SNAPSHOT_TABLES`]
+    Data,
+}
+
+impl BackupKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupKind::Schema => "schema",
+            BackupKind::Data => "data",
+        }
+    }
+}
+
+/// Whether migrations should be preceded by a backup: only when some are
+/// pending and the database isn't new (a fresh database has nothing to lose)
+pub fn needs_backup(applied: &[i64], available: &[i64]) -> bool {
+    !applied.is_empty() && available.iter().any(|version| !applied.contains(version))
+}
+
+/// File a backup is written to, named after when it was taken and the latest
+/// migration applied to the database at that time
+pub fn backup_path(dir: &Path, kind: BackupKind, taken_at: DateTime<Utc>, schema_version: i64) -> PathBuf {
+    dir.join(format!(
+        "readur-{}-{}-v{}.sql",
+        kind.as_str(),
+        taken_at.format("%Y%m%d-%H%M%S"),
+        schema_version
+    ))
+}
+
+/// Arguments making `pg_dump` write a plain SQL backup of `kind` to `output`
+pub fn pg_dump_args(database_url: &str, kind: BackupKind, output: &Path) -> Vec<String> {
+    let mut args = vec![
+        format!("--dbname={}", database_url),
+        format!("--file={}", output.display()),
+        "--no-owner".to_string(),
+        "--no-privileges".to_string(),
+    ];
+    match kind {
+        BackupKind::Schema => args.push("--schema-only".to_string()),
+        BackupKind::Data => {
+            args.push("--data-only".to_string());
+            args.extend(SNAPSHOT_TABLES.iter().map(|table| format!("--table={}", table)));
+        }
+    }
+    args
+}
+
+/// Backs up the schema, and with `include_data` the rows of [`SNAPSHOT_TABLES`],
+/// into `dir`, which is created if needed. Returns the files written.
+pub async fn back_up_before_migrate(
+    database_url: &str,
+    dir: &str,
+    include_data: bool,
+    schema_version: i64,
+) -> Result<Vec<PathBuf>> {
+    let dir = Path::new(dir);
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| anyhow!("Failed to create backup directory {}: {}", dir.display(), e))?;
+
+    let taken_at = Utc::now();
+    let kinds: &[BackupKind] = if include_data {
+        &[BackupKind::Schema, BackupKind::Data]
+    } else {
+        &[BackupKind::Schema]
+    };
+
+    let mut written = Vec::with_capacity(kinds.len());
+    for &kind in kinds {
+        let path = backup_path(dir, kind, taken_at, schema_version);
+        run_pg_dump(database_url, kind, &path).await?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+async fn run_pg_dump(database_url: &str, kind: BackupKind, output: &Path) -> Result<()> {
+    let child = tokio::process::Command::new("pg_dump")
+        .args(pg_dump_args(database_url, kind, output))
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start pg_dump: {}", e))?;
+
+    let finished = tokio::time::timeout(PG_DUMP_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| anyhow!("pg_dump timed out after {:?}", PG_DUMP_TIMEOUT))??;
+    if !finished.status.success() {
+        // A partial file would look like a usable backup
+        let _ = tokio::fs::remove_file(output).await;
+        return Err(anyhow!(
+            "pg_dump of the {} failed (exit code {:?}): {}",
+            kind.as_str(),
+            finished.status.code(),
+            String::from_utf8_lossy(&finished.stderr).trim()
+        ));
+    }
+    Ok(())
+}
@@ -16,8 +16,13 @@ pub mod ignored_files;
 pub mod constraint_validation;
 pub mod ocr_retry;
 pub mod shared_links;
+pub mod document_shares;
+pub mod document_notes;
 pub mod comments;
 pub mod api_keys;
+pub mod migration_backup;
+pub mod schema_check;
+pub mod search_index;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabasePoolHealth {
@@ -200,237 +205,9 @@ impl Database {
         unreachable!()
     }
 
+    /// Runs the SQLx migrations; the schema is defined only by ./migrations
     pub async fn migrate(&self) -> Result<()> {
-        // Create extensions
-        sqlx::query(r#"CREATE EXTENSION IF NOT EXISTS "uuid-ossp""#)
-            .execute(&self.pool)
-            .await?;
-        
-        sqlx::query(r#"CREATE EXTENSION IF NOT EXISTS "pg_trgm""#)
-            .execute(&self.pool)
-            .await?;
-        
-        // Create users table with OIDC support
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS users (
-                id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
-                username VARCHAR(255) UNIQUE NOT NULL,
-                email VARCHAR(255) UNIQUE NOT NULL,
-                password_hash VARCHAR(255),
-                role VARCHAR(20) DEFAULT 'user',
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW(),
-                oidc_subject VARCHAR(255),
-                oidc_issuer VARCHAR(255),
-                oidc_email VARCHAR(255),
-                auth_provider VARCHAR(50) DEFAULT 'local',
-                CONSTRAINT check_auth_method CHECK (
-                    (auth_provider = 'local' AND password_hash IS NOT NULL) OR 
-                    (auth_provider = 'oidc' AND oidc_subject IS NOT NULL AND oidc_issuer IS NOT NULL)
-                ),
-                CONSTRAINT check_user_role CHECK (role IN ('admin', 'user'))
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-        
-        // Create indexes for OIDC
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_users_oidc_subject_issuer ON users(oidc_subject, oidc_issuer)"#)
-            .execute(&self.pool)
-            .await?;
-            
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_users_auth_provider ON users(auth_provider)"#)
-            .execute(&self.pool)
-            .await?;
-        
-        
-        // Create documents table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS documents (
-                id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
-                filename VARCHAR(255) NOT NULL,
-                original_filename VARCHAR(255) NOT NULL,
-                file_path VARCHAR(500) NOT NULL,
-                file_size BIGINT NOT NULL,
-                mime_type VARCHAR(100) NOT NULL,
-                content TEXT,
-                ocr_text TEXT,
-                tags TEXT[] DEFAULT '{}',
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW(),
-                user_id UUID REFERENCES users(id) ON DELETE CASCADE
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-        
-        // Create indexes
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_documents_user_id ON documents(user_id)"#)
-            .execute(&self.pool)
-            .await?;
-        
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_documents_filename ON documents(filename)"#)
-            .execute(&self.pool)
-            .await?;
-        
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_documents_mime_type ON documents(mime_type)"#)
-            .execute(&self.pool)
-            .await?;
-        
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_documents_tags ON documents USING GIN(tags)"#)
-            .execute(&self.pool)
-            .await?;
-        
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_documents_content_search ON documents USING GIN(to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')))"#)
-            .execute(&self.pool)
-            .await?;
-        
-        // Enhanced indexes for substring matching and similarity
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_documents_filename_trgm ON documents USING GIN(filename gin_trgm_ops)"#)
-            .execute(&self.pool)
-            .await?;
-        
-        sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_documents_content_trgm ON documents USING GIN((COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')) gin_trgm_ops)"#)
-            .execute(&self.pool)
-            .await?;
-        
-        // Create settings table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS settings (
-                id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
-                user_id UUID REFERENCES users(id) ON DELETE CASCADE UNIQUE,
-                ocr_language VARCHAR(10) DEFAULT 'eng',
-                concurrent_ocr_jobs INT DEFAULT 4,
-                ocr_timeout_seconds INT DEFAULT 300,
-                max_file_size_mb INT DEFAULT 50,
-                allowed_file_types TEXT[] DEFAULT ARRAY['pdf', 'png', 'jpg', 'jpeg', 'tiff', 'bmp', 'txt'],
-                auto_rotate_images BOOLEAN DEFAULT TRUE,
-                enable_image_preprocessing BOOLEAN DEFAULT TRUE,
-                search_results_per_page INT DEFAULT 25,
-                search_snippet_length INT DEFAULT 200,
-                fuzzy_search_threshold REAL DEFAULT 0.8,
-                retention_days INT,
-                enable_auto_cleanup BOOLEAN DEFAULT FALSE,
-                enable_compression BOOLEAN DEFAULT FALSE,
-                memory_limit_mb INT DEFAULT 512,
-                cpu_priority VARCHAR(10) DEFAULT 'normal',
-                enable_background_ocr BOOLEAN DEFAULT TRUE,
-                ocr_page_segmentation_mode INT DEFAULT 3,
-                ocr_engine_mode INT DEFAULT 3,
-                ocr_min_confidence REAL DEFAULT 30.0,
-                ocr_dpi INT DEFAULT 300,
-                ocr_enhance_contrast BOOLEAN DEFAULT TRUE,
-                ocr_remove_noise BOOLEAN DEFAULT TRUE,
-                ocr_detect_orientation BOOLEAN DEFAULT TRUE,
-                ocr_whitelist_chars TEXT,
-                ocr_blacklist_chars TEXT,
-                webdav_enabled BOOLEAN DEFAULT FALSE,
-                webdav_server_url TEXT,
-                webdav_username TEXT,
-                webdav_password TEXT,
-                webdav_watch_folders TEXT[] DEFAULT ARRAY['/Documents']::TEXT[],
-                webdav_file_extensions TEXT[] DEFAULT ARRAY['pdf', 'png', 'jpg', 'jpeg', 'tiff', 'bmp', 'txt']::TEXT[],
-                webdav_auto_sync BOOLEAN DEFAULT FALSE,
-                webdav_sync_interval_minutes INTEGER DEFAULT 60,
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                updated_at TIMESTAMPTZ DEFAULT NOW()
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-        
-        // Run OCR queue migration - execute each statement separately
-        self.run_ocr_queue_migration().await?;
-
-        Ok(())
-    }
-
-    async fn run_ocr_queue_migration(&self) -> Result<()> {
-        // Create OCR queue table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS ocr_queue (
-                id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
-                document_id UUID REFERENCES documents(id) ON DELETE CASCADE,
-                status VARCHAR(20) DEFAULT 'pending',
-                priority INT DEFAULT 5,
-                attempts INT DEFAULT 0,
-                max_attempts INT DEFAULT 3,
-                created_at TIMESTAMPTZ DEFAULT NOW(),
-                started_at TIMESTAMPTZ,
-                completed_at TIMESTAMPTZ,
-                error_message TEXT,
-                worker_id VARCHAR(100),
-                processing_time_ms INT,
-                file_size BIGINT,
-                CONSTRAINT check_status CHECK (status IN ('pending', 'processing', 'completed', 'failed', 'cancelled'))
-            )
-            "#
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create indexes
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_ocr_queue_status ON ocr_queue(status, priority DESC, created_at)")
-            .execute(&self.pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_ocr_queue_document_id ON ocr_queue(document_id)")
-            .execute(&self.pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_ocr_queue_worker ON ocr_queue(worker_id) WHERE status = 'processing'")
-            .execute(&self.pool)
-            .await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_ocr_queue_created_at ON ocr_queue(created_at) WHERE status = 'pending'")
-            .execute(&self.pool)
-            .await?;
-
-        // Add columns to documents table
-        sqlx::query("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_status VARCHAR(20) DEFAULT 'pending'")
-            .execute(&self.pool)
-            .await?;
-
-        sqlx::query("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_error TEXT")
-            .execute(&self.pool)
-            .await?;
-
-        sqlx::query("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_completed_at TIMESTAMPTZ")
-            .execute(&self.pool)
-            .await?;
-
-        // Create metrics table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS ocr_metrics (
-                id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
-                date DATE DEFAULT CURRENT_DATE,
-                hour INT DEFAULT EXTRACT(HOUR FROM NOW()),
-                total_processed INT DEFAULT 0,
-                total_failed INT DEFAULT 0,
-                total_retried INT DEFAULT 0,
-                avg_processing_time_ms INT,
-                max_processing_time_ms INT,
-                min_processing_time_ms INT,
-                queue_depth INT,
-                active_workers INT,
-                UNIQUE(date, hour)
-            )
-            "#
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // NOTE: get_ocr_queue_stats() function is now managed by SQLx migrations
-        // See migrations/20250708000001_simplify_ocr_queue_stats.sql for current implementation
-
+        sqlx::migrate!("./migrations").run(&self.pool).await?;
         Ok(())
     }
 }
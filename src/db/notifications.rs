@@ -68,6 +68,15 @@ impl Database {
         Ok(notifications)
     }
 
+    pub async fn count_user_notifications(&self, user_id: Uuid) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) as count FROM notifications WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get("count"))
+    }
+
     pub async fn get_unread_notification_count(&self, user_id: Uuid) -> Result<i64> {
         let row = sqlx::query("SELECT COUNT(*) as count FROM notifications WHERE user_id = $1 AND read = false")
             .bind(user_id)
@@ -115,4 +124,102 @@ impl Database {
             recent_notifications,
         })
     }
-}
\ No newline at end of file
+
+    /// Delivers a notification according to the user's delivery mode.
+    ///
+    /// In digest mode everything except errors is queued for the next digest and
+    /// `None` is returned; errors always go out immediately.
+    pub async fn deliver_notification(&self, user_id: Uuid, notification: &crate::models::CreateNotification) -> Result<Option<crate::models::Notification>> {
+        let digest = notification.notification_type != "error"
+            && self.get_user_settings(user_id).await?
+                .map(|settings| settings.notification_delivery_mode == "digest")
+                .unwrap_or(false);
+
+        if !digest {
+            return self.create_notification(user_id, notification).await.map(Some);
+        }
+
+        sqlx::query(
+            r#"INSERT INTO pending_notifications (user_id, notification_type, title, message, action_url, metadata)
+               VALUES ($1, $2, $3, $4, $5, $6)"#
+        )
+        .bind(user_id)
+        .bind(&notification.notification_type)
+        .bind(&notification.title)
+        .bind(&notification.message)
+        .bind(&notification.action_url)
+        .bind(&notification.metadata)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(None)
+    }
+
+    /// Pending notifications whose digest is due, ordered by user, type and age.
+    ///
+    /// A (user, type) group is due once its oldest entry is older than the user's
+    /// digest interval, or as soon as the user switches back to immediate delivery.
+    pub async fn get_due_pending_notifications(&self) -> Result<Vec<crate::models::PendingNotification>> {
+        let pending = sqlx::query_as::<_, crate::models::PendingNotification>(
+            r#"SELECT p.id, p.user_id, p.notification_type, p.title, p.message, p.action_url, p.metadata, p.created_at
+               FROM pending_notifications p
+               JOIN (
+                   SELECT pn.user_id, pn.notification_type
+                   FROM pending_notifications pn
+                   LEFT JOIN settings s ON s.user_id = pn.user_id
+                   GROUP BY pn.user_id, pn.notification_type, s.notification_delivery_mode, s.notification_digest_interval_minutes
+                   HAVING COALESCE(s.notification_delivery_mode, 'immediate') <> 'digest'
+                       OR MIN(pn.created_at) <= NOW() - make_interval(mins => COALESCE(s.notification_digest_interval_minutes, 15))
+               ) due ON due.user_id = p.user_id AND due.notification_type = p.notification_type
+               ORDER BY p.user_id, p.notification_type, p.created_at"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(pending)
+    }
+
+    /// Replaces the given pending notifications with a single digest notification
+    pub async fn flush_pending_notifications(
+        &self,
+        user_id: Uuid,
+        digest: &crate::models::CreateNotification,
+        pending_ids: &[Uuid],
+    ) -> Result<crate::models::Notification> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"INSERT INTO notifications (user_id, notification_type, title, message, action_url, metadata)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id, user_id, notification_type, title, message, read, action_url, metadata, created_at"#
+        )
+        .bind(user_id)
+        .bind(&digest.notification_type)
+        .bind(&digest.title)
+        .bind(&digest.message)
+        .bind(&digest.action_url)
+        .bind(&digest.metadata)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM pending_notifications WHERE user_id = $1 AND id = ANY($2)")
+            .bind(user_id)
+            .bind(pending_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(crate::models::Notification {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            notification_type: row.get("notification_type"),
+            title: row.get("title"),
+            message: row.get("message"),
+            read: row.get("read"),
+            action_url: row.get("action_url"),
+            metadata: row.get("metadata"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
@@ -0,0 +1,280 @@
+//! Startup verification of the schema invariants the application relies on.
+//!
+//! Migrations are the only supported way to change the schema, but a database
+//! restored from an old dump or edited by hand can still end up missing
+//! columns or indexes. This check runs right after migrations and turns that
+//! situation into a clear startup failure instead of runtime query errors.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use super::Database;
+
+/// A column that must exist, with an idempotent statement that recreates it
+/// when the column can be safely added back without touching existing data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequiredColumn {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub repair: Option<&'static str>,
+}
+
+/// An index that must exist, with an idempotent statement that recreates it
+/// when rebuilding it cannot fail on existing data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequiredIndex {
+    pub name: &'static str,
+    pub table: &'static str,
+    pub repair: Option<&'static str>,
+}
+
+pub const REQUIRED_COLUMNS: &[RequiredColumn] = &[
+    RequiredColumn {
+        table: "documents",
+        column: "ocr_status",
+        repair: Some("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_status VARCHAR(20) DEFAULT 'pending'"),
+    },
+    RequiredColumn {
+        table: "documents",
+        column: "ocr_text",
+        repair: Some("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_text TEXT"),
+    },
+    RequiredColumn {
+        table: "documents",
+        column: "ocr_error",
+        repair: Some("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_error TEXT"),
+    },
+    RequiredColumn {
+        table: "documents",
+        column: "ocr_completed_at",
+        repair: Some("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_completed_at TIMESTAMPTZ"),
+    },
+    RequiredColumn {
+        table: "documents",
+        column: "ocr_retry_count",
+        repair: Some("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_retry_count INTEGER DEFAULT 0"),
+    },
+    RequiredColumn {
+        table: "documents",
+        column: "ocr_failure_reason",
+        repair: Some("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_failure_reason TEXT DEFAULT NULL"),
+    },
+    RequiredColumn {
+        table: "documents",
+        column: "ocr_hybrid_extraction",
+        repair: Some("ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_hybrid_extraction BOOLEAN NOT NULL DEFAULT FALSE"),
+    },
+    // The hash backs duplicate detection, so it cannot be recreated empty
+    RequiredColumn { table: "documents", column: "file_hash", repair: None },
+    RequiredColumn { table: "documents", column: "source_id", repair: None },
+    RequiredColumn {
+        table: "settings",
+        column: "ocr_max_pixels",
+        repair: Some("ALTER TABLE settings ADD COLUMN IF NOT EXISTS ocr_max_pixels BIGINT NOT NULL DEFAULT 40000000"),
+    },
+    RequiredColumn {
+        table: "settings",
+        column: "ocr_pdf_hybrid",
+        repair: Some("ALTER TABLE settings ADD COLUMN IF NOT EXISTS ocr_pdf_hybrid BOOLEAN NOT NULL DEFAULT FALSE"),
+    },
+    // Constrained by a CHECK that only its migration knows how to recreate
+    RequiredColumn { table: "settings", column: "notification_delivery_mode", repair: None },
+    RequiredColumn { table: "ocr_queue", column: "priority_class", repair: None },
+    RequiredColumn { table: "sources", column: "last_sync_at", repair: None },
+    RequiredColumn { table: "webdav_directories", column: "directory_etag", repair: None },
+    RequiredColumn { table: "pending_notifications", column: "notification_type", repair: None },
+];
+
+pub const REQUIRED_INDEXES: &[RequiredIndex] = &[
+    RequiredIndex {
+        name: "idx_documents_user_id",
+        table: "documents",
+        repair: Some("CREATE INDEX IF NOT EXISTS idx_documents_user_id ON documents(user_id)"),
+    },
+    RequiredIndex {
+        name: "idx_documents_source_id",
+        table: "documents",
+        repair: Some("CREATE INDEX IF NOT EXISTS idx_documents_source_id ON documents(source_id)"),
+    },
+    RequiredIndex {
+        name: "idx_ocr_queue_status",
+        table: "ocr_queue",
+        repair: Some("CREATE INDEX IF NOT EXISTS idx_ocr_queue_status ON ocr_queue(status, priority DESC, created_at)"),
+    },
+    RequiredIndex {
+        name: "idx_sources_user_id",
+        table: "sources",
+        repair: Some("CREATE INDEX IF NOT EXISTS idx_sources_user_id ON sources(user_id)"),
+    },
+    RequiredIndex {
+        name: "idx_webdav_directories_path",
+        table: "webdav_directories",
+        repair: Some("CREATE INDEX IF NOT EXISTS idx_webdav_directories_path ON webdav_directories(user_id, directory_path)"),
+    },
+    RequiredIndex {
+        name: "idx_notifications_user_id_created_at",
+        table: "notifications",
+        repair: Some("CREATE INDEX IF NOT EXISTS idx_notifications_user_id_created_at ON notifications(user_id, created_at DESC)"),
+    },
+    RequiredIndex {
+        name: "idx_ocr_queue_pending_priority_class",
+        table: "ocr_queue",
+        repair: Some("CREATE INDEX IF NOT EXISTS idx_ocr_queue_pending_priority_class ON ocr_queue(priority_class DESC, priority DESC, created_at) WHERE status = 'pending'"),
+    },
+    // Unique indexes can fail to build on data written while they were missing
+    RequiredIndex { name: "idx_documents_user_file_hash", table: "documents", repair: None },
+    RequiredIndex { name: "idx_ocr_queue_unique_pending_document", table: "ocr_queue", repair: None },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaIssue {
+    MissingColumn(RequiredColumn),
+    MissingIndex(RequiredIndex),
+}
+
+impl SchemaIssue {
+    pub fn repair_sql(&self) -> Option<&'static str> {
+        match self {
+            SchemaIssue::MissingColumn(column) => column.repair,
+            SchemaIssue::MissingIndex(index) => index.repair,
+        }
+    }
+
+    pub fn is_repairable(&self) -> bool {
+        self.repair_sql().is_some()
+    }
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaIssue::MissingColumn(column) => {
+                write!(f, "missing column {}.{}", column.table, column.column)
+            }
+            SchemaIssue::MissingIndex(index) => {
+                write!(f, "missing index {} on {}", index.name, index.table)
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaCheckError {
+    #[error(
+        "Database schema does not match what this version expects:\n{}\n\
+         Make sure all migrations in ./migrations have been applied (see the _sqlx_migrations table), \
+         or set SCHEMA_AUTO_REPAIR=true to recreate the columns and indexes that are safe to add back",
+        format_issues(.0)
+    )]
+    Mismatch(Vec<SchemaIssue>),
+    #[error(
+        "Database schema is still inconsistent after auto-repair:\n{}\n\
+         These problems need manual attention: restore the affected tables from a backup \
+         or re-run the migrations that create them",
+        format_issues(.0)
+    )]
+    Unrecoverable(Vec<SchemaIssue>),
+    #[error("Failed to inspect the database schema: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn format_issues(issues: &[SchemaIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| {
+            let hint = if issue.is_repairable() { "auto-repairable" } else { "manual fix required" };
+            format!("  - {} ({})", issue, hint)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compares the required invariants against the columns and indexes present in the database
+pub fn find_schema_issues(
+    columns: &HashSet<(String, String)>,
+    indexes: &HashSet<String>,
+) -> Vec<SchemaIssue> {
+    let missing_columns = REQUIRED_COLUMNS
+        .iter()
+        .filter(|required| !columns.contains(&(required.table.to_string(), required.column.to_string())))
+        .map(|required| SchemaIssue::MissingColumn(*required));
+
+    let missing_indexes = REQUIRED_INDEXES
+        .iter()
+        .filter(|required| !indexes.contains(required.name))
+        .map(|required| SchemaIssue::MissingIndex(*required));
+
+    missing_columns.chain(missing_indexes).collect()
+}
+
+impl Database {
+    /// Lists the required columns and indexes missing from the current schema
+    pub async fn find_schema_issues(&self) -> Result<Vec<SchemaIssue>, sqlx::Error> {
+        let columns: HashSet<(String, String)> = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT table_name::text, column_name::text
+            FROM information_schema.columns
+            WHERE table_schema = current_schema()
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        let indexes: HashSet<String> = sqlx::query_scalar::<_, String>(
+            "SELECT indexname::text FROM pg_indexes WHERE schemaname = current_schema()",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        Ok(find_schema_issues(&columns, &indexes))
+    }
+
+    /// Verifies the schema invariants, optionally repairing the issues that are safe to fix.
+    ///
+    /// Without `auto_repair` any issue is an error. With it, each repairable issue is fixed
+    /// in its own transaction, serialized across instances by an advisory lock, and the
+    /// schema is checked again; anything still missing is reported as unrecoverable.
+    pub async fn verify_schema(&self, auto_repair: bool) -> Result<(), SchemaCheckError> {
+        let issues = self.find_schema_issues().await?;
+        if issues.is_empty() {
+            return Ok(());
+        }
+
+        if !auto_repair {
+            return Err(SchemaCheckError::Mismatch(issues));
+        }
+
+        for issue in &issues {
+            let Some(sql) = issue.repair_sql() else {
+                tracing::warn!("Schema auto-repair: skipping {} (manual fix required)", issue);
+                continue;
+            };
+
+            let mut tx = self.pool.begin().await?;
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtext('readur_schema_repair'))")
+                .execute(&mut *tx)
+                .await?;
+            match sqlx::query(sql).execute(&mut *tx).await {
+                Ok(_) => {
+                    tx.commit().await?;
+                    tracing::warn!("Schema auto-repair: fixed {}", issue);
+                }
+                Err(e) => {
+                    tx.rollback().await?;
+                    tracing::error!("Schema auto-repair: failed to fix {}: {}", issue, e);
+                }
+            }
+        }
+
+        let remaining = self.find_schema_issues().await?;
+        if remaining.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaCheckError::Unrecoverable(remaining))
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! Per-user full-text indexes on documents, and a report on their health.
+//!
+//! The full-text indexes created by migrations hold every user's documents,
+//! so a user's search collects the matches of all tenants from the index
+//! before the `user_id` filter throws most of them away. With the `per_user`
+//! strategy, GIN indexes that start with `user_id` (through the btree_gin
+//! extension) are built next to them, and a search only reads the postings of
+//! its user. They are built and dropped concurrently at startup, so a large
+//! documents table stays writable while that happens.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::Row;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::documents::DOCUMENT_TEXT;
+use super::Database;
+use crate::models::{SearchRequest, UserRole};
+use crate::utils::text_search::DEFAULT_TEXT_SEARCH_CONFIG;
+
+/// Table size from which the per-user strategy is recommended to multi-user deployments
+pub const LARGE_TABLE_ROWS: i64 = 1_000_000;
+
+/// Share of dead rows from which a vacuum is recommended
+pub const DEAD_ROW_RATIO: f64 = 0.2;
+
+/// Age of the table statistics from which an analyze is recommended
+pub const STALE_STATISTICS_DAYS: i64 = 7;
+
+/// Size from which an index that searches never scan is reported
+pub const UNUSED_INDEX_MIN_BYTES: i64 = 100 * 1024 * 1024;
+
+/// How the full-text indexes of the documents table are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchIndexStrategy {
+    /// Only the indexes over everyone's documents
+    #[default]
+    Shared,
+    /// Also indexes led by `user_id`, so searches narrow to their user inside the index
+    PerUser,
+}
+
+impl SearchIndexStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchIndexStrategy::Shared => "shared",
+            SearchIndexStrategy::PerUser => "per_user",
+        }
+    }
+}
+
+impl FromStr for SearchIndexStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "shared" => Ok(SearchIndexStrategy::Shared),
+            "per_user" => Ok(SearchIndexStrategy::PerUser),
+            other => Err(format!("Invalid search index strategy '{}' (expected shared or per_user)", other)),
+        }
+    }
+}
+
+impl fmt::Display for SearchIndexStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Names and definitions of the per-user indexes. Their expressions are the
+/// ones searches use (see `push_text_match`): the text in the default
+/// configuration, and in the document's own language with accents ignored.
+pub fn per_user_search_indexes() -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "idx_documents_user_content_search",
+            format!("user_id, to_tsvector('{}', {})", DEFAULT_TEXT_SEARCH_CONFIG, DOCUMENT_TEXT),
+        ),
+        (
+            "idx_documents_user_content_search_language",
+            format!("user_id, to_tsvector(document_search_config(search_language, true), {})", DOCUMENT_TEXT),
+        ),
+    ]
+}
+
+/// A full-text index of the documents table
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchIndexInfo {
+    pub name: String,
+    /// False for an index whose concurrent build was interrupted; it is kept
+    /// up to date but never used
+    pub valid: bool,
+    pub size_bytes: i64,
+    /// Scans since the statistics were last reset
+    pub scans: i64,
+    pub definition: String,
+}
+
+/// State of the documents table and its full-text indexes
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchIndexHealth {
+    pub strategy: SearchIndexStrategy,
+    pub live_rows: i64,
+    pub dead_rows: i64,
+    /// Last manual or automatic analyze of the table
+    pub last_analyzed: Option<DateTime<Utc>>,
+    pub users: i64,
+    pub largest_user_documents: i64,
+    pub indexes: Vec<SearchIndexInfo>,
+    /// Per-user indexes the strategy calls for that don't exist
+    pub missing_indexes: Vec<String>,
+    /// Plan of a search in the largest user's documents, if there are any
+    pub sample_plan: Option<Vec<String>>,
+}
+
+impl SearchIndexHealth {
+    /// Whether the sample search reads one of the per-user indexes
+    pub fn sample_plan_uses_user_index(&self) -> bool {
+        self.sample_plan.as_ref().map_or(false, |plan| {
+            let names = per_user_search_indexes();
+            plan.iter().any(|line| names.iter().any(|(name, _)| line.contains(name)))
+        })
+    }
+
+    /// What to do about the problems found, most urgent first
+    pub fn advice(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut advice = Vec::new();
+
+        for index in self.indexes.iter().filter(|index| !index.valid) {
+            advice.push(format!(
+                "Index {} is invalid, probably from an interrupted concurrent build. Searches don't use it, but writes still maintain it: run REINDEX INDEX CONCURRENTLY {}",
+                index.name, index.name
+            ));
+        }
+
+        for name in &self.missing_indexes {
+            advice.push(format!(
+                "Per-user index {} is missing. It is built at startup, so check the startup log for why the build failed and restart Readur",
+                name
+            ));
+        }
+
+        if self.strategy == SearchIndexStrategy::Shared && self.live_rows >= LARGE_TABLE_ROWS && self.users > 1 {
+            advice.push(format!(
+                "documents holds {} rows of {} users. Set SEARCH_INDEX_STRATEGY=per_user so searches only read their user's part of the index",
+                self.live_rows, self.users
+            ));
+        }
+
+        let total_rows = self.live_rows + self.dead_rows;
+        if total_rows > 0 && self.dead_rows as f64 / total_rows as f64 >= DEAD_ROW_RATIO {
+            advice.push(format!(
+                "{:.0}% of the rows of documents are dead. Run VACUUM ANALYZE documents",
+                self.dead_rows as f64 * 100.0 / total_rows as f64
+            ));
+        }
+
+        match self.last_analyzed {
+            None if total_rows > 0 => advice.push(
+                "documents has never been analyzed. Run ANALYZE documents so the planner knows how its rows are spread over users".to_string(),
+            ),
+            Some(analyzed) if now - analyzed > Duration::days(STALE_STATISTICS_DAYS) => advice.push(format!(
+                "documents was last analyzed on {}. Run ANALYZE documents so the planner's estimates are current",
+                analyzed.format("%Y-%m-%d")
+            )),
+            _ => {}
+        }
+
+        if self.strategy == SearchIndexStrategy::PerUser
+            && self.missing_indexes.is_empty()
+            && self.sample_plan.is_some()
+            && !self.sample_plan_uses_user_index()
+        {
+            advice.push(
+                "The sample search doesn't read a per-user index. Check its plan above, and run ANALYZE documents if the estimates are off".to_string(),
+            );
+        }
+
+        for index in self
+            .indexes
+            .iter()
+            .filter(|index| index.valid && index.scans == 0 && index.size_bytes >= UNUSED_INDEX_MIN_BYTES)
+        {
+            advice.push(format!(
+                "Index {} takes {} MB and hasn't been scanned since the statistics were reset. It slows down writes without speeding up searches",
+                index.name,
+                index.size_bytes / (1024 * 1024)
+            ));
+        }
+
+        advice
+    }
+}
+
+impl Database {
+    /// Builds the per-user indexes for the `per_user` strategy, or drops them
+    /// for `shared`. Indexes left invalid by an interrupted build are rebuilt.
+    pub async fn apply_search_index_strategy(&self, strategy: SearchIndexStrategy) -> Result<()> {
+        for (name, definition) in per_user_search_indexes() {
+            let valid = self.search_index_validity(name).await?;
+            match strategy {
+                SearchIndexStrategy::PerUser => {
+                    if valid == Some(true) {
+                        continue;
+                    }
+                    if valid == Some(false) {
+                        warn!("Dropping invalid search index {} before rebuilding it", name);
+                        sqlx::raw_sql(&format!("DROP INDEX CONCURRENTLY IF EXISTS {}", name))
+                            .execute(&self.pool)
+                            .await?;
+                    }
+                    info!("Building search index {}", name);
+                    // Concurrent builds can't run in a transaction, so they go
+                    // through the simple query protocol
+                    sqlx::raw_sql(&format!(
+                        "CREATE INDEX CONCURRENTLY IF NOT EXISTS {} ON documents USING GIN ({})",
+                        name, definition
+                    ))
+                    .execute(&self.pool)
+                    .await?;
+                    info!("Search index {} is ready", name);
+                }
+                SearchIndexStrategy::Shared => {
+                    if valid.is_some() {
+                        info!("Dropping search index {}", name);
+                        sqlx::raw_sql(&format!("DROP INDEX CONCURRENTLY IF EXISTS {}", name))
+                            .execute(&self.pool)
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether an index is valid, or None if it doesn't exist
+    async fn search_index_validity(&self, name: &str) -> Result<Option<bool>> {
+        let valid = sqlx::query_scalar::<_, bool>(
+            "SELECT i.indisvalid FROM pg_index i JOIN pg_class c ON c.oid = i.indexrelid WHERE c.relname = $1",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(valid)
+    }
+
+    /// Collects the statistics of the documents table and its full-text
+    /// indexes, and the plan of a search in the largest user's documents
+    pub async fn search_index_health(&self, strategy: SearchIndexStrategy) -> Result<SearchIndexHealth> {
+        let table = sqlx::query(
+            r#"SELECT n_live_tup, n_dead_tup, GREATEST(last_analyze, last_autoanalyze) AS last_analyzed
+               FROM pg_stat_user_tables WHERE relname = 'documents'"#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        let (live_rows, dead_rows, last_analyzed): (i64, i64, Option<DateTime<Utc>>) = match table {
+            Some(row) => (row.get("n_live_tup"), row.get("n_dead_tup"), row.get("last_analyzed")),
+            None => (0, 0, None),
+        };
+
+        let users = sqlx::query(
+            r#"SELECT COUNT(*) AS users, COALESCE(MAX(documents), 0) AS largest,
+                      (ARRAY_AGG(user_id ORDER BY documents DESC))[1] AS largest_user_id
+               FROM (SELECT user_id, COUNT(*) AS documents FROM documents GROUP BY user_id) per_user"#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        let largest_user_id: Option<Uuid> = users.get("largest_user_id");
+
+        let indexes = sqlx::query(
+            r#"SELECT c.relname AS name, i.indisvalid AS valid, pg_relation_size(c.oid) AS size_bytes,
+                      COALESCE(s.idx_scan, 0) AS scans, pg_get_indexdef(c.oid) AS definition
+               FROM pg_index i
+               JOIN pg_class c ON c.oid = i.indexrelid
+               JOIN pg_class t ON t.oid = i.indrelid
+               JOIN pg_am am ON am.oid = c.relam
+               LEFT JOIN pg_stat_user_indexes s ON s.indexrelid = i.indexrelid
+               WHERE t.relname = 'documents' AND am.amname = 'gin'
+                 AND pg_get_indexdef(c.oid) LIKE '%to_tsvector%'
+               ORDER BY c.relname"#,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| SearchIndexInfo {
+            name: row.get("name"),
+            valid: row.get("valid"),
+            size_bytes: row.get("size_bytes"),
+            scans: row.get("scans"),
+            definition: row.get("definition"),
+        })
+        .collect::<Vec<_>>();
+
+        let missing_indexes = match strategy {
+            SearchIndexStrategy::PerUser => per_user_search_indexes()
+                .into_iter()
+                .map(|(name, _)| name.to_string())
+                .filter(|name| !indexes.iter().any(|index| &index.name == name))
+                .collect(),
+            SearchIndexStrategy::Shared => Vec::new(),
+        };
+
+        let sample_plan = match largest_user_id {
+            Some(user_id) => Some(self.explain_search(user_id, UserRole::User, &sample_search_request(), true).await?),
+            None => None,
+        };
+
+        Ok(SearchIndexHealth {
+            strategy,
+            live_rows,
+            dead_rows,
+            last_analyzed,
+            users: users.get("users"),
+            largest_user_documents: users.get("largest"),
+            indexes,
+            missing_indexes,
+            sample_plan,
+        })
+    }
+}
+
+/// A one-word search, which the planner estimates like most searches
+fn sample_search_request() -> SearchRequest {
+    SearchRequest {
+        query: "invoice".to_string(),
+        tags: None,
+        mime_types: None,
+        limit: None,
+        offset: None,
+        include_snippets: None,
+        snippet_length: None,
+        search_mode: None,
+        include_shared: None,
+        include_notes: None,
+        ignore_accents: None,
+        language: None,
+        document_date_from: None,
+        document_date_to: None,
+        collapse_duplicates: None,
+    }
+}
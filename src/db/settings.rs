@@ -4,6 +4,24 @@ use uuid::Uuid;
 use serde_json::Value;
 
 use super::Database;
+use crate::ocr::fallback_order::FallbackStrategy;
+
+/// Rejects a setting, keeping its name so the API can report the field
+fn invalid_setting(setting_name: &str, reason: String) -> anyhow::Error {
+    crate::errors::settings::SettingsError::validation_failed(setting_name.to_string(), reason).into()
+}
+
+/// The extraction order to save: the update spelled as the method names are,
+/// e.g. `catdoc,antiword` for `catdoc, ANTIWORD`, or the current order if unset
+fn extraction_order(strategy: FallbackStrategy, update: &Option<Option<String>>, current: &Option<String>) -> Option<String> {
+    match update {
+        Some(order) => order
+            .as_deref()
+            .and_then(|order| strategy.parse_order(order).ok())
+            .map(|methods| methods.join(",")),
+        None => current.clone(),
+    }
+}
 
 // Helper function to parse JSONB array to Vec<String>
 fn parse_jsonb_string_array(value: Value) -> Vec<String> {
@@ -78,6 +96,35 @@ fn settings_from_row(row: &sqlx::postgres::PgRow) -> crate::models::Settings {
         // Office document extraction configuration
         office_extraction_timeout_seconds: row.get("office_extraction_timeout_seconds"),
         office_extraction_enable_detailed_logging: row.get("office_extraction_enable_detailed_logging"),
+        ocr_auto_psm: row.get("ocr_auto_psm"),
+        ocr_quick_preview_pages: row.get("ocr_quick_preview_pages"),
+        notification_delivery_mode: row.get("notification_delivery_mode"),
+        notification_digest_interval_minutes: row.get("notification_digest_interval_minutes"),
+        ocr_max_pixels: row.get("ocr_max_pixels"),
+        ocr_pdf_hybrid: row.get("ocr_pdf_hybrid"),
+        ocr_reprocess_on_settings_change: row.get("ocr_reprocess_on_settings_change"),
+        auto_label_language: row.get("auto_label_language"),
+        extract_document_date: row.get("extract_document_date"),
+        document_date_priority: row.get("document_date_priority"),
+        document_date_rule: row.get("document_date_rule"),
+        document_date_order: row.get("document_date_order"),
+        search_include_notes: row.get("search_include_notes"),
+        ocr_verify_orientation: row.get("ocr_verify_orientation"),
+        auto_split_on_separator: row.get("auto_split_on_separator"),
+        auto_split_barcode: row.get("auto_split_barcode"),
+        expiry_reminder_days: row.get("expiry_reminder_days"),
+        search_language: row.get("search_language"),
+        search_ignore_accents: row.get("search_ignore_accents"),
+        extract_archives: row.get("extract_archives"),
+        keep_original_pdf: row.get("keep_original_pdf"),
+        decode_barcodes: row.get("decode_barcodes"),
+        search_collapse_duplicates: row.get("search_collapse_duplicates"),
+        ocr_incremental_pages: row.get("ocr_incremental_pages"),
+        parse_front_matter: row.get("parse_front_matter"),
+        extract_tables: row.get("extract_tables"),
+        doc_extraction_order: row.get("doc_extraction_order"),
+        pdf_extraction_order: row.get("pdf_extraction_order"),
+        extraction_auto_order: row.get("extraction_auto_order"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
     }
@@ -107,6 +154,35 @@ impl Database {
                    webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
                    COALESCE(office_extraction_timeout_seconds, 120) as office_extraction_timeout_seconds,
                    COALESCE(office_extraction_enable_detailed_logging, true) as office_extraction_enable_detailed_logging,
+                   COALESCE(ocr_auto_psm, false) as ocr_auto_psm,
+                   COALESCE(ocr_quick_preview_pages, 0) as ocr_quick_preview_pages,
+                   notification_delivery_mode,
+                   notification_digest_interval_minutes,
+                   ocr_max_pixels,
+                   ocr_pdf_hybrid,
+                   ocr_reprocess_on_settings_change,
+                   auto_label_language,
+                   extract_document_date,
+                   document_date_priority,
+                   document_date_rule,
+                   document_date_order,
+                   search_include_notes,
+                   ocr_verify_orientation,
+                   auto_split_on_separator,
+                   auto_split_barcode,
+                   expiry_reminder_days,
+                   search_language,
+                   search_ignore_accents,
+                   extract_archives,
+                   keep_original_pdf,
+                   decode_barcodes,
+                   search_collapse_duplicates,
+                   ocr_incremental_pages,
+                   parse_front_matter,
+                   extract_tables,
+                   doc_extraction_order,
+                   pdf_extraction_order,
+                   extraction_auto_order,
                    created_at, updated_at
                    FROM settings WHERE user_id = $1"#
             )
@@ -144,6 +220,35 @@ impl Database {
                webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
                COALESCE(office_extraction_timeout_seconds, 120) as office_extraction_timeout_seconds,
                COALESCE(office_extraction_enable_detailed_logging, false) as office_extraction_enable_detailed_logging,
+               COALESCE(ocr_auto_psm, false) as ocr_auto_psm,
+               COALESCE(ocr_quick_preview_pages, 0) as ocr_quick_preview_pages,
+               notification_delivery_mode,
+               notification_digest_interval_minutes,
+               ocr_max_pixels,
+               ocr_pdf_hybrid,
+               ocr_reprocess_on_settings_change,
+               auto_label_language,
+               extract_document_date,
+               document_date_priority,
+               document_date_rule,
+               document_date_order,
+               search_include_notes,
+               ocr_verify_orientation,
+               auto_split_on_separator,
+               auto_split_barcode,
+               expiry_reminder_days,
+               search_language,
+               search_ignore_accents,
+               extract_archives,
+               keep_original_pdf,
+               decode_barcodes,
+               search_collapse_duplicates,
+               ocr_incremental_pages,
+               parse_front_matter,
+               extract_tables,
+               doc_extraction_order,
+               pdf_extraction_order,
+               extraction_auto_order,
                created_at, updated_at
                FROM settings
                WHERE webdav_enabled = true AND webdav_auto_sync = true"#
@@ -163,16 +268,16 @@ impl Database {
         // Validate timeout
         if let Some(timeout) = settings.office_extraction_timeout_seconds {
             if timeout <= 0 {
-                return Err(anyhow!(
+                return Err(invalid_setting("office_extraction_timeout_seconds", format!(
                     "Office extraction timeout must be greater than 0 seconds, got: {}",
                     timeout
-                ));
+                )));
             }
             if timeout > 600 {
-                return Err(anyhow!(
+                return Err(invalid_setting("office_extraction_timeout_seconds", format!(
                     "Office extraction timeout cannot exceed 600 seconds (10 minutes) for system stability, got: {}",
                     timeout
-                ));
+                )));
             }
         }
         
@@ -186,77 +291,88 @@ impl Database {
         // Validate OCR settings
         if let Some(concurrent_jobs) = settings.concurrent_ocr_jobs {
             if concurrent_jobs < 1 || concurrent_jobs > 20 {
-                return Err(anyhow!(
+                return Err(invalid_setting("concurrent_ocr_jobs", format!(
                     "Concurrent OCR jobs must be between 1 and 20, got: {}",
                     concurrent_jobs
-                ));
+                )));
             }
         }
         
         if let Some(timeout) = settings.ocr_timeout_seconds {
             if timeout < 10 || timeout > 1800 {
-                return Err(anyhow!(
+                return Err(invalid_setting("ocr_timeout_seconds", format!(
                     "OCR timeout must be between 10 and 1800 seconds, got: {}",
                     timeout
-                ));
+                )));
             }
         }
         
         if let Some(max_size) = settings.max_file_size_mb {
             if max_size < 1 || max_size > 500 {
-                return Err(anyhow!(
+                return Err(invalid_setting("max_file_size_mb", format!(
                     "Maximum file size must be between 1 and 500 MB, got: {}",
                     max_size
-                ));
+                )));
             }
         }
         
         if let Some(memory_limit) = settings.memory_limit_mb {
             if memory_limit < 64 || memory_limit > 8192 {
-                return Err(anyhow!(
+                return Err(invalid_setting("memory_limit_mb", format!(
                     "Memory limit must be between 64 and 8192 MB, got: {}",
                     memory_limit
-                ));
+                )));
             }
         }
         
         if let Some(results_per_page) = settings.search_results_per_page {
             if results_per_page < 1 || results_per_page > 1000 {
-                return Err(anyhow!(
+                return Err(invalid_setting("search_results_per_page", format!(
                     "Search results per page must be between 1 and 1000, got: {}",
                     results_per_page
-                ));
+                )));
             }
         }
         
         if let Some(snippet_length) = settings.search_snippet_length {
             if snippet_length < 10 || snippet_length > 2000 {
-                return Err(anyhow!(
+                return Err(invalid_setting("search_snippet_length", format!(
                     "Search snippet length must be between 10 and 2000 characters, got: {}",
                     snippet_length
-                ));
+                )));
             }
         }
         
         if let Some(threshold) = settings.fuzzy_search_threshold {
             if threshold < 0.0 || threshold > 1.0 {
-                return Err(anyhow!(
+                return Err(invalid_setting("fuzzy_search_threshold", format!(
                     "Fuzzy search threshold must be between 0.0 and 1.0, got: {}",
                     threshold
-                ));
+                )));
             }
         }
         
         // Validate WebDAV settings
         if let Some(sync_interval) = settings.webdav_sync_interval_minutes {
             if sync_interval < 1 || sync_interval > 10080 { // max 1 week
-                return Err(anyhow!(
+                return Err(invalid_setting("webdav_sync_interval_minutes", format!(
                     "WebDAV sync interval must be between 1 and 10080 minutes (1 week), got: {}",
                     sync_interval
-                ));
+                )));
             }
         }
-        
+
+        // Validate extraction method orders
+        for strategy in FallbackStrategy::ALL {
+            let order = match strategy {
+                FallbackStrategy::LegacyDoc => &settings.doc_extraction_order,
+                FallbackStrategy::Pdf => &settings.pdf_extraction_order,
+            };
+            if let Some(Some(order)) = order {
+                strategy.parse_order(order).map_err(|reason| invalid_setting(strategy.setting_name(), reason))?;
+            }
+        }
+
         Ok(())
     }
 
@@ -292,9 +408,9 @@ impl Database {
                 ocr_quality_threshold_sharpness, ocr_skip_enhancement,
                 webdav_enabled, webdav_server_url, webdav_username, webdav_password,
                 webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
-                office_extraction_timeout_seconds, office_extraction_enable_detailed_logging
+                office_extraction_timeout_seconds, office_extraction_enable_detailed_logging, ocr_auto_psm, ocr_quick_preview_pages, notification_delivery_mode, notification_digest_interval_minutes, ocr_max_pixels, ocr_pdf_hybrid, ocr_reprocess_on_settings_change, auto_label_language, extract_document_date, document_date_priority, document_date_rule, document_date_order, search_include_notes, ocr_verify_orientation, auto_split_on_separator, auto_split_barcode, expiry_reminder_days, search_language, search_ignore_accents, extract_archives, keep_original_pdf, decode_barcodes, search_collapse_duplicates, ocr_incremental_pages, parse_front_matter, extract_tables, doc_extraction_order, pdf_extraction_order, extraction_auto_order
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $45, $46, $47, $48, $49, $50, $51, $52, $53, $54, $55)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $45, $46, $47, $48, $49, $50, $51, $52, $53, $54, $55, $56, $57, $58, $59, $60, $61, $62, $63, $64, $65, $66, $67, $68, $69, $70, $71, $72, $73, $74, $75, $76, $77, $78, $79, $80, $81, $82, $83, $84)
             ON CONFLICT (user_id) DO UPDATE SET
                 ocr_language = $2,
                 preferred_languages = $3,
@@ -350,6 +466,35 @@ impl Database {
                 webdav_sync_interval_minutes = $53,
                 office_extraction_timeout_seconds = $54,
                 office_extraction_enable_detailed_logging = $55,
+                ocr_auto_psm = $56,
+                ocr_quick_preview_pages = $57,
+                notification_delivery_mode = $58,
+                notification_digest_interval_minutes = $59,
+                ocr_max_pixels = $60,
+                ocr_pdf_hybrid = $61,
+                ocr_reprocess_on_settings_change = $62,
+                auto_label_language = $63,
+                extract_document_date = $64,
+                document_date_priority = $65,
+                document_date_rule = $66,
+                document_date_order = $67,
+                search_include_notes = $68,
+                ocr_verify_orientation = $69,
+                auto_split_on_separator = $70,
+                auto_split_barcode = $71,
+                expiry_reminder_days = $72,
+                search_language = $73,
+                search_ignore_accents = $74,
+                extract_archives = $75,
+                keep_original_pdf = $76,
+                decode_barcodes = $77,
+                search_collapse_duplicates = $78,
+                ocr_incremental_pages = $79,
+                parse_front_matter = $80,
+                extract_tables = $81,
+                doc_extraction_order = $82,
+                pdf_extraction_order = $83,
+                extraction_auto_order = $84,
                 updated_at = NOW()
             RETURNING id, user_id, ocr_language, 
                       COALESCE(preferred_languages, '["eng"]'::jsonb) as preferred_languages,
@@ -371,6 +516,35 @@ impl Database {
                       webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
                       COALESCE(office_extraction_timeout_seconds, 120) as office_extraction_timeout_seconds,
                       COALESCE(office_extraction_enable_detailed_logging, false) as office_extraction_enable_detailed_logging,
+                      COALESCE(ocr_auto_psm, false) as ocr_auto_psm,
+                      COALESCE(ocr_quick_preview_pages, 0) as ocr_quick_preview_pages,
+                      notification_delivery_mode,
+                      notification_digest_interval_minutes,
+                      ocr_max_pixels,
+                      ocr_pdf_hybrid,
+                      ocr_reprocess_on_settings_change,
+                      auto_label_language,
+                      extract_document_date,
+                      document_date_priority,
+                      document_date_rule,
+                      document_date_order,
+                      search_include_notes,
+                      ocr_verify_orientation,
+                      auto_split_on_separator,
+                      auto_split_barcode,
+                      expiry_reminder_days,
+                      search_language,
+                      search_ignore_accents,
+                      extract_archives,
+                      keep_original_pdf,
+                      decode_barcodes,
+                      search_collapse_duplicates,
+                      ocr_incremental_pages,
+                      parse_front_matter,
+                      extract_tables,
+                      doc_extraction_order,
+                      pdf_extraction_order,
+                      extraction_auto_order,
                       created_at, updated_at
             "#
         )
@@ -429,6 +603,35 @@ impl Database {
         .bind(settings.webdav_sync_interval_minutes.unwrap_or(current.webdav_sync_interval_minutes))
         .bind(settings.office_extraction_timeout_seconds.unwrap_or(current.office_extraction_timeout_seconds))
         .bind(settings.office_extraction_enable_detailed_logging.unwrap_or(current.office_extraction_enable_detailed_logging))
+        .bind(settings.ocr_auto_psm.unwrap_or(current.ocr_auto_psm))
+        .bind(settings.ocr_quick_preview_pages.unwrap_or(current.ocr_quick_preview_pages))
+        .bind(settings.notification_delivery_mode.as_ref().unwrap_or(&current.notification_delivery_mode))
+        .bind(settings.notification_digest_interval_minutes.unwrap_or(current.notification_digest_interval_minutes))
+        .bind(settings.ocr_max_pixels.unwrap_or(current.ocr_max_pixels))
+        .bind(settings.ocr_pdf_hybrid.unwrap_or(current.ocr_pdf_hybrid))
+        .bind(settings.ocr_reprocess_on_settings_change.unwrap_or(current.ocr_reprocess_on_settings_change))
+        .bind(settings.auto_label_language.unwrap_or(current.auto_label_language))
+        .bind(settings.extract_document_date.unwrap_or(current.extract_document_date))
+        .bind(settings.document_date_priority.as_ref().unwrap_or(&current.document_date_priority))
+        .bind(settings.document_date_rule.as_ref().unwrap_or(&current.document_date_rule))
+        .bind(settings.document_date_order.as_ref().unwrap_or(&current.document_date_order))
+        .bind(settings.search_include_notes.unwrap_or(current.search_include_notes))
+        .bind(settings.ocr_verify_orientation.unwrap_or(current.ocr_verify_orientation))
+        .bind(settings.auto_split_on_separator.unwrap_or(current.auto_split_on_separator))
+        .bind(settings.auto_split_barcode.as_ref().unwrap_or(&current.auto_split_barcode))
+        .bind(settings.expiry_reminder_days.unwrap_or(current.expiry_reminder_days))
+        .bind(settings.search_language.as_ref().unwrap_or(&current.search_language))
+        .bind(settings.search_ignore_accents.unwrap_or(current.search_ignore_accents))
+        .bind(settings.extract_archives.unwrap_or(current.extract_archives))
+        .bind(settings.keep_original_pdf.unwrap_or(current.keep_original_pdf))
+        .bind(settings.decode_barcodes.unwrap_or(current.decode_barcodes))
+        .bind(settings.search_collapse_duplicates.unwrap_or(current.search_collapse_duplicates))
+        .bind(settings.ocr_incremental_pages.unwrap_or(current.ocr_incremental_pages))
+        .bind(settings.parse_front_matter.unwrap_or(current.parse_front_matter))
+        .bind(settings.extract_tables.unwrap_or(current.extract_tables))
+        .bind(extraction_order(FallbackStrategy::LegacyDoc, &settings.doc_extraction_order, &current.doc_extraction_order))
+        .bind(extraction_order(FallbackStrategy::Pdf, &settings.pdf_extraction_order, &current.pdf_extraction_order))
+        .bind(settings.extraction_auto_order.unwrap_or(current.extraction_auto_order))
         .fetch_one(&self.pool)
         .await?;
 
@@ -455,4 +658,28 @@ impl Database {
             Ok(())
         }).await
     }
+
+    /// Current OCR settings version for a user; 1 until OCR-relevant settings first change
+    pub async fn get_ocr_settings_version(&self, user_id: Uuid) -> Result<i32> {
+        let version = sqlx::query_scalar::<_, i32>("SELECT ocr_settings_version FROM settings WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(version.unwrap_or(1))
+    }
+
+    /// Marks a change to OCR-relevant settings and returns the new version
+    pub async fn bump_ocr_settings_version(&self, user_id: Uuid) -> Result<i32> {
+        let version = sqlx::query_scalar::<_, i32>(
+            r#"UPDATE settings SET ocr_settings_version = ocr_settings_version + 1
+               WHERE user_id = $1
+               RETURNING ocr_settings_version"#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
 }
\ No newline at end of file
@@ -5,15 +5,30 @@ use uuid::Uuid;
 use tracing::{info, warn, error};
 
 use super::Database;
+use crate::services::credential_encryption::{installed_credential_keyring, CredentialKeyring, CredentialRotationSummary};
+
+/// Source configuration with its credentials decrypted. A configuration that
+/// can't be decrypted is returned as stored, so the source fails to
+/// authenticate instead of disappearing.
+fn decrypt_source_config(config: serde_json::Value) -> serde_json::Value {
+    match installed_credential_keyring().decrypt_config(&config) {
+        Ok(decrypted) => decrypted,
+        Err(e) => {
+            warn!("Could not decrypt source credentials: {}", e);
+            config
+        }
+    }
+}
 
 impl Database {
     pub async fn create_source(&self, user_id: Uuid, source: &crate::models::CreateSource) -> Result<crate::models::Source> {
         let id = Uuid::new_v4();
         let now = Utc::now();
+        let (config, key_version) = installed_credential_keyring().encrypt_config(&source.config)?;
         
         let row = sqlx::query(
-            r#"INSERT INTO sources (id, user_id, name, source_type, enabled, config, status, created_at, updated_at)
-               VALUES ($1, $2, $3, $4, $5, $6, 'idle', $7, $8)
+            r#"INSERT INTO sources (id, user_id, name, source_type, enabled, config, credentials_key_version, status, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, 'idle', $8, $9)
                RETURNING *"#
         )
         .bind(id)
@@ -21,7 +36,8 @@ impl Database {
         .bind(&source.name)
         .bind(source.source_type.to_string())
         .bind(source.enabled.unwrap_or(true))
-        .bind(&source.config)
+        .bind(&config)
+        .bind(key_version)
         .bind(now)
         .bind(now)
         .fetch_one(&self.pool)
@@ -33,7 +49,7 @@ impl Database {
             name: row.get("name"),
             source_type: row.get::<String, _>("source_type").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
             enabled: row.get("enabled"),
-            config: row.get("config"),
+            config: decrypt_source_config(row.get("config")),
             status: row.get::<String, _>("status").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
             last_sync_at: row.get("last_sync_at"),
             last_error: row.get("last_error"),
@@ -52,7 +68,7 @@ impl Database {
 
     pub async fn get_source(&self, user_id: Uuid, source_id: Uuid) -> Result<Option<crate::models::Source>> {
         let row = sqlx::query(
-            r#"SELECT * FROM sources WHERE id = $1 AND user_id = $2"#
+            r#"SELECT * FROM sources WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"#
         )
         .bind(source_id)
         .bind(user_id)
@@ -66,7 +82,7 @@ impl Database {
                 name: row.get("name"),
                 source_type: row.get::<String, _>("source_type").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
                 enabled: row.get("enabled"),
-                config: row.get("config"),
+                config: decrypt_source_config(row.get("config")),
                 status: row.get::<String, _>("status").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
                 last_sync_at: row.get("last_sync_at"),
                 last_error: row.get("last_error"),
@@ -87,7 +103,7 @@ impl Database {
 
     pub async fn get_sources(&self, user_id: Uuid) -> Result<Vec<crate::models::Source>> {
         let rows = sqlx::query(
-            r#"SELECT * FROM sources WHERE user_id = $1 ORDER BY created_at DESC"#
+            r#"SELECT * FROM sources WHERE user_id = $1 AND deleted_at IS NULL ORDER BY created_at DESC"#
         )
         .bind(user_id)
         .fetch_all(&self.pool)
@@ -101,7 +117,7 @@ impl Database {
                 name: row.get("name"),
                 source_type: row.get::<String, _>("source_type").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
                 enabled: row.get("enabled"),
-                config: row.get("config"),
+                config: decrypt_source_config(row.get("config")),
                 status: row.get::<String, _>("status").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
                 last_sync_at: row.get("last_sync_at"),
                 last_error: row.get("last_error"),
@@ -136,6 +152,8 @@ impl Database {
         if update.config.is_some() {
             bind_count += 1;
             query.push_str(&format!(", config = ${}", bind_count));
+            bind_count += 1;
+            query.push_str(&format!(", credentials_key_version = ${}", bind_count));
         }
 
         bind_count += 1;
@@ -152,8 +170,12 @@ impl Database {
         if let Some(enabled) = &update.enabled {
             query_builder = query_builder.bind(enabled);
         }
-        if let Some(config) = &update.config {
-            query_builder = query_builder.bind(config);
+        let encrypted_config = match &update.config {
+            Some(config) => Some(installed_credential_keyring().encrypt_config(config)?),
+            None => None,
+        };
+        if let Some((config, key_version)) = &encrypted_config {
+            query_builder = query_builder.bind(config).bind(key_version);
         }
         query_builder = query_builder.bind(source_id);
         query_builder = query_builder.bind(user_id);
@@ -166,7 +188,7 @@ impl Database {
             name: row.get("name"),
             source_type: row.get::<String, _>("source_type").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
             enabled: row.get("enabled"),
-            config: row.get("config"),
+            config: decrypt_source_config(row.get("config")),
             status: row.get::<String, _>("status").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
             last_sync_at: row.get("last_sync_at"),
             last_error: row.get("last_error"),
@@ -195,6 +217,65 @@ impl Database {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Hides a source until `purge_at`, when the purge removes it for good. Returns
+    /// false if the source doesn't exist or is already deleted.
+    pub async fn soft_delete_source(
+        &self,
+        user_id: Uuid,
+        source_id: Uuid,
+        purge_at: chrono::DateTime<Utc>,
+        purge_documents: bool,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"UPDATE sources
+               SET deleted_at = NOW(), purge_at = $3, purge_documents = $4, updated_at = NOW()
+               WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"#
+        )
+        .bind(source_id)
+        .bind(user_id)
+        .bind(purge_at)
+        .bind(purge_documents)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Undoes a soft delete that hasn't been purged yet
+    pub async fn restore_source(&self, user_id: Uuid, source_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"UPDATE sources
+               SET deleted_at = NULL, purge_at = NULL, purge_documents = FALSE, updated_at = NOW()
+               WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL"#
+        )
+        .bind(source_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Soft-deleted sources whose undo window has passed
+    pub async fn get_sources_due_for_purge(&self) -> Result<Vec<crate::models::PendingSourcePurge>> {
+        let rows = sqlx::query(
+            r#"SELECT id, user_id, purge_documents FROM sources
+               WHERE deleted_at IS NOT NULL AND purge_at <= NOW()
+               ORDER BY purge_at"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| crate::models::PendingSourcePurge {
+                source_id: row.get("id"),
+                user_id: row.get("user_id"),
+                purge_documents: row.get("purge_documents"),
+            })
+            .collect())
+    }
+
     pub async fn update_source_status(&self, source_id: Uuid, status: crate::models::SourceStatus, error: Option<String>) -> Result<()> {
         if let Some(error_msg) = error {
             sqlx::query(
@@ -245,7 +326,7 @@ impl Database {
             r#"SELECT id, user_id, name, source_type, enabled, config, status, 
                last_sync_at, last_error, last_error_at, total_files_synced, 
                total_files_pending, total_size_bytes, created_at, updated_at
-               FROM sources ORDER BY created_at DESC"#
+               FROM sources WHERE deleted_at IS NULL ORDER BY created_at DESC"#
         )
         .fetch_all(&self.pool)
         .await?;
@@ -259,7 +340,7 @@ impl Database {
                 source_type: row.get::<String, _>("source_type").try_into()
                     .map_err(|e| anyhow::anyhow!("Invalid source type: {}", e))?,
                 enabled: row.get("enabled"),
-                config: row.get("config"),
+                config: decrypt_source_config(row.get("config")),
                 status: row.get::<String, _>("status").try_into()
                     .map_err(|e| anyhow::anyhow!("Invalid source status: {}", e))?,
                 last_sync_at: row.get("last_sync_at"),
@@ -289,7 +370,7 @@ impl Database {
                total_files_pending, total_size_bytes, created_at, updated_at,
                validation_status, last_validation_at, validation_score, validation_issues
                FROM sources 
-               WHERE enabled = true AND status != 'syncing'
+               WHERE enabled = true AND status != 'syncing' AND deleted_at IS NULL
                ORDER BY last_sync_at ASC NULLS FIRST"#
         )
         .fetch_all(&self.pool)
@@ -338,7 +419,7 @@ impl Database {
                 source_type: source_type_str.clone().try_into()
                     .map_err(|e| anyhow::anyhow!("Invalid source type '{}' for source '{}': {}", source_type_str, source_name, e))?,
                 enabled: row.get("enabled"),
-                config: config_json,
+                config: decrypt_source_config(config_json),
                 status: {
                     let status_str: String = row.get("status");
                     status_str.clone().try_into()
@@ -370,7 +451,7 @@ impl Database {
                last_sync_at, last_error, last_error_at, total_files_synced, 
                total_files_pending, total_size_bytes, created_at, updated_at,
                validation_status, last_validation_at, validation_score, validation_issues
-               FROM sources WHERE id = $1"#
+               FROM sources WHERE id = $1 AND deleted_at IS NULL"#
         )
         .bind(source_id)
         .fetch_optional(&self.pool)
@@ -384,7 +465,7 @@ impl Database {
                 source_type: row.get::<String, _>("source_type").try_into()
                     .map_err(|e| anyhow::anyhow!("Invalid source type: {}", e))?,
                 enabled: row.get("enabled"),
-                config: row.get("config"),
+                config: decrypt_source_config(row.get("config")),
                 status: row.get::<String, _>("status").try_into()
                     .map_err(|e| anyhow::anyhow!("Invalid source status: {}", e))?,
                 last_sync_at: row.get("last_sync_at"),
@@ -646,4 +727,698 @@ impl Database {
         
         Ok(affected_rows)
     }
-}
\ No newline at end of file
+
+    /// Clear a source's sync tracking so its next sync is a complete deep scan.
+    ///
+    /// Removes the directory ETags tracked under `watch_folders` and the source's
+    /// last sync time. The WebDAV sync cursor is kept per user by the settings
+    /// based sync, so it is only cleared when `sync_cursor_server` is the server
+    /// of that sync and one of its watch folders lies under `watch_folders`;
+    /// the user's other sources keep their progress. Ingested documents and
+    /// recorded sync errors are left untouched.
+    pub async fn reset_source_sync_state(
+        &self,
+        user_id: Uuid,
+        source_id: Uuid,
+        watch_folders: &[String],
+        sync_cursor_server: Option<&str>,
+    ) -> Result<Option<crate::models::SourceSyncStateReset>> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous_last_sync_at: Option<Option<chrono::DateTime<Utc>>> = sqlx::query_scalar(
+            r#"SELECT last_sync_at FROM sources WHERE id = $1 AND user_id = $2 FOR UPDATE"#
+        )
+        .bind(source_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(previous_last_sync_at) = previous_last_sync_at else {
+            tx.rollback().await?;
+            return Ok(None);
+        };
+
+        // Watch folders are matched as path prefixes, so "/" covers every tracked directory
+        let prefixes: Vec<String> = watch_folders
+            .iter()
+            .map(|folder| folder.trim_end_matches('/').to_string())
+            .collect();
+
+        let directory_etags_cleared = sqlx::query(
+            r#"DELETE FROM webdav_directories
+               WHERE user_id = $1
+                 AND EXISTS (
+                     SELECT 1 FROM unnest($2::text[]) AS prefix
+                     WHERE rtrim(directory_path, '/') = prefix
+                        OR starts_with(directory_path, prefix || '/')
+                 )"#
+        )
+        .bind(user_id)
+        .bind(&prefixes)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+
+        let sync_cursor_cleared = if let Some(server_url) = sync_cursor_server {
+            sqlx::query(
+                r#"UPDATE webdav_sync_state
+                   SET last_sync_at = NULL, sync_cursor = NULL, current_folder = NULL,
+                       files_processed = 0, files_remaining = 0, updated_at = NOW()
+                   FROM settings
+                   WHERE webdav_sync_state.user_id = $1
+                     AND settings.user_id = $1
+                     AND (webdav_sync_state.last_sync_at IS NOT NULL OR webdav_sync_state.sync_cursor IS NOT NULL)
+                     AND rtrim(settings.webdav_server_url, '/') = rtrim($3, '/')
+                     AND EXISTS (
+                         SELECT 1
+                         FROM unnest(settings.webdav_watch_folders) AS folder,
+                              unnest($2::text[]) AS prefix
+                         WHERE rtrim(folder, '/') = prefix
+                            OR starts_with(folder, prefix || '/')
+                     )"#
+            )
+            .bind(user_id)
+            .bind(&prefixes)
+            .bind(server_url)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() > 0
+        } else {
+            false
+        };
+
+        sqlx::query(
+            r#"UPDATE sources SET last_sync_at = NULL, updated_at = NOW() WHERE id = $1"#
+        )
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        info!(
+            "Reset sync state for source {}: {} directory ETags cleared, sync cursor cleared: {}",
+            source_id, directory_etags_cleared, sync_cursor_cleared
+        );
+
+        Ok(Some(crate::models::SourceSyncStateReset {
+            source_id,
+            directory_etags_cleared,
+            sync_cursor_cleared,
+            previous_last_sync_at,
+        }))
+    }
+
+    pub async fn insert_source_sync_run(&self, run: &crate::models::SourceSyncRun) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO source_sync_runs
+                   (id, source_id, outcome, retry_of_failed, files_discovered, files_processed,
+                    files_failed, files_added, files_updated, files_skipped, bytes_transferred,
+                    failures, started_at, completed_at, failure_kind)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)"#
+        )
+        .bind(run.id)
+        .bind(run.source_id)
+        .bind(run.outcome.to_string())
+        .bind(run.retry_of_failed)
+        .bind(run.files_discovered)
+        .bind(run.files_processed)
+        .bind(run.files_failed)
+        .bind(run.files_added)
+        .bind(run.files_updated)
+        .bind(run.files_skipped)
+        .bind(run.bytes_transferred)
+        .bind(serde_json::to_value(&run.failures)?)
+        .bind(run.started_at)
+        .bind(run.completed_at)
+        .bind(run.failure_kind.map(|kind| kind.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The most recently finished sync of a source, if it has synced since
+    /// sync runs were recorded
+    pub async fn get_last_source_sync_run(&self, source_id: Uuid) -> Result<Option<crate::models::SourceSyncRun>> {
+        let row = sqlx::query(
+            r#"SELECT * FROM source_sync_runs
+               WHERE source_id = $1
+               ORDER BY completed_at DESC
+               LIMIT 1"#
+        )
+        .bind(source_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(crate::models::SourceSyncRun {
+                id: row.get("id"),
+                source_id: row.get("source_id"),
+                outcome: row.get::<String, _>("outcome").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
+                failure_kind: row
+                    .get::<Option<String>, _>("failure_kind")
+                    .map(TryInto::try_into)
+                    .transpose()
+                    .map_err(|e: String| anyhow::anyhow!(e))?,
+                retry_of_failed: row.get("retry_of_failed"),
+                files_discovered: row.get("files_discovered"),
+                files_processed: row.get("files_processed"),
+                files_failed: row.get("files_failed"),
+                files_added: row.get("files_added"),
+                files_updated: row.get("files_updated"),
+                files_skipped: row.get("files_skipped"),
+                bytes_transferred: row.get("bytes_transferred"),
+                failures: serde_json::from_value(row.get("failures"))?,
+                started_at: row.get("started_at"),
+                completed_at: row.get("completed_at"),
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Finished syncs of a source matching `filter`, newest first, and how
+    /// many match in total
+    pub async fn get_source_sync_history(
+        &self,
+        source_id: Uuid,
+        filter: &crate::models::SourceSyncHistoryFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<crate::models::SourceSyncRunSummary>, i64)> {
+        let outcome = filter.outcome.map(|outcome| outcome.to_string());
+
+        let runs = sqlx::query_as::<_, crate::models::SourceSyncRunSummary>(
+            r#"SELECT id, outcome, failure_kind, retry_of_failed, files_discovered, files_added, files_updated,
+                      files_skipped, files_failed, bytes_transferred, started_at, completed_at,
+                      (EXTRACT(EPOCH FROM (completed_at - started_at)) * 1000)::BIGINT AS duration_ms
+               FROM source_sync_runs
+               WHERE source_id = $1
+                 AND ($2::TEXT IS NULL OR outcome = $2)
+                 AND ($3::TIMESTAMPTZ IS NULL OR started_at >= $3)
+                 AND ($4::TIMESTAMPTZ IS NULL OR started_at < $4)
+               ORDER BY started_at DESC
+               LIMIT $5 OFFSET $6"#
+        )
+        .bind(source_id)
+        .bind(&outcome)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM source_sync_runs
+               WHERE source_id = $1
+                 AND ($2::TEXT IS NULL OR outcome = $2)
+                 AND ($3::TIMESTAMPTZ IS NULL OR started_at >= $3)
+                 AND ($4::TIMESTAMPTZ IS NULL OR started_at < $4)"#
+        )
+        .bind(source_id)
+        .bind(&outcome)
+        .bind(filter.from)
+        .bind(filter.to)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((runs, total))
+    }
+
+    /// Success rate, average duration and totals over the finished syncs of a
+    /// source matching `filter`
+    pub async fn get_source_sync_history_stats(
+        &self,
+        source_id: Uuid,
+        filter: &crate::models::SourceSyncHistoryFilter,
+    ) -> Result<crate::models::SourceSyncHistoryStats> {
+        let row = sqlx::query(
+            r#"SELECT COUNT(*) AS total_runs,
+                      COUNT(*) FILTER (WHERE outcome = 'success') AS successful_runs,
+                      COUNT(*) FILTER (WHERE outcome = 'partial') AS partial_runs,
+                      COUNT(*) FILTER (WHERE outcome = 'failed') AS failed_runs,
+                      (AVG(EXTRACT(EPOCH FROM (completed_at - started_at))) * 1000)::BIGINT AS average_duration_ms,
+                      COALESCE(SUM(files_added), 0)::BIGINT AS files_added,
+                      COALESCE(SUM(files_updated), 0)::BIGINT AS files_updated,
+                      COALESCE(SUM(bytes_transferred), 0)::BIGINT AS bytes_transferred
+               FROM source_sync_runs
+               WHERE source_id = $1
+                 AND ($2::TEXT IS NULL OR outcome = $2)
+                 AND ($3::TIMESTAMPTZ IS NULL OR started_at >= $3)
+                 AND ($4::TIMESTAMPTZ IS NULL OR started_at < $4)"#
+        )
+        .bind(source_id)
+        .bind(filter.outcome.map(|outcome| outcome.to_string()))
+        .bind(filter.from)
+        .bind(filter.to)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let total_runs: i64 = row.get("total_runs");
+        let successful_runs: i64 = row.get("successful_runs");
+
+        Ok(crate::models::SourceSyncHistoryStats {
+            total_runs,
+            successful_runs,
+            partial_runs: row.get("partial_runs"),
+            failed_runs: row.get("failed_runs"),
+            success_rate: (total_runs > 0).then(|| successful_runs as f64 / total_runs as f64),
+            average_duration_ms: row.get("average_duration_ms"),
+            files_added: row.get("files_added"),
+            files_updated: row.get("files_updated"),
+            bytes_transferred: row.get("bytes_transferred"),
+        })
+    }
+
+    /// Deletes a source's sync runs that started before `cutoff`, returning how
+    /// many were deleted
+    pub async fn prune_source_sync_runs(&self, source_id: Uuid, cutoff: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM source_sync_runs WHERE source_id = $1 AND started_at < $2")
+            .bind(source_id)
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Circuit breaker of a source, closed if it never recorded a failure
+    pub async fn get_source_circuit(&self, source_id: Uuid) -> Result<crate::models::SourceCircuit> {
+        let row = sqlx::query(
+            r#"SELECT source_id, state, consecutive_failures, trips, opened_at, cooldown_until
+               FROM source_circuit_breakers
+               WHERE source_id = $1"#
+        )
+        .bind(source_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(crate::models::SourceCircuit {
+                source_id: row.get("source_id"),
+                state: row.get::<String, _>("state").try_into().map_err(|e: String| anyhow::anyhow!(e))?,
+                consecutive_failures: row.get("consecutive_failures"),
+                trips: row.get("trips"),
+                opened_at: row.get("opened_at"),
+                cooldown_until: row.get("cooldown_until"),
+            }),
+            None => Ok(crate::models::SourceCircuit::closed(source_id)),
+        }
+    }
+
+    pub async fn save_source_circuit(&self, circuit: &crate::models::SourceCircuit) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO source_circuit_breakers
+                   (source_id, state, consecutive_failures, trips, opened_at, cooldown_until, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, NOW())
+               ON CONFLICT (source_id) DO UPDATE SET
+                   state = EXCLUDED.state,
+                   consecutive_failures = EXCLUDED.consecutive_failures,
+                   trips = EXCLUDED.trips,
+                   opened_at = EXCLUDED.opened_at,
+                   cooldown_until = EXCLUDED.cooldown_until,
+                   updated_at = NOW()"#
+        )
+        .bind(circuit.source_id)
+        .bind(circuit.state.to_string())
+        .bind(circuit.consecutive_failures)
+        .bind(circuit.trips)
+        .bind(circuit.opened_at)
+        .bind(circuit.cooldown_until)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_source_clock_skew(&self, source_id: Uuid) -> Result<Option<crate::models::SourceClockSkew>> {
+        let row = sqlx::query(
+            r#"SELECT source_id, skew_seconds, exceeds_threshold, measured_at
+               FROM source_clock_skew
+               WHERE source_id = $1"#
+        )
+        .bind(source_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| crate::models::SourceClockSkew {
+            source_id: row.get("source_id"),
+            skew_seconds: row.get("skew_seconds"),
+            exceeds_threshold: row.get("exceeds_threshold"),
+            measured_at: row.get("measured_at"),
+        }))
+    }
+
+    pub async fn save_source_clock_skew(&self, skew: &crate::models::SourceClockSkew) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO source_clock_skew (source_id, skew_seconds, exceeds_threshold, measured_at)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (source_id) DO UPDATE SET
+                   skew_seconds = EXCLUDED.skew_seconds,
+                   exceeds_threshold = EXCLUDED.exceeds_threshold,
+                   measured_at = EXCLUDED.measured_at"#
+        )
+        .bind(skew.source_id)
+        .bind(skew.skew_seconds)
+        .bind(skew.exceeds_threshold)
+        .bind(skew.measured_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The version of a file a sync of this source last stored, if its document still exists
+    pub async fn get_source_file_version(
+        &self,
+        source_id: Uuid,
+        file_path: &str,
+    ) -> Result<Option<crate::models::SourceFileVersion>> {
+        let row = sqlx::query(
+            r#"SELECT etag, file_size, last_modified
+               FROM source_file_versions
+               WHERE source_id = $1 AND file_path = $2"#
+        )
+        .bind(source_id)
+        .bind(file_path)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| crate::models::SourceFileVersion {
+            etag: row.get("etag"),
+            size: row.get("file_size"),
+            last_modified: row.get("last_modified"),
+        }))
+    }
+
+    /// The document a sync last stored from `file_path`
+    pub async fn get_source_file_document_id(&self, source_id: Uuid, file_path: &str) -> Result<Option<Uuid>> {
+        let document_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT document_id FROM source_file_versions WHERE source_id = $1 AND file_path = $2"
+        )
+        .bind(source_id)
+        .bind(file_path)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(document_id)
+    }
+
+    /// Records the version of a file a sync stored as `document_id`
+    pub async fn save_source_file_version(
+        &self,
+        source_id: Uuid,
+        file_path: &str,
+        document_id: Uuid,
+        version: &crate::models::SourceFileVersion,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO source_file_versions
+                   (source_id, file_path, document_id, etag, file_size, last_modified, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, NOW())
+               ON CONFLICT (source_id, file_path) DO UPDATE SET
+                   document_id = EXCLUDED.document_id,
+                   etag = EXCLUDED.etag,
+                   file_size = EXCLUDED.file_size,
+                   last_modified = EXCLUDED.last_modified,
+                   updated_at = NOW()"#
+        )
+        .bind(source_id)
+        .bind(file_path)
+        .bind(document_id)
+        .bind(&version.etag)
+        .bind(version.size)
+        .bind(version.last_modified)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The most recent document a source stored from each of `file_ids`, with
+    /// the path it was stored from, by file id
+    pub async fn find_source_documents_by_file_ids(
+        &self,
+        source_id: Uuid,
+        file_ids: &[String],
+    ) -> Result<std::collections::HashMap<String, (Uuid, Option<String>)>> {
+        let rows = sqlx::query(
+            r#"SELECT DISTINCT ON (source_metadata->>'file_id')
+                      source_metadata->>'file_id' AS file_id, id,
+                      COALESCE(source_metadata->'moved_from'->>'source_path', source_path) AS source_path
+               FROM documents
+               WHERE COALESCE((source_metadata->'moved_from'->>'source_id')::uuid, source_id) = $1
+                 AND source_metadata->>'file_id' = ANY($2)
+               ORDER BY source_metadata->>'file_id', created_at DESC"#
+        )
+        .bind(source_id)
+        .bind(file_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("file_id"), (row.get("id"), row.get("source_path"))))
+            .collect())
+    }
+
+    /// Points a document at the path its file was renamed or moved to. The
+    /// filename follows unless the user renamed the document. A document the
+    /// user moved keeps its location; only the remote path recorded in
+    /// `moved_from` follows the file.
+    pub async fn move_source_document(&self, document_id: Uuid, source_path: &str, file_name: &str) -> Result<()> {
+        sqlx::query(
+            r#"UPDATE documents
+               SET source_path = CASE WHEN source_metadata->'moved_from' IS NULL THEN $2 ELSE source_path END,
+                   filename = CASE WHEN filename = original_filename THEN $3 ELSE filename END,
+                   original_filename = $3,
+                   source_metadata = CASE
+                       WHEN source_metadata->'moved_from' IS NULL
+                           THEN jsonb_set(COALESCE(source_metadata, '{}'::jsonb), '{source_path}', to_jsonb($2::text))
+                       ELSE jsonb_set(
+                           jsonb_set(source_metadata, '{source_path}', to_jsonb($2::text)),
+                           '{moved_from,source_path}', to_jsonb($2::text)
+                       )
+                   END,
+                   updated_at = NOW()
+               WHERE id = $1"#
+        )
+        .bind(document_id)
+        .bind(source_path)
+        .bind(file_name)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Assigns existing documents of `user_id` to a source without ingesting them
+    /// again: those in `request.document_ids` that have no source or already
+    /// belong to this one, and those without a source matching `request.filter`.
+    /// Later syncs recognize the files by content and record their versions.
+    pub async fn adopt_documents_into_source(
+        &self,
+        user_id: Uuid,
+        source_id: Uuid,
+        request: &crate::models::AdoptDocumentsRequest,
+    ) -> Result<crate::models::AdoptDocumentsResponse> {
+        use crate::models::{AdoptionRejection, RejectedAdoption};
+
+        let mut tx = self.pool.begin().await?;
+        let mut response = crate::models::AdoptDocumentsResponse::default();
+        let mut to_adopt: Vec<Uuid> = Vec::new();
+
+        if !request.document_ids.is_empty() {
+            let rows = sqlx::query(
+                "SELECT id, source_id FROM documents WHERE id = ANY($1) AND user_id = $2 FOR UPDATE"
+            )
+            .bind(&request.document_ids)
+            .bind(user_id)
+            .fetch_all(&mut *tx)
+            .await?;
+            let owners: std::collections::HashMap<Uuid, Option<Uuid>> =
+                rows.iter().map(|row| (row.get("id"), row.get("source_id"))).collect();
+
+            for &document_id in &request.document_ids {
+                let reason = match owners.get(&document_id) {
+                    None => AdoptionRejection::NotFound,
+                    Some(Some(owner)) if *owner != source_id => AdoptionRejection::OwnedByOtherSource,
+                    Some(_) => {
+                        to_adopt.push(document_id);
+                        continue;
+                    }
+                };
+                response.rejected.push(RejectedAdoption { document_id, reason });
+            }
+        }
+
+        if let Some(filter) = &request.filter {
+            let matching: Vec<Uuid> = sqlx::query_scalar(
+                r#"SELECT id FROM documents
+                   WHERE user_id = $1 AND source_id IS NULL
+                     AND ($2::text[] IS NULL OR mime_type = ANY($2))
+                     AND ($3::timestamptz IS NULL OR created_at >= $3)
+                     AND ($4::timestamptz IS NULL OR created_at <= $4)
+                   ORDER BY created_at
+                   FOR UPDATE"#
+            )
+            .bind(&filter.mime_types)
+            .bind(filter.created_after)
+            .bind(filter.created_before)
+            .fetch_all(&mut *tx)
+            .await?;
+            to_adopt.extend(matching);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        to_adopt.retain(|id| seen.insert(*id));
+
+        if !to_adopt.is_empty() {
+            sqlx::query(
+                r#"UPDATE documents
+                   SET source_id = $1, source_type = 'source_sync', updated_at = NOW()
+                   WHERE id = ANY($2)"#
+            )
+            .bind(source_id)
+            .bind(&to_adopt)
+            .execute(&mut *tx)
+            .await?;
+
+            let (path_ids, paths): (Vec<Uuid>, Vec<String>) = request
+                .source_paths
+                .iter()
+                .filter(|(id, _)| seen.contains(*id))
+                .map(|(id, path)| (*id, path.clone()))
+                .unzip();
+            if !path_ids.is_empty() {
+                sqlx::query(
+                    r#"UPDATE documents d SET source_path = p.path
+                       FROM UNNEST($1::uuid[], $2::text[]) AS p(id, path)
+                       WHERE d.id = p.id"#
+                )
+                .bind(&path_ids)
+                .bind(&paths)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        info!("Adopted {} documents into source {} for user {}", to_adopt.len(), source_id, user_id);
+        response.adopted = to_adopt;
+        Ok(response)
+    }
+
+    /// Moves the credentials of every source not under the keyring's current
+    /// master key to it, `batch_size` sources per transaction. Sources locked by
+    /// a concurrent update are skipped and left for the next rotation, so
+    /// syncing and editing sources carries on meanwhile.
+    pub async fn rotate_source_credentials(
+        &self,
+        keyring: &CredentialKeyring,
+        batch_size: usize,
+    ) -> Result<CredentialRotationSummary> {
+        self.reencrypt_source_credentials(keyring, batch_size, false).await
+    }
+
+    /// Encrypts the credentials of sources still stored in plain text, e.g.
+    /// when master keys are configured for the first time. Sources under an
+    /// older key are left to [`Self::rotate_source_credentials`].
+    pub async fn encrypt_plaintext_source_credentials(
+        &self,
+        keyring: &CredentialKeyring,
+        batch_size: usize,
+    ) -> Result<CredentialRotationSummary> {
+        self.reencrypt_source_credentials(keyring, batch_size, true).await
+    }
+
+    /// Master key versions stored credentials are encrypted with
+    pub async fn source_credential_key_versions(&self) -> Result<Vec<i32>> {
+        let versions = sqlx::query_scalar(
+            "SELECT DISTINCT credentials_key_version FROM sources WHERE credentials_key_version IS NOT NULL ORDER BY 1"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(versions)
+    }
+
+    async fn reencrypt_source_credentials(
+        &self,
+        keyring: &CredentialKeyring,
+        batch_size: usize,
+        plaintext_only: bool,
+    ) -> Result<CredentialRotationSummary> {
+        let current = keyring
+            .current_version()
+            .ok_or_else(|| anyhow::anyhow!("No credential master key is configured"))?;
+        let mut summary = CredentialRotationSummary {
+            current_key_version: current,
+            ..Default::default()
+        };
+        let mut failed_ids: Vec<Uuid> = Vec::new();
+
+        loop {
+            let mut tx = self.pool.begin().await?;
+            let rows = sqlx::query(
+                r#"SELECT id, config FROM sources
+                   WHERE credentials_key_version IS DISTINCT FROM $1 AND id <> ALL($2)
+                     AND (NOT $4 OR credentials_key_version IS NULL)
+                   ORDER BY id
+                   LIMIT $3
+                   FOR UPDATE SKIP LOCKED"#
+            )
+            .bind(current)
+            .bind(&failed_ids)
+            .bind(batch_size.max(1) as i64)
+            .bind(plaintext_only)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            if rows.is_empty() {
+                tx.commit().await?;
+                break;
+            }
+            summary.batches += 1;
+
+            for row in rows {
+                let source_id: Uuid = row.get("id");
+                let config: serde_json::Value = row.get("config");
+                match keyring.encrypt_config(&config) {
+                    Ok((config, key_version)) => {
+                        sqlx::query("UPDATE sources SET config = $2, credentials_key_version = $3 WHERE id = $1")
+                            .bind(source_id)
+                            .bind(&config)
+                            .bind(key_version)
+                            .execute(&mut *tx)
+                            .await?;
+                        summary.rotated += 1;
+                    }
+                    Err(e) => {
+                        warn!("Could not rotate the credentials of source {}: {}", source_id, e);
+                        failed_ids.push(source_id);
+                        summary.failed += 1;
+                    }
+                }
+            }
+            tx.commit().await?;
+        }
+
+        summary.remaining = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM sources
+               WHERE credentials_key_version IS DISTINCT FROM $1
+                 AND (NOT $2 OR credentials_key_version IS NULL)"#
+        )
+        .bind(current)
+        .bind(plaintext_only)
+        .fetch_one(&self.pool)
+        .await?;
+
+        info!(
+            "{} the credentials of {} sources with master key v{} in {} batches ({} failed, {} remaining)",
+            if plaintext_only { "Encrypted" } else { "Rotated" },
+            summary.rotated, current, summary.batches, summary.failed, summary.remaining
+        );
+        Ok(summary)
+    }
+}
@@ -188,7 +188,7 @@ impl DocumentTransactionManager {
                 
                 -- Documents stuck in processing
                 (SELECT COUNT(*) FROM documents
-                 WHERE ocr_status = 'processing'
+                 WHERE ocr_status IN ('processing', 'preview')
                    AND updated_at < NOW() - INTERVAL '30 minutes') as stuck_processing,
                 
                 -- Inconsistent states
@@ -220,7 +220,7 @@ impl DocumentTransactionManager {
             r#"
             UPDATE documents
             SET ocr_status = 'pending'
-            WHERE ocr_status = 'processing'
+            WHERE ocr_status IN ('processing', 'preview')
               AND updated_at < NOW() - INTERVAL '30 minutes'
             "#
         )
@@ -0,0 +1,311 @@
+/*!
+ * Structured Error Responses
+ *
+ * With `API_ERROR_FORMAT=structured`, every error response (4xx and 5xx) of
+ * the API is rewritten into one envelope:
+ *
+ * ```json
+ * {"error": {"code": "NOT_FOUND", "message": "Not Found", "details": null, "request_id": "…"}}
+ * ```
+ *
+ * Handlers keep returning what they always did: bare status codes, the
+ * `{"error": "...", "code": "..."}` bodies of the `AppError` types, or the
+ * plain text of axum's extractor rejections. The message and code are taken
+ * from the body when it has them, and from the status otherwise. Rejected JSON
+ * bodies and query strings are reported as `VALIDATION_ERROR` with the
+ * offending fields listed in `details.fields`.
+ *
+ * Server errors can carry internals (SQL, storage paths, WebDAV responses), so
+ * their message and details are replaced by generic ones unless
+ * `API_ERROR_EXPOSE_DETAILS` is set. The default `legacy` format leaves
+ * responses untouched.
+ */
+
+use std::str::FromStr;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use utoipa::ToSchema;
+
+use crate::config::Config;
+
+/// Largest error body read for rewriting; larger bodies are replaced by the
+/// status's generic message
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// Prefixes of axum's rejection messages for bodies and query strings that
+/// don't match the expected type
+const DESERIALIZE_REJECTION_PREFIXES: [&str; 2] = [
+    "Failed to deserialize the JSON body into the target type: ",
+    "Failed to deserialize query string: ",
+];
+
+/// Fields of a legacy error body that are not carried over into `details`
+const LEGACY_ENVELOPE_FIELDS: [&str; 7] = ["error", "message", "code", "error_code", "status", "timestamp", "request_id"];
+
+/// Shape of API error responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiErrorFormat {
+    /// Whatever each handler returns
+    #[default]
+    Legacy,
+    /// The `{"error": {code, message, details}}` envelope
+    Structured,
+}
+
+impl ApiErrorFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiErrorFormat::Legacy => "legacy",
+            ApiErrorFormat::Structured => "structured",
+        }
+    }
+}
+
+impl FromStr for ApiErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "legacy" => Ok(ApiErrorFormat::Legacy),
+            "structured" | "envelope" => Ok(ApiErrorFormat::Structured),
+            other => Err(format!("Invalid API error format '{}' (expected legacy or structured)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiErrorFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error response settings handed to the middleware
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiErrorSettings {
+    pub format: ApiErrorFormat,
+    /// Keep the messages and technical details of server errors
+    pub expose_details: bool,
+}
+
+impl ApiErrorSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            format: config.api_error_format,
+            expose_details: config.api_error_expose_details,
+        }
+    }
+}
+
+/// Technical description of an error, attached to a response as an extension by
+/// the `AppError` types. It is only ever shown with `API_ERROR_EXPOSE_DETAILS`.
+#[derive(Debug, Clone)]
+pub struct TechnicalDetails(pub String);
+
+/// A rejected request field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    /// Path of the field, such as `name` or `config.watch_folders`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into() }
+    }
+}
+
+/// Body of a structured error response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorEnvelope {
+    pub error: ErrorBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+    /// Stable machine-readable code, such as `NOT_FOUND` or `SOURCE_NOT_FOUND`
+    pub code: String,
+    pub message: String,
+    /// Extra information about the error; `fields` lists rejected request fields
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<Value>,
+    /// The `x-request-id` of the request, for matching the error to the server logs
+    pub request_id: Option<String>,
+}
+
+/// Code used for an error response without one of its own
+pub fn code_for_status(status: StatusCode) -> String {
+    match status {
+        StatusCode::BAD_REQUEST => "BAD_REQUEST".to_string(),
+        StatusCode::UNAUTHORIZED => "UNAUTHORIZED".to_string(),
+        StatusCode::FORBIDDEN => "FORBIDDEN".to_string(),
+        StatusCode::NOT_FOUND => "NOT_FOUND".to_string(),
+        StatusCode::METHOD_NOT_ALLOWED => "METHOD_NOT_ALLOWED".to_string(),
+        StatusCode::CONFLICT => "CONFLICT".to_string(),
+        StatusCode::PAYLOAD_TOO_LARGE => "PAYLOAD_TOO_LARGE".to_string(),
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "UNSUPPORTED_MEDIA_TYPE".to_string(),
+        StatusCode::UNPROCESSABLE_ENTITY => "VALIDATION_ERROR".to_string(),
+        StatusCode::TOO_MANY_REQUESTS => "RATE_LIMITED".to_string(),
+        StatusCode::INTERNAL_SERVER_ERROR => "INTERNAL_ERROR".to_string(),
+        StatusCode::BAD_GATEWAY => "BAD_GATEWAY".to_string(),
+        StatusCode::SERVICE_UNAVAILABLE => "SERVICE_UNAVAILABLE".to_string(),
+        StatusCode::GATEWAY_TIMEOUT => "GATEWAY_TIMEOUT".to_string(),
+        other => format!("HTTP_{}", other.as_u16()),
+    }
+}
+
+/// Message used for an error response without one of its own
+fn message_for_status(status: StatusCode) -> String {
+    if status == StatusCode::INTERNAL_SERVER_ERROR {
+        return "An internal error occurred".to_string();
+    }
+    status.canonical_reason().unwrap_or("Request failed").to_string()
+}
+
+/// The fields named by an axum rejection of a JSON body or query string, such as
+/// `Failed to deserialize the JSON body into the target type: missing field `name` at line 1 column 2`
+pub fn rejection_field_errors(message: &str) -> Vec<FieldError> {
+    let Some(inner) = DESERIALIZE_REJECTION_PREFIXES.iter().find_map(|prefix| message.strip_prefix(prefix)) else {
+        return Vec::new();
+    };
+    let inner = strip_position(inner.trim());
+
+    // serde_path_to_error prefixes the message with the path unless the error is at the root
+    let (path, reason) = match inner.split_once(": ") {
+        Some((path, reason)) if !path.is_empty() && !path.contains(' ') => (Some(path), reason),
+        _ => (None, inner),
+    };
+
+    let field = match reason.strip_prefix("missing field `").and_then(|rest| rest.split_once('`')) {
+        Some((missing, _)) => match path {
+            Some(path) => format!("{}.{}", path, missing),
+            None => missing.to_string(),
+        },
+        None => match path {
+            Some(path) => path.to_string(),
+            None => return Vec::new(),
+        },
+    };
+    vec![FieldError::new(field, reason)]
+}
+
+/// `message` without serde_json's trailing ` at line 1 column 2`
+fn strip_position(message: &str) -> &str {
+    match message.rfind(" at line ") {
+        Some(index) if message[index + " at line ".len()..].contains(" column ") => &message[..index],
+        _ => message,
+    }
+}
+
+/// The envelope for an error response with `status` and `body`
+pub fn error_envelope(
+    status: StatusCode,
+    body: &[u8],
+    technical: Option<&str>,
+    expose_details: bool,
+    request_id: Option<String>,
+) -> ErrorEnvelope {
+    let mut code = None;
+    let mut message = None;
+    let mut details = Map::new();
+
+    match serde_json::from_slice::<Value>(body) {
+        // Already an envelope
+        Ok(Value::Object(object)) if object.get("error").is_some_and(Value::is_object) => {
+            if let Ok(envelope) = serde_json::from_value::<ErrorEnvelope>(Value::Object(object)) {
+                return envelope;
+            }
+        }
+        Ok(Value::Object(object)) => {
+            for (key, value) in object {
+                match key.as_str() {
+                    "error" | "message" if message.is_none() => message = value.as_str().map(str::to_string),
+                    "code" | "error_code" if code.is_none() => code = value.as_str().map(str::to_string),
+                    "details" => {
+                        if let Value::Object(inner) = value {
+                            details.extend(inner);
+                        } else if !value.is_null() {
+                            details.insert(key, value);
+                        }
+                    }
+                    _ if LEGACY_ENVELOPE_FIELDS.contains(&key.as_str()) => {}
+                    _ => {
+                        details.insert(key, value);
+                    }
+                }
+            }
+        }
+        Ok(Value::String(text)) => message = Some(text),
+        _ => {
+            let text = String::from_utf8_lossy(body).trim().to_string();
+            if !text.is_empty() {
+                message = Some(text);
+            }
+        }
+    }
+
+    if let Some(text) = &message {
+        let fields = rejection_field_errors(text);
+        if !fields.is_empty() {
+            code.get_or_insert_with(|| "VALIDATION_ERROR".to_string());
+            details.insert("fields".to_string(), json!(fields));
+        }
+    }
+
+    if status.is_server_error() && !expose_details {
+        message = None;
+        details.clear();
+    } else if let Some(technical) = technical.filter(|_| expose_details) {
+        details.insert("internal".to_string(), Value::String(technical.to_string()));
+    }
+
+    ErrorEnvelope {
+        error: ErrorBody {
+            code: code.unwrap_or_else(|| code_for_status(status)),
+            message: message.filter(|text| !text.trim().is_empty()).unwrap_or_else(|| message_for_status(status)),
+            details: (!details.is_empty()).then_some(Value::Object(details)),
+            request_id,
+        },
+    }
+}
+
+/// Middleware rewriting error responses into the envelope when the format is
+/// `structured`. It has to run inside response compression, since it reads the
+/// bodies it rewrites.
+pub async fn structured_errors(State(settings): State<ApiErrorSettings>, request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    let technical = response.extensions_mut().remove::<TechnicalDetails>();
+    let status = response.status();
+    if settings.format != ApiErrorFormat::Structured || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES).await.unwrap_or_default();
+    let envelope = error_envelope(
+        status,
+        &body,
+        technical.as_ref().map(|details| details.0.as_str()),
+        settings.expose_details,
+        crate::logging::current_request_id(),
+    );
+
+    let body = match serde_json::to_vec(&envelope) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::warn!("Could not serialize error envelope: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Response::from_parts(parts, Body::from(body))
+}
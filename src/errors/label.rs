@@ -51,6 +51,15 @@ pub enum LabelError {
     
     #[error("Label '{name}' is reserved and cannot be created")]
     ReservedName { name: String },
+    
+    #[error("Invalid OCR DPI {dpi}. Use a value between 72 and 1200")]
+    InvalidOcrDpi { dpi: i32 },
+
+    #[error("Invalid OCR region '{name}': {reason}")]
+    InvalidOcrRegion { name: String, reason: String },
+
+    #[error("Internal error: {message}")]
+    InternalError { message: String },
 }
 
 impl AppError for LabelError {
@@ -70,6 +79,9 @@ impl AppError for LabelError {
             LabelError::DeleteRestricted { .. } => StatusCode::CONFLICT,
             LabelError::InvalidAssignment { .. } => StatusCode::BAD_REQUEST,
             LabelError::ReservedName { .. } => StatusCode::CONFLICT,
+            LabelError::InvalidOcrDpi { .. } => StatusCode::BAD_REQUEST,
+            LabelError::InvalidOcrRegion { .. } => StatusCode::BAD_REQUEST,
+            LabelError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
     
@@ -89,6 +101,9 @@ impl AppError for LabelError {
             LabelError::DeleteRestricted { reason } => format!("Cannot delete label: {}", reason),
             LabelError::InvalidAssignment { reason, .. } => format!("Invalid label assignment: {}", reason),
             LabelError::ReservedName { .. } => "Label name is reserved and cannot be used".to_string(),
+            LabelError::InvalidOcrDpi { dpi } => format!("Invalid OCR DPI {} - use a value between 72 and 1200", dpi),
+            LabelError::InvalidOcrRegion { name, reason } => format!("Invalid OCR region '{}': {}", name, reason),
+            LabelError::InternalError { .. } => "An internal error occurred".to_string(),
         }
     }
     
@@ -109,6 +124,9 @@ impl AppError for LabelError {
             LabelError::DeleteRestricted { .. } => "LABEL_DELETE_RESTRICTED",
             LabelError::InvalidAssignment { .. } => "LABEL_INVALID_ASSIGNMENT",
             LabelError::ReservedName { .. } => "LABEL_RESERVED_NAME",
+            LabelError::InvalidOcrDpi { .. } => "LABEL_INVALID_OCR_DPI",
+            LabelError::InvalidOcrRegion { .. } => "LABEL_INVALID_OCR_REGION",
+            LabelError::InternalError { .. } => "LABEL_INTERNAL_ERROR",
         }
     }
     
@@ -124,6 +142,7 @@ impl AppError for LabelError {
         match self {
             LabelError::SystemLabelModification { .. } 
             | LabelError::PermissionDenied { .. } => ErrorSeverity::Important,
+            LabelError::InternalError { .. } => ErrorSeverity::Critical,
             LabelError::NotFound 
             | LabelError::DuplicateName { .. } 
             | LabelError::LabelInUse { .. } => ErrorSeverity::Expected,
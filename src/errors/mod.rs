@@ -5,9 +5,12 @@ use axum::{
 use serde_json::json;
 use thiserror::Error;
 
+use crate::error_envelope::FieldError;
 use crate::monitoring::error_management::{
     ErrorCategory, ErrorSeverity, ManagedError,
 };
+use crate::ocr::error::OcrError;
+use crate::storage::StorageAccessDenied;
 
 /// Common trait for all custom error types in the application
 pub trait AppError: std::error::Error + Send + Sync + 'static {
@@ -35,6 +38,12 @@ pub trait AppError: std::error::Error + Send + Sync + 'static {
     fn suggested_action(&self) -> Option<String> {
         None
     }
+
+    /// Get optional structured details, such as the rejected fields of a
+    /// validation error under `fields`
+    fn details(&self) -> Option<serde_json::Value> {
+        None
+    }
     
     /// Convert to a ManagedError for the error management system
     fn to_managed_error(&self) -> ManagedError {
@@ -70,13 +79,21 @@ macro_rules! impl_into_response {
                 
                 // Create HTTP response
                 let status = self.status_code();
-                let body = Json(json!({
+                let mut body = json!({
                     "error": self.user_message(),
                     "code": self.error_code(),
                     "status": status.as_u16()
-                }));
-                
-                (status, body).into_response()
+                });
+                if let Some(details) = self.details() {
+                    body["details"] = details;
+                }
+
+                // Internals are only shown with API_ERROR_EXPOSE_DETAILS
+                let mut response = (status, Json(body)).into_response();
+                response
+                    .extensions_mut()
+                    .insert(crate::error_envelope::TechnicalDetails(self.to_string()));
+                response
             }
         }
     };
@@ -111,6 +128,37 @@ pub enum ApiError {
     
     #[error("Service unavailable: {message}")]
     ServiceUnavailable { message: String },
+
+    #[error("Validation failed: {message}")]
+    Validation { message: String, fields: Vec<FieldError> },
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Storage error: {message}")]
+    Storage { message: String },
+
+    #[error(transparent)]
+    StorageAccessDenied(#[from] StorageAccessDenied),
+
+    #[error("OCR error: {0}")]
+    Ocr(#[from] OcrError),
+
+    #[error("WebDAV error: {message}")]
+    WebDav { kind: WebDavFailure, message: String },
+}
+
+/// How a request to a WebDAV server failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebDavFailure {
+    /// The server could not be reached
+    Unreachable,
+    /// The server did not answer in time
+    Timeout,
+    /// The server refused the source's credentials
+    AuthenticationFailed,
+    /// The server answered with an error
+    ServerError,
 }
 
 impl AppError for ApiError {
@@ -124,6 +172,29 @@ impl AppError for ApiError {
             ApiError::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
             ApiError::InternalServerError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::ServiceUnavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Validation { .. } => StatusCode::BAD_REQUEST,
+            ApiError::Database(error) => match error {
+                sqlx::Error::RowNotFound => StatusCode::NOT_FOUND,
+                sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => StatusCode::SERVICE_UNAVAILABLE,
+                _ if Self::database_error_code(error).is_some() => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            ApiError::Storage { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::StorageAccessDenied(_) => StatusCode::FORBIDDEN,
+            ApiError::Ocr(error) => match error {
+                OcrError::ImageTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+                OcrError::InvalidImageFormat { .. } | OcrError::LowConfidence { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+                OcrError::OcrTimeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+                _ if error.is_configuration_error() => StatusCode::SERVICE_UNAVAILABLE,
+                OcrError::InsufficientMemory { .. } => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            ApiError::WebDav { kind, .. } => match kind {
+                WebDavFailure::Timeout => StatusCode::GATEWAY_TIMEOUT,
+                WebDavFailure::Unreachable | WebDavFailure::AuthenticationFailed | WebDavFailure::ServerError => {
+                    StatusCode::BAD_GATEWAY
+                }
+            },
         }
     }
     
@@ -137,6 +208,22 @@ impl AppError for ApiError {
             ApiError::PayloadTooLarge { message } => message.clone(),
             ApiError::InternalServerError { .. } => "An internal error occurred".to_string(),
             ApiError::ServiceUnavailable { message } => message.clone(),
+            ApiError::Validation { message, .. } => message.clone(),
+            ApiError::Database(sqlx::Error::RowNotFound) => "Resource not found".to_string(),
+            ApiError::Database(error) if Self::database_error_code(error).is_some() => {
+                "The change conflicts with existing data".to_string()
+            }
+            ApiError::Database(_) => "A database error occurred".to_string(),
+            ApiError::Storage { .. } => "A storage error occurred".to_string(),
+            ApiError::StorageAccessDenied(_) => "Access to the file was denied".to_string(),
+            // OCR errors describe the file or the OCR setup, not the server's internals
+            ApiError::Ocr(error) => error.to_string(),
+            ApiError::WebDav { kind, .. } => match kind {
+                WebDavFailure::Unreachable => "The WebDAV server could not be reached".to_string(),
+                WebDavFailure::Timeout => "The WebDAV server did not respond in time".to_string(),
+                WebDavFailure::AuthenticationFailed => "The WebDAV server rejected the credentials".to_string(),
+                WebDavFailure::ServerError => "The WebDAV server returned an error".to_string(),
+            },
         }
     }
     
@@ -150,21 +237,49 @@ impl AppError for ApiError {
             ApiError::PayloadTooLarge { .. } => "PAYLOAD_TOO_LARGE",
             ApiError::InternalServerError { .. } => "INTERNAL_SERVER_ERROR",
             ApiError::ServiceUnavailable { .. } => "SERVICE_UNAVAILABLE",
+            ApiError::Validation { .. } => "VALIDATION_ERROR",
+            ApiError::Database(error) => match error {
+                sqlx::Error::RowNotFound => "NOT_FOUND",
+                sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => "DATABASE_UNAVAILABLE",
+                _ => Self::database_error_code(error).unwrap_or("DATABASE_ERROR"),
+            },
+            ApiError::Storage { .. } => "STORAGE_ERROR",
+            ApiError::StorageAccessDenied(_) => "STORAGE_ACCESS_DENIED",
+            ApiError::Ocr(error) => error.error_code(),
+            ApiError::WebDav { kind, .. } => match kind {
+                WebDavFailure::Unreachable => "WEBDAV_UNREACHABLE",
+                WebDavFailure::Timeout => "WEBDAV_TIMEOUT",
+                WebDavFailure::AuthenticationFailed => "WEBDAV_AUTH_FAILED",
+                WebDavFailure::ServerError => "WEBDAV_ERROR",
+            },
         }
     }
     
     fn error_category(&self) -> ErrorCategory {
-        ErrorCategory::Network // Default for generic API errors
+        match self {
+            ApiError::Database(_) => ErrorCategory::Database,
+            ApiError::Storage { .. } | ApiError::StorageAccessDenied(_) => ErrorCategory::FileSystem,
+            ApiError::Ocr(_) => ErrorCategory::OcrProcessing,
+            ApiError::Unauthorized | ApiError::Forbidden { .. } => ErrorCategory::Auth,
+            _ => ErrorCategory::Network, // Default for generic API errors
+        }
     }
     
     fn error_severity(&self) -> ErrorSeverity {
         match self {
-            ApiError::InternalServerError { .. } => ErrorSeverity::Critical,
+            ApiError::InternalServerError { .. } | ApiError::Database(_) | ApiError::Storage { .. } => ErrorSeverity::Critical,
             ApiError::ServiceUnavailable { .. } => ErrorSeverity::Important,
             ApiError::Unauthorized | ApiError::Forbidden { .. } => ErrorSeverity::Important,
             _ => ErrorSeverity::Minor,
         }
     }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ApiError::Validation { fields, .. } => Some(json!({ "fields": fields })),
+            _ => None,
+        }
+    }
 }
 
 impl_into_response!(ApiError);
@@ -194,6 +309,46 @@ impl ApiError {
     pub fn service_unavailable<S: Into<String>>(message: S) -> Self {
         Self::ServiceUnavailable { message: message.into() }
     }
+
+    /// Validation error rejecting one field
+    pub fn invalid_field<F: Into<String>, S: Into<String>>(field: F, message: S) -> Self {
+        let message = message.into();
+        Self::Validation {
+            fields: vec![FieldError::new(field, message.clone())],
+            message,
+        }
+    }
+
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage { message: message.into() }
+    }
+
+    /// Classifies a failed request to a WebDAV server by the HTTP client error
+    /// behind it
+    pub fn webdav(error: &anyhow::Error) -> Self {
+        let kind = match error.chain().find_map(|cause| cause.downcast_ref::<reqwest::Error>()) {
+            Some(e) if e.is_timeout() => WebDavFailure::Timeout,
+            Some(e) if e.is_connect() => WebDavFailure::Unreachable,
+            Some(e) if matches!(e.status().map(|status| status.as_u16()), Some(401 | 403)) => {
+                WebDavFailure::AuthenticationFailed
+            }
+            _ => WebDavFailure::ServerError,
+        };
+        Self::WebDav { kind, message: error.to_string() }
+    }
+
+    /// Code of a constraint violation the client can resolve, such as a
+    /// duplicate name
+    fn database_error_code(error: &sqlx::Error) -> Option<&'static str> {
+        let database_error = error.as_database_error()?;
+        if database_error.is_unique_violation() {
+            Some("DUPLICATE_RESOURCE")
+        } else if database_error.is_foreign_key_violation() {
+            Some("REFERENCED_RESOURCE_MISSING")
+        } else {
+            None
+        }
+    }
 }
 
 // Re-export commonly used types (already imported above)
@@ -207,4 +362,5 @@ pub mod settings;
 pub mod search;
 pub mod shared_link;
 pub mod comment;
+pub mod note;
 pub mod api_key;
\ No newline at end of file
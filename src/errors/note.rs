@@ -0,0 +1,77 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use super::{AppError, ErrorCategory, ErrorSeverity, impl_into_response};
+
+#[derive(Error, Debug)]
+pub enum NoteError {
+    #[error("Note not found")]
+    NotFound,
+
+    #[error("Document not found")]
+    DocumentNotFound,
+
+    #[error("Note content cannot be empty")]
+    ContentEmpty,
+
+    #[error("Note content too long: {length} characters (max: {max_length})")]
+    ContentTooLong { length: usize, max_length: usize },
+
+    #[error("Internal error: {message}")]
+    InternalError { message: String },
+}
+
+impl AppError for NoteError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            NoteError::NotFound | NoteError::DocumentNotFound => StatusCode::NOT_FOUND,
+            NoteError::ContentEmpty | NoteError::ContentTooLong { .. } => StatusCode::BAD_REQUEST,
+            NoteError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn user_message(&self) -> String {
+        match self {
+            NoteError::NotFound => "Note not found".to_string(),
+            NoteError::DocumentNotFound => "Document not found".to_string(),
+            NoteError::ContentEmpty => "Note cannot be empty".to_string(),
+            NoteError::ContentTooLong { max_length, .. } => format!("Note is too long (max {} characters)", max_length),
+            NoteError::InternalError { .. } => "An internal error occurred".to_string(),
+        }
+    }
+
+    fn error_code(&self) -> &'static str {
+        match self {
+            NoteError::NotFound => "NOTE_NOT_FOUND",
+            NoteError::DocumentNotFound => "NOTE_DOCUMENT_NOT_FOUND",
+            NoteError::ContentEmpty => "NOTE_CONTENT_EMPTY",
+            NoteError::ContentTooLong { .. } => "NOTE_CONTENT_TOO_LONG",
+            NoteError::InternalError { .. } => "NOTE_INTERNAL_ERROR",
+        }
+    }
+
+    fn error_category(&self) -> ErrorCategory {
+        match self {
+            NoteError::InternalError { .. } => ErrorCategory::Database,
+            _ => ErrorCategory::Network,
+        }
+    }
+
+    fn error_severity(&self) -> ErrorSeverity {
+        match self {
+            NoteError::InternalError { .. } => ErrorSeverity::Critical,
+            NoteError::NotFound | NoteError::DocumentNotFound => ErrorSeverity::Expected,
+            _ => ErrorSeverity::Minor,
+        }
+    }
+
+    fn suggested_action(&self) -> Option<String> {
+        match self {
+            NoteError::ContentEmpty => Some("Enter some text for your note".to_string()),
+            NoteError::ContentTooLong { max_length, .. } => Some(format!("Shorten your note to {} characters or less", max_length)),
+            _ => None,
+        }
+    }
+}
+
+impl_into_response!(NoteError);
@@ -30,6 +30,9 @@ pub enum SearchError {
     #[error("Invalid MIME type filter '{mime_type}'")]
     InvalidMimeType { mime_type: String },
     
+    #[error("Unsupported search language '{language}'")]
+    InvalidLanguage { language: String },
+    
     #[error("Invalid pagination parameters: offset {offset}, limit {limit}")]
     InvalidPagination { offset: i64, limit: i64 },
     
@@ -65,6 +68,12 @@ pub enum SearchError {
     
     #[error("Search feature is disabled")]
     SearchDisabled,
+
+    #[error("Unsupported export format '{format}'")]
+    InvalidExportFormat { format: String },
+
+    #[error("Unknown export column '{column}'")]
+    InvalidExportColumn { column: String },
 }
 
 impl AppError for SearchError {
@@ -78,6 +87,7 @@ impl AppError for SearchError {
             SearchError::SearchTimeout { .. } => StatusCode::REQUEST_TIMEOUT,
             SearchError::InvalidSearchMode { .. } => StatusCode::BAD_REQUEST,
             SearchError::InvalidMimeType { .. } => StatusCode::BAD_REQUEST,
+            SearchError::InvalidLanguage { .. } => StatusCode::BAD_REQUEST,
             SearchError::InvalidPagination { .. } => StatusCode::BAD_REQUEST,
             SearchError::BooleanSyntaxError { .. } => StatusCode::BAD_REQUEST,
             SearchError::InvalidFuzzyThreshold { .. } => StatusCode::BAD_REQUEST,
@@ -90,6 +100,8 @@ impl AppError for SearchError {
             SearchError::IndexCorruption { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             SearchError::PermissionDenied => StatusCode::FORBIDDEN,
             SearchError::SearchDisabled => StatusCode::SERVICE_UNAVAILABLE,
+            SearchError::InvalidExportFormat { .. } => StatusCode::BAD_REQUEST,
+            SearchError::InvalidExportColumn { .. } => StatusCode::BAD_REQUEST,
         }
     }
     
@@ -103,6 +115,7 @@ impl AppError for SearchError {
             SearchError::SearchTimeout { .. } => "Search timed out. Please try a more specific query".to_string(),
             SearchError::InvalidSearchMode { .. } => "Invalid search mode. Use: simple, phrase, fuzzy, or boolean".to_string(),
             SearchError::InvalidMimeType { .. } => "Invalid file type filter".to_string(),
+            SearchError::InvalidLanguage { language } => format!("Search language '{}' is not supported", language),
             SearchError::InvalidPagination { .. } => "Invalid pagination parameters".to_string(),
             SearchError::BooleanSyntaxError { details } => format!("Boolean search syntax error: {}", details),
             SearchError::InvalidFuzzyThreshold { .. } => "Fuzzy search threshold must be between 0.0 and 1.0".to_string(),
@@ -115,6 +128,8 @@ impl AppError for SearchError {
             SearchError::IndexCorruption { .. } => "Search index error. Please contact support".to_string(),
             SearchError::PermissionDenied => "Permission denied for search operation".to_string(),
             SearchError::SearchDisabled => "Search feature is currently disabled".to_string(),
+            SearchError::InvalidExportFormat { format } => format!("Export format '{}' is not supported", format),
+            SearchError::InvalidExportColumn { column } => format!("Unknown export column '{}'", column),
         }
     }
     
@@ -128,6 +143,7 @@ impl AppError for SearchError {
             SearchError::SearchTimeout { .. } => "SEARCH_TIMEOUT",
             SearchError::InvalidSearchMode { .. } => "SEARCH_INVALID_MODE",
             SearchError::InvalidMimeType { .. } => "SEARCH_INVALID_MIME_TYPE",
+            SearchError::InvalidLanguage { .. } => "SEARCH_INVALID_LANGUAGE",
             SearchError::InvalidPagination { .. } => "SEARCH_INVALID_PAGINATION",
             SearchError::BooleanSyntaxError { .. } => "SEARCH_BOOLEAN_SYNTAX_ERROR",
             SearchError::InvalidFuzzyThreshold { .. } => "SEARCH_INVALID_FUZZY_THRESHOLD",
@@ -140,6 +156,8 @@ impl AppError for SearchError {
             SearchError::IndexCorruption { .. } => "SEARCH_INDEX_CORRUPTION",
             SearchError::PermissionDenied => "SEARCH_PERMISSION_DENIED",
             SearchError::SearchDisabled => "SEARCH_DISABLED",
+            SearchError::InvalidExportFormat { .. } => "SEARCH_INVALID_EXPORT_FORMAT",
+            SearchError::InvalidExportColumn { .. } => "SEARCH_INVALID_EXPORT_COLUMN",
         }
     }
     
@@ -186,6 +204,7 @@ impl AppError for SearchError {
             SearchError::TooManyResults { .. } => Some("Use more specific search terms or apply filters".to_string()),
             SearchError::SearchTimeout { .. } => Some("Try a more specific search query".to_string()),
             SearchError::InvalidSearchMode { .. } => Some("Use one of: 'simple', 'phrase', 'fuzzy', or 'boolean'".to_string()),
+            SearchError::InvalidLanguage { .. } => Some("Use one of: simple, english, german, french, spanish, italian, portuguese or dutch".to_string()),
             SearchError::BooleanSyntaxError { .. } => Some("Check boolean operators (AND, OR, NOT) and parentheses".to_string()),
             SearchError::InvalidFuzzyThreshold { .. } => Some("Set fuzzy threshold between 0.0 (loose) and 1.0 (exact)".to_string()),
             SearchError::IndexRebuilding => Some("Wait a few minutes for index rebuild to complete".to_string()),
@@ -193,6 +212,8 @@ impl AppError for SearchError {
             SearchError::InvalidSnippetLength { min_length, max_length, .. } => Some(format!("Set snippet length between {} and {}", min_length, max_length)),
             SearchError::QuotaExceeded { .. } => Some("Wait until tomorrow or contact administrator for limit increase".to_string()),
             SearchError::SearchDisabled => Some("Contact administrator to enable search functionality".to_string()),
+            SearchError::InvalidExportFormat { .. } => Some("Use format=csv".to_string()),
+            SearchError::InvalidExportColumn { .. } => Some("Use columns from: id, filename, original_filename, mime_type, file_size, created_at, document_date, labels, ocr_status, ocr_confidence, ocr_word_count, source".to_string()),
             _ => None,
         }
     }
@@ -230,6 +251,10 @@ impl SearchError {
         Self::InvalidSearchMode { mode: mode.into() }
     }
     
+    pub fn invalid_language<S: Into<String>>(language: S) -> Self {
+        Self::InvalidLanguage { language: language.into() }
+    }
+    
     pub fn invalid_mime_type<S: Into<String>>(mime_type: S) -> Self {
         Self::InvalidMimeType { mime_type: mime_type.into() }
     }
@@ -261,4 +286,12 @@ impl SearchError {
     pub fn index_corruption<S: Into<String>>(details: S) -> Self {
         Self::IndexCorruption { details: details.into() }
     }
+    
+    pub fn invalid_export_format<S: Into<String>>(format: S) -> Self {
+        Self::InvalidExportFormat { format: format.into() }
+    }
+    
+    pub fn invalid_export_column<S: Into<String>>(column: S) -> Self {
+        Self::InvalidExportColumn { column: column.into() }
+    }
 }
\ No newline at end of file
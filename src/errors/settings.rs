@@ -3,6 +3,7 @@ use thiserror::Error;
 use uuid::Uuid;
 
 use super::{AppError, ErrorCategory, ErrorSeverity, impl_into_response};
+use crate::error_envelope::FieldError;
 
 /// Errors related to settings management operations
 #[derive(Error, Debug)]
@@ -186,6 +187,21 @@ impl AppError for SettingsError {
             _ => None,
         }
     }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        let field = match self {
+            SettingsError::InvalidLanguage { .. } => "ocr_language",
+            SettingsError::InvalidValue { setting_name, .. }
+            | SettingsError::ReadOnlySetting { setting_name }
+            | SettingsError::ValidationFailed { setting_name, .. }
+            | SettingsError::ValueOutOfRange { setting_name, .. } => setting_name.as_str(),
+            SettingsError::InvalidCpuPriority { .. } => "cpu_priority",
+            SettingsError::MemoryLimitTooLow { .. } | SettingsError::MemoryLimitTooHigh { .. } => "memory_limit_mb",
+            SettingsError::InvalidDpi { .. } => "ocr_dpi",
+            _ => return None,
+        };
+        Some(serde_json::json!({ "fields": [FieldError::new(field, self.user_message())] }))
+    }
 }
 
 impl_into_response!(SettingsError);
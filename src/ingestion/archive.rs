@@ -0,0 +1,159 @@
+//! Extraction of ZIP archives into the files they contain.
+//!
+//! Archives nested inside an archive are extracted in place, up to a configured
+//! depth; deeper ones are returned as plain files. The limits on total size and
+//! number of files cover the whole tree and count the bytes actually
+//! decompressed rather than the sizes the archive claims, so a zip bomb fails
+//! extraction instead of filling memory. Entries whose names would escape the
+//! archive (absolute paths, `..`) are skipped.
+
+use anyhow::{bail, Context, Result};
+use std::io::{Cursor, Read};
+use tracing::warn;
+
+/// MIME types treated as extractable archives
+pub const ARCHIVE_MIME_TYPES: &[&str] = &["application/zip", "application/x-zip-compressed", "application/x-zip"];
+
+/// Magic bytes at the start of a ZIP file
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+pub fn is_archive_mime(mime_type: &str) -> bool {
+    ARCHIVE_MIME_TYPES.contains(&mime_type)
+}
+
+/// How far and how much an archive may be extracted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveLimits {
+    /// Archive levels extracted; 1 extracts only the outer archive
+    pub max_depth: usize,
+    /// Most uncompressed bytes extracted, nested archives included
+    pub max_total_bytes: u64,
+    /// Most files extracted, nested archives included
+    pub max_entries: usize,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_total_bytes: 1024 * 1024 * 1024,
+            max_entries: 1000,
+        }
+    }
+}
+
+impl ArchiveLimits {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            max_depth: config.archive_max_depth.max(1),
+            max_total_bytes: config.archive_max_total_size_mb.saturating_mul(1024 * 1024),
+            max_entries: config.archive_max_entries,
+        }
+    }
+}
+
+/// A file extracted from an archive
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    /// Path inside the archive; files of nested archives are prefixed with the
+    /// nested archive's path, e.g. `2024/receipts.zip/march.pdf`
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+impl ArchiveEntry {
+    pub fn filename(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Normalizes an entry name to a relative `/`-separated path, or None when it
+/// is absolute, climbs out of the archive with `..`, or names no file
+pub fn safe_entry_path(name: &str) -> Option<String> {
+    let name = name.replace('\\', "/");
+    let has_drive_letter = name.len() >= 2 && name.as_bytes()[1] == b':' && name.as_bytes()[0].is_ascii_alphabetic();
+    if name.starts_with('/') || has_drive_letter {
+        return None;
+    }
+
+    let mut components = Vec::new();
+    for component in name.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => return None,
+            component => components.push(component),
+        }
+    }
+
+    if components.is_empty() {
+        None
+    } else {
+        Some(components.join("/"))
+    }
+}
+
+/// Extracts the files of a ZIP archive, descending into nested archives up to
+/// `limits.max_depth`. Fails when the archive can't be read or exceeds the
+/// size or file limits.
+pub fn extract_archive(data: &[u8], limits: &ArchiveLimits) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = Vec::new();
+    let mut total_bytes = 0;
+    extract_into(data, "", 1, limits, &mut entries, &mut total_bytes)?;
+    Ok(entries)
+}
+
+fn extract_into(
+    data: &[u8],
+    prefix: &str,
+    depth: usize,
+    limits: &ArchiveLimits,
+    entries: &mut Vec<ArchiveEntry>,
+    total_bytes: &mut u64,
+) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).context("Not a readable ZIP archive")?;
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).with_context(|| format!("Failed to read entry {} of the archive", index))?;
+        if file.is_dir() {
+            continue;
+        }
+        let Some(relative_path) = safe_entry_path(file.name()) else {
+            warn!("Skipping archive entry with unsafe path {:?}", file.name());
+            continue;
+        };
+        // Resource forks added by macOS archivers are not documents
+        if relative_path.starts_with("__MACOSX/") {
+            continue;
+        }
+        let path = format!("{}{}", prefix, relative_path);
+
+        let remaining = limits.max_total_bytes.saturating_sub(*total_bytes);
+        let mut content = Vec::new();
+        file.by_ref()
+            .take(remaining.saturating_add(1))
+            .read_to_end(&mut content)
+            .with_context(|| format!("Failed to decompress {}", path))?;
+        if content.len() as u64 > remaining {
+            bail!("Archive expands to more than {} bytes", limits.max_total_bytes);
+        }
+        *total_bytes += content.len() as u64;
+
+        if depth < limits.max_depth && is_nested_archive(&path, &content) {
+            extract_into(&content, &format!("{}/", path), depth + 1, limits, entries, total_bytes)?;
+            continue;
+        }
+
+        if entries.len() >= limits.max_entries {
+            bail!("Archive holds more than {} files", limits.max_entries);
+        }
+        entries.push(ArchiveEntry { path, data: content });
+    }
+
+    Ok(())
+}
+
+/// A `.zip` entry that really is a ZIP file. Office documents are ZIP files
+/// too, but keep their own extensions.
+fn is_nested_archive(path: &str, content: &[u8]) -> bool {
+    path.to_lowercase().ends_with(".zip") && content.starts_with(ZIP_MAGIC)
+}
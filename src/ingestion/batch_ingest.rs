@@ -13,7 +13,7 @@ use crate::{
     db::Database,
     services::file_service::FileService,
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult, DeduplicationPolicy},
-    ocr::queue::OcrQueueService,
+    ocr::queue::{OcrPriorityClass, OcrQueueService},
     models::FileIngestionInfo,
 };
 
@@ -116,7 +116,7 @@ impl BatchIngester {
                 // Batch insert documents into queue
                 if !queue_items.is_empty() {
                     info!("Enqueueing {} documents for OCR", queue_items.len());
-                    self.queue_service.enqueue_documents_batch(queue_items.clone()).await?;
+                    self.queue_service.enqueue_documents_batch_with_class(queue_items.clone(), OcrPriorityClass::Backfill).await?;
                     queue_items.clear();
                 }
                 
@@ -275,6 +275,14 @@ async fn process_single_file(
             info!("Tracked batch file {} as duplicate of existing document: {}", file_info.name, existing_document_id);
             Ok(None) // File was tracked as duplicate
         }
+        IngestionResult::Quarantined { failed_document_id, threat } => {
+            warn!("Quarantined batch file {} ({}), recorded as failed document {}", file_info.name, threat, failed_document_id);
+            Ok(None) // File was not stored
+        }
+        IngestionResult::Corrupt { failed_document_id, reason } => {
+            warn!("Batch file {} is corrupt ({}), recorded as failed document {}", file_info.name, reason, failed_document_id);
+            Ok(None) // File was not stored
+        }
     }
 }
 
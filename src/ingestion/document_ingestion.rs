@@ -8,13 +8,21 @@
 
 use uuid::Uuid;
 use sha2::{Digest, Sha256};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 use serde_json;
 use chrono::Utc;
 
-use crate::models::{Document, FileIngestionInfo};
+use crate::models::{Document, FailureReason, FailureStage, FileIngestionInfo};
 use crate::db::Database;
 use crate::services::file_service::FileService;
+use crate::services::file_validator::FileIntegrity;
+use crate::services::malware_scanner::ScanDecision;
+use crate::services::pdf_optimizer::{original_pdf_filename, PdfOptimizer};
+use crate::ocr::date_extraction::{file_date, DocumentDateOptions};
+use super::image_normalization::{
+    convert_image, image_format_name, normalized_filename, original_image_filename, ImageNormalizationConfig,
+};
+use super::spool::{DownloadedFile, SpooledFile};
 #[cfg(feature = "ocr")]
 use image::ImageFormat;
 #[cfg(feature = "ocr")]
@@ -42,6 +50,12 @@ pub enum IngestionResult {
     Skipped { existing_document_id: Uuid, reason: String },
     /// Document was tracked as duplicate (for WebDAV)
     TrackedAsDuplicate { existing_document_id: Uuid },
+    /// The malware scanner flagged the file; it was recorded as a failed document
+    /// instead of being stored
+    Quarantined { failed_document_id: Uuid, threat: String },
+    /// The file failed integrity checks (truncated or damaged); it was recorded
+    /// as a failed document instead of being stored
+    Corrupt { failed_document_id: Uuid, reason: String },
 }
 
 #[derive(Debug)]
@@ -70,6 +84,14 @@ pub struct DocumentIngestionRequest {
     pub source_metadata: Option<serde_json::Value>,
 }
 
+/// How a converted image is stored, see [`DocumentIngestionService::normalize_image`]
+struct NormalizedImage {
+    filename: String,
+    mime_type: String,
+    /// Recorded under `image_normalization` in the document's source metadata
+    metadata: serde_json::Value,
+}
+
 pub struct DocumentIngestionService {
     db: Database,
     file_service: FileService,
@@ -80,6 +102,143 @@ impl DocumentIngestionService {
         Self { db, file_service }
     }
 
+    /// Builds the failed document recorded for a file that ingestion refused to store
+    fn rejected_document(
+        request: &DocumentIngestionRequest,
+        file_hash: &str,
+        file_size: i64,
+        failure_reason: FailureReason,
+        failure_stage: FailureStage,
+        error_message: String,
+    ) -> crate::models::FailedDocument {
+        crate::models::FailedDocument {
+            id: Uuid::new_v4(),
+            user_id: request.user_id,
+            filename: request.filename.clone(),
+            original_filename: Some(request.original_filename.clone()),
+            original_path: request.source_path.clone(),
+            file_path: None, // rejected files are never stored
+            file_size: Some(file_size),
+            file_hash: Some(file_hash.to_string()),
+            mime_type: Some(request.mime_type.clone()),
+            content: None,
+            tags: Vec::new(),
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            failure_reason: failure_reason.to_string(),
+            failure_stage: failure_stage.to_string(),
+            existing_document_id: None,
+            ingestion_source: request.source_type.clone().unwrap_or_else(|| "upload".to_string()),
+            error_message: Some(error_message),
+            retry_count: Some(0),
+            last_retry_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Records a file flagged by the malware scanner as a quarantined failed document
+    /// and notifies the user. The file's bytes are never written to storage.
+    async fn quarantine_document(
+        &self,
+        request: &DocumentIngestionRequest,
+        file_hash: &str,
+        file_size: i64,
+        threat: String,
+    ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        warn!(
+            "Malware detected in {} for user {} ({}), quarantining",
+            request.filename, request.user_id, threat
+        );
+
+        let failed_document = Self::rejected_document(
+            request,
+            file_hash,
+            file_size,
+            FailureReason::VirusDetected,
+            FailureStage::Quarantined,
+            format!("Malware detected: {}", threat),
+        );
+        let failed_document = self.db.create_failed_document(failed_document).await?;
+
+        let notification = crate::models::CreateNotification {
+            notification_type: "warning".to_string(),
+            title: "File quarantined".to_string(),
+            message: format!(
+                "{} was not added to your library because the malware scanner detected {}",
+                request.original_filename, threat
+            ),
+            action_url: Some("/documents/management".to_string()),
+            metadata: Some(serde_json::json!({
+                "failed_document_id": failed_document.id,
+                "filename": request.original_filename,
+                "threat": threat,
+                "source_type": request.source_type,
+                "source_id": request.source_id,
+            })),
+        };
+        if let Err(e) = self.db.deliver_notification(request.user_id, &notification).await {
+            warn!("Failed to create quarantine notification for {}: {}", request.filename, e);
+        }
+
+        Ok(IngestionResult::Quarantined {
+            failed_document_id: failed_document.id,
+            threat,
+        })
+    }
+
+    /// Records a file that failed integrity checks as a corrupt failed document and
+    /// notifies the user. The file is neither stored nor queued for OCR.
+    async fn reject_corrupt_document(
+        &self,
+        request: &DocumentIngestionRequest,
+        file_hash: &str,
+        file_size: i64,
+        reason: String,
+    ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        warn!(
+            "{} for user {} failed validation ({}), not storing it",
+            request.filename, request.user_id, reason
+        );
+
+        let failed_document = Self::rejected_document(
+            request,
+            file_hash,
+            file_size,
+            FailureReason::FileCorrupted,
+            FailureStage::Validation,
+            format!("Corrupt file: {}", reason),
+        );
+        let failed_document = self.db.create_failed_document(failed_document).await?;
+
+        let notification = crate::models::CreateNotification {
+            notification_type: "warning".to_string(),
+            title: "Corrupt file skipped".to_string(),
+            message: format!(
+                "{} was not added to your library because it appears to be damaged or incomplete: {}",
+                request.original_filename, reason
+            ),
+            action_url: Some("/documents/management".to_string()),
+            metadata: Some(serde_json::json!({
+                "failed_document_id": failed_document.id,
+                "filename": request.original_filename,
+                "reason": reason,
+                "source_type": request.source_type,
+                "source_id": request.source_id,
+            })),
+        };
+        if let Err(e) = self.db.deliver_notification(request.user_id, &notification).await {
+            warn!("Failed to create corrupt file notification for {}: {}", request.filename, e);
+        }
+
+        Ok(IngestionResult::Corrupt {
+            failed_document_id: failed_document.id,
+            reason,
+        })
+    }
+
     /// Extract metadata from FileIngestionInfo for storage in document
     fn extract_metadata_from_file_info(file_info: &FileIngestionInfo) -> (Option<chrono::DateTime<chrono::Utc>>, Option<chrono::DateTime<chrono::Utc>>, Option<serde_json::Value>) {
         let original_created_at = file_info.created_at;
@@ -123,8 +282,20 @@ impl DocumentIngestionService {
 
     /// Unified document ingestion with configurable deduplication policy
     pub async fn ingest_document(&self, request: DocumentIngestionRequest) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
-        let file_hash = self.calculate_file_hash(&request.file_data);
-        let file_size = request.file_data.len() as i64;
+        self.ingest(request, None).await
+    }
+
+    /// Ingests either `request.file_data` or, when given, a spooled file whose
+    /// contents are read from disk instead
+    async fn ingest(
+        &self,
+        mut request: DocumentIngestionRequest,
+        spooled: Option<&SpooledFile>,
+    ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        let (file_hash, file_size) = match spooled {
+            Some(file) => (file.sha256().to_string(), file.size() as i64),
+            None => (self.calculate_file_hash(&request.file_data), request.file_data.len() as i64),
+        };
         
         // Clone source_type early for error handling
         let source_type_for_error = request.source_type.clone();
@@ -172,11 +343,41 @@ impl DocumentIngestionService {
             }
         }
 
+        // Scan the file before anything is written to storage
+        if let Some(scanner) = self.file_service.malware_scan() {
+            let decision = match spooled {
+                Some(file) => scanner.check_file(file.path()).await,
+                None => scanner.check(&request.file_data).await,
+            };
+            match decision {
+                ScanDecision::Allow => {}
+                ScanDecision::Quarantine(threat) => {
+                    return self.quarantine_document(&request, &file_hash, file_size, threat).await;
+                }
+                ScanDecision::Block(reason) => {
+                    warn!("Refusing to ingest {} because the malware scan did not complete: {}", request.filename, reason);
+                    return Err(reason.into());
+                }
+            }
+        }
+
+        // Damaged or partially downloaded files would only produce garbage OCR
+        if let Some(validator) = self.file_service.file_validator() {
+            let integrity = match spooled {
+                Some(file) => validator.check_file(file.path(), &request.mime_type).await,
+                None => validator.check(&request.file_data, &request.mime_type),
+            };
+            if let FileIntegrity::Corrupt(reason) = integrity {
+                return self.reject_corrupt_document(&request, &file_hash, file_size, reason).await;
+            }
+        }
+
         // Generate document ID upfront so we can use it for storage path
         let document_id = Uuid::new_v4();
 
-        // Rotate image if settings.auto_rotate_images based on EXIF data
-        let file_data = if request.mime_type.starts_with("image/") {
+        // Rotate image if settings.auto_rotate_images based on EXIF data. Spooled files
+        // are too large to decode in memory and are stored as downloaded.
+        let file_data = if spooled.is_none() && request.mime_type.starts_with("image/") {
             // Here is an image, get settings for user
             match self.db.get_user_settings(request.user_id).await? {
                 Some(settings) if settings.auto_rotate_images => { 
@@ -197,11 +398,54 @@ impl DocumentIngestionService {
             // Not an image, use original data
             request.file_data
         };
+
+        // Sources can store their images in one format. The hash stays that of the
+        // ingested content, so syncing the same image again is still a duplicate.
+        let (file_data, file_size) = match request.source_id {
+            Some(source_id) if spooled.is_none() && request.mime_type.starts_with("image/") => {
+                match self.normalize_image(source_id, request.user_id, document_id, &request.filename, &request.mime_type, file_data).await {
+                    (converted, Some(normalized)) => {
+                        let mut metadata = match request.source_metadata.take() {
+                            Some(serde_json::Value::Object(metadata)) => metadata,
+                            _ => serde_json::Map::new(),
+                        };
+                        metadata.insert("image_normalization".to_string(), normalized.metadata);
+                        request.source_metadata = Some(serde_json::Value::Object(metadata));
+                        request.filename = normalized.filename;
+                        request.mime_type = normalized.mime_type;
+                        let converted_size = converted.len() as i64;
+                        (converted, converted_size)
+                    }
+                    (data, None) => (data, file_size),
+                }
+            }
+            _ => (file_data, file_size),
+        };
+
+        // Optimize bloated PDFs. The hash stays that of the original content, so
+        // ingesting the same file again is still recognized as a duplicate.
+        let (file_data, pdf_original) = match self.file_service.pdf_optimizer() {
+            Some(optimizer) if spooled.is_none() && request.mime_type == "application/pdf" => {
+                self.optimize_pdf(optimizer, request.user_id, document_id, &request.filename, file_data).await
+            }
+            _ => (file_data, None),
+        };
+        let file_size = if pdf_original.is_some() { file_data.len() as i64 } else { file_size };
         
         // Save file to storage - use S3 if configured, otherwise local storage
-        let file_path = match self.file_service
-            .save_document_file(request.user_id, document_id, &request.filename, &file_data)
-            .await {
+        let saved = match spooled {
+            Some(file) => {
+                self.file_service
+                    .save_document_file_from_path(request.user_id, document_id, &request.filename, file.path())
+                    .await
+            }
+            None => {
+                self.file_service
+                    .save_document_file(request.user_id, document_id, &request.filename, &file_data)
+                    .await
+            }
+        };
+        let file_path = match saved {
                 Ok(path) => path,
                 Err(e) => {
                     warn!("Failed to save file {}: {}", request.filename, e);
@@ -330,6 +574,20 @@ impl DocumentIngestionService {
             }
         };
 
+        if let Some((original_size, original_path)) = pdf_original {
+            if let Err(e) = self.db.record_pdf_optimization(saved_document.id, original_size, original_path.as_deref()).await {
+                warn!("Failed to record PDF optimization of document {}: {}", saved_document.id, e);
+            }
+        }
+
+        // Text files are dated from their content right away; other documents are
+        // dated from their metadata until OCR finds a date in their text
+        let text = match spooled {
+            None if request.mime_type.starts_with("text/") => std::str::from_utf8(&file_data).ok(),
+            _ => None,
+        };
+        self.apply_document_date(&saved_document, text).await;
+
         debug!(
             "Successfully ingested document: {} (ID: {}) for user {}",
             saved_document.original_filename, saved_document.id, request.user_id
@@ -338,6 +596,168 @@ impl DocumentIngestionService {
         Ok(IngestionResult::Created(saved_document))
     }
 
+    /// Sets a new document's date for users who extract document dates, from
+    /// `text` when given and the document's metadata. OCR sets it again from the
+    /// recognized text, so this is the date of documents that are never OCR'd.
+    /// Failures are logged and never fail the ingestion.
+    async fn apply_document_date(&self, document: &Document, text: Option<&str>) {
+        let settings = match self.db.get_user_settings(document.user_id).await {
+            Ok(Some(settings)) if settings.extract_document_date => settings,
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Failed to load settings for user {}, not dating document {}: {}", document.user_id, document.id, e);
+                return;
+            }
+        };
+
+        let options = DocumentDateOptions::from_settings(&settings);
+        let file_date = file_date(document.source_metadata.as_ref(), document.original_modified_at, document.original_created_at);
+        let Some((date, source)) = options.resolve(text, file_date, Some(document.created_at.date_naive())) else {
+            return;
+        };
+        match self.db.set_document_date(document.id, date, source.as_str()).await {
+            Ok(()) => debug!("Dated document {} {} (from {}) at ingestion", document.id, date, source.as_str()),
+            Err(e) => warn!("Failed to set date of document {}: {}", document.id, e),
+        }
+    }
+
+    /// Runs a PDF through the optimizer. Returns the data to store and, when the
+    /// optimized version is used, the original size and the path the original was
+    /// kept at (if the user keeps originals). Anything that goes wrong leaves the
+    /// PDF as it was.
+    async fn optimize_pdf(
+        &self,
+        optimizer: &PdfOptimizer,
+        user_id: Uuid,
+        document_id: Uuid,
+        filename: &str,
+        data: Vec<u8>,
+    ) -> (Vec<u8>, Option<(i64, Option<String>)>) {
+        let optimized = match optimizer.optimize(&data).await {
+            Ok(Some(optimized)) => optimized,
+            Ok(None) => return (data, None),
+            Err(e) => {
+                warn!("Failed to optimize PDF {}: {}, proceeding with original data", filename, e);
+                return (data, None);
+            }
+        };
+
+        let keep_original = match self.db.get_user_settings(user_id).await {
+            Ok(settings) => settings.map(|settings| settings.keep_original_pdf).unwrap_or(true),
+            Err(e) => {
+                warn!("Failed to load settings for user {}: {}, keeping the original PDF", user_id, e);
+                true
+            }
+        };
+        let original_path = if keep_original {
+            match self
+                .file_service
+                .save_document_file(user_id, document_id, &original_pdf_filename(filename), &data)
+                .await
+            {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    warn!("Failed to keep the original of PDF {}: {}, storing it unoptimized", filename, e);
+                    return (data, None);
+                }
+            }
+        } else {
+            None
+        };
+
+        info!(
+            "Optimized PDF {} from {} to {} bytes ({:.0}% smaller)",
+            filename,
+            data.len(),
+            optimized.len(),
+            100.0 * (1.0 - optimized.len() as f64 / data.len() as f64)
+        );
+        (optimized, Some((data.len() as i64, original_path)))
+    }
+
+    /// Converts an image synced by a source to the source's normalized format.
+    /// Returns the data to store and, when it was converted, what the document
+    /// is stored as. Anything that goes wrong leaves the image as it was.
+    async fn normalize_image(
+        &self,
+        source_id: Uuid,
+        user_id: Uuid,
+        document_id: Uuid,
+        filename: &str,
+        mime_type: &str,
+        data: Vec<u8>,
+    ) -> (Vec<u8>, Option<NormalizedImage>) {
+        let normalization = match self.db.get_source_by_id(source_id).await {
+            Ok(Some(source)) => match ImageNormalizationConfig::from_source_config(&source.config) {
+                Ok(normalization) => normalization,
+                Err(e) => {
+                    warn!("{} on source {}, storing {} as it is", e, source_id, filename);
+                    return (data, None);
+                }
+            },
+            Ok(None) => return (data, None),
+            Err(e) => {
+                warn!("Failed to load source {}: {}, storing {} as it is", source_id, e, filename);
+                return (data, None);
+            }
+        };
+        if !normalization.converts(mime_type) {
+            return (data, None);
+        }
+
+        let format = normalization.format;
+        let converted = match tokio::task::spawn_blocking({
+            let data = data.clone();
+            move || convert_image(&data, format)
+        })
+        .await
+        {
+            Ok(Ok(converted)) => converted,
+            Ok(Err(e)) => {
+                warn!("Failed to convert image {} to {}: {}, storing it as it is", filename, format.extension(), e);
+                return (data, None);
+            }
+            Err(e) => {
+                warn!("Failed to join image conversion of {}: {}, storing it as it is", filename, e);
+                return (data, None);
+            }
+        };
+
+        let mut metadata = serde_json::json!({
+            "original_format": image_format_name(mime_type),
+            "original_mime_type": mime_type,
+            "original_size": data.len(),
+        });
+        if normalization.keep_original {
+            match self
+                .file_service
+                .save_document_file(user_id, document_id, &original_image_filename(filename, mime_type), &data)
+                .await
+            {
+                Ok(path) => metadata["original_path"] = serde_json::Value::String(path),
+                Err(e) => {
+                    warn!("Failed to keep the original of image {}: {}, storing it as it is", filename, e);
+                    return (data, None);
+                }
+            }
+        }
+
+        info!(
+            "Converted image {} from {} to {} ({} to {} bytes)",
+            filename,
+            image_format_name(mime_type),
+            format.extension(),
+            data.len(),
+            converted.len()
+        );
+        let normalized = NormalizedImage {
+            filename: normalized_filename(filename, format),
+            mime_type: format.mime_type().to_string(),
+            metadata,
+        };
+        (converted, Some(normalized))
+    }
+
     /// Calculate SHA256 hash of file content
     fn calculate_file_hash(&self, data: &[u8]) -> String {
         let mut hasher = Sha256::new();
@@ -417,10 +837,44 @@ impl DocumentIngestionService {
         source_type: &str,
         source_id: Option<Uuid>,
     ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        let request = Self::request_from_file_info(file_info, file_data, user_id, deduplication_policy, source_type, source_id);
+        self.ingest_document(request).await
+    }
+
+    /// Like [`ingest_from_file_info`](Self::ingest_from_file_info) for a download that may
+    /// have been spooled to disk. A spooled file is stored from disk and deleted afterwards.
+    pub async fn ingest_download_from_file_info(
+        &self,
+        file_info: &FileIngestionInfo,
+        download: DownloadedFile,
+        user_id: Uuid,
+        deduplication_policy: DeduplicationPolicy,
+        source_type: &str,
+        source_id: Option<Uuid>,
+    ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        match download {
+            DownloadedFile::InMemory(file_data) => {
+                self.ingest_from_file_info(file_info, file_data, user_id, deduplication_policy, source_type, source_id).await
+            }
+            DownloadedFile::Spooled(file) => {
+                let request = Self::request_from_file_info(file_info, Vec::new(), user_id, deduplication_policy, source_type, source_id);
+                self.ingest(request, Some(&file)).await
+            }
+        }
+    }
+
+    fn request_from_file_info(
+        file_info: &FileIngestionInfo,
+        file_data: Vec<u8>,
+        user_id: Uuid,
+        deduplication_policy: DeduplicationPolicy,
+        source_type: &str,
+        source_id: Option<Uuid>,
+    ) -> DocumentIngestionRequest {
         let (original_created_at, original_modified_at, source_metadata) = 
             Self::extract_metadata_from_file_info(file_info);
             
-        let request = DocumentIngestionRequest {
+        DocumentIngestionRequest {
             filename: file_info.name.clone(),
             original_filename: file_info.name.clone(),
             file_data,
@@ -436,9 +890,7 @@ impl DocumentIngestionService {
             file_owner: file_info.owner.clone(),
             file_group: file_info.group.clone(),
             source_metadata,
-        };
-
-        self.ingest_document(request).await
+        }
     }
 
     /// Convenience method for direct uploads (maintains backward compatibility)
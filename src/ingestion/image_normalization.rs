@@ -0,0 +1,168 @@
+//! Optional conversion of ingested images to one format
+//!
+//! A source can store the images it syncs in a single format, so thumbnails,
+//! previews and downloads deal with fewer formats and bulky ones such as BMP
+//! or uncompressed TIFF take less space. It is set with the
+//! `image_normalization` object of the source's config. Formats listed in
+//! `keep_mime_types` are stored as they are; by default that is JPEG, which
+//! would only lose quality when re-encoded.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// MIME types of the images that can be decoded for conversion
+const CONVERTIBLE_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/jpg",
+    "image/tiff",
+    "image/bmp",
+    "image/x-bmp",
+    "image/x-ms-bmp",
+];
+
+/// Format ingested images are converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalizedImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+}
+
+impl NormalizedImageFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            NormalizedImageFormat::Png => "image/png",
+            NormalizedImageFormat::Jpeg => "image/jpeg",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            NormalizedImageFormat::Png => "png",
+            NormalizedImageFormat::Jpeg => "jpg",
+        }
+    }
+}
+
+/// Image normalization of a source, read from the `image_normalization`
+/// object of its config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageNormalizationConfig {
+    /// Convert the images the source ingests
+    pub enabled: bool,
+    /// Format images are converted to
+    pub format: NormalizedImageFormat,
+    /// Image types stored as they are
+    pub keep_mime_types: Vec<String>,
+    /// Also store each converted image as it was ingested
+    pub keep_original: bool,
+}
+
+impl Default for ImageNormalizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: NormalizedImageFormat::Png,
+            keep_mime_types: vec!["image/jpeg".to_string()],
+            keep_original: false,
+        }
+    }
+}
+
+impl ImageNormalizationConfig {
+    /// Reads the normalization from a source config, disabled when not set
+    pub fn from_source_config(config: &serde_json::Value) -> Result<Self, String> {
+        let normalization = match config.get("image_normalization") {
+            Some(value) if !value.is_null() => serde_json::from_value::<Self>(value.clone())
+                .map_err(|e| format!("Invalid image_normalization configuration: {}", e))?,
+            _ => Self::default(),
+        };
+        normalization.validate()?;
+        Ok(normalization)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(mime_type) = self.keep_mime_types.iter().find(|mime_type| !mime_type.starts_with("image/")) {
+            return Err(format!("image_normalization.keep_mime_types must list image types, got '{}'", mime_type));
+        }
+        Ok(())
+    }
+
+    /// Whether an ingested file of `mime_type` is converted
+    pub fn converts(&self, mime_type: &str) -> bool {
+        let mime_type = mime_type.to_ascii_lowercase();
+        self.enabled
+            && CONVERTIBLE_MIME_TYPES.contains(&mime_type.as_str())
+            && !same_image_type(&mime_type, self.format.mime_type())
+            && !self.keep_mime_types.iter().any(|kept| same_image_type(&mime_type, kept))
+    }
+}
+
+/// Compares image MIME types, treating the aliases of a format as the same type
+fn same_image_type(a: &str, b: &str) -> bool {
+    let canonical = |mime_type: &str| match mime_type.to_ascii_lowercase().as_str() {
+        "image/jpg" => "image/jpeg".to_string(),
+        "image/x-bmp" | "image/x-ms-bmp" => "image/bmp".to_string(),
+        other => other.to_string(),
+    };
+    canonical(a) == canonical(b)
+}
+
+/// Short name of an image type for the document's metadata, such as `bmp`
+pub fn image_format_name(mime_type: &str) -> String {
+    match mime_type.to_ascii_lowercase().as_str() {
+        "image/jpeg" | "image/jpg" => "jpeg".to_string(),
+        "image/x-bmp" | "image/x-ms-bmp" => "bmp".to_string(),
+        other => other.trim_start_matches("image/").to_string(),
+    }
+}
+
+/// Filename of a converted image: the original name with the new format's extension
+pub fn normalized_filename(filename: &str, format: NormalizedImageFormat) -> String {
+    let path = Path::new(filename);
+    match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) if path.extension().is_some() => format!("{}.{}", stem, format.extension()),
+        _ => format!("{}.{}", filename, format.extension()),
+    }
+}
+
+/// Filename the original of a converted image is stored under. Its extension
+/// gives it a storage path of its own next to the converted image.
+pub fn original_image_filename(filename: &str, mime_type: &str) -> String {
+    let path = Path::new(filename);
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or(filename);
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| image_format_name(mime_type));
+    format!("{}.original.{}", stem, extension)
+}
+
+/// Converts image bytes to `format`. JPEG has no transparency, so images are
+/// flattened to RGB for it.
+pub fn convert_image(data: &[u8], format: NormalizedImageFormat) -> Result<Vec<u8>> {
+    #[cfg(not(feature = "ocr"))]
+    {
+        let _ = (data, format);
+        Err(anyhow!("image support not compiled in"))
+    }
+
+    #[cfg(feature = "ocr")]
+    {
+        let image = image::load_from_memory(data).map_err(|e| anyhow!("Failed to decode image: {}", e))?;
+        let (image, image_format) = match format {
+            NormalizedImageFormat::Png => (image, image::ImageFormat::Png),
+            NormalizedImageFormat::Jpeg => (image::DynamicImage::ImageRgb8(image.to_rgb8()), image::ImageFormat::Jpeg),
+        };
+        let mut converted = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut converted), image_format)
+            .map_err(|e| anyhow!("Failed to encode image as {}: {}", format.extension(), e))?;
+        Ok(converted)
+    }
+}
@@ -1,2 +1,6 @@
+pub mod archive;
 pub mod batch_ingest;
-pub mod document_ingestion;
\ No newline at end of file
+pub mod document_ingestion;
+pub mod image_normalization;
+pub mod pipeline;
+pub mod spool;
\ No newline at end of file
@@ -0,0 +1,357 @@
+//! Staged ingestion pipeline for source syncs.
+//!
+//! Discovery, download and storage run as separate stages connected by bounded
+//! channels. Each stage has its own concurrency limit, and a full channel makes
+//! the stage in front of it wait, so downloads that outpace storage stop
+//! instead of piling up files in memory or in the spool directory.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering};
+
+use anyhow::Result;
+use futures::stream::{self, Stream, StreamExt};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
+
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 5;
+pub const DEFAULT_STORE_CONCURRENCY: usize = 2;
+pub const DEFAULT_STAGE_QUEUE_SIZE: usize = 10;
+
+/// Concurrency of each stage and capacity of the channels between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IngestionPipelineConfig {
+    /// Files downloaded at the same time
+    pub download_concurrency: usize,
+    /// Downloaded files stored (and queued for OCR) at the same time
+    pub store_concurrency: usize,
+    /// Files waiting between two stages before the earlier stage pauses
+    pub stage_queue_size: usize,
+}
+
+impl Default for IngestionPipelineConfig {
+    fn default() -> Self {
+        Self {
+            download_concurrency: DEFAULT_DOWNLOAD_CONCURRENCY,
+            store_concurrency: DEFAULT_STORE_CONCURRENCY,
+            stage_queue_size: DEFAULT_STAGE_QUEUE_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PipelineStage {
+    Discovery,
+    Download,
+    Store,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 3] = [PipelineStage::Discovery, PipelineStage::Download, PipelineStage::Store];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PipelineStage::Discovery => "discovery",
+            PipelineStage::Download => "download",
+            PipelineStage::Store => "store",
+        }
+    }
+
+    fn counters(&self) -> &'static StageCounters {
+        &STAGE_COUNTERS[*self as usize]
+    }
+}
+
+struct StageCounters {
+    processed: AtomicU64,
+    failed: AtomicU64,
+    queued: AtomicI64,
+    in_flight: AtomicI64,
+}
+
+impl StageCounters {
+    const fn new() -> Self {
+        Self {
+            processed: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            queued: AtomicI64::new(0),
+            in_flight: AtomicI64::new(0),
+        }
+    }
+}
+
+static STAGE_COUNTERS: [StageCounters; 3] = [StageCounters::new(), StageCounters::new(), StageCounters::new()];
+
+/// Process-wide counters of one stage, summed over all running syncs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineStageMetrics {
+    pub stage: PipelineStage,
+    /// Files that completed the stage (for discovery: files found)
+    pub processed: u64,
+    pub failed: u64,
+    /// Files waiting in front of the stage
+    pub queue_depth: i64,
+    /// Files the stage is working on right now
+    pub in_flight: i64,
+}
+
+pub fn pipeline_stage_metrics() -> Vec<PipelineStageMetrics> {
+    PipelineStage::ALL
+        .iter()
+        .map(|stage| {
+            let counters = stage.counters();
+            PipelineStageMetrics {
+                stage: *stage,
+                processed: counters.processed.load(Ordering::Relaxed),
+                failed: counters.failed.load(Ordering::Relaxed),
+                queue_depth: counters.queued.load(Ordering::Relaxed),
+                in_flight: counters.in_flight.load(Ordering::Relaxed),
+            }
+        })
+        .collect()
+}
+
+/// Counts of one pipeline run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineSummary {
+    pub discovered: usize,
+    pub downloaded: usize,
+    /// Files the store stage reported as newly processed
+    pub stored: usize,
+    pub discovery_failures: usize,
+    pub download_failures: usize,
+    pub store_failures: usize,
+    /// The run stopped early because its cancellation token fired
+    pub cancelled: bool,
+}
+
+#[derive(Default)]
+struct RunCounters {
+    discovered: AtomicUsize,
+    downloaded: AtomicUsize,
+    stored: AtomicUsize,
+    discovery_failures: AtomicUsize,
+    download_failures: AtomicUsize,
+    store_failures: AtomicUsize,
+}
+
+impl RunCounters {
+    fn summary(&self, cancelled: bool) -> PipelineSummary {
+        PipelineSummary {
+            discovered: self.discovered.load(Ordering::Relaxed),
+            downloaded: self.downloaded.load(Ordering::Relaxed),
+            stored: self.stored.load(Ordering::Relaxed),
+            discovery_failures: self.discovery_failures.load(Ordering::Relaxed),
+            download_failures: self.download_failures.load(Ordering::Relaxed),
+            store_failures: self.store_failures.load(Ordering::Relaxed),
+            cancelled,
+        }
+    }
+}
+
+/// Marks work in progress in a stage for as long as it lives
+struct InFlight(PipelineStage);
+
+impl InFlight {
+    fn start(stage: PipelineStage) -> Self {
+        stage.counters().in_flight.fetch_add(1, Ordering::Relaxed);
+        Self(stage)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.counters().in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn receiver_stream<T>(receiver: mpsc::Receiver<T>) -> impl Stream<Item = T> {
+    stream::unfold(receiver, |mut receiver| async move { receiver.recv().await.map(|item| (item, receiver)) })
+}
+
+/// Sends `item` on a stage channel, waiting while it is full. Returns false when
+/// the run was cancelled or the receiving stage is gone.
+async fn send_to_stage<T>(sender: &mpsc::Sender<T>, item: T, stage: PipelineStage, cancellation_token: &CancellationToken) -> bool {
+    let sent = tokio::select! {
+        result = sender.send(item) => result.is_ok(),
+        _ = cancellation_token.cancelled() => false,
+    };
+    if sent {
+        stage.counters().queued.fetch_add(1, Ordering::Relaxed);
+    }
+    sent
+}
+
+pub struct IngestionPipeline {
+    config: IngestionPipelineConfig,
+    cancellation_token: CancellationToken,
+}
+
+impl IngestionPipeline {
+    pub fn new(config: IngestionPipelineConfig, cancellation_token: CancellationToken) -> Self {
+        Self { config, cancellation_token }
+    }
+
+    /// Runs discovery for each folder and pushes what it finds through the
+    /// download and store stages.
+    ///
+    /// `store` returns whether it processed a new file. `report` is called with
+    /// the running totals after each folder's discovery and each stored file.
+    /// Failures of single folders or files are logged and counted, not returned.
+    pub async fn run<I, D, Disc, DiscFut, Dl, DlFut, St, StFut, Rep, RepFut>(
+        &self,
+        folders: &[String],
+        discover: Disc,
+        download: Dl,
+        store: St,
+        mut report: Rep,
+    ) -> PipelineSummary
+    where
+        Disc: Fn(String) -> DiscFut,
+        DiscFut: Future<Output = Result<Vec<I>>>,
+        I: Clone,
+        Dl: Fn(I) -> DlFut,
+        DlFut: Future<Output = Result<D>>,
+        St: Fn(I, D) -> StFut,
+        StFut: Future<Output = Result<bool>>,
+        Rep: FnMut(PipelineSummary) -> RepFut,
+        RepFut: Future<Output = ()>,
+    {
+        let download_concurrency = self.config.download_concurrency.max(1);
+        let store_concurrency = self.config.store_concurrency.max(1);
+        let queue_size = self.config.stage_queue_size.max(1);
+        let cancellation_token = &self.cancellation_token;
+        let counters = RunCounters::default();
+        let counters = &counters;
+
+        let (download_tx, download_rx) = mpsc::channel::<I>(queue_size);
+        let (store_tx, store_rx) = mpsc::channel::<(I, D)>(queue_size);
+        let (report_tx, mut report_rx) = mpsc::unbounded_channel::<()>();
+        let discovery_report_tx = report_tx.clone();
+
+        let discovery = async move {
+            for folder in folders {
+                if cancellation_token.is_cancelled() {
+                    break;
+                }
+
+                let discovered = {
+                    let _in_flight = InFlight::start(PipelineStage::Discovery);
+                    discover(folder.clone()).await
+                };
+                let items = match discovered {
+                    Ok(items) => items,
+                    Err(e) => {
+                        error!("Failed to discover files in folder {}: {}", folder, e);
+                        counters.discovery_failures.fetch_add(1, Ordering::Relaxed);
+                        PipelineStage::Discovery.counters().failed.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    }
+                };
+
+                counters.discovered.fetch_add(items.len(), Ordering::Relaxed);
+                PipelineStage::Discovery.counters().processed.fetch_add(items.len() as u64, Ordering::Relaxed);
+                let _ = discovery_report_tx.send(());
+
+                for item in items {
+                    if !send_to_stage(&download_tx, item, PipelineStage::Download, cancellation_token).await {
+                        return;
+                    }
+                }
+            }
+        };
+
+        let downloading = async move {
+            let download = &download;
+            receiver_stream(download_rx)
+                .map(|item| {
+                    PipelineStage::Download.counters().queued.fetch_sub(1, Ordering::Relaxed);
+                    async move {
+                        if cancellation_token.is_cancelled() {
+                            return (item, None);
+                        }
+                        let _in_flight = InFlight::start(PipelineStage::Download);
+                        let result = download(item.clone()).await;
+                        (item, Some(result))
+                    }
+                })
+                .buffer_unordered(download_concurrency)
+                .for_each(|(item, result)| {
+                    let store_tx = &store_tx;
+                    async move {
+                        match result {
+                            Some(Ok(downloaded)) => {
+                                counters.downloaded.fetch_add(1, Ordering::Relaxed);
+                                PipelineStage::Download.counters().processed.fetch_add(1, Ordering::Relaxed);
+                                send_to_stage(store_tx, (item, downloaded), PipelineStage::Store, cancellation_token).await;
+                            }
+                            Some(Err(e)) => {
+                                error!("{}", e);
+                                counters.download_failures.fetch_add(1, Ordering::Relaxed);
+                                PipelineStage::Download.counters().failed.fetch_add(1, Ordering::Relaxed);
+                            }
+                            None => {}
+                        }
+                    }
+                })
+                .await;
+        };
+
+        let storing = async move {
+            let store = &store;
+            receiver_stream(store_rx)
+                .map(|(item, downloaded)| {
+                    PipelineStage::Store.counters().queued.fetch_sub(1, Ordering::Relaxed);
+                    async move {
+                        if cancellation_token.is_cancelled() {
+                            return None;
+                        }
+                        let _in_flight = InFlight::start(PipelineStage::Store);
+                        Some(store(item, downloaded).await)
+                    }
+                })
+                .buffer_unordered(store_concurrency)
+                .for_each(|result| {
+                    match result {
+                        Some(Ok(stored)) => {
+                            PipelineStage::Store.counters().processed.fetch_add(1, Ordering::Relaxed);
+                            if stored {
+                                counters.stored.fetch_add(1, Ordering::Relaxed);
+                                let _ = report_tx.send(());
+                            }
+                        }
+                        Some(Err(e)) => {
+                            error!("File processing error: {}", e);
+                            counters.store_failures.fetch_add(1, Ordering::Relaxed);
+                            PipelineStage::Store.counters().failed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        None => {}
+                    }
+                    async {}
+                })
+                .await;
+        };
+
+        // Reports run beside the stages so a slow report never stalls them
+        let stages = async {
+            tokio::join!(discovery, downloading, storing);
+        };
+        let reporting = async {
+            while report_rx.recv().await.is_some() {
+                // Collapse reports that queued up while the last one ran
+                while report_rx.try_recv().is_ok() {}
+                report(counters.summary(false)).await;
+            }
+        };
+        tokio::join!(stages, reporting);
+
+        let summary = counters.summary(cancellation_token.is_cancelled());
+        if summary.cancelled {
+            warn!(
+                "Ingestion cancelled after {} of {} discovered files were stored",
+                summary.stored, summary.discovered
+            );
+        }
+        summary
+    }
+}
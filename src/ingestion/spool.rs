@@ -0,0 +1,184 @@
+//! Spooling of large downloads to temporary files.
+//!
+//! Sources normally hand ingestion the whole file as a `Vec<u8>`, which doesn't
+//! work for multi-GB files. A download larger than the configured threshold is
+//! written to a temporary file instead, hashed as it streams in, and ingestion
+//! stores it from disk. The temporary file is removed when the [`SpooledFile`]
+//! is dropped, whether or not ingestion succeeded.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Decides which downloads are spooled to disk
+#[derive(Debug, Clone)]
+pub struct SpoolPolicy {
+    /// Downloads larger than this many bytes go to disk; 0 keeps every download in memory
+    pub threshold_bytes: u64,
+    /// Directory for the temporary files
+    pub directory: PathBuf,
+}
+
+impl SpoolPolicy {
+    pub fn new(threshold_mb: u64, directory: PathBuf) -> Self {
+        Self {
+            threshold_bytes: threshold_mb.saturating_mul(1024 * 1024),
+            directory,
+        }
+    }
+
+    /// Keeps every download in memory
+    pub fn in_memory() -> Self {
+        Self {
+            threshold_bytes: 0,
+            directory: std::env::temp_dir(),
+        }
+    }
+
+    pub fn should_spool(&self, size: u64) -> bool {
+        self.threshold_bytes > 0 && size > self.threshold_bytes
+    }
+}
+
+/// A downloaded file, either buffered in memory or spooled to disk
+#[derive(Debug)]
+pub enum DownloadedFile {
+    InMemory(Vec<u8>),
+    Spooled(SpooledFile),
+}
+
+impl DownloadedFile {
+    pub fn len(&self) -> u64 {
+        match self {
+            DownloadedFile::InMemory(data) => data.len() as u64,
+            DownloadedFile::Spooled(file) => file.size(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A temporary file holding a download, with the size and SHA-256 computed while
+/// it was written. The file is deleted on drop.
+#[derive(Debug)]
+pub struct SpooledFile {
+    path: PathBuf,
+    size: u64,
+    sha256: String,
+}
+
+impl SpooledFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Hex-encoded SHA-256 of the contents, in the format ingestion uses for deduplication
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+}
+
+impl Drop for SpooledFile {
+    fn drop(&mut self) {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => debug!("Removed spooled download {}", self.path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to remove spooled download {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// Temporary file being written; removed by the inner [`SpooledFile`] if never finished
+struct SpoolWriter {
+    file: tokio::io::BufWriter<tokio::fs::File>,
+    hasher: Sha256,
+    target: SpooledFile,
+}
+
+impl SpoolWriter {
+    async fn create(directory: &Path) -> Result<Self> {
+        tokio::fs::create_dir_all(directory)
+            .await
+            .with_context(|| format!("Failed to create spool directory {}", directory.display()))?;
+
+        let target = SpooledFile {
+            path: directory.join(format!("download-{}.part", Uuid::new_v4())),
+            size: 0,
+            sha256: String::new(),
+        };
+        let file = tokio::fs::File::create(&target.path)
+            .await
+            .with_context(|| format!("Failed to create spool file {}", target.path.display()))?;
+
+        Ok(Self {
+            file: tokio::io::BufWriter::new(file),
+            hasher: Sha256::new(),
+            target,
+        })
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        self.file.write_all(chunk).await?;
+        self.hasher.update(chunk);
+        self.target.size += chunk.len() as u64;
+        Ok(())
+    }
+
+    async fn finish(mut self) -> Result<SpooledFile> {
+        self.file.flush().await?;
+        self.file.get_ref().sync_all().await?;
+        self.target.sha256 = format!("{:x}", self.hasher.finalize());
+        Ok(self.target)
+    }
+}
+
+/// Collects a download chunk by chunk. Data is buffered in memory until it exceeds
+/// the policy's threshold, then moved to a temporary file, so at most
+/// `threshold_bytes` of a download is ever held in memory.
+pub struct DownloadSink<'a> {
+    policy: &'a SpoolPolicy,
+    buffer: Vec<u8>,
+    spool: Option<SpoolWriter>,
+}
+
+impl<'a> DownloadSink<'a> {
+    /// Starts spooling right away when the advertised size is already over the threshold
+    pub async fn new(policy: &'a SpoolPolicy, advertised_size: Option<u64>) -> Result<Self> {
+        let spool = match advertised_size {
+            Some(size) if policy.should_spool(size) => Some(SpoolWriter::create(&policy.directory).await?),
+            _ => None,
+        };
+        Ok(Self { policy, buffer: Vec::new(), spool })
+    }
+
+    pub async fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        if let Some(spool) = self.spool.as_mut() {
+            return spool.write(chunk).await;
+        }
+
+        self.buffer.extend_from_slice(chunk);
+        if self.policy.should_spool(self.buffer.len() as u64) {
+            let mut spool = SpoolWriter::create(&self.policy.directory).await?;
+            spool.write(&self.buffer).await?;
+            self.buffer = Vec::new();
+            self.spool = Some(spool);
+        }
+        Ok(())
+    }
+
+    pub async fn finish(self) -> Result<DownloadedFile> {
+        match self.spool {
+            Some(spool) => Ok(DownloadedFile::Spooled(spool.finish().await?)),
+            None => Ok(DownloadedFile::InMemory(self.buffer)),
+        }
+    }
+}
@@ -1,10 +1,14 @@
 pub mod auth;
+pub mod body_limit;
 pub mod commands;
+pub mod compression;
 pub mod config;
 pub mod db;
 pub mod db_guardrails_simple;
+pub mod error_envelope;
 pub mod errors;
 pub mod ingestion;
+pub mod logging;
 pub mod metadata_extraction;
 pub mod mime_detection;
 pub mod models;
@@ -0,0 +1,151 @@
+/*!
+ * Log Output
+ *
+ * Sets up the `tracing` subscriber in the format chosen with `LOG_FORMAT`, and
+ * the middleware that gives every HTTP request an id. `RUST_LOG` filtering
+ * applies to every format.
+ *
+ * JSON lines carry `timestamp`, `level`, `target` and the event's fields, plus a
+ * `spans` list holding the fields of the spans the event happened in: the
+ * `request_id` of an HTTP request, and the `user_id`, `source_id` or
+ * `document_id` of sync and OCR work.
+ *
+ * The id of the request being handled is also readable with
+ * `current_request_id`. OCR jobs store it when queued, and background work
+ * started by a request (such as a manual sync) keeps it through
+ * `in_current_request`, so their logs can be tied back to the request.
+ */
+
+use std::future::Future;
+use std::str::FromStr;
+
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use tracing_subscriber::EnvFilter;
+
+/// Header carrying the request id, taken from the request when a proxy set one
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest request id accepted from a client
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Output format of the logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// One line per event with its span context (tracing's default)
+    #[default]
+    Full,
+    /// Multi-line output, easiest to read in a terminal
+    Pretty,
+    /// One short line per event
+    Compact,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+impl LogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Full => "full",
+            LogFormat::Pretty => "pretty",
+            LogFormat::Compact => "compact",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "full" => Ok(LogFormat::Full),
+            "pretty" => Ok(LogFormat::Pretty),
+            "compact" => Ok(LogFormat::Compact),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("Invalid LOG_FORMAT '{}' (expected full, pretty, compact or json)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Installs the global subscriber writing logs in `format`
+pub fn init(format: LogFormat, filter: EnvFilter) {
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Full => builder.init(),
+        LogFormat::Pretty => builder.pretty().init(),
+        LogFormat::Compact => builder.compact().init(),
+        LogFormat::Json => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .init(),
+    }
+}
+
+/// Request id sent by the client, if it is short printable ASCII
+fn client_request_id(request: &Request) -> Option<String> {
+    let value = request.headers().get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| value.to_string())
+}
+
+/// Middleware running each request in a span with its id, and returning the id
+/// in the `x-request-id` response header
+pub async fn request_span(request: Request, next: Next) -> Response {
+    let request_id = client_request_id(&request).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request).instrument(span))
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Id of the HTTP request the current task works for, if any
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|request_id| request_id.clone()).ok()
+}
+
+/// Wraps a future to be spawned so that it runs with the request id and in the
+/// span of the request spawning it. Outside of a request the future is unchanged.
+pub fn in_current_request<F>(future: F) -> impl Future<Output = F::Output>
+where
+    F: Future,
+{
+    let request_id = current_request_id();
+    let span = tracing::Span::current();
+    async move {
+        match request_id {
+            Some(request_id) => REQUEST_ID.scope(request_id, future).await,
+            None => future.await,
+        }
+    }
+    .instrument(span)
+}
@@ -32,6 +32,8 @@ enum Commands {
     Serve,
     /// Reset the admin user's password
     ResetAdminPassword,
+    /// Report on the health of the search indexes and advise on fixing problems
+    SearchIndexHealth,
 }
 
 /// Determines the correct path for static files based on the environment
@@ -90,9 +92,15 @@ async fn main() -> anyhow::Result<()> {
                 .add_directive("readur=info".parse().unwrap())                 // Keep our app logs at info
         });
 
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .init();
+    // LOG_FORMAT=json writes one JSON object per line for log aggregation
+    let log_format = match std::env::var("LOG_FORMAT") {
+        Ok(value) => value.parse().unwrap_or_else(|e| {
+            eprintln!("⚠️  {}, using full", e);
+            readur::logging::LogFormat::Full
+        }),
+        Err(_) => readur::logging::LogFormat::Full,
+    };
+    readur::logging::init(log_format, env_filter);
 
     // Handle CLI commands
     match cli.command {
@@ -112,6 +120,16 @@ async fn main() -> anyhow::Result<()> {
 
             return Ok(());
         }
+        Some(Commands::SearchIndexHealth) => {
+            println!("\n🗂️  SEARCH INDEX HEALTH");
+            println!("{}", "=".repeat(60));
+
+            let config = Config::from_env()?;
+            let db = Database::new(&config.database_url).await?;
+            commands::search_index_health(&db, config.search_index_strategy).await?;
+
+            return Ok(());
+        }
         Some(Commands::Serve) | None => {
             // Default: Start the web server
             // Continue with normal server startup below
@@ -134,6 +152,27 @@ async fn main() -> anyhow::Result<()> {
             return Err(e);
         }
     };
+
+    if !config.mime_type_overrides.is_empty() {
+        readur::mime_detection::install_mime_overrides(config.mime_type_overrides.clone());
+    }
+    readur::ocr::model_cache::set_model_cache_capacity(config.ocr_model_cache_size);
+    readur::services::webdav::discovery_cache::install_configured_discovery_cache(&config);
+    readur::services::webdav::clock_skew::install_clock_skew_settings(
+        readur::services::webdav::ClockSkewSettings::from_config(&config),
+    );
+    readur::services::webdav::capabilities::install_capability_cache(std::sync::Arc::new(
+        readur::services::webdav::CapabilityCache::from_config(&config),
+    ));
+    readur::services::webdav::host_limits::install_host_download_limiter(std::sync::Arc::new(
+        readur::services::webdav::HostDownloadLimiter::from_config(&config),
+    ));
+    readur::services::credential_encryption::install_credential_keyring(std::sync::Arc::new(
+        readur::services::credential_encryption::CredentialKeyring::from_config(&config)?,
+    ));
+    readur::ocr::fallback_order::install_fallback_config(
+        readur::ocr::fallback_order::FallbackConfig::from_config(&config),
+    );
     
     // Log critical configuration values that affect startup
     println!("\n🔗 STARTUP CONFIGURATION:");
@@ -171,7 +210,7 @@ async fn main() -> anyhow::Result<()> {
     // Initialize file service using the new storage backend architecture with fallback
     info!("Initializing file service with storage backend...");
     let storage_config = readur::storage::factory::storage_config_from_env(&config)?;
-    let file_service = match readur::services::file_service::FileService::from_config(storage_config, config.upload_path.clone()).await {
+    let file_service = match readur::services::file_service::FileService::from_config_with_compression(storage_config, config.upload_path.clone(), config.storage_compression).await {
         Ok(service) => {
             info!("✅ File service initialized with {} storage backend", service.storage_type());
             service
@@ -185,7 +224,7 @@ async fn main() -> anyhow::Result<()> {
                 upload_path: config.upload_path.clone(),
             };
             
-            match readur::services::file_service::FileService::from_config(fallback_config, config.upload_path.clone()).await {
+            match readur::services::file_service::FileService::from_config_with_compression(fallback_config, config.upload_path.clone(), config.storage_compression).await {
                 Ok(fallback_service) => {
                     warn!("✅ Successfully initialized fallback local storage");
                     fallback_service
@@ -204,7 +243,30 @@ async fn main() -> anyhow::Result<()> {
         return Err(e.into());
     }
     info!("✅ Storage backend initialized successfully");
-    
+
+    // Attach the malware scanner, if enabled, so every ingestion path scans before storing
+    let malware_scan = readur::services::malware_scanner::MalwareScanService::from_config(&config)?;
+    if malware_scan.is_some() {
+        info!("✅ Malware scanning enabled for ingested files");
+    }
+    let file_service = file_service.with_malware_scan(malware_scan);
+
+    // Attach integrity checks, unless disabled, so corrupt files are not stored and OCRed
+    let file_validator = readur::services::file_validator::FileValidator::from_config(&config);
+    if let Some(validator) = &file_validator {
+        info!("✅ File validation enabled ({})", validator.mode());
+    }
+    let file_service = file_service.with_file_validator(file_validator);
+
+    // Attach the PDF optimizer, if configured, so bloated PDFs are rewritten before storing
+    let pdf_optimizer = readur::services::pdf_optimizer::PdfOptimizer::from_config(&config);
+    if let Some(optimizer) = &pdf_optimizer {
+        info!("✅ PDF optimization enabled using {}", optimizer.tool());
+    }
+    let file_service = file_service.with_pdf_optimizer(pdf_optimizer);
+    let file_service = file_service.with_user_isolation(config.storage_user_isolation);
+    let file_service = file_service.with_thumbnail_size(config.thumbnail_size);
+
     // Wrap file service in Arc for sharing across application state
     let file_service = std::sync::Arc::new(file_service);
     
@@ -248,9 +310,6 @@ async fn main() -> anyhow::Result<()> {
         }
     };
     
-    // Don't run the old migration system - let SQLx handle everything
-    // db.migrate().await?;
-    
     // Run SQLx migrations
     info!("Running SQLx migrations...");
     let migrations = sqlx::migrate!("./migrations");
@@ -316,6 +375,35 @@ async fn main() -> anyhow::Result<()> {
               i + 1, migration.version, migration.description, status);
     }
     
+    if config.backup_before_migrate {
+        let available: Vec<i64> = migrations.migrations.iter().map(|migration| migration.version).collect();
+        if readur::db::migration_backup::needs_backup(&applied_migrations, &available) {
+            let schema_version = applied_migrations.last().copied().unwrap_or_default();
+            info!("💾 Backing up the database before running pending migrations...");
+            match readur::db::migration_backup::back_up_before_migrate(
+                &config.database_url,
+                &config.migration_backup_dir,
+                config.migration_backup_include_data,
+                schema_version,
+            ).await {
+                Ok(files) => {
+                    for file in files {
+                        info!("💾 Pre-migration backup written to {}", file.display());
+                    }
+                }
+                Err(e) => {
+                    error!("❌ CRITICAL: Backup before migrations failed, not migrating: {}", e);
+                    error!("   Make sure pg_dump is installed and MIGRATION_BACKUP_DIR is writable, or set BACKUP_BEFORE_MIGRATE=false");
+                    return Err(e);
+                }
+            }
+        } else if applied_migrations.is_empty() {
+            info!("💾 Skipping pre-migration backup: fresh database");
+        } else {
+            info!("💾 Skipping pre-migration backup: no pending migrations");
+        }
+    }
+
     let result = migrations.run(web_db.get_pool()).await;
     match result {
         Ok(_) => {
@@ -359,6 +447,41 @@ async fn main() -> anyhow::Result<()> {
         }
     }
     
+    // Verify the schema invariants the application relies on before serving traffic
+    info!("🔍 Verifying database schema...");
+    if let Err(e) = web_db.verify_schema(config.schema_auto_repair).await {
+        error!("❌ CRITICAL: Database schema verification failed!");
+        error!("{}", e);
+        return Err(e.into());
+    }
+    info!("✅ Database schema verified");
+    
+    // Fetch missing Tesseract language packs before OCR workers start
+    if config.ocr_auto_download_langs {
+        let essential = readur::ocr::language_packs::split_languages([config.ocr_language.as_str()]);
+        // Languages users picked in their settings are nice to have; a failure there only logs
+        let user_settings = background_db.get_all_user_settings().await.unwrap_or_else(|e| {
+            warn!("Failed to load user settings for OCR language packs: {}", e);
+            Vec::new()
+        });
+        let optional = readur::ocr::language_packs::split_languages(
+            user_settings
+                .iter()
+                .flat_map(|settings| std::iter::once(&settings.ocr_language).chain(&settings.preferred_languages))
+                .map(String::as_str),
+        );
+
+        match readur::ocr::language_packs::LanguagePackDownloader::new() {
+            Ok(downloader) => {
+                let report = downloader.ensure_languages(&essential, &optional).await?;
+                if !report.downloaded.is_empty() {
+                    info!("✅ Installed OCR language packs: {}", report.downloaded.join(", "));
+                }
+            }
+            Err(e) => warn!("⚠️  OCR language pack download unavailable: {}", e),
+        }
+    }
+    
     // Seed admin user  
     seed::seed_admin_user(&background_db).await?;
     
@@ -385,7 +508,91 @@ async fn main() -> anyhow::Result<()> {
             warn!("Failed to reset running source syncs: {}", e);
         }
     }
+
+    // Encrypted source credentials are unusable without their master keys
+    let credential_keyring = readur::services::credential_encryption::installed_credential_keyring();
+    let missing_key_versions =
+        credential_keyring.missing_versions(&background_db.source_credential_key_versions().await?);
+    if !missing_key_versions.is_empty() {
+        println!("❌ CRITICAL: Source credentials are encrypted with master key versions {:?}, which are not configured!",
+            missing_key_versions);
+        println!("Set CREDENTIALS_MASTER_KEYS or CREDENTIALS_MASTER_KEYS_FILE to include them.");
+        return Err(anyhow::anyhow!(
+            "Missing credential master key versions {:?}", missing_key_versions
+        ));
+    }
+
+    // Encrypt credentials still stored in plain text before any source syncs
+    if credential_keyring.is_enabled() {
+        match background_db
+            .encrypt_plaintext_source_credentials(&credential_keyring, config.credentials_rotation_batch_size)
+            .await
+        {
+            Ok(summary) if summary.rotated > 0 => {
+                info!("Encrypted the credentials of {} sources stored in plain text", summary.rotated);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Failed to encrypt plain text source credentials: {}", e);
+            }
+        }
+    }
+
+    // Move source credentials to the newest master key in the background
+    if config.credentials_auto_rotate && credential_keyring.is_enabled() {
+        let rotation_db = background_db.clone();
+        let batch_size = config.credentials_rotation_batch_size;
+        tokio::spawn(async move {
+            if let Err(e) = rotation_db.rotate_source_credentials(&credential_keyring, batch_size).await {
+                warn!("Failed to rotate source credentials: {}", e);
+            }
+        });
+    }
     
+    // Build or drop the per-user search indexes in the background; concurrent
+    // builds of a large table take a while but don't block writes
+    let index_db = background_db.clone();
+    let search_index_strategy = config.search_index_strategy;
+    tokio::spawn(async move {
+        if let Err(e) = index_db.apply_search_index_strategy(search_index_strategy).await {
+            warn!("Failed to apply the {} search index strategy: {}", search_index_strategy, e);
+        }
+    });
+
+    // Local documents stored before per-user directories are moved into their owner's
+    let relocation_db = background_db.clone();
+    let relocation_files = file_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = readur::services::user_directories::move_into_user_directories(&relocation_db, &relocation_files).await {
+            warn!("Failed to move local documents into their owners' directories: {}", e);
+        }
+    });
+
+    // Thumbnails made before THUMBNAIL_SIZE changed are regenerated in the background
+    match background_db.count_stale_thumbnails(config.thumbnail_size as i32).await {
+        Ok(0) => {}
+        Ok(stale) if config.thumbnail_auto_regenerate => {
+            if let Err(e) = readur::services::thumbnail_regeneration::start(
+                background_db.clone(),
+                file_service.clone(),
+                config.thumbnail_regeneration_concurrency,
+            ).await {
+                warn!("Failed to start regenerating {} thumbnails: {}", stale, e);
+            }
+        }
+        Ok(stale) => {
+            warn!("{} thumbnails were made at another size than THUMBNAIL_SIZE ({}px); regenerate them with POST /api/admin/thumbnails/regenerate or THUMBNAIL_AUTO_REGENERATE=true",
+                stale, config.thumbnail_size);
+        }
+        Err(e) => {
+            warn!("Failed to count thumbnails made at another size: {}", e);
+        }
+    }
+
+    let handwriting_engine = readur::ocr::handwriting::HttpHandwritingEngine::from_config(&config)?
+        .map(|engine| Arc::new(engine) as Arc<dyn readur::ocr::engine::OcrEngine>);
+    let confidence_calibration = readur::ocr::confidence_calibration::ConfidenceCalibration::from_config(&config)?;
+
     // Create shared OCR queue service for both web and background operations
     let concurrent_jobs = 15; // Limit concurrent OCR jobs to prevent DB pool exhaustion
     let shared_queue_service = Arc::new(readur::ocr::queue::OcrQueueService::new(
@@ -396,7 +603,14 @@ async fn main() -> anyhow::Result<()> {
         config.max_pdf_size_mb,
         config.max_office_document_size_mb,
         config.ocr_timeout_seconds,
-    ));
+    ).with_text_sanitizer(readur::ocr::text_sanitizer::TextSanitizerOptions {
+        strip_control_chars: config.ocr_text_strip_control_chars,
+        normalize_unicode: config.ocr_text_normalize_unicode,
+    }).with_handwriting_engine(handwriting_engine)
+      .with_confidence_calibration(confidence_calibration)
+      .with_scheduling(readur::ocr::queue::OcrSchedulingPolicy::from_config(&config))
+      .with_archive_limits(readur::ingestion::archive::ArchiveLimits::from_config(&config))
+      .with_document_language(readur::ocr::language_detection::DocumentLanguagePolicy::from_config(&config)));
     
     // Initialize OIDC client if enabled
     let oidc_client = if config.oidc_enabled {
@@ -527,7 +741,34 @@ async fn main() -> anyhow::Result<()> {
             }
         }
     });
-    
+
+    // Remove temporary OCR files left behind by jobs interrupted by a crash
+    if config.temp_file_max_age_hours > 0 {
+        let max_age = std::time::Duration::from_secs(config.temp_file_max_age_hours * 60 * 60);
+        background_runtime.spawn(readur::ocr::temp_files::run_temp_file_cleanup(
+            readur::ocr::temp_files::temp_dir(),
+            max_age,
+        ));
+    }
+
+    // Flush notification digests for users in digest delivery mode
+    let notification_digest = readur::services::notification_digest::NotificationDigestService::new(background_state.db.clone());
+    background_runtime.spawn(async move {
+        notification_digest.start().await;
+    });
+
+    // Remind owners of documents approaching their expiry date
+    let expiry_reminders = readur::services::expiry_reminders::ExpiryReminderService::new(background_state.db.clone());
+    background_runtime.spawn(async move {
+        expiry_reminders.start().await;
+    });
+
+    // Purge deleted sources once their grace period has passed
+    let source_purge = readur::services::source_deletion::SourcePurgeService::new(background_state.db.clone(), file_service.clone());
+    background_runtime.spawn(async move {
+        source_purge.start().await;
+    });
+
     // Create universal source scheduler with background state (handles WebDAV, Local, S3)
     println!("\n📅 SCHEDULER INITIALIZATION:");
     println!("{}", "=".repeat(50));
@@ -576,6 +817,8 @@ async fn main() -> anyhow::Result<()> {
     // Create the router with the updated state
     let app = Router::new()
         .route("/api/health", get(readur::health_check))
+        .nest("/api/admin/keys", readur::routes::credential_keys::router())
+        .nest("/api/admin/thumbnails", readur::routes::thumbnails::router())
         .nest("/api/auth", readur::routes::auth::router())
         .nest("/api/documents", readur::routes::documents::router())
         .nest("/api/ignored/files", readur::routes::ignored_files::ignored_files_routes())
@@ -602,8 +845,23 @@ async fn main() -> anyhow::Result<()> {
                 .precompressed_br()
                 .fallback(ServeFile::new(&index_file))
         )
-        .layer(DefaultBodyLimit::max(config.max_file_size_mb as usize * 1024 * 1024))
+        .layer(axum::middleware::from_fn_with_state(
+            config.upload_body_limit(),
+            readur::body_limit::reject_oversized_body,
+        ))
+        .layer(DefaultBodyLimit::max(config.upload_body_limit()))
+        // Inside compression, since it rewrites error bodies
+        .layer(axum::middleware::from_fn_with_state(
+            readur::error_envelope::ApiErrorSettings::from_config(&config),
+            readur::error_envelope::structured_errors,
+        ));
+    let app = match readur::compression::configured_compression_layer(&config) {
+        Some(compression) => app.layer(compression),
+        None => app,
+    };
+    let app = app
         .layer(CorsLayer::permissive())
+        .layer(axum::middleware::from_fn(readur::logging::request_span))
         .with_state(web_state.clone());
 
     println!("\n🌐 STARTING HTTP SERVER:");
@@ -21,7 +21,7 @@ pub async fn extract_content_metadata(file_data: &[u8], mime_type: &str, filenam
         }
         
         // Text files - extract basic text info
-        "text/plain" => {
+        "text/plain" | "text/markdown" => {
             if let Ok(text_metadata) = extract_text_metadata(file_data).await {
                 metadata.extend(text_metadata);
             }
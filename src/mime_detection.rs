@@ -4,11 +4,16 @@
 /// 1. Content-based detection using magic bytes (most reliable)
 /// 2. Server-provided MIME type (when available and trusted)
 /// 3. Extension-based fallback (least reliable, but covers edge cases)
+/// 4. Admin-configured extension overrides, as a tiebreaker when content detection is inconclusive
 /// 
 /// The goal is to provide accurate MIME type detection that's particularly important
 /// for OCR processing where incorrectly classified image files can cause issues.
 
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
 use tracing::{debug, warn};
 
 /// Strategy for MIME type detection
@@ -60,6 +65,110 @@ pub enum DetectionMethod {
     Fallback,
     /// Hybrid approach using multiple methods
     Hybrid,
+    /// Forced by a configured extension override
+    Override,
+}
+
+/// Extension to MIME type overrides, configured with `MIME_TYPE_OVERRIDES`
+/// as a comma-separated list such as `jp2=image/jp2,heic=image/heic`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MimeOverrides {
+    by_extension: HashMap<String, String>,
+}
+
+impl MimeOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.by_extension.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_extension.len()
+    }
+
+    /// The forced MIME type for a filename's extension, if any
+    pub fn get(&self, filename: &str) -> Option<&str> {
+        let extension = Path::new(filename).extension()?.to_str()?.to_lowercase();
+        self.by_extension.get(&extension).map(String::as_str)
+    }
+}
+
+impl FromStr for MimeOverrides {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut by_extension = HashMap::new();
+        for entry in s.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (extension, mime_type) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("'{}' is not in the form extension=type/subtype", entry))?;
+
+            let extension = extension.trim().trim_start_matches('.').to_lowercase();
+            if extension.is_empty() || !extension.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '+')) {
+                return Err(format!("'{}' is not a valid file extension", extension));
+            }
+
+            let mime_type = mime_type.trim().to_lowercase();
+            if !is_valid_mime_type(&mime_type) {
+                return Err(format!("'{}' for .{} is not a valid MIME type", mime_type, extension));
+            }
+
+            by_extension.insert(extension, mime_type);
+        }
+        Ok(Self { by_extension })
+    }
+}
+
+/// Checks `type/subtype` against the RFC 6838 naming rules; parameters are not allowed
+pub fn is_valid_mime_type(mime_type: &str) -> bool {
+    fn is_restricted_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        matches!(chars.next(), Some(c) if c.is_ascii_alphanumeric())
+            && name.len() <= 127
+            && chars.all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c))
+    }
+
+    match mime_type.split_once('/') {
+        Some((top_level, subtype)) => is_restricted_name(top_level) && is_restricted_name(subtype),
+        None => false,
+    }
+}
+
+static MIME_OVERRIDES: OnceLock<MimeOverrides> = OnceLock::new();
+static NO_OVERRIDES: Lazy<MimeOverrides> = Lazy::new(MimeOverrides::default);
+
+/// Installs the process-wide overrides used by the detection functions.
+/// Returns false if overrides were already installed.
+pub fn install_mime_overrides(overrides: MimeOverrides) -> bool {
+    MIME_OVERRIDES.set(overrides).is_ok()
+}
+
+fn configured_overrides() -> &'static MimeOverrides {
+    MIME_OVERRIDES.get().unwrap_or(&NO_OVERRIDES)
+}
+
+/// Replaces a detection that didn't come from the file's content with the
+/// configured override for its extension
+fn apply_override(result: MimeDetectionResult, filename: &str, overrides: &MimeOverrides) -> MimeDetectionResult {
+    if matches!(result.detection_method, DetectionMethod::MagicBytes | DetectionMethod::Hybrid) {
+        return result;
+    }
+
+    match overrides.get(filename) {
+        Some(mime_type) => {
+            debug!("MIME override for {}: {} -> {}", filename, result.mime_type, mime_type);
+            MimeDetectionResult {
+                mime_type: mime_type.to_string(),
+                confidence: MimeConfidence::Medium,
+                detection_method: DetectionMethod::Override,
+                original_server_type: result.original_server_type,
+                detected_extension: Path::new(filename)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.to_string()),
+            }
+        }
+        None => result,
+    }
 }
 
 impl MimeDetectionResult {
@@ -123,6 +232,7 @@ impl MimeDetectionResult {
             "application/vnd.ms-powerpoint" |
             "application/vnd.openxmlformats-officedocument.presentationml.presentation" |
             "text/plain" |
+            "text/markdown" |
             "text/rtf" |
             "application/rtf"
         )
@@ -150,6 +260,24 @@ pub fn detect_mime_for_discovery(
     filename: &str,
     server_mime_type: Option<&str>,
     strategy: DetectionStrategy,
+) -> MimeDetectionResult {
+    detect_mime_for_discovery_with_overrides(filename, server_mime_type, strategy, configured_overrides())
+}
+
+/// [`detect_mime_for_discovery`] with an explicit set of extension overrides
+pub fn detect_mime_for_discovery_with_overrides(
+    filename: &str,
+    server_mime_type: Option<&str>,
+    strategy: DetectionStrategy,
+    overrides: &MimeOverrides,
+) -> MimeDetectionResult {
+    apply_override(discover_mime(filename, server_mime_type, strategy), filename, overrides)
+}
+
+fn discover_mime(
+    filename: &str,
+    server_mime_type: Option<&str>,
+    strategy: DetectionStrategy,
 ) -> MimeDetectionResult {
     debug!("Detecting MIME type for discovery: filename={}, server_type={:?}, strategy={:?}", 
            filename, server_mime_type, strategy);
@@ -198,6 +326,25 @@ pub fn detect_mime_from_content(
     content: &[u8],
     filename: &str,
     server_mime_type: Option<&str>,
+) -> MimeDetectionResult {
+    detect_mime_from_content_with_overrides(content, filename, server_mime_type, configured_overrides())
+}
+
+/// [`detect_mime_from_content`] with an explicit set of extension overrides, which
+/// only apply when the magic bytes don't identify the file
+pub fn detect_mime_from_content_with_overrides(
+    content: &[u8],
+    filename: &str,
+    server_mime_type: Option<&str>,
+    overrides: &MimeOverrides,
+) -> MimeDetectionResult {
+    apply_override(sniff_mime(content, filename, server_mime_type), filename, overrides)
+}
+
+fn sniff_mime(
+    content: &[u8],
+    filename: &str,
+    server_mime_type: Option<&str>,
 ) -> MimeDetectionResult {
     debug!("Detecting MIME type from content: filename={}, server_type={:?}, content_len={}", 
            filename, server_mime_type, content.len());
@@ -417,6 +564,46 @@ mod tests {
         assert_eq!(get_mime_type_from_extension("png"), "image/png");
     }
 
+    #[test]
+    fn test_override_classifies_ambiguous_file() {
+        let overrides: MimeOverrides = "scan=image/tiff, .JP2=image/jp2".parse().unwrap();
+        let unrecognized_content = b"\x00\x01vendor scanner payload";
+
+        let result = detect_mime_from_content_with_overrides(
+            unrecognized_content, "page.scan", Some("application/octet-stream"), &MimeOverrides::default());
+        assert_eq!(result.mime_type, "application/octet-stream");
+
+        let result = detect_mime_from_content_with_overrides(
+            unrecognized_content, "page.scan", Some("application/octet-stream"), &overrides);
+        assert_eq!(result.mime_type, "image/tiff");
+        assert_eq!(result.detection_method, DetectionMethod::Override);
+
+        let result = detect_mime_for_discovery_with_overrides(
+            "Photo.jp2", None, DetectionStrategy::Comprehensive, &overrides);
+        assert_eq!(result.mime_type, "image/jp2");
+    }
+
+    #[test]
+    fn test_override_does_not_beat_magic_bytes() {
+        let overrides: MimeOverrides = "scan=image/tiff".parse().unwrap();
+        let result = detect_mime_from_content_with_overrides(b"%PDF-1.4", "page.scan", None, &overrides);
+        assert_eq!(result.mime_type, "application/pdf");
+        assert_eq!(result.detection_method, DetectionMethod::MagicBytes);
+    }
+
+    #[test]
+    fn test_override_parsing_rejects_invalid_entries() {
+        assert!("".parse::<MimeOverrides>().unwrap().is_empty());
+        assert_eq!("jp2=image/jp2,heic=image/heic,".parse::<MimeOverrides>().unwrap().len(), 2);
+
+        assert!("jp2".parse::<MimeOverrides>().is_err());
+        assert!("jp2=image".parse::<MimeOverrides>().is_err());
+        assert!("jp2=image/jp2; q=1".parse::<MimeOverrides>().is_err());
+        assert!("jp2=/jp2".parse::<MimeOverrides>().is_err());
+        assert!("../x=image/png".parse::<MimeOverrides>().is_err());
+        assert!("=image/png".parse::<MimeOverrides>().is_err());
+    }
+
     #[test]
     fn test_ocr_suitability() {
         let pdf_result = MimeDetectionResult::from_content("application/pdf".to_string(), None);
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -100,6 +100,8 @@ pub enum FailureStage {
     Processing,
     #[serde(rename = "sync")]
     Sync,
+    #[serde(rename = "quarantined")]
+    Quarantined,
 }
 
 impl std::fmt::Display for FailureReason {
@@ -135,6 +137,7 @@ impl std::fmt::Display for FailureStage {
             FailureStage::Storage => write!(f, "storage"),
             FailureStage::Processing => write!(f, "processing"),
             FailureStage::Sync => write!(f, "sync"),
+            FailureStage::Quarantined => write!(f, "quarantined"),
         }
     }
 }
@@ -206,6 +209,49 @@ pub struct ProcessedImage {
     pub created_at: DateTime<Utc>,
 }
 
+/// Provenance of a document's OCR text after manual correction
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OcrTextCorrection {
+    pub document_id: Uuid,
+    /// Whether the OCR text was corrected by a user
+    pub manually_corrected: bool,
+    /// OCR output before the first correction
+    pub original_ocr_text: Option<String>,
+    pub corrected_at: Option<DateTime<Utc>>,
+}
+
+/// A document's date and where it came from
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DocumentDate {
+    pub document_id: Uuid,
+    pub document_date: NaiveDate,
+    /// "content", "file" or "ingestion"
+    pub document_date_source: String,
+}
+
+/// A document with an upcoming expiry date
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ExpiringDocument {
+    pub document_id: Uuid,
+    pub user_id: Uuid,
+    pub filename: String,
+    pub expiry_date: NaiveDate,
+    /// Whether the owner has been reminded of this expiry
+    pub reminded: bool,
+}
+
+/// Filters and ordering for listing documents
+#[derive(Debug, Clone, Default)]
+pub struct DocumentListFilter {
+    pub ocr_status: Option<String>,
+    pub document_date_from: Option<NaiveDate>,
+    pub document_date_to: Option<NaiveDate>,
+    /// Newest document date first with undated documents last, instead of newest upload first
+    pub sort_by_document_date: bool,
+    /// Also list archived documents, such as the originals of split scans
+    pub include_archived: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateProcessedImage {
     pub document_id: Uuid,
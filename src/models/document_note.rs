@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A note a user attached to a document. Notes are private to their author.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DocumentNote {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub user_id: Uuid,
+    /// Username of the note's author
+    pub author: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DocumentNoteRequest {
+    pub content: String,
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DocumentShare {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub shared_with_user_id: Uuid,
+    pub shared_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateDocumentShareRequest {
+    /// Username of the user to share the document with
+    pub username: String,
+}
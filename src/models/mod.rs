@@ -8,6 +8,8 @@ pub mod source;
 pub mod source_error;
 pub mod responses;
 pub mod shared_link;
+pub mod document_share;
+pub mod document_note;
 pub mod comment;
 pub mod api_key;
 
@@ -65,7 +65,7 @@ pub struct DocumentResponse {
     pub ocr_word_count: Option<i32>,
     /// Time taken for OCR processing in milliseconds
     pub ocr_processing_time_ms: Option<i32>,
-    /// Current status of OCR processing (pending, processing, completed, failed)
+    /// Current status of OCR processing (pending, processing, preview, completed, failed)
     pub ocr_status: Option<String>,
     /// Current page being processed (for multi-page documents during OCR)
     #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -100,6 +100,15 @@ pub struct DocumentResponse {
     /// Additional metadata from source system (EXIF data, PDF metadata, custom attributes, etc.)
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub source_metadata: Option<serde_json::Value>,
+    /// Date the document was written, when document date extraction is enabled
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub document_date: Option<chrono::NaiveDate>,
+    /// Where the document date came from: content, file or ingestion
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub document_date_source: Option<String>,
+    /// Day the document expires, when its owner set one
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expiry_date: Option<chrono::NaiveDate>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -126,12 +135,21 @@ pub struct EnhancedDocumentResponse {
     pub ocr_word_count: Option<i32>,
     /// Time taken for OCR processing in milliseconds
     pub ocr_processing_time_ms: Option<i32>,
-    /// Current status of OCR processing (pending, processing, completed, failed)
+    /// Current status of OCR processing (pending, processing, preview, completed, failed)
     pub ocr_status: Option<String>,
     /// Search relevance score (0-1, higher is more relevant)
     pub search_rank: Option<f32>,
     /// Text snippets showing search matches with highlights
     pub snippets: Vec<SearchSnippet>,
+    /// Whether the searching user owns the document (false for documents shared with them)
+    #[serde(default)]
+    pub is_owner: bool,
+    /// Number of other matching documents with the same file content collapsed into this one (0 unless duplicates are collapsed)
+    #[serde(default)]
+    pub duplicate_count: i64,
+    /// IDs of the collapsed duplicates, to expand the result
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicate_ids: Vec<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -178,8 +196,11 @@ pub struct DocumentOcrResponse {
     pub has_ocr_text: bool,
     /// OCR text content (if available)
     pub ocr_text: Option<String>,
-    /// OCR processing confidence score (0-100)
+    /// OCR processing confidence score (0-100), calibrated for the document's language
     pub ocr_confidence: Option<f32>,
+    /// Confidence as reported by the OCR engine, before calibration
+    #[serde(default)]
+    pub ocr_confidence_raw: Option<f32>,
     /// Current OCR processing status
     pub ocr_status: Option<String>,
     /// Time taken for OCR processing in milliseconds
@@ -188,6 +209,28 @@ pub struct DocumentOcrResponse {
     pub detected_language: Option<String>,
     /// Number of pages processed (for multi-page documents)
     pub pages_processed: Option<i32>,
+    /// Whether `ocr_text` is a quick preview that full OCR will replace
+    #[serde(default)]
+    pub ocr_text_is_preview: bool,
+    /// Whether `ocr_text` was corrected by a user
+    #[serde(default)]
+    pub ocr_manually_corrected: bool,
+    /// OCR output before the first manual correction
+    #[serde(default)]
+    pub original_ocr_text: Option<String>,
+    /// Whether the text combines a PDF text layer with OCR of its embedded images
+    #[serde(default)]
+    pub ocr_hybrid_extraction: bool,
+    /// Whether the document is flagged as handwritten
+    #[serde(default)]
+    pub ocr_handwriting: bool,
+    /// OCR engine that produced `ocr_text` (`tesseract` or `handwriting`)
+    #[serde(default)]
+    pub ocr_engine: Option<String>,
+    /// Caveat about how `ocr_text` was produced, such as a handwritten document
+    /// recognized by Tesseract
+    #[serde(default)]
+    pub ocr_engine_note: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -298,6 +341,9 @@ impl From<Document> for DocumentResponse {
             file_owner: doc.file_owner,
             file_group: doc.file_group,
             source_metadata: doc.source_metadata,
+            document_date: None, // Populated separately where needed
+            document_date_source: None,
+            expiry_date: None,
         }
     }
 }
@@ -324,6 +370,55 @@ impl From<crate::models::document::IgnoredFile> for IgnoredFileResponse {
     }
 }
 
+/// Aggregate statistics about the documents a user can see
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LibraryStats {
+    pub total_documents: i64,
+    pub total_storage_bytes: i64,
+    pub by_mime_type: Vec<super::search::FacetItem>,
+    pub by_ocr_status: Vec<super::search::FacetItem>,
+    pub by_source: Vec<SourceDocumentStats>,
+    /// One entry per day for the last 30 days, oldest first, including days without uploads
+    pub ingestion_last_30_days: Vec<DailyIngestionCount>,
+    /// Mean confidence of documents whose OCR completed, if any have
+    pub average_ocr_confidence: Option<f64>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceDocumentStats {
+    /// `None` for documents that were uploaded directly rather than synced from a source
+    pub source_id: Option<Uuid>,
+    pub source_name: Option<String>,
+    pub source_type: Option<String>,
+    pub document_count: i64,
+    pub storage_bytes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DailyIngestionCount {
+    pub date: chrono::NaiveDate,
+    pub count: i64,
+}
+
+impl DailyIngestionCount {
+    /// Expands sparse per-day counts into `days` consecutive days ending on `last_day`
+    pub fn series(counts: &[(chrono::NaiveDate, i64)], last_day: chrono::NaiveDate, days: u32) -> Vec<Self> {
+        (0..days)
+            .rev()
+            .filter_map(|offset| last_day.checked_sub_days(chrono::Days::new(offset as u64)))
+            .map(|date| Self {
+                date,
+                count: counts
+                    .iter()
+                    .filter(|(day, _)| *day == date)
+                    .map(|(_, count)| count)
+                    .sum(),
+            })
+            .collect()
+    }
+}
+
 mod uuid_as_string {
     use serde::{Deserialize, Deserializer, Serializer};
     use uuid::Uuid;
@@ -2,6 +2,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::{ToSchema, IntoParams};
 
 use super::responses::EnhancedDocumentResponse;
+use crate::utils::text_search::{text_search_config, DEFAULT_SEARCH_LANGUAGE};
 
 /// Maximum length for comma-separated query parameters (DoS protection)
 const MAX_COMMA_SEPARATED_LENGTH: usize = 2000;
@@ -57,6 +58,32 @@ pub struct SearchRequest {
     pub snippet_length: Option<i32>,
     /// Search algorithm to use (default: simple)
     pub search_mode: Option<SearchMode>,
+    /// Also search documents other users have shared with you (default: false)
+    pub include_shared: Option<bool>,
+    /// Also match the text of your own notes on documents (default: your `search_include_notes` setting)
+    pub include_notes: Option<bool>,
+    /// Match terms regardless of accents, so "cafe" finds "café" (default: your `search_ignore_accents` setting)
+    pub ignore_accents: Option<bool>,
+    /// Language whose stemming to use: simple, english, german, french, spanish, italian, portuguese or dutch (default: your `search_language` setting)
+    pub language: Option<String>,
+    /// Only documents dated on or after this day (YYYY-MM-DD)
+    #[serde(default)]
+    pub document_date_from: Option<chrono::NaiveDate>,
+    /// Only documents dated on or before this day (YYYY-MM-DD)
+    #[serde(default)]
+    pub document_date_to: Option<chrono::NaiveDate>,
+    /// Show one result per file content, the most recent of the matching documents with the same file hash, listing the others as its duplicates (default: your `search_collapse_duplicates` setting)
+    pub collapse_duplicates: Option<bool>,
+}
+
+impl SearchRequest {
+    /// Text search configuration for the request's language and accent handling
+    pub fn text_search_config(&self) -> &'static str {
+        text_search_config(
+            self.language.as_deref().unwrap_or(DEFAULT_SEARCH_LANGUAGE),
+            self.ignore_accents.unwrap_or(true),
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -88,9 +115,24 @@ pub struct SearchResponse {
     pub total: i64,
     pub query_time_ms: u64,
     pub suggestions: Vec<String>,
+    /// More documents matched than `SEARCH_MAX_RESULTS`; only that many can be
+    /// paged through, so the search should be refined
+    #[serde(default)]
+    pub capped: bool,
+    /// `total` is an estimate rather than an exact count, as it is for capped searches
+    #[serde(default)]
+    pub total_is_estimate: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+/// How many documents a search matches, counted exactly up to a cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchMatchCount {
+    pub total: i64,
+    pub capped: bool,
+    pub total_is_estimate: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FacetItem {
     pub value: String,
     pub count: i64,
@@ -101,3 +143,157 @@ pub struct SearchFacetsResponse {
     pub mime_types: Vec<FacetItem>,
     pub tags: Vec<FacetItem>,
 }
+
+/// A document ranked by similarity to another document
+#[derive(Debug, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct RelatedDocument {
+    pub id: uuid::Uuid,
+    pub filename: String,
+    pub original_filename: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Combined similarity score (0-1), the higher of `text_score` and `filename_similarity`
+    pub score: f32,
+    /// Full-text rank against the source document's most frequent terms (0-1)
+    pub text_score: f32,
+    /// Trigram similarity of the filenames (0-1)
+    pub filename_similarity: f32,
+}
+
+/// Query parameters of a search export, on top of the search's own
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SearchExportQuery {
+    /// Export format; only `csv` is supported (default: csv)
+    pub format: Option<String>,
+    /// Comma-separated columns to export, in order (default: filename, created_at,
+    /// document_date, labels, ocr_status, ocr_confidence, source). Available: id,
+    /// filename, original_filename, mime_type, file_size, created_at, document_date,
+    /// labels, ocr_status, ocr_confidence, ocr_word_count, source
+    pub columns: Option<String>,
+}
+
+/// Metadata of a document matched by an exported search
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SearchExportRow {
+    pub id: uuid::Uuid,
+    pub filename: String,
+    pub original_filename: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub document_date: Option<chrono::NaiveDate>,
+    /// Label names, alphabetically, separated by `; `
+    pub labels: Option<String>,
+    pub ocr_status: Option<String>,
+    pub ocr_confidence: Option<f32>,
+    pub ocr_word_count: Option<i32>,
+    pub source_type: Option<String>,
+    pub source_name: Option<String>,
+}
+
+/// A column of a search export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchExportColumn {
+    Id,
+    Filename,
+    OriginalFilename,
+    MimeType,
+    FileSize,
+    CreatedAt,
+    DocumentDate,
+    Labels,
+    OcrStatus,
+    OcrConfidence,
+    OcrWordCount,
+    Source,
+}
+
+impl SearchExportColumn {
+    pub const ALL: &'static [SearchExportColumn] = &[
+        SearchExportColumn::Id,
+        SearchExportColumn::Filename,
+        SearchExportColumn::OriginalFilename,
+        SearchExportColumn::MimeType,
+        SearchExportColumn::FileSize,
+        SearchExportColumn::CreatedAt,
+        SearchExportColumn::DocumentDate,
+        SearchExportColumn::Labels,
+        SearchExportColumn::OcrStatus,
+        SearchExportColumn::OcrConfidence,
+        SearchExportColumn::OcrWordCount,
+        SearchExportColumn::Source,
+    ];
+
+    pub const DEFAULT: &'static [SearchExportColumn] = &[
+        SearchExportColumn::Filename,
+        SearchExportColumn::CreatedAt,
+        SearchExportColumn::DocumentDate,
+        SearchExportColumn::Labels,
+        SearchExportColumn::OcrStatus,
+        SearchExportColumn::OcrConfidence,
+        SearchExportColumn::Source,
+    ];
+
+    /// Name used in the `columns` parameter and the header row
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchExportColumn::Id => "id",
+            SearchExportColumn::Filename => "filename",
+            SearchExportColumn::OriginalFilename => "original_filename",
+            SearchExportColumn::MimeType => "mime_type",
+            SearchExportColumn::FileSize => "file_size",
+            SearchExportColumn::CreatedAt => "created_at",
+            SearchExportColumn::DocumentDate => "document_date",
+            SearchExportColumn::Labels => "labels",
+            SearchExportColumn::OcrStatus => "ocr_status",
+            SearchExportColumn::OcrConfidence => "ocr_confidence",
+            SearchExportColumn::OcrWordCount => "ocr_word_count",
+            SearchExportColumn::Source => "source",
+        }
+    }
+
+    /// The column's value for a document; missing values are empty
+    pub fn value(&self, row: &SearchExportRow) -> String {
+        match self {
+            SearchExportColumn::Id => row.id.to_string(),
+            SearchExportColumn::Filename => row.filename.clone(),
+            SearchExportColumn::OriginalFilename => row.original_filename.clone(),
+            SearchExportColumn::MimeType => row.mime_type.clone(),
+            SearchExportColumn::FileSize => row.file_size.to_string(),
+            SearchExportColumn::CreatedAt => row.created_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            SearchExportColumn::DocumentDate => row.document_date.map(|date| date.to_string()).unwrap_or_default(),
+            SearchExportColumn::Labels => row.labels.clone().unwrap_or_default(),
+            SearchExportColumn::OcrStatus => row.ocr_status.clone().unwrap_or_default(),
+            SearchExportColumn::OcrConfidence => row.ocr_confidence.map(|confidence| format!("{:.1}", confidence)).unwrap_or_default(),
+            SearchExportColumn::OcrWordCount => row.ocr_word_count.map(|count| count.to_string()).unwrap_or_default(),
+            // The source's name, or how the document arrived when it has no source
+            SearchExportColumn::Source => row.source_name.clone().or_else(|| row.source_type.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Parses a comma-separated column list; an empty or missing list selects
+    /// the default columns. Fails with the first unknown column name.
+    pub fn parse_list(columns: Option<&str>) -> Result<Vec<SearchExportColumn>, String> {
+        let names: Vec<&str> = columns
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect();
+        if names.is_empty() {
+            return Ok(Self::DEFAULT.to_vec());
+        }
+
+        names
+            .into_iter()
+            .map(|name| {
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|column| column.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| name.to_string())
+            })
+            .collect()
+    }
+}
@@ -4,6 +4,9 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use utoipa::ToSchema;
 
+/// Resolutions accepted for OCR, wherever a DPI can be set
+pub const OCR_DPI_RANGE: std::ops::RangeInclusive<i32> = 72..=1200;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Settings {
     pub id: Uuid,
@@ -63,6 +66,38 @@ pub struct Settings {
     // Office document extraction configuration
     pub office_extraction_timeout_seconds: i32,
     pub office_extraction_enable_detailed_logging: bool,
+    pub ocr_auto_psm: bool,
+    pub ocr_quick_preview_pages: i32,
+    pub notification_delivery_mode: String,
+    pub notification_digest_interval_minutes: i32,
+    pub ocr_max_pixels: i64,
+    pub ocr_pdf_hybrid: bool,
+    pub ocr_reprocess_on_settings_change: bool,
+    pub auto_label_language: bool,
+    pub extract_document_date: bool,
+    pub document_date_priority: String,
+    pub document_date_rule: String,
+    pub document_date_order: String,
+    pub search_include_notes: bool,
+    pub ocr_verify_orientation: bool,
+    pub auto_split_on_separator: bool,
+    pub auto_split_barcode: Option<String>,
+    pub expiry_reminder_days: i32,
+    pub search_language: String,
+    pub search_ignore_accents: bool,
+    pub extract_archives: bool,
+    pub keep_original_pdf: bool,
+    pub decode_barcodes: bool,
+    pub search_collapse_duplicates: bool,
+    pub ocr_incremental_pages: bool,
+    pub parse_front_matter: bool,
+    pub extract_tables: bool,
+    /// Legacy Word tools to try, comma-separated; None uses the server order
+    pub doc_extraction_order: Option<String>,
+    /// PDF extraction methods to try, comma-separated; None uses the server order
+    pub pdf_extraction_order: Option<String>,
+    /// Try the most successful extraction methods first; None uses the server setting
+    pub extraction_auto_order: Option<bool>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -124,6 +159,113 @@ pub struct SettingsResponse {
     // Office document extraction configuration
     pub office_extraction_timeout_seconds: i32,
     pub office_extraction_enable_detailed_logging: bool,
+    pub ocr_auto_psm: bool,
+    pub ocr_quick_preview_pages: i32,
+    pub notification_delivery_mode: String,
+    pub notification_digest_interval_minutes: i32,
+    pub ocr_max_pixels: i64,
+    pub ocr_pdf_hybrid: bool,
+    pub ocr_reprocess_on_settings_change: bool,
+    pub auto_label_language: bool,
+    pub extract_document_date: bool,
+    pub document_date_priority: String,
+    pub document_date_rule: String,
+    pub document_date_order: String,
+    pub search_include_notes: bool,
+    pub ocr_verify_orientation: bool,
+    pub auto_split_on_separator: bool,
+    pub auto_split_barcode: Option<String>,
+    pub expiry_reminder_days: i32,
+    pub search_language: String,
+    pub search_ignore_accents: bool,
+    pub extract_archives: bool,
+    pub keep_original_pdf: bool,
+    pub decode_barcodes: bool,
+    pub search_collapse_duplicates: bool,
+    pub ocr_incremental_pages: bool,
+    pub parse_front_matter: bool,
+    pub extract_tables: bool,
+    pub doc_extraction_order: Option<String>,
+    pub pdf_extraction_order: Option<String>,
+    pub extraction_auto_order: Option<bool>,
+    /// Present after an update that changed OCR-relevant settings
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ocr_reprocess: Option<OcrReprocessSummary>,
+}
+
+/// Documents affected by a change to OCR-relevant settings
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OcrReprocessSummary {
+    /// Names of the OCR settings that changed
+    pub changed_settings: Vec<String>,
+    /// OCR settings version now in effect; documents record the version they were processed under
+    pub settings_version: i32,
+    /// Documents processed under older settings, excluding manually corrected ones
+    pub affected_documents: i64,
+    /// Documents queued for reprocessing (0 unless reprocessing on settings change is enabled)
+    pub queued_documents: i64,
+}
+
+/// OCR settings overridden for a single on-demand OCR job. Unset fields use the
+/// owner's settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OcrJobOverrides {
+    /// Tesseract languages to recognize, the first one being primary (e.g. ["eng", "deu"])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<String>>,
+    /// Tesseract page segmentation mode (0-13)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_segmentation_mode: Option<i32>,
+    /// Resolution images are rendered at for OCR
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dpi: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_image_preprocessing: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detect_orientation: Option<bool>,
+}
+
+impl OcrJobOverrides {
+    /// Checks the overrides that aren't languages, which are validated against
+    /// the installed Tesseract data
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(mode) = self.page_segmentation_mode {
+            if !(0..=13).contains(&mode) {
+                return Err(format!("Page segmentation mode must be between 0 and 13, got {}", mode));
+            }
+        }
+        if let Some(dpi) = self.dpi {
+            if !OCR_DPI_RANGE.contains(&dpi) {
+                return Err(format!("DPI must be between 72 and 1200, got {}", dpi));
+            }
+        }
+        if matches!(&self.languages, Some(languages) if languages.is_empty()) {
+            return Err("At least one language is required when languages are given".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn apply_to(&self, settings: &mut Settings) {
+        if let Some(languages) = self.languages.as_ref().filter(|languages| !languages.is_empty()) {
+            settings.preferred_languages = languages.clone();
+            settings.primary_language = languages[0].clone();
+            settings.ocr_language = languages[0].clone();
+        }
+        // An explicit mode also turns off automatic mode selection
+        if let Some(mode) = self.page_segmentation_mode {
+            settings.ocr_page_segmentation_mode = mode;
+            settings.ocr_auto_psm = false;
+        }
+        if let Some(dpi) = self.dpi {
+            settings.ocr_dpi = dpi;
+        }
+        if let Some(enabled) = self.enable_image_preprocessing {
+            settings.enable_image_preprocessing = enabled;
+        }
+        if let Some(enabled) = self.detect_orientation {
+            settings.ocr_detect_orientation = enabled;
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -183,6 +325,51 @@ pub struct UpdateSettings {
     // Office document extraction configuration
     pub office_extraction_timeout_seconds: Option<i32>,
     pub office_extraction_enable_detailed_logging: Option<bool>,
+    pub ocr_auto_psm: Option<bool>,
+    pub ocr_quick_preview_pages: Option<i32>,
+    pub notification_delivery_mode: Option<String>,
+    pub notification_digest_interval_minutes: Option<i32>,
+    pub ocr_max_pixels: Option<i64>,
+    pub ocr_pdf_hybrid: Option<bool>,
+    pub ocr_reprocess_on_settings_change: Option<bool>,
+    pub auto_label_language: Option<bool>,
+    pub extract_document_date: Option<bool>,
+    pub document_date_priority: Option<String>,
+    pub document_date_rule: Option<String>,
+    pub document_date_order: Option<String>,
+    pub search_include_notes: Option<bool>,
+    pub ocr_verify_orientation: Option<bool>,
+    pub auto_split_on_separator: Option<bool>,
+    pub auto_split_barcode: Option<Option<String>>,
+    pub expiry_reminder_days: Option<i32>,
+    pub search_language: Option<String>,
+    pub search_ignore_accents: Option<bool>,
+    pub extract_archives: Option<bool>,
+    pub keep_original_pdf: Option<bool>,
+    pub decode_barcodes: Option<bool>,
+    pub search_collapse_duplicates: Option<bool>,
+    pub ocr_incremental_pages: Option<bool>,
+    pub parse_front_matter: Option<bool>,
+    pub extract_tables: Option<bool>,
+    /// `null` goes back to the server order
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub doc_extraction_order: Option<Option<String>>,
+    /// `null` goes back to the server order
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub pdf_extraction_order: Option<Option<String>>,
+    /// `null` goes back to the server setting
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub extraction_auto_order: Option<Option<bool>>,
+}
+
+/// Reads a present field as `Some`, so that `null` clears the setting while a
+/// missing field leaves it unchanged
+fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 impl From<Settings> for SettingsResponse {
@@ -243,6 +430,36 @@ impl From<Settings> for SettingsResponse {
             // Office document extraction configuration
             office_extraction_timeout_seconds: settings.office_extraction_timeout_seconds,
             office_extraction_enable_detailed_logging: settings.office_extraction_enable_detailed_logging,
+            ocr_auto_psm: settings.ocr_auto_psm,
+            ocr_quick_preview_pages: settings.ocr_quick_preview_pages,
+            notification_delivery_mode: settings.notification_delivery_mode,
+            notification_digest_interval_minutes: settings.notification_digest_interval_minutes,
+            ocr_max_pixels: settings.ocr_max_pixels,
+            ocr_pdf_hybrid: settings.ocr_pdf_hybrid,
+            ocr_reprocess_on_settings_change: settings.ocr_reprocess_on_settings_change,
+            auto_label_language: settings.auto_label_language,
+            extract_document_date: settings.extract_document_date,
+            document_date_priority: settings.document_date_priority,
+            document_date_rule: settings.document_date_rule,
+            document_date_order: settings.document_date_order,
+            search_include_notes: settings.search_include_notes,
+            ocr_verify_orientation: settings.ocr_verify_orientation,
+            auto_split_on_separator: settings.auto_split_on_separator,
+            auto_split_barcode: settings.auto_split_barcode,
+            expiry_reminder_days: settings.expiry_reminder_days,
+            search_language: settings.search_language,
+            search_ignore_accents: settings.search_ignore_accents,
+            extract_archives: settings.extract_archives,
+            keep_original_pdf: settings.keep_original_pdf,
+            decode_barcodes: settings.decode_barcodes,
+            search_collapse_duplicates: settings.search_collapse_duplicates,
+            ocr_incremental_pages: settings.ocr_incremental_pages,
+            parse_front_matter: settings.parse_front_matter,
+            extract_tables: settings.extract_tables,
+            doc_extraction_order: settings.doc_extraction_order,
+            pdf_extraction_order: settings.pdf_extraction_order,
+            extraction_auto_order: settings.extraction_auto_order,
+            ocr_reprocess: None,
         }
     }
 }
@@ -310,10 +527,138 @@ impl UpdateSettings {
             // Office document extraction configuration - don't update these in language update
             office_extraction_timeout_seconds: None,
             office_extraction_enable_detailed_logging: None,
+            ocr_auto_psm: None,
+            ocr_quick_preview_pages: None,
+            notification_delivery_mode: None,
+            notification_digest_interval_minutes: None,
+            ocr_max_pixels: None,
+            ocr_pdf_hybrid: None,
+            ocr_reprocess_on_settings_change: None,
+            auto_label_language: None,
+            extract_document_date: None,
+            document_date_priority: None,
+            document_date_rule: None,
+            document_date_order: None,
+            search_include_notes: None,
+            ocr_verify_orientation: None,
+            auto_split_on_separator: None,
+            auto_split_barcode: None,
+            expiry_reminder_days: None,
+            search_language: None,
+            search_ignore_accents: None,
+            extract_archives: None,
+            keep_original_pdf: None,
+            decode_barcodes: None,
+            search_collapse_duplicates: None,
+            ocr_incremental_pages: None,
+            parse_front_matter: None,
+            extract_tables: None,
+            doc_extraction_order: None,
+            pdf_extraction_order: None,
+            extraction_auto_order: None,
         }
     }
 }
 
+impl Settings {
+    /// Names of the settings that affect OCR output and differ from `previous`
+    pub fn ocr_changes_since(&self, previous: &Settings) -> Vec<&'static str> {
+        macro_rules! changed_fields {
+            ($($field:ident),* $(,)?) => {
+                [$((stringify!($field), self.$field != previous.$field)),*]
+            };
+        }
+
+        changed_fields![
+            ocr_language,
+            preferred_languages,
+            primary_language,
+            auto_detect_language_combination,
+            auto_rotate_images,
+            enable_image_preprocessing,
+            ocr_page_segmentation_mode,
+            ocr_engine_mode,
+            ocr_dpi,
+            ocr_enhance_contrast,
+            ocr_remove_noise,
+            ocr_detect_orientation,
+            ocr_verify_orientation,
+            ocr_whitelist_chars,
+            ocr_blacklist_chars,
+            ocr_brightness_boost,
+            ocr_contrast_multiplier,
+            ocr_noise_reduction_level,
+            ocr_sharpening_strength,
+            ocr_morphological_operations,
+            ocr_adaptive_threshold_window_size,
+            ocr_histogram_equalization,
+            ocr_upscale_factor,
+            ocr_max_image_width,
+            ocr_max_image_height,
+            ocr_skip_enhancement,
+            ocr_auto_psm,
+            ocr_max_pixels,
+            ocr_pdf_hybrid,
+            decode_barcodes,
+            extract_tables,
+            doc_extraction_order,
+            pdf_extraction_order,
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+    }
+
+    /// Copy of these settings with the OCR fields set in `overrides` applied, for
+    /// trying settings out on a sample without saving them
+    pub fn with_ocr_overrides(&self, overrides: &UpdateSettings) -> Settings {
+        let mut settings = self.clone();
+        macro_rules! apply_overrides {
+            ($($field:ident),* $(,)?) => {
+                $(if let Some(value) = &overrides.$field {
+                    settings.$field = value.clone();
+                })*
+            };
+        }
+
+        apply_overrides![
+            ocr_language,
+            preferred_languages,
+            primary_language,
+            auto_detect_language_combination,
+            auto_rotate_images,
+            enable_image_preprocessing,
+            ocr_page_segmentation_mode,
+            ocr_engine_mode,
+            ocr_dpi,
+            ocr_enhance_contrast,
+            ocr_remove_noise,
+            ocr_detect_orientation,
+            ocr_verify_orientation,
+            ocr_whitelist_chars,
+            ocr_blacklist_chars,
+            ocr_brightness_boost,
+            ocr_contrast_multiplier,
+            ocr_noise_reduction_level,
+            ocr_sharpening_strength,
+            ocr_morphological_operations,
+            ocr_adaptive_threshold_window_size,
+            ocr_histogram_equalization,
+            ocr_upscale_factor,
+            ocr_max_image_width,
+            ocr_max_image_height,
+            ocr_quality_threshold_brightness,
+            ocr_quality_threshold_contrast,
+            ocr_quality_threshold_noise,
+            ocr_quality_threshold_sharpness,
+            ocr_skip_enhancement,
+            ocr_auto_psm,
+            ocr_max_pixels,
+        ];
+        settings
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -390,6 +735,35 @@ impl Default for Settings {
             // Office document extraction configuration defaults
             office_extraction_timeout_seconds: 120, // 2 minutes default timeout
             office_extraction_enable_detailed_logging: false, // Conservative default
+            ocr_auto_psm: false, // Use the fixed ocr_page_segmentation_mode by default
+            ocr_quick_preview_pages: 0, // Quick preview disabled by default
+            notification_delivery_mode: "immediate".to_string(), // "immediate" or "digest"
+            notification_digest_interval_minutes: 15, // Digest window when notification_delivery_mode is "digest"
+            ocr_max_pixels: 40_000_000, // Downscale larger images before OCR to bound memory
+            ocr_pdf_hybrid: false, // Also OCR embedded images in PDFs that have a text layer
+            ocr_reprocess_on_settings_change: false, // Only report affected documents; the user decides when to reprocess
+            auto_label_language: false, // Language labels are opt-in
+            extract_document_date: false, // Date extraction from content is opt-in
+            document_date_priority: "content,file,ingestion".to_string(), // Prefer dates found in the text
+            document_date_rule: "most_frequent".to_string(), // Ties go to the date mentioned first
+            document_date_order: "dmy".to_string(), // 03/04/2024 is 3 April
+            search_include_notes: false, // Notes are only matched when asked for
+            ocr_verify_orientation: true, // Undo rotations that make OCR worse
+            auto_split_on_separator: false, // Multi-document scans are kept whole unless enabled
+            auto_split_barcode: None, // Split on blank pages rather than barcodes
+            expiry_reminder_days: 30, // Remind a month before a document expires
+            search_language: "english".to_string(), // Stem search terms as English
+            search_ignore_accents: true, // café matches cafe
+            extract_archives: false, // Keep uploaded archives whole
+            keep_original_pdf: true, // Keep the original next to an optimized PDF
+            decode_barcodes: false, // Barcodes need zbar-tools
+            search_collapse_duplicates: false, // Show every matching document
+            ocr_incremental_pages: false, // OCR every page of a new file version
+            parse_front_matter: true, // Index the body of notes and label them with their tags
+            extract_tables: false, // Table detection runs pdftotext a second time
+            doc_extraction_order: None, // OFFICE_DOC_TOOL_ORDER
+            pdf_extraction_order: None, // PDF_EXTRACTION_ORDER
+            extraction_auto_order: None, // EXTRACTION_AUTO_ORDER
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
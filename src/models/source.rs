@@ -73,6 +73,42 @@ impl TryFrom<String> for SourceStatus {
     }
 }
 
+/// How the OCR queue processes documents synced from a source
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceProcessingMode {
+    /// Use existing text where there is enough of it and OCR the rest
+    #[default]
+    Full,
+    /// Use existing text layers only and never run OCR; image-only pages and images get no text
+    TextOnly,
+    /// Store documents without extracting any text
+    None,
+}
+
+impl std::fmt::Display for SourceProcessingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceProcessingMode::Full => write!(f, "full"),
+            SourceProcessingMode::TextOnly => write!(f, "text_only"),
+            SourceProcessingMode::None => write!(f, "none"),
+        }
+    }
+}
+
+impl TryFrom<String> for SourceProcessingMode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "full" => Ok(SourceProcessingMode::Full),
+            "text_only" => Ok(SourceProcessingMode::TextOnly),
+            "none" => Ok(SourceProcessingMode::None),
+            _ => Err(format!("Invalid source processing mode: {}", value)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Source {
     pub id: Uuid,
@@ -156,6 +192,10 @@ pub struct SourceWithStats {
     pub source: SourceResponse,
     pub recent_documents: Vec<DocumentResponse>,
     pub sync_progress: Option<f32>,
+    /// Circuit breaker of the source's scheduled syncs
+    pub circuit: SourceCircuit,
+    /// Skew between the WebDAV server's clock and readur's, as last measured
+    pub clock_skew: Option<SourceClockSkew>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -168,6 +208,204 @@ pub struct WebDAVSourceConfig {
     pub auto_sync: bool,
     pub sync_interval_minutes: i32,
     pub server_type: Option<String>,
+    /// Maximum directory depth to descend below each watch folder (None = unlimited)
+    #[serde(default)]
+    pub max_scan_depth: Option<u32>,
+    /// Process the most recently modified files first, so a large first sync makes
+    /// recent documents available before older ones backfill
+    #[serde(default)]
+    pub sync_newest_first: bool,
+    /// Sync as soon as the scheduler starts after a server start, instead of
+    /// waiting for the interval. Applies even when `auto_sync` is off.
+    #[serde(default)]
+    pub sync_on_startup: bool,
+    /// Whether synced documents are OCR'd, limited to their existing text, or stored without text
+    #[serde(default)]
+    pub processing_mode: SourceProcessingMode,
+    /// Queue synced documents for OCR. When off, documents are stored without
+    /// text unless `processing_mode` is `text_only`, which still reads their
+    /// existing text layers.
+    #[serde(default = "default_ocr_enabled")]
+    pub ocr_enabled: bool,
+    /// Re-download a known file with `If-None-Match`, skipping it when the server
+    /// reports it unchanged
+    #[serde(default = "default_conditional_downloads")]
+    pub conditional_downloads: bool,
+    /// Sync a file that appears at several paths with the same ETag only once.
+    /// Off by default because some servers reuse ETags for different files.
+    #[serde(default)]
+    pub detect_etag_aliases: bool,
+    /// User-Agent sent to the server instead of the default `Readur/<version>` one
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// `Depth` header of each kind of PROPFIND, for servers with quirks
+    #[serde(default)]
+    pub propfind_depths: PropfindDepths,
+    /// Checking downloads against the checksums the server sends
+    #[serde(default)]
+    pub download_verification: DownloadVerification,
+    /// Visits of a directory nested inside itself (the same name and ETag as an
+    /// ancestor) before a sync stops with a loop error; 0 turns this off
+    #[serde(default = "default_max_directory_visits")]
+    pub max_directory_visits: u32,
+}
+
+fn default_conditional_downloads() -> bool {
+    true
+}
+
+fn default_max_directory_visits() -> u32 {
+    crate::services::webdav::loop_detection::DEFAULT_MAX_DIRECTORY_VISITS
+}
+
+/// Value of the `Depth` header of a WebDAV PROPFIND request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PropfindDepth {
+    /// Only the resource itself
+    Zero,
+    /// The resource and its direct children
+    One,
+    /// The resource and everything below it
+    Infinity,
+}
+
+impl PropfindDepth {
+    pub fn as_header_value(self) -> &'static str {
+        match self {
+            PropfindDepth::Zero => "0",
+            PropfindDepth::One => "1",
+            PropfindDepth::Infinity => "infinity",
+        }
+    }
+}
+
+impl std::fmt::Display for PropfindDepth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_header_value())
+    }
+}
+
+impl TryFrom<String> for PropfindDepth {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "0" => Ok(PropfindDepth::Zero),
+            "1" => Ok(PropfindDepth::One),
+            "infinity" => Ok(PropfindDepth::Infinity),
+            _ => Err(format!("Invalid PROPFIND depth '{}' (expected 0, 1 or infinity)", value)),
+        }
+    }
+}
+
+impl From<PropfindDepth> for String {
+    fn from(depth: PropfindDepth) -> Self {
+        depth.as_header_value().to_string()
+    }
+}
+
+/// `Depth` sent with each kind of PROPFIND. The defaults suit most servers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct PropfindDepths {
+    /// Connection tests and health checks
+    #[schema(value_type = String, example = "1")]
+    pub connection_test: PropfindDepth,
+    /// Listing a directory during discovery. With `infinity`, the listing
+    /// includes everything below the directory.
+    #[schema(value_type = String, example = "1")]
+    pub directory_listing: PropfindDepth,
+    /// Looking up the metadata of a single file
+    #[schema(value_type = String, example = "0")]
+    pub file_metadata: PropfindDepth,
+}
+
+impl Default for PropfindDepths {
+    fn default() -> Self {
+        Self {
+            connection_test: PropfindDepth::One,
+            directory_listing: PropfindDepth::One,
+            file_metadata: PropfindDepth::Zero,
+        }
+    }
+}
+
+/// How strictly a downloaded file is checked against the checksum the server
+/// sends with it (Nextcloud/ownCloud `OC-Checksum`, `Content-MD5`, `Digest`, or
+/// an S3 ETag that is the object's MD5)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumVerification {
+    /// Checksums are ignored
+    Off,
+    /// A mismatch is logged and the download kept
+    Warn,
+    /// A mismatch is downloaded again, failing after the retries. Files
+    /// without a checksum are accepted.
+    #[default]
+    Verify,
+    /// Like `verify`, but a download without a checksum fails too
+    Require,
+}
+
+pub const DEFAULT_DOWNLOAD_VERIFICATION_RETRIES: u32 = 2;
+const MAX_DOWNLOAD_VERIFICATION_RETRIES: u32 = 10;
+
+/// Checking downloads against server-provided checksums, which catches a
+/// transfer corrupted on the way before the file is stored and OCR'd
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct DownloadVerification {
+    pub checksums: ChecksumVerification,
+    /// Downloads after the first one whose checksum didn't match, before giving up
+    pub retries: u32,
+}
+
+impl Default for DownloadVerification {
+    fn default() -> Self {
+        Self {
+            checksums: ChecksumVerification::default(),
+            retries: DEFAULT_DOWNLOAD_VERIFICATION_RETRIES,
+        }
+    }
+}
+
+impl DownloadVerification {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.retries > MAX_DOWNLOAD_VERIFICATION_RETRIES {
+            return Err("download_verification.retries must be at most 10");
+        }
+        Ok(())
+    }
+}
+
+fn default_ocr_enabled() -> bool {
+    true
+}
+
+/// Whether documents synced from a source are queued for text extraction. A
+/// source with OCR turned off still has its text layers read in `text_only` mode.
+pub fn source_queues_ocr(ocr_enabled: bool, processing_mode: SourceProcessingMode) -> bool {
+    ocr_enabled || processing_mode == SourceProcessingMode::TextOnly
+}
+
+impl WebDAVSourceConfig {
+    /// Checks a custom User-Agent can be sent as a header value
+    pub fn validate_user_agent(&self) -> Result<(), &'static str> {
+        match &self.user_agent {
+            Some(user_agent) if !is_valid_user_agent(user_agent) => {
+                Err("WebDAV user_agent must be non-empty printable text without line breaks")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether `user_agent` is usable as a User-Agent header value
+pub fn is_valid_user_agent(user_agent: &str) -> bool {
+    !user_agent.trim().is_empty()
+        && axum::http::HeaderValue::from_str(user_agent).is_ok()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -178,6 +416,23 @@ pub struct LocalFolderSourceConfig {
     pub sync_interval_minutes: i32,
     pub recursive: bool,
     pub follow_symlinks: bool,
+    /// See [`WebDAVSourceConfig::sync_newest_first`]
+    #[serde(default)]
+    pub sync_newest_first: bool,
+    /// See [`WebDAVSourceConfig::sync_on_startup`]
+    #[serde(default)]
+    pub sync_on_startup: bool,
+    /// See [`WebDAVSourceConfig::processing_mode`]
+    #[serde(default)]
+    pub processing_mode: SourceProcessingMode,
+    /// See [`WebDAVSourceConfig::ocr_enabled`]
+    #[serde(default = "default_ocr_enabled")]
+    pub ocr_enabled: bool,
+    /// Recognize files by their file id (device and inode) so a file renamed or
+    /// moved within the watched folders keeps its document, whose path is
+    /// updated. Where no file id is available, files are matched by content hash.
+    #[serde(default)]
+    pub track_file_ids: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -196,6 +451,82 @@ pub struct S3SourceConfig {
     pub file_extensions: Vec<String>,
     pub auto_sync: bool,
     pub sync_interval_minutes: i32,
+    /// See [`WebDAVSourceConfig::sync_newest_first`]
+    #[serde(default)]
+    pub sync_newest_first: bool,
+    /// See [`WebDAVSourceConfig::sync_on_startup`]
+    #[serde(default)]
+    pub sync_on_startup: bool,
+    /// See [`WebDAVSourceConfig::processing_mode`]
+    #[serde(default)]
+    pub processing_mode: SourceProcessingMode,
+    /// See [`WebDAVSourceConfig::ocr_enabled`]
+    #[serde(default = "default_ocr_enabled")]
+    pub ocr_enabled: bool,
+    /// Seconds to wait for a connection to the endpoint
+    #[serde(default = "default_s3_connect_timeout_seconds")]
+    pub connect_timeout_seconds: u64,
+    /// Seconds to wait for the next bytes of a response before giving up
+    #[serde(default = "default_s3_read_timeout_seconds")]
+    pub read_timeout_seconds: u64,
+    /// Seconds an operation may take in total, including retries
+    #[serde(default = "default_s3_operation_timeout_seconds")]
+    pub operation_timeout_seconds: u64,
+    /// Retries after a failed attempt, for timeouts, throttling and server errors
+    #[serde(default = "default_s3_max_retries")]
+    pub max_retries: u32,
+    /// Create the bucket when the storage backend starts and it doesn't exist
+    #[serde(default)]
+    pub create_bucket_if_missing: bool,
+    /// See [`WebDAVSourceConfig::download_verification`]. Only ETags of objects
+    /// uploaded in one part without KMS or customer-key encryption are MD5s.
+    #[serde(default)]
+    pub download_verification: DownloadVerification,
+}
+
+/// S3 timeouts default to values that let a hung endpoint fail a request within
+/// minutes instead of blocking a sync worker indefinitely
+pub const DEFAULT_S3_CONNECT_TIMEOUT_SECONDS: u64 = 10;
+pub const DEFAULT_S3_READ_TIMEOUT_SECONDS: u64 = 60;
+pub const DEFAULT_S3_OPERATION_TIMEOUT_SECONDS: u64 = 300;
+pub const DEFAULT_S3_MAX_RETRIES: u32 = 3;
+const MAX_S3_TIMEOUT_SECONDS: u64 = 3600;
+const MAX_S3_RETRIES: u32 = 10;
+
+fn default_s3_connect_timeout_seconds() -> u64 {
+    DEFAULT_S3_CONNECT_TIMEOUT_SECONDS
+}
+
+fn default_s3_read_timeout_seconds() -> u64 {
+    DEFAULT_S3_READ_TIMEOUT_SECONDS
+}
+
+fn default_s3_operation_timeout_seconds() -> u64 {
+    DEFAULT_S3_OPERATION_TIMEOUT_SECONDS
+}
+
+fn default_s3_max_retries() -> u32 {
+    DEFAULT_S3_MAX_RETRIES
+}
+
+impl S3SourceConfig {
+    /// Checks the timeout and retry settings are within usable bounds
+    pub fn validate_network_settings(&self) -> Result<(), &'static str> {
+        let timeouts = [self.connect_timeout_seconds, self.read_timeout_seconds, self.operation_timeout_seconds];
+        if timeouts.contains(&0) {
+            return Err("S3 timeouts must be at least 1 second");
+        }
+        if timeouts.iter().any(|timeout| *timeout > MAX_S3_TIMEOUT_SECONDS) {
+            return Err("S3 timeouts must be at most 3600 seconds");
+        }
+        if self.operation_timeout_seconds < self.connect_timeout_seconds {
+            return Err("S3 operation timeout must not be shorter than the connect timeout");
+        }
+        if self.max_retries > MAX_S3_RETRIES {
+            return Err("S3 max_retries must be at most 10");
+        }
+        Ok(())
+    }
 }
 
 // WebDAV-related structs
@@ -217,6 +548,88 @@ pub struct WebDAVCrawlEstimate {
     pub total_size_mb: f64,
 }
 
+/// Assumed cost of downloading and storing one file, for sync time estimates
+const ESTIMATED_SECONDS_PER_FILE: f64 = 0.2;
+/// Assumed download throughput, for sync time estimates
+const ESTIMATED_BYTES_PER_SECOND: f64 = 5.0 * 1024.0 * 1024.0;
+
+/// Estimated size of a source's first sync, extrapolated from a sample of its
+/// watch folders
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct SourcePreviewEstimate {
+    pub estimated_files: i64,
+    pub estimated_size_bytes: i64,
+    /// Rough time to list, download and store everything, excluding OCR
+    pub estimated_sync_hours: f64,
+    pub folders_sampled: usize,
+    pub folders_total: usize,
+    /// Whether every watch folder was listed, so the counts are not extrapolated
+    pub exact: bool,
+}
+
+impl SourcePreviewEstimate {
+    /// Extrapolates `files` and `bytes` found in `folders_sampled` of
+    /// `folders_total` watch folders, listed in `scan_time`
+    pub fn from_sample(
+        files: usize,
+        bytes: i64,
+        scan_time: std::time::Duration,
+        folders_sampled: usize,
+        folders_total: usize,
+    ) -> Self {
+        let scale = if folders_sampled > 0 {
+            folders_total as f64 / folders_sampled as f64
+        } else {
+            0.0
+        };
+        let estimated_files = (files as f64 * scale).round() as i64;
+        let estimated_size_bytes = (bytes as f64 * scale).round() as i64;
+        let seconds = scan_time.as_secs_f64() * scale
+            + estimated_files as f64 * ESTIMATED_SECONDS_PER_FILE
+            + estimated_size_bytes as f64 / ESTIMATED_BYTES_PER_SECOND;
+
+        Self {
+            estimated_files,
+            estimated_size_bytes,
+            estimated_sync_hours: seconds / 3600.0,
+            folders_sampled,
+            folders_total,
+            exact: folders_sampled == folders_total,
+        }
+    }
+
+    /// Converts a WebDAV crawl estimate, which samples at most `folders_sampled`
+    /// of `folders_total` watch folders
+    pub fn from_webdav(estimate: &WebDAVCrawlEstimate, folders_sampled: usize, folders_total: usize) -> Self {
+        let estimated_size_bytes = (estimate.total_size_mb * 1024.0 * 1024.0).round() as i64;
+        let seconds = estimate.total_estimated_time_hours as f64 * 3600.0
+            + estimate.total_supported_files as f64 * ESTIMATED_SECONDS_PER_FILE
+            + estimated_size_bytes as f64 / ESTIMATED_BYTES_PER_SECOND;
+
+        Self {
+            estimated_files: estimate.total_supported_files,
+            estimated_size_bytes,
+            estimated_sync_hours: seconds / 3600.0,
+            folders_sampled,
+            folders_total,
+            exact: folders_sampled == folders_total,
+        }
+    }
+}
+
+/// What a source config would sync, checked without saving it
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SourcePreviewResponse {
+    /// Whether the config is valid for the source type
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub connection_ok: bool,
+    pub connection_message: Option<String>,
+    /// None when the config is invalid, the connection failed or sampling timed out
+    pub estimate: Option<SourcePreviewEstimate>,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct WebDAVTestConnection {
     pub server_url: String,
@@ -231,6 +644,9 @@ pub struct WebDAVConnectionResult {
     pub message: String,
     pub server_version: Option<String>,
     pub server_type: Option<String>,
+    /// The server's clock minus readur's, when the server reported its time
+    #[serde(default)]
+    pub clock_skew_seconds: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -522,6 +938,577 @@ pub struct WebDAVFailureDiagnostics {
     pub user_action_required: bool,
 }
 
+/// The version of a source file a sync last stored, which makes re-downloading
+/// it conditional
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFileVersion {
+    /// Normalized ETag (no quotes or weak prefix), empty if the server sent none
+    pub etag: String,
+    pub size: i64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl SourceFileVersion {
+    pub fn from_file_info(file_info: &super::FileIngestionInfo) -> Self {
+        Self {
+            etag: file_info.etag.clone(),
+            size: file_info.size,
+            last_modified: file_info.last_modified,
+        }
+    }
+
+    /// `If-None-Match` header value for this version, if it has an ETag
+    pub fn if_none_match(&self) -> Option<String> {
+        (!self.etag.is_empty()).then(|| format!("\"{}\"", self.etag))
+    }
+
+    /// Whether a full response carries this version, for servers that ignore
+    /// `If-None-Match`. A response ETag decides on its own; without one, the size
+    /// and modification time (to the second, as HTTP dates have it) must match.
+    pub fn matches_response(
+        &self,
+        etag: Option<&str>,
+        content_length: Option<u64>,
+        last_modified: Option<DateTime<Utc>>,
+    ) -> bool {
+        let etag = etag.map(crate::webdav_xml_parser::normalize_etag).unwrap_or_default();
+        if !etag.is_empty() && !self.etag.is_empty() {
+            return etag == self.etag;
+        }
+
+        match (content_length, last_modified, self.last_modified) {
+            (Some(length), Some(modified), Some(known)) => {
+                length as i64 == self.size && modified.timestamp() == known.timestamp()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Sync tracking state cleared by a source sync-state reset
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceSyncStateReset {
+    pub source_id: Uuid,
+    /// Number of tracked directory ETags removed under the source's watch folders
+    pub directory_etags_cleared: i64,
+    /// Whether a WebDAV sync cursor or resume marker was cleared
+    pub sync_cursor_cleared: bool,
+    /// The source's last successful sync time before the reset, if any
+    pub previous_last_sync_at: Option<DateTime<Utc>>,
+}
+
+/// What deleting a source would affect, returned before the deletion is confirmed
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceDeletionPreview {
+    pub source_id: Uuid,
+    pub name: String,
+    /// Documents ingested from the source
+    pub document_count: i64,
+    /// Of those, documents with OCR text
+    pub documents_with_ocr: i64,
+    /// Pass as `confirmation_token` when deleting. Only valid while the
+    /// document count stays the same.
+    pub confirmation_token: String,
+    pub token_expires_at: DateTime<Utc>,
+    /// Whether deletion is refused without a confirmation token
+    pub confirmation_required: bool,
+    /// Minutes a deleted source can still be restored; 0 when deletion is immediate
+    pub grace_period_minutes: u64,
+}
+
+/// Outcome of deleting a source
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SourceDeletionSummary {
+    pub source_id: Uuid,
+    /// Documents deleted with the source, or to be deleted when it is purged
+    pub documents_deleted: i64,
+    /// Documents kept without a source
+    pub documents_orphaned: i64,
+    /// Deleted documents whose stored files could not be removed
+    pub files_failed: i64,
+    /// When the source is purged for good; null when it was deleted immediately.
+    /// Until then it can be restored.
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+/// A soft-deleted source whose undo window has passed
+#[derive(Debug, Clone)]
+pub struct PendingSourcePurge {
+    pub source_id: Uuid,
+    pub user_id: Uuid,
+    pub purge_documents: bool,
+}
+
+/// Overall result of a sync run, judged by the share of files that failed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOutcome {
+    /// No file failed
+    Success,
+    /// Some files failed, fewer than the configured failure ratio
+    Partial,
+    /// The share of failed files reached the configured failure ratio
+    Failed,
+}
+
+impl SyncOutcome {
+    /// Judges a run where `failed` of `attempted` files failed. A run with any
+    /// failures is `Failed` once `failed / attempted` reaches `failure_ratio_threshold`.
+    pub fn from_counts(failed: usize, attempted: usize, failure_ratio_threshold: f64) -> Self {
+        if failed == 0 {
+            return SyncOutcome::Success;
+        }
+        let ratio = failed as f64 / attempted.max(failed) as f64;
+        if ratio >= failure_ratio_threshold {
+            SyncOutcome::Failed
+        } else {
+            SyncOutcome::Partial
+        }
+    }
+}
+
+impl std::fmt::Display for SyncOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncOutcome::Success => write!(f, "success"),
+            SyncOutcome::Partial => write!(f, "partial"),
+            SyncOutcome::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl TryFrom<String> for SyncOutcome {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "success" => Ok(SyncOutcome::Success),
+            "partial" => Ok(SyncOutcome::Partial),
+            "failed" => Ok(SyncOutcome::Failed),
+            _ => Err(format!("Invalid sync outcome: {}", value)),
+        }
+    }
+}
+
+/// Why a failed sync run failed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncFailureKind {
+    /// Nothing was stored and every failure was a network error or timeout, so
+    /// the source may be reachable again shortly; scheduled syncs are retried
+    Transient,
+    /// Any other failure, such as rejected credentials, which waits for the
+    /// next scheduled sync
+    Permanent,
+}
+
+impl SyncFailureKind {
+    /// Classifies a failed run that stored `stored` files
+    pub fn of_failed_run(failures: &[SyncFileFailure], stored: usize) -> Self {
+        let unreachable = !failures.is_empty()
+            && failures.iter().all(|failure| {
+                matches!(failure.error_category, super::SourceErrorType::NetworkError | super::SourceErrorType::Timeout)
+            });
+        if stored == 0 && unreachable {
+            SyncFailureKind::Transient
+        } else {
+            SyncFailureKind::Permanent
+        }
+    }
+}
+
+impl std::fmt::Display for SyncFailureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncFailureKind::Transient => write!(f, "transient"),
+            SyncFailureKind::Permanent => write!(f, "permanent"),
+        }
+    }
+}
+
+impl TryFrom<String> for SyncFailureKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "transient" => Ok(SyncFailureKind::Transient),
+            "permanent" => Ok(SyncFailureKind::Permanent),
+            _ => Err(format!("Invalid sync failure kind: {}", value)),
+        }
+    }
+}
+
+/// Sync stage a file or folder failed in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncFailureStage {
+    /// Listing a watch folder failed; `path` is the folder
+    Discovery,
+    Download,
+    Store,
+}
+
+/// One file (or, for discovery, one folder) that failed during a sync
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct SyncFileFailure {
+    pub path: String,
+    pub stage: SyncFailureStage,
+    pub error_category: super::SourceErrorType,
+    pub error_message: String,
+    pub failed_at: DateTime<Utc>,
+    // What a retry needs to fetch the file again without listing its folder
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl SyncFileFailure {
+    /// Whether retrying the failed files of a sync picks this one up. Folders that
+    /// failed discovery are listed again by the next regular sync instead.
+    pub fn is_retryable(&self) -> bool {
+        self.stage != SyncFailureStage::Discovery
+    }
+
+    /// Rebuilds the file as discovery reported it, to download it again
+    pub fn to_file_info(&self) -> super::FileIngestionInfo {
+        super::FileIngestionInfo {
+            relative_path: self.path.clone(),
+            full_path: self.path.clone(),
+            #[allow(deprecated)]
+            path: self.path.clone(),
+            name: self.name.clone(),
+            size: self.size,
+            mime_type: self.mime_type.clone(),
+            last_modified: self.last_modified,
+            etag: String::new(),
+            is_directory: false,
+            created_at: None,
+            permissions: None,
+            owner: None,
+            group: None,
+            metadata: None,
+        }
+    }
+}
+
+/// A finished sync of a source with the files that failed in it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceSyncRun {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub outcome: SyncOutcome,
+    /// Set when the outcome is `Failed`
+    pub failure_kind: Option<SyncFailureKind>,
+    /// Whether the run only retried the failed files of an earlier run
+    pub retry_of_failed: bool,
+    pub files_discovered: i64,
+    pub files_processed: i64,
+    pub files_failed: i64,
+    /// Files stored as new documents
+    pub files_added: i64,
+    /// Files stored again because they changed since the previous sync
+    pub files_updated: i64,
+    /// Files left alone as unchanged, duplicates or quarantined
+    pub files_skipped: i64,
+    /// Bytes downloaded from the source
+    pub bytes_transferred: i64,
+    pub failures: Vec<SyncFileFailure>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// A finished sync in a source's history, without its failed files
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SourceSyncRunSummary {
+    pub id: Uuid,
+    pub outcome: String,
+    /// `transient` or `permanent` for failed runs, null otherwise
+    pub failure_kind: Option<String>,
+    pub retry_of_failed: bool,
+    pub files_discovered: i64,
+    pub files_added: i64,
+    pub files_updated: i64,
+    pub files_skipped: i64,
+    pub files_failed: i64,
+    pub bytes_transferred: i64,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub duration_ms: i64,
+}
+
+/// Totals over the sync runs matching a history query
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceSyncHistoryStats {
+    pub total_runs: i64,
+    pub successful_runs: i64,
+    pub partial_runs: i64,
+    pub failed_runs: i64,
+    /// Share of runs (0-1) that succeeded; null without runs
+    pub success_rate: Option<f64>,
+    /// Null without runs
+    pub average_duration_ms: Option<i64>,
+    pub files_added: i64,
+    pub files_updated: i64,
+    pub bytes_transferred: i64,
+}
+
+/// Filters for a source's sync history
+#[derive(Debug, Clone, Default)]
+pub struct SourceSyncHistoryFilter {
+    pub outcome: Option<SyncOutcome>,
+    /// Runs started at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Runs started before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Result of starting a retry of a source's failed files
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RetryFailedFilesResponse {
+    /// The sync run whose failed files are retried
+    pub sync_run_id: Uuid,
+    pub files_to_retry: usize,
+}
+
+/// Existing documents to assign to a source without ingesting them again
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct AdoptDocumentsRequest {
+    /// Documents to adopt, by ID
+    #[serde(default)]
+    pub document_ids: Vec<Uuid>,
+    /// Also adopt every document of the user without a source that matches this filter
+    #[serde(default)]
+    pub filter: Option<AdoptDocumentsFilter>,
+    /// Path of a document's file in the source, by document ID, recorded as
+    /// the document's source path
+    #[serde(default)]
+    #[schema(value_type = std::collections::HashMap<String, String>)]
+    pub source_paths: std::collections::HashMap<Uuid, String>,
+}
+
+/// Which documents without a source to adopt. Unset criteria match every document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct AdoptDocumentsFilter {
+    pub mime_types: Option<Vec<String>>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+/// Why a document asked for by ID was not adopted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdoptionRejection {
+    /// The document doesn't exist or belongs to another user
+    NotFound,
+    /// The document already belongs to another source
+    OwnedByOtherSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RejectedAdoption {
+    pub document_id: Uuid,
+    pub reason: AdoptionRejection,
+}
+
+/// Result of adopting documents into a source
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct AdoptDocumentsResponse {
+    /// Documents that now belong to the source, including ones that already did
+    pub adopted: Vec<Uuid>,
+    pub rejected: Vec<RejectedAdoption>,
+}
+
+/// OCR resolution set with `ocr_dpi` in a source config, used for the source's
+/// documents instead of the owner's OCR DPI setting. None when not set.
+pub fn source_ocr_dpi(config: &serde_json::Value) -> Result<Option<i32>, String> {
+    let dpi = match config.get("ocr_dpi") {
+        None | Some(serde_json::Value::Null) => return Ok(None),
+        Some(value) => value
+            .as_i64()
+            .and_then(|dpi| i32::try_from(dpi).ok())
+            .ok_or_else(|| format!("ocr_dpi must be a whole number, got {}", value))?,
+    };
+    if !crate::models::OCR_DPI_RANGE.contains(&dpi) {
+        return Err(format!("ocr_dpi must be between 72 and 1200, got {}", dpi));
+    }
+    Ok(Some(dpi))
+}
+
+/// Circuit breaker thresholds of a source, read from the `circuit_breaker`
+/// object of its config
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(default)]
+pub struct SourceCircuitBreakerConfig {
+    /// Consecutive failed syncs that open the circuit
+    pub failure_threshold: u32,
+    /// How long scheduled syncs are skipped after the circuit opens
+    pub cooldown_minutes: u32,
+    /// Upper bound for the cooldown, which doubles each time a recovery test fails
+    pub max_cooldown_minutes: u32,
+}
+
+impl Default for SourceCircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown_minutes: 30,
+            max_cooldown_minutes: 24 * 60,
+        }
+    }
+}
+
+impl SourceCircuitBreakerConfig {
+    /// Reads the thresholds from a source config, using the defaults for
+    /// anything not set
+    pub fn from_source_config(config: &serde_json::Value) -> Result<Self, String> {
+        let breaker = match config.get("circuit_breaker") {
+            Some(value) if !value.is_null() => serde_json::from_value::<Self>(value.clone())
+                .map_err(|e| format!("Invalid circuit_breaker configuration: {}", e))?,
+            _ => Self::default(),
+        };
+        breaker.validate()?;
+        Ok(breaker)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.failure_threshold == 0 {
+            return Err("circuit_breaker.failure_threshold must be at least 1".to_string());
+        }
+        if self.cooldown_minutes == 0 {
+            return Err("circuit_breaker.cooldown_minutes must be at least 1".to_string());
+        }
+        if self.max_cooldown_minutes < self.cooldown_minutes {
+            return Err("circuit_breaker.max_cooldown_minutes must not be less than cooldown_minutes".to_string());
+        }
+        Ok(())
+    }
+
+    /// Cooldown after the circuit opened for the `trips`-th time in a row
+    pub fn cooldown_for_trip(&self, trips: u32) -> chrono::Duration {
+        let factor = 2u64.saturating_pow(trips.saturating_sub(1).min(31));
+        let minutes = (self.cooldown_minutes as u64)
+            .saturating_mul(factor)
+            .min(self.max_cooldown_minutes as u64);
+        chrono::Duration::minutes(minutes as i64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceCircuitState {
+    /// Scheduled syncs run normally
+    Closed,
+    /// Scheduled syncs are skipped until the cooldown ends
+    Open,
+    /// The cooldown ended; the next scheduled sync tests whether the source recovered
+    HalfOpen,
+}
+
+impl std::fmt::Display for SourceCircuitState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceCircuitState::Closed => write!(f, "closed"),
+            SourceCircuitState::Open => write!(f, "open"),
+            SourceCircuitState::HalfOpen => write!(f, "half_open"),
+        }
+    }
+}
+
+impl TryFrom<String> for SourceCircuitState {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "closed" => Ok(SourceCircuitState::Closed),
+            "open" => Ok(SourceCircuitState::Open),
+            "half_open" => Ok(SourceCircuitState::HalfOpen),
+            _ => Err(format!("Invalid circuit state: {}", value)),
+        }
+    }
+}
+
+/// Circuit breaker of a source's scheduled syncs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct SourceCircuit {
+    pub source_id: Uuid,
+    pub state: SourceCircuitState,
+    /// Failed syncs since the last successful one
+    pub consecutive_failures: i32,
+    /// Times the circuit opened since the last successful sync; sets the cooldown
+    pub trips: i32,
+    pub opened_at: Option<DateTime<Utc>>,
+    /// When an open circuit lets the next scheduled sync through
+    pub cooldown_until: Option<DateTime<Utc>>,
+}
+
+impl SourceCircuit {
+    pub fn closed(source_id: Uuid) -> Self {
+        Self {
+            source_id,
+            state: SourceCircuitState::Closed,
+            consecutive_failures: 0,
+            trips: 0,
+            opened_at: None,
+            cooldown_until: None,
+        }
+    }
+
+    /// Whether a scheduled sync may run at `now`. An open circuit whose cooldown
+    /// ended moves to half-open and lets one sync through.
+    pub fn allow_scheduled_sync(&mut self, now: DateTime<Utc>) -> bool {
+        match self.state {
+            SourceCircuitState::Closed | SourceCircuitState::HalfOpen => true,
+            SourceCircuitState::Open => {
+                if self.cooldown_until.map(|until| now >= until).unwrap_or(true) {
+                    self.state = SourceCircuitState::HalfOpen;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        *self = Self::closed(self.source_id);
+    }
+
+    /// Counts a failed sync. Returns true when this failure opened the circuit.
+    pub fn record_failure(&mut self, config: &SourceCircuitBreakerConfig, now: DateTime<Utc>) -> bool {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let should_open = match self.state {
+            SourceCircuitState::Closed => self.consecutive_failures as u32 >= config.failure_threshold,
+            SourceCircuitState::HalfOpen => true,
+            // Manual syncs still run while open; their failures don't extend the cooldown
+            SourceCircuitState::Open => false,
+        };
+        if should_open {
+            self.trips = self.trips.saturating_add(1);
+            self.state = SourceCircuitState::Open;
+            self.opened_at = Some(now);
+            self.cooldown_until = Some(now + config.cooldown_for_trip(self.trips as u32));
+        }
+        should_open
+    }
+}
+
+/// Skew between a WebDAV source's clock and readur's, measured when the source
+/// last synced
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct SourceClockSkew {
+    pub source_id: Uuid,
+    /// The server's clock minus readur's; positive when the server is ahead
+    pub skew_seconds: i64,
+    /// Whether the skew exceeded `WEBDAV_CLOCK_SKEW_WARNING_SECONDS`
+    pub exceeds_threshold: bool,
+    pub measured_at: DateTime<Utc>,
+}
+
 // Notification-related structs
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Notification {
@@ -545,6 +1532,19 @@ pub struct CreateNotification {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// A notification held back for the user's next digest
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingNotification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub notification_type: String,
+    pub title: String,
+    pub message: String,
+    pub action_url: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct NotificationSummary {
     pub unread_count: i64,
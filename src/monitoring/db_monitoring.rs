@@ -170,8 +170,8 @@ impl DatabaseMonitor {
             r#"
             SELECT 
                 COUNT(*) FILTER (WHERE ocr_status = 'pending') as pending,
-                COUNT(*) FILTER (WHERE ocr_status = 'processing') as processing,
-                COUNT(*) FILTER (WHERE ocr_status = 'processing' AND updated_at < NOW() - INTERVAL '30 minutes') as stuck,
+                COUNT(*) FILTER (WHERE ocr_status IN ('processing', 'preview')) as processing,
+                COUNT(*) FILTER (WHERE ocr_status IN ('processing', 'preview') AND updated_at < NOW() - INTERVAL '30 minutes') as stuck,
                 COUNT(*) FILTER (WHERE ocr_status = 'failed' AND updated_at > NOW() - INTERVAL '1 hour') as failed_recent,
                 AVG(ocr_confidence) FILTER (WHERE ocr_status = 'completed' AND ocr_completed_at > NOW() - INTERVAL '1 hour') as avg_confidence,
                 AVG(ocr_processing_time_ms) FILTER (WHERE ocr_status = 'completed' AND ocr_completed_at > NOW() - INTERVAL '1 hour') as avg_time,
@@ -1,5 +1,6 @@
 use crate::ocr::image_ocr::ImageOcrService;
 use crate::ocr::error::OcrError;
+use crate::ocr::fallback_stats::{fallback_stats, FallbackStatsResponse};
 use crate::AppState;
 use axum::{
     extract::State,
@@ -16,6 +17,8 @@ pub struct OcrHealthResponse {
     pub available_languages: Vec<String>,
     pub diagnostics: Option<String>,
     pub errors: Vec<String>,
+    /// How the Office text extraction methods have fared
+    pub fallback_stats: FallbackStatsResponse,
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
@@ -59,6 +62,7 @@ pub async fn health_check(
                 available_languages: diag.available_languages,
                 diagnostics: Some(diagnostics),
                 errors: vec![],
+                fallback_stats: fallback_stats().snapshot(),
             }))
         }
         Err(errors) => {
@@ -78,6 +82,7 @@ pub async fn health_check(
                 available_languages: vec![],
                 diagnostics: Some(diagnostics),
                 errors: error_messages,
+                fallback_stats: fallback_stats().snapshot(),
             }))
         }
     }
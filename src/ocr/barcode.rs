@@ -0,0 +1,87 @@
+//! Barcodes and QR codes decoded into searchable text.
+//!
+//! Scanned documents often carry order numbers, shipment ids or links as
+//! barcodes. Decoding them with `zbarimg` (zbar-tools) makes the document
+//! findable by those values. Each value is appended to the OCR text on a line
+//! tagged with its symbology, e.g. `[QR-Code] ORDER-2026-4711`, so it can be
+//! told apart from the recognized text.
+
+use serde::{Deserialize, Serialize};
+
+/// Resolution PDF pages are rendered at for decoding
+pub const BARCODE_DECODE_DPI: u32 = super::document_split::BARCODE_DETECTION_DPI;
+
+/// A value decoded from a barcode or QR code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedBarcode {
+    /// Symbology as reported by zbar, e.g. `QR-Code` or `EAN-13`
+    pub symbology: String,
+    pub value: String,
+}
+
+/// Parses `zbarimg --quiet` output, one `SYMBOLOGY:value` per symbol. Lines
+/// without a symbology prefix continue the previous value, which happens for
+/// QR codes encoding several lines. Repeated symbols are only kept once.
+pub fn parse_zbar_output(output: &str) -> Vec<DecodedBarcode> {
+    let mut barcodes: Vec<DecodedBarcode> = Vec::new();
+    for line in output.lines() {
+        match split_symbology(line) {
+            Some((symbology, value)) => barcodes.push(DecodedBarcode {
+                symbology: symbology.to_string(),
+                value: value.to_string(),
+            }),
+            None => {
+                if let Some(last) = barcodes.last_mut() {
+                    last.value.push('\n');
+                    last.value.push_str(line);
+                }
+            }
+        }
+    }
+
+    let mut unique: Vec<DecodedBarcode> = Vec::new();
+    for mut barcode in barcodes {
+        barcode.value = barcode.value.trim().to_string();
+        if !barcode.value.is_empty() && !unique.contains(&barcode) {
+            unique.push(barcode);
+        }
+    }
+    unique
+}
+
+/// zbar symbology names (`EAN-13`, `I2/5`, `DataBar`) contain an uppercase
+/// letter, which keeps continuation lines such as `https://...` from being
+/// read as a new symbol
+fn split_symbology(line: &str) -> Option<(&str, &str)> {
+    let (symbology, value) = line.split_once(':')?;
+    let is_symbology = !symbology.is_empty()
+        && symbology.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '/')
+        && symbology.chars().any(|c| c.is_ascii_uppercase());
+    is_symbology.then_some((symbology, value))
+}
+
+/// Searchable text for decoded barcodes, one tagged line per value. Values
+/// spanning several lines are joined with spaces.
+pub fn barcode_search_text(barcodes: &[DecodedBarcode]) -> String {
+    barcodes
+        .iter()
+        .map(|barcode| {
+            let value = barcode.value.split_whitespace().collect::<Vec<_>>().join(" ");
+            format!("[{}] {}", barcode.symbology, value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// OCR text with the decoded barcodes appended after a blank line
+pub fn append_barcode_text(text: &str, barcodes: &[DecodedBarcode]) -> String {
+    if barcodes.is_empty() {
+        return text.to_string();
+    }
+    let barcode_text = barcode_search_text(barcodes);
+    if text.trim().is_empty() {
+        barcode_text
+    } else {
+        format!("{}\n\n{}", text.trim_end(), barcode_text)
+    }
+}
@@ -0,0 +1,158 @@
+//! Maps raw Tesseract confidence through per-language correction curves, so a
+//! stored confidence means about the same for every language and can be
+//! compared with one threshold. Without curves confidence is left as reported.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::config::Config;
+
+/// Curve used for languages without a curve of their own
+pub const DEFAULT_CURVE: &str = "default";
+
+/// Piecewise-linear mapping from raw to calibrated confidence (both 0-100)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationCurve {
+    /// (raw, calibrated) points in increasing raw order
+    points: Vec<(f32, f32)>,
+}
+
+impl CalibrationCurve {
+    /// A curve through `points` of (raw, calibrated) confidence. Raw values below
+    /// the first point or above the last map to that point's calibrated value.
+    pub fn from_points(mut points: Vec<(f32, f32)>) -> Result<Self> {
+        if points.len() < 2 {
+            bail!("a calibration curve needs at least two points");
+        }
+        if let Some((raw, calibrated)) = points
+            .iter()
+            .find(|(raw, calibrated)| !(0.0..=100.0).contains(raw) || !(0.0..=100.0).contains(calibrated))
+        {
+            bail!("calibration point ({}, {}) is outside 0-100", raw, calibrated);
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if points.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            bail!("calibration points must have distinct raw confidences");
+        }
+        Ok(Self { points })
+    }
+
+    /// Learns a curve from labeled samples of (raw confidence, measured accuracy
+    /// in percent), such as the share of correctly recognized words on pages
+    /// checked by hand. The accuracy is made non-decreasing in raw confidence by
+    /// pooling adjacent samples that violate it (isotonic regression).
+    pub fn fit(samples: &[(f32, f32)]) -> Result<Self> {
+        let mut samples = samples.to_vec();
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Blocks of (raw sum, accuracy sum, sample count)
+        let mut blocks: Vec<(f32, f32, f32)> = Vec::new();
+        for (raw, accuracy) in samples {
+            blocks.push((raw, accuracy, 1.0));
+            while blocks.len() > 1 {
+                let (raw_sum, accuracy_sum, count) = blocks[blocks.len() - 1];
+                let previous = blocks[blocks.len() - 2];
+                if previous.1 / previous.2 <= accuracy_sum / count {
+                    break;
+                }
+                blocks.pop();
+                let merged = blocks.last_mut().unwrap();
+                *merged = (merged.0 + raw_sum, merged.1 + accuracy_sum, merged.2 + count);
+            }
+        }
+
+        let mut points: Vec<(f32, f32)> = Vec::with_capacity(blocks.len());
+        for (raw_sum, accuracy_sum, count) in blocks {
+            let point = (raw_sum / count, accuracy_sum / count);
+            match points.last_mut() {
+                // Samples sharing a raw confidence collapse into one point
+                Some(last) if last.0 == point.0 => last.1 = last.1.max(point.1),
+                _ => points.push(point),
+            }
+        }
+        Self::from_points(points).context("calibration samples must cover at least two raw confidences")
+    }
+
+    pub fn apply(&self, raw: f32) -> f32 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if raw <= first.0 {
+            return first.1;
+        }
+        if raw >= last.0 {
+            return last.1;
+        }
+        let upper = self.points.iter().position(|point| point.0 >= raw).unwrap();
+        let (x0, y0) = self.points[upper - 1];
+        let (x1, y1) = self.points[upper];
+        y0 + (raw - x0) / (x1 - x0) * (y1 - y0)
+    }
+}
+
+/// A curve in the calibration file, given directly or as labeled samples
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CurveSpec {
+    Points(Vec<(f32, f32)>),
+    Samples(Vec<(f32, f32)>),
+}
+
+/// Correction curves by Tesseract language code, plus an optional `default`
+#[derive(Debug, Clone, Default)]
+pub struct ConfidenceCalibration {
+    curves: HashMap<String, CalibrationCurve>,
+}
+
+impl ConfidenceCalibration {
+    /// Parses a calibration file: a JSON object mapping language codes (or
+    /// `default`) to `{"points": [[raw, calibrated], ...]}` or
+    /// `{"samples": [[raw, accuracy], ...]}`
+    pub fn from_json(json: &str) -> Result<Self> {
+        let specs: HashMap<String, CurveSpec> =
+            serde_json::from_str(json).map_err(|e| anyhow!("Invalid confidence calibration: {}", e))?;
+
+        let mut curves = HashMap::with_capacity(specs.len());
+        for (language, spec) in specs {
+            let curve = match spec {
+                CurveSpec::Points(points) => CalibrationCurve::from_points(points),
+                CurveSpec::Samples(samples) => CalibrationCurve::fit(&samples),
+            }
+            .with_context(|| format!("Invalid confidence calibration for '{}'", language))?;
+            curves.insert(language, curve);
+        }
+        Ok(Self { curves })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read confidence calibration file {}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// The calibration in `OCR_CONFIDENCE_CALIBRATION_FILE`, or none
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match &config.ocr_confidence_calibration_file {
+            Some(path) => Self::from_file(Path::new(path)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Calibrated confidence of a result recognized in `language` (a Tesseract
+    /// code, or a `+` combination whose first language is used)
+    pub fn calibrate(&self, language: &str, raw: f32) -> f32 {
+        let primary = language.split('+').next().unwrap_or_default().trim();
+        match self.curves.get(primary).or_else(|| self.curves.get(DEFAULT_CURVE)) {
+            Some(curve) => curve.apply(raw),
+            None => raw,
+        }
+    }
+
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.curves.keys().map(String::as_str).collect();
+        languages.sort_unstable();
+        languages
+    }
+}
@@ -0,0 +1,395 @@
+//! Extraction of the date a document was written (invoice date, letter date)
+//! from its text, and the choice between that date and the file's own dates.
+//!
+//! Recognizes ISO dates, numeric dates with a configurable day/month order and
+//! dates with month names in the languages supported by language detection.
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Dates outside these years are treated as noise (reference numbers, amounts)
+const MIN_YEAR: i32 = 1900;
+const MAX_YEAR: i32 = 2100;
+
+static ISO_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(\d{4})([-/.])(\d{1,2})([-/.])(\d{1,2})\b").unwrap()
+});
+static NUMERIC_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(\d{1,2})([./-])(\d{1,2})([./-])(\d{4}|\d{2})\b").unwrap()
+});
+// "12 March 2024", "12. März 2024", "3rd of May, 2024", "5 de marzo de 2024"
+static DAY_MONTH_NAME_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})(?:st|nd|rd|th|er|º|\.)?\s+(?:of\s+|de\s+)?(\p{L}+)\.?,?\s+(?:de\s+)?(\d{4})\b").unwrap()
+});
+// "March 12, 2024", "Mar. 3rd 2024"
+static MONTH_NAME_DAY_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\p{L}+)\.?\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(\d{4})\b").unwrap()
+});
+
+const MONTH_NAMES: &[(&str, u32)] = &[
+    // English
+    ("january", 1), ("february", 2), ("march", 3), ("april", 4), ("may", 5), ("june", 6),
+    ("july", 7), ("august", 8), ("september", 9), ("october", 10), ("november", 11), ("december", 12),
+    // German
+    ("januar", 1), ("jänner", 1), ("februar", 2), ("märz", 3), ("mai", 5), ("juni", 6), ("juli", 7),
+    ("oktober", 10), ("dezember", 12),
+    // French
+    ("janvier", 1), ("février", 2), ("mars", 3), ("avril", 4), ("juin", 6), ("juillet", 7),
+    ("août", 8), ("septembre", 9), ("octobre", 10), ("novembre", 11), ("décembre", 12),
+    // Spanish
+    ("enero", 1), ("febrero", 2), ("marzo", 3), ("abril", 4), ("mayo", 5), ("junio", 6),
+    ("julio", 7), ("agosto", 8), ("septiembre", 9), ("setiembre", 9), ("octubre", 10),
+    ("noviembre", 11), ("diciembre", 12),
+    // Italian
+    ("gennaio", 1), ("febbraio", 2), ("aprile", 4), ("maggio", 5), ("giugno", 6), ("luglio", 7),
+    ("settembre", 9), ("ottobre", 10), ("dicembre", 12),
+    // Portuguese
+    ("janeiro", 1), ("fevereiro", 2), ("março", 3), ("maio", 5), ("junho", 6), ("julho", 7),
+    ("setembro", 9), ("outubro", 10), ("dezembro", 12),
+    // Dutch
+    ("januari", 1), ("februari", 2), ("maart", 3), ("mei", 5), ("augustus", 8),
+];
+
+/// Shortest abbreviation accepted for a month name ("Jan", "Sept", "févr")
+const MIN_ABBREVIATION_LEN: usize = 3;
+
+/// Order of day and month in numeric dates such as 03/04/2024
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericDateOrder {
+    /// 03/04/2024 is 3 April (most of Europe)
+    DayFirst,
+    /// 03/04/2024 is 4 March (United States)
+    MonthFirst,
+}
+
+impl TryFrom<&str> for NumericDateOrder {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "dmy" => Ok(Self::DayFirst),
+            "mdy" => Ok(Self::MonthFirst),
+            other => Err(format!("Unknown date order '{}'", other)),
+        }
+    }
+}
+
+/// How to pick the document date when the text mentions several dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSelectionRule {
+    /// The date mentioned most often; ties go to the one mentioned first
+    MostFrequent,
+    /// The first date in the text, usually the one in the letterhead
+    First,
+    Earliest,
+    Latest,
+}
+
+impl TryFrom<&str> for DateSelectionRule {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "most_frequent" => Ok(Self::MostFrequent),
+            "first" => Ok(Self::First),
+            "earliest" => Ok(Self::Earliest),
+            "latest" => Ok(Self::Latest),
+            other => Err(format!("Unknown date selection rule '{}'", other)),
+        }
+    }
+}
+
+/// Where a document date came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentDateSource {
+    /// A date found in the document's text
+    Content,
+    /// The file's modification (or creation) time on its source
+    File,
+    /// When the document was added to readur
+    Ingestion,
+}
+
+impl DocumentDateSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Content => "content",
+            Self::File => "file",
+            Self::Ingestion => "ingestion",
+        }
+    }
+}
+
+impl TryFrom<&str> for DocumentDateSource {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "content" => Ok(Self::Content),
+            "file" => Ok(Self::File),
+            "ingestion" => Ok(Self::Ingestion),
+            other => Err(format!("Unknown document date source '{}'", other)),
+        }
+    }
+}
+
+/// Parses a comma separated preference order such as "content,file,ingestion".
+/// Unknown and repeated entries are reported as an error.
+pub fn parse_date_source_priority(value: &str) -> Result<Vec<DocumentDateSource>, String> {
+    let mut sources = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let source = DocumentDateSource::try_from(name)?;
+        if sources.contains(&source) {
+            return Err(format!("Document date source '{}' is listed twice", name));
+        }
+        sources.push(source);
+    }
+    Ok(sources)
+}
+
+/// A date found in a text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateCandidate {
+    pub date: NaiveDate,
+    /// Byte offset of the date in the text
+    pub position: usize,
+}
+
+fn month_from_name(word: &str) -> Option<u32> {
+    let word = word.to_lowercase();
+    if let Some((_, month)) = MONTH_NAMES.iter().find(|(name, _)| *name == word) {
+        return Some(*month);
+    }
+    if word.chars().count() < MIN_ABBREVIATION_LEN {
+        return None;
+    }
+
+    // An abbreviation must not be shared by two months ("jui" is juin or juillet)
+    let mut months = MONTH_NAMES
+        .iter()
+        .filter(|(name, _)| name.starts_with(&word))
+        .map(|(_, month)| *month);
+    let month = months.next()?;
+    months.all(|other| other == month).then_some(month)
+}
+
+fn expand_year(year: &str) -> Option<i32> {
+    let value: i32 = year.parse().ok()?;
+    match year.len() {
+        2 if value < 70 => Some(2000 + value),
+        2 => Some(1900 + value),
+        _ => Some(value),
+    }
+}
+
+fn plausible_date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// Rejects numbers that are part of a longer dotted or dashed sequence, such as
+/// version numbers or account numbers like 12.03.2024.7
+fn stands_alone(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().rev().take(2).collect::<Vec<_>>();
+    let after = text[end..].chars().take(2).collect::<Vec<_>>();
+    let joined = |chars: &[char]| match chars {
+        [first, ..] if first.is_ascii_digit() => true,
+        [first, second, ..] if matches!(first, '.' | '/' | '-') => second.is_ascii_digit(),
+        _ => false,
+    };
+    !joined(&before) && !joined(&after)
+}
+
+fn numeric_date(first: u32, second: u32, year: i32, order: NumericDateOrder) -> Option<NaiveDate> {
+    // A component over 12 can only be the day, whatever the configured order
+    let (day, month) = if first > 12 {
+        (first, second)
+    } else if second > 12 {
+        (second, first)
+    } else {
+        match order {
+            NumericDateOrder::DayFirst => (first, second),
+            NumericDateOrder::MonthFirst => (second, first),
+        }
+    };
+    plausible_date(year, month, day)
+}
+
+/// Finds all dates in `text`, in the order they appear
+pub fn find_dates(text: &str, order: NumericDateOrder) -> Vec<DateCandidate> {
+    let mut found: Vec<(usize, usize, NaiveDate)> = Vec::new();
+    let mut add = |start: usize, end: usize, date: Option<NaiveDate>| {
+        if let Some(date) = date {
+            // Earlier patterns are more specific, so they win overlapping matches
+            if !found.iter().any(|(s, e, _)| start < *e && *s < end) {
+                found.push((start, end, date));
+            }
+        }
+    };
+
+    for caps in ISO_DATE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        if caps[2] != caps[4] || !stands_alone(text, whole.start(), whole.end()) {
+            continue;
+        }
+        let date = match (caps[1].parse(), caps[3].parse(), caps[5].parse()) {
+            (Ok(year), Ok(month), Ok(day)) => plausible_date(year, month, day),
+            _ => None,
+        };
+        add(whole.start(), whole.end(), date);
+    }
+
+    for caps in NUMERIC_DATE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        if caps[2] != caps[4] || !stands_alone(text, whole.start(), whole.end()) {
+            continue;
+        }
+        let date = match (caps[1].parse(), caps[3].parse(), expand_year(&caps[5])) {
+            (Ok(first), Ok(second), Some(year)) => numeric_date(first, second, year, order),
+            _ => None,
+        };
+        add(whole.start(), whole.end(), date);
+    }
+
+    for caps in DAY_MONTH_NAME_DATE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let date = match (caps[1].parse(), month_from_name(&caps[2]), caps[3].parse()) {
+            (Ok(day), Some(month), Ok(year)) => plausible_date(year, month, day),
+            _ => None,
+        };
+        add(whole.start(), whole.end(), date);
+    }
+
+    for caps in MONTH_NAME_DAY_DATE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let date = match (month_from_name(&caps[1]), caps[2].parse(), caps[3].parse()) {
+            (Some(month), Ok(day), Ok(year)) => plausible_date(year, month, day),
+            _ => None,
+        };
+        add(whole.start(), whole.end(), date);
+    }
+
+    found.sort_by_key(|(start, _, _)| *start);
+    found
+        .into_iter()
+        .map(|(position, _, date)| DateCandidate { date, position })
+        .collect()
+}
+
+/// Picks one date from the candidates according to `rule`
+pub fn select_date(candidates: &[DateCandidate], rule: DateSelectionRule) -> Option<NaiveDate> {
+    match rule {
+        DateSelectionRule::First => candidates.iter().min_by_key(|c| c.position).map(|c| c.date),
+        DateSelectionRule::Earliest => candidates.iter().map(|c| c.date).min(),
+        DateSelectionRule::Latest => candidates.iter().map(|c| c.date).max(),
+        DateSelectionRule::MostFrequent => {
+            // date -> (mentions, first position)
+            let mut counts: HashMap<NaiveDate, (usize, usize)> = HashMap::new();
+            for candidate in candidates {
+                let entry = counts.entry(candidate.date).or_insert((0, candidate.position));
+                entry.0 += 1;
+                entry.1 = entry.1.min(candidate.position);
+            }
+            counts
+                .into_iter()
+                .max_by(|(_, (count_a, pos_a)), (_, (count_b, pos_b))| {
+                    count_a.cmp(count_b).then(pos_b.cmp(pos_a))
+                })
+                .map(|(date, _)| date)
+        }
+    }
+}
+
+/// Extracts the document date from `text`, or `None` when it mentions no date
+pub fn extract_document_date(text: &str, order: NumericDateOrder, rule: DateSelectionRule) -> Option<NaiveDate> {
+    select_date(&find_dates(text, order), rule)
+}
+
+/// Takes the first available date in `priority` order
+pub fn resolve_document_date(
+    priority: &[DocumentDateSource],
+    content_date: Option<NaiveDate>,
+    file_date: Option<NaiveDate>,
+    ingestion_date: Option<NaiveDate>,
+) -> Option<(NaiveDate, DocumentDateSource)> {
+    priority.iter().find_map(|source| {
+        let date = match source {
+            DocumentDateSource::Content => content_date,
+            DocumentDateSource::File => file_date,
+            DocumentDateSource::Ingestion => ingestion_date,
+        };
+        date.map(|date| (date, *source))
+    })
+}
+
+/// The creation date a PDF's metadata gives, as recorded in a document's
+/// `pdf_creation_date` metadata in the PDF form `D:YYYYMMDDHHmmSS...`
+pub fn pdf_metadata_date(metadata: Option<&serde_json::Value>) -> Option<NaiveDate> {
+    let value = metadata?.get("pdf_creation_date")?.as_str()?;
+    let digits = value.trim().trim_start_matches("D:");
+    if digits.len() < 8 || !digits.as_bytes()[..8].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let date = NaiveDate::parse_from_str(&digits[..8], "%Y%m%d").ok()?;
+    (MIN_YEAR..=MAX_YEAR).contains(&date.year()).then_some(date)
+}
+
+/// A document's file date: the creation date in its PDF metadata, else its
+/// file's modification time, else its file's creation time
+pub fn file_date(
+    metadata: Option<&serde_json::Value>,
+    modified_at: Option<DateTime<Utc>>,
+    created_at: Option<DateTime<Utc>>,
+) -> Option<NaiveDate> {
+    pdf_metadata_date(metadata).or_else(|| modified_at.or(created_at).map(|time| time.date_naive()))
+}
+
+/// A user's document date settings, parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentDateOptions {
+    pub priority: Vec<DocumentDateSource>,
+    pub order: NumericDateOrder,
+    pub rule: DateSelectionRule,
+}
+
+impl DocumentDateOptions {
+    /// Reads the options from a user's settings. Invalid values are logged and
+    /// replaced by their defaults.
+    pub fn from_settings(settings: &crate::models::Settings) -> Self {
+        let priority = parse_date_source_priority(&settings.document_date_priority).unwrap_or_else(|e| {
+            warn!("{} in document date settings, using content,file,ingestion", e);
+            vec![DocumentDateSource::Content, DocumentDateSource::File, DocumentDateSource::Ingestion]
+        });
+        let order = NumericDateOrder::try_from(settings.document_date_order.as_str()).unwrap_or_else(|e| {
+            warn!("{} in document date settings, using dmy", e);
+            NumericDateOrder::DayFirst
+        });
+        let rule = DateSelectionRule::try_from(settings.document_date_rule.as_str()).unwrap_or_else(|e| {
+            warn!("{} in document date settings, using most_frequent", e);
+            DateSelectionRule::MostFrequent
+        });
+        Self { priority, order, rule }
+    }
+
+    /// The document date from its text, when there is any, and its file and
+    /// ingestion dates
+    pub fn resolve(
+        &self,
+        text: Option<&str>,
+        file_date: Option<NaiveDate>,
+        ingestion_date: Option<NaiveDate>,
+    ) -> Option<(NaiveDate, DocumentDateSource)> {
+        let content_date = match text {
+            Some(text) if self.priority.contains(&DocumentDateSource::Content) => {
+                extract_document_date(text, self.order, self.rule)
+            }
+            _ => None,
+        };
+        resolve_document_date(&self.priority, content_date, file_date, ingestion_date)
+    }
+}
@@ -0,0 +1,107 @@
+//! Splitting of multi-document scans.
+//!
+//! Batch scanning puts several logical documents into one PDF, separated by
+//! blank sheets or by sheets carrying a separator barcode. Separator pages are
+//! found on low-resolution renders of each page and dropped; every run of pages
+//! between them becomes a document of its own.
+
+/// Resolution used to look for blank separator pages
+pub const BLANK_DETECTION_DPI: u32 = 40;
+
+/// Resolution used to read separator barcodes
+pub const BARCODE_DETECTION_DPI: u32 = 150;
+
+/// Luma below which a pixel counts as ink
+const INK_LUMA_THRESHOLD: u8 = 160;
+
+/// Share of inked pixels up to which a page counts as blank, leaving room for
+/// scanner noise and dust
+const BLANK_PAGE_MAX_INK_RATIO: f64 = 0.003;
+
+/// Share of each edge ignored when looking for ink, where scanners leave
+/// shadows and punch holes
+const EDGE_MARGIN_RATIO: f64 = 0.05;
+
+/// What separates the documents of a scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeparatorKind {
+    BlankPage,
+    /// A page carrying a barcode or QR code with this value
+    Barcode(String),
+}
+
+impl SeparatorKind {
+    /// Barcode separators when a value is configured, blank pages otherwise
+    pub fn from_settings(barcode: Option<&str>) -> Self {
+        match barcode.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(value) => SeparatorKind::Barcode(value.to_string()),
+            None => SeparatorKind::BlankPage,
+        }
+    }
+}
+
+/// Separator pages found in a PDF
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeparatorScan {
+    pub page_count: usize,
+    /// 1-based page numbers, ascending
+    pub separator_pages: Vec<usize>,
+}
+
+/// Whether a grayscale page render is blank, judged by its share of inked
+/// pixels away from the edges
+pub fn is_blank_page(luma: &[u8], width: usize, height: usize) -> bool {
+    if width == 0 || height == 0 || luma.len() < width * height {
+        return false;
+    }
+
+    let margin_x = (width as f64 * EDGE_MARGIN_RATIO) as usize;
+    let margin_y = (height as f64 * EDGE_MARGIN_RATIO) as usize;
+    let (mut inked, mut total) = (0usize, 0usize);
+    for y in margin_y..height - margin_y {
+        let row = &luma[y * width..(y + 1) * width];
+        for &pixel in &row[margin_x..width - margin_x] {
+            total += 1;
+            if pixel < INK_LUMA_THRESHOLD {
+                inked += 1;
+            }
+        }
+    }
+
+    total > 0 && (inked as f64 / total as f64) <= BLANK_PAGE_MAX_INK_RATIO
+}
+
+/// Whether barcode reader output (one decoded value per line) contains the
+/// separator value
+pub fn has_separator_barcode(decoded: &str, separator: &str) -> bool {
+    decoded.lines().any(|line| line.trim() == separator)
+}
+
+/// Page ranges (1-based, inclusive) of the documents between separator pages.
+/// Separators are not part of any document, and runs of separators or
+/// separators at either end don't produce empty documents.
+pub fn split_segments(page_count: usize, separator_pages: &[usize]) -> Vec<(usize, usize)> {
+    let mut segments = Vec::new();
+    let mut start = None;
+    for page in 1..=page_count {
+        if separator_pages.contains(&page) {
+            if let Some(first) = start.take() {
+                segments.push((first, page - 1));
+            }
+        } else if start.is_none() {
+            start = Some(page);
+        }
+    }
+    if let Some(first) = start {
+        segments.push((first, page_count));
+    }
+    segments
+}
+
+/// File name of one part of a split document, e.g. `scan (part 2 of 3).pdf`
+pub fn split_part_filename(filename: &str, part: usize, parts: usize) -> String {
+    let path = std::path::Path::new(filename);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(filename);
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("pdf");
+    format!("{} (part {} of {}).{}", stem, part, parts, extension)
+}
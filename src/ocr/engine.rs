@@ -0,0 +1,58 @@
+//! OCR engines a document can be routed to
+//!
+//! Tesseract, through [`EnhancedOcrService`](super::enhanced::EnhancedOcrService),
+//! handles every document by default. Documents flagged as handwritten go to a
+//! handwriting engine instead when one is configured, since Tesseract recognizes
+//! handwriting poorly.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::models::Settings;
+use crate::ocr::enhanced::OcrResult;
+
+/// Engine name recorded for text produced by Tesseract
+pub const TESSERACT_ENGINE: &str = "tesseract";
+
+/// An OCR engine other than the built-in Tesseract pipeline
+#[async_trait]
+pub trait OcrEngine: Send + Sync {
+    /// Recognizes the text of an image or PDF
+    async fn recognize(&self, data: Vec<u8>, filename: &str, mime_type: &str, settings: &Settings) -> Result<OcrResult>;
+
+    /// Short name recorded with the text the engine produced
+    fn name(&self) -> &str;
+}
+
+/// Engine chosen for a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineRoute {
+    Tesseract,
+    Handwriting,
+    /// The document is handwritten but no handwriting engine is configured
+    TesseractForHandwriting,
+}
+
+impl EngineRoute {
+    pub fn for_document(handwriting: bool, handwriting_engine_configured: bool) -> Self {
+        match (handwriting, handwriting_engine_configured) {
+            (false, _) => EngineRoute::Tesseract,
+            (true, true) => EngineRoute::Handwriting,
+            (true, false) => EngineRoute::TesseractForHandwriting,
+        }
+    }
+}
+
+/// Note stored with handwritten documents that Tesseract OCR'd because no
+/// handwriting engine is configured
+pub const NO_HANDWRITING_ENGINE_NOTE: &str =
+    "Flagged as handwritten, but no handwriting OCR engine is configured; recognized with Tesseract, so the text may be poor";
+
+/// Note stored with handwritten documents that Tesseract OCR'd after the
+/// handwriting engine failed
+pub fn handwriting_engine_failed_note(error: &anyhow::Error) -> String {
+    format!(
+        "The handwriting OCR engine failed ({}); recognized with Tesseract, so the text may be poor",
+        error
+    )
+}
@@ -15,8 +15,16 @@ use imageproc::{
 use tesseract::{Tesseract, PageSegMode, OcrEngineMode};
 
 use std::sync::Arc;
-use crate::models::Settings;
+use crate::models::{Settings, OCR_DPI_RANGE};
 use crate::services::file_service::FileService;
+use super::barcode::DecodedBarcode;
+use super::document_split::{SeparatorKind, SeparatorScan};
+use super::fallback_order::installed_fallback_config;
+use super::incremental::PageDiff;
+use super::live_log;
+use super::orientation::OrientationCorrection;
+use super::table_extraction::ExtractedTable;
+use super::temp_files;
 use super::xml_extractor::XmlOfficeExtractor;
 
 /// Callback for reporting OCR progress (current_page, total_pages).
@@ -24,6 +32,88 @@ use super::xml_extractor::XmlOfficeExtractor;
 pub type ProgressCallback = Arc<dyn Fn(i32, i32) + Send + Sync>;
 // Removed text_sanitization import - now using minimal inline sanitization
 
+/// Marker in `OcrResult::preprocessing_applied` for PDFs whose text layer was
+/// combined with OCR of their embedded images
+pub const HYBRID_PDF_EXTRACTION: &str = "Hybrid PDF extraction (text layer + image OCR)";
+
+/// OCR DPI the image resize bounds were tuned for
+const DEFAULT_OCR_DPI: i32 = 300;
+
+/// Appends the lines of `ocr_text` that don't already appear in `text_layer`.
+///
+/// Lines are compared ignoring case, punctuation and whitespace, so OCR of text
+/// that is also in the layer is dropped and only text from images (scanned
+/// figures, screenshots) is added. Returns the merged text and the number of
+/// lines added.
+pub fn merge_hybrid_text(text_layer: &str, ocr_text: &str) -> (String, usize) {
+    fn normalize(text: &str) -> String {
+        text.chars()
+            .map(|c| if c.is_alphanumeric() { c.to_lowercase().next().unwrap_or(c) } else { ' ' })
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    let normalized_layer = normalize(text_layer);
+    let mut seen = std::collections::HashSet::new();
+    let mut added = Vec::new();
+
+    for line in ocr_text.lines().map(str::trim) {
+        let normalized_line = normalize(line);
+        // Very short fragments are usually OCR noise around graphics
+        if normalized_line.chars().filter(|c| c.is_alphanumeric()).count() < 3 {
+            continue;
+        }
+        if normalized_layer.contains(&normalized_line) || !seen.insert(normalized_line) {
+            continue;
+        }
+        added.push(line);
+    }
+
+    if added.is_empty() {
+        return (text_layer.to_string(), 0);
+    }
+
+    (format!("{}\n\n{}", text_layer.trim_end(), added.join("\n")), added.len())
+}
+
+/// A PDF's text layer as printed by pdftotext, with the pages that had no text dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfTextLayer {
+    pub text: String,
+    pub pages_with_text: usize,
+    pub skipped_pages: usize,
+}
+
+impl PdfTextLayer {
+    /// Splits pdftotext output on its form-feed page separators. A page counts as
+    /// having text when it contains anything other than whitespace.
+    pub fn from_pdftotext_output(output: &str) -> Self {
+        let mut pages = Vec::new();
+        let mut skipped_pages = 0;
+        if output.is_empty() {
+            return Self { text: String::new(), pages_with_text: 0, skipped_pages };
+        }
+
+        // pdftotext ends every page, including the last, with a form feed
+        let output = output.strip_suffix('\u{0C}').unwrap_or(output);
+        for page in output.split('\u{0C}') {
+            if page.trim().is_empty() {
+                skipped_pages += 1;
+            } else {
+                pages.push(page.trim_end());
+            }
+        }
+
+        Self {
+            text: pages.join("\n\n"),
+            pages_with_text: pages.len(),
+            skipped_pages,
+        }
+    }
+}
+
 /// Arguments for ocrmypdf Strategy 1: Standard OCR with cleaning.
 /// These must only use flags supported by ocrmypdf >= 14.0.
 pub fn ocrmypdf_strategy1_args() -> Vec<&'static str> {
@@ -72,12 +162,31 @@ impl Drop for FileCleanupGuard {
     }
 }
 
+/// Removes a temporary directory and everything in it when dropped
+#[cfg(feature = "ocr")]
+struct DirCleanupGuard {
+    dir_path: String,
+}
+
+#[cfg(feature = "ocr")]
+impl Drop for DirCleanupGuard {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to clean up temporary directory '{}': {}", self.dir_path, e);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ImageQualityStats {
     pub average_brightness: f32,
     pub contrast_ratio: f32,
     pub noise_level: f32,
     pub sharpness: f32,
+    /// Fraction of the page covered by ink (dark pixels), 0.0 - 1.0
+    pub text_density: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -88,6 +197,53 @@ pub struct OcrResult {
     pub word_count: usize,
     pub preprocessing_applied: Vec<String>,
     pub processed_image_path: Option<String>,
+    /// Tesseract page segmentation mode used for this result (None when Tesseract wasn't used)
+    pub page_segmentation_mode: Option<i32>,
+    /// Whether a rotation proposed by orientation detection was applied or reverted
+    /// (None when no rotation was proposed)
+    pub orientation_correction: Option<OrientationCorrection>,
+    /// Resolution the image was resized for before OCR (None when it wasn't resized for one)
+    pub ocr_dpi: Option<i32>,
+}
+
+/// OCR text of one PDF page
+#[derive(Debug, Clone)]
+pub struct PageOcrText {
+    /// 1-based page number
+    pub page: usize,
+    pub text: String,
+    pub confidence: f32,
+    pub word_count: usize,
+}
+
+/// A PDF page rendered to an image
+#[cfg(feature = "ocr")]
+#[derive(Debug, Clone)]
+struct RenderedPage {
+    /// 1-based page number, as rendered
+    page: usize,
+    path: String,
+}
+
+/// Result of OCRing selected pages of a PDF
+#[derive(Debug, Clone)]
+pub struct PdfPagesOcrResult {
+    /// Total pages in the document
+    pub page_count: usize,
+    pub pages: Vec<PageOcrText>,
+    pub processing_time_ms: u64,
+}
+
+/// Result of OCRing one region of an image or PDF page
+#[derive(Debug, Clone)]
+pub struct RegionOcrResult {
+    /// Size of the full page image the region was cut from
+    pub image_width: u32,
+    pub image_height: u32,
+    pub text: String,
+    pub confidence: f32,
+    pub word_count: usize,
+    pub processing_time_ms: u64,
 }
 
 pub struct EnhancedOcrService {
@@ -102,6 +258,16 @@ impl EnhancedOcrService {
     // Security limits for Office document processing
     const MAX_ENTRY_NAME_LENGTH: usize = 255; // Maximum length of entry names
 
+    // Text density thresholds used when automatically selecting a page segmentation mode
+    const SPARSE_TEXT_DENSITY_THRESHOLD: f32 = 0.03;
+    const DENSE_TEXT_DENSITY_THRESHOLD: f32 = 0.12;
+
+    // Render resolution for quick preview OCR; full OCR renders at 300 DPI
+    const PREVIEW_RENDER_DPI: u32 = 100;
+
+    // Largest width or height accepted for OCR settings previews
+    const MAX_PREVIEW_DIMENSION: u32 = 8000;
+
     /// Remove null bytes from text to prevent PostgreSQL errors
     /// This is the ONLY sanitization we do - preserving all other original content
     fn remove_null_bytes(text: &str) -> String {
@@ -143,11 +309,13 @@ impl EnhancedOcrService {
         let mut preprocessing_applied = Vec::new();
         
         // Load and preprocess the image
-        let (processed_image_path, preprocess_steps) = if settings.enable_image_preprocessing {
-            let (processed_path, steps) = self.preprocess_image(file_path, settings).await?;
-            (processed_path, steps)
+        let (processed_image_path, preprocess_steps, orientation_correction) = if settings.enable_image_preprocessing {
+            self.preprocess_image(file_path, settings).await?
         } else {
-            (file_path.to_string(), Vec::new())
+            match self.downscale_oversized_image(file_path, settings.ocr_max_pixels).await? {
+                Some(downscaled_path) => (downscaled_path, vec!["Downscaled oversized image".to_string()], None),
+                None => (file_path.to_string(), Vec::new(), None),
+            }
         };
         
         preprocessing_applied.extend(preprocess_steps);
@@ -157,18 +325,24 @@ impl EnhancedOcrService {
         let settings_clone = settings.clone();
         let temp_dir = self.temp_dir.clone();
         
-        let ocr_result = tokio::task::spawn_blocking(move || -> Result<(String, f32)> {
+        let ocr_result = tokio::task::spawn_blocking(move || -> Result<(String, f32, i32)> {
+            // Reuse this thread's instance for the language when there is one
+            let language_combination = Self::build_language_combination_static(&settings_clone);
+            let tesseract = super::model_cache::checkout_tesseract(&language_combination)?;
+
             // Configure Tesseract with optimal settings
-            let mut tesseract = Self::configure_tesseract_static(&processed_image_path_clone, &settings_clone)?;
+            let (mut tesseract, psm) = Self::configure_tesseract_static(tesseract, &processed_image_path_clone, &settings_clone)?;
             
             // Extract text with confidence
             let text = tesseract.get_text()?.trim().to_string();
             let confidence = Self::calculate_overall_confidence_static(&mut tesseract)?;
+
+            super::model_cache::return_tesseract(language_combination, tesseract);
             
-            Ok((text, confidence))
+            Ok((text, confidence, psm))
         }).await??;
 
-        let (text, confidence) = ocr_result;
+        let (text, confidence, page_segmentation_mode) = ocr_result;
 
         // Sanitize null bytes to prevent PostgreSQL errors
         let text = Self::remove_null_bytes(&text);
@@ -195,6 +369,9 @@ impl EnhancedOcrService {
             word_count,
             preprocessing_applied,
             processed_image_path: result_processed_image_path,
+            page_segmentation_mode: Some(page_segmentation_mode),
+            orientation_correction,
+            ocr_dpi: settings.enable_image_preprocessing.then(|| Self::effective_ocr_dpi(settings)),
         };
         
         // Clean up temporary files if not saved for review
@@ -207,24 +384,65 @@ impl EnhancedOcrService {
         Ok(result)
     }
 
-    /// Preprocess image for optimal OCR quality, especially for challenging conditions
+    /// Write a downscaled copy of an image larger than `max_pixels` to the temp dir.
+    /// Returns `None` without decoding the image when it already fits.
     #[cfg(feature = "ocr")]
-    async fn preprocess_image(&self, input_path: &str, settings: &Settings) -> Result<(String, Vec<String>)> {
+    async fn downscale_oversized_image(&self, input_path: &str, max_pixels: i64) -> Result<Option<String>> {
+        let resolved_path = self.resolve_file_path(input_path).await?;
+        let (width, height) = image::image_dimensions(&resolved_path)?;
+        if Self::capped_dimensions(width, height, max_pixels).is_none() {
+            return Ok(None);
+        }
+
+        let img = Self::downscale_to_max_pixels(image::open(&resolved_path)?, max_pixels);
+        let temp_path = temp_files::temp_path(&self.temp_dir, &format!("downscaled_{}_{}.png",
+            std::process::id(),
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
+        ));
+        img.save(&temp_path)?;
+
+        Ok(Some(temp_path))
+    }
+
+    /// Preprocess image for optimal OCR quality, especially for challenging conditions.
+    /// Returns the processed image path, the steps applied and what became of any
+    /// orientation correction.
+    #[cfg(feature = "ocr")]
+    async fn preprocess_image(&self, input_path: &str, settings: &Settings) -> Result<(String, Vec<String>, Option<OrientationCorrection>)> {
         // Resolve the file path first
         let resolved_path = self.resolve_file_path(input_path).await?;
         let img = image::open(&resolved_path)?;
-        let mut processed_img = img;
         let mut preprocessing_applied = Vec::new();
         
-        info!("Original image dimensions: {}x{}", processed_img.width(), processed_img.height());
+        info!("Original image dimensions: {}x{}", img.width(), img.height());
+        
+        // Bound memory for the enhancement steps below on huge scans
+        let (original_width, original_height) = img.dimensions();
+        let mut processed_img = Self::downscale_to_max_pixels(img, settings.ocr_max_pixels);
+        if processed_img.dimensions() != (original_width, original_height) {
+            preprocessing_applied.push("Downscaled oversized image".to_string());
+        }
         
         // Apply orientation detection and correction
+        let mut orientation_correction = None;
         if settings.ocr_detect_orientation {
-            processed_img = self.detect_and_correct_orientation(processed_img)?;
+            if let Some(rotated) = self.detect_orientation_correction(&processed_img) {
+                let keep_rotation = !settings.ocr_verify_orientation
+                    || self.verify_orientation(&processed_img, &rotated, settings).await;
+                if keep_rotation {
+                    processed_img = rotated;
+                    preprocessing_applied.push("Orientation correction".to_string());
+                    orientation_correction = Some(OrientationCorrection::Applied);
+                } else {
+                    preprocessing_applied.push("Orientation correction reverted".to_string());
+                    orientation_correction = Some(OrientationCorrection::Reverted);
+                }
+            }
         }
         
         // Aggressively upscale low-resolution images for better OCR
-        processed_img = self.smart_resize_for_ocr(processed_img, settings.ocr_dpi)?;
+        processed_img = self.smart_resize_for_ocr(processed_img, Self::effective_ocr_dpi(settings))?;
+        processed_img = Self::downscale_to_max_pixels(processed_img, settings.ocr_max_pixels);
         
         // Convert to grayscale for better OCR
         let gray_img = processed_img.to_luma8();
@@ -294,13 +512,13 @@ impl EnhancedOcrService {
             std::process::id(), 
             std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
         );
-        let temp_path = format!("{}/{}", self.temp_dir, temp_filename);
+        let temp_path = temp_files::temp_path(&self.temp_dir, &temp_filename);
         
         let dynamic_processed = DynamicImage::ImageLuma8(processed_gray);
         dynamic_processed.save(&temp_path)?;
         
         info!("Processed image saved to: {}", temp_path);
-        Ok((temp_path, preprocessing_applied))
+        Ok((temp_path, preprocessing_applied, orientation_correction))
     }
 
     /// Determine if image needs enhancement based on quality thresholds
@@ -339,7 +557,7 @@ impl EnhancedOcrService {
     }
     
     /// Build language combination string for Tesseract (e.g., "eng+spa")
-    fn build_language_combination(&self, settings: &Settings) -> String {
+    pub fn build_language_combination(&self, settings: &Settings) -> String {
         if settings.preferred_languages.len() > 1 {
             // Use preferred_languages with primary_language first
             let mut languages = settings.preferred_languages.clone();
@@ -361,32 +579,16 @@ impl EnhancedOcrService {
 
     /// Configure Tesseract with optimal settings
     #[cfg(feature = "ocr")]
-    fn configure_tesseract(&self, image_path: &str, settings: &Settings) -> Result<Tesseract> {
+    fn configure_tesseract(&self, image_path: &str, settings: &Settings) -> Result<(Tesseract, i32)> {
         let language_combination = self.build_language_combination(settings);
         let mut tesseract = Tesseract::new(None, Some(&language_combination))?;
         
         // Set the image
         tesseract = tesseract.set_image(image_path)?;
         
-        // Configure Page Segmentation Mode (PSM)
-        let psm = match settings.ocr_page_segmentation_mode {
-            0 => PageSegMode::PsmOsdOnly,
-            1 => PageSegMode::PsmAutoOsd,
-            2 => PageSegMode::PsmAutoOnly,
-            3 => PageSegMode::PsmAuto,
-            4 => PageSegMode::PsmSingleColumn,
-            5 => PageSegMode::PsmSingleBlockVertText,
-            6 => PageSegMode::PsmSingleBlock,
-            7 => PageSegMode::PsmSingleLine,
-            8 => PageSegMode::PsmSingleWord,
-            9 => PageSegMode::PsmCircleWord,
-            10 => PageSegMode::PsmSingleChar,
-            11 => PageSegMode::PsmSparseText,
-            12 => PageSegMode::PsmSparseTextOsd,
-            13 => PageSegMode::PsmRawLine,
-            _ => PageSegMode::PsmAuto, // Default fallback
-        };
-        tesseract.set_page_seg_mode(psm);
+        // Configure Page Segmentation Mode (PSM), picking one from the image when auto PSM is enabled
+        let psm_mode = Self::resolve_page_segmentation_mode(image_path, settings);
+        tesseract.set_page_seg_mode(Self::page_seg_mode_from_setting(psm_mode));
         
         // Configure OCR Engine Mode (OEM)
         let _oem = match settings.ocr_engine_mode {
@@ -403,7 +605,65 @@ impl EnhancedOcrService {
         // Basic configuration - skip advanced settings that might cause issues
         // Only set essential variables that are widely supported
         
-        Ok(tesseract)
+        Ok((tesseract, psm_mode))
+    }
+
+    /// Map a numeric PSM setting to Tesseract's page segmentation mode
+    #[cfg(feature = "ocr")]
+    fn page_seg_mode_from_setting(mode: i32) -> PageSegMode {
+        match mode {
+            0 => PageSegMode::PsmOsdOnly,
+            1 => PageSegMode::PsmAutoOsd,
+            2 => PageSegMode::PsmAutoOnly,
+            3 => PageSegMode::PsmAuto,
+            4 => PageSegMode::PsmSingleColumn,
+            5 => PageSegMode::PsmSingleBlockVertText,
+            6 => PageSegMode::PsmSingleBlock,
+            7 => PageSegMode::PsmSingleLine,
+            8 => PageSegMode::PsmSingleWord,
+            9 => PageSegMode::PsmCircleWord,
+            10 => PageSegMode::PsmSingleChar,
+            11 => PageSegMode::PsmSparseText,
+            12 => PageSegMode::PsmSparseTextOsd,
+            13 => PageSegMode::PsmRawLine,
+            _ => PageSegMode::PsmAuto, // Default fallback
+        }
+    }
+
+    /// Determine the PSM to use for an image. With `ocr_auto_psm` enabled the mode is
+    /// picked from the image's text density; otherwise the configured PSM is used.
+    #[cfg(feature = "ocr")]
+    fn resolve_page_segmentation_mode(image_path: &str, settings: &Settings) -> i32 {
+        if !settings.ocr_auto_psm {
+            return settings.ocr_page_segmentation_mode;
+        }
+
+        match image::open(image_path) {
+            Ok(img) => {
+                let text_density = Self::estimate_text_density(&img.to_luma8());
+                let psm = Self::select_psm_for_text_density(text_density);
+                info!("Auto PSM selected mode {} for {} (text density {:.3})", psm, image_path, text_density);
+                psm
+            }
+            Err(e) => {
+                warn!("Failed to analyze {} for auto PSM, using configured mode {}: {}",
+                      image_path, settings.ocr_page_segmentation_mode, e);
+                settings.ocr_page_segmentation_mode
+            }
+        }
+    }
+
+    /// Pick a page segmentation mode from text density: sparse pages (receipts, forms,
+    /// labels) use sparse text mode, dense pages use single block mode, everything else
+    /// falls back to fully automatic segmentation.
+    pub fn select_psm_for_text_density(text_density: f32) -> i32 {
+        if text_density < Self::SPARSE_TEXT_DENSITY_THRESHOLD {
+            11 // PSM_SPARSE_TEXT
+        } else if text_density > Self::DENSE_TEXT_DENSITY_THRESHOLD {
+            6 // PSM_SINGLE_BLOCK
+        } else {
+            3 // PSM_AUTO
+        }
     }
     
     /// Calculate overall confidence score using Tesseract's mean confidence
@@ -423,57 +683,168 @@ impl EnhancedOcrService {
         Ok(clamped_confidence)
     }
     
-    /// Detect and correct image orientation
+    /// Detect image orientation, returning the corrected image when it needs rotating
     #[cfg(feature = "ocr")]
-    fn detect_and_correct_orientation(&self, img: DynamicImage) -> Result<DynamicImage> {
+    fn detect_orientation_correction(&self, img: &DynamicImage) -> Option<DynamicImage> {
         // For now, we'll implement basic rotation detection
         // In a production system, you might want to use Tesseract's OSD or advanced algorithms
         let (width, height) = img.dimensions();
         
         // If image is wider than tall by significant margin, it might need rotation
         if width as f32 / height as f32 > 2.0 {
-            Ok(img.rotate90())
+            Some(img.rotate90())
         } else {
-            Ok(img)
+            None
+        }
+    }
+
+    /// OCR a downscaled sample of the image as-is and rotated, and report whether
+    /// the rotation reads at least as well. Keeps the rotation when the samples
+    /// can't be OCR'd, as happened before verification existed.
+    #[cfg(feature = "ocr")]
+    async fn verify_orientation(&self, original: &DynamicImage, rotated: &DynamicImage, settings: &Settings) -> bool {
+        let original = Self::downscale_to_max_pixels(original.clone(), super::orientation::ORIENTATION_SAMPLE_MAX_PIXELS);
+        let rotated = Self::downscale_to_max_pixels(rotated.clone(), super::orientation::ORIENTATION_SAMPLE_MAX_PIXELS);
+        let language_combination = self.build_language_combination(settings);
+        let psm = settings.ocr_page_segmentation_mode;
+
+        let samples = tokio::task::spawn_blocking(move || -> Result<_> {
+            let tesseract = super::model_cache::checkout_tesseract(&language_combination)?;
+            let (tesseract, original_sample) = Self::read_orientation_sample(tesseract, &original, psm)?;
+            let (tesseract, rotated_sample) = Self::read_orientation_sample(tesseract, &rotated, psm)?;
+            super::model_cache::return_tesseract(language_combination, tesseract);
+            Ok((original_sample, rotated_sample))
+        })
+        .await;
+
+        match samples {
+            Ok(Ok((original_sample, rotated_sample))) => {
+                let keep = super::orientation::keep_rotation(&original_sample, &rotated_sample);
+                info!(
+                    "Orientation check: original {:.1}% / {} words, rotated {:.1}% / {} words, {}",
+                    original_sample.confidence,
+                    original_sample.word_count,
+                    rotated_sample.confidence,
+                    rotated_sample.word_count,
+                    if keep { "keeping rotation" } else { "reverting rotation" }
+                );
+                keep
+            }
+            Ok(Err(e)) => {
+                warn!("Orientation check failed, keeping detected rotation: {}", e);
+                true
+            }
+            Err(e) => {
+                warn!("Orientation check task failed, keeping detected rotation: {}", e);
+                true
+            }
         }
     }
     
-    /// Smart resize for OCR - optimize image size for best OCR performance
+    /// OCR one orientation sample, handing the instance back for the next one
     #[cfg(feature = "ocr")]
-    fn smart_resize_for_ocr(&self, img: DynamicImage, _target_dpi: i32) -> Result<DynamicImage> {
-        let (width, height) = img.dimensions();
+    fn read_orientation_sample(tesseract: Tesseract, img: &DynamicImage, psm: i32) -> Result<(Tesseract, super::orientation::OrientationSample)> {
+        let mut png = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+        let mut tesseract = tesseract.set_image_from_mem(&png)?;
+        tesseract.set_page_seg_mode(Self::page_seg_mode_from_setting(psm));
+        let word_count = tesseract.get_text()?.split_whitespace().count();
+        let confidence = Self::calculate_overall_confidence_static(&mut tesseract)?;
+        Ok((tesseract, super::orientation::OrientationSample { confidence, word_count }))
+    }
+    
+    /// DPI images are resized for: the job's, label's or source's override when
+    /// the queue applied one to the settings, otherwise the user's OCR DPI
+    pub fn effective_ocr_dpi(settings: &Settings) -> i32 {
+        settings.ocr_dpi.clamp(*OCR_DPI_RANGE.start(), *OCR_DPI_RANGE.end())
+    }
+
+    /// OCR DPI for a document: a label's override wins over the source's, which
+    /// wins over the user's setting
+    pub fn resolve_ocr_dpi(user_dpi: i32, source_dpi: Option<i32>, label_dpi: Option<i32>) -> i32 {
+        label_dpi.or(source_dpi).unwrap_or(user_dpi)
+    }
+
+    /// Size to resize an image to before OCR at `target_dpi`, or None to keep it.
+    /// Large images are scaled down and tiny ones up; both bounds grow with the
+    /// DPI, and at the default 300 DPI they are 2048 and 300 pixels.
+    pub fn ocr_resize_dimensions(width: u32, height: u32, target_dpi: i32) -> Option<(u32, u32)> {
         let max_dimension = width.max(height);
         let min_dimension = width.min(height);
-        
-        // Calculate optimal dimensions for OCR
-        let mut new_width = width;
-        let mut new_height = height;
-        
-        // Scale DOWN large images for better OCR performance and memory efficiency
-        if max_dimension > 2048 {
-            let scale_factor = 2048.0 / max_dimension as f32;
-            new_width = (width as f32 * scale_factor) as u32;
-            new_height = (height as f32 * scale_factor) as u32;
-            info!("Scaling down large image ({}x{}) by factor {:.2}x to {}x{} for optimal OCR", 
-                  width, height, scale_factor, new_width, new_height);
-        }
-        // Scale UP very small images that would produce poor OCR results
-        else if min_dimension < 300 {
-            let scale_factor = 600.0 / min_dimension as f32;
-            new_width = (width as f32 * scale_factor) as u32;
-            new_height = (height as f32 * scale_factor) as u32;
-            info!("Scaling up small image ({}x{}) by factor {:.2}x to {}x{} for better OCR", 
-                  width, height, scale_factor, new_width, new_height);
-        }
-        
-        if new_width != width || new_height != height {
-            // Use Lanczos3 for best quality upscaling
-            Ok(img.resize(new_width, new_height, image::imageops::FilterType::Lanczos3))
+        if min_dimension == 0 {
+            return None;
+        }
+
+        let dpi_scale = target_dpi as f32 / DEFAULT_OCR_DPI as f32;
+        let largest_dimension = (2048.0 * dpi_scale) as u32;
+        let smallest_dimension = (300.0 * dpi_scale) as u32;
+
+        let scale_factor = if max_dimension > largest_dimension {
+            largest_dimension as f32 / max_dimension as f32
+        } else if min_dimension < smallest_dimension {
+            2.0 * smallest_dimension as f32 / min_dimension as f32
         } else {
-            Ok(img)
+            return None;
+        };
+        let new_width = ((width as f32 * scale_factor) as u32).max(1);
+        let new_height = ((height as f32 * scale_factor) as u32).max(1);
+        (new_width != width || new_height != height).then_some((new_width, new_height))
+    }
+
+    /// Smart resize for OCR - optimize image size for best OCR performance
+    #[cfg(feature = "ocr")]
+    fn smart_resize_for_ocr(&self, img: DynamicImage, target_dpi: i32) -> Result<DynamicImage> {
+        let (width, height) = img.dimensions();
+        match Self::ocr_resize_dimensions(width, height, target_dpi) {
+            Some((new_width, new_height)) => {
+                info!("Resizing image ({}x{}) to {}x{} for OCR at {} DPI",
+                      width, height, new_width, new_height, target_dpi);
+                // Use Lanczos3 for best quality upscaling
+                Ok(img.resize(new_width, new_height, image::imageops::FilterType::Lanczos3))
+            }
+            None => Ok(img),
         }
     }
     
+    /// Dimensions that fit within `max_pixels` while keeping the aspect ratio, or
+    /// `None` when the image already fits.
+    pub fn capped_dimensions(width: u32, height: u32, max_pixels: i64) -> Option<(u32, u32)> {
+        let pixels = width as u64 * height as u64;
+        let max_pixels = max_pixels.max(1) as u64;
+        if pixels <= max_pixels {
+            return None;
+        }
+
+        let scale = (max_pixels as f64 / pixels as f64).sqrt();
+        let mut new_width = ((width as f64 * scale).floor() as u32).max(1);
+        let mut new_height = ((height as f64 * scale).floor() as u32).max(1);
+
+        // Floating point rounding can leave the result a few pixels over the cap
+        while new_width as u64 * new_height as u64 > max_pixels {
+            if new_width >= new_height {
+                new_width -= 1;
+            } else {
+                new_height -= 1;
+            }
+        }
+
+        Some((new_width, new_height))
+    }
+
+    /// Downscale images larger than `max_pixels`, preserving the aspect ratio
+    #[cfg(feature = "ocr")]
+    pub fn downscale_to_max_pixels(img: DynamicImage, max_pixels: i64) -> DynamicImage {
+        let (width, height) = img.dimensions();
+        match Self::capped_dimensions(width, height, max_pixels) {
+            Some((new_width, new_height)) => {
+                warn!("Image {}x{} ({} pixels) exceeds ocr_max_pixels ({}), downscaling to {}x{} before OCR",
+                      width, height, width as u64 * height as u64, max_pixels, new_width, new_height);
+                img.resize_exact(new_width, new_height, image::imageops::FilterType::Triangle)
+            }
+            None => img,
+        }
+    }
+
     /// Analyze image quality metrics
     #[cfg(feature = "ocr")]
     fn analyze_image_quality(&self, img: &ImageBuffer<Luma<u8>, Vec<u8>>) -> ImageQualityStats {
@@ -495,11 +866,15 @@ impl EnhancedOcrService {
         // Estimate sharpness using gradient magnitude
         let sharpness = self.estimate_sharpness(img);
         
+        // Estimate how much of the page is covered by text
+        let text_density = Self::estimate_text_density(img);
+        
         ImageQualityStats {
             average_brightness,
             contrast_ratio,
             noise_level,
             sharpness,
+            text_density,
         }
     }
     
@@ -602,6 +977,34 @@ impl EnhancedOcrService {
         }
     }
     
+    /// Estimate text density as the fraction of ink pixels on the page.
+    /// Ink is whichever of dark/light pixels is the minority, so inverted scans
+    /// (light text on a dark background) are measured the same way.
+    #[cfg(feature = "ocr")]
+    pub fn estimate_text_density(img: &ImageBuffer<Luma<u8>, Vec<u8>>) -> f32 {
+        let (width, height) = img.dimensions();
+        let total_pixels = (width as u64) * (height as u64);
+        let step_size = if total_pixels > 4_000_000 { 4 } else { 1 }; // Sample large images
+        
+        let mut dark_count = 0u64;
+        let mut sample_count = 0u64;
+        for y in (0..height).step_by(step_size) {
+            for x in (0..width).step_by(step_size) {
+                if img.get_pixel(x, y)[0] < 128 {
+                    dark_count += 1;
+                }
+                sample_count += 1;
+            }
+        }
+        
+        if sample_count == 0 {
+            return 0.0;
+        }
+        
+        let dark_ratio = dark_count as f32 / sample_count as f32;
+        dark_ratio.min(1.0 - dark_ratio)
+    }
+    
     /// Estimate image sharpness using gradient magnitude
     #[cfg(feature = "ocr")]
     fn estimate_sharpness(&self, img: &ImageBuffer<Luma<u8>, Vec<u8>>) -> f32 {
@@ -949,80 +1352,134 @@ impl EnhancedOcrService {
         // If it does, we MUST use image-based OCR to capture content from both text layers AND images
         let has_images = self.pdf_has_images(file_path).await;
 
-        if has_images {
-            // PDF has images - use image-based OCR to capture EVERYTHING (text + images)
-            info!("PDF '{}' has embedded images, using image-based OCR for comprehensive extraction", file_path);
-
-            if self.is_pdftoppm_available().await {
-                match self.extract_text_from_pdf_via_images(file_path, settings, start_time, progress_callback.clone()).await {
-                    Ok(result) if result.word_count > 0 => {
-                        info!("PDF image-based OCR successful for '{}': {} words", file_path, result.word_count);
-                        return Ok(result);
-                    }
-                    Ok(_) => {
-                        warn!("Image-based OCR returned no words for '{}', falling back to ocrmypdf", file_path);
-                    }
-                    Err(e) => {
-                        warn!("Image-based OCR failed for '{}': {}, falling back to ocrmypdf", file_path, e);
-                    }
-                }
+        if has_images && settings.ocr_pdf_hybrid && self.is_pdftoppm_available().await {
+            match self.extract_text_from_pdf_hybrid(file_path, file_size, settings, start_time, progress_callback.clone()).await {
+                Ok(Some(result)) => return Ok(result),
+                Ok(None) => info!("PDF '{}' has no usable text layer, skipping hybrid extraction", file_path),
+                Err(e) => warn!("Hybrid extraction failed for '{}': {}, falling back to image-based OCR", file_path, e),
             }
-
-            // Fallback to ocrmypdf for PDFs with images
-            return self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await;
         }
 
-        // No images detected - use fast pdftotext extraction
-        let quick_extraction_result = self.extract_pdf_text_quick(file_path).await;
-
-        match quick_extraction_result {
-            Ok((text, extraction_time)) => {
-                let word_count = self.count_words_safely(&text);
-
-                // Check if quick extraction got good results
-                if self.is_text_extraction_quality_sufficient(&text, word_count, file_size) {
-                    info!("PDF text extraction successful for '{}' using quick method", file_path);
-
-                    // Sanitize null bytes to prevent PostgreSQL errors
-                    let text = Self::remove_null_bytes(&text);
+        // Try the extraction methods in the user's order until one reads the PDF
+        let methods = installed_fallback_config()
+            .with_settings(settings)
+            .effective_order(super::fallback_order::FallbackStrategy::Pdf, &super::fallback_stats::fallback_stats().snapshot());
+        let mut last_error: Option<anyhow::Error> = None;
 
-                    return Ok(OcrResult {
-                        text,
-                        confidence: 95.0,
-                        processing_time_ms: extraction_time,
-                        word_count,
-                        preprocessing_applied: vec!["PDF text extraction (pdftotext)".to_string()],
-                        processed_image_path: None,
-                    });
-                } else {
-                    info!("Quick PDF extraction insufficient for '{}' ({} words), using full OCR", file_path, word_count);
+        for method in &methods {
+            let method_start = std::time::Instant::now();
+            let result = match method.as_str() {
+                // A text layer misses whatever is in the images, so PDFs with images must be OCR'd
+                "pdftotext" if has_images => {
+                    info!("PDF '{}' has embedded images, skipping pdftotext", file_path);
+                    continue;
                 }
-            }
-            Err(e) => {
-                warn!("Quick PDF extraction failed for '{}': {}, using full OCR", file_path, e);
-            }
-        }
+                "pdftotext" => self.extract_text_from_pdf_text_layer(file_path, file_size).await,
+                "pdftoppm" => {
+                    if !self.is_pdftoppm_available().await {
+                        warn!("pdftoppm is not available, skipping image-based OCR of '{}'", file_path);
+                        continue;
+                    }
+                    match self.extract_text_from_pdf_via_images(file_path, settings, start_time, progress_callback.clone()).await {
+                        Ok(result) if result.word_count == 0 => Err(anyhow!("Image-based OCR returned no words")),
+                        result => result,
+                    }
+                }
+                "ocrmypdf" => self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await,
+                other => {
+                    warn!("Unknown PDF extraction method '{}', skipping it", other);
+                    continue;
+                }
+            };
+            super::fallback_stats::fallback_stats().record(method, result.is_ok(), method_start.elapsed());
 
-        // pdftotext failed/insufficient - try image-based OCR then ocrmypdf
-        if self.is_pdftoppm_available().await {
-            match self.extract_text_from_pdf_via_images(file_path, settings, start_time, progress_callback).await {
-                Ok(result) if result.word_count > 0 => {
-                    info!("PDF image-based OCR successful for '{}': {} words", file_path, result.word_count);
+            match result {
+                Ok(result) => {
+                    info!("PDF extraction with {} successful for '{}': {} words", method, file_path, result.word_count);
                     return Ok(result);
                 }
-                Ok(_) => {
-                    warn!("Image-based OCR returned no words for '{}', trying ocrmypdf", file_path);
-                }
                 Err(e) => {
-                    warn!("Image-based OCR failed for '{}': {}, trying ocrmypdf", file_path, e);
+                    warn!("PDF extraction with {} failed for '{}': {}", method, file_path, e);
+                    last_error = Some(e);
                 }
             }
         }
 
-        // Fall back to ocrmypdf
-        self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await
+        Err(last_error.unwrap_or_else(|| {
+            anyhow!("None of the PDF extraction methods ({}) could be used for '{}'", methods.join(", "), file_path)
+        }))
+    }
+
+    /// Read the PDF's text layer with pdftotext, failing when it holds too little
+    /// text for the file's size to be the whole document
+    #[cfg(feature = "ocr")]
+    async fn extract_text_from_pdf_text_layer(&self, file_path: &str, file_size: u64) -> Result<OcrResult> {
+        let (text, extraction_time) = self.extract_pdf_text_quick(file_path).await?;
+        let word_count = self.count_words_safely(&text);
+
+        if !self.is_text_extraction_quality_sufficient(&text, word_count, file_size) {
+            return Err(anyhow!("Text layer is insufficient ({} words)", word_count));
+        }
+
+        // Sanitize null bytes to prevent PostgreSQL errors
+        let text = Self::remove_null_bytes(&text);
+
+        Ok(OcrResult {
+            text,
+            confidence: 95.0,
+            processing_time_ms: extraction_time,
+            word_count,
+            preprocessing_applied: vec!["PDF text extraction (pdftotext)".to_string()],
+            processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
+        })
     }
     
+    /// Keep the PDF's text layer and append OCR text found only in its images.
+    ///
+    /// Returns `None` when the text layer is missing or too poor to build on, so the
+    /// caller can fall back to plain image-based OCR.
+    #[cfg(feature = "ocr")]
+    async fn extract_text_from_pdf_hybrid(&self, file_path: &str, file_size: u64, settings: &Settings, start_time: std::time::Instant, progress_callback: Option<ProgressCallback>) -> Result<Option<OcrResult>> {
+        let (text_layer, _) = self.extract_pdf_text_quick(file_path).await?;
+        let text_layer = Self::remove_null_bytes(&text_layer);
+        let layer_word_count = self.count_words_safely(&text_layer);
+        if !self.is_text_extraction_quality_sufficient(&text_layer, layer_word_count, file_size) {
+            return Ok(None);
+        }
+
+        let ocr_result = self.extract_text_from_pdf_via_images(file_path, settings, start_time, progress_callback).await?;
+        let (text, added_lines) = merge_hybrid_text(&text_layer, &ocr_result.text);
+        let word_count = self.count_words_safely(&text);
+
+        // Text layer words are exact; only the appended words carry OCR uncertainty
+        let added_word_count = word_count.saturating_sub(layer_word_count);
+        let confidence = if added_word_count == 0 {
+            95.0
+        } else {
+            (layer_word_count as f32 * 95.0 + added_word_count as f32 * ocr_result.confidence) / word_count as f32
+        };
+
+        info!("Hybrid extraction for '{}': {} words from text layer, {} lines ({} words) added from OCR",
+              file_path, layer_word_count, added_lines, added_word_count);
+
+        Ok(Some(OcrResult {
+            text,
+            confidence,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+            word_count,
+            preprocessing_applied: vec![
+                "PDF text extraction (pdftotext)".to_string(),
+                HYBRID_PDF_EXTRACTION.to_string(),
+            ],
+            processed_image_path: None,
+            page_segmentation_mode: ocr_result.page_segmentation_mode,
+            orientation_correction: ocr_result.orientation_correction,
+        }))
+    }
+
     /// Assess if text extraction quality is sufficient or if OCR fallback is needed
     #[cfg(feature = "ocr")]
     fn is_text_extraction_quality_sufficient(&self, text: &str, word_count: usize, file_size: u64) -> bool {
@@ -1102,9 +1559,10 @@ impl EnhancedOcrService {
             std::process::id(), 
             std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
         );
-        let temp_ocr_path = format!("{}/{}", self.temp_dir, temp_ocr_filename);
+        let temp_ocr_path = temp_files::temp_path(&self.temp_dir, &temp_ocr_filename);
         
         // Run ocrmypdf with progressive fallback strategies
+        let document_id = live_log::current_document();
         let ocrmypdf_result = tokio::time::timeout(
             std::time::Duration::from_secs(self.ocr_timeout_seconds),
             tokio::task::spawn_blocking({
@@ -1116,10 +1574,8 @@ impl EnhancedOcrService {
                     for arg in ocrmypdf_strategy1_args() {
                         cmd.arg(arg);
                     }
-                    let mut result = cmd
-                        .arg(&file_path)
-                        .arg(&temp_ocr_path)
-                        .output();
+                    cmd.arg(&file_path).arg(&temp_ocr_path);
+                    let mut result = live_log::output_blocking(document_id, &mut cmd, "ocrmypdf");
                     
                     if result.is_ok() && result.as_ref().unwrap().status.success() {
                         return result;
@@ -1131,10 +1587,8 @@ impl EnhancedOcrService {
                     for arg in ocrmypdf_strategy2_args() {
                         cmd.arg(arg);
                     }
-                    result = cmd
-                        .arg(&file_path)
-                        .arg(&temp_ocr_path)
-                        .output();
+                    cmd.arg(&file_path).arg(&temp_ocr_path);
+                    result = live_log::output_blocking(document_id, &mut cmd, "ocrmypdf");
                     
                     if result.is_ok() && result.as_ref().unwrap().status.success() {
                         return result;
@@ -1181,13 +1635,16 @@ impl EnhancedOcrService {
                 // Catch panics from pdf-extract library (same pattern as used elsewhere)
                 // Extract text from the OCR'd PDF using ocrmypdf's sidecar option
                 let temp_text_path = format!("{}.txt", temp_ocr_path);
-                let extract_result = std::process::Command::new("ocrmypdf")
-                    .arg("--skip-text") // Don't re-OCR — just extract existing text layer
-                    .arg("--sidecar")   // Extract text to a sidecar file
-                    .arg(&temp_text_path)
-                    .arg(&temp_ocr_path)
-                    .arg("-")  // Output to stdout (dummy, required by ocrmypdf)
-                    .output()?;
+                let extract_result = live_log::output_blocking(
+                    document_id,
+                    std::process::Command::new("ocrmypdf")
+                        .arg("--skip-text") // Don't re-OCR — just extract existing text layer
+                        .arg("--sidecar") // Extract text to a sidecar file
+                        .arg(&temp_text_path)
+                        .arg(&temp_ocr_path)
+                        .arg("-"), // Output to stdout (dummy, required by ocrmypdf)
+                    "ocrmypdf",
+                )?;
                 
                 if !extract_result.status.success() {
                     let stderr = String::from_utf8_lossy(&extract_result.stderr);
@@ -1198,7 +1655,8 @@ impl EnhancedOcrService {
                 }
                 
                 // Read the extracted text from the sidecar file
-                let text = std::fs::read_to_string(&temp_text_path)?;
+                // The sidecar can contain invalid UTF-8 from a broken text layer
+                let text = String::from_utf8_lossy(&std::fs::read(&temp_text_path)?).into_owned();
                 
                 // Clean up the text file
                 let _ = std::fs::remove_file(&temp_text_path);
@@ -1225,6 +1683,9 @@ impl EnhancedOcrService {
             word_count,
             preprocessing_applied: vec!["OCR via ocrmypdf".to_string()],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         })
     }
     
@@ -1238,17 +1699,18 @@ impl EnhancedOcrService {
             std::process::id(),
             std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
         );
-        let temp_text_path = format!("{}/{}", self.temp_dir, temp_text_filename);
+        let temp_text_path = temp_files::temp_path(&self.temp_dir, &temp_text_filename);
 
         // Strategy 1: Fast text extraction using pdftotext (for existing text)
         debug!("Trying pdftotext for existing text extraction: {}", file_path);
         debug!("Using temp file path: {}", temp_text_path);
-        let pdftotext_result = tokio::process::Command::new("pdftotext")
-            .arg("-layout")  // Preserve layout
-            .arg(file_path)
-            .arg(&temp_text_path)
-            .output()
-            .await;
+        let pdftotext_result = live_log::output(
+            tokio::process::Command::new("pdftotext")
+                .arg("-layout") // Preserve layout
+                .arg(file_path)
+                .arg(&temp_text_path),
+            "pdftotext",
+        ).await;
 
         if let Ok(output) = pdftotext_result {
             debug!("pdftotext exit status: {}", output.status);
@@ -1256,7 +1718,8 @@ impl EnhancedOcrService {
                 debug!("pdftotext stderr: {}", String::from_utf8_lossy(&output.stderr));
             }
             if output.status.success() {
-                if let Ok(text) = tokio::fs::read_to_string(&temp_text_path).await {
+                if let Ok(bytes) = tokio::fs::read(&temp_text_path).await {
+                    let text = String::from_utf8_lossy(&bytes);
                     let _ = tokio::fs::remove_file(&temp_text_path).await;
                     let word_count = text.split_whitespace().count();
                     debug!("pdftotext extracted {} words from temp file", word_count);
@@ -1283,18 +1746,20 @@ impl EnhancedOcrService {
         info!("pdftotext extraction insufficient for '{}', will use OCR", file_path);
 
         // Strategy 2: Use ocrmypdf --sidecar to extract existing OCR text layer (if PDF already has one)
-        let ocrmypdf_result = tokio::process::Command::new("ocrmypdf")
-            .arg("--skip-text") // Don't re-OCR — just extract existing text layer
-            .arg("--sidecar")
-            .arg(&temp_text_path)
-            .arg(file_path)
-            .arg("-")  // Dummy output (we only want sidecar)
-            .output()
-            .await;
+        let ocrmypdf_result = live_log::output(
+            tokio::process::Command::new("ocrmypdf")
+                .arg("--skip-text") // Don't re-OCR — just extract existing text layer
+                .arg("--sidecar")
+                .arg(&temp_text_path)
+                .arg(file_path)
+                .arg("-"), // Dummy output (we only want sidecar)
+            "ocrmypdf",
+        ).await;
 
         if let Ok(output) = &ocrmypdf_result {
             if output.status.success() {
-                if let Ok(text) = tokio::fs::read_to_string(&temp_text_path).await {
+                if let Ok(bytes) = tokio::fs::read(&temp_text_path).await {
+                    let text = String::from_utf8_lossy(&bytes);
                     let _ = tokio::fs::remove_file(&temp_text_path).await;
                     let word_count = text.split_whitespace().count();
                     if word_count > 5 {
@@ -1401,11 +1866,10 @@ impl EnhancedOcrService {
     /// Convert PDF pages to PNG images using pdftoppm
     #[cfg(feature = "ocr")]
     async fn extract_pdf_pages_as_images(&self, file_path: &str, page_count: usize) -> Result<Vec<String>> {
-        let temp_prefix = format!("{}/pdf_page_{}_{}",
-            self.temp_dir,
+        let temp_prefix = temp_files::temp_path(&self.temp_dir, &format!("pdf_page_{}_{}",
             std::process::id(),
             std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
-        );
+        ));
 
         // Convert all pages to PNG at 300 DPI for good OCR quality
         let output = tokio::process::Command::new("pdftoppm")
@@ -1504,6 +1968,9 @@ impl EnhancedOcrService {
             word_count: total_words,
             preprocessing_applied: vec![format!("PDF page-to-image OCR ({} pages)", successful_pages)],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         })
     }
     
@@ -1559,8 +2026,9 @@ impl EnhancedOcrService {
             ));
         }
         
-        // Use XML extraction as the primary method
-        let xml_extractor = XmlOfficeExtractor::new(self.temp_dir.clone());
+        // Use XML extraction as the primary method, with the user's legacy Word tool order
+        let xml_extractor = XmlOfficeExtractor::new(self.temp_dir.clone())
+            .with_fallback_config(installed_fallback_config().with_settings(settings));
         let xml_result = xml_extractor.extract_text_from_office(file_path, mime_type).await?;
         
         let total_time = start_time.elapsed().as_millis() as u64;
@@ -1579,36 +2047,648 @@ impl EnhancedOcrService {
             word_count: xml_result.word_count,
             preprocessing_applied: vec![format!("XML extraction - {}", xml_result.extraction_method)],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         })
     }
 
     /// Extract text from any supported file type
     pub async fn extract_text(&self, file_path: &str, mime_type: &str, settings: &Settings, progress_callback: Option<ProgressCallback>) -> Result<OcrResult> {
-        // Files in a remote storage backend (s3://...) must be downloaded to a
-        // local temp file before the extractors (which read the local FS) run.
-        if file_path.starts_with("s3://") {
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        self.extract_text_from_local_path(&local_path, mime_type, settings, progress_callback)
+            .await
+    }
+
+    /// Extract only the text a file already contains, never running OCR. PDFs use
+    /// their text layer with image-only pages skipped, images yield no text, and
+    /// text and Office files are read as usual.
+    pub async fn extract_existing_text(&self, file_path: &str, mime_type: &str, settings: &Settings) -> Result<OcrResult> {
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        match mime_type {
+            "application/pdf" => self.extract_pdf_text_layer(&local_path).await,
+            mime if mime.starts_with("image/") => Ok(OcrResult {
+                text: String::new(),
+                confidence: 0.0,
+                processing_time_ms: 0,
+                word_count: 0,
+                preprocessing_applied: vec!["Image without text layer (OCR disabled)".to_string()],
+                processed_image_path: None,
+                page_segmentation_mode: None,
+                orientation_correction: None,
+                ocr_dpi: None,
+            }),
+            _ => self.extract_text_from_local_path(&local_path, mime_type, settings, None).await,
+        }
+    }
+
+    /// Read a PDF's text layer with pdftotext, dropping pages that only contain images
+    async fn extract_pdf_text_layer(&self, file_path: &str) -> Result<OcrResult> {
+        let start_time = std::time::Instant::now();
+        let output = live_log::output(
+            tokio::process::Command::new("pdftotext")
+                .arg("-layout")
+                .arg(file_path)
+                .arg("-"),
+            "pdftotext",
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to run pdftotext (is poppler-utils installed?): {}", e))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "pdftotext failed for '{}': {}",
+                file_path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let layer = PdfTextLayer::from_pdftotext_output(&String::from_utf8_lossy(&output.stdout));
+        if layer.skipped_pages > 0 {
+            info!("Skipped {} of {} pages without a text layer in '{}'",
+                  layer.skipped_pages, layer.pages_with_text + layer.skipped_pages, file_path);
+        }
+
+        let text = Self::remove_null_bytes(&layer.text);
+        let word_count = self.count_words_safely(&text);
+        Ok(OcrResult {
+            text,
+            confidence: if word_count > 0 { 95.0 } else { 0.0 },
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+            word_count,
+            preprocessing_applied: vec!["PDF text layer only (pdftotext)".to_string()],
+            processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
+        })
+    }
+
+    /// Returns a local filesystem path for a stored file. Files in a remote storage
+    /// backend (s3://...) or compressed at rest must be written to a local temp file
+    /// before the extractors (which read the local FS) run; the returned guard
+    /// removes it when dropped.
+    async fn materialize_local_file(&self, file_path: &str) -> Result<(String, Option<FileCleanupGuard>)> {
+        if file_path.starts_with("s3://") || crate::storage::compression::is_compressed_path(file_path) {
             let data = self.file_service.read_file(file_path).await?;
-            let extension = std::path::Path::new(file_path)
+            let extension = std::path::Path::new(crate::storage::compression::strip_compression_suffix(file_path))
                 .extension()
                 .and_then(|e| e.to_str())
                 .unwrap_or("bin");
             tokio::fs::create_dir_all(&self.temp_dir).await?;
-            let temp_path = format!(
-                "{}/ocr_download_{}.{}",
-                self.temp_dir,
-                uuid::Uuid::new_v4(),
-                extension
+            let temp_path = temp_files::temp_path(
+                &self.temp_dir,
+                &format!("ocr_download_{}.{}", uuid::Uuid::new_v4(), extension),
             );
             tokio::fs::write(&temp_path, &data).await?;
-            let _cleanup = FileCleanupGuard::new(&temp_path);
-            return self
-                .extract_text_from_local_path(&temp_path, mime_type, settings, progress_callback)
-                .await;
+            let cleanup = FileCleanupGuard::new(&temp_path);
+            return Ok((temp_path, Some(cleanup)));
         }
 
         let resolved_path = self.resolve_file_path(file_path).await?;
-        self.extract_text_from_local_path(&resolved_path, mime_type, settings, progress_callback)
-            .await
+        Ok((resolved_path, None))
+    }
+
+    /// Quick, low-DPI OCR of the first `max_pages` pages of a PDF, stored as preview
+    /// text while full-quality OCR runs. Returns None when the PDF has no more pages
+    /// than the preview would cover, since full OCR won't take meaningfully longer.
+    #[cfg(feature = "ocr")]
+    pub async fn extract_pdf_preview(&self, file_path: &str, max_pages: usize, settings: &Settings) -> Result<Option<OcrResult>> {
+        let start_time = std::time::Instant::now();
+        if max_pages == 0 {
+            return Ok(None);
+        }
+
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        let page_count = self.get_pdf_page_count(&local_path).await?;
+        if page_count <= max_pages {
+            debug!("PDF '{}' has {} pages, skipping {}-page preview", file_path, page_count, max_pages);
+            return Ok(None);
+        }
+
+        info!("Generating quick OCR preview of {}/{} pages for: {}", max_pages, page_count, file_path);
+        let last_page = max_pages.to_string();
+
+        // Use the existing text layer for the preview pages when there is one
+        let pdftotext_output = live_log::output(
+            tokio::process::Command::new("pdftotext")
+                .arg("-layout")
+                .arg("-f").arg("1")
+                .arg("-l").arg(&last_page)
+                .arg(&local_path)
+                .arg("-"),
+            "pdftotext",
+        ).await;
+        if let Ok(output) = pdftotext_output {
+            if output.status.success() {
+                let text = Self::remove_null_bytes(String::from_utf8_lossy(&output.stdout).trim());
+                let word_count = self.count_words_safely(&text);
+                if word_count > 5 {
+                    return Ok(Some(OcrResult {
+                        text,
+                        confidence: 95.0,
+                        processing_time_ms: start_time.elapsed().as_millis() as u64,
+                        word_count,
+                        preprocessing_applied: vec![format!("Quick preview: PDF text extraction ({} pages)", max_pages)],
+                        processed_image_path: None,
+                        page_segmentation_mode: None,
+                        orientation_correction: None,
+                        ocr_dpi: None,
+                    }));
+                }
+            }
+        }
+
+        // Otherwise render the preview pages at low resolution and OCR them without
+        // preprocessing - speed matters more than accuracy here
+        let image_paths: Vec<String> = self
+            .render_pdf_page_range(&local_path, 1, max_pages, Self::PREVIEW_RENDER_DPI, "pdf_preview")
+            .await?
+            .into_iter()
+            .map(|rendered| rendered.path)
+            .collect();
+
+        let mut preview_settings = settings.clone();
+        preview_settings.enable_image_preprocessing = false;
+        preview_settings.save_processed_images = false;
+
+        let mut pages_text = Vec::new();
+        let mut total_confidence = 0.0f32;
+        for image_path in &image_paths {
+            let _page_cleanup = FileCleanupGuard::new(image_path);
+            match self.extract_text_from_image(image_path, &preview_settings).await {
+                Ok(result) => {
+                    if !result.text.is_empty() {
+                        pages_text.push(result.text);
+                    }
+                    total_confidence += result.confidence;
+                }
+                Err(e) => warn!("Failed to OCR preview page {}: {}", image_path, e),
+            }
+        }
+
+        if pages_text.is_empty() {
+            return Ok(None);
+        }
+
+        let text = pages_text.join("\n\n--- Page Break ---\n\n");
+        let word_count = self.count_words_safely(&text);
+        Ok(Some(OcrResult {
+            text,
+            confidence: total_confidence / image_paths.len() as f32,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+            word_count,
+            preprocessing_applied: vec![format!(
+                "Quick preview: {} DPI page OCR ({} pages)", Self::PREVIEW_RENDER_DPI, image_paths.len()
+            )],
+            processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
+        }))
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn extract_pdf_preview(&self, _file_path: &str, _max_pages: usize, _settings: &Settings) -> Result<Option<OcrResult>> {
+        Ok(None)
+    }
+
+    /// Render pages `first..=last` of a local PDF to PNGs, returned in page order
+    /// with the page each one shows. A page pdftoppm could not render is missing,
+    /// so callers must not count pages from `first`. Callers own the files and
+    /// must remove them.
+    #[cfg(feature = "ocr")]
+    async fn render_pdf_page_range(&self, local_path: &str, first: usize, last: usize, dpi: u32, name_prefix: &str) -> Result<Vec<RenderedPage>> {
+        let temp_name = temp_files::temp_file_name(&format!("{}_{}", name_prefix, uuid::Uuid::new_v4()));
+        let temp_prefix = format!("{}/{}", self.temp_dir, temp_name);
+        tokio::fs::create_dir_all(&self.temp_dir).await?;
+
+        let output = tokio::process::Command::new("pdftoppm")
+            .arg("-png")
+            .arg("-r").arg(dpi.to_string())
+            .arg("-f").arg(first.to_string())
+            .arg("-l").arg(last.to_string())
+            .arg(local_path)
+            .arg(&temp_prefix)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!("pdftoppm failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        // pdftoppm zero-pads page numbers based on the document's page count, so
+        // collect the rendered pages by prefix and read the page from each name
+        let mut rendered = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.temp_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(page) = super::page_range::rendered_page_number(&name, &temp_name) {
+                rendered.push(RenderedPage { page, path: entry.path().to_string_lossy().to_string() });
+            }
+        }
+        rendered.sort_by_key(|rendered| rendered.page);
+
+        if rendered.len() < last + 1 - first {
+            warn!("pdftoppm rendered {} of pages {}-{} of {}", rendered.len(), first, last, local_path);
+        }
+        Ok(rendered)
+    }
+
+    /// OCR only the pages of a PDF selected by a page range spec (`1-3,7,10-12`).
+    /// Nothing is stored; an invalid or out-of-range spec fails with a `PageRangeError`.
+    #[cfg(feature = "ocr")]
+    pub async fn extract_pdf_pages(&self, file_path: &str, page_spec: &str, settings: &Settings) -> Result<PdfPagesOcrResult> {
+        let start_time = std::time::Instant::now();
+        // Reject a malformed spec before fetching the file
+        super::page_range::parse_page_ranges(page_spec)?;
+
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        let page_count = self.get_pdf_page_count(&local_path).await?;
+        let ranges = super::page_range::resolve_page_ranges(page_spec, page_count)?;
+
+        let mut pages = Vec::new();
+        for range in ranges {
+            let (first, last) = (*range.start(), *range.end());
+            info!("OCR processing pages {}-{} of {} for: {}", first, last, page_count, file_path);
+
+            let rendered = self.render_pdf_page_range(&local_path, first, last, 300, "pdf_pages").await?;
+            let _page_cleanups: Vec<FileCleanupGuard> = rendered.iter().map(|page| FileCleanupGuard::new(&page.path)).collect();
+            for page in first..=last {
+                // A page that failed to render is returned empty under its own number
+                let Some(image) = rendered.iter().find(|rendered| rendered.page == page) else {
+                    warn!("Page {} of {} could not be rendered", page, file_path);
+                    pages.push(PageOcrText { page, text: String::new(), confidence: 0.0, word_count: 0 });
+                    continue;
+                };
+                match self.extract_text_from_image(&image.path, settings).await {
+                    Ok(result) => pages.push(PageOcrText {
+                        page,
+                        text: Self::remove_null_bytes(&result.text),
+                        confidence: result.confidence,
+                        word_count: result.word_count,
+                    }),
+                    Err(e) => {
+                        warn!("Failed to OCR page {} of {}: {}", page, file_path, e);
+                        pages.push(PageOcrText { page, text: String::new(), confidence: 0.0, word_count: 0 });
+                    }
+                }
+            }
+        }
+
+        Ok(PdfPagesOcrResult {
+            page_count,
+            pages,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+        })
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn extract_pdf_pages(&self, _file_path: &str, _page_spec: &str, _settings: &Settings) -> Result<PdfPagesOcrResult> {
+        Err(anyhow!("OCR feature not enabled"))
+    }
+
+    /// Find the separator pages of a PDF: blank pages, or pages carrying the
+    /// separator barcode. Reading barcodes requires `zbarimg` (zbar-tools).
+    #[cfg(feature = "ocr")]
+    pub async fn find_separator_pages(&self, file_path: &str, separator: &SeparatorKind) -> Result<SeparatorScan> {
+        use super::document_split::{has_separator_barcode, is_blank_page, BARCODE_DETECTION_DPI, BLANK_DETECTION_DPI};
+
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        let page_count = self.get_pdf_page_count(&local_path).await?;
+        let dpi = match separator {
+            SeparatorKind::BlankPage => BLANK_DETECTION_DPI,
+            SeparatorKind::Barcode(_) => BARCODE_DETECTION_DPI,
+        };
+        let rendered = self.render_pdf_page_range(&local_path, 1, page_count, dpi, "pdf_split").await?;
+        let _page_cleanups: Vec<FileCleanupGuard> = rendered.iter().map(|page| FileCleanupGuard::new(&page.path)).collect();
+
+        let mut separator_pages = Vec::new();
+        for RenderedPage { page, path: image_path } in &rendered {
+            let is_separator = match separator {
+                SeparatorKind::BlankPage => {
+                    let page = image::open(image_path)?.to_luma8();
+                    is_blank_page(page.as_raw(), page.width() as usize, page.height() as usize)
+                }
+                SeparatorKind::Barcode(value) => {
+                    // zbarimg exits non-zero when a page has no barcode, so only its output counts
+                    let output = tokio::process::Command::new("zbarimg")
+                        .arg("--quiet")
+                        .arg("--raw")
+                        .arg(image_path)
+                        .output()
+                        .await
+                        .map_err(|e| anyhow!("zbarimg is required to read separator barcodes: {}", e))?;
+                    has_separator_barcode(&String::from_utf8_lossy(&output.stdout), value)
+                }
+            };
+            if is_separator {
+                separator_pages.push(*page);
+            }
+        }
+
+        debug!("Found separator pages {:?} in {} ({} pages)", separator_pages, file_path, page_count);
+        Ok(SeparatorScan { page_count, separator_pages })
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn find_separator_pages(&self, _file_path: &str, _separator: &SeparatorKind) -> Result<SeparatorScan> {
+        Err(anyhow!("OCR feature not enabled"))
+    }
+
+    /// Compare each page of a PDF with the same page of its previous version.
+    /// Returns None when the versions have different page counts.
+    #[cfg(feature = "ocr")]
+    pub async fn diff_pdf_pages(&self, previous_path: &str, current_path: &str) -> Result<Option<Vec<PageDiff>>> {
+        use super::incremental::{compare_page_renders, PAGE_DIFF_DPI};
+
+        let (previous_local, _previous_cleanup) = self.materialize_local_file(previous_path).await?;
+        let (current_local, _current_cleanup) = self.materialize_local_file(current_path).await?;
+        let page_count = self.get_pdf_page_count(&current_local).await?;
+        if self.get_pdf_page_count(&previous_local).await? != page_count {
+            return Ok(None);
+        }
+
+        let previous_images = self.render_pdf_page_range(&previous_local, 1, page_count, PAGE_DIFF_DPI, "pdf_diff_prev").await?;
+        let current_images = self.render_pdf_page_range(&current_local, 1, page_count, PAGE_DIFF_DPI, "pdf_diff_curr").await?;
+        let _page_cleanups: Vec<FileCleanupGuard> = previous_images
+            .iter()
+            .chain(&current_images)
+            .map(|page| FileCleanupGuard::new(&page.path))
+            .collect();
+
+        // A page missing from either rendering cannot be compared
+        let mut diffs = Vec::with_capacity(page_count);
+        for page in 1..=page_count {
+            let previous_image = previous_images.iter().find(|rendered| rendered.page == page);
+            let current_image = current_images.iter().find(|rendered| rendered.page == page);
+            let (Some(previous_image), Some(current_image)) = (previous_image, current_image) else {
+                diffs.push(PageDiff::Inconclusive);
+                continue;
+            };
+            let diff = match (image::open(&previous_image.path), image::open(&current_image.path)) {
+                (Ok(previous), Ok(current)) => {
+                    let (previous, current) = (previous.to_luma8(), current.to_luma8());
+                    compare_page_renders(
+                        previous.as_raw(),
+                        (previous.width() as usize, previous.height() as usize),
+                        current.as_raw(),
+                        (current.width() as usize, current.height() as usize),
+                    )
+                }
+                _ => PageDiff::Inconclusive,
+            };
+            diffs.push(diff);
+        }
+
+        debug!("Compared {} pages of {} with its previous version: {:?}", page_count, current_path, diffs);
+        Ok(Some(diffs))
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn diff_pdf_pages(&self, _previous_path: &str, _current_path: &str) -> Result<Option<Vec<PageDiff>>> {
+        Err(anyhow!("OCR feature not enabled"))
+    }
+
+    /// Detect the tables in the text layer of a PDF. Other documents, and PDFs
+    /// without a text layer, have none.
+    #[cfg(feature = "ocr")]
+    pub async fn extract_tables(&self, file_path: &str, mime_type: &str) -> Result<Vec<ExtractedTable>> {
+        if mime_type != "application/pdf" {
+            return Ok(Vec::new());
+        }
+
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        let output = live_log::output(
+            tokio::process::Command::new("pdftotext")
+                .arg("-layout")
+                .arg(&local_path)
+                .arg("-"),
+            "pdftotext",
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to run pdftotext (is poppler-utils installed?): {}", e))?;
+        if !output.status.success() {
+            return Err(anyhow!("pdftotext failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let tables = super::table_extraction::parse_layout_tables(&String::from_utf8_lossy(&output.stdout));
+        debug!("Found {} tables in {}", tables.len(), file_path);
+        Ok(tables)
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn extract_tables(&self, _file_path: &str, _mime_type: &str) -> Result<Vec<ExtractedTable>> {
+        Err(anyhow!("OCR feature not enabled"))
+    }
+
+    /// Decode the barcodes and QR codes of an image, or of every page of a
+    /// PDF. Requires `zbarimg` (zbar-tools).
+    #[cfg(feature = "ocr")]
+    pub async fn decode_barcodes(&self, file_path: &str, mime_type: &str) -> Result<Vec<DecodedBarcode>> {
+        use super::barcode::{parse_zbar_output, BARCODE_DECODE_DPI};
+
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        let (image_paths, _page_cleanups) = if mime_type == "application/pdf" {
+            let page_count = self.get_pdf_page_count(&local_path).await?;
+            let image_paths: Vec<String> = self
+                .render_pdf_page_range(&local_path, 1, page_count, BARCODE_DECODE_DPI, "barcodes")
+                .await?
+                .into_iter()
+                .map(|rendered| rendered.path)
+                .collect();
+            let cleanups: Vec<FileCleanupGuard> = image_paths.iter().map(|path| FileCleanupGuard::new(path)).collect();
+            (image_paths, cleanups)
+        } else if mime_type.starts_with("image/") {
+            (vec![local_path.clone()], Vec::new())
+        } else {
+            return Ok(Vec::new());
+        };
+
+        let mut output = String::new();
+        for image_path in &image_paths {
+            // zbarimg exits non-zero when an image has no barcode, so only its output counts
+            let result = tokio::process::Command::new("zbarimg")
+                .arg("--quiet")
+                .arg(image_path)
+                .output()
+                .await
+                .map_err(|e| anyhow!("zbarimg is required to decode barcodes: {}", e))?;
+            output.push_str(&String::from_utf8_lossy(&result.stdout));
+        }
+
+        let barcodes = parse_zbar_output(&output);
+        debug!("Decoded {} barcodes in {}", barcodes.len(), file_path);
+        Ok(barcodes)
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn decode_barcodes(&self, _file_path: &str, _mime_type: &str) -> Result<Vec<DecodedBarcode>> {
+        Err(anyhow!("OCR feature not enabled"))
+    }
+
+    /// Copy each page range (1-based, inclusive) of a PDF into a PDF of its own,
+    /// without re-rendering the pages. Uses `pdfseparate` and `pdfunite`.
+    #[cfg(feature = "ocr")]
+    pub async fn extract_pdf_page_ranges(&self, file_path: &str, ranges: &[(usize, usize)]) -> Result<Vec<Vec<u8>>> {
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        tokio::fs::create_dir_all(&self.temp_dir).await?;
+        let temp_prefix = temp_files::temp_path(&self.temp_dir, &format!("pdf_part_{}", uuid::Uuid::new_v4()));
+
+        let mut parts = Vec::new();
+        for &(first, last) in ranges {
+            let output = tokio::process::Command::new("pdfseparate")
+                .arg("-f").arg(first.to_string())
+                .arg("-l").arg(last.to_string())
+                .arg(&local_path)
+                .arg(format!("{}_page_%d.pdf", temp_prefix))
+                .output()
+                .await?;
+            let page_paths: Vec<String> = (first..=last).map(|page| format!("{}_page_{}.pdf", temp_prefix, page)).collect();
+            let _page_cleanups: Vec<FileCleanupGuard> = page_paths.iter().map(|path| FileCleanupGuard::new(path)).collect();
+            if !output.status.success() {
+                return Err(anyhow!("pdfseparate failed: {}", String::from_utf8_lossy(&output.stderr)));
+            }
+
+            let part_path = format!("{}_{}-{}.pdf", temp_prefix, first, last);
+            let _part_cleanup = FileCleanupGuard::new(&part_path);
+            let output = tokio::process::Command::new("pdfunite")
+                .args(&page_paths)
+                .arg(&part_path)
+                .output()
+                .await?;
+            if !output.status.success() {
+                return Err(anyhow!("pdfunite failed: {}", String::from_utf8_lossy(&output.stderr)));
+            }
+
+            parts.push(tokio::fs::read(&part_path).await?);
+        }
+
+        Ok(parts)
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn extract_pdf_page_ranges(&self, _file_path: &str, _ranges: &[(usize, usize)]) -> Result<Vec<Vec<u8>>> {
+        Err(anyhow!("OCR feature not enabled"))
+    }
+
+    /// OCR only the area of an image, or of one PDF page, inside `region`.
+    /// Nothing is stored; a region outside the image fails with a `RegionError`
+    /// and a missing PDF page with a `PageRangeError`.
+    #[cfg(feature = "ocr")]
+    pub async fn extract_region(&self, file_path: &str, mime_type: &str, page: Option<usize>, region: &super::region::OcrRegion, settings: &Settings) -> Result<RegionOcrResult> {
+        let start_time = std::time::Instant::now();
+        // Reject a malformed region before fetching the file
+        region.validate()?;
+
+        let (local_path, _cleanup) = self.materialize_local_file(file_path).await?;
+        let (image_path, _page_cleanup) = if mime_type == "application/pdf" {
+            let page = page.unwrap_or(1);
+            let page_count = self.get_pdf_page_count(&local_path).await?;
+            if page == 0 || page > page_count {
+                return Err(super::page_range::PageRangeError::OutOfRange { page, page_count }.into());
+            }
+            let rendered = self.render_pdf_page_range(&local_path, page, page, 300, "pdf_region").await?;
+            let image_path = rendered
+                .into_iter()
+                .find(|rendered| rendered.page == page)
+                .map(|rendered| rendered.path)
+                .ok_or_else(|| anyhow!("pdftoppm produced no image for page {}", page))?;
+            let cleanup = FileCleanupGuard::new(&image_path);
+            (image_path, Some(cleanup))
+        } else if mime_type.starts_with("image/") {
+            if let Some(page) = page.filter(|page| *page != 1) {
+                return Err(super::page_range::PageRangeError::OutOfRange { page, page_count: 1 }.into());
+            }
+            (local_path, None)
+        } else {
+            return Err(anyhow!("Region OCR is only supported for images and PDFs, not {}", mime_type));
+        };
+
+        let img = image::open(&image_path)?;
+        let (image_width, image_height) = img.dimensions();
+        let rect = region.to_pixel_rect(image_width, image_height)?;
+        info!(
+            "OCR processing region {}x{} at ({}, {}) of {}x{} image for: {}",
+            rect.width, rect.height, rect.x, rect.y, image_width, image_height, file_path
+        );
+
+        tokio::fs::create_dir_all(&self.temp_dir).await?;
+        let crop_path = temp_files::temp_path(&self.temp_dir, &format!("ocr_region_{}.png", uuid::Uuid::new_v4()));
+        img.crop_imm(rect.x, rect.y, rect.width, rect.height).save(&crop_path)?;
+        let _crop_cleanup = FileCleanupGuard::new(&crop_path);
+
+        // Coordinates refer to the page as stored, so a small crop must not be rotated
+        let mut region_settings = settings.clone();
+        region_settings.ocr_detect_orientation = false;
+        let result = self.extract_text_from_image(&crop_path, &region_settings).await?;
+
+        Ok(RegionOcrResult {
+            image_width,
+            image_height,
+            text: Self::remove_null_bytes(&result.text),
+            confidence: result.confidence,
+            word_count: result.word_count,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+        })
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn extract_region(&self, _file_path: &str, _mime_type: &str, _page: Option<usize>, _region: &super::region::OcrRegion, _settings: &Settings) -> Result<RegionOcrResult> {
+        Err(anyhow!("OCR feature not enabled"))
+    }
+
+    /// OCR an uploaded image with `settings` without storing anything. Returns the result
+    /// and, when `include_processed_image` is set, the preprocessed image as PNG.
+    /// Everything is written to a scratch directory that is removed before returning.
+    #[cfg(feature = "ocr")]
+    pub async fn preview_image_ocr(&self, image_data: &[u8], settings: &Settings, include_processed_image: bool) -> Result<(OcrResult, Option<Vec<u8>>)> {
+        let invalid_format = |e: image::ImageError| super::error::OcrError::InvalidImageFormat { details: e.to_string() };
+        let format = image::guess_format(image_data).map_err(invalid_format)?;
+        let extension = format.extensions_str().first().copied().unwrap_or("img");
+
+        // Reading the header is enough to turn away huge images before decoding them
+        let (width, height) = image::ImageReader::with_format(std::io::Cursor::new(image_data), format)
+            .into_dimensions()
+            .map_err(invalid_format)?;
+        if width > Self::MAX_PREVIEW_DIMENSION || height > Self::MAX_PREVIEW_DIMENSION {
+            return Err(super::error::OcrError::ImageTooLarge {
+                width,
+                height,
+                max_width: Self::MAX_PREVIEW_DIMENSION,
+                max_height: Self::MAX_PREVIEW_DIMENSION,
+            }
+            .into());
+        }
+
+        let work_dir = temp_files::temp_path(&self.temp_dir, &format!("ocr_preview_{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&work_dir).await?;
+        let _work_dir_cleanup = DirCleanupGuard { dir_path: work_dir.clone() };
+
+        let input_path = format!("{}/input.{}", work_dir, extension);
+        tokio::fs::write(&input_path, image_data).await?;
+
+        let scratch_service = EnhancedOcrService {
+            temp_dir: work_dir.clone(),
+            file_service: self.file_service.clone(),
+            max_pdf_size: self.max_pdf_size,
+            max_office_document_size: self.max_office_document_size,
+            ocr_timeout_seconds: self.ocr_timeout_seconds,
+        };
+        // Keep the processed image until it has been read back; the scratch dir goes anyway
+        let mut preview_settings = settings.clone();
+        preview_settings.save_processed_images = true;
+        let mut result = scratch_service.extract_text_from_image(&input_path, &preview_settings).await?;
+
+        let processed_image = match result.processed_image_path.take() {
+            Some(path) if include_processed_image => Some(tokio::fs::read(&path).await?),
+            _ => None,
+        };
+        Ok((result, processed_image))
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn preview_image_ocr(&self, _image_data: &[u8], _settings: &Settings, _include_processed_image: bool) -> Result<(OcrResult, Option<Vec<u8>>)> {
+        Err(anyhow!("OCR feature not enabled"))
     }
 
     /// Run the type-specific extractors against a path on the local filesystem.
@@ -1634,7 +2714,7 @@ impl EnhancedOcrService {
                     Err(anyhow::anyhow!("OCR feature not enabled"))
                 }
             }
-            "text/plain" => {
+            "text/plain" | "text/markdown" => {
                 let start_time = std::time::Instant::now();
                 
                 // Check file size before loading into memory
@@ -1651,7 +2731,8 @@ impl EnhancedOcrService {
                     ));
                 }
                 
-                let text = tokio::fs::read_to_string(&resolved_path).await?;
+                // Text files aren't always UTF-8; keep what decodes instead of failing the job
+                let text = String::from_utf8_lossy(&tokio::fs::read(&resolved_path).await?).into_owned();
                 
                 // Only remove null bytes - preserve all original formatting
                 let cleaned_text = Self::remove_null_bytes(&text);
@@ -1675,6 +2756,9 @@ impl EnhancedOcrService {
                     word_count,
                     preprocessing_applied: vec!["Plain text read".to_string()],
                     processed_image_path: None, // No image processing for plain text
+                    page_segmentation_mode: None,
+                    orientation_correction: None,
+                    ocr_dpi: None,
                 })
             }
             // Handle Office document formats
@@ -1871,34 +2955,16 @@ fn is_valid_pdf(data: &[u8]) -> bool {
 }
 
 impl EnhancedOcrService {
-    /// Static version of configure_tesseract for use in spawn_blocking
+    /// Static version of configure_tesseract for use in spawn_blocking, applied to
+    /// an instance already initialized for the settings' languages
     #[cfg(feature = "ocr")]
-    fn configure_tesseract_static(image_path: &str, settings: &Settings) -> Result<Tesseract> {
-        let language_combination = Self::build_language_combination_static(settings);
-        let mut tesseract = Tesseract::new(None, Some(&language_combination))?;
-        
-        // Set the image
-        tesseract = tesseract.set_image(image_path)?;
+    fn configure_tesseract_static(tesseract: Tesseract, image_path: &str, settings: &Settings) -> Result<(Tesseract, i32)> {
+        // Set the image, replacing the previous job's image and results
+        let mut tesseract = tesseract.set_image(image_path)?;
         
-        // Configure Page Segmentation Mode (PSM)
-        let psm = match settings.ocr_page_segmentation_mode {
-            0 => PageSegMode::PsmOsdOnly,
-            1 => PageSegMode::PsmAutoOsd,
-            2 => PageSegMode::PsmAutoOnly,
-            3 => PageSegMode::PsmAuto,
-            4 => PageSegMode::PsmSingleColumn,
-            5 => PageSegMode::PsmSingleBlockVertText,
-            6 => PageSegMode::PsmSingleBlock,
-            7 => PageSegMode::PsmSingleLine,
-            8 => PageSegMode::PsmSingleWord,
-            9 => PageSegMode::PsmCircleWord,
-            10 => PageSegMode::PsmSingleChar,
-            11 => PageSegMode::PsmSparseText,
-            12 => PageSegMode::PsmSparseTextOsd,
-            13 => PageSegMode::PsmRawLine,
-            _ => PageSegMode::PsmAuto, // Default fallback
-        };
-        tesseract.set_page_seg_mode(psm);
+        // Configure Page Segmentation Mode (PSM), picking one from the image when auto PSM is enabled
+        let psm_mode = Self::resolve_page_segmentation_mode(image_path, settings);
+        tesseract.set_page_seg_mode(Self::page_seg_mode_from_setting(psm_mode));
         
         // Configure OCR Engine Mode (OEM)  
         let _oem = match settings.ocr_engine_mode {
@@ -1909,7 +2975,7 @@ impl EnhancedOcrService {
             _ => OcrEngineMode::Default, // Default fallback
         };
         
-        Ok(tesseract)
+        Ok((tesseract, psm_mode))
     }
     
     /// Static version of calculate_overall_confidence for use in spawn_blocking
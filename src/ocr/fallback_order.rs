@@ -0,0 +1,181 @@
+//! Order in which text extraction methods are tried.
+//!
+//! Some file types can be read several ways, each tried in turn until one
+//! extracts text: legacy `.doc` files with external tools, and PDFs from
+//! their text layer, by OCR of rendered pages, or with ocrmypdf. The server
+//! order of each [`FallbackStrategy`] is configured with environment
+//! variables, and users can override it in their settings; leaving a method
+//! out stops it being tried at all. With auto ordering, methods that have
+//! extracted the most documents according to the
+//! [fallback statistics](super::fallback_stats) are tried first, and the
+//! configured order only breaks ties.
+
+use std::sync::OnceLock;
+
+use super::fallback_stats::FallbackStatsResponse;
+use crate::config::Config;
+use crate::models::Settings;
+
+/// Tools able to read legacy Word files, in their default order
+pub const LEGACY_DOC_TOOLS: &[&str] = &["antiword", "catdoc", "wvText"];
+
+/// Ways of reading a PDF, in their default order: its text layer, Tesseract
+/// on pages rendered by pdftoppm, and ocrmypdf
+pub const PDF_EXTRACTION_METHODS: &[&str] = &["pdftotext", "pdftoppm", "ocrmypdf"];
+
+/// A file type whose extraction methods are tried in turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackStrategy {
+    /// Legacy Word (`.doc`) files
+    LegacyDoc,
+    /// PDFs
+    Pdf,
+}
+
+impl FallbackStrategy {
+    pub const ALL: [FallbackStrategy; 2] = [FallbackStrategy::LegacyDoc, FallbackStrategy::Pdf];
+
+    /// The methods able to extract this file type, in their default order
+    pub fn methods(self) -> &'static [&'static str] {
+        match self {
+            FallbackStrategy::LegacyDoc => LEGACY_DOC_TOOLS,
+            FallbackStrategy::Pdf => PDF_EXTRACTION_METHODS,
+        }
+    }
+
+    /// The user setting overriding the order
+    pub fn setting_name(self) -> &'static str {
+        match self {
+            FallbackStrategy::LegacyDoc => "doc_extraction_order",
+            FallbackStrategy::Pdf => "pdf_extraction_order",
+        }
+    }
+
+    /// Parses a comma-separated order such as `catdoc,antiword`. Names are
+    /// matched case-insensitively; unknown, repeated or missing methods are
+    /// rejected.
+    pub fn parse_order(self, value: &str) -> Result<Vec<String>, String> {
+        let methods = self.methods();
+        let mut order: Vec<String> = Vec::new();
+        for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let method = methods
+                .iter()
+                .find(|method| method.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("unknown method '{}' (expected {})", name, methods.join(", ")))?;
+            if order.iter().any(|listed| listed == method) {
+                return Err(format!("method '{}' is listed twice", method));
+            }
+            order.push(method.to_string());
+        }
+        if order.is_empty() {
+            return Err("at least one method is required".to_string());
+        }
+        Ok(order)
+    }
+
+    fn default_order(self) -> Vec<String> {
+        self.methods().iter().map(|method| method.to_string()).collect()
+    }
+}
+
+/// How the extraction methods of every strategy are ordered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackConfig {
+    /// Legacy Word tools to try, first to last
+    pub doc_tool_order: Vec<String>,
+    /// PDF extraction methods to try, first to last
+    pub pdf_method_order: Vec<String>,
+    /// Try the methods with the most successful extractions first
+    pub reorder_by_success: bool,
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        Self {
+            doc_tool_order: FallbackStrategy::LegacyDoc.default_order(),
+            pdf_method_order: FallbackStrategy::Pdf.default_order(),
+            reorder_by_success: false,
+        }
+    }
+}
+
+impl FallbackConfig {
+    /// The server order configured with `OFFICE_DOC_TOOL_ORDER`,
+    /// `PDF_EXTRACTION_ORDER` and `EXTRACTION_AUTO_ORDER`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            doc_tool_order: config.office_doc_tool_order.clone(),
+            pdf_method_order: config.pdf_extraction_order.clone(),
+            reorder_by_success: config.extraction_auto_order,
+        }
+    }
+
+    /// This order with the user's overrides applied. Settings are validated
+    /// when saved, so an order that no longer parses is logged and ignored.
+    pub fn with_settings(mut self, settings: &Settings) -> Self {
+        for strategy in FallbackStrategy::ALL {
+            let value = match strategy {
+                FallbackStrategy::LegacyDoc => settings.doc_extraction_order.as_deref(),
+                FallbackStrategy::Pdf => settings.pdf_extraction_order.as_deref(),
+            };
+            let Some(value) = value else { continue };
+            match strategy.parse_order(value) {
+                Ok(order) => *self.order_mut(strategy) = order,
+                Err(e) => tracing::warn!(
+                    "Ignoring {} '{}' of user {}: {}",
+                    strategy.setting_name(), value, settings.user_id, e
+                ),
+            }
+        }
+        if let Some(reorder_by_success) = settings.extraction_auto_order {
+            self.reorder_by_success = reorder_by_success;
+        }
+        self
+    }
+
+    /// The configured order of `strategy`
+    pub fn order(&self, strategy: FallbackStrategy) -> &[String] {
+        match strategy {
+            FallbackStrategy::LegacyDoc => &self.doc_tool_order,
+            FallbackStrategy::Pdf => &self.pdf_method_order,
+        }
+    }
+
+    fn order_mut(&mut self, strategy: FallbackStrategy) -> &mut Vec<String> {
+        match strategy {
+            FallbackStrategy::LegacyDoc => &mut self.doc_tool_order,
+            FallbackStrategy::Pdf => &mut self.pdf_method_order,
+        }
+    }
+
+    /// The methods to try for the next file of `strategy`. Reordering by
+    /// success is stable, so methods that extracted equally many documents,
+    /// such as methods never tried yet, keep their configured order.
+    pub fn effective_order(&self, strategy: FallbackStrategy, stats: &FallbackStatsResponse) -> Vec<String> {
+        let mut order = self.order(strategy).to_vec();
+        if self.reorder_by_success {
+            let successes = |name: &String| {
+                stats
+                    .methods
+                    .iter()
+                    .find(|method| &method.method == name)
+                    .map_or(0, |method| method.successes)
+            };
+            order.sort_by_key(|name| std::cmp::Reverse(successes(name)));
+        }
+        order
+    }
+}
+
+static FALLBACK_CONFIG: OnceLock<FallbackConfig> = OnceLock::new();
+
+/// Installs the process-wide order used by every extractor created
+/// afterwards. Returns false if an order was already installed.
+pub fn install_fallback_config(config: FallbackConfig) -> bool {
+    FALLBACK_CONFIG.set(config).is_ok()
+}
+
+/// The process-wide order, or the default one if none was installed
+pub fn installed_fallback_config() -> FallbackConfig {
+    FALLBACK_CONFIG.get().cloned().unwrap_or_default()
+}
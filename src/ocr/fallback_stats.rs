@@ -0,0 +1,109 @@
+//! Success and failure counts of the text extraction methods.
+//!
+//! DOCX and XLSX files are read by parsing their XML, legacy Word files by
+//! trying antiword, catdoc and wvText in turn until one works, and PDFs by
+//! trying pdftotext, pdftoppm and ocrmypdf in turn. Counting how each method
+//! fares shows which tools actually work for the documents a server sees, and
+//! which are missing or failing, so the fallback order can be tuned. Counts are
+//! kept in memory since the process started or was last reset.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Attempts of one extraction method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionMethodStats {
+    /// `docx`, `xlsx`, a legacy Word tool, e.g. `antiword`, or a PDF method, e.g. `pdftotext`
+    pub method: String,
+    pub successes: u64,
+    pub failures: u64,
+    /// Average time of all attempts, successful or not
+    pub avg_processing_time_ms: Option<f64>,
+}
+
+/// Attempts of every extraction method used since `since`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FallbackStatsResponse {
+    pub since: DateTime<Utc>,
+    /// Ordered by method name
+    pub methods: Vec<ExtractionMethodStats>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct MethodCounters {
+    successes: u64,
+    failures: u64,
+    total_time_ms: u64,
+}
+
+#[derive(Debug)]
+struct Counters {
+    since: DateTime<Utc>,
+    methods: BTreeMap<String, MethodCounters>,
+}
+
+/// Per-method counts of extraction attempts
+#[derive(Debug)]
+pub struct FallbackStats {
+    counters: Mutex<Counters>,
+}
+
+impl Default for FallbackStats {
+    fn default() -> Self {
+        Self {
+            counters: Mutex::new(Counters { since: Utc::now(), methods: BTreeMap::new() }),
+        }
+    }
+}
+
+impl FallbackStats {
+    /// Counts one attempt of `method` that took `elapsed`
+    pub fn record(&self, method: &str, succeeded: bool, elapsed: Duration) {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let method = counters.methods.entry(method.to_string()).or_default();
+        if succeeded {
+            method.successes += 1;
+        } else {
+            method.failures += 1;
+        }
+        method.total_time_ms += elapsed.as_millis() as u64;
+    }
+
+    pub fn snapshot(&self) -> FallbackStatsResponse {
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let methods = counters
+            .methods
+            .iter()
+            .map(|(method, counts)| {
+                let attempts = counts.successes + counts.failures;
+                ExtractionMethodStats {
+                    method: method.clone(),
+                    successes: counts.successes,
+                    failures: counts.failures,
+                    avg_processing_time_ms: (attempts > 0).then(|| counts.total_time_ms as f64 / attempts as f64),
+                }
+            })
+            .collect();
+        FallbackStatsResponse { since: counters.since, methods }
+    }
+
+    /// Clears all counts and starts counting anew
+    pub fn reset(&self) {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        counters.methods.clear();
+        counters.since = Utc::now();
+    }
+}
+
+static FALLBACK_STATS: Lazy<FallbackStats> = Lazy::new(FallbackStats::default);
+
+/// The process-wide counts the Office extractor records into
+pub fn fallback_stats() -> &'static FallbackStats {
+    &FALLBACK_STATS
+}
@@ -0,0 +1,207 @@
+//! YAML front-matter of markdown and text files.
+//!
+//! Notes exported from Obsidian, Jekyll, Hugo and the like start with a block
+//! such as:
+//!
+//! ```text
+//! ---
+//! title: Meeting notes
+//! tags: [project, "q3 planning"]
+//! ---
+//! ```
+//!
+//! The block is split off so only the body is indexed; its fields are kept as
+//! document metadata and its tags become labels. Only the flat subset of YAML
+//! these exports use is understood: scalars, inline lists and block lists.
+//! Anything else (a text that merely starts with a `---` rule, nested maps) is
+//! not treated as front-matter, and the file is indexed as it is.
+
+use serde_json::{Map, Number, Value};
+
+/// MIME types whose text may start with front-matter
+pub const FRONT_MATTER_MIME_TYPES: [&str; 2] = ["text/plain", "text/markdown"];
+
+/// Keys whose values become labels, in the order their tags are collected
+const TAG_KEYS: [&str; 4] = ["tags", "tag", "keywords", "labels"];
+
+/// Longest label name created from a tag
+const MAX_TAG_LENGTH: usize = 100;
+
+/// Parsed front-matter of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontMatter {
+    /// Every field, kept in the document's `source_metadata` under `front_matter`
+    pub fields: Map<String, Value>,
+    /// Tags for labels, without `#` and duplicates
+    pub tags: Vec<String>,
+}
+
+pub fn is_front_matter_mime(mime_type: &str) -> bool {
+    FRONT_MATTER_MIME_TYPES.contains(&mime_type)
+}
+
+/// Splits `text` into its front-matter and the body after it, or None if it
+/// doesn't start with a front-matter block this parser understands
+pub fn split_front_matter(text: &str) -> Option<(FrontMatter, &str)> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut lines = text.split_inclusive('\n');
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+
+    let mut block = Vec::new();
+    let mut consumed = text.find('\n')? + 1;
+    let mut closed = false;
+    for line in lines {
+        consumed += line.len();
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line == "---" || line == "..." {
+            closed = true;
+            break;
+        }
+        block.push(line);
+    }
+    if !closed {
+        return None;
+    }
+
+    let fields = parse_block(&block)?;
+    let tags = collect_tags(&fields);
+    Some((FrontMatter { fields, tags }, text[consumed..].trim_start_matches(['\r', '\n'])))
+}
+
+/// Fields of a front-matter block, or None if a line isn't part of the supported subset
+fn parse_block(lines: &[&str]) -> Option<Map<String, Value>> {
+    let mut fields = Map::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if line.starts_with([' ', '\t', '-']) {
+            return None;
+        }
+
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim().trim_matches(['"', '\'']);
+        // Prose such as `Note to self: ...` is not a key
+        if key.is_empty() || key.split_whitespace().count() > 3 {
+            return None;
+        }
+        let value = value.trim();
+
+        let parsed = if value.is_empty() {
+            // A block list, or an empty value
+            let mut items = Vec::new();
+            while let Some(item) = lines.get(index).map(|line| line.trim_start()) {
+                if let Some(item) = item.strip_prefix('-') {
+                    items.push(parse_scalar(item.trim()));
+                    index += 1;
+                } else if item.is_empty() {
+                    index += 1;
+                } else if lines[index].starts_with([' ', '\t']) {
+                    // Nested maps aren't supported
+                    return None;
+                } else {
+                    break;
+                }
+            }
+            if items.is_empty() {
+                Value::Null
+            } else {
+                Value::Array(items)
+            }
+        } else if let Some(inner) = value.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            Value::Array(split_inline_list(inner).into_iter().map(parse_scalar).collect())
+        } else {
+            parse_scalar(value)
+        };
+        fields.insert(key.to_string(), parsed);
+    }
+    (!fields.is_empty()).then_some(fields)
+}
+
+/// Items of an inline list, without splitting inside quotes
+fn split_inline_list(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (index, c) in inner.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            (',', None) => {
+                items.push(inner[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(inner[start..].trim());
+    items.into_iter().filter(|item| !item.is_empty()).collect()
+}
+
+fn parse_scalar(value: &str) -> Value {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|rest| rest.strip_suffix(quote)) {
+            return Value::String(inner.to_string());
+        }
+    }
+    // An unquoted value ends at a comment
+    let value = match value.find(" #") {
+        Some(index) => value[..index].trim_end(),
+        None => value,
+    };
+    match value {
+        "" | "~" | "null" => Value::Null,
+        "true" | "True" | "yes" => Value::Bool(true),
+        "false" | "False" | "no" => Value::Bool(false),
+        _ => {
+            if let Ok(number) = value.parse::<i64>() {
+                Value::Number(number.into())
+            } else if let Some(number) = value.parse::<f64>().ok().and_then(Number::from_f64) {
+                Value::Number(number)
+            } else {
+                Value::String(value.to_string())
+            }
+        }
+    }
+}
+
+/// Tags from the tag keys, whether lists or comma or space separated strings
+fn collect_tags(fields: &Map<String, Value>) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let tag_fields = TAG_KEYS
+        .iter()
+        .flat_map(|tag_key| fields.iter().filter(move |(key, _)| key.eq_ignore_ascii_case(tag_key)));
+    for (_, value) in tag_fields {
+        let candidates: Vec<String> = match value {
+            Value::Array(items) => items.iter().filter_map(scalar_text).collect(),
+            Value::String(text) if text.contains(',') => text.split(',').map(str::to_string).collect(),
+            Value::String(text) => text.split_whitespace().map(str::to_string).collect(),
+            other => scalar_text(other).into_iter().collect(),
+        };
+        for candidate in candidates {
+            let tag = candidate.trim().trim_start_matches('#').trim();
+            // Label names can't contain commas, which separate label filters in searches
+            if tag.is_empty() || tag.contains(',') || tag.chars().count() > MAX_TAG_LENGTH {
+                continue;
+            }
+            if !tags.iter().any(|known| known.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+    }
+    tags
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,119 @@
+//! Handwriting OCR through an external HTTP service
+//!
+//! The file is POSTed as multipart form data with a `file` part and a
+//! `languages` part (comma-separated Tesseract language codes from the user's
+//! settings). The service answers with JSON: `{"text": "...", "confidence": 87.5}`,
+//! where `confidence` (0-100) is optional.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::config::Config;
+use crate::models::Settings;
+use crate::ocr::engine::OcrEngine;
+use crate::ocr::enhanced::OcrResult;
+
+/// Confidence used when the service doesn't report one
+const UNREPORTED_CONFIDENCE: f32 = 100.0;
+
+#[derive(Debug, Deserialize)]
+pub struct HandwritingOcrResponse {
+    pub text: String,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
+impl HandwritingOcrResponse {
+    pub fn into_ocr_result(self, processing_time_ms: u64) -> OcrResult {
+        let word_count = self.text.split_whitespace().count();
+        OcrResult {
+            text: self.text,
+            confidence: self.confidence.unwrap_or(UNREPORTED_CONFIDENCE).clamp(0.0, 100.0),
+            processing_time_ms,
+            word_count,
+            preprocessing_applied: Vec::new(),
+            processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
+        }
+    }
+}
+
+pub struct HttpHandwritingEngine {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl HttpHandwritingEngine {
+    pub fn new(endpoint: String, api_key: Option<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| anyhow!("Failed to create handwriting OCR client: {}", e))?;
+        Ok(Self { client, endpoint, api_key })
+    }
+
+    /// Builds the engine from configuration. Returns None when no endpoint is set.
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        match &config.handwriting_ocr_endpoint {
+            Some(endpoint) => Ok(Some(Self::new(
+                endpoint.clone(),
+                config.handwriting_ocr_api_key.clone(),
+                Duration::from_secs(config.handwriting_ocr_timeout_seconds),
+            )?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl OcrEngine for HttpHandwritingEngine {
+    async fn recognize(&self, data: Vec<u8>, filename: &str, mime_type: &str, settings: &Settings) -> Result<OcrResult> {
+        let start = Instant::now();
+        let languages = if settings.preferred_languages.is_empty() {
+            settings.ocr_language.clone()
+        } else {
+            settings.preferred_languages.join(",")
+        };
+
+        let file_part = reqwest::multipart::Part::bytes(data)
+            .file_name(filename.to_string())
+            .mime_str(mime_type)
+            .map_err(|e| anyhow!("Invalid MIME type '{}' for handwriting OCR: {}", mime_type, e))?;
+        let form = reqwest::multipart::Form::new()
+            .part("file", file_part)
+            .text("languages", languages);
+
+        let mut request = self.client.post(&self.endpoint).multipart(form);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Handwriting OCR request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Handwriting OCR service returned {}: {}", status, body.trim()));
+        }
+
+        let parsed: HandwritingOcrResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid handwriting OCR response: {}", e))?;
+        let result = parsed.into_ocr_result(start.elapsed().as_millis() as u64);
+        debug!("Handwriting OCR of {} returned {} words in {}ms", filename, result.word_count, result.processing_time_ms);
+        Ok(result)
+    }
+
+    fn name(&self) -> &str {
+        "handwriting"
+    }
+}
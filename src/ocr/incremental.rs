@@ -0,0 +1,119 @@
+//! Incremental OCR of changed files.
+//!
+//! When a source with versioning stores a new version of a PDF, the pages of
+//! both versions are rendered at low resolution and compared. Only the pages
+//! whose renders differ are OCR'd again; the others keep the text of the
+//! previous version. Whenever the comparison can't be trusted (the page count
+//! changed, the previous text isn't split per page, a page couldn't be
+//! compared) the whole document is OCR'd instead.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Resolution pages are rendered at for comparing versions
+pub const PAGE_DIFF_DPI: u32 = 50;
+
+/// Separator placed between the OCR text of consecutive PDF pages
+pub const PAGE_BREAK: &str = "\n\n--- Page Break ---\n\n";
+
+/// Difference in luma above which two pixels count as different, ignoring
+/// anti-aliasing and compression noise
+const PIXEL_DIFF_THRESHOLD: u8 = 48;
+
+/// Share of differing pixels above which a page counts as changed
+const CHANGED_PAGE_MIN_DIFF_RATIO: f64 = 0.0005;
+
+/// Outcome of comparing one page of two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageDiff {
+    Unchanged,
+    Changed,
+    /// The renders couldn't be compared
+    Inconclusive,
+}
+
+/// Compares grayscale renders of the same page of two versions
+pub fn compare_page_renders(
+    previous: &[u8],
+    previous_size: (usize, usize),
+    current: &[u8],
+    current_size: (usize, usize),
+) -> PageDiff {
+    let (width, height) = current_size;
+    if width == 0 || height == 0 || previous.len() < previous_size.0 * previous_size.1 || current.len() < width * height {
+        return PageDiff::Inconclusive;
+    }
+    // A resized page always has new content
+    if previous_size != current_size {
+        return PageDiff::Changed;
+    }
+
+    let pixels = width * height;
+    let differing = previous[..pixels]
+        .iter()
+        .zip(&current[..pixels])
+        .filter(|(a, b)| a.abs_diff(**b) > PIXEL_DIFF_THRESHOLD)
+        .count();
+
+    if differing as f64 / pixels as f64 > CHANGED_PAGE_MIN_DIFF_RATIO {
+        PageDiff::Changed
+    } else {
+        PageDiff::Unchanged
+    }
+}
+
+/// The text of each page of a previous OCR run, or None unless it splits into
+/// exactly `page_count` pages
+pub fn split_page_text(text: &str, page_count: usize) -> Option<Vec<String>> {
+    let pages: Vec<String> = text.split(PAGE_BREAK.trim()).map(|page| page.trim().to_string()).collect();
+    (page_count > 0 && pages.len() == page_count).then_some(pages)
+}
+
+/// The 1-based pages to OCR again, or None when the whole document has to be
+/// OCR'd: a page couldn't be compared, or the previous text doesn't split into
+/// the same number of pages
+pub fn pages_to_reocr(diffs: &[PageDiff], previous_pages: Option<&[String]>) -> Option<Vec<usize>> {
+    if diffs.is_empty() || previous_pages.map(<[String]>::len) != Some(diffs.len()) {
+        return None;
+    }
+    if diffs.contains(&PageDiff::Inconclusive) {
+        return None;
+    }
+    Some(
+        diffs
+            .iter()
+            .enumerate()
+            .filter(|(_, diff)| **diff == PageDiff::Changed)
+            .map(|(index, _)| index + 1)
+            .collect(),
+    )
+}
+
+/// Page range spec (`2,5,7`) for OCRing `pages`
+pub fn page_spec(pages: &[usize]) -> String {
+    pages.iter().map(usize::to_string).collect::<Vec<_>>().join(",")
+}
+
+/// The previous pages with the re-OCR'd ones (1-based page, text) replaced,
+/// joined with page breaks. Empty pages are kept so the text stays split per
+/// page for the next version.
+pub fn merge_page_text(previous_pages: &[String], reocr_pages: &[(usize, String)]) -> String {
+    let mut pages = previous_pages.to_vec();
+    for (page, text) in reocr_pages {
+        if let Some(slot) = page.checked_sub(1).and_then(|index| pages.get_mut(index)) {
+            *slot = text.trim().to_string();
+        }
+    }
+    pages.join(PAGE_BREAK)
+}
+
+/// Record of an incremental OCR run, kept in the document's `source_metadata`
+/// under `incremental_ocr`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncrementalOcrRecord {
+    /// Document holding the previous version of the file
+    pub previous_document_id: Uuid,
+    pub page_count: usize,
+    /// 1-based pages that were OCR'd again; the others kept their previous text
+    pub reocr_pages: Vec<usize>,
+}
@@ -0,0 +1,212 @@
+//! Lightweight language detection for extracted text, based on how often each
+//! language's most common function words appear. Good enough to label whole
+//! documents; not meant for short snippets or mixed-language pages.
+
+use std::collections::HashMap;
+
+use crate::utils::text_search::DEFAULT_SEARCH_LANGUAGE;
+
+/// Prefix of the system labels applied to documents by detected language
+pub const LANGUAGE_LABEL_PREFIX: &str = "lang:";
+
+/// Fewer words than this aren't enough to tell languages apart reliably
+const MIN_WORDS: usize = 20;
+
+/// Share of the words that must be stopwords of the winning language
+const MIN_STOPWORD_RATIO: f64 = 0.08;
+
+/// The winner must have this many times the stopword hits of the runner-up
+const MIN_MARGIN: f64 = 1.5;
+
+struct LanguageProfile {
+    /// Tesseract language code, as used in OCR settings
+    tesseract_code: &'static str,
+    /// ISO 639-1 code, as used in `lang:xx` labels
+    iso_code: &'static str,
+    /// Full-text search language, if PostgreSQL stems this language
+    search_language: Option<&'static str>,
+    stopwords: &'static [&'static str],
+}
+
+const PROFILES: &[LanguageProfile] = &[
+    LanguageProfile {
+        tesseract_code: "eng",
+        iso_code: "en",
+        search_language: Some("english"),
+        stopwords: &[
+            "the", "and", "of", "to", "in", "is", "that", "for", "it", "with", "as", "was", "on",
+            "are", "be", "this", "by", "have", "from", "or", "at", "not", "which", "an", "were",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "deu",
+        iso_code: "de",
+        search_language: Some("german"),
+        stopwords: &[
+            "der", "die", "und", "das", "ist", "nicht", "ein", "eine", "zu", "den", "mit", "sich",
+            "des", "auf", "für", "im", "dem", "auch", "es", "von", "wird", "sind", "oder", "bei",
+            "werden",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "fra",
+        iso_code: "fr",
+        search_language: Some("french"),
+        stopwords: &[
+            "le", "la", "les", "et", "des", "est", "une", "du", "que", "dans", "pour", "qui", "sur",
+            "pas", "au", "avec", "ce", "il", "sont", "par", "aux", "ne", "nous", "vous", "être",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "spa",
+        iso_code: "es",
+        search_language: Some("spanish"),
+        stopwords: &[
+            "el", "la", "los", "las", "y", "que", "del", "en", "un", "una", "por", "con", "para",
+            "es", "se", "al", "lo", "como", "su", "más", "pero", "sus", "está", "son", "también",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "ita",
+        iso_code: "it",
+        search_language: Some("italian"),
+        stopwords: &[
+            "il", "di", "che", "e", "la", "per", "un", "non", "sono", "della", "del", "una", "gli",
+            "con", "alla", "nel", "anche", "questo", "come", "ma", "delle", "dei", "più", "è", "lo",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "por",
+        iso_code: "pt",
+        search_language: Some("portuguese"),
+        stopwords: &[
+            "o", "a", "os", "as", "de", "que", "do", "da", "em", "um", "uma", "para", "com", "não",
+            "no", "na", "por", "mais", "dos", "das", "como", "mas", "ao", "foi", "são",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "nld",
+        iso_code: "nl",
+        search_language: Some("dutch"),
+        stopwords: &[
+            "de", "het", "een", "en", "van", "is", "dat", "niet", "op", "te", "zijn", "voor", "met",
+            "die", "aan", "er", "maar", "om", "ook", "als", "bij", "wordt", "naar", "uit", "worden",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "swe",
+        iso_code: "sv",
+        search_language: None,
+        stopwords: &[
+            "och", "att", "det", "som", "en", "på", "är", "av", "för", "med", "till", "den", "har",
+            "inte", "om", "ett", "var", "jag", "men", "från", "vid", "eller", "så", "kan", "också",
+        ],
+    },
+    LanguageProfile {
+        tesseract_code: "pol",
+        iso_code: "pl",
+        search_language: None,
+        stopwords: &[
+            "i", "w", "nie", "na", "się", "z", "do", "jest", "że", "to", "jak", "co", "ale", "od",
+            "po", "dla", "przez", "tak", "przy", "są", "oraz", "jego", "tym", "czy", "lub",
+        ],
+    },
+];
+
+/// Language detected in a text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedLanguage {
+    pub tesseract_code: &'static str,
+    pub iso_code: &'static str,
+    /// Full-text search language, if the text search configurations cover it
+    pub search_language: Option<&'static str>,
+    /// Fraction of the words that are stopwords of this language
+    pub stopword_ratio: f64,
+}
+
+impl DetectedLanguage {
+    /// Name of the system label applied to documents in this language
+    pub fn label_name(&self) -> String {
+        format!("{}{}", LANGUAGE_LABEL_PREFIX, self.iso_code)
+    }
+}
+
+/// How the language a document is searched in is chosen after OCR
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentLanguagePolicy {
+    /// Use the language detected in the document text when search can stem it
+    pub detect: bool,
+    /// Search language of documents whose language isn't detected
+    pub default_language: String,
+}
+
+impl Default for DocumentLanguagePolicy {
+    fn default() -> Self {
+        Self {
+            detect: true,
+            default_language: DEFAULT_SEARCH_LANGUAGE.to_string(),
+        }
+    }
+}
+
+impl DocumentLanguagePolicy {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            detect: config.detect_document_language,
+            default_language: config.default_document_language.clone(),
+        }
+    }
+
+    /// Search language of a document with `text`
+    pub fn search_language(&self, text: &str) -> &str {
+        self.detect
+            .then(|| detect_language(text).and_then(|language| language.search_language))
+            .flatten()
+            .unwrap_or(self.default_language.as_str())
+    }
+}
+
+/// Detects the dominant language of `text`, or `None` when the text is too short
+/// or no language clearly stands out
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < MIN_WORDS {
+        return None;
+    }
+
+    let mut frequencies: HashMap<&str, usize> = HashMap::new();
+    for word in &words {
+        *frequencies.entry(word.as_str()).or_default() += 1;
+    }
+
+    let mut scores: Vec<(&LanguageProfile, usize)> = PROFILES
+        .iter()
+        .map(|profile| {
+            let hits = profile
+                .stopwords
+                .iter()
+                .map(|stopword| frequencies.get(stopword).copied().unwrap_or(0))
+                .sum();
+            (profile, hits)
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let (best, best_hits) = scores[0];
+    let runner_up_hits = scores.get(1).map_or(0, |(_, hits)| *hits);
+    let stopword_ratio = best_hits as f64 / words.len() as f64;
+    if stopword_ratio < MIN_STOPWORD_RATIO || (best_hits as f64) < runner_up_hits as f64 * MIN_MARGIN {
+        return None;
+    }
+
+    Some(DetectedLanguage {
+        tesseract_code: best.tesseract_code,
+        iso_code: best.iso_code,
+        search_language: best.search_language,
+        stopword_ratio,
+    })
+}
@@ -0,0 +1,241 @@
+//! Downloads missing Tesseract language packs (`<lang>.traineddata`) from the
+//! official tessdata repository. Opt-in via `OCR_AUTO_DOWNLOAD_LANGS`, since
+//! air-gapped installs must not reach out to GitHub.
+
+use anyhow::{anyhow, Context, Result};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+
+use super::health::OcrHealthChecker;
+
+const TESSDATA_REPO: &str = "tesseract-ocr/tessdata";
+const TESSDATA_BRANCH: &str = "main";
+
+/// Git blob SHA-1 of a file, as reported for each file by the GitHub contents API
+pub fn git_blob_sha1(data: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", data.len()).as_bytes());
+    hasher.update(data);
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Extracts the tessdata directory from `tesseract --list-langs` output, whose first
+/// line reads `List of available languages in "/usr/share/tesseract-ocr/5/tessdata/" (3):`
+pub fn parse_tessdata_dir(list_langs_output: &str) -> Option<PathBuf> {
+    let first_line = list_langs_output.lines().next()?;
+    let start = first_line.find('"')? + 1;
+    let end = start + first_line[start..].find('"')?;
+    let dir = &first_line[start..end];
+    (!dir.is_empty()).then(|| PathBuf::from(dir))
+}
+
+/// Splits language combinations such as `eng+deu` into distinct language codes
+pub fn split_languages<'a>(combinations: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for lang in combinations.into_iter().flat_map(|combination| combination.split('+')) {
+        let lang = lang.trim();
+        if !lang.is_empty() && !languages.iter().any(|known| known == lang) {
+            languages.push(lang.to_string());
+        }
+    }
+    languages
+}
+
+/// A language code is a file name in the tessdata repo; reject anything that could
+/// escape the tessdata directory or the download URL
+pub fn is_valid_language_code(lang: &str) -> bool {
+    !lang.is_empty()
+        && lang.len() <= 32
+        && lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Outcome of a language pack check
+#[derive(Debug, Default)]
+pub struct LanguagePackReport {
+    pub downloaded: Vec<String>,
+    pub failed: Vec<(String, String)>,
+}
+
+pub struct LanguagePackDownloader {
+    client: reqwest::Client,
+    tessdata_dir: PathBuf,
+}
+
+impl LanguagePackDownloader {
+    /// Uses `TESSDATA_PREFIX` when set, otherwise the directory Tesseract reports
+    pub fn new() -> Result<Self> {
+        let tessdata_dir = match std::env::var("TESSDATA_PREFIX") {
+            Ok(prefix) if !prefix.trim().is_empty() => PathBuf::from(prefix.trim()),
+            _ => {
+                let output = std::process::Command::new("tesseract")
+                    .arg("--list-langs")
+                    .output()
+                    .context("Failed to run tesseract --list-langs")?;
+                // Tesseract 4 prints the list on stderr, Tesseract 5 on stdout
+                let listing = format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_tessdata_dir(&listing)
+                    .ok_or_else(|| anyhow!("Could not determine the tessdata directory; set TESSDATA_PREFIX"))?
+            }
+        };
+
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("readur/", env!("CARGO_PKG_VERSION")))
+            .timeout(std::time::Duration::from_secs(600))
+            .build()?;
+
+        Ok(Self { client, tessdata_dir })
+    }
+
+    pub fn tessdata_dir(&self) -> &Path {
+        &self.tessdata_dir
+    }
+
+    /// Downloads every language in `essential` and `optional` that Tesseract doesn't have.
+    /// Fails only when an essential language cannot be installed.
+    pub async fn ensure_languages(&self, essential: &[String], optional: &[String]) -> Result<LanguagePackReport> {
+        let installed = OcrHealthChecker::new().get_available_languages().unwrap_or_default();
+        let missing = |langs: &[String]| -> Vec<String> {
+            langs.iter().filter(|lang| !installed.contains(lang)).cloned().collect()
+        };
+        let missing_essential = missing(essential);
+        let missing_optional: Vec<String> = missing(optional)
+            .into_iter()
+            .filter(|lang| !missing_essential.contains(lang))
+            .collect();
+
+        let mut report = LanguagePackReport::default();
+        if missing_essential.is_empty() && missing_optional.is_empty() {
+            info!("All configured OCR languages are installed");
+            return Ok(report);
+        }
+
+        info!(
+            "Downloading missing OCR language packs into {}: {}",
+            self.tessdata_dir.display(),
+            missing_essential.iter().chain(&missing_optional).cloned().collect::<Vec<_>>().join(", ")
+        );
+
+        let checksums = match self.fetch_checksums().await {
+            Ok(checksums) => checksums,
+            Err(e) => {
+                for lang in missing_essential.iter().chain(&missing_optional) {
+                    report.failed.push((lang.clone(), format!("could not fetch checksums: {}", e)));
+                }
+                HashMap::new()
+            }
+        };
+
+        if !checksums.is_empty() {
+            for lang in missing_essential.iter().chain(&missing_optional) {
+                match self.download_language(lang, &checksums).await {
+                    Ok(()) => report.downloaded.push(lang.clone()),
+                    Err(e) => report.failed.push((lang.clone(), e.to_string())),
+                }
+            }
+        }
+
+        for (lang, reason) in &report.failed {
+            if missing_essential.contains(lang) {
+                error!("❌ Failed to install OCR language pack '{}': {}", lang, reason);
+            } else {
+                warn!("⚠️  Failed to install optional OCR language pack '{}': {}", lang, reason);
+            }
+        }
+
+        let failed_essential: Vec<&str> = report
+            .failed
+            .iter()
+            .map(|(lang, _)| lang.as_str())
+            .filter(|lang| missing_essential.iter().any(|essential| essential == lang))
+            .collect();
+        if !failed_essential.is_empty() {
+            return Err(anyhow!(
+                "OCR language pack(s) {} are required by OCR_LANGUAGE but could not be downloaded. \
+                 Install them manually into {} or check network access to github.com",
+                failed_essential.join(", "),
+                self.tessdata_dir.display()
+            ));
+        }
+
+        Ok(report)
+    }
+
+    /// Maps file names in the tessdata repo to their git blob SHA-1
+    async fn fetch_checksums(&self) -> Result<HashMap<String, String>> {
+        #[derive(serde::Deserialize)]
+        struct ContentEntry {
+            name: String,
+            sha: String,
+        }
+
+        let url = format!("https://api.github.com/repos/{}/contents/?ref={}", TESSDATA_REPO, TESSDATA_BRANCH);
+        let entries: Vec<ContentEntry> = self
+            .client
+            .get(&url)
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(entries.into_iter().map(|entry| (entry.name, entry.sha)).collect())
+    }
+
+    async fn download_language(&self, lang: &str, checksums: &HashMap<String, String>) -> Result<()> {
+        if !is_valid_language_code(lang) {
+            return Err(anyhow!("invalid language code"));
+        }
+        let file_name = format!("{}.traineddata", lang);
+        let expected_sha = checksums
+            .get(&file_name)
+            .ok_or_else(|| anyhow!("not available in {}", TESSDATA_REPO))?;
+
+        let url = format!("https://raw.githubusercontent.com/{}/{}/{}", TESSDATA_REPO, TESSDATA_BRANCH, file_name);
+        let mut response = self.client.get(&url).send().await?.error_for_status()?;
+        let total = response.content_length();
+
+        let mut data = Vec::with_capacity(total.unwrap_or(0) as usize);
+        let mut next_report = 25;
+        while let Some(chunk) = response.chunk().await? {
+            data.extend_from_slice(&chunk);
+            if let Some(total) = total.filter(|total| *total > 0) {
+                let percent = data.len() as u64 * 100 / total;
+                if percent >= next_report && percent < 100 {
+                    info!("Downloading {}: {}% ({} / {} bytes)", file_name, percent, data.len(), total);
+                    next_report = (percent / 25 + 1) * 25;
+                }
+            }
+        }
+
+        let actual_sha = git_blob_sha1(&data);
+        if actual_sha != *expected_sha {
+            return Err(anyhow!("checksum mismatch (expected {}, got {})", expected_sha, actual_sha));
+        }
+
+        // Write next to the target and rename, so Tesseract never sees a partial file
+        tokio::fs::create_dir_all(&self.tessdata_dir).await?;
+        let target = self.tessdata_dir.join(&file_name);
+        let partial = self.tessdata_dir.join(format!("{}.part", file_name));
+        tokio::fs::write(&partial, &data)
+            .await
+            .with_context(|| format!("cannot write to {}", self.tessdata_dir.display()))?;
+        if let Err(e) = tokio::fs::rename(&partial, &target).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e.into());
+        }
+
+        info!("✅ Installed OCR language pack {} ({} bytes, checksum verified)", file_name, data.len());
+        Ok(())
+    }
+}
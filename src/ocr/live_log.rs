@@ -0,0 +1,219 @@
+//! Live log of a document's OCR run.
+//!
+//! While a job is processed inside [`capture`], its extraction steps and the
+//! stderr of the tools it runs (ocrmypdf, pdftotext, ...) are broadcast on a
+//! channel keyed by the document, so the OCR debug endpoint can stream them as
+//! they happen. Nothing is kept once the job finishes: a subscriber only sees
+//! what happens after it subscribed, followed by a `finished` event.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Output, Stdio};
+use std::sync::{Mutex, OnceLock};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Events buffered per document before slow subscribers start missing some
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrLogEventKind {
+    /// A step of the extraction, such as running a tool or its result
+    Step,
+    /// A line a tool wrote to stderr
+    Stderr,
+    /// The job finished; nothing follows
+    Finished,
+}
+
+impl OcrLogEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Step => "step",
+            Self::Stderr => "stderr",
+            Self::Finished => "finished",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OcrLogEvent {
+    pub kind: OcrLogEventKind,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+tokio::task_local! {
+    static CURRENT_DOCUMENT: Uuid;
+}
+
+fn channels() -> &'static Mutex<HashMap<Uuid, broadcast::Sender<OcrLogEvent>>> {
+    static CHANNELS: OnceLock<Mutex<HashMap<Uuid, broadcast::Sender<OcrLogEvent>>>> = OnceLock::new();
+    CHANNELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Closes a document's channel when its job ends, even if the job panics or is cancelled
+struct CaptureGuard {
+    document_id: Uuid,
+    sender: broadcast::Sender<OcrLogEvent>,
+}
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        let _ = self.sender.send(OcrLogEvent {
+            kind: OcrLogEventKind::Finished,
+            message: "OCR job finished".to_string(),
+            timestamp: Utc::now(),
+        });
+        let mut channels = channels().lock().unwrap_or_else(|e| e.into_inner());
+        if channels
+            .get(&self.document_id)
+            .is_some_and(|sender| sender.same_channel(&self.sender))
+        {
+            channels.remove(&self.document_id);
+        }
+    }
+}
+
+/// Runs `job` with its steps and tool output broadcast to subscribers of `document_id`
+pub async fn capture<F: Future>(document_id: Uuid, job: F) -> F::Output {
+    let sender = broadcast::channel(CHANNEL_CAPACITY).0;
+    channels()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(document_id, sender.clone());
+    let _guard = CaptureGuard { document_id, sender };
+    CURRENT_DOCUMENT.scope(document_id, job).await
+}
+
+/// Subscribes to the live log of a document, or None if it isn't being OCR'd
+pub fn subscribe(document_id: Uuid) -> Option<broadcast::Receiver<OcrLogEvent>> {
+    channels()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&document_id)
+        .map(|sender| sender.subscribe())
+}
+
+/// The document whose job the current task is processing
+pub fn current_document() -> Option<Uuid> {
+    CURRENT_DOCUMENT.try_with(|document_id| *document_id).ok()
+}
+
+fn emit(document_id: Uuid, kind: OcrLogEventKind, message: String) {
+    let channels = channels().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(sender) = channels.get(&document_id) {
+        // Fails only when nobody is listening
+        let _ = sender.send(OcrLogEvent { kind, message, timestamp: Utc::now() });
+    }
+}
+
+/// Records a step of the current document's job
+pub fn log_step(message: impl Into<String>) {
+    if let Some(document_id) = current_document() {
+        emit(document_id, OcrLogEventKind::Step, message.into());
+    }
+}
+
+fn captured_document(document_id: Option<Uuid>) -> Option<Uuid> {
+    document_id.filter(|document_id| channels().lock().unwrap_or_else(|e| e.into_inner()).contains_key(document_id))
+}
+
+fn exit_message(tool: &str, output: &Output) -> String {
+    match output.status.code() {
+        Some(code) => format!("{} exited with code {}", tool, code),
+        None => format!("{} was terminated by a signal", tool),
+    }
+}
+
+/// Like [`tokio::process::Command::output`], relaying `tool`'s stderr to the
+/// live log line by line while it runs
+pub async fn output(cmd: &mut tokio::process::Command, tool: &str) -> std::io::Result<Output> {
+    let Some(document_id) = captured_document(current_document()) else {
+        return cmd.output().await;
+    };
+
+    emit(document_id, OcrLogEventKind::Step, format!("Running {}", tool));
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+
+    let read_stdout = async {
+        let mut buffer = Vec::new();
+        stdout.read_to_end(&mut buffer).await.map(|_| buffer)
+    };
+    let read_stderr = async {
+        let mut reader = tokio::io::BufReader::new(stderr);
+        let mut buffer = Vec::new();
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line).await? == 0 {
+                break;
+            }
+            emit(document_id, OcrLogEventKind::Stderr, stderr_line(tool, &line));
+            buffer.extend_from_slice(&line);
+        }
+        Ok::<_, std::io::Error>(buffer)
+    };
+    let (stdout, stderr, status) = tokio::try_join!(read_stdout, read_stderr, child.wait())?;
+
+    let output = Output { status, stdout, stderr };
+    emit(document_id, OcrLogEventKind::Step, exit_message(tool, &output));
+    Ok(output)
+}
+
+/// Like [`std::process::Command::output`] for code off the async runtime, where
+/// the document has to be passed in since the job's task isn't the current one
+pub fn output_blocking(document_id: Option<Uuid>, cmd: &mut std::process::Command, tool: &str) -> std::io::Result<Output> {
+    let Some(document_id) = captured_document(document_id) else {
+        return cmd.output();
+    };
+
+    emit(document_id, OcrLogEventKind::Step, format!("Running {}", tool));
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+
+    let read_stdout = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        stdout.read_to_end(&mut buffer).map(|_| buffer)
+    });
+    let mut reader = BufReader::new(stderr);
+    let mut stderr = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        emit(document_id, OcrLogEventKind::Stderr, stderr_line(tool, &line));
+        stderr.extend_from_slice(&line);
+    }
+    let stdout = read_stdout
+        .join()
+        .map_err(|_| std::io::Error::other(format!("reading the output of {} panicked", tool)))??;
+    let status = child.wait()?;
+
+    let output = Output { status, stdout, stderr };
+    emit(document_id, OcrLogEventKind::Step, exit_message(tool, &output));
+    Ok(output)
+}
+
+fn stderr_line(tool: &str, line: &[u8]) -> String {
+    format!("{}: {}", tool, String::from_utf8_lossy(line).trim_end())
+}
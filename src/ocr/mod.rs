@@ -1,16 +1,37 @@
 pub mod api;
+pub mod barcode;
+pub mod confidence_calibration;
+pub mod date_extraction;
+pub mod document_split;
+pub mod engine;
 pub mod enhanced;
+pub mod fallback_order;
+pub mod fallback_stats;
+pub mod front_matter;
+pub mod handwriting;
 pub mod image_ocr;
+pub mod incremental;
+pub mod page_range;
+pub mod region;
+pub mod table_extraction;
 pub mod error;
 pub mod health;
+pub mod language_detection;
+pub mod language_packs;
+pub mod live_log;
+pub mod model_cache;
+pub mod orientation;
 pub mod queue;
+pub mod temp_files;
 pub mod tests;
+pub mod text_sanitizer;
 pub mod xml_extractor;
 
 use anyhow::{anyhow, Result};
 use std::path::Path;
 use crate::ocr::error::OcrError;
 use crate::ocr::health::OcrHealthChecker;
+use crate::ocr::temp_files;
 
 #[cfg(feature = "ocr")]
 use tesseract::Tesseract;
@@ -30,7 +51,7 @@ pub struct OcrConfig {
 impl Default for OcrConfig {
     fn default() -> Self {
         Self {
-            temp_dir: std::env::var("TEMP_DIR").unwrap_or_else(|_| "/tmp".to_string()),
+            temp_dir: temp_files::temp_dir(),
         }
     }
 }
@@ -39,7 +60,7 @@ impl OcrService {
     pub fn new() -> Self {
         Self {
             health_checker: OcrHealthChecker::new(),
-            temp_dir: std::env::var("TEMP_DIR").unwrap_or_else(|_| "/tmp".to_string()),
+            temp_dir: temp_files::temp_dir(),
         }
     }
 
@@ -102,8 +123,7 @@ impl OcrService {
             }
             
             // Create temporary file for text extraction
-            let temp_dir = std::env::var("TEMP_DIR").unwrap_or_else(|_| "/tmp".to_string());
-            let temp_text_path = format!("{}/pdf_text_{}.txt", temp_dir, std::process::id());
+            let temp_text_path = temp_files::temp_path(&temp_files::temp_dir(), &format!("pdf_text_{}.txt", std::process::id()));
             
             // Progressive extraction with fallback strategies
             // Strategy 1: pdftotext for existing text (fastest)
@@ -209,6 +229,9 @@ impl OcrService {
             word_count: result.word_count,
             preprocessing_applied: vec![format!("XML extraction - {}", result.extraction_method)],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         })
     }
 
@@ -242,7 +265,7 @@ impl OcrService {
             "image/png" | "image/jpeg" | "image/jpg" | "image/tiff" | "image/bmp" => {
                 self.extract_text_from_image_with_lang(file_path, lang).await
             }
-            "text/plain" => {
+            "text/plain" | "text/markdown" => {
                 let text = tokio::fs::read_to_string(file_path).await?;
                 Ok(text)
             }
@@ -325,6 +348,7 @@ impl OcrService {
             "image/tiff",
             "image/bmp",
             "text/plain",
+            "text/markdown",
         ];
 
         // Office document types are always supported via XML extraction
@@ -0,0 +1,158 @@
+//! Reuse of initialized Tesseract instances across OCR jobs.
+//!
+//! Loading a language model takes far longer than OCR of a small page, so each
+//! blocking worker thread keeps the instances it has initialized, keyed by
+//! language combination, and hands them back out to later jobs on the same
+//! thread. `Tesseract` is `Send` but not `Sync`, so instances are never shared
+//! between threads.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Instances kept per worker thread unless configured otherwise
+pub const DEFAULT_MODEL_CACHE_SIZE: usize = 2;
+
+static MODEL_CACHE_CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_MODEL_CACHE_SIZE);
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static MODEL_LOADS: AtomicU64 = AtomicU64::new(0);
+static MODEL_LOAD_TIME_US: AtomicU64 = AtomicU64::new(0);
+
+/// Log a summary of the savings every this many cache hits
+const STATS_LOG_INTERVAL: u64 = 100;
+
+/// Sets how many initialized instances each worker thread keeps; 0 disables reuse
+pub fn set_model_cache_capacity(capacity: usize) {
+    MODEL_CACHE_CAPACITY.store(capacity, Ordering::Relaxed);
+}
+
+pub fn model_cache_capacity() -> usize {
+    MODEL_CACHE_CAPACITY.load(Ordering::Relaxed)
+}
+
+/// Process-wide counters of model loads and reuse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelCacheStats {
+    pub hits: u64,
+    pub loads: u64,
+    pub average_load_ms: f64,
+    /// Loading time avoided by reuse, assuming each hit saved an average load
+    pub estimated_saved_ms: f64,
+}
+
+pub fn model_cache_stats() -> ModelCacheStats {
+    let hits = CACHE_HITS.load(Ordering::Relaxed);
+    let loads = MODEL_LOADS.load(Ordering::Relaxed);
+    let average_load_ms = if loads == 0 {
+        0.0
+    } else {
+        MODEL_LOAD_TIME_US.load(Ordering::Relaxed) as f64 / loads as f64 / 1000.0
+    };
+    ModelCacheStats {
+        hits,
+        loads,
+        average_load_ms,
+        estimated_saved_ms: hits as f64 * average_load_ms,
+    }
+}
+
+/// Small most-recently-used cache of models keyed by language combination
+pub struct ModelCache<T> {
+    entries: Vec<(String, T)>,
+}
+
+impl<T> Default for ModelCache<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new() }
+    }
+}
+
+impl<T> ModelCache<T> {
+    /// Removes and returns the model for `language`, if cached
+    pub fn take(&mut self, language: &str) -> Option<T> {
+        let index = self.entries.iter().position(|(cached, _)| cached == language)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Stores a model as the most recently used, evicting the least recently
+    /// used ones beyond `capacity`
+    pub fn put(&mut self, language: String, model: T, capacity: usize) {
+        self.entries.retain(|(cached, _)| cached != &language);
+        self.entries.insert(0, (language, model));
+        self.entries.truncate(capacity);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn languages(&self) -> Vec<&str> {
+        self.entries.iter().map(|(language, _)| language.as_str()).collect()
+    }
+}
+
+#[cfg(feature = "ocr")]
+mod tesseract_cache {
+    use super::*;
+    use anyhow::Result;
+    use std::cell::RefCell;
+    use std::time::Instant;
+    use tesseract::Tesseract;
+    use tracing::{debug, info};
+
+    thread_local! {
+        static TESSERACT_CACHE: RefCell<ModelCache<Tesseract>> = RefCell::new(ModelCache::default());
+    }
+
+    /// Takes this thread's cached instance for `language`, or initializes a new one.
+    ///
+    /// Hand the instance back with [`return_tesseract`] once the job succeeded;
+    /// instances from failed jobs are simply dropped.
+    pub fn checkout_tesseract(language: &str) -> Result<Tesseract> {
+        let capacity = model_cache_capacity();
+        if capacity > 0 {
+            if let Some(tesseract) = TESSERACT_CACHE.with(|cache| cache.borrow_mut().take(language)) {
+                let hits = CACHE_HITS.fetch_add(1, Ordering::Relaxed) + 1;
+                let stats = model_cache_stats();
+                debug!("Reusing Tesseract model '{}' (saves ~{:.0}ms)", language, stats.average_load_ms);
+                if hits.is_multiple_of(STATS_LOG_INTERVAL) {
+                    info!(
+                        "Tesseract model cache: {} reuses, {} loads averaging {:.0}ms, ~{:.1}s of loading saved",
+                        stats.hits,
+                        stats.loads,
+                        stats.average_load_ms,
+                        stats.estimated_saved_ms / 1000.0
+                    );
+                }
+                return Ok(tesseract);
+            }
+        }
+
+        let started = Instant::now();
+        let mut tesseract = Tesseract::new(None, Some(language))?;
+        if capacity > 0 {
+            // Keep the legacy engine's adaptive classifier from learning across
+            // jobs, so a reused instance reads a page the same way a fresh one would
+            tesseract = tesseract.set_variable("classify_enable_learning", "0")?;
+        }
+        let elapsed = started.elapsed();
+        MODEL_LOADS.fetch_add(1, Ordering::Relaxed);
+        MODEL_LOAD_TIME_US.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        info!("Loaded Tesseract model '{}' in {}ms", language, elapsed.as_millis());
+        Ok(tesseract)
+    }
+
+    /// Keeps an instance for later jobs on this thread
+    pub fn return_tesseract(language: String, tesseract: Tesseract) {
+        let capacity = model_cache_capacity();
+        if capacity == 0 {
+            return;
+        }
+        TESSERACT_CACHE.with(|cache| cache.borrow_mut().put(language, tesseract, capacity));
+    }
+}
+
+#[cfg(feature = "ocr")]
+pub use tesseract_cache::{checkout_tesseract, return_tesseract};
@@ -0,0 +1,53 @@
+//! Verification of automatic orientation correction.
+//!
+//! The orientation detector only looks at the page's shape, so it can turn an
+//! already-upright scan on its side. With verification enabled, a downscaled
+//! sample is OCR'd both as-is and rotated, and the rotation is kept only if it
+//! reads at least as well.
+
+/// Largest sample OCR'd for each candidate orientation
+pub const ORIENTATION_SAMPLE_MAX_PIXELS: i64 = 1_000_000;
+
+/// Confidence difference (in percentage points) below which two samples count
+/// as reading equally well, leaving the word count to decide
+const CONFIDENCE_TIE_MARGIN: f32 = 2.0;
+
+/// What happened to a rotation proposed by orientation detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrientationCorrection {
+    /// The image was rotated before OCR
+    Applied,
+    /// The rotation read worse than the original during verification and was undone
+    Reverted,
+}
+
+impl OrientationCorrection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrientationCorrection::Applied => "applied",
+            OrientationCorrection::Reverted => "reverted",
+        }
+    }
+}
+
+/// How well a sample read in one orientation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientationSample {
+    pub confidence: f32,
+    pub word_count: usize,
+}
+
+/// Whether the rotated sample reads at least as well as the original one.
+///
+/// Confidence decides, except when the two are within a couple of points of
+/// each other, where the orientation that recognized more words wins. A
+/// rotation that recognizes nothing is never kept over one that recognized text.
+pub fn keep_rotation(original: &OrientationSample, rotated: &OrientationSample) -> bool {
+    if rotated.word_count == 0 && original.word_count > 0 {
+        return false;
+    }
+    if (rotated.confidence - original.confidence).abs() < CONFIDENCE_TIE_MARGIN {
+        return rotated.word_count >= original.word_count;
+    }
+    rotated.confidence > original.confidence
+}
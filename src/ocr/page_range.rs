@@ -0,0 +1,88 @@
+//! Page range specs such as `1-3,7,10-12` for on-demand OCR of selected pages
+
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PageRangeError {
+    #[error("Page range is empty")]
+    Empty,
+    #[error("Invalid page range '{0}': expected a page number or 'first-last', starting at 1")]
+    Invalid(String),
+    #[error("Invalid page range '{0}': the first page is after the last")]
+    Reversed(String),
+    #[error("Page {page} is out of range: the document has {page_count} page(s)")]
+    OutOfRange { page: usize, page_count: usize },
+}
+
+/// Parses a comma-separated page range spec into sorted, merged, 1-based ranges
+pub fn parse_page_ranges(spec: &str) -> Result<Vec<RangeInclusive<usize>>, PageRangeError> {
+    let parse_page = |part: &str, value: &str| -> Result<usize, PageRangeError> {
+        match value.trim().parse::<usize>() {
+            Ok(page) if page >= 1 => Ok(page),
+            _ => Err(PageRangeError::Invalid(part.to_string())),
+        }
+    };
+
+    let mut ranges = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let range = match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (parse_page(part, first)?, parse_page(part, last)?);
+                if first > last {
+                    return Err(PageRangeError::Reversed(part.to_string()));
+                }
+                first..=last
+            }
+            None => {
+                let page = parse_page(part, part)?;
+                page..=page
+            }
+        };
+        ranges.push(range);
+    }
+
+    if ranges.is_empty() {
+        return Err(PageRangeError::Empty);
+    }
+
+    // Merge overlapping and adjacent ranges so every page is rendered once
+    ranges.sort_by_key(|range| *range.start());
+    let mut merged: Vec<RangeInclusive<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if *range.start() <= *last.end() + 1 => {
+                *last = *last.start()..=(*last.end()).max(*range.end());
+            }
+            _ => merged.push(range),
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Parses a page range spec and checks every page exists in a document of `page_count` pages
+pub fn resolve_page_ranges(spec: &str, page_count: usize) -> Result<Vec<RangeInclusive<usize>>, PageRangeError> {
+    let ranges = parse_page_ranges(spec)?;
+    if let Some(last) = ranges.last() {
+        if *last.end() > page_count {
+            let page = ranges
+                .iter()
+                .flat_map(|range| [*range.start(), *range.end()])
+                .find(|page| *page > page_count)
+                .unwrap_or(*last.end());
+            return Err(PageRangeError::OutOfRange { page, page_count });
+        }
+    }
+    Ok(ranges)
+}
+
+/// The page number in the name of an image `pdftoppm` rendered with output
+/// `prefix`, such as `prefix-07.png`. Page numbers are zero-padded to the
+/// width of the document's page count.
+pub fn rendered_page_number(file_name: &str, prefix: &str) -> Option<usize> {
+    let number = file_name.strip_prefix(prefix)?.strip_prefix('-')?.strip_suffix(".png")?;
+    if number.is_empty() || !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    number.parse().ok().filter(|page| *page >= 1)
+}
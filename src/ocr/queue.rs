@@ -2,6 +2,8 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, Row, Column};
+use std::collections::HashSet;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Semaphore;
@@ -10,6 +12,23 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::{db::Database, ocr::enhanced::EnhancedOcrService, db_guardrails_simple::DocumentTransactionManager, monitoring::request_throttler::RequestThrottler};
+use crate::models::{OcrJobOverrides, SourceProcessingMode};
+use crate::ingestion::archive::{extract_archive, is_archive_mime, ArchiveLimits};
+use crate::ingestion::document_ingestion::{
+    DeduplicationPolicy, DocumentIngestionRequest, DocumentIngestionService, IngestionResult,
+};
+use crate::ocr::date_extraction::{file_date, DocumentDateOptions};
+use crate::ocr::document_split::{split_part_filename, split_segments, SeparatorKind};
+use crate::ocr::barcode::{append_barcode_text, DecodedBarcode};
+use crate::ocr::confidence_calibration::ConfidenceCalibration;
+use crate::ocr::engine::{handwriting_engine_failed_note, EngineRoute, OcrEngine, NO_HANDWRITING_ENGINE_NOTE, TESSERACT_ENGINE};
+use crate::ocr::enhanced::OcrResult;
+use crate::ocr::front_matter::{is_front_matter_mime, split_front_matter, FrontMatter};
+use crate::ocr::incremental::{merge_page_text, page_spec, pages_to_reocr, split_page_text, IncrementalOcrRecord};
+use crate::ocr::language_detection::{detect_language, DocumentLanguagePolicy};
+use crate::ocr::live_log;
+use crate::ocr::region::ExtractedField;
+use crate::ocr::text_sanitizer::{sanitize_ocr_text, TextSanitizerOptions};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct OcrQueueItem {
@@ -28,6 +47,235 @@ pub struct OcrQueueItem {
     pub file_size: Option<i64>,
 }
 
+/// Outcome of requesting OCR for a document on demand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnDemandEnqueue {
+    /// A new job was queued
+    Queued(Uuid),
+    /// The document already had a pending or running job, which is left as is
+    AlreadyQueued(Uuid),
+}
+
+/// Scheduling class of a queue item. Workers always drain higher classes first;
+/// the numeric priority only orders items within a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrPriorityClass {
+    /// Bulk work such as source backfills and reprocessing after settings changes
+    Backfill,
+    Normal,
+    /// Documents a user is waiting on, such as direct uploads
+    Interactive,
+}
+
+impl OcrPriorityClass {
+    /// Value stored in `ocr_queue.priority_class`
+    pub fn rank(self) -> i16 {
+        match self {
+            OcrPriorityClass::Backfill => 0,
+            OcrPriorityClass::Normal => 1,
+            OcrPriorityClass::Interactive => 2,
+        }
+    }
+
+    pub fn from_rank(rank: i16) -> Self {
+        match rank {
+            i16::MIN..=0 => OcrPriorityClass::Backfill,
+            1 => OcrPriorityClass::Normal,
+            _ => OcrPriorityClass::Interactive,
+        }
+    }
+}
+
+/// How pending items of different users or sources share the workers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OcrFairnessPolicy {
+    /// Strictly by priority and age, so a large burst runs before later work
+    #[default]
+    Fifo,
+    /// Round-robin across the users owning the documents
+    FairByUser,
+    /// Round-robin across sources; uploads and other documents without a
+    /// source share one turn per user
+    FairBySource,
+}
+
+impl OcrFairnessPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OcrFairnessPolicy::Fifo => "fifo",
+            OcrFairnessPolicy::FairByUser => "fair_by_user",
+            OcrFairnessPolicy::FairBySource => "fair_by_source",
+        }
+    }
+
+    /// Expression over the documents table aliased `documents` that items take
+    /// turns by, or none for FIFO
+    fn key(&self, documents: &str) -> Option<String> {
+        match self {
+            OcrFairnessPolicy::Fifo => None,
+            OcrFairnessPolicy::FairByUser => Some(format!("{}.user_id", documents)),
+            OcrFairnessPolicy::FairBySource => Some(format!("COALESCE({0}.source_id, {0}.user_id)", documents)),
+        }
+    }
+}
+
+impl FromStr for OcrFairnessPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "fifo" => Ok(OcrFairnessPolicy::Fifo),
+            "fair_by_user" => Ok(OcrFairnessPolicy::FairByUser),
+            "fair_by_source" => Ok(OcrFairnessPolicy::FairBySource),
+            other => Err(format!(
+                "Invalid OCR scheduling policy '{}' (expected fifo, fair_by_user or fair_by_source)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OcrFairnessPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How workers pick among pending items of the same class, and how many large
+/// files they OCR at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OcrSchedulingPolicy {
+    /// Whose items go next within a class
+    pub fairness: OcrFairnessPolicy,
+    /// Pick smaller files first instead of the oldest
+    pub smaller_files_first: bool,
+    /// Files of at least this many bytes run in the large-file lane
+    pub large_file_threshold_bytes: Option<i64>,
+    /// Concurrent jobs in the large-file lane
+    pub large_file_concurrency: usize,
+}
+
+impl Default for OcrSchedulingPolicy {
+    fn default() -> Self {
+        Self {
+            fairness: OcrFairnessPolicy::Fifo,
+            smaller_files_first: false,
+            large_file_threshold_bytes: None,
+            large_file_concurrency: 1,
+        }
+    }
+}
+
+impl OcrSchedulingPolicy {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            fairness: config.ocr_scheduling_policy,
+            smaller_files_first: config.ocr_smaller_files_first,
+            large_file_threshold_bytes: (config.ocr_large_file_threshold_mb > 0)
+                .then(|| (config.ocr_large_file_threshold_mb * 1024 * 1024) as i64),
+            large_file_concurrency: config.ocr_large_file_concurrency.max(1),
+        }
+    }
+
+    /// Whether an item belongs in the large-file lane. Items of unknown size don't.
+    pub fn is_large(&self, file_size: Option<i64>) -> bool {
+        match (self.large_file_threshold_bytes, file_size) {
+            (Some(threshold), Some(size)) => size >= threshold,
+            _ => false,
+        }
+    }
+
+    /// Joins giving each pending item `q` the jobs running for its fairness key
+    /// (`served.running`) and when the key last had a job started
+    /// (`served.last_started_at`). Empty for FIFO.
+    fn fairness_joins(&self) -> String {
+        let (Some(key), Some(served_key)) = (self.fairness.key("d"), self.fairness.key("sd")) else {
+            return String::new();
+        };
+        format!(
+            r#"
+            JOIN documents d ON d.id = q.document_id
+            LEFT JOIN (
+                SELECT {served_key} AS fairness_key,
+                       COUNT(*) FILTER (WHERE sq.status = 'processing') AS running,
+                       MAX(sq.started_at) AS last_started_at
+                FROM ocr_queue sq
+                JOIN documents sd ON sd.id = sq.document_id
+                WHERE sq.status = 'processing' OR sq.started_at > NOW() - INTERVAL '1 day'
+                GROUP BY 1
+            ) served ON served.fairness_key = {key}
+            "#
+        )
+    }
+
+    /// Queue order: scheduling class, then under a fair policy the key with the
+    /// fewest running jobs and longest wait, then numeric priority, then size or age
+    fn order_by(&self) -> String {
+        let mut order = vec!["q.priority_class DESC"];
+        if self.fairness != OcrFairnessPolicy::Fifo {
+            order.push("COALESCE(served.running, 0) ASC");
+            order.push("served.last_started_at ASC NULLS FIRST");
+        }
+        order.push("q.priority DESC");
+        if self.smaller_files_first {
+            order.push("q.file_size ASC NULLS LAST");
+        }
+        order.push("q.created_at ASC");
+        order.join(", ")
+    }
+}
+
+/// Width of the buckets OCR statistics are grouped into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrStatsGranularity {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl OcrStatsGranularity {
+    /// Unit passed to `date_trunc`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OcrStatsGranularity::Hour => "hour",
+            OcrStatsGranularity::Day => "day",
+        }
+    }
+
+    pub fn bucket_duration(&self) -> chrono::Duration {
+        match self {
+            OcrStatsGranularity::Hour => chrono::Duration::hours(1),
+            OcrStatsGranularity::Day => chrono::Duration::days(1),
+        }
+    }
+
+    /// Window covered when none is requested: a day of hours or 30 days
+    pub fn default_window(&self) -> chrono::Duration {
+        match self {
+            OcrStatsGranularity::Hour => chrono::Duration::hours(24),
+            OcrStatsGranularity::Day => chrono::Duration::days(30),
+        }
+    }
+}
+
+/// OCR jobs that finished in one time bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct OcrTimeseriesBucket {
+    /// Start of the bucket (UTC)
+    pub start: DateTime<Utc>,
+    pub documents_completed: i64,
+    /// Pages of the completed documents; documents without a page count count as one
+    pub pages_completed: i64,
+    /// Jobs that failed for good, after their last attempt
+    pub documents_failed: i64,
+    /// Failed share of the finished jobs (0-1), 0 when none finished
+    pub failure_rate: f64,
+    pub avg_processing_time_ms: Option<f64>,
+    pub p95_processing_time_ms: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueStats {
     pub pending_count: i64,
@@ -51,6 +299,12 @@ pub struct OcrQueueService {
     max_pdf_size_mb: u64,
     max_office_document_size_mb: u64,
     ocr_timeout_seconds: u64,
+    text_sanitizer: TextSanitizerOptions,
+    handwriting_engine: Option<Arc<dyn OcrEngine>>,
+    confidence_calibration: Arc<ConfidenceCalibration>,
+    scheduling: OcrSchedulingPolicy,
+    archive_limits: ArchiveLimits,
+    document_language: DocumentLanguagePolicy,
 }
 
 impl OcrQueueService {
@@ -86,14 +340,248 @@ impl OcrQueueService {
             max_pdf_size_mb,
             max_office_document_size_mb,
             ocr_timeout_seconds,
+            text_sanitizer: TextSanitizerOptions::default(),
+            handwriting_engine: None,
+            confidence_calibration: Arc::new(ConfidenceCalibration::default()),
+            scheduling: OcrSchedulingPolicy::default(),
+            archive_limits: ArchiveLimits::default(),
+            document_language: DocumentLanguagePolicy::default(),
+        }
+    }
+
+    /// Sets the engine for documents flagged as handwritten; without one they are OCR'd by Tesseract
+    pub fn with_handwriting_engine(mut self, engine: Option<Arc<dyn OcrEngine>>) -> Self {
+        self.handwriting_engine = engine;
+        self
+    }
+
+    /// Sets the curves Tesseract confidence is calibrated with; without them it is stored as reported
+    pub fn with_confidence_calibration(mut self, calibration: ConfidenceCalibration) -> Self {
+        self.confidence_calibration = Arc::new(calibration);
+        self
+    }
+
+    /// Runs the handwriting engine on a document, returning the engine's name with its result
+    async fn recognize_handwriting(&self, file_path: &str, filename: &str, mime_type: &str, settings: &crate::models::Settings) -> Result<(String, OcrResult)> {
+        let engine = self.handwriting_engine.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No handwriting OCR engine is configured"))?;
+        let data = self.file_service.read_file(file_path).await?;
+        let result = engine.recognize(data, filename, mime_type, settings).await?;
+        Ok((engine.name().to_string(), result))
+    }
+
+    /// Splits a PDF at its separator pages into new documents linked to it, queues
+    /// each part for OCR and archives the original. Returns the number of parts,
+    /// or 0 when the PDF holds a single document.
+    async fn split_on_separators(
+        &self,
+        document_id: Uuid,
+        user_id: Uuid,
+        file_path: &str,
+        filename: &str,
+        settings: &crate::models::Settings,
+        priority: i32,
+        ocr_service: &EnhancedOcrService,
+    ) -> Result<usize> {
+        let separator = SeparatorKind::from_settings(settings.auto_split_barcode.as_deref());
+        let scan = ocr_service.find_separator_pages(file_path, &separator).await?;
+        let segments = split_segments(scan.page_count, &scan.separator_pages);
+        if segments.len() < 2 {
+            return Ok(0);
         }
+
+        let parts = ocr_service.extract_pdf_page_ranges(file_path, &segments).await?;
+        let ingestion = DocumentIngestionService::new(self.db.clone(), (*self.file_service).clone());
+        for (index, (file_data, &(first_page, last_page))) in parts.into_iter().zip(&segments).enumerate() {
+            let part_filename = split_part_filename(filename, index + 1, segments.len());
+            let file_size = file_data.len() as i64;
+            let request = DocumentIngestionRequest {
+                filename: part_filename.clone(),
+                original_filename: part_filename,
+                file_data,
+                mime_type: "application/pdf".to_string(),
+                user_id,
+                deduplication_policy: DeduplicationPolicy::AllowDuplicateContent,
+                source_type: Some("split".to_string()),
+                source_id: None,
+                original_created_at: None,
+                original_modified_at: None,
+                source_path: None,
+                file_permissions: None,
+                file_owner: None,
+                file_group: None,
+                source_metadata: None,
+            };
+
+            let part = match ingestion.ingest_document(request).await
+                .map_err(|e| anyhow::anyhow!("Failed to store part {} of document {}: {}", index + 1, document_id, e))?
+            {
+                IngestionResult::Created(document) => document,
+                other => return Err(anyhow::anyhow!("Part {} of document {} was not stored: {:?}", index + 1, document_id, other)),
+            };
+            self.db.link_split_part(part.id, document_id, first_page as i32, last_page as i32).await?;
+            self.enqueue_document(part.id, priority, file_size).await?;
+        }
+
+        self.db.archive_document(document_id).await?;
+        Ok(segments.len())
+    }
+
+    /// Sets how deep and how much archives are extracted
+    pub fn with_archive_limits(mut self, archive_limits: ArchiveLimits) -> Self {
+        self.archive_limits = archive_limits;
+        self
+    }
+
+    /// Sets how the language documents are searched in is chosen
+    pub fn with_document_language(mut self, document_language: DocumentLanguagePolicy) -> Self {
+        self.document_language = document_language;
+        self
+    }
+
+    /// Sets the order pending items are picked in and the large-file lane
+    pub fn with_scheduling(mut self, scheduling: OcrSchedulingPolicy) -> Self {
+        self.scheduling = scheduling;
+        self
+    }
+
+    /// Extracts the files of an archive into new documents linked to it, queues
+    /// each for OCR and archives the original. Files the user's settings don't
+    /// allow, files over their size limit and duplicates are left out. Entries
+    /// linked by an earlier, interrupted run are not stored again. A file that
+    /// fails to store is logged and the rest are still extracted; the archive is
+    /// only kept whole when no file could be stored. Returns the number of
+    /// documents created.
+    async fn extract_archive_entries(
+        &self,
+        document_id: Uuid,
+        user_id: Uuid,
+        file_path: &str,
+        settings: &crate::models::Settings,
+        priority: i32,
+    ) -> Result<usize> {
+        let data = self.file_service.read_file(file_path).await?;
+        let limits = self.archive_limits;
+        let entries = tokio::task::spawn_blocking(move || extract_archive(&data, &limits)).await??;
+
+        let max_file_size = settings.max_file_size_mb.max(0) as usize * 1024 * 1024;
+        let ingestion = DocumentIngestionService::new(self.db.clone(), (*self.file_service).clone());
+        let already_linked: HashSet<String> = self.db.get_archive_entries(document_id).await?
+            .into_iter()
+            .map(|(_, _, path)| path)
+            .collect();
+        let mut extracted = 0;
+        let mut failures = Vec::new();
+        for entry in entries {
+            if already_linked.contains(&entry.path) {
+                continue;
+            }
+            let filename = entry.filename().to_string();
+            if !self.file_service.is_allowed_file_type(&filename, &settings.allowed_file_types) {
+                info!("Skipping {} in archive {}: file type not allowed", entry.path, document_id);
+                continue;
+            }
+            if entry.data.len() > max_file_size {
+                warn!("Skipping {} in archive {}: {} bytes exceeds the {}MB limit",
+                      entry.path, document_id, entry.data.len(), settings.max_file_size_mb);
+                continue;
+            }
+
+            let mime_type = crate::mime_detection::detect_mime_from_content(&entry.data, &filename, None).mime_type;
+            let file_size = entry.data.len() as i64;
+            let request = DocumentIngestionRequest {
+                filename: filename.clone(),
+                original_filename: filename,
+                file_data: entry.data,
+                mime_type,
+                user_id,
+                deduplication_policy: DeduplicationPolicy::Skip,
+                source_type: Some("archive".to_string()),
+                source_id: None,
+                original_created_at: None,
+                original_modified_at: None,
+                source_path: Some(entry.path.clone()),
+                file_permissions: None,
+                file_owner: None,
+                file_group: None,
+                source_metadata: None,
+            };
+
+            let document = match ingestion.ingest_document(request).await {
+                Ok(IngestionResult::Created(document)) => document,
+                Ok(other) => {
+                    info!("{} from archive {} was not stored: {:?}", entry.path, document_id, other);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to store {} from archive {}: {}", entry.path, document_id, e);
+                    failures.push(entry.path);
+                    continue;
+                }
+            };
+            // The document exists from here on, so it counts as extracted even if
+            // linking or queueing it fails
+            extracted += 1;
+            if let Err(e) = self.db.link_archive_entry(document.id, document_id, &entry.path).await {
+                warn!("Failed to link {} to archive {}: {}", document.id, document_id, e);
+                failures.push(entry.path);
+                continue;
+            }
+            if let Err(e) = self.enqueue_document(document.id, priority, file_size).await {
+                warn!("Failed to queue {} from archive {} for OCR: {}", entry.path, document_id, e);
+                failures.push(entry.path);
+            }
+        }
+
+        if extracted == 0 && already_linked.is_empty() && !failures.is_empty() {
+            return Err(anyhow::anyhow!("No file could be stored, {} failed: {}", failures.len(), failures.join(", ")));
+        }
+        if !failures.is_empty() {
+            warn!("Archive {} was extracted without {} files: {}", document_id, failures.len(), failures.join(", "));
+        }
+
+        self.db.archive_document(document_id).await?;
+        Ok(extracted)
+    }
+
+    /// Sets how OCR text is cleaned before storage; NUL bytes are always removed
+    pub fn with_text_sanitizer(mut self, options: TextSanitizerOptions) -> Self {
+        self.text_sanitizer = options;
+        self
+    }
+
+    /// Cleans OCR output so it can be stored in Postgres, logging what was changed
+    fn sanitize_ocr_output(&self, document_id: Uuid, text: &str) -> String {
+        let sanitized = sanitize_ocr_text(text, &self.text_sanitizer);
+        if sanitized.was_modified() {
+            warn!(
+                "Sanitized OCR text for document {}: removed {} NUL bytes and {} control characters{}",
+                document_id,
+                sanitized.nul_bytes_removed,
+                sanitized.control_chars_removed,
+                if sanitized.normalized { ", normalized to NFC" } else { "" }
+            );
+        }
+        sanitized.text
     }
     
 
     /// Add a document to the OCR queue
     pub async fn enqueue_document(&self, document_id: Uuid, priority: i32, file_size: i64) -> Result<Uuid> {
+        self.enqueue_document_with_class(document_id, OcrPriorityClass::Normal, priority, file_size).await
+    }
+
+    /// Add a document to the OCR queue in the given scheduling class
+    pub async fn enqueue_document_with_class(
+        &self,
+        document_id: Uuid,
+        class: OcrPriorityClass,
+        priority: i32,
+        file_size: i64,
+    ) -> Result<Uuid> {
         crate::debug_log!("OCR_QUEUE",
             "document_id" => document_id,
+            "priority_class" => format!("{:?}", class),
             "priority" => priority,
             "file_size" => file_size,
             "message" => "Enqueueing document"
@@ -101,14 +589,16 @@ impl OcrQueueService {
         
         let row = sqlx::query(
             r#"
-            INSERT INTO ocr_queue (document_id, priority, file_size)
-            VALUES ($1, $2, $3)
+            INSERT INTO ocr_queue (document_id, priority, file_size, priority_class, request_id)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING id
             "#
         )
         .bind(document_id)
         .bind(priority)
         .bind(file_size)
+        .bind(class.rank())
+        .bind(crate::logging::current_request_id())
         .fetch_one(&self.pool)
         .await
         .map_err(|e| {
@@ -126,28 +616,107 @@ impl OcrQueueService {
             "message" => "Successfully enqueued document"
         );
         
-        info!("Enqueued document {} with priority {} for OCR processing", document_id, priority);
+        info!("Enqueued document {} with priority {} ({:?}) for OCR processing", document_id, priority, class);
         Ok(id)
     }
 
+    /// Queue OCR of a document at a user's request. The job runs the full OCR
+    /// pipeline regardless of the processing mode of the document's source, with
+    /// `overrides` applied to the owner's settings. A document with a pending or
+    /// running job isn't queued again.
+    pub async fn enqueue_on_demand(&self, document_id: Uuid, file_size: i64, overrides: &OcrJobOverrides) -> Result<OnDemandEnqueue> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locking the document serializes concurrent requests for it
+        sqlx::query("SELECT id FROM documents WHERE id = $1 FOR UPDATE")
+            .bind(document_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let existing: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM ocr_queue
+            WHERE document_id = $1 AND status IN ('pending', 'processing')
+            ORDER BY created_at
+            LIMIT 1
+            "#
+        )
+        .bind(document_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(id) = existing {
+            tx.rollback().await?;
+            return Ok(OnDemandEnqueue::AlreadyQueued(id));
+        }
+
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO ocr_queue (document_id, priority, file_size, priority_class, ocr_overrides, request_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#
+        )
+        .bind(document_id)
+        .bind(5)
+        .bind(file_size)
+        .bind(OcrPriorityClass::Interactive.rank())
+        .bind(serde_json::to_value(overrides)?)
+        .bind(crate::logging::current_request_id())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Documents stored without OCR are marked skipped; show them as waiting again
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET ocr_status = 'pending',
+                ocr_error = NULL,
+                ocr_failure_reason = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        info!("Enqueued document {} for on-demand OCR as job {}", document_id, id);
+        Ok(OnDemandEnqueue::Queued(id))
+    }
+
     /// Batch enqueue multiple documents
     pub async fn enqueue_documents_batch(&self, documents: Vec<(Uuid, i32, i64)>) -> Result<Vec<Uuid>> {
+        self.enqueue_documents_batch_with_class(documents, OcrPriorityClass::Normal).await
+    }
+
+    /// Batch enqueue multiple documents in the given scheduling class
+    pub async fn enqueue_documents_batch_with_class(
+        &self,
+        documents: Vec<(Uuid, i32, i64)>,
+        class: OcrPriorityClass,
+    ) -> Result<Vec<Uuid>> {
         let mut ids = Vec::new();
         
+        let request_id = crate::logging::current_request_id();
+
         // Use a transaction for batch insert
         let mut tx = self.pool.begin().await?;
         
         for (document_id, priority, file_size) in documents {
             let row = sqlx::query(
                 r#"
-                INSERT INTO ocr_queue (document_id, priority, file_size)
-                VALUES ($1, $2, $3)
+                INSERT INTO ocr_queue (document_id, priority, file_size, priority_class, request_id)
+                VALUES ($1, $2, $3, $4, $5)
                 RETURNING id
                 "#
             )
             .bind(document_id)
             .bind(priority)
             .bind(file_size)
+            .bind(class.rank())
+            .bind(&request_id)
             .fetch_one(&mut *tx)
             .await?;
             
@@ -157,12 +726,24 @@ impl OcrQueueService {
         
         tx.commit().await?;
         
-        info!("Batch enqueued {} documents for OCR processing", ids.len());
+        info!("Batch enqueued {} documents ({:?}) for OCR processing", ids.len(), class);
         Ok(ids)
     }
 
     /// Get the next item from the queue with atomic job claiming and retry logic
     pub async fn dequeue(&self) -> Result<Option<OcrQueueItem>> {
+        self.dequeue_next(false).await
+    }
+
+    /// Like [`Self::dequeue`], but leaves items for the large-file lane in the
+    /// queue. Used while the lane is full, so small items keep flowing.
+    pub async fn dequeue_excluding_large(&self) -> Result<Option<OcrQueueItem>> {
+        self.dequeue_next(true).await
+    }
+
+    async fn dequeue_next(&self, exclude_large: bool) -> Result<Option<OcrQueueItem>> {
+        let max_file_size = if exclude_large { self.scheduling.large_file_threshold_bytes } else { None };
+
         crate::debug_log!("OCR_QUEUE", 
             "worker_id" => &self.worker_id,
             "message" => "Starting dequeue operation"
@@ -178,20 +759,28 @@ impl OcrQueueService {
             // Use a transaction to ensure atomic job claiming
             let mut tx = self.pool.begin().await?;
         
-        // Step 1: Find and lock the next available job atomically
-        let job_row = sqlx::query(
+        // Step 1: Find and lock the next available job atomically. Higher scheduling
+        // classes always go first, so an interactive upload is picked up before the
+        // rest of a backfill burst. Under a fair policy, users or sources then take
+        // turns within a class.
+        let job_row = sqlx::query(&format!(
             r#"
-            SELECT id, document_id, priority, status, attempts, max_attempts, 
-                   created_at, started_at, completed_at, error_message, 
-                   worker_id, processing_time_ms, file_size
-            FROM ocr_queue
-            WHERE status = 'pending'
-              AND attempts < max_attempts
-            ORDER BY priority DESC, created_at ASC
-            FOR UPDATE SKIP LOCKED
+            SELECT q.id, q.document_id, q.priority, q.status, q.attempts, q.max_attempts,
+                   q.created_at, q.started_at, q.completed_at, q.error_message,
+                   q.worker_id, q.processing_time_ms, q.file_size
+            FROM ocr_queue q
+            {}
+            WHERE q.status = 'pending'
+              AND q.attempts < q.max_attempts
+              AND ($1::BIGINT IS NULL OR COALESCE(q.file_size, 0) < $1)
+            ORDER BY {}
+            FOR UPDATE OF q SKIP LOCKED
             LIMIT 1
-            "#
-        )
+            "#,
+            self.scheduling.fairness_joins(),
+            self.scheduling.order_by()
+        ))
+        .bind(max_file_size)
         .fetch_optional(&mut *tx)
         .await?;
 
@@ -355,6 +944,324 @@ impl OcrQueueService {
         Ok(())
     }
 
+    /// Record that a document was deliberately left without extracted text and
+    /// complete its queue item
+    async fn mark_skipped(&self, item_id: Uuid, document_id: Uuid, processing_time_ms: i32) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET ocr_status = 'skipped',
+                ocr_error = NULL,
+                ocr_failure_reason = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.mark_completed(item_id, processing_time_ms).await
+    }
+
+    /// Processing mode of the document's source; documents without a source use the full pipeline
+    fn resolve_processing_mode(document_id: Uuid, configured: Option<String>) -> SourceProcessingMode {
+        match configured.map(SourceProcessingMode::try_from) {
+            Some(Ok(mode)) => mode,
+            Some(Err(e)) => {
+                warn!("{} on the source of document {}, using the full pipeline", e, document_id);
+                SourceProcessingMode::Full
+            }
+            None => SourceProcessingMode::Full,
+        }
+    }
+
+    /// OCR DPI set on the document's source, if any
+    fn resolve_source_ocr_dpi(document_id: Uuid, configured: Option<serde_json::Value>) -> Option<i32> {
+        let config = serde_json::json!({ "ocr_dpi": configured? });
+        match crate::models::source_ocr_dpi(&config) {
+            Ok(dpi) => dpi,
+            Err(e) => {
+                warn!("{} on the source of document {}, using the user's OCR DPI", e, document_id);
+                None
+            }
+        }
+    }
+
+    /// Settings overrides of an on-demand job; None for regular jobs
+    fn resolve_ocr_overrides(item_id: Uuid, stored: Option<serde_json::Value>) -> Option<OcrJobOverrides> {
+        let stored = stored?;
+        match serde_json::from_value(stored) {
+            Ok(overrides) => Some(overrides),
+            Err(e) => {
+                warn!("Ignoring invalid settings overrides of OCR job {}: {}", item_id, e);
+                Some(OcrJobOverrides::default())
+            }
+        }
+    }
+
+    /// Labels a document with the language detected in its text. Failures are
+    /// logged and never affect the OCR result.
+    async fn apply_language_label(&self, document_id: Uuid, text: &str) {
+        let Some(language) = detect_language(text) else {
+            tracing::debug!("No dominant language detected for document {}", document_id);
+            return;
+        };
+
+        let label_name = language.label_name();
+        match self.db.set_document_language_label(document_id, &label_name).await {
+            Ok(_) => info!("Labeled document {} as '{}'", document_id, label_name),
+            Err(e) => warn!("Failed to apply language label '{}' to document {}: {}", label_name, document_id, e),
+        }
+    }
+
+    /// Records the language a document is searched in, detected in its text or
+    /// the configured default. Failures are logged and never affect the OCR result.
+    async fn apply_search_language(&self, document_id: Uuid, text: &str) {
+        let language = self.document_language.search_language(text);
+        match self.db.set_document_search_language(document_id, language).await {
+            Ok(_) => tracing::debug!("Document {} is searched in {}", document_id, language),
+            Err(e) => warn!("Failed to record the search language of document {}: {}", document_id, e),
+        }
+    }
+
+    /// Keeps a note's front-matter as document metadata and labels the document
+    /// with its tags. Failures are logged and never affect the OCR result.
+    async fn apply_front_matter(&self, document_id: Uuid, user_id: Uuid, front_matter: &FrontMatter) {
+        if let Err(e) = self.db.set_document_front_matter(document_id, &front_matter.fields).await {
+            warn!("Failed to record the front-matter of document {}: {}", document_id, e);
+        }
+        if front_matter.tags.is_empty() {
+            return;
+        }
+        match self.db.add_document_user_labels(document_id, user_id, &front_matter.tags).await {
+            Ok(count) => info!("Labeled document {} with {} front-matter tags", document_id, count),
+            Err(e) => warn!("Failed to apply front-matter tags to document {}: {}", document_id, e),
+        }
+    }
+
+    /// Sets the document date from its text, its file or its ingestion, in the
+    /// order the user prefers. Failures are logged and never affect the OCR result.
+    async fn apply_document_date(&self, document_id: Uuid, text: &str, settings: &crate::models::Settings) {
+        let options = DocumentDateOptions::from_settings(settings);
+
+        let row = sqlx::query(
+            "SELECT original_modified_at, original_created_at, created_at, source_metadata FROM documents WHERE id = $1"
+        )
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await;
+        let (file_date, ingestion_date) = match row {
+            Ok(Some(row)) => {
+                let modified: Option<DateTime<Utc>> = row.get("original_modified_at");
+                let created: Option<DateTime<Utc>> = row.get("original_created_at");
+                let ingested: DateTime<Utc> = row.get("created_at");
+                let metadata: Option<serde_json::Value> = row.get("source_metadata");
+                (file_date(metadata.as_ref(), modified, created), Some(ingested.date_naive()))
+            }
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to load dates of document {}: {}", document_id, e);
+                return;
+            }
+        };
+
+        let Some((date, source)) = options.resolve(Some(text), file_date, ingestion_date) else {
+            tracing::debug!("No document date found for document {}", document_id);
+            return;
+        };
+        match self.db.set_document_date(document_id, date, source.as_str()).await {
+            Ok(()) => info!("Dated document {} {} (from {})", document_id, date, source.as_str()),
+            Err(e) => warn!("Failed to set date of document {}: {}", document_id, e),
+        }
+    }
+
+    /// Barcodes and QR codes decoded from a document, or None when they could
+    /// not be decoded
+    async fn decode_barcodes(
+        &self,
+        document_id: Uuid,
+        file_path: &str,
+        mime_type: &str,
+        ocr_service: &EnhancedOcrService,
+    ) -> Option<Vec<DecodedBarcode>> {
+        match ocr_service.decode_barcodes(file_path, mime_type).await {
+            Ok(barcodes) => {
+                if !barcodes.is_empty() {
+                    info!("Decoded {} barcodes in document {}", barcodes.len(), document_id);
+                }
+                Some(barcodes)
+            }
+            Err(e) => {
+                warn!("Could not decode barcodes of document {}: {}", document_id, e);
+                None
+            }
+        }
+    }
+
+    /// Extracts the tables of a PDF and stores them with the document
+    async fn apply_table_extraction(
+        &self,
+        document_id: Uuid,
+        file_path: &str,
+        mime_type: &str,
+        ocr_service: &EnhancedOcrService,
+    ) {
+        live_log::log_step("Extracting tables");
+        let tables = match ocr_service.extract_tables(file_path, mime_type).await {
+            Ok(tables) => tables,
+            Err(e) => {
+                warn!("Could not extract tables of document {}: {}", document_id, e);
+                return;
+            }
+        };
+        if !tables.is_empty() {
+            info!("Extracted {} tables from document {}", tables.len(), document_id);
+        }
+        if let Err(e) = self.db.set_document_tables(document_id, &tables).await {
+            warn!("Failed to record tables of document {}: {}", document_id, e);
+        }
+    }
+
+    /// Reads the named OCR regions of the document's labels and stores their text
+    /// as the document's fields. Documents without such labels are left alone.
+    async fn apply_region_fields(
+        &self,
+        document_id: Uuid,
+        file_path: &str,
+        mime_type: &str,
+        settings: &crate::models::Settings,
+        ocr_service: &EnhancedOcrService,
+    ) {
+        let regions = match self.db.get_document_ocr_regions(document_id).await {
+            Ok(regions) if regions.is_empty() => return,
+            Ok(regions) => regions,
+            Err(e) => {
+                warn!("Could not load the OCR regions of document {}: {}", document_id, e);
+                return;
+            }
+        };
+
+        live_log::log_step("Reading named regions");
+        let mut fields = Vec::with_capacity(regions.len());
+        for (label, named) in regions {
+            match ocr_service.extract_region(file_path, mime_type, Some(named.page), &named.region, settings).await {
+                Ok(result) => fields.push(ExtractedField {
+                    name: named.name,
+                    label,
+                    page: named.page,
+                    text: result.text.trim().to_string(),
+                    confidence: result.confidence,
+                }),
+                Err(e) => warn!(
+                    "Could not read region '{}' of label '{}' from document {}: {}",
+                    named.name, label, document_id, e
+                ),
+            }
+        }
+
+        info!("Read {} named regions of document {}", fields.len(), document_id);
+        if let Err(e) = self.db.set_document_fields(document_id, &fields).await {
+            warn!("Failed to record the fields of document {}: {}", document_id, e);
+        }
+    }
+
+    /// OCRs only the pages of a new file version whose renders differ from the
+    /// previous version and reuses the previous text for the others. None means
+    /// the whole document has to be OCR'd: the previous version has no completed
+    /// OCR, its page count differs, or the comparison was inconclusive.
+    async fn incremental_pdf_ocr(
+        &self,
+        document_id: Uuid,
+        previous_document_id: Uuid,
+        file_path: &str,
+        settings: &crate::models::Settings,
+        ocr_service: &EnhancedOcrService,
+    ) -> Option<(OcrResult, IncrementalOcrRecord)> {
+        let start_time = std::time::Instant::now();
+        let previous = sqlx::query(
+            r#"
+            SELECT COALESCE(original_file_path, file_path) AS file_path, ocr_text,
+                   COALESCE(ocr_confidence_raw, ocr_confidence) AS confidence
+            FROM documents
+            WHERE id = $1 AND mime_type = 'application/pdf' AND ocr_status = 'completed'
+            "#
+        )
+        .bind(previous_document_id)
+        .fetch_optional(&self.pool)
+        .await;
+        let previous = match previous {
+            Ok(Some(row)) => row,
+            Ok(None) => {
+                info!("Previous version {} of document {} has no OCR text to reuse, OCRing every page",
+                      previous_document_id, document_id);
+                return None;
+            }
+            Err(e) => {
+                warn!("Could not load previous version {} of document {}: {}", previous_document_id, document_id, e);
+                return None;
+            }
+        };
+        let previous_path: String = previous.get("file_path");
+        let previous_text: Option<String> = previous.get("ocr_text");
+        let previous_confidence: Option<f32> = previous.get("confidence");
+
+        let diffs = match ocr_service.diff_pdf_pages(&previous_path, file_path).await {
+            Ok(Some(diffs)) => diffs,
+            Ok(None) => {
+                info!("Page count of document {} changed since version {}, OCRing every page", document_id, previous_document_id);
+                return None;
+            }
+            Err(e) => {
+                warn!("Could not compare document {} with version {}, OCRing every page: {}", document_id, previous_document_id, e);
+                return None;
+            }
+        };
+        let page_count = diffs.len();
+        let previous_pages = previous_text.as_deref().and_then(|text| split_page_text(text, page_count));
+        let (Some(previous_pages), Some(reocr_pages)) = (previous_pages.as_deref(), pages_to_reocr(&diffs, previous_pages.as_deref())) else {
+            info!("Comparing document {} with version {} was inconclusive, OCRing every page", document_id, previous_document_id);
+            return None;
+        };
+
+        let reocr_results = if reocr_pages.is_empty() {
+            Vec::new()
+        } else {
+            match ocr_service.extract_pdf_pages(file_path, &page_spec(&reocr_pages), settings).await {
+                Ok(result) => result.pages,
+                Err(e) => {
+                    warn!("Could not OCR the changed pages of document {}, OCRing every page: {}", document_id, e);
+                    return None;
+                }
+            }
+        };
+
+        let text = merge_page_text(
+            previous_pages,
+            &reocr_results.iter().map(|page| (page.page, page.text.clone())).collect::<Vec<_>>(),
+        );
+        let unchanged_pages = page_count - reocr_pages.len();
+        let confidence = (previous_confidence.unwrap_or(0.0) * unchanged_pages as f32
+            + reocr_results.iter().map(|page| page.confidence).sum::<f32>())
+            / page_count as f32;
+        info!("OCR'd {} of {} pages of document {} again, reusing the text of version {} for the others",
+              reocr_pages.len(), page_count, document_id, previous_document_id);
+
+        let result = OcrResult {
+            word_count: text.split_whitespace().count(),
+            text,
+            confidence,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+            preprocessing_applied: vec![format!("Incremental PDF OCR ({} of {} pages)", reocr_pages.len(), page_count)],
+            processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
+        };
+        let record = IncrementalOcrRecord { previous_document_id, page_count, reocr_pages };
+        Some((result, record))
+    }
+
     /// Mark an item as failed
     async fn mark_failed(&self, item_id: Uuid, error: &str) -> Result<()> {
         let result = sqlx::query(
@@ -366,6 +1273,10 @@ impl OcrQueueService {
                 END,
                 error_message = $2,
                 started_at = NULL,
+                completed_at = CASE
+                    WHEN attempts >= max_attempts THEN NOW()
+                    ELSE NULL
+                END,
                 worker_id = NULL
             WHERE id = $1
             RETURNING status
@@ -385,28 +1296,103 @@ impl OcrQueueService {
     }
 
     /// Process a single queue item
+    #[tracing::instrument(
+        name = "ocr_job",
+        skip_all,
+        fields(
+            job_id = %item.id,
+            document_id = %item.document_id,
+            user_id = tracing::field::Empty,
+            request_id = tracing::field::Empty,
+        )
+    )]
     pub async fn process_item(&self, item: OcrQueueItem, ocr_service: &EnhancedOcrService) -> Result<()> {
+        // The OCR debug endpoint streams the job's steps and tool output while it runs
+        live_log::capture(item.document_id, self.process_item_captured(item, ocr_service)).await
+    }
+
+    async fn process_item_captured(&self, item: OcrQueueItem, ocr_service: &EnhancedOcrService) -> Result<()> {
         let start_time = std::time::Instant::now();
         
-        // Get document details including filename for validation
+        // Get document details including filename for validation. An optimized PDF
+        // is OCRed from the original when one was kept, at its full image quality.
         let document = sqlx::query(
             r#"
-            SELECT file_path, mime_type, user_id, filename, file_size
-            FROM documents
-            WHERE id = $1
+            SELECT COALESCE(d.original_file_path, d.file_path) AS file_path, d.mime_type, d.user_id, d.filename,
+                   CASE WHEN d.original_file_path IS NOT NULL THEN d.original_file_size ELSE d.file_size END AS file_size,
+                   d.ocr_manually_corrected,
+                   d.ocr_handwriting, s.config->>'processing_mode' AS processing_mode,
+                   s.config->'ocr_dpi' AS source_ocr_dpi,
+                   (SELECT MAX(l.ocr_dpi) FROM document_labels dl JOIN labels l ON l.id = dl.label_id
+                    WHERE dl.document_id = d.id) AS label_ocr_dpi,
+                   d.split_parent_id IS NOT NULL AS is_split_part, d.archived_at IS NOT NULL AS is_archived,
+                   d.archive_parent_id IS NOT NULL AS is_archive_entry, q.ocr_overrides, q.request_id,
+                   d.source_metadata->>'previous_version_document_id' AS previous_version_document_id
+            FROM documents d
+            LEFT JOIN sources s ON s.id = d.source_id
+            LEFT JOIN ocr_queue q ON q.id = $2
+            WHERE d.id = $1
             "#
         )
         .bind(item.document_id)
+        .bind(item.id)
         .fetch_optional(&self.pool)
         .await?;
 
+        // Ties the job's logs to the request or sync that queued it
+        if let Some(request_id) = document.as_ref().and_then(|row| row.get::<Option<String>, _>("request_id")) {
+            tracing::Span::current().record("request_id", tracing::field::display(request_id));
+        }
+
         match document {
+            Some(row) if row.get::<bool, _>("ocr_manually_corrected") => {
+                // Forced retries clear the correction before enqueueing, so anything
+                // reaching this point was queued automatically and must not overwrite it
+                info!("Skipping OCR job {} for document {}: OCR text was manually corrected",
+                      item.id, item.document_id);
+                sqlx::query(
+                    r#"
+                    UPDATE documents
+                    SET ocr_status = 'completed',
+                        updated_at = NOW()
+                    WHERE id = $1
+                      AND ocr_status IN ('pending', 'processing')
+                    "#
+                )
+                .bind(item.document_id)
+                .execute(&self.pool)
+                .await?;
+                self.mark_completed(item.id, 0).await?;
+            }
             Some(row) => {
                 let file_path: String = row.get("file_path");
                 let mime_type: String = row.get("mime_type");
                 let user_id: Option<Uuid> = row.get("user_id");
+                if let Some(user_id) = user_id {
+                    tracing::Span::current().record("user_id", tracing::field::display(user_id));
+                }
                 let filename: String = row.get("filename");
                 let file_size: i64 = row.get("file_size");
+                let handwriting: bool = row.get("ocr_handwriting");
+                let is_split_part: bool = row.get("is_split_part");
+                let is_archived: bool = row.get("is_archived");
+                let is_archive_entry: bool = row.get("is_archive_entry");
+                let overrides = Self::resolve_ocr_overrides(item.id, row.get("ocr_overrides"));
+                let previous_version_id = row
+                    .get::<Option<String>, _>("previous_version_document_id")
+                    .and_then(|id| Uuid::parse_str(&id).ok());
+                let processing_mode = if overrides.is_some() {
+                    SourceProcessingMode::Full
+                } else {
+                    Self::resolve_processing_mode(item.document_id, row.get("processing_mode"))
+                };
+
+                if processing_mode == SourceProcessingMode::None {
+                    info!("Skipping OCR job {} for document {}: its source stores documents without text extraction",
+                          item.id, item.document_id);
+                    self.mark_skipped(item.id, item.document_id, start_time.elapsed().as_millis() as i32).await?;
+                    return Ok(());
+                }
                 
                 // Format file size for better readability
                 let file_size_mb = file_size as f64 / (1024.0 * 1024.0);
@@ -415,13 +1401,59 @@ impl OcrQueueService {
                     "Processing OCR job {} for document {} | File: '{}' | Type: {} | Size: {:.2} MB", 
                     item.id, item.document_id, filename, mime_type, file_size_mb
                 );
+                live_log::log_step(format!("Processing '{}' ({}, {:.2} MB)", filename, mime_type, file_size_mb));
                 // Get user's OCR settings or use defaults
-                let settings = if let Some(user_id) = user_id {
+                let mut settings = if let Some(user_id) = user_id {
                     self.db.get_user_settings(user_id).await.ok().flatten()
                         .unwrap_or_else(|| crate::models::Settings::default())
                 } else {
                     crate::models::Settings::default()
                 };
+                let source_ocr_dpi = Self::resolve_source_ocr_dpi(item.document_id, row.get("source_ocr_dpi"));
+                settings.ocr_dpi = EnhancedOcrService::resolve_ocr_dpi(settings.ocr_dpi, source_ocr_dpi, row.get("label_ocr_dpi"));
+                if let Some(overrides) = &overrides {
+                    overrides.apply_to(&mut settings);
+                }
+                // Read with the settings so a change made mid-run leaves the document marked stale
+                let settings_version = match user_id {
+                    Some(user_id) => self.db.get_ocr_settings_version(user_id).await.unwrap_or(1),
+                    None => 1,
+                };
+
+                // Archives become one document per contained file. Files extracted from an
+                // archive are never extracted again; nested archives were handled with their parent.
+                if settings.extract_archives && is_archive_mime(&mime_type) && !is_archive_entry && !is_archived {
+                    if let Some(user_id) = user_id {
+                        match self.extract_archive_entries(item.document_id, user_id, &file_path, &settings, item.priority).await {
+                            Ok(extracted) => {
+                                info!("Extracted {} documents from archive {} and archived it", extracted, item.document_id);
+                            }
+                            Err(e) => {
+                                warn!("Could not extract archive {}, keeping it whole: {}", item.document_id, e);
+                            }
+                        }
+                        self.mark_skipped(item.id, item.document_id, start_time.elapsed().as_millis() as i32).await?;
+                        return Ok(());
+                    }
+                }
+
+                // Multi-document scans become one document per part, each OCR'd on its own
+                if settings.auto_split_on_separator && mime_type == "application/pdf" && !is_split_part && !is_archived {
+                    if let Some(user_id) = user_id {
+                        match self.split_on_separators(item.document_id, user_id, &file_path, &filename, &settings, item.priority, ocr_service).await {
+                            Ok(0) => {}
+                            Ok(parts) => {
+                                info!("Split document {} into {} documents at separator pages and archived the original",
+                                      item.document_id, parts);
+                                self.mark_skipped(item.id, item.document_id, start_time.elapsed().as_millis() as i32).await?;
+                                return Ok(());
+                            }
+                            Err(e) => {
+                                warn!("Could not split document {} at separator pages, processing it whole: {}", item.document_id, e);
+                            }
+                        }
+                    }
+                }
 
                 // Create progress callback to report page-level OCR progress
                 let progress_pool = self.pool.clone();
@@ -440,12 +1472,118 @@ impl OcrQueueService {
                     });
                 }));
 
-                // Perform enhanced OCR
-                match ocr_service.extract_text_with_context(&file_path, &mime_type, &filename, file_size, &settings, progress_callback).await {
-                    Ok(ocr_result) => {
+                // Text-only extraction runs no OCR engine
+                let mut ocr_engine: Option<String> = None;
+                let mut ocr_engine_note: Option<String> = None;
+                let mut incremental_ocr: Option<IncrementalOcrRecord> = None;
+                let extraction = if processing_mode == SourceProcessingMode::TextOnly {
+                    live_log::log_step("Reading the existing text layer, OCR is disabled for the source");
+                    ocr_service.extract_existing_text(&file_path, &mime_type, &settings).await
+                } else {
+                    ocr_engine = Some(TESSERACT_ENGINE.to_string());
+                    let handwriting_result = match EngineRoute::for_document(handwriting, self.handwriting_engine.is_some()) {
+                        EngineRoute::Tesseract => None,
+                        EngineRoute::TesseractForHandwriting => {
+                            info!("Document {} is handwritten but no handwriting OCR engine is configured, using Tesseract",
+                                  item.document_id);
+                            ocr_engine_note = Some(NO_HANDWRITING_ENGINE_NOTE.to_string());
+                            None
+                        }
+                        EngineRoute::Handwriting => {
+                            match self.recognize_handwriting(&file_path, &filename, &mime_type, &settings).await {
+                                Ok((engine, result)) => {
+                                    ocr_engine = Some(engine);
+                                    Some(result)
+                                }
+                                Err(e) => {
+                                    warn!("Handwriting OCR failed for document {}, falling back to Tesseract: {}", item.document_id, e);
+                                    ocr_engine_note = Some(handwriting_engine_failed_note(&e));
+                                    None
+                                }
+                            }
+                        }
+                    };
+
+                    match handwriting_result {
+                        Some(result) => Ok(result),
+                        None => {
+                            // A new version of a synced PDF only needs its changed pages OCR'd
+                            let incremental = match previous_version_id {
+                                Some(previous_id) if settings.ocr_incremental_pages && mime_type == "application/pdf" => {
+                                    self.incremental_pdf_ocr(item.document_id, previous_id, &file_path, &settings, ocr_service).await
+                                }
+                                _ => None,
+                            };
+
+                            match incremental {
+                                Some((result, record)) => {
+                                    incremental_ocr = Some(record);
+                                    Ok(result)
+                                }
+                                None => {
+                                    // Give large PDFs a quick preview before the full-quality pass
+                                    self.store_quick_preview(item.document_id, &file_path, &mime_type, &settings, ocr_service).await;
+
+                                    // Perform enhanced OCR
+                                    live_log::log_step(format!("Extracting text with {} DPI", settings.ocr_dpi));
+                                    ocr_service.extract_text_with_context(&file_path, &mime_type, &filename, file_size, &settings, progress_callback).await
+                                }
+                            }
+                        }
+                    }
+                };
+
+                match extraction {
+                    Ok(mut ocr_result) => {
+                        live_log::log_step(format!(
+                            "Extracted {} words at {:.1}% confidence in {}ms",
+                            ocr_result.word_count, ocr_result.confidence, ocr_result.processing_time_ms
+                        ));
+                        ocr_result.text = self.sanitize_ocr_output(item.document_id, &ocr_result.text);
+
+                        // Notes index their body; the front-matter becomes metadata and labels
+                        let mut front_matter: Option<FrontMatter> = None;
+                        if settings.parse_front_matter && is_front_matter_mime(&mime_type) {
+                            if let Some((parsed, body)) = split_front_matter(&ocr_result.text) {
+                                // A note with nothing but front-matter stays searchable by it
+                                if !body.trim().is_empty() {
+                                    ocr_result.text = body.trim().to_string();
+                                    ocr_result.word_count = ocr_result.text.split_whitespace().count();
+                                }
+                                front_matter = Some(parsed);
+                            }
+                        }
+
+                        // Barcode values are searchable like any other text
+                        let barcodes = if settings.decode_barcodes {
+                            self.decode_barcodes(item.document_id, &file_path, &mime_type, ocr_service).await
+                        } else {
+                            None
+                        };
+                        if let Some(barcodes) = barcodes.as_deref().filter(|barcodes| !barcodes.is_empty()) {
+                            ocr_result.text = append_barcode_text(&ocr_result.text, barcodes);
+                            ocr_result.word_count = ocr_result.text.split_whitespace().count();
+                        }
+
+                        // The minimum confidence applies to the calibrated confidence
+                        let raw_confidence = ocr_result.confidence;
+                        if ocr_engine.as_deref() == Some(TESSERACT_ENGINE) {
+                            let languages = ocr_service.build_language_combination(&settings);
+                            ocr_result.confidence = self.confidence_calibration.calibrate(&languages, raw_confidence);
+                        }
+
+                        // Without OCR, scans and images legitimately have no text
+                        if processing_mode == SourceProcessingMode::TextOnly && ocr_result.text.trim().is_empty() {
+                            info!("No text layer in '{}' | Job: {} | Document: {} | OCR disabled for its source",
+                                  filename, item.id, item.document_id);
+                            self.mark_skipped(item.id, item.document_id, start_time.elapsed().as_millis() as i32).await?;
+                            return Ok(());
+                        }
+
                         // Validate OCR quality
                         if let Err(validation_error) = ocr_service.validate_ocr_quality(&ocr_result, &settings) {
                             let error_msg = format!("OCR quality validation failed: {}", validation_error);
+                            live_log::log_step(error_msg.clone());
                             warn!("⚠️  OCR quality issues for '{}' | Job: {} | Document: {} | {:.1}% confidence | {} words", 
                                   filename, item.id, item.document_id, ocr_result.confidence, ocr_result.word_count);
                             
@@ -475,6 +1613,9 @@ impl OcrQueueService {
                             .bind(&error_msg)
                             .execute(&self.pool)
                             .await?;
+                            if let Err(e) = self.db.set_ocr_settings_version(item.document_id, settings_version).await {
+                                warn!("Failed to record OCR settings version for document {}: {}", item.document_id, e);
+                            }
                             
                             self.mark_failed(item.id, &error_msg).await?;
                             return Ok(());
@@ -494,6 +1635,45 @@ impl OcrQueueService {
                             ).await {
                                 Ok(true) => {
                                     info!("✅ Transaction-safe OCR update successful for document {}", item.document_id);
+
+                                    let hybrid = ocr_result.preprocessing_applied.iter()
+                                        .any(|step| step == crate::ocr::enhanced::HYBRID_PDF_EXTRACTION);
+                                    if let Err(e) = self.db.set_ocr_hybrid_extraction(item.document_id, hybrid).await {
+                                        warn!("Failed to record extraction method for document {}: {}", item.document_id, e);
+                                    }
+                                    if let Err(e) = self.db.set_ocr_engine(item.document_id, ocr_engine.as_deref(), ocr_engine_note.as_deref()).await {
+                                        warn!("Failed to record OCR engine for document {}: {}", item.document_id, e);
+                                    }
+                                    if let Err(e) = self.db.set_ocr_raw_confidence(item.document_id, Some(raw_confidence)).await {
+                                        warn!("Failed to record raw OCR confidence for document {}: {}", item.document_id, e);
+                                    }
+                                    if let Err(e) = self.db.set_ocr_settings_version(item.document_id, settings_version).await {
+                                        warn!("Failed to record OCR settings version for document {}: {}", item.document_id, e);
+                                    }
+                                    if settings.auto_label_language {
+                                        self.apply_language_label(item.document_id, &ocr_result.text).await;
+                                    }
+                                    self.apply_search_language(item.document_id, &ocr_result.text).await;
+                                    if settings.extract_document_date {
+                                        self.apply_document_date(item.document_id, &ocr_result.text, &settings).await;
+                                    }
+                                    if let Some(barcodes) = &barcodes {
+                                        if let Err(e) = self.db.set_document_barcodes(item.document_id, barcodes).await {
+                                            warn!("Failed to record barcodes of document {}: {}", item.document_id, e);
+                                        }
+                                    }
+                                    if settings.extract_tables && mime_type == "application/pdf" {
+                                        self.apply_table_extraction(item.document_id, &file_path, &mime_type, ocr_service).await;
+                                    }
+                                    if let Err(e) = self.db.set_incremental_ocr(item.document_id, incremental_ocr.as_ref()).await {
+                                        warn!("Failed to record the re-OCR'd pages of document {}: {}", item.document_id, e);
+                                    }
+                                    if let (Some(front_matter), Some(user_id)) = (&front_matter, user_id) {
+                                        self.apply_front_matter(item.document_id, user_id, front_matter).await;
+                                    }
+                                    if mime_type == "application/pdf" || mime_type.starts_with("image/") {
+                                        self.apply_region_fields(item.document_id, &file_path, &mime_type, &settings, ocr_service).await;
+                                    }
                                 }
                                 Ok(false) => {
                                     let error_msg = "OCR update failed validation (document may have been modified)";
@@ -541,6 +1721,7 @@ impl OcrQueueService {
                         } else {
                             // Handle empty text results - fail the document since no searchable content was extracted
                             let error_msg = format!("No extractable text found in document (0 words)");
+                            live_log::log_step(error_msg.clone());
                             warn!("⚠️  No searchable content extracted for '{}' | Job: {} | Document: {} | 0 words", 
                                   filename, item.id, item.document_id);
                             
@@ -605,6 +1786,7 @@ impl OcrQueueService {
 
                         let processing_time_ms = start_time.elapsed().as_millis() as i32;
                         self.mark_completed(item.id, processing_time_ms).await?;
+                        live_log::log_step(format!("OCR completed in {}ms", processing_time_ms));
                         
                         info!(
                             "✅ OCR completed for '{}' | Job: {} | Document: {} | {:.1}% confidence | {} words | {}ms | Preprocessing: {:?}",
@@ -615,6 +1797,7 @@ impl OcrQueueService {
                     Err(e) => {
                         let error_msg = format!("OCR extraction failed: {}", e);
                         let error_str = e.to_string();
+                        live_log::log_step(error_msg.clone());
                         
                         // Classify error type and determine failure reason
                         let (failure_reason, should_suppress) = Self::classify_ocr_error(&error_str);
@@ -672,6 +1855,62 @@ impl OcrQueueService {
         Ok(())
     }
 
+    /// Stores a fast, low-DPI OCR of the first pages of a large PDF with
+    /// `ocr_status = 'preview'`. The full OCR result replaces it when done. Preview
+    /// failures are logged and never affect the full OCR run.
+    async fn store_quick_preview(
+        &self,
+        document_id: Uuid,
+        file_path: &str,
+        mime_type: &str,
+        settings: &crate::models::Settings,
+        ocr_service: &EnhancedOcrService,
+    ) {
+        if settings.ocr_quick_preview_pages <= 0 || mime_type != "application/pdf" {
+            return;
+        }
+
+        let mut preview = match ocr_service
+            .extract_pdf_preview(file_path, settings.ocr_quick_preview_pages as usize, settings)
+            .await
+        {
+            Ok(Some(preview)) => preview,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Quick OCR preview failed for document {}: {}", document_id, e);
+                return;
+            }
+        };
+        preview.text = self.sanitize_ocr_output(document_id, &preview.text);
+
+        let result = sqlx::query(
+            r#"
+            UPDATE documents
+            SET ocr_text = $2,
+                ocr_confidence = $3,
+                ocr_word_count = $4,
+                ocr_status = 'preview',
+                updated_at = NOW()
+            WHERE id = $1
+              AND ocr_status IN ('pending', 'processing')
+            "#
+        )
+        .bind(document_id)
+        .bind(&preview.text)
+        .bind(preview.confidence)
+        .bind(preview.word_count as i32)
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(_) => info!(
+                "Stored quick OCR preview for document {} ({} words in {}ms)",
+                document_id, preview.word_count, preview.processing_time_ms
+            ),
+            Err(e) => warn!("Failed to store OCR preview for document {}: {}", document_id, e),
+        }
+    }
+
     /// Pause OCR processing
     pub fn pause(&self) {
         self.is_paused.store(true, Ordering::SeqCst);
@@ -692,8 +1931,10 @@ impl OcrQueueService {
     /// Start the worker loop
     pub async fn start_worker(self: Arc<Self>) -> Result<()> {
         let semaphore = Arc::new(Semaphore::new(self.max_concurrent_jobs));
+        // Large files also hold a permit of their own lane, so they never take every job slot
+        let large_file_lane = Arc::new(Semaphore::new(self.scheduling.large_file_concurrency));
         let ocr_service = Arc::new(EnhancedOcrService::new(
-            "/tmp".to_string(),
+            crate::ocr::temp_files::temp_dir(),
             (*self.file_service).clone(),
             self.max_pdf_size_mb,
             self.max_office_document_size_mb,
@@ -728,8 +1969,15 @@ impl OcrQueueService {
                 "message" => "Worker loop iteration - checking for items to process"
             );
 
-            // Check for items to process
-            match self.dequeue().await {
+            // Check for items to process; while the large-file lane is full only
+            // smaller items are claimed
+            let large_file_lane_full = large_file_lane.available_permits() == 0;
+            let next = if large_file_lane_full {
+                self.dequeue_excluding_large().await
+            } else {
+                self.dequeue().await
+            };
+            match next {
                 Ok(Some(item)) => {
                     crate::debug_log!("OCR_WORKER", 
                         "worker_id" => &self.worker_id,
@@ -739,6 +1987,11 @@ impl OcrQueueService {
                         "message" => "Dequeued job, spawning processing task"
                     );
                     
+                    let large_file_permit = if self.scheduling.is_large(item.file_size) {
+                        Some(large_file_lane.clone().acquire_owned().await?)
+                    } else {
+                        None
+                    };
                     let permit = semaphore.clone().acquire_owned().await?;
                     let self_clone = self.clone();
                     let ocr_service_clone = ocr_service.clone();
@@ -763,6 +2016,7 @@ impl OcrQueueService {
                             }
                         }
                         drop(permit);
+                        drop(large_file_permit);
                     });
                 }
                 Ok(None) => {
@@ -793,8 +2047,8 @@ impl OcrQueueService {
     ) -> Result<()> {
         use std::path::Path;
         
-        // Use the FileService to get the proper processed images directory
-        let processed_images_dir = self.file_service.get_processed_images_path();
+        // Use the FileService to get the user's processed images directory
+        let processed_images_dir = self.file_service.get_processed_images_path().join(user_id.to_string());
         
         // Ensure the directory exists with proper error handling
         if let Err(e) = tokio::fs::create_dir_all(&processed_images_dir).await {
@@ -1060,6 +2314,82 @@ impl OcrQueueService {
         })
     }
 
+    /// Jobs that finished in `[from, to)`, grouped into buckets of `granularity`
+    /// aligned to UTC. Every bucket in the window is returned, including empty
+    /// ones. Completed jobs are only counted until `cleanup_completed` removes them.
+    pub async fn get_processing_timeseries(
+        &self,
+        granularity: OcrStatsGranularity,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<OcrTimeseriesBucket>> {
+        let rows = sqlx::query(
+            r#"
+            WITH buckets AS (
+                SELECT generate_series(
+                    date_trunc($1, $2::timestamptz, 'UTC'),
+                    $3::timestamptz - INTERVAL '1 microsecond',
+                    $4 * INTERVAL '1 second'
+                ) AS start
+            ),
+            finished AS (
+                SELECT date_trunc($1, completed_at, 'UTC') AS start,
+                       status,
+                       processing_time_ms,
+                       GREATEST(COALESCE(progress_total, 0), 1) AS pages
+                FROM ocr_queue
+                WHERE status IN ('completed', 'failed')
+                  AND completed_at >= $2
+                  AND completed_at < $3
+            ),
+            stats AS (
+                SELECT start,
+                       COUNT(*) FILTER (WHERE status = 'completed') AS documents_completed,
+                       SUM(pages) FILTER (WHERE status = 'completed') AS pages_completed,
+                       COUNT(*) FILTER (WHERE status = 'failed') AS documents_failed,
+                       AVG(processing_time_ms) FILTER (WHERE status = 'completed') AS avg_processing_time_ms,
+                       percentile_cont(0.95) WITHIN GROUP (ORDER BY processing_time_ms)
+                           FILTER (WHERE status = 'completed') AS p95_processing_time_ms
+                FROM finished
+                GROUP BY start
+            )
+            SELECT b.start,
+                   COALESCE(s.documents_completed, 0) AS documents_completed,
+                   COALESCE(s.pages_completed, 0)::BIGINT AS pages_completed,
+                   COALESCE(s.documents_failed, 0) AS documents_failed,
+                   s.avg_processing_time_ms::DOUBLE PRECISION AS avg_processing_time_ms,
+                   s.p95_processing_time_ms::DOUBLE PRECISION AS p95_processing_time_ms
+            FROM buckets b
+            LEFT JOIN stats s ON s.start = b.start
+            ORDER BY b.start
+            "#
+        )
+        .bind(granularity.as_str())
+        .bind(from)
+        .bind(to)
+        .bind(granularity.bucket_duration().num_seconds() as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let documents_completed: i64 = row.get("documents_completed");
+                let documents_failed: i64 = row.get("documents_failed");
+                let finished = documents_completed + documents_failed;
+                OcrTimeseriesBucket {
+                    start: row.get("start"),
+                    documents_completed,
+                    pages_completed: row.get("pages_completed"),
+                    documents_failed,
+                    failure_rate: if finished > 0 { documents_failed as f64 / finished as f64 } else { 0.0 },
+                    avg_processing_time_ms: row.get("avg_processing_time_ms"),
+                    p95_processing_time_ms: row.get("p95_processing_time_ms"),
+                }
+            })
+            .collect())
+    }
+
     /// Requeue failed items
     pub async fn requeue_failed_items(&self) -> Result<i64> {
         tracing::debug!("Attempting to requeue failed items");
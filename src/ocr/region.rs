@@ -0,0 +1,123 @@
+//! Rectangular regions of a page, in relative coordinates, for OCR of a single area.
+//!
+//! Labels act as document types: named regions stored for a label, such as the
+//! total of an invoice, are read from every document with that label when it is
+//! OCR'd and kept with the document as [extracted fields](ExtractedField).
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A bounding box relative to the page size: `0.0` is the top/left edge and `1.0`
+/// the bottom/right edge, so the same region applies at any render resolution
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OcrRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A region resolved to pixels of a concrete image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RegionError {
+    #[error("Region coordinates must be finite numbers")]
+    NotFinite,
+    #[error("Region width and height must be greater than 0")]
+    Empty,
+    #[error("Region ({x}, {y}, {width}x{height}) is outside the image: coordinates are relative, from 0.0 to 1.0")]
+    OutOfBounds { x: f64, y: f64, width: f64, height: f64 },
+    #[error("Region is smaller than one pixel of the {image_width}x{image_height} image")]
+    TooSmall { image_width: u32, image_height: u32 },
+    #[error("Region name must not be empty")]
+    MissingName,
+    #[error("Pages are numbered from 1")]
+    PageZero,
+}
+
+/// A region holding one form field, e.g. `invoice_total`, on a page of the
+/// documents of a type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NamedOcrRegion {
+    pub name: String,
+    /// 1-based PDF page the region is on; images only have page 1
+    #[serde(default = "first_page")]
+    pub page: usize,
+    pub region: OcrRegion,
+}
+
+fn first_page() -> usize {
+    1
+}
+
+impl NamedOcrRegion {
+    /// Checks the region has a name, a page and a box within the page
+    pub fn validate(&self) -> Result<(), RegionError> {
+        if self.name.trim().is_empty() {
+            return Err(RegionError::MissingName);
+        }
+        if self.page == 0 {
+            return Err(RegionError::PageZero);
+        }
+        self.region.validate()
+    }
+}
+
+/// Text read from a named region when its document was OCR'd
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractedField {
+    pub name: String,
+    /// Label the region is defined for
+    pub label: String,
+    pub page: usize,
+    pub text: String,
+    pub confidence: f32,
+}
+
+/// Tolerance for boxes whose far edge lands on 1.0 after floating point arithmetic
+const EDGE_EPSILON: f64 = 1e-9;
+
+impl OcrRegion {
+    /// Checks the box is non-empty and lies within the page
+    pub fn validate(&self) -> Result<(), RegionError> {
+        let Self { x, y, width, height } = *self;
+        if ![x, y, width, height].iter().all(|value| value.is_finite()) {
+            return Err(RegionError::NotFinite);
+        }
+        if width <= 0.0 || height <= 0.0 {
+            return Err(RegionError::Empty);
+        }
+        if x < 0.0 || y < 0.0 || x + width > 1.0 + EDGE_EPSILON || y + height > 1.0 + EDGE_EPSILON {
+            return Err(RegionError::OutOfBounds { x, y, width, height });
+        }
+        Ok(())
+    }
+
+    /// Resolves the region to a pixel rectangle of an `image_width` x `image_height` image
+    pub fn to_pixel_rect(&self, image_width: u32, image_height: u32) -> Result<PixelRect, RegionError> {
+        self.validate()?;
+
+        let left = (self.x * image_width as f64).floor() as u32;
+        let top = (self.y * image_height as f64).floor() as u32;
+        let right = ((self.x + self.width) * image_width as f64).ceil().min(image_width as f64) as u32;
+        let bottom = ((self.y + self.height) * image_height as f64).ceil().min(image_height as f64) as u32;
+
+        if right <= left || bottom <= top {
+            return Err(RegionError::TooSmall { image_width, image_height });
+        }
+
+        Ok(PixelRect {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+}
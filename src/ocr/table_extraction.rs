@@ -0,0 +1,133 @@
+//! Tables detected in the text layer of PDFs.
+//!
+//! `pdftotext -layout` keeps the columns of a table lined up with spaces.
+//! Consecutive lines with several cells separated by two or more spaces form
+//! a block, and the columns are where the gaps of every line of the block
+//! coincide. Cells that wrap onto a line of their own end the table, so long
+//! descriptions can split a table in two. The cells are stored as JSON next to
+//! the document and can be exported as CSV.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::utils::csv;
+
+/// Fewest rows and columns a block of aligned lines needs to count as a table
+const MIN_TABLE_ROWS: usize = 2;
+const MIN_TABLE_COLUMNS: usize = 2;
+
+/// Consecutive blank lines that end a table
+const MAX_BLANK_LINES: usize = 1;
+
+/// A table found in a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractedTable {
+    /// Page the table is on, starting at 1
+    pub page: usize,
+    /// Cells row by row, all rows with the same number of cells. The first
+    /// row is usually the header.
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Finds the tables in `pdftotext -layout` output, whose pages are separated
+/// by form feeds
+pub fn parse_layout_tables(text: &str) -> Vec<ExtractedTable> {
+    let mut tables = Vec::new();
+    for (page_index, page) in text.split('\u{c}').enumerate() {
+        let mut block: Vec<Vec<char>> = Vec::new();
+        let mut blank_lines = 0;
+        for line in page.lines() {
+            let chars: Vec<char> = line.trim_end().chars().collect();
+            if chars.is_empty() {
+                blank_lines += 1;
+                if blank_lines > MAX_BLANK_LINES {
+                    tables.extend(table_from_block(page_index + 1, &block));
+                    block.clear();
+                }
+                continue;
+            }
+            blank_lines = 0;
+
+            if cell_count(&chars) >= MIN_TABLE_COLUMNS {
+                block.push(chars);
+            } else {
+                tables.extend(table_from_block(page_index + 1, &block));
+                block.clear();
+            }
+        }
+        tables.extend(table_from_block(page_index + 1, &block));
+    }
+    tables
+}
+
+/// Whether position `index` of a line separates cells: a space in a run of
+/// two or more, indentation, or past the end of the line
+fn is_separator(line: &[char], index: usize) -> bool {
+    let Some(c) = line.get(index) else {
+        return true;
+    };
+    if !c.is_whitespace() {
+        return false;
+    }
+    let before = index.checked_sub(1).map_or(true, |previous| line[previous].is_whitespace());
+    let after = line.get(index + 1).map_or(true, |next| next.is_whitespace());
+    before || after
+}
+
+fn cell_count(line: &[char]) -> usize {
+    (0..line.len())
+        .filter(|&index| !is_separator(line, index) && (index == 0 || is_separator(line, index - 1)))
+        .count()
+}
+
+/// Splits a block of lines into columns where every line has a gap
+fn table_from_block(page: usize, block: &[Vec<char>]) -> Option<ExtractedTable> {
+    if block.len() < MIN_TABLE_ROWS {
+        return None;
+    }
+
+    let width = block.iter().map(Vec::len).max().unwrap_or(0);
+    let gap: Vec<bool> = (0..width)
+        .map(|index| block.iter().all(|line| is_separator(line, index)))
+        .collect();
+    let mut columns: Vec<(usize, usize)> = Vec::new();
+    let mut start = None;
+    for (index, &is_gap) in gap.iter().chain(std::iter::once(&true)).enumerate() {
+        match (is_gap, start) {
+            (false, None) => start = Some(index),
+            (true, Some(column_start)) => {
+                columns.push((column_start, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if columns.len() < MIN_TABLE_COLUMNS {
+        return None;
+    }
+
+    let rows = block
+        .iter()
+        .map(|line| {
+            columns
+                .iter()
+                .map(|&(start, end)| {
+                    let end = end.min(line.len());
+                    let cell: String = line.get(start..end).map(|chars| chars.iter().collect()).unwrap_or_default();
+                    cell.trim().to_string()
+                })
+                .collect()
+        })
+        .collect();
+    Some(ExtractedTable { page, rows })
+}
+
+/// A table as CSV, one record per row
+pub fn table_to_csv(table: &ExtractedTable) -> String {
+    table.rows.iter().map(csv::record).collect()
+}
+
+/// Tables as CSV, separated by an empty line
+pub fn tables_to_csv(tables: &[ExtractedTable]) -> String {
+    tables.iter().map(table_to_csv).collect::<Vec<_>>().join("\r\n")
+}
@@ -0,0 +1,126 @@
+//! Naming and cleanup of the temporary files OCR writes to `TEMP_DIR`
+//!
+//! Every temporary file or directory starts with [`TEMP_FILE_PREFIX`]. Jobs
+//! remove their own files, but a crash mid-job leaks them, so a maintenance
+//! task removes prefixed entries older than `TEMP_FILE_MAX_AGE_HOURS` at
+//! startup and every hour. Entries without the prefix are never touched.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, error, info, warn};
+
+/// Start of the name of every temporary file and directory written by OCR
+pub const TEMP_FILE_PREFIX: &str = "readur-tmp-";
+
+/// How often stale temporary files are looked for
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Directory OCR writes its temporary files to
+pub fn temp_dir() -> String {
+    std::env::var("TEMP_DIR").unwrap_or_else(|_| "/tmp".to_string())
+}
+
+/// `name` with the temporary file prefix, to be created in the temp directory
+pub fn temp_file_name(name: &str) -> String {
+    format!("{}{}", TEMP_FILE_PREFIX, name)
+}
+
+/// Full path of a prefixed temporary file or directory in `dir`
+pub fn temp_path(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir, temp_file_name(name))
+}
+
+/// What one cleanup pass removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TempCleanupReport {
+    pub entries_removed: usize,
+    pub bytes_reclaimed: u64,
+}
+
+/// Removes prefixed files and directories in `dir` last modified more than
+/// `max_age` ago. Entries that can't be inspected or removed are skipped.
+pub async fn remove_stale_temp_files(dir: &Path, max_age: Duration) -> Result<TempCleanupReport> {
+    let dir = dir.to_path_buf();
+    tokio::task::spawn_blocking(move || remove_stale_entries(&dir, max_age)).await?
+}
+
+fn remove_stale_entries(dir: &Path, max_age: Duration) -> Result<TempCleanupReport> {
+    let mut report = TempCleanupReport::default();
+    let now = SystemTime::now();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_name().to_string_lossy().starts_with(TEMP_FILE_PREFIX) {
+            continue;
+        }
+
+        let path = entry.path();
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                warn!("Failed to inspect temporary file {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age <= max_age {
+            continue;
+        }
+
+        let size = if metadata.is_dir() { directory_size(&path) } else { metadata.len() };
+        let removed = if metadata.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        match removed {
+            Ok(()) => {
+                debug!("Removed stale temporary file {} ({} bytes)", path.display(), size);
+                report.entries_removed += 1;
+                report.bytes_reclaimed += size;
+            }
+            Err(e) => warn!("Failed to remove stale temporary file {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(report)
+}
+
+fn directory_size(dir: &Path) -> u64 {
+    let mut size = 0;
+    let mut pending: Vec<PathBuf> = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => pending.push(entry.path()),
+                Ok(metadata) => size += metadata.len(),
+                Err(_) => {}
+            }
+        }
+    }
+    size
+}
+
+/// Removes stale temporary files from `dir` now and then once per
+/// [`CLEANUP_INTERVAL`], forever
+pub async fn run_temp_file_cleanup(dir: String, max_age: Duration) {
+    info!("Removing temporary OCR files older than {:?} from {}", max_age, dir);
+    let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+    loop {
+        interval.tick().await;
+        match remove_stale_temp_files(Path::new(&dir), max_age).await {
+            Ok(report) if report.entries_removed > 0 => info!(
+                "Removed {} stale temporary OCR files from {}, reclaiming {} bytes",
+                report.entries_removed, dir, report.bytes_reclaimed
+            ),
+            Ok(_) => debug!("No stale temporary OCR files in {}", dir),
+            Err(e) => error!("Failed to clean up temporary OCR files in {}: {}", dir, e),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Cleans OCR output before it is stored. Postgres rejects NUL in text columns,
+//! so a single stray byte from an OCR engine or a malformed text layer would
+//! otherwise fail the whole job.
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// What to do beyond removing NUL bytes, which is always done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSanitizerOptions {
+    /// Drop control characters other than tab, line breaks and form feed (page breaks)
+    pub strip_control_chars: bool,
+    /// Normalize to Unicode NFC so composed and decomposed accents match in search
+    pub normalize_unicode: bool,
+}
+
+impl Default for TextSanitizerOptions {
+    fn default() -> Self {
+        Self {
+            strip_control_chars: true,
+            normalize_unicode: true,
+        }
+    }
+}
+
+/// Sanitized text and what had to be changed to get it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizedText {
+    pub text: String,
+    pub nul_bytes_removed: usize,
+    pub control_chars_removed: usize,
+    pub invalid_sequences_replaced: usize,
+    pub normalized: bool,
+}
+
+impl SanitizedText {
+    pub fn was_modified(&self) -> bool {
+        self.nul_bytes_removed > 0
+            || self.control_chars_removed > 0
+            || self.invalid_sequences_replaced > 0
+            || self.normalized
+    }
+}
+
+/// Whitespace-like control characters that carry layout and are kept
+fn is_layout_control(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{0C}')
+}
+
+/// Decodes raw OCR output, replacing invalid UTF-8 sequences with U+FFFD, then sanitizes it
+pub fn sanitize_ocr_bytes(bytes: &[u8], options: &TextSanitizerOptions) -> SanitizedText {
+    let decoded = String::from_utf8_lossy(bytes);
+    let invalid_sequences_replaced = match &decoded {
+        std::borrow::Cow::Borrowed(_) => 0,
+        // Count only replacements introduced by decoding, not U+FFFD already in the input
+        std::borrow::Cow::Owned(text) => {
+            let existing = bytes
+                .windows(3)
+                .filter(|window| *window == "\u{FFFD}".as_bytes())
+                .count();
+            text.matches('\u{FFFD}').count().saturating_sub(existing)
+        }
+    };
+
+    let mut sanitized = sanitize_ocr_text(&decoded, options);
+    sanitized.invalid_sequences_replaced = invalid_sequences_replaced;
+    sanitized
+}
+
+/// Removes NUL bytes and, depending on `options`, other control characters,
+/// then normalizes the result to NFC
+pub fn sanitize_ocr_text(text: &str, options: &TextSanitizerOptions) -> SanitizedText {
+    let mut nul_bytes_removed = 0;
+    let mut control_chars_removed = 0;
+
+    let cleaned: String = text
+        .chars()
+        .filter(|&c| {
+            if c == '\0' {
+                nul_bytes_removed += 1;
+                false
+            } else if options.strip_control_chars && c.is_control() && !is_layout_control(c) {
+                control_chars_removed += 1;
+                false
+            } else {
+                true
+            }
+        })
+        .collect();
+
+    let (text, normalized) = if options.normalize_unicode && !is_nfc(&cleaned) {
+        (cleaned.nfc().collect(), true)
+    } else {
+        (cleaned, false)
+    };
+
+    SanitizedText {
+        text,
+        nul_bytes_removed,
+        control_chars_removed,
+        invalid_sequences_replaced: 0,
+        normalized,
+    }
+}
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::time::{timeout, Duration};
 use super::enhanced::OcrResult;
+use super::fallback_order::{installed_fallback_config, FallbackConfig, FallbackStrategy};
+use super::fallback_stats::fallback_stats;
 
 /// User-friendly error messages for Office document extraction issues
 pub struct OfficeExtractionError;
@@ -156,6 +158,9 @@ impl From<OfficeExtractionResult> for OcrResult {
             word_count: office_result.word_count,
             preprocessing_applied: vec![office_result.extraction_method],
             processed_image_path: None, // XML extraction doesn't produce processed images
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         }
     }
 }
@@ -248,6 +253,8 @@ impl ExtractionContext {
 pub struct XmlOfficeExtractor {
     /// Temporary directory for file processing
     pub temp_dir: String,
+    /// Order of the extraction methods, of which the legacy Word tools apply here
+    fallback: FallbackConfig,
 }
 
 impl XmlOfficeExtractor {
@@ -274,7 +281,18 @@ impl XmlOfficeExtractor {
 
     /// Create a new XML Office extractor
     pub fn new(temp_dir: String) -> Self {
-        Self { temp_dir }
+        Self { temp_dir, fallback: installed_fallback_config() }
+    }
+
+    /// Use `fallback` instead of the process-wide tool order
+    pub fn with_fallback_config(mut self, fallback: FallbackConfig) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// The legacy Word tools the next `.doc` file is tried with, first to last
+    pub fn doc_tool_order(&self) -> Vec<String> {
+        self.fallback.effective_order(FallbackStrategy::LegacyDoc, &fallback_stats().snapshot())
     }
     
     /// Create a secure XML reader with protection against entity expansion attacks
@@ -656,10 +674,14 @@ impl XmlOfficeExtractor {
         
         match mime_type {
             "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => {
-                self.extract_text_from_docx(file_path, start_time, &context).await
+                let result = self.extract_text_from_docx(file_path, start_time, &context).await;
+                fallback_stats().record("docx", result.is_ok(), start_time.elapsed());
+                result
             }
             "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => {
-                self.extract_text_from_xlsx(file_path, start_time, &context).await
+                let result = self.extract_text_from_xlsx(file_path, start_time, &context).await;
+                fallback_stats().record("xlsx", result.is_ok(), start_time.elapsed());
+                result
             }
             "application/msword" => {
                 self.extract_text_from_legacy_doc(file_path, start_time).await
@@ -1072,12 +1094,9 @@ impl XmlOfficeExtractor {
         // Validate file path for security
         self.validate_file_path_security(file_path)?;
         
-        // Try external tools in order of preference
-        let tools = vec![
-            ("antiword", vec![file_path]),
-            ("catdoc", vec![file_path]),
-            ("wvText", vec![file_path]),
-        ];
+        // Try external tools in the configured order
+        let tool_order = self.doc_tool_order();
+        let tools: Vec<(&str, Vec<&str>)> = tool_order.iter().map(|tool| (tool.as_str(), vec![file_path])).collect();
         
         let mut last_error: Option<String> = None;
         let mut tried_tools = Vec::new();
@@ -1086,7 +1105,10 @@ impl XmlOfficeExtractor {
             tried_tools.push(tool_name);
             info!("Attempting DOC extraction with {}", tool_name);
             
-            match self.try_external_tool(tool_name, &args, file_path).await {
+            let tool_start = Instant::now();
+            let tool_result = self.try_external_tool(tool_name, &args, file_path).await;
+            fallback_stats().record(tool_name, tool_result.is_ok(), tool_start.elapsed());
+            match tool_result {
                 Ok(extracted_text) => {
                     let processing_time = start_time.elapsed().as_millis() as u64;
                     
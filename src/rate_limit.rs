@@ -76,6 +76,8 @@ pub struct RateLimiters {
     pub shared_link_creation: RateLimiter<Uuid>,
     /// User-based limiter for API key creation (10/hour per user)
     pub api_key_creation: RateLimiter<Uuid>,
+    /// User-based limiter for OCR settings previews (10/min per user)
+    pub ocr_preview: RateLimiter<Uuid>,
 }
 
 impl RateLimiters {
@@ -86,6 +88,7 @@ impl RateLimiters {
             comment_creation: RateLimiter::new(10, Duration::from_secs(60)),
             shared_link_creation: RateLimiter::new(20, Duration::from_secs(3600)),
             api_key_creation: RateLimiter::new(10, Duration::from_secs(3600)),
+            ocr_preview: RateLimiter::new(10, Duration::from_secs(60)),
         }
     }
 
@@ -96,6 +99,7 @@ impl RateLimiters {
         self.comment_creation.cleanup().await;
         self.shared_link_creation.cleanup().await;
         self.api_key_creation.cleanup().await;
+        self.ocr_preview.cleanup().await;
     }
 }
 
@@ -0,0 +1,57 @@
+use axum::{extract::State, http::StatusCode, response::Json, routing::post, Router};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::{
+    auth::AuthUser,
+    routes::queue::require_admin,
+    services::credential_encryption::{installed_credential_keyring, CredentialRotationSummary},
+    AppState,
+};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/rotate", post(rotate_keys))
+}
+
+/// Re-encrypt stored source credentials under the current master key
+///
+/// Rewraps the credentials of every source that isn't under the newest key in
+/// `CREDENTIALS_MASTER_KEYS`, a batch of sources per transaction, and encrypts
+/// credentials still stored in plain text. Sources keep syncing meanwhile;
+/// once `remaining` is 0 the older keys can be removed from the configuration.
+#[utoipa::path(
+    post,
+    path = "/api/admin/keys/rotate",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Credentials rotated to the current master key", body = CredentialRotationSummary),
+        (status = 400, description = "No credential master keys are configured"),
+        (status = 401, description = "Unauthorized - valid authentication required"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn rotate_keys(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<Json<CredentialRotationSummary>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let keyring = installed_credential_keyring();
+    if !keyring.is_enabled() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let summary = state
+        .db
+        .rotate_source_credentials(&keyring, state.config.credentials_rotation_batch_size)
+        .await
+        .map_err(|e| {
+            error!("Failed to rotate source credentials: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(summary))
+}
@@ -3,14 +3,123 @@ use axum::{
     http::StatusCode,
     response::Json,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
 use crate::{
     auth::AuthUser,
+    models::DocumentResponse,
     AppState,
 };
-use super::types::{BulkDeleteRequest, DeleteLowConfidenceRequest, BulkDeleteResponse};
+use super::types::{
+    BatchDocumentResult, BatchDocumentStatus, BatchDocumentsRequest, BatchDocumentsResponse,
+    BulkDeleteRequest, DeleteLowConfidenceRequest, BulkDeleteResponse,
+};
+
+/// Most documents fetched by one batch request
+pub const MAX_BATCH_DOCUMENTS: usize = 200;
+
+/// Fetch the metadata of many documents at once
+///
+/// Results come back in request order, one per requested id. Documents that
+/// don't exist and documents the user may not read are both reported as
+/// `not_found`, as a single document request would answer 404 for either.
+#[utoipa::path(
+    post,
+    path = "/api/documents/batch",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = BatchDocumentsRequest,
+    responses(
+        (status = 200, description = "Document metadata in request order", body = BatchDocumentsResponse),
+        (status = 400, description = "No ids, or more than 200"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_documents_batch(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<BatchDocumentsRequest>,
+) -> Result<Json<BatchDocumentsResponse>, StatusCode> {
+    if request.document_ids.is_empty() || request.document_ids.len() > MAX_BATCH_DOCUMENTS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let documents = state
+        .db
+        .get_readable_documents_by_ids(&request.document_ids, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Failed to get documents by ids: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let found_ids: Vec<uuid::Uuid> = documents.iter().map(|d| d.id).collect();
+    let mut labels_map: HashMap<_, _> = HashMap::new();
+    let mut dates_map: HashMap<_, _> = HashMap::new();
+    if !found_ids.is_empty() {
+        labels_map = state
+            .db
+            .get_labels_for_documents(&found_ids)
+            .await
+            .map_err(|e| {
+                error!("Failed to get labels for documents: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .into_iter()
+            .collect();
+        dates_map = state
+            .db
+            .get_document_dates(&found_ids)
+            .await
+            .map_err(|e| {
+                error!("Failed to get document dates: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .into_iter()
+            .map(|date| (date.document_id, date))
+            .collect();
+    }
+
+    let responses: HashMap<uuid::Uuid, DocumentResponse> = documents
+        .into_iter()
+        .map(|doc| {
+            let id = doc.id;
+            let mut response = DocumentResponse::from(doc);
+            if let Some(labels) = labels_map.remove(&id) {
+                response.labels = labels;
+            }
+            if let Some(date) = dates_map.remove(&id) {
+                response.document_date = Some(date.document_date);
+                response.document_date_source = Some(date.document_date_source);
+            }
+            (id, response)
+        })
+        .collect();
+
+    let results = request
+        .document_ids
+        .into_iter()
+        .map(|id| match responses.get(&id) {
+            Some(response) => BatchDocumentResult {
+                id,
+                status: BatchDocumentStatus::Found,
+                document: Some(response.clone()),
+            },
+            None => BatchDocumentResult {
+                id,
+                status: BatchDocumentStatus::NotFound,
+                document: None,
+            },
+        })
+        .collect();
+
+    Ok(Json(BatchDocumentsResponse { documents: results }))
+}
 
 /// Bulk delete multiple documents
 #[utoipa::path(
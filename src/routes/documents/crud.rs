@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Multipart, Path, Query, State},
+    extract::{multipart::MultipartError, Multipart, Path, Query, State},
     http::{StatusCode, header::CONTENT_TYPE},
     response::{Json, Response, IntoResponse},
     body::Body,
@@ -12,9 +12,12 @@ use crate::{
     auth::AuthUser,
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
     models::DocumentResponse,
+    ocr::queue::OcrPriorityClass,
+    routes::pagination::{Pagination, PaginationParams},
+    storage::StorageAccessDenied,
     AppState,
 };
-use super::types::{PaginationQuery, DocumentUploadResponse, PaginatedDocumentsResponse, DocumentPaginationInfo};
+use super::types::{DocumentListQuery, DocumentUploadResponse, PaginatedDocumentsResponse};
 
 /// Custom error type for document operations
 #[derive(Debug)]
@@ -29,6 +32,9 @@ pub enum DocumentError {
     OcrProcessingError(String),
     FileProcessingError(String),
     ConcurrentUploadError(String),
+    MalwareDetected(String),
+    MalwareScanFailed(String),
+    CorruptFile(String),
 }
 
 impl IntoResponse for DocumentError {
@@ -44,6 +50,9 @@ impl IntoResponse for DocumentError {
             DocumentError::OcrProcessingError(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg, "UPLOAD_OCR_ERROR"),
             DocumentError::FileProcessingError(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg, "UPLOAD_FILE_PROCESSING_ERROR"),
             DocumentError::ConcurrentUploadError(msg) => (StatusCode::TOO_MANY_REQUESTS, msg, "UPLOAD_CONCURRENT_ERROR"),
+            DocumentError::MalwareDetected(threat) => (StatusCode::UNPROCESSABLE_ENTITY, format!("File quarantined: malware detected ({})", threat), "UPLOAD_MALWARE_DETECTED"),
+            DocumentError::MalwareScanFailed(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg, "UPLOAD_MALWARE_SCAN_FAILED"),
+            DocumentError::CorruptFile(reason) => (StatusCode::UNPROCESSABLE_ENTITY, format!("File appears to be corrupt: {}", reason), "UPLOAD_CORRUPT_FILE"),
         };
         
         (status, Json(json!({
@@ -56,6 +65,18 @@ impl IntoResponse for DocumentError {
     }
 }
 
+impl DocumentError {
+    /// Maps a failure reading a multipart body, reporting bodies cut off by the
+    /// request size limit as 413 rather than a malformed request
+    pub(crate) fn from_multipart(context: &str, error: MultipartError, max_bytes: usize) -> Self {
+        if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            DocumentError::PayloadTooLarge(crate::body_limit::limit_exceeded_message(max_bytes, None))
+        } else {
+            DocumentError::BadRequest(format!("{}: {}", context, error))
+        }
+    }
+}
+
 /// Upload a new document
 #[utoipa::path(
     post,
@@ -70,7 +91,9 @@ impl IntoResponse for DocumentError {
         (status = 400, description = "Bad request"),
         (status = 401, description = "Unauthorized"),
         (status = 413, description = "File too large"),
-        (status = 500, description = "Internal server error")
+        (status = 422, description = "File quarantined by the malware scanner"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Malware scan could not be completed")
     )
 )]
 pub async fn upload_document(
@@ -81,12 +104,13 @@ pub async fn upload_document(
     let mut uploaded_file = None;
     let mut ocr_language: Option<String> = None;
     let mut ocr_languages: Vec<String> = Vec::new();
+    let mut handwriting = false;
     
     // First pass: collect all multipart fields
     while let Some(field) = multipart.next_field().await.map_err(|e| {
-        let error_msg = format!("Failed to get multipart field: {}", e);
-        error!("{}", error_msg);
-        DocumentError::BadRequest(error_msg)
+        let error = DocumentError::from_multipart("Failed to get multipart field", e, state.config.upload_body_limit());
+        error!("{:?}", error);
+        error
     })? {
         let name = field.name().unwrap_or("").to_string();
         
@@ -133,6 +157,9 @@ pub async fn upload_document(
                     }
                 }
             }
+        } else if name == "handwriting" {
+            let value = field.text().await.map_err(|_| DocumentError::BadRequest("Failed to read handwriting field".to_string()))?;
+            handwriting = value.trim().eq_ignore_ascii_case("true");
         } else if name == "file" {
             let filename = field.file_name()
                 .ok_or_else(|| {
@@ -147,9 +174,9 @@ pub async fn upload_document(
                 .to_string();
             
             let data = field.bytes().await.map_err(|e| {
-                let error_msg = format!("Failed to read file data: {}", e);
-                error!("{}", error_msg);
-                DocumentError::BadRequest(error_msg)
+                let error = DocumentError::from_multipart("Failed to read file data", e, state.config.upload_body_limit());
+                error!("{:?}", error);
+                error
             })?;
             
             uploaded_file = Some((filename, content_type, data.to_vec()));
@@ -171,6 +198,10 @@ pub async fn upload_document(
         return Err(DocumentError::PayloadTooLarge(error_msg));
     }
     
+    // Browsers report whatever the OS associates with the extension, so sniff the content,
+    // falling back to the configured extension overrides when that is inconclusive
+    let content_type = crate::mime_detection::detect_mime_from_content(&data, &filename, Some(&content_type)).mime_type;
+
     info!("Uploading document: {} ({} bytes)", filename, data.len());
     
     // Create FileIngestionInfo from uploaded data
@@ -252,9 +283,16 @@ pub async fn upload_document(
                 }
             }
             
+            if handwriting {
+                if let Err(e) = state.db.set_document_handwriting(document.id, true).await {
+                    warn!("Failed to flag document {} as handwritten: {}", document.id, e);
+                }
+            }
+            
             // Auto-enqueue document for OCR processing
-            let priority = 5; // Normal priority for direct uploads
-            if let Err(e) = state.queue_service.enqueue_document(document.id, priority, document.file_size).await {
+            // Direct uploads are interactive: the user is waiting on them
+            let priority = 5;
+            if let Err(e) = state.queue_service.enqueue_document_with_class(document.id, OcrPriorityClass::Interactive, priority, document.file_size).await {
                 error!("Failed to enqueue document {} for OCR: {}", document.id, e);
                 // Don't fail the upload if OCR queueing fails, just log the error
             } else {
@@ -291,13 +329,23 @@ pub async fn upload_document(
             info!("{}", error_msg);
             Err(DocumentError::Conflict(error_msg))
         }
+        Ok(IngestionResult::Quarantined { threat, .. }) => {
+            warn!("Upload of {} quarantined: {}", filename, threat);
+            Err(DocumentError::MalwareDetected(threat))
+        }
+        Ok(IngestionResult::Corrupt { reason, .. }) => {
+            warn!("Upload of {} rejected as corrupt: {}", filename, reason);
+            Err(DocumentError::CorruptFile(reason))
+        }
         Err(e) => {
             let ingestion_duration = ingestion_start.elapsed();
             let error_msg = format!("Failed to ingest document: {} (failed after {:?})", e, ingestion_duration);
             error!("[UPLOAD_DEBUG] {}", error_msg);
             
             // Categorize the error for better client handling
-            if e.to_string().contains("constraint") || e.to_string().contains("duplicate") {
+            if e.to_string().starts_with("Malware scan failed") {
+                return Err(DocumentError::MalwareScanFailed(e.to_string()));
+            } else if e.to_string().contains("constraint") || e.to_string().contains("duplicate") {
                 return Err(DocumentError::DatabaseConstraintViolation(format!("Database constraint violation during upload: {}", e)));
             } else if e.to_string().contains("timeout") {
                 return Err(DocumentError::UploadTimeout(format!("Upload processing timed out: {}", e)));
@@ -369,6 +417,21 @@ pub async fn get_document_by_id(
     response.labels = labels;
     response.username = username;
 
+    match state.db.get_document_dates(&[document_id]).await {
+        Ok(dates) => {
+            if let Some(date) = dates.into_iter().next() {
+                response.document_date = Some(date.document_date);
+                response.document_date_source = Some(date.document_date_source);
+            }
+        }
+        Err(e) => warn!("Failed to get document date for {}: {}", document_id, e),
+    }
+
+    match state.db.get_document_expiry(document_id).await {
+        Ok(expiry_date) => response.expiry_date = expiry_date,
+        Err(e) => warn!("Failed to get expiry date for {}: {}", document_id, e),
+    }
+
     // Populate OCR progress from ocr_queue when actively processing
     if response.ocr_status.as_deref() == Some("processing") {
         if let Ok(Some(row)) = sqlx::query_as::<_, (Option<i32>, Option<i32>)>(
@@ -393,9 +456,10 @@ pub async fn get_document_by_id(
     security(
         ("bearer_auth" = [])
     ),
-    params(PaginationQuery),
+    params(PaginationParams, DocumentListQuery),
     responses(
         (status = 200, description = "Paginated list of documents", body = PaginatedDocumentsResponse),
+        (status = 400, description = "Invalid pagination or sort parameters"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -403,61 +467,30 @@ pub async fn get_document_by_id(
 pub async fn list_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(query): Query<PaginationQuery>,
+    pagination: Pagination,
+    Query(query): Query<DocumentListQuery>,
 ) -> Result<Json<PaginatedDocumentsResponse>, StatusCode> {
-    let limit = query.limit.unwrap_or(25);
-    let offset = query.offset.unwrap_or(0);
+    let Pagination { limit, offset } = pagination;
+    let filter = query.into_filter()?;
 
     // Get total count for pagination
-    let total_count = if let Some(ocr_status) = query.ocr_status.as_deref() {
-        state
-            .db
-            .count_documents_by_user_with_role_and_filter(
-                auth_user.user.id,
-                auth_user.user.role,
-                Some(ocr_status),
-            )
-            .await
-    } else {
-        state
-            .db
-            .count_documents_by_user_with_role(
-                auth_user.user.id,
-                auth_user.user.role,
-            )
-            .await
-    }
-    .map_err(|e| {
-        error!("Database error counting documents: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let total_count = state
+        .db
+        .count_documents_by_user_with_role_and_filter(auth_user.user.id, auth_user.user.role, &filter)
+        .await
+        .map_err(|e| {
+            error!("Database error counting documents: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-    let documents = if let Some(ocr_status) = query.ocr_status.as_deref() {
-        state
-            .db
-            .get_documents_by_user_with_role_and_filter(
-                auth_user.user.id,
-                auth_user.user.role,
-                Some(ocr_status),
-                limit,
-                offset,
-            )
-            .await
-    } else {
-        state
-            .db
-            .get_documents_by_user_with_role(
-                auth_user.user.id,
-                auth_user.user.role,
-                limit,
-                offset,
-            )
-            .await
-    }
-    .map_err(|e| {
-        error!("Database error listing documents: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let documents = state
+        .db
+        .get_documents_by_user_with_role_and_filter(auth_user.user.id, auth_user.user.role, &filter, limit, offset)
+        .await
+        .map_err(|e| {
+            error!("Database error listing documents: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
     // Get document IDs for batch label fetching
     let document_ids: Vec<uuid::Uuid> = documents.iter().map(|d| d.id).collect();
@@ -478,6 +511,22 @@ pub async fn list_documents(
         std::collections::HashMap::new()
     };
 
+    let dates_map: std::collections::HashMap<_, _> = if !document_ids.is_empty() {
+        state
+            .db
+            .get_document_dates(&document_ids)
+            .await
+            .map_err(|e| {
+                error!("Failed to get document dates: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .into_iter()
+            .map(|date| (date.document_id, date))
+            .collect()
+    } else {
+        std::collections::HashMap::new()
+    };
+
     // Convert to response format with labels
     let responses: Vec<DocumentResponse> = documents
         .into_iter()
@@ -486,21 +535,17 @@ pub async fn list_documents(
             if let Some(labels) = labels_map.get(&doc.id) {
                 response.labels = labels.clone();
             }
+            if let Some(date) = dates_map.get(&doc.id) {
+                response.document_date = Some(date.document_date);
+                response.document_date_source = Some(date.document_date_source.clone());
+            }
             response
         })
         .collect();
 
-    // Create pagination info
-    let pagination = DocumentPaginationInfo {
-        total: total_count,
-        limit,
-        offset,
-        has_more: offset + limit < total_count,
-    };
-
     Ok(Json(PaginatedDocumentsResponse {
         documents: responses,
-        pagination,
+        pagination: pagination.meta(total_count),
     }))
 }
 
@@ -577,6 +622,7 @@ pub async fn delete_document(
     responses(
         (status = 200, description = "Document file", content_type = "application/octet-stream"),
         (status = 404, description = "Document not found"),
+        (status = 403, description = "Document file is outside its owner's storage namespace"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -598,11 +644,15 @@ pub async fn download_document(
 
     let file_service = &state.file_service;
     let file_data = file_service
-        .read_file(&document.file_path)
+        .read_user_file(document.user_id, &document.file_path)
         .await
         .map_err(|e| {
             error!("Failed to read document file {}: {}", document_id, e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            if e.is::<StorageAccessDenied>() {
+                StatusCode::FORBIDDEN
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
         })?;
 
     let response = Response::builder()
@@ -634,6 +684,7 @@ pub async fn download_document(
     responses(
         (status = 200, description = "Document file for viewing", content_type = "application/octet-stream"),
         (status = 404, description = "Document not found"),
+        (status = 403, description = "Document file is outside its owner's storage namespace"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -655,11 +706,15 @@ pub async fn view_document(
 
     let file_service = &state.file_service;
     let file_data = file_service
-        .read_file(&document.file_path)
+        .read_user_file(document.user_id, &document.file_path)
         .await
         .map_err(|e| {
             error!("Failed to read document file {}: {}", document_id, e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            if e.is::<StorageAccessDenied>() {
+                StatusCode::FORBIDDEN
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
         })?;
 
     let response = Response::builder()
@@ -684,22 +739,19 @@ pub async fn view_document(
     security(
         ("bearer_auth" = [])
     ),
-    params(
-        ("limit" = Option<i64>, Query, description = "Number of duplicate groups to return per page"),
-        ("offset" = Option<i64>, Query, description = "Number of duplicate groups to skip")
-    ),
+    params(PaginationParams),
     responses(
         (status = 200, description = "User's duplicate documents grouped by hash", body = serde_json::Value),
+        (status = 400, description = "Invalid pagination parameters"),
         (status = 401, description = "Unauthorized")
     )
 )]
 pub async fn get_user_duplicates(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(query): Query<PaginationQuery>,
+    pagination: Pagination,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let limit = query.limit.unwrap_or(25);
-    let offset = query.offset.unwrap_or(0);
+    let Pagination { limit, offset } = pagination;
 
     let duplicates = state
         .db
@@ -714,12 +766,7 @@ pub async fn get_user_duplicates(
 
     let response = serde_json::json!({
         "duplicates": duplicates,
-        "pagination": {
-            "total": total_count,
-            "limit": limit,
-            "offset": offset,
-            "has_more": offset + limit < total_count
-        },
+        "pagination": pagination.meta(total_count),
         "statistics": {
             "total_duplicate_groups": total_count
         }
@@ -1,13 +1,21 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    response::Json,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Json,
+    },
 };
+use futures::stream::Stream;
+use std::convert::Infallible;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error, info};
 
 use crate::{
     auth::AuthUser,
+    models::UserRole,
+    ocr::live_log::{self, OcrLogEvent, OcrLogEventKind},
     AppState,
 };
 use super::types::DocumentDebugInfo;
@@ -50,7 +58,7 @@ pub async fn get_document_debug_info(
     // Check file existence and readability
     let file_exists = tokio::fs::metadata(&document.file_path).await.is_ok();
     let readable = if file_exists {
-        file_service.read_file(&document.file_path).await.is_ok()
+        file_service.read_user_file(document.user_id, &document.file_path).await.is_ok()
     } else {
         false
     };
@@ -147,10 +155,20 @@ pub async fn get_document_thumbnail(
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let file_service = &state.file_service;
-    
-    // Use the FileService to get or generate thumbnail
+
+    // Thumbnails made at another size, or never made, are regenerated
+    let thumbnail_size = state.db.get_document_thumbnail_size(document_id).await.map_err(|e| {
+        error!("Database error getting thumbnail size of document {}: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let thumbnail = if thumbnail_size == Some(file_service.thumbnail_size() as i32) {
+        file_service.get_or_generate_thumbnail(document.user_id, &document.file_path, &document.original_filename).await
+    } else {
+        crate::services::thumbnail_regeneration::regenerate_thumbnail(&state.db, file_service, &document).await
+    };
+
     #[cfg(feature = "ocr")]
-    match file_service.get_or_generate_thumbnail(&document.file_path, &document.original_filename).await {
+    match thumbnail {
         Ok(data) => {
             let response = axum::response::Response::builder()
                 .status(StatusCode::OK)
@@ -174,6 +192,7 @@ pub async fn get_document_thumbnail(
     
     #[cfg(not(feature = "ocr"))]
     {
+        let _ = thumbnail;
         error!("Thumbnail generation requires OCR feature to be enabled");
         Err(StatusCode::NOT_FOUND)
     }
@@ -327,7 +346,7 @@ pub async fn validate_document_integrity(
 
     // Check file readability
     checks.push("file_readability".to_string());
-    match file_service.read_file(&document.file_path).await {
+    match file_service.read_user_file(document.user_id, &document.file_path).await {
         Ok(data) => {
             // Verify file size matches
             if data.len() as i64 != document.file_size {
@@ -376,4 +395,74 @@ pub async fn validate_document_integrity(
             format!("Found {} integrity issues", issues.len())
         }
     })))
-}
\ No newline at end of file
+}
+
+/// Stream the live OCR log of a document (Admin only)
+///
+/// Server-sent events with the steps of the document's running OCR job and
+/// what the tools it runs, such as ocrmypdf and pdftotext, write to stderr.
+/// Each event is named after its kind; the stream ends with a `finished`
+/// event when the job does.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/ocr/log",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Server-sent events of the running OCR job", content_type = "text/event-stream", body = OcrLogEvent),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 404, description = "Document not found, or OCR debug endpoints are disabled"),
+        (status = 409, description = "The document isn't being OCR'd"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn stream_ocr_log(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    if !state.config.ocr_debug_endpoints {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    if auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let receiver = live_log::subscribe(document_id).ok_or(StatusCode::CONFLICT)?;
+    debug!("Streaming the OCR log of document {}", document_id);
+
+    let events = futures::stream::unfold(Some(receiver), |receiver| async move {
+        let mut receiver = receiver?;
+        match receiver.recv().await {
+            Ok(event) => {
+                let finished = event.kind == OcrLogEventKind::Finished;
+                let data = serde_json::to_string(&event).unwrap_or_default();
+                let sse = Event::default().event(event.kind.as_str()).data(data);
+                Some((Ok(sse), (!finished).then_some(receiver)))
+            }
+            // A slow client missed events, which it's told rather than cut off
+            Err(RecvError::Lagged(skipped)) => {
+                let sse = Event::default().event("lagged").data(skipped.to_string());
+                Some((Ok(sse), Some(receiver)))
+            }
+            Err(RecvError::Closed) => None,
+        }
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
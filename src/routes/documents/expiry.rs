@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{auth::AuthUser, services::expiry_reminders::days_until_expiry, AppState};
+use super::crud::DocumentError;
+use super::types::{ExpiringDocumentEntry, ExpiringDocumentsQuery, ExpiringDocumentsResponse, UpdateExpiryRequest};
+
+/// Reminder window used when the user has no settings yet
+const DEFAULT_EXPIRY_WINDOW_DAYS: i32 = 30;
+
+/// Furthest the expiring documents list looks ahead
+const MAX_EXPIRY_WINDOW_DAYS: i32 = 3650;
+
+/// Set or clear a document's expiry date
+///
+/// The owner is reminded `expiry_reminder_days` (see settings) before the
+/// document expires. Changing the date re-arms the reminder.
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}/expiry",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = UpdateExpiryRequest,
+    responses(
+        (status = 204, description = "Expiry date updated"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_document_expiry(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<UpdateExpiryRequest>,
+) -> Result<StatusCode, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let updated = state
+        .db
+        .set_document_expiry(document.id, request.expiry_date)
+        .await
+        .map_err(|e| {
+            error!("Failed to update expiry date of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to update expiry date".to_string())
+        })?;
+    if !updated {
+        return Err(DocumentError::NotFound);
+    }
+
+    match request.expiry_date {
+        Some(date) => info!("Document {} set to expire on {} by user {}", document_id, date, auth_user.user.id),
+        None => info!("Expiry date of document {} cleared by user {}", document_id, auth_user.user.id),
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the user's documents expiring soon
+#[utoipa::path(
+    get,
+    path = "/api/documents/expiring",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(ExpiringDocumentsQuery),
+    responses(
+        (status = 200, description = "Documents expiring between today and the end of the window, soonest first", body = ExpiringDocumentsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_expiring_documents(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<ExpiringDocumentsQuery>,
+) -> Result<Json<ExpiringDocumentsResponse>, DocumentError> {
+    let days = match query.days {
+        Some(days) => days,
+        None => state
+            .db
+            .get_user_settings(auth_user.user.id)
+            .await
+            .map_err(|e| {
+                error!("Failed to get settings of user {}: {}", auth_user.user.id, e);
+                DocumentError::InternalServerError("Failed to load settings".to_string())
+            })?
+            .map(|settings| settings.expiry_reminder_days)
+            .unwrap_or(DEFAULT_EXPIRY_WINDOW_DAYS),
+    }
+    .clamp(0, MAX_EXPIRY_WINDOW_DAYS);
+
+    let expiring = state
+        .db
+        .get_expiring_documents(auth_user.user.id, days)
+        .await
+        .map_err(|e| {
+            error!("Failed to list expiring documents of user {}: {}", auth_user.user.id, e);
+            DocumentError::InternalServerError("Failed to list expiring documents".to_string())
+        })?;
+
+    let today = chrono::Utc::now().date_naive();
+    let documents = expiring
+        .into_iter()
+        .map(|document| ExpiringDocumentEntry {
+            days_until_expiry: days_until_expiry(document.expiry_date, today),
+            document_id: document.document_id,
+            filename: document.filename,
+            expiry_date: document.expiry_date,
+            reminded: document.reminded,
+        })
+        .collect();
+
+    Ok(Json(ExpiringDocumentsResponse { days, documents }))
+}
@@ -12,6 +12,8 @@ use sqlx::Row;
 use crate::{
     auth::AuthUser,
     models::UserRole,
+    routes::pagination::{Pagination, PaginationParams},
+    storage::StorageAccessDenied,
     AppState,
 };
 use super::types::FailedDocumentsQuery;
@@ -25,13 +27,13 @@ use super::types::FailedDocumentsQuery;
         ("bearer_auth" = [])
     ),
     params(
-        ("limit" = Option<i64>, Query, description = "Number of documents to return"),
-        ("offset" = Option<i64>, Query, description = "Number of documents to skip"),
+        PaginationParams,
         ("stage" = Option<String>, Query, description = "Filter by failure stage (ocr, ingestion, validation, etc.)"),
         ("reason" = Option<String>, Query, description = "Filter by failure reason")
     ),
     responses(
         (status = 200, description = "Failed documents list", body = serde_json::Value),
+        (status = 400, description = "Invalid pagination parameters"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -39,10 +41,10 @@ use super::types::FailedDocumentsQuery;
 pub async fn get_failed_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
+    pagination: Pagination,
     Query(params): Query<FailedDocumentsQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let limit = params.limit.unwrap_or(25);
-    let offset = params.offset.unwrap_or(0);
+    let Pagination { limit, offset } = pagination;
     
     // Query the unified failed_documents table
     let mut query_builder = sqlx::QueryBuilder::new(
@@ -175,6 +177,7 @@ pub async fn get_failed_documents(
                 "storage" => "File Storage",
                 "processing" => "Document Processing",
                 "sync" => "Source Synchronization",
+                "quarantined" => "Malware Quarantine",
                 _ => "Unknown"
             }
         })
@@ -194,12 +197,7 @@ pub async fn get_failed_documents(
     
     let response = serde_json::json!({
         "documents": documents,
-        "pagination": {
-            "limit": limit,
-            "offset": offset,
-            "total": total_count,
-            "total_pages": (total_count as f64 / limit as f64).ceil() as i64
-        },
+        "pagination": pagination.meta(total_count),
         "statistics": {
             "total_failed": total_count,
             "by_stage": stage_stats,
@@ -222,12 +220,10 @@ pub async fn get_failed_documents(
     security(
         ("bearer_auth" = [])
     ),
-    params(
-        ("limit" = Option<i64>, Query, description = "Number of documents to return"),
-        ("offset" = Option<i64>, Query, description = "Number of documents to skip")
-    ),
+    params(PaginationParams),
     responses(
         (status = 200, description = "Failed OCR documents list", body = serde_json::Value),
+        (status = 400, description = "Invalid pagination parameters"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -235,10 +231,9 @@ pub async fn get_failed_documents(
 pub async fn get_failed_ocr_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(pagination): Query<super::types::PaginationQuery>,
+    pagination: Pagination,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let limit = pagination.limit.unwrap_or(50);
-    let offset = pagination.offset.unwrap_or(0);
+    let Pagination { limit, offset } = pagination;
     
     // Get failed OCR documents with additional failure details
     let failed_docs = sqlx::query(
@@ -331,12 +326,7 @@ pub async fn get_failed_ocr_documents(
     
     let response = serde_json::json!({
         "documents": failed_documents,
-        "pagination": {
-            "total": total_count,
-            "limit": limit,
-            "offset": offset,
-            "has_more": offset + limit < total_count
-        },
+        "pagination": pagination.meta(total_count),
         "statistics": {
             "total_failed": total_count,
             "failure_categories": get_failure_statistics(&state, auth_user.user.id, auth_user.user.role.clone()).await?
@@ -360,6 +350,7 @@ pub async fn get_failed_ocr_documents(
     responses(
         (status = 200, description = "Failed document content for viewing in browser"),
         (status = 404, description = "Failed document not found or file deleted"),
+        (status = 403, description = "Document file is outside its owner's storage namespace"),
         (status = 401, description = "Unauthorized")
     )
 )]
@@ -393,17 +384,22 @@ pub async fn view_failed_document(
     let file_path: Option<String> = row.get("file_path");
     let filename: String = row.get("filename");
     let mime_type: Option<String> = row.get("mime_type");
+    let owner_id: uuid::Uuid = row.get("user_id");
     
     // Check if file_path exists (some failed documents might not have been saved)
     let file_path = file_path.ok_or(StatusCode::NOT_FOUND)?;
     
     let file_service = &state.file_service;
     let file_data = file_service
-        .read_file(&file_path)
+        .read_user_file(owner_id, &file_path)
         .await
         .map_err(|e| {
             error!("Failed to read failed document file: {}", e);
-            StatusCode::NOT_FOUND
+            if e.is::<StorageAccessDenied>() {
+                StatusCode::FORBIDDEN
+            } else {
+                StatusCode::NOT_FOUND
+            }
         })?;
     
     // Determine content type from mime_type or file extension
@@ -437,6 +433,7 @@ fn categorize_failure_reason(failure_reason: Option<&str>, error_message: Option
         Some("memory_limit") => "Memory Limit",
         Some("pdf_parsing_panic") => "PDF Parsing Error",
         Some("low_ocr_confidence") => "Low OCR Confidence",
+        Some("virus_detected") => "Malware Detected",
         Some("unknown") | None => {
             // Try to categorize based on error message
             if let Some(error) = error_message {
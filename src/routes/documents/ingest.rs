@@ -0,0 +1,308 @@
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Multipart, Request, State},
+    http::header::CONTENT_TYPE,
+    response::Json,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::{
+    auth::AuthUser,
+    ingestion::document_ingestion::{
+        DeduplicationPolicy, DocumentIngestionRequest, DocumentIngestionService, IngestionResult,
+    },
+    AppState,
+};
+use super::crud::DocumentError;
+use super::types::{ExternalIngestRecord, ExternalIngestRecordResult, ExternalIngestResponse};
+
+/// Name of the multipart part that carries the JSON Lines records
+const RECORDS_PART_NAME: &str = "records";
+
+/// An uploaded multipart part that records can reference by name
+struct IngestBlob {
+    content_type: Option<String>,
+    data: Vec<u8>,
+}
+
+/// Parses a JSON Lines payload into records. Each line is parsed and validated on
+/// its own so a malformed line only fails that record. Blank lines are ignored.
+pub fn parse_ingest_records(payload: &str) -> Vec<(usize, Result<ExternalIngestRecord, String>)> {
+    payload
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let record = serde_json::from_str::<ExternalIngestRecord>(line)
+                .map_err(|e| format!("Invalid JSON record: {}", e))
+                .and_then(|record| validate_ingest_record(&record).map(|_| record));
+            (index + 1, record)
+        })
+        .collect()
+}
+
+/// Validates the fields of a single ingest record
+pub fn validate_ingest_record(record: &ExternalIngestRecord) -> Result<(), String> {
+    if record.filename.trim().is_empty() {
+        return Err("filename cannot be empty".to_string());
+    }
+
+    match (&record.text, &record.text_blob) {
+        (Some(_), Some(_)) => return Err("Provide either text or text_blob, not both".to_string()),
+        (None, None) => return Err("Record must include text or text_blob".to_string()),
+        _ => {}
+    }
+
+    if let Some(metadata) = &record.metadata {
+        if !metadata.is_object() {
+            return Err("metadata must be a JSON object".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Bulk-ingest documents with pre-extracted text
+///
+/// Accepts either a JSON Lines body (one record per line) or a multipart form with a
+/// `records` part containing JSON Lines plus any number of file parts that records
+/// reference by part name via `text_blob` / `file_blob`. Documents are created with
+/// `ocr_status = 'external'` and are never queued for OCR.
+#[utoipa::path(
+    post,
+    path = "/api/documents/ingest",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(content = String, description = "JSON Lines records, or multipart form with a `records` part and referenced blobs", content_type = "application/x-ndjson"),
+    responses(
+        (status = 200, description = "Per-record ingest results", body = ExternalIngestResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 413, description = "Payload too large")
+    )
+)]
+pub async fn ingest_external_documents(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    request: Request,
+) -> Result<Json<ExternalIngestResponse>, DocumentError> {
+    let is_multipart = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("multipart/form-data"))
+        .unwrap_or(false);
+
+    let mut blobs: HashMap<String, IngestBlob> = HashMap::new();
+
+    let payload = if is_multipart {
+        let mut multipart = Multipart::from_request(request, &state).await.map_err(|e| {
+            DocumentError::BadRequest(format!("Invalid multipart request: {}", e))
+        })?;
+
+        let mut records = None;
+        while let Some(field) = multipart.next_field().await.map_err(|e| {
+            let error = DocumentError::from_multipart("Failed to get multipart field", e, state.config.upload_body_limit());
+            error!("{:?}", error);
+            error
+        })? {
+            let name = field.name().unwrap_or("").to_string();
+
+            if name == RECORDS_PART_NAME {
+                records = Some(field.text().await.map_err(|e| {
+                    DocumentError::BadRequest(format!("Failed to read records: {}", e))
+                })?);
+            } else if !name.is_empty() {
+                let content_type = field.content_type().map(|ct| ct.to_string());
+                let data = field.bytes().await.map_err(|e| {
+                    DocumentError::from_multipart(&format!("Failed to read blob '{}'", name), e, state.config.upload_body_limit())
+                })?;
+                blobs.insert(name, IngestBlob { content_type, data: data.to_vec() });
+            }
+        }
+
+        records.ok_or_else(|| {
+            DocumentError::BadRequest(format!("Multipart request must include a '{}' part", RECORDS_PART_NAME))
+        })?
+    } else {
+        let body = Bytes::from_request(request, &state).await.map_err(|e| {
+            DocumentError::PayloadTooLarge(format!("Failed to read request body: {}", e))
+        })?;
+        String::from_utf8(body.to_vec())
+            .map_err(|_| DocumentError::BadRequest("Request body must be UTF-8 JSON Lines".to_string()))?
+    };
+
+    let records = parse_ingest_records(&payload);
+    if records.is_empty() {
+        return Err(DocumentError::BadRequest("No records found in request".to_string()));
+    }
+
+    info!("Bulk ingest of {} records for user {}", records.len(), auth_user.user.id);
+
+    let ingestion_service = DocumentIngestionService::new(
+        state.db.clone(),
+        state.file_service.as_ref().clone(),
+    );
+
+    let mut results = Vec::with_capacity(records.len());
+    for (line, record) in records {
+        let result = match record {
+            Ok(record) => ingest_record(&state, &ingestion_service, &auth_user, line, record, &blobs).await,
+            Err(e) => ExternalIngestRecordResult {
+                line,
+                filename: None,
+                status: "failed".to_string(),
+                document_id: None,
+                error: Some(e),
+            },
+        };
+        results.push(result);
+    }
+
+    let count = |status: &str| results.iter().filter(|r| r.status == status).count();
+    let response = ExternalIngestResponse {
+        total: results.len(),
+        created: count("created"),
+        duplicates: count("duplicate"),
+        failed: count("failed"),
+        results,
+    };
+
+    info!(
+        "Bulk ingest finished for user {}: {} created, {} duplicates, {} failed",
+        auth_user.user.id, response.created, response.duplicates, response.failed
+    );
+
+    Ok(Json(response))
+}
+
+/// Creates a single document from a validated record and stores its text
+async fn ingest_record(
+    state: &Arc<AppState>,
+    ingestion_service: &DocumentIngestionService,
+    auth_user: &AuthUser,
+    line: usize,
+    record: ExternalIngestRecord,
+    blobs: &HashMap<String, IngestBlob>,
+) -> ExternalIngestRecordResult {
+    let filename = record.filename.clone();
+    let failed = |error: String| ExternalIngestRecordResult {
+        line,
+        filename: Some(filename.clone()),
+        status: "failed".to_string(),
+        document_id: None,
+        error: Some(error),
+    };
+
+    let text = match (&record.text, &record.text_blob) {
+        (Some(text), _) => text.clone(),
+        (None, Some(blob_name)) => match blobs.get(blob_name) {
+            Some(blob) => match String::from_utf8(blob.data.clone()) {
+                Ok(text) => text,
+                Err(_) => return failed(format!("text_blob '{}' is not valid UTF-8", blob_name)),
+            },
+            None => return failed(format!("text_blob '{}' was not uploaded", blob_name)),
+        },
+        (None, None) => return failed("Record must include text or text_blob".to_string()),
+    };
+
+    // Store the original file when one was uploaded, otherwise keep the text itself
+    let (file_data, mime_type) = match &record.file_blob {
+        Some(blob_name) => match blobs.get(blob_name) {
+            Some(blob) => {
+                let mime_type = record.mime_type.clone()
+                    .or_else(|| blob.content_type.clone())
+                    .unwrap_or_else(|| mime_guess::from_path(&record.filename).first_or_octet_stream().to_string());
+                (blob.data.clone(), mime_type)
+            }
+            None => return failed(format!("file_blob '{}' was not uploaded", blob_name)),
+        },
+        None => (text.clone().into_bytes(), "text/plain".to_string()),
+    };
+
+    let max_file_size_bytes = state.config.max_file_size_mb as usize * 1024 * 1024;
+    if file_data.len() > max_file_size_bytes {
+        return failed(format!(
+            "File size ({} bytes) exceeds maximum allowed size ({}MB)",
+            file_data.len(), state.config.max_file_size_mb
+        ));
+    }
+
+    let request = DocumentIngestionRequest {
+        filename: record.filename.clone(),
+        original_filename: record.filename.clone(),
+        file_data,
+        mime_type,
+        user_id: auth_user.user.id,
+        deduplication_policy: DeduplicationPolicy::Skip,
+        source_type: Some("external_ingest".to_string()),
+        source_id: None,
+        original_created_at: record.original_created_at,
+        original_modified_at: record.original_modified_at,
+        source_path: record.source_path.clone(),
+        file_permissions: None,
+        file_owner: None,
+        file_group: None,
+        source_metadata: record.metadata.clone(),
+    };
+
+    match ingestion_service.ingest_document(request).await {
+        Ok(IngestionResult::Created(document)) => {
+            if let Err(e) = state.db.update_document_external_text(document.id, &text).await {
+                error!("Failed to store external text for document {}: {}", document.id, e);
+                return ExternalIngestRecordResult {
+                    line,
+                    filename: Some(filename.clone()),
+                    status: "failed".to_string(),
+                    document_id: Some(document.id),
+                    error: Some(format!("Document created but text could not be stored: {}", e)),
+                };
+            }
+
+            ExternalIngestRecordResult {
+                line,
+                filename: Some(filename.clone()),
+                status: "created".to_string(),
+                document_id: Some(document.id),
+                error: None,
+            }
+        }
+        Ok(IngestionResult::Skipped { existing_document_id, .. })
+        | Ok(IngestionResult::TrackedAsDuplicate { existing_document_id }) => ExternalIngestRecordResult {
+            line,
+            filename: Some(filename.clone()),
+            status: "duplicate".to_string(),
+            document_id: Some(existing_document_id),
+            error: None,
+        },
+        Ok(IngestionResult::ExistingDocument(existing_doc)) => ExternalIngestRecordResult {
+            line,
+            filename: Some(filename.clone()),
+            status: "duplicate".to_string(),
+            document_id: Some(existing_doc.id),
+            error: None,
+        },
+        Ok(IngestionResult::Quarantined { threat, .. }) => ExternalIngestRecordResult {
+            line,
+            filename: Some(filename.clone()),
+            status: "quarantined".to_string(),
+            document_id: None,
+            error: Some(format!("Malware detected: {}", threat)),
+        },
+        Ok(IngestionResult::Corrupt { reason, .. }) => ExternalIngestRecordResult {
+            line,
+            filename: Some(filename.clone()),
+            status: "corrupt".to_string(),
+            document_id: None,
+            error: Some(format!("Corrupt file: {}", reason)),
+        },
+        Err(e) => {
+            warn!("Bulk ingest of record {} ({}) failed: {}", line, filename, e);
+            failed(format!("Failed to ingest document: {}", e))
+        }
+    }
+}
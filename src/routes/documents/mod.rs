@@ -1,4 +1,4 @@
-use axum::{routing::{get, post, delete}, Router};
+use axum::{routing::{get, post, put, delete}, Router};
 use std::sync::Arc;
 use crate::AppState;
 
@@ -8,6 +8,13 @@ pub mod ocr;
 pub mod bulk;
 pub mod debug;
 pub mod failed;
+pub mod ingest;
+pub mod organize;
+pub mod related;
+pub mod shares;
+pub mod notes;
+pub mod expiry;
+pub mod tables;
 
 // Re-export commonly used types and functions for backward compatibility
 pub use types::*;
@@ -16,6 +23,13 @@ pub use ocr::*;
 pub use bulk::*;
 pub use debug::*;
 pub use failed::*;
+pub use ingest::*;
+pub use organize::*;
+pub use related::*;
+pub use shares::*;
+pub use notes::*;
+pub use expiry::*;
+pub use tables::*;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -26,12 +40,33 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/{id}", delete(delete_document))
         .route("/{id}/download", get(download_document))
         .route("/{id}/view", get(view_document))
+        .route("/ingest", post(ingest_external_documents))
+        .route("/{id}/move", post(move_document))
+        .route("/{id}/related", get(get_related_documents))
+        .route("/{id}/splits", get(get_document_splits))
+        .route("/{id}/archive-entries", get(get_document_archive_entries))
+        .route("/{id}/shares", post(share_document))
+        .route("/{id}/shares", get(list_document_shares))
+        .route("/{id}/shares/{user_id}", delete(unshare_document))
+        .route("/{id}/notes", post(create_document_note))
+        .route("/{id}/notes", get(list_document_notes))
+        .route("/{id}/notes/{note_id}", put(update_document_note))
+        .route("/{id}/notes/{note_id}", delete(delete_document_note))
+        .route("/{id}/expiry", put(update_document_expiry))
+        .route("/expiring", get(list_expiring_documents))
+        .route("/{id}/tables", get(get_document_tables))
         
         // OCR operations
         .route("/{id}/ocr", get(get_document_ocr))
+        .route("/{id}/ocr", post(enqueue_document_ocr))
         .route("/{id}/ocr/retry", post(retry_ocr))
+        .route("/{id}/ocr/pages", post(ocr_document_pages))
+        .route("/{id}/ocr/region", post(ocr_document_region))
+        .route("/{id}/fields", get(get_document_fields))
         .route("/ocr/stats", get(get_ocr_stats))
         .route("/{id}/ocr/stop", post(cancel_ocr))
+        .route("/{id}/text", put(update_document_text))
+        .route("/{id}/handwriting", put(update_document_handwriting))
         
         // OCR retry operations
         .route("/ocr/retry/stats", get(crate::routes::documents_ocr_retry::get_ocr_retry_stats))
@@ -40,12 +75,15 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/{id}/ocr/retry/history", get(crate::routes::documents_ocr_retry::get_document_retry_history))
         
         // Bulk operations
+        .route("/batch", post(get_documents_batch))
         .route("/bulk/delete", post(bulk_delete_documents))
+        .route("/bulk/move", post(bulk_move_documents))
         .route("/cleanup/low/confidence", delete(delete_low_confidence_documents))
         .route("/cleanup/failed/ocr", delete(delete_failed_ocr_documents))
         
         // Debug operations
         .route("/{id}/debug", get(get_document_debug_info))
+        .route("/{id}/ocr/log", get(stream_ocr_log))
         .route("/{id}/thumbnail", get(get_document_thumbnail))
         .route("/{id}/processed/image", get(get_processed_image))
         .route("/{id}/validate", get(validate_document_integrity))
@@ -0,0 +1,212 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    errors::note::NoteError,
+    models::document_note::{DocumentNote, DocumentNoteRequest},
+    AppState,
+};
+
+const MAX_NOTE_LENGTH: usize = 10_000;
+
+/// Notes can be attached to any document the user can read, including documents
+/// shared with them
+async fn verify_document_access(state: &AppState, auth_user: &AuthUser, document_id: Uuid) -> Result<(), NoteError> {
+    let readable = state
+        .db
+        .can_read_document(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Failed to verify document access: {}", e);
+            NoteError::InternalError { message: "Failed to verify document access".into() }
+        })?;
+
+    if readable {
+        Ok(())
+    } else {
+        Err(NoteError::DocumentNotFound)
+    }
+}
+
+fn validate_content(content: &str) -> Result<&str, NoteError> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(NoteError::ContentEmpty);
+    }
+    if content.len() > MAX_NOTE_LENGTH {
+        return Err(NoteError::ContentTooLong {
+            length: content.len(),
+            max_length: MAX_NOTE_LENGTH,
+        });
+    }
+    Ok(content)
+}
+
+/// Add a note to a document
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/notes",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = DocumentNoteRequest,
+    responses(
+        (status = 201, description = "Note created", body = DocumentNote),
+        (status = 400, description = "Empty or too long note"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn create_document_note(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<Uuid>,
+    Json(payload): Json<DocumentNoteRequest>,
+) -> Result<(StatusCode, Json<DocumentNote>), NoteError> {
+    verify_document_access(&state, &auth_user, document_id).await?;
+    let content = validate_content(&payload.content)?;
+
+    let note = state
+        .db
+        .create_document_note(document_id, auth_user.user.id, content)
+        .await
+        .map_err(|e| {
+            error!("Failed to create note on document {}: {}", document_id, e);
+            NoteError::InternalError { message: "Failed to create note".into() }
+        })?;
+
+    debug!("Note created on document {} by user {}", document_id, auth_user.user.id);
+    Ok((StatusCode::CREATED, Json(note)))
+}
+
+/// List your notes on a document, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/notes",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Your notes on the document", body = Vec<DocumentNote>),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_document_notes(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<Uuid>,
+) -> Result<Json<Vec<DocumentNote>>, NoteError> {
+    verify_document_access(&state, &auth_user, document_id).await?;
+
+    let notes = state
+        .db
+        .get_document_notes(document_id, auth_user.user.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list notes on document {}: {}", document_id, e);
+            NoteError::InternalError { message: "Failed to list notes".into() }
+        })?;
+
+    Ok(Json(notes))
+}
+
+/// Edit one of your notes
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}/notes/{note_id}",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("note_id" = uuid::Uuid, Path, description = "Note ID")
+    ),
+    request_body = DocumentNoteRequest,
+    responses(
+        (status = 200, description = "Note updated", body = DocumentNote),
+        (status = 400, description = "Empty or too long note"),
+        (status = 404, description = "Document or note not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn update_document_note(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path((document_id, note_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<DocumentNoteRequest>,
+) -> Result<Json<DocumentNote>, NoteError> {
+    verify_document_access(&state, &auth_user, document_id).await?;
+    let content = validate_content(&payload.content)?;
+
+    // Only the author's own notes match, so other users' notes read as not found
+    let note = state
+        .db
+        .update_document_note(note_id, document_id, auth_user.user.id, content)
+        .await
+        .map_err(|e| {
+            error!("Failed to update note {}: {}", note_id, e);
+            NoteError::InternalError { message: "Failed to update note".into() }
+        })?
+        .ok_or(NoteError::NotFound)?;
+
+    Ok(Json(note))
+}
+
+/// Delete one of your notes
+#[utoipa::path(
+    delete,
+    path = "/api/documents/{id}/notes/{note_id}",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("note_id" = uuid::Uuid, Path, description = "Note ID")
+    ),
+    responses(
+        (status = 204, description = "Note deleted"),
+        (status = 404, description = "Document or note not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn delete_document_note(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path((document_id, note_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, NoteError> {
+    verify_document_access(&state, &auth_user, document_id).await?;
+
+    let deleted = state
+        .db
+        .delete_document_note(note_id, document_id, auth_user.user.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete note {}: {}", note_id, e);
+            NoteError::InternalError { message: "Failed to delete note".into() }
+        })?;
+
+    if deleted {
+        debug!("Note {} deleted by user {}", note_id, auth_user.user.id);
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(NoteError::NotFound)
+    }
+}
@@ -8,9 +8,16 @@ use tracing::{debug, error, info, warn};
 
 use crate::{
     auth::AuthUser,
-    models::DocumentOcrResponse,
+    models::{DocumentOcrResponse, OcrJobOverrides},
+    ocr::{
+        enhanced::EnhancedOcrService,
+        page_range::PageRangeError,
+        queue::{OcrPriorityClass, OnDemandEnqueue},
+        region::RegionError,
+    },
     AppState,
 };
+use super::crud::DocumentError;
 
 /// Get OCR text for a document
 #[utoipa::path(
@@ -45,8 +52,29 @@ pub async fn get_document_ocr(
         })?
         .ok_or(StatusCode::NOT_FOUND)?;
 
+    let correction = state.db.get_ocr_text_correction(document_id).await.map_err(|e| {
+        error!("Database error getting OCR correction for document {}: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let ocr_hybrid_extraction = state.db.get_ocr_hybrid_extraction(document_id).await.map_err(|e| {
+        error!("Database error getting extraction method for document {}: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let (ocr_handwriting, ocr_engine, ocr_engine_note) = state.db.get_ocr_engine_info(document_id).await.map_err(|e| {
+        error!("Database error getting OCR engine for document {}: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let ocr_confidence_raw = state.db.get_ocr_raw_confidence(document_id).await.map_err(|e| {
+        error!("Database error getting raw OCR confidence for document {}: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
     // Fetch progress from ocr_queue when actively processing
-    let pages_processed = if document.ocr_status.as_deref() == Some("processing") {
+    let ocr_text_is_preview = document.ocr_status.as_deref() == Some("preview");
+    let pages_processed = if document.ocr_status.as_deref() == Some("processing") || ocr_text_is_preview {
         sqlx::query_as::<_, (Option<i32>,)>(
             "SELECT progress_current FROM ocr_queue WHERE document_id = $1 AND status = 'processing' LIMIT 1"
         )
@@ -66,15 +94,482 @@ pub async fn get_document_ocr(
         has_ocr_text: document.ocr_text.is_some(),
         ocr_text: document.ocr_text,
         ocr_confidence: document.ocr_confidence,
+        ocr_confidence_raw,
         ocr_status: document.ocr_status,
         ocr_processing_time_ms: document.ocr_processing_time_ms,
         detected_language: None,
         pages_processed,
+        ocr_text_is_preview,
+        ocr_manually_corrected: correction.as_ref().map(|c| c.manually_corrected).unwrap_or(false),
+        original_ocr_text: correction.and_then(|c| c.original_ocr_text),
+        ocr_hybrid_extraction,
+        ocr_handwriting,
+        ocr_engine,
+        ocr_engine_note,
     };
 
     Ok(ResponseJson(response))
 }
 
+/// Replace a document's OCR text with a manual correction
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}/text",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = super::types::UpdateDocumentTextRequest,
+    responses(
+        (status = 200, description = "Corrected OCR text", body = DocumentOcrResponse),
+        (status = 400, description = "Corrected text is empty"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "OCR is still in progress"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_document_text(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<super::types::UpdateDocumentTextRequest>,
+) -> Result<ResponseJson<DocumentOcrResponse>, StatusCode> {
+    if request.text.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (ocr_handwriting, _, _) = state.db.get_ocr_engine_info(document_id).await.map_err(|e| {
+        error!("Database error getting OCR engine for document {}: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    // Search indexes are expressions over ocr_text, so updating the column re-indexes the document
+    let correction = state
+        .db
+        .correct_document_ocr_text(document.id, &request.text)
+        .await
+        .map_err(|e| {
+            error!("Failed to store corrected text for document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::CONFLICT)?;
+
+    info!("OCR text of document {} manually corrected by user {}", document_id, auth_user.user.id);
+
+    Ok(ResponseJson(DocumentOcrResponse {
+        id: document.id,
+        filename: document.original_filename,
+        has_ocr_text: true,
+        ocr_text: Some(request.text),
+        ocr_confidence: document.ocr_confidence,
+        ocr_confidence_raw: None,
+        ocr_status: Some(if document.ocr_status.as_deref() == Some("external") { "external" } else { "completed" }.to_string()),
+        ocr_processing_time_ms: document.ocr_processing_time_ms,
+        detected_language: None,
+        pages_processed: None,
+        ocr_text_is_preview: false,
+        ocr_manually_corrected: correction.manually_corrected,
+        original_ocr_text: correction.original_ocr_text,
+        ocr_hybrid_extraction: false,
+        ocr_handwriting,
+        ocr_engine: None,
+        ocr_engine_note: None,
+    }))
+}
+
+/// Flag or unflag a document as handwritten
+///
+/// Handwritten documents are OCR'd by the configured handwriting engine, or by
+/// Tesseract with a note when none is configured. The flag applies from the
+/// document's next OCR run; retry OCR to apply it to existing text.
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}/handwriting",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = super::types::UpdateHandwritingRequest,
+    responses(
+        (status = 204, description = "Handwriting flag updated"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_document_handwriting(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<super::types::UpdateHandwritingRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let updated = state
+        .db
+        .set_document_handwriting(document.id, request.handwriting)
+        .await
+        .map_err(|e| {
+            error!("Failed to update handwriting flag of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    info!("Document {} flagged as {}handwritten by user {}",
+          document_id, if request.handwriting { "" } else { "not " }, auth_user.user.id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Run OCR on selected pages of a PDF without changing the stored text
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/ocr/pages",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = super::types::OcrPagesRequest,
+    responses(
+        (status = 200, description = "OCR text of the requested pages", body = super::types::OcrPagesResponse),
+        (status = 400, description = "Invalid or out-of-range page spec, or the document is not a PDF"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn ocr_document_pages(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<super::types::OcrPagesRequest>,
+) -> Result<ResponseJson<super::types::OcrPagesResponse>, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    if document.mime_type != "application/pdf" {
+        return Err(DocumentError::BadRequest(format!(
+            "Page OCR is only supported for PDFs, not {}",
+            document.mime_type
+        )));
+    }
+
+    let settings = state
+        .db
+        .get_user_settings(auth_user.user.id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    let ocr_service = EnhancedOcrService::new(
+        crate::ocr::temp_files::temp_dir(),
+        (*state.file_service).clone(),
+        state.config.max_pdf_size_mb,
+        state.config.max_office_document_size_mb,
+        state.config.ocr_timeout_seconds,
+    );
+
+    let result = ocr_service
+        .extract_pdf_pages(&document.file_path, &request.pages, &settings)
+        .await
+        .map_err(|e| match e.downcast_ref::<PageRangeError>() {
+            Some(range_error) => DocumentError::BadRequest(range_error.to_string()),
+            None => {
+                error!("Page OCR failed for document {}: {}", document_id, e);
+                DocumentError::OcrProcessingError(format!("Page OCR failed: {}", e))
+            }
+        })?;
+
+    let text = result
+        .pages
+        .iter()
+        .map(|page| page.text.as_str())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n--- Page Break ---\n\n");
+
+    Ok(ResponseJson(super::types::OcrPagesResponse {
+        document_id: document.id,
+        page_count: result.page_count,
+        pages: result
+            .pages
+            .into_iter()
+            .map(|page| super::types::OcrPageText {
+                page: page.page,
+                text: page.text,
+                confidence: page.confidence,
+                word_count: page.word_count,
+            })
+            .collect(),
+        text,
+        processing_time_ms: result.processing_time_ms,
+    }))
+}
+
+/// Run OCR on one region of an image or PDF page without changing the stored text
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/ocr/region",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = super::types::OcrRegionRequest,
+    responses(
+        (status = 200, description = "OCR text within the region", body = super::types::OcrRegionResponse),
+        (status = 400, description = "Region outside the image, page out of range, or the document is not an image or PDF"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn ocr_document_region(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<super::types::OcrRegionRequest>,
+) -> Result<ResponseJson<super::types::OcrRegionResponse>, DocumentError> {
+    request
+        .region
+        .validate()
+        .map_err(|e| DocumentError::BadRequest(e.to_string()))?;
+
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let is_pdf = document.mime_type == "application/pdf";
+    if !is_pdf && !document.mime_type.starts_with("image/") {
+        return Err(DocumentError::BadRequest(format!(
+            "Region OCR is only supported for images and PDFs, not {}",
+            document.mime_type
+        )));
+    }
+
+    let settings = state
+        .db
+        .get_user_settings(auth_user.user.id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    let ocr_service = EnhancedOcrService::new(
+        crate::ocr::temp_files::temp_dir(),
+        (*state.file_service).clone(),
+        state.config.max_pdf_size_mb,
+        state.config.max_office_document_size_mb,
+        state.config.ocr_timeout_seconds,
+    );
+
+    let result = ocr_service
+        .extract_region(&document.file_path, &document.mime_type, request.page, &request.region, &settings)
+        .await
+        .map_err(|e| {
+            if let Some(region_error) = e.downcast_ref::<RegionError>() {
+                return DocumentError::BadRequest(region_error.to_string());
+            }
+            if let Some(range_error) = e.downcast_ref::<PageRangeError>() {
+                return DocumentError::BadRequest(range_error.to_string());
+            }
+            error!("Region OCR failed for document {}: {}", document_id, e);
+            DocumentError::OcrProcessingError(format!("Region OCR failed: {}", e))
+        })?;
+
+    Ok(ResponseJson(super::types::OcrRegionResponse {
+        document_id: document.id,
+        name: request.name,
+        page: is_pdf.then_some(request.page.unwrap_or(1)),
+        region: request.region,
+        image_width: result.image_width,
+        image_height: result.image_height,
+        text: result.text,
+        confidence: result.confidence,
+        word_count: result.word_count,
+        processing_time_ms: result.processing_time_ms,
+    }))
+}
+
+/// Queue OCR of a document on demand, such as one stored without OCR by its
+/// source's processing mode or by the ingest API
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/ocr",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body(content = OcrJobOverrides, description = "Settings to use instead of your own for this job; send {} to use your settings"),
+    responses(
+        (status = 202, description = "Document queued for OCR", body = super::types::OnDemandOcrResponse),
+        (status = 400, description = "Invalid settings overrides"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "OCR is already queued or running, or the OCR text was manually corrected"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn enqueue_document_ocr(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(overrides): Json<OcrJobOverrides>,
+) -> Result<(StatusCode, ResponseJson<super::types::OnDemandOcrResponse>), DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    overrides.validate().map_err(DocumentError::BadRequest)?;
+    if let Some(languages) = &overrides.languages {
+        crate::ocr::health::OcrHealthChecker::new()
+            .validate_preferred_languages(languages)
+            .map_err(|e| DocumentError::BadRequest(format!("Invalid OCR languages: {}", e)))?;
+    }
+
+    // The queue never overwrites a manual correction; discarding one takes a forced retry
+    let manually_corrected = state
+        .db
+        .get_ocr_text_correction(document_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting OCR correction for document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .map(|c| c.manually_corrected)
+        .unwrap_or(false);
+    if manually_corrected {
+        return Err(DocumentError::Conflict(
+            "OCR text was manually corrected; use OCR retry with force to discard the correction".to_string(),
+        ));
+    }
+
+    let queued = state
+        .queue_service
+        .enqueue_on_demand(document.id, document.file_size, &overrides)
+        .await
+        .map_err(|e| {
+            error!("Failed to queue document {} for OCR: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to queue document for OCR".to_string())
+        })?;
+
+    match queued {
+        OnDemandEnqueue::Queued(queue_item_id) => {
+            info!("Document {} queued for on-demand OCR by user {}", document_id, auth_user.user.id);
+            Ok((
+                StatusCode::ACCEPTED,
+                ResponseJson(super::types::OnDemandOcrResponse {
+                    queue_item_id,
+                    document_id: document.id,
+                }),
+            ))
+        }
+        OnDemandEnqueue::AlreadyQueued(queue_item_id) => Err(DocumentError::Conflict(format!(
+            "OCR is already queued or running for this document (queue item {})",
+            queue_item_id
+        ))),
+    }
+}
+
+/// Get the fields read from the named OCR regions of a document's labels
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/fields",
+    tag = "documents",
+    description = "Text of the named regions defined for the document's labels, read when the document was last OCR'd.",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Extracted fields", body = super::types::DocumentFieldsResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_document_fields(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<ResponseJson<super::types::DocumentFieldsResponse>, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let fields = state.db.get_document_fields(document.id).await.map_err(|e| {
+        error!("Failed to load fields of document {}: {}", document_id, e);
+        DocumentError::InternalServerError("Failed to load fields".to_string())
+    })?;
+
+    Ok(ResponseJson(super::types::DocumentFieldsResponse {
+        document_id: document.id,
+        extracted: fields.is_some(),
+        fields: fields.unwrap_or_default(),
+    }))
+}
+
 /// Retry OCR processing for a document
 #[utoipa::path(
     post,
@@ -116,7 +611,7 @@ pub async fn retry_ocr(
 
     // Check if OCR is already in progress
     if let Some(ref status) = document.ocr_status {
-        if status == "processing" {
+        if status == "processing" || status == "preview" {
             return Ok(ResponseJson(serde_json::json!({
                 "success": false,
                 "message": "OCR is already in progress for this document"
@@ -124,6 +619,29 @@ pub async fn retry_ocr(
         }
     }
 
+    // Reprocessing would overwrite a manual correction, so it must be forced
+    let manually_corrected = state.db.get_ocr_text_correction(document_id).await
+        .map_err(|e| {
+            error!("Database error getting OCR correction for document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map(|c| c.manually_corrected)
+        .unwrap_or(false);
+    if manually_corrected {
+        if !request.force {
+            return Ok(ResponseJson(serde_json::json!({
+                "success": false,
+                "message": "OCR text was manually corrected; retry with force to discard the correction"
+            })));
+        }
+
+        if let Err(e) = state.db.clear_ocr_text_correction(document_id).await {
+            error!("Failed to clear OCR correction for document {}: {}", document_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        info!("Discarding manual OCR correction for document {} (forced retry)", document_id);
+    }
+
     // Update user's OCR language settings based on what was provided
     if let Some(languages) = &request.languages {
         // Multi-language support: validate and update preferred languages
@@ -168,7 +686,7 @@ pub async fn retry_ocr(
     }
 
     // Add to OCR queue
-    match state.queue_service.enqueue_document(document.id, 5, document.file_size).await {
+    match state.queue_service.enqueue_document_with_class(document.id, OcrPriorityClass::Interactive, 5, document.file_size).await {
         Ok(_) => {
             info!("Document {} queued for OCR retry", document_id);
             Ok(ResponseJson(serde_json::json!({
@@ -0,0 +1,286 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::{
+    auth::AuthUser,
+    models::Document,
+    AppState,
+};
+use super::crud::DocumentError;
+use super::types::{
+    BulkMoveDocumentsRequest, BulkMoveDocumentsResponse, MoveDocumentFailure, MoveDocumentRequest,
+    MoveDocumentResponse,
+};
+
+/// Normalizes a logical folder path: forward slashes, a leading slash and no trailing
+/// slash. Parent directory references are rejected.
+pub fn normalize_target_folder(folder: &str) -> Result<String, String> {
+    let segments: Vec<&str> = folder
+        .trim()
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+
+    if segments.iter().any(|segment| *segment == "..") {
+        return Err("Target folder cannot contain '..'".to_string());
+    }
+
+    Ok(format!("/{}", segments.join("/")))
+}
+
+/// Move a document to another folder and/or source
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/move",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = MoveDocumentRequest,
+    responses(
+        (status = 200, description = "Document moved", body = MoveDocumentResponse),
+        (status = 400, description = "Invalid move request"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn move_document(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<MoveDocumentRequest>,
+) -> Result<Json<MoveDocumentResponse>, DocumentError> {
+    let target_folder = validate_move_request(
+        &state,
+        &auth_user,
+        request.target_folder.as_deref(),
+        request.target_source_id,
+        request.restore_file,
+    ).await?;
+
+    let response = move_single_document(
+        &state,
+        &auth_user,
+        document_id,
+        target_folder.as_deref(),
+        request.target_source_id,
+        request.restore_file,
+    ).await?;
+
+    Ok(Json(response))
+}
+
+/// Move multiple documents to another folder and/or source
+#[utoipa::path(
+    post,
+    path = "/api/documents/bulk/move",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = BulkMoveDocumentsRequest,
+    responses(
+        (status = 200, description = "Bulk move results", body = BulkMoveDocumentsResponse),
+        (status = 400, description = "Invalid move request"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn bulk_move_documents(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<BulkMoveDocumentsRequest>,
+) -> Result<Json<BulkMoveDocumentsResponse>, DocumentError> {
+    if request.document_ids.is_empty() {
+        return Err(DocumentError::BadRequest("No documents specified".to_string()));
+    }
+
+    if request.document_ids.len() > 1000 {
+        return Err(DocumentError::BadRequest("Cannot move more than 1000 documents at once".to_string()));
+    }
+
+    let target_folder = validate_move_request(
+        &state,
+        &auth_user,
+        request.target_folder.as_deref(),
+        request.target_source_id,
+        request.restore_file,
+    ).await?;
+
+    info!("Bulk moving {} documents", request.document_ids.len());
+
+    let mut moved_documents = Vec::new();
+    let mut failed_documents = Vec::new();
+
+    for document_id in &request.document_ids {
+        match move_single_document(
+            &state,
+            &auth_user,
+            *document_id,
+            target_folder.as_deref(),
+            request.target_source_id,
+            request.restore_file,
+        ).await {
+            Ok(moved) => moved_documents.push(moved),
+            Err(e) => {
+                let error = match e {
+                    DocumentError::NotFound => "Document not found".to_string(),
+                    DocumentError::BadRequest(msg)
+                    | DocumentError::InternalServerError(msg)
+                    | DocumentError::FileProcessingError(msg) => msg,
+                    other => format!("{:?}", other),
+                };
+                failed_documents.push(MoveDocumentFailure { document_id: *document_id, error });
+            }
+        }
+    }
+
+    let response = BulkMoveDocumentsResponse {
+        moved_count: moved_documents.len() as i64,
+        failed_count: failed_documents.len() as i64,
+        moved_documents,
+        failed_documents,
+    };
+
+    info!("Bulk move completed: {} moved, {} failed",
+        response.moved_count, response.failed_count);
+
+    Ok(Json(response))
+}
+
+/// Validates the move target and returns the normalized folder, if any
+async fn validate_move_request(
+    state: &Arc<AppState>,
+    auth_user: &AuthUser,
+    target_folder: Option<&str>,
+    target_source_id: Option<uuid::Uuid>,
+    restore_file: bool,
+) -> Result<Option<String>, DocumentError> {
+    if target_folder.is_none() && target_source_id.is_none() && !restore_file {
+        return Err(DocumentError::BadRequest(
+            "Specify a target_folder, target_source_id or restore_file".to_string(),
+        ));
+    }
+
+    if let Some(source_id) = target_source_id {
+        let source = state.db.get_source(auth_user.user.id, source_id).await.map_err(|e| {
+            error!("Failed to look up source {}: {}", source_id, e);
+            DocumentError::InternalServerError("Failed to look up target source".to_string())
+        })?;
+        if source.is_none() {
+            return Err(DocumentError::BadRequest(format!("Source {} not found", source_id)));
+        }
+    }
+
+    target_folder
+        .map(normalize_target_folder)
+        .transpose()
+        .map_err(DocumentError::BadRequest)
+}
+
+async fn move_single_document(
+    state: &Arc<AppState>,
+    auth_user: &AuthUser,
+    document_id: uuid::Uuid,
+    target_folder: Option<&str>,
+    target_source_id: Option<uuid::Uuid>,
+    restore_file: bool,
+) -> Result<MoveDocumentResponse, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let new_source_path = target_folder.map(|folder| {
+        format!("{}/{}", folder.trim_end_matches('/'), document.original_filename)
+    });
+
+    let new_file_path = if restore_file {
+        restore_document_file(state, &document).await?
+    } else {
+        None
+    };
+
+    let moved = state
+        .db
+        .move_document(
+            document_id,
+            auth_user.user.id,
+            auth_user.user.role,
+            new_source_path.as_deref(),
+            target_source_id,
+            new_file_path.as_deref(),
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to move document {}: {}", document_id, e);
+            DocumentError::InternalServerError(format!("Failed to move document: {}", e))
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    // Only drop the old copy once the document points at the new one
+    if let Some(new_path) = &new_file_path {
+        remove_previous_file(state, &document, new_path).await;
+    }
+
+    info!("Moved document {} to {:?} (source: {:?})", document_id, moved.source_path, moved.source_id);
+
+    Ok(MoveDocumentResponse {
+        document_id: moved.id,
+        source_path: moved.source_path,
+        source_id: moved.source_id,
+        file_path: moved.file_path,
+        file_restored: new_file_path.is_some(),
+    })
+}
+
+/// Re-saves the document through the current storage backend. Returns the new storage
+/// path, or None when the file already lives at that path. Thumbnails and processed
+/// images are keyed by document ID, so they keep resolving after the file moves.
+async fn restore_document_file(
+    state: &Arc<AppState>,
+    document: &Document,
+) -> Result<Option<String>, DocumentError> {
+    let data = state.file_service.read_user_file(document.user_id, &document.file_path).await.map_err(|e| {
+        error!("Failed to read file for document {}: {}", document.id, e);
+        DocumentError::FileProcessingError(format!("Failed to read document file: {}", e))
+    })?;
+
+    let new_path = state
+        .file_service
+        .save_document_file(document.user_id, document.id, &document.filename, &data)
+        .await
+        .map_err(|e| {
+            error!("Failed to re-store file for document {}: {}", document.id, e);
+            DocumentError::FileProcessingError(format!("Failed to store document file: {}", e))
+        })?;
+
+    if new_path == document.file_path {
+        Ok(None)
+    } else {
+        Ok(Some(new_path))
+    }
+}
+
+/// Removes the previous copy of a re-stored file through the storage that holds it
+async fn remove_previous_file(state: &Arc<AppState>, document: &Document, new_path: &str) {
+    let old_path = &document.file_path;
+    if old_path == new_path {
+        return;
+    }
+
+    if let Err(e) = state.file_service.delete_user_file(document.user_id, old_path).await {
+        warn!("Failed to remove previous file {} after move: {}", old_path, e);
+    }
+}
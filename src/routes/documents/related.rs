@@ -0,0 +1,240 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::{auth::AuthUser, AppState};
+use super::crud::DocumentError;
+use super::types::{
+    DocumentArchiveEntriesResponse, DocumentArchiveEntry, DocumentSplitPart, DocumentSplitsResponse,
+    RelatedDocumentsQuery, RelatedDocumentsResponse,
+};
+
+/// Number of the source document's most frequent terms used to find related documents
+const RELATED_TERM_COUNT: i64 = 20;
+
+/// Minimum trigram similarity for a filename match on its own (pg_trgm's default threshold)
+const MIN_FILENAME_SIMILARITY: f32 = 0.3;
+
+const DEFAULT_RELATED_LIMIT: i64 = 10;
+const MAX_RELATED_LIMIT: i64 = 50;
+
+/// Find documents similar to a document
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/related",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        RelatedDocumentsQuery
+    ),
+    responses(
+        (status = 200, description = "Related documents, most similar first", body = RelatedDocumentsResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_related_documents(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Query(query): Query<RelatedDocumentsQuery>,
+) -> Result<Json<RelatedDocumentsResponse>, DocumentError> {
+    // Access check; related documents are then limited to the same owner's library
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_RELATED_LIMIT).clamp(1, MAX_RELATED_LIMIT);
+
+    let related = state
+        .db
+        .find_related_documents(document.id, RELATED_TERM_COUNT, MIN_FILENAME_SIMILARITY, limit)
+        .await
+        .map_err(|e| {
+            error!("Failed to find documents related to {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to find related documents".to_string())
+        })?;
+
+    Ok(Json(RelatedDocumentsResponse {
+        document_id: document.id,
+        related,
+    }))
+}
+
+/// Show how a document relates to the multi-document scan it was split from, or
+/// the documents it was split into
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/splits",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Original scan and split parts of the document", body = DocumentSplitsResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_document_splits(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<DocumentSplitsResponse>, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let (archived_at, split_parent_id, first_page, last_page) = state
+        .db
+        .get_document_split_info(document.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load split info of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load split info".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let split_from = match (split_parent_id, first_page, last_page) {
+        (Some(parent_id), Some(first_page), Some(last_page)) => {
+            let parent = state
+                .db
+                .get_document_by_id(parent_id, auth_user.user.id, auth_user.user.role)
+                .await
+                .map_err(|e| {
+                    error!("Database error getting document {}: {}", parent_id, e);
+                    DocumentError::InternalServerError("Failed to load document".to_string())
+                })?;
+            parent.map(|parent| DocumentSplitPart {
+                document_id: parent.id,
+                filename: parent.original_filename,
+                first_page,
+                last_page,
+            })
+        }
+        _ => None,
+    };
+
+    let parts = state
+        .db
+        .get_split_parts(document.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load split parts of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load split parts".to_string())
+        })?
+        .into_iter()
+        .map(|(document_id, filename, first_page, last_page)| DocumentSplitPart {
+            document_id,
+            filename,
+            first_page,
+            last_page,
+        })
+        .collect();
+
+    Ok(Json(DocumentSplitsResponse {
+        document_id: document.id,
+        archived_at,
+        split_from,
+        parts,
+    }))
+}
+
+/// Show the archive a document was extracted from, or the documents extracted
+/// from an archive
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/archive-entries",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Parent archive and extracted files of the document", body = DocumentArchiveEntriesResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_document_archive_entries(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<DocumentArchiveEntriesResponse>, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let (archived_at, _, _, _) = state
+        .db
+        .get_document_split_info(document.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load archive state of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load archive info".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let (extracted_from, entry_path) = state
+        .db
+        .get_document_archive_info(document.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load archive info of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load archive info".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let entries = state
+        .db
+        .get_archive_entries(document.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load archive entries of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load archive entries".to_string())
+        })?
+        .into_iter()
+        .map(|(document_id, filename, entry_path)| DocumentArchiveEntry {
+            document_id,
+            filename,
+            entry_path,
+        })
+        .collect();
+
+    Ok(Json(DocumentArchiveEntriesResponse {
+        document_id: document.id,
+        archived_at,
+        extracted_from,
+        entry_path,
+        entries,
+    }))
+}
@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::{
+    auth::AuthUser,
+    models::document_share::{CreateDocumentShareRequest, DocumentShare},
+    AppState,
+};
+use super::crud::DocumentError;
+
+/// Share a document you own with another user
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/shares",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = CreateDocumentShareRequest,
+    responses(
+        (status = 200, description = "Document shared", body = DocumentShare),
+        (status = 400, description = "Unknown user, or sharing with yourself"),
+        (status = 404, description = "Document not found or not owned by you"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn share_document(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<CreateDocumentShareRequest>,
+) -> Result<Json<DocumentShare>, DocumentError> {
+    let recipient = state
+        .db
+        .get_user_by_username(request.username.trim())
+        .await
+        .map_err(|e| {
+            error!("Failed to look up user '{}': {}", request.username, e);
+            DocumentError::InternalServerError("Failed to look up user".to_string())
+        })?
+        .ok_or_else(|| DocumentError::BadRequest(format!("User '{}' not found", request.username.trim())))?;
+
+    if recipient.id == auth_user.user.id {
+        return Err(DocumentError::BadRequest("You cannot share a document with yourself".to_string()));
+    }
+
+    let share = state
+        .db
+        .share_document(document_id, auth_user.user.id, recipient.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to share document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to share document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    Ok(Json(share))
+}
+
+/// List the users a document you own is shared with
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/shares",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Shares of the document", body = Vec<DocumentShare>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_document_shares(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<Vec<DocumentShare>>, DocumentError> {
+    let shares = state
+        .db
+        .get_document_shares(document_id, auth_user.user.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to list shares of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to list document shares".to_string())
+        })?;
+
+    Ok(Json(shares))
+}
+
+/// Stop sharing a document you own with a user
+#[utoipa::path(
+    delete,
+    path = "/api/documents/{id}/shares/{user_id}",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("user_id" = uuid::Uuid, Path, description = "User the document is shared with")
+    ),
+    responses(
+        (status = 204, description = "Share removed"),
+        (status = 404, description = "Share not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn unshare_document(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path((document_id, user_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+) -> Result<StatusCode, DocumentError> {
+    let removed = state
+        .db
+        .unshare_document(document_id, auth_user.user.id, user_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to unshare document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to remove document share".to_string())
+        })?;
+
+    if removed {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(DocumentError::NotFound)
+    }
+}
@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Json, Response},
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::{
+    auth::AuthUser,
+    ocr::table_extraction::{table_to_csv, tables_to_csv},
+    AppState,
+};
+use super::crud::DocumentError;
+use super::types::{DocumentTablesQuery, DocumentTablesResponse};
+
+/// Get the tables extracted from a document, as JSON or CSV
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/tables",
+    tag = "documents",
+    description = "Tables detected in the text layout of a PDF when the owner's `extract_tables` setting is on. CSV exports separate several tables with a blank line.",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        DocumentTablesQuery
+    ),
+    responses(
+        (status = 200, description = "Extracted tables", body = DocumentTablesResponse),
+        (status = 200, description = "Extracted tables as CSV when format=csv", content_type = "text/csv"),
+        (status = 400, description = "Unsupported format"),
+        (status = 404, description = "Document or table not found"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_document_tables(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Query(query): Query<DocumentTablesQuery>,
+) -> Result<Response, DocumentError> {
+    let format = query.format.as_deref().unwrap_or("json").to_ascii_lowercase();
+    if format != "json" && format != "csv" {
+        return Err(DocumentError::BadRequest(format!("Unsupported format '{}', expected json or csv", format)));
+    }
+
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let stored = state.db.get_document_tables(document.id).await.map_err(|e| {
+        error!("Failed to load tables of document {}: {}", document_id, e);
+        DocumentError::InternalServerError("Failed to load tables".to_string())
+    })?;
+    let extracted = stored.is_some();
+    let mut tables = stored.unwrap_or_default();
+    if let Some(index) = query.table {
+        if index >= tables.len() {
+            return Err(DocumentError::NotFound);
+        }
+        tables = vec![tables.swap_remove(index)];
+    }
+
+    if format == "csv" {
+        let (csv, filename) = match (query.table, tables.as_slice()) {
+            (Some(index), [table]) => (table_to_csv(table), format!("{}-table-{}.csv", document.id, index)),
+            _ => (tables_to_csv(&tables), format!("{}-tables.csv", document.id)),
+        };
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+            ],
+            csv,
+        )
+            .into_response());
+    }
+
+    Ok(Json(DocumentTablesResponse {
+        document_id: document.id,
+        extracted,
+        tables,
+    })
+    .into_response())
+}
@@ -1,17 +1,39 @@
 use serde::{Deserialize, Serialize};
 use utoipa::{ToSchema, IntoParams};
 
-#[derive(Deserialize, ToSchema, IntoParams)]
-pub struct PaginationQuery {
-    pub limit: Option<i64>,
-    pub offset: Option<i64>,
+#[derive(Default, Deserialize, ToSchema, IntoParams)]
+pub struct DocumentListQuery {
     pub ocr_status: Option<String>,
+    /// Only documents dated on or after this day (YYYY-MM-DD)
+    pub document_date_from: Option<chrono::NaiveDate>,
+    /// Only documents dated on or before this day (YYYY-MM-DD)
+    pub document_date_to: Option<chrono::NaiveDate>,
+    /// `created_at` (default, newest upload first) or `document_date` (newest document date first, undated last)
+    pub sort: Option<String>,
+    /// Also list archived documents, such as the originals of split scans
+    pub include_archived: Option<bool>,
 }
 
-#[derive(Deserialize, ToSchema, IntoParams)]
+impl DocumentListQuery {
+    pub fn into_filter(self) -> Result<crate::models::DocumentListFilter, axum::http::StatusCode> {
+        let sort_by_document_date = match self.sort.as_deref() {
+            None | Some("created_at") => false,
+            Some("document_date") => true,
+            Some(_) => return Err(axum::http::StatusCode::BAD_REQUEST),
+        };
+
+        Ok(crate::models::DocumentListFilter {
+            ocr_status: self.ocr_status,
+            document_date_from: self.document_date_from,
+            document_date_to: self.document_date_to,
+            sort_by_document_date,
+            include_archived: self.include_archived.unwrap_or(false),
+        })
+    }
+}
+
+#[derive(Default, Deserialize, ToSchema, IntoParams)]
 pub struct FailedDocumentsQuery {
-    pub limit: Option<i64>,
-    pub offset: Option<i64>,
     pub stage: Option<String>,  // 'ocr', 'ingestion', 'validation', etc.
     pub reason: Option<String>, // 'duplicate_content', 'low_ocr_confidence', etc.
 }
@@ -31,6 +53,88 @@ pub struct DeleteLowConfidenceRequest {
 pub struct RetryOcrRequest {
     pub language: Option<String>,
     pub languages: Option<Vec<String>>,
+    /// Reprocess even if the OCR text was manually corrected, discarding the correction
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct OnDemandOcrResponse {
+    /// OCR queue job processing the document
+    pub queue_item_id: uuid::Uuid,
+    pub document_id: uuid::Uuid,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct UpdateHandwritingRequest {
+    /// Route OCR of the document to the handwriting engine
+    pub handwriting: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct UpdateDocumentTextRequest {
+    /// Corrected text that replaces the document's OCR text
+    pub text: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct OcrPagesRequest {
+    /// Pages to OCR, e.g. `1-3,7,10-12` (1-based)
+    pub pages: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OcrPageText {
+    pub page: usize,
+    pub text: String,
+    pub confidence: f32,
+    pub word_count: usize,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OcrPagesResponse {
+    pub document_id: uuid::Uuid,
+    /// Total pages in the document
+    pub page_count: usize,
+    pub pages: Vec<OcrPageText>,
+    /// Text of all requested pages, separated by page breaks
+    pub text: String,
+    pub processing_time_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct OcrRegionRequest {
+    /// Bounding box relative to the page, from 0.0 to 1.0
+    pub region: crate::ocr::region::OcrRegion,
+    /// 1-based PDF page the region is on (default 1)
+    pub page: Option<usize>,
+    /// Field name echoed back, e.g. `invoice_total`
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OcrRegionResponse {
+    pub document_id: uuid::Uuid,
+    pub name: Option<String>,
+    /// PDF page the region was read from; omitted for images
+    pub page: Option<usize>,
+    pub region: crate::ocr::region::OcrRegion,
+    /// Size in pixels of the page image the region was cut from
+    pub image_width: u32,
+    pub image_height: u32,
+    pub text: String,
+    pub confidence: f32,
+    pub word_count: usize,
+    pub processing_time_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DocumentFieldsResponse {
+    pub document_id: uuid::Uuid,
+    /// Whether named regions have been read from the document
+    pub extracted: bool,
+    /// Fields ordered by label and name
+    pub fields: Vec<crate::ocr::region::ExtractedField>,
 }
 
 #[derive(Deserialize, Serialize, ToSchema)]
@@ -43,6 +147,85 @@ pub struct DocumentUploadResponse {
     pub message: String,
 }
 
+/// A single record in a bulk ingest request (one JSON object per line)
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ExternalIngestRecord {
+    pub filename: String,
+    /// Pre-extracted text to index for this document
+    pub text: Option<String>,
+    /// Name of the multipart part holding the pre-extracted text (alternative to `text`)
+    pub text_blob: Option<String>,
+    /// Name of the multipart part holding the original file; when omitted the text itself is stored
+    pub file_blob: Option<String>,
+    pub mime_type: Option<String>,
+    pub source_path: Option<String>,
+    pub original_created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub original_modified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExternalIngestRecordResult {
+    /// 1-based line number of the record in the JSON Lines payload
+    pub line: usize,
+    pub filename: Option<String>,
+    pub status: String, // 'created', 'duplicate', 'quarantined', 'corrupt', 'failed'
+    pub document_id: Option<uuid::Uuid>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExternalIngestResponse {
+    pub total: usize,
+    pub created: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+    pub results: Vec<ExternalIngestRecordResult>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct MoveDocumentRequest {
+    /// Logical folder to move the document into (e.g. "/Invoices/2024")
+    pub target_folder: Option<String>,
+    /// Source to associate the document with
+    pub target_source_id: Option<uuid::Uuid>,
+    /// Re-save the file through the current storage backend and update its storage path
+    #[serde(default)]
+    pub restore_file: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BulkMoveDocumentsRequest {
+    pub document_ids: Vec<uuid::Uuid>,
+    pub target_folder: Option<String>,
+    pub target_source_id: Option<uuid::Uuid>,
+    #[serde(default)]
+    pub restore_file: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MoveDocumentResponse {
+    pub document_id: uuid::Uuid,
+    pub source_path: Option<String>,
+    pub source_id: Option<uuid::Uuid>,
+    pub file_path: String,
+    pub file_restored: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MoveDocumentFailure {
+    pub document_id: uuid::Uuid,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkMoveDocumentsResponse {
+    pub moved_count: i64,
+    pub failed_count: i64,
+    pub moved_documents: Vec<MoveDocumentResponse>,
+    pub failed_documents: Vec<MoveDocumentFailure>,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct BulkDeleteResponse {
     pub deleted_count: i64,
@@ -71,37 +254,139 @@ pub struct DocumentDebugInfo {
     pub user_settings: Option<crate::models::SettingsResponse>,
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
-pub struct DocumentPaginationInfo {
-    pub total: i64,
-    pub limit: i64,
-    pub offset: i64,
-    pub has_more: bool,
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchDocumentsRequest {
+    /// Documents to fetch, at most 200; the response keeps this order
+    pub document_ids: Vec<uuid::Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchDocumentStatus {
+    Found,
+    /// The document doesn't exist or the user may not read it
+    NotFound,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchDocumentResult {
+    pub id: uuid::Uuid,
+    pub status: BatchDocumentStatus,
+    /// Set when the document was found
+    pub document: Option<crate::models::DocumentResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchDocumentsResponse {
+    /// One result per requested id, in request order
+    pub documents: Vec<BatchDocumentResult>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct PaginatedDocumentsResponse {
     pub documents: Vec<crate::models::DocumentResponse>,
-    pub pagination: DocumentPaginationInfo,
+    pub pagination: crate::routes::pagination::PaginationMeta,
 }
 
-impl Default for PaginationQuery {
-    fn default() -> Self {
-        Self {
-            limit: Some(25),
-            offset: Some(0),
-            ocr_status: None,
-        }
-    }
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct RelatedDocumentsQuery {
+    /// Maximum number of related documents to return (default 10, max 50)
+    pub limit: Option<i64>,
 }
 
-impl Default for FailedDocumentsQuery {
-    fn default() -> Self {
-        Self {
-            limit: Some(25),
-            offset: Some(0),
-            stage: None,
-            reason: None,
-        }
-    }
-}
\ No newline at end of file
+#[derive(Serialize, ToSchema)]
+pub struct RelatedDocumentsResponse {
+    pub document_id: uuid::Uuid,
+    pub related: Vec<crate::models::RelatedDocument>,
+}
+
+/// A document split out of a multi-document scan
+#[derive(Serialize, ToSchema)]
+pub struct DocumentSplitPart {
+    pub document_id: uuid::Uuid,
+    pub filename: String,
+    /// First page of the original scan in this part (1-based)
+    pub first_page: i32,
+    /// Last page of the original scan in this part (1-based)
+    pub last_page: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentSplitsResponse {
+    pub document_id: uuid::Uuid,
+    /// Set when the document is archived, e.g. because it was split
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The scan this document was split from, if any
+    pub split_from: Option<DocumentSplitPart>,
+    /// The documents this scan was split into, in page order
+    pub parts: Vec<DocumentSplitPart>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentArchiveEntry {
+    pub document_id: uuid::Uuid,
+    pub filename: String,
+    /// Path of the file inside the archive, through any nested archives
+    pub entry_path: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentArchiveEntriesResponse {
+    pub document_id: uuid::Uuid,
+    /// Set when the document is archived, e.g. because its files were extracted
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The archive this document was extracted from, if any
+    pub extracted_from: Option<uuid::Uuid>,
+    /// Path of this document inside that archive
+    pub entry_path: Option<String>,
+    /// The documents extracted from this archive, by path
+    pub entries: Vec<DocumentArchiveEntry>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct UpdateExpiryRequest {
+    /// Day the document expires (YYYY-MM-DD); null clears it
+    pub expiry_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct ExpiringDocumentsQuery {
+    /// Look this many days ahead; defaults to the user's `expiry_reminder_days`
+    pub days: Option<i32>,
+}
+
+/// A document expiring within the requested window
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ExpiringDocumentEntry {
+    pub document_id: uuid::Uuid,
+    pub filename: String,
+    pub expiry_date: chrono::NaiveDate,
+    /// 0 when the document expires today
+    pub days_until_expiry: i64,
+    /// Whether a reminder notification was sent
+    pub reminded: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ExpiringDocumentsResponse {
+    pub days: i32,
+    /// Soonest expiry first
+    pub documents: Vec<ExpiringDocumentEntry>,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct DocumentTablesQuery {
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+    /// Return only the table at this index (0-based), in extraction order
+    pub table: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DocumentTablesResponse {
+    pub document_id: uuid::Uuid,
+    /// Whether table extraction has run on the document
+    pub extracted: bool,
+    /// Tables in page order
+    pub tables: Vec<crate::ocr::table_extraction::ExtractedTable>,
+}
@@ -28,6 +28,8 @@ pub struct BulkOcrRetryRequest {
     pub priority_override: Option<i32>,
     /// Preview mode - just return what would be processed
     pub preview_only: Option<bool>,
+    /// Include documents with manually corrected OCR text, discarding the corrections
+    pub force: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
@@ -147,6 +149,21 @@ pub async fn bulk_retry_ocr(
             }
         }
     };
+
+    // Reprocessing would overwrite manual corrections unless explicitly forced
+    let documents = if request.force.unwrap_or(false) {
+        documents
+    } else {
+        let ids: Vec<Uuid> = documents.iter().map(|doc| doc.id).collect();
+        let corrected = state.db.get_manually_corrected_document_ids(&ids).await.map_err(|e| {
+            error!("Failed to check manual OCR corrections: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        if !corrected.is_empty() {
+            info!("Skipping {} manually corrected documents in bulk OCR retry", corrected.len());
+        }
+        documents.into_iter().filter(|doc| !corrected.contains(&doc.id)).collect()
+    };
     
     let matched_count = documents.len();
     crate::debug_log!("BULK_OCR_RETRY",
@@ -688,9 +705,13 @@ async fn reset_document_ocr_status(state: &Arc<AppState>, document_id: Uuid) ->
             ocr_failure_reason = NULL,
             ocr_retry_count = NULL,
             ocr_confidence = NULL,
+            ocr_confidence_raw = NULL,
             ocr_word_count = NULL,
             ocr_processing_time_ms = NULL,
             ocr_completed_at = NULL,
+            ocr_manually_corrected = FALSE,
+            ocr_original_text = NULL,
+            ocr_corrected_at = NULL,
             updated_at = NOW()
         WHERE id = $1
         "#
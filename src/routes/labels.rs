@@ -13,6 +13,9 @@ use chrono::{DateTime, Utc};
 use sqlx::{FromRow, Row};
 
 use crate::{auth::AuthUser, errors::label::LabelError, AppState};
+use crate::models::OCR_DPI_RANGE;
+use crate::ocr::language_detection::LANGUAGE_LABEL_PREFIX;
+use crate::ocr::region::NamedOcrRegion;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Label {
@@ -26,6 +29,11 @@ pub struct Label {
     pub is_system: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// OCR resolution for documents with this label, overriding the source's
+    /// and the owner's. With several such labels the highest DPI is used.
+    #[serde(default)]
+    #[sqlx(default)]
+    pub ocr_dpi: Option<i32>,
     #[serde(default)]
     pub document_count: i64,
     #[serde(default)]
@@ -40,6 +48,8 @@ pub struct CreateLabel {
     pub color: String,
     pub background_color: Option<String>,
     pub icon: Option<String>,
+    /// See [`Label::ocr_dpi`]
+    pub ocr_dpi: Option<i32>,
 }
 
 fn default_color() -> String {
@@ -53,6 +63,7 @@ pub struct UpdateLabel {
     pub color: Option<String>,
     pub background_color: Option<String>,
     pub icon: Option<String>,
+    pub ocr_dpi: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -64,6 +75,9 @@ pub struct LabelAssignment {
 pub struct LabelQuery {
     #[serde(default)]
     pub include_counts: bool,
+    /// Leave out the `lang:xx` system labels applied by language detection
+    #[serde(default)]
+    pub exclude_language: bool,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -78,6 +92,15 @@ fn default_bulk_mode() -> String {
     "replace".to_string()
 }
 
+/// Named regions read from every document with a label when it is OCR'd
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LabelOcrRegions {
+    pub regions: Vec<NamedOcrRegion>,
+}
+
+/// Most regions a label can have, each of which is OCR'd separately
+pub const MAX_OCR_REGIONS_PER_LABEL: usize = 50;
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_labels))
@@ -85,6 +108,8 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/{id}", get(get_label))
         .route("/{id}", put(update_label))
         .route("/{id}", delete(delete_label))
+        .route("/{id}/ocr-regions", get(get_label_ocr_regions))
+        .route("/{id}/ocr-regions", put(set_label_ocr_regions))
         .route("/documents/{document_id}", get(get_document_labels))
         .route("/documents/{document_id}", put(update_document_labels))
         .route("/documents/{document_id}/labels/{label_id}", post(add_document_label))
@@ -114,32 +139,38 @@ pub async fn get_labels(
             r#"
             SELECT 
                 l.id, l.user_id, l.name, l.description, l.color, 
-                l.background_color, l.icon, l.is_system, l.created_at, l.updated_at,
+                l.background_color, l.icon, l.is_system, l.created_at, l.updated_at, l.ocr_dpi,
                 COUNT(DISTINCT dl.document_id) as document_count,
                 COUNT(DISTINCT sl.source_id) as source_count
             FROM labels l
             LEFT JOIN document_labels dl ON l.id = dl.label_id
             LEFT JOIN source_labels sl ON l.id = sl.label_id
             WHERE (l.user_id = $1 OR l.is_system = TRUE)
+              AND NOT ($2 AND l.is_system AND starts_with(l.name, $3))
             GROUP BY l.id, l.user_id, l.name, l.description, l.color, 
-                     l.background_color, l.icon, l.is_system, l.created_at, l.updated_at
+                     l.background_color, l.icon, l.is_system, l.created_at, l.updated_at, l.ocr_dpi
             ORDER BY l.name
             "#
         )
         .bind(user_id)
+        .bind(query.exclude_language)
+        .bind(LANGUAGE_LABEL_PREFIX)
     } else {
         sqlx::query_as::<_, Label>(
             r#"
             SELECT 
                 id, user_id, name, description, color, 
-                background_color, icon, is_system, created_at, updated_at,
+                background_color, icon, is_system, created_at, updated_at, ocr_dpi,
                 0::bigint as document_count, 0::bigint as source_count
             FROM labels
             WHERE (user_id = $1 OR is_system = TRUE)
+              AND NOT ($2 AND is_system AND starts_with(name, $3))
             ORDER BY name
             "#
         )
         .bind(user_id)
+        .bind(query.exclude_language)
+        .bind(LANGUAGE_LABEL_PREFIX)
     }
     .fetch_all(state.db.get_pool())
     .await
@@ -191,13 +222,17 @@ pub async fn create_label(
         }
     }
 
+    if let Some(dpi) = payload.ocr_dpi.filter(|dpi| !OCR_DPI_RANGE.contains(dpi)) {
+        return Err(LabelError::InvalidOcrDpi { dpi });
+    }
+
     let label = sqlx::query_as::<_, Label>(
         r#"
-        INSERT INTO labels (user_id, name, description, color, background_color, icon)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO labels (user_id, name, description, color, background_color, icon, ocr_dpi)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING
             id, user_id, name, description, color, background_color, icon,
-            is_system, created_at, updated_at,
+            is_system, created_at, updated_at, ocr_dpi,
             0::bigint as document_count, 0::bigint as source_count
         "#
     )
@@ -207,6 +242,7 @@ pub async fn create_label(
     .bind(payload.color)
     .bind(payload.background_color)
     .bind(payload.icon)
+    .bind(payload.ocr_dpi)
     .fetch_one(state.db.get_pool())
     .await
     .map_err(|e| {
@@ -245,7 +281,7 @@ pub async fn get_label(
         r#"
         SELECT 
             l.id, l.user_id, l.name, l.description, l.color, 
-            l.background_color, l.icon, l.is_system, l.created_at, l.updated_at,
+            l.background_color, l.icon, l.is_system, l.created_at, l.updated_at, l.ocr_dpi,
             COUNT(DISTINCT dl.document_id) as document_count,
             COUNT(DISTINCT sl.source_id) as source_count
         FROM labels l
@@ -253,7 +289,7 @@ pub async fn get_label(
         LEFT JOIN source_labels sl ON l.id = sl.label_id
         WHERE l.id = $1 AND (l.user_id = $2 OR l.is_system = TRUE)
         GROUP BY l.id, l.user_id, l.name, l.description, l.color, 
-                 l.background_color, l.icon, l.is_system, l.created_at, l.updated_at
+                 l.background_color, l.icon, l.is_system, l.created_at, l.updated_at, l.ocr_dpi
         "#
     )
     .bind(label_id)
@@ -319,6 +355,10 @@ pub async fn update_label(
         }
     }
 
+    if let Some(dpi) = payload.ocr_dpi.filter(|dpi| !OCR_DPI_RANGE.contains(dpi)) {
+        return Err(LabelError::InvalidOcrDpi { dpi });
+    }
+
     // Check if label exists and user has permission
     let existing = sqlx::query(
         "SELECT id FROM labels WHERE id = $1 AND user_id = $2 AND is_system = FALSE"
@@ -346,11 +386,12 @@ pub async fn update_label(
             color = COALESCE($4, color),
             background_color = COALESCE($5, background_color),
             icon = COALESCE($6, icon),
+            ocr_dpi = COALESCE($7, ocr_dpi),
             updated_at = CURRENT_TIMESTAMP
         WHERE id = $1
         RETURNING
             id, user_id, name, description, color, background_color, icon,
-            is_system, created_at, updated_at,
+            is_system, created_at, updated_at, ocr_dpi,
             0::bigint as document_count, 0::bigint as source_count
         "#
     )
@@ -360,6 +401,7 @@ pub async fn update_label(
     .bind(payload.color)
     .bind(payload.background_color)
     .bind(payload.icon)
+    .bind(payload.ocr_dpi)
     .fetch_one(state.db.get_pool())
     .await
     .map_err(|e| {
@@ -413,6 +455,113 @@ pub async fn delete_label(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/labels/{id}/ocr-regions",
+    tag = "labels",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Label ID")
+    ),
+    responses(
+        (status = 200, description = "Named OCR regions of the label", body = LabelOcrRegions),
+        (status = 404, description = "Label not found"),
+    )
+)]
+pub async fn get_label_ocr_regions(
+    Path(label_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<Json<LabelOcrRegions>, LabelError> {
+    let visible = sqlx::query("SELECT id FROM labels WHERE id = $1 AND (user_id = $2 OR is_system = TRUE)")
+        .bind(label_id)
+        .bind(auth_user.user.id)
+        .fetch_optional(state.db.get_pool())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to check label existence: {}", e);
+            LabelError::InternalError { message: e.to_string() }
+        })?;
+    if visible.is_none() {
+        return Err(LabelError::NotFound);
+    }
+
+    let regions = state.db.get_label_ocr_regions(label_id).await.map_err(|e| {
+        tracing::error!("Failed to fetch OCR regions of label {}: {}", label_id, e);
+        LabelError::InternalError { message: e.to_string() }
+    })?;
+
+    Ok(Json(LabelOcrRegions { regions }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/labels/{id}/ocr-regions",
+    tag = "labels",
+    description = "Replaces the named regions read from documents with this label when they are OCR'd. The text of each region is stored as a field of the document.",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Label ID")
+    ),
+    request_body = LabelOcrRegions,
+    responses(
+        (status = 200, description = "Named OCR regions saved", body = LabelOcrRegions),
+        (status = 404, description = "Label not found"),
+        (status = 400, description = "Invalid, repeated or too many regions"),
+    )
+)]
+pub async fn set_label_ocr_regions(
+    Path(label_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(payload): Json<LabelOcrRegions>,
+) -> Result<Json<LabelOcrRegions>, LabelError> {
+    if payload.regions.len() > MAX_OCR_REGIONS_PER_LABEL {
+        return Err(LabelError::InvalidOcrRegion {
+            name: payload.regions[MAX_OCR_REGIONS_PER_LABEL].name.clone(),
+            reason: format!("a label can have at most {} regions", MAX_OCR_REGIONS_PER_LABEL),
+        });
+    }
+    let mut names = std::collections::HashSet::new();
+    for region in &payload.regions {
+        region.validate().map_err(|e| LabelError::InvalidOcrRegion {
+            name: region.name.clone(),
+            reason: e.to_string(),
+        })?;
+        if !names.insert(region.name.trim()) {
+            return Err(LabelError::InvalidOcrRegion {
+                name: region.name.clone(),
+                reason: "the name is used twice".to_string(),
+            });
+        }
+    }
+
+    // Only the owner's own labels have regions; system labels are shared
+    let owned = sqlx::query("SELECT id FROM labels WHERE id = $1 AND user_id = $2 AND is_system = FALSE")
+        .bind(label_id)
+        .bind(auth_user.user.id)
+        .fetch_optional(state.db.get_pool())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to check label existence: {}", e);
+            LabelError::InternalError { message: e.to_string() }
+        })?;
+    if owned.is_none() {
+        return Err(LabelError::NotFound);
+    }
+
+    state.db.set_label_ocr_regions(label_id, &payload.regions).await.map_err(|e| {
+        tracing::error!("Failed to save OCR regions of label {}: {}", label_id, e);
+        LabelError::InternalError { message: e.to_string() }
+    })?;
+    let regions = state.db.get_label_ocr_regions(label_id).await.map_err(|e| {
+        tracing::error!("Failed to fetch OCR regions of label {}: {}", label_id, e);
+        LabelError::InternalError { message: e.to_string() }
+    })?;
+
+    Ok(Json(LabelOcrRegions { regions }))
+}
+
 #[utoipa::path(
     get,
     path = "/api/labels/documents/{document_id}",
@@ -454,7 +603,7 @@ pub async fn get_document_labels(
         r#"
         SELECT 
             l.id, l.user_id, l.name, l.description, l.color, 
-            l.background_color, l.icon, l.is_system, l.created_at, l.updated_at,
+            l.background_color, l.icon, l.is_system, l.created_at, l.updated_at, l.ocr_dpi,
             0::bigint as document_count, 0::bigint as source_count
         FROM labels l
         INNER JOIN document_labels dl ON l.id = dl.label_id
@@ -1,15 +1,31 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     response::Json,
     routing::get,
     Router,
 };
-use std::sync::Arc;
-use serde::Serialize;
-use utoipa::ToSchema;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 
-use crate::{auth::AuthUser, AppState, models::UserRole};
+use crate::{auth::AuthUser, AppState, models::{LibraryStats, UserRole}};
+use crate::ocr::queue::{OcrStatsGranularity, OcrTimeseriesBucket};
+
+/// Library stats scan every document the user can see, so dashboards polling
+/// the endpoint are served from a short-lived per-user cache
+const LIBRARY_STATS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Most buckets one OCR timeseries request may cover
+pub const MAX_OCR_TIMESERIES_BUCKETS: i64 = 2000;
+
+static LIBRARY_STATS_CACHE: Lazy<Mutex<HashMap<Uuid, (Instant, LibraryStats)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 fn require_admin(auth_user: &AuthUser) -> Result<(), StatusCode> {
     if auth_user.user.role != UserRole::Admin {
@@ -71,9 +87,30 @@ pub struct GeneralSystemMetrics {
     pub rust_version: String,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OcrTimeseriesQuery {
+    /// Bucket width, `hour` (default) or `day`
+    pub granularity: Option<OcrStatsGranularity>,
+    /// Start of the window; defaults to 24 hours (hourly) or 30 days (daily) before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// End of the window (exclusive); defaults to now
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OcrTimeseriesResponse {
+    pub granularity: OcrStatsGranularity,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// One entry per bucket in the window, oldest first
+    pub buckets: Vec<OcrTimeseriesBucket>,
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_system_metrics))
+        .route("/library", get(get_library_stats))
+        .route("/ocr/timeseries", get(get_ocr_timeseries))
 }
 
 #[utoipa::path(
@@ -118,6 +155,93 @@ pub async fn get_system_metrics(
     Ok(Json(metrics))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/metrics/library",
+    tag = "metrics",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Document counts, storage and OCR statistics for the user's library (the whole library for admins)", body = LibraryStats),
+        (status = 401, description = "Unauthorized - valid authentication required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_library_stats(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<Json<LibraryStats>, StatusCode> {
+    let user_id = auth_user.user.id;
+
+    if let Some((cached_at, stats)) = LIBRARY_STATS_CACHE.lock().unwrap().get(&user_id) {
+        if cached_at.elapsed() < LIBRARY_STATS_CACHE_TTL {
+            return Ok(Json(stats.clone()));
+        }
+    }
+
+    let stats = state
+        .db
+        .get_library_stats(user_id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to compute library stats: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut cache = LIBRARY_STATS_CACHE.lock().unwrap();
+    cache.retain(|_, (cached_at, _)| cached_at.elapsed() < LIBRARY_STATS_CACHE_TTL);
+    cache.insert(user_id, (Instant::now(), stats.clone()));
+
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/metrics/ocr/timeseries",
+    tag = "metrics",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(OcrTimeseriesQuery),
+    responses(
+        (status = 200, description = "OCR throughput, processing time and failure rate per hour or day", body = OcrTimeseriesResponse),
+        (status = 400, description = "Window is empty or spans too many buckets"),
+        (status = 401, description = "Unauthorized - valid authentication required"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_ocr_timeseries(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<OcrTimeseriesQuery>,
+) -> Result<Json<OcrTimeseriesResponse>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let granularity = query.granularity.unwrap_or_default();
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - granularity.default_window());
+    if from >= to {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let bucket_seconds = granularity.bucket_duration().num_seconds();
+    if (to - from).num_seconds() / bucket_seconds >= MAX_OCR_TIMESERIES_BUCKETS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let buckets = state
+        .queue_service
+        .get_processing_timeseries(granularity, from, to)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to compute OCR timeseries: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(OcrTimeseriesResponse { granularity, from, to, buckets }))
+}
+
 async fn collect_database_metrics(state: &Arc<AppState>) -> Result<DatabaseMetrics, StatusCode> {
     // Get connection pool information
     let _pool_info = state.db.pool.options();
@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod credential_keys;
 pub mod documents;
 pub mod documents_ocr_retry;
 pub mod ignored_files;
@@ -6,12 +7,14 @@ pub mod labels;
 pub mod metrics;
 pub mod notifications;
 pub mod ocr;
+pub mod pagination;
 pub mod prometheus_metrics;
 pub mod queue;
 pub mod search;
 pub mod settings;
 pub mod source_errors;
 pub mod sources;
+pub mod thumbnails;
 pub mod users;
 pub mod webdav;
 pub mod webdav_scan_failures;
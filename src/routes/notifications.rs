@@ -1,23 +1,24 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     http::StatusCode,
     routing::{get, post, delete},
     Json, Router,
 };
-use serde::Deserialize;
+use serde::Serialize;
 use std::sync::Arc;
 use utoipa::ToSchema;
 
 use crate::{
     auth::AuthUser,
     models::{Notification, NotificationSummary},
+    routes::pagination::{Pagination, PaginationMeta, PaginationParams},
     AppState,
 };
 
-#[derive(Deserialize, ToSchema)]
-struct PaginationQuery {
-    limit: Option<i64>,
-    offset: Option<i64>,
+#[derive(Serialize, ToSchema)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<Notification>,
+    pub pagination: PaginationMeta,
 }
 
 pub fn router() -> Router<Arc<AppState>> {
@@ -36,12 +37,10 @@ pub fn router() -> Router<Arc<AppState>> {
     security(
         ("bearer_auth" = [])
     ),
-    params(
-        ("limit" = Option<i64>, Query, description = "Number of notifications to return (default: 25)"),
-        ("offset" = Option<i64>, Query, description = "Number of notifications to skip (default: 0)")
-    ),
+    params(PaginationParams),
     responses(
-        (status = 200, description = "List of user notifications", body = Vec<Notification>),
+        (status = 200, description = "Paginated list of user notifications", body = NotificationListResponse),
+        (status = 400, description = "Invalid pagination parameters"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -49,18 +48,24 @@ pub fn router() -> Router<Arc<AppState>> {
 async fn get_notifications(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(pagination): Query<PaginationQuery>,
-) -> Result<Json<Vec<Notification>>, StatusCode> {
-    let limit = pagination.limit.unwrap_or(25);
-    let offset = pagination.offset.unwrap_or(0);
-    
+    pagination: Pagination,
+) -> Result<Json<NotificationListResponse>, StatusCode> {
     let notifications = state
         .db
-        .get_user_notifications(auth_user.user.id, limit, offset)
+        .get_user_notifications(auth_user.user.id, pagination.limit, pagination.offset)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    
-    Ok(Json(notifications))
+
+    let total = state
+        .db
+        .count_user_notifications(auth_user.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(NotificationListResponse {
+        notifications,
+        pagination: pagination.meta(total),
+    }))
 }
 
 #[utoipa::path(
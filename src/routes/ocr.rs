@@ -1,20 +1,32 @@
 use axum::{
-    extract::State,
+    extract::{Multipart, State},
     http::StatusCode,
     response::Json,
     routing::get,
     Router,
 };
+use base64ct::Encoding;
 use serde::Serialize;
 use std::sync::Arc;
+use tracing::{error, info, warn};
 use utoipa::ToSchema;
 
 use crate::{
     auth::AuthUser,
-    ocr::health::OcrHealthChecker,
+    models::{UpdateSettings, UserRole},
+    ocr::{
+        api::OcrErrorResponse,
+        enhanced::EnhancedOcrService,
+        error::OcrError,
+        fallback_stats::{fallback_stats, FallbackStatsResponse},
+        health::OcrHealthChecker,
+    },
     AppState,
 };
 
+/// Largest image accepted by the OCR settings preview
+const MAX_PREVIEW_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
 #[derive(Serialize, ToSchema)]
 pub struct AvailableLanguagesResponse {
     pub available_languages: Vec<LanguageInfo>,
@@ -28,11 +40,225 @@ pub struct LanguageInfo {
     pub installed: bool,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct OcrPreviewResponse {
+    pub text: String,
+    pub confidence: f32,
+    pub word_count: usize,
+    pub processing_time_ms: u64,
+    pub preprocessing_applied: Vec<String>,
+    /// Tesseract page segmentation mode used
+    pub page_segmentation_mode: Option<i32>,
+    /// `applied` or `reverted` when orientation detection proposed a rotation
+    pub orientation_correction: Option<String>,
+    /// Preprocessed image as base64 PNG; only when requested and preprocessing changed the image
+    pub processed_image_png_base64: Option<String>,
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/health", get(crate::ocr::api::health_check))
         .route("/perform", axum::routing::post(crate::ocr::api::perform_ocr))
         .route("/languages", get(get_available_languages))
+        .route("/preview", axum::routing::post(preview_ocr_settings))
+        .route("/fallback-stats", get(get_fallback_stats))
+        .route("/fallback-stats/reset", axum::routing::post(reset_fallback_stats))
+}
+
+/// Successes, failures and average time of each Office text extraction method
+/// since the server started or the counts were last reset
+#[utoipa::path(
+    get,
+    path = "/api/ocr/fallback-stats",
+    tag = "ocr",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Extraction method statistics", body = FallbackStatsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+async fn get_fallback_stats(auth_user: AuthUser) -> Result<Json<FallbackStatsResponse>, StatusCode> {
+    if auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(Json(fallback_stats().snapshot()))
+}
+
+/// Clears the Office text extraction statistics, for instance after installing
+/// or removing an extraction tool
+#[utoipa::path(
+    post,
+    path = "/api/ocr/fallback-stats/reset",
+    tag = "ocr",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Statistics cleared", body = FallbackStatsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin access required")
+    )
+)]
+async fn reset_fallback_stats(auth_user: AuthUser) -> Result<Json<FallbackStatsResponse>, StatusCode> {
+    if auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    fallback_stats().reset();
+    info!(user_id = %auth_user.user.id, "Office extraction statistics reset");
+    Ok(Json(fallback_stats().snapshot()))
+}
+
+fn preview_error(status: StatusCode, error_code: &str, error: String) -> (StatusCode, Json<OcrErrorResponse>) {
+    (
+        status,
+        Json(OcrErrorResponse {
+            error,
+            error_code: error_code.to_string(),
+            details: None,
+            is_recoverable: false,
+        }),
+    )
+}
+
+/// Runs preprocessing and OCR on a sample image with the user's settings plus any
+/// overrides, without storing anything, so settings can be tuned before saving them
+#[utoipa::path(
+    post,
+    path = "/api/ocr/preview",
+    tag = "ocr",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(
+        content = String,
+        description = "Multipart form: `file` (image, required), `settings` (JSON with the same fields as the settings update; only OCR fields are used), `include_processed_image` (`true` to return the preprocessed image)",
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = 200, description = "Text extracted with the previewed settings", body = OcrPreviewResponse),
+        (status = 400, description = "Missing image, invalid settings or unavailable language", body = OcrErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 413, description = "Image too large", body = OcrErrorResponse),
+        (status = 422, description = "Not a supported image", body = OcrErrorResponse),
+        (status = 429, description = "Too many previews", body = OcrErrorResponse),
+        (status = 500, description = "OCR failed", body = OcrErrorResponse),
+        (status = 503, description = "Tesseract unavailable", body = OcrErrorResponse)
+    )
+)]
+async fn preview_ocr_settings(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    mut multipart: Multipart,
+) -> Result<Json<OcrPreviewResponse>, (StatusCode, Json<OcrErrorResponse>)> {
+    let user_id = auth_user.user.id;
+
+    // Previews are CPU-heavy, so limit them before reading the upload
+    if let Err(retry_after) = state.rate_limiters.ocr_preview.check(&user_id).await {
+        warn!(user_id = %user_id, "Rate limited OCR preview");
+        return Err(preview_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "OCR_PREVIEW_RATE_LIMITED",
+            format!("Too many OCR previews; try again in {} seconds", retry_after),
+        ));
+    }
+
+    let mut image_data = None;
+    let mut overrides = None;
+    let mut include_processed_image = false;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        preview_error(e.status(), "OCR_PREVIEW_BAD_REQUEST", format!("Failed to read multipart field: {}", e))
+    })? {
+        let read_error = |e: axum::extract::multipart::MultipartError| {
+            preview_error(e.status(), "OCR_PREVIEW_BAD_REQUEST", format!("Failed to read multipart field: {}", e))
+        };
+        match field.name().unwrap_or("") {
+            "file" => {
+                let data = field.bytes().await.map_err(read_error)?;
+                if data.len() > MAX_PREVIEW_IMAGE_BYTES {
+                    return Err(preview_error(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        "OCR_IMAGE_TOO_LARGE",
+                        format!("Preview images are limited to {} bytes, got {}", MAX_PREVIEW_IMAGE_BYTES, data.len()),
+                    ));
+                }
+                image_data = Some(data);
+            }
+            "settings" => {
+                let json = field.text().await.map_err(read_error)?;
+                let parsed: UpdateSettings = serde_json::from_str(&json).map_err(|e| {
+                    preview_error(StatusCode::BAD_REQUEST, "OCR_PREVIEW_INVALID_SETTINGS", format!("Invalid settings: {}", e))
+                })?;
+                overrides = Some(parsed);
+            }
+            "include_processed_image" => {
+                include_processed_image = field.text().await.map_err(read_error)?.trim().eq_ignore_ascii_case("true");
+            }
+            _ => {}
+        }
+    }
+
+    let image_data = image_data.ok_or_else(|| {
+        preview_error(StatusCode::BAD_REQUEST, "OCR_PREVIEW_BAD_REQUEST", "No image found in the 'file' field".to_string())
+    })?;
+
+    let current = state
+        .db
+        .get_user_settings(user_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load settings for OCR preview: {}", e);
+            preview_error(StatusCode::INTERNAL_SERVER_ERROR, "OCR_PREVIEW_FAILED", "Failed to load settings".to_string())
+        })?
+        .unwrap_or_default();
+    let settings = match &overrides {
+        Some(overrides) => current.with_ocr_overrides(overrides),
+        None => current,
+    };
+
+    OcrHealthChecker::new().validate_language_combination(&settings.ocr_language).map_err(|e| {
+        let status = match e {
+            OcrError::LanguageDataNotFound { .. } => StatusCode::BAD_REQUEST,
+            _ => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        preview_error(status, e.error_code(), format!("OCR language '{}' is not available: {}", settings.ocr_language, e))
+    })?;
+
+    let ocr_service = EnhancedOcrService::new(
+        crate::ocr::temp_files::temp_dir(),
+        (*state.file_service).clone(),
+        state.config.max_pdf_size_mb,
+        state.config.max_office_document_size_mb,
+        state.config.ocr_timeout_seconds,
+    );
+    let (result, processed_image) = ocr_service
+        .preview_image_ocr(&image_data, &settings, include_processed_image)
+        .await
+        .map_err(|e| match e.downcast_ref::<OcrError>() {
+            Some(ocr_error @ OcrError::ImageTooLarge { .. }) => {
+                preview_error(StatusCode::PAYLOAD_TOO_LARGE, ocr_error.error_code(), ocr_error.to_string())
+            }
+            Some(ocr_error @ OcrError::InvalidImageFormat { .. }) => {
+                preview_error(StatusCode::UNPROCESSABLE_ENTITY, ocr_error.error_code(), ocr_error.to_string())
+            }
+            _ => {
+                error!("OCR preview failed for user {}: {}", user_id, e);
+                preview_error(StatusCode::INTERNAL_SERVER_ERROR, "OCR_PREVIEW_FAILED", format!("OCR preview failed: {}", e))
+            }
+        })?;
+
+    Ok(Json(OcrPreviewResponse {
+        text: result.text,
+        confidence: result.confidence,
+        word_count: result.word_count,
+        processing_time_ms: result.processing_time_ms,
+        preprocessing_applied: result.preprocessing_applied,
+        page_segmentation_mode: result.page_segmentation_mode,
+        orientation_correction: result.orientation_correction.map(|correction| correction.as_str().to_string()),
+        processed_image_png_base64: processed_image.map(|png| base64ct::Base64::encode_string(&png)),
+    }))
 }
 
 #[utoipa::path(
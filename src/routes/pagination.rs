@@ -0,0 +1,211 @@
+//! Shared pagination contract for list endpoints
+//!
+//! Clients page either with `page`/`per_page` (1-based pages) or with
+//! `limit`/`offset`. Both forms resolve to the same [`Pagination`], and list
+//! responses report it back through [`PaginationMeta`]. The default and maximum
+//! page sizes come from `PAGINATION_DEFAULT_PER_PAGE` / `PAGINATION_MAX_PER_PAGE`;
+//! out-of-range values are rejected with 400 unless `PAGINATION_CLAMP_OUT_OF_RANGE`
+//! is set, in which case they are clamped and a warning is logged.
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{config::Config, AppState};
+
+/// Page size limits applied by the [`Pagination`] extractor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationSettings {
+    pub default_per_page: i64,
+    pub max_per_page: i64,
+    /// Clamp out-of-range values instead of rejecting the request
+    pub clamp_out_of_range: bool,
+}
+
+impl PaginationSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            default_per_page: config.pagination_default_per_page,
+            max_per_page: config.pagination_max_per_page,
+            clamp_out_of_range: config.pagination_clamp_out_of_range,
+        }
+    }
+}
+
+impl Default for PaginationSettings {
+    fn default() -> Self {
+        Self {
+            default_per_page: 25,
+            max_per_page: 100,
+            clamp_out_of_range: false,
+        }
+    }
+}
+
+/// Raw pagination query parameters
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PaginationParams {
+    /// Page number, starting at 1 (use instead of `offset`)
+    pub page: Option<i64>,
+    /// Items per page (alias: `limit`)
+    pub per_page: Option<i64>,
+    /// Items per page (alias: `per_page`)
+    pub limit: Option<i64>,
+    /// Number of items to skip (use instead of `page`)
+    pub offset: Option<i64>,
+}
+
+impl PaginationParams {
+    /// Whether no pagination parameter was given
+    pub fn is_empty(&self) -> bool {
+        self.page.is_none() && self.per_page.is_none() && self.limit.is_none() && self.offset.is_none()
+    }
+}
+
+/// A validated page request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Pagination {
+    /// Resolve raw parameters against the configured limits
+    pub fn from_params(params: &PaginationParams, settings: &PaginationSettings) -> Result<Self, PaginationError> {
+        if params.page.is_some() && params.offset.is_some() {
+            return Err(PaginationError("Use either 'page' or 'offset', not both".to_string()));
+        }
+
+        let per_page = match (params.per_page, params.limit) {
+            (Some(per_page), Some(limit)) if per_page != limit => {
+                return Err(PaginationError("'per_page' and 'limit' disagree".to_string()));
+            }
+            (per_page, limit) => per_page.or(limit).unwrap_or(settings.default_per_page.min(settings.max_per_page)),
+        };
+
+        let per_page = if (1..=settings.max_per_page).contains(&per_page) {
+            per_page
+        } else if settings.clamp_out_of_range {
+            let clamped = per_page.clamp(1, settings.max_per_page);
+            warn!("Page size {} is out of range, clamping to {}", per_page, clamped);
+            clamped
+        } else {
+            return Err(PaginationError(format!(
+                "Page size must be between 1 and {}, got {}",
+                settings.max_per_page, per_page
+            )));
+        };
+
+        let offset = match (params.page, params.offset) {
+            (Some(page), _) if page < 1 => {
+                if !settings.clamp_out_of_range {
+                    return Err(PaginationError(format!("'page' must be at least 1, got {}", page)));
+                }
+                warn!("Page {} is out of range, clamping to 1", page);
+                0
+            }
+            (Some(page), _) => (page - 1).saturating_mul(per_page),
+            (None, Some(offset)) if offset < 0 => {
+                if !settings.clamp_out_of_range {
+                    return Err(PaginationError(format!("'offset' must not be negative, got {}", offset)));
+                }
+                warn!("Offset {} is out of range, clamping to 0", offset);
+                0
+            }
+            (None, offset) => offset.unwrap_or(0),
+        };
+
+        Ok(Self { limit: per_page, offset })
+    }
+
+    /// 1-based page containing `offset`
+    pub fn page(&self) -> i64 {
+        self.offset / self.limit + 1
+    }
+
+    /// Pagination block for a response listing `total` items overall
+    pub fn meta(&self, total: i64) -> PaginationMeta {
+        PaginationMeta {
+            total,
+            page: self.page(),
+            per_page: self.limit,
+            total_pages: (total + self.limit - 1) / self.limit,
+            limit: self.limit,
+            offset: self.offset,
+            has_more: self.offset + self.limit < total,
+        }
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for Pagination {
+    type Rejection = PaginationError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let Query(params) = Query::<PaginationParams>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| PaginationError(format!("Invalid pagination parameters: {}", e.body_text())))?;
+
+        Self::from_params(&params, &PaginationSettings::from_config(&state.config))
+    }
+}
+
+/// A page request, or `None` when no pagination parameter is sent, for list
+/// endpoints that return every item unless asked for a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionalPagination(pub Option<Pagination>);
+
+impl FromRequestParts<Arc<AppState>> for OptionalPagination {
+    type Rejection = PaginationError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let Query(params) = Query::<PaginationParams>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| PaginationError(format!("Invalid pagination parameters: {}", e.body_text())))?;
+
+        if params.is_empty() {
+            return Ok(Self(None));
+        }
+        Pagination::from_params(&params, &PaginationSettings::from_config(&state.config)).map(|pagination| Self(Some(pagination)))
+    }
+}
+
+/// Pagination block included in every paginated list response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PaginationMeta {
+    /// Total number of items across all pages
+    pub total: i64,
+    /// Current page, starting at 1
+    pub page: i64,
+    /// Items per page
+    pub per_page: i64,
+    /// Number of pages needed for `total` items
+    pub total_pages: i64,
+    /// Same as `per_page`, for offset-based clients
+    pub limit: i64,
+    /// Number of items skipped
+    pub offset: i64,
+    /// Whether more items follow this page
+    pub has_more: bool,
+}
+
+/// Rejection for invalid pagination parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaginationError(pub String);
+
+impl IntoResponse for PaginationError {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(json!({
+            "error": self.0,
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "error_code": "INVALID_PAGINATION",
+        }))).into_response()
+    }
+}
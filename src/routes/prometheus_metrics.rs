@@ -246,6 +246,33 @@ pub async fn get_prometheus_metrics(
     writeln!(&mut output, "# HELP readur_webdav_error_rate_last_hour WebDAV error rate in the last hour (percentage)").unwrap();
     writeln!(&mut output, "# TYPE readur_webdav_error_rate_last_hour gauge").unwrap();
     writeln!(&mut output, "readur_webdav_error_rate_last_hour {} {}", webdav_metrics.error_rate_last_hour, timestamp).unwrap();
+
+    // Source sync ingestion pipeline metrics
+    let stage_metrics = crate::ingestion::pipeline::pipeline_stage_metrics();
+
+    writeln!(&mut output, "# HELP readur_ingestion_stage_items_total Files that completed a source sync ingestion stage").unwrap();
+    writeln!(&mut output, "# TYPE readur_ingestion_stage_items_total counter").unwrap();
+    for stage in &stage_metrics {
+        writeln!(&mut output, "readur_ingestion_stage_items_total{{stage=\"{}\"}} {} {}", stage.stage.as_str(), stage.processed, timestamp).unwrap();
+    }
+
+    writeln!(&mut output, "# HELP readur_ingestion_stage_failures_total Failures in a source sync ingestion stage").unwrap();
+    writeln!(&mut output, "# TYPE readur_ingestion_stage_failures_total counter").unwrap();
+    for stage in &stage_metrics {
+        writeln!(&mut output, "readur_ingestion_stage_failures_total{{stage=\"{}\"}} {} {}", stage.stage.as_str(), stage.failed, timestamp).unwrap();
+    }
+
+    writeln!(&mut output, "# HELP readur_ingestion_stage_queue_depth Files waiting for a source sync ingestion stage").unwrap();
+    writeln!(&mut output, "# TYPE readur_ingestion_stage_queue_depth gauge").unwrap();
+    for stage in &stage_metrics {
+        writeln!(&mut output, "readur_ingestion_stage_queue_depth{{stage=\"{}\"}} {} {}", stage.stage.as_str(), stage.queue_depth, timestamp).unwrap();
+    }
+
+    writeln!(&mut output, "# HELP readur_ingestion_stage_in_flight Files a source sync ingestion stage is working on").unwrap();
+    writeln!(&mut output, "# TYPE readur_ingestion_stage_in_flight gauge").unwrap();
+    for stage in &stage_metrics {
+        writeln!(&mut output, "readur_ingestion_stage_in_flight{{stage=\"{}\"}} {} {}", stage.stage.as_str(), stage.in_flight, timestamp).unwrap();
+    }
     
     // Return the metrics with the correct content type
     Ok((
@@ -397,7 +424,7 @@ async fn collect_ocr_metrics(state: &Arc<AppState>) -> Result<OcrMetrics, Status
     
     // Get additional OCR metrics
     let stuck_jobs = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM documents WHERE ocr_status = 'processing' AND updated_at < NOW() - INTERVAL '30 minutes'"
+        "SELECT COUNT(*) FROM documents WHERE ocr_status IN ('processing', 'preview') AND updated_at < NOW() - INTERVAL '30 minutes'"
     )
     .fetch_one(&state.db.pool)
     .await
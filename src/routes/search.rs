@@ -1,24 +1,70 @@
 use axum::{
+    body::{Body, Bytes},
     extract::{Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
     routing::get,
     Router,
 };
+use futures::{stream, StreamExt};
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 use crate::{
     auth::AuthUser,
-    errors::search::SearchError,
-    models::{SearchRequest, SearchResponse, EnhancedDocumentResponse, SearchFacetsResponse},
+    errors::{search::SearchError, AppError},
+    models::{SearchRequest, SearchResponse, EnhancedDocumentResponse, SearchFacetsResponse, Settings, SearchExportQuery, SearchExportColumn, SearchExportRow},
+    utils::{csv, text_search::is_supported_search_language},
     AppState,
 };
 
+/// Rows buffered between the database and a slow export download
+const EXPORT_BUFFER_ROWS: usize = 256;
+
+/// Fills in what a search leaves open from the user's settings: whether it
+/// matches their notes (`search_include_notes`), ignores accents
+/// (`search_ignore_accents`), which language it stems for (`search_language`)
+/// and whether it collapses duplicates (`search_collapse_duplicates`)
+async fn resolve_search_defaults(state: &AppState, user_id: uuid::Uuid, search_request: &mut SearchRequest) {
+    if search_request.include_notes.is_some()
+        && search_request.ignore_accents.is_some()
+        && search_request.language.is_some()
+        && search_request.collapse_duplicates.is_some()
+    {
+        return;
+    }
+
+    let settings = match state.db.get_user_settings(user_id).await {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Failed to load settings of user {}, searching with defaults: {}", user_id, e);
+            Settings::default()
+        }
+    };
+    search_request.include_notes.get_or_insert(settings.search_include_notes);
+    search_request.ignore_accents.get_or_insert(settings.search_ignore_accents);
+    search_request.language.get_or_insert(settings.search_language);
+    search_request.collapse_duplicates.get_or_insert(settings.search_collapse_duplicates);
+}
+
+/// Keeps the requested page within the first `max_results` results, the most a
+/// search can be paged through. Pages starting past them are refused.
+fn limit_to_max_results(search_request: &mut SearchRequest, max_results: i64) -> Result<(), SearchError> {
+    let offset = search_request.offset.unwrap_or(0);
+    if offset >= max_results {
+        return Err(SearchError::too_many_results(offset + 1, max_results));
+    }
+    let limit = search_request.limit.unwrap_or(25);
+    search_request.limit = Some(limit.min(max_results - offset));
+    Ok(())
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(search_documents))
         .route("/enhanced", get(enhanced_search_documents))
         .route("/facets", get(get_search_facets))
+        .route("/export", get(export_search_results))
 }
 
 #[utoipa::path(
@@ -41,7 +87,7 @@ pub fn router() -> Router<Arc<AppState>> {
 async fn search_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(search_request): Query<SearchRequest>,
+    Query(mut search_request): Query<SearchRequest>,
 ) -> Result<Json<SearchResponse>, SearchError> {
     // Validate query length (allow empty query if filters are present)
     let has_filters = search_request.tags.as_ref().map_or(false, |t| !t.is_empty())
@@ -60,26 +106,29 @@ async fn search_documents(
         return Err(SearchError::invalid_pagination(offset, limit));
     }
     
+    if let Some(language) = search_request.language.as_deref() {
+        if !is_supported_search_language(language) {
+            return Err(SearchError::invalid_language(language));
+        }
+    }
+    resolve_search_defaults(&state, auth_user.user.id, &mut search_request).await;
+    limit_to_max_results(&mut search_request, state.config.search_max_results)?;
+
     // Get total count (without pagination) for proper pagination support
-    let total = state
+    let count = state
         .db
-        .count_search_documents(auth_user.user.id, auth_user.user.role.clone(), &search_request)
+        .count_search_documents_capped(auth_user.user.id, auth_user.user.role, &search_request, state.config.search_max_results)
         .await
         .map_err(|e| SearchError::index_unavailable(format!("Count failed: {}", e)))?;
 
-    // Check if too many results
-    if total > 10000 {
-        return Err(SearchError::too_many_results(total, 10000));
-    }
-
     let documents = state
         .db
-        .search_documents(auth_user.user.id, &search_request)
+        .search_documents_with_duplicates(auth_user.user.id, &search_request)
         .await
         .map_err(|e| SearchError::index_unavailable(format!("Search failed: {}", e)))?;
 
     let response = SearchResponse {
-        documents: documents.into_iter().map(|doc| EnhancedDocumentResponse {
+        documents: documents.into_iter().map(|(doc, duplicate_ids)| EnhancedDocumentResponse {
             id: doc.id,
             filename: doc.filename,
             original_filename: doc.original_filename,
@@ -94,10 +143,15 @@ async fn search_documents(
             ocr_status: doc.ocr_status,
             search_rank: None,
             snippets: Vec::new(),
+            is_owner: doc.user_id == auth_user.user.id,
+            duplicate_count: duplicate_ids.len() as i64,
+            duplicate_ids,
         }).collect(),
-        total,
+        total: count.total,
         query_time_ms: 0,
         suggestions: Vec::new(),
+        capped: count.capped,
+        total_is_estimate: count.total_is_estimate,
     };
 
     Ok(Json(response))
@@ -123,7 +177,7 @@ async fn search_documents(
 async fn enhanced_search_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(search_request): Query<SearchRequest>,
+    Query(mut search_request): Query<SearchRequest>,
 ) -> Result<Json<SearchResponse>, StatusCode> {
     // Validate query length (allow empty query if filters are present)
     let has_filters = search_request.tags.as_ref().map_or(false, |t| !t.is_empty())
@@ -137,10 +191,16 @@ async fn enhanced_search_documents(
 
     let start_time = std::time::Instant::now();
 
+    if search_request.language.as_deref().is_some_and(|language| !is_supported_search_language(language)) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    resolve_search_defaults(&state, auth_user.user.id, &mut search_request).await;
+    limit_to_max_results(&mut search_request, state.config.search_max_results).map_err(|e| e.status_code())?;
+
     // Get total count (without pagination) for proper pagination support
-    let total = state
+    let count = state
         .db
-        .count_search_documents(auth_user.user.id, auth_user.user.role.clone(), &search_request)
+        .count_search_documents_capped(auth_user.user.id, auth_user.user.role, &search_request, state.config.search_max_results)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -154,14 +214,91 @@ async fn enhanced_search_documents(
 
     let response = SearchResponse {
         documents,
-        total,
+        total: count.total,
         query_time_ms: query_time,
         suggestions,
+        capped: count.capped,
+        total_is_estimate: count.total_is_estimate,
     };
 
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/search/export",
+    tag = "search",
+    description = "Export the metadata of every document a search matches as CSV. Takes the same query and filters as search, without pagination, and streams the rows as they are read.",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        SearchRequest,
+        SearchExportQuery
+    ),
+    responses(
+        (status = 200, description = "CSV file with a header row and one row per matching document", content_type = "text/csv"),
+        (status = 400, description = "Invalid query, format or column"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn export_search_results(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(mut search_request): Query<SearchRequest>,
+    Query(export): Query<SearchExportQuery>,
+) -> Result<Response, SearchError> {
+    let format = export.format.as_deref().unwrap_or("csv");
+    if !format.eq_ignore_ascii_case("csv") {
+        return Err(SearchError::invalid_export_format(format));
+    }
+    let columns = SearchExportColumn::parse_list(export.columns.as_deref()).map_err(SearchError::invalid_export_column)?;
+
+    // Same validation as search, except that the whole result set is exported
+    let has_filters = search_request.tags.as_ref().map_or(false, |t| !t.is_empty())
+        || search_request.mime_types.as_ref().map_or(false, |m| !m.is_empty());
+    if search_request.query.len() < 2 && !has_filters {
+        return Err(SearchError::query_too_short(search_request.query.len(), 2));
+    }
+    if search_request.query.len() > 1000 {
+        return Err(SearchError::query_too_long(search_request.query.len(), 1000));
+    }
+    if let Some(language) = search_request.language.as_deref() {
+        if !is_supported_search_language(language) {
+            return Err(SearchError::invalid_language(language));
+        }
+    }
+    resolve_search_defaults(&state, auth_user.user.id, &mut search_request).await;
+
+    let (sender, receiver) = mpsc::channel::<anyhow::Result<SearchExportRow>>(EXPORT_BUFFER_ROWS);
+    let user = auth_user.user;
+    tokio::spawn(async move {
+        if let Err(e) = state.db.export_search_documents(user.id, user.role, &search_request, &sender).await {
+            tracing::error!("Search export for user {} failed: {}", user.id, e);
+            let _ = sender.send(Err(e)).await;
+        }
+    });
+
+    // A failure after the header has gone out aborts the download rather than
+    // ending it early, so a truncated export isn't mistaken for a complete one
+    let header_row = csv::record(columns.iter().map(|column| column.name()));
+    let rows = stream::unfold(receiver, |mut receiver| async move { receiver.recv().await.map(|row| (row, receiver)) })
+        .map(move |row| {
+            row.map(|row| Bytes::from(csv::record(columns.iter().map(|column| column.value(&row)))))
+                .map_err(|e| std::io::Error::other(e.to_string()))
+        });
+    let body = stream::once(async move { Ok::<_, std::io::Error>(Bytes::from(header_row)) }).chain(rows);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"search-results.csv\""),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
+
 fn generate_search_suggestions(query: &str) -> Vec<String> {
     // Simple suggestion generation - could be enhanced with a proper suggestion system
     let mut suggestions = Vec::new();
@@ -2,23 +2,26 @@ use axum::{
     extract::State,
     http::StatusCode,
     response::Json,
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use std::sync::Arc;
 
 use crate::{
     auth::AuthUser,
-    errors::settings::SettingsError,
-    models::{SettingsResponse, UpdateSettings, UserRole},
+    errors::{settings::SettingsError, ApiError},
+    models::{OcrReprocessSummary, Settings, SettingsResponse, UpdateSettings, UserRole},
+    ocr::queue::OcrPriorityClass,
     AppState,
 };
 use serde::Serialize;
+use tracing::{error, info};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_settings).put(update_settings))
         .route("/config", get(get_server_configuration))
+        .route("/ocr/reprocess", post(reprocess_outdated_ocr))
 }
 
 #[utoipa::path(
@@ -104,6 +107,36 @@ async fn get_settings(
                 // Office document extraction configuration
                 office_extraction_timeout_seconds: default.office_extraction_timeout_seconds,
                 office_extraction_enable_detailed_logging: default.office_extraction_enable_detailed_logging,
+                ocr_auto_psm: default.ocr_auto_psm,
+                ocr_quick_preview_pages: default.ocr_quick_preview_pages,
+                notification_delivery_mode: default.notification_delivery_mode,
+                notification_digest_interval_minutes: default.notification_digest_interval_minutes,
+                ocr_max_pixels: default.ocr_max_pixels,
+                ocr_pdf_hybrid: default.ocr_pdf_hybrid,
+                ocr_reprocess_on_settings_change: default.ocr_reprocess_on_settings_change,
+                auto_label_language: default.auto_label_language,
+                extract_document_date: default.extract_document_date,
+                document_date_priority: default.document_date_priority,
+                document_date_rule: default.document_date_rule,
+                document_date_order: default.document_date_order,
+                search_include_notes: default.search_include_notes,
+                ocr_verify_orientation: default.ocr_verify_orientation,
+                auto_split_on_separator: default.auto_split_on_separator,
+                auto_split_barcode: default.auto_split_barcode,
+                expiry_reminder_days: default.expiry_reminder_days,
+                search_language: default.search_language,
+                search_ignore_accents: default.search_ignore_accents,
+                extract_archives: default.extract_archives,
+                keep_original_pdf: default.keep_original_pdf,
+                decode_barcodes: default.decode_barcodes,
+                search_collapse_duplicates: default.search_collapse_duplicates,
+                ocr_incremental_pages: default.ocr_incremental_pages,
+                parse_front_matter: default.parse_front_matter,
+                extract_tables: default.extract_tables,
+                doc_extraction_order: default.doc_extraction_order,
+                pdf_extraction_order: default.pdf_extraction_order,
+                extraction_auto_order: default.extraction_auto_order,
+                ocr_reprocess: None,
             }
         },
     };
@@ -121,7 +154,7 @@ async fn get_settings(
     request_body = UpdateSettings,
     responses(
         (status = 200, description = "Settings updated successfully", body = SettingsResponse),
-        (status = 400, description = "Bad request - invalid settings data"),
+        (status = 400, description = "Bad request - invalid settings data; the rejected setting is listed in `details.fields`"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -130,14 +163,154 @@ async fn update_settings(
     auth_user: AuthUser,
     State(state): State<Arc<AppState>>,
     Json(update_data): Json<UpdateSettings>,
-) -> Result<Json<SettingsResponse>, StatusCode> {
+) -> Result<Json<SettingsResponse>, ApiError> {
+    let previous = state
+        .db
+        .get_user_settings(auth_user.user.id)
+        .await
+        .map_err(settings_update_error)?
+        .unwrap_or_default();
+
     let settings = state
         .db
         .create_or_update_settings(auth_user.user.id, &update_data)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(settings_update_error)?;
+
+    let changed_settings = settings.ocr_changes_since(&previous);
+    let ocr_reprocess = if changed_settings.is_empty() {
+        None
+    } else {
+        Some(handle_ocr_settings_change(&state, &settings, changed_settings).await)
+    };
+
+    let mut response: SettingsResponse = settings.into();
+    response.ocr_reprocess = ocr_reprocess;
+    Ok(Json(response))
+}
+
+/// Reports a rejected setting as a validation error of its field, and anything
+/// else by its cause
+fn settings_update_error(error: anyhow::Error) -> ApiError {
+    let error = match error.downcast::<SettingsError>() {
+        Ok(SettingsError::ValidationFailed { setting_name, reason }) => return ApiError::invalid_field(setting_name, reason),
+        Ok(other) => return ApiError::bad_request(other.to_string()),
+        Err(error) => error,
+    };
+    match error.downcast::<sqlx::Error>() {
+        Ok(database_error) => ApiError::Database(database_error),
+        Err(error) => ApiError::internal_server_error(error.to_string()),
+    }
+}
+
+/// Priority within the backfill class for reprocessing after a settings change
+const OCR_REPROCESS_PRIORITY: i32 = 1;
+
+/// Bumps the OCR settings version, then counts, and optionally queues, the documents
+/// processed under older settings. Failures here are logged rather than failing the
+/// settings update, which has already been saved.
+async fn handle_ocr_settings_change(
+    state: &Arc<AppState>,
+    settings: &Settings,
+    changed_settings: Vec<&'static str>,
+) -> OcrReprocessSummary {
+    let user_id = settings.user_id;
+    info!("OCR settings changed for user {}: {}", user_id, changed_settings.join(", "));
+
+    let mut summary = OcrReprocessSummary {
+        changed_settings: changed_settings.into_iter().map(String::from).collect(),
+        settings_version: 0,
+        affected_documents: 0,
+        queued_documents: 0,
+    };
+
+    summary.settings_version = match state.db.bump_ocr_settings_version(user_id).await {
+        Ok(version) => version,
+        Err(e) => {
+            error!("Failed to bump OCR settings version for user {}: {}", user_id, e);
+            return summary;
+        }
+    };
+
+    if settings.ocr_reprocess_on_settings_change {
+        match queue_ocr_reprocess(state, user_id, summary.settings_version).await {
+            Ok(queued) => {
+                summary.affected_documents = queued;
+                summary.queued_documents = queued;
+            }
+            Err(e) => error!("Failed to queue OCR reprocessing for user {}: {}", user_id, e),
+        }
+    } else {
+        match state.db.count_documents_for_ocr_reprocess(user_id, summary.settings_version).await {
+            Ok(count) => summary.affected_documents = count,
+            Err(e) => error!("Failed to count documents to reprocess for user {}: {}", user_id, e),
+        }
+    }
+
+    summary
+}
+
+async fn queue_ocr_reprocess(state: &Arc<AppState>, user_id: uuid::Uuid, version: i32) -> anyhow::Result<i64> {
+    let documents = state.db.get_documents_for_ocr_reprocess(user_id, version).await?;
+    if documents.is_empty() {
+        return Ok(0);
+    }
+
+    let document_ids: Vec<uuid::Uuid> = documents.iter().map(|(document_id, _)| *document_id).collect();
+    state.db.mark_documents_for_ocr_reprocess(&document_ids).await?;
+
+    let queued = state
+        .queue_service
+        .enqueue_documents_batch_with_class(
+            documents
+                .into_iter()
+                .map(|(document_id, file_size)| (document_id, OCR_REPROCESS_PRIORITY, file_size))
+                .collect(),
+            OcrPriorityClass::Backfill,
+        )
+        .await?;
+
+    info!("Queued {} documents for OCR reprocessing under settings version {}", queued.len(), version);
+    Ok(queued.len() as i64)
+}
+
+/// Queue documents processed under older OCR settings for reprocessing
+#[utoipa::path(
+    post,
+    path = "/api/settings/ocr/reprocess",
+    tag = "settings",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Documents queued for OCR reprocessing", body = OcrReprocessSummary),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn reprocess_outdated_ocr(
+    auth_user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<OcrReprocessSummary>, StatusCode> {
+    let version = state
+        .db
+        .get_ocr_settings_version(auth_user.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let queued = queue_ocr_reprocess(&state, auth_user.user.id, version)
+        .await
+        .map_err(|e| {
+            error!("Failed to queue OCR reprocessing for user {}: {}", auth_user.user.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-    Ok(Json(settings.into()))
+    Ok(Json(OcrReprocessSummary {
+        changed_settings: Vec::new(),
+        settings_version: version,
+        affected_documents: queued,
+        queued_documents: queued,
+    }))
 }
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
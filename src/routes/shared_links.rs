@@ -20,6 +20,7 @@ use crate::{
         SharedLinkResponse,
     },
     models::UserRole,
+    storage::StorageAccessDenied,
     AppState,
 };
 
@@ -407,11 +408,15 @@ pub async fn download_shared_document(
 
     let file_data = state
         .file_service
-        .read_file(&document.file_path)
+        .read_user_file(document.user_id, &document.file_path)
         .await
         .map_err(|e| {
             error!("Failed to read document file: {}", e);
-            SharedLinkError::InternalError { message: "Failed to read document file".into() }
+            if e.is::<StorageAccessDenied>() {
+                SharedLinkError::PermissionDenied { reason: "Document file is not accessible".into() }
+            } else {
+                SharedLinkError::InternalError { message: "Failed to read document file".into() }
+            }
         })?;
 
     let response = Response::builder()
@@ -478,11 +483,15 @@ pub async fn view_shared_document(
 
     let file_data = state
         .file_service
-        .read_file(&document.file_path)
+        .read_user_file(document.user_id, &document.file_path)
         .await
         .map_err(|e| {
             error!("Failed to read document file: {}", e);
-            SharedLinkError::InternalError { message: "Failed to read document file".into() }
+            if e.is::<StorageAccessDenied>() {
+                SharedLinkError::PermissionDenied { reason: "Document file is not accessible".into() }
+            } else {
+                SharedLinkError::InternalError { message: "Failed to read document file".into() }
+            }
         })?;
 
     let response = Response::builder()
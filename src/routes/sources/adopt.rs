@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    models::{AdoptDocumentsRequest, AdoptDocumentsResponse},
+    AppState,
+};
+
+/// Most document IDs one adopt request may list
+pub const MAX_ADOPT_DOCUMENT_IDS: usize = 1000;
+
+/// Assign existing documents to a source without ingesting them again
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/adopt",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    request_body = AdoptDocumentsRequest,
+    responses(
+        (status = 200, description = "Documents adopted; documents that could not be are listed with the reason", body = AdoptDocumentsResponse),
+        (status = 400, description = "Neither document IDs nor a filter given, too many IDs, or an empty source path"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn adopt_documents(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<AdoptDocumentsRequest>,
+) -> Result<Json<AdoptDocumentsResponse>, StatusCode> {
+    if request.document_ids.is_empty() && request.filter.is_none() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if request.document_ids.len() > MAX_ADOPT_DOCUMENT_IDS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if request.source_paths.values().any(|path| path.trim().is_empty()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let response = state
+        .db
+        .adopt_documents_into_source(auth_user.user.id, source_id, &request)
+        .await
+        .map_err(|e| {
+            error!("Failed to adopt documents into source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(response))
+}
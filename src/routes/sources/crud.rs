@@ -1,20 +1,36 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::Json,
+    response::{IntoResponse, Json, Response},
 };
+use chrono::Utc;
+use serde::Deserialize;
 use std::sync::Arc;
 use uuid::Uuid;
 use tracing::{error, info};
+use utoipa::IntoParams;
 
 use crate::{
     auth::AuthUser,
     errors::source::SourceError,
-    models::{CreateSource, SourceResponse, SourceWithStats, UpdateSource, SourceType},
+    models::{
+        CreateSource, SourceDeletionPreview, SourceDeletionSummary, SourceResponse, SourceWithStats, UpdateSource,
+        SourceType,
+    },
+    routes::pagination::{OptionalPagination, PaginationMeta, PaginationParams},
+    services::source_deletion,
     AppState,
 };
 
-/// List all sources for the authenticated user
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct SourceListResponse {
+    pub sources: Vec<SourceResponse>,
+    pub pagination: PaginationMeta,
+}
+
+/// List sources for the authenticated user. All sources are returned as an
+/// array unless pagination parameters are sent, which returns one page of them
+/// with a pagination block.
 #[utoipa::path(
     get,
     path = "/api/sources",
@@ -22,8 +38,10 @@ use crate::{
     security(
         ("bearer_auth" = [])
     ),
+    params(PaginationParams),
     responses(
-        (status = 200, description = "List of user sources", body = Vec<SourceResponse>),
+        (status = 200, description = "List of user sources, or a `SourceListResponse` page when pagination parameters are sent", body = Vec<SourceResponse>),
+        (status = 400, description = "Invalid pagination parameters"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -31,13 +49,25 @@ use crate::{
 pub async fn list_sources(
     auth_user: AuthUser,
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<SourceResponse>>, SourceError> {
+    OptionalPagination(pagination): OptionalPagination,
+) -> Result<Response, SourceError> {
     let sources = state
         .db
         .get_sources(auth_user.user.id)
         .await
         .map_err(|e| SourceError::connection_failed(format!("Failed to retrieve sources: {}", e)))?;
 
+    // Users have a handful of sources, so the page is cut in memory
+    let total = sources.len() as i64;
+    let sources: Vec<_> = match pagination {
+        Some(pagination) => sources
+            .into_iter()
+            .skip(pagination.offset as usize)
+            .take(pagination.limit as usize)
+            .collect(),
+        None => sources,
+    };
+
     // Get source IDs for batch counting
     let source_ids: Vec<Uuid> = sources.iter().map(|s| s.id).collect();
     
@@ -64,8 +94,15 @@ pub async fn list_sources(
             response
         })
         .collect();
-    
-    Ok(Json(responses))
+
+    Ok(match pagination {
+        Some(pagination) => Json(SourceListResponse {
+            sources: responses,
+            pagination: pagination.meta(total),
+        })
+        .into_response(),
+        None => Json(responses).into_response(),
+    })
 }
 
 /// Create a new source
@@ -173,6 +210,18 @@ pub async fn get_source(
         None
     };
 
+    let circuit = state
+        .db
+        .get_source_circuit(source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let clock_skew = state
+        .db
+        .get_source_clock_skew(source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
     let mut source_response: SourceResponse = source.into();
     source_response.total_documents = total_documents;
     source_response.total_documents_ocr = total_documents_ocr;
@@ -181,6 +230,8 @@ pub async fn get_source(
         source: source_response,
         recent_documents: recent_documents.into_iter().map(|d| d.into()).collect(),
         sync_progress,
+        circuit,
+        clock_skew,
     };
 
     Ok(Json(response))
@@ -255,6 +306,78 @@ pub async fn update_source(
     Ok(Json(response))
 }
 
+#[derive(Deserialize, IntoParams)]
+pub struct DeleteSourceQuery {
+    /// Also delete the source's documents and their files instead of keeping
+    /// them without a source
+    #[serde(default)]
+    pub delete_documents: bool,
+    /// Token from `GET /api/sources/{id}/delete-preview`. Required when
+    /// SOURCE_DELETE_REQUIRE_CONFIRMATION is set.
+    pub confirmation_token: Option<String>,
+}
+
+/// Preview what deleting a source would affect
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/delete-preview",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Affected documents and a confirmation token", body = SourceDeletionPreview),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn preview_source_deletion(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SourceDeletionPreview>, StatusCode> {
+    let source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (document_count, documents_with_ocr) = state
+        .db
+        .count_documents_for_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let token_expires_at = Utc::now() + chrono::Duration::minutes(source_deletion::CONFIRMATION_TOKEN_TTL_MINUTES);
+    let confirmation_token = source_deletion::create_confirmation_token(
+        &state.config.jwt_secret,
+        auth_user.user.id,
+        source_id,
+        document_count,
+        token_expires_at,
+    )
+    .map_err(|e| {
+        error!("Failed to create deletion token for source {}: {}", source_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(SourceDeletionPreview {
+        source_id,
+        name: source.name,
+        document_count,
+        documents_with_ocr,
+        confirmation_token,
+        token_expires_at,
+        confirmation_required: state.config.source_delete_require_confirmation,
+        grace_period_minutes: state.config.source_delete_grace_period_minutes,
+    }))
+}
+
 /// Delete a source
 #[utoipa::path(
     delete,
@@ -264,31 +387,138 @@ pub async fn update_source(
         ("bearer_auth" = [])
     ),
     params(
-        ("id" = Uuid, Path, description = "Source ID")
+        ("id" = Uuid, Path, description = "Source ID"),
+        DeleteSourceQuery
     ),
     responses(
-        (status = 204, description = "Source deleted successfully"),
+        (status = 200, description = "Source deleted, or scheduled for deletion during the grace period", body = SourceDeletionSummary),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Source not found"),
+        (status = 409, description = "Confirmation token missing, invalid, expired or out of date"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn delete_source(
     auth_user: AuthUser,
     Path(source_id): Path<Uuid>,
+    Query(query): Query<DeleteSourceQuery>,
     State(state): State<Arc<AppState>>,
-) -> Result<StatusCode, StatusCode> {
-    let deleted = state
+) -> Result<Json<SourceDeletionSummary>, SourceError> {
+    let user_id = auth_user.user.id;
+    let source = state
         .db
-        .delete_source(auth_user.user.id, source_id)
+        .get_source(user_id, source_id)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|e| SourceError::connection_failed(format!("Failed to retrieve source: {}", e)))?
+        .ok_or(SourceError::not_found_by_id(source_id))?;
 
-    if deleted {
-        Ok(StatusCode::NO_CONTENT)
-    } else {
-        Err(StatusCode::NOT_FOUND)
+    let (document_count, _) = state
+        .db
+        .count_documents_for_source(user_id, source_id)
+        .await
+        .map_err(|e| SourceError::connection_failed(format!("Failed to count source documents: {}", e)))?;
+
+    match query.confirmation_token.as_deref() {
+        Some(token) => {
+            if !source_deletion::verify_confirmation_token(&state.config.jwt_secret, token, user_id, source_id, document_count) {
+                return Err(SourceError::delete_restricted(
+                    source.name,
+                    "the confirmation token is invalid, expired or out of date; preview the deletion again".to_string(),
+                ));
+            }
+        }
+        None if state.config.source_delete_require_confirmation => {
+            return Err(SourceError::delete_restricted(
+                source.name,
+                "a confirmation token from the deletion preview is required".to_string(),
+            ));
+        }
+        None => {}
+    }
+
+    // With a grace period the source is only hidden; the purge service removes it later
+    if let Some(purge_at) = source_deletion::purge_time(Utc::now(), state.config.source_delete_grace_period_minutes) {
+        let deleted = state
+            .db
+            .soft_delete_source(user_id, source_id, purge_at, query.delete_documents)
+            .await
+            .map_err(|e| SourceError::connection_failed(format!("Failed to delete source: {}", e)))?;
+        if !deleted {
+            return Err(SourceError::not_found_by_id(source_id));
+        }
+
+        info!("Source {} deleted, restorable until {}", source_id, purge_at);
+        let (documents_deleted, documents_orphaned) = if query.delete_documents {
+            (document_count, 0)
+        } else {
+            (0, document_count)
+        };
+        return Ok(Json(SourceDeletionSummary {
+            source_id,
+            documents_deleted,
+            documents_orphaned,
+            files_failed: 0,
+            purge_at: Some(purge_at),
+        }));
+    }
+
+    let summary = source_deletion::purge_source(&state.db, &state.file_service, user_id, source_id, query.delete_documents)
+        .await
+        .map_err(|e| SourceError::connection_failed(format!("Failed to delete source: {}", e)))?;
+    Ok(Json(summary))
+}
+
+/// Restore a deleted source during its grace period
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/restore",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Source restored", body = SourceResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No deleted source with this ID, or it was already purged"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn restore_source(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SourceResponse>, StatusCode> {
+    let restored = state
+        .db
+        .restore_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !restored {
+        return Err(StatusCode::NOT_FOUND);
     }
+
+    let source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (total_documents, total_documents_ocr) = state
+        .db
+        .count_documents_for_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut response: SourceResponse = source.into();
+    response.total_documents = total_documents;
+    response.total_documents_ocr = total_documents_ocr;
+
+    info!("Restored deleted source {}: {}", source_id, response.name);
+    Ok(Json(response))
 }
 
 /// Validate source configuration based on type
@@ -301,11 +531,18 @@ pub fn validate_config_for_type(
     source_type: &SourceType,
     config: &serde_json::Value,
 ) -> Result<(), &'static str> {
+    crate::models::SourceCircuitBreakerConfig::from_source_config(config)
+        .map_err(|_| "Invalid circuit_breaker configuration")?;
+    crate::models::source_ocr_dpi(config).map_err(|_| "ocr_dpi must be a whole number between 72 and 1200")?;
+    crate::ingestion::image_normalization::ImageNormalizationConfig::from_source_config(config)
+        .map_err(|_| "Invalid image_normalization configuration")?;
+
     match source_type {
         SourceType::WebDAV => {
-            let _: crate::models::WebDAVSourceConfig =
+            let webdav_config: crate::models::WebDAVSourceConfig =
                 serde_json::from_value(config.clone()).map_err(|_| "Invalid WebDAV configuration")?;
-            Ok(())
+            webdav_config.validate_user_agent()?;
+            webdav_config.download_verification.validate()
         }
         SourceType::LocalFolder => {
             let _: crate::models::LocalFolderSourceConfig =
@@ -313,9 +550,10 @@ pub fn validate_config_for_type(
             Ok(())
         }
         SourceType::S3 => {
-            let _: crate::models::S3SourceConfig =
+            let s3_config: crate::models::S3SourceConfig =
                 serde_json::from_value(config.clone()).map_err(|_| "Invalid S3 configuration")?;
-            Ok(())
+            s3_config.validate_network_settings()?;
+            s3_config.download_verification.validate()
         }
     }
 }
\ No newline at end of file
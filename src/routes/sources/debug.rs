@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    models::{FileIngestionInfo, SourceType, UserRole},
+    AppState,
+};
+
+#[derive(Deserialize, IntoParams)]
+pub struct PropfindDebugQuery {
+    /// Path to list, relative to the server's WebDAV root (default `/`)
+    pub path: Option<String>,
+}
+
+/// One item parsed from a PROPFIND response
+#[derive(Serialize, ToSchema)]
+pub struct PropfindDebugItem {
+    pub relative_path: String,
+    pub full_path: String,
+    pub name: String,
+    pub size: i64,
+    pub mime_type: String,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub etag: String,
+    pub is_directory: bool,
+}
+
+impl From<FileIngestionInfo> for PropfindDebugItem {
+    fn from(info: FileIngestionInfo) -> Self {
+        Self {
+            relative_path: info.relative_path,
+            full_path: info.full_path,
+            name: info.name,
+            size: info.size,
+            mime_type: info.mime_type,
+            last_modified: info.last_modified,
+            etag: info.etag,
+            is_directory: info.is_directory,
+        }
+    }
+}
+
+/// A raw PROPFIND response next to what discovery parsed from it
+#[derive(Serialize, ToSchema)]
+pub struct PropfindDebugResponse {
+    /// Request URL, with any credentials removed
+    pub url: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub duration_ms: u64,
+    /// Response body as received, with credentials removed from URLs in it
+    pub raw_xml: String,
+    /// Items discovery parsed from the body; empty when parsing failed
+    pub parsed: Vec<PropfindDebugItem>,
+    pub parse_error: Option<String>,
+}
+
+/// Run a single PROPFIND against a WebDAV source and return the raw response
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/debug/propfind",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID"),
+        PropfindDebugQuery
+    ),
+    responses(
+        (status = 200, description = "Raw and parsed PROPFIND response", body = PropfindDebugResponse),
+        (status = 400, description = "Source is not a WebDAV source"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Source not found, or source debug endpoints are disabled"),
+        (status = 502, description = "The WebDAV server could not be reached")
+    )
+)]
+pub async fn debug_propfind(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PropfindDebugQuery>,
+) -> Result<Json<PropfindDebugResponse>, StatusCode> {
+    if !state.config.source_debug_endpoints {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    if auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let source = state
+        .db
+        .get_source_by_id(source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if source.source_type != SourceType::WebDAV {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let config: crate::models::WebDAVSourceConfig =
+        serde_json::from_value(source.config).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let webdav_config = crate::services::webdav::WebDAVConfig {
+        server_url: config.server_url,
+        username: config.username,
+        password: config.password,
+        watch_folders: config.watch_folders,
+        file_extensions: config.file_extensions,
+        timeout_seconds: 30,
+        server_type: config.server_type,
+        max_scan_depth: config.max_scan_depth,
+        detect_etag_aliases: config.detect_etag_aliases,
+        user_agent: config.user_agent.clone(),
+    };
+    let webdav_service = crate::services::webdav::WebDAVService::new(webdav_config)
+        .map_err(|e| {
+            error!("Failed to create WebDAV service for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .with_propfind_depths(config.propfind_depths);
+
+    let path = query.path.unwrap_or_else(|| "/".to_string());
+    info!("Admin {} requested a debug PROPFIND of '{}' on source {}", auth_user.user.id, path, source_id);
+    let result = webdav_service.debug_propfind(&path).await.map_err(|e| {
+        error!("Debug PROPFIND on source {} failed: {}", source_id, e);
+        StatusCode::BAD_GATEWAY
+    })?;
+
+    let (parsed, parse_error) = match result.parsed {
+        Ok(items) => (items.into_iter().map(PropfindDebugItem::from).collect(), None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+
+    Ok(Json(PropfindDebugResponse {
+        url: result.url,
+        status: result.status,
+        content_type: result.content_type,
+        duration_ms: result.duration_ms,
+        raw_xml: result.raw_xml,
+        parsed,
+        parse_error,
+    }))
+}
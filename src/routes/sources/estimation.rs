@@ -3,14 +3,32 @@ use axum::{
     http::StatusCode,
     response::Json,
 };
+use serde::Deserialize;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
     auth::AuthUser,
+    models::{SourcePreviewEstimate, SourcePreviewResponse, SourceType},
     AppState,
 };
 
+/// Watch folders listed for a source preview; the rest are extrapolated.
+/// `WebDAVService::estimate_crawl` samples the same number.
+const PREVIEW_SAMPLE_FOLDERS: usize = 3;
+/// Time budget for listing the sampled folders of a source preview
+const PREVIEW_SAMPLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Estimated size from which a preview warns about a large first sync
+const LARGE_SOURCE_BYTES: i64 = 100 * 1024 * 1024 * 1024;
+
+#[derive(Deserialize, ToSchema)]
+pub struct PreviewSourceRequest {
+    pub source_type: SourceType,
+    pub config: serde_json::Value,
+}
+
 /// Estimate crawl for an existing source
 #[utoipa::path(
     post,
@@ -87,19 +105,12 @@ async fn estimate_webdav_crawl_internal(
     config: &crate::models::WebDAVSourceConfig,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     // Create WebDAV service config
-    let webdav_config = crate::services::webdav::WebDAVConfig {
-        server_url: config.server_url.clone(),
-        username: config.username.clone(),
-        password: config.password.clone(),
-        watch_folders: config.watch_folders.clone(),
-        file_extensions: config.file_extensions.clone(),
-        timeout_seconds: 300,
-        server_type: config.server_type.clone(),
-    };
+    let webdav_config = webdav_service_config(config, 300);
 
     // Create WebDAV service and estimate crawl
     match crate::services::webdav::WebDAVService::new(webdav_config) {
         Ok(webdav_service) => {
+            let webdav_service = webdav_service.with_propfind_depths(config.propfind_depths);
             match webdav_service.estimate_crawl().await {
                 Ok(estimate) => Ok(Json(serde_json::to_value(estimate).unwrap())),
                 Err(e) => Ok(Json(serde_json::json!({
@@ -121,4 +132,154 @@ async fn estimate_webdav_crawl_internal(
             "total_size_mb": 0.0,
         }))),
     }
-}
\ No newline at end of file
+}
+
+fn webdav_service_config(
+    config: &crate::models::WebDAVSourceConfig,
+    timeout_seconds: u64,
+) -> crate::services::webdav::WebDAVConfig {
+    crate::services::webdav::WebDAVConfig {
+        server_url: config.server_url.clone(),
+        username: config.username.clone(),
+        password: config.password.clone(),
+        watch_folders: config.watch_folders.clone(),
+        file_extensions: config.file_extensions.clone(),
+        timeout_seconds,
+        server_type: config.server_type.clone(),
+        max_scan_depth: config.max_scan_depth,
+        detect_etag_aliases: config.detect_etag_aliases,
+        user_agent: config.user_agent.clone(),
+    }
+}
+
+/// Validate a source config, test its connection and estimate what it would sync, without saving it
+#[utoipa::path(
+    post,
+    path = "/api/sources/preview",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = PreviewSourceRequest,
+    responses(
+        (status = 200, description = "Source preview", body = SourcePreviewResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn preview_source(
+    _auth_user: AuthUser,
+    State(_state): State<Arc<AppState>>,
+    Json(request): Json<PreviewSourceRequest>,
+) -> Result<Json<SourcePreviewResponse>, StatusCode> {
+    let mut response = SourcePreviewResponse {
+        valid: true,
+        errors: Vec::new(),
+        connection_ok: false,
+        connection_message: None,
+        estimate: None,
+        warnings: Vec::new(),
+    };
+
+    if let Err(e) = super::crud::validate_config_for_type(&request.source_type, &request.config) {
+        response.valid = false;
+        response.errors.push(e.to_string());
+        return Ok(Json(response));
+    }
+
+    let (connection_ok, message) =
+        super::validation::test_config_connection(&request.source_type, request.config.clone())
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    response.connection_ok = connection_ok;
+    response.connection_message = Some(message);
+    if !connection_ok {
+        return Ok(Json(response));
+    }
+
+    match tokio::time::timeout(PREVIEW_SAMPLE_TIMEOUT, sample_source(&request.source_type, request.config)).await {
+        Ok(Ok(estimate)) => {
+            if estimate.estimated_size_bytes >= LARGE_SOURCE_BYTES {
+                response.warnings.push(format!(
+                    "The first sync would ingest an estimated {:.0} GB",
+                    estimate.estimated_size_bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+                ));
+            }
+            if !estimate.exact {
+                response.warnings.push(format!(
+                    "Only {} of {} watch folders were listed; the estimate is extrapolated from them",
+                    estimate.folders_sampled, estimate.folders_total
+                ));
+            }
+            response.estimate = Some(estimate);
+        }
+        Ok(Err(e)) => response.warnings.push(format!("Could not estimate the source's size: {}", e)),
+        Err(_) => response.warnings.push(format!(
+            "Listing the source took longer than {} seconds, so it is probably very large",
+            PREVIEW_SAMPLE_TIMEOUT.as_secs()
+        )),
+    }
+
+    Ok(Json(response))
+}
+
+/// Lists up to `PREVIEW_SAMPLE_FOLDERS` watch folders and extrapolates to the rest
+async fn sample_source(source_type: &SourceType, config: serde_json::Value) -> anyhow::Result<SourcePreviewEstimate> {
+    match source_type {
+        SourceType::WebDAV => {
+            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(config)?;
+            let folders_total = config.watch_folders.len();
+            let service = crate::services::webdav::WebDAVService::new(webdav_service_config(
+                &config,
+                PREVIEW_SAMPLE_TIMEOUT.as_secs(),
+            ))?
+            .with_propfind_depths(config.propfind_depths);
+            let estimate = service.estimate_crawl().await?;
+            Ok(SourcePreviewEstimate::from_webdav(
+                &estimate,
+                folders_total.min(PREVIEW_SAMPLE_FOLDERS),
+                folders_total,
+            ))
+        }
+        SourceType::LocalFolder => {
+            let config: crate::models::LocalFolderSourceConfig = serde_json::from_value(config)?;
+            let watch_folders = config.watch_folders.clone();
+            let service = crate::services::local_folder_service::LocalFolderService::new(config)?;
+
+            let start = Instant::now();
+            let (mut files, mut bytes) = (0, 0i64);
+            for folder in watch_folders.iter().take(PREVIEW_SAMPLE_FOLDERS) {
+                let discovered = service.discover_files_in_folder(folder).await?;
+                files += discovered.len();
+                bytes += discovered.iter().map(|f| f.size).sum::<i64>();
+            }
+            Ok(SourcePreviewEstimate::from_sample(
+                files,
+                bytes,
+                start.elapsed(),
+                watch_folders.len().min(PREVIEW_SAMPLE_FOLDERS),
+                watch_folders.len(),
+            ))
+        }
+        SourceType::S3 => {
+            let config: crate::models::S3SourceConfig = serde_json::from_value(config)?;
+            let watch_folders = config.watch_folders.clone();
+            let service = crate::services::s3_service::S3Service::new(config).await?;
+
+            let start = Instant::now();
+            let (mut files, mut bytes) = (0, 0i64);
+            for folder in watch_folders.iter().take(PREVIEW_SAMPLE_FOLDERS) {
+                let discovered = service.discover_files_in_folder(folder).await?;
+                files += discovered.len();
+                bytes += discovered.iter().map(|f| f.size).sum::<i64>();
+            }
+            Ok(SourcePreviewEstimate::from_sample(
+                files,
+                bytes,
+                start.elapsed(),
+                watch_folders.len().min(PREVIEW_SAMPLE_FOLDERS),
+                watch_folders.len(),
+            ))
+        }
+    }
+}
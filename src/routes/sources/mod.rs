@@ -2,16 +2,20 @@ use axum::{routing::{get, post, delete, put}, Router};
 use std::sync::Arc;
 use crate::AppState;
 
+pub mod adopt;
 pub mod crud;
 pub mod sync;
 pub mod validation;
 pub mod estimation;
+pub mod debug;
 
 // Re-export commonly used functions and types for backward compatibility
+pub use adopt::*;
 pub use crud::*;
 pub use sync::*;
 pub use validation::*;
 pub use estimation::*;
+pub use debug::*;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -21,19 +25,32 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/{id}", get(get_source))
         .route("/{id}", put(update_source))
         .route("/{id}", delete(delete_source))
+        .route("/{id}/delete-preview", get(preview_source_deletion))
+        .route("/{id}/restore", post(restore_source))
         
         // Sync operations
         .route("/{id}/sync", post(trigger_sync))
         .route("/{id}/sync/stop", post(stop_sync))
+        .route("/{id}/sync/last", get(get_last_sync_run))
+        .route("/{id}/sync/retry-failed", post(retry_failed_sync))
+        .route("/{id}/sync-history", get(get_sync_history))
         .route("/{id}/sync/progress/ws", get(sync_progress_websocket))
         .route("/{id}/sync/status", get(get_sync_status))
         .route("/{id}/scan/deep", post(trigger_deep_scan))
+        .route("/{id}/reset-sync-state", post(reset_sync_state))
+        .route("/{id}/circuit/reset", post(reset_source_circuit))
+        .route("/{id}/adopt", post(adopt_documents))
         
         // Validation operations
         .route("/{id}/validate", post(validate_source))
+        .route("/{id}/capabilities", get(get_source_capabilities))
         .route("/test/connection", post(test_connection_with_config))
         
         // Estimation operations
         .route("/{id}/estimate", get(estimate_crawl))
         .route("/estimate", post(estimate_crawl_with_config))
+        .route("/preview", post(preview_source))
+        
+        // Debug operations (admin only, behind SOURCE_DEBUG_ENDPOINTS)
+        .route("/{id}/debug/propfind", get(debug_propfind))
 }
\ No newline at end of file
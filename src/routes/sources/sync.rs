@@ -1,21 +1,46 @@
 use axum::{
-    extract::{Path, State, WebSocketUpgrade},
+    extract::{Path, Query, State, WebSocketUpgrade},
     extract::ws::{WebSocket, Message},
     http::{StatusCode, HeaderMap},
     response::{Json, Response},
 };
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
 use tracing::{error, info};
 use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
     auth::AuthUser,
-    models::SourceStatus,
+    models::{
+        RetryFailedFilesResponse, SourceCircuit, SourceStatus, SourceSyncHistoryFilter, SourceSyncHistoryStats,
+        SourceSyncRun, SourceSyncRunSummary, SyncOutcome,
+    },
+    routes::pagination::{Pagination, PaginationMeta, PaginationParams},
     services::webdav::{SyncProgress, SyncPhase},
     AppState,
 };
 
+#[derive(Deserialize, IntoParams)]
+pub struct SyncHistoryQuery {
+    /// Only runs with this outcome (`success`, `partial` or `failed`)
+    pub status: Option<SyncOutcome>,
+    /// Only runs started at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Only runs started before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SyncHistoryResponse {
+    pub runs: Vec<SourceSyncRunSummary>,
+    /// Computed over every run matching the filters, not just this page
+    pub stats: SourceSyncHistoryStats,
+    pub pagination: PaginationMeta,
+}
+
 // Removed WebSocketAuthQuery - using secure header-based authentication instead
 
 /// Trigger a sync for a source
@@ -108,6 +133,230 @@ pub async fn trigger_sync(
     Ok(StatusCode::OK)
 }
 
+/// Get the result of a source's last finished sync, with the files that failed
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/sync/last",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Last sync result", body = crate::models::SourceSyncRun),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found or not synced yet"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_last_sync_run(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SourceSyncRun>, StatusCode> {
+    state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let run = state
+        .db
+        .get_last_source_sync_run(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load last sync run for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(run))
+}
+
+/// List a source's finished syncs, newest first, with stats over the runs matching the filters
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/sync-history",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID"),
+        SyncHistoryQuery,
+        PaginationParams
+    ),
+    responses(
+        (status = 200, description = "Paginated sync history", body = SyncHistoryResponse),
+        (status = 400, description = "Invalid filter or pagination parameters"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_sync_history(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SyncHistoryQuery>,
+    pagination: Pagination,
+) -> Result<Json<SyncHistoryResponse>, StatusCode> {
+    state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let filter = SourceSyncHistoryFilter {
+        outcome: query.status,
+        from: query.from,
+        to: query.to,
+    };
+
+    let (runs, total) = state
+        .db
+        .get_source_sync_history(source_id, &filter, pagination.limit, pagination.offset)
+        .await
+        .map_err(|e| {
+            error!("Failed to load sync history for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let stats = state
+        .db
+        .get_source_sync_history_stats(source_id, &filter)
+        .await
+        .map_err(|e| {
+            error!("Failed to load sync history stats for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(SyncHistoryResponse {
+        runs,
+        stats,
+        pagination: pagination.meta(total),
+    }))
+}
+
+/// Close a source's circuit breaker so scheduled syncs resume right away
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/circuit/reset",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Circuit closed", body = crate::models::SourceCircuit),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn reset_source_circuit(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SourceCircuit>, StatusCode> {
+    state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let circuit = SourceCircuit::closed(source_id);
+    state.db.save_source_circuit(&circuit).await.map_err(|e| {
+        error!("Failed to reset circuit breaker of source {}: {}", source_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    info!("Circuit breaker of source {} reset by user {}", source_id, auth_user.user.id);
+    Ok(Json(circuit))
+}
+
+/// Retry only the files that failed in a source's last sync
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/sync/retry-failed",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Retry started", body = crate::models::RetryFailedFilesResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found or not synced yet"),
+        (status = 409, description = "Source is already syncing, or its last sync has no failed files to retry"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn retry_failed_sync(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<RetryFailedFilesResponse>, StatusCode> {
+    state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let run = state
+        .db
+        .get_last_source_sync_run(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load last sync run for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let failed_files: Vec<_> = run
+        .failures
+        .iter()
+        .filter(|failure| failure.is_retryable())
+        .map(|failure| failure.to_file_info())
+        .collect();
+    if failed_files.is_empty() {
+        return Err(StatusCode::CONFLICT);
+    }
+    let files_to_retry = failed_files.len();
+
+    let scheduler = state.source_scheduler.as_ref().ok_or_else(|| {
+        error!("Cannot retry failed files for source {}: no source scheduler", source_id);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if let Err(e) = scheduler.trigger_retry_failed(source_id, failed_files).await {
+        let error_msg = e.to_string();
+        error!("Failed to retry failed files for source {}: {}", source_id, error_msg);
+
+        if error_msg.contains("already syncing") || error_msg.contains("already running") {
+            return Err(StatusCode::CONFLICT);
+        } else if error_msg.contains("not found") {
+            return Err(StatusCode::NOT_FOUND);
+        } else {
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    Ok(Json(RetryFailedFilesResponse {
+        sync_run_id: run.id,
+        files_to_retry,
+    }))
+}
+
 /// Stop sync for a source
 #[utoipa::path(
     post,
@@ -240,13 +489,19 @@ pub async fn trigger_deep_scan(
                 file_extensions: config.file_extensions.clone(),
                 timeout_seconds: 600, // 10 minutes for deep scan
                 server_type: config.server_type.clone(),
+                max_scan_depth: config.max_scan_depth,
+                detect_etag_aliases: config.detect_etag_aliases,
+                user_agent: config.user_agent.clone(),
             };
 
             let webdav_service = crate::services::webdav::WebDAVService::new(webdav_config.clone())
                 .map_err(|e| {
                     error!("Failed to create WebDAV service for deep scan: {}", e);
                     StatusCode::INTERNAL_SERVER_ERROR
-                })?;
+                })?
+                .with_propfind_depths(config.propfind_depths)
+                .with_download_verification(config.download_verification)
+                .with_max_directory_visits(config.max_directory_visits);
 
             // Update source status to syncing
             state
@@ -266,7 +521,7 @@ pub async fn trigger_deep_scan(
             let source_id_clone = source_id;
             let config_clone = config.clone();
             
-            tokio::spawn(async move {
+            tokio::spawn(crate::logging::in_current_request(async move {
                 let start_time = chrono::Utc::now();
                 
                 // Create progress tracker for manual deep scan
@@ -385,7 +640,7 @@ pub async fn trigger_deep_scan(
                                         })),
                                     };
                                     
-                                    if let Err(e) = state_clone.db.create_notification(user_id, &notification).await {
+                                    if let Err(e) = state_clone.db.deliver_notification(user_id, &notification).await {
                                         error!("Failed to create deep scan success notification: {}", e);
                                     }
                                 }
@@ -424,7 +679,7 @@ pub async fn trigger_deep_scan(
                                         })),
                                     };
                                     
-                                    if let Err(e) = state_clone.db.create_notification(user_id, &notification).await {
+                                    if let Err(e) = state_clone.db.deliver_notification(user_id, &notification).await {
                                         error!("Failed to create deep scan error notification: {}", e);
                                     }
                                 }
@@ -457,7 +712,7 @@ pub async fn trigger_deep_scan(
                         if !progress_unregistered {
                             state_clone.sync_progress_tracker.unregister_sync(source_id_clone);
                         }
-            });
+            }));
 
             Ok(Json(serde_json::json!({
                 "success": true,
@@ -696,6 +951,71 @@ fn extract_websocket_token_and_protocol(headers: &HeaderMap) -> Option<(String,
     None
 }
 
+/// Reset a source's sync tracking state so the next sync is a full deep scan
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/reset-sync-state",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Sync state cleared", body = crate::models::SourceSyncStateReset),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 409, description = "Source is currently syncing"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn reset_sync_state(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<crate::models::SourceSyncStateReset>, StatusCode> {
+    let source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Clearing tracking underneath a running sync would let it write stale ETags back
+    if matches!(source.status, SourceStatus::Syncing) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    // Only WebDAV sources keep directory ETags and a sync cursor; local folder and
+    // S3 sources always rescan fully once their last sync time is cleared
+    let (watch_folders, sync_cursor_server) = match source.source_type {
+        crate::models::SourceType::WebDAV => {
+            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(source.config)
+                .map_err(|e| {
+                    error!("Failed to parse WebDAV config for source {}: {}", source_id, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            (config.watch_folders, Some(config.server_url))
+        }
+        _ => (Vec::new(), None),
+    };
+
+    let reset = state
+        .db
+        .reset_source_sync_state(auth_user.user.id, source_id, &watch_folders, sync_cursor_server.as_deref())
+        .await
+        .map_err(|e| {
+            error!("Failed to reset sync state for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    info!("Sync state reset for source {} by user {}", source_id, auth_user.user.username);
+
+    Ok(Json(reset))
+}
+
 /// Get current sync progress (one-time API call)
 #[utoipa::path(
     get,
@@ -1,13 +1,14 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
 };
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use uuid::Uuid;
 use tracing::{error, info};
-use serde::{Deserialize};
-use utoipa::ToSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
     auth::AuthUser,
@@ -22,6 +23,32 @@ pub struct TestConnectionRequest {
     pub config: serde_json::Value,
 }
 
+#[derive(Deserialize, IntoParams)]
+pub struct SourceCapabilitiesQuery {
+    /// Check the server again even if its capabilities were checked recently
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// What a WebDAV source's server supports, as last checked
+#[derive(Serialize, ToSchema)]
+pub struct SourceCapabilitiesResponse {
+    pub dav_compliance: String,
+    pub allowed_methods: String,
+    pub server_software: Option<String>,
+    pub supports_etag: bool,
+    pub supports_depth_infinity: bool,
+    /// Whether a `Depth: infinity` PROPFIND was tried
+    pub infinity_depth_tested: bool,
+    /// Whether the server answered it
+    pub infinity_depth_works: bool,
+    pub checked_at: DateTime<Utc>,
+    pub age_seconds: u64,
+    /// Age after which the next sync checks the capabilities again
+    pub ttl_seconds: u64,
+    pub stale: bool,
+}
+
 /// Test connection for an existing source
 #[utoipa::path(
     post,
@@ -148,11 +175,26 @@ pub async fn test_connection_with_config(
     State(_state): State<Arc<AppState>>,
     Json(request): Json<TestConnectionRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    match request.source_type {
+    let (success, message) = test_config_connection(&request.source_type, request.config)
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    Ok(Json(serde_json::json!({
+        "success": success,
+        "message": message
+    })))
+}
+
+/// Tests whether a source with this config could connect. Returns whether it
+/// succeeded with a message, or an error when the config can't be parsed.
+pub(crate) async fn test_config_connection(
+    source_type: &SourceType,
+    config: serde_json::Value,
+) -> Result<(bool, String), serde_json::Error> {
+    match source_type {
         SourceType::WebDAV => {
             // Test WebDAV connection
-            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(request.config)
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(config)?;
 
             let test_config = WebDAVTestConnection {
                 server_url: config.server_url,
@@ -162,62 +204,36 @@ pub async fn test_connection_with_config(
             };
             
             match crate::services::webdav::test_webdav_connection(&test_config).await {
-                Ok(result) => Ok(Json(serde_json::json!({
-                    "success": result.success,
-                    "message": result.message
-                }))),
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("WebDAV connection failed: {}", e)
-                }))),
+                Ok(result) => Ok((result.success, result.message)),
+                Err(e) => Ok((false, format!("WebDAV connection failed: {}", e))),
             }
         }
         SourceType::LocalFolder => {
             // Test Local Folder access
-            let config: crate::models::LocalFolderSourceConfig = serde_json::from_value(request.config)
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            let config: crate::models::LocalFolderSourceConfig = serde_json::from_value(config)?;
 
             match crate::services::local_folder_service::LocalFolderService::new(config) {
                 Ok(service) => {
                     match service.test_connection().await {
-                        Ok(message) => Ok(Json(serde_json::json!({
-                            "success": true,
-                            "message": message
-                        }))),
-                        Err(e) => Ok(Json(serde_json::json!({
-                            "success": false,
-                            "message": format!("Local folder test failed: {}", e)
-                        }))),
+                        Ok(message) => Ok((true, message)),
+                        Err(e) => Ok((false, format!("Local folder test failed: {}", e))),
                     }
                 }
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("Local folder configuration error: {}", e)
-                }))),
+                Err(e) => Ok((false, format!("Local folder configuration error: {}", e))),
             }
         }
         SourceType::S3 => {
             // Test S3 connection
-            let config: crate::models::S3SourceConfig = serde_json::from_value(request.config)
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            let config: crate::models::S3SourceConfig = serde_json::from_value(config)?;
 
             match crate::services::s3_service::S3Service::new(config).await {
                 Ok(service) => {
                     match service.test_connection().await {
-                        Ok(message) => Ok(Json(serde_json::json!({
-                            "success": true,
-                            "message": message
-                        }))),
-                        Err(e) => Ok(Json(serde_json::json!({
-                            "success": false,
-                            "message": format!("S3 test failed: {}", e)
-                        }))),
+                        Ok(message) => Ok((true, message)),
+                        Err(e) => Ok((false, format!("S3 test failed: {}", e))),
                     }
                 }
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("S3 configuration error: {}", e)
-                }))),
+                Err(e) => Ok((false, format!("S3 configuration error: {}", e))),
             }
         }
     }
@@ -268,4 +284,88 @@ pub async fn validate_source(
         "success": true,
         "message": format!("Validation check started for source '{}'", source.name)
     })))
-}
\ No newline at end of file
+}
+/// Get the capabilities of a WebDAV source's server and how long ago they were checked
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/capabilities",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID"),
+        SourceCapabilitiesQuery
+    ),
+    responses(
+        (status = 200, description = "Server capabilities", body = SourceCapabilitiesResponse),
+        (status = 400, description = "Source is not a WebDAV source"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 502, description = "The WebDAV server could not be reached")
+    )
+)]
+pub async fn get_source_capabilities(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SourceCapabilitiesQuery>,
+) -> Result<Json<SourceCapabilitiesResponse>, StatusCode> {
+    let source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if source.source_type != SourceType::WebDAV {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let config: crate::models::WebDAVSourceConfig =
+        serde_json::from_value(source.config).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let webdav_config = crate::services::webdav::WebDAVConfig {
+        server_url: config.server_url,
+        username: config.username,
+        password: config.password,
+        watch_folders: config.watch_folders,
+        file_extensions: config.file_extensions,
+        timeout_seconds: 30,
+        server_type: config.server_type,
+        max_scan_depth: config.max_scan_depth,
+        detect_etag_aliases: config.detect_etag_aliases,
+        user_agent: config.user_agent,
+    };
+    let webdav_service = crate::services::webdav::WebDAVService::new(webdav_config)
+        .map_err(|e| {
+            error!("Failed to create WebDAV service for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .with_propfind_depths(config.propfind_depths);
+
+    // Report what the last check found, however old, unless asked to check again
+    let capabilities = match webdav_service.cached_capabilities() {
+        Some(capabilities) if !query.refresh => capabilities,
+        _ => webdav_service.refresh_capabilities().await.map_err(|e| {
+            error!("Failed to check the capabilities of source {}: {}", source_id, e);
+            StatusCode::BAD_GATEWAY
+        })?,
+    };
+
+    let age = capabilities.age();
+    let ttl = webdav_service.capability_ttl();
+    let checked_at = Utc::now() - chrono::Duration::from_std(age).unwrap_or_default();
+
+    Ok(Json(SourceCapabilitiesResponse {
+        dav_compliance: capabilities.dav_compliance,
+        allowed_methods: capabilities.allowed_methods,
+        server_software: capabilities.server_software,
+        supports_etag: capabilities.supports_etag,
+        supports_depth_infinity: capabilities.supports_depth_infinity,
+        infinity_depth_tested: capabilities.infinity_depth_tested,
+        infinity_depth_works: capabilities.infinity_depth_works,
+        checked_at,
+        age_seconds: age.as_secs(),
+        ttl_seconds: ttl.as_secs(),
+        stale: age >= ttl,
+    }))
+}
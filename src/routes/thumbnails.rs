@@ -0,0 +1,88 @@
+use axum::{extract::State, http::StatusCode, response::Json, routing::get, Router};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::{
+    auth::AuthUser,
+    routes::queue::require_admin,
+    services::thumbnail_regeneration::{self, ThumbnailRegenerationProgress},
+    AppState,
+};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/regenerate", get(get_regeneration_progress).post(regenerate_thumbnails))
+}
+
+/// Regenerate thumbnails made at another size than `THUMBNAIL_SIZE`
+///
+/// Starts regenerating, in the background and `THUMBNAIL_REGENERATION_CONCURRENCY`
+/// at a time, the thumbnails of every document whose thumbnail was made at
+/// another size. Documents already regenerated are skipped, so a regeneration
+/// that was interrupted resumes where it stopped.
+#[utoipa::path(
+    post,
+    path = "/api/admin/thumbnails/regenerate",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 202, description = "Regeneration started", body = ThumbnailRegenerationProgress),
+        (status = 401, description = "Unauthorized - valid authentication required"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 409, description = "A regeneration is already running"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn regenerate_thumbnails(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<(StatusCode, Json<ThumbnailRegenerationProgress>), StatusCode> {
+    require_admin(&auth_user)?;
+
+    let started = thumbnail_regeneration::start(
+        state.db.clone(),
+        state.file_service.clone(),
+        state.config.thumbnail_regeneration_concurrency,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to start thumbnail regeneration: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::CONFLICT)?;
+    Ok((StatusCode::ACCEPTED, Json(started)))
+}
+
+/// Progress of the latest thumbnail regeneration
+///
+/// `remaining` counts the thumbnails still at another size than `THUMBNAIL_SIZE`,
+/// whether or not a regeneration is running.
+#[utoipa::path(
+    get,
+    path = "/api/admin/thumbnails/regenerate",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Progress of the latest regeneration", body = ThumbnailRegenerationProgress),
+        (status = 401, description = "Unauthorized - valid authentication required"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_regeneration_progress(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<Json<ThumbnailRegenerationProgress>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let progress = thumbnail_regeneration::progress(&state.db, &state.file_service)
+        .await
+        .map_err(|e| {
+            error!("Failed to get thumbnail regeneration progress: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(progress))
+}
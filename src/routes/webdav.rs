@@ -68,6 +68,9 @@ async fn get_user_webdav_config(state: &Arc<AppState>, user_id: uuid::Uuid) -> R
         file_extensions: settings.webdav_file_extensions,
         timeout_seconds: 300, // 5 minutes timeout for crawl estimation
         server_type: Some("nextcloud".to_string()), // Default to Nextcloud
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     })
 }
 
@@ -103,6 +106,9 @@ async fn test_webdav_connection(
         file_extensions: Vec::new(),
         timeout_seconds: 300, // 5 minutes timeout for crawl estimation
         server_type: test_config.server_type.clone(),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     // Create WebDAV service and test connection
@@ -120,6 +126,7 @@ async fn test_webdav_connection(
                         message: format!("Connection test failed: {}", e),
                         server_version: None,
                         server_type: None,
+                        clock_skew_seconds: None,
                     }))
                 }
             }
@@ -131,6 +138,7 @@ async fn test_webdav_connection(
                 message: format!("Service creation failed: {}", e),
                 server_version: None,
                 server_type: None,
+                clock_skew_seconds: None,
             }))
         }
     }
@@ -347,7 +355,7 @@ async fn start_webdav_sync(
     let user_id = auth_user.user.id;
     let enable_background_ocr = user_settings.enable_background_ocr;
     
-    tokio::spawn(async move {
+    tokio::spawn(crate::logging::in_current_request(async move {
         match perform_webdav_sync_with_tracking(state_clone.clone(), user_id, webdav_service, webdav_config, enable_background_ocr, None).await {
             Ok(files_processed) => {
                 info!("WebDAV sync completed successfully for user {}: {} files processed", user_id, files_processed);
@@ -368,7 +376,7 @@ async fn start_webdav_sync(
                     })),
                 };
                 
-                if let Err(e) = state_clone.db.create_notification(user_id, &notification).await {
+                if let Err(e) = state_clone.db.deliver_notification(user_id, &notification).await {
                     error!("Failed to create success notification: {}", e);
                 }
             }
@@ -387,12 +395,12 @@ async fn start_webdav_sync(
                     })),
                 };
                 
-                if let Err(e) = state_clone.db.create_notification(user_id, &notification).await {
+                if let Err(e) = state_clone.db.deliver_notification(user_id, &notification).await {
                     error!("Failed to create error notification: {}", e);
                 }
             }
         }
-    });
+    }));
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -453,7 +461,7 @@ async fn cancel_webdav_sync(
                 })),
             };
             
-            if let Err(e) = state.db.create_notification(auth_user.user.id, &notification).await {
+            if let Err(e) = state.db.deliver_notification(auth_user.user.id, &notification).await {
                 error!("Failed to create cancellation notification: {}", e);
             }
             
@@ -11,6 +11,8 @@ use crate::{
     AppState,
     models::source::{CreateWebDAVFile, UpdateWebDAVSyncState},
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
+    ingestion::spool::SpoolPolicy,
+    ocr::queue::OcrPriorityClass,
     services::webdav::{WebDAVConfig, WebDAVService, SmartSyncService, SyncProgress, SyncPhase},
 };
 
@@ -400,7 +402,11 @@ async fn process_single_file(
     
     // Download the file
     let download_start = Instant::now();
-    let file_data = webdav_service.download_file(&file_info.path).await
+    let spool_policy = SpoolPolicy::new(
+        state.config.webdav_stream_threshold_mb,
+        state.file_service.get_temp_path(),
+    );
+    let download = webdav_service.download_file_spooled(&file_info.path, &spool_policy).await
         .map_err(|e| {
             error!("[{}] Failed to download '{}': {}", file_request_id, file_info.path, e);
             format!("Failed to download {}: {}", file_info.path, e)
@@ -408,13 +414,13 @@ async fn process_single_file(
     
     let download_elapsed = download_start.elapsed();
     let download_speed = if download_elapsed.as_secs_f64() > 0.0 {
-        file_data.len() as f64 / download_elapsed.as_secs_f64() / 1024.0 // KB/s
+        download.len() as f64 / download_elapsed.as_secs_f64() / 1024.0 // KB/s
     } else {
         0.0
     };
     
     debug!("[{}] ⬇️ Downloaded file: '{}' ({} bytes in {:.2}s, {:.1} KB/s)", 
-           file_request_id, file_info.name, file_data.len(), 
+           file_request_id, file_info.name, download.len(), 
            download_elapsed.as_secs_f64(), download_speed);
     
     // Use the unified ingestion service for consistent deduplication
@@ -423,9 +429,9 @@ async fn process_single_file(
     
     let result = if let Some(source_id) = webdav_source_id {
         ingestion_service
-            .ingest_from_file_info(
+            .ingest_download_from_file_info(
                 &file_info,
-                file_data,
+                download,
                 user_id,
                 crate::ingestion::document_ingestion::DeduplicationPolicy::TrackAsDuplicate,
                 "webdav_sync",
@@ -435,9 +441,9 @@ async fn process_single_file(
     } else {
         // Fallback for backward compatibility - treat as generic WebDAV sync
         ingestion_service
-            .ingest_from_file_info(
+            .ingest_download_from_file_info(
                 &file_info,
-                file_data,
+                download,
                 user_id,
                 crate::ingestion::document_ingestion::DeduplicationPolicy::Skip,
                 "webdav_sync",
@@ -497,6 +503,35 @@ async fn process_single_file(
             
             (existing_doc, false, "duplicate_content") // Track as duplicate
         }
+        IngestionResult::Quarantined { failed_document_id, threat } => {
+            warn!("[{}] 🛑 Quarantined '{}' ({}), recorded as failed document {}",
+                  file_request_id, file_info.name, threat, failed_document_id);
+
+            // Track the file so unchanged infected files aren't downloaded again every sync
+            let webdav_file = CreateWebDAVFile {
+                user_id,
+                webdav_path: file_info.path.clone(),
+                etag: file_info.etag.clone(),
+                last_modified: file_info.last_modified,
+                file_size: file_info.size,
+                mime_type: file_info.mime_type.clone(),
+                document_id: None,
+                sync_status: "quarantined".to_string(),
+                sync_error: Some(format!("Malware detected: {}", threat)),
+            };
+            if let Err(e) = state.db.create_or_update_webdav_file(&webdav_file).await {
+                error!("[{}] Failed to record quarantined WebDAV file '{}': {}",
+                       file_request_id, file_info.path, e);
+            }
+
+            return Ok(false);
+        }
+        IngestionResult::Corrupt { failed_document_id, reason } => {
+            // Not tracked, so a file cut off mid-download is fetched again next sync
+            warn!("[{}] 🩹 '{}' is corrupt ({}), recorded as failed document {}",
+                  file_request_id, file_info.name, reason, failed_document_id);
+            return Ok(false);
+        }
     };
 
     // Record WebDAV file in tracking table
@@ -532,7 +567,7 @@ async fn process_single_file(
         else if file_info.size <= 50 * 1024 * 1024 { 4 } // ≤ 50MB: Low priority
         else { 2 }; // > 50MB: Lowest priority
         
-        if let Err(e) = state.queue_service.enqueue_document(document.id, priority, file_info.size).await {
+        if let Err(e) = state.queue_service.enqueue_document_with_class(document.id, OcrPriorityClass::Backfill, priority, file_info.size).await {
             error!("[{}] Failed to enqueue document {} for OCR: {}", 
                    file_request_id, document.id, e);
         } else {
@@ -12,9 +12,10 @@ use sqlx::Row;
 use crate::{
     AppState,
     models::{SourceType, LocalFolderSourceConfig, S3SourceConfig, WebDAVSourceConfig},
+    models::{Source, SourceCircuitBreakerConfig, SourceCircuitState},
     models::source::WebDAVTestConnection,
 };
-use super::source_sync::SourceSyncService;
+use super::source_sync::{retry_transient_failures, SourceSyncService};
 
 struct SyncHealthAnalysis {
     score_penalty: i32,
@@ -55,13 +56,16 @@ impl SourceScheduler {
         }
         
         let mut interval_timer = interval(self.check_interval);
+        // The first check runs right away and also syncs the sources flagged to sync on startup
+        let mut startup = true;
         
         loop {
             interval_timer.tick().await;
             
-            if let Err(e) = self.check_and_sync_sources().await {
+            if let Err(e) = self.check_and_sync_sources(startup).await {
                 error!("Error in source sync scheduler: {}", e);
             }
+            startup = false;
             
             // Run periodic validation checks for all sources
             if let Err(e) = self.run_periodic_validations().await {
@@ -117,9 +121,13 @@ impl SourceScheduler {
         Ok(())
     }
 
-    async fn check_and_sync_sources(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// Starts the syncs of sources that are due. On `startup`, sources with
+    /// `sync_on_startup` are due regardless of their interval. Returns the ids
+    /// of the sources whose sync was started.
+    pub async fn check_and_sync_sources(&self, startup: bool) -> Result<Vec<Uuid>, Box<dyn std::error::Error + Send + Sync>> {
         // Get all sources that might need syncing
         let sources = self.state.db.get_sources_for_sync().await?;
+        let mut started = Vec::new();
         
         for source in sources {
             // Skip sources that are already in error status due to configuration issues
@@ -153,8 +161,43 @@ impl SourceScheduler {
                 continue;
             }
             
+            // A sync waiting to retry a transient failure is still running,
+            // although the source shows the error status meanwhile
+            if self.running_syncs.read().await.contains_key(&source.id) {
+                continue;
+            }
+
             // Check if sync is due for this source
-            if self.is_sync_due(&source).await? {
+            let due = if startup && Self::syncs_on_startup(&source) {
+                info!("Source {} syncs on startup", source.name);
+                source.status != crate::models::SourceStatus::Syncing
+            } else {
+                self.is_sync_due(&source).await?
+            };
+            if due {
+                // Skip sources whose circuit breaker is open after repeated failures
+                // A database error for one source must not stop the others from syncing
+                let mut circuit = match self.state.db.get_source_circuit(source.id).await {
+                    Ok(circuit) => circuit,
+                    Err(e) => {
+                        error!("Failed to load circuit breaker state of source {}: {}", source.name, e);
+                        continue;
+                    }
+                };
+                let was_open = circuit.state == SourceCircuitState::Open;
+                if !circuit.allow_scheduled_sync(Utc::now()) {
+                    crate::debug_log!("SOURCE_SCHEDULER", "Skipping scheduled sync of source {}: circuit open until {:?}",
+                        source.name, circuit.cooldown_until);
+                    continue;
+                }
+                if was_open {
+                    info!("Cooldown of source {} ended, testing whether it recovered", source.name);
+                    if let Err(e) = self.state.db.save_source_circuit(&circuit).await {
+                        error!("Failed to save circuit breaker state of source {}: {}", source.name, e);
+                        continue;
+                    }
+                }
+
                 info!("Starting background sync for source: {} ({})", source.name, source.source_type);
                 
                 let sync_service = self.sync_service.clone();
@@ -181,12 +224,21 @@ impl SourceScheduler {
                     progress.set_phase(crate::services::webdav::SyncPhase::Initializing);
                     state_clone.sync_progress_tracker.register_sync(source.id, progress.clone());
                     
-                    // Pass cancellation token to sync service
-                    match sync_service.sync_source_with_cancellation(&source_clone, enable_background_ocr, cancellation_token.clone()).await {
+                    // A sync that failed because the source was unreachable is retried
+                    // before waiting for the next interval
+                    let sync_result = retry_transient_failures(
+                        state_clone.config.sync_transient_retries,
+                        Duration::from_secs(state_clone.config.sync_transient_retry_delay_seconds),
+                        &cancellation_token,
+                        || sync_service.sync_source_with_cancellation(&source_clone, enable_background_ocr, cancellation_token.clone()),
+                    ).await;
+                    match sync_result {
                         Ok(files_processed) => {
                             info!("Background sync completed for source {}: {} files processed", 
                                   source_clone.name, files_processed);
                             
+                            Self::record_sync_for_circuit(&state_clone, &source_clone, None).await;
+                            
                             // Perform automatic validation check after sync completion
                             if let Err(e) = Self::validate_source_health(&source_clone, &state_clone).await {
                                 error!("Failed to perform validation check: {}", e);
@@ -222,7 +274,7 @@ impl SourceScheduler {
                                     })),
                                 };
                                 
-                                if let Err(e) = state_clone.db.create_notification(source_clone.user_id, &notification).await {
+                                if let Err(e) = state_clone.db.deliver_notification(source_clone.user_id, &notification).await {
                                     error!("Failed to create success notification: {}", e);
                                 }
                             }
@@ -230,6 +282,8 @@ impl SourceScheduler {
                         Err(e) => {
                             error!("Background sync failed for source {}: {}", source_clone.name, e);
                             
+                            Self::record_sync_for_circuit(&state_clone, &source_clone, Some(&e.to_string())).await;
+                            
                             // Send error notification
                             let notification = crate::models::CreateNotification {
                                 notification_type: "error".to_string(),
@@ -243,7 +297,7 @@ impl SourceScheduler {
                                 })),
                             };
                             
-                            if let Err(e) = state_clone.db.create_notification(source_clone.user_id, &notification).await {
+                            if let Err(e) = state_clone.db.deliver_notification(source_clone.user_id, &notification).await {
                                 error!("Failed to create error notification: {}", e);
                             }
                         }
@@ -256,10 +310,24 @@ impl SourceScheduler {
                     }
                     state_clone.sync_progress_tracker.unregister_sync(source_clone.id);
                 });
+                started.push(source.id);
             }
         }
 
-        Ok(())
+        Ok(started)
+    }
+
+    /// Whether the source is flagged to sync as soon as the scheduler starts
+    fn syncs_on_startup(source: &Source) -> bool {
+        match source.source_type {
+            SourceType::WebDAV => serde_json::from_value::<WebDAVSourceConfig>(source.config.clone())
+                .map(|config| config.sync_on_startup),
+            SourceType::LocalFolder => serde_json::from_value::<LocalFolderSourceConfig>(source.config.clone())
+                .map(|config| config.sync_on_startup),
+            SourceType::S3 => serde_json::from_value::<S3SourceConfig>(source.config.clone())
+                .map(|config| config.sync_on_startup),
+        }
+        .unwrap_or(false)
     }
 
     async fn is_sync_due(&self, source: &crate::models::Source) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
@@ -319,6 +387,24 @@ impl SourceScheduler {
 
     pub async fn trigger_sync(&self, source_id: uuid::Uuid) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("Triggering manual sync for source {}", source_id);
+        self.start_manual_sync(source_id, None).await
+    }
+
+    /// Starts a sync that only downloads and stores `failed_files` again
+    pub async fn trigger_retry_failed(
+        &self,
+        source_id: Uuid,
+        failed_files: Vec<crate::models::FileIngestionInfo>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        info!("Triggering retry of {} failed files for source {}", failed_files.len(), source_id);
+        self.start_manual_sync(source_id, Some(failed_files)).await
+    }
+
+    async fn start_manual_sync(
+        &self,
+        source_id: Uuid,
+        retry_files: Option<Vec<crate::models::FileIngestionInfo>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         
         // Check if sync is already running
         {
@@ -357,7 +443,9 @@ impl SourceScheduler {
             running_syncs.insert(source_id, cancellation_token.clone());
         }
         
-        tokio::spawn(async move {
+        // The sync keeps the id of the request that started it, so its logs, its
+        // WebDAV discovery and the OCR jobs it queues can be traced back to it
+        tokio::spawn(crate::logging::in_current_request(async move {
             let enable_background_ocr = true; // Could be made configurable
             
             // Create progress tracker for this sync and register it
@@ -375,10 +463,17 @@ impl SourceScheduler {
                 state_clone.sync_progress_tracker.unregister_sync(source_id);
             };
             
+            let sync_operation = async {
+                match retry_files {
+                    Some(files) => sync_service.retry_failed_files_with_cancellation(&source, enable_background_ocr, cancellation_token, files).await,
+                    None => sync_service.sync_source_with_cancellation(&source, enable_background_ocr, cancellation_token).await,
+                }
+            };
+
             // Execute the sync operation with a timeout to prevent hanging
             let sync_result = tokio::time::timeout(
                 std::time::Duration::from_secs(300), // 5 minute timeout for sync operations
-                sync_service.sync_source_with_cancellation(&source, enable_background_ocr, cancellation_token)
+                sync_operation
             ).await;
             
             match sync_result {
@@ -386,6 +481,8 @@ impl SourceScheduler {
                     info!("Manual sync completed for source {}: {} files processed", 
                           source.name, files_processed);
                     
+                    Self::record_sync_for_circuit(&state_clone, &source, None).await;
+                    
                     // Atomically complete the sync
                     if let Err(e) = state_clone.db.complete_sync_atomic(
                         source_id, 
@@ -406,6 +503,8 @@ impl SourceScheduler {
                 Ok(Err(e)) => {
                     error!("Manual sync failed for source {}: {}", source.name, e);
                     
+                    Self::record_sync_for_circuit(&state_clone, &source, Some(&e.to_string())).await;
+                    
                     // Atomically mark sync as failed
                     if let Err(complete_err) = state_clone.db.complete_sync_atomic(
                         source_id, 
@@ -450,11 +549,73 @@ impl SourceScheduler {
             }
             
             cleanup().await;
-        });
+        }));
         
         Ok(())
     }
 
+    /// Updates the source's circuit breaker with the result of a sync (`error`
+    /// is None when it succeeded) and notifies the owner when the circuit opens
+    async fn record_sync_for_circuit(state: &Arc<AppState>, source: &Source, error: Option<&str>) {
+        let mut circuit = match state.db.get_source_circuit(source.id).await {
+            Ok(circuit) => circuit,
+            Err(e) => {
+                error!("Failed to load circuit breaker of source {}: {}", source.name, e);
+                return;
+            }
+        };
+
+        let opened = match error {
+            None => {
+                if circuit.state != SourceCircuitState::Closed {
+                    info!("Source {} recovered, closing its circuit breaker", source.name);
+                } else if circuit.consecutive_failures == 0 {
+                    return;
+                }
+                circuit.record_success();
+                false
+            }
+            Some(_) => {
+                let config = SourceCircuitBreakerConfig::from_source_config(&source.config).unwrap_or_default();
+                circuit.record_failure(&config, Utc::now())
+            }
+        };
+
+        if let Err(e) = state.db.save_source_circuit(&circuit).await {
+            error!("Failed to save circuit breaker of source {}: {}", source.name, e);
+            return;
+        }
+
+        if opened {
+            let cooldown_until = circuit.cooldown_until.unwrap_or_else(Utc::now);
+            warn!("Circuit breaker of source {} opened after {} consecutive failed syncs; skipping scheduled syncs until {}",
+                  source.name, circuit.consecutive_failures, cooldown_until);
+
+            let notification = crate::models::CreateNotification {
+                notification_type: "warning".to_string(),
+                title: "Source Sync Paused".to_string(),
+                message: format!(
+                    "{} failed to sync {} times in a row. Scheduled syncs are paused until {}.",
+                    source.name,
+                    circuit.consecutive_failures,
+                    cooldown_until.format("%Y-%m-%d %H:%M UTC")
+                ),
+                action_url: Some("/sources".to_string()),
+                metadata: Some(serde_json::json!({
+                    "source_type": source.source_type.to_string(),
+                    "source_id": source.id,
+                    "consecutive_failures": circuit.consecutive_failures,
+                    "cooldown_until": cooldown_until,
+                    "error": error
+                })),
+            };
+
+            if let Err(e) = state.db.deliver_notification(source.user_id, &notification).await {
+                error!("Failed to create circuit breaker notification: {}", e);
+            }
+        }
+    }
+
     pub async fn stop_sync(&self, source_id: Uuid) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("Stopping sync for source {}", source_id);
         
@@ -528,6 +689,8 @@ impl SourceScheduler {
     fn validate_source_config(&self, source: &crate::models::Source) -> Result<(), String> {
         use crate::models::{SourceType, WebDAVSourceConfig, S3SourceConfig, LocalFolderSourceConfig};
         
+        SourceCircuitBreakerConfig::from_source_config(&source.config)?;
+        
         match source.source_type {
             SourceType::WebDAV => {
                 // Attempt to deserialize WebDAV config
@@ -729,7 +892,7 @@ impl SourceScheduler {
                 })),
             };
             
-            if let Err(e) = state.db.create_notification(source.user_id, &notification).await {
+            if let Err(e) = state.db.deliver_notification(source.user_id, &notification).await {
                 error!("Failed to create deep scan notification: {}", e);
             }
             
@@ -745,8 +908,14 @@ impl SourceScheduler {
                     file_extensions: webdav_config.file_extensions.clone(),
                     timeout_seconds: 600, // 10 minutes for deep scan
                     server_type: webdav_config.server_type.clone(),
+                    max_scan_depth: webdav_config.max_scan_depth,
+                    detect_etag_aliases: webdav_config.detect_etag_aliases,
+                    user_agent: webdav_config.user_agent.clone(),
                 }
-            )?;
+            )?
+            .with_propfind_depths(webdav_config.propfind_depths)
+            .with_download_verification(webdav_config.download_verification)
+            .with_max_directory_visits(webdav_config.max_directory_visits);
             
             // Run smart deep scan in background
             let source_clone = source.clone();
@@ -831,7 +1000,7 @@ impl SourceScheduler {
                             })),
                         };
                         
-                        if let Err(e) = state_clone.db.create_notification(source_clone.user_id, &notification).await {
+                        if let Err(e) = state_clone.db.deliver_notification(source_clone.user_id, &notification).await {
                             error!("Failed to create success notification: {}", e);
                         }
             });
@@ -969,7 +1138,7 @@ impl SourceScheduler {
                 })),
             };
 
-            if let Err(e) = state.db.create_notification(source.user_id, &notification).await {
+            if let Err(e) = state.db.deliver_notification(source.user_id, &notification).await {
                 error!("Failed to create validation notification: {}", e);
             }
         }
@@ -1032,6 +1201,9 @@ impl SourceScheduler {
             file_extensions: config.file_extensions.clone(),
             timeout_seconds: 30, // Quick connectivity test
             server_type: config.server_type.clone(),
+            max_scan_depth: config.max_scan_depth,
+            detect_etag_aliases: config.detect_etag_aliases,
+            user_agent: config.user_agent.clone(),
         };
 
         let webdav_service = crate::services::webdav::WebDAVService::new(webdav_config)
@@ -1,21 +1,212 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
 use anyhow::{anyhow, Result};
-use tokio::sync::Semaphore;
+use chrono::{DateTime, Utc};
 use tokio_util::sync::CancellationToken;
-use futures::stream::{FuturesUnordered, StreamExt};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::{
     AppState,
-    models::{FileIngestionInfo, Source, SourceType, SourceStatus, LocalFolderSourceConfig, S3SourceConfig, WebDAVSourceConfig},
+    models::{
+        FileIngestionInfo, Source, SourceType, SourceStatus, LocalFolderSourceConfig, S3SourceConfig, WebDAVSourceConfig, source_queues_ocr,
+        ErrorContext, ErrorSourceType, SourceClockSkew, SourceFileVersion, SourceSyncRun, SyncFailureKind, SyncFailureStage, SyncFileFailure, SyncOutcome,
+    },
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
-    services::local_folder_service::LocalFolderService,
+    ingestion::pipeline::{IngestionPipeline, PipelineSummary},
+    ingestion::spool::{DownloadedFile, SpoolPolicy},
+    ocr::queue::OcrPriorityClass,
+    services::local_folder_error_classifier::LocalFolderErrorClassifier,
+    services::local_folder_service::{LocalFolderService, FILE_ID_METADATA_KEY},
+    services::s3_error_classifier::S3ErrorClassifier,
     services::s3_service::S3Service,
-    services::webdav::{WebDAVService, WebDAVConfig, SyncProgress, SyncPhase},
+    services::source_error_tracker::SourceErrorTracker,
+    services::webdav::{clock_skew::describe_skew, ConditionalDownload, WebDAVService, WebDAVConfig, SyncProgress, SyncPhase},
 };
 
+/// Error of a sync that failed as a whole because the source couldn't be
+/// reached, and may succeed when run again shortly
+#[derive(Debug)]
+pub struct TransientSyncFailure(pub String);
+
+impl std::fmt::Display for TransientSyncFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TransientSyncFailure {}
+
+/// Runs `sync`, and runs it again while it fails with a `TransientSyncFailure`,
+/// up to `retries` times with a delay starting at `base_delay` and doubling
+/// each time. Other errors are returned at once.
+pub async fn retry_transient_failures<F, Fut>(
+    retries: u32,
+    base_delay: Duration,
+    cancellation_token: &CancellationToken,
+    mut sync: F,
+) -> Result<usize>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<usize>>,
+{
+    let mut attempt = 0;
+    loop {
+        let error = match sync().await {
+            Err(e) if attempt < retries && e.downcast_ref::<TransientSyncFailure>().is_some() => e,
+            result => return result,
+        };
+
+        let delay = base_delay.saturating_mul(2u32.saturating_pow(attempt));
+        attempt += 1;
+        warn!("Sync failed transiently ({}), retrying in {}s (retry {} of {})", error, delay.as_secs(), attempt, retries);
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancellation_token.cancelled() => return Err(error),
+        }
+    }
+}
+
+/// Orders files by modification time, newest first. Files without one keep their
+/// discovery order after all dated files.
+pub fn sort_newest_first(files: &mut [FileIngestionInfo]) {
+    files.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+}
+
+/// OCR queue priority for a synced file. Small files normally go first; in
+/// newest-first syncs the file's age decides, so recent documents are OCR'd
+/// before the backfill.
+pub fn ocr_priority_for_file(file_info: &FileIngestionInfo, newest_first: bool, now: DateTime<Utc>) -> i32 {
+    if newest_first {
+        let Some(last_modified) = file_info.last_modified else {
+            return 2;
+        };
+        let age_days = (now - last_modified).num_days();
+        return if age_days <= 1 { 10 }
+        else if age_days <= 7 { 8 }
+        else if age_days <= 30 { 6 }
+        else if age_days <= 365 { 4 }
+        else { 2 };
+    }
+
+    if file_info.size <= 1024 * 1024 { 10 }
+    else if file_info.size <= 5 * 1024 * 1024 { 8 }
+    else if file_info.size <= 10 * 1024 * 1024 { 6 }
+    else if file_info.size <= 50 * 1024 * 1024 { 4 }
+    else { 2 }
+}
+
+/// Folders holding the given files, in the order they first appear
+pub fn parent_folders(files: &[FileIngestionInfo]) -> Vec<String> {
+    let mut folders: Vec<String> = Vec::new();
+    for file_info in files {
+        let folder = parent_folder(&file_info.relative_path);
+        if !folders.contains(&folder) {
+            folders.push(folder);
+        }
+    }
+    folders
+}
+
+fn parent_folder(path: &str) -> String {
+    Path::new(path)
+        .parent()
+        .map(|parent| parent.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Files and folders that failed during one sync, classified as they fail
+struct SyncFailureLog {
+    source_id: Uuid,
+    source_type: ErrorSourceType,
+    error_tracker: SourceErrorTracker,
+    failures: Mutex<Vec<SyncFileFailure>>,
+}
+
+impl SyncFailureLog {
+    fn new(state: &AppState, source: &Source) -> Self {
+        let mut error_tracker = SourceErrorTracker::new(state.db.clone());
+        let source_type = match source.source_type {
+            SourceType::WebDAV => ErrorSourceType::WebDAV,
+            SourceType::LocalFolder => {
+                error_tracker.register_classifier(Arc::new(LocalFolderErrorClassifier::new()));
+                ErrorSourceType::Local
+            }
+            SourceType::S3 => {
+                error_tracker.register_classifier(Arc::new(S3ErrorClassifier::new()));
+                ErrorSourceType::S3
+            }
+        };
+        Self {
+            source_id: source.id,
+            source_type,
+            error_tracker,
+            failures: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn record_folder(&self, folder_path: &str, error: &anyhow::Error) {
+        self.record(folder_path, SyncFailureStage::Discovery, None, error);
+    }
+
+    fn record_file(&self, stage: SyncFailureStage, file_info: &FileIngestionInfo, error: &anyhow::Error) {
+        self.record(&file_info.relative_path, stage, Some(file_info), error);
+    }
+
+    fn record(&self, path: &str, stage: SyncFailureStage, file_info: Option<&FileIngestionInfo>, error: &anyhow::Error) {
+        let operation = match stage {
+            SyncFailureStage::Discovery => "list_directory",
+            SyncFailureStage::Download => "read_file",
+            SyncFailureStage::Store => "store_file",
+        };
+        let context = ErrorContext::new(path.to_string())
+            .with_source_id(self.source_id)
+            .with_operation(operation.to_string());
+        let classification = self.error_tracker.classify_error(self.source_type, error, &context);
+
+        let failure = SyncFileFailure {
+            path: path.to_string(),
+            stage,
+            error_category: classification.error_type,
+            error_message: error.to_string(),
+            failed_at: Utc::now(),
+            name: file_info.map(|f| f.name.clone()).unwrap_or_default(),
+            size: file_info.map(|f| f.size).unwrap_or_default(),
+            mime_type: file_info.map(|f| f.mime_type.clone()).unwrap_or_default(),
+            last_modified: file_info.and_then(|f| f.last_modified),
+        };
+        self.failures.lock().unwrap().push(failure);
+    }
+
+    fn into_failures(self) -> Vec<SyncFileFailure> {
+        self.failures.into_inner().unwrap()
+    }
+}
+
+/// What storing a downloaded file did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StoredFile {
+    /// A new document was created
+    Added,
+    /// A new document was created for a file stored by an earlier sync
+    Updated,
+    /// The file matched a document that already exists
+    Existing,
+    /// The file was a duplicate or was quarantined
+    NotStored,
+}
+
+/// Per-file counts of a sync run, shared by the pipeline stages
+#[derive(Default)]
+struct SyncRunCounts {
+    files_added: AtomicI64,
+    files_updated: AtomicI64,
+    files_skipped: AtomicI64,
+    bytes_transferred: AtomicI64,
+}
+
 #[derive(Clone)]
 pub struct SourceSyncService {
     state: Arc<AppState>,
@@ -34,7 +225,33 @@ impl SourceSyncService {
 
     /// Perform sync for any source type with cancellation support
     pub async fn sync_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
-        info!("Starting sync for source {} ({})", source.name, source.source_type);
+        self.run_sync(source, enable_background_ocr, cancellation_token, None).await
+    }
+
+    /// Downloads and stores only the given files, which failed in an earlier sync,
+    /// instead of listing the source's watch folders
+    pub async fn retry_failed_files_with_cancellation(
+        &self,
+        source: &Source,
+        enable_background_ocr: bool,
+        cancellation_token: CancellationToken,
+        failed_files: Vec<FileIngestionInfo>,
+    ) -> Result<usize> {
+        self.run_sync(source, enable_background_ocr, cancellation_token, Some(failed_files)).await
+    }
+
+    #[tracing::instrument(name = "source_sync", skip_all, fields(source_id = %source.id, user_id = %source.user_id))]
+    async fn run_sync(
+        &self,
+        source: &Source,
+        enable_background_ocr: bool,
+        cancellation_token: CancellationToken,
+        retry_files: Option<Vec<FileIngestionInfo>>,
+    ) -> Result<usize> {
+        match &retry_files {
+            Some(files) => info!("Retrying {} failed files for source {} ({})", files.len(), source.name, source.source_type),
+            None => info!("Starting sync for source {} ({})", source.name, source.source_type),
+        }
 
         // Check for cancellation before starting
         if cancellation_token.is_cancelled() {
@@ -48,9 +265,9 @@ impl SourceSyncService {
         }
 
         let sync_result = match source.source_type {
-            SourceType::WebDAV => self.sync_webdav_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone()).await,
-            SourceType::LocalFolder => self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone()).await,
-            SourceType::S3 => self.sync_s3_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone()).await,
+            SourceType::WebDAV => self.sync_webdav_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), retry_files).await,
+            SourceType::LocalFolder => self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), retry_files).await,
+            SourceType::S3 => self.sync_s3_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), retry_files).await,
         };
 
         match &sync_result {
@@ -89,10 +306,16 @@ impl SourceSyncService {
     }
 
     async fn sync_webdav_source(&self, source: &Source, enable_background_ocr: bool) -> Result<usize> {
-        self.sync_webdav_source_with_cancellation(source, enable_background_ocr, CancellationToken::new()).await
+        self.sync_webdav_source_with_cancellation(source, enable_background_ocr, CancellationToken::new(), None).await
     }
 
-    async fn sync_webdav_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
+    async fn sync_webdav_source_with_cancellation(
+        &self,
+        source: &Source,
+        enable_background_ocr: bool,
+        cancellation_token: CancellationToken,
+        retry_files: Option<Vec<FileIngestionInfo>>,
+    ) -> Result<usize> {
         let config: WebDAVSourceConfig = serde_json::from_value(source.config.clone())
             .map_err(|e| anyhow!("Invalid WebDAV config: {}", e))?;
 
@@ -109,26 +332,47 @@ impl SourceSyncService {
             file_extensions: config.file_extensions,
             timeout_seconds: 180, // 3 minutes for discover_files_in_folder operations
             server_type: config.server_type,
+            max_scan_depth: config.max_scan_depth,
+            detect_etag_aliases: config.detect_etag_aliases,
+            user_agent: config.user_agent,
         };
 
         let webdav_service = WebDAVService::new(webdav_config.clone())
-            .map_err(|e| anyhow!("Failed to create WebDAV service: {}", e))?;
+            .map_err(|e| anyhow!("Failed to create WebDAV service: {}", e))?
+            .with_propfind_depths(config.propfind_depths)
+            .with_download_verification(config.download_verification)
+            .with_max_directory_visits(config.max_directory_visits);
 
         info!("WebDAV service created successfully, starting sync with {} folders", webdav_config.watch_folders.len());
 
+        self.record_clock_skew(source, &webdav_service).await;
+
+        // Re-checks the server's capabilities once they are stale or the server was upgraded
+        if let Err(e) = webdav_service.capabilities().await {
+            warn!("Failed to check the capabilities of WebDAV source '{}': {}", source.name, e);
+        }
+
         // Create progress tracker for scheduled sync and register it globally
         let progress = Arc::new(SyncProgress::new());
         progress.set_phase(SyncPhase::Initializing);
         self.state.sync_progress_tracker.register_sync(source.id, progress.clone());
         info!("🚀 Starting scheduled WebDAV sync with progress tracking for source '{}'", source.name);
 
+        let spool_policy = SpoolPolicy::new(
+            self.state.config.webdav_stream_threshold_mb,
+            self.state.file_service.get_temp_path(),
+        );
+
         let sync_result = self.perform_sync_internal_with_cancellation(
-            source.user_id,
-            source.id,
+            source,
             &webdav_config.watch_folders,
             &webdav_config.file_extensions,
             enable_background_ocr,
+            source_queues_ocr(config.ocr_enabled, config.processing_mode),
+            config.sync_newest_first,
+            config.conditional_downloads,
             cancellation_token,
+            retry_files,
             |folder_path| {
                 let service = webdav_service.clone();
                 let state_clone = self.state.clone();
@@ -161,15 +405,31 @@ impl SourceSyncService {
             |file_path| {
                 let service = webdav_service.clone();
                 let progress = progress.clone(); // Clone progress for the async closure
+                let spool_policy = spool_policy.clone();
+                let state = self.state.clone();
+                let source_id = source.id;
+                let conditional_downloads = config.conditional_downloads;
                 async move { 
                     debug!("WebDAV download_file called for: {}", file_path);
                     progress.set_current_file(Some(&file_path));
-                    let result = service.download_file(&file_path).await;
+
+                    // A file stored by an earlier sync is only downloaded again if it changed
+                    let known = if conditional_downloads {
+                        state.db.get_source_file_version(source_id, &file_path).await.unwrap_or_else(|e| {
+                            warn!("Failed to look up the stored version of {}, downloading it: {}", file_path, e);
+                            None
+                        })
+                    } else {
+                        None
+                    };
+
+                    let result = service.download_file_spooled_if_changed(&file_path, known.as_ref(), &spool_policy).await;
                     match &result {
-                        Ok(data) => debug!("WebDAV downloaded {} bytes for file: {}", data.len(), file_path),
+                        Ok(ConditionalDownload::Modified(download)) => debug!("WebDAV downloaded {} bytes for file: {}", download.len(), file_path),
+                        Ok(ConditionalDownload::Unchanged) => debug!("WebDAV file unchanged since last sync: {}", file_path),
                         Err(e) => error!("WebDAV download failed for file {}: {}", file_path, e),
                     }
-                    result
+                    result.map(ConditionalDownload::into_modified)
                 }
             }
         ).await;
@@ -188,14 +448,59 @@ impl SourceSyncService {
         // Always unregister the progress tracker to prevent memory leaks
         self.state.sync_progress_tracker.unregister_sync(source.id);
 
+        // Keep this sync's directory listings for the next run, across restarts
+        if let Err(e) = webdav_service.persist_discovery_cache().await {
+            warn!("Failed to save the WebDAV discovery cache: {:#}", e);
+        }
+
         sync_result
     }
 
+    /// Measures the skew between the source's clock and ours and records it on
+    /// the source, warning when it exceeds the configured threshold
+    async fn record_clock_skew(&self, source: &Source, service: &WebDAVService) {
+        let skew = match service.measure_clock_skew().await {
+            Ok(Some(skew)) => skew,
+            Ok(None) => {
+                debug!("WebDAV source '{}' sent no Date header; clock skew unknown", source.name);
+                return;
+            }
+            Err(e) => {
+                warn!("Failed to measure the clock skew of WebDAV source '{}': {}", source.name, e);
+                return;
+            }
+        };
+
+        let exceeds_threshold = service.clock_skew_settings().exceeds_warning_threshold(skew);
+        if exceeds_threshold {
+            warn!(
+                "⏰ Clock of WebDAV source '{}' is {} of ours; modification times may be misjudged",
+                source.name, describe_skew(skew)
+            );
+        }
+
+        let record = SourceClockSkew {
+            source_id: source.id,
+            skew_seconds: skew.num_seconds(),
+            exceeds_threshold,
+            measured_at: Utc::now(),
+        };
+        if let Err(e) = self.state.db.save_source_clock_skew(&record).await {
+            warn!("Failed to record the clock skew of source '{}': {}", source.name, e);
+        }
+    }
+
     async fn sync_local_folder_source(&self, source: &Source, enable_background_ocr: bool) -> Result<usize> {
-        self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, CancellationToken::new()).await
+        self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, CancellationToken::new(), None).await
     }
 
-    async fn sync_local_folder_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
+    async fn sync_local_folder_source_with_cancellation(
+        &self,
+        source: &Source,
+        enable_background_ocr: bool,
+        cancellation_token: CancellationToken,
+        retry_files: Option<Vec<FileIngestionInfo>>,
+    ) -> Result<usize> {
         let config: LocalFolderSourceConfig = serde_json::from_value(source.config.clone())
             .map_err(|e| anyhow!("Invalid LocalFolder config: {}", e))?;
 
@@ -209,19 +514,30 @@ impl SourceSyncService {
         info!("🚀 Starting local folder sync with progress tracking for source '{}'", source.name);
 
         let sync_result = self.perform_sync_internal_with_cancellation(
-            source.user_id,
-            source.id,
+            source,
             &config.watch_folders,
             &config.file_extensions,
             enable_background_ocr,
+            source_queues_ocr(config.ocr_enabled, config.processing_mode),
+            config.sync_newest_first,
+            false,
             cancellation_token,
+            retry_files,
             |folder_path| {
                 let service = local_service.clone();
-                async move { service.discover_files_in_folder(&folder_path).await }
+                let state = self.state.clone();
+                let track_file_ids = config.track_file_ids;
+                async move {
+                    let files = service.discover_files_in_folder(&folder_path).await?;
+                    if track_file_ids {
+                        Self::reconcile_moved_files(&state, source.id, &files).await;
+                    }
+                    Ok(files)
+                }
             },
             |file_path| {
                 let service = local_service.clone();
-                async move { service.read_file(&file_path).await }
+                async move { service.read_file(&file_path).await.map(|data| Some(DownloadedFile::InMemory(data))) }
             }
         ).await;
         
@@ -237,11 +553,64 @@ impl SourceSyncService {
         sync_result
     }
 
+    /// Points documents at the new paths of files renamed or moved since they
+    /// were stored, recognized by file id, so the sync finds them unchanged
+    /// instead of storing them again. A document whose old path still exists,
+    /// such as a hard link, keeps it.
+    async fn reconcile_moved_files(state: &AppState, source_id: Uuid, files: &[FileIngestionInfo]) {
+        let file_ids: Vec<String> = files
+            .iter()
+            .filter_map(|file| file.metadata.as_ref()?.get(FILE_ID_METADATA_KEY)?.as_str().map(str::to_string))
+            .collect();
+        if file_ids.is_empty() {
+            return;
+        }
+
+        let stored = match state.db.find_source_documents_by_file_ids(source_id, &file_ids).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                warn!("Failed to look up files of source {} by file id: {}", source_id, e);
+                return;
+            }
+        };
+
+        for file in files {
+            let Some(file_id) = file.metadata.as_ref().and_then(|m| m.get(FILE_ID_METADATA_KEY)).and_then(|id| id.as_str()) else {
+                continue;
+            };
+            let Some((document_id, old_path)) = stored.get(file_id) else {
+                continue;
+            };
+            if old_path.as_deref() == Some(file.relative_path.as_str()) {
+                continue;
+            }
+            if let Some(old_path) = old_path {
+                if tokio::fs::try_exists(old_path).await.unwrap_or(true) {
+                    continue;
+                }
+            }
+
+            match state.db.move_source_document(*document_id, &file.relative_path, &file.name).await {
+                Ok(()) => info!(
+                    "File {} was moved to {}, updated document {}",
+                    old_path.as_deref().unwrap_or("(unknown)"), file.relative_path, document_id
+                ),
+                Err(e) => warn!("Failed to update the path of document {} to {}: {}", document_id, file.relative_path, e),
+            }
+        }
+    }
+
     async fn sync_s3_source(&self, source: &Source, enable_background_ocr: bool) -> Result<usize> {
-        self.sync_s3_source_with_cancellation(source, enable_background_ocr, CancellationToken::new()).await
+        self.sync_s3_source_with_cancellation(source, enable_background_ocr, CancellationToken::new(), None).await
     }
 
-    async fn sync_s3_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
+    async fn sync_s3_source_with_cancellation(
+        &self,
+        source: &Source,
+        enable_background_ocr: bool,
+        cancellation_token: CancellationToken,
+        retry_files: Option<Vec<FileIngestionInfo>>,
+    ) -> Result<usize> {
         let config: S3SourceConfig = serde_json::from_value(source.config.clone())
             .map_err(|e| anyhow!("Invalid S3 config: {}", e))?;
 
@@ -255,19 +624,22 @@ impl SourceSyncService {
         info!("🚀 Starting S3 sync with progress tracking for source '{}'", source.name);
 
         let sync_result = self.perform_sync_internal_with_cancellation(
-            source.user_id,
-            source.id,
+            source,
             &config.watch_folders,
             &config.file_extensions,
             enable_background_ocr,
+            source_queues_ocr(config.ocr_enabled, config.processing_mode),
+            config.sync_newest_first,
+            false,
             cancellation_token,
+            retry_files,
             |folder_path| {
                 let service = s3_service.clone();
                 async move { service.discover_files_in_folder(&folder_path).await }
             },
             |file_path| {
                 let service = s3_service.clone();
-                async move { service.download_file(&file_path).await }
+                async move { service.download_file(&file_path).await.map(|data| Some(DownloadedFile::InMemory(data))) }
             }
         ).await;
         
@@ -283,438 +655,296 @@ impl SourceSyncService {
         sync_result
     }
 
-    async fn perform_sync_internal<F, D, Fut1, Fut2>(
-        &self,
-        user_id: Uuid,
-        source_id: Uuid,
-        watch_folders: &[String],
-        file_extensions: &[String],
-        enable_background_ocr: bool,
-        discover_files: F,
-        download_file: D,
-    ) -> Result<usize>
-    where
-        F: Fn(String) -> Fut1,
-        D: Fn(String) -> Fut2 + Clone,
-        Fut1: std::future::Future<Output = Result<Vec<FileIngestionInfo>>>,
-        Fut2: std::future::Future<Output = Result<Vec<u8>>>,
-    {
-        let mut total_files_processed = 0;
-
-        for folder_path in watch_folders {
-            info!("Syncing folder: {}", folder_path);
-
-            // Discover files in the folder
-            match discover_files(folder_path.clone()).await {
-                Ok(files) => {
-                    info!("Found {} files in folder {}", files.len(), folder_path);
-
-                    // Filter files for processing
-                    let files_to_process: Vec<_> = files.into_iter()
-                        .filter(|file_info| {
-                            if file_info.is_directory {
-                                return false;
-                            }
-
-                            let file_extension = Path::new(&file_info.name)
-                                .extension()
-                                .and_then(|ext| ext.to_str())
-                                .unwrap_or("")
-                                .to_lowercase();
-
-                            file_extensions.contains(&file_extension)
-                        })
-                        .collect();
-
-                    info!("Processing {} files from folder {}", files_to_process.len(), folder_path);
+    /// Drops directories and files with extensions the source doesn't sync
+    fn files_to_ingest(files: Vec<FileIngestionInfo>, file_extensions: &[String], newest_first: bool) -> Vec<FileIngestionInfo> {
+        let mut files_to_process: Vec<_> = files.into_iter()
+            .filter(|file_info| {
+                if file_info.is_directory {
+                    return false;
+                }
 
-                    // Process files concurrently with a limit
-                    let concurrent_limit = 5;
-                    let semaphore = Arc::new(Semaphore::new(concurrent_limit));
-                    let mut folder_files_processed = 0;
-
-                    let mut file_futures = FuturesUnordered::new();
-
-                    for file_info in files_to_process.iter() {
-                        let state_clone = self.state.clone();
-                        let file_info_clone = file_info.clone();
-                        let semaphore_clone = semaphore.clone();
-                        let download_file_clone = download_file.clone();
-
-                        let future = async move {
-                            Self::process_single_file(
-                                state_clone,
-                                user_id,
-                                source_id,
-                                &file_info_clone,
-                                enable_background_ocr,
-                                semaphore_clone,
-                                download_file_clone,
-                            ).await
-                        };
-
-                        file_futures.push(future);
-                    }
+                let file_extension = Path::new(&file_info.name)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("")
+                    .to_lowercase();
 
-                    // Process files concurrently
-                    while let Some(result) = file_futures.next().await {
-                        match result {
-                            Ok(processed) => {
-                                if processed {
-                                    folder_files_processed += 1;
-                                    debug!("Successfully processed file ({} completed in this folder)", folder_files_processed);
-                                }
-                            }
-                            Err(error) => {
-                                error!("File processing error: {}", error);
-                            }
-                        }
-                    }
+                file_extensions.contains(&file_extension)
+            })
+            .collect();
 
-                    total_files_processed += folder_files_processed;
-                }
-                Err(e) => {
-                    error!("Failed to discover files in folder {}: {}", folder_path, e);
-                }
-            }
+        if newest_first {
+            sort_newest_first(&mut files_to_process);
         }
 
-        info!("Source sync completed: {} files processed", total_files_processed);
-        Ok(total_files_processed)
+        files_to_process
+    }
+
+    /// Files of `files` directly inside `folder_path`
+    fn files_in_folder(files: &[FileIngestionInfo], folder_path: &str) -> Vec<FileIngestionInfo> {
+        files.iter()
+            .filter(|file_info| parent_folder(&file_info.relative_path) == folder_path)
+            .cloned()
+            .collect()
     }
 
+    /// Runs the ingestion pipeline over the watch folders, or over the folders of
+    /// `retry_files` when only those are retried, and records the finished run
+    /// with its failures. `download_file` returns None for a file that is
+    /// unchanged since it was stored; with `record_file_versions` the version of
+    /// each stored file is kept for that check.
     async fn perform_sync_internal_with_cancellation<F, D, Fut1, Fut2>(
         &self,
-        user_id: Uuid,
-        source_id: Uuid,
+        source: &Source,
         watch_folders: &[String],
         file_extensions: &[String],
         enable_background_ocr: bool,
+        source_ocr_enabled: bool,
+        newest_first: bool,
+        record_file_versions: bool,
         cancellation_token: CancellationToken,
+        retry_files: Option<Vec<FileIngestionInfo>>,
         discover_files: F,
         download_file: D,
     ) -> Result<usize>
     where
         F: Fn(String) -> Fut1,
-        D: Fn(String) -> Fut2 + Clone,
+        D: Fn(String) -> Fut2,
         Fut1: std::future::Future<Output = Result<Vec<FileIngestionInfo>>>,
-        Fut2: std::future::Future<Output = Result<Vec<u8>>>,
+        Fut2: std::future::Future<Output = Result<Option<DownloadedFile>>>,
     {
-        let mut total_files_processed = 0;
-        let mut total_files_discovered = 0;
-        let mut total_size_bytes = 0i64;
-
-        // First pass: discover all files and calculate totals
-        for folder_path in watch_folders {
-            if cancellation_token.is_cancelled() {
-                info!("Sync cancelled during folder discovery");
-                return Err(anyhow!("Sync cancelled"));
+        let user_id = source.user_id;
+        let source_id = source.id;
+        let started_at = Utc::now();
+        let failure_log = SyncFailureLog::new(&self.state, source);
+
+        let retry_folders;
+        let folders = match &retry_files {
+            Some(files) => {
+                retry_folders = parent_folders(files);
+                retry_folders.as_slice()
             }
+            None => watch_folders,
+        };
 
-            match discover_files(folder_path.clone()).await {
-                Ok(files) => {
-                    let files_to_process: Vec<_> = files.into_iter()
-                        .filter(|file_info| {
-                            if file_info.is_directory {
-                                return false;
-                            }
-
-                            let file_extension = Path::new(&file_info.name)
-                                .extension()
-                                .and_then(|ext| ext.to_str())
-                                .unwrap_or("")
-                                .to_lowercase();
-
-                            file_extensions.contains(&file_extension)
-                        })
-                        .collect();
-
-                    total_files_discovered += files_to_process.len();
-                    total_size_bytes += files_to_process.iter().map(|f| f.size).sum::<i64>();
-                }
-                Err(e) => {
-                    error!("Failed to discover files in folder {}: {}", folder_path, e);
-                }
-            }
-        }
-
-        // Update initial statistics with discovered files
-        if let Err(e) = self.state.db.update_source_sync_stats(
-            source_id,
-            0, // files_synced starts at 0
-            total_files_discovered as i64,
-            total_size_bytes,
-        ).await {
-            error!("Failed to update initial sync stats: {}", e);
-        }
-
-        // Second pass: process files and update stats progressively
-        for folder_path in watch_folders {
-            // Check for cancellation before processing each folder
-            if cancellation_token.is_cancelled() {
-                info!("Sync cancelled during folder processing");
-                return Err(anyhow!("Sync cancelled"));
-            }
-
-            info!("Syncing folder: {}", folder_path);
-
-            // Discover files in the folder
-            match discover_files(folder_path.clone()).await {
-                Ok(files) => {
-                    if cancellation_token.is_cancelled() {
-                        info!("Sync cancelled after discovering files");
-                        return Err(anyhow!("Sync cancelled"));
-                    }
-
-                    info!("Found {} files in folder {}", files.len(), folder_path);
-
-                    // Filter files for processing
-                    let files_to_process: Vec<_> = files.into_iter()
-                        .filter(|file_info| {
-                            if file_info.is_directory {
-                                return false;
-                            }
-
-                            let file_extension = Path::new(&file_info.name)
-                                .extension()
-                                .and_then(|ext| ext.to_str())
-                                .unwrap_or("")
-                                .to_lowercase();
-
-                            file_extensions.contains(&file_extension)
-                        })
-                        .collect();
-
-                    info!("Processing {} files from folder {}", files_to_process.len(), folder_path);
-
-                    // Process files concurrently with a limit
-                    let concurrent_limit = 5;
-                    let semaphore = Arc::new(Semaphore::new(concurrent_limit));
-                    let mut folder_files_processed = 0;
-
-                    let mut file_futures = FuturesUnordered::new();
+        // Each folder is discovered once; its files start downloading while the
+        // next folder is still being listed
+        let total_size_bytes = AtomicI64::new(0);
+        let counts = SyncRunCounts::default();
+        let mut reported = PipelineSummary::default();
+        let pipeline = IngestionPipeline::new(self.state.config.ingestion_pipeline, cancellation_token);
 
-                    for file_info in files_to_process.iter() {
-                        // Check for cancellation before processing each file
-                        if cancellation_token.is_cancelled() {
-                            info!("Sync cancelled during file processing");
-                            return Err(anyhow!("Sync cancelled"));
+        let summary = pipeline.run(
+            folders,
+            |folder_path| {
+                // A retry never lists the folder, it takes the failed files as they were found
+                let discovery = match &retry_files {
+                    Some(_) => None,
+                    None => Some(discover_files(folder_path.clone())),
+                };
+                let retry_files = retry_files.as_deref();
+                let failure_log = &failure_log;
+                let total_size_bytes = &total_size_bytes;
+                async move {
+                    let files = match discovery {
+                        Some(discovery) => {
+                            info!("Syncing folder: {}", folder_path);
+                            let files = discovery.await.map_err(|e| {
+                                failure_log.record_folder(&folder_path, &e);
+                                e
+                            })?;
+                            info!("Found {} files in folder {}", files.len(), folder_path);
+                            files
                         }
-
-                        let state_clone = self.state.clone();
-                        let file_info_clone = file_info.clone();
-                        let semaphore_clone = semaphore.clone();
-                        let download_file_clone = download_file.clone();
-                        let cancellation_token_clone = cancellation_token.clone();
-
-                        let future = async move {
-                            Self::process_single_file_with_cancellation(
-                                state_clone,
-                                user_id,
-                                source_id,
-                                &file_info_clone,
-                                enable_background_ocr,
-                                semaphore_clone,
-                                download_file_clone,
-                                cancellation_token_clone,
-                            ).await
-                        };
-
-                        file_futures.push(future);
-                    }
-
-                    // Process files concurrently and update stats periodically
-                    while let Some(result) = file_futures.next().await {
-                        // Check for cancellation during processing
-                        if cancellation_token.is_cancelled() {
-                            info!("Sync cancelled during concurrent file processing");
-                            return Err(anyhow!("Sync cancelled"));
+                        None => {
+                            info!("Retrying failed files in folder: {}", folder_path);
+                            Self::files_in_folder(retry_files.unwrap_or_default(), &folder_path)
                         }
+                    };
 
-                        match result {
-                            Ok(processed) => {
-                                if processed {
-                                    folder_files_processed += 1;
-                                    total_files_processed += 1;
-                                    
-                                    // Update statistics every 10 files processed or every file if under 10 total
-                                    if total_files_processed % 10 == 0 || total_files_discovered <= 10 {
-                                        let files_pending = total_files_discovered as i64 - total_files_processed as i64;
-                                        if let Err(e) = self.state.db.update_source_sync_stats(
-                                            source_id,
-                                            total_files_processed as i64,
-                                            files_pending.max(0),
-                                            total_size_bytes,
-                                        ).await {
-                                            error!("Failed to update sync stats: {}", e);
-                                        }
-                                    }
-                                    
-                                    debug!("Successfully processed file ({} completed in this folder, {} total)", folder_files_processed, total_files_processed);
-                                }
-                            }
-                            Err(error) => {
-                                error!("File processing error: {}", error);
-                            }
-                        }
+                    let files_to_process = Self::files_to_ingest(files, file_extensions, newest_first);
+                    total_size_bytes.fetch_add(files_to_process.iter().map(|f| f.size).sum::<i64>(), Ordering::Relaxed);
+                    info!("Processing {} files from folder {}", files_to_process.len(), folder_path);
+                    Ok(files_to_process)
+                }
+            },
+            |file_info: FileIngestionInfo| {
+                let download = download_file(file_info.relative_path.clone());
+                let failure_log = &failure_log;
+                let counts = &counts;
+                async move {
+                    let download = download.await
+                        .map_err(|e| {
+                            failure_log.record_file(SyncFailureStage::Download, &file_info, &e);
+                            anyhow!("Failed to download {}: {}", file_info.relative_path, e)
+                        })?;
+                    if let Some(download) = &download {
+                        counts.bytes_transferred.fetch_add(download.len() as i64, Ordering::Relaxed);
                     }
+                    Ok(download)
                 }
-                Err(e) => {
-                    error!("Failed to discover files in folder {}: {}", folder_path, e);
+            },
+            |file_info: FileIngestionInfo, download: Option<DownloadedFile>| {
+                let store = download.map(|download| Self::store_downloaded_file(
+                    self.state.clone(),
+                    user_id,
+                    source_id,
+                    file_info.clone(),
+                    download,
+                    enable_background_ocr,
+                    source_ocr_enabled,
+                    newest_first,
+                    record_file_versions,
+                ));
+                let failure_log = &failure_log;
+                let counts = &counts;
+                async move {
+                    let Some(store) = store else {
+                        debug!("Skipping unchanged file {}", file_info.relative_path);
+                        counts.files_skipped.fetch_add(1, Ordering::Relaxed);
+                        return Ok(false);
+                    };
+                    let stored = store.await.map_err(|e| {
+                        failure_log.record_file(SyncFailureStage::Store, &file_info, &e);
+                        e
+                    })?;
+                    let counter = match stored {
+                        StoredFile::Added => &counts.files_added,
+                        StoredFile::Updated => &counts.files_updated,
+                        StoredFile::Existing | StoredFile::NotStored => &counts.files_skipped,
+                    };
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    Ok(stored != StoredFile::NotStored)
                 }
-            }
+            },
+            |progress| {
+                // Update statistics when a folder was discovered, and every 10 files
+                // processed or every file if under 10 total
+                let should_update = progress.discovered != reported.discovered
+                    || progress.stored >= reported.stored + 10
+                    || progress.discovered <= 10;
+                if should_update {
+                    reported = progress;
+                }
+                let state = self.state.clone();
+                let total_size_bytes = total_size_bytes.load(Ordering::Relaxed);
+                async move {
+                    if !should_update {
+                        return;
+                    }
+                    let files_pending = progress.discovered as i64 - progress.stored as i64;
+                    if let Err(e) = state.db.update_source_sync_stats(
+                        source_id,
+                        progress.stored as i64,
+                        files_pending.max(0),
+                        total_size_bytes,
+                    ).await {
+                        error!("Failed to update sync stats: {}", e);
+                    }
+                }
+            },
+        ).await;
+
+        if summary.cancelled {
+            info!("Sync cancelled after {} files were processed", summary.stored);
+            return Err(anyhow!("Sync cancelled"));
         }
 
         // Final statistics update
         if let Err(e) = self.state.db.update_source_sync_stats(
             source_id,
-            total_files_processed as i64,
+            summary.stored as i64,
             0, // All files are now processed
-            total_size_bytes,
+            total_size_bytes.load(Ordering::Relaxed),
         ).await {
             error!("Failed to update final sync stats: {}", e);
         }
 
-        info!("Source sync completed: {} files processed", total_files_processed);
-        Ok(total_files_processed)
-    }
-
-    async fn process_single_file<D, Fut>(
-        state: Arc<AppState>,
-        user_id: Uuid,
-        source_id: Uuid,
-        file_info: &FileIngestionInfo,
-        enable_background_ocr: bool,
-        semaphore: Arc<Semaphore>,
-        download_file: D,
-    ) -> Result<bool>
-    where
-        D: Fn(String) -> Fut,
-        Fut: std::future::Future<Output = Result<Vec<u8>>>,
-    {
-        let _permit = semaphore.acquire().await
-            .map_err(|e| anyhow!("Semaphore error: {}", e))?;
-
-        debug!("Processing file: {}", file_info.relative_path);
-        
-        // Download the file
-        let file_data = download_file(file_info.relative_path.clone()).await
-            .map_err(|e| anyhow!("Failed to download {}: {}", file_info.relative_path, e))?;
-
-        debug!("Downloaded file: {} ({} bytes)", file_info.name, file_data.len());
-
-        // Use the unified ingestion service for consistent deduplication
-        let file_service = (*state.file_service).clone();
-        let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service);
-        
-        let result = ingestion_service
-            .ingest_from_file_info(
-                file_info,
-                file_data,
-                user_id,
-                crate::ingestion::document_ingestion::DeduplicationPolicy::Skip,
-                "source_sync",
-                Some(source_id),
-            )
-            .await
-            .map_err(|e| anyhow!("Document ingestion failed for {}: {}", file_info.name, e))?;
-
-        let (document, should_queue_ocr) = match result {
-            IngestionResult::Created(doc) => {
-                debug!("Created new document for {}: {}", file_info.name, doc.id);
-                (doc, true) // New document - queue for OCR
-            }
-            IngestionResult::Skipped { existing_document_id, reason } => {
-                info!("Skipped duplicate file {}: {} (existing: {})", file_info.name, reason, existing_document_id);
-                return Ok(false); // File was skipped due to deduplication
-            }
-            IngestionResult::ExistingDocument(doc) => {
-                debug!("Found existing document for {}: {}", file_info.name, doc.id);
-                (doc, false) // Existing document - don't re-queue OCR
-            }
-            IngestionResult::TrackedAsDuplicate { existing_document_id } => {
-                info!("Tracked {} as duplicate of existing document: {}", file_info.name, existing_document_id);
-                return Ok(false); // File was tracked as duplicate
-            }
+        info!(
+            "Source sync completed: {} files processed ({} download failures, {} processing failures)",
+            summary.stored, summary.download_failures, summary.store_failures
+        );
+
+        // A folder that could not be listed counts as one failed item, so a sync
+        // whose folders all fail is not reported as a success over zero files
+        let failures = failure_log.into_failures();
+        let attempted = summary.discovered + summary.discovery_failures;
+        let outcome = SyncOutcome::from_counts(failures.len(), attempted, self.state.config.sync_failure_ratio_threshold);
+        let failure_kind = (outcome == SyncOutcome::Failed).then(|| SyncFailureKind::of_failed_run(&failures, summary.stored));
+        let run = SourceSyncRun {
+            id: Uuid::new_v4(),
+            source_id,
+            outcome,
+            failure_kind,
+            retry_of_failed: retry_files.is_some(),
+            files_discovered: summary.discovered as i64,
+            files_processed: summary.stored as i64,
+            files_failed: failures.len() as i64,
+            files_added: counts.files_added.load(Ordering::Relaxed),
+            files_updated: counts.files_updated.load(Ordering::Relaxed),
+            files_skipped: counts.files_skipped.load(Ordering::Relaxed),
+            bytes_transferred: counts.bytes_transferred.load(Ordering::Relaxed),
+            failures,
+            started_at,
+            completed_at: Utc::now(),
         };
+        if let Err(e) = self.state.db.insert_source_sync_run(&run).await {
+            error!("Failed to record sync run for source {}: {}", source_id, e);
+        }
+        self.prune_sync_history(source_id).await;
 
-        // Queue for OCR if enabled and this is a new document
-        if enable_background_ocr && should_queue_ocr {
-            debug!("Background OCR enabled, queueing document {} for processing", document.id);
-
-            let priority = if file_info.size <= 1024 * 1024 { 10 }
-            else if file_info.size <= 5 * 1024 * 1024 { 8 }
-            else if file_info.size <= 10 * 1024 * 1024 { 6 }
-            else if file_info.size <= 50 * 1024 * 1024 { 4 }
-            else { 2 };
-
-            if let Err(e) = state.queue_service.enqueue_document(document.id, priority, file_info.size).await {
-                error!("Failed to enqueue document for OCR: {}", e);
-            } else {
-                debug!("Enqueued document {} for OCR processing", document.id);
+        match outcome {
+            SyncOutcome::Success => Ok(summary.stored),
+            SyncOutcome::Partial => {
+                warn!("Source sync partially failed: {} of {} files failed", run.files_failed, attempted);
+                Ok(summary.stored)
+            }
+            SyncOutcome::Failed => {
+                let message = format!("{} of {} files failed", run.files_failed, attempted);
+                match failure_kind {
+                    Some(SyncFailureKind::Transient) => Err(TransientSyncFailure(message).into()),
+                    _ => Err(anyhow!(message)),
+                }
             }
         }
+    }
 
-        Ok(true)
+    /// Deletes the source's sync runs that are past the history retention period
+    async fn prune_sync_history(&self, source_id: Uuid) {
+        let retention_days = self.state.config.sync_history_retention_days;
+        if retention_days == 0 {
+            return;
+        }
+        let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+        match self.state.db.prune_source_sync_runs(source_id, cutoff).await {
+            Ok(0) => {}
+            Ok(pruned) => debug!("Pruned {} sync runs of source {} older than {} days", pruned, source_id, retention_days),
+            Err(e) => warn!("Failed to prune sync history of source {}: {}", source_id, e),
+        }
     }
 
-    async fn process_single_file_with_cancellation<D, Fut>(
+    /// Ingests a downloaded file and queues new documents for OCR
+    async fn store_downloaded_file(
         state: Arc<AppState>,
         user_id: Uuid,
         source_id: Uuid,
-        file_info: &FileIngestionInfo,
+        file_info: FileIngestionInfo,
+        download: DownloadedFile,
         enable_background_ocr: bool,
-        semaphore: Arc<Semaphore>,
-        download_file: D,
-        cancellation_token: CancellationToken,
-    ) -> Result<bool>
-    where
-        D: Fn(String) -> Fut,
-        Fut: std::future::Future<Output = Result<Vec<u8>>>,
-    {
-        // Check for cancellation before starting file processing
-        if cancellation_token.is_cancelled() {
-            info!("File processing cancelled before starting: {}", file_info.relative_path);
-            return Err(anyhow!("Processing cancelled"));
-        }
-
-        let _permit = semaphore.acquire().await
-            .map_err(|e| anyhow!("Semaphore error: {}", e))?;
-
-        debug!("Processing file: {}", file_info.relative_path);
-
-        // Check for cancellation again after acquiring semaphore
-        if cancellation_token.is_cancelled() {
-            info!("File processing cancelled after acquiring semaphore: {}", file_info.relative_path);
-            return Err(anyhow!("Processing cancelled"));
-        }
-
-        // Download the file
-        let file_data = download_file(file_info.relative_path.clone()).await
-            .map_err(|e| anyhow!("Failed to download {}: {}", file_info.relative_path, e))?;
-
-        // Check for cancellation after download
-        if cancellation_token.is_cancelled() {
-            info!("File processing cancelled after download: {}", file_info.relative_path);
-            return Err(anyhow!("Processing cancelled"));
-        }
-
-        debug!("Downloaded file: {} ({} bytes)", file_info.name, file_data.len());
-
-        // Check for cancellation before processing
-        if cancellation_token.is_cancelled() {
-            info!("File processing cancelled before ingestion: {}", file_info.relative_path);
-            return Err(anyhow!("Processing cancelled"));
-        }
+        source_ocr_enabled: bool,
+        newest_first: bool,
+        record_file_version: bool,
+    ) -> Result<StoredFile> {
+        debug!("Downloaded file: {} ({} bytes)", file_info.name, download.len());
 
         // Use the unified ingestion service for consistent deduplication
         let file_service = (*state.file_service).clone();
         let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service);
         
         let result = ingestion_service
-            .ingest_from_file_info(
-                file_info,
-                file_data,
+            .ingest_download_from_file_info(
+                &file_info,
+                download,
                 user_id,
                 crate::ingestion::document_ingestion::DeduplicationPolicy::Skip,
                 "source_sync",
@@ -730,7 +960,10 @@ impl SourceSyncService {
             }
             IngestionResult::Skipped { existing_document_id, reason } => {
                 info!("Skipped duplicate file {}: {} (existing: {})", file_info.name, reason, existing_document_id);
-                return Ok(false); // File was skipped due to deduplication
+                if record_file_version {
+                    Self::record_file_version(&state, source_id, &file_info, existing_document_id).await;
+                }
+                return Ok(StoredFile::NotStored); // File was skipped due to deduplication
             }
             IngestionResult::ExistingDocument(doc) => {
                 debug!("Found existing document for {}: {}", file_info.name, doc.id);
@@ -738,28 +971,70 @@ impl SourceSyncService {
             }
             IngestionResult::TrackedAsDuplicate { existing_document_id } => {
                 info!("Tracked {} as duplicate of existing document: {}", file_info.name, existing_document_id);
-                return Ok(false); // File was tracked as duplicate
+                if record_file_version {
+                    Self::record_file_version(&state, source_id, &file_info, existing_document_id).await;
+                }
+                return Ok(StoredFile::NotStored); // File was tracked as duplicate
+            }
+            IngestionResult::Quarantined { failed_document_id, threat } => {
+                warn!("Quarantined {} ({}), recorded as failed document {}", file_info.name, threat, failed_document_id);
+                return Ok(StoredFile::NotStored); // File was not stored
+            }
+            IngestionResult::Corrupt { failed_document_id, reason } => {
+                warn!("{} is corrupt ({}), recorded as failed document {}", file_info.name, reason, failed_document_id);
+                return Ok(StoredFile::NotStored); // File was not stored
             }
         };
 
+        // A new document for a path stored by an earlier sync is a changed file,
+        // linked to its previous version so OCR can reuse the unchanged pages
+        let mut stored = if should_queue_ocr { StoredFile::Added } else { StoredFile::Existing };
+        if record_file_version {
+            if should_queue_ocr {
+                if let Ok(Some(previous_document_id)) = state.db.get_source_file_document_id(source_id, &file_info.relative_path).await {
+                    stored = StoredFile::Updated;
+                    if let Err(e) = state.db.set_previous_version_document(document.id, previous_document_id).await {
+                        warn!("Failed to link document {} to its previous version {}: {}", document.id, previous_document_id, e);
+                    }
+                    if let Err(e) = state.db.inherit_document_move(document.id, previous_document_id).await {
+                        warn!("Failed to move document {} to where its previous version {} was moved: {}", document.id, previous_document_id, e);
+                    }
+                }
+            }
+            Self::record_file_version(&state, source_id, &file_info, document.id).await;
+        }
+
+        // Sources with OCR turned off store their documents without text
+        if !source_ocr_enabled && should_queue_ocr {
+            debug!("OCR disabled for source {}, not queueing document {}", source_id, document.id);
+            if let Err(e) = state.db.mark_ocr_skipped(document.id).await {
+                warn!("Failed to mark document {} as stored without OCR: {}", document.id, e);
+            }
+            return Ok(stored);
+        }
+
         // Queue for OCR if enabled and this is a new document (OCR continues even if sync is cancelled)
         if enable_background_ocr && should_queue_ocr {
             debug!("Background OCR enabled, queueing document {} for processing", document.id);
 
-            let priority = if file_info.size <= 1024 * 1024 { 10 }
-            else if file_info.size <= 5 * 1024 * 1024 { 8 }
-            else if file_info.size <= 10 * 1024 * 1024 { 6 }
-            else if file_info.size <= 50 * 1024 * 1024 { 4 }
-            else { 2 };
+            let priority = ocr_priority_for_file(&file_info, newest_first, Utc::now());
 
-            if let Err(e) = state.queue_service.enqueue_document(document.id, priority, file_info.size).await {
+            if let Err(e) = state.queue_service.enqueue_document_with_class(document.id, OcrPriorityClass::Backfill, priority, file_info.size).await {
                 error!("Failed to enqueue document for OCR: {}", e);
             } else {
                 debug!("Enqueued document {} for OCR processing", document.id);
             }
         }
 
-        Ok(true)
+        Ok(stored)
+    }
+
+    /// Remembers the stored version of a file, so the next sync only downloads it if it changed
+    async fn record_file_version(state: &AppState, source_id: Uuid, file_info: &FileIngestionInfo, document_id: Uuid) {
+        let version = SourceFileVersion::from_file_info(file_info);
+        if let Err(e) = state.db.save_source_file_version(source_id, &file_info.relative_path, document_id, &version).await {
+            warn!("Failed to record the stored version of {}: {}", file_info.relative_path, e);
+        }
     }
 
     async fn update_source_status(&self, source_id: Uuid, status: SourceStatus, error_message: Option<&str>) -> Result<()> {
@@ -546,6 +546,12 @@ async fn process_file(
         IngestionResult::TrackedAsDuplicate { existing_document_id } => {
             info!("Tracked watch folder file {} as duplicate of existing document: {}", file_info.name, existing_document_id);
         }
+        IngestionResult::Quarantined { failed_document_id, threat } => {
+            warn!("Quarantined watch folder file {} ({}), recorded as failed document {}", file_info.name, threat, failed_document_id);
+        }
+        IngestionResult::Corrupt { failed_document_id, reason } => {
+            warn!("Watch folder file {} is corrupt ({}), recorded as failed document {}", file_info.name, reason, failed_document_id);
+        }
     }
     
     Ok(())
@@ -625,8 +631,8 @@ fn is_text_extractable_file(mime_type: &str) -> bool {
         "application/pdf" | 
         "image/png" | "image/jpeg" | "image/jpg" | 
         "image/tiff" | "image/bmp" | "image/gif" |
-        // Plain text
-        "text/plain" |
+        // Plain text and markdown
+        "text/plain" | "text/markdown" |
         // Office document formats
         "application/vnd.openxmlformats-officedocument.wordprocessingml.document" | // DOCX
         "application/msword" |                                                      // DOC
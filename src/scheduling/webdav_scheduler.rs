@@ -111,7 +111,7 @@ impl WebDAVScheduler {
                                             })),
                                         };
                                         
-                                        if let Err(e) = state_clone.db.create_notification(user_id, &notification).await {
+                                        if let Err(e) = state_clone.db.deliver_notification(user_id, &notification).await {
                                             error!("Failed to create resume notification: {}", e);
                                         }
                                     }
@@ -172,7 +172,7 @@ impl WebDAVScheduler {
                                             })),
                                         };
                                         
-                                        if let Err(e) = state_clone.db.create_notification(user_id, &notification).await {
+                                        if let Err(e) = state_clone.db.deliver_notification(user_id, &notification).await {
                                             error!("Failed to create success notification: {}", e);
                                         }
                                     }
@@ -192,7 +192,7 @@ impl WebDAVScheduler {
                                         })),
                                     };
                                     
-                                    if let Err(e) = state_clone.db.create_notification(user_id, &notification).await {
+                                    if let Err(e) = state_clone.db.deliver_notification(user_id, &notification).await {
                                         error!("Failed to create error notification: {}", e);
                                     }
                                 }
@@ -275,6 +275,9 @@ impl WebDAVScheduler {
             file_extensions: settings.webdav_file_extensions.clone(),
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         })
     }
 
@@ -0,0 +1,325 @@
+//! Envelope encryption of the credentials stored in source configurations
+//!
+//! Each secret (a WebDAV password, an S3 secret key) is encrypted with its own
+//! random data key using AES-256-GCM, and the data key is encrypted ("wrapped")
+//! with a master key from `CREDENTIALS_MASTER_KEYS`. The stored value names the
+//! master key version it was wrapped with:
+//!
+//! ```text
+//! enc:v2:<wrapped data key>:<encrypted secret>
+//! ```
+//!
+//! Rotating to a new master key only rewraps the data keys; the secrets
+//! themselves are never re-encrypted. Older master keys stay in the keyring
+//! until every record has been rotated, so sources keep syncing meanwhile.
+//! Without master keys, credentials are stored in plain text as before.
+
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{anyhow, Result};
+use base64ct::{Base64, Encoding};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::config::Config;
+
+/// Prefix of encrypted values
+const ENCRYPTED_PREFIX: &str = "enc:v";
+
+/// Length of master and data keys in bytes
+const KEY_LEN: usize = 32;
+
+/// Fields of source configurations that hold secrets
+pub const SECRET_FIELDS: [&str; 2] = ["password", "secret_access_key"];
+
+/// A master key and its version
+#[derive(Clone)]
+pub struct MasterKey {
+    pub version: i32,
+    key: [u8; KEY_LEN],
+}
+
+impl std::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MasterKey").field("version", &self.version).finish_non_exhaustive()
+    }
+}
+
+impl MasterKey {
+    pub fn new(version: i32, key: [u8; KEY_LEN]) -> Self {
+        Self { version, key }
+    }
+
+    fn sealing_key(&self) -> LessSafeKey {
+        aead_key(&self.key)
+    }
+}
+
+/// The master keys credentials may be wrapped with; the highest version is current
+#[derive(Debug, Clone, Default)]
+pub struct CredentialKeyring {
+    keys: Vec<MasterKey>,
+}
+
+impl CredentialKeyring {
+    pub fn new(mut keys: Vec<MasterKey>) -> Self {
+        keys.sort_by(|a, b| b.version.cmp(&a.version));
+        Self { keys }
+    }
+
+    /// Parses `version:base64key` pairs separated by commas, e.g. `2:...,1:...`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut keys = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (version, key) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Master key entries must look like 'version:base64key'"))?;
+            let version: i32 = version
+                .trim()
+                .parse()
+                .ok()
+                .filter(|version| *version > 0)
+                .ok_or_else(|| anyhow!("Invalid master key version '{}' (expected a positive integer)", version))?;
+            let bytes = Base64::decode_vec(key.trim())
+                .map_err(|_| anyhow!("Master key {} is not valid base64", version))?;
+            let key: [u8; KEY_LEN] = bytes
+                .try_into()
+                .map_err(|bytes: Vec<u8>| anyhow!("Master key {} is {} bytes, expected {}", version, bytes.len(), KEY_LEN))?;
+            if keys.iter().any(|known: &MasterKey| known.version == version) {
+                return Err(anyhow!("Master key version {} is listed twice", version));
+            }
+            keys.push(MasterKey::new(version, key));
+        }
+        Ok(Self::new(keys))
+    }
+
+    /// The keyring configured with `CREDENTIALS_MASTER_KEYS`
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Self::parse(&config.credentials_master_keys)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Version of the key new credentials are wrapped with
+    pub fn current_version(&self) -> Option<i32> {
+        self.keys.first().map(|key| key.version)
+    }
+
+    pub fn versions(&self) -> Vec<i32> {
+        self.keys.iter().map(|key| key.version).collect()
+    }
+
+    /// Versions among `versions` that aren't in the keyring
+    pub fn missing_versions(&self, versions: &[i32]) -> Vec<i32> {
+        versions
+            .iter()
+            .copied()
+            .filter(|version| self.keys.iter().all(|key| key.version != *version))
+            .collect()
+    }
+
+    fn key(&self, version: i32) -> Result<&MasterKey> {
+        self.keys
+            .iter()
+            .find(|key| key.version == version)
+            .ok_or_else(|| anyhow!("Master key version {} is not configured", version))
+    }
+
+    fn current_key(&self) -> Result<&MasterKey> {
+        self.keys.first().ok_or_else(|| anyhow!("No credential master key is configured"))
+    }
+
+    /// Encrypts a secret under the current master key
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let master = self.current_key()?;
+        let mut data_key = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut data_key)
+            .map_err(|_| anyhow!("Could not generate a data key"))?;
+
+        let payload = seal(&aead_key(&data_key), plaintext.as_bytes(), b"")?;
+        let wrapped = wrap_data_key(master, &data_key)?;
+        Ok(format_encrypted(master.version, &wrapped, &payload))
+    }
+
+    /// Decrypts a value written by [`Self::encrypt`]; plain text is returned as it is
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let Some(encrypted) = EncryptedValue::parse(value)? else {
+            return Ok(value.to_string());
+        };
+        let data_key = self.unwrap_data_key(&encrypted)?;
+        let plaintext = open(&aead_key(&data_key), &encrypted.payload, b"")
+            .map_err(|_| anyhow!("Credential could not be decrypted with master key {}", encrypted.version))?;
+        String::from_utf8(plaintext).map_err(|_| anyhow!("Decrypted credential is not valid UTF-8"))
+    }
+
+    /// Rewraps the data key of a value with the current master key. Plain text
+    /// is encrypted, and values already under the current key are returned unchanged.
+    pub fn rewrap(&self, value: &str) -> Result<String> {
+        let current = self.current_key()?;
+        let Some(encrypted) = EncryptedValue::parse(value)? else {
+            return self.encrypt(value);
+        };
+        if encrypted.version == current.version {
+            return Ok(value.to_string());
+        }
+        let data_key = self.unwrap_data_key(&encrypted)?;
+        let wrapped = wrap_data_key(current, &data_key)?;
+        Ok(format_encrypted(current.version, &wrapped, &encrypted.payload))
+    }
+
+    fn unwrap_data_key(&self, encrypted: &EncryptedValue) -> Result<[u8; KEY_LEN]> {
+        let master = self.key(encrypted.version)?;
+        let data_key = open(&master.sealing_key(), &encrypted.wrapped_key, &data_key_aad(master.version))
+            .map_err(|_| anyhow!("Data key could not be unwrapped with master key {}", master.version))?;
+        data_key
+            .try_into()
+            .map_err(|_| anyhow!("Unwrapped data key has the wrong length"))
+    }
+
+    /// Encrypts the secret fields of a source configuration. Returns the
+    /// configuration and the master key version it is now under, or None when
+    /// encryption is disabled.
+    pub fn encrypt_config(&self, config: &Value) -> Result<(Value, Option<i32>)> {
+        if !self.is_enabled() {
+            return Ok((config.clone(), None));
+        }
+        let config = map_secret_fields(config, |value| self.rewrap(value))?;
+        Ok((config, self.current_version()))
+    }
+
+    /// Decrypts the secret fields of a source configuration
+    pub fn decrypt_config(&self, config: &Value) -> Result<Value> {
+        map_secret_fields(config, |value| self.decrypt(value))
+    }
+}
+
+/// Outcome of moving source credentials to the current master key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CredentialRotationSummary {
+    /// Master key version the credentials are now wrapped with
+    pub current_key_version: i32,
+    /// Sources whose credentials were rewrapped or encrypted
+    pub rotated: usize,
+    /// Sources whose credentials couldn't be decrypted, e.g. because their key
+    /// is no longer configured
+    pub failed: usize,
+    /// Transactions the sources were rotated in
+    pub batches: usize,
+    /// Sources still not under the current key, including failed ones and
+    /// sources being updated during the rotation
+    pub remaining: i64,
+}
+
+/// Master key version a value is wrapped with, or None for plain text
+pub fn encrypted_key_version(value: &str) -> Option<i32> {
+    EncryptedValue::parse(value).ok().flatten().map(|encrypted| encrypted.version)
+}
+
+/// A parsed `enc:v<version>:<wrapped key>:<payload>` value
+struct EncryptedValue {
+    version: i32,
+    wrapped_key: Vec<u8>,
+    payload: Vec<u8>,
+}
+
+impl EncryptedValue {
+    fn parse(value: &str) -> Result<Option<Self>> {
+        let Some(rest) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(None);
+        };
+        let mut parts = rest.splitn(3, ':');
+        let (Some(version), Some(wrapped_key), Some(payload)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(anyhow!("Malformed encrypted credential"));
+        };
+        Ok(Some(Self {
+            version: version.parse().map_err(|_| anyhow!("Malformed encrypted credential version"))?,
+            wrapped_key: Base64::decode_vec(wrapped_key).map_err(|_| anyhow!("Malformed encrypted credential key"))?,
+            payload: Base64::decode_vec(payload).map_err(|_| anyhow!("Malformed encrypted credential payload"))?,
+        }))
+    }
+}
+
+fn format_encrypted(version: i32, wrapped_key: &[u8], payload: &[u8]) -> String {
+    format!(
+        "{}{}:{}:{}",
+        ENCRYPTED_PREFIX,
+        version,
+        Base64::encode_string(wrapped_key),
+        Base64::encode_string(payload)
+    )
+}
+
+/// Associated data binding a wrapped data key to its master key version
+fn data_key_aad(version: i32) -> Vec<u8> {
+    format!("readur-credential-key-v{}", version).into_bytes()
+}
+
+fn wrap_data_key(master: &MasterKey, data_key: &[u8; KEY_LEN]) -> Result<Vec<u8>> {
+    seal(&master.sealing_key(), data_key, &data_key_aad(master.version))
+}
+
+fn aead_key(key: &[u8; KEY_LEN]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).expect("AES-256 keys are 32 bytes"))
+}
+
+/// A random nonce followed by the ciphertext and tag
+fn seal(key: &LessSafeKey, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow!("Could not generate a nonce"))?;
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad), &mut in_out)
+        .map_err(|_| anyhow!("Encryption failed"))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&in_out);
+    Ok(sealed)
+}
+
+fn open(key: &LessSafeKey, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return Err(anyhow!("Ciphertext is too short"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("Invalid nonce"))?;
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| anyhow!("Decryption failed"))?;
+    Ok(plaintext.to_vec())
+}
+
+/// `config` with `transform` applied to the string values of its secret fields
+fn map_secret_fields(config: &Value, transform: impl Fn(&str) -> Result<String>) -> Result<Value> {
+    let mut config = config.clone();
+    if let Some(object) = config.as_object_mut() {
+        for field in SECRET_FIELDS {
+            if let Some(Value::String(value)) = object.get_mut(field) {
+                if !value.is_empty() {
+                    *value = transform(value)?;
+                }
+            }
+        }
+    }
+    Ok(config)
+}
+
+static CREDENTIAL_KEYRING: OnceLock<Arc<CredentialKeyring>> = OnceLock::new();
+
+/// Installs the process-wide keyring used for source credentials. Returns false
+/// if a keyring was already installed.
+pub fn install_credential_keyring(keyring: Arc<CredentialKeyring>) -> bool {
+    CREDENTIAL_KEYRING.set(keyring).is_ok()
+}
+
+/// The process-wide keyring, or an empty one (credentials in plain text) if
+/// none was installed
+pub fn installed_credential_keyring() -> Arc<CredentialKeyring> {
+    CREDENTIAL_KEYRING.get_or_init(|| Arc::new(CredentialKeyring::default())).clone()
+}
@@ -0,0 +1,298 @@
+//! Checks downloaded files against the checksums servers send with them, so a
+//! transfer corrupted on the way is caught before the file is stored and OCR'd.
+
+use base64ct::{Base64, Encoding};
+use md5::Md5;
+use reqwest::header::HeaderMap;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::models::ChecksumVerification;
+
+/// Hash algorithms servers send checksums in, from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChecksumAlgorithm {
+    Adler32,
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Adler32 => "ADLER32",
+            ChecksumAlgorithm::Md5 => "MD5",
+            ChecksumAlgorithm::Sha1 => "SHA1",
+            ChecksumAlgorithm::Sha256 => "SHA256",
+        }
+    }
+
+    fn digest_len(self) -> usize {
+        match self {
+            ChecksumAlgorithm::Adler32 => 4,
+            ChecksumAlgorithm::Md5 => 16,
+            ChecksumAlgorithm::Sha1 => 20,
+            ChecksumAlgorithm::Sha256 => 32,
+        }
+    }
+}
+
+/// A checksum the server sent for a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedChecksum {
+    pub algorithm: ChecksumAlgorithm,
+    pub digest: Vec<u8>,
+    /// Header the checksum came from
+    pub source: &'static str,
+}
+
+impl ExpectedChecksum {
+    fn new(algorithm: ChecksumAlgorithm, digest: Vec<u8>, source: &'static str) -> Option<Self> {
+        (digest.len() == algorithm.digest_len()).then_some(Self { algorithm, digest, source })
+    }
+}
+
+/// Checksums in the response headers of a download:
+///
+/// * `OC-Checksum` (Nextcloud/ownCloud): space-separated `SHA1:<hex>`, `MD5:<hex>`
+///   or `ADLER32:<hex>`
+/// * `Content-MD5`: base64 MD5 of the body
+/// * `Digest` (RFC 3230): comma-separated `sha-256=`, `sha=` or `md5=` with a base64
+///   digest, or `adler32=` with a hex one
+///
+/// Values that can't be parsed are ignored.
+pub fn checksums_from_headers(headers: &HeaderMap) -> Vec<ExpectedChecksum> {
+    let mut checksums = Vec::new();
+
+    for value in headers.get_all("oc-checksum").iter().filter_map(|value| value.to_str().ok()) {
+        for entry in value.split_whitespace() {
+            let Some((algorithm, digest)) = entry.split_once(':') else {
+                continue;
+            };
+            let algorithm = match algorithm.to_ascii_uppercase().as_str() {
+                "SHA1" => ChecksumAlgorithm::Sha1,
+                "SHA256" => ChecksumAlgorithm::Sha256,
+                "MD5" => ChecksumAlgorithm::Md5,
+                "ADLER32" => ChecksumAlgorithm::Adler32,
+                _ => continue,
+            };
+            checksums.extend(decode_hex(digest).and_then(|digest| ExpectedChecksum::new(algorithm, digest, "OC-Checksum")));
+        }
+    }
+
+    if let Some(value) = headers.get("content-md5").and_then(|value| value.to_str().ok()) {
+        checksums.extend(
+            Base64::decode_vec(value.trim())
+                .ok()
+                .and_then(|digest| ExpectedChecksum::new(ChecksumAlgorithm::Md5, digest, "Content-MD5")),
+        );
+    }
+
+    for value in headers.get_all("digest").iter().filter_map(|value| value.to_str().ok()) {
+        for entry in value.split(',') {
+            let Some((algorithm, digest)) = entry.trim().split_once('=') else {
+                continue;
+            };
+            let (algorithm, digest) = match algorithm.to_ascii_lowercase().as_str() {
+                "sha-256" => (ChecksumAlgorithm::Sha256, Base64::decode_vec(digest).ok()),
+                "sha" => (ChecksumAlgorithm::Sha1, Base64::decode_vec(digest).ok()),
+                "md5" => (ChecksumAlgorithm::Md5, Base64::decode_vec(digest).ok()),
+                "adler32" => (ChecksumAlgorithm::Adler32, decode_hex(digest)),
+                _ => continue,
+            };
+            checksums.extend(digest.and_then(|digest| ExpectedChecksum::new(algorithm, digest, "Digest")));
+        }
+    }
+
+    checksums
+}
+
+/// The MD5 of an S3 object from its ETag, where the ETag is one. It isn't for
+/// multipart uploads (`<hex>-<parts>`) or objects encrypted with KMS or a
+/// customer-provided key, whose ETags are opaque.
+pub fn checksum_from_s3_etag(etag: &str, opaque_encryption: bool) -> Option<ExpectedChecksum> {
+    if opaque_encryption {
+        return None;
+    }
+    let etag = etag.trim().trim_matches('"');
+    if etag.len() != 32 {
+        return None;
+    }
+    decode_hex(etag).and_then(|digest| ExpectedChecksum::new(ChecksumAlgorithm::Md5, digest, "ETag"))
+}
+
+/// Outcome of checking a download against the server's checksums
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumCheck {
+    /// Every checksum matched; the strongest algorithm is given
+    Verified(ChecksumAlgorithm),
+    /// The server sent no checksum
+    Unavailable,
+    Mismatch {
+        algorithm: ChecksumAlgorithm,
+        source: &'static str,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Hashes a download as it arrives, with the algorithms of the server's checksums
+pub struct ChecksumHasher {
+    expected: Vec<ExpectedChecksum>,
+    adler32: Option<Adler32>,
+    md5: Option<Md5>,
+    sha1: Option<Sha1>,
+    sha256: Option<Sha256>,
+}
+
+impl ChecksumHasher {
+    pub fn new(mut expected: Vec<ExpectedChecksum>) -> Self {
+        // Report a mismatch in the strongest algorithm first
+        expected.sort_by(|a, b| b.algorithm.cmp(&a.algorithm));
+        let uses = |algorithm: ChecksumAlgorithm| expected.iter().any(|checksum| checksum.algorithm == algorithm);
+        Self {
+            adler32: uses(ChecksumAlgorithm::Adler32).then(Adler32::new),
+            md5: uses(ChecksumAlgorithm::Md5).then(Md5::new),
+            sha1: uses(ChecksumAlgorithm::Sha1).then(Sha1::new),
+            sha256: uses(ChecksumAlgorithm::Sha256).then(Sha256::new),
+            expected,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        if let Some(hasher) = &mut self.adler32 {
+            hasher.update(data);
+        }
+        if let Some(hasher) = &mut self.md5 {
+            hasher.update(data);
+        }
+        if let Some(hasher) = &mut self.sha1 {
+            hasher.update(data);
+        }
+        if let Some(hasher) = &mut self.sha256 {
+            hasher.update(data);
+        }
+    }
+
+    pub fn finish(self) -> ChecksumCheck {
+        let actual = |algorithm: ChecksumAlgorithm| -> Vec<u8> {
+            match algorithm {
+                ChecksumAlgorithm::Adler32 => self.adler32.as_ref().map(|h| h.finish().to_be_bytes().to_vec()),
+                ChecksumAlgorithm::Md5 => self.md5.clone().map(|h| h.finalize().to_vec()),
+                ChecksumAlgorithm::Sha1 => self.sha1.clone().map(|h| h.finalize().to_vec()),
+                ChecksumAlgorithm::Sha256 => self.sha256.clone().map(|h| h.finalize().to_vec()),
+            }
+            .unwrap_or_default()
+        };
+
+        for checksum in &self.expected {
+            let digest = actual(checksum.algorithm);
+            if digest != checksum.digest {
+                return ChecksumCheck::Mismatch {
+                    algorithm: checksum.algorithm,
+                    source: checksum.source,
+                    expected: encode_hex(&checksum.digest),
+                    actual: encode_hex(&digest),
+                };
+            }
+        }
+
+        match self.expected.first() {
+            Some(strongest) => ChecksumCheck::Verified(strongest.algorithm),
+            None => ChecksumCheck::Unavailable,
+        }
+    }
+}
+
+/// Checks `data` against the server's checksums
+pub fn verify(data: &[u8], expected: Vec<ExpectedChecksum>) -> ChecksumCheck {
+    let mut hasher = ChecksumHasher::new(expected);
+    hasher.update(data);
+    hasher.finish()
+}
+
+/// What to do with a download after checking it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadVerdict {
+    Keep,
+    /// The checksum didn't match; downloading again may fix a corrupted transfer
+    Retry(String),
+    /// Downloading again won't help
+    Reject(String),
+}
+
+/// Applies `verification` to the check of a download of `file`
+pub fn judge(verification: ChecksumVerification, file: &str, check: &ChecksumCheck) -> DownloadVerdict {
+    match (verification, check) {
+        (ChecksumVerification::Off, _) | (_, ChecksumCheck::Verified(_)) => DownloadVerdict::Keep,
+        (ChecksumVerification::Require, ChecksumCheck::Unavailable) => DownloadVerdict::Reject(format!(
+            "The server sent no checksum for '{}', and checksums are required",
+            file
+        )),
+        (_, ChecksumCheck::Unavailable) => DownloadVerdict::Keep,
+        (verification, ChecksumCheck::Mismatch { algorithm, source, expected, actual }) => {
+            let message = format!(
+                "Checksum mismatch downloading '{}': the server's {} {} ({}) doesn't match the downloaded {}",
+                file,
+                algorithm.name(),
+                expected,
+                source,
+                actual
+            );
+            if verification == ChecksumVerification::Warn {
+                warn!("⚠️ {}; keeping the download", message);
+                DownloadVerdict::Keep
+            } else {
+                DownloadVerdict::Retry(message)
+            }
+        }
+    }
+}
+
+/// Adler-32 as Nextcloud's `ADLER32` checksums use it
+struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MODULUS: u32 = 65521;
+    /// Most bytes that can be summed before `b` might overflow
+    const CHUNK: usize = 5552;
+
+    fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(Self::CHUNK) {
+            for &byte in chunk {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= Self::MODULUS;
+            self.b %= Self::MODULUS;
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    if hex.len() % 2 != 0 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,91 @@
+//! Reminders for expiring documents
+//!
+//! Owners can give a document an expiry date. Once a day this service looks for
+//! documents that entered their owner's reminder window (`expiry_reminder_days`
+//! in the settings, 0 disables reminders) and sends one "warning" notification
+//! per document through [`Database::deliver_notification`], so digest delivery
+//! preferences apply.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+use crate::{
+    db::Database,
+    models::{CreateNotification, ExpiringDocument},
+};
+
+/// How often documents are checked for due expiry reminders
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone)]
+pub struct ExpiryReminderService {
+    db: Database,
+}
+
+impl ExpiryReminderService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Sends due reminders forever, once per [`CHECK_INTERVAL`]
+    pub async fn start(&self) {
+        info!("Starting document expiry reminders");
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.send_due_reminders().await {
+                error!("Failed to send document expiry reminders: {}", e);
+            }
+        }
+    }
+
+    /// Reminds owners of documents that entered their reminder window and
+    /// returns how many reminders were sent
+    pub async fn send_due_reminders(&self) -> Result<usize> {
+        let due = self.db.get_documents_due_expiry_reminder().await?;
+        let today = chrono::Utc::now().date_naive();
+        let mut sent = 0;
+
+        for document in due {
+            let reminder = build_expiry_reminder(&document, today);
+            if let Err(e) = self.db.deliver_notification(document.user_id, &reminder).await {
+                error!("Failed to remind user {} of expiring document {}: {}", document.user_id, document.document_id, e);
+                continue;
+            }
+            self.db.mark_expiry_reminded(document.document_id).await?;
+            debug!("Reminded user {} that document {} expires on {}", document.user_id, document.document_id, document.expiry_date);
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+}
+
+/// Days from `today` until the document expires; 0 on the day itself
+pub fn days_until_expiry(expiry_date: NaiveDate, today: NaiveDate) -> i64 {
+    (expiry_date - today).num_days()
+}
+
+/// Builds the reminder notification for an expiring document
+pub fn build_expiry_reminder(document: &ExpiringDocument, today: NaiveDate) -> CreateNotification {
+    let days = days_until_expiry(document.expiry_date, today);
+    let when = match days {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        days => format!("in {} days", days),
+    };
+
+    CreateNotification {
+        notification_type: "warning".to_string(),
+        title: "Document expiring soon".to_string(),
+        message: format!("{} expires {} ({})", document.filename, when, document.expiry_date.format("%Y-%m-%d")),
+        action_url: Some(format!("/documents/{}", document.document_id)),
+        metadata: Some(serde_json::json!({
+            "document_id": document.document_id,
+            "expiry_date": document.expiry_date,
+            "days_until_expiry": days,
+        })),
+    }
+}
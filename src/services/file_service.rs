@@ -8,11 +8,18 @@ use tracing::{info, warn, error};
 
 use crate::models::Document;
 use crate::services::s3_service::S3Service;
-use crate::storage::{StorageBackend, StorageConfig, factory};
+use crate::services::file_validator::FileValidator;
+use crate::services::malware_scanner::MalwareScanService;
+use crate::services::pdf_optimizer::PdfOptimizer;
+use crate::storage::{StorageAccessDenied, StorageBackend, StorageConfig, factory};
+use crate::storage::compression::StorageCompression;
 
 #[cfg(feature = "ocr")]
 use image::{DynamicImage, ImageFormat, imageops::FilterType};
 
+/// Longest side of document thumbnails in pixels unless configured otherwise
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 200;
+
 #[derive(Clone)]
 pub struct FileService {
     upload_path: String,
@@ -21,6 +28,16 @@ pub struct FileService {
     /// Legacy S3 service reference for backward compatibility
     /// TODO: Remove this after all usage sites are migrated
     s3_service: Option<Arc<S3Service>>,
+    /// Malware scanner run on files before ingestion stores them
+    malware_scan: Option<Arc<MalwareScanService>>,
+    /// Integrity checks run on files before ingestion stores them
+    file_validator: Option<Arc<FileValidator>>,
+    /// Optimizer run on ingested PDFs before they are stored
+    pdf_optimizer: Option<Arc<PdfOptimizer>>,
+    /// Whether reads and deletes on behalf of a user are confined to their namespace
+    user_isolation: bool,
+    /// Longest side of generated thumbnails in pixels
+    thumbnail_size: u32,
 }
 
 impl FileService {
@@ -33,6 +50,11 @@ impl FileService {
             upload_path,
             storage: Arc::new(local_backend),
             s3_service: None,
+            malware_scan: None,
+            file_validator: None,
+            pdf_optimizer: None,
+            user_isolation: true,
+            thumbnail_size: DEFAULT_THUMBNAIL_SIZE,
         }
     }
 
@@ -44,6 +66,11 @@ impl FileService {
             upload_path,
             storage: storage_backend,
             s3_service: Some(s3_service),
+            malware_scan: None,
+            file_validator: None,
+            pdf_optimizer: None,
+            user_isolation: true,
+            thumbnail_size: DEFAULT_THUMBNAIL_SIZE,
         }
     }
     
@@ -53,8 +80,63 @@ impl FileService {
             upload_path,
             storage,
             s3_service: None, // New API doesn't need legacy S3 reference
+            malware_scan: None,
+            file_validator: None,
+            pdf_optimizer: None,
+            user_isolation: true,
+            thumbnail_size: DEFAULT_THUMBNAIL_SIZE,
         }
     }
+
+    /// Scan ingested files with the given malware scanner before storing them
+    pub fn with_malware_scan(mut self, malware_scan: Option<MalwareScanService>) -> Self {
+        self.malware_scan = malware_scan.map(Arc::new);
+        self
+    }
+
+    /// Malware scanner for ingested files, if scanning is enabled
+    pub fn malware_scan(&self) -> Option<&MalwareScanService> {
+        self.malware_scan.as_deref()
+    }
+
+    /// Check ingested files for corruption before storing them
+    pub fn with_file_validator(mut self, file_validator: Option<FileValidator>) -> Self {
+        self.file_validator = file_validator.map(Arc::new);
+        self
+    }
+
+    /// Integrity checks for ingested files, if validation is enabled
+    pub fn file_validator(&self) -> Option<&FileValidator> {
+        self.file_validator.as_deref()
+    }
+
+    /// Optimize ingested PDFs with the given optimizer before storing them
+    pub fn with_pdf_optimizer(mut self, pdf_optimizer: Option<PdfOptimizer>) -> Self {
+        self.pdf_optimizer = pdf_optimizer.map(Arc::new);
+        self
+    }
+
+    /// Confines reads and deletes on behalf of a user to their storage namespace
+    pub fn with_user_isolation(mut self, enabled: bool) -> Self {
+        self.user_isolation = enabled;
+        self
+    }
+
+    /// Generate thumbnails whose longest side is `size` pixels
+    pub fn with_thumbnail_size(mut self, size: u32) -> Self {
+        self.thumbnail_size = size;
+        self
+    }
+
+    /// Longest side of generated thumbnails in pixels
+    pub fn thumbnail_size(&self) -> u32 {
+        self.thumbnail_size
+    }
+
+    /// Optimizer for ingested PDFs, if optimization is enabled
+    pub fn pdf_optimizer(&self) -> Option<&PdfOptimizer> {
+        self.pdf_optimizer.as_deref()
+    }
     
     /// Create FileService from storage configuration (factory pattern)
     pub async fn from_config(config: StorageConfig, upload_path: String) -> Result<Self> {
@@ -62,6 +144,16 @@ impl FileService {
         Ok(Self::with_storage(upload_path, storage))
     }
 
+    /// Create FileService from storage configuration with at-rest compression
+    pub async fn from_config_with_compression(
+        config: StorageConfig,
+        upload_path: String,
+        compression: StorageCompression,
+    ) -> Result<Self> {
+        let storage = factory::create_storage_backend_with_compression(config, compression).await?;
+        Ok(Self::with_storage(upload_path, storage))
+    }
+
     /// Check if S3 storage is enabled
     pub fn is_s3_enabled(&self) -> bool {
         // Check if storage backend is S3 type
@@ -110,6 +202,11 @@ impl FileService {
         Ok(())
     }
 
+    /// Get the base upload directory
+    pub fn get_upload_path(&self) -> &str {
+        &self.upload_path
+    }
+
     /// Get the path for a specific subdirectory
     pub fn get_subdirectory_path(&self, subdir: &str) -> PathBuf {
         Path::new(&self.upload_path).join(subdir)
@@ -220,6 +317,13 @@ impl FileService {
         Ok(storage_path)
     }
 
+    /// Save a document from a file on local disk, e.g. a download spooled to a temporary file
+    pub async fn save_document_file_from_path(&self, user_id: Uuid, document_id: Uuid, filename: &str, source: &Path) -> Result<String> {
+        let storage_path = self.storage.store_document_from_path(user_id, document_id, filename, source).await?;
+        info!("Saved document via storage backend: {}", storage_path);
+        Ok(storage_path)
+    }
+
     /// Save thumbnail (works with both local and S3)
     pub async fn save_thumbnail(&self, user_id: Uuid, document_id: Uuid, data: &[u8]) -> Result<String> {
         let storage_path = self.storage.store_thumbnail(user_id, document_id, data).await?;
@@ -385,28 +489,56 @@ impl FileService {
                 // Fall back to legacy file resolution for backward compatibility
                 let resolved_path = self.resolve_file_path(file_path).await?;
                 let data = fs::read(&resolved_path).await?;
-                Ok(data)
+                crate::storage::compression::decompress_for_path(&resolved_path, data)
             }
         }
     }
 
-    #[cfg(feature = "ocr")]
-    pub async fn get_or_generate_thumbnail(&self, file_path: &str, filename: &str) -> Result<Vec<u8>> {
-        // Use the structured thumbnails directory
-        let thumbnails_dir = self.get_thumbnails_path();
-        if !thumbnails_dir.exists() {
-            if let Err(e) = fs::create_dir_all(&thumbnails_dir).await {
-                error!("Failed to create thumbnails directory: {}", e);
-                return Err(anyhow::anyhow!("Failed to create thumbnails directory: {}", e));
-            }
+    /// Reads a file on behalf of `user_id`. With user isolation enabled, paths
+    /// outside the user's storage namespace fail with [`StorageAccessDenied`].
+    pub async fn read_user_file(&self, user_id: Uuid, file_path: &str) -> Result<Vec<u8>> {
+        self.check_user_path(user_id, file_path)?;
+        self.read_file(file_path).await
+    }
+
+    /// Deletes a single file on behalf of `user_id`, through the storage backend
+    /// that holds it. Local files kept from before a move to S3 are removed
+    /// from disk.
+    pub async fn delete_user_file(&self, user_id: Uuid, file_path: &str) -> Result<()> {
+        self.check_user_path(user_id, file_path)?;
+        if file_path.starts_with("s3://") || self.storage.storage_type() == "local" {
+            return self.storage.delete_file(file_path).await;
+        }
+        match fs::remove_file(file_path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
         }
+    }
 
-        // Generate thumbnail filename based on original file path
-        let file_stem = Path::new(file_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown");
-        let thumbnail_path = thumbnails_dir.join(format!("{}_thumb.jpg", file_stem));
+    /// Fails with [`StorageAccessDenied`] if user isolation is enabled and
+    /// `file_path` is outside the storage namespace of `user_id`. Object keys are
+    /// judged by the storage backend; local paths, including files kept from
+    /// before a move to S3, must be in the user's directories under the upload
+    /// directory, such as `documents/<user id>/`.
+    pub fn check_user_path(&self, user_id: Uuid, file_path: &str) -> Result<()> {
+        if !self.user_isolation {
+            return Ok(());
+        }
+        let allowed = if file_path.starts_with("s3://") {
+            self.storage.is_user_path(user_id, file_path)
+        } else {
+            crate::storage::is_user_local_path(user_id, file_path, &self.upload_path)
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(StorageAccessDenied::new(user_id, file_path).into())
+        }
+    }
+
+    #[cfg(feature = "ocr")]
+    pub async fn get_or_generate_thumbnail(&self, user_id: Uuid, file_path: &str, filename: &str) -> Result<Vec<u8>> {
+        let thumbnail_path = self.thumbnail_cache_path(user_id, file_path).await?;
 
         // Check if thumbnail already exists
         if thumbnail_path.exists() {
@@ -423,6 +555,37 @@ impl FileService {
         Ok(thumbnail_data)
     }
 
+    /// Generates a document's thumbnail at the configured size, replacing the
+    /// cached one
+    #[cfg(feature = "ocr")]
+    pub async fn regenerate_thumbnail(&self, user_id: Uuid, file_path: &str, filename: &str) -> Result<Vec<u8>> {
+        let thumbnail_path = self.thumbnail_cache_path(user_id, file_path).await?;
+        let thumbnail_data = self.generate_thumbnail(file_path, filename).await?;
+        fs::write(&thumbnail_path, &thumbnail_data).await?;
+        Ok(thumbnail_data)
+    }
+
+    /// Where the thumbnail of the file at `file_path` is cached, creating the
+    /// user's thumbnails directory if needed
+    #[cfg(feature = "ocr")]
+    async fn thumbnail_cache_path(&self, user_id: Uuid, file_path: &str) -> Result<PathBuf> {
+        // Use the user's structured thumbnails directory
+        let thumbnails_dir = self.get_thumbnails_path().join(user_id.to_string());
+        if !thumbnails_dir.exists() {
+            if let Err(e) = fs::create_dir_all(&thumbnails_dir).await {
+                error!("Failed to create thumbnails directory: {}", e);
+                return Err(anyhow::anyhow!("Failed to create thumbnails directory: {}", e));
+            }
+        }
+
+        // Generate thumbnail filename based on original file path
+        let file_stem = Path::new(file_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown");
+        Ok(thumbnails_dir.join(format!("{}_thumb.jpg", file_stem)))
+    }
+
     #[cfg(feature = "ocr")]
     async fn generate_thumbnail(&self, file_path: &str, filename: &str) -> Result<Vec<u8>> {
         let file_data = self.read_file(file_path).await?;
@@ -441,7 +604,7 @@ impl FileService {
             "pdf" => {
                 self.generate_pdf_thumbnail(&file_data).await
             }
-            "txt" => {
+            "txt" | "md" | "markdown" => {
                 self.generate_text_thumbnail(&file_data).await
             }
             "doc" | "docx" => {
@@ -457,7 +620,7 @@ impl FileService {
     #[cfg(feature = "ocr")]
     async fn generate_image_thumbnail(&self, file_data: &[u8]) -> Result<Vec<u8>> {
         let img = image::load_from_memory(file_data)?;
-        let thumbnail = img.resize(200, 200, FilterType::Lanczos3);
+        let thumbnail = img.resize(self.thumbnail_size, self.thumbnail_size, FilterType::Lanczos3);
         
         // Convert to RGB if the image has an alpha channel (RGBA)
         // JPEG doesn't support transparency, so we need to remove the alpha channel
@@ -500,7 +663,7 @@ impl FileService {
         let output = Command::new("pdftoppm")
             .arg("-f").arg("1")          // First page only
             .arg("-l").arg("1")          // Last page (same as first)
-            .arg("-scale-to").arg("200") // Scale to 200px width
+            .arg("-scale-to").arg(self.thumbnail_size.to_string()) // Scale to the thumbnail size
             .arg("-png")                 // Output as PNG
             .arg(&temp_pdf_path)
             .arg(&format!("/tmp/pdf_thumb_{}", temp_id)) // Output prefix
@@ -523,8 +686,8 @@ impl FileService {
                         // Convert PNG to JPEG thumbnail
                         match image::load_from_memory(&png_data) {
                             Ok(img) => {
-                                // Resize to 200x200 maintaining aspect ratio
-                                let thumbnail = img.resize(200, 200, image::imageops::FilterType::Lanczos3);
+                                // Resize to the thumbnail size maintaining aspect ratio
+                                let thumbnail = img.resize(self.thumbnail_size, self.thumbnail_size, image::imageops::FilterType::Lanczos3);
                                 
                                 // Convert to JPEG
                                 let mut buffer = Vec::new();
@@ -566,8 +729,8 @@ impl FileService {
     async fn generate_text_based_thumbnail(&self, text: &str, file_type: &str, bg_color: image::Rgb<u8>) -> Result<Vec<u8>> {
         use image::{RgbImage, Rgb, DynamicImage, ImageFormat};
         
-        let width = 200;
-        let height = 200;
+        let width = self.thumbnail_size;
+        let height = self.thumbnail_size;
         let mut img = RgbImage::new(width, height);
         
         // Fill background
@@ -621,7 +784,7 @@ impl FileService {
         // Create a simple colored rectangle as placeholder
         use image::{RgbImage, Rgb};
         
-        let mut img = RgbImage::new(200, 200);
+        let mut img = RgbImage::new(self.thumbnail_size, self.thumbnail_size);
         
         // Different colors for different file types
         let color = match file_type {
@@ -645,17 +808,31 @@ impl FileService {
     }
 
     #[cfg(not(feature = "ocr"))]
-    pub async fn get_or_generate_thumbnail(&self, _file_path: &str, _filename: &str) -> Result<Vec<u8>> {
+    pub async fn get_or_generate_thumbnail(&self, _user_id: Uuid, _file_path: &str, _filename: &str) -> Result<Vec<u8>> {
+        anyhow::bail!("Thumbnail generation requires OCR feature")
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub async fn regenerate_thumbnail(&self, _user_id: Uuid, _file_path: &str, _filename: &str) -> Result<Vec<u8>> {
         anyhow::bail!("Thumbnail generation requires OCR feature")
     }
 
     pub async fn delete_document_files(&self, document: &Document) -> Result<()> {
+        // An optimized PDF may have its original kept next to it
+        if document.mime_type == "application/pdf" {
+            let original_filename = crate::services::pdf_optimizer::original_pdf_filename(&document.filename);
+            if let Err(e) = self.storage.delete_document_files(document.user_id, document.id, &original_filename).await {
+                warn!("Failed to delete the original PDF of document {}: {}", document.id, e);
+            }
+        }
+
         // Use storage backend for deletion - it handles both S3 and local storage
         match self.storage.delete_document_files(document.user_id, document.id, &document.filename).await {
             Ok(_) => {
                 info!("Successfully deleted files for document {} via storage backend", document.id);
                 return Ok(());
             }
+            Err(e) if e.is::<StorageAccessDenied>() => return Err(e),
             Err(e) => {
                 warn!("Storage backend deletion failed for document {}: {}. Falling back to legacy deletion.", document.id, e);
                 // Fall back to legacy deletion logic for backward compatibility
@@ -692,23 +869,27 @@ impl FileService {
         }
 
         // Delete main document file
+        self.check_user_path(document.user_id, &document.file_path)?;
         let main_file = Path::new(&document.file_path);
         if let Some(deleted_path) = safe_delete(&main_file, &mut serious_errors).await {
             deleted_files.push(deleted_path);
         }
 
-        // Delete thumbnail if it exists
+        // Delete thumbnail and processed image if they exist, in the user's
+        // directories or the flat ones used before
+        let user_dir = document.user_id.to_string();
         let thumbnail_filename = format!("{}_thumb.jpg", document.id);
-        let thumbnail_path = self.get_thumbnails_path().join(&thumbnail_filename);
-        if let Some(deleted_path) = safe_delete(&thumbnail_path, &mut serious_errors).await {
-            deleted_files.push(deleted_path);
-        }
-
-        // Delete processed image if it exists
         let processed_image_filename = format!("{}_processed.png", document.id);
-        let processed_image_path = self.get_processed_images_path().join(&processed_image_filename);
-        if let Some(deleted_path) = safe_delete(&processed_image_path, &mut serious_errors).await {
-            deleted_files.push(deleted_path);
+        let derived_files = [
+            self.get_thumbnails_path().join(&user_dir).join(&thumbnail_filename),
+            self.get_thumbnails_path().join(&thumbnail_filename),
+            self.get_processed_images_path().join(&user_dir).join(&processed_image_filename),
+            self.get_processed_images_path().join(&processed_image_filename),
+        ];
+        for derived_file in &derived_files {
+            if let Some(deleted_path) = safe_delete(derived_file, &mut serious_errors).await {
+                deleted_files.push(deleted_path);
+            }
         }
 
         // Only fail if there were serious errors (not "file not found")
@@ -0,0 +1,302 @@
+//! Structural integrity checks for ingested files
+//!
+//! Partially downloaded or damaged files are caught before they are stored and
+//! queued for OCR. The `lenient` mode only looks for the markers a truncated file
+//! loses: the PDF header and `%%EOF` trailer, image signatures and end markers,
+//! and the end of central directory record of ZIP-based Office documents. The
+//! `strict` mode also decodes images and reads the whole ZIP central directory.
+
+use std::io::{BufRead, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::str::FromStr;
+use tracing::warn;
+
+use crate::config::Config;
+
+/// PDF readers look for `%%EOF` within the last kilobyte of the file
+const PDF_TRAILER_WINDOW: u64 = 1024;
+/// The PDF header may be preceded by up to a kilobyte of junk
+const PDF_HEADER_WINDOW: u64 = 1024;
+/// End markers may be followed by a little padding
+const IMAGE_TRAILER_WINDOW: u64 = 1024;
+/// The end of central directory record is 22 bytes plus a comment of up to 64KiB
+const ZIP_EOCD_WINDOW: u64 = 22 + u16::MAX as u64;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_END: &[u8] = b"IEND";
+const JPEG_SIGNATURE: &[u8] = b"\xff\xd8\xff";
+const JPEG_END: &[u8] = b"\xff\xd9";
+const ZIP_LOCAL_HEADER: &[u8] = b"PK\x03\x04";
+const ZIP_EOCD: &[u8] = b"PK\x05\x06";
+
+/// How thoroughly ingested files are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileValidationMode {
+    Off,
+    /// Headers and end markers only; cheap enough for every file
+    #[default]
+    Lenient,
+    /// Also decode images and read the whole ZIP central directory
+    Strict,
+}
+
+impl FileValidationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileValidationMode::Off => "off",
+            FileValidationMode::Lenient => "lenient",
+            FileValidationMode::Strict => "strict",
+        }
+    }
+}
+
+impl FromStr for FileValidationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "none" | "false" => Ok(FileValidationMode::Off),
+            "lenient" | "basic" => Ok(FileValidationMode::Lenient),
+            "strict" => Ok(FileValidationMode::Strict),
+            other => Err(format!("Invalid file validation mode '{}' (expected off, lenient or strict)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for FileValidationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of checking a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileIntegrity {
+    Valid,
+    /// The file is damaged or truncated; holds what the check found
+    Corrupt(String),
+}
+
+/// File types with a structure worth checking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckedKind {
+    Pdf,
+    Png,
+    Jpeg,
+    Tiff,
+    Bmp,
+    OfficeZip,
+}
+
+impl CheckedKind {
+    fn from_mime(mime_type: &str) -> Option<Self> {
+        let mime_type = mime_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        match mime_type.as_str() {
+            "application/pdf" => Some(CheckedKind::Pdf),
+            "image/png" => Some(CheckedKind::Png),
+            "image/jpeg" | "image/jpg" => Some(CheckedKind::Jpeg),
+            "image/tiff" => Some(CheckedKind::Tiff),
+            "image/bmp" | "image/x-ms-bmp" => Some(CheckedKind::Bmp),
+            m if m.starts_with("application/vnd.openxmlformats-officedocument.")
+                || m.starts_with("application/vnd.oasis.opendocument.") =>
+            {
+                Some(CheckedKind::OfficeZip)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checks ingested files for damage before they are stored
+#[derive(Debug, Clone)]
+pub struct FileValidator {
+    mode: FileValidationMode,
+}
+
+impl FileValidator {
+    pub fn new(mode: FileValidationMode) -> Self {
+        Self { mode }
+    }
+
+    /// Returns None when FILE_VALIDATION_MODE is off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.file_validation_mode == FileValidationMode::Off {
+            return None;
+        }
+        Some(Self::new(config.file_validation_mode))
+    }
+
+    pub fn mode(&self) -> FileValidationMode {
+        self.mode
+    }
+
+    /// Checks a file held in memory
+    pub fn check(&self, data: &[u8], mime_type: &str) -> FileIntegrity {
+        self.check_reader(std::io::Cursor::new(data), mime_type)
+    }
+
+    /// Like [`check`](Self::check), for a file on disk. Only the parts of the file
+    /// the checks need are read.
+    pub async fn check_file(&self, path: &Path, mime_type: &str) -> FileIntegrity {
+        let validator = self.clone();
+        let path = path.to_path_buf();
+        let mime_type = mime_type.to_string();
+
+        let checked = tokio::task::spawn_blocking(move || {
+            std::fs::File::open(&path).map(|file| validator.check_reader(std::io::BufReader::new(file), &mime_type))
+        })
+        .await;
+
+        match checked {
+            Ok(Ok(integrity)) => integrity,
+            // Not a verdict on the file; ingestion reports the read failure itself
+            Ok(Err(e)) => {
+                warn!("Could not open file for validation: {}", e);
+                FileIntegrity::Valid
+            }
+            Err(e) => {
+                warn!("File validation task failed: {}", e);
+                FileIntegrity::Valid
+            }
+        }
+    }
+
+    fn check_reader<R: BufRead + Seek>(&self, mut reader: R, mime_type: &str) -> FileIntegrity {
+        if self.mode == FileValidationMode::Off {
+            return FileIntegrity::Valid;
+        }
+        let Some(kind) = CheckedKind::from_mime(mime_type) else {
+            return FileIntegrity::Valid;
+        };
+
+        match self.check_kind(&mut reader, kind) {
+            Ok(()) => FileIntegrity::Valid,
+            Err(reason) => FileIntegrity::Corrupt(reason),
+        }
+    }
+
+    fn check_kind<R: BufRead + Seek>(&self, reader: &mut R, kind: CheckedKind) -> Result<(), String> {
+        let size = reader.seek(SeekFrom::End(0)).map_err(read_error)?;
+        if size == 0 {
+            return Err("File is empty".to_string());
+        }
+
+        match kind {
+            CheckedKind::Pdf => {
+                if !contains(&read_range(reader, 0, PDF_HEADER_WINDOW)?, b"%PDF-") {
+                    return Err("Missing PDF header".to_string());
+                }
+                let trailer = read_tail(reader, size, PDF_TRAILER_WINDOW)?;
+                if !contains(&trailer, b"%%EOF") {
+                    return Err("Missing %%EOF marker; the PDF appears to be truncated".to_string());
+                }
+                if self.mode == FileValidationMode::Strict && !contains(&trailer, b"startxref") {
+                    return Err("Missing startxref; the PDF cross-reference table can't be located".to_string());
+                }
+                Ok(())
+            }
+            CheckedKind::Png => {
+                check_signature(reader, PNG_SIGNATURE, "PNG")?;
+                check_end_marker(reader, size, PNG_END, "PNG")?;
+                self.check_decodes(reader, kind)
+            }
+            CheckedKind::Jpeg => {
+                check_signature(reader, JPEG_SIGNATURE, "JPEG")?;
+                check_end_marker(reader, size, JPEG_END, "JPEG")?;
+                self.check_decodes(reader, kind)
+            }
+            CheckedKind::Tiff => {
+                let head = read_range(reader, 0, 4)?;
+                if head != b"II*\0" && head != b"MM\0*" {
+                    return Err("Missing TIFF signature".to_string());
+                }
+                self.check_decodes(reader, kind)
+            }
+            CheckedKind::Bmp => {
+                check_signature(reader, b"BM", "BMP")?;
+                self.check_decodes(reader, kind)
+            }
+            CheckedKind::OfficeZip => {
+                check_signature(reader, ZIP_LOCAL_HEADER, "ZIP")?;
+                if !contains(&read_tail(reader, size, ZIP_EOCD_WINDOW)?, ZIP_EOCD) {
+                    return Err("Missing ZIP end of central directory record; the document appears to be truncated".to_string());
+                }
+                if self.mode == FileValidationMode::Strict {
+                    reader.seek(SeekFrom::Start(0)).map_err(read_error)?;
+                    let mut archive = zip::ZipArchive::new(&mut *reader)
+                        .map_err(|e| format!("ZIP central directory could not be read: {}", e))?;
+                    for index in 0..archive.len() {
+                        archive
+                            .by_index_raw(index)
+                            .map_err(|e| format!("ZIP entry {} could not be read: {}", index, e))?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg(feature = "ocr")]
+    fn check_decodes<R: BufRead + Seek>(&self, reader: &mut R, kind: CheckedKind) -> Result<(), String> {
+        use image::{ImageError, ImageFormat};
+
+        if self.mode != FileValidationMode::Strict {
+            return Ok(());
+        }
+        let format = match kind {
+            CheckedKind::Png => ImageFormat::Png,
+            CheckedKind::Jpeg => ImageFormat::Jpeg,
+            CheckedKind::Tiff => ImageFormat::Tiff,
+            CheckedKind::Bmp => ImageFormat::Bmp,
+            CheckedKind::Pdf | CheckedKind::OfficeZip => return Ok(()),
+        };
+
+        reader.seek(SeekFrom::Start(0)).map_err(read_error)?;
+        match image::ImageReader::with_format(&mut *reader, format).decode() {
+            Ok(_) => Ok(()),
+            // Images this build can't decode, or that are too large to decode, aren't known to be damaged
+            Err(ImageError::Unsupported(_)) | Err(ImageError::Limits(_)) => Ok(()),
+            Err(e) => Err(format!("Image could not be decoded: {}", e)),
+        }
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    fn check_decodes<R: BufRead + Seek>(&self, _reader: &mut R, _kind: CheckedKind) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn read_error(e: std::io::Error) -> String {
+    format!("Failed to read file: {}", e)
+}
+
+fn read_range<R: Read + Seek>(reader: &mut R, start: u64, len: u64) -> Result<Vec<u8>, String> {
+    reader.seek(SeekFrom::Start(start)).map_err(read_error)?;
+    let mut buf = Vec::new();
+    reader.take(len).read_to_end(&mut buf).map_err(read_error)?;
+    Ok(buf)
+}
+
+fn read_tail<R: Read + Seek>(reader: &mut R, size: u64, len: u64) -> Result<Vec<u8>, String> {
+    read_range(reader, size.saturating_sub(len), len)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+fn check_signature<R: Read + Seek>(reader: &mut R, signature: &[u8], format: &str) -> Result<(), String> {
+    if read_range(reader, 0, signature.len() as u64)? == signature {
+        Ok(())
+    } else {
+        Err(format!("Missing {} signature", format))
+    }
+}
+
+fn check_end_marker<R: Read + Seek>(reader: &mut R, size: u64, marker: &[u8], format: &str) -> Result<(), String> {
+    if contains(&read_tail(reader, size, IMAGE_TRAILER_WINDOW)?, marker) {
+        Ok(())
+    } else {
+        Err(format!("Missing {} end marker; the image appears to be truncated", format))
+    }
+}
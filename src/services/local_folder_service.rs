@@ -9,6 +9,24 @@ use serde_json;
 
 use crate::models::{FileIngestionInfo, LocalFolderSourceConfig};
 
+/// Key of the file id in the source metadata of discovered files
+pub const FILE_ID_METADATA_KEY: &str = "file_id";
+
+/// Identifier of a file that survives renames and moves on the same
+/// filesystem: `device:inode` on Unix. None where the platform has none.
+pub fn file_id(metadata: &fs::Metadata) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(format!("{}:{}", metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LocalFolderService {
     config: LocalFolderSourceConfig,
@@ -133,6 +151,9 @@ impl LocalFolderService {
                                     additional_metadata.insert("nlinks".to_string(), serde_json::Value::Number(metadata.nlink().into()));
                                     additional_metadata.insert("device".to_string(), serde_json::Value::Number(metadata.dev().into()));
                                 }
+                                if let Some(file_id) = file_id(&metadata) {
+                                    additional_metadata.insert(FILE_ID_METADATA_KEY.to_string(), serde_json::Value::String(file_id));
+                                }
                                 
                                 // Add file attributes
                                 additional_metadata.insert("readonly".to_string(), serde_json::Value::Bool(metadata.permissions().readonly()));
@@ -231,6 +252,7 @@ impl LocalFolderService {
         match extension {
             "pdf" => "application/pdf",
             "txt" => "text/plain",
+            "md" | "markdown" => "text/markdown",
             "png" => "image/png",
             "jpg" | "jpeg" => "image/jpeg",
             "tiff" | "tif" => "image/tiff",
@@ -284,6 +306,11 @@ mod tests {
             sync_interval_minutes: 60,
             recursive: false,
             follow_symlinks: false,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            track_file_ids: false,
         };
 
         let service = LocalFolderService::new(config).unwrap();
@@ -317,6 +344,11 @@ mod tests {
             sync_interval_minutes: 60,
             recursive: false,
             follow_symlinks: false,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            track_file_ids: false,
         };
 
         let service = LocalFolderService::new(config).unwrap();
@@ -0,0 +1,275 @@
+//! Optional malware scanning of ingested files
+//!
+//! Bytes are handed to an external scanner before they are stored. Two scanners
+//! are supported: a ClamAV daemon reached over its socket (`INSTREAM` protocol),
+//! or any command that reads the file from stdin and exits 0 for clean and 1 for
+//! infected (the `clamdscan -` / `clamscan -` convention).
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::{debug, warn};
+
+use crate::config::Config;
+
+/// Chunk size for clamd INSTREAM; well below clamd's default StreamMaxLength chunking
+const CLAMD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Result of scanning a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// The scanner flagged the file; holds the reported signature/threat name
+    Infected(String),
+}
+
+/// What ingestion should do with a file after scanning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanDecision {
+    /// Store the file normally
+    Allow,
+    /// Quarantine the file instead of storing it
+    Quarantine(String),
+    /// The scan couldn't complete and the scanner is configured fail-closed
+    Block(String),
+}
+
+/// A malware scanner backend
+#[async_trait]
+pub trait MalwareScanner: Send + Sync {
+    async fn scan(&self, data: &[u8]) -> Result<ScanVerdict>;
+
+    /// Scan a file on disk. The default reads it into memory; scanners that can
+    /// stream from the file override this.
+    async fn scan_file(&self, path: &Path) -> Result<ScanVerdict> {
+        let data = tokio::fs::read(path).await?;
+        self.scan(&data).await
+    }
+
+    /// Human-readable name used in logs
+    fn name(&self) -> &str;
+}
+
+/// Scans via a clamd socket, e.g. `tcp://clamav:3310` or `unix:///run/clamav/clamd.ctl`
+pub struct ClamdScanner {
+    endpoint: String,
+    timeout: Duration,
+}
+
+impl ClamdScanner {
+    pub fn new(endpoint: String, timeout: Duration) -> Self {
+        Self { endpoint, timeout }
+    }
+
+    async fn instream<S, R>(mut stream: S, mut source: R) -> Result<String>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+        R: AsyncRead + Unpin,
+    {
+        stream.write_all(b"zINSTREAM\0").await?;
+        let mut chunk = vec![0u8; CLAMD_CHUNK_SIZE];
+        loop {
+            let read = source.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            stream.write_all(&(read as u32).to_be_bytes()).await?;
+            stream.write_all(&chunk[..read]).await?;
+        }
+        stream.write_all(&0u32.to_be_bytes()).await?;
+        stream.flush().await?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await?;
+        Ok(String::from_utf8_lossy(&response).to_string())
+    }
+
+    async fn scan_source<R: AsyncRead + Unpin>(&self, source: R) -> Result<ScanVerdict> {
+        let scan = async {
+            if let Some(path) = self.endpoint.strip_prefix("unix://") {
+                #[cfg(unix)]
+                {
+                    let stream = tokio::net::UnixStream::connect(path).await?;
+                    return Self::instream(stream, source).await;
+                }
+                #[cfg(not(unix))]
+                {
+                    return Err(anyhow!("Unix sockets are not supported on this platform: {}", path));
+                }
+            }
+
+            let address = self.endpoint.strip_prefix("tcp://").unwrap_or(&self.endpoint);
+            let stream = tokio::net::TcpStream::connect(address).await?;
+            Self::instream(stream, source).await
+        };
+
+        let response = tokio::time::timeout(self.timeout, scan)
+            .await
+            .map_err(|_| anyhow!("clamd scan timed out after {:?}", self.timeout))??;
+
+        parse_clamd_response(&response)
+    }
+}
+
+#[async_trait]
+impl MalwareScanner for ClamdScanner {
+    async fn scan(&self, data: &[u8]) -> Result<ScanVerdict> {
+        self.scan_source(data).await
+    }
+
+    async fn scan_file(&self, path: &Path) -> Result<ScanVerdict> {
+        let file = tokio::fs::File::open(path).await?;
+        self.scan_source(file).await
+    }
+
+    fn name(&self) -> &str {
+        "clamd"
+    }
+}
+
+/// Parses a clamd reply such as `stream: OK` or `stream: Eicar-Signature FOUND`
+pub fn parse_clamd_response(response: &str) -> Result<ScanVerdict> {
+    let reply = response.trim_end_matches(['\0', '\n', '\r']).trim();
+    let result = reply.split_once(": ").map(|(_, result)| result).unwrap_or(reply);
+
+    if result == "OK" {
+        Ok(ScanVerdict::Clean)
+    } else if let Some(signature) = result.strip_suffix(" FOUND") {
+        Ok(ScanVerdict::Infected(signature.trim().to_string()))
+    } else {
+        Err(anyhow!("Unexpected clamd response: {}", reply))
+    }
+}
+
+/// Scans by piping the file to a command's stdin
+pub struct CommandScanner {
+    command: String,
+    timeout: Duration,
+}
+
+impl CommandScanner {
+    pub fn new(command: String, timeout: Duration) -> Self {
+        Self { command, timeout }
+    }
+
+    async fn scan_source<R: AsyncRead + Unpin>(&self, mut source: R) -> Result<ScanVerdict> {
+        let mut parts = self.command.split_whitespace();
+        let program = parts.next().ok_or_else(|| anyhow!("Malware scan command is empty"))?;
+
+        let mut child = tokio::process::Command::new(program)
+            .args(parts)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start malware scan command '{}': {}", program, e))?;
+
+        let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("Failed to open scanner stdin"))?;
+        let write = async {
+            // A scanner may exit before reading everything (e.g. on first match)
+            if let Err(e) = tokio::io::copy(&mut source, &mut stdin).await {
+                debug!("Malware scan command closed stdin early: {}", e);
+            }
+            drop(stdin);
+        };
+
+        let (_, output) = tokio::time::timeout(self.timeout, async { tokio::join!(write, child.wait_with_output()) })
+            .await
+            .map_err(|_| anyhow!("Malware scan command timed out after {:?}", self.timeout))?;
+        let output = output?;
+
+        match output.status.code() {
+            Some(0) => Ok(ScanVerdict::Clean),
+            Some(1) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let signature = stdout
+                    .lines()
+                    .find_map(|line| line.trim().strip_suffix(" FOUND"))
+                    .map(|line| line.rsplit(": ").next().unwrap_or(line).to_string())
+                    .or_else(|| stdout.lines().map(str::trim).find(|line| !line.is_empty()).map(str::to_string))
+                    .unwrap_or_else(|| "unknown threat".to_string());
+                Ok(ScanVerdict::Infected(signature))
+            }
+            code => Err(anyhow!(
+                "Malware scan command failed (exit code {:?}): {}",
+                code,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl MalwareScanner for CommandScanner {
+    async fn scan(&self, data: &[u8]) -> Result<ScanVerdict> {
+        self.scan_source(data).await
+    }
+
+    async fn scan_file(&self, path: &Path) -> Result<ScanVerdict> {
+        let file = tokio::fs::File::open(path).await?;
+        self.scan_source(file).await
+    }
+
+    fn name(&self) -> &str {
+        "command"
+    }
+}
+
+/// Applies the fail-open/fail-closed policy on top of a scanner
+#[derive(Clone)]
+pub struct MalwareScanService {
+    scanner: Arc<dyn MalwareScanner>,
+    fail_open: bool,
+}
+
+impl MalwareScanService {
+    pub fn new(scanner: Arc<dyn MalwareScanner>, fail_open: bool) -> Self {
+        Self { scanner, fail_open }
+    }
+
+    /// Build the scan service from configuration. Returns None when scanning is disabled.
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.malware_scan_enabled {
+            return Ok(None);
+        }
+
+        let timeout = Duration::from_secs(config.malware_scan_timeout_seconds);
+        let scanner: Arc<dyn MalwareScanner> = match (&config.malware_scan_endpoint, &config.malware_scan_command) {
+            (Some(endpoint), _) => Arc::new(ClamdScanner::new(endpoint.clone(), timeout)),
+            (None, Some(command)) => Arc::new(CommandScanner::new(command.clone(), timeout)),
+            (None, None) => {
+                return Err(anyhow!(
+                    "MALWARE_SCAN_ENABLED is set but neither MALWARE_SCAN_ENDPOINT nor MALWARE_SCAN_COMMAND is configured"
+                ))
+            }
+        };
+
+        Ok(Some(Self::new(scanner, config.malware_scan_fail_open)))
+    }
+
+    /// Scan a file and decide whether ingestion may store it
+    pub async fn check(&self, data: &[u8]) -> ScanDecision {
+        self.decide(self.scanner.scan(data).await)
+    }
+
+    /// Like [`check`](Self::check), for a file on disk
+    pub async fn check_file(&self, path: &Path) -> ScanDecision {
+        self.decide(self.scanner.scan_file(path).await)
+    }
+
+    fn decide(&self, verdict: Result<ScanVerdict>) -> ScanDecision {
+        match verdict {
+            Ok(ScanVerdict::Clean) => ScanDecision::Allow,
+            Ok(ScanVerdict::Infected(signature)) => ScanDecision::Quarantine(signature),
+            Err(e) if self.fail_open => {
+                warn!("Malware scan with {} failed, allowing file (fail-open): {}", self.scanner.name(), e);
+                ScanDecision::Allow
+            }
+            Err(e) => ScanDecision::Block(format!("Malware scan failed: {}", e)),
+        }
+    }
+}
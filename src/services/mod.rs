@@ -1,12 +1,22 @@
+pub mod expiry_reminders;
 pub mod file_service;
+pub mod credential_encryption;
+pub mod download_integrity;
+pub mod file_validator;
 pub mod local_folder_service;
 pub mod local_folder_error_classifier;
+pub mod malware_scanner;
+pub mod notification_digest;
 pub mod ocr_retry_service;
+pub mod pdf_optimizer;
 pub mod s3_service;
 pub mod s3_service_stub;
 pub mod s3_error_classifier;
+pub mod source_deletion;
 pub mod source_error_tracker;
 pub mod sync_progress_tracker;
+pub mod thumbnail_regeneration;
+pub mod user_directories;
 pub mod user_watch_service;
 pub mod webdav;
 pub mod webdav_metrics_simple;
@@ -0,0 +1,145 @@
+//! Digest delivery for notifications
+//!
+//! Users with `notification_delivery_mode = "digest"` get their non-error
+//! notifications queued in `pending_notifications` (see
+//! [`Database::deliver_notification`]). This service periodically coalesces each
+//! due (user, type) group into one summary notification.
+
+use anyhow::Result;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+use crate::{
+    db::Database,
+    models::{CreateNotification, PendingNotification},
+};
+
+/// How often pending notifications are checked for due digests
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Number of individual messages quoted in a digest before summarizing the rest
+const MAX_QUOTED_MESSAGES: usize = 3;
+
+#[derive(Clone)]
+pub struct NotificationDigestService {
+    db: Database,
+}
+
+impl NotificationDigestService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Flushes due digests forever, once per [`FLUSH_INTERVAL`]
+    pub async fn start(&self) {
+        info!("Starting notification digest flusher");
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.flush_due().await {
+                error!("Failed to flush notification digests: {}", e);
+            }
+        }
+    }
+
+    /// Sends one digest per due (user, type) group and returns how many were sent
+    pub async fn flush_due(&self) -> Result<usize> {
+        let pending = self.db.get_due_pending_notifications().await?;
+        let mut sent = 0;
+
+        // Rows arrive ordered by user and type, so groups are contiguous
+        for group in pending.chunk_by(|a, b| a.user_id == b.user_id && a.notification_type == b.notification_type) {
+            let user_id = group[0].user_id;
+            let digest = build_digest(group);
+            let ids: Vec<_> = group.iter().map(|n| n.id).collect();
+
+            match self.db.flush_pending_notifications(user_id, &digest, &ids).await {
+                Ok(_) => {
+                    debug!("Sent digest of {} '{}' notifications to user {}", group.len(), digest.notification_type, user_id);
+                    sent += 1;
+                }
+                Err(e) => error!("Failed to send notification digest to user {}: {}", user_id, e),
+            }
+        }
+
+        Ok(sent)
+    }
+}
+
+/// Builds the summary notification for a group of pending notifications of one type.
+///
+/// A single pending notification is delivered unchanged. Larger groups keep a
+/// shared title and action URL when every entry agrees, and report the total of
+/// any `files_processed` counts, e.g. "Sync completed: 1,240 documents added".
+pub fn build_digest(pending: &[PendingNotification]) -> CreateNotification {
+    let first = &pending[0];
+    if pending.len() == 1 {
+        return CreateNotification {
+            notification_type: first.notification_type.clone(),
+            title: first.title.clone(),
+            message: first.message.clone(),
+            action_url: first.action_url.clone(),
+            metadata: first.metadata.clone(),
+        };
+    }
+
+    let count = pending.len();
+    let title = if pending.iter().all(|n| n.title == first.title) {
+        format!("{} ({})", first.title, count)
+    } else {
+        format!("{} notifications", count)
+    };
+    let action_url = if pending.iter().all(|n| n.action_url == first.action_url) {
+        first.action_url.clone()
+    } else {
+        None
+    };
+
+    let files_processed: u64 = pending
+        .iter()
+        .filter_map(|n| n.metadata.as_ref()?.get("files_processed")?.as_u64())
+        .sum();
+
+    let message = if files_processed > 0 {
+        format!(
+            "Sync completed: {} {} added",
+            format_count(files_processed),
+            if files_processed == 1 { "document" } else { "documents" }
+        )
+    } else {
+        let mut quoted: Vec<&str> = pending.iter().rev().take(MAX_QUOTED_MESSAGES).map(|n| n.message.as_str()).collect();
+        quoted.reverse();
+        let mut message = quoted.join("\n");
+        if count > MAX_QUOTED_MESSAGES {
+            message.push_str(&format!("\n…and {} more", count - MAX_QUOTED_MESSAGES));
+        }
+        message
+    };
+
+    CreateNotification {
+        notification_type: first.notification_type.clone(),
+        title,
+        message,
+        action_url,
+        metadata: Some(serde_json::json!({
+            "digest": true,
+            "notification_count": count,
+            "files_processed": files_processed,
+            "first_created_at": first.created_at,
+            "last_created_at": pending[count - 1].created_at,
+        })),
+    }
+}
+
+/// Formats a count with thousands separators, e.g. 1240 -> "1,240"
+fn format_count(value: u64) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
@@ -242,6 +242,7 @@ impl OcrRetryService {
                 ocr_error = NULL,
                 ocr_failure_reason = NULL,
                 ocr_confidence = NULL,
+                ocr_confidence_raw = NULL,
                 ocr_word_count = NULL,
                 ocr_processing_time_ms = NULL,
                 ocr_completed_at = NULL,
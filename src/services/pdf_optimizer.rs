@@ -0,0 +1,213 @@
+//! Optional optimization of ingested PDFs
+//!
+//! Bloated PDFs (typically uncompressed scans) are rewritten by an external
+//! optimizer before they are stored: Ghostscript's `pdfwrite` device or
+//! `ocrmypdf --optimize`. Small PDFs and PDFs already produced by one of these
+//! tools are left alone, and the optimized output is only used when it saves
+//! enough space. Settings keep images at print resolution so the optimized
+//! file still OCRs well.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Optimized output must be at most this fraction of the original to be kept
+const MIN_SAVINGS_RATIO: f64 = 0.9;
+
+/// Producer strings of PDFs that have already been through an optimizer
+const OPTIMIZED_PRODUCERS: &[&[u8]] = &[b"GPL Ghostscript", b"Artifex Ghostscript", b"ocrmypdf", b"OCRmyPDF"];
+
+/// External tool used to optimize PDFs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PdfOptimizerTool {
+    #[default]
+    None,
+    Ghostscript,
+    Ocrmypdf,
+}
+
+impl PdfOptimizerTool {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PdfOptimizerTool::None => "none",
+            PdfOptimizerTool::Ghostscript => "ghostscript",
+            PdfOptimizerTool::Ocrmypdf => "ocrmypdf",
+        }
+    }
+
+    /// Program and arguments that rewrite `input` to `output`
+    pub fn command(&self, input: &Path, output: &Path) -> Option<(&'static str, Vec<String>)> {
+        let input = input.to_string_lossy().to_string();
+        let output = output.to_string_lossy().to_string();
+        match self {
+            PdfOptimizerTool::None => None,
+            // /printer keeps images at 300 dpi, enough for Tesseract
+            PdfOptimizerTool::Ghostscript => Some((
+                "gs",
+                vec![
+                    "-sDEVICE=pdfwrite".to_string(),
+                    "-dCompatibilityLevel=1.5".to_string(),
+                    "-dPDFSETTINGS=/printer".to_string(),
+                    "-dNOPAUSE".to_string(),
+                    "-dBATCH".to_string(),
+                    "-dQUIET".to_string(),
+                    "-dSAFER".to_string(),
+                    format!("-sOutputFile={}", output),
+                    input,
+                ],
+            )),
+            // Optimization only: pages are not OCRed here, the OCR queue does that
+            PdfOptimizerTool::Ocrmypdf => Some((
+                "ocrmypdf",
+                vec![
+                    "--skip-text".to_string(),
+                    "--tesseract-timeout".to_string(),
+                    "0".to_string(),
+                    "--optimize".to_string(),
+                    "1".to_string(),
+                    "--output-type".to_string(),
+                    "pdf".to_string(),
+                    input,
+                    output,
+                ],
+            )),
+        }
+    }
+}
+
+impl FromStr for PdfOptimizerTool {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "none" | "off" | "false" => Ok(PdfOptimizerTool::None),
+            "ghostscript" | "gs" => Ok(PdfOptimizerTool::Ghostscript),
+            "ocrmypdf" => Ok(PdfOptimizerTool::Ocrmypdf),
+            other => Err(format!("Invalid PDF optimizer '{}' (expected none, ghostscript or ocrmypdf)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for PdfOptimizerTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether a PDF was written by one of the supported optimizers
+pub fn is_already_optimized(data: &[u8]) -> bool {
+    OPTIMIZED_PRODUCERS
+        .iter()
+        .any(|producer| data.windows(producer.len()).any(|window| window == *producer))
+}
+
+/// Filename the original of an optimized PDF is stored under. The extension
+/// gives it a storage path of its own next to the optimized file.
+pub fn original_pdf_filename(filename: &str) -> String {
+    format!("{}.original", filename)
+}
+
+/// Runs the configured optimizer on ingested PDFs
+#[derive(Debug, Clone)]
+pub struct PdfOptimizer {
+    tool: PdfOptimizerTool,
+    min_size_bytes: u64,
+    timeout: Duration,
+    temp_dir: PathBuf,
+}
+
+impl PdfOptimizer {
+    pub fn new(tool: PdfOptimizerTool, min_size_bytes: u64, timeout: Duration) -> Self {
+        Self {
+            tool,
+            min_size_bytes,
+            timeout,
+            temp_dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Returns None when PDF_OPTIMIZER is not set
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.pdf_optimizer == PdfOptimizerTool::None {
+            return None;
+        }
+        Some(Self::new(
+            config.pdf_optimizer,
+            config.pdf_optimize_min_size_kb.saturating_mul(1024),
+            Duration::from_secs(config.pdf_optimize_timeout_seconds),
+        ))
+    }
+
+    pub fn tool(&self) -> PdfOptimizerTool {
+        self.tool
+    }
+
+    /// Whether a PDF is worth running through the optimizer
+    pub fn should_optimize(&self, data: &[u8]) -> bool {
+        (data.len() as u64) >= self.min_size_bytes && data.starts_with(b"%PDF") && !is_already_optimized(data)
+    }
+
+    /// Optimizes a PDF. Returns None when the PDF is skipped or the optimized
+    /// output doesn't save enough space.
+    pub async fn optimize(&self, data: &[u8]) -> Result<Option<Vec<u8>>> {
+        if !self.should_optimize(data) {
+            return Ok(None);
+        }
+
+        let id = Uuid::new_v4();
+        let input = self.temp_dir.join(format!("pdf-optimize-{}-in.pdf", id));
+        let output = self.temp_dir.join(format!("pdf-optimize-{}-out.pdf", id));
+        tokio::fs::write(&input, data).await?;
+        let result = self.run(&input, &output).await;
+        let _ = tokio::fs::remove_file(&input).await;
+        let _ = tokio::fs::remove_file(&output).await;
+
+        let optimized = result?;
+        if !optimized.starts_with(b"%PDF") {
+            return Err(anyhow!("{} did not produce a PDF", self.tool));
+        }
+        if (optimized.len() as f64) > (data.len() as f64) * MIN_SAVINGS_RATIO {
+            debug!(
+                "Keeping original PDF: {} only reduced it from {} to {} bytes",
+                self.tool,
+                data.len(),
+                optimized.len()
+            );
+            return Ok(None);
+        }
+        Ok(Some(optimized))
+    }
+
+    async fn run(&self, input: &Path, output: &Path) -> Result<Vec<u8>> {
+        let (program, args) = self
+            .tool
+            .command(input, output)
+            .ok_or_else(|| anyhow!("No PDF optimizer configured"))?;
+
+        let child = tokio::process::Command::new(program)
+            .args(&args)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start PDF optimizer '{}': {}", program, e))?;
+
+        let finished = tokio::time::timeout(self.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| anyhow!("PDF optimizer timed out after {:?}", self.timeout))??;
+        if !finished.status.success() {
+            return Err(anyhow!(
+                "PDF optimizer failed (exit code {:?}): {}",
+                finished.status.code(),
+                String::from_utf8_lossy(&finished.stderr).trim()
+            ));
+        }
+
+        Ok(tokio::fs::read(output).await?)
+    }
+}
@@ -17,8 +17,20 @@ impl S3ErrorClassifier {
     fn classify_s3_error_type(&self, error: &anyhow::Error) -> SourceErrorType {
         let error_str = error.to_string().to_lowercase();
         
+        // Timeouts and throttling are marked by S3Service from the SDK's error
+        // details, so they are recognized even when the AWS error code is missing
+        if error_str.contains("s3 request timed out") {
+            SourceErrorType::Timeout
+        } else if error_str.contains("s3 request throttled") {
+            SourceErrorType::RateLimited
         // AWS S3 specific error patterns
-        if error_str.contains("nosuchbucket") || error_str.contains("no such bucket") {
+        } else if error_str.contains("nosuchbucket") || error_str.contains("no such bucket") {
+            SourceErrorType::NotFound
+        } else if error_str.contains("rejected the credentials") {
+            SourceErrorType::PermissionDenied
+        } else if error_str.contains("could not reach the s3 endpoint") {
+            SourceErrorType::NetworkError
+        } else if error_str.contains("bucket") && error_str.contains("does not exist") {
             SourceErrorType::NotFound
         } else if error_str.contains("nosuchkey") || error_str.contains("no such key") {
             SourceErrorType::NotFound
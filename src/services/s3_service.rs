@@ -4,6 +4,8 @@ use chrono::{DateTime, Datelike};
 use tracing::{debug, info, warn, error};
 use serde_json;
 use std::collections::HashMap;
+#[cfg(feature = "s3")]
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use uuid::Uuid;
 use futures::stream::StreamExt;
@@ -18,10 +20,19 @@ use aws_types::region::Region as AwsRegion;
 #[cfg(feature = "s3")]
 use aws_sdk_s3::primitives::ByteStream;
 #[cfg(feature = "s3")]
-use aws_sdk_s3::types::{CompletedPart, CompletedMultipartUpload};
+use aws_sdk_s3::types::{CompletedPart, CompletedMultipartUpload, ServerSideEncryption};
+#[cfg(feature = "s3")]
+use aws_sdk_s3::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+#[cfg(feature = "s3")]
+use aws_sdk_s3::config::{retry::RetryConfig, timeout::TimeoutConfig};
 
 use crate::models::{FileIngestionInfo, S3SourceConfig};
+#[cfg(feature = "s3")]
+use crate::models::ChecksumVerification;
+#[cfg(feature = "s3")]
+use crate::services::download_integrity::{self, DownloadVerdict};
 use crate::storage::StorageBackend;
+use crate::storage::compression::{self, StorageCompression};
 
 /// Threshold for using streaming multipart uploads (100MB)
 const STREAMING_THRESHOLD: usize = 100 * 1024 * 1024;
@@ -29,11 +40,140 @@ const STREAMING_THRESHOLD: usize = 100 * 1024 * 1024;
 /// Multipart upload chunk size (16MB - AWS minimum is 5MB, we use 16MB for better performance)
 const MULTIPART_CHUNK_SIZE: usize = 16 * 1024 * 1024;
 
+/// Why a bucket health check (HeadBucket) failed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum S3HealthCheckError {
+    #[error("S3 rejected the credentials for bucket '{bucket}' (HTTP {status}); check the access key ID and secret access key")]
+    AuthenticationFailed { bucket: String, status: u16 },
+    #[error("S3 bucket '{bucket}' does not exist")]
+    BucketNotFound { bucket: String },
+    #[error("S3 bucket '{bucket}' already exists but is owned by another account; choose another bucket name")]
+    BucketOwnedByAnotherAccount { bucket: String },
+    #[error("Could not reach the S3 endpoint for bucket '{bucket}': {message}")]
+    Network { bucket: String, message: String },
+    #[error("S3 bucket '{bucket}' is in region '{actual}', but region '{configured}' is configured")]
+    RegionMismatch { bucket: String, configured: String, actual: String },
+    #[error("S3 health check for bucket '{bucket}' failed: {message}")]
+    Other { bucket: String, message: String },
+}
+
+/// Classifies a failed HeadBucket request. `status` is None when no HTTP response
+/// arrived (DNS, TLS, connection refused, timeout). `bucket_region` is the
+/// `x-amz-bucket-region` header S3 sends when the request went to the wrong region.
+pub fn classify_head_bucket_failure(
+    bucket: &str,
+    configured_region: &str,
+    status: Option<u16>,
+    bucket_region: Option<&str>,
+    message: &str,
+) -> S3HealthCheckError {
+    if let Some(actual) = bucket_region.filter(|region| !region.is_empty() && *region != configured_region) {
+        return S3HealthCheckError::RegionMismatch {
+            bucket: bucket.to_string(),
+            configured: configured_region.to_string(),
+            actual: actual.to_string(),
+        };
+    }
+
+    match status {
+        None => S3HealthCheckError::Network { bucket: bucket.to_string(), message: message.to_string() },
+        Some(status @ (401 | 403)) => S3HealthCheckError::AuthenticationFailed { bucket: bucket.to_string(), status },
+        Some(404) => S3HealthCheckError::BucketNotFound { bucket: bucket.to_string() },
+        Some(status) => S3HealthCheckError::Other {
+            bucket: bucket.to_string(),
+            message: format!("HTTP {}: {}", status, message),
+        },
+    }
+}
+
+/// Classifies a failed CreateBucket request by its S3 error code. Ok means the
+/// bucket exists and is ours, e.g. because another instance created it first.
+pub fn classify_create_bucket_failure(
+    bucket: &str,
+    error_code: Option<&str>,
+    message: &str,
+) -> std::result::Result<(), S3HealthCheckError> {
+    match error_code {
+        Some("BucketAlreadyOwnedByYou") => Ok(()),
+        Some("BucketAlreadyExists") => Err(S3HealthCheckError::BucketOwnedByAnotherAccount { bucket: bucket.to_string() }),
+        _ => Err(S3HealthCheckError::Other {
+            bucket: bucket.to_string(),
+            message: format!("could not create the bucket: {}", message),
+        }),
+    }
+}
+
+/// What went wrong with a failed S3 request, as far as retrying is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum S3FailureKind {
+    /// The connection, a read or the whole operation exceeded its timeout
+    Timeout,
+    /// S3 asked us to slow down
+    Throttled,
+    Other,
+}
+
+impl S3FailureKind {
+    /// Prefix for error messages, worded so `S3ErrorClassifier` recognizes the kind
+    fn message_prefix(&self) -> Option<&'static str> {
+        match self {
+            Self::Timeout => Some("S3 request timed out"),
+            Self::Throttled => Some("S3 request throttled"),
+            Self::Other => None,
+        }
+    }
+}
+
+/// Classifies a failed request from what the SDK reports: whether it hit a client
+/// side timeout, the HTTP status and the S3 error code of the response, if any
+pub fn classify_s3_failure(timed_out: bool, status: Option<u16>, error_code: Option<&str>) -> S3FailureKind {
+    if timed_out {
+        return S3FailureKind::Timeout;
+    }
+    match error_code {
+        Some("RequestTimeout" | "RequestTimeoutException") => return S3FailureKind::Timeout,
+        Some(
+            "SlowDown" | "Throttling" | "ThrottlingException" | "ThrottledException" | "RequestLimitExceeded"
+            | "TooManyRequestsException" | "RequestThrottled" | "RequestThrottledException",
+        ) => return S3FailureKind::Throttled,
+        _ => {}
+    }
+    match status {
+        Some(408 | 504) => S3FailureKind::Timeout,
+        Some(429) => S3FailureKind::Throttled,
+        _ => S3FailureKind::Other,
+    }
+}
+
+/// Describes a failed SDK request, marking timeouts and throttling so they are
+/// tracked and backed off distinctly from other failures
+#[cfg(feature = "s3")]
+fn describe_sdk_error<E>(error: &SdkError<E, aws_sdk_s3::config::http::HttpResponse>) -> String
+where
+    E: ProvideErrorMetadata + std::error::Error + 'static,
+{
+    let timed_out = match error {
+        SdkError::TimeoutError(_) => true,
+        SdkError::DispatchFailure(failure) => failure.is_timeout(),
+        _ => false,
+    };
+    let status = error.raw_response().map(|response| response.status().as_u16());
+    let kind = classify_s3_failure(timed_out, status, error.code());
+    let detail = DisplayErrorContext(error).to_string();
+    match kind.message_prefix() {
+        Some(prefix) => format!("{}: {}", prefix, detail),
+        None => detail,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct S3Service {
+    /// Shared so a region correction in `initialize` applies to every clone
     #[cfg(feature = "s3")]
-    client: Client,
+    client: Arc<RwLock<Client>>,
     config: S3SourceConfig,
+    /// Compression applied to newly stored documents
+    compression: StorageCompression,
 }
 
 impl S3Service {
@@ -55,6 +195,7 @@ impl S3Service {
         if config.secret_access_key.is_empty() {
             return Err(anyhow!("Secret access key is required"));
         }
+        config.validate_network_settings().map_err(|e| anyhow!(e))?;
 
         // Create S3 client with custom configuration
         let credentials = Credentials::new(
@@ -71,11 +212,21 @@ impl S3Service {
             config.region.clone()
         };
 
+        let timeout_config = TimeoutConfig::builder()
+            .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
+            .read_timeout(Duration::from_secs(config.read_timeout_seconds))
+            .operation_timeout(Duration::from_secs(config.operation_timeout_seconds))
+            .build();
+        // The SDK counts the first attempt too
+        let retry_config = RetryConfig::standard().with_max_attempts(config.max_retries + 1);
+
         let build_client = |force_path_style: bool| {
             let mut builder = aws_sdk_s3::config::Builder::new()
                 .region(AwsRegion::new(region.clone()))
                 .credentials_provider(credentials.clone())
                 .behavior_version_latest()
+                .timeout_config(timeout_config.clone())
+                .retry_config(retry_config.clone())
                 .force_path_style(force_path_style);
             if let Some(endpoint_url) = &config.endpoint_url {
                 if !endpoint_url.is_empty() {
@@ -138,12 +289,143 @@ impl S3Service {
 
         Ok(Self {
             #[cfg(feature = "s3")]
-            client,
-            config
+            client: Arc::new(RwLock::new(client)),
+            config,
+            compression: StorageCompression::None,
         })
         }
     }
 
+    /// Compress newly stored documents with the given algorithm when worthwhile
+    pub fn with_compression(mut self, compression: StorageCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    #[cfg(feature = "s3")]
+    fn client(&self) -> Client {
+        self.client.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    #[cfg(feature = "s3")]
+    fn current_region(&self) -> String {
+        self.client()
+            .config()
+            .region()
+            .map(|region| region.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Rebuild the client for another region, keeping credentials, endpoint and addressing style
+    #[cfg(feature = "s3")]
+    fn switch_region(&self, region: &str) {
+        let mut client = self.client.write().unwrap_or_else(|e| e.into_inner());
+        let config = client
+            .config()
+            .to_builder()
+            .region(AwsRegion::new(region.to_string()))
+            .build();
+        *client = Client::from_conf(config);
+    }
+
+    /// Check the bucket is reachable and the credentials are accepted (HeadBucket)
+    pub async fn check_bucket_health(&self) -> std::result::Result<(), S3HealthCheckError> {
+        #[cfg(not(feature = "s3"))]
+        {
+            return Err(S3HealthCheckError::Other {
+                bucket: self.config.bucket_name.clone(),
+                message: "S3 support not compiled in".to_string(),
+            });
+        }
+
+        #[cfg(feature = "s3")]
+        {
+            let result = self.client()
+                .head_bucket()
+                .bucket(&self.config.bucket_name)
+                .send()
+                .await;
+
+            match result {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    let raw = e.raw_response();
+                    let status = raw.map(|response| response.status().as_u16());
+                    let bucket_region = raw.and_then(|response| response.headers().get("x-amz-bucket-region"));
+                    // Dispatch and timeout failures never produced a usable response
+                    let status = match e {
+                        SdkError::DispatchFailure(_) | SdkError::TimeoutError(_) => None,
+                        _ => status,
+                    };
+                    Err(classify_head_bucket_failure(
+                        &self.config.bucket_name,
+                        &self.current_region(),
+                        status,
+                        bucket_region,
+                        &DisplayErrorContext(&e).to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Run the bucket health check, switching to the bucket's actual region if
+    /// S3 reports a region mismatch
+    pub async fn ensure_bucket_reachable(&self) -> Result<()> {
+        #[cfg(not(feature = "s3"))]
+        {
+            return Err(anyhow!("S3 support not compiled in"));
+        }
+
+        #[cfg(feature = "s3")]
+        {
+            match self.check_bucket_health().await {
+                Ok(()) => Ok(()),
+                Err(S3HealthCheckError::RegionMismatch { bucket, configured, actual }) => {
+                    warn!(
+                        "S3 bucket '{}' is in region '{}' but '{}' is configured; retrying in '{}'. \
+                         Set the region to '{}' in the S3 configuration to avoid this extra round trip.",
+                        bucket, actual, configured, actual, actual
+                    );
+                    self.switch_region(&actual);
+                    self.check_bucket_health().await.map_err(|e| anyhow!(e))
+                }
+                Err(e) => Err(anyhow!(e)),
+            }
+        }
+    }
+
+    /// Create the configured bucket in the current region. A bucket created
+    /// concurrently by someone else with the same credentials counts as success.
+    #[cfg(feature = "s3")]
+    async fn create_bucket(&self) -> Result<()> {
+        use aws_sdk_s3::types::{BucketLocationConstraint, CreateBucketConfiguration};
+
+        let bucket = &self.config.bucket_name;
+        let region = self.current_region();
+        let mut request = self.client().create_bucket().bucket(bucket);
+        // us-east-1 is the default location and S3 rejects it as a constraint
+        if !region.is_empty() && region != "us-east-1" {
+            request = request.create_bucket_configuration(
+                CreateBucketConfiguration::builder()
+                    .location_constraint(BucketLocationConstraint::from(region.as_str()))
+                    .build(),
+            );
+        }
+
+        match request.send().await {
+            Ok(_) => {
+                info!("Created S3 bucket '{}' in region '{}'", bucket, region);
+                Ok(())
+            }
+            Err(e) => {
+                classify_create_bucket_failure(bucket, e.code(), &describe_sdk_error(&e)).map_err(|e| anyhow!(e))?;
+                info!("S3 bucket '{}' was created concurrently; using it", bucket);
+                Ok(())
+            }
+        }
+    }
+
     /// Which S3 addressing styles to try, in priority order.
     /// true = path-style (http://endpoint/bucket/key), false = virtual-hosted.
     fn addressing_styles_to_try(config: &S3SourceConfig) -> Vec<bool> {
@@ -176,7 +458,7 @@ impl S3Service {
         let mut continuation_token: Option<String> = None;
 
         loop {
-            let mut list_request = self.client
+            let mut list_request = self.client()
                 .list_objects_v2()
                 .bucket(&self.config.bucket_name)
                 .prefix(folder_path);
@@ -288,7 +570,7 @@ impl S3Service {
                     }
                 }
                 Err(e) => {
-                    return Err(anyhow!("Failed to list S3 objects: {}", e));
+                    return Err(anyhow!("Failed to list S3 objects: {}", describe_sdk_error(&e)));
                 }
             }
         }
@@ -309,21 +591,51 @@ impl S3Service {
         {
         info!("Downloading S3 object: {}/{}", self.config.bucket_name, object_key);
 
-        let response = self.client
-            .get_object()
-            .bucket(&self.config.bucket_name)
-            .key(object_key)
-            .send()
-            .await
-            .map_err(|e| anyhow!("Failed to download S3 object {}: {}", object_key, e))?;
+        let verification = self.config.download_verification;
+        let mut attempt = 0;
+        loop {
+            let response = self.client()
+                .get_object()
+                .bucket(&self.config.bucket_name)
+                .key(object_key)
+                .send()
+                .await
+                .map_err(|e| anyhow!("Failed to download S3 object {}: {}", object_key, describe_sdk_error(&e)))?;
 
-        let body = response.body.collect().await
-            .map_err(|e| anyhow!("Failed to read S3 object body: {}", e))?;
+            let expected = if verification.checksums == ChecksumVerification::Off {
+                None
+            } else {
+                let opaque_encryption = response.sse_customer_algorithm.is_some()
+                    || matches!(
+                        response.server_side_encryption,
+                        Some(ServerSideEncryption::AwsKms | ServerSideEncryption::AwsKmsDsse)
+                    );
+                response.e_tag.as_deref()
+                    .and_then(|etag| download_integrity::checksum_from_s3_etag(etag, opaque_encryption))
+            };
+
+            let body = response.body.collect().await
+                .map_err(|e| anyhow!("Failed to read S3 object body: {}", e))?;
+
+            let bytes = body.into_bytes().to_vec();
+
+            let check = download_integrity::verify(&bytes, expected.into_iter().collect());
+            match download_integrity::judge(verification.checksums, object_key, &check) {
+                DownloadVerdict::Keep => {}
+                DownloadVerdict::Reject(reason) => return Err(anyhow!(reason)),
+                DownloadVerdict::Retry(reason) if attempt < verification.retries => {
+                    attempt += 1;
+                    warn!("{}; downloading again (retry {}/{})", reason, attempt, verification.retries);
+                    continue;
+                }
+                DownloadVerdict::Retry(reason) => {
+                    return Err(anyhow!("{}; giving up after {} attempts", reason, attempt + 1));
+                }
+            }
 
-        let bytes = body.into_bytes().to_vec();
-        info!("Downloaded S3 object {} ({} bytes)", object_key, bytes.len());
-        
-        Ok(bytes)
+            info!("Downloaded S3 object {} ({} bytes)", object_key, bytes.len());
+            return Ok(bytes);
+        }
         }
     }
 
@@ -338,22 +650,17 @@ impl S3Service {
         {
             info!("Testing S3 connection to bucket: {}", self.config.bucket_name);
 
-            // Test bucket access by listing objects with a limit
-            let response = self.client
+            // HeadBucket first: it distinguishes auth, missing bucket, network and region problems
+            self.ensure_bucket_reachable().await?;
+
+            // Then make sure the credentials can list objects
+            let response = self.client()
                 .list_objects_v2()
                 .bucket(&self.config.bucket_name)
                 .max_keys(1)
                 .send()
                 .await
-                .map_err(|e| anyhow!("Failed to access S3 bucket {}: {}", self.config.bucket_name, e))?;
-
-            // Test if we can get bucket region (additional validation)
-            let _head_bucket_response = self.client
-                .head_bucket()
-                .bucket(&self.config.bucket_name)
-                .send()
-                .await
-                .map_err(|e| anyhow!("Cannot access bucket {}: {}", self.config.bucket_name, e))?;
+                .map_err(|e| anyhow!("Failed to list objects in S3 bucket {}: {}", self.config.bucket_name, describe_sdk_error(&e)))?;
 
             let object_count = response.key_count.unwrap_or(0);
             
@@ -389,6 +696,7 @@ impl S3Service {
         match extension {
             "pdf" => "application/pdf",
             "txt" => "text/plain",
+            "md" | "markdown" => "text/markdown",
             "png" => "image/png",
             "jpg" | "jpeg" => "image/jpeg",
             "tiff" | "tif" => "image/tiff",
@@ -481,7 +789,7 @@ impl S3Service {
             let data_owned = data.to_vec();
             let metadata_owned = metadata.clone();
             let bucket_name = self.config.bucket_name.clone();
-            let client = self.client.clone();
+            let client = self.client();
 
             self.retry_operation(&format!("store_file: {}", key), || {
                 let key = key_owned.clone();
@@ -511,7 +819,7 @@ impl S3Service {
                     }
 
                     put_request.send().await
-                        .map_err(|e| anyhow!("Failed to store file {}: {}", key, e))?;
+                        .map_err(|e| anyhow!("Failed to store file {}: {}", key, describe_sdk_error(&e)))?;
 
                     Ok(())
                 }
@@ -537,7 +845,7 @@ impl S3Service {
             let data_owned = data.to_vec();
             let metadata_owned = metadata.clone();
             let bucket_name = self.config.bucket_name.clone();
-            let client = self.client.clone();
+            let client = self.client();
 
             self.retry_operation(&format!("store_file_multipart: {}", key), || {
                 let key = key_owned.clone();
@@ -567,7 +875,7 @@ impl S3Service {
                     }
 
                     let create_response = create_request.send().await
-                        .map_err(|e| anyhow!("Failed to initiate multipart upload for {}: {}", key, e))?;
+                        .map_err(|e| anyhow!("Failed to initiate multipart upload for {}: {}", key, describe_sdk_error(&e)))?;
                     
                     let upload_id = create_response.upload_id()
                         .ok_or_else(|| anyhow!("Missing upload ID in multipart upload response"))?;
@@ -593,7 +901,7 @@ impl S3Service {
                             .body(ByteStream::from(chunk.to_vec()))
                             .send()
                             .await
-                            .map_err(|e| anyhow!("Failed to upload part {} for {}: {}", part_number, key, e))?;
+                            .map_err(|e| anyhow!("Failed to upload part {} for {}: {}", part_number, key, describe_sdk_error(&e)))?;
 
                         let etag = upload_part_response.e_tag()
                             .ok_or_else(|| anyhow!("Missing ETag in upload part response"))?;
@@ -641,7 +949,7 @@ impl S3Service {
                                 }
                             });
                             
-                            anyhow!("Failed to complete multipart upload for {}: {}", key, e)
+                            anyhow!("Failed to complete multipart upload for {}: {}", key, describe_sdk_error(&e))
                         })?;
 
                     info!("Successfully completed multipart upload for {}", key);
@@ -666,7 +974,7 @@ impl S3Service {
 
             let key_owned = key.to_string();
             let bucket_name = self.config.bucket_name.clone();
-            let client = self.client.clone();
+            let client = self.client();
 
             let bytes = self.retry_operation(&format!("retrieve_file: {}", key), || {
                 let key = key_owned.clone();
@@ -680,7 +988,7 @@ impl S3Service {
                         .key(&key)
                         .send()
                         .await
-                        .map_err(|e| anyhow!("Failed to retrieve file {}: {}", key, e))?;
+                        .map_err(|e| anyhow!("Failed to retrieve file {}: {}", key, describe_sdk_error(&e)))?;
 
                     let body = response.body.collect().await
                         .map_err(|e| anyhow!("Failed to read file body: {}", e))?;
@@ -705,13 +1013,13 @@ impl S3Service {
         {
             info!("Deleting file from S3: {}/{}", self.config.bucket_name, key);
 
-            self.client
+            self.client()
                 .delete_object()
                 .bucket(&self.config.bucket_name)
                 .key(key)
                 .send()
                 .await
-                .map_err(|e| anyhow!("Failed to delete file {}: {}", key, e))?;
+                .map_err(|e| anyhow!("Failed to delete file {}: {}", key, describe_sdk_error(&e)))?;
 
             info!("Successfully deleted file: {}", key);
             Ok(())
@@ -727,7 +1035,7 @@ impl S3Service {
         
         #[cfg(feature = "s3")]
         {
-            match self.client
+            match self.client()
                 .head_object()
                 .bucket(&self.config.bucket_name)
                 .key(key)
@@ -740,7 +1048,7 @@ impl S3Service {
                     if error_msg.contains("NotFound") || error_msg.contains("404") {
                         Ok(false)
                     } else {
-                        Err(anyhow!("Failed to check file existence {}: {}", key, e))
+                        Err(anyhow!("Failed to check file existence {}: {}", key, describe_sdk_error(&e)))
                     }
                 }
             }
@@ -880,7 +1188,27 @@ impl StorageBackend for S3Service {
     }
     async fn store_document(&self, user_id: Uuid, document_id: Uuid, filename: &str, data: &[u8]) -> Result<String> {
         // Generate S3 key
-        let key = self.generate_document_key(user_id, document_id, filename);
+        let mut key = self.generate_document_key(user_id, document_id, filename);
+        
+        let mode = self.compression;
+        let compressed = if mode == StorageCompression::None {
+            None
+        } else {
+            let filename_owned = filename.to_string();
+            let owned_data = data.to_vec();
+            tokio::task::spawn_blocking(move || {
+                compression::compress_for_storage(mode, &filename_owned, &owned_data)
+            }).await??
+        };
+        let data = match &compressed {
+            Some(compressed_data) => {
+                // The key suffix marks the object as compressed for retrieve_file
+                key.push_str(mode.suffix());
+                info!("Compressed document with {}: {} -> {} bytes", mode, data.len(), compressed_data.len());
+                compressed_data.as_slice()
+            }
+            None => data,
+        };
         
         // Use streaming upload for large files
         if data.len() > STREAMING_THRESHOLD {
@@ -924,7 +1252,7 @@ impl StorageBackend for S3Service {
 
             let key_owned = key.to_string();
             let bucket_name = self.config.bucket_name.clone();
-            let client = self.client.clone();
+            let client = self.client();
 
             let bytes = self.retry_operation(&format!("retrieve_file: {}", key), || {
                 let key = key_owned.clone();
@@ -938,7 +1266,7 @@ impl StorageBackend for S3Service {
                         .key(&key)
                         .send()
                         .await
-                        .map_err(|e| anyhow!("Failed to retrieve file {}: {}", key, e))?;
+                        .map_err(|e| anyhow!("Failed to retrieve file {}: {}", key, describe_sdk_error(&e)))?;
 
                     let body = response.body.collect().await
                         .map_err(|e| anyhow!("Failed to read file body: {}", e))?;
@@ -948,10 +1276,26 @@ impl StorageBackend for S3Service {
             }).await?;
 
             info!("Successfully retrieved file: {} ({} bytes)", key, bytes.len());
-            Ok(bytes)
+
+            if !compression::is_compressed_path(key) {
+                return Ok(bytes);
+            }
+
+            let compressed_key = key.to_string();
+            tokio::task::spawn_blocking(move || compression::decompress_for_path(&compressed_key, bytes)).await?
         }
     }
 
+    fn is_user_path(&self, user_id: Uuid, path: &str) -> bool {
+        crate::storage::is_user_object_key(user_id, path)
+    }
+
+    async fn delete_file(&self, path: &str) -> Result<()> {
+        let key = path.strip_prefix("s3://").unwrap_or(path);
+        // S3 reports success for keys that don't exist
+        S3Service::delete_file(self, key).await
+    }
+
     async fn delete_document_files(&self, user_id: Uuid, document_id: Uuid, filename: &str) -> Result<()> {
         #[cfg(not(feature = "s3"))]
         {
@@ -966,10 +1310,12 @@ impl StorageBackend for S3Service {
 
             let mut errors = Vec::new();
 
-            // Delete document file
-            if let Err(e) = self.delete_file(&document_key).await {
-                if !e.to_string().contains("NotFound") {
-                    errors.push(format!("Document: {}", e));
+            // Delete document file, including any compressed variant
+            for suffix in ["", StorageCompression::Zstd.suffix(), StorageCompression::Gzip.suffix()] {
+                if let Err(e) = self.delete_file(&format!("{}{}", document_key, suffix)).await {
+                    if !e.to_string().contains("NotFound") {
+                        errors.push(format!("Document: {}", e));
+                    }
                 }
             }
 
@@ -1011,7 +1357,7 @@ impl StorageBackend for S3Service {
         
         #[cfg(feature = "s3")]
         {
-            match self.client
+            match self.client()
                 .head_object()
                 .bucket(&self.config.bucket_name)
                 .key(key)
@@ -1024,7 +1370,7 @@ impl StorageBackend for S3Service {
                     if error_msg.contains("NotFound") || error_msg.contains("404") {
                         Ok(false)
                     } else {
-                        Err(anyhow!("Failed to check file existence {}: {}", key, e))
+                        Err(anyhow!("Failed to check file existence {}: {}", key, describe_sdk_error(&e)))
                     }
                 }
             }
@@ -1036,8 +1382,18 @@ impl StorageBackend for S3Service {
     }
 
     async fn initialize(&self) -> Result<()> {
-        self.test_connection().await?;
-        info!("S3 storage backend initialized successfully");
+        #[cfg(feature = "s3")]
+        if self.config.create_bucket_if_missing {
+            if let Err(S3HealthCheckError::BucketNotFound { bucket }) = self.check_bucket_health().await {
+                info!(
+                    "S3 bucket '{}' does not exist; creating it because S3_CREATE_BUCKET_IF_MISSING is enabled",
+                    bucket
+                );
+                self.create_bucket().await?;
+            }
+        }
+        self.ensure_bucket_reachable().await?;
+        info!("S3 storage backend initialized successfully (bucket '{}')", self.config.bucket_name);
         Ok(())
     }
 }
@@ -1060,6 +1416,16 @@ mod tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             auto_sync: true,
             sync_interval_minutes: 60,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            connect_timeout_seconds: 10,
+            read_timeout_seconds: 60,
+            operation_timeout_seconds: 300,
+            max_retries: 3,
+            create_bucket_if_missing: false,
+            download_verification: Default::default(),
         };
 
         // This will create the client but won't test actual S3 access
@@ -1091,6 +1457,17 @@ mod tests {
             file_extensions: vec![],
             auto_sync: false,
             sync_interval_minutes: 0,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            sync_on_startup: false,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            connect_timeout_seconds: 10,
+            read_timeout_seconds: 60,
+            operation_timeout_seconds: 300,
+            max_retries: 3,
+            create_bucket_if_missing: false,
+            download_verification: Default::default(),
         }
     }
 
@@ -1112,6 +1489,120 @@ mod tests {
         assert_eq!(S3Service::addressing_styles_to_try(&cfg), vec![true, false]);
     }
 
+    #[test]
+    fn head_bucket_failures_are_classified() {
+        let classify = |status, region| classify_head_bucket_failure("b", "us-east-1", status, region, "boom");
+
+        assert_eq!(
+            classify(Some(403), None),
+            S3HealthCheckError::AuthenticationFailed { bucket: "b".to_string(), status: 403 }
+        );
+        assert_eq!(classify(Some(404), None), S3HealthCheckError::BucketNotFound { bucket: "b".to_string() });
+        assert!(matches!(classify(None, None), S3HealthCheckError::Network { .. }));
+        assert!(matches!(classify(Some(500), None), S3HealthCheckError::Other { .. }));
+    }
+
+    #[test]
+    fn head_bucket_region_header_means_mismatch() {
+        assert_eq!(
+            classify_head_bucket_failure("b", "us-east-1", Some(301), Some("eu-central-1"), ""),
+            S3HealthCheckError::RegionMismatch {
+                bucket: "b".to_string(),
+                configured: "us-east-1".to_string(),
+                actual: "eu-central-1".to_string(),
+            }
+        );
+        // The header echoing the configured region is not a mismatch
+        assert!(matches!(
+            classify_head_bucket_failure("b", "us-east-1", Some(403), Some("us-east-1"), ""),
+            S3HealthCheckError::AuthenticationFailed { .. }
+        ));
+    }
+
+    #[test]
+    fn create_bucket_failures_are_classified() {
+        // Lost the race to another instance using the same account
+        assert_eq!(classify_create_bucket_failure("b", Some("BucketAlreadyOwnedByYou"), ""), Ok(()));
+        assert_eq!(
+            classify_create_bucket_failure("b", Some("BucketAlreadyExists"), ""),
+            Err(S3HealthCheckError::BucketOwnedByAnotherAccount { bucket: "b".to_string() })
+        );
+        assert!(matches!(
+            classify_create_bucket_failure("b", Some("AccessDenied"), "denied"),
+            Err(S3HealthCheckError::Other { .. })
+        ));
+        assert!(matches!(classify_create_bucket_failure("b", None, "boom"), Err(S3HealthCheckError::Other { .. })));
+    }
+
+    #[test]
+    fn timeouts_and_throttling_are_classified_distinctly() {
+        assert_eq!(classify_s3_failure(true, None, None), S3FailureKind::Timeout);
+        assert_eq!(classify_s3_failure(false, Some(400), Some("RequestTimeout")), S3FailureKind::Timeout);
+        assert_eq!(classify_s3_failure(false, Some(504), None), S3FailureKind::Timeout);
+        assert_eq!(classify_s3_failure(false, Some(503), Some("SlowDown")), S3FailureKind::Throttled);
+        assert_eq!(classify_s3_failure(false, Some(429), None), S3FailureKind::Throttled);
+        assert_eq!(classify_s3_failure(false, Some(503), Some("ServiceUnavailable")), S3FailureKind::Other);
+        assert_eq!(classify_s3_failure(false, Some(403), Some("AccessDenied")), S3FailureKind::Other);
+    }
+
+    #[test]
+    fn marked_failures_get_matching_error_types() {
+        use crate::models::{ErrorContext, SourceErrorClassifier, SourceErrorType};
+        use crate::services::s3_error_classifier::S3ErrorClassifier;
+
+        let classifier = S3ErrorClassifier::new();
+        let context = ErrorContext::new("documents/a.pdf".to_string());
+        let classify = |message: &str| classifier.classify_error(&anyhow!(message.to_string()), &context).error_type;
+
+        assert_eq!(
+            classify("Failed to download S3 object a.pdf: S3 request timed out: dispatch failure"),
+            SourceErrorType::Timeout
+        );
+        assert_eq!(
+            classify("Failed to download S3 object a.pdf: S3 request throttled: service error: SlowDown"),
+            SourceErrorType::RateLimited
+        );
+    }
+
+    #[test]
+    fn network_settings_are_validated() {
+        assert!(base_config().validate_network_settings().is_ok());
+
+        let mut cfg = base_config();
+        cfg.read_timeout_seconds = 0;
+        assert!(cfg.validate_network_settings().is_err());
+
+        let mut cfg = base_config();
+        cfg.connect_timeout_seconds = 30;
+        cfg.operation_timeout_seconds = 20;
+        assert!(cfg.validate_network_settings().is_err());
+
+        let mut cfg = base_config();
+        cfg.max_retries = 11;
+        assert!(cfg.validate_network_settings().is_err());
+    }
+
+    #[test]
+    fn network_settings_default_when_omitted() {
+        let cfg: S3SourceConfig = serde_json::from_value(serde_json::json!({
+            "bucket_name": "b",
+            "region": "us-east-1",
+            "access_key_id": "k",
+            "secret_access_key": "s",
+            "endpoint_url": null,
+            "prefix": null,
+            "watch_folders": [],
+            "file_extensions": [],
+            "auto_sync": false,
+            "sync_interval_minutes": 60
+        }))
+        .unwrap();
+        assert_eq!(cfg.connect_timeout_seconds, crate::models::DEFAULT_S3_CONNECT_TIMEOUT_SECONDS);
+        assert_eq!(cfg.read_timeout_seconds, crate::models::DEFAULT_S3_READ_TIMEOUT_SECONDS);
+        assert_eq!(cfg.operation_timeout_seconds, crate::models::DEFAULT_S3_OPERATION_TIMEOUT_SECONDS);
+        assert_eq!(cfg.max_retries, crate::models::DEFAULT_S3_MAX_RETRIES);
+    }
+
     #[test]
     fn addressing_style_aws_default_without_endpoint() {
         let cfg = base_config();
@@ -0,0 +1,180 @@
+//! Guarded deletion of sources
+//!
+//! Deleting a source can take thousands of documents with it. A deletion preview
+//! returns the affected document count with a short-lived confirmation token
+//! bound to the source and that count; with `SOURCE_DELETE_REQUIRE_CONFIRMATION`
+//! set, deletion is refused without one. With `SOURCE_DELETE_GRACE_PERIOD_MINUTES`
+//! set, deleting only hides the source, and [`SourcePurgeService`] removes it once
+//! the grace period has passed unless it is restored first.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::Database,
+    models::{SourceDeletionSummary, UserRole},
+    services::file_service::FileService,
+};
+
+/// How long a confirmation token from the deletion preview stays valid
+pub const CONFIRMATION_TOKEN_TTL_MINUTES: i64 = 15;
+
+/// Documents deleted per batch when a source is deleted with its documents
+pub const DOCUMENT_DELETE_BATCH_SIZE: i64 = 100;
+
+/// How often soft-deleted sources are checked for an expired grace period
+const PURGE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Keeps deletion tokens from being accepted as login tokens and vice versa
+const CONFIRMATION_AUDIENCE: &str = "source-delete";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DeleteConfirmationClaims {
+    /// The source being deleted
+    sub: Uuid,
+    user: Uuid,
+    /// Document count shown in the preview
+    documents: i64,
+    aud: String,
+    exp: usize,
+}
+
+/// Creates the confirmation token returned by the deletion preview
+pub fn create_confirmation_token(
+    secret: &str,
+    user_id: Uuid,
+    source_id: Uuid,
+    document_count: i64,
+    expires_at: DateTime<Utc>,
+) -> Result<String> {
+    let claims = DeleteConfirmationClaims {
+        sub: source_id,
+        user: user_id,
+        documents: document_count,
+        aud: CONFIRMATION_AUDIENCE.to_string(),
+        exp: expires_at.timestamp() as usize,
+    };
+    Ok(encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes()))?)
+}
+
+/// Whether `token` confirms deleting the source while it has `document_count`
+/// documents. A token goes stale once documents are added or removed, so the
+/// user always confirms the count that is actually deleted.
+pub fn verify_confirmation_token(secret: &str, token: &str, user_id: Uuid, source_id: Uuid, document_count: i64) -> bool {
+    let mut validation = Validation::default();
+    validation.set_audience(&[CONFIRMATION_AUDIENCE]);
+
+    match decode::<DeleteConfirmationClaims>(token, &DecodingKey::from_secret(secret.as_bytes()), &validation) {
+        Ok(data) => {
+            let claims = data.claims;
+            claims.sub == source_id && claims.user == user_id && claims.documents == document_count
+        }
+        Err(_) => false,
+    }
+}
+
+/// When a source deleted now is purged, or None when deletion is immediate
+pub fn purge_time(deleted_at: DateTime<Utc>, grace_period_minutes: u64) -> Option<DateTime<Utc>> {
+    if grace_period_minutes == 0 {
+        return None;
+    }
+    Some(deleted_at + Duration::minutes(grace_period_minutes as i64))
+}
+
+/// Removes a source for good. With `delete_documents` its documents and their
+/// stored files are deleted in batches first; otherwise they are kept without a source.
+pub async fn purge_source(
+    db: &Database,
+    file_service: &FileService,
+    user_id: Uuid,
+    source_id: Uuid,
+    delete_documents: bool,
+) -> Result<SourceDeletionSummary> {
+    let mut summary = SourceDeletionSummary {
+        source_id,
+        ..Default::default()
+    };
+
+    if delete_documents {
+        loop {
+            let batch = db
+                .get_recent_documents_for_source(user_id, source_id, DOCUMENT_DELETE_BATCH_SIZE)
+                .await?;
+            if batch.is_empty() {
+                break;
+            }
+
+            let ids: Vec<Uuid> = batch.iter().map(|document| document.id).collect();
+            let (deleted_ids, failed_ids) = db.bulk_delete_documents(&ids, user_id, UserRole::User).await?;
+            for document in batch.iter().filter(|document| deleted_ids.contains(&document.id)) {
+                if let Err(e) = file_service.delete_document_files(document).await {
+                    warn!("Failed to delete files for document {} of source {}: {}", document.id, source_id, e);
+                    summary.files_failed += 1;
+                }
+            }
+            summary.documents_deleted += deleted_ids.len() as i64;
+
+            // Documents that can't be deleted would be fetched again forever
+            if deleted_ids.is_empty() {
+                warn!("Could not delete {} documents of source {}, keeping them", failed_ids.len(), source_id);
+                break;
+            }
+        }
+    }
+
+    // Whatever is left keeps existing without a source (documents.source_id is ON DELETE SET NULL)
+    summary.documents_orphaned = db.count_documents_for_source(user_id, source_id).await?.0;
+    db.delete_source(user_id, source_id).await?;
+
+    info!(
+        "Purged source {}: {} documents deleted, {} kept without a source",
+        source_id, summary.documents_deleted, summary.documents_orphaned
+    );
+    Ok(summary)
+}
+
+/// Purges soft-deleted sources once their grace period has passed
+#[derive(Clone)]
+pub struct SourcePurgeService {
+    db: Database,
+    file_service: Arc<FileService>,
+}
+
+impl SourcePurgeService {
+    pub fn new(db: Database, file_service: Arc<FileService>) -> Self {
+        Self { db, file_service }
+    }
+
+    /// Purges due sources forever, once per [`PURGE_CHECK_INTERVAL`]
+    pub async fn start(&self) {
+        info!("Starting purge of deleted sources");
+        let mut interval = tokio::time::interval(PURGE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.purge_due_sources().await {
+                error!("Failed to purge deleted sources: {}", e);
+            }
+        }
+    }
+
+    /// Purges every soft-deleted source whose grace period has passed and returns
+    /// how many were purged
+    pub async fn purge_due_sources(&self) -> Result<usize> {
+        let due = self.db.get_sources_due_for_purge().await?;
+        let mut purged = 0;
+
+        for pending in due {
+            match purge_source(&self.db, &self.file_service, pending.user_id, pending.source_id, pending.purge_documents).await {
+                Ok(_) => purged += 1,
+                Err(e) => error!("Failed to purge deleted source {}: {}", pending.source_id, e),
+            }
+        }
+
+        Ok(purged)
+    }
+}
@@ -8,6 +8,8 @@ use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::db::Database;
+use crate::services::webdav::loop_detection::DirectoryLoopError;
+use crate::webdav_xml_parser::InvalidMultistatusError;
 use crate::models::{
     CreateSourceScanFailure, SourceScanFailure, SourceScanFailureResponse,
     SourceScanFailureStats, ErrorSourceType, SourceErrorType, SourceErrorSeverity,
@@ -70,12 +72,7 @@ impl SourceErrorTracker {
         error: &anyhow::Error,
         context: ErrorContext,
     ) -> Result<Uuid> {
-        let classification = if let Some(classifier) = self.classifiers.get(&source_type) {
-            classifier.classify_error(error, &context)
-        } else {
-            // Fallback to generic classification
-            self.classify_error_generic(error, &context)
-        };
+        let classification = self.classify_error(source_type, error, &context);
 
         let create_failure = CreateSourceScanFailure {
             user_id,
@@ -110,6 +107,16 @@ impl SourceErrorTracker {
         }
     }
 
+    /// Classify an error with the source type's classifier, or generically if none is registered
+    pub fn classify_error(&self, source_type: ErrorSourceType, error: &anyhow::Error, context: &ErrorContext) -> ErrorClassification {
+        if let Some(classifier) = self.classifiers.get(&source_type) {
+            classifier.classify_error(error, context)
+        } else {
+            // Fallback to generic classification
+            self.classify_error_generic(error, context)
+        }
+    }
+
     /// Check if a resource should be skipped due to previous failures
     pub async fn should_skip_resource(
         &self,
@@ -428,10 +435,20 @@ impl SourceErrorTracker {
 
     /// Generic error classification fallback
     fn classify_error_generic(&self, error: &anyhow::Error, context: &ErrorContext) -> ErrorClassification {
+        if let Some(invalid) = error.downcast_ref::<InvalidMultistatusError>() {
+            return Self::classify_invalid_multistatus(invalid, context);
+        }
+        if let Some(directory_loop) = error.downcast_ref::<DirectoryLoopError>() {
+            return Self::classify_directory_loop(directory_loop, context);
+        }
+
         let error_str = error.to_string().to_lowercase();
         
         let error_type = if error_str.contains("timeout") || error_str.contains("timed out") {
             SourceErrorType::Timeout
+        } else if error_str.contains("throttled") {
+            // Checked before server errors, since throttling often arrives as a 503
+            SourceErrorType::RateLimited
         } else if error_str.contains("permission denied") || error_str.contains("forbidden") || error_str.contains("401") || error_str.contains("403") {
             SourceErrorType::PermissionDenied
         } else if error_str.contains("not found") || error_str.contains("404") {
@@ -489,6 +506,66 @@ impl SourceErrorTracker {
         }
     }
 
+    /// A PROPFIND answered with something other than a multistatus document (usually an
+    /// HTML error or login page) points at the server setup, not at our XML parsing
+    fn classify_invalid_multistatus(invalid: &InvalidMultistatusError, context: &ErrorContext) -> ErrorClassification {
+        ErrorClassification {
+            error_type: SourceErrorType::ServerError,
+            severity: SourceErrorSeverity::High,
+            retry_strategy: crate::models::RetryStrategy::Exponential,
+            retry_delay_seconds: 1800,
+            max_retries: 3,
+            user_friendly_message: format!(
+                "The WebDAV server answered with a non-WebDAV response ({}); it is likely misconfigured",
+                invalid.reason
+            ),
+            recommended_action: "Check the server URL and that WebDAV is enabled, and look for a proxy, \
+                login page or maintenance page answering in place of the WebDAV server.".to_string(),
+            diagnostic_data: serde_json::json!({
+                "error_message": invalid.to_string(),
+                "server_misconfiguration": true,
+                "reason": invalid.reason,
+                "http_status": invalid.status,
+                "content_type": invalid.content_type,
+                "body_snippet": invalid.snippet,
+                "context": {
+                    "operation": context.operation,
+                    "response_time_ms": context.response_time.map(|d| d.as_millis()),
+                    "response_size": context.response_size,
+                }
+            }),
+        }
+    }
+
+    /// A directory listed inside itself is a server-side loop; skipping the path
+    /// where it was detected ends the loop there on the next syncs
+    fn classify_directory_loop(directory_loop: &DirectoryLoopError, context: &ErrorContext) -> ErrorClassification {
+        ErrorClassification {
+            error_type: SourceErrorType::DepthLimit,
+            severity: SourceErrorSeverity::High,
+            retry_strategy: crate::models::RetryStrategy::Exponential,
+            retry_delay_seconds: 300,
+            max_retries: 3,
+            user_friendly_message: format!(
+                "The WebDAV server lists '{}' inside itself; the directory loop was cut off at '{}'",
+                directory_loop.first_path, directory_loop.path
+            ),
+            recommended_action: "Look for a symlink or server rewrite that points a directory at one of its \
+                parents, or exclude the directory from the watch folders.".to_string(),
+            diagnostic_data: serde_json::json!({
+                "error_message": directory_loop.to_string(),
+                "directory_loop": true,
+                "first_path": directory_loop.first_path,
+                "visits": directory_loop.visits,
+                "max_visits": directory_loop.max_visits,
+                "context": {
+                    "operation": context.operation,
+                    "response_time_ms": context.response_time.map(|d| d.as_millis()),
+                }
+            }),
+        }
+    }
+
     /// Generic diagnostics builder
     fn build_diagnostics_generic(&self, failure: &SourceScanFailure) -> crate::models::SourceFailureDiagnostics {
         let resource_size_mb = failure.resource_size_bytes.map(|b| b as f64 / 1_048_576.0);
@@ -635,6 +712,11 @@ impl SourceErrorTracker {
 
     /// Extract HTTP status code from error if present
     fn extract_http_status(&self, error: &anyhow::Error) -> Option<i32> {
+        // The quoted body of an invalid multistatus response may contain unrelated numbers
+        if let Some(invalid) = error.downcast_ref::<InvalidMultistatusError>() {
+            return invalid.status.map(i32::from);
+        }
+
         let error_str = error.to_string();
         
         // Look for common HTTP status code patterns first (fast path)
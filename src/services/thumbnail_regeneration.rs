@@ -0,0 +1,141 @@
+//! Regenerating document thumbnails after `THUMBNAIL_SIZE` changes
+//!
+//! Each document records the size its cached thumbnail was generated at.
+//! Documents whose thumbnail is at another size are regenerated in batches,
+//! a few at a time, and recorded as they go, so a run that is interrupted
+//! resumes where it stopped. One run goes at a time; its progress is kept for
+//! the admin endpoint.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::Serialize;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::db::Database;
+use crate::models::Document;
+use crate::services::file_service::FileService;
+
+/// Documents loaded per batch
+const BATCH_SIZE: i64 = 100;
+
+/// Progress of the latest thumbnail regeneration
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ThumbnailRegenerationProgress {
+    /// Whether a regeneration is running
+    pub running: bool,
+    /// Size thumbnails are regenerated at, in pixels
+    pub thumbnail_size: u32,
+    /// Thumbnails at another size when the run started
+    pub total: i64,
+    pub regenerated: i64,
+    pub failed: i64,
+    /// Thumbnails still at another size
+    pub remaining: i64,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+fn latest() -> &'static Mutex<ThumbnailRegenerationProgress> {
+    static LATEST: OnceLock<Mutex<ThumbnailRegenerationProgress>> = OnceLock::new();
+    LATEST.get_or_init(|| Mutex::new(ThumbnailRegenerationProgress::default()))
+}
+
+fn update(change: impl FnOnce(&mut ThumbnailRegenerationProgress)) {
+    change(&mut latest().lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+}
+
+/// Progress of the latest regeneration, with `remaining` counted now
+pub async fn progress(db: &Database, file_service: &FileService) -> Result<ThumbnailRegenerationProgress> {
+    let remaining = db.count_stale_thumbnails(file_service.thumbnail_size() as i32).await?;
+    let mut progress = latest().lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    progress.remaining = remaining;
+    Ok(progress)
+}
+
+/// Starts regenerating the thumbnails made at another size than the configured
+/// one in the background, `concurrency` at a time. Returns `None` if a
+/// regeneration is already running.
+pub async fn start(
+    db: Database,
+    file_service: Arc<FileService>,
+    concurrency: usize,
+) -> Result<Option<ThumbnailRegenerationProgress>> {
+    let thumbnail_size = file_service.thumbnail_size();
+    let total = db.count_stale_thumbnails(thumbnail_size as i32).await?;
+
+    let started = {
+        let mut progress = latest().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if progress.running {
+            return Ok(None);
+        }
+        *progress = ThumbnailRegenerationProgress {
+            running: true,
+            thumbnail_size,
+            total,
+            remaining: total,
+            started_at: Some(Utc::now()),
+            ..ThumbnailRegenerationProgress::default()
+        };
+        progress.clone()
+    };
+
+    info!("Regenerating {} thumbnails at {}px", total, thumbnail_size);
+    tokio::spawn(async move {
+        if let Err(e) = run(&db, &file_service, concurrency.max(1)).await {
+            warn!("Thumbnail regeneration stopped: {}", e);
+        }
+        update(|progress| {
+            progress.running = false;
+            progress.finished_at = Some(Utc::now());
+            info!(
+                "Thumbnail regeneration finished: {} regenerated, {} failed",
+                progress.regenerated, progress.failed
+            );
+        });
+    });
+
+    Ok(Some(started))
+}
+
+async fn run(db: &Database, file_service: &FileService, concurrency: usize) -> Result<()> {
+    let thumbnail_size = file_service.thumbnail_size() as i32;
+    let mut after = None;
+    loop {
+        let documents = db.get_documents_with_stale_thumbnails(thumbnail_size, after, BATCH_SIZE).await?;
+        let Some(last) = documents.last() else {
+            return Ok(());
+        };
+        after = Some(last.id);
+
+        futures::stream::iter(documents)
+            .for_each_concurrent(concurrency, |document| async move {
+                let regenerated = regenerate_thumbnail(db, file_service, &document).await;
+                if let Err(e) = &regenerated {
+                    warn!("Failed to regenerate the thumbnail of document {}: {}", document.id, e);
+                }
+                update(|progress| {
+                    if regenerated.is_ok() {
+                        progress.regenerated += 1;
+                    } else {
+                        progress.failed += 1;
+                    }
+                    progress.remaining = (progress.total - progress.regenerated - progress.failed).max(0);
+                });
+            })
+            .await;
+    }
+}
+
+/// Generates a document's thumbnail at the configured size, replacing the
+/// cached one, and records the size
+pub async fn regenerate_thumbnail(db: &Database, file_service: &FileService, document: &Document) -> Result<Vec<u8>> {
+    let thumbnail = file_service
+        .regenerate_thumbnail(document.user_id, &document.file_path, &document.original_filename)
+        .await?;
+    db.set_document_thumbnail_size(document.id, file_service.thumbnail_size() as i32)
+        .await?;
+    Ok(thumbnail)
+}
@@ -0,0 +1,109 @@
+//! Moving local documents into their owner's directories
+//!
+//! Local storage keeps each user's files under `documents/<user id>/`,
+//! `thumbnails/<user id>/` and `processed_images/<user id>/`, which is what
+//! storage user isolation checks paths against. Documents stored before
+//! then sit directly in `documents/`; they are moved into their owner's
+//! directory at startup, in batches, with their thumbnail and processed
+//! image. A run that is interrupted picks up the rest on the next start.
+
+use anyhow::Result;
+use std::path::Path;
+use tokio::fs;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::services::file_service::FileService;
+use crate::storage::is_user_local_path;
+use crate::utils::security::path_relative_to_base;
+
+/// Documents loaded per batch
+const BATCH_SIZE: i64 = 500;
+
+/// Moves the files of documents stored directly in `documents/` into their
+/// owner's directory and returns how many were moved
+pub async fn move_into_user_directories(db: &Database, file_service: &FileService) -> Result<usize> {
+    let upload_path = file_service.get_upload_path();
+    let mut moved = 0;
+    let mut after = None;
+
+    loop {
+        let batch = db.get_local_document_paths(after, BATCH_SIZE).await?;
+        let Some((last_id, _, _)) = batch.last() else {
+            break;
+        };
+        after = Some(*last_id);
+
+        for (document_id, user_id, file_path) in batch {
+            if is_user_local_path(user_id, &file_path, upload_path) {
+                continue;
+            }
+            let Ok(resolved_path) = file_service.resolve_file_path(&file_path).await else {
+                continue;
+            };
+            // Only files the storage backend put in the flat documents directory
+            // are moved; anything else is left for an administrator to look at
+            let in_flat_documents_dir = path_relative_to_base(&resolved_path, upload_path)
+                .map_or(false, |relative| relative.parent() == Some(Path::new("documents")));
+            if !in_flat_documents_dir {
+                continue;
+            }
+            match move_document(db, file_service, document_id, user_id, &resolved_path).await {
+                Ok(()) => moved += 1,
+                Err(e) => warn!("Failed to move document {} into the directory of user {}: {}", document_id, user_id, e),
+            }
+        }
+    }
+
+    if moved > 0 {
+        info!("Moved {} documents into their owners' storage directories", moved);
+    }
+    Ok(moved)
+}
+
+async fn move_document(
+    db: &Database,
+    file_service: &FileService,
+    document_id: Uuid,
+    user_id: Uuid,
+    resolved_path: &str,
+) -> Result<()> {
+    let user_dir = user_id.to_string();
+    let file_name = Path::new(resolved_path)
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("{} has no file name", resolved_path))?;
+
+    let target_dir = file_service.get_documents_path().join(&user_dir);
+    fs::create_dir_all(&target_dir).await?;
+    let target = target_dir.join(file_name);
+    fs::rename(resolved_path, &target).await?;
+    if let Err(e) = db.update_document_file_path(document_id, &target.to_string_lossy()).await {
+        // Put the file back so the recorded path stays valid
+        fs::rename(&target, resolved_path).await?;
+        return Err(e);
+    }
+
+    // Thumbnails and processed images are regenerated if missing, so failing
+    // to move them is not an error
+    let derived_files = [
+        (file_service.get_thumbnails_path(), format!("{}_thumb.jpg", document_id)),
+        (file_service.get_processed_images_path(), format!("{}_processed.png", document_id)),
+    ];
+    for (dir, file_name) in derived_files {
+        let source = dir.join(&file_name);
+        if !source.exists() {
+            continue;
+        }
+        let target_dir = dir.join(&user_dir);
+        if let Err(e) = fs::create_dir_all(&target_dir).await {
+            warn!("Failed to create {}: {}", target_dir.display(), e);
+            continue;
+        }
+        if let Err(e) = fs::rename(&source, target_dir.join(&file_name)).await {
+            warn!("Failed to move {} into the directory of user {}: {}", source.display(), user_id, e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Cache of what each WebDAV server supports
+//!
+//! Capabilities are checked with an OPTIONS request and, when the server claims
+//! `Depth: infinity` support, a PROPFIND testing whether it actually answers one.
+//! Servers change what they support when they're upgraded, so a cached check is
+//! redone once it is older than the TTL, or as soon as a response carries a
+//! `Server` header different from the one seen when it was checked.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tracing::info;
+
+use crate::config::Config;
+
+use super::ServerCapabilities;
+
+/// TTL used when no cache was installed, matching `WEBDAV_CAPABILITY_TTL_MINUTES`'s default
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Capabilities by server and user
+#[derive(Debug)]
+pub struct CapabilityCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, ServerCapabilities>>,
+}
+
+impl CapabilityCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cache configured with `WEBDAV_CAPABILITY_TTL_MINUTES`
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(Duration::from_secs(config.webdav_capability_ttl_minutes * 60))
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The capabilities cached for `scope`, if they were checked within the TTL
+    pub fn get_fresh(&self, scope: &str) -> Option<ServerCapabilities> {
+        self.get(scope).filter(|capabilities| capabilities.last_checked.elapsed() < self.ttl)
+    }
+
+    /// The capabilities cached for `scope`, however old
+    pub fn get(&self, scope: &str) -> Option<ServerCapabilities> {
+        self.entries.lock().unwrap().get(scope).cloned()
+    }
+
+    pub fn insert(&self, scope: &str, capabilities: ServerCapabilities) {
+        self.entries.lock().unwrap().insert(scope.to_string(), capabilities);
+    }
+
+    /// Drops the capabilities cached for `scope` when `server` differs from the
+    /// `Server` header they were checked with, which usually means the server
+    /// was upgraded. Returns whether they were dropped.
+    pub fn observe_server_header(&self, scope: &str, server: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let changed = entries
+            .get(scope)
+            .and_then(|capabilities| capabilities.server_software.as_deref())
+            .is_some_and(|known| known != server);
+        if changed {
+            let previous = entries.remove(scope).and_then(|capabilities| capabilities.server_software);
+            info!(
+                "WebDAV server now identifies as '{}' (was '{}'); its capabilities will be checked again",
+                server,
+                previous.unwrap_or_default()
+            );
+        }
+        changed
+    }
+}
+
+static CAPABILITY_CACHE: OnceLock<Arc<CapabilityCache>> = OnceLock::new();
+
+/// Installs the process-wide cache used by every WebDAV service created
+/// afterwards. Returns false if a cache was already installed.
+pub fn install_capability_cache(cache: Arc<CapabilityCache>) -> bool {
+    CAPABILITY_CACHE.set(cache).is_ok()
+}
+
+/// The process-wide cache, or one with the default TTL if none was installed
+pub fn installed_capability_cache() -> Arc<CapabilityCache> {
+    CAPABILITY_CACHE
+        .get_or_init(|| Arc::new(CapabilityCache::new(DEFAULT_TTL)))
+        .clone()
+}
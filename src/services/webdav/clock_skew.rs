@@ -0,0 +1,80 @@
+//! Clock skew between readur and WebDAV servers
+//!
+//! The skew is read from the `Date` header of a server response and measured
+//! against the midpoint of the request's round trip. The header only has
+//! whole-second precision, so a skew of a second or two is noise. Modification
+//! times reported by a server with a skewed clock can fall on the wrong side of
+//! an incremental sync's cutoff; the configured tolerance widens that cutoff.
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::Config;
+
+/// How much skew is reported and tolerated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkewSettings {
+    /// Skew beyond which a warning is logged and recorded; zero disables it
+    pub warning_threshold: Duration,
+    /// How far before an incremental sync's cutoff a file may have been
+    /// modified and still be synced
+    pub tolerance: Duration,
+}
+
+impl Default for ClockSkewSettings {
+    fn default() -> Self {
+        Self {
+            warning_threshold: Duration::seconds(300),
+            tolerance: Duration::zero(),
+        }
+    }
+}
+
+impl ClockSkewSettings {
+    /// The settings configured with `WEBDAV_CLOCK_SKEW_*`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            warning_threshold: Duration::seconds(config.webdav_clock_skew_warning_seconds as i64),
+            tolerance: Duration::seconds(config.webdav_clock_skew_tolerance_seconds as i64),
+        }
+    }
+
+    /// Whether `skew`, in either direction, is large enough to warn about
+    pub fn exceeds_warning_threshold(&self, skew: Duration) -> bool {
+        !self.warning_threshold.is_zero() && skew.abs() > self.warning_threshold
+    }
+}
+
+/// The server's clock minus ours, from a `Date` header received in a response
+/// to a request sent at `sent_at` and answered at `received_at`. None when the
+/// header isn't a valid HTTP date.
+pub fn skew_from_date_header(date: &str, sent_at: DateTime<Utc>, received_at: DateTime<Utc>) -> Option<Duration> {
+    let server_time = DateTime::parse_from_rfc2822(date.trim()).ok()?.with_timezone(&Utc);
+    let local_time = sent_at + (received_at - sent_at) / 2;
+    Some(server_time - local_time)
+}
+
+/// Human-readable direction and size of `skew`, e.g. "7m 5s ahead"
+pub fn describe_skew(skew: Duration) -> String {
+    let seconds = skew.num_seconds().abs();
+    let size = match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    };
+    format!("{} {}", size, if skew < Duration::zero() { "behind" } else { "ahead" })
+}
+
+static CLOCK_SKEW_SETTINGS: OnceLock<ClockSkewSettings> = OnceLock::new();
+
+/// Installs the process-wide settings used by every WebDAV service created
+/// afterwards. Returns false if settings were already installed.
+pub fn install_clock_skew_settings(settings: ClockSkewSettings) -> bool {
+    CLOCK_SKEW_SETTINGS.set(settings).is_ok()
+}
+
+/// The process-wide settings, or the defaults if none were installed
+pub fn installed_clock_skew_settings() -> ClockSkewSettings {
+    CLOCK_SKEW_SETTINGS.get().copied().unwrap_or_default()
+}
@@ -5,4 +5,32 @@
 pub fn build_user_agent() -> String {
     format!("Readur/{} (WebDAV-Sync; +https://github.com/readur)", 
             env!("CARGO_PKG_VERSION"))
-}
\ No newline at end of file
+}
+
+static URL_USERINFO_REGEX: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+    regex::Regex::new(r#"(?i)\b([a-z][a-z0-9+.-]*://)[^/@\s<>"']+@"#).expect("URL userinfo regex should be valid")
+});
+
+/// Removes `user:password@` from every URL in `text`, so URLs and server responses
+/// can be shown without leaking credentials
+pub fn redact_url_credentials(text: &str) -> String {
+    URL_USERINFO_REGEX.replace_all(text, "${1}").into_owned()
+}
+
+/// Percent-encodes each segment of `path`, keeping the slashes between them, so
+/// names with spaces, `#`, `?` or non-ASCII characters form a valid URL. Segments
+/// are decoded first, so paths that are already encoded aren't encoded twice.
+pub fn encode_path_segments(path: &str) -> String {
+    path.split('/')
+        .map(|segment| urlencoding::encode(&decode_path(segment)).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Decodes a percent-encoded path, such as an href from a PROPFIND response.
+/// Invalid escapes are kept as they are.
+pub fn decode_path(path: &str) -> String {
+    urlencoding::decode(path)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
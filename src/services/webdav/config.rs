@@ -1,5 +1,7 @@
 
 
+use std::collections::HashMap;
+
 /// WebDAV server configuration
 #[derive(Debug, Clone)]
 pub struct WebDAVConfig {
@@ -10,6 +12,14 @@ pub struct WebDAVConfig {
     pub file_extensions: Vec<String>,
     pub timeout_seconds: u64,
     pub server_type: Option<String>, // "nextcloud", "owncloud", "generic"
+    /// Maximum number of directory levels to descend below a watch folder
+    /// during recursive discovery (None = unlimited)
+    pub max_scan_depth: Option<u32>,
+    /// Treat files found at several paths with the same ETag (e.g. hardlinks) as
+    /// one file, and don't descend into a directory whose ETag was already seen
+    pub detect_etag_aliases: bool,
+    /// User-Agent sent with every request instead of the default one
+    pub user_agent: Option<String>,
 }
 
 /// Retry configuration for WebDAV operations
@@ -21,14 +31,54 @@ pub struct RetryConfig {
     pub backoff_multiplier: f64,
     pub timeout_seconds: u64,
     pub rate_limit_backoff_ms: u64, // Additional backoff for 429 responses
+    /// Per-status overrides, consulted before the built-in status handling
+    pub status_policies: HashMap<u16, StatusRetryPolicy>,
+}
+
+/// Retry behavior for a specific HTTP status. Unset fields fall back to the
+/// corresponding `RetryConfig` values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusRetryPolicy {
+    pub retryable: bool,
+    pub max_retries: Option<u32>,
+    pub initial_delay_ms: Option<u64>,
+    pub max_delay_ms: Option<u64>,
 }
 
-/// Concurrency configuration for WebDAV operations
+/// A status retry policy with all fallbacks applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedRetryPolicy {
+    pub retryable: bool,
+    pub max_retries: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+/// Concurrency and connection pooling configuration for WebDAV operations
 #[derive(Debug, Clone)]
 pub struct ConcurrencyConfig {
     pub max_concurrent_scans: usize,
     pub max_concurrent_downloads: usize,
     pub adaptive_rate_limiting: bool,
+    /// Idle connections kept open per host for reuse (0 disables pooling)
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle pooled connection is kept before it is closed
+    pub pool_idle_timeout_seconds: u64,
+    /// TCP keepalive interval for open connections (None disables keepalive probes)
+    pub tcp_keepalive_seconds: Option<u64>,
+    pub http_version: HttpVersionPreference,
+}
+
+/// HTTP protocol version used by the WebDAV client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersionPreference {
+    /// Negotiate HTTP/2 via ALPN on HTTPS, fall back to HTTP/1.1
+    #[default]
+    Auto,
+    /// Always use HTTP/1.1 (for servers or proxies with broken HTTP/2 support)
+    Http1Only,
+    /// Use HTTP/2 without negotiation, including over plain HTTP (h2c)
+    Http2PriorKnowledge,
 }
 
 /// Configuration for Depth infinity PROPFIND optimizations
@@ -57,6 +107,102 @@ impl Default for RetryConfig {
             backoff_multiplier: 2.0,
             timeout_seconds: 30,
             rate_limit_backoff_ms: 5000, // 5 seconds
+            status_policies: RetryConfig::default_status_policies(),
+        }
+    }
+}
+
+impl StatusRetryPolicy {
+    /// Retry this status using the standard retry settings
+    pub fn retryable() -> Self {
+        Self {
+            retryable: true,
+            max_retries: None,
+            initial_delay_ms: None,
+            max_delay_ms: None,
+        }
+    }
+
+    /// Fail immediately on this status
+    pub fn not_retryable() -> Self {
+        Self {
+            retryable: false,
+            ..Self::retryable()
+        }
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    pub fn with_initial_delay_ms(mut self, initial_delay_ms: u64) -> Self {
+        self.initial_delay_ms = Some(initial_delay_ms);
+        self
+    }
+
+    pub fn with_max_delay_ms(mut self, max_delay_ms: u64) -> Self {
+        self.max_delay_ms = Some(max_delay_ms);
+        self
+    }
+}
+
+impl RetryConfig {
+    /// Default per-status overrides:
+    /// - 423 Locked: the file is usually being written or synced by another
+    ///   client, so retry a few times with a long backoff
+    /// - 502/503/504: gateway and availability errors are typically transient
+    ///   (proxy restarts, maintenance mode) and get more attempts than a 500
+    pub fn default_status_policies() -> HashMap<u16, StatusRetryPolicy> {
+        let mut policies = HashMap::new();
+        policies.insert(
+            423,
+            StatusRetryPolicy::retryable()
+                .with_max_retries(4)
+                .with_initial_delay_ms(5000)
+                .with_max_delay_ms(60000),
+        );
+        for status in [502, 503, 504] {
+            policies.insert(status, StatusRetryPolicy::retryable().with_max_retries(5));
+        }
+        policies
+    }
+
+    /// Resolves the retry policy for an HTTP error status. Statuses without an
+    /// entry in `status_policies` keep the built-in behavior: 5xx responses are
+    /// retried up to `max_retries`, 429 waits `rate_limit_backoff_ms` between
+    /// attempts until the server stops rate limiting, and other 4xx responses
+    /// fail immediately.
+    pub fn policy_for_status(&self, status: u16) -> ResolvedRetryPolicy {
+        let builtin = match status {
+            429 => ResolvedRetryPolicy {
+                retryable: true,
+                max_retries: u32::MAX,
+                initial_delay_ms: self.rate_limit_backoff_ms,
+                max_delay_ms: self.rate_limit_backoff_ms,
+            },
+            500..=599 => ResolvedRetryPolicy {
+                retryable: true,
+                max_retries: self.max_retries,
+                initial_delay_ms: self.initial_delay_ms,
+                max_delay_ms: self.max_delay_ms,
+            },
+            _ => ResolvedRetryPolicy {
+                retryable: false,
+                max_retries: 0,
+                initial_delay_ms: self.initial_delay_ms,
+                max_delay_ms: self.max_delay_ms,
+            },
+        };
+
+        match self.status_policies.get(&status) {
+            Some(policy) => ResolvedRetryPolicy {
+                retryable: policy.retryable,
+                max_retries: policy.max_retries.unwrap_or(self.max_retries),
+                initial_delay_ms: policy.initial_delay_ms.unwrap_or(self.initial_delay_ms),
+                max_delay_ms: policy.max_delay_ms.unwrap_or(self.max_delay_ms),
+            },
+            None => builtin,
         }
     }
 }
@@ -67,7 +213,54 @@ impl Default for ConcurrencyConfig {
             max_concurrent_scans: 4,
             max_concurrent_downloads: 8,
             adaptive_rate_limiting: true,
+            pool_max_idle_per_host: 16,
+            pool_idle_timeout_seconds: 90,
+            tcp_keepalive_seconds: Some(60),
+            http_version: HttpVersionPreference::Auto,
+        }
+    }
+}
+
+impl ConcurrencyConfig {
+    /// Connection pools larger than this multiple of the maximum number of
+    /// concurrent requests only hold sockets open that are never reused
+    const MAX_POOL_TO_CONCURRENCY_RATIO: usize = 4;
+
+    /// Maximum number of requests in flight at once
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.max_concurrent_scans + self.max_concurrent_downloads
+    }
+
+    /// Validates the concurrency and pooling settings
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.max_concurrent_scans == 0 {
+            return Err(anyhow::anyhow!("max_concurrent_scans must be at least 1"));
+        }
+
+        if self.max_concurrent_downloads == 0 {
+            return Err(anyhow::anyhow!("max_concurrent_downloads must be at least 1"));
         }
+
+        let max_requests = self.max_concurrent_requests();
+        if self.pool_max_idle_per_host > max_requests * Self::MAX_POOL_TO_CONCURRENCY_RATIO {
+            return Err(anyhow::anyhow!(
+                "pool_max_idle_per_host ({}) is more than {}x the maximum concurrent requests ({} scans + {} downloads)",
+                self.pool_max_idle_per_host,
+                Self::MAX_POOL_TO_CONCURRENCY_RATIO,
+                self.max_concurrent_scans,
+                self.max_concurrent_downloads
+            ));
+        }
+
+        if self.pool_max_idle_per_host > 0 && self.pool_max_idle_per_host < max_requests {
+            tracing::warn!(
+                "WebDAV pool_max_idle_per_host ({}) is below the maximum concurrent requests ({}); \
+                 connections beyond the pool size will be closed and re-established",
+                self.pool_max_idle_per_host, max_requests
+            );
+        }
+
+        Ok(())
     }
 }
 
@@ -101,6 +294,9 @@ impl WebDAVConfig {
             file_extensions,
             timeout_seconds: 30,
             server_type: None,
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         }
     }
 
@@ -148,6 +344,12 @@ impl WebDAVConfig {
             return Err(anyhow::anyhow!("At least one watch folder must be specified"));
         }
 
+        if let Some(user_agent) = &self.user_agent {
+            if !crate::models::is_valid_user_agent(user_agent) {
+                return Err(anyhow::anyhow!("User-Agent must be non-empty printable text without line breaks"));
+            }
+        }
+
         // Validate URL format - now accepts URLs without protocol
         // Protocol detection and fallback will be handled during connection testing
         let normalized_url = Self::normalize_server_url(&self.server_url);
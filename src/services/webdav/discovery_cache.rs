@@ -0,0 +1,357 @@
+//! Cache of directory listings between WebDAV sync passes
+//!
+//! A directory whose ETag, as reported in its parent's listing, is the one its
+//! listing was cached under reuses that listing instead of a fresh PROPFIND.
+//! Listings are keyed by server, user and path, bounded in number (the least
+//! recently used is evicted first) and expire after a TTL. Reusing a listing is
+//! only safe on servers whose directory ETags change whenever anything below the
+//! directory changes, as Nextcloud's and ownCloud's do.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::models::FileIngestionInfo;
+use crate::webdav_xml_parser::compare_etags;
+
+use super::WebDAVDiscoveryResult;
+
+/// (server and user, directory path)
+type CacheKey = (String, String);
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    etag: String,
+    files: Vec<FileIngestionInfo>,
+    directories: Vec<FileIngestionInfo>,
+    cached_at: DateTime<Utc>,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+}
+
+impl CacheState {
+    fn remove(&mut self, key: &CacheKey) -> Option<CacheEntry> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        Some(entry)
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.last_used);
+            entry.last_used = self.tick;
+            self.recency.insert(self.tick, key.clone());
+        }
+    }
+}
+
+/// Bounded, expiring cache of single-directory discovery results
+#[derive(Debug)]
+pub struct DiscoveryCache {
+    max_entries: usize,
+    ttl: Duration,
+    /// File the cache is saved to after each sync and loaded from at startup
+    persist_path: Option<PathBuf>,
+    state: Mutex<CacheState>,
+}
+
+impl DiscoveryCache {
+    pub fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            max_entries,
+            ttl,
+            persist_path: None,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Saves the cache to `path` on [`DiscoveryCache::persist`]
+    pub fn with_persist_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.persist_path = Some(path.into());
+        self
+    }
+
+    /// The cache configured with `WEBDAV_DISCOVERY_CACHE_*`, or none when the
+    /// cache is disabled
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.webdav_discovery_cache_enabled {
+            return None;
+        }
+        let cache = Self::new(
+            config.webdav_discovery_cache_max_entries,
+            Duration::from_secs(config.webdav_discovery_cache_ttl_minutes * 60),
+        );
+        Some(match &config.webdav_discovery_cache_file {
+            Some(path) => cache.with_persist_path(path),
+            None => cache,
+        })
+    }
+
+    /// The cached listing of `path`, if it was cached under an ETag matching
+    /// `etag` and hasn't expired. Stale listings are dropped.
+    pub fn get(&self, scope: &str, path: &str, etag: &str) -> Option<WebDAVDiscoveryResult> {
+        let key = cache_key(scope, path);
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entries.get(&key)?;
+
+        if !compare_etags(&entry.etag, etag) {
+            debug!("Dropping cached listing of {}: ETag changed", path);
+            state.remove(&key);
+            return None;
+        }
+        if self.is_expired(entry.cached_at) {
+            debug!("Dropping cached listing of {}: older than {:?}", path, self.ttl);
+            state.remove(&key);
+            return None;
+        }
+
+        let result = WebDAVDiscoveryResult {
+            files: entry.files.clone(),
+            directories: entry.directories.clone(),
+            depth_limited_directories: Vec::new(),
+            etag_aliases: Vec::new(),
+        };
+        state.touch(&key);
+        Some(result)
+    }
+
+    /// Caches the listing of `path` under the directory's `etag`
+    pub fn insert(&self, scope: &str, path: &str, etag: &str, result: &WebDAVDiscoveryResult) {
+        self.insert_entry(cache_key(scope, path), etag.to_string(), result.files.clone(), result.directories.clone(), Utc::now());
+    }
+
+    fn insert_entry(
+        &self,
+        key: CacheKey,
+        etag: String,
+        files: Vec<FileIngestionInfo>,
+        directories: Vec<FileIngestionInfo>,
+        cached_at: DateTime<Utc>,
+    ) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.remove(&key);
+        while state.entries.len() >= self.max_entries {
+            let Some((_, oldest)) = state.recency.pop_first() else { break };
+            state.entries.remove(&oldest);
+        }
+        state.entries.insert(key.clone(), CacheEntry { etag, files, directories, cached_at, last_used: 0 });
+        state.touch(&key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_expired(&self, cached_at: DateTime<Utc>) -> bool {
+        let age = Utc::now().signed_duration_since(cached_at).to_std().unwrap_or_default();
+        age >= self.ttl
+    }
+
+    /// Writes the cache as JSON to `path`, replacing the file atomically
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        let json = {
+            let state = self.state.lock().unwrap();
+            let entries: Vec<PersistedEntry> = state
+                .recency
+                .values()
+                .filter_map(|key| state.entries.get(key).map(|entry| PersistedEntry::new(key, entry)))
+                .collect();
+            serde_json::to_vec(&entries)?
+        };
+
+        let temp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&temp_path, json)
+            .await
+            .with_context(|| format!("Failed to write discovery cache to {}", temp_path.display()))?;
+        if let Err(e) = tokio::fs::rename(&temp_path, path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e).with_context(|| format!("Failed to replace discovery cache {}", path.display()));
+        }
+        Ok(())
+    }
+
+    /// Saves the cache to its persist path, if it has one
+    pub async fn persist(&self) -> Result<()> {
+        match &self.persist_path {
+            Some(path) => self.save(path).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Adds the unexpired listings saved in `path` and returns how many were
+    /// added. A missing file is an empty cache.
+    pub fn load(&self, path: &Path) -> Result<usize> {
+        let json = match std::fs::read(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read discovery cache {}", path.display())),
+        };
+        let entries: Vec<PersistedEntry> = serde_json::from_slice(&json)
+            .with_context(|| format!("Invalid discovery cache {}", path.display()))?;
+
+        let mut loaded = 0;
+        for entry in entries {
+            if self.is_expired(entry.cached_at) {
+                continue;
+            }
+            self.insert_entry(
+                (entry.scope, entry.path),
+                entry.etag,
+                entry.files.into_iter().map(FileIngestionInfo::from).collect(),
+                entry.directories.into_iter().map(FileIngestionInfo::from).collect(),
+                entry.cached_at,
+            );
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Loads the cache from its persist path, if it has one
+    pub fn load_persisted(&self) -> Result<usize> {
+        match &self.persist_path {
+            Some(path) => self.load(path),
+            None => Ok(0),
+        }
+    }
+}
+
+fn cache_key(scope: &str, path: &str) -> CacheKey {
+    let path = path.trim_end_matches('/');
+    (scope.to_string(), if path.is_empty() { "/".to_string() } else { path.to_string() })
+}
+
+/// A cached listing as saved to the persist file
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedEntry {
+    scope: String,
+    path: String,
+    etag: String,
+    cached_at: DateTime<Utc>,
+    files: Vec<CachedFile>,
+    directories: Vec<CachedFile>,
+}
+
+impl PersistedEntry {
+    fn new(key: &CacheKey, entry: &CacheEntry) -> Self {
+        Self {
+            scope: key.0.clone(),
+            path: key.1.clone(),
+            etag: entry.etag.clone(),
+            cached_at: entry.cached_at,
+            files: entry.files.iter().map(CachedFile::from).collect(),
+            directories: entry.directories.iter().map(CachedFile::from).collect(),
+        }
+    }
+}
+
+/// [`FileIngestionInfo`] as saved to the persist file
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFile {
+    relative_path: String,
+    full_path: String,
+    name: String,
+    size: i64,
+    mime_type: String,
+    last_modified: Option<DateTime<Utc>>,
+    etag: String,
+    is_directory: bool,
+    created_at: Option<DateTime<Utc>>,
+    permissions: Option<u32>,
+    owner: Option<String>,
+    group: Option<String>,
+    metadata: Option<serde_json::Value>,
+}
+
+impl From<&FileIngestionInfo> for CachedFile {
+    fn from(file: &FileIngestionInfo) -> Self {
+        Self {
+            relative_path: file.relative_path.clone(),
+            full_path: file.full_path.clone(),
+            name: file.name.clone(),
+            size: file.size,
+            mime_type: file.mime_type.clone(),
+            last_modified: file.last_modified,
+            etag: file.etag.clone(),
+            is_directory: file.is_directory,
+            created_at: file.created_at,
+            permissions: file.permissions,
+            owner: file.owner.clone(),
+            group: file.group.clone(),
+            metadata: file.metadata.clone(),
+        }
+    }
+}
+
+impl From<CachedFile> for FileIngestionInfo {
+    #[allow(deprecated)]
+    fn from(file: CachedFile) -> Self {
+        Self {
+            path: file.relative_path.clone(),
+            relative_path: file.relative_path,
+            full_path: file.full_path,
+            name: file.name,
+            size: file.size,
+            mime_type: file.mime_type,
+            last_modified: file.last_modified,
+            etag: file.etag,
+            is_directory: file.is_directory,
+            created_at: file.created_at,
+            permissions: file.permissions,
+            owner: file.owner,
+            group: file.group,
+            metadata: file.metadata,
+        }
+    }
+}
+
+static DISCOVERY_CACHE: OnceLock<Arc<DiscoveryCache>> = OnceLock::new();
+
+/// Installs the process-wide cache used by every WebDAV service created
+/// afterwards. Returns false if a cache was already installed.
+pub fn install_discovery_cache(cache: Arc<DiscoveryCache>) -> bool {
+    DISCOVERY_CACHE.set(cache).is_ok()
+}
+
+/// The process-wide cache, if one was installed
+pub fn installed_discovery_cache() -> Option<Arc<DiscoveryCache>> {
+    DISCOVERY_CACHE.get().cloned()
+}
+
+/// Installs the cache configured with `WEBDAV_DISCOVERY_CACHE_*`, loading the
+/// listings saved by the previous run. An unreadable cache file is ignored.
+pub fn install_configured_discovery_cache(config: &Config) {
+    let Some(cache) = DiscoveryCache::from_config(config) else {
+        return;
+    };
+    let loaded = cache.load_persisted().unwrap_or_else(|e| {
+        warn!("Starting with an empty WebDAV discovery cache: {:#}", e);
+        0
+    });
+    info!(
+        "WebDAV discovery cache enabled: up to {} directories for {} minutes ({} loaded)",
+        config.webdav_discovery_cache_max_entries, config.webdav_discovery_cache_ttl_minutes, loaded
+    );
+    install_discovery_cache(Arc::new(cache));
+}
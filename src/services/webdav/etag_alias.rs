@@ -0,0 +1,95 @@
+//! Detection of files and directories that a server exposes at several paths
+//! with the same ETag, as it does for hardlinked inodes on some filesystems
+
+use std::collections::HashMap;
+
+use crate::models::FileIngestionInfo;
+
+/// A path whose ETag was already discovered at another path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EtagAlias {
+    pub path: String,
+    /// Path the file or directory is synced under
+    pub canonical_path: String,
+    pub etag: String,
+    pub is_directory: bool,
+}
+
+/// Tracks ETags seen during one recursive discovery. When disabled every path
+/// is treated as distinct, for servers that reuse ETags for different files.
+#[derive(Debug, Default)]
+pub struct EtagAliasTracker {
+    enabled: bool,
+    directories: HashMap<String, String>,
+    aliases: Vec<EtagAlias>,
+}
+
+impl EtagAliasTracker {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// Whether a subdirectory is another path to a directory already discovered.
+    /// Such a directory is recorded as an alias and must not be descended into,
+    /// which also stops hardlink loops.
+    pub fn is_directory_alias(&mut self, directory: &FileIngestionInfo) -> bool {
+        if !self.enabled || directory.etag.is_empty() {
+            return false;
+        }
+
+        let path = directory.relative_path.trim_end_matches('/');
+        match self.directories.get(&directory.etag) {
+            Some(canonical_path) if canonical_path != path => {
+                self.aliases.push(EtagAlias {
+                    path: path.to_string(),
+                    canonical_path: canonical_path.clone(),
+                    etag: directory.etag.clone(),
+                    is_directory: true,
+                });
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.directories.insert(directory.etag.clone(), path.to_string());
+                false
+            }
+        }
+    }
+
+    /// Keeps one file per ETag and returns the kept files with every alias found.
+    /// The file is kept under its lexicographically first path, so the same path
+    /// is synced on every run regardless of discovery order.
+    pub fn finish(self, files: Vec<FileIngestionInfo>) -> (Vec<FileIngestionInfo>, Vec<EtagAlias>) {
+        let mut aliases = self.aliases;
+        if !self.enabled {
+            return (files, aliases);
+        }
+
+        let mut canonical_paths: HashMap<String, String> = HashMap::new();
+        for file in files.iter().filter(|file| !file.etag.is_empty()) {
+            let path = canonical_paths
+                .entry(file.etag.clone())
+                .or_insert_with(|| file.relative_path.clone());
+            if file.relative_path < *path {
+                *path = file.relative_path.clone();
+            }
+        }
+
+        let mut kept = Vec::with_capacity(files.len());
+        for file in files {
+            match canonical_paths.get(&file.etag) {
+                Some(canonical_path) if *canonical_path != file.relative_path => aliases.push(EtagAlias {
+                    path: file.relative_path,
+                    canonical_path: canonical_path.clone(),
+                    etag: file.etag,
+                    is_directory: false,
+                }),
+                _ => kept.push(file),
+            }
+        }
+        (kept, aliases)
+    }
+}
@@ -0,0 +1,103 @@
+//! Limit on concurrent downloads from each WebDAV server
+//!
+//! `max_concurrent_downloads` bounds the downloads of one source. Several
+//! sources pointing at the same server (one per user of a Nextcloud, say)
+//! would together exceed what the server tolerates, and servers answer a flood
+//! of connections with 429s or a ban. Every download therefore also takes a
+//! permit from a semaphore shared by all sources on the same host.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::Result;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::Config;
+
+/// Limit used when no limiter was installed, matching `WEBDAV_MAX_DOWNLOADS_PER_HOST`'s default
+pub const DEFAULT_MAX_DOWNLOADS_PER_HOST: usize = 8;
+
+/// Download semaphores by host
+#[derive(Debug)]
+pub struct HostDownloadLimiter {
+    /// Downloads in flight per host (0 = no limit)
+    max_per_host: usize,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostDownloadLimiter {
+    pub fn new(max_per_host: usize) -> Self {
+        Self {
+            max_per_host,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The limiter configured with `WEBDAV_MAX_DOWNLOADS_PER_HOST`
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.webdav_max_downloads_per_host)
+    }
+
+    pub fn max_per_host(&self) -> usize {
+        self.max_per_host
+    }
+
+    /// Waits for a download slot on the host of `server_url`. Returns None when
+    /// downloads aren't limited per host.
+    pub async fn acquire(&self, server_url: &str) -> Result<Option<OwnedSemaphorePermit>> {
+        let Some(semaphore) = self.semaphore(server_url) else {
+            return Ok(None);
+        };
+        Ok(Some(semaphore.acquire_owned().await?))
+    }
+
+    /// Downloads from the host of `server_url` that can start right now
+    pub fn available_permits(&self, server_url: &str) -> Option<usize> {
+        self.semaphore(server_url).map(|semaphore| semaphore.available_permits())
+    }
+
+    fn semaphore(&self, server_url: &str) -> Option<Arc<Semaphore>> {
+        if self.max_per_host == 0 {
+            return None;
+        }
+        let mut hosts = self.hosts.lock().unwrap();
+        let semaphore = hosts
+            .entry(host_key(server_url))
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)));
+        Some(Arc::clone(semaphore))
+    }
+}
+
+/// `scheme://host:port` of a server URL, so sources on the same server share a
+/// limit whatever their paths, credentials or letter case
+pub fn host_key(server_url: &str) -> String {
+    let trimmed = server_url.trim();
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    };
+    match url::Url::parse(&with_scheme) {
+        Ok(url) => match (url.host_str(), url.port_or_known_default()) {
+            (Some(host), Some(port)) => format!("{}://{}:{}", url.scheme(), host.to_lowercase(), port),
+            (Some(host), None) => format!("{}://{}", url.scheme(), host.to_lowercase()),
+            _ => trimmed.to_lowercase(),
+        },
+        Err(_) => trimmed.to_lowercase(),
+    }
+}
+
+static HOST_DOWNLOAD_LIMITER: OnceLock<Arc<HostDownloadLimiter>> = OnceLock::new();
+
+/// Installs the process-wide limiter used by every WebDAV service created
+/// afterwards. Returns false if a limiter was already installed.
+pub fn install_host_download_limiter(limiter: Arc<HostDownloadLimiter>) -> bool {
+    HOST_DOWNLOAD_LIMITER.set(limiter).is_ok()
+}
+
+/// The process-wide limiter, or one with the default limit if none was installed
+pub fn installed_host_download_limiter() -> Arc<HostDownloadLimiter> {
+    HOST_DOWNLOAD_LIMITER
+        .get_or_init(|| Arc::new(HostDownloadLimiter::new(DEFAULT_MAX_DOWNLOADS_PER_HOST)))
+        .clone()
+}
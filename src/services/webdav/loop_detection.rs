@@ -0,0 +1,76 @@
+//! Detects directory loops during a recursive discovery. A server that lists a
+//! directory inside itself, through a symlink to a parent or a misconfigured
+//! rewrite, would otherwise make the scanned paths grow without end.
+
+use std::collections::HashMap;
+
+/// Default of [`WebDAVSourceConfig::max_directory_visits`](crate::models::WebDAVSourceConfig::max_directory_visits)
+pub const DEFAULT_MAX_DIRECTORY_VISITS: u32 = 5;
+
+/// A directory was found nested inside itself more often than allowed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Directory loop detected: '{path}' is '{first_path}' nested inside itself, visited {visits} times \
+     (more than the limit of {max_visits}). The server likely lists a self-referential directory; \
+     discovery was stopped"
+)]
+pub struct DirectoryLoopError {
+    /// Path of the visit that went over the limit
+    pub path: String,
+    /// Path of the first visit of the directory
+    pub first_path: String,
+    pub visits: u32,
+    pub max_visits: u32,
+}
+
+/// Visits of the directories found in one recursive discovery
+#[derive(Debug, Default)]
+pub struct DirectoryVisits {
+    max_visits: u32,
+    /// ETags of the directories found so far, by path without trailing slash
+    etags: HashMap<String, String>,
+}
+
+impl DirectoryVisits {
+    /// A `max_visits` of 0 turns loop detection off
+    pub fn new(max_visits: u32) -> Self {
+        Self {
+            max_visits,
+            etags: HashMap::new(),
+        }
+    }
+
+    /// Records a directory found in a listing and returns its visits: one, plus
+    /// one for each ancestor with the same name and ETag, which is the same
+    /// directory listed inside itself. Fails once the visits exceed the limit.
+    pub fn record(&mut self, path: &str, etag: &str) -> Result<u32, DirectoryLoopError> {
+        if self.max_visits == 0 {
+            return Ok(1);
+        }
+
+        let path = path.trim_end_matches('/');
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let mut visits = 1;
+        let mut first_path = path;
+        let mut ancestor = path;
+        while let Some((parent, _)) = ancestor.rsplit_once('/') {
+            ancestor = parent;
+            let parent_name = parent.rsplit('/').next().unwrap_or(parent);
+            if parent_name == name && self.etags.get(parent).map(String::as_str) == Some(etag) {
+                visits += 1;
+                first_path = parent;
+            }
+        }
+        self.etags.insert(path.to_string(), etag.to_string());
+
+        if visits > self.max_visits {
+            return Err(DirectoryLoopError {
+                path: path.to_string(),
+                first_path: first_path.to_string(),
+                visits,
+                max_visits: self.max_visits,
+            });
+        }
+        Ok(visits)
+    }
+}
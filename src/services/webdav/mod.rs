@@ -1,16 +1,28 @@
 // Simplified WebDAV service modules - consolidated architecture
 
+pub mod capabilities;
+pub mod clock_skew;
 pub mod common; // Common utilities and shared functions
 pub mod config;
+pub mod discovery_cache;
+pub mod etag_alias;
+pub mod host_limits;
+pub mod loop_detection;
 pub mod service; 
 pub mod smart_sync;
 pub mod progress_shim; // Backward compatibility shim for simplified progress tracking
 
 // Re-export main types for convenience
-pub use common::build_user_agent;
-pub use config::{WebDAVConfig, RetryConfig, ConcurrencyConfig};
+pub use capabilities::CapabilityCache;
+pub use clock_skew::ClockSkewSettings;
+pub use common::{build_user_agent, decode_path, encode_path_segments, redact_url_credentials};
+pub use config::{WebDAVConfig, RetryConfig, ConcurrencyConfig, HttpVersionPreference, StatusRetryPolicy, ResolvedRetryPolicy};
+pub use discovery_cache::DiscoveryCache;
+pub use etag_alias::EtagAlias;
+pub use host_limits::HostDownloadLimiter;
+pub use loop_detection::{DirectoryLoopError, DirectoryVisits};
 pub use service::{
-    WebDAVService, WebDAVDiscoveryResult, WebDAVDownloadResult, ServerCapabilities, HealthStatus, test_webdav_connection,
+    WebDAVService, WebDAVDiscoveryResult, WebDAVDownloadResult, ConditionalDownload, PropfindDebugResult, ServerCapabilities, HealthStatus, test_webdav_connection,
     ValidationReport, ValidationIssue, ValidationIssueType, ValidationSeverity, 
     ValidationRecommendation, ValidationAction, ValidationSummary
 };
@@ -12,6 +12,9 @@ mod tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         }
     }
 
@@ -25,6 +28,9 @@ mod tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         }
     }
 
@@ -38,6 +44,9 @@ mod tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         }
     }
 
@@ -3,7 +3,7 @@ use reqwest::{Client, Method};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use std::collections::HashSet;
-use tokio::sync::Semaphore;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, SemaphorePermit};
 use tokio::time::sleep;
 // futures_util::stream import removed as unused
 use tracing::{debug, error, info, warn};
@@ -11,24 +11,72 @@ use serde::{Deserialize, Serialize};
 use rand::Rng;
 
 use crate::models::{
-    FileIngestionInfo,
+    ChecksumVerification, DownloadVerification, FileIngestionInfo, PropfindDepth, PropfindDepths, SourceFileVersion,
 };
 use crate::models::source::{
     WebDAVConnectionResult, WebDAVCrawlEstimate, WebDAVTestConnection,
 };
 use crate::models::source_error::{ErrorSourceType, ErrorContext};
+use crate::services::download_integrity::{self, ChecksumCheck, ChecksumHasher, DownloadVerdict};
 use crate::services::source_error_tracker::SourceErrorTracker;
-use crate::webdav_xml_parser::{parse_propfind_response, parse_propfind_response_with_directories};
+use crate::webdav_xml_parser::{parse_propfind_response, parse_propfind_response_with_directories, InvalidMultistatusError};
 use crate::mime_detection::{detect_mime_from_content, MimeDetectionResult};
+use crate::ingestion::spool::{DownloadSink, DownloadedFile, SpoolPolicy};
+
+use super::{config::{WebDAVConfig, RetryConfig, ConcurrencyConfig, HttpVersionPreference, ResolvedRetryPolicy}, SyncProgress};
+use super::common::{build_user_agent, decode_path, encode_path_segments, redact_url_credentials};
+use super::capabilities::{installed_capability_cache, CapabilityCache};
+use super::host_limits::{installed_host_download_limiter, HostDownloadLimiter};
+use super::clock_skew::{self, installed_clock_skew_settings, ClockSkewSettings};
+use super::discovery_cache::{installed_discovery_cache, DiscoveryCache};
+use super::etag_alias::{EtagAlias, EtagAliasTracker};
+use super::loop_detection::{DirectoryLoopError, DirectoryVisits, DEFAULT_MAX_DIRECTORY_VISITS};
+
+/// Properties requested when listing a directory during discovery
+const DISCOVERY_PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+            <D:propfind xmlns:D="DAV:">
+                <D:prop>
+                    <D:displayname/>
+                    <D:getcontentlength/>
+                    <D:getlastmodified/>
+                    <D:getetag/>
+                    <D:resourcetype/>
+                    <D:creationdate/>
+                </D:prop>
+            </D:propfind>"#;
 
-use super::{config::{WebDAVConfig, RetryConfig, ConcurrencyConfig}, SyncProgress};
-use super::common::build_user_agent;
+/// Smallest PROPFIND body, for testing whether the server answers `Depth: infinity`
+const INFINITY_DEPTH_TEST_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+            <D:propfind xmlns:D="DAV:">
+                <D:prop>
+                    <D:resourcetype/>
+                </D:prop>
+            </D:propfind>"#;
 
 /// Results from WebDAV discovery including both files and directories
 #[derive(Debug, Clone)]
 pub struct WebDAVDiscoveryResult {
     pub files: Vec<FileIngestionInfo>,
     pub directories: Vec<FileIngestionInfo>,
+    /// Directories that were not scanned because they exceed `max_scan_depth`
+    pub depth_limited_directories: Vec<String>,
+    /// Paths left out because their ETag was already discovered at another path
+    /// (only with `detect_etag_aliases`)
+    pub etag_aliases: Vec<EtagAlias>,
+}
+
+/// One PROPFIND exchange, returned as-is for debugging a source
+#[derive(Debug, Clone)]
+pub struct PropfindDebugResult {
+    /// Request URL, with any credentials removed
+    pub url: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// Response body as received, with credentials removed from URLs in it
+    pub raw_xml: String,
+    /// What discovery parses from the body, or why parsing failed
+    pub parsed: std::result::Result<Vec<FileIngestionInfo>, String>,
+    pub duration_ms: u64,
 }
 
 /// Result of downloading a file with MIME type detection
@@ -40,6 +88,25 @@ pub struct WebDAVDownloadResult {
     pub mime_type_updated: bool,
 }
 
+/// Result of a download made conditional on a known version of the file
+#[derive(Debug)]
+pub enum ConditionalDownload<T> {
+    /// The file differs from the known version, or no version was known
+    Modified(T),
+    /// The known version is still current, so the body wasn't downloaded
+    Unchanged,
+}
+
+impl<T> ConditionalDownload<T> {
+    /// The downloaded content, None if the file was unchanged
+    pub fn into_modified(self) -> Option<T> {
+        match self {
+            ConditionalDownload::Modified(content) => Some(content),
+            ConditionalDownload::Unchanged => None,
+        }
+    }
+}
+
 /// Server capabilities information
 #[derive(Debug, Clone)]
 pub struct ServerCapabilities {
@@ -56,6 +123,13 @@ pub struct ServerCapabilities {
     pub last_checked: std::time::Instant,
 }
 
+impl ServerCapabilities {
+    /// How long ago the capabilities were checked
+    pub fn age(&self) -> Duration {
+        self.last_checked.elapsed()
+    }
+}
+
 /// Health status information
 #[derive(Debug, Clone)]
 pub struct HealthStatus {
@@ -153,6 +227,20 @@ pub struct WebDAVService {
     download_semaphore: Arc<Semaphore>,
     /// Stores the working protocol (updated after successful protocol detection)
     working_protocol: Arc<std::sync::RwLock<Option<String>>>,
+    /// Listings of unchanged directories reused between sync passes
+    discovery_cache: Option<Arc<DiscoveryCache>>,
+    /// How much clock skew with the server is reported and tolerated
+    clock_skew: ClockSkewSettings,
+    /// Capabilities of servers, rechecked when stale or after a server upgrade
+    capability_cache: Arc<CapabilityCache>,
+    /// Downloads in flight per server, shared with other sources on the same host
+    host_download_limiter: Arc<HostDownloadLimiter>,
+    /// `Depth` header of each kind of PROPFIND
+    propfind_depths: PropfindDepths,
+    /// Checking downloads against the checksums the server sends
+    download_verification: DownloadVerification,
+    /// Visits of a directory nested inside itself before discovery stops; 0 turns this off
+    max_directory_visits: u32,
 }
 
 impl WebDAVService {
@@ -174,11 +262,23 @@ impl WebDAVService {
     ) -> Result<Self> {
         // Validate configuration
         config.validate()?;
+        concurrency_config.validate()?;
 
-        // Create HTTP client with timeout
-        let client = Client::builder()
+        // Create HTTP client with timeout and connection reuse, which avoids a TLS
+        // handshake per request when scanning thousands of directories
+        let mut client_builder = Client::builder()
             .timeout(config.timeout())
-            .build()?;
+            .pool_max_idle_per_host(concurrency_config.pool_max_idle_per_host)
+            .pool_idle_timeout(Duration::from_secs(concurrency_config.pool_idle_timeout_seconds))
+            .tcp_keepalive(concurrency_config.tcp_keepalive_seconds.map(Duration::from_secs));
+
+        client_builder = match concurrency_config.http_version {
+            HttpVersionPreference::Auto => client_builder,
+            HttpVersionPreference::Http1Only => client_builder.http1_only(),
+            HttpVersionPreference::Http2PriorKnowledge => client_builder.http2_prior_knowledge(),
+        };
+
+        let client = client_builder.build()?;
 
         // Create semaphores for concurrency control
         let scan_semaphore = Arc::new(Semaphore::new(concurrency_config.max_concurrent_scans));
@@ -192,9 +292,95 @@ impl WebDAVService {
             scan_semaphore,
             download_semaphore,
             working_protocol: Arc::new(std::sync::RwLock::new(None)),
+            discovery_cache: installed_discovery_cache(),
+            clock_skew: installed_clock_skew_settings(),
+            capability_cache: installed_capability_cache(),
+            host_download_limiter: installed_host_download_limiter(),
+            propfind_depths: PropfindDepths::default(),
+            download_verification: DownloadVerification::default(),
+            max_directory_visits: DEFAULT_MAX_DIRECTORY_VISITS,
         })
     }
 
+    /// Uses `cache` for directory listings instead of the process-wide cache
+    pub fn with_discovery_cache(mut self, cache: Option<Arc<DiscoveryCache>>) -> Self {
+        self.discovery_cache = cache;
+        self
+    }
+
+    /// Uses `settings` for clock skew instead of the process-wide settings
+    pub fn with_clock_skew_settings(mut self, settings: ClockSkewSettings) -> Self {
+        self.clock_skew = settings;
+        self
+    }
+
+    pub fn clock_skew_settings(&self) -> ClockSkewSettings {
+        self.clock_skew
+    }
+
+    /// Uses `cache` for server capabilities instead of the process-wide cache
+    pub fn with_capability_cache(mut self, cache: Arc<CapabilityCache>) -> Self {
+        self.capability_cache = cache;
+        self
+    }
+
+    /// Uses `limiter` for per-host downloads instead of the process-wide limiter
+    pub fn with_host_download_limiter(mut self, limiter: Arc<HostDownloadLimiter>) -> Self {
+        self.host_download_limiter = limiter;
+        self
+    }
+
+    /// Sends PROPFINDs with `depths` instead of the default depths
+    pub fn with_propfind_depths(mut self, depths: PropfindDepths) -> Self {
+        self.propfind_depths = depths;
+        self
+    }
+
+    pub fn propfind_depths(&self) -> PropfindDepths {
+        self.propfind_depths
+    }
+
+    /// Checks downloads with `verification` instead of the default
+    pub fn with_download_verification(mut self, verification: DownloadVerification) -> Self {
+        self.download_verification = verification;
+        self
+    }
+
+    /// Stops a recursive discovery once a directory is nested inside itself more
+    /// than `max_visits` times, instead of the default limit; 0 turns this off
+    pub fn with_max_directory_visits(mut self, max_visits: u32) -> Self {
+        self.max_directory_visits = max_visits;
+        self
+    }
+
+    /// Waits for a download slot of this service and one of its server's host
+    async fn acquire_download_permits(&self) -> Result<(SemaphorePermit<'_>, Option<OwnedSemaphorePermit>)> {
+        let permit = self.download_semaphore.acquire().await?;
+        let host_permit = self.host_download_limiter.acquire(&self.config.server_url).await?;
+        Ok((permit, host_permit))
+    }
+
+    /// Key of this server and user in the discovery and capability caches
+    fn cache_scope(&self) -> String {
+        format!("{}|{}", self.config.webdav_url(), self.config.username)
+    }
+
+    /// Invalidates the cached capabilities if the response's `Server` header
+    /// shows the server software changed since they were checked
+    fn observe_server_header(&self, response: &reqwest::Response) {
+        if let Some(server) = response.headers().get(reqwest::header::SERVER).and_then(|v| v.to_str().ok()) {
+            self.capability_cache.observe_server_header(&self.cache_scope(), server);
+        }
+    }
+
+    /// Saves the discovery cache to its file, if it has one
+    pub async fn persist_discovery_cache(&self) -> Result<()> {
+        match &self.discovery_cache {
+            Some(cache) => cache.persist().await,
+            None => Ok(()),
+        }
+    }
+
 
     // ============================================================================
     // Protocol Detection Methods
@@ -283,6 +469,9 @@ impl WebDAVService {
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
             server_type: self.config.server_type.clone(),
+            max_scan_depth: self.config.max_scan_depth,
+            detect_etag_aliases: self.config.detect_etag_aliases,
+            user_agent: self.config.user_agent.clone(),
         };
 
         // Test basic OPTIONS request
@@ -292,6 +481,7 @@ impl WebDAVService {
         let response = self.client
             .request(Method::OPTIONS, &webdav_url)
             .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("User-Agent", self.user_agent())
             .send()
             .await
             .map_err(|e| anyhow!("Connection failed: {}", e))?;
@@ -357,6 +547,7 @@ impl WebDAVService {
                 message: format!("Configuration error: {}", e),
                 server_version: None,
                 server_type: None,
+                clock_skew_seconds: None,
             });
         }
 
@@ -373,20 +564,30 @@ impl WebDAVService {
                     message: format!("Protocol detection failed: {}", e),
                     server_version: None,
                     server_type: None,
+                    clock_skew_seconds: None,
                 });
             }
         };
 
         // Test basic connectivity with OPTIONS request using detected protocol
         match self.test_options_request().await {
-            Ok((server_version, server_type)) => {
+            Ok((server_version, server_type, clock_skew)) => {
                 let effective_url = self.get_effective_server_url();
                 info!("✅ WebDAV connection successful using {} ({})", working_protocol.to_uppercase(), effective_url);
+                let mut message = format!("Connection successful using {}", working_protocol.to_uppercase());
+                if let Some(skew) = clock_skew.filter(|skew| self.clock_skew.exceeds_warning_threshold(*skew)) {
+                    warn!("⏰ Clock of WebDAV server {} is {} of ours", effective_url, clock_skew::describe_skew(skew));
+                    message.push_str(&format!(
+                        ", but the server's clock is {} of readur's; modification times may be misjudged",
+                        clock_skew::describe_skew(skew)
+                    ));
+                }
                 Ok(WebDAVConnectionResult {
                     success: true,
-                    message: format!("Connection successful using {}", working_protocol.to_uppercase()),
+                    message,
                     server_version,
                     server_type,
+                    clock_skew_seconds: clock_skew.map(|skew| skew.num_seconds()),
                 })
             }
             Err(e) => {
@@ -396,6 +597,7 @@ impl WebDAVService {
                     message: format!("Connection failed: {}", e),
                     server_version: None,
                     server_type: None,
+                    clock_skew_seconds: None,
                 })
             }
         }
@@ -411,14 +613,18 @@ impl WebDAVService {
             file_extensions: vec![],
             timeout_seconds: 30,
             server_type: test_config.server_type.clone(),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
 
         let service = Self::new(config)?;
         service.test_connection().await
     }
 
-    /// Performs OPTIONS request to test basic connectivity
-    async fn test_options_request(&self) -> Result<(Option<String>, Option<String>)> {
+    /// Performs OPTIONS request to test basic connectivity. Returns the server's
+    /// version and type and its clock skew.
+    async fn test_options_request(&self) -> Result<(Option<String>, Option<String>, Option<chrono::Duration>)> {
         // Create a temporary config with the effective server URL for WebDAV operations
         let effective_server_url = self.get_effective_server_url();
         let temp_config = WebDAVConfig {
@@ -429,14 +635,20 @@ impl WebDAVService {
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
             server_type: self.config.server_type.clone(),
+            max_scan_depth: self.config.max_scan_depth,
+            detect_etag_aliases: self.config.detect_etag_aliases,
+            user_agent: self.config.user_agent.clone(),
         };
         let webdav_url = temp_config.webdav_url();
         
+        let sent_at = chrono::Utc::now();
         let response = self.client
             .request(Method::OPTIONS, &webdav_url)
             .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("User-Agent", self.user_agent())
             .send()
             .await?;
+        let clock_skew = Self::response_clock_skew(&response, sent_at);
 
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -455,7 +667,29 @@ impl WebDAVService {
 
         let server_type = self.detect_server_type(&response, &server_version).await;
 
-        Ok((server_version, server_type))
+        Ok((server_version, server_type, clock_skew))
+    }
+
+    /// Measures the server's clock minus ours with an OPTIONS request. None when
+    /// the server doesn't send a usable `Date` header.
+    pub async fn measure_clock_skew(&self) -> Result<Option<chrono::Duration>> {
+        let url = self.get_url_for_path("/");
+        let sent_at = chrono::Utc::now();
+        let response = self.client
+            .request(Method::OPTIONS, &url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("User-Agent", self.user_agent())
+            .send()
+            .await?;
+
+        // Any response carries the server's time, so the status doesn't matter
+        self.observe_server_header(&response);
+        Ok(Self::response_clock_skew(&response, sent_at))
+    }
+
+    fn response_clock_skew(response: &reqwest::Response, sent_at: chrono::DateTime<chrono::Utc>) -> Option<chrono::Duration> {
+        let date = response.headers().get(reqwest::header::DATE)?.to_str().ok()?;
+        clock_skew::skew_from_date_header(date, sent_at, chrono::Utc::now())
     }
 
     /// Detects the WebDAV server type based on response headers and capabilities
@@ -506,6 +740,7 @@ impl WebDAVService {
         let response = self.client
             .get(&capabilities_url)
             .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("User-Agent", self.user_agent())
             .header("OCS-APIRequest", "true")
             .send()
             .await?;
@@ -553,7 +788,7 @@ impl WebDAVService {
             &url,
             Some(propfind_body.to_string()),
             Some(vec![
-                ("Depth", "1"),
+                ("Depth", self.propfind_depths.connection_test.as_header_value()),
                 ("Content-Type", "application/xml"),
             ]),
         ).await?;
@@ -652,6 +887,15 @@ impl WebDAVService {
         std::cmp::min(jittered_delay, self.retry_config.max_delay_ms)
     }
 
+    /// Calculates the jittered delay for retrying an HTTP status, using the policy's
+    /// base and maximum delay instead of the global ones
+    fn calculate_policy_retry_delay(&self, attempt: u32, policy: &ResolvedRetryPolicy) -> u64 {
+        let exponential_delay = (policy.initial_delay_ms as f64 * self.retry_config.backoff_multiplier.powi(attempt as i32)) as u64;
+        let jitter_multiplier = 0.9 + rand::thread_rng().gen::<f64>() * 0.2;
+        let jittered_delay = (exponential_delay as f64 * jitter_multiplier) as u64;
+        std::cmp::min(jittered_delay, policy.max_delay_ms)
+    }
+
     /// Performs authenticated request with simple retry logic (simplified from complex error recovery)
     pub async fn authenticated_request(
         &self,
@@ -663,8 +907,7 @@ impl WebDAVService {
         let mut attempt = 0;
         let mut delay = self.retry_config.initial_delay_ms;
 
-        // Build custom User-Agent header using centralized function
-        let user_agent = build_user_agent();
+        let user_agent = self.user_agent();
 
         // Enhanced debug logging for HTTP requests
         debug!("🌐 HTTP Request Details:");
@@ -706,6 +949,7 @@ impl WebDAVService {
                 Ok(response) => {
                     let status = response.status();
                     debug!("📥 HTTP Response: {} {}", status.as_u16(), status.canonical_reason().unwrap_or(""));
+                    self.observe_server_header(&response);
                     
                     // Log response headers for debugging
                     for (key, value) in response.headers() {
@@ -716,20 +960,30 @@ impl WebDAVService {
                         }
                     }
                     
-                    if status.is_success() || status.as_u16() == 207 {
+                    // 304 only answers conditional downloads, which treat it as "unchanged"
+                    if status.is_success() || status.as_u16() == 207 || status == reqwest::StatusCode::NOT_MODIFIED {
                         debug!("✅ HTTP request successful: {} {}", status.as_u16(), status.canonical_reason().unwrap_or(""));
                         return Ok(response);
                     }
 
-                    // Handle rate limiting
-                    if status.as_u16() == 429 {
-                        warn!("Rate limited, backing off for {}ms", self.retry_config.rate_limit_backoff_ms);
-                        sleep(Duration::from_millis(self.retry_config.rate_limit_backoff_ms)).await;
+                    // Retry according to the policy for this status (423 Locked, 429, 5xx by default)
+                    let policy = self.retry_config.policy_for_status(status.as_u16());
+                    if policy.retryable && attempt < policy.max_retries {
+                        let jittered_delay = self.calculate_policy_retry_delay(attempt, &policy);
+                        if status.as_u16() == 429 {
+                            warn!("Rate limited, backing off for {}ms", jittered_delay);
+                        } else {
+                            warn!("HTTP {} for {} {}, retrying in {}ms with jitter (attempt {}/{})",
+                                status, method, url, jittered_delay, attempt + 1, policy.max_retries);
+                        }
+
+                        sleep(Duration::from_millis(jittered_delay)).await;
+                        attempt += 1;
                         continue;
                     }
 
-                    // Handle client errors (don't retry)
-                    if status.is_client_error() && status.as_u16() != 429 {
+                    // Handle client errors that aren't (or are no longer) retried
+                    if status.is_client_error() {
                         let error_body = response.text().await.unwrap_or_default();
                         
                         // Provide specific guidance for 405 Method Not Allowed errors
@@ -766,21 +1020,6 @@ impl WebDAVService {
                         return Err(anyhow!("Client error: {} - {}", status, error_body));
                     }
 
-                    // Handle server errors (retry)
-                    if status.is_server_error() && attempt < self.retry_config.max_retries {
-                        let jittered_delay = self.calculate_retry_delay_with_jitter(attempt, self.retry_config.initial_delay_ms);
-                        warn!("Server error {}, retrying in {}ms with jitter (attempt {}/{}, base exponential: {}ms)", 
-                            status, jittered_delay, attempt + 1, self.retry_config.max_retries, delay);
-                        
-                        sleep(Duration::from_millis(jittered_delay)).await;
-                        delay = std::cmp::min(
-                            (delay as f64 * self.retry_config.backoff_multiplier) as u64,
-                            self.retry_config.max_delay_ms
-                        );
-                        attempt += 1;
-                        continue;
-                    }
-
                     return Err(anyhow!("Request failed: {} - {}", status,
                         response.text().await.unwrap_or_default()));
                 }
@@ -806,6 +1045,11 @@ impl WebDAVService {
         }
     }
 
+    /// User-Agent sent with requests: the source's own, or the default one
+    pub fn user_agent(&self) -> String {
+        self.config.user_agent.clone().unwrap_or_else(build_user_agent)
+    }
+
     // ============================================================================
     // URL Management Helper Methods (Previously separate module)
     // ============================================================================
@@ -822,9 +1066,12 @@ impl WebDAVService {
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
             server_type: self.config.server_type.clone(),
+            max_scan_depth: self.config.max_scan_depth,
+            detect_etag_aliases: self.config.detect_etag_aliases,
+            user_agent: self.config.user_agent.clone(),
         };
         let base_url = temp_config.webdav_url();
-        let clean_path = path.trim_start_matches('/');
+        let clean_path = encode_path_segments(path.trim_start_matches('/'));
         
         let final_url = if clean_path.is_empty() {
             base_url.clone()
@@ -845,44 +1092,28 @@ impl WebDAVService {
         final_url
     }
 
-    /// Convert full WebDAV href (from XML response) to relative path
+    /// Convert full WebDAV href (from XML response) to a decoded relative path
     /// 
-    /// Input:  "/remote.php/dav/files/username/Photos/image.jpg"
-    /// Output: "/Photos/image.jpg"
+    /// Input:  "/remote.php/dav/files/username/Photos/report%20%233.pdf"
+    /// Output: "/Photos/report #3.pdf"
     pub fn href_to_relative_path(&self, href: &str) -> String {
-        match self.config.server_type.as_deref() {
-            Some("nextcloud") => {
-                let prefix = format!("/remote.php/dav/files/{}", self.config.username);
-                if href.starts_with(&prefix) {
-                    let relative = &href[prefix.len()..];
-                    if relative.is_empty() { "/" } else { relative }.to_string()
-                } else {
-                    href.to_string()
-                }
-            }
-            Some("owncloud") => {
-                if href.starts_with("/remote.php/webdav") {
-                    let relative = &href[18..]; // Remove "/remote.php/webdav"
-                    if relative.is_empty() { "/" } else { relative }.to_string()
-                } else {
-                    href.to_string()
-                }
-            }
-            Some("generic") => {
-                if href.starts_with("/webdav") {
-                    let relative = &href[7..]; // Remove "/webdav"
-                    if relative.is_empty() { "/" } else { relative }.to_string()
-                } else {
-                    href.to_string()
-                }
-            }
-            _ => href.to_string()
+        let href = decode_path(href);
+        let prefix = match self.config.server_type.as_deref() {
+            Some("nextcloud") => format!("/remote.php/dav/files/{}", self.config.username),
+            Some("owncloud") => "/remote.php/webdav".to_string(),
+            Some("generic") => "/webdav".to_string(),
+            _ => return href,
+        };
+        match href.strip_prefix(&prefix) {
+            Some("") => "/".to_string(),
+            Some(relative) => relative.to_string(),
+            None => href,
         }
     }
 
     /// Convert file paths to the proper URL format for the server
     pub fn path_to_url(&self, relative_path: &str) -> String {
-        let clean_path = relative_path.trim_start_matches('/');
+        let clean_path = encode_path_segments(relative_path.trim_start_matches('/'));
         // Create a temporary config with the effective server URL
         let effective_server_url = self.get_effective_server_url();
         let temp_config = WebDAVConfig {
@@ -893,6 +1124,9 @@ impl WebDAVService {
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
             server_type: self.config.server_type.clone(),
+            max_scan_depth: self.config.max_scan_depth,
+            detect_etag_aliases: self.config.detect_etag_aliases,
+            user_agent: self.config.user_agent.clone(),
         };
         let base_url = temp_config.webdav_url();
         
@@ -951,9 +1185,10 @@ impl WebDAVService {
 
     /// Discovers both files and directories with their ETags for directory tracking
     pub async fn discover_files_and_directories(&self, directory_path: &str, recursive: bool) -> Result<WebDAVDiscoveryResult> {
-        let discovery_request_id = uuid::Uuid::new_v4();
+        let discovery_request_id = crate::logging::current_request_id()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         info!("[{}] 🔍 Discovering files and directories in: '{}' (recursive: {}, user_agent: '{}')", 
-              discovery_request_id, directory_path, recursive, build_user_agent());
+              discovery_request_id, directory_path, recursive, self.user_agent());
         
         let start_time = std::time::Instant::now();
         let result = if recursive {
@@ -985,9 +1220,10 @@ impl WebDAVService {
         recursive: bool, 
         _progress: Option<&SyncProgress> // Simplified: just placeholder for API compatibility
     ) -> Result<WebDAVDiscoveryResult> {
-        let discovery_request_id = uuid::Uuid::new_v4();
+        let discovery_request_id = crate::logging::current_request_id()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         info!("[{}] 🔍 Discovering files and directories in: '{}' (progress tracking simplified, recursive: {}, user_agent: '{}')", 
-              discovery_request_id, directory_path, recursive, build_user_agent());
+              discovery_request_id, directory_path, recursive, self.user_agent());
         
         let start_time = std::time::Instant::now();
         let result = if recursive {
@@ -1033,13 +1269,12 @@ impl WebDAVService {
             &url,
             Some(propfind_body.to_string()),
             Some(vec![
-                ("Depth", "1"),
+                ("Depth", self.propfind_depths.directory_listing.as_header_value()),
                 ("Content-Type", "application/xml"),
             ]),
         ).await?;
 
-        let body = response.text().await?;
-        let files = parse_propfind_response(&body)?;
+        let files = parse_propfind_body(response, parse_propfind_response).await?;
         
         // Filter out the directory itself and only return files
         let filtered_files: Vec<FileIngestionInfo> = files
@@ -1056,6 +1291,11 @@ impl WebDAVService {
         let mut all_files = Vec::new();
         let mut directories_to_scan = vec![directory_path.to_string()];
         let mut scanned_directories = std::collections::HashSet::new();
+        let mut depth_limited_directories = Vec::new();
+        // ETags of queued directories, from their parent's listing
+        let mut directory_etags: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        let mut etag_aliases = EtagAliasTracker::new(self.config.detect_etag_aliases);
+        let mut directory_visits = DirectoryVisits::new(self.max_directory_visits);
         let semaphore = Arc::new(Semaphore::new(self.concurrency_config.max_concurrent_scans));
         
         debug!("Starting recursive file scan from: {}", directory_path);
@@ -1079,10 +1319,11 @@ impl WebDAVService {
                 
                 let permit = semaphore.clone();
                 let service = self.clone();
+                let etag = directory_etags.remove(&dir);
                 
                 Some(async move {
                     let _permit = permit.acquire().await.unwrap();
-                    let result = service.discover_files_and_directories_single(&dir).await;
+                    let result = service.discover_directory_listing(&dir, etag.as_deref()).await;
                     (dir, result)
                 })
             });
@@ -1099,8 +1340,24 @@ impl WebDAVService {
                         
                         // Add subdirectories to the queue for the next iteration
                         for dir in discovery_result.directories {
+                            if self.exceeds_max_scan_depth(directory_path, &dir.relative_path) {
+                                debug!("Skipping directory beyond max scan depth: {}", dir.relative_path);
+                                depth_limited_directories.push(dir.relative_path);
+                                continue;
+                            }
+                            if etag_aliases.is_directory_alias(&dir) {
+                                debug!("Skipping directory with an already discovered ETag: {}", dir.relative_path);
+                                continue;
+                            }
+                            if let Err(loop_error) = directory_visits.record(&dir.relative_path, &dir.etag) {
+                                error!("🔁 {}", loop_error);
+                                return Err(loop_error.into());
+                            }
                             if dir.is_directory && !scanned_directories.contains(&dir.relative_path) {
                                 directories_to_scan.push(dir.relative_path.clone());
+                                if !dir.etag.is_empty() {
+                                    directory_etags.insert(dir.relative_path.clone(), dir.etag.clone());
+                                }
                                 debug!("Added subdirectory to scan queue: {}", dir.relative_path);
                             }
                         }
@@ -1115,7 +1372,15 @@ impl WebDAVService {
                    all_files.len(), directories_to_scan.len());
         }
 
+        let (all_files, aliases) = etag_aliases.finish(all_files);
         info!("Recursive scan completed. Found {} files total", all_files.len());
+        if !depth_limited_directories.is_empty() {
+            info!("Skipped {} directories beyond max scan depth {:?}: {}",
+                  depth_limited_directories.len(), self.config.max_scan_depth, depth_limited_directories.join(", "));
+        }
+        if !aliases.is_empty() {
+            info!("Skipped {} paths whose ETag was already discovered at another path", aliases.len());
+        }
         Ok(all_files)
     }
 
@@ -1141,6 +1406,24 @@ impl WebDAVService {
         result
     }
 
+    /// Lists a single directory, reusing the cached listing when the directory's
+    /// ETag from its parent's listing is unchanged
+    async fn discover_directory_listing(&self, directory_path: &str, etag: Option<&str>) -> Result<WebDAVDiscoveryResult> {
+        let (Some(cache), Some(etag)) = (&self.discovery_cache, etag) else {
+            return self.discover_files_and_directories_single(directory_path).await;
+        };
+
+        let scope = self.cache_scope();
+        if let Some(cached) = cache.get(&scope, directory_path, etag) {
+            debug!("Reusing cached listing of unchanged directory: {}", directory_path);
+            return Ok(cached);
+        }
+
+        let result = self.discover_files_and_directories_single(directory_path).await?;
+        cache.insert(&scope, directory_path, etag, &result);
+        Ok(result)
+    }
+
     /// Tries fallback URLs when the primary WebDAV URL fails with 405
     async fn try_fallback_discovery(&self, directory_path: &str) -> Result<WebDAVDiscoveryResult> {
         let fallback_urls = self.config.webdav_fallback_urls();
@@ -1149,7 +1432,7 @@ impl WebDAVService {
             let fallback_url = if directory_path == "/" || directory_path.is_empty() {
                 fallback_base_url.clone()
             } else {
-                format!("{}/{}", fallback_base_url.trim_end_matches('/'), directory_path.trim_start_matches('/'))
+                format!("{}/{}", fallback_base_url.trim_end_matches('/'), encode_path_segments(directory_path.trim_start_matches('/')))
             };
             
             info!("🔄 Trying fallback URL #{}: {}", i + 1, fallback_url);
@@ -1173,6 +1456,48 @@ impl WebDAVService {
         ))
     }
 
+    /// Sends a single directory listing PROPFIND for `path`, without retries, and returns the
+    /// raw response next to what discovery parses from it. Error statuses are
+    /// returned rather than raised, since those responses are often the ones worth seeing.
+    pub async fn debug_propfind(&self, path: &str) -> Result<PropfindDebugResult> {
+        let relative_path = self.convert_to_relative_path(path);
+        let url = self.get_url_for_path(&relative_path);
+        let started = Instant::now();
+
+        let response = self.client
+            .request(Method::from_bytes(b"PROPFIND")?, &url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("User-Agent", self.user_agent())
+            .header("Depth", self.propfind_depths.directory_listing.as_header_value())
+            .header("Content-Type", "application/xml")
+            .body(DISCOVERY_PROPFIND_BODY)
+            .send()
+            .await
+            .map_err(|e| anyhow!("PROPFIND to {} failed: {}", redact_url_credentials(&url), e))?;
+
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        let parsed = parse_propfind_response_with_directories(&body)
+            .map(|items| self.process_file_infos(items))
+            .map_err(|e| redact_url_credentials(&e.to_string()));
+
+        Ok(PropfindDebugResult {
+            url: redact_url_credentials(&url),
+            status,
+            content_type,
+            raw_xml: redact_url_credentials(&body),
+            parsed,
+            duration_ms,
+        })
+    }
+
     /// Performs the actual discovery with a specific URL
     async fn discover_files_and_directories_single_with_url(&self, directory_path: &str, url: &str) -> Result<WebDAVDiscoveryResult> {
         // Enhanced debug logging for WebDAV URL construction
@@ -1181,17 +1506,7 @@ impl WebDAVService {
         debug!("🔧 WebDAV config - Server URL: '{}', Username: '{}', WebDAV base URL: '{}'", 
                self.config.server_url, self.config.username, self.config.webdav_url());
         
-        let propfind_body = r#"<?xml version="1.0" encoding="utf-8"?>
-            <D:propfind xmlns:D="DAV:">
-                <D:prop>
-                    <D:displayname/>
-                    <D:getcontentlength/>
-                    <D:getlastmodified/>
-                    <D:getetag/>
-                    <D:resourcetype/>
-                    <D:creationdate/>
-                </D:prop>
-            </D:propfind>"#;
+        let propfind_body = DISCOVERY_PROPFIND_BODY;
 
         debug!("📤 Sending PROPFIND request to URL: {}", url);
         debug!("📋 PROPFIND body length: {} bytes", propfind_body.len());
@@ -1201,7 +1516,7 @@ impl WebDAVService {
             url,
             Some(propfind_body.to_string()),
             Some(vec![
-                ("Depth", "1"),
+                ("Depth", self.propfind_depths.directory_listing.as_header_value()),
                 ("Content-Type", "application/xml"),
             ]),
         ).await.map_err(|e| {
@@ -1210,8 +1525,7 @@ impl WebDAVService {
             e
         })?;
 
-        let body = response.text().await?;
-        let all_items = parse_propfind_response_with_directories(&body)?;
+        let all_items = parse_propfind_body(response, parse_propfind_response_with_directories).await?;
         
         // Process the items to convert href to relative paths
         let processed_items = self.process_file_infos(all_items);
@@ -1220,10 +1534,10 @@ impl WebDAVService {
         let mut files = Vec::new();
         let mut directories = Vec::new();
         
+        let normalized_directory_path = decode_path(directory_path.trim_end_matches('/'));
         for item in processed_items {
             // Skip the directory itself (handle both with and without trailing slash)
             let normalized_item_path = item.relative_path.trim_end_matches('/');
-            let normalized_directory_path = directory_path.trim_end_matches('/');
             
             if normalized_item_path == normalized_directory_path {
                 continue; // Skip the directory itself
@@ -1237,7 +1551,7 @@ impl WebDAVService {
         }
 
         debug!("Found {} files and {} directories in: {}", files.len(), directories.len(), directory_path);
-        Ok(WebDAVDiscoveryResult { files, directories })
+        Ok(WebDAVDiscoveryResult { files, directories, depth_limited_directories: Vec::new(), etag_aliases: Vec::new() })
     }
 
     /// Discovers files and directories recursively
@@ -1245,8 +1559,13 @@ impl WebDAVService {
         
         let mut all_files = Vec::new();
         let mut all_directories = Vec::new();
+        let mut depth_limited_directories = Vec::new();
         let mut directories_to_scan = vec![directory_path.to_string()];
         let mut scanned_directories = std::collections::HashSet::new();
+        // ETags of queued directories, from their parent's listing
+        let mut directory_etags: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        let mut etag_aliases = EtagAliasTracker::new(self.config.detect_etag_aliases);
+        let mut directory_visits = DirectoryVisits::new(self.max_directory_visits);
         let semaphore = Arc::new(Semaphore::new(self.concurrency_config.max_concurrent_scans));
         
         debug!("Starting recursive scan from: {}", directory_path);
@@ -1270,10 +1589,11 @@ impl WebDAVService {
                 
                 let permit = semaphore.clone();
                 let service = self.clone();
+                let etag = directory_etags.remove(&dir);
                 
                 Some(async move {
                     let _permit = permit.acquire().await.unwrap();
-                    let result = service.discover_files_and_directories_single(&dir).await;
+                    let result = service.discover_directory_listing(&dir, etag.as_deref()).await;
                     (dir, result)
                 })
             });
@@ -1290,9 +1610,25 @@ impl WebDAVService {
                         
                         // Add directories to our results and to the scan queue
                         for dir in discovery_result.directories {
+                            if self.exceeds_max_scan_depth(directory_path, &dir.relative_path) {
+                                debug!("Skipping directory beyond max scan depth: {}", dir.relative_path);
+                                depth_limited_directories.push(dir.relative_path);
+                                continue;
+                            }
+                            if etag_aliases.is_directory_alias(&dir) {
+                                debug!("Skipping directory with an already discovered ETag: {}", dir.relative_path);
+                                continue;
+                            }
+                            if let Err(loop_error) = directory_visits.record(&dir.relative_path, &dir.etag) {
+                                error!("🔁 {}", loop_error);
+                                return Err(loop_error.into());
+                            }
                             // Only add to scan queue if not already scanned
                             if !scanned_directories.contains(&dir.relative_path) {
                                 directories_to_scan.push(dir.relative_path.clone());
+                                if !dir.etag.is_empty() {
+                                    directory_etags.insert(dir.relative_path.clone(), dir.etag.clone());
+                                }
                                 debug!("Added subdirectory to scan queue: {} (scanned set size: {})", 
                                        dir.relative_path, scanned_directories.len());
                             } else {
@@ -1311,11 +1647,21 @@ impl WebDAVService {
                    all_files.len(), all_directories.len(), directories_to_scan.len());
         }
 
+        let (all_files, etag_aliases) = etag_aliases.finish(all_files);
         info!("Recursive scan completed. Found {} files and {} directories", all_files.len(), all_directories.len());
+        if !depth_limited_directories.is_empty() {
+            info!("Skipped {} directories beyond max scan depth {:?}", 
+                  depth_limited_directories.len(), self.config.max_scan_depth);
+        }
+        if !etag_aliases.is_empty() {
+            info!("Skipped {} paths whose ETag was already discovered at another path", etag_aliases.len());
+        }
         
         Ok(WebDAVDiscoveryResult { 
             files: all_files, 
-            directories: all_directories 
+            directories: all_directories,
+            depth_limited_directories,
+            etag_aliases,
         })
     }
 
@@ -1323,9 +1669,11 @@ impl WebDAVService {
     pub async fn estimate_crawl(&self) -> Result<WebDAVCrawlEstimate> {
         info!("📊 Estimating WebDAV crawl requirements");
         
-        let start_time = Instant::now();
         let mut total_directories = 0;
         let mut total_files = 0;
+        let mut supported_files = 0;
+        let mut total_bytes = 0i64;
+        let mut sampled_folders = 0;
         let mut sample_scan_time = Duration::from_millis(0);
         
         // Sample the first few watch folders to estimate
@@ -1337,6 +1685,11 @@ impl WebDAVService {
                 Ok(result) => {
                     total_directories += result.directories.len();
                     total_files += result.files.len();
+                    supported_files += result.files.iter()
+                        .filter(|file| self.config.is_supported_extension(&file.name))
+                        .count();
+                    total_bytes += result.files.iter().map(|file| file.size).sum::<i64>();
+                    sampled_folders += 1;
                     sample_scan_time += scan_start.elapsed();
                 }
                 Err(e) => {
@@ -1345,6 +1698,13 @@ impl WebDAVService {
             }
         }
         
+        // Extrapolate the sampled folders to all watch folders
+        let scale = if sampled_folders > 0 {
+            self.config.watch_folders.len() as f64 / sampled_folders as f64
+        } else {
+            0.0
+        };
+        
         // Simple estimation based on sample
         let avg_scan_time_per_folder = if total_directories > 0 {
             sample_scan_time.as_millis() as f64 / total_directories as f64
@@ -1353,15 +1713,15 @@ impl WebDAVService {
         };
         
         let estimated_total_scan_time = Duration::from_millis(
-            (avg_scan_time_per_folder * total_directories as f64 * self.config.watch_folders.len() as f64) as u64
+            (avg_scan_time_per_folder * total_directories as f64 * scale) as u64
         );
         
         Ok(WebDAVCrawlEstimate {
             folders: vec![], // Simplified: not building detailed folder info for basic estimation
-            total_files: (total_files * self.config.watch_folders.len()) as i64,
-            total_supported_files: (total_files * self.config.watch_folders.len()) as i64, // Assume all files are supported
+            total_files: (total_files as f64 * scale) as i64,
+            total_supported_files: (supported_files as f64 * scale) as i64,
             total_estimated_time_hours: (estimated_total_scan_time.as_secs_f64() / 3600.0) as f32,
-            total_size_mb: (total_files * 2) as f64, // Rough estimate in MB
+            total_size_mb: total_bytes as f64 * scale / (1024.0 * 1024.0),
         })
     }
 
@@ -1373,10 +1733,12 @@ impl WebDAVService {
         }).collect()
     }
 
-    /// Filters files by date for incremental syncs
+    /// Filters files by date for incremental syncs. Files modified up to the
+    /// clock skew tolerance before `since` are kept.
     pub fn filter_files_by_date(&self, files: Vec<FileIngestionInfo>, since: chrono::DateTime<chrono::Utc>) -> Vec<FileIngestionInfo> {
+        let cutoff = since - self.clock_skew.tolerance;
         files.into_iter().filter(|file| {
-            file.last_modified.map_or(false, |modified| modified > since)
+            file.last_modified.map_or(false, |modified| modified > cutoff)
         }).collect()
     }
 
@@ -1395,10 +1757,12 @@ impl WebDAVService {
         source_id: Option<uuid::Uuid>,
     ) -> Result<WebDAVDiscoveryResult> {
         let start_time = std::time::Instant::now();
-        let discovery_request_id = uuid::Uuid::new_v4();
+        // A sync started over HTTP logs its discovery under the request's id
+        let discovery_request_id = crate::logging::current_request_id()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         
         info!("[{}] 🔍 Starting WebDAV discovery for '{}' (user: {}, source: {:?}, recursive: {}, user_agent: '{}')", 
-              discovery_request_id, directory_path, user_id, source_id, recursive, build_user_agent());
+              discovery_request_id, directory_path, user_id, source_id, recursive, self.user_agent());
         
         // Check if we should skip this directory due to previous failures
         let error_check_start = std::time::Instant::now();
@@ -1421,6 +1785,8 @@ impl WebDAVService {
                     return Ok(WebDAVDiscoveryResult {
                         files: Vec::new(),
                         directories: Vec::new(),
+                        depth_limited_directories: Vec::new(),
+                        etag_aliases: Vec::new(),
                     });
                 } else {
                     debug!("[{}] ✅ Directory '{}' cleared for discovery (error check: {:.2}ms)", 
@@ -1477,6 +1843,11 @@ impl WebDAVService {
                           discovery_result.files.len() + discovery_result.directories.len());
                 }
             }
+            Err(e) if e.downcast_ref::<DirectoryLoopError>().is_some() => {
+                // Tracked at the looping directory, which later syncs skip, so the
+                // rest of the watch folder is still scanned
+                error!("[{}] ❌ WebDAV discovery stopped for '{}': {}", discovery_request_id, directory_path, e);
+            }
             Err(e) => {
                 let total_elapsed = start_time.elapsed();
                 error!("[{}] ❌ WebDAV discovery failed for '{}' after {:.2}s: {}", 
@@ -1484,8 +1855,8 @@ impl WebDAVService {
                 
                 // Track the error with enhanced context
                 let mut additional_context = std::collections::HashMap::new();
-                additional_context.insert("request_id".to_string(), serde_json::Value::String(discovery_request_id.to_string()));
-                additional_context.insert("user_agent".to_string(), serde_json::Value::String(build_user_agent()));
+                additional_context.insert("request_id".to_string(), serde_json::Value::String(discovery_request_id.clone()));
+                additional_context.insert("user_agent".to_string(), serde_json::Value::String(self.user_agent()));
                 additional_context.insert("recursive".to_string(), serde_json::Value::Bool(recursive));
                 
                 let context = ErrorContext {
@@ -1528,8 +1899,13 @@ impl WebDAVService {
     ) -> Result<WebDAVDiscoveryResult> {
         let mut all_files = Vec::new();
         let mut all_directories = Vec::new();
+        let mut depth_limited_directories = Vec::new();
         let mut directories_to_scan = vec![directory_path.to_string()];
         let mut scanned_directories = std::collections::HashSet::new();
+        // ETags of queued directories, from their parent's listing
+        let mut directory_etags: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        let mut etag_aliases = EtagAliasTracker::new(self.config.detect_etag_aliases);
+        let mut directory_visits = DirectoryVisits::new(self.max_directory_visits);
         let semaphore = Arc::new(Semaphore::new(self.concurrency_config.max_concurrent_scans));
         
         debug!("Starting recursive scan with error tracking from: {}", directory_path);
@@ -1554,6 +1930,7 @@ impl WebDAVService {
                 let permit = semaphore.clone();
                 let service = self.clone();
                 let error_tracker = error_tracker.clone();
+                let etag = directory_etags.remove(&dir);
                 
                 Some(async move {
                     let _permit = permit.acquire().await.unwrap();
@@ -1571,6 +1948,8 @@ impl WebDAVService {
                                 return (dir, Ok(WebDAVDiscoveryResult {
                                     files: Vec::new(),
                                     directories: Vec::new(),
+                                    depth_limited_directories: Vec::new(),
+                                    etag_aliases: Vec::new(),
                                 }));
                             }
                         }
@@ -1581,7 +1960,7 @@ impl WebDAVService {
                     }
                     
                     let dir_start_time = std::time::Instant::now();
-                    let result = service.discover_files_and_directories_single(&dir).await;
+                    let result = service.discover_directory_listing(&dir, etag.as_deref()).await;
                     
                     // Track success or failure for this specific directory
                     match &result {
@@ -1637,8 +2016,26 @@ impl WebDAVService {
                         
                         // Add subdirectories to the queue for the next iteration
                         for dir in discovery_result.directories {
+                            if self.exceeds_max_scan_depth(directory_path, &dir.relative_path) {
+                                debug!("Skipping directory beyond max scan depth: {}", dir.relative_path);
+                                depth_limited_directories.push(dir.relative_path);
+                                continue;
+                            }
+                            if etag_aliases.is_directory_alias(&dir) {
+                                debug!("Skipping directory with an already discovered ETag: {}", dir.relative_path);
+                                continue;
+                            }
+                            if let Err(loop_error) = directory_visits.record(&dir.relative_path, &dir.etag) {
+                                error!("🔁 {}", loop_error);
+                                let error = anyhow::Error::from(loop_error);
+                                self.track_directory_loop(user_id, error_tracker, source_id, &scanned_dir, &error, start_time).await;
+                                return Err(error);
+                            }
                             if dir.is_directory && !scanned_directories.contains(&dir.relative_path) {
                                 directories_to_scan.push(dir.relative_path.clone());
+                                if !dir.etag.is_empty() {
+                                    directory_etags.insert(dir.relative_path.clone(), dir.etag.clone());
+                                }
                                 debug!("Added subdirectory to scan queue: {}", dir.relative_path);
                             }
                             all_directories.push(dir);
@@ -1655,13 +2052,57 @@ impl WebDAVService {
                    all_files.len(), all_directories.len(), directories_to_scan.len());
         }
 
+        let (all_files, etag_aliases) = etag_aliases.finish(all_files);
         info!("Recursive scan with error tracking completed. Found {} files and {} directories", all_files.len(), all_directories.len());
+        if !depth_limited_directories.is_empty() {
+            info!("Skipped {} directories beyond max scan depth {:?}", 
+                  depth_limited_directories.len(), self.config.max_scan_depth);
+        }
+        if !etag_aliases.is_empty() {
+            info!("Skipped {} paths whose ETag was already discovered at another path", etag_aliases.len());
+        }
         Ok(WebDAVDiscoveryResult { 
             files: all_files, 
-            directories: all_directories 
+            directories: all_directories,
+            depth_limited_directories,
+            etag_aliases,
         })
     }
 
+    /// Records the directory whose listing went over the visit limit as a
+    /// problematic path, so later syncs skip it and the loop ends there
+    async fn track_directory_loop(
+        &self,
+        user_id: uuid::Uuid,
+        error_tracker: &SourceErrorTracker,
+        source_id: Option<uuid::Uuid>,
+        loop_path: &str,
+        error: &anyhow::Error,
+        start_time: std::time::Instant,
+    ) {
+        let context = ErrorContext {
+            resource_path: loop_path.to_string(),
+            source_id,
+            operation: "discover_recursive".to_string(),
+            response_time: Some(start_time.elapsed()),
+            response_size: None,
+            server_type: self.config.server_type.clone(),
+            server_version: None,
+            additional_context: std::collections::HashMap::new(),
+        };
+
+        if let Err(track_error) = error_tracker.track_error(
+            user_id,
+            ErrorSourceType::WebDAV,
+            source_id,
+            loop_path,
+            error,
+            context,
+        ).await {
+            warn!("Failed to track directory loop at '{}': {}", loop_path, track_error);
+        }
+    }
+
     /// Enhanced single directory discovery with error tracking
     async fn discover_files_and_directories_single_with_error_tracking(
         &self,
@@ -1727,7 +2168,7 @@ impl WebDAVService {
             url,
             Some(propfind_body.to_string()),
             Some(vec![
-                ("Depth", "1"),
+                ("Depth", self.propfind_depths.directory_listing.as_header_value()),
                 ("Content-Type", "application/xml"),
             ]),
         ).await.map_err(|e| {
@@ -1773,8 +2214,7 @@ impl WebDAVService {
             e
         })?;
 
-        let body = response.text().await?;
-        let all_items = parse_propfind_response_with_directories(&body)?;
+        let all_items = parse_propfind_body(response, parse_propfind_response_with_directories).await?;
         
         // Process the items to convert href to relative paths
         let processed_items = self.process_file_infos(all_items);
@@ -1783,10 +2223,10 @@ impl WebDAVService {
         let mut files = Vec::new();
         let mut directories = Vec::new();
         
+        let normalized_directory_path = decode_path(directory_path.trim_end_matches('/'));
         for item in processed_items {
             // Skip the directory itself (handle both with and without trailing slash)
             let normalized_item_path = item.relative_path.trim_end_matches('/');
-            let normalized_directory_path = directory_path.trim_end_matches('/');
             
             if normalized_item_path == normalized_directory_path {
                 continue; // Skip the directory itself
@@ -1800,7 +2240,7 @@ impl WebDAVService {
         }
 
         debug!("Found {} files and {} directories in: {}", files.len(), directories.len(), directory_path);
-        Ok(WebDAVDiscoveryResult { files, directories })
+        Ok(WebDAVDiscoveryResult { files, directories, depth_limited_directories: Vec::new(), etag_aliases: Vec::new() })
     }
 
     /// Tries fallback URLs with error tracking when the primary WebDAV URL fails with 405
@@ -1818,7 +2258,7 @@ impl WebDAVService {
             let fallback_url = if directory_path == "/" || directory_path.is_empty() {
                 fallback_base_url.clone()
             } else {
-                format!("{}/{}", fallback_base_url.trim_end_matches('/'), directory_path.trim_start_matches('/'))
+                format!("{}/{}", fallback_base_url.trim_end_matches('/'), encode_path_segments(directory_path.trim_start_matches('/')))
             };
             
             info!("🔄 Trying fallback URL #{}: {}", i + 1, fallback_url);
@@ -1972,7 +2412,7 @@ impl WebDAVService {
 
     /// Downloads a file from WebDAV server by path
     pub async fn download_file(&self, file_path: &str) -> Result<Vec<u8>> {
-        let _permit = self.download_semaphore.acquire().await?;
+        let _permits = self.acquire_download_permits().await?;
         
         debug!("⬇️ Downloading file: {}", file_path);
         
@@ -2001,35 +2441,176 @@ impl WebDAVService {
         Ok(content.to_vec())
     }
 
-    /// Downloads a file from WebDAV server using FileIngestionInfo
-    pub async fn download_file_info(&self, file_info: &FileIngestionInfo) -> Result<Vec<u8>> {
-        let _permit = self.download_semaphore.acquire().await?;
+    /// Downloads a file from WebDAV server by path, streaming it to a temporary file
+    /// instead of memory when it is larger than the policy's threshold
+    pub async fn download_file_spooled(&self, file_path: &str, policy: &SpoolPolicy) -> Result<DownloadedFile> {
+        self.download_file_spooled_if_changed(file_path, None, policy).await?
+            .into_modified()
+            .ok_or_else(|| anyhow!("Unconditional download of '{}' reported no change", file_path))
+    }
+
+    /// Like [`Self::download_file_spooled`], but skips the download when the file is
+    /// still the `known` version
+    pub async fn download_file_spooled_if_changed(
+        &self,
+        file_path: &str,
+        known: Option<&SourceFileVersion>,
+        policy: &SpoolPolicy,
+    ) -> Result<ConditionalDownload<DownloadedFile>> {
+        let _permits = self.acquire_download_permits().await?;
+
+        debug!("⬇️ Downloading file: {}", file_path);
+
+        let relative_path = self.convert_to_relative_path(file_path);
+        let url = self.get_url_for_path(&relative_path);
+
+        let mut attempt = 0;
+        loop {
+            let Some(mut response) = self.conditional_get(&url, file_path, known).await? else {
+                return Ok(ConditionalDownload::Unchanged);
+            };
+
+            let mut hasher = self.checksum_hasher(&response);
+            let mut sink = DownloadSink::new(policy, response.content_length()).await?;
+            while let Some(chunk) = response.chunk().await? {
+                hasher.update(&chunk);
+                sink.write(&chunk).await?;
+            }
+            let downloaded = sink.finish().await?;
+
+            // A corrupted download is dropped, which removes its spool file
+            if !self.keep_download(file_path, hasher, &mut attempt)? {
+                continue;
+            }
+
+            match &downloaded {
+                DownloadedFile::InMemory(data) => debug!("✅ Downloaded {} bytes for file: {}", data.len(), file_path),
+                DownloadedFile::Spooled(file) => debug!(
+                    "✅ Downloaded {} bytes for file: {} (spooled to {})",
+                    file.size(), file_path, file.path().display()
+                ),
+            }
+
+            return Ok(ConditionalDownload::Modified(downloaded));
+        }
+    }
+
+    /// Downloads a file from WebDAV server using FileIngestionInfo. When re-syncing a
+    /// file whose `known` version was stored before, the download is conditional on
+    /// it having changed.
+    pub async fn download_file_info(
+        &self,
+        file_info: &FileIngestionInfo,
+        known: Option<&SourceFileVersion>,
+    ) -> Result<ConditionalDownload<Vec<u8>>> {
+        let _permits = self.acquire_download_permits().await?;
         
         debug!("⬇️ Downloading file: {}", file_info.relative_path);
         
         // Use the relative path directly since it's already processed
         let relative_path = &file_info.relative_path;
         let url = self.get_url_for_path(&relative_path);
-        
+
+        let mut attempt = 0;
+        loop {
+            let Some(response) = self.conditional_get(&url, &file_info.relative_path, known).await? else {
+                return Ok(ConditionalDownload::Unchanged);
+            };
+
+            let mut hasher = self.checksum_hasher(&response);
+            let content = response.bytes().await?;
+            hasher.update(&content);
+
+            if !self.keep_download(&file_info.relative_path, hasher, &mut attempt)? {
+                continue;
+            }
+
+            debug!("✅ Downloaded {} bytes for file: {}", content.len(), file_info.relative_path);
+            return Ok(ConditionalDownload::Modified(content.to_vec()));
+        }
+    }
+
+    /// Hasher for the checksums the server sent with a download, which hashes
+    /// nothing when checksums are off or the server sent none
+    fn checksum_hasher(&self, response: &reqwest::Response) -> ChecksumHasher {
+        let expected = if self.download_verification.checksums == ChecksumVerification::Off {
+            Vec::new()
+        } else {
+            download_integrity::checksums_from_headers(response.headers())
+        };
+        ChecksumHasher::new(expected)
+    }
+
+    /// Whether to keep a download, given the hasher it went through. A checksum
+    /// mismatch is downloaded again until the retries run out, then fails the
+    /// download. `attempt` counts the retries so far.
+    fn keep_download(&self, file_path: &str, hasher: ChecksumHasher, attempt: &mut u32) -> Result<bool> {
+        let check = hasher.finish();
+        match download_integrity::judge(self.download_verification.checksums, file_path, &check) {
+            DownloadVerdict::Keep => {
+                if let ChecksumCheck::Verified(algorithm) = check {
+                    debug!("🔒 {} checksum verified for file: {}", algorithm.name(), file_path);
+                }
+                Ok(true)
+            }
+            DownloadVerdict::Reject(reason) => Err(anyhow!(reason)),
+            DownloadVerdict::Retry(reason) if *attempt < self.download_verification.retries => {
+                *attempt += 1;
+                warn!("⚠️ {}; downloading again (retry {}/{})", reason, attempt, self.download_verification.retries);
+                Ok(false)
+            }
+            DownloadVerdict::Retry(reason) => Err(anyhow!("{}; giving up after {} attempts", reason, *attempt + 1)),
+        }
+    }
+
+    /// GETs a file, with `If-None-Match` when a `known` version is given. Returns
+    /// None if the file is unchanged: the server answered 304 Not Modified, or it
+    /// ignored the condition but the response headers describe the known version,
+    /// in which case the body is dropped unread.
+    async fn conditional_get(
+        &self,
+        url: &str,
+        file_path: &str,
+        known: Option<&SourceFileVersion>,
+    ) -> Result<Option<reqwest::Response>> {
+        let if_none_match = known.and_then(|version| version.if_none_match());
+        let headers = if_none_match.as_deref().map(|etag| vec![("If-None-Match", etag)]);
+
         let response = self.authenticated_request(
             reqwest::Method::GET,
-            &url,
-            None,
+            url,
             None,
+            headers,
         ).await?;
 
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            debug!("⏭️ File unchanged since last sync (304): {}", file_path);
+            return Ok(None);
+        }
+
         if !response.status().is_success() {
             return Err(anyhow!(
                 "Failed to download file '{}': HTTP {}",
-                file_info.relative_path,
+                file_path,
                 response.status()
             ));
         }
 
-        let content = response.bytes().await?;
-        debug!("✅ Downloaded {} bytes for file: {}", content.len(), file_info.relative_path);
-        
-        Ok(content.to_vec())
+        if let Some(known) = known {
+            let header = |name: reqwest::header::HeaderName| {
+                response.headers().get(name).and_then(|value| value.to_str().ok())
+            };
+            let etag = header(reqwest::header::ETAG);
+            let last_modified = header(reqwest::header::LAST_MODIFIED)
+                .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+                .map(|date| date.with_timezone(&chrono::Utc));
+            if known.matches_response(etag, response.content_length(), last_modified) {
+                debug!("⏭️ File unchanged since last sync (server ignored If-None-Match): {}", file_path);
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(response))
     }
 
     /// Downloads multiple files concurrently
@@ -2041,7 +2622,10 @@ impl WebDAVService {
             let service_clone = self.clone();
             
             async move {
-                let result = service_clone.download_file_info(&file_clone).await;
+                let result = service_clone.download_file_info(&file_clone, None).await.and_then(|download| {
+                    download.into_modified()
+                        .ok_or_else(|| anyhow!("Unconditional download of '{}' reported no change", file_clone.relative_path))
+                });
                 (file_clone, result)
             }
         });
@@ -2068,7 +2652,7 @@ impl WebDAVService {
     /// # Returns
     /// A `WebDAVDownloadResult` containing the file content, updated file info, and MIME detection details
     pub async fn download_file_with_mime_detection(&self, file_info: &FileIngestionInfo) -> Result<WebDAVDownloadResult> {
-        let _permit = self.download_semaphore.acquire().await?;
+        let _permits = self.acquire_download_permits().await?;
         
         debug!("⬇️🔍 Downloading file with MIME detection: {}", file_info.relative_path);
         
@@ -2206,13 +2790,12 @@ impl WebDAVService {
             &url,
             Some(propfind_body.to_string()),
             Some(vec![
-                ("Depth", "0"),
+                ("Depth", self.propfind_depths.file_metadata.as_header_value()),
                 ("Content-Type", "application/xml"),
             ]),
         ).await?;
 
-        let body = response.text().await?;
-        let files = parse_propfind_response(&body)?;
+        let files = parse_propfind_body(response, parse_propfind_response).await?;
         
         files.into_iter()
             .find(|f| f.relative_path == file_path)
@@ -2245,6 +2828,9 @@ impl WebDAVService {
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
             server_type: self.config.server_type.clone(),
+            max_scan_depth: self.config.max_scan_depth,
+            detect_etag_aliases: self.config.detect_etag_aliases,
+            user_agent: self.config.user_agent.clone(),
         };
         
         let options_response = self.authenticated_request(
@@ -2286,6 +2872,77 @@ impl WebDAVService {
         })
     }
 
+    /// The server's capabilities, from the cache unless they are older than its
+    /// TTL or the server software changed since they were checked
+    pub async fn capabilities(&self) -> Result<ServerCapabilities> {
+        if let Some(capabilities) = self.capability_cache.get_fresh(&self.cache_scope()) {
+            return Ok(capabilities);
+        }
+        self.refresh_capabilities().await
+    }
+
+    /// The capabilities cached for this server, however old, without contacting it
+    pub fn cached_capabilities(&self) -> Option<ServerCapabilities> {
+        self.capability_cache.get(&self.cache_scope())
+    }
+
+    pub fn capability_ttl(&self) -> Duration {
+        self.capability_cache.ttl()
+    }
+
+    /// Checks the server's capabilities, including whether `Depth: infinity`
+    /// works in practice, and caches them
+    pub async fn refresh_capabilities(&self) -> Result<ServerCapabilities> {
+        let scope = self.cache_scope();
+        let previous = self.capability_cache.get(&scope);
+
+        let mut capabilities = self.get_server_capabilities().await?;
+        if capabilities.supports_depth_infinity {
+            capabilities.infinity_depth_works = self.test_infinity_depth().await?;
+            capabilities.infinity_depth_tested = true;
+        }
+
+        if let Some(previous) = previous.filter(|previous| previous.infinity_depth_tested) {
+            if previous.infinity_depth_works != capabilities.infinity_depth_works {
+                info!(
+                    "Depth infinity PROPFIND {} working on {}",
+                    if capabilities.infinity_depth_works { "started" } else { "stopped" },
+                    redact_url_credentials(&self.config.webdav_url())
+                );
+            }
+        }
+
+        self.capability_cache.insert(&scope, capabilities.clone());
+        Ok(capabilities)
+    }
+
+    /// Whether the server answers a `Depth: infinity` PROPFIND of the first watch
+    /// folder with a multistatus. Only the status is read, so a large listing
+    /// isn't downloaded.
+    async fn test_infinity_depth(&self) -> Result<bool> {
+        let folder = self.config.watch_folders.first().map(String::as_str).unwrap_or("/");
+        let response = self.client
+            .request(Method::from_bytes(b"PROPFIND")?, self.get_url_for_path(folder))
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("User-Agent", self.user_agent())
+            .header("Depth", PropfindDepth::Infinity.as_header_value())
+            .header("Content-Type", "application/xml")
+            .body(INFINITY_DEPTH_TEST_BODY)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => {
+                debug!("Depth infinity PROPFIND of {} answered {}", folder, response.status());
+                Ok(response.status().as_u16() == 207)
+            }
+            Err(e) => {
+                debug!("Depth infinity PROPFIND of {} failed: {}", folder, e);
+                Ok(false)
+            }
+        }
+    }
+
     /// Performs a health check on the WebDAV service
     pub async fn health_check(&self) -> Result<HealthStatus> {
         info!("🏥 Performing WebDAV service health check");
@@ -2527,6 +3184,37 @@ impl WebDAVService {
         remainder.starts_with('/') && remainder[1..].find('/').is_none()
     }
 
+    /// Returns how many directory levels `path` sits below the watch folder it belongs to.
+    /// Falls back to `scan_root` when the path is not under any configured watch folder.
+    pub fn scan_depth_below_watch_root(&self, scan_root: &str, path: &str) -> u32 {
+        let normalized_path = path.trim_end_matches('/');
+
+        let watch_root = self.config.watch_folders.iter()
+            .map(|folder| folder.trim_end_matches('/'))
+            .filter(|folder| {
+                normalized_path == *folder
+                    || normalized_path.starts_with(&format!("{}/", folder))
+                    || folder.is_empty()
+            })
+            .max_by_key(|folder| folder.len())
+            .unwrap_or_else(|| scan_root.trim_end_matches('/'));
+
+        normalized_path
+            .strip_prefix(watch_root)
+            .unwrap_or(normalized_path)
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .count() as u32
+    }
+
+    /// Checks whether a directory lies beyond the configured `max_scan_depth`
+    pub fn exceeds_max_scan_depth(&self, scan_root: &str, directory_path: &str) -> bool {
+        match self.config.max_scan_depth {
+            Some(max_depth) => self.scan_depth_below_watch_root(scan_root, directory_path) > max_depth,
+            None => false,
+        }
+    }
+
     /// Gets configuration information
     pub fn get_config(&self) -> &WebDAVConfig {
         &self.config
@@ -2583,6 +3271,13 @@ impl Clone for WebDAVService {
             scan_semaphore: Arc::clone(&self.scan_semaphore),
             download_semaphore: Arc::clone(&self.download_semaphore),
             working_protocol: Arc::clone(&self.working_protocol),
+            discovery_cache: self.discovery_cache.clone(),
+            clock_skew: self.clock_skew,
+            capability_cache: Arc::clone(&self.capability_cache),
+            host_download_limiter: Arc::clone(&self.host_download_limiter),
+            propfind_depths: self.propfind_depths,
+            download_verification: self.download_verification,
+            max_directory_visits: self.max_directory_visits,
         }
     }
 }
@@ -2592,6 +3287,22 @@ pub async fn test_webdav_connection(test_config: &WebDAVTestConnection) -> Resul
     WebDAVService::test_connection_with_config(test_config).await
 }
 
+/// Reads a PROPFIND response and parses it with `parse`, naming the response's status
+/// and content type when the body turns out not to be a multistatus document
+async fn parse_propfind_body(
+    response: reqwest::Response,
+    parse: fn(&str) -> Result<Vec<FileIngestionInfo>>,
+) -> Result<Vec<FileIngestionInfo>> {
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await?;
+    parse(&body).map_err(|e| InvalidMultistatusError::add_response_details(e, status, content_type.as_deref()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2612,6 +3323,9 @@ mod tests {
             file_extensions: vec![],
             timeout_seconds: 30,
             server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         let service = WebDAVService::new(config).expect("Failed to create WebDAV service");
@@ -2637,6 +3351,9 @@ mod tests {
             file_extensions: vec![],
             timeout_seconds: 30,
             server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         let retry_config = RetryConfig {
@@ -2646,6 +3363,7 @@ mod tests {
             backoff_multiplier: 2.0,
             timeout_seconds: 30,
             rate_limit_backoff_ms: 5000,
+            status_policies: RetryConfig::default_status_policies(),
         };
         
         let service = WebDAVService::new_with_retry(config, retry_config).expect("Failed to create WebDAV service");
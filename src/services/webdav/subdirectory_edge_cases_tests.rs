@@ -16,6 +16,9 @@ fn create_test_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string(), "docx".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     WebDAVService::new(config).unwrap()
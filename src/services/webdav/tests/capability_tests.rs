@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod capability_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::services::webdav::{CapabilityCache, WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const BASE: &str = "/remote.php/dav/files/testuser";
+
+    fn create_test_service(server_url: &str, cache: Arc<CapabilityCache>) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Docs".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new(config)
+            .expect("Failed to create test service")
+            .with_capability_cache(cache)
+    }
+
+    fn options_response(server: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .insert_header("dav", "1, 2")
+            .insert_header("allow", "OPTIONS, GET, PROPFIND")
+            .insert_header("server", server)
+    }
+
+    async fn mount_infinity_propfind(mock_server: &MockServer, status: u16) {
+        Mock::given(method("PROPFIND"))
+            .and(path(format!("{BASE}/Docs")))
+            .and(header("depth", "infinity"))
+            .respond_with(ResponseTemplate::new(status))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_are_cached_within_the_ttl() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("OPTIONS"))
+            .and(path(BASE))
+            .respond_with(options_response("Apache/2.4.62"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        mount_infinity_propfind(&mock_server, 207).await;
+
+        let service = create_test_service(&mock_server.uri(), Arc::new(CapabilityCache::new(Duration::from_secs(3600))));
+        assert!(service.cached_capabilities().is_none());
+
+        let capabilities = service.capabilities().await.expect("Checking should succeed");
+        assert_eq!(capabilities.server_software.as_deref(), Some("Apache/2.4.62"));
+        assert!(capabilities.infinity_depth_tested);
+        assert!(capabilities.infinity_depth_works);
+
+        // Served from the cache, so OPTIONS is only sent once
+        let cached = service.capabilities().await.expect("Cached capabilities");
+        assert!(cached.infinity_depth_works);
+        assert!(service.cached_capabilities().is_some());
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_stale_capabilities_are_checked_again() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("OPTIONS"))
+            .and(path(BASE))
+            .respond_with(options_response("Apache/2.4.62"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        mount_infinity_propfind(&mock_server, 403).await;
+
+        let service = create_test_service(&mock_server.uri(), Arc::new(CapabilityCache::new(Duration::ZERO)));
+        let capabilities = service.capabilities().await.expect("Checking should succeed");
+        assert!(capabilities.infinity_depth_tested);
+        assert!(!capabilities.infinity_depth_works, "403 means Depth: infinity is refused");
+
+        service.capabilities().await.expect("Checking again should succeed");
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_changed_server_header_invalidates_capabilities() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("OPTIONS"))
+            .and(path(BASE))
+            .respond_with(options_response("Apache/2.4.62"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("OPTIONS"))
+            .and(path(BASE))
+            .respond_with(options_response("Apache/2.4.63"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        mount_infinity_propfind(&mock_server, 207).await;
+
+        let service = create_test_service(&mock_server.uri(), Arc::new(CapabilityCache::new(Duration::from_secs(3600))));
+        service.capabilities().await.expect("Checking should succeed");
+
+        // The clock skew check at the start of a sync sees the upgraded server
+        service.measure_clock_skew().await.expect("Measuring should succeed");
+        assert!(service.cached_capabilities().is_none());
+
+        let capabilities = service.capabilities().await.expect("Checking again should succeed");
+        assert_eq!(capabilities.server_software.as_deref(), Some("Apache/2.4.63"));
+
+        mock_server.verify().await;
+    }
+
+    #[test]
+    fn test_server_header_without_cached_capabilities_is_ignored() {
+        let cache = CapabilityCache::new(Duration::from_secs(3600));
+        assert!(!cache.observe_server_header("scope", "nginx"));
+        assert!(cache.get("scope").is_none());
+    }
+}
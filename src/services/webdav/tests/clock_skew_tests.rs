@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod clock_skew_tests {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    use crate::models::FileIngestionInfo;
+    use crate::services::webdav::clock_skew::{describe_skew, skew_from_date_header};
+    use crate::services::webdav::{ClockSkewSettings, WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const BASE: &str = "/remote.php/dav/files/testuser";
+
+    fn create_test_service(server_url: &str) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Docs".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new(config).expect("Failed to create test service")
+    }
+
+    #[allow(deprecated)]
+    fn modified_at(name: &str, last_modified: DateTime<Utc>) -> FileIngestionInfo {
+        FileIngestionInfo {
+            relative_path: format!("/Docs/{name}"),
+            full_path: format!("{BASE}/Docs/{name}"),
+            path: format!("/Docs/{name}"),
+            name: name.to_string(),
+            size: 1024,
+            mime_type: "application/pdf".to_string(),
+            last_modified: Some(last_modified),
+            etag: format!("{name}-etag"),
+            is_directory: false,
+            created_at: None,
+            permissions: None,
+            owner: None,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_skew_is_measured_against_the_round_trip_midpoint() {
+        let sent_at = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let received_at = sent_at + Duration::seconds(2);
+
+        let ahead = skew_from_date_header("Fri, 16 Oct 2026 12:10:01 GMT", sent_at, received_at);
+        assert_eq!(ahead, Some(Duration::seconds(600)));
+
+        let behind = skew_from_date_header("Fri, 16 Oct 2026 11:59:01 GMT", sent_at, received_at);
+        assert_eq!(behind, Some(Duration::seconds(-60)));
+
+        assert_eq!(skew_from_date_header("yesterday", sent_at, received_at), None);
+    }
+
+    #[test]
+    fn test_skew_is_described_with_its_direction() {
+        assert_eq!(describe_skew(Duration::seconds(42)), "42s ahead");
+        assert_eq!(describe_skew(Duration::seconds(-425)), "7m 5s behind");
+        assert_eq!(describe_skew(Duration::seconds(2 * 3600 + 90)), "2h 1m ahead");
+    }
+
+    #[test]
+    fn test_warning_threshold_applies_in_both_directions() {
+        let settings = ClockSkewSettings::default();
+        assert!(!settings.exceeds_warning_threshold(Duration::seconds(300)));
+        assert!(settings.exceeds_warning_threshold(Duration::seconds(301)));
+        assert!(settings.exceeds_warning_threshold(Duration::seconds(-301)));
+
+        let disabled = ClockSkewSettings { warning_threshold: Duration::zero(), ..settings };
+        assert!(!disabled.exceeds_warning_threshold(Duration::days(1)));
+    }
+
+    #[test]
+    fn test_tolerance_keeps_files_modified_just_before_the_cutoff() {
+        let since = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let files = || {
+            vec![
+                modified_at("after.pdf", since + Duration::seconds(1)),
+                modified_at("borderline.pdf", since - Duration::seconds(30)),
+                modified_at("old.pdf", since - Duration::hours(1)),
+            ]
+        };
+        let names = |files: Vec<FileIngestionInfo>| files.into_iter().map(|f| f.name).collect::<Vec<_>>();
+
+        let strict = create_test_service("https://cloud.example.com");
+        assert_eq!(names(strict.filter_files_by_date(files(), since)), vec!["after.pdf"]);
+
+        let tolerant = create_test_service("https://cloud.example.com").with_clock_skew_settings(ClockSkewSettings {
+            tolerance: Duration::seconds(60),
+            ..ClockSkewSettings::default()
+        });
+        assert_eq!(
+            names(tolerant.filter_files_by_date(files(), since)),
+            vec!["after.pdf", "borderline.pdf"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skew_is_measured_from_the_date_header() {
+        let mock_server = MockServer::start().await;
+        let server_time = (Utc::now() + Duration::minutes(10)).format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+
+        // Even a rejected request tells the server's time
+        Mock::given(method("OPTIONS"))
+            .and(path(BASE))
+            .respond_with(ResponseTemplate::new(401).insert_header("date", server_time.as_str()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri());
+        let skew = service
+            .measure_clock_skew()
+            .await
+            .expect("Measuring should succeed")
+            .expect("The server sent its time");
+
+        assert!((598..=601).contains(&skew.num_seconds()), "measured {}", skew);
+        assert!(service.clock_skew_settings().exceeds_warning_threshold(skew));
+
+        mock_server.verify().await;
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod concurrency_config_tests {
+    use crate::services::webdav::{
+        ConcurrencyConfig, HttpVersionPreference, RetryConfig, WebDAVConfig, WebDAVService,
+    };
+
+    fn test_webdav_config() -> WebDAVConfig {
+        WebDAVConfig {
+            server_url: "https://cloud.example.com".to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        }
+    }
+
+    #[test]
+    fn test_default_pool_covers_concurrent_requests() {
+        let config = ConcurrencyConfig::default();
+        assert!(config.validate().is_ok());
+        assert!(config.pool_max_idle_per_host >= config.max_concurrent_requests());
+        assert_eq!(config.http_version, HttpVersionPreference::Auto);
+    }
+
+    #[test]
+    fn test_oversized_pool_is_rejected() {
+        let config = ConcurrencyConfig {
+            max_concurrent_scans: 2,
+            max_concurrent_downloads: 2,
+            pool_max_idle_per_host: 64,
+            ..ConcurrencyConfig::default()
+        };
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("pool_max_idle_per_host"));
+    }
+
+    #[test]
+    fn test_small_or_disabled_pool_is_allowed() {
+        // Smaller pools only cost extra handshakes, so they're valid
+        let small = ConcurrencyConfig {
+            pool_max_idle_per_host: 2,
+            ..ConcurrencyConfig::default()
+        };
+        assert!(small.validate().is_ok());
+
+        let disabled = ConcurrencyConfig {
+            pool_max_idle_per_host: 0,
+            ..ConcurrencyConfig::default()
+        };
+        assert!(disabled.validate().is_ok());
+    }
+
+    #[test]
+    fn test_zero_concurrency_is_rejected() {
+        let no_scans = ConcurrencyConfig {
+            max_concurrent_scans: 0,
+            ..ConcurrencyConfig::default()
+        };
+        assert!(no_scans.validate().is_err());
+
+        let no_downloads = ConcurrencyConfig {
+            max_concurrent_downloads: 0,
+            ..ConcurrencyConfig::default()
+        };
+        assert!(no_downloads.validate().is_err());
+    }
+
+    #[test]
+    fn test_service_builds_client_for_each_http_version() {
+        for http_version in [
+            HttpVersionPreference::Auto,
+            HttpVersionPreference::Http1Only,
+            HttpVersionPreference::Http2PriorKnowledge,
+        ] {
+            let concurrency = ConcurrencyConfig {
+                http_version,
+                tcp_keepalive_seconds: None,
+                ..ConcurrencyConfig::default()
+            };
+            let service = WebDAVService::new_with_configs(test_webdav_config(), RetryConfig::default(), concurrency);
+            assert!(service.is_ok(), "client should build for {:?}", http_version);
+        }
+    }
+
+    #[test]
+    fn test_service_rejects_invalid_concurrency_config() {
+        let concurrency = ConcurrencyConfig {
+            max_concurrent_scans: 1,
+            max_concurrent_downloads: 1,
+            pool_max_idle_per_host: 100,
+            ..ConcurrencyConfig::default()
+        };
+        assert!(WebDAVService::new_with_configs(test_webdav_config(), RetryConfig::default(), concurrency).is_err());
+    }
+}
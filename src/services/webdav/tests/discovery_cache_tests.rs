@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod discovery_cache_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::models::FileIngestionInfo;
+    use crate::services::webdav::{DiscoveryCache, WebDAVConfig, WebDAVDiscoveryResult, WebDAVService};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const BASE: &str = "/remote.php/dav/files/testuser";
+    const SCOPE: &str = "https://cloud.example.com/remote.php/dav/files/testuser|testuser";
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[allow(deprecated)]
+    fn file(relative_path: &str, is_directory: bool) -> FileIngestionInfo {
+        FileIngestionInfo {
+            relative_path: relative_path.to_string(),
+            full_path: format!("{BASE}{relative_path}"),
+            path: relative_path.to_string(),
+            name: relative_path.rsplit('/').next().unwrap_or_default().to_string(),
+            size: if is_directory { 0 } else { 1024 },
+            mime_type: if is_directory { String::new() } else { "application/pdf".to_string() },
+            last_modified: None,
+            etag: format!("{relative_path}-etag"),
+            is_directory,
+            created_at: None,
+            permissions: None,
+            owner: None,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    fn listing_of(files: &[&str], directories: &[&str]) -> WebDAVDiscoveryResult {
+        WebDAVDiscoveryResult {
+            files: files.iter().map(|path| file(path, false)).collect(),
+            directories: directories.iter().map(|path| file(path, true)).collect(),
+            depth_limited_directories: Vec::new(),
+            etag_aliases: Vec::new(),
+        }
+    }
+
+    fn file_paths(result: &WebDAVDiscoveryResult) -> Vec<&str> {
+        result.files.iter().map(|f| f.relative_path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_listing_is_reused_while_etag_is_unchanged() {
+        let cache = DiscoveryCache::new(10, DAY);
+        cache.insert(SCOPE, "/Root/Sub/", "\"sub-v1\"", &listing_of(&["/Root/Sub/a.pdf"], &["/Root/Sub/Deeper"]));
+
+        let cached = cache.get(SCOPE, "/Root/Sub", "\"sub-v1\"").expect("listing should be cached");
+        assert_eq!(file_paths(&cached), vec!["/Root/Sub/a.pdf"]);
+        assert_eq!(cached.directories.len(), 1);
+
+        // Listings are per server and user
+        assert!(cache.get("https://other.example.com|testuser", "/Root/Sub", "\"sub-v1\"").is_none());
+    }
+
+    #[test]
+    fn test_changed_etag_invalidates_listing() {
+        let cache = DiscoveryCache::new(10, DAY);
+        cache.insert(SCOPE, "/Root/Sub", "\"sub-v1\"", &listing_of(&["/Root/Sub/a.pdf"], &[]));
+
+        assert!(cache.get(SCOPE, "/Root/Sub", "\"sub-v2\"").is_none());
+        assert!(cache.is_empty());
+        // The old ETag doesn't bring the dropped listing back
+        assert!(cache.get(SCOPE, "/Root/Sub", "\"sub-v1\"").is_none());
+    }
+
+    #[test]
+    fn test_listing_expires_after_ttl() {
+        let cache = DiscoveryCache::new(10, Duration::from_millis(20));
+        cache.insert(SCOPE, "/Root", "\"root-v1\"", &listing_of(&["/Root/a.pdf"], &[]));
+        assert!(cache.get(SCOPE, "/Root", "\"root-v1\"").is_some());
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(cache.get(SCOPE, "/Root", "\"root-v1\"").is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_least_recently_used_listing_is_evicted() {
+        let cache = DiscoveryCache::new(2, DAY);
+        cache.insert(SCOPE, "/A", "\"a\"", &listing_of(&["/A/a.pdf"], &[]));
+        cache.insert(SCOPE, "/B", "\"b\"", &listing_of(&["/B/b.pdf"], &[]));
+
+        // Using /A makes /B the least recently used
+        assert!(cache.get(SCOPE, "/A", "\"a\"").is_some());
+        cache.insert(SCOPE, "/C", "\"c\"", &listing_of(&["/C/c.pdf"], &[]));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(SCOPE, "/A", "\"a\"").is_some());
+        assert!(cache.get(SCOPE, "/B", "\"b\"").is_none());
+        assert!(cache.get(SCOPE, "/C", "\"c\"").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_saved_cache_loads_into_new_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_file = dir.path().join("discovery-cache.json");
+
+        let cache = DiscoveryCache::new(10, DAY).with_persist_path(&cache_file);
+        cache.insert(SCOPE, "/Root", "\"root-v1\"", &listing_of(&["/Root/a.pdf", "/Root/b.pdf"], &["/Root/Sub"]));
+        cache.persist().await.expect("cache should be saved");
+
+        let restored = DiscoveryCache::new(10, DAY).with_persist_path(&cache_file);
+        assert_eq!(restored.load_persisted().unwrap(), 1);
+        let cached = restored.get(SCOPE, "/Root", "\"root-v1\"").expect("listing should be restored");
+        assert_eq!(file_paths(&cached), vec!["/Root/a.pdf", "/Root/b.pdf"]);
+        assert_eq!(cached.directories[0].relative_path, "/Root/Sub");
+        assert!(cached.directories[0].is_directory);
+
+        // A missing file is an empty cache
+        assert_eq!(DiscoveryCache::new(10, DAY).load(&dir.path().join("missing.json")).unwrap(), 0);
+    }
+
+    fn create_test_service(mock_server_url: &str, cache: Arc<DiscoveryCache>) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: mock_server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Root".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new(config)
+            .expect("Failed to create test service")
+            .with_discovery_cache(Some(cache))
+    }
+
+    /// PROPFIND response listing `dir` with one file and, optionally, a
+    /// subdirectory with the given ETag
+    fn listing(dir: &str, child_dir: Option<(&str, &str)>) -> String {
+        let child = child_dir.map(|(name, etag)| format!(r#"
+    <d:response>
+        <d:href>{BASE}{dir}/{name}/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:displayname>{name}</d:displayname>
+                <d:getetag>"{etag}"</d:getetag>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>"#)).unwrap_or_default();
+
+        format!(r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+    <d:response>
+        <d:href>{BASE}{dir}/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:getetag>"dir-etag"</d:getetag>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>{child}
+    <d:response>
+        <d:href>{BASE}{dir}/doc.pdf</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:displayname>doc.pdf</d:displayname>
+                <d:getetag>"{dir}-file-etag"</d:getetag>
+                <d:getcontentlength>1024</d:getcontentlength>
+                <d:resourcetype/>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>
+</d:multistatus>"#)
+    }
+
+    async fn mount(server: &MockServer, dir: &str, child_dir: Option<(&str, &str)>, expected_requests: u64) {
+        Mock::given(method("PROPFIND"))
+            .and(path(format!("{BASE}{dir}")))
+            .respond_with(
+                ResponseTemplate::new(207)
+                    .set_body_string(listing(dir, child_dir))
+                    .insert_header("content-type", "application/xml")
+            )
+            .expect(expected_requests)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_directory_is_not_listed_again() {
+        let mock_server = MockServer::start().await;
+        // The watch folder is listed on every pass; Sub and Deeper only once
+        mount(&mock_server, "/Root", Some(("Sub", "sub-v1")), 2).await;
+        mount(&mock_server, "/Root/Sub", Some(("Deeper", "deeper-v1")), 1).await;
+        mount(&mock_server, "/Root/Sub/Deeper", None, 1).await;
+
+        let cache = Arc::new(DiscoveryCache::new(100, DAY));
+        let service = create_test_service(&mock_server.uri(), cache.clone());
+
+        let first = service.discover_files_and_directories("/Root", true).await
+            .expect("First discovery should succeed");
+        let second = service.discover_files_and_directories("/Root", true).await
+            .expect("Second discovery should succeed");
+
+        let mut first_paths = file_paths(&first);
+        let mut second_paths = file_paths(&second);
+        first_paths.sort();
+        second_paths.sort();
+        assert_eq!(first_paths, vec!["/Root/Sub/Deeper/doc.pdf", "/Root/Sub/doc.pdf", "/Root/doc.pdf"]);
+        assert_eq!(second_paths, first_paths);
+        assert_eq!(second.directories.len(), 2);
+        assert_eq!(cache.len(), 2);
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_directory_with_new_etag_is_listed_again() {
+        let mock_server = MockServer::start().await;
+        mount(&mock_server, "/Root", Some(("Sub", "sub-v2")), 1).await;
+        mount(&mock_server, "/Root/Sub", None, 1).await;
+
+        // Sub was cached under the ETag it had before it changed
+        let cache = Arc::new(DiscoveryCache::new(100, DAY));
+        let scope = format!("{}{}|testuser", mock_server.uri(), BASE);
+        cache.insert(&scope, "/Root/Sub", "\"sub-v1\"", &listing_of(&["/Root/Sub/stale.pdf"], &[]));
+        let service = create_test_service(&mock_server.uri(), cache);
+
+        let result = service.discover_files_and_directories("/Root", true).await
+            .expect("Discovery should succeed");
+
+        let mut paths = file_paths(&result);
+        paths.sort();
+        assert_eq!(paths, vec!["/Root/Sub/doc.pdf", "/Root/doc.pdf"]);
+
+        mock_server.verify().await;
+    }
+}
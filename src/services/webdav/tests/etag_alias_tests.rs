@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod etag_alias_tests {
+    use crate::services::webdav::{WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const BASE: &str = "/remote.php/dav/files/testuser";
+
+    fn create_test_service(mock_server_url: &str, detect_etag_aliases: bool) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: mock_server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Root".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases,
+            user_agent: None,
+        };
+        WebDAVService::new(config).expect("Failed to create test service")
+    }
+
+    /// PROPFIND response listing `dir` with the given `(name, etag, is_directory)` children
+    fn listing(dir: &str, children: &[(&str, &str, bool)]) -> String {
+        let entries: String = children.iter().map(|(name, etag, is_directory)| {
+            let (slash, resource_type) = if *is_directory { ("/", "<d:collection/>") } else { ("", "") };
+            format!(r#"
+    <d:response>
+        <d:href>{BASE}{dir}/{name}{slash}</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:displayname>{name}</d:displayname>
+                <d:getetag>"{etag}"</d:getetag>
+                <d:getcontentlength>1024</d:getcontentlength>
+                <d:resourcetype>{resource_type}</d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>"#)
+        }).collect();
+
+        format!(r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+    <d:response>
+        <d:href>{BASE}{dir}/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:getetag>"{dir}-etag"</d:getetag>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>{entries}
+</d:multistatus>"#)
+    }
+
+    async fn mount(server: &MockServer, dir: &str, children: &[(&str, &str, bool)], expected_requests: u64) {
+        Mock::given(method("PROPFIND"))
+            .and(path(format!("{BASE}{dir}")))
+            .respond_with(
+                ResponseTemplate::new(207)
+                    .set_body_string(listing(dir, children))
+                    .insert_header("content-type", "application/xml")
+            )
+            .expect(expected_requests)
+            .mount(server)
+            .await;
+    }
+
+    /// /Root holds a hardlink to /Root/Sub/invoice.pdf, a second path to /Root/Sub
+    /// (Mirror) and /Root/Sub links back to itself (Loop)
+    async fn mount_hardlinked_tree(server: &MockServer, aliases_are_listed: bool) {
+        let alias_requests = if aliases_are_listed { 1 } else { 0 };
+        mount(server, "/Root", &[
+            ("invoice.pdf", "inode-42", false),
+            ("Sub", "inode-7", true),
+            ("Mirror", "inode-7", true),
+        ], 1).await;
+        mount(server, "/Root/Sub", &[
+            ("invoice.pdf", "inode-42", false),
+            ("report.pdf", "inode-43", false),
+            ("Loop", "inode-7", true),
+        ], 1).await;
+        mount(server, "/Root/Mirror", &[], alias_requests).await;
+        mount(server, "/Root/Sub/Loop", &[], alias_requests).await;
+    }
+
+    #[tokio::test]
+    async fn test_file_at_two_paths_with_one_etag_is_discovered_once() {
+        let mock_server = MockServer::start().await;
+        mount_hardlinked_tree(&mock_server, false).await;
+        let service = create_test_service(&mock_server.uri(), true);
+
+        let result = service.discover_files_and_directories("/Root", true).await
+            .expect("Recursive discovery should succeed");
+
+        let mut file_paths: Vec<&str> = result.files.iter().map(|f| f.relative_path.as_str()).collect();
+        file_paths.sort();
+        assert_eq!(file_paths, vec!["/Root/Sub/invoice.pdf", "/Root/Sub/report.pdf"]);
+
+        let mut aliases: Vec<(&str, &str, bool)> = result.etag_aliases.iter()
+            .map(|a| (a.path.as_str(), a.canonical_path.as_str(), a.is_directory))
+            .collect();
+        aliases.sort();
+        assert_eq!(aliases, vec![
+            ("/Root/Mirror", "/Root/Sub", true),
+            ("/Root/Sub/Loop", "/Root/Sub", true),
+            ("/Root/invoice.pdf", "/Root/Sub/invoice.pdf", false),
+        ]);
+
+        // Neither the second path to Sub nor the loop back to it is listed
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_shared_etags_are_distinct_files_when_detection_is_off() {
+        let mock_server = MockServer::start().await;
+        mount_hardlinked_tree(&mock_server, true).await;
+        let service = create_test_service(&mock_server.uri(), false);
+
+        let result = service.discover_files_and_directories("/Root", true).await
+            .expect("Recursive discovery should succeed");
+
+        let mut file_paths: Vec<&str> = result.files.iter().map(|f| f.relative_path.as_str()).collect();
+        file_paths.sort();
+        assert_eq!(file_paths, vec!["/Root/Sub/invoice.pdf", "/Root/Sub/report.pdf", "/Root/invoice.pdf"]);
+        assert!(result.etag_aliases.is_empty());
+
+        mock_server.verify().await;
+    }
+}
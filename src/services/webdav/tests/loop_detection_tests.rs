@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod loop_detection_tests {
+    use crate::services::webdav::{DirectoryLoopError, DirectoryVisits, WebDAVConfig, WebDAVService};
+    use wiremock::{matchers::method, Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    const BASE: &str = "/remote.php/dav/files/testuser";
+
+    fn create_test_service(server_url: &str, max_directory_visits: u32) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new(config)
+            .expect("Failed to create test service")
+            .with_max_directory_visits(max_directory_visits)
+    }
+
+    /// Lists every directory with a `loop` subdirectory that is the directory
+    /// itself, like a symlink pointing at its parent
+    struct SelfReferentialServer;
+
+    impl Respond for SelfReferentialServer {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let dir = request.url.path().trim_end_matches('/');
+            let body = format!(r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+    <d:response>
+        <d:href>{dir}/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:getetag>"loop-etag"</d:getetag>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>
+    <d:response>
+        <d:href>{dir}/loop/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:displayname>loop</d:displayname>
+                <d:getetag>"loop-etag"</d:getetag>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>
+</d:multistatus>"#);
+            ResponseTemplate::new(207)
+                .set_body_string(body)
+                .insert_header("content-type", "application/xml")
+        }
+    }
+
+    #[test]
+    fn test_visits_count_ancestors_with_the_same_name_and_etag() {
+        let mut visits = DirectoryVisits::new(3);
+
+        assert_eq!(visits.record("/Documents/loop/", "\"e1\""), Ok(1));
+        assert_eq!(visits.record("/Documents/loop/loop/", "\"e1\""), Ok(2));
+        // Another directory of the same name, or the same ETag under another name, isn't a repeat
+        assert_eq!(visits.record("/Documents/loop/loop/loop", "\"e2\""), Ok(1));
+        assert_eq!(visits.record("/Documents/loop/loop/other", "\"e1\""), Ok(1));
+        assert_eq!(visits.record("/Documents/loop/loop/other/loop", "\"e1\""), Ok(3));
+
+        let error = visits.record("/Documents/loop/loop/other/loop/loop", "\"e1\"").unwrap_err();
+        assert_eq!(
+            error,
+            DirectoryLoopError {
+                path: "/Documents/loop/loop/other/loop/loop".to_string(),
+                first_path: "/Documents/loop".to_string(),
+                visits: 4,
+                max_visits: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_zero_max_visits_turns_detection_off() {
+        let mut visits = DirectoryVisits::new(0);
+        let mut path = "/Documents".to_string();
+        for _ in 0..20 {
+            path.push_str("/loop");
+            assert!(visits.record(&path, "\"e1\"").is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_self_referential_directory_stops_discovery() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PROPFIND"))
+            .respond_with(SelfReferentialServer)
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri(), 3);
+        let error = service
+            .discover_files_and_directories("/Documents", true)
+            .await
+            .expect_err("A self-referential directory should stop discovery");
+
+        let directory_loop = error
+            .downcast_ref::<DirectoryLoopError>()
+            .unwrap_or_else(|| panic!("expected a directory loop error, got {}", error));
+        assert_eq!(directory_loop.path, "/Documents/loop/loop/loop/loop");
+        assert_eq!(directory_loop.first_path, "/Documents/loop");
+        assert_eq!(directory_loop.visits, 4);
+        assert!(error.to_string().contains("Directory loop detected"), "{}", error);
+
+        // /Documents and the three allowed visits of loop were listed, the fourth wasn't
+        let listed = mock_server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|request| request.url.path().starts_with(&format!("{}/Documents", BASE)))
+            .count();
+        assert_eq!(listed, 4);
+    }
+}
@@ -1,2 +1,14 @@
+pub mod capability_tests;
+pub mod clock_skew_tests;
+pub mod concurrency_config_tests;
+pub mod discovery_cache_tests;
+pub mod etag_alias_tests;
 pub mod etag_comparison_tests;
-pub mod path_processing_tests;
\ No newline at end of file
+pub mod loop_detection_tests;
+pub mod path_processing_tests;
+pub mod propfind_debug_tests;
+pub mod propfind_depth_tests;
+pub mod retry_policy_tests;
+pub mod scan_depth_tests;
+pub mod special_characters_tests;
+pub mod user_agent_tests;
\ No newline at end of file
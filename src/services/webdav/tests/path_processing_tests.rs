@@ -18,6 +18,9 @@ mod path_processing_tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         WebDAVService::new(config).expect("Failed to create test service")
     }
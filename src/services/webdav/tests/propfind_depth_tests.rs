@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod propfind_depth_tests {
+    use crate::models::{PropfindDepth, PropfindDepths, WebDAVSourceConfig};
+    use crate::services::webdav::{WebDAVConfig, WebDAVService};
+    use serde_json::json;
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const MULTISTATUS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/Documents/report.pdf</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:displayname>report.pdf</d:displayname>
+        <d:getcontentlength>1024</d:getcontentlength>
+        <d:getlastmodified>Mon, 01 Jan 2024 12:00:00 GMT</d:getlastmodified>
+        <d:getetag>"abc123"</d:getetag>
+        <d:resourcetype/>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    const ALL_DEPTHS: [PropfindDepth; 3] = [PropfindDepth::Zero, PropfindDepth::One, PropfindDepth::Infinity];
+
+    fn create_test_service(server_url: &str, depths: PropfindDepths) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new(config)
+            .expect("Failed to create test service")
+            .with_propfind_depths(depths)
+    }
+
+    /// A server expecting exactly one PROPFIND of `request_path` with `depth`
+    async fn expect_propfind(request_path: &str, depth: &str) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("PROPFIND"))
+            .and(path(request_path))
+            .and(header("Depth", depth))
+            .respond_with(
+                ResponseTemplate::new(207)
+                    .insert_header("Content-Type", "application/xml; charset=utf-8")
+                    .set_body_string(MULTISTATUS),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[test]
+    fn test_header_values() {
+        assert_eq!(PropfindDepth::Zero.as_header_value(), "0");
+        assert_eq!(PropfindDepth::One.as_header_value(), "1");
+        assert_eq!(PropfindDepth::Infinity.as_header_value(), "infinity");
+        assert_eq!(PropfindDepth::Infinity.to_string(), "infinity");
+    }
+
+    #[test]
+    fn test_parsing_validates_the_depth() {
+        assert_eq!(PropfindDepth::try_from("0".to_string()), Ok(PropfindDepth::Zero));
+        assert_eq!(PropfindDepth::try_from(" 1 ".to_string()), Ok(PropfindDepth::One));
+        assert_eq!(PropfindDepth::try_from("Infinity".to_string()), Ok(PropfindDepth::Infinity));
+        for invalid in ["2", "-1", "", "infinite", "1\r\nX-Injected: yes"] {
+            assert!(PropfindDepth::try_from(invalid.to_string()).is_err(), "accepted {:?}", invalid);
+        }
+
+        let error = serde_json::from_value::<PropfindDepths>(json!({ "file_metadata": "2" })).unwrap_err();
+        assert!(error.to_string().contains("Invalid PROPFIND depth '2'"), "unexpected error: {}", error);
+    }
+
+    #[test]
+    fn test_defaults_keep_the_current_depths() {
+        let defaults = PropfindDepths::default();
+        assert_eq!(defaults.connection_test, PropfindDepth::One);
+        assert_eq!(defaults.directory_listing, PropfindDepth::One);
+        assert_eq!(defaults.file_metadata, PropfindDepth::Zero);
+
+        let depths: PropfindDepths = serde_json::from_value(json!({ "directory_listing": "infinity" })).unwrap();
+        assert_eq!(depths, PropfindDepths { directory_listing: PropfindDepth::Infinity, ..defaults });
+        assert_eq!(serde_json::to_value(depths).unwrap()["directory_listing"], "infinity");
+
+        let config: WebDAVSourceConfig = serde_json::from_value(json!({
+            "server_url": "https://cloud.example.com",
+            "username": "alice",
+            "password": "secret",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": true,
+            "sync_interval_minutes": 60,
+            "server_type": "nextcloud"
+        }))
+        .unwrap();
+        assert_eq!(config.propfind_depths, defaults);
+    }
+
+    #[tokio::test]
+    async fn test_directory_listing_sends_the_configured_depth() {
+        for depth in ALL_DEPTHS {
+            let server = expect_propfind("/Documents", depth.as_header_value()).await;
+            let depths = PropfindDepths { directory_listing: depth, ..PropfindDepths::default() };
+            let service = create_test_service(&server.uri(), depths);
+
+            let result = service.debug_propfind("/Documents").await.unwrap();
+            assert_eq!(result.status, 207, "Depth {}", depth);
+            server.verify().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_test_sends_the_configured_depth() {
+        for depth in ALL_DEPTHS {
+            let server = expect_propfind("/Documents", depth.as_header_value()).await;
+            let depths = PropfindDepths { connection_test: depth, ..PropfindDepths::default() };
+            let service = create_test_service(&server.uri(), depths);
+
+            service.test_propfind("/Documents").await.unwrap();
+            server.verify().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_metadata_sends_the_configured_depth() {
+        for depth in ALL_DEPTHS {
+            let server = expect_propfind("/Documents/report.pdf", depth.as_header_value()).await;
+            let depths = PropfindDepths { file_metadata: depth, ..PropfindDepths::default() };
+            let service = create_test_service(&server.uri(), depths);
+
+            // Only the request matters here, not how the listing maps to the path
+            let _ = service.get_file_metadata("/Documents/report.pdf").await;
+            server.verify().await;
+        }
+    }
+}
@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod retry_policy_tests {
+    use crate::services::webdav::{RetryConfig, StatusRetryPolicy, WebDAVConfig, WebDAVService};
+    use reqwest::Method;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    /// Retry settings with millisecond delays so the tests don't sleep for real
+    fn fast_retry_config() -> RetryConfig {
+        let mut status_policies = RetryConfig::default_status_policies();
+        for policy in status_policies.values_mut() {
+            policy.initial_delay_ms = Some(1);
+            policy.max_delay_ms = Some(5);
+        }
+
+        RetryConfig {
+            max_retries: 2,
+            initial_delay_ms: 1,
+            max_delay_ms: 5,
+            backoff_multiplier: 2.0,
+            timeout_seconds: 30,
+            rate_limit_backoff_ms: 1,
+            status_policies,
+        }
+    }
+
+    fn create_test_service(mock_server_url: &str, retry_config: RetryConfig) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: mock_server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new_with_retry(config, retry_config).expect("Failed to create test service")
+    }
+
+    /// Responds with `status` for the first `times` requests, then 200
+    async fn mount_status_sequence(server: &MockServer, status: u16, times: u64, expected_successes: u64) {
+        Mock::given(method("GET"))
+            .and(path("/Documents/report.pdf"))
+            .respond_with(ResponseTemplate::new(status))
+            .up_to_n_times(times)
+            .expect(times)
+            .mount(server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/Documents/report.pdf"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("content"))
+            .expect(expected_successes)
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_default_policies() {
+        let config = RetryConfig::default();
+
+        let locked = config.policy_for_status(423);
+        assert!(locked.retryable);
+        assert_eq!(locked.max_retries, 4);
+        assert!(locked.initial_delay_ms > config.initial_delay_ms);
+
+        for status in [502, 503, 504] {
+            let policy = config.policy_for_status(status);
+            assert!(policy.retryable);
+            assert!(policy.max_retries > config.max_retries);
+        }
+
+        // Statuses without an override keep the built-in behavior
+        let server_error = config.policy_for_status(500);
+        assert!(server_error.retryable);
+        assert_eq!(server_error.max_retries, config.max_retries);
+
+        let rate_limited = config.policy_for_status(429);
+        assert!(rate_limited.retryable);
+        assert_eq!(rate_limited.initial_delay_ms, config.rate_limit_backoff_ms);
+
+        assert!(!config.policy_for_status(404).retryable);
+        assert!(!config.policy_for_status(405).retryable);
+    }
+
+    #[test]
+    fn test_override_falls_back_to_global_values() {
+        let mut config = RetryConfig::default();
+        config.status_policies.insert(409, StatusRetryPolicy::retryable().with_initial_delay_ms(250));
+        config.status_policies.insert(503, StatusRetryPolicy::not_retryable());
+
+        let conflict = config.policy_for_status(409);
+        assert!(conflict.retryable);
+        assert_eq!(conflict.max_retries, config.max_retries);
+        assert_eq!(conflict.initial_delay_ms, 250);
+        assert_eq!(conflict.max_delay_ms, config.max_delay_ms);
+
+        assert!(!config.policy_for_status(503).retryable);
+    }
+
+    #[tokio::test]
+    async fn test_locked_response_is_retried() {
+        let server = MockServer::start().await;
+        mount_status_sequence(&server, 423, 3, 1).await;
+
+        let service = create_test_service(&server.uri(), fast_retry_config());
+        let response = service
+            .authenticated_request(Method::GET, &format!("{}/Documents/report.pdf", server.uri()), None, None)
+            .await
+            .expect("423 should be retried until the lock is released");
+
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_locked_response_gives_up_after_policy_max_retries() {
+        let server = MockServer::start().await;
+        // Initial attempt plus the 4 retries from the default 423 policy
+        mount_status_sequence(&server, 423, 5, 0).await;
+
+        let service = create_test_service(&server.uri(), fast_retry_config());
+        let error = service
+            .authenticated_request(Method::GET, &format!("{}/Documents/report.pdf", server.uri()), None, None)
+            .await
+            .expect_err("423 should fail once retries are exhausted");
+
+        assert!(error.to_string().contains("423"));
+    }
+
+    #[tokio::test]
+    async fn test_service_unavailable_gets_more_retries_than_500() {
+        // Four 503s exceed the global max_retries (2) but fit the 503 policy (5)
+        let server = MockServer::start().await;
+        mount_status_sequence(&server, 503, 4, 1).await;
+
+        let service = create_test_service(&server.uri(), fast_retry_config());
+        let response = service
+            .authenticated_request(Method::GET, &format!("{}/Documents/report.pdf", server.uri()), None, None)
+            .await
+            .expect("503 should be retried under its own policy");
+        assert_eq!(response.status().as_u16(), 200);
+
+        // The same sequence of 500s exhausts the global retry budget
+        let server = MockServer::start().await;
+        mount_status_sequence(&server, 500, 3, 0).await;
+
+        let service = create_test_service(&server.uri(), fast_retry_config());
+        let error = service
+            .authenticated_request(Method::GET, &format!("{}/Documents/report.pdf", server.uri()), None, None)
+            .await
+            .expect_err("500 should fail after the global max_retries");
+        assert!(error.to_string().contains("500"));
+    }
+
+    #[tokio::test]
+    async fn test_unlisted_client_error_is_not_retried() {
+        let server = MockServer::start().await;
+        mount_status_sequence(&server, 404, 1, 0).await;
+
+        let service = create_test_service(&server.uri(), fast_retry_config());
+        let result = service
+            .authenticated_request(Method::GET, &format!("{}/Documents/report.pdf", server.uri()), None, None)
+            .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod scan_depth_tests {
+    use crate::services::webdav::{WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    /// Creates a test WebDAV service with an optional depth limit
+    fn create_test_service(mock_server_url: &str, max_scan_depth: Option<u32>) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: mock_server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Root".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new(config).expect("Failed to create test service")
+    }
+
+    /// PROPFIND response for `dir` containing one file and, optionally, one subdirectory
+    fn mock_level_response(dir: &str, child_dir: Option<&str>) -> String {
+        let base = "/remote.php/dav/files/testuser";
+        let child = child_dir.map(|child| format!(r#"
+    <d:response>
+        <d:href>{base}{dir}/{child}/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:displayname>{child}</d:displayname>
+                <d:getetag>"{child}-etag"</d:getetag>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>"#)).unwrap_or_default();
+
+        format!(r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+    <d:response>
+        <d:href>{base}{dir}/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:getetag>"dir-etag"</d:getetag>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>{child}
+    <d:response>
+        <d:href>{base}{dir}/doc.pdf</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:displayname>doc.pdf</d:displayname>
+                <d:getetag>"file-etag"</d:getetag>
+                <d:getcontentlength>1024</d:getcontentlength>
+                <d:resourcetype/>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>
+</d:multistatus>"#)
+    }
+
+    #[test]
+    fn test_scan_depth_is_relative_to_watch_root() {
+        let service = create_test_service("http://test.example.com", Some(2));
+
+        assert_eq!(service.scan_depth_below_watch_root("/Root", "/Root"), 0);
+        assert_eq!(service.scan_depth_below_watch_root("/Root", "/Root/L1/"), 1);
+        assert_eq!(service.scan_depth_below_watch_root("/Root", "/Root/L1/L2/L3"), 3);
+        // Targeted scans of a subdirectory still count from the watch root
+        assert_eq!(service.scan_depth_below_watch_root("/Root/L1", "/Root/L1/L2"), 2);
+        // Paths outside any watch folder count from the scan root
+        assert_eq!(service.scan_depth_below_watch_root("/Other", "/Other/A/B"), 2);
+
+        assert!(!service.exceeds_max_scan_depth("/Root", "/Root/L1/L2"));
+        assert!(service.exceeds_max_scan_depth("/Root", "/Root/L1/L2/L3"));
+    }
+
+    #[test]
+    fn test_unlimited_scan_depth_by_default() {
+        let service = create_test_service("http://test.example.com", None);
+        assert!(!service.exceeds_max_scan_depth("/Root", "/Root/L1/L2/L3/L4/L5"));
+    }
+
+    /// Serves a five-level tree /Root/L1/L2/L3/L4/L5 and expects only /Root, L1
+    /// and L2 to be listed, as with a depth limit of 2
+    async fn mount_five_level_tree(mock_server: &MockServer) {
+        let levels = ["/Root", "/Root/L1", "/Root/L1/L2", "/Root/L1/L2/L3", "/Root/L1/L2/L3/L4", "/Root/L1/L2/L3/L4/L5"];
+        let children = [Some("L1"), Some("L2"), Some("L3"), Some("L4"), Some("L5"), None];
+
+        for (index, (dir, child)) in levels.iter().zip(children.iter()).enumerate() {
+            let expected_requests = if index <= 2 { 1 } else { 0 };
+            Mock::given(method("PROPFIND"))
+                .and(path(format!("/remote.php/dav/files/testuser{}", dir)))
+                .respond_with(
+                    ResponseTemplate::new(207)
+                        .set_body_string(mock_level_response(dir, *child))
+                        .insert_header("content-type", "application/xml")
+                )
+                .expect(expected_requests)
+                .mount(mock_server)
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recursive_discovery_respects_max_scan_depth() {
+        let mock_server = MockServer::start().await;
+        mount_five_level_tree(&mock_server).await;
+
+        let service = create_test_service(&mock_server.uri(), Some(2));
+
+        let result = service.discover_files_and_directories("/Root", true).await
+            .expect("Recursive discovery should succeed");
+
+        let dir_paths: Vec<&str> = result.directories.iter()
+            .map(|d| d.relative_path.trim_end_matches('/'))
+            .collect();
+        assert_eq!(dir_paths, vec!["/Root/L1", "/Root/L1/L2"]);
+        assert_eq!(result.files.len(), 3, "Should find one file per scanned level");
+
+        assert_eq!(result.depth_limited_directories.len(), 1);
+        assert_eq!(result.depth_limited_directories[0].trim_end_matches('/'), "/Root/L1/L2/L3");
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_recursive_file_discovery_respects_max_scan_depth() {
+        let mock_server = MockServer::start().await;
+        mount_five_level_tree(&mock_server).await;
+
+        let service = create_test_service(&mock_server.uri(), Some(2));
+
+        let files = service.discover_files("/Root", true).await
+            .expect("Recursive discovery should succeed");
+        assert_eq!(files.len(), 3, "Should find one file per scanned level");
+
+        mock_server.verify().await;
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod special_characters_tests {
+    use crate::services::webdav::{decode_path, encode_path_segments, WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const BASE: &str = "/remote.php/dav/files/testuser";
+
+    /// Names with characters that break a URL when concatenated as-is
+    const SPECIAL_NAMES: &[(&str, &str)] = &[
+        ("report #3.pdf", "report%20%233.pdf"),
+        ("what?.pdf", "what%3F.pdf"),
+        ("a+b.pdf", "a%2Bb.pdf"),
+        ("Übersicht café.pdf", "%C3%9Cbersicht%20caf%C3%A9.pdf"),
+        ("日本語.pdf", "%E6%97%A5%E6%9C%AC%E8%AA%9E.pdf"),
+        ("50% off.pdf", "50%25%20off.pdf"),
+    ];
+
+    fn create_test_service(server_url: &str) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Docs".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        WebDAVService::new(config).expect("Failed to create test service")
+    }
+
+    #[test]
+    fn test_each_segment_is_encoded_but_not_the_slashes() {
+        for (name, encoded) in SPECIAL_NAMES {
+            assert_eq!(
+                encode_path_segments(&format!("/My Docs/{}", name)),
+                format!("/My%20Docs/{}", encoded),
+                "encoding {}",
+                name
+            );
+        }
+        assert_eq!(encode_path_segments("/Docs/Sub/"), "/Docs/Sub/");
+        assert_eq!(encode_path_segments(""), "");
+    }
+
+    #[test]
+    fn test_encoded_paths_are_not_encoded_twice() {
+        for (_, encoded) in SPECIAL_NAMES {
+            let path = format!("/My%20Docs/{}", encoded);
+            assert_eq!(encode_path_segments(&path), path);
+        }
+    }
+
+    #[test]
+    fn test_paths_round_trip_through_url_and_href() {
+        let service = create_test_service("https://cloud.example.com");
+
+        for (name, encoded) in SPECIAL_NAMES {
+            let relative_path = format!("/My Docs/{}", name);
+            let url = service.get_url_for_path(&relative_path);
+            assert_eq!(url, format!("https://cloud.example.com{}/My%20Docs/{}", BASE, encoded));
+            assert_eq!(service.relative_path_to_url(&relative_path), url);
+
+            let parsed = reqwest::Url::parse(&url).expect("URL should be valid");
+            assert!(parsed.query().is_none() && parsed.fragment().is_none(), "{} split the URL", name);
+            assert_eq!(service.href_to_relative_path(parsed.path()), relative_path);
+            assert_eq!(decode_path(parsed.path()), format!("{}{}", BASE, relative_path));
+        }
+    }
+
+    #[test]
+    fn test_hrefs_are_decoded_to_relative_paths() {
+        let service = create_test_service("https://cloud.example.com");
+
+        assert_eq!(
+            service.href_to_relative_path(&format!("{}/Docs/report%20%233.pdf", BASE)),
+            "/Docs/report #3.pdf"
+        );
+        assert_eq!(service.href_to_relative_path(&format!("{}/a%2Bb/", BASE)), "/a+b/");
+        // A plus sign in a path is not a space
+        assert_eq!(service.href_to_relative_path(&format!("{}/a+b.pdf", BASE)), "/a+b.pdf");
+        assert_eq!(service.href_to_relative_path(&format!("{}/", BASE)), "/");
+    }
+
+    fn listing_with(names: &[&str]) -> String {
+        let entries: String = names
+            .iter()
+            .map(|name| {
+                format!(
+                    r#"
+    <d:response>
+        <d:href>{BASE}/Docs/{href}</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:getetag>"{href}-etag"</d:getetag>
+                <d:getcontentlength>4</d:getcontentlength>
+                <d:resourcetype/>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>"#,
+                    href = encode_path_segments(name)
+                )
+            })
+            .collect();
+
+        format!(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+    <d:response>
+        <d:href>{BASE}/Docs/</d:href>
+        <d:propstat>
+            <d:prop>
+                <d:resourcetype><d:collection/></d:resourcetype>
+            </d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status>
+        </d:propstat>
+    </d:response>{entries}
+</d:multistatus>"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_discovered_files_with_special_characters_download() {
+        let mock_server = MockServer::start().await;
+        let names: Vec<&str> = SPECIAL_NAMES.iter().map(|(name, _)| *name).collect();
+
+        Mock::given(method("PROPFIND"))
+            .and(path(format!("{}/Docs", BASE)))
+            .respond_with(
+                ResponseTemplate::new(207)
+                    .set_body_string(listing_with(&names))
+                    .insert_header("content-type", "application/xml"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        for (_, encoded) in SPECIAL_NAMES {
+            Mock::given(method("GET"))
+                .and(path(format!("{}/Docs/{}", BASE, encoded)))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF".to_vec()))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let service = create_test_service(&mock_server.uri());
+        let result = service
+            .discover_files_and_directories("/Docs", false)
+            .await
+            .expect("Discovery should succeed");
+
+        let mut discovered: Vec<&str> = result.files.iter().map(|f| f.relative_path.as_str()).collect();
+        discovered.sort();
+        let mut expected: Vec<String> = names.iter().map(|name| format!("/Docs/{}", name)).collect();
+        expected.sort();
+        assert_eq!(discovered, expected);
+
+        for file in &result.files {
+            let content = service
+                .download_file(&file.relative_path)
+                .await
+                .unwrap_or_else(|e| panic!("Downloading {} failed: {}", file.relative_path, e));
+            assert_eq!(content, b"%PDF");
+        }
+
+        mock_server.verify().await;
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod user_agent_tests {
+    use crate::models::WebDAVSourceConfig;
+    use crate::services::webdav::{build_user_agent, WebDAVConfig, WebDAVService};
+    use reqwest::Method;
+    use wiremock::{
+        matchers::{header, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn create_test_config(server_url: &str, user_agent: Option<&str>) -> WebDAVConfig {
+        WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: user_agent.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_user_agent_is_sent() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("User-Agent", "ScannerArchive/1.0 (source 7)"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = create_test_config(&server.uri(), Some("ScannerArchive/1.0 (source 7)"));
+        let service = WebDAVService::new(config).unwrap();
+        let url = format!("{}/Documents", server.uri());
+        let response = service
+            .authenticated_request(Method::GET, &url, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_default_user_agent_is_sent_without_override() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("User-Agent", build_user_agent().as_str()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let service = WebDAVService::new(create_test_config(&server.uri(), None)).unwrap();
+        let url = format!("{}/Documents", server.uri());
+        service
+            .authenticated_request(Method::GET, &url, None, None)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_malformed_user_agent_is_rejected() {
+        for user_agent in ["", "   ", "Readur\r\nX-Injected: 1"] {
+            let config = create_test_config("https://dav.example.com", Some(user_agent));
+            assert!(config.validate().is_err(), "accepted {:?}", user_agent);
+        }
+
+        let source_config: WebDAVSourceConfig = serde_json::from_value(serde_json::json!({
+            "server_url": "https://dav.example.com",
+            "username": "testuser",
+            "password": "testpass",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": true,
+            "sync_interval_minutes": 60,
+            "server_type": "generic",
+            "user_agent": "Readur\nX-Injected: 1"
+        }))
+        .unwrap();
+        assert!(source_config.validate_user_agent().is_err());
+    }
+}
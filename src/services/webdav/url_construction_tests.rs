@@ -12,6 +12,9 @@ fn create_nextcloud_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     WebDAVService::new(config).unwrap()
@@ -27,6 +30,9 @@ fn create_generic_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     WebDAVService::new(config).unwrap()
@@ -200,6 +206,9 @@ async fn test_different_usernames() {
             file_extensions: vec!["pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         let service = WebDAVService::new(config).unwrap();
@@ -224,6 +233,9 @@ async fn test_fix_prevents_original_bug() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     let service = WebDAVService::new(config).unwrap();
     
@@ -266,6 +278,9 @@ async fn test_nextcloud_url_normalization_with_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     // This should not panic and should normalize the URL properly
@@ -286,6 +301,9 @@ async fn test_nextcloud_url_normalization_without_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let webdav_url = config.webdav_url();
@@ -305,6 +323,9 @@ async fn test_owncloud_url_normalization_with_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("owncloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let webdav_url = config.webdav_url();
@@ -324,6 +345,9 @@ async fn test_owncloud_url_normalization_without_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("owncloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let webdav_url = config.webdav_url();
@@ -343,6 +367,9 @@ async fn test_generic_webdav_url_normalization_with_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let webdav_url = config.webdav_url();
@@ -361,6 +388,9 @@ async fn test_generic_webdav_url_normalization_without_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let webdav_url = config.webdav_url();
@@ -379,6 +409,9 @@ async fn test_connection_get_url_for_path_normalization() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -421,6 +454,9 @@ async fn test_url_normalization_edge_cases() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let webdav_url = config.webdav_url();
@@ -456,6 +492,9 @@ async fn test_all_server_types_url_consistency() {
                 file_extensions: vec!["pdf".to_string()],
                 timeout_seconds: 30,
                 server_type: server_type.clone(),
+                max_scan_depth: None,
+                detect_etag_aliases: false,
+                user_agent: None,
             };
             
             let webdav_url = config.webdav_url();
@@ -484,6 +523,9 @@ async fn test_service_download_file_url_construction() {
         file_extensions: vec!["png".to_string(), "pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -548,6 +590,9 @@ async fn test_file_fetch_url_construction_with_convert_to_relative_path() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -601,6 +646,9 @@ async fn test_file_fetch_real_world_error_scenario() {
         file_extensions: vec!["png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -676,6 +724,9 @@ async fn test_file_fetch_different_server_types() {
             file_extensions: vec!["pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some(server_type.to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         let service = WebDAVService::new(config).unwrap();
@@ -721,6 +772,9 @@ async fn test_webdav_service_methods_use_correct_url_construction() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let service = WebDAVService::new(config).unwrap();
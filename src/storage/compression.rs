@@ -0,0 +1,212 @@
+//! Optional at-rest compression for stored document blobs
+//!
+//! Compressed objects are marked by a suffix on their storage path/key
+//! (`.readur.gz` or `.readur.zst`), so each object records how it was written.
+//! Stored documents are named `{document_id}` or `{document_id}.{ext}`, and an
+//! extension never contains a dot, so no uncompressed document can end in a
+//! marker: a user's own `archive.gz` is stored as `{id}.gz` and read back
+//! byte for byte. Reads always honour the marker regardless of the current
+//! configuration, which keeps mixed-mode libraries readable after
+//! `STORAGE_COMPRESSION` changes.
+
+use anyhow::{anyhow, Result};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Upper bound for decompressed data, matching the storage size limits
+const MAX_DECOMPRESSED_SIZE: u64 = 1_000_000_000;
+
+/// Blobs smaller than this are stored as-is; the savings aren't worth it
+const MIN_COMPRESSIBLE_SIZE: usize = 1024;
+
+/// Compressed output must be at most this fraction of the original to be kept
+const MIN_SAVINGS_RATIO: f64 = 0.95;
+
+/// zstd level used for stored documents (zstd's own default)
+const ZSTD_LEVEL: i32 = 3;
+
+/// File extensions whose content is already compressed
+const PRECOMPRESSED_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "heic", "heif", "avif",
+    "zip", "gz", "tgz", "zst", "bz2", "xz", "7z", "rar",
+    "docx", "xlsx", "pptx", "odt", "ods", "odp", "epub",
+    "mp3", "mp4", "m4a", "mov", "avi", "mkv", "webm",
+];
+
+/// Compression applied to document blobs at rest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageCompression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl StorageCompression {
+    /// Path suffix marking an object stored with this compression
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            StorageCompression::None => "",
+            StorageCompression::Gzip => ".readur.gz",
+            StorageCompression::Zstd => ".readur.zst",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageCompression::None => "none",
+            StorageCompression::Gzip => "gzip",
+            StorageCompression::Zstd => "zstd",
+        }
+    }
+
+    /// Detect the compression an object was stored with from its path/key.
+    /// Only stored document names carry a marker, so files named by users,
+    /// such as legacy uploads kept under their original name, are never
+    /// treated as compressed.
+    pub fn from_path(path: &str) -> Self {
+        let Some(name) = Path::new(path).file_name().and_then(|name| name.to_str()) else {
+            return StorageCompression::None;
+        };
+        [StorageCompression::Zstd, StorageCompression::Gzip]
+            .into_iter()
+            .find(|compression| {
+                name.strip_suffix(compression.suffix())
+                    .map_or(false, is_document_stem)
+            })
+            .unwrap_or(StorageCompression::None)
+    }
+}
+
+impl FromStr for StorageCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "none" | "off" | "false" => Ok(StorageCompression::None),
+            "gzip" | "gz" => Ok(StorageCompression::Gzip),
+            "zstd" | "zst" => Ok(StorageCompression::Zstd),
+            other => Err(format!("Invalid storage compression '{}' (expected none, gzip or zstd)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for StorageCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether `stem` is a stored document name: `{document_id}` or `{document_id}.{ext}`
+fn is_document_stem(stem: &str) -> bool {
+    let (id, extension) = match stem.split_once('.') {
+        Some((id, extension)) => (id, Some(extension)),
+        None => (stem, None),
+    };
+    Uuid::parse_str(id).is_ok()
+        && extension.map_or(true, |extension| !extension.is_empty() && !extension.contains('.'))
+}
+
+/// Whether a path/key refers to a compressed object
+pub fn is_compressed_path(path: &str) -> bool {
+    StorageCompression::from_path(path) != StorageCompression::None
+}
+
+/// Strips the compression suffix from a path/key, if present
+pub fn strip_compression_suffix(path: &str) -> &str {
+    let suffix = StorageCompression::from_path(path).suffix();
+    &path[..path.len() - suffix.len()]
+}
+
+/// Whether content is worth compressing. Formats that are already compressed
+/// (JPEG, PNG, ZIP-based Office documents, archives) are detected by extension
+/// and by their magic bytes.
+pub fn is_compressible(filename: &str, data: &[u8]) -> bool {
+    if data.len() < MIN_COMPRESSIBLE_SIZE {
+        return false;
+    }
+
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+    if PRECOMPRESSED_EXTENSIONS.contains(&extension.as_str()) {
+        return false;
+    }
+
+    const PRECOMPRESSED_MAGIC: &[&[u8]] = &[
+        &[0xFF, 0xD8, 0xFF],             // JPEG
+        &[0x89, b'P', b'N', b'G'],       // PNG
+        b"GIF8",                         // GIF
+        &[b'P', b'K', 0x03, 0x04],       // ZIP / OOXML / ODF
+        &[0x1F, 0x8B],                   // gzip
+        &[0x28, 0xB5, 0x2F, 0xFD],       // zstd
+        b"7z\xBC\xAF\x27\x1C",           // 7-Zip
+        b"Rar!",                         // RAR
+    ];
+    !PRECOMPRESSED_MAGIC.iter().any(|magic| data.starts_with(magic))
+}
+
+/// Compresses data for storage. Returns None when compression is disabled, the
+/// content isn't compressible, or compressing it wouldn't save enough space.
+pub fn compress_for_storage(
+    compression: StorageCompression,
+    filename: &str,
+    data: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    if compression == StorageCompression::None || !is_compressible(filename, data) {
+        return Ok(None);
+    }
+
+    let compressed = compress(compression, data)?;
+    if (compressed.len() as f64) > data.len() as f64 * MIN_SAVINGS_RATIO {
+        return Ok(None);
+    }
+
+    Ok(Some(compressed))
+}
+
+/// Compresses data with the given algorithm
+pub fn compress(compression: StorageCompression, data: &[u8]) -> Result<Vec<u8>> {
+    match compression {
+        StorageCompression::None => Ok(data.to_vec()),
+        StorageCompression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?)
+        }
+        StorageCompression::Zstd => Ok(zstd::stream::encode_all(data, ZSTD_LEVEL)?),
+    }
+}
+
+/// Decompresses data with the given algorithm
+pub fn decompress(compression: StorageCompression, data: &[u8]) -> Result<Vec<u8>> {
+    let reader: Box<dyn Read + '_> = match compression {
+        StorageCompression::None => return Ok(data.to_vec()),
+        StorageCompression::Gzip => Box::new(flate2::read::GzDecoder::new(data)),
+        StorageCompression::Zstd => Box::new(zstd::stream::read::Decoder::new(data)?),
+    };
+
+    let mut decompressed = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| anyhow!("Failed to decompress {} data: {}", compression, e))?;
+
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(anyhow!("Decompressed file too large to read safely"));
+    }
+
+    Ok(decompressed)
+}
+
+/// Decompresses data read from `path` if the path marks it as compressed
+pub fn decompress_for_path(path: &str, data: Vec<u8>) -> Result<Vec<u8>> {
+    match StorageCompression::from_path(path) {
+        StorageCompression::None => Ok(data),
+        compression => decompress(compression, &data),
+    }
+}
@@ -5,21 +5,31 @@ use std::sync::Arc;
 
 use super::{StorageBackend, StorageConfig};
 use super::local::LocalStorageBackend;
+use super::compression::StorageCompression;
 
 #[cfg(feature = "s3")]
 use crate::services::s3_service::S3Service;
 
 /// Create a storage backend based on the provided configuration
 pub async fn create_storage_backend(config: StorageConfig) -> Result<Arc<dyn StorageBackend>> {
+    create_storage_backend_with_compression(config, StorageCompression::None).await
+}
+
+/// Create a storage backend that compresses newly stored documents. Existing
+/// objects are read according to their own compression regardless of this setting.
+pub async fn create_storage_backend_with_compression(
+    config: StorageConfig,
+    compression: StorageCompression,
+) -> Result<Arc<dyn StorageBackend>> {
     match config {
         StorageConfig::Local { upload_path } => {
-            let backend = LocalStorageBackend::new(upload_path);
+            let backend = LocalStorageBackend::new(upload_path).with_compression(compression);
             backend.initialize().await?;
             Ok(Arc::new(backend))
         }
         #[cfg(feature = "s3")]
         StorageConfig::S3 { s3_config, .. } => {
-            let backend = S3Service::new(s3_config).await?;
+            let backend = S3Service::new(s3_config).await?.with_compression(compression);
             backend.initialize().await?;
             Ok(Arc::new(backend))
         }
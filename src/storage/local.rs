@@ -10,7 +10,8 @@ use tokio::sync::RwLock;
 use tracing::{info, error, warn, debug};
 use uuid::Uuid;
 
-use super::StorageBackend;
+use super::{ensure_plain_filename, is_user_local_path, StorageBackend};
+use super::compression::{self, StorageCompression};
 use crate::utils::security::{validate_filename, validate_and_sanitize_path, validate_path_within_base};
 
 /// Local filesystem storage backend
@@ -18,6 +19,8 @@ pub struct LocalStorageBackend {
     upload_path: String,
     /// Cache for resolved file paths to reduce filesystem calls
     path_cache: Arc<RwLock<HashMap<String, Option<String>>>>,
+    /// Compression applied to newly stored documents
+    compression: StorageCompression,
 }
 
 impl LocalStorageBackend {
@@ -26,9 +29,16 @@ impl LocalStorageBackend {
         Self { 
             upload_path,
             path_cache: Arc::new(RwLock::new(HashMap::new())),
+            compression: StorageCompression::None,
         }
     }
 
+    /// Compress newly stored documents with the given algorithm when worthwhile
+    pub fn with_compression(mut self, compression: StorageCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
     /// Get the base upload path
     pub fn get_upload_path(&self) -> &str {
         &self.upload_path
@@ -39,6 +49,21 @@ impl LocalStorageBackend {
         Path::new(&self.upload_path).join("documents")
     }
 
+    /// Get path for a user's documents, `documents/<user id>`
+    pub fn get_user_documents_path(&self, user_id: Uuid) -> PathBuf {
+        self.get_documents_path().join(user_id.to_string())
+    }
+
+    /// Get path for a user's thumbnails, `thumbnails/<user id>`
+    pub fn get_user_thumbnails_path(&self, user_id: Uuid) -> PathBuf {
+        self.get_thumbnails_path().join(user_id.to_string())
+    }
+
+    /// Get path for a user's processed images, `processed_images/<user id>`
+    pub fn get_user_processed_images_path(&self, user_id: Uuid) -> PathBuf {
+        self.get_processed_images_path().join(user_id.to_string())
+    }
+
     /// Get path for thumbnails subdirectory
     pub fn get_thumbnails_path(&self) -> PathBuf {
         Path::new(&self.upload_path).join("thumbnails")
@@ -218,7 +243,7 @@ impl LocalStorageBackend {
 
 #[async_trait]
 impl StorageBackend for LocalStorageBackend {
-    async fn store_document(&self, _user_id: Uuid, document_id: Uuid, filename: &str, data: &[u8]) -> Result<String> {
+    async fn store_document(&self, user_id: Uuid, document_id: Uuid, filename: &str, data: &[u8]) -> Result<String> {
         // Validate and sanitize the filename
         let sanitized_filename = validate_filename(filename)?;
         
@@ -227,13 +252,33 @@ impl StorageBackend for LocalStorageBackend {
             .and_then(|ext| ext.to_str())
             .unwrap_or("");
         
+        // Validate data size (prevent extremely large files from causing issues)
+        if data.len() > 1_000_000_000 { // 1GB limit
+            return Err(anyhow::anyhow!("File too large for storage (max 1GB)"));
+        }
+        
+        let mode = self.compression;
+        let compressed = if mode == StorageCompression::None {
+            None
+        } else {
+            let filename_owned = sanitized_filename.clone();
+            let owned_data = data.to_vec();
+            tokio::task::spawn_blocking(move || {
+                compression::compress_for_storage(mode, &filename_owned, &owned_data)
+            }).await??
+        };
+        let (data, suffix) = match &compressed {
+            Some(compressed_data) => (compressed_data.as_slice(), mode.suffix()),
+            None => (data, ""),
+        };
+        
         let document_filename = if extension.is_empty() {
-            document_id.to_string()
+            format!("{}{}", document_id, suffix)
         } else {
-            format!("{}.{}", document_id, extension)
+            format!("{}.{}{}", document_id, extension, suffix)
         };
         
-        let documents_dir = self.get_documents_path();
+        let documents_dir = self.get_user_documents_path(user_id);
         let file_path = documents_dir.join(&document_filename);
         
         // Validate that the final path is within our base directory
@@ -245,23 +290,59 @@ impl StorageBackend for LocalStorageBackend {
         // Ensure the documents directory exists
         fs::create_dir_all(&documents_dir).await?;
         
-        // Validate data size (prevent extremely large files from causing issues)
-        if data.len() > 1_000_000_000 { // 1GB limit
-            return Err(anyhow::anyhow!("File too large for storage (max 1GB)"));
-        }
-        
         fs::write(&file_path, data).await?;
         
         // Invalidate any cached negative results for this path
         let path_str = file_path.to_string_lossy().to_string();
         self.invalidate_cache_entry(&path_str).await;
         
-        info!("Stored document locally: {}", file_path.display());
+        if suffix.is_empty() {
+            info!("Stored document locally: {}", file_path.display());
+        } else {
+            info!("Stored document locally with {} compression: {}", mode, file_path.display());
+        }
+        Ok(path_str)
+    }
+
+    async fn store_document_from_path(&self, user_id: Uuid, document_id: Uuid, filename: &str, source: &Path) -> Result<String> {
+        // Compression needs the whole file in memory anyway
+        if self.compression != StorageCompression::None {
+            let data = fs::read(source).await?;
+            return self.store_document(user_id, document_id, filename, &data).await;
+        }
+
+        let sanitized_filename = validate_filename(filename)?;
+        let extension = Path::new(&sanitized_filename)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("");
+        let document_filename = if extension.is_empty() {
+            document_id.to_string()
+        } else {
+            format!("{}.{}", document_id, extension)
+        };
+
+        let documents_dir = self.get_user_documents_path(user_id);
+        let file_path = documents_dir.join(&document_filename);
+        validate_path_within_base(
+            &file_path.to_string_lossy(),
+            &self.upload_path
+        )?;
+
+        fs::create_dir_all(&documents_dir).await?;
+        // Copied rather than renamed: the source may be on another filesystem and is
+        // cleaned up by its owner. No size limit applies since nothing is buffered.
+        fs::copy(source, &file_path).await?;
+
+        let path_str = file_path.to_string_lossy().to_string();
+        self.invalidate_cache_entry(&path_str).await;
+
+        info!("Stored document locally from {}: {}", source.display(), file_path.display());
         Ok(path_str)
     }
 
-    async fn store_thumbnail(&self, _user_id: Uuid, document_id: Uuid, data: &[u8]) -> Result<String> {
-        let thumbnails_dir = self.get_thumbnails_path();
+    async fn store_thumbnail(&self, user_id: Uuid, document_id: Uuid, data: &[u8]) -> Result<String> {
+        let thumbnails_dir = self.get_user_thumbnails_path(user_id);
         fs::create_dir_all(&thumbnails_dir).await?;
 
         let thumbnail_filename = format!("{}_thumb.jpg", document_id);
@@ -288,8 +369,8 @@ impl StorageBackend for LocalStorageBackend {
         Ok(path_str)
     }
 
-    async fn store_processed_image(&self, _user_id: Uuid, document_id: Uuid, data: &[u8]) -> Result<String> {
-        let processed_dir = self.get_processed_images_path();
+    async fn store_processed_image(&self, user_id: Uuid, document_id: Uuid, data: &[u8]) -> Result<String> {
+        let processed_dir = self.get_user_processed_images_path(user_id);
         fs::create_dir_all(&processed_dir).await?;
 
         let processed_filename = format!("{}_processed.png", document_id);
@@ -333,10 +414,23 @@ impl StorageBackend for LocalStorageBackend {
             return Err(anyhow::anyhow!("File too large to read safely"));
         }
         
-        Ok(data)
+        if !compression::is_compressed_path(&resolved_path) {
+            return Ok(data);
+        }
+        
+        // Objects carry their compression in the path, so this works regardless
+        // of the currently configured compression
+        tokio::task::spawn_blocking(move || compression::decompress_for_path(&resolved_path, data)).await?
     }
 
-    async fn delete_document_files(&self, _user_id: Uuid, document_id: Uuid, filename: &str) -> Result<()> {
+    fn is_user_path(&self, user_id: Uuid, path: &str) -> bool {
+        is_user_local_path(user_id, path, &self.upload_path)
+    }
+
+    async fn delete_document_files(&self, user_id: Uuid, document_id: Uuid, filename: &str) -> Result<()> {
+        // The filename is joined to the storage directories below
+        ensure_plain_filename(user_id, filename)?;
+
         let mut deleted_files = Vec::new();
         let mut serious_errors = Vec::new();
 
@@ -377,13 +471,20 @@ impl StorageBackend for LocalStorageBackend {
             .and_then(|ext| ext.to_str())
             .unwrap_or("");
         
-        // Strategy 1: Try document ID-based filename (new structured approach)
+        // Strategy 1: Try document ID-based filename in the user's directory
         let document_filename = if extension.is_empty() {
             document_id.to_string()
         } else {
             format!("{}.{}", document_id, extension)
         };
-        let main_file_structured = self.get_documents_path().join(&document_filename);
+        let user_documents_dir = self.get_user_documents_path(user_id);
+        let main_file_structured = user_documents_dir.join(&document_filename);
+        let main_file_gzip = user_documents_dir
+            .join(format!("{}{}", document_filename, StorageCompression::Gzip.suffix()));
+        let main_file_zstd = user_documents_dir
+            .join(format!("{}{}", document_filename, StorageCompression::Zstd.suffix()));
+        // Documents stored before the per-user directories were introduced
+        let main_file_flat = self.get_documents_path().join(&document_filename);
         
         // Strategy 2: Try original filename in documents directory
         let main_file_original = self.get_documents_path().join(filename);
@@ -394,6 +495,9 @@ impl StorageBackend for LocalStorageBackend {
         // Try to delete main document file using all strategies
         let main_file_candidates = [
             &main_file_structured,
+            &main_file_zstd,
+            &main_file_gzip,
+            &main_file_flat,
             &main_file_original,  
             &main_file_legacy,
         ];
@@ -413,18 +517,20 @@ impl StorageBackend for LocalStorageBackend {
             info!("Main document file not found in any expected location for document {}", document_id);
         }
 
-        // Delete thumbnail if it exists
+        // Delete thumbnail and processed image if they exist, in the user's
+        // directories or the flat ones used before
         let thumbnail_filename = format!("{}_thumb.jpg", document_id);
-        let thumbnail_path = self.get_thumbnails_path().join(&thumbnail_filename);
-        if let Some(deleted_path) = safe_delete(&thumbnail_path, &mut serious_errors, storage_backend).await {
-            deleted_files.push(deleted_path);
-        }
-
-        // Delete processed image if it exists
         let processed_image_filename = format!("{}_processed.png", document_id);
-        let processed_image_path = self.get_processed_images_path().join(&processed_image_filename);
-        if let Some(deleted_path) = safe_delete(&processed_image_path, &mut serious_errors, storage_backend).await {
-            deleted_files.push(deleted_path);
+        let derived_files = [
+            self.get_user_thumbnails_path(user_id).join(&thumbnail_filename),
+            self.get_thumbnails_path().join(&thumbnail_filename),
+            self.get_user_processed_images_path(user_id).join(&processed_image_filename),
+            self.get_processed_images_path().join(&processed_image_filename),
+        ];
+        for derived_file in &derived_files {
+            if let Some(deleted_path) = safe_delete(derived_file, &mut serious_errors, storage_backend).await {
+                deleted_files.push(deleted_path);
+            }
         }
 
         // Only fail if there were serious errors (not "file not found")
@@ -442,6 +548,19 @@ impl StorageBackend for LocalStorageBackend {
         Ok(())
     }
 
+    async fn delete_file(&self, path: &str) -> Result<()> {
+        let sanitized_path = validate_and_sanitize_path(path)?;
+        validate_path_within_base(&sanitized_path, &self.upload_path)?;
+
+        match fs::remove_file(&sanitized_path).await {
+            Ok(()) => info!("Deleted file: {}", sanitized_path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(anyhow::anyhow!("Failed to delete file {}: {}", sanitized_path, e)),
+        }
+        self.invalidate_cache_entry(path).await;
+        Ok(())
+    }
+
     async fn file_exists(&self, path: &str) -> Result<bool> {
         // Validate and sanitize the input path
         let sanitized_path = match validate_and_sanitize_path(path) {
@@ -5,10 +5,71 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use std::path::{Component, Path};
+use tracing::warn;
 use uuid::Uuid;
 
 pub mod local;
 pub mod factory;
+pub mod compression;
+
+/// A storage path or filename that would reach outside the namespace of the
+/// user it was requested for
+#[derive(Debug, thiserror::Error)]
+#[error("Storage path {path:?} is outside the namespace of user {user_id}")]
+pub struct StorageAccessDenied {
+    pub user_id: Uuid,
+    pub path: String,
+}
+
+impl StorageAccessDenied {
+    pub fn new(user_id: Uuid, path: &str) -> Self {
+        warn!("Refused storage access for user {} to {:?}", user_id, path);
+        Self { user_id, path: path.to_string() }
+    }
+}
+
+/// Top-level prefixes under which object stores keep each user's files, as
+/// `<prefix>/<user id>/...`
+pub const USER_KEY_PREFIXES: [&str; 3] = ["documents", "thumbnails", "processed_images"];
+
+/// Whether the object `key` lies under one of `user_id`'s prefixes
+pub fn is_user_object_key(user_id: Uuid, key: &str) -> bool {
+    let key = key.strip_prefix("s3://").unwrap_or(key);
+    let mut segments = key.split('/');
+    let (Some(prefix), Some(owner)) = (segments.next(), segments.next()) else {
+        return false;
+    };
+    let rest: Vec<&str> = segments.collect();
+    USER_KEY_PREFIXES.contains(&prefix)
+        && owner == user_id.to_string()
+        && !rest.is_empty()
+        && rest.iter().all(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+}
+
+/// Whether the local `path` lies in one of `user_id`'s directories under
+/// `upload_path`, laid out like object keys: `<prefix>/<user id>/...`
+pub fn is_user_local_path(user_id: Uuid, path: &str, upload_path: &str) -> bool {
+    let Some(relative) = crate::utils::security::path_relative_to_base(path, upload_path) else {
+        return false;
+    };
+    let segments: Vec<_> = relative.components().map(|component| component.as_os_str().to_string_lossy()).collect();
+    is_user_object_key(user_id, &segments.join("/"))
+}
+
+/// Fails with [`StorageAccessDenied`] unless `filename` is a plain file name, so
+/// paths built by joining it to a directory stay in that directory
+pub fn ensure_plain_filename(user_id: Uuid, filename: &str) -> Result<()> {
+    let mut components = Path::new(filename).components();
+    let plain = matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !filename.contains('\\');
+    if plain {
+        Ok(())
+    } else {
+        Err(StorageAccessDenied::new(user_id, filename).into())
+    }
+}
 
 /// Core storage backend trait that all storage implementations must implement
 #[async_trait]
@@ -20,6 +81,13 @@ pub trait StorageBackend: Send + Sync {
     /// Store a document file
     /// Returns the storage path/key where the document was stored
     async fn store_document(&self, user_id: Uuid, document_id: Uuid, filename: &str, data: &[u8]) -> Result<String>;
+
+    /// Store a document from a file on local disk, such as a spooled download.
+    /// The default reads the file into memory; backends that can copy it directly override this.
+    async fn store_document_from_path(&self, user_id: Uuid, document_id: Uuid, filename: &str, source: &std::path::Path) -> Result<String> {
+        let data = tokio::fs::read(source).await?;
+        self.store_document(user_id, document_id, filename, &data).await
+    }
     
     /// Store a thumbnail image
     /// Returns the storage path/key where the thumbnail was stored
@@ -31,10 +99,28 @@ pub trait StorageBackend: Send + Sync {
     
     /// Retrieve file data by storage path/key
     async fn retrieve_file(&self, path: &str) -> Result<Vec<u8>>;
+
+    /// Whether `path` lies within the storage namespace of `user_id`
+    fn is_user_path(&self, user_id: Uuid, path: &str) -> bool;
+
+    /// Retrieve file data on behalf of `user_id`, failing with
+    /// [`StorageAccessDenied`] for paths outside the user's namespace
+    async fn retrieve_user_file(&self, user_id: Uuid, path: &str) -> Result<Vec<u8>> {
+        if !self.is_user_path(user_id, path) {
+            return Err(StorageAccessDenied::new(user_id, path).into());
+        }
+        self.retrieve_file(path).await
+    }
     
-    /// Delete all files associated with a document (document, thumbnail, processed image)
+    /// Delete all files associated with a document (document, thumbnail, processed image).
+    /// Backends that build paths from `filename` fail with [`StorageAccessDenied`]
+    /// if it isn't a plain file name.
     async fn delete_document_files(&self, user_id: Uuid, document_id: Uuid, filename: &str) -> Result<()>;
     
+    /// Delete a single file by storage path/key. A file that doesn't exist is
+    /// not an error.
+    async fn delete_file(&self, path: &str) -> Result<()>;
+
     /// Check if a file exists at the given path/key
     async fn file_exists(&self, path: &str) -> Result<bool>;
     
@@ -24,7 +24,8 @@ use crate::{
             SystemMetrics, DatabaseMetrics, OcrMetrics, DocumentMetrics, UserMetrics, GeneralSystemMetrics
         },
         labels::{
-            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, BulkUpdateRequest as LabelBulkUpdateRequest
+            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, BulkUpdateRequest as LabelBulkUpdateRequest,
+            LabelOcrRegions
         },
         documents::BulkDeleteRequest
     },
@@ -42,17 +43,41 @@ use crate::{
         crate::routes::auth::oidc_callback,
         // Document endpoints
         crate::routes::documents::crud::upload_document,
+        crate::routes::documents::ingest::ingest_external_documents,
         crate::routes::documents::crud::list_documents,
         crate::routes::documents::crud::get_document_by_id,
         crate::routes::documents::crud::delete_document,
         crate::routes::documents::bulk::bulk_delete_documents,
+        crate::routes::documents::bulk::get_documents_batch,
+        crate::routes::documents::organize::move_document,
+        crate::routes::documents::organize::bulk_move_documents,
+        crate::routes::documents::related::get_related_documents,
+        crate::routes::documents::related::get_document_splits,
+        crate::routes::documents::related::get_document_archive_entries,
         crate::routes::documents::crud::download_document,
         crate::routes::documents::crud::view_document,
         crate::routes::documents::debug::get_document_thumbnail,
         crate::routes::documents::ocr::get_document_ocr,
+        crate::routes::documents::ocr::enqueue_document_ocr,
         crate::routes::documents::debug::get_processed_image,
         crate::routes::documents::ocr::retry_ocr,
+        crate::routes::documents::ocr::update_document_text,
+        crate::routes::documents::ocr::update_document_handwriting,
+        crate::routes::documents::ocr::ocr_document_pages,
+        crate::routes::documents::ocr::ocr_document_region,
+        crate::routes::documents::ocr::get_document_fields,
+        crate::routes::documents::shares::share_document,
+        crate::routes::documents::shares::list_document_shares,
+        crate::routes::documents::shares::unshare_document,
+        crate::routes::documents::notes::create_document_note,
+        crate::routes::documents::notes::list_document_notes,
+        crate::routes::documents::notes::update_document_note,
+        crate::routes::documents::notes::delete_document_note,
+        crate::routes::documents::expiry::update_document_expiry,
+        crate::routes::documents::expiry::list_expiring_documents,
+        crate::routes::documents::tables::get_document_tables,
         crate::routes::documents::debug::get_document_debug_info,
+        crate::routes::documents::debug::stream_ocr_log,
         crate::routes::documents::failed::get_failed_ocr_documents,
         crate::routes::documents::failed::view_failed_document,
         crate::routes::documents::bulk::delete_low_confidence_documents,
@@ -64,6 +89,8 @@ use crate::{
         crate::routes::labels::get_label,
         crate::routes::labels::update_label,
         crate::routes::labels::delete_label,
+        crate::routes::labels::get_label_ocr_regions,
+        crate::routes::labels::set_label_ocr_regions,
         crate::routes::labels::get_document_labels,
         crate::routes::labels::update_document_labels,
         crate::routes::labels::add_document_label,
@@ -73,9 +100,11 @@ use crate::{
         crate::routes::search::search_documents,
         crate::routes::search::enhanced_search_documents,
         crate::routes::search::get_search_facets,
+        crate::routes::search::export_search_results,
         // Settings endpoints
         crate::routes::settings::get_settings,
         crate::routes::settings::update_settings,
+        crate::routes::settings::reprocess_outdated_ocr,
         // User endpoints
         crate::routes::users::list_users,
         crate::routes::users::create_user,
@@ -91,6 +120,8 @@ use crate::{
         crate::routes::queue::resume_ocr_processing,
         // Metrics endpoints
         crate::routes::metrics::get_system_metrics,
+        crate::routes::metrics::get_library_stats,
+        crate::routes::metrics::get_ocr_timeseries,
         crate::routes::prometheus_metrics::get_prometheus_metrics,
         // Notifications endpoints
         crate::routes::notifications::get_notifications,
@@ -104,16 +135,27 @@ use crate::{
         crate::routes::sources::crud::get_source,
         crate::routes::sources::crud::update_source,
         crate::routes::sources::crud::delete_source,
+        crate::routes::sources::crud::preview_source_deletion,
+        crate::routes::sources::crud::restore_source,
         crate::routes::sources::sync::trigger_sync,
         crate::routes::sources::sync::stop_sync,
+        crate::routes::sources::sync::get_last_sync_run,
+        crate::routes::sources::sync::get_sync_history,
+        crate::routes::sources::sync::retry_failed_sync,
+        crate::routes::sources::adopt::adopt_documents,
+        crate::routes::sources::sync::reset_source_circuit,
         crate::routes::sources::sync::trigger_deep_scan,
         crate::routes::sources::sync::sync_progress_websocket,
         crate::routes::sources::sync::get_sync_status,
+        crate::routes::sources::sync::reset_sync_state,
         crate::routes::sources::validation::test_connection,
         crate::routes::sources::validation::validate_source,
+        crate::routes::sources::validation::get_source_capabilities,
         crate::routes::sources::estimation::estimate_crawl,
         crate::routes::sources::estimation::estimate_crawl_with_config,
+        crate::routes::sources::estimation::preview_source,
         crate::routes::sources::validation::test_connection_with_config,
+        crate::routes::sources::debug::debug_propfind,
         // WebDAV endpoints
         crate::routes::webdav::start_webdav_sync,
         crate::routes::webdav::cancel_webdav_sync,
@@ -122,8 +164,15 @@ use crate::{
         crate::routes::webdav::estimate_webdav_crawl,
         // OCR endpoints
         crate::routes::ocr::get_available_languages,
+        crate::routes::ocr::preview_ocr_settings,
+        crate::routes::ocr::get_fallback_stats,
+        crate::routes::ocr::reset_fallback_stats,
         crate::ocr::api::health_check,
         crate::ocr::api::perform_ocr,
+        // Credential key endpoints
+        crate::routes::credential_keys::rotate_keys,
+        crate::routes::thumbnails::regenerate_thumbnails,
+        crate::routes::thumbnails::get_regeneration_progress,
         // Ignored files endpoints
         crate::routes::ignored_files::list_ignored_files,
         crate::routes::ignored_files::get_ignored_file,
@@ -137,7 +186,7 @@ use crate::{
         schemas(
             CreateUser, LoginRequest, LoginResponse, UserResponse, UpdateUser,
             DocumentResponse, SearchRequest, SearchResponse, EnhancedDocumentResponse,
-            SettingsResponse, UpdateSettings, SearchMode, SearchSnippet, HighlightRange,
+            SettingsResponse, UpdateSettings, crate::models::OcrReprocessSummary, SearchMode, SearchSnippet, HighlightRange,
             FacetItem, SearchFacetsResponse, Notification, NotificationSummary, CreateNotification,
             Source, SourceResponse, CreateSource, UpdateSource, SourceWithStats,
             WebDAVSourceConfig, LocalFolderSourceConfig, S3SourceConfig,
@@ -147,14 +196,59 @@ use crate::{
             crate::routes::ignored_files::IgnoredFilesStats,
             crate::routes::ignored_files::SourceTypeCount,
             SystemMetrics, DatabaseMetrics, OcrMetrics, DocumentMetrics, UserMetrics, GeneralSystemMetrics,
+            crate::models::LibraryStats, crate::models::SourceDocumentStats, crate::models::DailyIngestionCount,
+            crate::routes::metrics::OcrTimeseriesResponse, crate::ocr::queue::OcrTimeseriesBucket, crate::ocr::queue::OcrStatsGranularity,
             // Labels schemas
-            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, LabelBulkUpdateRequest,
+            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, LabelBulkUpdateRequest, LabelOcrRegions,
+            crate::ocr::region::NamedOcrRegion, crate::ocr::region::ExtractedField,
             // Document schemas
             BulkDeleteRequest, DocumentListResponse, DocumentOcrResponse, DocumentOperationResponse,
             BulkDeleteResponse, PaginationInfo, DocumentDuplicatesResponse, crate::routes::documents::RetryOcrRequest,
+            crate::routes::documents::ExternalIngestRecord, crate::routes::documents::ExternalIngestRecordResult,
+            crate::routes::documents::ExternalIngestResponse,
+            crate::routes::documents::BatchDocumentsRequest, crate::routes::documents::BatchDocumentsResponse,
+            crate::routes::documents::BatchDocumentResult, crate::routes::documents::BatchDocumentStatus,
+            crate::routes::documents::MoveDocumentRequest, crate::routes::documents::BulkMoveDocumentsRequest,
+            crate::routes::documents::MoveDocumentResponse, crate::routes::documents::MoveDocumentFailure,
+            crate::routes::documents::BulkMoveDocumentsResponse,
+            crate::routes::documents::UpdateDocumentTextRequest,
+            crate::routes::documents::UpdateHandwritingRequest,
+            crate::routes::documents::OcrPagesRequest, crate::routes::documents::OcrPagesResponse,
+            crate::routes::documents::OnDemandOcrResponse, crate::models::OcrJobOverrides,
+            crate::routes::documents::OcrPageText,
+            crate::routes::documents::OcrRegionRequest, crate::routes::documents::OcrRegionResponse,
+            crate::routes::documents::DocumentFieldsResponse,
+            crate::ocr::region::OcrRegion,
+            crate::routes::documents::DocumentTablesResponse, crate::ocr::table_extraction::ExtractedTable,
+            crate::models::document_share::DocumentShare, crate::models::document_share::CreateDocumentShareRequest,
+            crate::models::document_note::DocumentNote, crate::models::document_note::DocumentNoteRequest,
+            crate::routes::documents::RelatedDocumentsResponse, crate::models::RelatedDocument,
+            crate::routes::documents::DocumentSplitsResponse, crate::routes::documents::DocumentSplitPart,
+            crate::routes::documents::DocumentArchiveEntriesResponse, crate::routes::documents::DocumentArchiveEntry,
+            crate::routes::documents::UpdateExpiryRequest, crate::routes::documents::ExpiringDocumentsResponse,
+            crate::routes::documents::ExpiringDocumentEntry,
+            crate::error_envelope::ErrorEnvelope, crate::error_envelope::ErrorBody, crate::error_envelope::FieldError,
+            crate::routes::pagination::PaginationMeta, crate::routes::notifications::NotificationListResponse,
+            crate::routes::sources::SourceListResponse, crate::models::SourceSyncStateReset,
+            crate::models::SourceDeletionPreview, crate::models::SourceDeletionSummary,
+            crate::models::SourceSyncRun, crate::models::SyncFileFailure, crate::models::SyncOutcome,
+            crate::models::SyncFailureStage, crate::models::SyncFailureKind, crate::models::RetryFailedFilesResponse,
+            crate::models::AdoptDocumentsRequest, crate::models::AdoptDocumentsFilter, crate::models::AdoptionRejection,
+            crate::models::RejectedAdoption, crate::models::AdoptDocumentsResponse,
+            crate::models::SourceSyncRunSummary, crate::models::SourceSyncHistoryStats,
+            crate::routes::sources::SyncHistoryResponse,
+            crate::models::SourceCircuit, crate::models::SourceCircuitState, crate::models::SourceCircuitBreakerConfig,
+            crate::models::SourceClockSkew,
+            crate::routes::sources::PreviewSourceRequest, crate::models::SourcePreviewResponse, crate::models::SourcePreviewEstimate,
+            crate::routes::sources::PropfindDebugResponse, crate::routes::sources::PropfindDebugItem,
+            crate::routes::sources::SourceCapabilitiesResponse,
+            crate::services::credential_encryption::CredentialRotationSummary,
+            crate::services::thumbnail_regeneration::ThumbnailRegenerationProgress,
             // OCR schemas
-            crate::routes::ocr::AvailableLanguagesResponse, crate::routes::ocr::LanguageInfo,
+            crate::ocr::live_log::OcrLogEvent, crate::ocr::live_log::OcrLogEventKind,
+            crate::routes::ocr::AvailableLanguagesResponse, crate::routes::ocr::LanguageInfo, crate::routes::ocr::OcrPreviewResponse,
             crate::ocr::api::OcrHealthResponse, crate::ocr::api::OcrErrorResponse, crate::ocr::api::OcrRequest,
+            crate::ocr::fallback_stats::FallbackStatsResponse, crate::ocr::fallback_stats::ExtractionMethodStats,
             // Sync progress schemas
             crate::services::sync_progress_tracker::SyncProgressInfo
         )
@@ -190,17 +190,33 @@ pub fn create_test_config_with_db(database_url: &str) -> Config {
         user_watch_base_dir: "/tmp/user_watch".to_string(),
         enable_per_user_watch: false,
         allowed_file_types: vec!["pdf".to_string(), "png".to_string(), "jpg".to_string(), "txt".to_string()],
+        mime_type_overrides: Default::default(),
         watch_interval_seconds: Some(10),
         file_stability_check_ms: Some(500),
         max_file_age_hours: Some(24),
         
         // OCR Configuration
         ocr_language: "eng".to_string(),
+        ocr_auto_download_langs: false,
+        ocr_text_strip_control_chars: true,
+        ocr_text_normalize_unicode: true,
         concurrent_ocr_jobs: 2,
         ocr_timeout_seconds: 60,
+        ocr_model_cache_size: 2,
+        ocr_scheduling_policy: crate::ocr::queue::OcrFairnessPolicy::Fifo,
+        ocr_smaller_files_first: false,
+        ocr_large_file_threshold_mb: 0,
+        ocr_large_file_concurrency: 1,
         max_file_size_mb: 50,
+        max_upload_bytes: None,
         max_pdf_size_mb: 100,
         max_office_document_size_mb: 100,
+        office_doc_tool_order: crate::ocr::fallback_order::FallbackConfig::default().doc_tool_order,
+        pdf_extraction_order: crate::ocr::fallback_order::FallbackConfig::default().pdf_method_order,
+        extraction_auto_order: false,
+        archive_max_depth: 3,
+        archive_max_total_size_mb: 1024,
+        archive_max_entries: 1000,
 
         // Performance
         memory_limit_mb: 256,
@@ -218,9 +234,64 @@ pub fn create_test_config_with_db(database_url: &str) -> Config {
         // S3 Configuration (disabled for tests by default)
         s3_enabled: false,
         s3_config: None,
+        storage_compression: crate::storage::compression::StorageCompression::None,
+        storage_user_isolation: true,
+        webdav_stream_threshold_mb: 100,
+        webdav_discovery_cache_enabled: false,
+        webdav_discovery_cache_max_entries: 10000,
+        webdav_discovery_cache_ttl_minutes: 1440,
+        webdav_discovery_cache_file: None,
+        webdav_clock_skew_warning_seconds: 300,
+        webdav_clock_skew_tolerance_seconds: 0,
+        webdav_capability_ttl_minutes: 60,
+        webdav_max_downloads_per_host: 8,
+        ingestion_pipeline: crate::ingestion::pipeline::IngestionPipelineConfig::default(),
+        sync_failure_ratio_threshold: 0.5,
+        sync_history_retention_days: 90,
+        sync_transient_retries: 2,
+        sync_transient_retry_delay_seconds: 30,
+        source_delete_require_confirmation: false,
+        source_delete_grace_period_minutes: 0,
+        malware_scan_enabled: false,
+        malware_scan_endpoint: None,
+        malware_scan_command: None,
+        malware_scan_fail_open: false,
+        malware_scan_timeout_seconds: 60,
+        file_validation_mode: crate::services::file_validator::FileValidationMode::Lenient,
+        pdf_optimizer: crate::services::pdf_optimizer::PdfOptimizerTool::None,
+        pdf_optimize_min_size_kb: 1024,
+        pdf_optimize_timeout_seconds: 300,
+        handwriting_ocr_endpoint: None,
+        handwriting_ocr_api_key: None,
+        handwriting_ocr_timeout_seconds: 120,
+        ocr_confidence_calibration_file: None,
+        temp_file_max_age_hours: 24,
+        pagination_default_per_page: 25,
+        pagination_max_per_page: 100,
+        pagination_clamp_out_of_range: false,
+        search_max_results: 10000,
+        search_index_strategy: crate::db::search_index::SearchIndexStrategy::Shared,
+        response_compression_enabled: true,
+        response_compression_min_bytes: 1024,
 
         // Public URL
         public_url: None,
+        schema_auto_repair: false,
+        backup_before_migrate: false,
+        migration_backup_dir: "./backups".to_string(),
+        migration_backup_include_data: false,
+        source_debug_endpoints: false,
+        ocr_debug_endpoints: false,
+        detect_document_language: true,
+        default_document_language: "english".to_string(),
+        thumbnail_size: 200,
+        thumbnail_auto_regenerate: false,
+        thumbnail_regeneration_concurrency: 2,
+        api_error_format: crate::error_envelope::ApiErrorFormat::Legacy,
+        api_error_expose_details: false,
+        credentials_master_keys: String::new(),
+        credentials_auto_rotate: false,
+        credentials_rotation_batch_size: 100,
     }
 }
 
@@ -288,7 +288,7 @@ impl TestContext {
         let file_service = Arc::new(crate::services::file_service::FileService::with_storage(
             config.upload_path.clone(), 
             storage_backend
-        ));
+        ).with_thumbnail_size(config.thumbnail_size));
         
         let queue_service = Arc::new(crate::ocr::queue::OcrQueueService::new(
             db.clone(),
@@ -298,9 +298,11 @@ impl TestContext {
             100, // Default 100MB for tests
             100, // Default 100MB for tests
             300, // Default 300s OCR timeout for tests
-        ));
+        ).with_document_language(crate::ocr::language_detection::DocumentLanguagePolicy::from_config(&config)));
 
-        let max_body_size = config.max_file_size_mb as usize * 1024 * 1024;
+        let max_body_size = config.upload_body_limit();
+        let compression = crate::compression::configured_compression_layer(&config);
+        let api_errors = crate::error_envelope::ApiErrorSettings::from_config(&config);
         let state = Arc::new(AppState {
             db,
             config,
@@ -316,17 +318,34 @@ impl TestContext {
         });
         
         let app = Router::new()
+            .nest("/api/admin/keys", crate::routes::credential_keys::router())
+            .nest("/api/admin/thumbnails", crate::routes::thumbnails::router())
             .nest("/api/auth", crate::routes::auth::router())
             .nest("/api/documents", crate::routes::documents::router())
             .nest("/api/search", crate::routes::search::router())
             .nest("/api/settings", crate::routes::settings::router())
+            .nest("/api/sources", crate::routes::sources::router())
             .nest("/api/users", crate::routes::users::router())
             .nest("/api/ignored/files", crate::routes::ignored_files::ignored_files_routes())
             .nest("/api/ocr", crate::routes::ocr::router())
             .nest("/api/metrics", crate::routes::metrics::router())
             .nest("/metrics", crate::routes::prometheus_metrics::router())
             .with_state(state.clone())
-            .layer(axum::extract::DefaultBodyLimit::max(max_body_size));
+            .layer(axum::middleware::from_fn_with_state(
+                max_body_size,
+                crate::body_limit::reject_oversized_body,
+            ))
+            .layer(axum::extract::DefaultBodyLimit::max(max_body_size))
+            // Inside compression, since it rewrites error bodies
+            .layer(axum::middleware::from_fn_with_state(
+                api_errors,
+                crate::error_envelope::structured_errors,
+            ));
+        let app = match compression {
+            Some(compression) => app.layer(compression),
+            None => app,
+        };
+        let app = app.layer(axum::middleware::from_fn(crate::logging::request_span));
         
         Self { 
             app, 
@@ -770,8 +789,16 @@ pub struct TestConfigBuilder {
     concurrent_ocr_jobs: usize,
     ocr_timeout_seconds: u64,
     max_file_size_mb: u64,
+    max_upload_bytes: Option<u64>,
     memory_limit_mb: u64,
     oidc_enabled: bool,
+    search_max_results: i64,
+    source_delete_require_confirmation: bool,
+    source_delete_grace_period_minutes: u64,
+    api_error_format: crate::error_envelope::ApiErrorFormat,
+    api_error_expose_details: bool,
+    ocr_debug_endpoints: bool,
+    thumbnail_size: u32,
 }
 
 #[cfg(any(test, feature = "test-utils"))]
@@ -784,8 +811,16 @@ impl Default for TestConfigBuilder {
             concurrent_ocr_jobs: 2,
             ocr_timeout_seconds: 60,
             max_file_size_mb: 10,
+            max_upload_bytes: None,
             memory_limit_mb: 256,
             oidc_enabled: false,
+            search_max_results: 10000,
+            source_delete_require_confirmation: false,
+            source_delete_grace_period_minutes: 0,
+            api_error_format: crate::error_envelope::ApiErrorFormat::Legacy,
+            api_error_expose_details: false,
+            ocr_debug_endpoints: false,
+            thumbnail_size: 200,
         }
     }
 }
@@ -816,6 +851,38 @@ impl TestConfigBuilder {
         self.max_file_size_mb = size_mb;
         self
     }
+
+    pub fn with_max_upload_bytes(mut self, bytes: u64) -> Self {
+        self.max_upload_bytes = Some(bytes);
+        self
+    }
+
+    pub fn with_search_max_results(mut self, max_results: i64) -> Self {
+        self.search_max_results = max_results;
+        self
+    }
+
+    pub fn with_source_deletion(mut self, require_confirmation: bool, grace_period_minutes: u64) -> Self {
+        self.source_delete_require_confirmation = require_confirmation;
+        self.source_delete_grace_period_minutes = grace_period_minutes;
+        self
+    }
+
+    pub fn with_api_errors(mut self, format: crate::error_envelope::ApiErrorFormat, expose_details: bool) -> Self {
+        self.api_error_format = format;
+        self.api_error_expose_details = expose_details;
+        self
+    }
+
+    pub fn with_ocr_debug_endpoints(mut self, enabled: bool) -> Self {
+        self.ocr_debug_endpoints = enabled;
+        self
+    }
+
+    pub fn with_thumbnail_size(mut self, size: u32) -> Self {
+        self.thumbnail_size = size;
+        self
+    }
     
     fn build(self, database_url: String) -> crate::config::Config {
         crate::config::Config {
@@ -827,17 +894,33 @@ impl TestConfigBuilder {
             user_watch_base_dir: "./test-user-watch".to_string(),
             enable_per_user_watch: false,
             allowed_file_types: vec!["pdf".to_string(), "txt".to_string(), "png".to_string()],
+            mime_type_overrides: Default::default(),
             watch_interval_seconds: Some(30),
             file_stability_check_ms: Some(500),
             max_file_age_hours: None,
             
             // OCR Configuration
             ocr_language: "eng".to_string(),
+            ocr_auto_download_langs: false,
+            ocr_text_strip_control_chars: true,
+            ocr_text_normalize_unicode: true,
             concurrent_ocr_jobs: self.concurrent_ocr_jobs,
             ocr_timeout_seconds: self.ocr_timeout_seconds,
+            ocr_model_cache_size: 2,
+            ocr_scheduling_policy: crate::ocr::queue::OcrFairnessPolicy::Fifo,
+            ocr_smaller_files_first: false,
+            ocr_large_file_threshold_mb: 0,
+            ocr_large_file_concurrency: 1,
             max_file_size_mb: self.max_file_size_mb,
+            max_upload_bytes: self.max_upload_bytes,
             max_pdf_size_mb: 100,
             max_office_document_size_mb: 100,
+            office_doc_tool_order: crate::ocr::fallback_order::FallbackConfig::default().doc_tool_order,
+            pdf_extraction_order: crate::ocr::fallback_order::FallbackConfig::default().pdf_method_order,
+            extraction_auto_order: false,
+            archive_max_depth: 3,
+            archive_max_total_size_mb: 1024,
+            archive_max_entries: 1000,
 
             // Performance
             memory_limit_mb: self.memory_limit_mb as usize,
@@ -855,9 +938,64 @@ impl TestConfigBuilder {
             // S3 Configuration
             s3_enabled: false,
             s3_config: None,
+            storage_compression: crate::storage::compression::StorageCompression::None,
+            storage_user_isolation: true,
+            webdav_stream_threshold_mb: 100,
+            webdav_discovery_cache_enabled: false,
+            webdav_discovery_cache_max_entries: 10000,
+            webdav_discovery_cache_ttl_minutes: 1440,
+            webdav_discovery_cache_file: None,
+            webdav_clock_skew_warning_seconds: 300,
+            webdav_clock_skew_tolerance_seconds: 0,
+            webdav_capability_ttl_minutes: 60,
+            webdav_max_downloads_per_host: 8,
+            ingestion_pipeline: crate::ingestion::pipeline::IngestionPipelineConfig::default(),
+            sync_failure_ratio_threshold: 0.5,
+            sync_history_retention_days: 90,
+            sync_transient_retries: 2,
+            sync_transient_retry_delay_seconds: 30,
+            source_delete_require_confirmation: self.source_delete_require_confirmation,
+            source_delete_grace_period_minutes: self.source_delete_grace_period_minutes,
+            malware_scan_enabled: false,
+            malware_scan_endpoint: None,
+            malware_scan_command: None,
+            malware_scan_fail_open: false,
+            malware_scan_timeout_seconds: 60,
+            file_validation_mode: crate::services::file_validator::FileValidationMode::Lenient,
+            pdf_optimizer: crate::services::pdf_optimizer::PdfOptimizerTool::None,
+            pdf_optimize_min_size_kb: 1024,
+            pdf_optimize_timeout_seconds: 300,
+            handwriting_ocr_endpoint: None,
+            handwriting_ocr_api_key: None,
+            handwriting_ocr_timeout_seconds: 120,
+            ocr_confidence_calibration_file: None,
+            temp_file_max_age_hours: 24,
+            pagination_default_per_page: 25,
+            pagination_max_per_page: 100,
+            pagination_clamp_out_of_range: false,
+            search_max_results: self.search_max_results,
+            search_index_strategy: crate::db::search_index::SearchIndexStrategy::Shared,
+            response_compression_enabled: true,
+            response_compression_min_bytes: 1024,
 
             // Public URL
             public_url: None,
+            schema_auto_repair: false,
+            backup_before_migrate: false,
+            migration_backup_dir: "./backups".to_string(),
+            migration_backup_include_data: false,
+            source_debug_endpoints: false,
+            ocr_debug_endpoints: self.ocr_debug_endpoints,
+            detect_document_language: true,
+            default_document_language: "english".to_string(),
+            thumbnail_size: self.thumbnail_size,
+            thumbnail_auto_regenerate: false,
+            thumbnail_regeneration_concurrency: 2,
+            api_error_format: self.api_error_format,
+            api_error_expose_details: self.api_error_expose_details,
+            credentials_master_keys: String::new(),
+            credentials_auto_rotate: false,
+            credentials_rotation_batch_size: 100,
         }
     }
 }
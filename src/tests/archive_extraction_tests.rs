@@ -0,0 +1,108 @@
+use std::io::Write;
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::ingestion::archive::{extract_archive, is_archive_mime, safe_entry_path, ArchiveLimits};
+
+fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    {
+        let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buffer));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, content) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+    buffer
+}
+
+fn paths(data: &[u8], limits: &ArchiveLimits) -> Vec<String> {
+    extract_archive(data, limits).unwrap().into_iter().map(|entry| entry.path).collect()
+}
+
+#[test]
+fn test_archive_mime_types() {
+    assert!(is_archive_mime("application/zip"));
+    assert!(is_archive_mime("application/x-zip-compressed"));
+    assert!(!is_archive_mime("application/vnd.openxmlformats-officedocument.wordprocessingml.document"));
+    assert!(!is_archive_mime("application/pdf"));
+}
+
+#[test]
+fn test_entry_paths_that_escape_the_archive_are_rejected() {
+    assert_eq!(safe_entry_path("scans/page1.png").as_deref(), Some("scans/page1.png"));
+    assert_eq!(safe_entry_path("./scans//page1.png").as_deref(), Some("scans/page1.png"));
+    assert_eq!(safe_entry_path("scans\\page1.png").as_deref(), Some("scans/page1.png"));
+    assert_eq!(safe_entry_path("../etc/passwd"), None);
+    assert_eq!(safe_entry_path("scans/../../etc/passwd"), None);
+    assert_eq!(safe_entry_path("/etc/passwd"), None);
+    assert_eq!(safe_entry_path("C:\\Windows\\win.ini"), None);
+    assert_eq!(safe_entry_path("scans/"), None);
+}
+
+#[test]
+fn test_nested_archives_are_extracted_in_place() {
+    let inner = zip_of(&[("march.pdf", b"%PDF-1.4 march")]);
+    let outer = zip_of(&[
+        ("receipt.png", b"png data"),
+        ("2024/older.zip", &inner),
+        ("../outside.txt", b"escaped"),
+        ("__MACOSX/._receipt.png", b"resource fork"),
+    ]);
+
+    let entries = extract_archive(&outer, &ArchiveLimits::default()).unwrap();
+    let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+    assert_eq!(paths, vec!["receipt.png", "2024/older.zip/march.pdf"]);
+    assert_eq!(entries[1].filename(), "march.pdf");
+    assert_eq!(entries[1].data, b"%PDF-1.4 march");
+}
+
+#[test]
+fn test_archives_below_the_depth_limit_are_kept_as_files() {
+    let innermost = zip_of(&[("deep.txt", b"deep")]);
+    let inner = zip_of(&[("innermost.zip", &innermost)]);
+    let outer = zip_of(&[("inner.zip", &inner)]);
+
+    let limits = ArchiveLimits { max_depth: 2, ..ArchiveLimits::default() };
+    assert_eq!(paths(&outer, &limits), vec!["inner.zip/innermost.zip"]);
+
+    let limits = ArchiveLimits { max_depth: 3, ..ArchiveLimits::default() };
+    assert_eq!(paths(&outer, &limits), vec!["inner.zip/innermost.zip/deep.txt"]);
+
+    let limits = ArchiveLimits { max_depth: 1, ..ArchiveLimits::default() };
+    assert_eq!(paths(&outer, &limits), vec!["inner.zip"]);
+}
+
+#[test]
+fn test_zip_bomb_exceeding_the_total_size_fails() {
+    // Zeros compress to almost nothing, so the archive is tiny but expands to 4 MB
+    let zeros = vec![0u8; 1024 * 1024];
+    let bomb = zip_of(&[("a.bin", &zeros), ("b.bin", &zeros), ("c.bin", &zeros), ("d.bin", &zeros)]);
+    assert!(bomb.len() < 64 * 1024);
+
+    let limits = ArchiveLimits { max_total_bytes: 3 * 1024 * 1024, ..ArchiveLimits::default() };
+    assert!(extract_archive(&bomb, &limits).is_err());
+
+    let limits = ArchiveLimits { max_total_bytes: 4 * 1024 * 1024, ..ArchiveLimits::default() };
+    assert_eq!(extract_archive(&bomb, &limits).unwrap().len(), 4);
+}
+
+#[test]
+fn test_nested_files_count_towards_the_entry_limit() {
+    let inner = zip_of(&[("one.txt", b"1"), ("two.txt", b"2")]);
+    let outer = zip_of(&[("three.txt", b"3"), ("inner.zip", &inner)]);
+
+    let limits = ArchiveLimits { max_entries: 2, ..ArchiveLimits::default() };
+    assert!(extract_archive(&outer, &limits).is_err());
+
+    let limits = ArchiveLimits { max_entries: 3, ..ArchiveLimits::default() };
+    assert_eq!(paths(&outer, &limits).len(), 3);
+}
+
+#[test]
+fn test_unreadable_archive_fails() {
+    assert!(extract_archive(b"not a zip", &ArchiveLimits::default()).is_err());
+}
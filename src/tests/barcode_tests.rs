@@ -0,0 +1,105 @@
+use crate::ocr::barcode::{append_barcode_text, barcode_search_text, parse_zbar_output, DecodedBarcode};
+
+/// A QR code encoding `ORDER-2026-4711`
+const QR_FIXTURE: &str = "tests/fixtures/barcodes/order_qr_code.png";
+
+fn barcode(symbology: &str, value: &str) -> DecodedBarcode {
+    DecodedBarcode {
+        symbology: symbology.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn zbarimg_available() -> bool {
+    std::process::Command::new("zbarimg")
+        .arg("--version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+#[test]
+fn test_zbar_output_is_parsed_per_symbol() {
+    let output = "QR-Code:ORDER-2026-4711\nEAN-13:4006381333931\nI2/5:00123456\n";
+    assert_eq!(
+        parse_zbar_output(output),
+        vec![
+            barcode("QR-Code", "ORDER-2026-4711"),
+            barcode("EAN-13", "4006381333931"),
+            barcode("I2/5", "00123456"),
+        ]
+    );
+}
+
+#[test]
+fn test_multi_line_qr_codes_stay_one_value() {
+    let output = "QR-Code:Invoice 42\nhttps://shop.example.com/orders/42\nCODE-128:PKG-9\n";
+    assert_eq!(
+        parse_zbar_output(output),
+        vec![
+            barcode("QR-Code", "Invoice 42\nhttps://shop.example.com/orders/42"),
+            barcode("CODE-128", "PKG-9"),
+        ]
+    );
+}
+
+#[test]
+fn test_repeated_and_empty_symbols_are_dropped() {
+    // The same code on two pages, and a symbol without data
+    let output = "QR-Code:ORDER-1\nQR-Code:ORDER-1\nEAN-8:\nQR-Code:ORDER-2\n";
+    assert_eq!(
+        parse_zbar_output(output),
+        vec![barcode("QR-Code", "ORDER-1"), barcode("QR-Code", "ORDER-2")]
+    );
+    assert!(parse_zbar_output("").is_empty());
+}
+
+#[test]
+fn test_barcodes_are_appended_as_tagged_lines() {
+    let barcodes = vec![
+        barcode("QR-Code", "Invoice 42\nhttps://shop.example.com/orders/42"),
+        barcode("EAN-13", "4006381333931"),
+    ];
+    assert_eq!(
+        barcode_search_text(&barcodes),
+        "[QR-Code] Invoice 42 https://shop.example.com/orders/42\n[EAN-13] 4006381333931"
+    );
+    assert_eq!(
+        append_barcode_text("Delivery note\n", &barcodes[1..]),
+        "Delivery note\n\n[EAN-13] 4006381333931"
+    );
+    assert_eq!(append_barcode_text("  ", &barcodes[1..]), "[EAN-13] 4006381333931");
+    assert_eq!(append_barcode_text("Delivery note", &[]), "Delivery note");
+}
+
+#[cfg(feature = "ocr")]
+#[tokio::test]
+async fn test_qr_code_is_decoded_from_fixture_image() {
+    use crate::ocr::enhanced::EnhancedOcrService;
+    use crate::services::file_service::FileService;
+    use crate::storage::factory::create_storage_backend;
+    use crate::storage::StorageConfig;
+
+    if !zbarimg_available() {
+        eprintln!("Skipping barcode decoding test: zbarimg (zbar-tools) is not installed");
+        return;
+    }
+
+    let storage_config = StorageConfig::Local { upload_path: "tests".to_string() };
+    let storage_backend = create_storage_backend(storage_config).await.unwrap();
+    let file_service = FileService::with_storage("tests".to_string(), storage_backend);
+    let ocr_service = EnhancedOcrService::new("tests".to_string(), file_service, 100, 100, 300);
+
+    let barcodes = ocr_service
+        .decode_barcodes(QR_FIXTURE, "image/png")
+        .await
+        .expect("Decoding should succeed");
+    assert_eq!(barcodes, vec![barcode("QR-Code", "ORDER-2026-4711")]);
+
+    let text = append_barcode_text("Purchase order", &barcodes);
+    assert!(text.ends_with("[QR-Code] ORDER-2026-4711"), "{}", text);
+
+    // Files that can't hold a barcode are skipped
+    let none = ocr_service.decode_barcodes("tests/test_images/test1.png", "text/plain").await.unwrap();
+    assert!(none.is_empty());
+}
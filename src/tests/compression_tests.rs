@@ -0,0 +1,91 @@
+use std::io::Read;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use tower::util::ServiceExt;
+
+use crate::compression::{compression_layer, is_compressible_content_type};
+
+fn large_json() -> String {
+    let items: Vec<String> = (0..200).map(|i| format!("{{\"id\":{},\"name\":\"document-{}.pdf\"}}", i, i)).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/json", get(|| async { ([(header::CONTENT_TYPE, "application/json")], large_json()) }))
+        .route("/small", get(|| async { ([(header::CONTENT_TYPE, "application/json")], "{\"ok\":true}") }))
+        .route("/pdf", get(|| async { ([(header::CONTENT_TYPE, "application/pdf")], large_json()) }))
+        .route(
+            "/range",
+            get(|| async {
+                (
+                    StatusCode::PARTIAL_CONTENT,
+                    [(header::CONTENT_TYPE, "text/plain"), (header::CONTENT_RANGE, "bytes 0-4999/10000")],
+                    "a".repeat(5000),
+                )
+                    .into_response()
+            }),
+        )
+        .layer(compression_layer(1024))
+}
+
+async fn get_response(path: &str, accept_encoding: Option<&str>) -> (Option<String>, Vec<u8>) {
+    let mut request = Request::builder().uri(path);
+    if let Some(accept_encoding) = accept_encoding {
+        request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+    }
+    let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let encoding = response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (encoding, body.to_vec())
+}
+
+#[test]
+fn test_compressible_content_types() {
+    assert!(is_compressible_content_type("application/json"));
+    assert!(is_compressible_content_type("text/plain; charset=utf-8"));
+    assert!(is_compressible_content_type("application/problem+json"));
+    assert!(!is_compressible_content_type("application/pdf"));
+    assert!(!is_compressible_content_type("image/png"));
+    assert!(!is_compressible_content_type("application/zip"));
+    assert!(!is_compressible_content_type("text/event-stream"));
+}
+
+#[tokio::test]
+async fn test_large_json_is_gzipped_when_accepted() {
+    let (encoding, body) = get_response("/json", Some("gzip")).await;
+    assert_eq!(encoding.as_deref(), Some("gzip"));
+
+    let mut decoded = String::new();
+    flate2::read::GzDecoder::new(body.as_slice()).read_to_string(&mut decoded).unwrap();
+    assert_eq!(decoded, large_json());
+}
+
+#[tokio::test]
+async fn test_brotli_is_used_when_accepted() {
+    let (encoding, _) = get_response("/json", Some("br")).await;
+    assert_eq!(encoding.as_deref(), Some("br"));
+}
+
+#[tokio::test]
+async fn test_uncompressed_without_accept_encoding() {
+    let (encoding, body) = get_response("/json", None).await;
+    assert_eq!(encoding, None);
+    assert_eq!(body, large_json().into_bytes());
+}
+
+#[tokio::test]
+async fn test_small_binary_and_partial_responses_are_not_compressed() {
+    assert_eq!(get_response("/small", Some("gzip")).await.0, None);
+    assert_eq!(get_response("/pdf", Some("gzip, br")).await.0, None);
+    assert_eq!(get_response("/range", Some("gzip")).await.0, None);
+}
@@ -0,0 +1,75 @@
+use crate::ocr::confidence_calibration::{CalibrationCurve, ConfidenceCalibration};
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 0.01, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_without_curves_confidence_is_unchanged() {
+    let calibration = ConfidenceCalibration::default();
+    assert_eq!(calibration.calibrate("eng", 73.5), 73.5);
+    assert!(calibration.languages().is_empty());
+}
+
+#[test]
+fn test_curve_interpolates_between_points() {
+    let curve = CalibrationCurve::from_points(vec![(100.0, 100.0), (0.0, 0.0), (60.0, 40.0)]).unwrap();
+    assert_close(curve.apply(30.0), 20.0);
+    assert_close(curve.apply(60.0), 40.0);
+    assert_close(curve.apply(80.0), 70.0);
+    assert_close(curve.apply(100.0), 100.0);
+}
+
+#[test]
+fn test_curve_clamps_outside_its_points() {
+    let curve = CalibrationCurve::from_points(vec![(20.0, 10.0), (90.0, 95.0)]).unwrap();
+    assert_close(curve.apply(5.0), 10.0);
+    assert_close(curve.apply(99.0), 95.0);
+}
+
+#[test]
+fn test_invalid_curves_are_rejected() {
+    assert!(CalibrationCurve::from_points(vec![(50.0, 50.0)]).is_err());
+    assert!(CalibrationCurve::from_points(vec![(0.0, 0.0), (120.0, 100.0)]).is_err());
+    assert!(CalibrationCurve::from_points(vec![(0.0, 0.0), (50.0, 40.0), (50.0, 60.0)]).is_err());
+}
+
+#[test]
+fn test_fitted_curve_rises_with_raw_confidence() {
+    // The accuracy dip at 74 is pooled with the sample before it
+    let curve = CalibrationCurve::fit(&[(52.0, 61.0), (68.0, 80.0), (74.0, 78.0), (88.0, 95.0)]).unwrap();
+    assert_close(curve.apply(52.0), 61.0);
+    assert_close(curve.apply(71.0), 79.0);
+    assert_close(curve.apply(88.0), 95.0);
+
+    let mut previous = 0.0;
+    for raw in 0..=100 {
+        let calibrated = curve.apply(raw as f32);
+        assert!(calibrated >= previous);
+        previous = calibrated;
+    }
+
+    assert!(CalibrationCurve::fit(&[(80.0, 90.0), (80.0, 70.0)]).is_err());
+}
+
+#[test]
+fn test_calibration_uses_the_primary_language_then_default() {
+    let calibration = ConfidenceCalibration::from_json(
+        r#"{
+            "deu": { "points": [[0, 0], [50, 70], [100, 100]] },
+            "default": { "points": [[0, 0], [100, 50]] }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(calibration.languages(), vec!["default", "deu"]);
+    assert_close(calibration.calibrate("deu", 50.0), 70.0);
+    assert_close(calibration.calibrate("deu+eng", 50.0), 70.0);
+    assert_close(calibration.calibrate("eng+deu", 50.0), 25.0);
+}
+
+#[test]
+fn test_calibration_file_errors_name_the_language() {
+    let error = ConfidenceCalibration::from_json(r#"{ "fra": { "points": [[0, 0]] } }"#).unwrap_err();
+    assert!(format!("{:#}", error).contains("fra"));
+    assert!(ConfidenceCalibration::from_json(r#"{ "fra": { "curve": [] } }"#).is_err());
+}
@@ -0,0 +1,109 @@
+use base64ct::{Base64, Encoding};
+use serde_json::json;
+
+use crate::services::credential_encryption::{encrypted_key_version, CredentialKeyring, MasterKey};
+
+fn key(version: i32, byte: u8) -> MasterKey {
+    MasterKey::new(version, [byte; 32])
+}
+
+#[test]
+fn test_encrypt_and_decrypt_roundtrip() {
+    let keyring = CredentialKeyring::new(vec![key(1, 7)]);
+    let encrypted = keyring.encrypt("hunter2").unwrap();
+    assert!(encrypted.starts_with("enc:v1:"));
+    assert!(!encrypted.contains("hunter2"));
+    assert_eq!(encrypted_key_version(&encrypted), Some(1));
+    assert_eq!(keyring.decrypt(&encrypted).unwrap(), "hunter2");
+
+    // Every value gets its own data key and nonce
+    assert_ne!(keyring.encrypt("hunter2").unwrap(), encrypted);
+}
+
+#[test]
+fn test_plain_text_is_passed_through() {
+    let keyring = CredentialKeyring::new(vec![key(1, 7)]);
+    assert_eq!(keyring.decrypt("hunter2").unwrap(), "hunter2");
+    assert_eq!(encrypted_key_version("hunter2"), None);
+    assert_eq!(CredentialKeyring::default().decrypt("hunter2").unwrap(), "hunter2");
+}
+
+#[test]
+fn test_rewrap_moves_values_to_the_current_key() {
+    let old = CredentialKeyring::new(vec![key(1, 7)]);
+    let encrypted = old.encrypt("hunter2").unwrap();
+
+    let rotating = CredentialKeyring::new(vec![key(1, 7), key(2, 9)]);
+    assert_eq!(rotating.current_version(), Some(2));
+    let rewrapped = rotating.rewrap(&encrypted).unwrap();
+    assert_eq!(encrypted_key_version(&rewrapped), Some(2));
+    // Already current values are left alone
+    assert_eq!(rotating.rewrap(&rewrapped).unwrap(), rewrapped);
+
+    // Once rotated, the old key is no longer needed
+    let new_only = CredentialKeyring::new(vec![key(2, 9)]);
+    assert_eq!(new_only.decrypt(&rewrapped).unwrap(), "hunter2");
+    assert!(new_only.decrypt(&encrypted).is_err());
+}
+
+#[test]
+fn test_wrong_or_tampered_keys_are_rejected() {
+    let keyring = CredentialKeyring::new(vec![key(1, 7)]);
+    let encrypted = keyring.encrypt("hunter2").unwrap();
+    assert!(CredentialKeyring::new(vec![key(1, 8)]).decrypt(&encrypted).is_err());
+
+    let mut tampered = encrypted.clone();
+    tampered.pop();
+    tampered.push(if encrypted.ends_with('A') { 'B' } else { 'A' });
+    assert!(keyring.decrypt(&tampered).is_err());
+    assert!(keyring.decrypt("enc:v1:not-base64").is_err());
+}
+
+#[test]
+fn test_parse_keyring() {
+    let v1 = Base64::encode_string(&[1u8; 32]);
+    let v2 = Base64::encode_string(&[2u8; 32]);
+    let keyring = CredentialKeyring::parse(&format!("1:{}, 2:{}", v1, v2)).unwrap();
+    assert_eq!(keyring.versions(), vec![2, 1]);
+    assert_eq!(keyring.current_version(), Some(2));
+
+    assert!(!CredentialKeyring::parse("").unwrap().is_enabled());
+    assert!(CredentialKeyring::parse(&v1).is_err());
+    assert!(CredentialKeyring::parse(&format!("0:{}", v1)).is_err());
+    assert!(CredentialKeyring::parse("1:c2hvcnQ=").is_err());
+    assert!(CredentialKeyring::parse(&format!("1:{},1:{}", v1, v2)).is_err());
+}
+
+#[test]
+fn test_only_secret_fields_of_configs_are_encrypted() {
+    let keyring = CredentialKeyring::new(vec![key(3, 5)]);
+    let config = json!({
+        "server_url": "https://cloud.example.com",
+        "username": "alice",
+        "password": "hunter2",
+        "secret_access_key": "",
+        "auto_sync": true
+    });
+
+    let (encrypted, version) = keyring.encrypt_config(&config).unwrap();
+    assert_eq!(version, Some(3));
+    assert_eq!(encrypted["server_url"], config["server_url"]);
+    assert_eq!(encrypted["username"], "alice");
+    assert_eq!(encrypted["secret_access_key"], "");
+    assert_eq!(encrypted["auto_sync"], true);
+    assert_eq!(encrypted_key_version(encrypted["password"].as_str().unwrap()), Some(3));
+    assert_eq!(keyring.decrypt_config(&encrypted).unwrap(), config);
+
+    // Without master keys configs are stored as they are
+    let (plain, version) = CredentialKeyring::default().encrypt_config(&config).unwrap();
+    assert_eq!(plain, config);
+    assert_eq!(version, None);
+}
+
+#[test]
+fn test_missing_versions() {
+    let keyring = CredentialKeyring::new(vec![key(2, 9), key(3, 5)]);
+    assert_eq!(keyring.missing_versions(&[1, 2, 3]), vec![1]);
+    assert!(keyring.missing_versions(&[]).is_empty());
+    assert_eq!(CredentialKeyring::default().missing_versions(&[2]), vec![2]);
+}
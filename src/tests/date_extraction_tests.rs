@@ -0,0 +1,140 @@
+use chrono::NaiveDate;
+
+use crate::ocr::date_extraction::{
+    extract_document_date, file_date, find_dates, parse_date_source_priority, pdf_metadata_date,
+    resolve_document_date, DateSelectionRule, DocumentDateSource, NumericDateOrder,
+};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn first_date(text: &str, order: NumericDateOrder) -> Option<NaiveDate> {
+    extract_document_date(text, order, DateSelectionRule::First)
+}
+
+#[test]
+fn test_recognizes_common_date_formats() {
+    let cases = [
+        ("Invoice date: 2024-03-15", date(2024, 3, 15)),
+        ("Rechnungsdatum: 15.03.2024", date(2024, 3, 15)),
+        ("Dated 15/03/24", date(2024, 3, 15)),
+        ("London, 15 March 2024", date(2024, 3, 15)),
+        ("Berlin, den 15. März 2024", date(2024, 3, 15)),
+        ("Paris, le 1er mars 2024", date(2024, 3, 1)),
+        ("Madrid, 15 de marzo de 2024", date(2024, 3, 15)),
+        ("Issued on the 3rd of May, 2024", date(2024, 5, 3)),
+        ("March 15, 2024", date(2024, 3, 15)),
+        ("Sept. 9th 2023", date(2023, 9, 9)),
+        ("Amsterdam, 2 mei 2024", date(2024, 5, 2)),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(first_date(text, NumericDateOrder::DayFirst), Some(expected), "text: {}", text);
+    }
+}
+
+#[test]
+fn test_numeric_dates_follow_configured_order() {
+    let text = "Date: 03/04/2024";
+    assert_eq!(first_date(text, NumericDateOrder::DayFirst), Some(date(2024, 4, 3)));
+    assert_eq!(first_date(text, NumericDateOrder::MonthFirst), Some(date(2024, 3, 4)));
+
+    // A component over 12 can only be the day
+    assert_eq!(first_date("Date: 04/25/2024", NumericDateOrder::DayFirst), Some(date(2024, 4, 25)));
+    assert_eq!(first_date("Date: 25/04/2024", NumericDateOrder::MonthFirst), Some(date(2024, 4, 25)));
+}
+
+#[test]
+fn test_ignores_numbers_that_are_not_dates() {
+    let text = "Version 1.2.3.4, order 31/31/2024, amount 1.250,00, 99 bottles 2024, ref 12.03.2024.7, May 40 2024";
+    assert!(find_dates(text, NumericDateOrder::DayFirst).is_empty());
+    assert!(find_dates("Year 1776 and 15.03.1850", NumericDateOrder::DayFirst).is_empty());
+    // Abbreviations shared by two months are ambiguous
+    assert!(find_dates("3 jui 2024", NumericDateOrder::DayFirst).is_empty());
+}
+
+#[test]
+fn test_selection_rules_with_several_dates() {
+    let text = "Invoice date: 10.01.2024\n\
+        Delivery: 05.01.2024\n\
+        Please pay by 10.02.2024. Invoice of 10.01.2024, thank you.";
+
+    let pick = |rule| extract_document_date(text, NumericDateOrder::DayFirst, rule);
+    assert_eq!(pick(DateSelectionRule::MostFrequent), Some(date(2024, 1, 10)));
+    assert_eq!(pick(DateSelectionRule::First), Some(date(2024, 1, 10)));
+    assert_eq!(pick(DateSelectionRule::Earliest), Some(date(2024, 1, 5)));
+    assert_eq!(pick(DateSelectionRule::Latest), Some(date(2024, 2, 10)));
+
+    // Without a repeated date, the most frequent rule falls back to the first one
+    let letter = "Zurich, 2 June 2023. Re: your letter of 15 May 2023";
+    assert_eq!(
+        extract_document_date(letter, NumericDateOrder::DayFirst, DateSelectionRule::MostFrequent),
+        Some(date(2023, 6, 2))
+    );
+}
+
+#[test]
+fn test_text_without_dates() {
+    assert_eq!(first_date("", NumericDateOrder::DayFirst), None);
+    assert_eq!(first_date("Meeting notes, no date given", NumericDateOrder::DayFirst), None);
+}
+
+#[test]
+fn test_resolve_document_date_uses_priority_order() {
+    let content = Some(date(2024, 1, 10));
+    let file = Some(date(2024, 2, 1));
+    let ingestion = Some(date(2024, 3, 1));
+    let priority = parse_date_source_priority("content,file,ingestion").unwrap();
+
+    assert_eq!(
+        resolve_document_date(&priority, content, file, ingestion),
+        Some((date(2024, 1, 10), DocumentDateSource::Content))
+    );
+    assert_eq!(
+        resolve_document_date(&priority, None, file, ingestion),
+        Some((date(2024, 2, 1), DocumentDateSource::File))
+    );
+    assert_eq!(
+        resolve_document_date(&priority, None, None, ingestion),
+        Some((date(2024, 3, 1), DocumentDateSource::Ingestion))
+    );
+
+    let file_first = parse_date_source_priority("file, content").unwrap();
+    assert_eq!(
+        resolve_document_date(&file_first, content, file, ingestion),
+        Some((date(2024, 2, 1), DocumentDateSource::File))
+    );
+    assert_eq!(resolve_document_date(&file_first, None, None, ingestion), None);
+}
+
+#[test]
+fn test_parse_date_source_priority_rejects_bad_entries() {
+    assert!(parse_date_source_priority("content,mtime").is_err());
+    assert!(parse_date_source_priority("file,file").is_err());
+    assert_eq!(parse_date_source_priority("").unwrap(), Vec::new());
+}
+
+#[test]
+fn test_pdf_metadata_creation_date() {
+    let metadata = serde_json::json!({ "pdf_creation_date": "D:20230105093000+01'00'" });
+    assert_eq!(pdf_metadata_date(Some(&metadata)), Some(date(2023, 1, 5)));
+    assert_eq!(pdf_metadata_date(Some(&serde_json::json!({ "pdf_creation_date": "20230105" }))), Some(date(2023, 1, 5)));
+
+    for broken in ["D:2023", "D:2023-01-05", "D:18000105000000", "D:20231305000000"] {
+        assert_eq!(pdf_metadata_date(Some(&serde_json::json!({ "pdf_creation_date": broken }))), None, "{}", broken);
+    }
+    assert_eq!(pdf_metadata_date(Some(&serde_json::json!({ "page_count": 2 }))), None);
+    assert_eq!(pdf_metadata_date(None), None);
+}
+
+#[test]
+fn test_file_date_prefers_pdf_metadata_over_file_times() {
+    let modified = "2024-02-01T10:00:00Z".parse().ok();
+    let created = "2024-01-01T10:00:00Z".parse().ok();
+    let metadata = serde_json::json!({ "pdf_creation_date": "D:20230105093000" });
+
+    assert_eq!(file_date(Some(&metadata), modified, created), Some(date(2023, 1, 5)));
+    assert_eq!(file_date(None, modified, created), Some(date(2024, 2, 1)));
+    assert_eq!(file_date(None, None, created), Some(date(2024, 1, 1)));
+    assert_eq!(file_date(None, None, None), None);
+}
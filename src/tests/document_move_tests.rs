@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod tests {
+    use crate::routes::documents::normalize_target_folder;
+
+    #[test]
+    fn test_normalize_target_folder() {
+        assert_eq!(normalize_target_folder("Invoices/2024/").unwrap(), "/Invoices/2024");
+        assert_eq!(normalize_target_folder("  /Invoices//2024  ").unwrap(), "/Invoices/2024");
+        assert_eq!(normalize_target_folder("Scans\\Receipts").unwrap(), "/Scans/Receipts");
+        assert_eq!(normalize_target_folder("./Archive/.").unwrap(), "/Archive");
+        assert_eq!(normalize_target_folder("/").unwrap(), "/");
+        assert_eq!(normalize_target_folder("").unwrap(), "/");
+    }
+
+    #[test]
+    fn test_normalize_target_folder_rejects_parent_references() {
+        assert!(normalize_target_folder("/Invoices/../Private").is_err());
+        assert!(normalize_target_folder("..").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::ocr::document_split::{
+    has_separator_barcode, is_blank_page, split_part_filename, split_segments, SeparatorKind,
+};
+
+fn page(width: usize, height: usize) -> Vec<u8> {
+    vec![255u8; width * height]
+}
+
+fn fill(luma: &mut [u8], width: usize, x: std::ops::Range<usize>, y: std::ops::Range<usize>) {
+    for row in y {
+        for column in x.clone() {
+            luma[row * width + column] = 0;
+        }
+    }
+}
+
+#[test]
+fn test_three_documents_separated_by_blank_pages() {
+    assert_eq!(split_segments(5, &[2, 4]), vec![(1, 1), (3, 3), (5, 5)]);
+    assert_eq!(split_segments(8, &[3, 6]), vec![(1, 2), (4, 5), (7, 8)]);
+}
+
+#[test]
+fn test_separator_runs_and_edges_make_no_empty_documents() {
+    assert_eq!(split_segments(7, &[1, 3, 4, 7]), vec![(2, 2), (5, 6)]);
+    assert_eq!(split_segments(3, &[1, 2, 3]), Vec::<(usize, usize)>::new());
+}
+
+#[test]
+fn test_scan_without_separators_is_one_document() {
+    assert_eq!(split_segments(4, &[]), vec![(1, 4)]);
+}
+
+#[test]
+fn test_white_page_is_blank() {
+    assert!(is_blank_page(&page(100, 140), 100, 140));
+}
+
+#[test]
+fn test_page_with_text_block_is_not_blank() {
+    let mut luma = page(100, 140);
+    fill(&mut luma, 100, 20..80, 30..40);
+    assert!(!is_blank_page(&luma, 100, 140));
+}
+
+#[test]
+fn test_specks_and_edge_shadows_are_ignored() {
+    let mut luma = page(100, 140);
+    // Scanner shadow along the left edge and two dust specks
+    fill(&mut luma, 100, 0..3, 0..140);
+    fill(&mut luma, 100, 50..51, 70..71);
+    fill(&mut luma, 100, 30..31, 90..91);
+    assert!(is_blank_page(&luma, 100, 140));
+}
+
+#[test]
+fn test_separator_barcode_matches_whole_value() {
+    assert!(has_separator_barcode("INVOICE-7\nPATCH-T\n", "PATCH-T"));
+    assert!(!has_separator_barcode("PATCH-T2\n", "PATCH-T"));
+    assert!(!has_separator_barcode("", "PATCH-T"));
+}
+
+#[test]
+fn test_separator_kind_from_settings() {
+    assert_eq!(SeparatorKind::from_settings(None), SeparatorKind::BlankPage);
+    assert_eq!(SeparatorKind::from_settings(Some("  ")), SeparatorKind::BlankPage);
+    assert_eq!(
+        SeparatorKind::from_settings(Some("PATCH-T")),
+        SeparatorKind::Barcode("PATCH-T".to_string())
+    );
+}
+
+#[test]
+fn test_split_part_filename() {
+    assert_eq!(split_part_filename("scan.pdf", 2, 3), "scan (part 2 of 3).pdf");
+    assert_eq!(split_part_filename("batch.2024.PDF", 1, 2), "batch.2024 (part 1 of 2).PDF");
+}
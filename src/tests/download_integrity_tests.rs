@@ -0,0 +1,137 @@
+use crate::models::{ChecksumVerification, DownloadVerification, WebDAVSourceConfig};
+use crate::services::download_integrity::{
+    checksum_from_s3_etag, checksums_from_headers, judge, verify, ChecksumAlgorithm, ChecksumCheck, ChecksumHasher,
+    DownloadVerdict,
+};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde_json::json;
+
+const CONTENT: &[u8] = b"hello world";
+const MD5_HEX: &str = "5eb63bbbe01eeed093cb22bb8f5acdc3";
+const SHA1_HEX: &str = "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed";
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.append(*name, HeaderValue::from_str(value).unwrap());
+    }
+    headers
+}
+
+#[test]
+fn test_nextcloud_checksum_header_is_parsed() {
+    let value = format!("SHA1:{} MD5:{} ADLER32:1a0b045d", SHA1_HEX, MD5_HEX);
+    let checksums = checksums_from_headers(&headers(&[("OC-Checksum", &value)]));
+
+    let algorithms: Vec<_> = checksums.iter().map(|checksum| checksum.algorithm).collect();
+    assert_eq!(algorithms, vec![ChecksumAlgorithm::Sha1, ChecksumAlgorithm::Md5, ChecksumAlgorithm::Adler32]);
+    assert!(checksums.iter().all(|checksum| checksum.source == "OC-Checksum"));
+    assert_eq!(verify(CONTENT, checksums), ChecksumCheck::Verified(ChecksumAlgorithm::Sha1));
+}
+
+#[test]
+fn test_content_md5_and_digest_headers_are_parsed() {
+    let checksums = checksums_from_headers(&headers(&[
+        ("Content-MD5", "XrY7u+Ae7tCTyyK7j1rNww=="),
+        ("Digest", "sha-256=uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek=, unixsum=1234"),
+    ]));
+
+    assert_eq!(checksums.len(), 2);
+    assert_eq!(checksums[0].source, "Content-MD5");
+    assert_eq!(checksums[1].algorithm, ChecksumAlgorithm::Sha256);
+    assert_eq!(verify(CONTENT, checksums), ChecksumCheck::Verified(ChecksumAlgorithm::Sha256));
+}
+
+#[test]
+fn test_unparseable_checksums_are_ignored() {
+    let checksums = checksums_from_headers(&headers(&[
+        ("OC-Checksum", "SHA1:not-hex CRC32:1234abcd MD5:5eb63b"),
+        ("Content-MD5", "???"),
+        ("Digest", "md5"),
+    ]));
+    assert!(checksums.is_empty());
+    assert_eq!(verify(CONTENT, checksums), ChecksumCheck::Unavailable);
+}
+
+#[test]
+fn test_mismatch_reports_the_strongest_algorithm() {
+    // The MD5 matches, the SHA-1 (of other content) doesn't
+    let value = format!("MD5:{} SHA1:{}", MD5_HEX, "0".repeat(40));
+    let checksums = checksums_from_headers(&headers(&[("OC-Checksum", &value)]));
+
+    match verify(CONTENT, checksums) {
+        ChecksumCheck::Mismatch { algorithm, source, expected, actual } => {
+            assert_eq!(algorithm, ChecksumAlgorithm::Sha1);
+            assert_eq!(source, "OC-Checksum");
+            assert_eq!(expected, "0".repeat(40));
+            assert_eq!(actual, SHA1_HEX);
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_hashing_in_chunks_matches_hashing_at_once() {
+    let data: Vec<u8> = (0..=255u8).cycle().take(25_600).collect();
+    let checksums = checksums_from_headers(&headers(&[("OC-Checksum", "ADLER32:747fd0e0")]));
+
+    let mut hasher = ChecksumHasher::new(checksums.clone());
+    for chunk in data.chunks(1000) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hasher.finish(), ChecksumCheck::Verified(ChecksumAlgorithm::Adler32));
+    assert_eq!(verify(&data, checksums), ChecksumCheck::Verified(ChecksumAlgorithm::Adler32));
+}
+
+#[test]
+fn test_only_single_part_unencrypted_s3_etags_are_md5s() {
+    let etag = format!("\"{}\"", MD5_HEX);
+    let checksum = checksum_from_s3_etag(&etag, false).expect("ETag is an MD5");
+    assert_eq!(checksum.algorithm, ChecksumAlgorithm::Md5);
+    assert_eq!(checksum.source, "ETag");
+
+    assert!(checksum_from_s3_etag(&etag, true).is_none());
+    assert!(checksum_from_s3_etag("\"5eb63bbbe01eeed093cb22bb8f5acdc3-4\"", false).is_none());
+    assert!(checksum_from_s3_etag("\"v1\"", false).is_none());
+}
+
+#[test]
+fn test_strictness_decides_what_happens_to_a_download() {
+    let mismatch = verify(CONTENT, checksum_from_s3_etag(&"0".repeat(32), false).into_iter().collect());
+
+    assert_eq!(judge(ChecksumVerification::Off, "a.pdf", &mismatch), DownloadVerdict::Keep);
+    assert_eq!(judge(ChecksumVerification::Warn, "a.pdf", &mismatch), DownloadVerdict::Keep);
+    match judge(ChecksumVerification::Verify, "a.pdf", &mismatch) {
+        DownloadVerdict::Retry(reason) => {
+            assert!(reason.contains("Checksum mismatch downloading 'a.pdf'"), "{}", reason);
+            assert!(reason.contains("ETag"), "{}", reason);
+        }
+        other => panic!("expected a retry, got {:?}", other),
+    }
+
+    let unavailable = ChecksumCheck::Unavailable;
+    assert_eq!(judge(ChecksumVerification::Verify, "a.pdf", &unavailable), DownloadVerdict::Keep);
+    assert!(matches!(judge(ChecksumVerification::Require, "a.pdf", &unavailable), DownloadVerdict::Reject(_)));
+}
+
+#[test]
+fn test_source_config_defaults_to_verifying() {
+    let config: WebDAVSourceConfig = serde_json::from_value(json!({
+        "server_url": "https://cloud.example.com",
+        "username": "user",
+        "password": "pass",
+        "watch_folders": ["/Documents"],
+        "file_extensions": ["pdf"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "server_type": "nextcloud"
+    }))
+    .unwrap();
+    assert_eq!(config.download_verification, DownloadVerification::default());
+    assert_eq!(config.download_verification.checksums, ChecksumVerification::Verify);
+
+    let verification: DownloadVerification =
+        serde_json::from_value(json!({"checksums": "require", "retries": 11})).unwrap();
+    assert_eq!(verification.checksums, ChecksumVerification::Require);
+    assert!(verification.validate().is_err());
+}
@@ -0,0 +1,100 @@
+use sha2::{Digest, Sha256};
+
+use crate::ingestion::spool::{DownloadSink, DownloadedFile, SpoolPolicy};
+
+const MIB: usize = 1024 * 1024;
+
+async fn collect(policy: &SpoolPolicy, advertised_size: Option<u64>, data: &[u8]) -> DownloadedFile {
+    let mut sink = DownloadSink::new(policy, advertised_size).await.unwrap();
+    for chunk in data.chunks(64 * 1024) {
+        sink.write(chunk).await.unwrap();
+    }
+    sink.finish().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_download_below_threshold_stays_in_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy = SpoolPolicy::new(1, dir.path().to_path_buf());
+    let data = vec![7u8; MIB];
+
+    match collect(&policy, Some(data.len() as u64), &data).await {
+        DownloadedFile::InMemory(buffered) => assert_eq!(buffered, data),
+        DownloadedFile::Spooled(_) => panic!("download at the threshold should not be spooled"),
+    }
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn test_download_crossing_threshold_is_spooled_to_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy = SpoolPolicy::new(1, dir.path().to_path_buf());
+    let data: Vec<u8> = (0..3 * MIB).map(|i| (i % 253) as u8).collect();
+
+    // No advertised size, so the sink only switches to disk once the buffer is too big
+    let file = match collect(&policy, None, &data).await {
+        DownloadedFile::Spooled(file) => file,
+        DownloadedFile::InMemory(_) => panic!("download over the threshold should be spooled"),
+    };
+
+    assert_eq!(file.size(), data.len() as u64);
+    assert_eq!(file.sha256(), format!("{:x}", Sha256::digest(&data)));
+    assert_eq!(std::fs::read(file.path()).unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_advertised_size_over_threshold_spools_immediately() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy = SpoolPolicy::new(1, dir.path().to_path_buf());
+
+    let mut sink = DownloadSink::new(&policy, Some(2 * MIB as u64)).await.unwrap();
+    sink.write(b"first chunk").await.unwrap();
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    match sink.finish().await.unwrap() {
+        DownloadedFile::Spooled(file) => assert_eq!(file.size(), 11),
+        DownloadedFile::InMemory(_) => panic!("advertised size over the threshold should spool"),
+    }
+}
+
+#[tokio::test]
+async fn test_zero_threshold_never_spools() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy = SpoolPolicy::new(0, dir.path().to_path_buf());
+    let data = vec![1u8; 2 * MIB];
+
+    assert!(matches!(
+        collect(&policy, Some(data.len() as u64), &data).await,
+        DownloadedFile::InMemory(_)
+    ));
+    assert!(!SpoolPolicy::in_memory().should_spool(u64::MAX));
+}
+
+#[tokio::test]
+async fn test_spooled_file_is_removed_on_drop() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy = SpoolPolicy::new(1, dir.path().to_path_buf());
+    let data = vec![9u8; 2 * MIB];
+
+    let download = collect(&policy, None, &data).await;
+    let path = match &download {
+        DownloadedFile::Spooled(file) => file.path().to_path_buf(),
+        DownloadedFile::InMemory(_) => panic!("download over the threshold should be spooled"),
+    };
+    assert!(path.exists());
+
+    drop(download);
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_unfinished_spool_is_removed_on_drop() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy = SpoolPolicy::new(1, dir.path().to_path_buf());
+
+    let mut sink = DownloadSink::new(&policy, Some(2 * MIB as u64)).await.unwrap();
+    sink.write(&[0u8; 1024]).await.unwrap();
+    drop(sink);
+
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
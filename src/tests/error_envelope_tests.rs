@@ -0,0 +1,176 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tower::util::ServiceExt;
+
+use crate::error_envelope::{
+    error_envelope, rejection_field_errors, structured_errors, ApiErrorFormat, ApiErrorSettings, FieldError,
+};
+use crate::errors::ApiError;
+
+#[derive(Deserialize)]
+struct CreateThing {
+    name: String,
+    count: u32,
+}
+
+fn app(format: ApiErrorFormat, expose_details: bool) -> Router {
+    Router::new()
+        .route("/missing", get(|| async { StatusCode::NOT_FOUND }))
+        .route("/ok", get(|| async { "fine" }))
+        .route("/things", post(|Json(thing): Json<CreateThing>| async move { format!("{} x{}", thing.name, thing.count) }))
+        .route("/row", get(|| async { Err::<(), _>(ApiError::Database(sqlx::Error::RowNotFound)) }))
+        .route("/pool", get(|| async { Err::<(), _>(ApiError::Database(sqlx::Error::PoolTimedOut)) }))
+        .route("/internal", get(|| async { Err::<(), _>(ApiError::internal_server_error("relation \"documents\" is locked")) }))
+        .route("/field", get(|| async { Err::<(), _>(ApiError::invalid_field("ocr_dpi", "DPI must be between 72 and 1200")) }))
+        .layer(axum::middleware::from_fn_with_state(
+            ApiErrorSettings { format, expose_details },
+            structured_errors,
+        ))
+}
+
+async fn send(app: Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn get_error(path: &str, expose_details: bool) -> (StatusCode, Value) {
+    let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+    send(app(ApiErrorFormat::Structured, expose_details), request).await
+}
+
+async fn post_json(body: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/things")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    send(app(ApiErrorFormat::Structured, false), request).await
+}
+
+#[test]
+fn test_api_error_format_parsing() {
+    assert_eq!("structured".parse::<ApiErrorFormat>(), Ok(ApiErrorFormat::Structured));
+    assert_eq!(" Legacy ".parse::<ApiErrorFormat>(), Ok(ApiErrorFormat::Legacy));
+    assert!("xml".parse::<ApiErrorFormat>().is_err());
+}
+
+#[test]
+fn test_rejection_field_errors() {
+    assert_eq!(
+        rejection_field_errors("Failed to deserialize the JSON body into the target type: missing field `name` at line 1 column 2"),
+        vec![FieldError::new("name", "missing field `name`")]
+    );
+    assert_eq!(
+        rejection_field_errors(
+            "Failed to deserialize the JSON body into the target type: count: invalid type: string \"x\", expected u32 at line 1 column 24"
+        ),
+        vec![FieldError::new("count", "invalid type: string \"x\", expected u32")]
+    );
+    assert_eq!(
+        rejection_field_errors("Failed to deserialize the JSON body into the target type: config: missing field `watch_folders` at line 1 column 40"),
+        vec![FieldError::new("config.watch_folders", "missing field `watch_folders`")]
+    );
+    assert_eq!(
+        rejection_field_errors("Failed to deserialize query string: per_page: invalid digit found in string"),
+        vec![FieldError::new("per_page", "invalid digit found in string")]
+    );
+    assert!(rejection_field_errors("Failed to parse the request body as JSON: EOF while parsing").is_empty());
+}
+
+#[test]
+fn test_legacy_bodies_are_carried_over() {
+    let body = json!({"error": "Source not found", "code": "SOURCE_NOT_FOUND", "status": 404, "source_id": "abc"});
+    let envelope = error_envelope(StatusCode::NOT_FOUND, body.to_string().as_bytes(), None, false, Some("req-1".to_string()));
+    assert_eq!(envelope.error.code, "SOURCE_NOT_FOUND");
+    assert_eq!(envelope.error.message, "Source not found");
+    assert_eq!(envelope.error.details, Some(json!({"source_id": "abc"})));
+    assert_eq!(envelope.error.request_id.as_deref(), Some("req-1"));
+
+    // error_code is the older spelling of code
+    let body = json!({"error": "Too large", "error_code": "UPLOAD_TOO_LARGE", "timestamp": "2026-01-01T00:00:00Z"});
+    let envelope = error_envelope(StatusCode::PAYLOAD_TOO_LARGE, body.to_string().as_bytes(), None, false, None);
+    assert_eq!(envelope.error.code, "UPLOAD_TOO_LARGE");
+    assert_eq!(envelope.error.details, None);
+}
+
+#[test]
+fn test_server_error_internals_are_hidden_unless_exposed() {
+    let body = json!({"error": "Database error: connection reset", "code": "DATABASE_ERROR", "query": "SELECT 1"});
+    let hidden = error_envelope(StatusCode::INTERNAL_SERVER_ERROR, body.to_string().as_bytes(), Some("pool reset"), false, None);
+    assert_eq!(hidden.error.code, "DATABASE_ERROR");
+    assert_eq!(hidden.error.message, "An internal error occurred");
+    assert_eq!(hidden.error.details, None);
+
+    let exposed = error_envelope(StatusCode::INTERNAL_SERVER_ERROR, body.to_string().as_bytes(), Some("pool reset"), true, None);
+    assert_eq!(exposed.error.message, "Database error: connection reset");
+    assert_eq!(exposed.error.details, Some(json!({"query": "SELECT 1", "internal": "pool reset"})));
+}
+
+#[tokio::test]
+async fn test_bare_status_codes_get_an_envelope() {
+    let (status, body) = get_error("/missing", false).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body, json!({"error": {"code": "NOT_FOUND", "message": "Not Found", "details": null, "request_id": null}}));
+}
+
+#[tokio::test]
+async fn test_rejected_json_fields_are_listed() {
+    let (status, body) = post_json(r#"{"count": 3}"#).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+    assert_eq!(body["error"]["details"]["fields"], json!([{"field": "name", "message": "missing field `name`"}]));
+
+    let (status, body) = post_json(r#"{"name": "a", "count": "many"}"#).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["details"]["fields"][0]["field"], "count");
+}
+
+#[tokio::test]
+async fn test_internal_errors_map_to_statuses_and_codes() {
+    let (status, body) = get_error("/row", false).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+
+    let (status, body) = get_error("/pool", false).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"]["code"], "DATABASE_UNAVAILABLE");
+    assert_eq!(body["error"]["details"], Value::Null);
+
+    let (status, body) = get_error("/field", false).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+    assert_eq!(body["error"]["details"]["fields"], json!([{"field": "ocr_dpi", "message": "DPI must be between 72 and 1200"}]));
+}
+
+#[tokio::test]
+async fn test_internal_details_are_only_shown_when_exposed() {
+    let (_, hidden) = get_error("/internal", false).await;
+    assert_eq!(hidden["error"]["message"], "An internal error occurred");
+    assert_eq!(hidden["error"]["details"], Value::Null);
+
+    let (_, exposed) = get_error("/internal", true).await;
+    assert_eq!(exposed["error"]["details"]["internal"], "Internal server error: relation \"documents\" is locked");
+}
+
+#[tokio::test]
+async fn test_legacy_format_and_successes_are_untouched() {
+    let request = Request::builder().uri("/field").body(Body::empty()).unwrap();
+    let (status, body) = send(app(ApiErrorFormat::Legacy, false), request).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "DPI must be between 72 and 1200");
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+
+    let request = Request::builder().uri("/ok").body(Body::empty()).unwrap();
+    let response = app(ApiErrorFormat::Structured, false).oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"fine");
+}
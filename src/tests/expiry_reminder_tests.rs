@@ -0,0 +1,53 @@
+use crate::models::ExpiringDocument;
+use crate::services::expiry_reminders::{build_expiry_reminder, days_until_expiry};
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn expiring(filename: &str, expiry_date: NaiveDate) -> ExpiringDocument {
+    ExpiringDocument {
+        document_id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        filename: filename.to_string(),
+        expiry_date,
+        reminded: false,
+    }
+}
+
+#[test]
+fn test_days_until_expiry() {
+    let today = date(2026, 2, 20);
+    assert_eq!(days_until_expiry(date(2026, 2, 20), today), 0);
+    assert_eq!(days_until_expiry(date(2026, 3, 2), today), 10);
+    assert_eq!(days_until_expiry(date(2026, 2, 19), today), -1);
+}
+
+#[test]
+fn test_reminder_names_the_document_and_links_to_it() {
+    let document = expiring("passport.pdf", date(2026, 3, 2));
+    let reminder = build_expiry_reminder(&document, date(2026, 2, 20));
+
+    assert_eq!(reminder.notification_type, "warning");
+    assert_eq!(reminder.title, "Document expiring soon");
+    assert_eq!(reminder.message, "passport.pdf expires in 10 days (2026-03-02)");
+    assert_eq!(reminder.action_url, Some(format!("/documents/{}", document.document_id)));
+
+    let metadata = reminder.metadata.unwrap();
+    assert_eq!(metadata["document_id"], document.document_id.to_string());
+    assert_eq!(metadata["expiry_date"], "2026-03-02");
+    assert_eq!(metadata["days_until_expiry"], 10);
+}
+
+#[test]
+fn test_reminder_wording_close_to_expiry() {
+    let document = expiring("lease.pdf", date(2026, 2, 21));
+
+    let tomorrow = build_expiry_reminder(&document, date(2026, 2, 20));
+    assert_eq!(tomorrow.message, "lease.pdf expires tomorrow (2026-02-21)");
+
+    let today = build_expiry_reminder(&document, date(2026, 2, 21));
+    assert_eq!(today.message, "lease.pdf expires today (2026-02-21)");
+}
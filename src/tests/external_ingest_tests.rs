@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use crate::routes::documents::parse_ingest_records;
+
+    #[test]
+    fn test_parse_ingest_records_validates_each_line_independently() {
+        let payload = r#"{"filename": "invoice.pdf", "text": "Invoice 42", "metadata": {"system": "erp"}}
+
+not json
+{"filename": "scan.pdf", "text_blob": "scan-text", "file_blob": "scan-file"}
+{"filename": "   ", "text": "no name"}
+{"filename": "empty.pdf"}
+{"filename": "both.pdf", "text": "a", "text_blob": "b"}
+{"filename": "meta.pdf", "text": "a", "metadata": ["not", "an", "object"]}"#;
+
+        let records = parse_ingest_records(payload);
+
+        // Blank lines are skipped but line numbers still match the payload
+        let lines: Vec<usize> = records.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![1, 3, 4, 5, 6, 7, 8]);
+
+        let first = records[0].1.as_ref().expect("first record should be valid");
+        assert_eq!(first.filename, "invoice.pdf");
+        assert_eq!(first.text.as_deref(), Some("Invoice 42"));
+
+        assert!(records[1].1.as_ref().unwrap_err().contains("Invalid JSON"));
+
+        let blob_record = records[2].1.as_ref().expect("blob record should be valid");
+        assert_eq!(blob_record.text_blob.as_deref(), Some("scan-text"));
+        assert_eq!(blob_record.file_blob.as_deref(), Some("scan-file"));
+
+        assert!(records[3].1.as_ref().unwrap_err().contains("filename"));
+        assert!(records[4].1.as_ref().unwrap_err().contains("text or text_blob"));
+        assert!(records[5].1.as_ref().unwrap_err().contains("not both"));
+        assert!(records[6].1.as_ref().unwrap_err().contains("metadata"));
+    }
+
+    #[test]
+    fn test_parse_ingest_records_empty_payload() {
+        assert!(parse_ingest_records("\n  \n").is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use crate::models::{Settings, UpdateSettings};
+use crate::ocr::fallback_order::{FallbackConfig, FallbackStrategy};
+use crate::ocr::fallback_stats::FallbackStats;
+use crate::ocr::xml_extractor::XmlOfficeExtractor;
+
+fn order(tools: &[&str]) -> Vec<String> {
+    tools.iter().map(|tool| tool.to_string()).collect()
+}
+
+#[test]
+fn test_tool_order_is_parsed_and_validated() {
+    let doc = FallbackStrategy::LegacyDoc;
+    assert_eq!(doc.parse_order("catdoc, ANTIWORD"), Ok(order(&["catdoc", "antiword"])));
+    assert_eq!(doc.parse_order("wvtext,catdoc,antiword,"), Ok(order(&["wvText", "catdoc", "antiword"])));
+
+    assert!(doc.parse_order("catdoc,libreoffice").unwrap_err().contains("unknown method 'libreoffice'"));
+    assert!(doc.parse_order("catdoc,catdoc").unwrap_err().contains("listed twice"));
+    assert!(doc.parse_order(" , ").is_err());
+}
+
+#[test]
+fn test_each_strategy_only_accepts_its_own_methods() {
+    assert_eq!(FallbackStrategy::Pdf.parse_order("OCRmyPDF,pdftotext"), Ok(order(&["ocrmypdf", "pdftotext"])));
+    assert!(FallbackStrategy::Pdf.parse_order("pdftotext,antiword").is_err());
+    assert!(FallbackStrategy::LegacyDoc.parse_order("ocrmypdf").is_err());
+}
+
+#[test]
+fn test_custom_order_is_respected() {
+    let fallback = FallbackConfig {
+        doc_tool_order: order(&["wvText", "catdoc"]),
+        ..FallbackConfig::default()
+    };
+    let extractor = XmlOfficeExtractor::new("/tmp".to_string()).with_fallback_config(fallback);
+    assert_eq!(extractor.doc_tool_order(), order(&["wvText", "catdoc"]));
+
+    assert_eq!(
+        XmlOfficeExtractor::new("/tmp".to_string()).doc_tool_order(),
+        order(&["antiword", "catdoc", "wvText"])
+    );
+}
+
+#[test]
+fn test_most_successful_tools_are_tried_first_when_reordering() {
+    let stats = FallbackStats::default();
+    stats.record("antiword", false, Duration::from_millis(2));
+    stats.record("catdoc", true, Duration::from_millis(30));
+    stats.record("catdoc", true, Duration::from_millis(30));
+    stats.record("wvText", true, Duration::from_millis(50));
+
+    let mut fallback = FallbackConfig::default();
+    assert_eq!(fallback.effective_order(FallbackStrategy::LegacyDoc, &stats.snapshot()), order(&["antiword", "catdoc", "wvText"]));
+
+    fallback.reorder_by_success = true;
+    assert_eq!(fallback.effective_order(FallbackStrategy::LegacyDoc, &stats.snapshot()), order(&["catdoc", "wvText", "antiword"]));
+
+    // Without a track record the configured order stands
+    assert_eq!(fallback.effective_order(FallbackStrategy::LegacyDoc, &FallbackStats::default().snapshot()), order(&["antiword", "catdoc", "wvText"]));
+}
+
+#[test]
+fn test_user_settings_override_the_server_order() {
+    let server = FallbackConfig {
+        pdf_method_order: order(&["pdftoppm", "ocrmypdf"]),
+        ..FallbackConfig::default()
+    };
+
+    // Without overrides the server order stands
+    assert_eq!(server.clone().with_settings(&Settings::default()), server);
+
+    let settings = Settings {
+        doc_extraction_order: Some("catdoc".to_string()),
+        pdf_extraction_order: Some("ocrmypdf,pdftotext".to_string()),
+        extraction_auto_order: Some(true),
+        ..Settings::default()
+    };
+    let user = server.clone().with_settings(&settings);
+    assert_eq!(user.order(FallbackStrategy::LegacyDoc), order(&["catdoc"]));
+    assert_eq!(user.order(FallbackStrategy::Pdf), order(&["ocrmypdf", "pdftotext"]));
+    assert!(user.reorder_by_success);
+
+    // An order saved before a method was dropped is ignored rather than failing extraction
+    let stale = Settings { pdf_extraction_order: Some("tesseract".to_string()), ..Settings::default() };
+    assert_eq!(server.clone().with_settings(&stale).order(FallbackStrategy::Pdf), server.order(FallbackStrategy::Pdf));
+}
+
+#[test]
+fn test_pdf_methods_are_reordered_by_their_own_successes() {
+    let stats = FallbackStats::default();
+    stats.record("ocrmypdf", true, Duration::from_millis(900));
+    stats.record("pdftotext", false, Duration::from_millis(20));
+
+    let fallback = FallbackConfig { reorder_by_success: true, ..FallbackConfig::default() };
+    assert_eq!(
+        fallback.effective_order(FallbackStrategy::Pdf, &stats.snapshot()),
+        order(&["ocrmypdf", "pdftotext", "pdftoppm"])
+    );
+    assert_eq!(
+        fallback.effective_order(FallbackStrategy::LegacyDoc, &stats.snapshot()),
+        order(&["antiword", "catdoc", "wvText"])
+    );
+}
+
+#[test]
+fn test_null_clears_an_order_and_a_missing_field_keeps_it() {
+    let cleared: UpdateSettings = serde_json::from_value(serde_json::json!({ "pdf_extraction_order": null })).unwrap();
+    assert_eq!(cleared.pdf_extraction_order, Some(None));
+    assert_eq!(cleared.doc_extraction_order, None);
+
+    let set: UpdateSettings = serde_json::from_value(serde_json::json!({ "extraction_auto_order": true })).unwrap();
+    assert_eq!(set.extraction_auto_order, Some(Some(true)));
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use crate::ocr::fallback_stats::{ExtractionMethodStats, FallbackStats};
+
+#[test]
+fn test_attempts_are_counted_per_method() {
+    let stats = FallbackStats::default();
+    stats.record("antiword", false, Duration::from_millis(4));
+    stats.record("catdoc", true, Duration::from_millis(40));
+    stats.record("antiword", false, Duration::from_millis(6));
+    stats.record("catdoc", true, Duration::from_millis(20));
+    stats.record("catdoc", false, Duration::from_millis(90));
+
+    assert_eq!(
+        stats.snapshot().methods,
+        vec![
+            ExtractionMethodStats {
+                method: "antiword".to_string(),
+                successes: 0,
+                failures: 2,
+                avg_processing_time_ms: Some(5.0),
+            },
+            ExtractionMethodStats {
+                method: "catdoc".to_string(),
+                successes: 2,
+                failures: 1,
+                avg_processing_time_ms: Some(50.0),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_reset_clears_counts_and_restarts_the_window() {
+    let stats = FallbackStats::default();
+    stats.record("docx", true, Duration::from_millis(10));
+    let before = stats.snapshot();
+
+    stats.reset();
+    let after = stats.snapshot();
+    assert!(after.methods.is_empty());
+    assert!(after.since >= before.since);
+
+    stats.record("docx", false, Duration::from_millis(30));
+    assert_eq!(stats.snapshot().methods[0].failures, 1);
+    assert_eq!(stats.snapshot().methods[0].successes, 0);
+}
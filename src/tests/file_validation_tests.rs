@@ -0,0 +1,151 @@
+use crate::services::file_validator::{FileIntegrity, FileValidationMode, FileValidator};
+use std::io::Write;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+const DOCX_MIME: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+fn sample_pdf() -> Vec<u8> {
+    b"%PDF-1.4\n\
+      1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+      2 0 obj << /Type /Pages /Kids [3 0 R] /Count 1 >> endobj\n\
+      3 0 obj << /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >> endobj\n\
+      xref\n0 4\n0000000000 65535 f \n0000000009 00000 n \n0000000058 00000 n \n0000000115 00000 n \n\
+      trailer << /Size 4 /Root 1 0 R >>\nstartxref\n186\n%%EOF\n"
+        .to_vec()
+}
+
+fn sample_docx() -> Vec<u8> {
+    let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    writer.start_file("[Content_Types].xml", SimpleFileOptions::default()).unwrap();
+    writer.write_all(b"<Types/>").unwrap();
+    writer.start_file("word/document.xml", SimpleFileOptions::default()).unwrap();
+    writer.write_all(b"<w:document><w:body>Quarterly report</w:body></w:document>").unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+fn assert_corrupt(integrity: FileIntegrity, expected: &str) {
+    match integrity {
+        FileIntegrity::Corrupt(reason) => assert!(reason.contains(expected), "unexpected reason: {}", reason),
+        FileIntegrity::Valid => panic!("Expected the file to be reported as corrupt ({})", expected),
+    }
+}
+
+#[test]
+fn test_parse_validation_mode() {
+    assert_eq!("off".parse::<FileValidationMode>().unwrap(), FileValidationMode::Off);
+    assert_eq!(" Lenient ".parse::<FileValidationMode>().unwrap(), FileValidationMode::Lenient);
+    assert_eq!("STRICT".parse::<FileValidationMode>().unwrap(), FileValidationMode::Strict);
+    assert!("paranoid".parse::<FileValidationMode>().is_err());
+    assert_eq!(FileValidationMode::default(), FileValidationMode::Lenient);
+}
+
+#[test]
+fn test_complete_pdf_is_valid() {
+    for mode in [FileValidationMode::Lenient, FileValidationMode::Strict] {
+        assert_eq!(FileValidator::new(mode).check(&sample_pdf(), "application/pdf"), FileIntegrity::Valid);
+    }
+}
+
+#[test]
+fn test_truncated_pdf_is_corrupt() {
+    let pdf = sample_pdf();
+    let truncated = &pdf[..pdf.len() / 2];
+
+    let validator = FileValidator::new(FileValidationMode::Lenient);
+    assert_corrupt(validator.check(truncated, "application/pdf"), "%%EOF");
+    assert_corrupt(validator.check(b"<html>Not found</html>", "application/pdf"), "PDF header");
+    assert_corrupt(validator.check(b"", "application/pdf"), "empty");
+}
+
+#[test]
+fn test_strict_mode_requires_startxref() {
+    let pdf = b"%PDF-1.4\n1 0 obj << >> endobj\n%%EOF\n";
+    assert_eq!(FileValidator::new(FileValidationMode::Lenient).check(pdf, "application/pdf"), FileIntegrity::Valid);
+    assert_corrupt(FileValidator::new(FileValidationMode::Strict).check(pdf, "application/pdf"), "startxref");
+}
+
+#[test]
+fn test_off_mode_and_unchecked_types_are_valid() {
+    let pdf = sample_pdf();
+    let truncated = &pdf[..pdf.len() / 2];
+    assert_eq!(FileValidator::new(FileValidationMode::Off).check(truncated, "application/pdf"), FileIntegrity::Valid);
+
+    let validator = FileValidator::new(FileValidationMode::Strict);
+    assert_eq!(validator.check(b"plain text", "text/plain"), FileIntegrity::Valid);
+    assert_eq!(validator.check(truncated, "application/octet-stream"), FileIntegrity::Valid);
+}
+
+#[test]
+fn test_truncated_docx_is_corrupt() {
+    let docx = sample_docx();
+    let validator = FileValidator::new(FileValidationMode::Lenient);
+    assert_eq!(validator.check(&docx, DOCX_MIME), FileIntegrity::Valid);
+    assert_corrupt(validator.check(&docx[..docx.len() - 30], DOCX_MIME), "end of central directory");
+}
+
+#[test]
+fn test_strict_mode_reads_the_zip_central_directory() {
+    let mut docx = sample_docx();
+    // Break the first central directory entry while keeping the end record intact
+    let entry = docx.windows(4).position(|window| window == b"PK\x01\x02").unwrap();
+    docx[entry..entry + 4].copy_from_slice(b"XXXX");
+
+    assert_eq!(FileValidator::new(FileValidationMode::Lenient).check(&docx, DOCX_MIME), FileIntegrity::Valid);
+    assert_corrupt(FileValidator::new(FileValidationMode::Strict).check(&docx, DOCX_MIME), "ZIP");
+}
+
+#[tokio::test]
+async fn test_check_file_reads_from_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let pdf = sample_pdf();
+    let complete = dir.path().join("complete.pdf");
+    let truncated = dir.path().join("truncated.pdf");
+    std::fs::write(&complete, &pdf).unwrap();
+    std::fs::write(&truncated, &pdf[..pdf.len() - 10]).unwrap();
+
+    let validator = FileValidator::new(FileValidationMode::Strict);
+    assert_eq!(validator.check_file(&complete, "application/pdf").await, FileIntegrity::Valid);
+    assert_corrupt(validator.check_file(&truncated, "application/pdf").await, "%%EOF");
+}
+
+#[cfg(feature = "ocr")]
+mod images {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+
+    fn sample_png() -> Vec<u8> {
+        let image = RgbImage::from_fn(32, 32, |x, y| Rgb([(x * 8) as u8, (y * 8) as u8, 128]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, ImageFormat::Png).unwrap();
+        png.into_inner()
+    }
+
+    #[test]
+    fn test_complete_png_is_valid() {
+        for mode in [FileValidationMode::Lenient, FileValidationMode::Strict] {
+            assert_eq!(FileValidator::new(mode).check(&sample_png(), "image/png"), FileIntegrity::Valid);
+        }
+    }
+
+    #[test]
+    fn test_truncated_png_is_corrupt() {
+        let png = sample_png();
+        let validator = FileValidator::new(FileValidationMode::Lenient);
+        assert_corrupt(validator.check(&png[..png.len() / 2], "image/png"), "end marker");
+        assert_corrupt(validator.check(&png[8..], "image/png"), "PNG signature");
+    }
+
+    #[test]
+    fn test_corrupt_png_data_is_caught_in_strict_mode() {
+        let mut png = sample_png();
+        // Flip bytes inside the image data; signature and IEND stay intact
+        let idat = png.windows(4).position(|window| window == b"IDAT").unwrap();
+        for byte in &mut png[idat + 6..idat + 14] {
+            *byte ^= 0xff;
+        }
+
+        assert_eq!(FileValidator::new(FileValidationMode::Lenient).check(&png, "image/png"), FileIntegrity::Valid);
+        assert_corrupt(FileValidator::new(FileValidationMode::Strict).check(&png, "image/png"), "could not be decoded");
+    }
+}
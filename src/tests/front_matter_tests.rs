@@ -0,0 +1,84 @@
+use serde_json::json;
+
+use crate::ocr::front_matter::{is_front_matter_mime, split_front_matter};
+
+const NOTE: &str = "---\n\
+title: Q3 planning\n\
+date: 2026-07-01\n\
+tags: [project-alpha, \"#meeting\", 'q3 planning']\n\
+reviewed: true\n\
+attendees: 4\n\
+---\n\
+\n\
+Decided to move the launch to September.\n";
+
+#[test]
+fn test_splits_front_matter_from_the_body() {
+    let (front_matter, body) = split_front_matter(NOTE).expect("front-matter");
+    assert_eq!(body, "Decided to move the launch to September.\n");
+    assert_eq!(front_matter.fields["title"], json!("Q3 planning"));
+    assert_eq!(front_matter.fields["date"], json!("2026-07-01"));
+    assert_eq!(front_matter.fields["reviewed"], json!(true));
+    assert_eq!(front_matter.fields["attendees"], json!(4));
+    assert_eq!(front_matter.fields["tags"], json!(["project-alpha", "#meeting", "q3 planning"]));
+    assert_eq!(front_matter.tags, vec!["project-alpha", "meeting", "q3 planning"]);
+}
+
+#[test]
+fn test_block_lists_and_string_tags() {
+    let text = "---\r\ntags:\r\n  - inbox\r\n  - Inbox\r\n  - \"#todo\"\r\nkeywords: invoices, tax\r\n...\r\nBody";
+    let (front_matter, body) = split_front_matter(text).expect("front-matter");
+    assert_eq!(body, "Body");
+    assert_eq!(front_matter.tags, vec!["inbox", "todo", "invoices", "tax"]);
+
+    let (front_matter, _) = split_front_matter("---\ntags: travel #work\n---\nBody").expect("front-matter");
+    assert_eq!(front_matter.tags, vec!["travel"]);
+
+    let (front_matter, _) = split_front_matter("---\ntags: travel work\n---\nBody").expect("front-matter");
+    assert_eq!(front_matter.tags, vec!["travel", "work"]);
+}
+
+#[test]
+fn test_empty_values_and_comments() {
+    let text = "\u{feff}---\n# exported by obsidian\nalias:\nsource: ~\nurl: https://example.com/a#b\n---\nBody";
+    let (front_matter, body) = split_front_matter(text).expect("front-matter");
+    assert_eq!(body, "Body");
+    assert_eq!(front_matter.fields["alias"], json!(null));
+    assert_eq!(front_matter.fields["source"], json!(null));
+    assert_eq!(front_matter.fields["url"], json!("https://example.com/a#b"));
+    assert!(front_matter.tags.is_empty());
+}
+
+#[test]
+fn test_text_without_front_matter_is_left_alone() {
+    // No block at the start
+    assert!(split_front_matter("Meeting notes\n---\ntitle: x\n---\n").is_none());
+    // A horizontal rule followed by prose
+    assert!(split_front_matter("---\nNote to self about the launch: move it\n---\n").is_none());
+    assert!(split_front_matter("---\nJust a paragraph between rules\n---\nMore").is_none());
+    // Never closed
+    assert!(split_front_matter("---\ntitle: Draft\nBody without a closing line").is_none());
+    // Empty block
+    assert!(split_front_matter("---\n---\nBody").is_none());
+}
+
+#[test]
+fn test_nested_maps_are_not_supported() {
+    let text = "---\ntitle: Trip\nlocation:\n  city: Lisbon\n  country: Portugal\n---\nBody";
+    assert!(split_front_matter(text).is_none());
+}
+
+#[test]
+fn test_tags_with_commas_or_overlong_names_are_dropped() {
+    let long = "x".repeat(101);
+    let text = format!("---\ntags: [\"a, b\", ok, {}]\n---\nBody", long);
+    let (front_matter, _) = split_front_matter(&text).expect("front-matter");
+    assert_eq!(front_matter.tags, vec!["ok"]);
+}
+
+#[test]
+fn test_front_matter_mime_types() {
+    assert!(is_front_matter_mime("text/markdown"));
+    assert!(is_front_matter_mime("text/plain"));
+    assert!(!is_front_matter_mime("application/pdf"));
+}
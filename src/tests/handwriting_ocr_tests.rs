@@ -0,0 +1,56 @@
+use crate::ocr::engine::{handwriting_engine_failed_note, EngineRoute, NO_HANDWRITING_ENGINE_NOTE};
+use crate::ocr::handwriting::HandwritingOcrResponse;
+
+#[test]
+fn test_printed_documents_use_tesseract() {
+    assert_eq!(EngineRoute::for_document(false, false), EngineRoute::Tesseract);
+    assert_eq!(EngineRoute::for_document(false, true), EngineRoute::Tesseract);
+}
+
+#[test]
+fn test_handwritten_documents_use_configured_engine() {
+    assert_eq!(EngineRoute::for_document(true, true), EngineRoute::Handwriting);
+}
+
+#[test]
+fn test_handwritten_documents_fall_back_to_tesseract() {
+    assert_eq!(EngineRoute::for_document(true, false), EngineRoute::TesseractForHandwriting);
+}
+
+#[test]
+fn test_response_with_confidence() {
+    let response: HandwritingOcrResponse =
+        serde_json::from_str(r#"{"text": "Dear Anna,\nthank you", "confidence": 87.5}"#).unwrap();
+    let result = response.into_ocr_result(420);
+    assert_eq!(result.text, "Dear Anna,\nthank you");
+    assert_eq!(result.confidence, 87.5);
+    assert_eq!(result.word_count, 4);
+    assert_eq!(result.processing_time_ms, 420);
+    assert!(result.page_segmentation_mode.is_none());
+}
+
+#[test]
+fn test_response_without_confidence() {
+    let response: HandwritingOcrResponse = serde_json::from_str(r#"{"text": "note"}"#).unwrap();
+    assert_eq!(response.into_ocr_result(0).confidence, 100.0);
+}
+
+#[test]
+fn test_response_confidence_is_clamped() {
+    let response: HandwritingOcrResponse =
+        serde_json::from_str(r#"{"text": "note", "confidence": 140}"#).unwrap();
+    assert_eq!(response.into_ocr_result(0).confidence, 100.0);
+}
+
+#[test]
+fn test_response_without_text_is_rejected() {
+    assert!(serde_json::from_str::<HandwritingOcrResponse>(r#"{"confidence": 50}"#).is_err());
+}
+
+#[test]
+fn test_fallback_notes_mention_tesseract() {
+    assert!(NO_HANDWRITING_ENGINE_NOTE.contains("Tesseract"));
+    let note = handwriting_engine_failed_note(&anyhow::anyhow!("HTTP 503"));
+    assert!(note.contains("HTTP 503"));
+    assert!(note.contains("Tesseract"));
+}
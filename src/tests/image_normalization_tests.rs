@@ -0,0 +1,75 @@
+use serde_json::json;
+
+use crate::ingestion::image_normalization::{
+    image_format_name, normalized_filename, original_image_filename, ImageNormalizationConfig, NormalizedImageFormat,
+};
+
+#[test]
+fn test_normalization_is_off_unless_configured() {
+    let normalization = ImageNormalizationConfig::from_source_config(&json!({"watch_folders": ["/scans"]})).unwrap();
+    assert_eq!(normalization, ImageNormalizationConfig::default());
+    assert!(!normalization.converts("image/bmp"));
+}
+
+#[test]
+fn test_configured_types_are_converted_and_kept_ones_are_not() {
+    let normalization =
+        ImageNormalizationConfig::from_source_config(&json!({"image_normalization": {"enabled": true}})).unwrap();
+    assert_eq!(normalization.format, NormalizedImageFormat::Png);
+    assert!(normalization.converts("image/bmp"));
+    assert!(normalization.converts("image/x-ms-bmp"));
+    assert!(normalization.converts("image/tiff"));
+    // JPEG is kept by default, and PNG is already the target
+    assert!(!normalization.converts("image/jpeg"));
+    assert!(!normalization.converts("image/jpg"));
+    assert!(!normalization.converts("image/png"));
+    // Formats that can't be decoded are stored as they are
+    assert!(!normalization.converts("image/heic"));
+    assert!(!normalization.converts("application/pdf"));
+
+    let normalization = ImageNormalizationConfig::from_source_config(&json!({
+        "image_normalization": {"enabled": true, "format": "jpeg", "keep_mime_types": []}
+    }))
+    .unwrap();
+    assert!(normalization.converts("image/png"));
+    assert!(!normalization.converts("image/jpeg"));
+}
+
+#[test]
+fn test_invalid_normalization_is_rejected() {
+    assert!(ImageNormalizationConfig::from_source_config(&json!({"image_normalization": {"format": "gif"}})).is_err());
+    assert!(ImageNormalizationConfig::from_source_config(&json!({
+        "image_normalization": {"keep_mime_types": ["application/pdf"]}
+    }))
+    .is_err());
+}
+
+#[test]
+fn test_filenames_of_converted_images() {
+    assert_eq!(normalized_filename("scan.bmp", NormalizedImageFormat::Png), "scan.png");
+    assert_eq!(normalized_filename("photo.tiff", NormalizedImageFormat::Jpeg), "photo.jpg");
+    assert_eq!(normalized_filename("scan", NormalizedImageFormat::Png), "scan.png");
+    assert_eq!(original_image_filename("scan.bmp", "image/bmp"), "scan.original.bmp");
+    assert_eq!(original_image_filename("scan", "image/x-ms-bmp"), "scan.original.bmp");
+    assert_eq!(image_format_name("image/tiff"), "tiff");
+    assert_eq!(image_format_name("image/jpg"), "jpeg");
+}
+
+#[cfg(feature = "ocr")]
+#[test]
+fn test_bmp_is_converted_to_png() {
+    use crate::ingestion::image_normalization::convert_image;
+
+    let image = image::RgbImage::from_fn(8, 4, |x, y| image::Rgb([(x * 30) as u8, (y * 60) as u8, 128]));
+    let mut bmp = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut bmp), image::ImageFormat::Bmp)
+        .unwrap();
+
+    let png = convert_image(&bmp, NormalizedImageFormat::Png).unwrap();
+    assert_eq!(image::guess_format(&png).unwrap(), image::ImageFormat::Png);
+    let decoded = image::load_from_memory(&png).unwrap().to_rgb8();
+    assert_eq!(decoded, image);
+
+    assert!(convert_image(b"not an image", NormalizedImageFormat::Png).is_err());
+}
@@ -0,0 +1,91 @@
+use crate::ocr::incremental::{
+    compare_page_renders, merge_page_text, page_spec, pages_to_reocr, split_page_text, PageDiff, PAGE_BREAK,
+};
+
+fn page(width: usize, height: usize) -> Vec<u8> {
+    vec![255u8; width * height]
+}
+
+fn fill(luma: &mut [u8], width: usize, x: std::ops::Range<usize>, y: std::ops::Range<usize>) {
+    for row in y {
+        for column in x.clone() {
+            luma[row * width + column] = 0;
+        }
+    }
+}
+
+fn pages(texts: &[&str]) -> Vec<String> {
+    texts.iter().map(|text| text.to_string()).collect()
+}
+
+#[test]
+fn test_identical_renders_are_unchanged() {
+    let mut previous = page(100, 100);
+    fill(&mut previous, 100, 10..90, 10..20);
+    let current = previous.clone();
+    assert_eq!(compare_page_renders(&previous, (100, 100), &current, (100, 100)), PageDiff::Unchanged);
+}
+
+#[test]
+fn test_new_text_on_a_page_is_a_change() {
+    let previous = page(100, 100);
+    let mut current = previous.clone();
+    fill(&mut current, 100, 10..40, 50..55);
+    assert_eq!(compare_page_renders(&previous, (100, 100), &current, (100, 100)), PageDiff::Changed);
+}
+
+#[test]
+fn test_rendering_noise_is_not_a_change() {
+    let previous = page(100, 100);
+    let mut current = previous.clone();
+    // A few faint or stray pixels, as compression and anti-aliasing leave
+    current[..50].iter_mut().for_each(|pixel| *pixel = 230);
+    current[5_000..5_004].iter_mut().for_each(|pixel| *pixel = 0);
+    assert_eq!(compare_page_renders(&previous, (100, 100), &current, (100, 100)), PageDiff::Unchanged);
+}
+
+#[test]
+fn test_resized_or_unreadable_renders() {
+    let previous = page(100, 100);
+    assert_eq!(compare_page_renders(&previous, (100, 100), &page(100, 120), (100, 120)), PageDiff::Changed);
+    assert_eq!(compare_page_renders(&previous, (100, 100), &[], (0, 0)), PageDiff::Inconclusive);
+    assert_eq!(compare_page_renders(&previous[..10], (100, 100), &previous, (100, 100)), PageDiff::Inconclusive);
+}
+
+#[test]
+fn test_previous_text_splits_only_into_the_page_count() {
+    let text = ["one", "two", "three"].join(PAGE_BREAK);
+    assert_eq!(split_page_text(&text, 3), Some(pages(&["one", "two", "three"])));
+    assert_eq!(split_page_text(&text, 4), None);
+    // Text extracted without page breaks can't be reused page by page
+    assert_eq!(split_page_text("one two three", 3), None);
+}
+
+#[test]
+fn test_only_changed_pages_are_ocred_again() {
+    let previous = pages(&["one", "two", "three", "four"]);
+    let diffs = [PageDiff::Unchanged, PageDiff::Changed, PageDiff::Unchanged, PageDiff::Changed];
+    let reocr = pages_to_reocr(&diffs, Some(&previous)).unwrap();
+    assert_eq!(reocr, vec![2, 4]);
+    assert_eq!(page_spec(&reocr), "2,4");
+
+    let unchanged = [PageDiff::Unchanged; 4];
+    assert_eq!(pages_to_reocr(&unchanged, Some(&previous)), Some(vec![]));
+}
+
+#[test]
+fn test_inconclusive_comparisons_fall_back_to_full_ocr() {
+    let previous = pages(&["one", "two"]);
+    assert_eq!(pages_to_reocr(&[PageDiff::Changed, PageDiff::Inconclusive], Some(&previous)), None);
+    assert_eq!(pages_to_reocr(&[PageDiff::Changed, PageDiff::Unchanged], None), None);
+    assert_eq!(pages_to_reocr(&[PageDiff::Changed; 3], Some(&previous)), None);
+    assert_eq!(pages_to_reocr(&[], Some(&[])), None);
+}
+
+#[test]
+fn test_merged_text_keeps_unchanged_pages_and_stays_split() {
+    let previous = pages(&["one", "two", "three"]);
+    let merged = merge_page_text(&previous, &[(2, " second \n".to_string()), (3, String::new())]);
+    assert_eq!(merged, ["one", "second", ""].join(PAGE_BREAK));
+    assert_eq!(split_page_text(&merged, 3), Some(pages(&["one", "second", ""])));
+}
@@ -0,0 +1,188 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+use crate::ingestion::pipeline::{IngestionPipeline, IngestionPipelineConfig};
+
+fn folders(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn files_in(folder: &str, count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("{}/file{}.pdf", folder, i)).collect()
+}
+
+#[tokio::test]
+async fn test_every_discovered_file_is_stored() {
+    let pipeline = IngestionPipeline::new(IngestionPipelineConfig::default(), CancellationToken::new());
+    let stored = std::sync::Mutex::new(Vec::new());
+
+    let summary = pipeline
+        .run(
+            &folders(&["/a", "/b"]),
+            |folder| async move { Ok(files_in(&folder, 7)) },
+            |path| async move { Ok(path.len()) },
+            |path, size| {
+                assert_eq!(path.len(), size);
+                stored.lock().unwrap().push(path);
+                async { Ok(true) }
+            },
+            |_| async {},
+        )
+        .await;
+
+    assert_eq!(summary.discovered, 14);
+    assert_eq!(summary.downloaded, 14);
+    assert_eq!(summary.stored, 14);
+    assert!(!summary.cancelled);
+    let mut stored = stored.into_inner().unwrap();
+    stored.sort();
+    let mut expected = [files_in("/a", 7), files_in("/b", 7)].concat();
+    expected.sort();
+    assert_eq!(stored, expected);
+}
+
+#[tokio::test]
+async fn test_failures_are_counted_per_stage() {
+    let pipeline = IngestionPipeline::new(IngestionPipelineConfig::default(), CancellationToken::new());
+
+    let summary = pipeline
+        .run(
+            &folders(&["/ok", "/broken"]),
+            |folder| async move {
+                if folder == "/broken" {
+                    Err(anyhow!("PROPFIND failed"))
+                } else {
+                    Ok((0..10).collect::<Vec<usize>>())
+                }
+            },
+            |i| async move { if i % 5 == 0 { Err(anyhow!("download of {} failed", i)) } else { Ok(i) } },
+            |_, i| async move {
+                match i {
+                    3 => Err(anyhow!("ingestion failed")),
+                    // Duplicates are processed without counting as stored
+                    4 => Ok(false),
+                    _ => Ok(true),
+                }
+            },
+            |_| async {},
+        )
+        .await;
+
+    assert_eq!(summary.discovered, 10);
+    assert_eq!(summary.discovery_failures, 1);
+    assert_eq!(summary.download_failures, 2);
+    assert_eq!(summary.downloaded, 8);
+    assert_eq!(summary.store_failures, 1);
+    assert_eq!(summary.stored, 6);
+}
+
+#[tokio::test]
+async fn test_slow_store_stage_holds_back_downloads() {
+    let config = IngestionPipelineConfig { download_concurrency: 2, store_concurrency: 1, stage_queue_size: 2 };
+    let pipeline = IngestionPipeline::new(config, CancellationToken::new());
+    let downloads = AtomicUsize::new(0);
+    let store_gate = Arc::new(Semaphore::new(0));
+    let watch_folders = folders(&["/big"]);
+
+    let run = pipeline.run(
+        &watch_folders,
+        |folder| async move { Ok(files_in(&folder, 50)) },
+        |path| {
+            downloads.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(path) }
+        },
+        |_, _| {
+            let store_gate = store_gate.clone();
+            async move {
+                store_gate.acquire().await.unwrap().forget();
+                Ok(true)
+            }
+        },
+        |_| async {},
+    );
+
+    let release = async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // Stored: 1 in progress, 2 queued, 1 waiting to be queued, plus at most
+        // one finished download per download slot
+        let held_back = downloads.load(Ordering::SeqCst);
+        store_gate.add_permits(50);
+        held_back
+    };
+
+    let (summary, held_back) = tokio::join!(run, release);
+    assert!(held_back <= 6, "{} files were downloaded while storage was blocked", held_back);
+    assert_eq!(summary.stored, 50);
+    assert_eq!(downloads.load(Ordering::SeqCst), 50);
+}
+
+#[tokio::test]
+async fn test_cancellation_stops_the_pipeline() {
+    let token = CancellationToken::new();
+    let pipeline = IngestionPipeline::new(IngestionPipelineConfig::default(), token.clone());
+
+    let summary = pipeline
+        .run(
+            &folders(&["/a"]),
+            |folder| async move { Ok(files_in(&folder, 100)) },
+            |path| {
+                token.cancel();
+                async move { Ok(path) }
+            },
+            |_, _| async { Ok(true) },
+            |_| async {},
+        )
+        .await;
+
+    assert!(summary.cancelled);
+    assert!(summary.stored < 100);
+
+    let downloads = AtomicUsize::new(0);
+    let summary = pipeline
+        .run(
+            &folders(&["/a"]),
+            |folder| async move { Ok(files_in(&folder, 5)) },
+            |path| {
+                downloads.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(path) }
+            },
+            |_, _| async { Ok(true) },
+            |_| async {},
+        )
+        .await;
+
+    // An already-cancelled sync doesn't even list its folders
+    assert!(summary.cancelled);
+    assert_eq!(summary.discovered, 0);
+    assert_eq!(downloads.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_progress_is_reported_with_running_totals() {
+    let pipeline = IngestionPipeline::new(IngestionPipelineConfig::default(), CancellationToken::new());
+    let reports = std::sync::Mutex::new(Vec::new());
+
+    let summary = pipeline
+        .run(
+            &folders(&["/a", "/b"]),
+            |folder| async move { Ok(files_in(&folder, 3)) },
+            |path| async move { Ok(path) },
+            |_, _| async { Ok(true) },
+            |progress| {
+                reports.lock().unwrap().push(progress);
+                async {}
+            },
+        )
+        .await;
+
+    let reports = reports.into_inner().unwrap();
+    assert!(!reports.is_empty());
+    assert!(reports.windows(2).all(|w| w[0].stored <= w[1].stored && w[0].discovered <= w[1].discovered));
+    assert_eq!(reports.last().unwrap().stored, summary.stored);
+    assert_eq!(summary.stored, 6);
+}
@@ -0,0 +1,88 @@
+use crate::ocr::language_detection::{detect_language, DocumentLanguagePolicy, LANGUAGE_LABEL_PREFIX};
+
+const ENGLISH: &str = "The annual report describes the results of the company for the last year. \
+    It was prepared by the finance team and is based on the figures that were available at the end \
+    of the year. The board has reviewed it and approved it for publication with a few changes.";
+
+const GERMAN: &str = "Die Rechnung ist bis zum Ende des Monats zu bezahlen. Der Betrag wird von \
+    dem Konto abgebucht, das Sie bei der Anmeldung angegeben haben. Wenn die Zahlung nicht \
+    rechtzeitig eingeht, wird eine Mahnung mit einer Gebühr an die Adresse des Kunden geschickt.";
+
+const FRENCH: &str = "Le contrat est signé par les deux parties et il entre en vigueur dans les \
+    jours qui suivent. Les conditions sont décrites dans le document joint, avec la liste des \
+    services qui sont inclus dans le prix. Nous vous prions de conserver une copie pour vos archives.";
+
+const SPANISH: &str = "El informe de la empresa muestra que las ventas del año fueron mejores que \
+    lo esperado. Los resultados se presentan en la tabla del anexo, con una comparación por región \
+    y por producto. También se incluyen las previsiones para el próximo año y los riesgos principales.";
+
+#[test]
+fn test_detects_common_languages() {
+    let cases = [(ENGLISH, "eng", "en"), (GERMAN, "deu", "de"), (FRENCH, "fra", "fr"), (SPANISH, "spa", "es")];
+    for (text, tesseract_code, iso_code) in cases {
+        let detected = detect_language(text).unwrap_or_else(|| panic!("no language detected for {}", iso_code));
+        assert_eq!(detected.tesseract_code, tesseract_code);
+        assert_eq!(detected.iso_code, iso_code);
+        assert!(detected.stopword_ratio > 0.0 && detected.stopword_ratio <= 1.0);
+    }
+}
+
+#[test]
+fn test_detection_ignores_case_and_punctuation() {
+    let shouted = GERMAN.to_uppercase().replace(' ', " -- ");
+    assert_eq!(detect_language(&shouted).map(|d| d.iso_code), Some("de"));
+}
+
+#[test]
+fn test_short_text_is_not_labeled() {
+    assert_eq!(detect_language(""), None);
+    assert_eq!(detect_language("The invoice for the month of May"), None);
+}
+
+#[test]
+fn test_text_without_stopwords_is_not_labeled() {
+    let part_numbers = "XR-200 bracket steel M8 bolt washer hinge panel gasket ".repeat(5);
+    assert_eq!(detect_language(&part_numbers), None);
+}
+
+#[test]
+fn test_evenly_mixed_text_is_not_labeled() {
+    // A bilingual cover letter: one paragraph each in English and German
+    let english = "The annual report describes the results of the company for the last year. It was \
+        prepared by the finance team and is based on the figures that were available at the end of the year.";
+    let mixed = format!("{} {}", english, GERMAN);
+    assert_eq!(detect_language(&mixed), None);
+}
+
+#[test]
+fn test_label_name_uses_iso_code() {
+    let detected = detect_language(FRENCH).expect("french detected");
+    assert_eq!(detected.label_name(), "lang:fr");
+    assert!(detected.label_name().starts_with(LANGUAGE_LABEL_PREFIX));
+}
+
+#[test]
+fn test_detected_languages_map_to_search_languages() {
+    assert_eq!(detect_language(GERMAN).and_then(|d| d.search_language), Some("german"));
+    assert_eq!(detect_language(FRENCH).and_then(|d| d.search_language), Some("french"));
+
+    // PostgreSQL has no Polish stemmer
+    let polish = "Faktura jest do zapłaty w ciągu miesiąca i nie może być przesłana przez pocztę. \
+        To jest ważne dla nas oraz dla klienta, że kwota jest na koncie, jak co miesiąc przy tym \
+        samym terminie, czy to przez bank, czy to od razu w kasie, lub na miejscu przy odbiorze.";
+    let detected = detect_language(polish).expect("polish detected");
+    assert_eq!(detected.iso_code, "pl");
+    assert_eq!(detected.search_language, None);
+}
+
+#[test]
+fn test_document_language_falls_back_to_the_default() {
+    let policy = DocumentLanguagePolicy { detect: true, default_language: "dutch".to_string() };
+    assert_eq!(policy.search_language(GERMAN), "german");
+    assert_eq!(policy.search_language("Invoice 2024-001"), "dutch");
+
+    let policy = DocumentLanguagePolicy { detect: false, default_language: "dutch".to_string() };
+    assert_eq!(policy.search_language(GERMAN), "dutch");
+
+    assert_eq!(DocumentLanguagePolicy::default().search_language(""), "english");
+}
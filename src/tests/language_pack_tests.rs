@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use crate::ocr::language_packs::{git_blob_sha1, is_valid_language_code, parse_tessdata_dir, split_languages};
+
+#[test]
+fn test_git_blob_sha1_matches_git() {
+    // Same values as `git hash-object`
+    assert_eq!(git_blob_sha1(b""), "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    assert_eq!(git_blob_sha1(b"hello\n"), "ce013625030ba8dba906f756967f9e9ca394464a");
+}
+
+#[test]
+fn test_parse_tessdata_dir_from_list_langs() {
+    let output = "List of available languages in \"/usr/share/tesseract-ocr/5/tessdata/\" (3):\neng\nosd\nspa\n";
+    assert_eq!(parse_tessdata_dir(output), Some(PathBuf::from("/usr/share/tesseract-ocr/5/tessdata/")));
+
+    assert_eq!(parse_tessdata_dir("List of available languages (2):\neng\nosd\n"), None);
+    assert_eq!(parse_tessdata_dir("List of available languages in \"\" (0):"), None);
+    assert_eq!(parse_tessdata_dir(""), None);
+}
+
+#[test]
+fn test_split_languages_dedupes_combinations() {
+    assert_eq!(split_languages(["eng+deu", "deu", " fra ", "eng+"]), vec!["eng", "deu", "fra"]);
+    assert!(split_languages(["", "+"]).is_empty());
+}
+
+#[test]
+fn test_language_codes_cannot_escape_tessdata() {
+    assert!(is_valid_language_code("eng"));
+    assert!(is_valid_language_code("chi_sim"));
+    assert!(is_valid_language_code("chi_sim_vert"));
+    assert!(!is_valid_language_code(""));
+    assert!(!is_valid_language_code("../eng"));
+    assert!(!is_valid_language_code("eng/osd"));
+    assert!(!is_valid_language_code("eng.traineddata"));
+}
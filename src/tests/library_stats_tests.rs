@@ -0,0 +1,35 @@
+use chrono::NaiveDate;
+
+use crate::models::DailyIngestionCount;
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+}
+
+#[test]
+fn test_daily_series_fills_days_without_uploads() {
+    let series = DailyIngestionCount::series(&[(date(2), 4), (date(5), 1)], date(5), 5);
+
+    let counts: Vec<(NaiveDate, i64)> = series.iter().map(|day| (day.date, day.count)).collect();
+    assert_eq!(
+        counts,
+        vec![(date(1), 0), (date(2), 4), (date(3), 0), (date(4), 0), (date(5), 1)]
+    );
+}
+
+#[test]
+fn test_daily_series_ignores_days_outside_window() {
+    let series = DailyIngestionCount::series(&[(date(1), 7), (date(10), 2)], date(10), 3);
+
+    assert_eq!(series.len(), 3);
+    assert_eq!(series.first().unwrap().date, date(8));
+    assert_eq!(series.iter().map(|day| day.count).sum::<i64>(), 2);
+}
+
+#[test]
+fn test_daily_series_crosses_month_boundary() {
+    let series = DailyIngestionCount::series(&[], date(1), 2);
+
+    assert_eq!(series[0].date, NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+    assert_eq!(series[1].date, date(1));
+}
@@ -0,0 +1,80 @@
+use axum::{body::Body, http::Request, routing::get, Router};
+use tower::util::ServiceExt;
+
+use crate::logging::{current_request_id, in_current_request, request_span, LogFormat, REQUEST_ID_HEADER};
+
+fn app() -> Router {
+    Router::new()
+        .route("/", get(|| async { "ok" }))
+        .route("/id", get(|| async { current_request_id().unwrap_or_default() }))
+        .route(
+            "/spawned",
+            get(|| async {
+                tokio::spawn(in_current_request(async { current_request_id() }))
+                    .await
+                    .unwrap()
+                    .unwrap_or_default()
+            }),
+        )
+        .layer(axum::middleware::from_fn(request_span))
+}
+
+async fn response_request_id(request_id: Option<&str>) -> String {
+    let mut request = Request::builder().uri("/");
+    if let Some(request_id) = request_id {
+        request = request.header(REQUEST_ID_HEADER, request_id);
+    }
+    let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_log_format_parses() {
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert_eq!(" Pretty ".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+    assert_eq!("compact".parse::<LogFormat>().unwrap(), LogFormat::Compact);
+    assert_eq!("".parse::<LogFormat>().unwrap(), LogFormat::Full);
+    assert!("logfmt".parse::<LogFormat>().is_err());
+}
+
+#[tokio::test]
+async fn test_requests_get_a_generated_id() {
+    let request_id = response_request_id(None).await;
+    assert!(uuid::Uuid::parse_str(&request_id).is_ok(), "unexpected id {}", request_id);
+}
+
+#[tokio::test]
+async fn test_request_id_from_proxy_is_kept() {
+    assert_eq!(response_request_id(Some("lb-4f2a9c")).await, "lb-4f2a9c");
+
+    // Unusable ids are replaced
+    let too_long = "a".repeat(200);
+    assert_ne!(response_request_id(Some(&too_long)).await, too_long);
+    assert_ne!(response_request_id(Some("two words")).await, "two words");
+}
+
+async fn handler_request_id(path: &str) -> (String, String) {
+    let request = Request::builder()
+        .uri(path)
+        .header(REQUEST_ID_HEADER, "trace-77")
+        .body(Body::empty())
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    let header = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (header, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_handlers_see_the_request_id() {
+    assert_eq!(handler_request_id("/id").await, ("trace-77".to_string(), "trace-77".to_string()));
+}
+
+#[tokio::test]
+async fn test_spawned_work_keeps_the_request_id() {
+    assert_eq!(handler_request_id("/spawned").await.1, "trace-77");
+
+    // Outside of a request there is no id to keep
+    assert_eq!(current_request_id(), None);
+    assert_eq!(tokio::spawn(in_current_request(async { current_request_id() })).await.unwrap(), None);
+}
@@ -0,0 +1,165 @@
+use crate::services::malware_scanner::{
+    parse_clamd_response, ClamdScanner, CommandScanner, MalwareScanService, MalwareScanner,
+    ScanDecision, ScanVerdict,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const EICAR: &[u8] = br"X5O!P%@AP[4\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*";
+
+/// Flags any file containing the EICAR test string
+struct MockScanner;
+
+#[async_trait]
+impl MalwareScanner for MockScanner {
+    async fn scan(&self, data: &[u8]) -> Result<ScanVerdict> {
+        if data.windows(EICAR.len()).any(|window| window == EICAR) {
+            Ok(ScanVerdict::Infected("Eicar-Test-Signature".to_string()))
+        } else {
+            Ok(ScanVerdict::Clean)
+        }
+    }
+
+    fn name(&self) -> &str {
+        "mock"
+    }
+}
+
+/// Simulates a scanner that is down
+struct UnavailableScanner;
+
+#[async_trait]
+impl MalwareScanner for UnavailableScanner {
+    async fn scan(&self, _data: &[u8]) -> Result<ScanVerdict> {
+        Err(anyhow!("connection refused"))
+    }
+
+    fn name(&self) -> &str {
+        "unavailable"
+    }
+}
+
+#[tokio::test]
+async fn test_clean_file_is_allowed() {
+    let service = MalwareScanService::new(Arc::new(MockScanner), false);
+    assert_eq!(service.check(b"Quarterly report").await, ScanDecision::Allow);
+}
+
+#[tokio::test]
+async fn test_infected_file_is_quarantined() {
+    let service = MalwareScanService::new(Arc::new(MockScanner), false);
+    assert_eq!(
+        service.check(EICAR).await,
+        ScanDecision::Quarantine("Eicar-Test-Signature".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_scanner_failure_blocks_by_default() {
+    let service = MalwareScanService::new(Arc::new(UnavailableScanner), false);
+    match service.check(b"Quarterly report").await {
+        ScanDecision::Block(reason) => {
+            assert!(reason.starts_with("Malware scan failed"));
+            assert!(reason.contains("connection refused"));
+        }
+        other => panic!("Expected Block, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_scanner_failure_allows_when_fail_open() {
+    let service = MalwareScanService::new(Arc::new(UnavailableScanner), true);
+    assert_eq!(service.check(b"Quarterly report").await, ScanDecision::Allow);
+}
+
+#[test]
+fn test_parse_clamd_response() {
+    assert_eq!(parse_clamd_response("stream: OK\0").unwrap(), ScanVerdict::Clean);
+    assert_eq!(
+        parse_clamd_response("stream: Win.Test.EICAR_HDB-1 FOUND\0").unwrap(),
+        ScanVerdict::Infected("Win.Test.EICAR_HDB-1".to_string())
+    );
+    assert!(parse_clamd_response("INSTREAM size limit exceeded. ERROR\0").is_err());
+    assert!(parse_clamd_response("").is_err());
+}
+
+#[tokio::test]
+async fn test_clamd_scanner_speaks_instream() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    // Minimal clamd: read the INSTREAM chunks and report EICAR if present
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut command = [0u8; 10];
+        socket.read_exact(&mut command).await.unwrap();
+        assert_eq!(&command, b"zINSTREAM\0");
+
+        let mut received = Vec::new();
+        loop {
+            let length = socket.read_u32().await.unwrap() as usize;
+            if length == 0 {
+                break;
+            }
+            let mut chunk = vec![0u8; length];
+            socket.read_exact(&mut chunk).await.unwrap();
+            received.extend(chunk);
+        }
+
+        let reply: &[u8] = if received == EICAR { b"stream: Eicar-Signature FOUND\0" } else { b"stream: OK\0" };
+        socket.write_all(reply).await.unwrap();
+    });
+
+    let scanner = ClamdScanner::new(format!("tcp://{}", address), Duration::from_secs(5));
+    assert_eq!(
+        scanner.scan(EICAR).await.unwrap(),
+        ScanVerdict::Infected("Eicar-Signature".to_string())
+    );
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_clamd_scanner_unreachable_is_an_error() {
+    // Bind and drop to get a port nothing is listening on
+    let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let scanner = ClamdScanner::new(format!("tcp://{}", address), Duration::from_secs(5));
+    assert!(scanner.scan(b"Quarterly report").await.is_err());
+}
+
+#[cfg(unix)]
+fn write_scan_script(dir: &tempfile::TempDir, body: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.path().join("scan.sh");
+    std::fs::write(&path, format!("#!/bin/sh\ncat > /dev/null\n{}\n", body)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_command_scanner_exit_codes() {
+    let dir = tempfile::TempDir::new().unwrap();
+
+    let clean = CommandScanner::new(write_scan_script(&dir, "exit 0"), Duration::from_secs(10));
+    assert_eq!(clean.scan(b"Quarterly report").await.unwrap(), ScanVerdict::Clean);
+
+    let infected = CommandScanner::new(
+        write_scan_script(&dir, "echo 'stdin: Eicar-Signature FOUND'\nexit 1"),
+        Duration::from_secs(10),
+    );
+    assert_eq!(
+        infected.scan(EICAR).await.unwrap(),
+        ScanVerdict::Infected("Eicar-Signature".to_string())
+    );
+
+    let broken = CommandScanner::new(
+        write_scan_script(&dir, "echo 'database missing' >&2\nexit 2"),
+        Duration::from_secs(10),
+    );
+    let error = broken.scan(b"Quarterly report").await.unwrap_err().to_string();
+    assert!(error.contains("database missing"));
+}
@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use chrono::TimeZone;
+
+use crate::db::migration_backup::{backup_path, needs_backup, pg_dump_args, BackupKind, SNAPSHOT_TABLES};
+
+#[test]
+fn test_backup_only_for_existing_databases_with_pending_migrations() {
+    let available = [1, 2, 3];
+    assert!(needs_backup(&[1, 2], &available));
+    assert!(!needs_backup(&[1, 2, 3], &available));
+    assert!(!needs_backup(&[], &available), "a fresh database is not backed up");
+}
+
+#[test]
+fn test_backup_path_names_time_and_version() {
+    let taken_at = chrono::Utc.with_ymd_and_hms(2026, 10, 16, 8, 5, 9).unwrap();
+    let path = backup_path(Path::new("/backups"), BackupKind::Schema, taken_at, 20261016000032);
+    assert_eq!(path, Path::new("/backups/readur-schema-20261016-080509-v20261016000032.sql"));
+}
+
+#[test]
+fn test_schema_backup_has_no_rows() {
+    let args = pg_dump_args("postgres://readur@db/readur", BackupKind::Schema, Path::new("/backups/schema.sql"));
+    assert!(args.contains(&"--dbname=postgres://readur@db/readur".to_string()));
+    assert!(args.contains(&"--file=/backups/schema.sql".to_string()));
+    assert!(args.contains(&"--schema-only".to_string()));
+    assert!(!args.iter().any(|arg| arg.starts_with("--table")));
+}
+
+#[test]
+fn test_data_backup_covers_only_snapshot_tables() {
+    let args = pg_dump_args("postgres://readur@db/readur", BackupKind::Data, Path::new("/backups/data.sql"));
+    assert!(args.contains(&"--data-only".to_string()));
+    let tables: Vec<&str> = args.iter().filter_map(|arg| arg.strip_prefix("--table=")).collect();
+    assert_eq!(tables, SNAPSHOT_TABLES);
+}
@@ -1,6 +1,60 @@
 // Pure unit tests (no external dependencies)
+mod archive_extraction_tests;
+mod barcode_tests;
+mod compression_tests;
+mod confidence_calibration_tests;
+mod credential_encryption_tests;
 mod config_tests;
+mod date_extraction_tests;
+mod document_move_tests;
+mod document_split_tests;
 mod document_response_serialization_tests;
+mod download_integrity_tests;
+mod download_spool_tests;
+mod error_envelope_tests;
+mod expiry_reminder_tests;
+mod external_ingest_tests;
+mod fallback_order_tests;
+mod fallback_stats_tests;
+mod front_matter_tests;
+mod file_validation_tests;
+mod handwriting_ocr_tests;
+mod image_normalization_tests;
+mod incremental_ocr_tests;
+mod ingestion_pipeline_tests;
+mod language_detection_tests;
+mod language_pack_tests;
+mod library_stats_tests;
+mod logging_tests;
+mod malware_scan_tests;
+mod migration_backup_tests;
+mod notification_digest_tests;
+mod ocr_dpi_tests;
+mod ocr_live_log_tests;
+mod ocr_model_cache_tests;
+mod ocr_orientation_tests;
+mod ocr_region_tests;
+mod ocr_text_sanitizer_tests;
 mod ocr_tests;
+mod page_range_tests;
+mod pagination_tests;
+mod partial_sync_tests;
+mod pdf_optimizer_tests;
 mod regression_tests;
-mod route_compilation_tests; 
+mod route_compilation_tests;
+mod schema_check_tests;
+mod search_export_tests;
+mod search_index_tests;
+mod settings_change_tests;
+mod source_circuit_breaker_tests;
+mod source_deletion_tests;
+mod source_file_version_tests;
+mod source_preview_tests;
+mod source_processing_mode_tests;
+mod storage_compression_tests;
+mod storage_isolation_tests;
+mod sync_ordering_tests; 
+mod sync_retry_tests;
+mod table_extraction_tests;
+mod temp_file_cleanup_tests;
+mod text_search_tests;
@@ -0,0 +1,60 @@
+use crate::models::PendingNotification;
+use crate::services::notification_digest::build_digest;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+fn pending(title: &str, message: &str, metadata: Option<serde_json::Value>, minutes_ago: i64) -> PendingNotification {
+    PendingNotification {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        notification_type: "success".to_string(),
+        title: title.to_string(),
+        message: message.to_string(),
+        action_url: Some("/documents".to_string()),
+        metadata,
+        created_at: Utc::now() - Duration::minutes(minutes_ago),
+    }
+}
+
+#[test]
+fn test_single_notification_is_delivered_unchanged() {
+    let metadata = serde_json::json!({ "files_processed": 12 });
+    let digest = build_digest(&[pending("Source Sync Completed", "Processed 12 files", Some(metadata.clone()), 5)]);
+
+    assert_eq!(digest.title, "Source Sync Completed");
+    assert_eq!(digest.message, "Processed 12 files");
+    assert_eq!(digest.metadata, Some(metadata));
+}
+
+#[test]
+fn test_sync_notifications_are_summarized_by_files_processed() {
+    let group = vec![
+        pending("Source Sync Completed", "Processed 1000 files", Some(serde_json::json!({ "files_processed": 1000 })), 10),
+        pending("Source Sync Completed", "Processed 240 files", Some(serde_json::json!({ "files_processed": 240 })), 5),
+        pending("Source Sync Completed", "Processed 0 files", Some(serde_json::json!({ "files_processed": 0 })), 1),
+    ];
+    let digest = build_digest(&group);
+
+    assert_eq!(digest.notification_type, "success");
+    assert_eq!(digest.title, "Source Sync Completed (3)");
+    assert_eq!(digest.message, "Sync completed: 1,240 documents added");
+    assert_eq!(digest.action_url.as_deref(), Some("/documents"));
+
+    let metadata = digest.metadata.unwrap();
+    assert_eq!(metadata["digest"], true);
+    assert_eq!(metadata["notification_count"], 3);
+    assert_eq!(metadata["files_processed"], 1240);
+}
+
+#[test]
+fn test_mixed_notifications_quote_the_latest_messages() {
+    let mut group: Vec<_> = (1..=5)
+        .map(|i| pending(&format!("Deep Scan {}", i), &format!("message {}", i), None, 10 - i))
+        .collect();
+    group[4].action_url = Some("/sources".to_string());
+    let digest = build_digest(&group);
+
+    assert_eq!(digest.title, "5 notifications");
+    assert_eq!(digest.message, "message 3\nmessage 4\nmessage 5\n…and 2 more");
+    assert_eq!(digest.action_url, None);
+}
@@ -0,0 +1,57 @@
+use serde_json::json;
+
+use crate::models::{source_ocr_dpi, Settings};
+use crate::ocr::enhanced::EnhancedOcrService;
+
+#[test]
+fn test_label_override_wins_over_source_and_user() {
+    assert_eq!(EnhancedOcrService::resolve_ocr_dpi(300, None, None), 300);
+    assert_eq!(EnhancedOcrService::resolve_ocr_dpi(300, Some(400), None), 400);
+    assert_eq!(EnhancedOcrService::resolve_ocr_dpi(300, Some(400), Some(600)), 600);
+    assert_eq!(EnhancedOcrService::resolve_ocr_dpi(300, None, Some(200)), 200);
+}
+
+#[test]
+fn test_default_dpi_keeps_previous_resize_bounds() {
+    assert_eq!(EnhancedOcrService::ocr_resize_dimensions(4096, 2048, 300), Some((2048, 1024)));
+    assert_eq!(EnhancedOcrService::ocr_resize_dimensions(200, 400, 300), Some((600, 1200)));
+    assert_eq!(EnhancedOcrService::ocr_resize_dimensions(1500, 2000, 300), None);
+}
+
+#[test]
+fn test_different_overrides_resize_differently() {
+    let (width, height) = (2550, 3300);
+    let text = EnhancedOcrService::resolve_ocr_dpi(300, None, None);
+    let form = EnhancedOcrService::resolve_ocr_dpi(300, Some(200), Some(450));
+    let low = EnhancedOcrService::resolve_ocr_dpi(300, Some(150), None);
+
+    let text_size = EnhancedOcrService::ocr_resize_dimensions(width, height, text);
+    let form_size = EnhancedOcrService::ocr_resize_dimensions(width, height, form);
+    let low_size = EnhancedOcrService::ocr_resize_dimensions(width, height, low);
+
+    assert_eq!(text_size, Some((1582, 2048)));
+    assert_eq!(form_size, Some((2373, 3072)));
+    assert_eq!(low_size, Some((791, 1024)));
+
+    // A small scan is upscaled further for a higher DPI
+    assert_eq!(EnhancedOcrService::ocr_resize_dimensions(400, 500, 300), None);
+    assert_eq!(EnhancedOcrService::ocr_resize_dimensions(400, 500, 600), Some((1200, 1500)));
+}
+
+#[test]
+fn test_effective_dpi_is_clamped_to_supported_range() {
+    let mut settings = Settings::default();
+    settings.ocr_dpi = 5000;
+    assert_eq!(EnhancedOcrService::effective_ocr_dpi(&settings), 1200);
+    settings.ocr_dpi = 10;
+    assert_eq!(EnhancedOcrService::effective_ocr_dpi(&settings), 72);
+}
+
+#[test]
+fn test_source_ocr_dpi_is_read_from_config() {
+    assert_eq!(source_ocr_dpi(&json!({})), Ok(None));
+    assert_eq!(source_ocr_dpi(&json!({ "ocr_dpi": null })), Ok(None));
+    assert_eq!(source_ocr_dpi(&json!({ "ocr_dpi": 400 })), Ok(Some(400)));
+    assert!(source_ocr_dpi(&json!({ "ocr_dpi": 20 })).is_err());
+    assert!(source_ocr_dpi(&json!({ "ocr_dpi": "400" })).is_err());
+}
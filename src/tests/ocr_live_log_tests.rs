@@ -0,0 +1,131 @@
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot};
+use uuid::Uuid;
+
+use crate::ocr::live_log::{self, OcrLogEvent, OcrLogEventKind};
+
+/// Starts a job for a new document that waits for the returned sender, and subscribes to its log
+async fn start_job<F>(job: F) -> (Uuid, oneshot::Sender<()>, broadcast::Receiver<OcrLogEvent>, tokio::task::JoinHandle<()>)
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    let document_id = Uuid::new_v4();
+    let (start, started) = oneshot::channel::<()>();
+    let handle = tokio::spawn(live_log::capture(document_id, async move {
+        let _ = started.await;
+        job.await;
+    }));
+    // The channel exists once the job's task has run
+    let receiver = loop {
+        if let Some(receiver) = live_log::subscribe(document_id) {
+            break receiver;
+        }
+        tokio::task::yield_now().await;
+    };
+    (document_id, start, receiver, handle)
+}
+
+async fn collect(mut receiver: broadcast::Receiver<OcrLogEvent>) -> Vec<OcrLogEvent> {
+    let mut events = Vec::new();
+    while let Ok(event) = tokio::time::timeout(Duration::from_secs(10), receiver.recv()).await.expect("event in time") {
+        let finished = event.kind == OcrLogEventKind::Finished;
+        events.push(event);
+        if finished {
+            break;
+        }
+    }
+    events
+}
+
+#[tokio::test]
+async fn test_steps_are_streamed_and_the_log_finishes_with_the_job() {
+    let (document_id, start, receiver, handle) = start_job(async {
+        assert!(live_log::current_document().is_some());
+        live_log::log_step("Preprocessing page 1");
+    })
+    .await;
+    start.send(()).unwrap();
+    let events = collect(receiver).await;
+    handle.await.unwrap();
+
+    let kinds: Vec<_> = events.iter().map(|event| event.kind).collect();
+    assert_eq!(kinds, vec![OcrLogEventKind::Step, OcrLogEventKind::Finished]);
+    assert_eq!(events[0].message, "Preprocessing page 1");
+    assert!(live_log::subscribe(document_id).is_none(), "the channel is closed with the job");
+}
+
+#[tokio::test]
+async fn test_logging_outside_a_job_is_ignored() {
+    assert!(live_log::current_document().is_none());
+    live_log::log_step("nobody is listening");
+    assert!(live_log::subscribe(Uuid::new_v4()).is_none());
+}
+
+#[tokio::test]
+async fn test_tool_stderr_is_relayed_line_by_line() {
+    let (_, start, receiver, handle) = start_job(async {
+        let output = live_log::output(
+            tokio::process::Command::new("sh").arg("-c").arg("echo text; echo 'page 1' >&2; echo 'page 2' >&2"),
+            "sh",
+        )
+        .await
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"text\n");
+        assert_eq!(output.stderr, b"page 1\npage 2\n");
+    })
+    .await;
+    start.send(()).unwrap();
+    let events = collect(receiver).await;
+    handle.await.unwrap();
+
+    let messages: Vec<_> = events.iter().map(|event| (event.kind, event.message.as_str())).collect();
+    assert_eq!(
+        messages,
+        vec![
+            (OcrLogEventKind::Step, "Running sh"),
+            (OcrLogEventKind::Stderr, "sh: page 1"),
+            (OcrLogEventKind::Stderr, "sh: page 2"),
+            (OcrLogEventKind::Step, "sh exited with code 0"),
+            (OcrLogEventKind::Finished, "OCR job finished"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_blocking_tool_stderr_is_relayed() {
+    let (_, start, receiver, handle) = start_job(async {
+        let document_id = live_log::current_document();
+        let output = tokio::task::spawn_blocking(move || {
+            live_log::output_blocking(
+                document_id,
+                std::process::Command::new("sh").arg("-c").arg("echo 'cannot read page' >&2; exit 2"),
+                "sh",
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(output.status.code(), Some(2));
+    })
+    .await;
+    start.send(()).unwrap();
+    let events = collect(receiver).await;
+    handle.await.unwrap();
+
+    assert!(events
+        .iter()
+        .any(|event| event.kind == OcrLogEventKind::Stderr && event.message == "sh: cannot read page"));
+    assert!(events.iter().any(|event| event.message == "sh exited with code 2"));
+    assert_eq!(events.last().unwrap().kind, OcrLogEventKind::Finished);
+}
+
+#[tokio::test]
+async fn test_a_cancelled_job_still_finishes_its_log() {
+    let (document_id, _start, receiver, handle) = start_job(std::future::pending()).await;
+    handle.abort();
+    let events = collect(receiver).await;
+
+    assert_eq!(events.last().unwrap().kind, OcrLogEventKind::Finished);
+    assert!(live_log::subscribe(document_id).is_none());
+}
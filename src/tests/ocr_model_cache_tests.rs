@@ -0,0 +1,73 @@
+use crate::ocr::model_cache::ModelCache;
+
+#[test]
+fn test_take_returns_the_model_for_that_language_only() {
+    let mut cache = ModelCache::default();
+    assert!(cache.take("eng").is_none());
+
+    cache.put("eng".to_string(), 1, 2);
+    cache.put("deu".to_string(), 2, 2);
+
+    // Consecutive jobs in different languages each get their own model
+    assert_eq!(cache.take("deu"), Some(2));
+    assert_eq!(cache.take("eng"), Some(1));
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_language_combinations_are_separate_entries() {
+    let mut cache = ModelCache::default();
+    cache.put("eng".to_string(), 1, 3);
+    cache.put("eng+deu".to_string(), 2, 3);
+    cache.put("deu+eng".to_string(), 3, 3);
+
+    assert_eq!(cache.take("eng+deu"), Some(2));
+    assert_eq!(cache.take("deu+eng"), Some(3));
+    assert_eq!(cache.take("eng"), Some(1));
+}
+
+#[test]
+fn test_taken_model_is_not_shared_until_returned() {
+    let mut cache = ModelCache::default();
+    cache.put("eng".to_string(), 1, 2);
+
+    assert_eq!(cache.take("eng"), Some(1));
+    assert!(cache.take("eng").is_none());
+
+    cache.put("eng".to_string(), 1, 2);
+    assert_eq!(cache.take("eng"), Some(1));
+}
+
+#[test]
+fn test_least_recently_used_model_is_evicted() {
+    let mut cache = ModelCache::default();
+    cache.put("eng".to_string(), 1, 2);
+    cache.put("deu".to_string(), 2, 2);
+
+    // Using English again makes German the least recently used
+    let eng = cache.take("eng").unwrap();
+    cache.put("eng".to_string(), eng, 2);
+    cache.put("fra".to_string(), 3, 2);
+
+    assert_eq!(cache.languages(), vec!["fra", "eng"]);
+    assert!(cache.take("deu").is_none());
+}
+
+#[test]
+fn test_putting_same_language_replaces_entry() {
+    let mut cache = ModelCache::default();
+    cache.put("eng".to_string(), 1, 2);
+    cache.put("eng".to_string(), 2, 2);
+
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.take("eng"), Some(2));
+}
+
+#[test]
+fn test_zero_capacity_keeps_nothing() {
+    let mut cache = ModelCache::default();
+    cache.put("eng".to_string(), 1, 0);
+
+    assert!(cache.is_empty());
+    assert!(cache.take("eng").is_none());
+}
@@ -0,0 +1,29 @@
+use crate::ocr::orientation::{keep_rotation, OrientationSample};
+
+fn sample(confidence: f32, word_count: usize) -> OrientationSample {
+    OrientationSample { confidence, word_count }
+}
+
+#[test]
+fn test_rotation_kept_when_it_reads_better() {
+    assert!(keep_rotation(&sample(35.0, 12), &sample(88.0, 140)));
+}
+
+#[test]
+fn test_rotation_reverted_when_confidence_drops() {
+    // Sideways text often yields plenty of low-confidence junk "words"
+    assert!(!keep_rotation(&sample(91.0, 150), &sample(42.0, 180)));
+}
+
+#[test]
+fn test_word_count_breaks_confidence_ties() {
+    assert!(keep_rotation(&sample(80.0, 100), &sample(79.0, 120)));
+    assert!(!keep_rotation(&sample(80.0, 120), &sample(81.0, 100)));
+    assert!(keep_rotation(&sample(80.0, 100), &sample(80.0, 100)));
+}
+
+#[test]
+fn test_rotation_that_reads_nothing_is_reverted() {
+    assert!(!keep_rotation(&sample(60.0, 25), &sample(95.0, 0)));
+    assert!(keep_rotation(&sample(0.0, 0), &sample(0.0, 0)));
+}
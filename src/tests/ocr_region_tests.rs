@@ -0,0 +1,81 @@
+use crate::ocr::region::{NamedOcrRegion, OcrRegion, PixelRect, RegionError};
+
+fn region(x: f64, y: f64, width: f64, height: f64) -> OcrRegion {
+    OcrRegion { x, y, width, height }
+}
+
+#[test]
+fn test_region_resolves_to_pixels() {
+    assert_eq!(
+        region(0.5, 0.25, 0.25, 0.5).to_pixel_rect(1000, 800).unwrap(),
+        PixelRect { x: 500, y: 200, width: 250, height: 400 }
+    );
+    assert_eq!(
+        region(0.0, 0.0, 1.0, 1.0).to_pixel_rect(2480, 3508).unwrap(),
+        PixelRect { x: 0, y: 0, width: 2480, height: 3508 }
+    );
+}
+
+#[test]
+fn test_partial_pixels_are_included() {
+    // 0.1 * 15 = 1.5 and 0.3 * 15 = 4.5: the box covers pixels 1 through 4
+    assert_eq!(
+        region(0.1, 0.1, 0.2, 0.2).to_pixel_rect(15, 15).unwrap(),
+        PixelRect { x: 1, y: 1, width: 4, height: 4 }
+    );
+}
+
+#[test]
+fn test_box_ending_on_the_edge_is_accepted() {
+    let rect = region(0.7, 0.9, 0.3, 0.1).to_pixel_rect(1000, 1000).unwrap();
+    assert_eq!(rect.x + rect.width, 1000);
+    assert_eq!(rect.y + rect.height, 1000);
+}
+
+#[test]
+fn test_box_outside_the_image_is_rejected() {
+    assert!(matches!(region(0.8, 0.0, 0.3, 0.5).validate(), Err(RegionError::OutOfBounds { .. })));
+    assert!(matches!(region(0.0, 0.6, 0.5, 0.5).validate(), Err(RegionError::OutOfBounds { .. })));
+    assert!(matches!(region(-0.1, 0.0, 0.5, 0.5).validate(), Err(RegionError::OutOfBounds { .. })));
+    assert!(matches!(region(0.0, 0.0, 2.0, 1.0).validate(), Err(RegionError::OutOfBounds { .. })));
+}
+
+#[test]
+fn test_empty_or_invalid_box_is_rejected() {
+    assert_eq!(region(0.1, 0.1, 0.0, 0.5).validate(), Err(RegionError::Empty));
+    assert_eq!(region(0.1, 0.1, 0.5, -0.2).validate(), Err(RegionError::Empty));
+    assert_eq!(region(f64::NAN, 0.1, 0.5, 0.5).validate(), Err(RegionError::NotFinite));
+    assert_eq!(region(0.1, 0.1, f64::INFINITY, 0.5).validate(), Err(RegionError::NotFinite));
+}
+
+#[test]
+fn test_box_smaller_than_a_pixel_is_rejected() {
+    assert_eq!(
+        region(0.5, 0.5, 1e-17, 1e-17).to_pixel_rect(100, 100),
+        Err(RegionError::TooSmall { image_width: 100, image_height: 100 })
+    );
+}
+
+#[test]
+fn test_named_regions_default_to_the_first_page() {
+    let named: NamedOcrRegion = serde_json::from_value(serde_json::json!({
+        "name": "invoice_total",
+        "region": { "x": 0.6, "y": 0.85, "width": 0.3, "height": 0.05 }
+    }))
+    .unwrap();
+    assert_eq!(named.page, 1);
+    assert_eq!(named.validate(), Ok(()));
+}
+
+#[test]
+fn test_named_regions_need_a_name_a_page_and_a_box_on_it() {
+    let named = |name: &str, page: usize, region: OcrRegion| NamedOcrRegion { name: name.to_string(), page, region };
+    let inside = region(0.1, 0.1, 0.2, 0.2);
+
+    assert_eq!(named("  ", 1, inside).validate(), Err(RegionError::MissingName));
+    assert_eq!(named("total", 0, inside).validate(), Err(RegionError::PageZero));
+    assert!(matches!(
+        named("total", 1, region(0.9, 0.1, 0.2, 0.2)).validate(),
+        Err(RegionError::OutOfBounds { .. })
+    ));
+}
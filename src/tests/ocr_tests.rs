@@ -797,6 +797,9 @@ This tests the error handling for files that aren't actually PDFs.";
             file_owner: None,
             file_group: None,
             source_metadata: None,
+            document_date: None,
+            document_date_source: None,
+            expiry_date: None,
         };
 
         let json = serde_json::to_value(&response).unwrap();
@@ -839,6 +842,9 @@ This tests the error handling for files that aren't actually PDFs.";
             file_owner: None,
             file_group: None,
             source_metadata: None,
+            document_date: None,
+            document_date_source: None,
+            expiry_date: None,
         };
 
         let json = serde_json::to_value(&response).unwrap();
@@ -0,0 +1,62 @@
+use crate::ocr::text_sanitizer::{sanitize_ocr_bytes, sanitize_ocr_text, TextSanitizerOptions};
+
+#[test]
+fn test_nul_bytes_are_always_removed() {
+    let options = TextSanitizerOptions { strip_control_chars: false, normalize_unicode: false };
+    let sanitized = sanitize_ocr_text("Hello\0World\0!", &options);
+    assert_eq!(sanitized.text, "HelloWorld!");
+    assert_eq!(sanitized.nul_bytes_removed, 2);
+    assert!(sanitized.was_modified());
+}
+
+#[test]
+fn test_control_chars_are_stripped_but_layout_is_kept() {
+    let sanitized = sanitize_ocr_text("Line\u{07} one\tcol\r\nLine two\u{0C}Page\u{1B}", &TextSanitizerOptions::default());
+    assert_eq!(sanitized.text, "Line one\tcol\r\nLine two\u{0C}Page");
+    assert_eq!(sanitized.control_chars_removed, 2);
+}
+
+#[test]
+fn test_control_chars_are_kept_when_disabled() {
+    let options = TextSanitizerOptions { strip_control_chars: false, normalize_unicode: true };
+    let sanitized = sanitize_ocr_text("bell\u{07}", &options);
+    assert_eq!(sanitized.text, "bell\u{07}");
+    assert!(!sanitized.was_modified());
+}
+
+#[test]
+fn test_text_is_normalized_to_nfc() {
+    let sanitized = sanitize_ocr_text("Cafe\u{301}", &TextSanitizerOptions::default());
+    assert_eq!(sanitized.text, "Caf\u{e9}");
+    assert!(sanitized.normalized);
+
+    let options = TextSanitizerOptions { strip_control_chars: true, normalize_unicode: false };
+    assert_eq!(sanitize_ocr_text("Cafe\u{301}", &options).text, "Cafe\u{301}");
+}
+
+#[test]
+fn test_clean_text_is_unchanged() {
+    let text = "Plain text with special chars: €£¥ and ünïcödé";
+    let sanitized = sanitize_ocr_text(text, &TextSanitizerOptions::default());
+    assert_eq!(sanitized.text, text);
+    assert!(!sanitized.was_modified());
+}
+
+#[test]
+fn test_invalid_utf8_is_replaced() {
+    let sanitized = sanitize_ocr_bytes(b"total \xFF\xFE 42\0", &TextSanitizerOptions::default());
+    assert_eq!(sanitized.text, "total \u{FFFD}\u{FFFD} 42");
+    assert_eq!(sanitized.invalid_sequences_replaced, 2);
+    assert_eq!(sanitized.nul_bytes_removed, 1);
+}
+
+#[test]
+fn test_existing_replacement_chars_are_not_counted() {
+    let sanitized = sanitize_ocr_bytes("already \u{FFFD} here \u{FFFD}".as_bytes(), &TextSanitizerOptions::default());
+    assert_eq!(sanitized.invalid_sequences_replaced, 0);
+
+    let mut bytes = "kept \u{FFFD} ".as_bytes().to_vec();
+    bytes.push(0xC3);
+    let sanitized = sanitize_ocr_bytes(&bytes, &TextSanitizerOptions::default());
+    assert_eq!(sanitized.invalid_sequences_replaced, 1);
+}
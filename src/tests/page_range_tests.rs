@@ -0,0 +1,55 @@
+use crate::ocr::page_range::{parse_page_ranges, rendered_page_number, resolve_page_ranges, PageRangeError};
+
+#[test]
+fn test_parse_single_pages_and_ranges() {
+    assert_eq!(parse_page_ranges("1-3,7,10-12").unwrap(), vec![1..=3, 7..=7, 10..=12]);
+    assert_eq!(parse_page_ranges(" 5 ").unwrap(), vec![5..=5]);
+    assert_eq!(parse_page_ranges("2 - 4").unwrap(), vec![2..=4]);
+}
+
+#[test]
+fn test_overlapping_and_adjacent_ranges_are_merged() {
+    assert_eq!(parse_page_ranges("7,1-3,2-4").unwrap(), vec![1..=4, 7..=7]);
+    assert_eq!(parse_page_ranges("1-2,3,4-5").unwrap(), vec![1..=5]);
+    assert_eq!(parse_page_ranges("3,3,3").unwrap(), vec![3..=3]);
+}
+
+#[test]
+fn test_invalid_specs_are_rejected() {
+    assert_eq!(parse_page_ranges(""), Err(PageRangeError::Empty));
+    assert_eq!(parse_page_ranges(" , "), Err(PageRangeError::Empty));
+    assert_eq!(parse_page_ranges("0"), Err(PageRangeError::Invalid("0".to_string())));
+    assert_eq!(parse_page_ranges("a-3"), Err(PageRangeError::Invalid("a-3".to_string())));
+    assert_eq!(parse_page_ranges("1-"), Err(PageRangeError::Invalid("1-".to_string())));
+    assert_eq!(parse_page_ranges("-2"), Err(PageRangeError::Invalid("-2".to_string())));
+    assert_eq!(parse_page_ranges("5-2"), Err(PageRangeError::Reversed("5-2".to_string())));
+}
+
+#[test]
+fn test_pages_are_validated_against_page_count() {
+    assert_eq!(resolve_page_ranges("1-3,7", 7).unwrap(), vec![1..=3, 7..=7]);
+    assert_eq!(
+        resolve_page_ranges("1-3,7,10-12", 8),
+        Err(PageRangeError::OutOfRange { page: 10, page_count: 8 })
+    );
+    assert_eq!(
+        resolve_page_ranges("2-9", 8),
+        Err(PageRangeError::OutOfRange { page: 9, page_count: 8 })
+    );
+
+    let message = resolve_page_ranges("12", 3).unwrap_err().to_string();
+    assert!(message.contains("Page 12"));
+    assert!(message.contains("3 page"));
+}
+
+#[test]
+fn test_rendered_page_number_is_read_from_the_file_name() {
+    assert_eq!(rendered_page_number("pdf_pages_1-7.png", "pdf_pages_1"), Some(7));
+    assert_eq!(rendered_page_number("pdf_pages_1-012.png", "pdf_pages_1"), Some(12));
+
+    assert_eq!(rendered_page_number("pdf_pages_2-7.png", "pdf_pages_1"), None);
+    assert_eq!(rendered_page_number("pdf_pages_1-7.ppm", "pdf_pages_1"), None);
+    assert_eq!(rendered_page_number("pdf_pages_1-.png", "pdf_pages_1"), None);
+    assert_eq!(rendered_page_number("pdf_pages_1-0.png", "pdf_pages_1"), None);
+    assert_eq!(rendered_page_number("pdf_pages_1-x7.png", "pdf_pages_1"), None);
+}
@@ -0,0 +1,87 @@
+use crate::routes::pagination::{Pagination, PaginationParams, PaginationSettings};
+
+fn settings(clamp_out_of_range: bool) -> PaginationSettings {
+    PaginationSettings {
+        default_per_page: 25,
+        max_per_page: 100,
+        clamp_out_of_range,
+    }
+}
+
+fn resolve(params: PaginationParams) -> Result<Pagination, String> {
+    Pagination::from_params(&params, &settings(false)).map_err(|e| e.0)
+}
+
+#[test]
+fn test_defaults() {
+    let pagination = resolve(PaginationParams::default()).unwrap();
+    assert_eq!(pagination, Pagination { limit: 25, offset: 0 });
+    assert_eq!(pagination.page(), 1);
+}
+
+#[test]
+fn test_page_and_limit_styles_are_equivalent() {
+    let by_page = resolve(PaginationParams { page: Some(3), per_page: Some(20), ..Default::default() }).unwrap();
+    let by_offset = resolve(PaginationParams { limit: Some(20), offset: Some(40), ..Default::default() }).unwrap();
+
+    assert_eq!(by_page, by_offset);
+    assert_eq!(by_page.page(), 3);
+}
+
+#[test]
+fn test_out_of_range_values_are_rejected() {
+    assert!(resolve(PaginationParams { per_page: Some(101), ..Default::default() }).is_err());
+    assert!(resolve(PaginationParams { limit: Some(0), ..Default::default() }).is_err());
+    assert!(resolve(PaginationParams { page: Some(0), ..Default::default() }).is_err());
+    assert!(resolve(PaginationParams { offset: Some(-1), ..Default::default() }).is_err());
+    assert!(resolve(PaginationParams { per_page: Some(100), ..Default::default() }).is_ok());
+}
+
+#[test]
+fn test_conflicting_parameters_are_rejected() {
+    assert!(resolve(PaginationParams { page: Some(2), offset: Some(10), ..Default::default() }).is_err());
+    assert!(resolve(PaginationParams { per_page: Some(10), limit: Some(20), ..Default::default() }).is_err());
+    assert!(resolve(PaginationParams { per_page: Some(10), limit: Some(10), ..Default::default() }).is_ok());
+}
+
+#[test]
+fn test_out_of_range_values_are_clamped_when_configured() {
+    let params = PaginationParams { per_page: Some(500), page: Some(0), ..Default::default() };
+    let pagination = Pagination::from_params(&params, &settings(true)).unwrap();
+    assert_eq!(pagination, Pagination { limit: 100, offset: 0 });
+
+    let params = PaginationParams { limit: Some(-5), offset: Some(-10), ..Default::default() };
+    let pagination = Pagination::from_params(&params, &settings(true)).unwrap();
+    assert_eq!(pagination, Pagination { limit: 1, offset: 0 });
+}
+
+#[test]
+fn test_default_never_exceeds_max() {
+    let settings = PaginationSettings { default_per_page: 250, max_per_page: 50, clamp_out_of_range: false };
+    let pagination = Pagination::from_params(&PaginationParams::default(), &settings).unwrap();
+    assert_eq!(pagination.limit, 50);
+}
+
+#[test]
+fn test_meta() {
+    let meta = Pagination { limit: 20, offset: 40 }.meta(95);
+    assert_eq!(meta.total, 95);
+    assert_eq!(meta.page, 3);
+    assert_eq!(meta.per_page, 20);
+    assert_eq!(meta.total_pages, 5);
+    assert!(meta.has_more);
+
+    let last = Pagination { limit: 20, offset: 80 }.meta(95);
+    assert!(!last.has_more);
+
+    let empty = Pagination { limit: 20, offset: 0 }.meta(0);
+    assert_eq!(empty.total_pages, 0);
+    assert!(!empty.has_more);
+}
+
+#[test]
+fn test_params_without_any_value_are_empty() {
+    assert!(PaginationParams::default().is_empty());
+    assert!(!PaginationParams { offset: Some(0), ..Default::default() }.is_empty());
+    assert!(!PaginationParams { per_page: Some(10), ..Default::default() }.is_empty());
+}
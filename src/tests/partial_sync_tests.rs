@@ -0,0 +1,94 @@
+use chrono::Utc;
+
+use crate::models::{FileIngestionInfo, SourceErrorType, SyncFailureStage, SyncFileFailure, SyncOutcome};
+use crate::scheduling::source_sync::parent_folders;
+
+fn failure(path: &str, stage: SyncFailureStage) -> SyncFileFailure {
+    SyncFileFailure {
+        path: path.to_string(),
+        stage,
+        error_category: SourceErrorType::Timeout,
+        error_message: "Request timed out".to_string(),
+        failed_at: Utc::now(),
+        name: path.rsplit('/').next().unwrap_or_default().to_string(),
+        size: 2048,
+        mime_type: "application/pdf".to_string(),
+        last_modified: Some(Utc::now()),
+    }
+}
+
+#[test]
+fn test_sync_outcome_without_failures_is_success() {
+    assert_eq!(SyncOutcome::from_counts(0, 10_000, 0.5), SyncOutcome::Success);
+    assert_eq!(SyncOutcome::from_counts(0, 0, 0.5), SyncOutcome::Success);
+}
+
+#[test]
+fn test_sync_outcome_below_threshold_is_partial() {
+    assert_eq!(SyncOutcome::from_counts(10, 10_000, 0.5), SyncOutcome::Partial);
+    assert_eq!(SyncOutcome::from_counts(4, 10, 0.5), SyncOutcome::Partial);
+}
+
+#[test]
+fn test_sync_outcome_at_threshold_is_failed() {
+    assert_eq!(SyncOutcome::from_counts(5, 10, 0.5), SyncOutcome::Failed);
+    assert_eq!(SyncOutcome::from_counts(1, 1, 1.0), SyncOutcome::Failed);
+    // Failures without any counted attempts, e.g. every folder failed discovery
+    assert_eq!(SyncOutcome::from_counts(2, 0, 0.5), SyncOutcome::Failed);
+}
+
+#[test]
+fn test_sync_outcome_round_trips_through_its_name() {
+    for outcome in [SyncOutcome::Success, SyncOutcome::Partial, SyncOutcome::Failed] {
+        assert_eq!(SyncOutcome::try_from(outcome.to_string()).unwrap(), outcome);
+    }
+    assert!(SyncOutcome::try_from("unknown".to_string()).is_err());
+}
+
+#[test]
+fn test_only_file_failures_are_retryable() {
+    assert!(failure("/Documents/a.pdf", SyncFailureStage::Download).is_retryable());
+    assert!(failure("/Documents/a.pdf", SyncFailureStage::Store).is_retryable());
+    assert!(!failure("/Documents", SyncFailureStage::Discovery).is_retryable());
+}
+
+#[test]
+fn test_failure_rebuilds_file_info_for_retry() {
+    let failed = failure("/Documents/scans/a.pdf", SyncFailureStage::Download);
+    let file_info = failed.to_file_info();
+
+    assert_eq!(file_info.relative_path, "/Documents/scans/a.pdf");
+    assert_eq!(file_info.name, "a.pdf");
+    assert_eq!(file_info.size, 2048);
+    assert_eq!(file_info.mime_type, "application/pdf");
+    assert_eq!(file_info.last_modified, failed.last_modified);
+    assert!(!file_info.is_directory);
+}
+
+#[test]
+fn test_failure_list_survives_json_round_trip() {
+    let failures = vec![
+        failure("/Documents/a.pdf", SyncFailureStage::Download),
+        failure("/Documents", SyncFailureStage::Discovery),
+    ];
+
+    let json = serde_json::to_value(&failures).unwrap();
+    assert_eq!(json[0]["stage"], "download");
+    let restored: Vec<SyncFileFailure> = serde_json::from_value(json).unwrap();
+    assert_eq!(restored, failures);
+}
+
+#[test]
+fn test_parent_folders_of_retried_files() {
+    let files: Vec<FileIngestionInfo> = [
+        "/Documents/a.pdf",
+        "/Documents/scans/b.pdf",
+        "/Documents/c.pdf",
+        "/root.pdf",
+    ]
+    .iter()
+    .map(|path| failure(path, SyncFailureStage::Store).to_file_info())
+    .collect();
+
+    assert_eq!(parent_folders(&files), vec!["/Documents", "/Documents/scans", "/"]);
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::services::pdf_optimizer::{is_already_optimized, original_pdf_filename, PdfOptimizer, PdfOptimizerTool};
+
+fn pdf_of_size(size: usize, producer: &str) -> Vec<u8> {
+    let mut data = format!("%PDF-1.4\n1 0 obj\n<< /Producer ({}) >>\nendobj\n", producer).into_bytes();
+    data.resize(size, b' ');
+    data
+}
+
+#[test]
+fn test_optimizer_tool_parsing() {
+    assert_eq!("".parse::<PdfOptimizerTool>().unwrap(), PdfOptimizerTool::None);
+    assert_eq!("off".parse::<PdfOptimizerTool>().unwrap(), PdfOptimizerTool::None);
+    assert_eq!("Ghostscript".parse::<PdfOptimizerTool>().unwrap(), PdfOptimizerTool::Ghostscript);
+    assert_eq!("gs".parse::<PdfOptimizerTool>().unwrap(), PdfOptimizerTool::Ghostscript);
+    assert_eq!(" ocrmypdf ".parse::<PdfOptimizerTool>().unwrap(), PdfOptimizerTool::Ocrmypdf);
+    assert!("qpdf".parse::<PdfOptimizerTool>().is_err());
+}
+
+#[test]
+fn test_commands_write_the_output_file() {
+    let (program, args) = PdfOptimizerTool::Ghostscript
+        .command(Path::new("/tmp/in.pdf"), Path::new("/tmp/out.pdf"))
+        .unwrap();
+    assert_eq!(program, "gs");
+    assert!(args.contains(&"-sOutputFile=/tmp/out.pdf".to_string()));
+    assert!(args.contains(&"-dSAFER".to_string()));
+    assert_eq!(args.last().unwrap(), "/tmp/in.pdf");
+
+    let (program, args) = PdfOptimizerTool::Ocrmypdf
+        .command(Path::new("/tmp/in.pdf"), Path::new("/tmp/out.pdf"))
+        .unwrap();
+    assert_eq!(program, "ocrmypdf");
+    assert_eq!(&args[args.len() - 2..], ["/tmp/in.pdf", "/tmp/out.pdf"]);
+
+    assert!(PdfOptimizerTool::None.command(Path::new("in.pdf"), Path::new("out.pdf")).is_none());
+}
+
+#[test]
+fn test_pdfs_written_by_an_optimizer_are_recognized() {
+    assert!(is_already_optimized(&pdf_of_size(100, "GPL Ghostscript 10.02.1")));
+    assert!(is_already_optimized(&pdf_of_size(100, "pikepdf 8.0 / ocrmypdf 15.4")));
+    assert!(!is_already_optimized(&pdf_of_size(100, "Canon ScanGear")));
+}
+
+#[test]
+fn test_only_large_unoptimized_pdfs_are_optimized() {
+    let optimizer = PdfOptimizer::new(PdfOptimizerTool::Ghostscript, 1024, Duration::from_secs(5));
+    assert!(optimizer.should_optimize(&pdf_of_size(4096, "Canon ScanGear")));
+    assert!(!optimizer.should_optimize(&pdf_of_size(512, "Canon ScanGear")));
+    assert!(!optimizer.should_optimize(&pdf_of_size(4096, "GPL Ghostscript 10.02.1")));
+    assert!(!optimizer.should_optimize(&vec![b'x'; 4096]));
+}
+
+#[tokio::test]
+async fn test_skipped_pdfs_are_not_sent_to_the_optimizer() {
+    // No optimizer runs, so a missing program can't fail these
+    let optimizer = PdfOptimizer::new(PdfOptimizerTool::Ghostscript, 1024, Duration::from_secs(5));
+    assert!(optimizer.optimize(&pdf_of_size(512, "Canon ScanGear")).await.unwrap().is_none());
+    assert!(optimizer.optimize(&pdf_of_size(4096, "GPL Ghostscript")).await.unwrap().is_none());
+}
+
+#[test]
+fn test_originals_get_a_storage_path_of_their_own() {
+    let original = original_pdf_filename("scan.pdf");
+    assert_eq!(original, "scan.pdf.original");
+    assert_eq!(Path::new(&original).extension().unwrap(), "original");
+}
@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+
+use crate::db::schema_check::{
+    find_schema_issues, SchemaCheckError, SchemaIssue, REQUIRED_COLUMNS, REQUIRED_INDEXES,
+};
+
+fn complete_schema() -> (HashSet<(String, String)>, HashSet<String>) {
+    let columns = REQUIRED_COLUMNS
+        .iter()
+        .map(|required| (required.table.to_string(), required.column.to_string()))
+        .collect();
+    let indexes = REQUIRED_INDEXES.iter().map(|required| required.name.to_string()).collect();
+    (columns, indexes)
+}
+
+#[test]
+fn test_complete_schema_has_no_issues() {
+    let (columns, indexes) = complete_schema();
+    assert!(find_schema_issues(&columns, &indexes).is_empty());
+}
+
+#[test]
+fn test_missing_column_and_index_are_reported() {
+    let (mut columns, mut indexes) = complete_schema();
+    columns.remove(&("documents".to_string(), "ocr_status".to_string()));
+    indexes.remove("idx_documents_user_file_hash");
+
+    let issues = find_schema_issues(&columns, &indexes);
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].to_string(), "missing column documents.ocr_status");
+    assert!(issues[0].is_repairable());
+    assert_eq!(issues[1].to_string(), "missing index idx_documents_user_file_hash on documents");
+    assert!(!issues[1].is_repairable());
+}
+
+#[test]
+fn test_same_column_name_on_another_table_does_not_count() {
+    let (mut columns, indexes) = complete_schema();
+    columns.remove(&("sources".to_string(), "last_sync_at".to_string()));
+    columns.insert(("webdav_sync_state".to_string(), "last_sync_at".to_string()));
+
+    let issues = find_schema_issues(&columns, &indexes);
+    assert!(matches!(
+        issues.as_slice(),
+        [SchemaIssue::MissingColumn(column)] if column.table == "sources" && column.column == "last_sync_at"
+    ));
+}
+
+#[test]
+fn test_repair_statements_are_idempotent() {
+    let statements = REQUIRED_COLUMNS
+        .iter()
+        .filter_map(|required| required.repair)
+        .chain(REQUIRED_INDEXES.iter().filter_map(|required| required.repair));
+
+    for sql in statements {
+        assert!(sql.contains("IF NOT EXISTS"), "repair statement is not idempotent: {}", sql);
+    }
+}
+
+#[test]
+fn test_mismatch_error_is_actionable() {
+    let (mut columns, indexes) = complete_schema();
+    columns.remove(&("settings".to_string(), "ocr_max_pixels".to_string()));
+    columns.remove(&("webdav_directories".to_string(), "directory_etag".to_string()));
+
+    let message = SchemaCheckError::Mismatch(find_schema_issues(&columns, &indexes)).to_string();
+    assert!(message.contains("missing column settings.ocr_max_pixels (auto-repairable)"));
+    assert!(message.contains("missing column webdav_directories.directory_etag (manual fix required)"));
+    assert!(message.contains("SCHEMA_AUTO_REPAIR=true"));
+}
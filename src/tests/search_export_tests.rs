@@ -0,0 +1,35 @@
+use crate::models::SearchExportColumn;
+use crate::utils::csv::{escape_field, record};
+
+#[test]
+fn test_fields_are_quoted_only_when_needed() {
+    assert_eq!(escape_field("invoice.pdf"), "invoice.pdf");
+    assert_eq!(escape_field("Smith, J.pdf"), "\"Smith, J.pdf\"");
+    assert_eq!(escape_field("the \"final\" draft"), "\"the \"\"final\"\" draft\"");
+    assert_eq!(escape_field("two\nlines"), "\"two\nlines\"");
+    assert_eq!(escape_field(""), "");
+}
+
+#[test]
+fn test_formulas_are_neutralized() {
+    assert_eq!(escape_field("=SUM(A1:A2)"), "'=SUM(A1:A2)");
+    assert_eq!(escape_field("+1"), "'+1");
+    assert_eq!(escape_field("@cmd"), "'@cmd");
+    assert_eq!(escape_field("=1,2"), "\"'=1,2\"");
+}
+
+#[test]
+fn test_records_end_with_crlf() {
+    assert_eq!(record(["a", "b,c", ""]), "a,\"b,c\",\r\n");
+}
+
+#[test]
+fn test_export_columns_are_parsed_in_order() {
+    assert_eq!(SearchExportColumn::parse_list(None).unwrap(), SearchExportColumn::DEFAULT);
+    assert_eq!(SearchExportColumn::parse_list(Some(" , ")).unwrap(), SearchExportColumn::DEFAULT);
+    assert_eq!(
+        SearchExportColumn::parse_list(Some("Labels, filename")).unwrap(),
+        vec![SearchExportColumn::Labels, SearchExportColumn::Filename]
+    );
+    assert_eq!(SearchExportColumn::parse_list(Some("filename,ocr_text")), Err("ocr_text".to_string()));
+}
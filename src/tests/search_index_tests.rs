@@ -0,0 +1,119 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::db::search_index::{
+    per_user_search_indexes, SearchIndexHealth, SearchIndexInfo, SearchIndexStrategy, LARGE_TABLE_ROWS,
+    UNUSED_INDEX_MIN_BYTES,
+};
+
+fn index(name: &str) -> SearchIndexInfo {
+    SearchIndexInfo {
+        name: name.to_string(),
+        valid: true,
+        size_bytes: 8192,
+        scans: 12,
+        definition: format!("CREATE INDEX {} ON public.documents USING gin (...)", name),
+    }
+}
+
+fn healthy(strategy: SearchIndexStrategy) -> SearchIndexHealth {
+    let mut indexes = vec![index("idx_documents_content_search_unaccent")];
+    if strategy == SearchIndexStrategy::PerUser {
+        indexes.extend(per_user_search_indexes().into_iter().map(|(name, _)| index(name)));
+    }
+    SearchIndexHealth {
+        strategy,
+        live_rows: 5000,
+        dead_rows: 100,
+        last_analyzed: Some(Utc.with_ymd_and_hms(2026, 10, 15, 3, 0, 0).unwrap()),
+        users: 4,
+        largest_user_documents: 2000,
+        indexes,
+        missing_indexes: Vec::new(),
+        sample_plan: Some(vec![
+            "Bitmap Heap Scan on documents  (cost=24.03..28.05 rows=1 width=16)".to_string(),
+            "  ->  Bitmap Index Scan on idx_documents_user_content_search  (cost=0.00..24.03 rows=1 width=0)".to_string(),
+        ]),
+    }
+}
+
+fn now() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap()
+}
+
+#[test]
+fn test_strategy_parsing() {
+    assert_eq!("per_user".parse::<SearchIndexStrategy>().unwrap(), SearchIndexStrategy::PerUser);
+    assert_eq!(" Shared ".parse::<SearchIndexStrategy>().unwrap(), SearchIndexStrategy::Shared);
+    assert_eq!("".parse::<SearchIndexStrategy>().unwrap(), SearchIndexStrategy::Shared);
+    assert!("partitioned".parse::<SearchIndexStrategy>().is_err());
+    assert_eq!(SearchIndexStrategy::PerUser.to_string(), "per_user");
+}
+
+#[test]
+fn test_per_user_indexes_lead_with_the_user() {
+    let indexes = per_user_search_indexes();
+    assert_eq!(indexes.len(), 2);
+    assert!(indexes.iter().all(|(_, definition)| definition.starts_with("user_id, to_tsvector(")));
+    assert!(indexes[0].1.contains("'english_unaccent'"));
+    assert!(indexes[1].1.contains("document_search_config(search_language, true)"));
+}
+
+#[test]
+fn test_healthy_tables_get_no_advice() {
+    assert!(healthy(SearchIndexStrategy::Shared).advice(now()).is_empty());
+    let health = healthy(SearchIndexStrategy::PerUser);
+    assert!(health.sample_plan_uses_user_index());
+    assert!(health.advice(now()).is_empty());
+}
+
+#[test]
+fn test_invalid_and_missing_indexes_are_reported_first() {
+    let mut health = healthy(SearchIndexStrategy::PerUser);
+    health.indexes.retain(|index| index.name != "idx_documents_user_content_search_language");
+    health.missing_indexes = vec!["idx_documents_user_content_search_language".to_string()];
+    health.indexes[1].valid = false;
+
+    let advice = health.advice(now());
+    assert_eq!(advice.len(), 2, "{:?}", advice);
+    assert!(advice[0].contains("REINDEX INDEX CONCURRENTLY idx_documents_user_content_search"));
+    assert!(advice[1].contains("idx_documents_user_content_search_language is missing"));
+}
+
+#[test]
+fn test_large_shared_tables_are_advised_to_index_per_user() {
+    let mut health = healthy(SearchIndexStrategy::Shared);
+    health.live_rows = LARGE_TABLE_ROWS;
+    assert!(health.advice(now())[0].contains("SEARCH_INDEX_STRATEGY=per_user"));
+
+    // A single user's table doesn't gain anything from it
+    health.users = 1;
+    assert!(health.advice(now()).is_empty());
+}
+
+#[test]
+fn test_table_maintenance_advice() {
+    let mut health = healthy(SearchIndexStrategy::Shared);
+    health.dead_rows = 2500;
+    health.last_analyzed = Some(now() - Duration::days(30));
+    let advice = health.advice(now());
+    assert_eq!(advice.len(), 2, "{:?}", advice);
+    assert!(advice[0].starts_with("33% of the rows of documents are dead"));
+    assert!(advice[1].contains("last analyzed on 2026-09-16"));
+
+    health.dead_rows = 0;
+    health.last_analyzed = None;
+    assert!(health.advice(now())[0].contains("never been analyzed"));
+}
+
+#[test]
+fn test_plans_without_a_user_index_and_unused_indexes() {
+    let mut health = healthy(SearchIndexStrategy::PerUser);
+    health.sample_plan = Some(vec!["Seq Scan on documents  (cost=0.00..35.50 rows=1 width=16)".to_string()]);
+    health.indexes[0].scans = 0;
+    health.indexes[0].size_bytes = UNUSED_INDEX_MIN_BYTES;
+
+    let advice = health.advice(now());
+    assert_eq!(advice.len(), 2, "{:?}", advice);
+    assert!(advice[0].contains("doesn't read a per-user index"));
+    assert!(advice[1].contains("idx_documents_content_search_unaccent takes 100 MB"));
+}
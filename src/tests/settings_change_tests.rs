@@ -0,0 +1,53 @@
+use crate::models::{Settings, UpdateSettings};
+
+#[test]
+fn test_identical_settings_have_no_ocr_changes() {
+    let settings = Settings::default();
+    assert!(settings.ocr_changes_since(&settings.clone()).is_empty());
+}
+
+#[test]
+fn test_ocr_changes_are_reported_by_name() {
+    let previous = Settings::default();
+    let mut current = previous.clone();
+    current.ocr_language = "deu".to_string();
+    current.ocr_dpi = previous.ocr_dpi + 100;
+
+    assert_eq!(current.ocr_changes_since(&previous), vec!["ocr_language", "ocr_dpi"]);
+}
+
+#[test]
+fn test_non_ocr_changes_are_ignored() {
+    let previous = Settings::default();
+    let mut current = previous.clone();
+    current.search_results_per_page += 10;
+    current.ocr_reprocess_on_settings_change = !previous.ocr_reprocess_on_settings_change;
+
+    assert!(current.ocr_changes_since(&previous).is_empty());
+}
+
+#[test]
+fn test_ocr_overrides_apply_only_ocr_fields() {
+    let settings = Settings::default();
+    let overrides: UpdateSettings = serde_json::from_value(serde_json::json!({
+        "ocr_language": "deu",
+        "ocr_brightness_boost": 1.5,
+        "ocr_whitelist_chars": "0123456789",
+        "search_results_per_page": 99,
+    }))
+    .unwrap();
+
+    let preview = settings.with_ocr_overrides(&overrides);
+    assert_eq!(preview.ocr_language, "deu");
+    assert_eq!(preview.ocr_brightness_boost, 1.5);
+    assert_eq!(preview.ocr_whitelist_chars.as_deref(), Some("0123456789"));
+    assert_eq!(preview.search_results_per_page, settings.search_results_per_page);
+    assert_eq!(preview.ocr_changes_since(&settings), vec!["ocr_language", "ocr_whitelist_chars", "ocr_brightness_boost"]);
+}
+
+#[test]
+fn test_empty_ocr_overrides_keep_settings() {
+    let settings = Settings::default();
+    let overrides: UpdateSettings = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert!(settings.with_ocr_overrides(&overrides).ocr_changes_since(&settings).is_empty());
+}
@@ -0,0 +1,116 @@
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::models::{SourceCircuit, SourceCircuitBreakerConfig, SourceCircuitState};
+
+fn config(failure_threshold: u32, cooldown_minutes: u32, max_cooldown_minutes: u32) -> SourceCircuitBreakerConfig {
+    SourceCircuitBreakerConfig { failure_threshold, cooldown_minutes, max_cooldown_minutes }
+}
+
+#[test]
+fn test_circuit_opens_after_threshold() {
+    let config = config(3, 10, 60);
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+    let mut circuit = SourceCircuit::closed(Uuid::new_v4());
+
+    assert!(!circuit.record_failure(&config, now));
+    assert!(!circuit.record_failure(&config, now));
+    assert_eq!(circuit.state, SourceCircuitState::Closed);
+    assert!(circuit.record_failure(&config, now));
+
+    assert_eq!(circuit.state, SourceCircuitState::Open);
+    assert_eq!(circuit.trips, 1);
+    assert_eq!(circuit.opened_at, Some(now));
+    assert_eq!(circuit.cooldown_until, Some(now + Duration::minutes(10)));
+}
+
+#[test]
+fn test_open_circuit_skips_syncs_until_cooldown_ends() {
+    let config = config(1, 10, 60);
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+    let mut circuit = SourceCircuit::closed(Uuid::new_v4());
+    circuit.record_failure(&config, now);
+
+    assert!(!circuit.allow_scheduled_sync(now + Duration::minutes(9)));
+    assert_eq!(circuit.state, SourceCircuitState::Open);
+    assert!(circuit.allow_scheduled_sync(now + Duration::minutes(10)));
+    assert_eq!(circuit.state, SourceCircuitState::HalfOpen);
+}
+
+#[test]
+fn test_failed_recovery_reopens_with_doubled_cooldown() {
+    let config = config(1, 10, 60);
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+    let mut circuit = SourceCircuit::closed(Uuid::new_v4());
+    circuit.record_failure(&config, now);
+
+    let retry_at = now + Duration::minutes(10);
+    assert!(circuit.allow_scheduled_sync(retry_at));
+    assert!(circuit.record_failure(&config, retry_at));
+    assert_eq!(circuit.state, SourceCircuitState::Open);
+    assert_eq!(circuit.trips, 2);
+    assert_eq!(circuit.cooldown_until, Some(retry_at + Duration::minutes(20)));
+}
+
+#[test]
+fn test_successful_recovery_closes_circuit() {
+    let config = config(1, 10, 60);
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+    let mut circuit = SourceCircuit::closed(Uuid::new_v4());
+    circuit.record_failure(&config, now);
+    circuit.allow_scheduled_sync(now + Duration::minutes(10));
+
+    circuit.record_success();
+    assert_eq!(circuit, SourceCircuit::closed(circuit.source_id));
+}
+
+#[test]
+fn test_failures_while_open_do_not_extend_cooldown() {
+    let config = config(1, 10, 60);
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+    let mut circuit = SourceCircuit::closed(Uuid::new_v4());
+    circuit.record_failure(&config, now);
+
+    assert!(!circuit.record_failure(&config, now + Duration::minutes(5)));
+    assert_eq!(circuit.consecutive_failures, 2);
+    assert_eq!(circuit.cooldown_until, Some(now + Duration::minutes(10)));
+}
+
+#[test]
+fn test_cooldown_is_capped() {
+    let config = config(1, 10, 60);
+    assert_eq!(config.cooldown_for_trip(1), Duration::minutes(10));
+    assert_eq!(config.cooldown_for_trip(3), Duration::minutes(40));
+    assert_eq!(config.cooldown_for_trip(4), Duration::minutes(60));
+    assert_eq!(config.cooldown_for_trip(100), Duration::minutes(60));
+}
+
+#[test]
+fn test_config_from_source_config() {
+    let defaults = SourceCircuitBreakerConfig::from_source_config(&json!({"auto_sync": true})).unwrap();
+    assert_eq!(defaults, SourceCircuitBreakerConfig::default());
+
+    let custom = SourceCircuitBreakerConfig::from_source_config(&json!({
+        "circuit_breaker": {"failure_threshold": 2}
+    }))
+    .unwrap();
+    assert_eq!(custom.failure_threshold, 2);
+    assert_eq!(custom.cooldown_minutes, SourceCircuitBreakerConfig::default().cooldown_minutes);
+}
+
+#[test]
+fn test_invalid_config_is_rejected() {
+    assert!(SourceCircuitBreakerConfig::from_source_config(&json!({
+        "circuit_breaker": {"failure_threshold": 0}
+    }))
+    .is_err());
+    assert!(SourceCircuitBreakerConfig::from_source_config(&json!({
+        "circuit_breaker": {"cooldown_minutes": 120, "max_cooldown_minutes": 60}
+    }))
+    .is_err());
+    assert!(SourceCircuitBreakerConfig::from_source_config(&json!({
+        "circuit_breaker": {"failure_threshold": "many"}
+    }))
+    .is_err());
+}
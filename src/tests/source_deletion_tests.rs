@@ -0,0 +1,53 @@
+use crate::services::source_deletion::{create_confirmation_token, purge_time, verify_confirmation_token};
+use chrono::{Duration, TimeZone, Utc};
+use uuid::Uuid;
+
+const SECRET: &str = "test-secret";
+
+#[test]
+fn test_confirmation_token_is_bound_to_source_user_and_count() {
+    let user_id = Uuid::new_v4();
+    let source_id = Uuid::new_v4();
+    let token = create_confirmation_token(SECRET, user_id, source_id, 42, Utc::now() + Duration::minutes(15)).unwrap();
+
+    assert!(verify_confirmation_token(SECRET, &token, user_id, source_id, 42));
+    assert!(!verify_confirmation_token(SECRET, &token, user_id, source_id, 43), "count changed since the preview");
+    assert!(!verify_confirmation_token(SECRET, &token, user_id, Uuid::new_v4(), 42), "other source");
+    assert!(!verify_confirmation_token(SECRET, &token, Uuid::new_v4(), source_id, 42), "other user");
+    assert!(!verify_confirmation_token("other-secret", &token, user_id, source_id, 42), "other secret");
+    assert!(!verify_confirmation_token(SECRET, "not-a-token", user_id, source_id, 42));
+}
+
+#[test]
+fn test_expired_confirmation_token_is_rejected() {
+    let user_id = Uuid::new_v4();
+    let source_id = Uuid::new_v4();
+    let token = create_confirmation_token(SECRET, user_id, source_id, 1, Utc::now() - Duration::hours(1)).unwrap();
+    assert!(!verify_confirmation_token(SECRET, &token, user_id, source_id, 1));
+}
+
+#[test]
+fn test_login_token_does_not_confirm_deletion() {
+    let user = crate::models::User {
+        id: Uuid::new_v4(),
+        username: "alice".to_string(),
+        email: "alice@example.com".to_string(),
+        password_hash: None,
+        role: crate::models::UserRole::User,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        oidc_subject: None,
+        oidc_issuer: None,
+        oidc_email: None,
+        auth_provider: crate::models::AuthProvider::Local,
+    };
+    let login_token = crate::auth::create_jwt(&user, SECRET).unwrap();
+    assert!(!verify_confirmation_token(SECRET, &login_token, user.id, user.id, 0));
+}
+
+#[test]
+fn test_purge_time_follows_the_grace_period() {
+    let deleted_at = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+    assert_eq!(purge_time(deleted_at, 0), None);
+    assert_eq!(purge_time(deleted_at, 90), Some(Utc.with_ymd_and_hms(2026, 10, 16, 13, 30, 0).unwrap()));
+}
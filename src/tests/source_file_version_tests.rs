@@ -0,0 +1,41 @@
+use chrono::{TimeZone, Utc};
+
+use crate::models::SourceFileVersion;
+
+fn version(etag: &str) -> SourceFileVersion {
+    SourceFileVersion {
+        etag: etag.to_string(),
+        size: 2048,
+        last_modified: Some(Utc.with_ymd_and_hms(2024, 5, 14, 9, 30, 0).unwrap()),
+    }
+}
+
+#[test]
+fn test_if_none_match_quotes_the_etag() {
+    assert_eq!(version("abc123").if_none_match().as_deref(), Some("\"abc123\""));
+    assert_eq!(version("").if_none_match(), None);
+}
+
+#[test]
+fn test_response_etag_decides_when_present() {
+    let known = version("abc123");
+    let modified = known.last_modified;
+    assert!(known.matches_response(Some("\"abc123\""), None, None));
+    assert!(known.matches_response(Some("W/\"abc123\""), None, None));
+    // A different ETag wins over a matching size and modification time
+    assert!(!known.matches_response(Some("\"def456\""), Some(2048), modified));
+}
+
+#[test]
+fn test_without_etags_size_and_mtime_must_match() {
+    let known = version("abc123");
+    let modified = known.last_modified;
+    let half_second_later = modified.map(|time| time + chrono::Duration::milliseconds(500));
+    assert!(known.matches_response(None, Some(2048), modified));
+    assert!(known.matches_response(None, Some(2048), half_second_later));
+    assert!(!known.matches_response(None, Some(4096), modified));
+    assert!(!known.matches_response(None, Some(2048), modified.map(|time| time + chrono::Duration::seconds(5))));
+    // Nothing to compare means the file is downloaded
+    assert!(!known.matches_response(None, None, modified));
+    assert!(!version("").matches_response(None, Some(2048), None));
+}
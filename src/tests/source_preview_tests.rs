@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use crate::models::{SourcePreviewEstimate, WebDAVCrawlEstimate};
+
+#[test]
+fn test_complete_sample_is_exact() {
+    let estimate = SourcePreviewEstimate::from_sample(100, 1024 * 1024, Duration::from_secs(2), 2, 2);
+    assert_eq!(estimate.estimated_files, 100);
+    assert_eq!(estimate.estimated_size_bytes, 1024 * 1024);
+    assert!(estimate.exact);
+}
+
+#[test]
+fn test_partial_sample_is_extrapolated() {
+    let estimate = SourcePreviewEstimate::from_sample(300, 3_000, Duration::from_secs(3), 3, 9);
+    assert_eq!(estimate.estimated_files, 900);
+    assert_eq!(estimate.estimated_size_bytes, 9_000);
+    assert_eq!(estimate.folders_sampled, 3);
+    assert_eq!(estimate.folders_total, 9);
+    assert!(!estimate.exact);
+}
+
+#[test]
+fn test_sync_time_grows_with_files_and_size() {
+    let small = SourcePreviewEstimate::from_sample(10, 1024, Duration::from_secs(1), 1, 1);
+    let more_files = SourcePreviewEstimate::from_sample(10_000, 1024, Duration::from_secs(1), 1, 1);
+    let larger = SourcePreviewEstimate::from_sample(10, 50 * 1024 * 1024 * 1024, Duration::from_secs(1), 1, 1);
+    assert!(more_files.estimated_sync_hours > small.estimated_sync_hours);
+    assert!(larger.estimated_sync_hours > small.estimated_sync_hours);
+    assert!(small.estimated_sync_hours > 0.0);
+}
+
+#[test]
+fn test_empty_sample_estimates_nothing() {
+    let estimate = SourcePreviewEstimate::from_sample(0, 0, Duration::ZERO, 0, 0);
+    assert_eq!(estimate.estimated_files, 0);
+    assert_eq!(estimate.estimated_size_bytes, 0);
+    assert_eq!(estimate.estimated_sync_hours, 0.0);
+}
+
+#[test]
+fn test_webdav_estimate_is_converted() {
+    let crawl = WebDAVCrawlEstimate {
+        folders: vec![],
+        total_files: 120,
+        total_supported_files: 100,
+        total_estimated_time_hours: 0.5,
+        total_size_mb: 2.0,
+    };
+    let estimate = SourcePreviewEstimate::from_webdav(&crawl, 3, 5);
+    assert_eq!(estimate.estimated_files, 100);
+    assert_eq!(estimate.estimated_size_bytes, 2 * 1024 * 1024);
+    assert!(estimate.estimated_sync_hours > 0.5);
+    assert!(!estimate.exact);
+}
@@ -0,0 +1,96 @@
+use serde_json::json;
+
+use crate::models::{source_queues_ocr, LocalFolderSourceConfig, SourceProcessingMode};
+use crate::ocr::enhanced::PdfTextLayer;
+
+fn local_folder_config(extra: serde_json::Value) -> serde_json::Value {
+    let mut config = json!({
+        "watch_folders": ["/data"],
+        "file_extensions": ["pdf"],
+        "auto_sync": true,
+        "sync_interval_minutes": 60,
+        "recursive": true,
+        "follow_symlinks": false,
+    });
+    config.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    config
+}
+
+#[test]
+fn test_processing_mode_defaults_to_full_for_existing_sources() {
+    let config: LocalFolderSourceConfig = serde_json::from_value(local_folder_config(json!({}))).unwrap();
+    assert_eq!(config.processing_mode, SourceProcessingMode::Full);
+}
+
+#[test]
+fn test_processing_mode_parses_from_source_config() {
+    for (value, expected) in [
+        ("full", SourceProcessingMode::Full),
+        ("text_only", SourceProcessingMode::TextOnly),
+        ("none", SourceProcessingMode::None),
+    ] {
+        let config: LocalFolderSourceConfig =
+            serde_json::from_value(local_folder_config(json!({ "processing_mode": value }))).unwrap();
+        assert_eq!(config.processing_mode, expected);
+        assert_eq!(expected.to_string(), value);
+        assert_eq!(SourceProcessingMode::try_from(value.to_string()), Ok(expected));
+    }
+}
+
+#[test]
+fn test_unknown_processing_mode_is_rejected() {
+    let result: Result<LocalFolderSourceConfig, _> =
+        serde_json::from_value(local_folder_config(json!({ "processing_mode": "ocr_only" })));
+    assert!(result.is_err());
+    assert!(SourceProcessingMode::try_from("ocr_only".to_string()).is_err());
+}
+
+#[test]
+fn test_text_layer_keeps_pages_with_text() {
+    let layer = PdfTextLayer::from_pdftotext_output("Page one text\n\x0cPage two text\n\x0c");
+
+    assert_eq!(layer.text, "Page one text\n\nPage two text");
+    assert_eq!(layer.pages_with_text, 2);
+    assert_eq!(layer.skipped_pages, 0);
+}
+
+#[test]
+fn test_text_layer_skips_image_only_pages() {
+    // Page 2 is a scan: pdftotext prints nothing but whitespace for it
+    let layer = PdfTextLayer::from_pdftotext_output("Cover letter\n\x0c  \n\n\x0cAppendix\n\x0c");
+
+    assert_eq!(layer.text, "Cover letter\n\nAppendix");
+    assert_eq!(layer.pages_with_text, 2);
+    assert_eq!(layer.skipped_pages, 1);
+}
+
+#[test]
+fn test_text_layer_of_fully_scanned_pdf_is_empty() {
+    let layer = PdfTextLayer::from_pdftotext_output("\x0c\x0c\x0c");
+
+    assert!(layer.text.is_empty());
+    assert_eq!(layer.pages_with_text, 0);
+    assert_eq!(layer.skipped_pages, 3);
+
+    let empty = PdfTextLayer::from_pdftotext_output("");
+    assert_eq!((empty.pages_with_text, empty.skipped_pages), (0, 0));
+}
+
+#[test]
+fn test_ocr_is_enabled_unless_turned_off() {
+    let config: LocalFolderSourceConfig = serde_json::from_value(local_folder_config(json!({}))).unwrap();
+    assert!(config.ocr_enabled);
+
+    let config: LocalFolderSourceConfig =
+        serde_json::from_value(local_folder_config(json!({ "ocr_enabled": false }))).unwrap();
+    assert!(!config.ocr_enabled);
+}
+
+#[test]
+fn test_sources_without_ocr_still_queue_text_layer_extraction() {
+    assert!(source_queues_ocr(true, SourceProcessingMode::Full));
+    assert!(source_queues_ocr(true, SourceProcessingMode::None));
+    assert!(!source_queues_ocr(false, SourceProcessingMode::Full));
+    assert!(!source_queues_ocr(false, SourceProcessingMode::None));
+    assert!(source_queues_ocr(false, SourceProcessingMode::TextOnly));
+}
@@ -0,0 +1,205 @@
+use crate::storage::compression::{
+    compress, compress_for_storage, decompress, decompress_for_path, is_compressed_path,
+    is_compressible, strip_compression_suffix, StorageCompression,
+};
+use crate::storage::local::LocalStorageBackend;
+use crate::storage::StorageBackend;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+fn compressible_text() -> Vec<u8> {
+    "Invoice 2024-001 for consulting services rendered. ".repeat(200).into_bytes()
+}
+
+#[test]
+fn test_parse_storage_compression() {
+    assert_eq!("none".parse::<StorageCompression>(), Ok(StorageCompression::None));
+    assert_eq!("".parse::<StorageCompression>(), Ok(StorageCompression::None));
+    assert_eq!("GZIP".parse::<StorageCompression>(), Ok(StorageCompression::Gzip));
+    assert_eq!(" zstd ".parse::<StorageCompression>(), Ok(StorageCompression::Zstd));
+    assert!("brotli".parse::<StorageCompression>().is_err());
+}
+
+#[test]
+fn test_gzip_round_trip() {
+    let data = compressible_text();
+    let compressed = compress(StorageCompression::Gzip, &data).unwrap();
+    assert!(compressed.len() < data.len());
+    assert_eq!(decompress(StorageCompression::Gzip, &compressed).unwrap(), data);
+}
+
+#[test]
+fn test_zstd_round_trip() {
+    let data = compressible_text();
+    let compressed = compress(StorageCompression::Zstd, &data).unwrap();
+    assert!(compressed.len() < data.len());
+    assert_eq!(decompress(StorageCompression::Zstd, &compressed).unwrap(), data);
+}
+
+#[test]
+fn test_compression_detected_from_path() {
+    let id = Uuid::new_v4();
+    assert_eq!(StorageCompression::from_path(&format!("documents/{}.pdf.readur.zst", id)), StorageCompression::Zstd);
+    assert_eq!(StorageCompression::from_path(&format!("documents/{}.tiff.readur.gz", id)), StorageCompression::Gzip);
+    assert_eq!(StorageCompression::from_path(&format!("documents/{}.readur.gz", id)), StorageCompression::Gzip);
+    assert_eq!(StorageCompression::from_path(&format!("documents/{}.pdf", id)), StorageCompression::None);
+    assert!(is_compressed_path(&format!("s3://documents/u/2024/01/{}.txt.readur.zst", id)));
+    assert_eq!(
+        strip_compression_suffix(&format!("documents/{}.pdf.readur.zst", id)),
+        format!("documents/{}.pdf", id)
+    );
+    assert_eq!(strip_compression_suffix(&format!("documents/{}.pdf", id)), format!("documents/{}.pdf", id));
+}
+
+#[test]
+fn test_user_file_extensions_are_not_compression_markers() {
+    let id = Uuid::new_v4();
+    // Documents whose own extension is a compression format are stored as-is
+    assert_eq!(StorageCompression::from_path(&format!("documents/{}.gz", id)), StorageCompression::None);
+    assert_eq!(StorageCompression::from_path(&format!("documents/{}.zst", id)), StorageCompression::None);
+    // Legacy uploads keep the name the user gave them
+    assert_eq!(StorageCompression::from_path("uploads/notes.readur.gz"), StorageCompression::None);
+    assert_eq!(StorageCompression::from_path("uploads/archive.tar.gz"), StorageCompression::None);
+}
+
+#[test]
+fn test_decompress_for_path_passes_through_uncompressed() {
+    let id = Uuid::new_v4();
+    let data = compressible_text();
+    assert_eq!(decompress_for_path(&format!("documents/{}.txt", id), data.clone()).unwrap(), data);
+
+    let compressed = compress(StorageCompression::Gzip, &data).unwrap();
+    assert_eq!(decompress_for_path(&format!("documents/{}.gz", id), compressed.clone()).unwrap(), compressed);
+    assert_eq!(decompress_for_path(&format!("documents/{}.txt.readur.gz", id), compressed).unwrap(), data);
+}
+
+#[test]
+fn test_already_compressed_formats_are_skipped() {
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0];
+    jpeg.extend(vec![0u8; 4096]);
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    png.extend(vec![0u8; 4096]);
+
+    assert!(!is_compressible("scan.jpg", &jpeg));
+    assert!(!is_compressible("scan.bin", &jpeg));
+    assert!(!is_compressible("scan.bin", &png));
+    assert!(!is_compressible("report.docx", &compressible_text()));
+    assert!(is_compressible("report.pdf", &compressible_text()));
+    assert!(is_compressible("scan.tiff", &compressible_text()));
+
+    assert_eq!(compress_for_storage(StorageCompression::Zstd, "scan.jpg", &jpeg).unwrap(), None);
+}
+
+#[test]
+fn test_small_or_disabled_content_is_not_compressed() {
+    assert_eq!(compress_for_storage(StorageCompression::Gzip, "note.txt", b"short note").unwrap(), None);
+    assert_eq!(compress_for_storage(StorageCompression::None, "note.txt", &compressible_text()).unwrap(), None);
+    assert!(compress_for_storage(StorageCompression::Zstd, "note.txt", &compressible_text()).unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_local_backend_compressed_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let upload_path = temp_dir.path().to_string_lossy().to_string();
+    let data = compressible_text();
+
+    for compression in [StorageCompression::Gzip, StorageCompression::Zstd] {
+        let backend = LocalStorageBackend::new(upload_path.clone()).with_compression(compression);
+        let path = backend
+            .store_document(Uuid::new_v4(), Uuid::new_v4(), "invoice.txt", &data)
+            .await
+            .unwrap();
+
+        assert!(path.ends_with(&format!(".txt{}", compression.suffix())));
+        let on_disk = std::fs::read(&path).unwrap();
+        assert!(on_disk.len() < data.len());
+        assert_eq!(backend.retrieve_file(&path).await.unwrap(), data);
+    }
+}
+
+#[tokio::test]
+async fn test_local_backend_reads_mixed_mode_library() {
+    let temp_dir = TempDir::new().unwrap();
+    let upload_path = temp_dir.path().to_string_lossy().to_string();
+    let data = compressible_text();
+
+    let compressed_backend = LocalStorageBackend::new(upload_path.clone())
+        .with_compression(StorageCompression::Zstd);
+    let compressed_path = compressed_backend
+        .store_document(Uuid::new_v4(), Uuid::new_v4(), "old.txt", &data)
+        .await
+        .unwrap();
+
+    // After switching compression off, both old and new objects must be readable
+    let plain_backend = LocalStorageBackend::new(upload_path);
+    let plain_path = plain_backend
+        .store_document(Uuid::new_v4(), Uuid::new_v4(), "new.txt", &data)
+        .await
+        .unwrap();
+
+    assert!(!is_compressed_path(&plain_path));
+    assert_eq!(plain_backend.retrieve_file(&compressed_path).await.unwrap(), data);
+    assert_eq!(plain_backend.retrieve_file(&plain_path).await.unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_local_backend_stores_images_uncompressed() {
+    let temp_dir = TempDir::new().unwrap();
+    let backend = LocalStorageBackend::new(temp_dir.path().to_string_lossy().to_string())
+        .with_compression(StorageCompression::Gzip);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    png.extend(vec![0u8; 4096]);
+
+    let path = backend
+        .store_document(Uuid::new_v4(), Uuid::new_v4(), "scan.png", &png)
+        .await
+        .unwrap();
+
+    assert!(path.ends_with(".png"));
+    assert_eq!(backend.retrieve_file(&path).await.unwrap(), png);
+}
+
+#[tokio::test]
+async fn test_local_backend_deletes_compressed_document() {
+    let temp_dir = TempDir::new().unwrap();
+    let backend = LocalStorageBackend::new(temp_dir.path().to_string_lossy().to_string())
+        .with_compression(StorageCompression::Zstd);
+    let document_id = Uuid::new_v4();
+
+    let path = backend
+        .store_document(Uuid::new_v4(), document_id, "invoice.txt", &compressible_text())
+        .await
+        .unwrap();
+    assert!(std::path::Path::new(&path).exists());
+
+    backend.delete_document_files(Uuid::new_v4(), document_id, "invoice.txt").await.unwrap();
+    assert!(!std::path::Path::new(&path).exists());
+}
+
+#[tokio::test]
+async fn test_local_backend_returns_gzip_upload_unchanged() {
+    let temp_dir = TempDir::new().unwrap();
+    let upload_path = temp_dir.path().to_string_lossy().to_string();
+    let archive = compress(StorageCompression::Gzip, &compressible_text()).unwrap();
+    let zstd_named = compressible_text();
+
+    for compression in [StorageCompression::None, StorageCompression::Gzip, StorageCompression::Zstd] {
+        let backend = LocalStorageBackend::new(upload_path.clone()).with_compression(compression);
+
+        let path = backend
+            .store_document(Uuid::new_v4(), Uuid::new_v4(), "archive.gz", &archive)
+            .await
+            .unwrap();
+        assert!(!is_compressed_path(&path), "{}", path);
+        assert_eq!(backend.retrieve_file(&path).await.unwrap(), archive);
+
+        // A plain file that merely has a .zst name
+        let path = backend
+            .store_document(Uuid::new_v4(), Uuid::new_v4(), "notes.zst", &zstd_named)
+            .await
+            .unwrap();
+        assert!(!is_compressed_path(&path), "{}", path);
+        assert_eq!(backend.retrieve_file(&path).await.unwrap(), zstd_named);
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::services::file_service::FileService;
+use crate::storage::local::LocalStorageBackend;
+use crate::storage::{is_user_object_key, StorageAccessDenied, StorageBackend};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+fn local_backend(temp_dir: &TempDir) -> LocalStorageBackend {
+    LocalStorageBackend::new(temp_dir.path().join("uploads").to_string_lossy().to_string())
+}
+
+fn is_access_denied(result: anyhow::Result<impl std::fmt::Debug>) -> bool {
+    result.expect_err("access should be refused").is::<StorageAccessDenied>()
+}
+
+#[test]
+fn test_object_keys_are_confined_to_the_users_prefixes() {
+    let user_id = Uuid::new_v4();
+    let other_id = Uuid::new_v4();
+
+    assert!(is_user_object_key(user_id, &format!("s3://documents/{}/2026/10/doc.pdf", user_id)));
+    assert!(is_user_object_key(user_id, &format!("thumbnails/{}/doc_thumb.jpg", user_id)));
+    assert!(is_user_object_key(user_id, &format!("processed_images/{}/doc_processed.png", user_id)));
+
+    assert!(!is_user_object_key(user_id, &format!("s3://documents/{}/2026/10/doc.pdf", other_id)));
+    assert!(!is_user_object_key(user_id, &format!("documents/{}/../{}/doc.pdf", user_id, other_id)));
+    assert!(!is_user_object_key(user_id, &format!("/documents/{}/doc.pdf", user_id)));
+    assert!(!is_user_object_key(user_id, &format!("backups/{}/dump.sql", user_id)));
+    assert!(!is_user_object_key(user_id, &format!("documents/{}", user_id)));
+}
+
+#[tokio::test]
+async fn test_local_backend_refuses_paths_outside_the_upload_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let backend = local_backend(&temp_dir);
+    let user_id = Uuid::new_v4();
+    let secret = temp_dir.path().join("secret.txt");
+    std::fs::write(&secret, b"not yours").unwrap();
+
+    let path = backend.store_document(user_id, Uuid::new_v4(), "invoice.pdf", b"%PDF").await.unwrap();
+    assert_eq!(backend.retrieve_user_file(user_id, &path).await.unwrap(), b"%PDF");
+
+    let traversal = format!("{}/../../secret.txt", backend.get_documents_path().display());
+    assert!(is_access_denied(backend.retrieve_user_file(user_id, &traversal).await));
+    assert!(is_access_denied(backend.retrieve_user_file(user_id, &secret.to_string_lossy()).await));
+    assert!(is_access_denied(backend.retrieve_user_file(user_id, "/etc/passwd").await));
+}
+
+#[tokio::test]
+async fn test_local_backend_keeps_each_users_files_in_their_own_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let backend = local_backend(&temp_dir);
+    let user_a = Uuid::new_v4();
+    let user_b = Uuid::new_v4();
+    let document_id = Uuid::new_v4();
+
+    let document = backend.store_document(user_b, document_id, "invoice.pdf", b"%PDF").await.unwrap();
+    let thumbnail = backend.store_thumbnail(user_b, document_id, b"jpeg").await.unwrap();
+    let processed = backend.store_processed_image(user_b, document_id, b"png").await.unwrap();
+    assert!(document.starts_with(&backend.get_user_documents_path(user_b).to_string_lossy().to_string()));
+    assert!(thumbnail.starts_with(&backend.get_user_thumbnails_path(user_b).to_string_lossy().to_string()));
+    assert!(processed.starts_with(&backend.get_user_processed_images_path(user_b).to_string_lossy().to_string()));
+
+    for path in [&document, &thumbnail, &processed] {
+        assert!(backend.is_user_path(user_b, path));
+        assert!(!backend.is_user_path(user_a, path));
+    }
+    assert_eq!(backend.retrieve_user_file(user_b, &document).await.unwrap(), b"%PDF");
+    assert!(is_access_denied(backend.retrieve_user_file(user_a, &document).await));
+
+    // A document stored before per-user directories isn't in anyone's namespace
+    let flat = backend.get_documents_path().join(format!("{}.pdf", Uuid::new_v4()));
+    assert!(!backend.is_user_path(user_b, &flat.to_string_lossy()));
+
+    backend.delete_document_files(user_b, document_id, "invoice.pdf").await.unwrap();
+    for path in [&document, &thumbnail, &processed] {
+        assert!(!std::path::Path::new(path).exists(), "{} was not deleted", path);
+    }
+}
+
+#[tokio::test]
+async fn test_local_backend_refuses_to_delete_through_a_traversing_filename() {
+    let temp_dir = TempDir::new().unwrap();
+    let backend = local_backend(&temp_dir);
+    backend.initialize().await.unwrap();
+    let secret = temp_dir.path().join("secret.txt");
+    std::fs::write(&secret, b"not yours").unwrap();
+
+    let result = backend.delete_document_files(Uuid::new_v4(), Uuid::new_v4(), "../../secret.txt").await;
+    assert!(is_access_denied(result));
+    let result = backend.delete_document_files(Uuid::new_v4(), Uuid::new_v4(), &secret.to_string_lossy()).await;
+    assert!(is_access_denied(result));
+    assert!(secret.exists());
+}
+
+#[tokio::test]
+async fn test_file_service_confines_user_reads_unless_isolation_is_disabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let backend = local_backend(&temp_dir);
+    let upload_path = backend.get_upload_path().to_string();
+    let user_id = Uuid::new_v4();
+    let outside = temp_dir.path().join("outside.txt");
+    std::fs::write(&outside, b"not yours").unwrap();
+    let outside = outside.to_string_lossy().to_string();
+
+    let file_service = FileService::with_storage(upload_path.clone(), Arc::new(backend));
+    let path = file_service.save_document_file(user_id, Uuid::new_v4(), "notes.txt", b"notes").await.unwrap();
+    assert_eq!(file_service.read_user_file(user_id, &path).await.unwrap(), b"notes");
+    assert!(is_access_denied(file_service.read_user_file(user_id, &outside).await));
+    assert!(is_access_denied(
+        file_service.read_user_file(user_id, &format!("{}/documents/../../outside.txt", upload_path)).await
+    ));
+    let other_users_object = format!("s3://documents/{}/2026/10/doc.pdf", Uuid::new_v4());
+    assert!(is_access_denied(file_service.read_user_file(user_id, &other_users_object).await));
+    assert!(is_access_denied(file_service.read_user_file(Uuid::new_v4(), &path).await));
+
+    let unconfined = file_service.with_user_isolation(false);
+    assert_eq!(unconfined.read_user_file(user_id, &outside).await.unwrap(), b"not yours");
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::FileIngestionInfo;
+use crate::scheduling::source_sync::{ocr_priority_for_file, sort_newest_first};
+
+fn file_info(name: &str, size: i64, last_modified: Option<DateTime<Utc>>) -> FileIngestionInfo {
+    FileIngestionInfo {
+        name: name.to_string(),
+        relative_path: format!("/archive/{}", name),
+        full_path: format!("/archive/{}", name),
+        #[allow(deprecated)]
+        path: format!("/archive/{}", name),
+        size,
+        last_modified,
+        etag: format!("etag-{}", name),
+        mime_type: "application/pdf".to_string(),
+        is_directory: false,
+        created_at: None,
+        permissions: None,
+        owner: None,
+        group: None,
+        metadata: None,
+    }
+}
+
+#[test]
+fn test_sort_newest_first_puts_undated_files_last() {
+    let now = Utc::now();
+    let mut files = vec![
+        file_info("undated-a.pdf", 1, None),
+        file_info("old.pdf", 1, Some(now - Duration::days(400))),
+        file_info("undated-b.pdf", 1, None),
+        file_info("new.pdf", 1, Some(now)),
+        file_info("mid.pdf", 1, Some(now - Duration::days(10))),
+    ];
+
+    sort_newest_first(&mut files);
+
+    let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["new.pdf", "mid.pdf", "old.pdf", "undated-a.pdf", "undated-b.pdf"]);
+}
+
+#[test]
+fn test_priority_by_size_by_default() {
+    let now = Utc::now();
+    let small_old = file_info("small.pdf", 512 * 1024, Some(now - Duration::days(1000)));
+    let large_new = file_info("large.pdf", 100 * 1024 * 1024, Some(now));
+
+    assert_eq!(ocr_priority_for_file(&small_old, false, now), 10);
+    assert_eq!(ocr_priority_for_file(&large_new, false, now), 2);
+}
+
+#[test]
+fn test_priority_by_age_when_newest_first() {
+    let now = Utc::now();
+    let priority = |days: i64| {
+        let file = file_info("scan.pdf", 100 * 1024 * 1024, Some(now - Duration::days(days)));
+        ocr_priority_for_file(&file, true, now)
+    };
+
+    assert_eq!(priority(0), 10);
+    assert_eq!(priority(5), 8);
+    assert_eq!(priority(20), 6);
+    assert_eq!(priority(200), 4);
+    assert_eq!(priority(2000), 2);
+
+    // Without a modification time the file can't be placed, so it backfills last
+    assert_eq!(ocr_priority_for_file(&file_info("undated.pdf", 10, None), true, now), 2);
+}
@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chrono::Utc;
+use tokio_util::sync::CancellationToken;
+
+use crate::models::{SourceErrorType, SyncFailureKind, SyncFailureStage, SyncFileFailure};
+use crate::scheduling::source_sync::{retry_transient_failures, TransientSyncFailure};
+
+fn folder_failure(error_category: SourceErrorType) -> SyncFileFailure {
+    SyncFileFailure {
+        path: "/Documents".to_string(),
+        stage: SyncFailureStage::Discovery,
+        error_category,
+        error_message: "error sending request: connection refused".to_string(),
+        failed_at: Utc::now(),
+        name: String::new(),
+        size: 0,
+        mime_type: String::new(),
+        last_modified: None,
+    }
+}
+
+/// Runs a sync failing with the errors of `failures` in turn, then succeeding
+async fn run_with_retries(retries: u32, failures: Vec<anyhow::Error>) -> (anyhow::Result<usize>, u32) {
+    let attempts = AtomicU32::new(0);
+    let mut failures = failures.into_iter();
+    let result = retry_transient_failures(retries, Duration::from_millis(1), &CancellationToken::new(), || {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        let next = failures.next();
+        async move {
+            match next {
+                Some(error) => Err(error),
+                None => Ok(7),
+            }
+        }
+    })
+    .await;
+    (result, attempts.load(Ordering::SeqCst))
+}
+
+fn transient() -> anyhow::Error {
+    TransientSyncFailure("1 of 1 files failed".to_string()).into()
+}
+
+#[test]
+fn test_unreachable_source_is_a_transient_failure() {
+    let failures = vec![folder_failure(SourceErrorType::NetworkError), folder_failure(SourceErrorType::Timeout)];
+    assert_eq!(SyncFailureKind::of_failed_run(&failures, 0), SyncFailureKind::Transient);
+}
+
+#[test]
+fn test_other_failures_are_permanent() {
+    let auth = vec![folder_failure(SourceErrorType::NetworkError), folder_failure(SourceErrorType::PermissionDenied)];
+    assert_eq!(SyncFailureKind::of_failed_run(&auth, 0), SyncFailureKind::Permanent);
+
+    // Some files got through, so the source was reachable
+    let network = vec![folder_failure(SourceErrorType::NetworkError)];
+    assert_eq!(SyncFailureKind::of_failed_run(&network, 3), SyncFailureKind::Permanent);
+
+    assert_eq!(SyncFailureKind::of_failed_run(&[], 0), SyncFailureKind::Permanent);
+}
+
+#[test]
+fn test_sync_failure_kind_round_trips_through_its_name() {
+    for kind in [SyncFailureKind::Transient, SyncFailureKind::Permanent] {
+        assert_eq!(SyncFailureKind::try_from(kind.to_string()).unwrap(), kind);
+    }
+    assert!(SyncFailureKind::try_from("flaky".to_string()).is_err());
+}
+
+#[tokio::test]
+async fn test_transient_failure_then_success_is_retried() {
+    let (result, attempts) = run_with_retries(2, vec![transient()]).await;
+    assert_eq!(result.unwrap(), 7);
+    assert_eq!(attempts, 2);
+}
+
+#[tokio::test]
+async fn test_permanent_failure_is_not_retried() {
+    let (result, attempts) = run_with_retries(2, vec![anyhow!("401 Unauthorized")]).await;
+    assert!(result.is_err());
+    assert_eq!(attempts, 1);
+}
+
+#[tokio::test]
+async fn test_retries_give_up_after_the_configured_count() {
+    let (result, attempts) = run_with_retries(2, vec![transient(), transient(), transient()]).await;
+    assert!(result.unwrap_err().downcast_ref::<TransientSyncFailure>().is_some());
+    assert_eq!(attempts, 3);
+
+    let (result, attempts) = run_with_retries(0, vec![transient()]).await;
+    assert!(result.is_err());
+    assert_eq!(attempts, 1);
+}
+
+#[tokio::test]
+async fn test_cancelling_stops_waiting_for_a_retry() {
+    let cancellation_token = CancellationToken::new();
+    cancellation_token.cancel();
+    let attempts = AtomicU32::new(0);
+    let result = retry_transient_failures(2, Duration::from_secs(3600), &cancellation_token, || {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        async { Err(transient()) }
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,64 @@
+use crate::ocr::table_extraction::{parse_layout_tables, table_to_csv, tables_to_csv, ExtractedTable};
+
+const INVOICE: &str = "\
+Invoice 2026-104
+
+Item              Qty    Price
+Paper A4          10     4.50
+Toner black       2      89.00
+
+Total due: 107.00
+";
+
+fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+    rows.iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect()
+}
+
+#[test]
+fn test_columns_are_found_where_every_row_has_a_gap() {
+    let tables = parse_layout_tables(INVOICE);
+    assert_eq!(
+        tables,
+        vec![ExtractedTable {
+            page: 1,
+            rows: rows(&[&["Item", "Qty", "Price"], &["Paper A4", "10", "4.50"], &["Toner black", "2", "89.00"]]),
+        }]
+    );
+}
+
+#[test]
+fn test_empty_cells_and_pages() {
+    let text = "Cover page\n\u{c}\
+Name        Role         Ext
+Ada         Engineer     101
+Grace                    102
+\u{c}";
+    let tables = parse_layout_tables(text);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].page, 2);
+    assert_eq!(tables[0].rows[2], vec!["Grace", "", "102"]);
+}
+
+#[test]
+fn test_prose_and_single_rows_are_not_tables() {
+    assert!(parse_layout_tables("Dear customer,\nthank you for your order.\n").is_empty());
+    assert!(parse_layout_tables("Date:      2026-10-16\n\nRegards\n").is_empty());
+    // Two blank lines end a table
+    let text = "A      B\n1      2\n\n\n3      4\n";
+    let tables = parse_layout_tables(text);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].rows, rows(&[&["A", "B"], &["1", "2"]]));
+}
+
+#[test]
+fn test_tables_as_csv() {
+    let table = ExtractedTable {
+        page: 1,
+        rows: rows(&[&["Item", "Price"], &["Paper, A4", "=4.50"]]),
+    };
+    assert_eq!(table_to_csv(&table), "Item,Price\r\n\"Paper, A4\",'=4.50\r\n");
+
+    let other = ExtractedTable { page: 2, rows: rows(&[&["a", "b"]]) };
+    assert_eq!(tables_to_csv(&[table, other]), "Item,Price\r\n\"Paper, A4\",'=4.50\r\n\r\na,b\r\n");
+    assert_eq!(tables_to_csv(&[]), "");
+}
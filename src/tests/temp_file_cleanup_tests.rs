@@ -0,0 +1,55 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::ocr::temp_files::{remove_stale_temp_files, temp_file_name, TempCleanupReport, TEMP_FILE_PREFIX};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn write_file(path: &Path, size: usize, age: Duration) {
+    std::fs::write(path, vec![0u8; size]).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+}
+
+#[test]
+fn test_temp_file_names_carry_the_prefix() {
+    let name = temp_file_name("processed_1_2.png");
+    assert!(name.starts_with(TEMP_FILE_PREFIX));
+    assert!(name.ends_with("processed_1_2.png"));
+}
+
+#[tokio::test]
+async fn test_cleanup_removes_only_stale_prefixed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let stale = dir.path().join(temp_file_name("ocr_1_1.pdf"));
+    let fresh = dir.path().join(temp_file_name("ocr_2_2.pdf"));
+    let unrelated = dir.path().join("ocr_3_3.pdf");
+    write_file(&stale, 1000, 2 * DAY);
+    write_file(&fresh, 500, Duration::from_secs(60));
+    write_file(&unrelated, 700, 2 * DAY);
+
+    let report = remove_stale_temp_files(dir.path(), DAY).await.unwrap();
+
+    assert_eq!(report, TempCleanupReport { entries_removed: 1, bytes_reclaimed: 1000 });
+    assert!(!stale.exists());
+    assert!(fresh.exists());
+    assert!(unrelated.exists());
+}
+
+#[tokio::test]
+async fn test_cleanup_removes_stale_prefixed_directories_with_their_contents() {
+    let dir = tempfile::tempdir().unwrap();
+    let work_dir = dir.path().join(temp_file_name("ocr_preview_abc"));
+    std::fs::create_dir(&work_dir).unwrap();
+    write_file(&work_dir.join("input.pdf"), 300, 2 * DAY);
+    write_file(&work_dir.join("page-1.png"), 200, 2 * DAY);
+    std::fs::File::open(&work_dir)
+        .unwrap()
+        .set_modified(SystemTime::now() - 2 * DAY)
+        .unwrap();
+
+    let report = remove_stale_temp_files(dir.path(), DAY).await.unwrap();
+
+    assert_eq!(report, TempCleanupReport { entries_removed: 1, bytes_reclaimed: 500 });
+    assert!(!work_dir.exists());
+}
@@ -0,0 +1,47 @@
+use crate::utils::text_search::{is_supported_search_language, text_search_config, FoldedText};
+
+#[test]
+fn test_text_search_config_per_language() {
+    assert_eq!(text_search_config("english", true), "english_unaccent");
+    assert_eq!(text_search_config("french", false), "french");
+    assert_eq!(text_search_config(" German ", true), "german_unaccent");
+    assert_eq!(text_search_config("simple", true), "simple_unaccent");
+}
+
+#[test]
+fn test_unsupported_language_falls_back_to_english() {
+    assert!(!is_supported_search_language("klingon"));
+    assert!(!is_supported_search_language("english'; DROP TABLE documents; --"));
+    assert_eq!(text_search_config("klingon", true), "english_unaccent");
+    assert_eq!(text_search_config("english'; DROP TABLE documents; --", false), "english");
+}
+
+#[test]
+fn test_folding_strips_accents_and_case() {
+    assert_eq!(FoldedText::new("Café Müller", true).text, "cafe muller");
+    assert_eq!(FoldedText::new("Café Müller", false).text, "café müller");
+    // Decomposed input folds the same as precomposed
+    assert_eq!(FoldedText::new("Cafe\u{301}", true).text, "cafe");
+}
+
+#[test]
+fn test_folded_ranges_map_back_to_the_original() {
+    let original = "Crème brûlée";
+    let folded = FoldedText::new(original, true);
+    let start = folded.text.find("brulee").unwrap();
+
+    let (from, to) = folded.original_range(start, start + "brulee".len());
+    assert_eq!(&original[from..to], "brûlée");
+
+    let (from, to) = folded.original_range(0, "creme".len());
+    assert_eq!(&original[from..to], "Crème");
+}
+
+#[test]
+fn test_any_lexeme_tsquery_quotes_each_lexeme() {
+    use crate::db::documents::any_lexeme_tsquery;
+
+    let lexemes = vec![r"c:\finance".to_string(), "o'brien".to_string(), "invoice".to_string()];
+    assert_eq!(any_lexeme_tsquery(&lexemes), r"'c:\\finance' | 'o''brien' | 'invoice'");
+    assert_eq!(any_lexeme_tsquery(&[]), "");
+}
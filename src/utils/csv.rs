@@ -0,0 +1,38 @@
+//! Writing CSV records (RFC 4180) for exports.
+
+use std::borrow::Cow;
+
+/// Leading characters that make spreadsheet applications evaluate a cell as a
+/// formula. Such values are prefixed with `'` so they open as plain text.
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// Escapes a single field: values containing a separator, quote or line break
+/// are quoted with inner quotes doubled
+pub fn escape_field(value: &str) -> Cow<'_, str> {
+    let value: Cow<'_, str> = if value.starts_with(FORMULA_PREFIXES) {
+        Cow::Owned(format!("'{}", value))
+    } else {
+        Cow::Borrowed(value)
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        value
+    }
+}
+
+/// One CSV line, terminated by CRLF
+pub fn record<I, S>(fields: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut line = fields
+        .into_iter()
+        .map(|field| escape_field(field.as_ref()).into_owned())
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
@@ -1,2 +1,4 @@
+pub mod csv;
 pub mod debug;
-pub mod security;
\ No newline at end of file
+pub mod security;
+pub mod text_search;
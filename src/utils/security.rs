@@ -167,6 +167,27 @@ fn normalize_path(path: &Path) -> PathBuf {
     normalized
 }
 
+/// Whether `path` lies within `base_dir` without leaving it through `..`,
+/// judged from the paths alone. Relative paths are taken relative to the
+/// working directory.
+pub fn is_path_within_base(path: &str, base_dir: &str) -> bool {
+    path_relative_to_base(path, base_dir).is_some()
+}
+
+/// `path` relative to `base_dir`, if it lies within it without leaving it
+/// through `..`. Judged like [`is_path_within_base`].
+pub fn path_relative_to_base(path: &str, base_dir: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    if path.components().any(|component| component == Component::ParentDir) {
+        return None;
+    }
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    normalize_path(&current_dir.join(path))
+        .strip_prefix(normalize_path(&current_dir.join(base_dir)))
+        .ok()
+        .map(Path::to_path_buf)
+}
+
 /// Validate that a path is within the allowed base directory
 pub fn validate_path_within_base(path: &str, base_dir: &str) -> Result<()> {
     let path_buf = PathBuf::from(path);
@@ -286,6 +307,28 @@ mod tests {
         assert!(validate_and_sanitize_path("file\0name.txt").is_err());
     }
 
+    #[test]
+    fn test_is_path_within_base() {
+        assert!(is_path_within_base("./uploads/documents/file.pdf", "./uploads"));
+        assert!(is_path_within_base("uploads/thumbnails/x_thumb.jpg", "./uploads"));
+        assert!(is_path_within_base("/srv/uploads/documents/file.pdf", "/srv/uploads/"));
+
+        assert!(!is_path_within_base("./uploads/../secrets.txt", "./uploads"));
+        assert!(!is_path_within_base("/etc/passwd", "/srv/uploads"));
+        assert!(!is_path_within_base("/srv/uploads-other/file.pdf", "/srv/uploads"));
+        assert!(!is_path_within_base("file.pdf", "./uploads"));
+    }
+
+    #[test]
+    fn test_path_relative_to_base() {
+        assert_eq!(
+            path_relative_to_base("./uploads/documents/u/file.pdf", "uploads"),
+            Some(PathBuf::from("documents/u/file.pdf"))
+        );
+        assert_eq!(path_relative_to_base("/srv/uploads/", "/srv/uploads"), Some(PathBuf::new()));
+        assert_eq!(path_relative_to_base("/srv/uploads/../etc/passwd", "/srv/uploads"), None);
+    }
+
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("file<>name.txt"), "file__name.txt");
@@ -0,0 +1,83 @@
+//! Text search configurations and accent folding for full-text search.
+//!
+//! Each supported language has a PostgreSQL text search configuration of the
+//! same name and an `<language>_unaccent` variant that strips accents before
+//! stemming, so "café" and "cafe" index and query alike (see the
+//! `add_unaccent_search` migration).
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Supported languages and their accent-insensitive configurations; `simple`
+/// splits words without stemming
+const TEXT_SEARCH_CONFIGS: &[(&str, &str)] = &[
+    ("simple", "simple_unaccent"),
+    ("english", "english_unaccent"),
+    ("german", "german_unaccent"),
+    ("french", "french_unaccent"),
+    ("spanish", "spanish_unaccent"),
+    ("italian", "italian_unaccent"),
+    ("portuguese", "portuguese_unaccent"),
+    ("dutch", "dutch_unaccent"),
+];
+
+/// Language used when none, or an unsupported one, is configured
+pub const DEFAULT_SEARCH_LANGUAGE: &str = "english";
+
+/// Configuration of the default language with accents ignored, which the
+/// document text index and the stored note vectors are built with
+pub const DEFAULT_TEXT_SEARCH_CONFIG: &str = "english_unaccent";
+
+/// Names of the supported languages
+pub fn supported_search_languages() -> impl Iterator<Item = &'static str> {
+    TEXT_SEARCH_CONFIGS.iter().map(|(name, _)| *name)
+}
+
+pub fn is_supported_search_language(language: &str) -> bool {
+    TEXT_SEARCH_CONFIGS.iter().any(|(name, _)| name.eq_ignore_ascii_case(language.trim()))
+}
+
+/// Name of the text search configuration for a language, falling back to
+/// [`DEFAULT_SEARCH_LANGUAGE`]. The result is always one of a fixed set of
+/// names, so it is safe to put into SQL as a literal.
+pub fn text_search_config(language: &str, ignore_accents: bool) -> &'static str {
+    let (plain, unaccent) = TEXT_SEARCH_CONFIGS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(language.trim()))
+        .copied()
+        .unwrap_or(("english", DEFAULT_TEXT_SEARCH_CONFIG));
+    if ignore_accents { unaccent } else { plain }
+}
+
+/// Text lowercased, and optionally stripped of accents, for matching search
+/// terms, remembering where each byte of it came from in the original
+pub struct FoldedText {
+    pub text: String,
+    offsets: Vec<usize>,
+    original_len: usize,
+}
+
+impl FoldedText {
+    pub fn new(original: &str, ignore_accents: bool) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut offsets = Vec::with_capacity(original.len());
+        for (offset, c) in original.char_indices() {
+            let folded: String = if ignore_accents {
+                c.to_lowercase().collect::<String>().nfd().filter(|c| !is_combining_mark(*c)).collect()
+            } else {
+                c.to_lowercase().collect()
+            };
+            offsets.extend(std::iter::repeat(offset).take(folded.len()));
+            text.push_str(&folded);
+        }
+
+        Self { text, offsets, original_len: original.len() }
+    }
+
+    /// Byte range in the original text of a range of the folded text
+    pub fn original_range(&self, start: usize, end: usize) -> (usize, usize) {
+        let original_start = self.offsets.get(start).copied().unwrap_or(self.original_len);
+        // The end is where the character after the match starts in the original
+        let original_end = self.offsets.get(end).copied().unwrap_or(self.original_len);
+        (original_start, original_end.max(original_start))
+    }
+}
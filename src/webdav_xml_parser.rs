@@ -25,7 +25,100 @@ struct PropFindResponse {
     metadata: Option<serde_json::Value>,
 }
 
+/// How much of an unexpected PROPFIND body is quoted in the error
+const BODY_SNIPPET_CHARS: usize = 200;
+
+/// A PROPFIND response whose body is not a WebDAV multistatus document, such as
+/// an HTML error or login page that a misconfigured server or proxy sends with 207
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error(
+    "PROPFIND response{} is not a WebDAV multistatus document: {reason}. \
+     The server or a proxy in front of it is likely misconfigured. Body starts with: {snippet:?}",
+    response_details(.status, .content_type)
+)]
+pub struct InvalidMultistatusError {
+    pub reason: String,
+    pub snippet: String,
+    /// HTTP status of the response, when known
+    pub status: Option<u16>,
+    /// Content type of the response, when known
+    pub content_type: Option<String>,
+}
+
+impl InvalidMultistatusError {
+    /// Adds the HTTP status and content type of the response to an
+    /// `InvalidMultistatusError`; other errors are returned unchanged
+    pub fn add_response_details(error: anyhow::Error, status: u16, content_type: Option<&str>) -> anyhow::Error {
+        match error.downcast::<InvalidMultistatusError>() {
+            Ok(mut invalid) => {
+                invalid.status = Some(status);
+                invalid.content_type = content_type.map(str::to_string);
+                invalid.into()
+            }
+            Err(error) => error,
+        }
+    }
+}
+
+fn response_details(status: &Option<u16>, content_type: &Option<String>) -> String {
+    match (status, content_type) {
+        (Some(status), Some(content_type)) => format!(" (HTTP {}, {})", status, content_type),
+        (Some(status), None) => format!(" (HTTP {})", status),
+        (None, Some(content_type)) => format!(" ({})", content_type),
+        (None, None) => String::new(),
+    }
+}
+
+/// Checks that `body` is an XML document whose root element is `multistatus`,
+/// before the parser silently finds no `response` elements in it
+pub fn ensure_multistatus(body: &str) -> Result<(), InvalidMultistatusError> {
+    let trimmed = body.trim_start_matches('\u{feff}').trim();
+    let invalid = |reason: String| InvalidMultistatusError {
+        reason,
+        snippet: body_snippet(trimmed),
+        status: None,
+        content_type: None,
+    };
+
+    if trimmed.is_empty() {
+        return Err(invalid("the body is empty".to_string()));
+    }
+    if !trimmed.starts_with('<') {
+        return Err(invalid("the body is not XML".to_string()));
+    }
+
+    let mut reader = Reader::from_str(trimmed);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let root = get_local_name(&e).unwrap_or_default();
+                return if root.eq_ignore_ascii_case("multistatus") {
+                    Ok(())
+                } else {
+                    Err(invalid(format!("the root element is <{}>", root)))
+                };
+            }
+            Ok(Event::Eof) => return Err(invalid("the body has no root element".to_string())),
+            Err(e) => return Err(invalid(format!("the body is not well-formed XML ({})", e))),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Start of `body` with runs of whitespace collapsed, for error messages
+fn body_snippet(body: &str) -> String {
+    let collapsed = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    match collapsed.char_indices().nth(BODY_SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}...", &collapsed[..end]),
+        None => collapsed,
+    }
+}
+
 pub fn parse_propfind_response(xml_text: &str) -> Result<Vec<FileIngestionInfo>> {
+    ensure_multistatus(xml_text)?;
+
     let mut reader = Reader::from_str(xml_text);
     reader.config_mut().trim_text(true);
     
@@ -261,6 +354,8 @@ pub fn parse_propfind_response(xml_text: &str) -> Result<Vec<FileIngestionInfo>>
 /// Parse PROPFIND response including both files and directories
 /// This is used for shallow directory scans where we need to track directory structure
 pub fn parse_propfind_response_with_directories(xml_text: &str) -> Result<Vec<FileIngestionInfo>> {
+    ensure_multistatus(xml_text)?;
+
     let mut reader = Reader::from_str(xml_text);
     reader.config_mut().trim_text(true);
     
@@ -914,6 +1009,56 @@ mod tests {
         assert_eq!(files.len(), 0);
     }
 
+    #[test]
+    fn test_html_body_is_reported_as_invalid_multistatus() {
+        let html = r#"<!DOCTYPE html>
+        <html>
+          <head><title>502 Bad Gateway</title></head>
+          <body><h1>Bad Gateway</h1><p>The proxy server received an invalid response.</p></body>
+        </html>"#;
+
+        for result in [parse_propfind_response(html), parse_propfind_response_with_directories(html)] {
+            let error = result.unwrap_err();
+            let invalid = error.downcast_ref::<InvalidMultistatusError>().expect("typed error");
+            assert_eq!(invalid.reason, "the root element is <html>");
+            assert!(invalid.snippet.starts_with("<!DOCTYPE html> <html> <head><title>502 Bad Gateway"));
+            assert!(error.to_string().contains("likely misconfigured"));
+        }
+    }
+
+    #[test]
+    fn test_non_xml_bodies_are_reported_as_invalid_multistatus() {
+        let reason = |body: &str| ensure_multistatus(body).unwrap_err().reason;
+        assert_eq!(reason(""), "the body is empty");
+        assert_eq!(reason("  \n "), "the body is empty");
+        assert_eq!(reason("Service Unavailable"), "the body is not XML");
+        assert_eq!(reason("<?xml version=\"1.0\"?>"), "the body has no root element");
+        assert_eq!(reason("<?xml version=\"1.0\"?><error><message>Locked</message></error>"), "the root element is <error>");
+
+        assert!(ensure_multistatus("\u{feff}<?xml version=\"1.0\"?><D:multistatus xmlns:D=\"DAV:\"/>").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_multistatus_snippet_is_truncated() {
+        let body = format!("<html>{}</html>", "x".repeat(1000));
+        let invalid = ensure_multistatus(&body).unwrap_err();
+        assert_eq!(invalid.snippet.chars().count(), BODY_SNIPPET_CHARS + 3);
+        assert!(invalid.snippet.ends_with("..."));
+    }
+
+    #[test]
+    fn test_invalid_multistatus_response_details() {
+        let error = anyhow::Error::from(ensure_multistatus("<html></html>").unwrap_err());
+        let error = InvalidMultistatusError::add_response_details(error, 207, Some("text/html; charset=utf-8"));
+        let invalid = error.downcast_ref::<InvalidMultistatusError>().unwrap();
+        assert_eq!(invalid.status, Some(207));
+        assert!(error.to_string().starts_with("PROPFIND response (HTTP 207, text/html; charset=utf-8) is not"));
+
+        // Unrelated errors pass through untouched
+        let other = InvalidMultistatusError::add_response_details(anyhow!("timed out"), 207, None);
+        assert_eq!(other.to_string(), "timed out");
+    }
+
     #[test]
     fn test_normalize_etag() {
         // Test various ETag formats that WebDAV servers might return
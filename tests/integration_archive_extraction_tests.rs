@@ -0,0 +1,208 @@
+//! Integration tests for extracting uploaded ZIP archives into documents.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::ingestion::document_ingestion::{
+        DeduplicationPolicy, DocumentIngestionRequest, DocumentIngestionService, IngestionResult,
+    };
+    use readur::models::{Document, UpdateSettings};
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::ocr::queue::OcrQueueItem;
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use std::io::Write;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    fn zip_of(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        {
+            let mut zip = ZipWriter::new(std::io::Cursor::new(&mut buffer));
+            let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+            for (name, content) in files {
+                zip.start_file(*name, options).unwrap();
+                zip.write_all(content).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        buffer
+    }
+
+    fn image(label: &str) -> Vec<u8> {
+        [PNG_MAGIC, label.as_bytes()].concat()
+    }
+
+    /// Two scans, a nested archive with a PDF and an executable, and an entry
+    /// that tries to escape the archive
+    fn scans_archive() -> Vec<u8> {
+        let nested = zip_of(&[
+            ("invoice.pdf", b"%PDF-1.4 invoice".to_vec()),
+            ("setup.exe", b"MZ not a document".to_vec()),
+        ]);
+        zip_of(&[
+            ("scans/page1.png", image("page one")),
+            ("scans/page2.png", image("page two")),
+            ("scans/older.zip", nested),
+            ("../escape.png", image("escaped")),
+        ])
+    }
+
+    async fn enable_extraction(ctx: &TestContext, user_id: Uuid) -> Result<()> {
+        let settings = UpdateSettings {
+            extract_archives: Some(true),
+            allowed_file_types: Some(vec!["pdf".to_string(), "png".to_string()]),
+            ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+        };
+        ctx.state().db.create_or_update_settings(user_id, &settings).await?;
+        Ok(())
+    }
+
+    async fn store(ctx: &TestContext, user_id: Uuid, filename: &str, mime_type: &str, data: Vec<u8>) -> Result<Document> {
+        let state = ctx.state();
+        let ingestion = DocumentIngestionService::new(state.db.clone(), (*state.file_service).clone());
+        match ingestion
+            .ingest_document(DocumentIngestionRequest {
+                filename: filename.to_string(),
+                original_filename: filename.to_string(),
+                file_data: data,
+                mime_type: mime_type.to_string(),
+                user_id,
+                deduplication_policy: DeduplicationPolicy::Skip,
+                source_type: Some("web_upload".to_string()),
+                source_id: None,
+                original_created_at: None,
+                original_modified_at: None,
+                source_path: None,
+                file_permissions: None,
+                file_owner: None,
+                file_group: None,
+                source_metadata: None,
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?
+        {
+            IngestionResult::Created(document) => Ok(document),
+            other => panic!("{} was not stored: {:?}", filename, other),
+        }
+    }
+
+    /// Queues the archive and runs its OCR job, which extracts it
+    async fn extract(ctx: &TestContext, archive: &Document) -> Result<()> {
+        let state = ctx.state();
+        state.queue_service.enqueue_document(archive.id, 5, archive.file_size).await?;
+
+        // Fetch this document's job directly so jobs from concurrent tests are left alone
+        let item = sqlx::query_as::<_, OcrQueueItem>("SELECT * FROM ocr_queue WHERE document_id = $1")
+            .bind(archive.id)
+            .fetch_one(state.db.get_pool())
+            .await?;
+        let temp_dir = tempfile::tempdir()?;
+        let ocr_service = EnhancedOcrService::new(
+            temp_dir.path().to_string_lossy().to_string(),
+            (*state.file_service).clone(),
+            100,
+            100,
+            300,
+        );
+        state.queue_service.process_item(item, &ocr_service).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_zip_with_images_and_nested_zip_is_extracted() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            enable_extraction(&ctx, user_id).await?;
+
+            let archive = store(&ctx, user_id, "scans.zip", "application/zip", scans_archive()).await?;
+            extract(&ctx, &archive).await?;
+
+            // The executable isn't an allowed type and the escaping entry is dropped
+            let entries = state.db.get_archive_entries(archive.id).await?;
+            let paths: Vec<&str> = entries.iter().map(|(_, _, path)| path.as_str()).collect();
+            assert_eq!(paths, vec!["scans/older.zip/invoice.pdf", "scans/page1.png", "scans/page2.png"]);
+
+            // Each extracted file is queued for OCR of its own
+            for (document_id, _, _) in &entries {
+                let status: String = sqlx::query_scalar("SELECT status FROM ocr_queue WHERE document_id = $1")
+                    .bind(document_id)
+                    .fetch_one(state.db.get_pool())
+                    .await?;
+                assert_eq!(status, "pending");
+            }
+
+            let (archived_at, _, _, _) = state.db.get_document_split_info(archive.id).await?.unwrap();
+            assert!(archived_at.is_some(), "the archive is kept as an archived parent");
+
+            let request = axum::http::Request::builder()
+                .uri(format!("/api/documents/{}/archive-entries", entries[1].0))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(axum::body::Body::empty())
+                .unwrap();
+            let response = ctx.app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body)?;
+            assert_eq!(body["extracted_from"], archive.id.to_string());
+            assert_eq!(body["entry_path"], "scans/page1.png");
+            assert_eq!(body["entries"].as_array().unwrap().len(), 0);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_entries_linked_by_an_interrupted_run_are_not_stored_again() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            enable_extraction(&ctx, user_id).await?;
+
+            let archive = store(&ctx, user_id, "scans.zip", "application/zip", scans_archive()).await?;
+
+            // An earlier run stored the first page before it was interrupted
+            let earlier = store(&ctx, user_id, "page1.png", "image/png", image("page one, earlier run")).await?;
+            state.db.link_archive_entry(earlier.id, archive.id, "scans/page1.png").await?;
+
+            extract(&ctx, &archive).await?;
+
+            let entries = state.db.get_archive_entries(archive.id).await?;
+            let paths: Vec<&str> = entries.iter().map(|(_, _, path)| path.as_str()).collect();
+            assert_eq!(paths, vec!["scans/older.zip/invoice.pdf", "scans/page1.png", "scans/page2.png"]);
+            assert_eq!(entries[1].0, earlier.id);
+
+            let (archived_at, _, _, _) = state.db.get_document_split_info(archive.id).await?.unwrap();
+            assert!(archived_at.is_some());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
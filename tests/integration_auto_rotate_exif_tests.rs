@@ -102,6 +102,35 @@ fn create_settings_with_auto_rotate(enabled: bool) -> UpdateSettings {
         webdav_sync_interval_minutes: None,
         office_extraction_timeout_seconds: None,
         office_extraction_enable_detailed_logging: None,
+        ocr_auto_psm: None,
+        ocr_quick_preview_pages: None,
+        notification_delivery_mode: None,
+        notification_digest_interval_minutes: None,
+        ocr_max_pixels: None,
+        ocr_pdf_hybrid: None,
+        ocr_reprocess_on_settings_change: None,
+        auto_label_language: None,
+        extract_document_date: None,
+        document_date_priority: None,
+        document_date_rule: None,
+        document_date_order: None,
+        search_include_notes: None,
+        ocr_verify_orientation: None,
+        auto_split_on_separator: None,
+        auto_split_barcode: None,
+        expiry_reminder_days: None,
+        search_language: None,
+        search_ignore_accents: None,
+        extract_archives: None,
+        keep_original_pdf: None,
+        decode_barcodes: None,
+        search_collapse_duplicates: None,
+        ocr_incremental_pages: None,
+        parse_front_matter: None,
+        extract_tables: None,
+        doc_extraction_order: None,
+        pdf_extraction_order: None,
+        extraction_auto_order: None,
     }
 }
 
@@ -249,6 +249,23 @@ impl SourceTestClient {
         let sources: Vec<Value> = response.json().await?;
         Ok(sources)
     }
+
+    /// Get one page of sources, with its pagination block
+    async fn get_sources_page(&self, per_page: i64, page: i64) -> Result<Value, Box<dyn std::error::Error>> {
+        let token = self.token.as_ref().ok_or("Not authenticated")?;
+
+        let response = self.client
+            .get(&format!("{}/api/sources?per_page={}&page={}", get_base_url(), per_page, page))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Get sources page failed: {}", response.text().await?).into());
+        }
+
+        Ok(response.json().await?)
+    }
     
     /// Get a specific source by ID
     async fn get_source(&self, source_id: &str) -> Result<Value, Box<dyn std::error::Error>> {
@@ -449,6 +466,14 @@ async fn test_webdav_source_crud_operations() {
         .expect("Created source should be in list");
     assert_eq!(found_source["name"], "Updated WebDAV Source");
     println!("✅ Source found in list");
+
+    // Pagination parameters return one page with a pagination block
+    let page = client.get_sources_page(1, 1).await
+        .expect("Failed to get a page of sources");
+    assert_eq!(page["sources"].as_array().unwrap().len(), 1);
+    assert_eq!(page["pagination"]["per_page"], 1);
+    assert_eq!(page["pagination"]["total"].as_i64().unwrap(), sources.len() as i64);
+    println!("✅ Sources paged when asked");
     
     // Delete source
     client.delete_source(source_id).await
@@ -0,0 +1,223 @@
+//! Integration tests for rotating the master key source credentials are encrypted with.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::{CreateSource, SourceType};
+    use readur::services::credential_encryption::{encrypted_key_version, CredentialKeyring, MasterKey};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use serde_json::json;
+    use sqlx::Row;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn webdav_source(name: &str, password: &str) -> CreateSource {
+        CreateSource {
+            name: name.to_string(),
+            source_type: SourceType::WebDAV,
+            enabled: Some(true),
+            config: json!({
+                "server_url": "https://cloud.example.com",
+                "username": name,
+                "password": password,
+                "watch_folders": ["/Documents"],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "server_type": "nextcloud"
+            }),
+        }
+    }
+
+    fn v1() -> MasterKey {
+        MasterKey::new(1, [11; 32])
+    }
+
+    fn v2() -> MasterKey {
+        MasterKey::new(2, [22; 32])
+    }
+
+    /// The stored config and key version of a source, as they are in the database
+    async fn stored_credentials(ctx: &TestContext, source_id: Uuid) -> Result<(serde_json::Value, Option<i32>)> {
+        let row = sqlx::query("SELECT config, credentials_key_version FROM sources WHERE id = $1")
+            .bind(source_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        Ok((row.get("config"), row.get("credentials_key_version")))
+    }
+
+    /// Stores the credentials of a source encrypted with `keyring`, as an older
+    /// server would have
+    async fn encrypt_stored_credentials(ctx: &TestContext, source_id: Uuid, keyring: &CredentialKeyring) -> Result<()> {
+        let (config, _) = stored_credentials(ctx, source_id).await?;
+        let (config, key_version) = keyring.encrypt_config(&config)?;
+        sqlx::query("UPDATE sources SET config = $2, credentials_key_version = $3 WHERE id = $1")
+            .bind(source_id)
+            .bind(&config)
+            .bind(key_version)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn post_rotate(ctx: &TestContext, token: &str) -> StatusCode {
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/admin/keys/rotate")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        ctx.app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_rotation_keeps_credentials_decryptable() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+
+            let old_keyring = CredentialKeyring::new(vec![v1()]);
+            let mut sources = Vec::new();
+            for (name, password) in [("alice", "alice-secret"), ("bob", "bob-secret"), ("carol", "carol-secret")] {
+                let source = db.create_source(user_id, &webdav_source(name, password)).await?;
+                encrypt_stored_credentials(&ctx, source.id, &old_keyring).await?;
+                sources.push((source.id, password));
+            }
+            // Stored before encryption was enabled
+            let plain = db.create_source(user_id, &webdav_source("dave", "dave-secret")).await?;
+            sources.push((plain.id, "dave-secret"));
+            assert_eq!(stored_credentials(&ctx, plain.id).await?.1, None);
+
+            let rotating = CredentialKeyring::new(vec![v2(), v1()]);
+            let summary = db.rotate_source_credentials(&rotating, 3).await?;
+            assert_eq!(summary.current_key_version, 2);
+            assert_eq!(summary.rotated, 4);
+            assert_eq!(summary.failed, 0);
+            assert_eq!(summary.batches, 2);
+            assert_eq!(summary.remaining, 0);
+
+            // The old key can be dropped: everything decrypts with the new one alone
+            let new_only = CredentialKeyring::new(vec![v2()]);
+            for (source_id, password) in &sources {
+                let (config, key_version) = stored_credentials(&ctx, *source_id).await?;
+                assert_eq!(key_version, Some(2));
+                let stored_password = config["password"].as_str().unwrap();
+                assert_eq!(encrypted_key_version(stored_password), Some(2));
+                assert_eq!(new_only.decrypt_config(&config)?["password"], *password);
+                assert_eq!(config["server_url"], "https://cloud.example.com");
+            }
+
+            // Rotating again has nothing to do
+            let summary = db.rotate_source_credentials(&rotating, 3).await?;
+            assert_eq!((summary.rotated, summary.batches, summary.remaining), (0, 0, 0));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sources_under_a_missing_key_are_reported_not_lost() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+
+            let source = db.create_source(user_id, &webdav_source("erin", "erin-secret")).await?;
+            encrypt_stored_credentials(&ctx, source.id, &CredentialKeyring::new(vec![v1()])).await?;
+            let (before, _) = stored_credentials(&ctx, source.id).await?;
+
+            // v1 was removed from the configuration too early
+            let summary = db.rotate_source_credentials(&CredentialKeyring::new(vec![v2()]), 10).await?;
+            assert_eq!((summary.rotated, summary.failed, summary.remaining), (0, 1, 1));
+
+            let (after, key_version) = stored_credentials(&ctx, source.id).await?;
+            assert_eq!(after, before);
+            assert_eq!(key_version, Some(1));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_first_run_encrypts_only_plain_text_credentials() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+
+            let plain = db.create_source(user_id, &webdav_source("frank", "frank-secret")).await?;
+            let old = db.create_source(user_id, &webdav_source("grace", "grace-secret")).await?;
+            encrypt_stored_credentials(&ctx, old.id, &CredentialKeyring::new(vec![v1()])).await?;
+            let (old_config, _) = stored_credentials(&ctx, old.id).await?;
+            assert_eq!(db.source_credential_key_versions().await?, vec![1]);
+
+            let keyring = CredentialKeyring::new(vec![v2(), v1()]);
+            let summary = db.encrypt_plaintext_source_credentials(&keyring, 10).await?;
+            assert_eq!((summary.rotated, summary.failed, summary.remaining), (1, 0, 0));
+
+            let (config, key_version) = stored_credentials(&ctx, plain.id).await?;
+            assert_eq!(key_version, Some(2));
+            assert_ne!(config["password"], "frank-secret");
+            assert_eq!(keyring.decrypt_config(&config)?["password"], "frank-secret");
+            // Left for the rotation
+            assert_eq!(stored_credentials(&ctx, old.id).await?, (old_config, Some(1)));
+            assert_eq!(db.source_credential_key_versions().await?, vec![1, 2]);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rotate_endpoint_requires_admin_and_configured_keys() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            assert_eq!(post_rotate(&ctx, &token).await, StatusCode::FORBIDDEN);
+
+            let admin = auth_helper.create_admin_user().await;
+            let token = auth_helper.login_user(&admin.username, "adminpass123").await;
+            // No CREDENTIALS_MASTER_KEYS in tests
+            assert_eq!(post_rotate(&ctx, &token).await, StatusCode::BAD_REQUEST);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
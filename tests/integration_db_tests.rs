@@ -231,6 +231,13 @@ mod tests {
                 include_snippets: Some(true),
                 snippet_length: Some(200),
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let result = db.search_documents(user.id, &search_request).await;
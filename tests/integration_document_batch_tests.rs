@@ -0,0 +1,116 @@
+//! Integration tests for fetching many documents in one request.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::routes::documents::{BatchDocumentStatus, BatchDocumentsResponse, MAX_BATCH_DOCUMENTS};
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use serde_json::json;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    async fn post_batch(ctx: &TestContext, token: &str, body: serde_json::Value) -> (StatusCode, Vec<u8>) {
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/documents/batch")
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    fn new_document(user_id: Uuid, filename: &str) -> readur::models::Document {
+        let mut document = create_test_document(user_id);
+        document.filename = filename.to_string();
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    #[tokio::test]
+    async fn test_batch_keeps_request_order_and_hides_unreadable_documents() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let other = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let first = db.create_document(new_document(user.user_response.id, "first.pdf")).await?;
+            let second = db.create_document(new_document(user.user_response.id, "second.pdf")).await?;
+            let shared = db.create_document(new_document(other.user_response.id, "shared.pdf")).await?;
+            let private = db.create_document(new_document(other.user_response.id, "private.pdf")).await?;
+            assert!(db.share_document(shared.id, other.user_response.id, user.user_response.id).await?.is_some());
+            let missing = Uuid::new_v4();
+
+            let ids = vec![second.id, private.id, shared.id, missing, first.id, second.id];
+            let (status, body) = post_batch(&ctx, &token, json!({ "document_ids": ids })).await;
+            assert_eq!(status, StatusCode::OK);
+            let response: BatchDocumentsResponse = serde_json::from_slice(&body)?;
+
+            let returned: Vec<Uuid> = response.documents.iter().map(|result| result.id).collect();
+            assert_eq!(returned, ids);
+            let statuses: Vec<BatchDocumentStatus> = response.documents.iter().map(|result| result.status).collect();
+            assert_eq!(
+                statuses,
+                vec![
+                    BatchDocumentStatus::Found,
+                    BatchDocumentStatus::NotFound,
+                    BatchDocumentStatus::Found,
+                    BatchDocumentStatus::NotFound,
+                    BatchDocumentStatus::Found,
+                    BatchDocumentStatus::Found,
+                ]
+            );
+
+            assert_eq!(response.documents[0].document.as_ref().unwrap().filename, "second.pdf");
+            assert!(response.documents[1].document.is_none());
+            assert_eq!(response.documents[2].document.as_ref().unwrap().filename, "shared.pdf");
+            assert_eq!(response.documents[4].document.as_ref().unwrap().filename, "first.pdf");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_batch_rejects_empty_and_oversized_requests() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let (status, _) = post_batch(&ctx, &token, json!({ "document_ids": [] })).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            let ids: Vec<Uuid> = (0..=MAX_BATCH_DOCUMENTS).map(|_| Uuid::new_v4()).collect();
+            let (status, _) = post_batch(&ctx, &token, json!({ "document_ids": ids })).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            let ids: Vec<Uuid> = (0..MAX_BATCH_DOCUMENTS).map(|_| Uuid::new_v4()).collect();
+            let (status, _) = post_batch(&ctx, &token, json!({ "document_ids": ids })).await;
+            assert_eq!(status, StatusCode::OK);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,286 @@
+//! Integration tests for extracting document dates after OCR and listing documents by them.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use chrono::{NaiveDate, Utc};
+    use readur::ingestion::document_ingestion::{
+        DeduplicationPolicy, DocumentIngestionRequest, DocumentIngestionService, IngestionResult,
+    };
+    use readur::models::{CreateUser, Document, DocumentListFilter, UpdateSettings, UserRole};
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::ocr::queue::OcrQueueItem;
+    use readur::test_utils::TestContext;
+    use readur::AppState;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    const INVOICE_TEXT: &str = "Invoice 2024-117\nInvoice date: 12.03.2024\n\
+        Payment is due by 12.04.2024. Please quote the invoice date 12.03.2024 with your payment.";
+    const UNDATED_TEXT: &str = "Meeting notes: agreed to move the archive to the new server.";
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        CreateUser {
+            username: format!("document_date_{}", suffix),
+            email: format!("document_date_{}@example.com", suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_text_document(user_id: Uuid, file_path: &str, file_size: i64) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: "invoice.txt".to_string(),
+            original_filename: "invoice.txt".to_string(),
+            file_path: file_path.to_string(),
+            file_size,
+            mime_type: "text/plain".to_string(),
+            content: None,
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("pending".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    async fn create_user_with_date_extraction(state: &Arc<AppState>, priority: &str) -> Result<Uuid> {
+        let user = state
+            .db
+            .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+            .await?;
+        let settings = UpdateSettings {
+            extract_document_date: Some(true),
+            document_date_priority: Some(priority.to_string()),
+            document_date_rule: Some("most_frequent".to_string()),
+            document_date_order: Some("dmy".to_string()),
+            ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+        };
+        state.db.create_or_update_settings(user.id, &settings).await?;
+        Ok(user.id)
+    }
+
+    /// Runs OCR on a text document with the given content and returns it
+    async fn process_text_document(state: &Arc<AppState>, user_id: Uuid, text: &str) -> Result<Document> {
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("invoice.txt");
+        std::fs::write(&file_path, text)?;
+
+        let document = state
+            .db
+            .create_document(create_text_document(user_id, file_path.to_str().unwrap(), text.len() as i64))
+            .await?;
+        state.queue_service.enqueue_document(document.id, 5, text.len() as i64).await?;
+
+        // Fetch this document's job directly so jobs from concurrent tests are left alone
+        let item = sqlx::query_as::<_, OcrQueueItem>("SELECT * FROM ocr_queue WHERE document_id = $1")
+            .bind(document.id)
+            .fetch_one(state.db.get_pool())
+            .await?;
+
+        let ocr_service = EnhancedOcrService::new(
+            temp_dir.path().to_string_lossy().to_string(),
+            (*state.file_service).clone(),
+            100,
+            100,
+            300,
+        );
+        state.queue_service.process_item(item, &ocr_service).await?;
+
+        let stored = state
+            .db
+            .get_document_by_id(document.id, user_id, UserRole::User)
+            .await?
+            .expect("document exists");
+        assert_eq!(stored.ocr_status.as_deref(), Some("completed"), "error: {:?}", stored.ocr_error);
+        Ok(stored)
+    }
+
+    /// Ingests a file without running OCR
+    async fn ingest(
+        state: &Arc<AppState>,
+        user_id: Uuid,
+        filename: &str,
+        mime_type: &str,
+        data: &[u8],
+        source_metadata: Option<serde_json::Value>,
+    ) -> Result<Document> {
+        let ingestion = DocumentIngestionService::new(state.db.clone(), (*state.file_service).clone());
+        match ingestion
+            .ingest_document(DocumentIngestionRequest {
+                filename: filename.to_string(),
+                original_filename: filename.to_string(),
+                file_data: data.to_vec(),
+                mime_type: mime_type.to_string(),
+                user_id,
+                deduplication_policy: DeduplicationPolicy::Skip,
+                source_type: Some("web_upload".to_string()),
+                source_id: None,
+                original_created_at: None,
+                original_modified_at: None,
+                source_path: None,
+                file_permissions: None,
+                file_owner: None,
+                file_group: None,
+                source_metadata,
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?
+        {
+            IngestionResult::Created(document) => Ok(document),
+            other => panic!("{} was not stored: {:?}", filename, other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_documents_are_dated_at_ingestion_without_ocr() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user_id = create_user_with_date_extraction(state, "content,file,ingestion").await?;
+
+            let text = ingest(state, user_id, "invoice.txt", "text/plain", INVOICE_TEXT.as_bytes(), None).await?;
+            let metadata = serde_json::json!({ "pdf_creation_date": "D:20230105093000+01'00'" });
+            let pdf = ingest(state, user_id, "scan.pdf", "application/pdf", b"%PDF-1.4 scanned letter", Some(metadata)).await?;
+            let undated = ingest(state, user_id, "notes.txt", "text/plain", UNDATED_TEXT.as_bytes(), None).await?;
+
+            let dates = state.db.get_document_dates(&[text.id, pdf.id, undated.id]).await?;
+            let date_of = |id: Uuid| {
+                let date = dates.iter().find(|date| date.document_id == id).expect("document is dated");
+                (date.document_date, date.document_date_source.as_str())
+            };
+            assert_eq!(date_of(text.id), (NaiveDate::from_ymd_opt(2024, 3, 12).unwrap(), "content"));
+            assert_eq!(date_of(pdf.id), (NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(), "file"));
+            assert_eq!(date_of(undated.id), (undated.created_at.date_naive(), "ingestion"));
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ocr_extracts_most_frequent_date_from_content() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user_id = create_user_with_date_extraction(state, "content,file,ingestion").await?;
+            let document = process_text_document(state, user_id, INVOICE_TEXT).await?;
+
+            let dates = state.db.get_document_dates(&[document.id]).await?;
+            assert_eq!(dates.len(), 1);
+            assert_eq!(dates[0].document_date, NaiveDate::from_ymd_opt(2024, 3, 12).unwrap());
+            assert_eq!(dates[0].document_date_source, "content");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ocr_falls_back_to_ingestion_date_without_content_date() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user_id = create_user_with_date_extraction(state, "content,ingestion").await?;
+            let document = process_text_document(state, user_id, UNDATED_TEXT).await?;
+
+            let dates = state.db.get_document_dates(&[document.id]).await?;
+            assert_eq!(dates.len(), 1);
+            assert_eq!(dates[0].document_date, document.created_at.date_naive());
+            assert_eq!(dates[0].document_date_source, "ingestion");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_documents_filters_and_sorts_by_document_date() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state().db;
+            let user = db
+                .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+                .await?;
+
+            let march = db.create_document(create_text_document(user.id, "/tmp/march.txt", 10)).await?;
+            let january = db.create_document(create_text_document(user.id, "/tmp/january.txt", 10)).await?;
+            let undated = db.create_document(create_text_document(user.id, "/tmp/undated.txt", 10)).await?;
+            db.set_document_date(march.id, NaiveDate::from_ymd_opt(2024, 3, 12).unwrap(), "content").await?;
+            db.set_document_date(january.id, NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(), "file").await?;
+
+            // Newest document date first, undated documents last
+            let filter = DocumentListFilter { sort_by_document_date: true, ..Default::default() };
+            let ids: Vec<Uuid> = db
+                .get_documents_by_user_with_role_and_filter(user.id, UserRole::User, &filter, 10, 0)
+                .await?
+                .into_iter()
+                .map(|document| document.id)
+                .collect();
+            assert_eq!(ids, vec![march.id, january.id, undated.id]);
+
+            // Date range bounds are inclusive and exclude undated documents
+            let filter = DocumentListFilter {
+                document_date_from: NaiveDate::from_ymd_opt(2024, 2, 1),
+                document_date_to: NaiveDate::from_ymd_opt(2024, 3, 12),
+                ..Default::default()
+            };
+            let documents = db
+                .get_documents_by_user_with_role_and_filter(user.id, UserRole::User, &filter, 10, 0)
+                .await?;
+            assert_eq!(documents.len(), 1);
+            assert_eq!(documents[0].id, march.id);
+            assert_eq!(
+                db.count_documents_by_user_with_role_and_filter(user.id, UserRole::User, &filter).await?,
+                1
+            );
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+//! Integration tests for document expiry dates and expiry reminders.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use chrono::{Duration, NaiveDate, Utc};
+    use readur::models::{Document, UpdateSettings};
+    use readur::services::expiry_reminders::ExpiryReminderService;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn named_document(user_id: Uuid, filename: &str) -> Document {
+        let mut document = create_test_document(user_id);
+        document.filename = filename.to_string();
+        document.original_filename = filename.to_string();
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    fn days_from_today(days: i64) -> NaiveDate {
+        Utc::now().date_naive() + Duration::days(days)
+    }
+
+    async fn request(
+        app: &axum::Router,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("Content-Type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap(),
+            None => builder.body(axum::body::Body::empty()).unwrap(),
+        };
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    fn expiring_filenames(response: &serde_json::Value) -> Vec<String> {
+        response["documents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|document| document["filename"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_expiring_documents_and_reminders() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let passport = db.create_document(named_document(user_id, "passport.pdf")).await?;
+            let lease = db.create_document(named_document(user_id, "lease.pdf")).await?;
+            let warranty = db.create_document(named_document(user_id, "warranty.pdf")).await?;
+            for (document, days) in [(&passport, 10), (&lease, 60), (&warranty, -1)] {
+                let uri = format!("/api/documents/{}/expiry", document.id);
+                let body = serde_json::json!({ "expiry_date": days_from_today(days) });
+                let (status, _) = request(&ctx.app, "PUT", &uri, &token, Some(body)).await;
+                assert_eq!(status, StatusCode::NO_CONTENT);
+            }
+
+            let (status, document) = request(&ctx.app, "GET", &format!("/api/documents/{}", passport.id), &token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(document["expiry_date"], days_from_today(10).to_string());
+
+            // The window defaults to the 30-day reminder setting; expired documents are left out
+            let (status, expiring) = request(&ctx.app, "GET", "/api/documents/expiring", &token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(expiring["days"], 30);
+            assert_eq!(expiring_filenames(&expiring), vec!["passport.pdf"]);
+            assert_eq!(expiring["documents"][0]["days_until_expiry"], 10);
+            assert_eq!(expiring["documents"][0]["reminded"], false);
+
+            let (_, expiring) = request(&ctx.app, "GET", "/api/documents/expiring?days=90", &token, None).await;
+            assert_eq!(expiring_filenames(&expiring), vec!["passport.pdf", "lease.pdf"]);
+
+            // Only documents inside the window are reminded, once
+            let service = ExpiryReminderService::new(db.clone());
+            service.send_due_reminders().await?;
+            let notifications = db.get_user_notifications(user_id, 10, 0).await?;
+            assert_eq!(notifications.len(), 1);
+            assert_eq!(notifications[0].notification_type, "warning");
+            assert_eq!(notifications[0].message, format!("passport.pdf expires in 10 days ({})", days_from_today(10)));
+            assert_eq!(notifications[0].action_url.as_deref(), Some(format!("/documents/{}", passport.id).as_str()));
+
+            service.send_due_reminders().await?;
+            assert_eq!(db.get_user_notifications(user_id, 10, 0).await?.len(), 1);
+            let (_, expiring) = request(&ctx.app, "GET", "/api/documents/expiring", &token, None).await;
+            assert_eq!(expiring["documents"][0]["reminded"], true);
+
+            // Moving the expiry date re-arms the reminder
+            let uri = format!("/api/documents/{}/expiry", passport.id);
+            let body = serde_json::json!({ "expiry_date": days_from_today(5) });
+            request(&ctx.app, "PUT", &uri, &token, Some(body)).await;
+            let (_, expiring) = request(&ctx.app, "GET", "/api/documents/expiring", &token, None).await;
+            assert_eq!(expiring["documents"][0]["reminded"], false);
+
+            // Clearing it takes the document off the list
+            request(&ctx.app, "PUT", &uri, &token, Some(serde_json::json!({ "expiry_date": null }))).await;
+            let (_, expiring) = request(&ctx.app, "GET", "/api/documents/expiring", &token, None).await;
+            assert!(expiring_filenames(&expiring).is_empty());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reminder_window_follows_user_settings() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let service = ExpiryReminderService::new(db.clone());
+
+            let contract = db.create_document(named_document(user_id, "contract.pdf")).await?;
+            db.set_document_expiry(contract.id, Some(days_from_today(45))).await?;
+
+            // Outside the default 30-day window
+            service.send_due_reminders().await?;
+            assert!(db.get_user_notifications(user_id, 10, 0).await?.is_empty());
+
+            let wide_window = UpdateSettings {
+                expiry_reminder_days: Some(60),
+                ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+            };
+            db.create_or_update_settings(user_id, &wide_window).await?;
+            service.send_due_reminders().await?;
+            assert_eq!(db.get_user_notifications(user_id, 10, 0).await?.len(), 1);
+
+            // 0 turns reminders off
+            let other = db.create_document(named_document(user_id, "insurance.pdf")).await?;
+            db.set_document_expiry(other.id, Some(days_from_today(1))).await?;
+            let disabled = UpdateSettings {
+                expiry_reminder_days: Some(0),
+                ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+            };
+            db.create_or_update_settings(user_id, &disabled).await?;
+            service.send_due_reminders().await?;
+            assert_eq!(db.get_user_notifications(user_id, 10, 0).await?.len(), 1);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
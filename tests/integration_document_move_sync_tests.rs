@@ -0,0 +1,80 @@
+//! Integration tests for syncing a source again after one of its documents was
+//! moved to another folder.
+
+#[cfg(all(test, unix))]
+mod tests {
+    use anyhow::Result;
+    use readur::models::{CreateSource, SourceType, UserRole};
+    use readur::scheduling::source_sync::SourceSyncService;
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use serde_json::json;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    fn local_folder_source(folder: &str) -> CreateSource {
+        CreateSource {
+            name: format!("Scans {}", Uuid::new_v4().simple()),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": [folder],
+                "file_extensions": ["txt"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false,
+                "track_file_ids": true
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_moved_document_is_not_ingested_again_or_moved_back() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let db = &ctx.state.db;
+
+            let folder = TempDir::new()?;
+            let original = folder.path().join("invoice.txt");
+            std::fs::write(&original, format!("Invoice {}", Uuid::new_v4()))?;
+
+            let source = db.create_source(user_id, &local_folder_source(folder.path().to_str().unwrap())).await?;
+            let sync_service = SourceSyncService::new(ctx.state.clone());
+            sync_service.sync_source(&source, false).await?;
+            let documents = db.get_documents_by_user(user_id, 100, 0).await?;
+            assert_eq!(documents.len(), 1);
+
+            db.move_document(documents[0].id, user_id, UserRole::User, Some("/Archive/invoice.txt"), None, None)
+                .await?
+                .expect("the document should be moved");
+
+            // An unchanged file is recognized, and the move is kept
+            sync_service.sync_source(&source, false).await?;
+            let documents = db.get_documents_by_user(user_id, 100, 0).await?;
+            assert_eq!(documents.len(), 1, "the moved document must not be ingested again");
+            assert_eq!(documents[0].source_path.as_deref(), Some("/Archive/invoice.txt"));
+
+            // A file renamed on the source updates the remote path, not the location
+            let renamed = folder.path().join("invoice-2026.txt");
+            std::fs::rename(&original, &renamed)?;
+            sync_service.sync_source(&source, false).await?;
+            let documents = db.get_documents_by_user(user_id, 100, 0).await?;
+            assert_eq!(documents.len(), 1);
+            assert_eq!(documents[0].source_path.as_deref(), Some("/Archive/invoice.txt"));
+            let moved_from = &documents[0].source_metadata.as_ref().unwrap()["moved_from"];
+            assert_eq!(moved_from["source_path"].as_str(), renamed.to_str());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+//! Integration tests for private document notes and searching by them.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::{Document, UpdateSettings};
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn searchable_document(user_id: Uuid, content: &str) -> Document {
+        let mut document = create_test_document(user_id);
+        document.content = Some(content.to_string());
+        document.ocr_status = Some("completed".to_string());
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    async fn request(
+        app: &axum::Router,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("Content-Type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap(),
+            None => builder.body(axum::body::Body::empty()).unwrap(),
+        };
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    fn search_result_ids(response: &serde_json::Value) -> Vec<String> {
+        response["documents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|document| document["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_note_crud() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let document = ctx
+                .state
+                .db
+                .create_document(searchable_document(user.user_response.id, "Electricity bill"))
+                .await?;
+            let notes_uri = format!("/api/documents/{}/notes", document.id);
+
+            let (status, note) =
+                request(&ctx.app, "POST", &notes_uri, &token, Some(serde_json::json!({"content": "Paid in March"}))).await;
+            assert_eq!(status, StatusCode::CREATED);
+            assert_eq!(note["content"], "Paid in March");
+            assert_eq!(note["author"], user.username.as_str());
+            let note_uri = format!("{}/{}", notes_uri, note["id"].as_str().unwrap());
+
+            let (status, note) =
+                request(&ctx.app, "PUT", &note_uri, &token, Some(serde_json::json!({"content": "Paid on 3 March"}))).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(note["content"], "Paid on 3 March");
+
+            let (status, notes) = request(&ctx.app, "GET", &notes_uri, &token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(notes.as_array().unwrap().len(), 1);
+            assert_eq!(notes[0]["content"], "Paid on 3 March");
+
+            // Blank notes are rejected
+            let (status, error) =
+                request(&ctx.app, "POST", &notes_uri, &token, Some(serde_json::json!({"content": "   "}))).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(error["code"], "NOTE_CONTENT_EMPTY");
+
+            let (status, _) = request(&ctx.app, "DELETE", &note_uri, &token, None).await;
+            assert_eq!(status, StatusCode::NO_CONTENT);
+            let (_, notes) = request(&ctx.app, "GET", &notes_uri, &token, None).await;
+            assert!(notes.as_array().unwrap().is_empty());
+
+            // The document text is never touched by notes
+            let stored = ctx
+                .state
+                .db
+                .get_document_by_id(document.id, user.user_response.id, readur::models::UserRole::User)
+                .await?
+                .expect("document exists");
+            assert_eq!(stored.content.as_deref(), Some("Electricity bill"));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_notes_on_shared_documents_stay_private() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let owner = auth_helper.create_test_user().await;
+            let owner_token = auth_helper.login_user(&owner.username, "password123").await;
+            let recipient = auth_helper.create_test_user().await;
+            let recipient_token = auth_helper.login_user(&recipient.username, "password123").await;
+            let outsider = auth_helper.create_test_user().await;
+            let outsider_token = auth_helper.login_user(&outsider.username, "password123").await;
+
+            let document = db.create_document(searchable_document(owner.user_response.id, "Lease agreement")).await?;
+            db.share_document(document.id, owner.user_response.id, recipient.user_response.id).await?;
+            let notes_uri = format!("/api/documents/{}/notes", document.id);
+
+            let (status, owner_note) = request(
+                &ctx.app,
+                "POST",
+                &notes_uri,
+                &owner_token,
+                Some(serde_json::json!({"content": "Renew before June"})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+            let (status, _) = request(
+                &ctx.app,
+                "POST",
+                &notes_uri,
+                &recipient_token,
+                Some(serde_json::json!({"content": "Ask about parking"})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+
+            // Each user only sees their own notes
+            let (_, notes) = request(&ctx.app, "GET", &notes_uri, &recipient_token, None).await;
+            assert_eq!(notes.as_array().unwrap().len(), 1);
+            assert_eq!(notes[0]["content"], "Ask about parking");
+            let (_, notes) = request(&ctx.app, "GET", &notes_uri, &owner_token, None).await;
+            assert_eq!(notes.as_array().unwrap().len(), 1);
+            assert_eq!(notes[0]["content"], "Renew before June");
+
+            // The recipient can neither edit nor delete the owner's note
+            let owner_note_uri = format!("{}/{}", notes_uri, owner_note["id"].as_str().unwrap());
+            let (status, error) = request(
+                &ctx.app,
+                "PUT",
+                &owner_note_uri,
+                &recipient_token,
+                Some(serde_json::json!({"content": "changed"})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(error["code"], "NOTE_NOT_FOUND");
+            let (status, _) = request(&ctx.app, "DELETE", &owner_note_uri, &recipient_token, None).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+
+            // Users without access to the document can't annotate it
+            let (status, error) = request(
+                &ctx.app,
+                "POST",
+                &notes_uri,
+                &outsider_token,
+                Some(serde_json::json!({"content": "hello"})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(error["code"], "NOTE_DOCUMENT_NOT_FOUND");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_notes_when_enabled() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let other = auth_helper.create_test_user().await;
+            let other_token = auth_helper.login_user(&other.username, "password123").await;
+
+            let document = db.create_document(searchable_document(user.user_response.id, "Water bill")).await?;
+            db.share_document(document.id, user.user_response.id, other.user_response.id).await?;
+            let (status, _) = request(
+                &ctx.app,
+                "POST",
+                &format!("/api/documents/{}/notes", document.id),
+                &token,
+                Some(serde_json::json!({"content": "reimbursable expense"})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+            let document_id = document.id.to_string();
+
+            // Notes are left out unless the search asks for them
+            let (status, response) = request(&ctx.app, "GET", "/api/search?query=reimbursable", &token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(search_result_ids(&response).is_empty());
+
+            let (_, response) =
+                request(&ctx.app, "GET", "/api/search?query=reimbursable&include_notes=true", &token, None).await;
+            assert_eq!(search_result_ids(&response), vec![document_id.clone()]);
+            assert_eq!(response["total"], 1);
+
+            // The setting makes note matching the default, and a search can still opt out
+            let settings = UpdateSettings {
+                search_include_notes: Some(true),
+                ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+            };
+            db.create_or_update_settings(user.user_response.id, &settings).await?;
+            let (_, response) = request(&ctx.app, "GET", "/api/search/enhanced?query=reimbursable", &token, None).await;
+            assert_eq!(search_result_ids(&response), vec![document_id.clone()]);
+            let (_, response) =
+                request(&ctx.app, "GET", "/api/search/enhanced?query=reimbursable&include_notes=false", &token, None)
+                    .await;
+            assert!(search_result_ids(&response).is_empty());
+
+            // Another user's notes never match, even on a document shared with them
+            let (_, response) = request(
+                &ctx.app,
+                "GET",
+                "/api/search?query=reimbursable&include_notes=true&include_shared=true",
+                &other_token,
+                None,
+            )
+            .await;
+            assert!(search_result_ids(&response).is_empty());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
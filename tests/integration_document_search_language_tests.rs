@@ -0,0 +1,111 @@
+//! Integration tests for searching documents with the stemming of their own language.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::models::{Document, SearchMode, SearchRequest, UserRole};
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use std::collections::HashSet;
+    use uuid::Uuid;
+
+    fn document_with_text(user_id: Uuid, content: &str) -> Document {
+        let mut document = create_test_document(user_id);
+        document.content = Some(content.to_string());
+        document.ocr_text = None;
+        document.ocr_status = Some("completed".to_string());
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    fn search(query: &str, mode: SearchMode, language: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            tags: None,
+            mime_types: None,
+            limit: Some(50),
+            offset: Some(0),
+            include_snippets: Some(false),
+            snippet_length: None,
+            search_mode: Some(mode),
+            include_shared: None,
+            include_notes: Some(false),
+            ignore_accents: Some(true),
+            language: Some(language.to_string()),
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
+        }
+    }
+
+    async fn matching_ids(db: &readur::db::Database, user_id: Uuid, request: &SearchRequest) -> Result<HashSet<Uuid>> {
+        let results = db.enhanced_search_documents_with_role(user_id, UserRole::User, request).await?;
+        let total = db.count_search_documents(user_id, UserRole::User, request).await?;
+        assert_eq!(total as usize, results.len(), "count disagrees with results");
+        Ok(results.into_iter().map(|document| document.id).collect())
+    }
+
+    #[tokio::test]
+    async fn test_documents_are_stemmed_in_their_own_language() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user_id = auth_helper.create_test_user().await.user_response.id;
+
+            // English stemming leaves "Rechnungen" alone, German stemming reduces it to "rechnung"
+            let german = db.create_document(document_with_text(user_id, "Alle Rechnungen des Jahres 2024")).await?;
+            db.set_document_search_language(german.id, "german").await?;
+            let undetected = db.create_document(document_with_text(user_id, "Offene Rechnungen vom Mai")).await?;
+            let english = db.create_document(document_with_text(user_id, "Invoices paid in March")).await?;
+            db.set_document_search_language(english.id, "english").await?;
+
+            for mode in [SearchMode::Simple, SearchMode::Phrase, SearchMode::Boolean] {
+                let ids = matching_ids(db, user_id, &search("Rechnung", mode, "english")).await?;
+                assert_eq!(ids, HashSet::from([german.id]));
+            }
+
+            // Documents without a language are still stemmed in the searcher's language
+            let ids = matching_ids(db, user_id, &search("Rechnung", SearchMode::Simple, "german")).await?;
+            assert_eq!(ids, HashSet::from([german.id, undetected.id]));
+
+            let ids = matching_ids(db, user_id, &search("invoice", SearchMode::Simple, "english")).await?;
+            assert_eq!(ids, HashSet::from([english.id]));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_language_is_recorded_per_document() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user_id = auth_helper.create_test_user().await.user_response.id;
+            let document = db.create_document(document_with_text(user_id, "Facture de mars")).await?;
+
+            assert_eq!(db.get_document_search_language(document.id).await?, None);
+            db.set_document_search_language(document.id, "french").await?;
+            assert_eq!(db.get_document_search_language(document.id).await?.as_deref(), Some("french"));
+
+            // Only languages with a text search configuration can be stored
+            assert!(db.set_document_search_language(document.id, "klingon").await.is_err());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
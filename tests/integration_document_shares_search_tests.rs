@@ -0,0 +1,236 @@
+//! Integration tests for searching owned and shared documents together.
+//!
+//! The access filter is applied in SQL, so these tests check that result sets and
+//! counts contain exactly the caller's own documents plus those shared with them.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chrono::{Duration, Utc};
+    use readur::models::{CreateUser, Document, SearchRequest, UserRole};
+    use readur::test_utils::TestContext;
+    use std::collections::{HashMap, HashSet};
+    use uuid::Uuid;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        let test_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+            .to_string();
+        let unique_suffix = &test_id[test_id.len().saturating_sub(8)..];
+
+        CreateUser {
+            username: format!("testuser_{}_{}", suffix, unique_suffix),
+            email: format!("test_{}_{}@example.com", suffix, unique_suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_searchable_document(user_id: Uuid, name: &str) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: format!("{}.txt", name),
+            original_filename: format!("{}.txt", name),
+            file_path: format!("/path/to/{}.txt", name),
+            file_size: 1024,
+            mime_type: "text/plain".to_string(),
+            content: Some(format!("Quarterly budget report {}", name)),
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("completed".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    fn budget_search(include_shared: bool) -> SearchRequest {
+        SearchRequest {
+            query: "budget".to_string(),
+            tags: None,
+            mime_types: None,
+            limit: Some(100),
+            offset: Some(0),
+            include_snippets: Some(false),
+            snippet_length: None,
+            search_mode: None,
+            include_shared: Some(include_shared),
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
+        }
+    }
+
+    /// Runs an enhanced search and returns document id -> is_owner, checking the count agrees
+    async fn search_as(
+        db: &readur::db::Database,
+        user_id: Uuid,
+        include_shared: bool,
+    ) -> Result<HashMap<Uuid, bool>> {
+        let request = budget_search(include_shared);
+        let results = db
+            .enhanced_search_documents_with_role(user_id, UserRole::User, &request)
+            .await?;
+        let total = db.count_search_documents(user_id, UserRole::User, &request).await?;
+        assert_eq!(total as usize, results.len(), "count disagrees with results");
+
+        Ok(results.into_iter().map(|doc| (doc.id, doc.is_owner)).collect())
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_shares_return_exact_result_sets() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let alice = db.create_user(create_test_user_data("alice")).await?;
+            let bob = db.create_user(create_test_user_data("bob")).await?;
+            let carol = db.create_user(create_test_user_data("carol")).await?;
+
+            let alice_shared = db.create_document(create_searchable_document(alice.id, "alice_shared")).await?;
+            let alice_private = db.create_document(create_searchable_document(alice.id, "alice_private")).await?;
+            let bob_shared = db.create_document(create_searchable_document(bob.id, "bob_shared")).await?;
+            let carol_private = db.create_document(create_searchable_document(carol.id, "carol_private")).await?;
+
+            // alice_shared goes to both Bob and Carol; bob_shared only to Alice
+            assert!(db.share_document(alice_shared.id, alice.id, bob.id).await?.is_some());
+            assert!(db.share_document(alice_shared.id, alice.id, carol.id).await?.is_some());
+            assert!(db.share_document(bob_shared.id, bob.id, alice.id).await?.is_some());
+
+            let alice_results = search_as(db, alice.id, true).await?;
+            assert_eq!(
+                alice_results,
+                HashMap::from([(alice_shared.id, true), (alice_private.id, true), (bob_shared.id, false)])
+            );
+
+            let bob_results = search_as(db, bob.id, true).await?;
+            assert_eq!(bob_results, HashMap::from([(bob_shared.id, true), (alice_shared.id, false)]));
+
+            let carol_results = search_as(db, carol.id, true).await?;
+            assert_eq!(carol_results, HashMap::from([(carol_private.id, true), (alice_shared.id, false)]));
+
+            // Without include_shared, search stays limited to owned documents
+            let alice_owned: HashSet<Uuid> = search_as(db, alice.id, false).await?.into_keys().collect();
+            assert_eq!(alice_owned, HashSet::from([alice_shared.id, alice_private.id]));
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_owner_can_share_and_unshare_revokes_access() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let alice = db.create_user(create_test_user_data("owner")).await?;
+            let bob = db.create_user(create_test_user_data("recipient")).await?;
+            let mallory = db.create_user(create_test_user_data("other")).await?;
+
+            let document = db.create_document(create_searchable_document(alice.id, "owned")).await?;
+
+            // A non-owner cannot share someone else's document, even with themselves
+            assert!(db.share_document(document.id, mallory.id, mallory.id).await?.is_none());
+            assert!(search_as(db, mallory.id, true).await?.is_empty());
+
+            // Sharing twice keeps a single share
+            db.share_document(document.id, alice.id, bob.id).await?;
+            db.share_document(document.id, alice.id, bob.id).await?;
+            assert_eq!(db.get_document_shares(document.id, alice.id).await?.len(), 1);
+            assert!(db.get_document_shares(document.id, bob.id).await?.is_empty());
+            assert_eq!(search_as(db, bob.id, true).await?, HashMap::from([(document.id, false)]));
+
+            // Only the owner can revoke
+            assert!(!db.unshare_document(document.id, bob.id, bob.id).await?);
+            assert!(db.unshare_document(document.id, alice.id, bob.id).await?);
+            assert!(search_as(db, bob.id, true).await?.is_empty());
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_collapsed_duplicates_keep_the_most_recent_document() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let alice = db.create_user(create_test_user_data("alice")).await?;
+            let bob = db.create_user(create_test_user_data("bob")).await?;
+
+            // Alice and Bob each stored the same file; Bob's copy is newer and shared with Alice
+            let mut alice_copy = create_searchable_document(alice.id, "report");
+            alice_copy.created_at = Utc::now() - Duration::days(1);
+            let mut bob_copy = create_searchable_document(bob.id, "report");
+            bob_copy.file_hash = alice_copy.file_hash.clone();
+            let alice_copy = db.create_document(alice_copy).await?;
+            let bob_copy = db.create_document(bob_copy).await?;
+            let alice_other = db.create_document(create_searchable_document(alice.id, "other")).await?;
+            assert!(db.share_document(bob_copy.id, bob.id, alice.id).await?.is_some());
+
+            assert_eq!(search_as(db, alice.id, true).await?.len(), 3);
+
+            let mut request = budget_search(true);
+            request.collapse_duplicates = Some(true);
+            let results = db
+                .enhanced_search_documents_with_role(alice.id, UserRole::User, &request)
+                .await?;
+            let collapsed: HashMap<Uuid, Vec<Uuid>> =
+                results.into_iter().map(|doc| (doc.id, doc.duplicate_ids)).collect();
+            assert_eq!(
+                collapsed,
+                HashMap::from([(bob_copy.id, vec![alice_copy.id]), (alice_other.id, Vec::new())])
+            );
+            assert_eq!(db.count_search_documents(alice.id, UserRole::User, &request).await?, 2);
+
+            let basic = db.search_documents_with_duplicates(alice.id, &request).await?;
+            assert_eq!(basic.len(), 2);
+            assert!(basic.iter().any(|(doc, duplicates)| doc.id == bob_copy.id && duplicates == &vec![alice_copy.id]));
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
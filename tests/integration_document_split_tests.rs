@@ -0,0 +1,113 @@
+//! Integration tests for splitting multi-document scans at separator pages.
+//!
+//! Uses a five-page PDF holding three one-page documents separated by blank
+//! pages. Needs poppler-utils (pdftoppm, pdfseparate, pdfunite).
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use readur::ocr::document_split::{split_segments, SeparatorKind};
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::services::file_service::FileService;
+    use tempfile::TempDir;
+
+    /// Assembles a PDF from its objects, computing the xref table
+    fn build_pdf(objects: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_offset
+            )
+            .as_bytes(),
+        );
+        pdf
+    }
+
+    fn stream_object(data: &[u8]) -> Vec<u8> {
+        let mut object = format!("<< /Length {} >>\nstream\n", data.len()).into_bytes();
+        object.extend_from_slice(data);
+        object.extend_from_slice(b"\nendstream");
+        object
+    }
+
+    /// Three documents separated by blank pages: each document page carries a
+    /// large dark block, each separator page is empty
+    fn three_document_scan() -> Vec<u8> {
+        let pages = [true, false, true, false, true];
+        let kids: Vec<String> = (0..pages.len()).map(|index| format!("{} 0 R", 3 + index * 2)).collect();
+        let mut objects = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes(),
+        ];
+        for (index, has_content) in pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents {} 0 R >>",
+                    4 + index * 2
+                )
+                .into_bytes(),
+            );
+            let content: &[u8] = if *has_content { b"0 g 100 300 400 300 re f" } else { b"" };
+            objects.push(stream_object(content));
+        }
+        build_pdf(objects)
+    }
+
+    fn poppler_available() -> bool {
+        ["pdftoppm", "pdfseparate", "pdfunite"]
+            .iter()
+            .all(|tool| std::process::Command::new(tool).arg("-v").output().is_ok())
+    }
+
+    #[tokio::test]
+    async fn test_three_document_scan_splits_at_blank_pages() {
+        if !poppler_available() {
+            eprintln!("Skipping: poppler-utils not installed");
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap().to_string();
+        let service = EnhancedOcrService::new(temp_path.clone(), FileService::new(temp_path.clone()), 100, 100, 300);
+        let scan_path = temp_dir.path().join("scan.pdf");
+        std::fs::write(&scan_path, three_document_scan()).unwrap();
+        let scan_path = scan_path.to_str().unwrap();
+
+        let scan = service.find_separator_pages(scan_path, &SeparatorKind::BlankPage).await.unwrap();
+        assert_eq!(scan.page_count, 5);
+        assert_eq!(scan.separator_pages, vec![2, 4]);
+
+        let segments = split_segments(scan.page_count, &scan.separator_pages);
+        assert_eq!(segments, vec![(1, 1), (3, 3), (5, 5)]);
+
+        let parts = service.extract_pdf_page_ranges(scan_path, &segments).await.unwrap();
+        assert_eq!(parts.len(), 3);
+        for (index, part) in parts.iter().enumerate() {
+            let part_path = temp_dir.path().join(format!("part_{}.pdf", index + 1));
+            std::fs::write(&part_path, part).unwrap();
+            let page_count = service.get_pdf_page_count(part_path.to_str().unwrap()).await.unwrap();
+            assert_eq!(page_count, 1, "part {} should hold one page", index + 1);
+
+            // The separators are dropped, so every part is a document page
+            let scan = service
+                .find_separator_pages(part_path.to_str().unwrap(), &SeparatorKind::BlankPage)
+                .await
+                .unwrap();
+            assert!(scan.separator_pages.is_empty());
+        }
+    }
+}
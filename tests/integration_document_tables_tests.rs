@@ -0,0 +1,109 @@
+//! Integration tests for returning the tables extracted from a document.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::ocr::table_extraction::ExtractedTable;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn tables_request(document_id: Uuid, query: &str, token: &str) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::builder()
+            .method("GET")
+            .uri(format!("/api/documents/{}/tables{}", document_id, query))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    fn table(page: usize, rows: &[&[&str]]) -> ExtractedTable {
+        ExtractedTable {
+            page,
+            rows: rows.iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tables_are_returned_as_json_and_csv() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let document = ctx.state.db.create_document(create_test_document(user.user_response.id)).await?;
+
+            // Extraction hasn't run yet
+            let response = ctx.app.clone().oneshot(tables_request(document.id, "", &token)).await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value =
+                serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await?)?;
+            assert_eq!(body["extracted"], false);
+            assert_eq!(body["tables"], serde_json::json!([]));
+
+            let tables = vec![
+                table(1, &[&["Item", "Qty"], &["Paper, A4", "10"]]),
+                table(3, &[&["Name", "Ext"], &["Ada", "101"]]),
+            ];
+            ctx.state.db.set_document_tables(document.id, &tables).await?;
+
+            let response = ctx.app.clone().oneshot(tables_request(document.id, "", &token)).await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value =
+                serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await?)?;
+            assert_eq!(body["extracted"], true);
+            assert_eq!(body["tables"][1]["page"], 3);
+            assert_eq!(body["tables"][0]["rows"][1], serde_json::json!(["Paper, A4", "10"]));
+
+            let response = ctx.app.clone().oneshot(tables_request(document.id, "?format=csv&table=1", &token)).await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()["content-type"], "text/csv; charset=utf-8");
+            let csv = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            assert_eq!(&csv[..], b"Name,Ext\r\nAda,101\r\n");
+
+            let response = ctx.app.clone().oneshot(tables_request(document.id, "?format=csv", &token)).await?;
+            let csv = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            assert_eq!(&csv[..], b"Item,Qty\r\n\"Paper, A4\",10\r\n\r\nName,Ext\r\nAda,101\r\n");
+
+            let response = ctx.app.clone().oneshot(tables_request(document.id, "?table=2", &token)).await?;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            let response = ctx.app.clone().oneshot(tables_request(document.id, "?format=xlsx", &token)).await?;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tables_of_other_users_documents_are_not_found() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let owner = auth_helper.create_test_user().await;
+            let other = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&other.username, "password123").await;
+            let document = ctx.state.db.create_document(create_test_document(owner.user_response.id)).await?;
+            ctx.state.db.set_document_tables(document.id, &[table(1, &[&["a", "b"], &["1", "2"]])]).await?;
+
+            let response = ctx.app.clone().oneshot(tables_request(document.id, "", &token)).await?;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
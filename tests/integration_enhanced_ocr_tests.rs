@@ -39,6 +39,7 @@ mod tests {
             contrast_ratio: 0.5,
             noise_level: 0.1,
             sharpness: 0.8,
+            text_density: 0.08,
         };
         
         assert_eq!(stats.average_brightness, 128.0);
@@ -163,6 +164,9 @@ mod tests {
             word_count: 2,
             preprocessing_applied: vec!["noise_reduction".to_string()],
             processed_image_path: Some("/tmp/processed.png".to_string()),
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
         
         assert_eq!(result.text, "Test text");
@@ -305,6 +309,9 @@ mod tests {
             word_count: 9,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
         
         let result_validation = service.validate_ocr_quality(&result, &settings);
@@ -328,6 +335,9 @@ mod tests {
             word_count: 3,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         // Low confidence is now accepted with a warning, not rejected
@@ -351,6 +361,9 @@ mod tests {
             word_count: 0, // No words
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
         
         let result_validation = service.validate_ocr_quality(&result, &settings);
@@ -373,6 +386,9 @@ mod tests {
             word_count: 1,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
         
         let result_validation = service.validate_ocr_quality(&result, &settings);
@@ -395,6 +411,9 @@ mod tests {
             word_count: 10,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
         
         let result_validation = service.validate_ocr_quality(&result, &settings);
@@ -564,6 +583,87 @@ startxref
         assert!(settings.ocr_enhance_contrast);
         assert!(settings.ocr_remove_noise);
         assert!(settings.ocr_detect_orientation);
+        assert!(!settings.ocr_auto_psm);
+    }
+
+    #[test]
+    fn test_select_psm_for_text_density() {
+        // Sparse pages (receipts, labels) use sparse text mode
+        assert_eq!(EnhancedOcrService::select_psm_for_text_density(0.005), 11);
+        // Typical pages use fully automatic segmentation
+        assert_eq!(EnhancedOcrService::select_psm_for_text_density(0.07), 3);
+        // Dense text blocks use single block mode
+        assert_eq!(EnhancedOcrService::select_psm_for_text_density(0.25), 6);
+    }
+
+    #[test]
+    fn test_merge_hybrid_text_appends_only_new_lines() {
+        use readur::ocr::enhanced::merge_hybrid_text;
+
+        let text_layer = "Quarterly Report\nRevenue grew by 12% compared to last year.\nSee Figure 1.";
+        let ocr_text = "Quarterly Report\nRevenue grew by 12%\ncompared to last year.\n\nFigure 1: Sales by Region\nNorth 4,200\n|\nSee Figure 1.\nNorth 4,200";
+
+        let (merged, added) = merge_hybrid_text(text_layer, ocr_text);
+        assert_eq!(added, 2);
+        assert_eq!(merged, format!("{}\n\nFigure 1: Sales by Region\nNorth 4,200", text_layer));
+    }
+
+    #[test]
+    fn test_merge_hybrid_text_without_new_text_keeps_layer() {
+        use readur::ocr::enhanced::merge_hybrid_text;
+
+        let text_layer = "Invoice 2024-001\nTotal due: 100 EUR";
+        // Casing and punctuation differences from OCR don't count as new text
+        let (merged, added) = merge_hybrid_text(text_layer, "INVOICE 2024 001\nTotal due 100 EUR");
+        assert_eq!(added, 0);
+        assert_eq!(merged, text_layer);
+    }
+
+    #[test]
+    fn test_capped_dimensions() {
+        // Images within the cap are left alone
+        assert_eq!(EnhancedOcrService::capped_dimensions(2000, 1000, 2_000_000), None);
+
+        // A0 at 600 DPI is roughly 19866x28087 (~558 megapixels)
+        let (width, height) = EnhancedOcrService::capped_dimensions(19866, 28087, 40_000_000).unwrap();
+        assert!(width as u64 * height as u64 <= 40_000_000);
+        let original_ratio = 19866.0 / 28087.0;
+        assert!((width as f64 / height as f64 - original_ratio).abs() < 0.001);
+    }
+
+    #[cfg(feature = "ocr")]
+    #[test]
+    fn test_downscale_to_max_pixels() {
+        use image::{DynamicImage, GenericImageView, ImageBuffer, Luma};
+
+        let max_pixels = 1_000_000;
+        let large: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::from_pixel(6000, 4500, Luma([255u8]));
+        let downscaled = EnhancedOcrService::downscale_to_max_pixels(DynamicImage::ImageLuma8(large), max_pixels);
+
+        let (width, height) = downscaled.dimensions();
+        assert!((width as i64) * (height as i64) <= max_pixels);
+        assert!((width as f64 / height as f64 - 6000.0 / 4500.0).abs() < 0.01);
+
+        let small: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::from_pixel(800, 600, Luma([255u8]));
+        let unchanged = EnhancedOcrService::downscale_to_max_pixels(DynamicImage::ImageLuma8(small), max_pixels);
+        assert_eq!(unchanged.dimensions(), (800, 600));
+    }
+
+    #[cfg(feature = "ocr")]
+    #[test]
+    fn test_estimate_text_density() {
+        use image::{ImageBuffer, Luma};
+
+        let blank: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::from_pixel(100, 100, Luma([255u8]));
+        assert_eq!(EnhancedOcrService::estimate_text_density(&blank), 0.0);
+
+        // 10 dark rows out of 100 on a white page
+        let lined = ImageBuffer::from_fn(100, 100, |_, y| if y % 10 == 0 { Luma([0u8]) } else { Luma([255u8]) });
+        assert!((EnhancedOcrService::estimate_text_density(&lined) - 0.1).abs() < 0.001);
+
+        // Inverted page (light text on dark background) measures the same
+        let inverted = ImageBuffer::from_fn(100, 100, |_, y| if y % 10 == 0 { Luma([255u8]) } else { Luma([0u8]) });
+        assert!((EnhancedOcrService::estimate_text_density(&inverted) - 0.1).abs() < 0.001);
     }
 
     #[tokio::test]
@@ -633,6 +733,9 @@ startxref
             word_count: 2,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         let validation_result = service.validate_ocr_quality(&result, &settings);
@@ -661,6 +764,9 @@ startxref
             word_count: 3,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         let validation_result = service.validate_ocr_quality(&result, &settings);
@@ -686,6 +792,9 @@ startxref
             word_count: 5,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         // Calculate to verify we have good content ratio (letters + digits)
@@ -717,6 +826,9 @@ startxref
             word_count: 2,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         // Verify 100% content (30% digits + 70% letters)
@@ -751,6 +863,9 @@ startxref
             word_count: 1,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         // Verify exactly 10% content (letters+digits)
@@ -784,6 +899,9 @@ startxref
             word_count: 1,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         // Verify <10% content (letters+digits)
@@ -819,6 +937,9 @@ startxref
             word_count: 0,
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         let validation_result = service.validate_ocr_quality(&result, &settings);
@@ -848,6 +969,9 @@ startxref
             word_count: 0, // Whitespace doesn't count as words
             preprocessing_applied: vec![],
             processed_image_path: None,
+            page_segmentation_mode: None,
+            orientation_correction: None,
+            ocr_dpi: None,
         };
 
         let validation_result = service.validate_ocr_quality(&result, &settings);
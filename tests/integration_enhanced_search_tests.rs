@@ -214,6 +214,13 @@ mod tests {
             include_snippets: None,
             snippet_length: None,
             search_mode: None,
+            include_shared: None,
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
         };
         
         // Test that default values work correctly
@@ -233,6 +240,13 @@ mod tests {
             include_snippets: Some(true),
             snippet_length: Some(300),
             search_mode: Some(SearchMode::Phrase),
+            include_shared: None,
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
         };
         
         assert_eq!(request.query, "test query");
@@ -306,7 +320,10 @@ mod tests {
             ocr_status: Some("completed".to_string()),
             search_rank: Some(0.75),
             snippets,
-        };
+        
+            is_owner: true,
+            duplicate_count: 0,
+            duplicate_ids: Vec::new(),};
         
         assert_eq!(response.id, doc_id);
         assert_eq!(response.filename, "test.pdf");
@@ -600,6 +617,13 @@ mod tests {
             include_snippets: None,
             snippet_length: None,
             search_mode: None,
+            include_shared: None,
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
         };
         
         // Should handle empty query gracefully
@@ -615,6 +639,13 @@ mod tests {
             include_snippets: Some(true),
             snippet_length: Some(i32::MAX),
             search_mode: Some(SearchMode::Boolean),
+            include_shared: None,
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
         };
         
         // Should handle extreme values without panicking
@@ -698,6 +729,9 @@ mod tests {
                     ],
                 }
             ],
+            is_owner: true,
+            duplicate_count: 0,
+            duplicate_ids: Vec::new(),
         };
         
         // Test that all fields are properly accessible
@@ -953,6 +987,13 @@ mod tests {
             include_snippets: Some(true),
             snippet_length: Some(100),
             search_mode: Some(SearchMode::Simple),
+            include_shared: None,
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
         };
         
         let result = ctx.state.db.enhanced_search_documents(user.user_response.id, &search_request).await;
@@ -0,0 +1,137 @@
+//! Integration tests for the structured error envelope.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::error_envelope::ApiErrorFormat;
+    use readur::test_utils::{TestAuthHelper, TestConfigBuilder, TestContext};
+    use serde_json::Value;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    async fn send(ctx: &TestContext, token: Option<&str>, method: &str, uri: &str, body: Option<&str>) -> (StatusCode, Value) {
+        let mut request = axum::http::Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => request
+                .header("Content-Type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap(),
+            None => request.body(axum::body::Body::empty()).unwrap(),
+        };
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    /// Asserts the envelope shape and returns its `error` object
+    fn envelope(body: &Value) -> &Value {
+        let error = &body["error"];
+        assert!(error.is_object(), "expected an error envelope, got {}", body);
+        assert!(error["code"].is_string());
+        assert!(error["message"].is_string());
+        assert!(error.get("details").is_some());
+        assert!(error["request_id"].is_string());
+        error
+    }
+
+    #[tokio::test]
+    async fn test_error_responses_share_the_envelope() {
+        let ctx = TestContext::with_config(
+            TestConfigBuilder::default().with_api_errors(ApiErrorFormat::Structured, false),
+        )
+        .await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let (status, body) = send(&ctx, None, "GET", "/api/documents", None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert_eq!(envelope(&body)["code"], "UNAUTHORIZED");
+
+            let uri = format!("/api/documents/{}", Uuid::new_v4());
+            let (status, body) = send(&ctx, Some(&token), "GET", &uri, None).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(envelope(&body)["code"], "NOT_FOUND");
+
+            // Admin-only route, with the code of the route's own error type
+            let (status, body) = send(&ctx, Some(&token), "GET", "/api/users", None).await;
+            assert_eq!(status, StatusCode::FORBIDDEN);
+            assert_eq!(envelope(&body)["code"], "USER_PERMISSION_DENIED");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validation_errors_list_the_fields() {
+        let ctx = TestContext::with_config(
+            TestConfigBuilder::default().with_api_errors(ApiErrorFormat::Structured, false),
+        )
+        .await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            // Rejected by the settings validation
+            let (status, body) = send(&ctx, Some(&token), "PUT", "/api/settings", Some(r#"{"concurrent_ocr_jobs": 50}"#)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            let error = envelope(&body);
+            assert_eq!(error["code"], "VALIDATION_ERROR");
+            assert_eq!(error["details"]["fields"][0]["field"], "concurrent_ocr_jobs");
+
+            // Rejected while reading the body
+            let (status, body) = send(&ctx, Some(&token), "PUT", "/api/settings", Some(r#"{"ocr_dpi": "high"}"#)).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            let error = envelope(&body);
+            assert_eq!(error["code"], "VALIDATION_ERROR");
+            assert_eq!(error["details"]["fields"][0]["field"], "ocr_dpi");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_legacy_format_is_the_default() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let (status, body) = send(&ctx, Some(&token), "GET", "/api/users", None).await;
+            assert_eq!(status, StatusCode::FORBIDDEN);
+            assert!(body["error"].is_string());
+            assert_eq!(body["code"], "USER_PERMISSION_DENIED");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
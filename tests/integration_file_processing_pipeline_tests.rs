@@ -424,6 +424,8 @@ impl FileProcessingTestClient {
                                 file_owner: doc.file_owner.clone(),
                                 file_group: doc.file_group.clone(),
                                 source_metadata: doc.source_metadata.clone(),
+                                document_date: doc.document_date,
+                                document_date_source: doc.document_date_source.clone(),
                             };
                             return Ok(doc_copy);
                         }
@@ -0,0 +1,174 @@
+//! Integration tests for indexing markdown notes without their front-matter.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use chrono::Utc;
+    use readur::models::{CreateUser, Document, UpdateSettings, UserRole};
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::ocr::queue::OcrQueueItem;
+    use readur::test_utils::TestContext;
+    use readur::AppState;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    const NOTE: &str = "---\n\
+title: Q3 planning\n\
+tags:\n  - project-alpha\n  - \"#meeting\"\n\
+---\n\
+\n\
+Decided to move the launch to September after the supplier review.\n";
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        CreateUser {
+            username: format!("front_matter_{}", suffix),
+            email: format!("front_matter_{}@example.com", suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_pending_markdown_document(user_id: Uuid, file_path: &str, file_size: i64) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: "q3-planning.md".to_string(),
+            original_filename: "q3-planning.md".to_string(),
+            file_path: file_path.to_string(),
+            file_size,
+            mime_type: "text/markdown".to_string(),
+            content: None,
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("pending".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    /// Creates a user with front-matter parsing switched on or off, runs OCR on
+    /// the markdown note and returns the stored document and its label names
+    async fn process_note(state: &Arc<AppState>, parse_front_matter: bool) -> Result<(Document, Vec<String>)> {
+        let user = state
+            .db
+            .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+            .await?;
+        let settings = UpdateSettings {
+            parse_front_matter: Some(parse_front_matter),
+            auto_label_language: Some(false),
+            ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+        };
+        state.db.create_or_update_settings(user.id, &settings).await?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("q3-planning.md");
+        std::fs::write(&file_path, NOTE)?;
+
+        let document = state
+            .db
+            .create_document(create_pending_markdown_document(
+                user.id,
+                file_path.to_str().unwrap(),
+                NOTE.len() as i64,
+            ))
+            .await?;
+        state.queue_service.enqueue_document(document.id, 5, NOTE.len() as i64).await?;
+
+        // Fetch this document's job directly so jobs from concurrent tests are left alone
+        let item = sqlx::query_as::<_, OcrQueueItem>("SELECT * FROM ocr_queue WHERE document_id = $1")
+            .bind(document.id)
+            .fetch_one(state.db.get_pool())
+            .await?;
+
+        let ocr_service = EnhancedOcrService::new(
+            temp_dir.path().to_string_lossy().to_string(),
+            (*state.file_service).clone(),
+            100,
+            100,
+            300,
+        );
+        state.queue_service.process_item(item, &ocr_service).await?;
+
+        let stored = state
+            .db
+            .get_document_by_id(document.id, user.id, UserRole::User)
+            .await?
+            .expect("document exists");
+        assert_eq!(stored.ocr_status.as_deref(), Some("completed"), "error: {:?}", stored.ocr_error);
+
+        let labels = state.db.get_document_labels(document.id).await?;
+        for label in &labels {
+            assert_eq!(label.user_id, Some(user.id), "front-matter labels belong to the owner");
+            assert!(!label.is_system);
+        }
+        Ok((stored, labels.into_iter().map(|label| label.name).collect()))
+    }
+
+    #[tokio::test]
+    async fn test_markdown_front_matter_becomes_labels_and_metadata() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let (document, labels) = process_note(ctx.state(), true).await?;
+
+            let text = document.ocr_text.unwrap_or_default();
+            assert_eq!(text, "Decided to move the launch to September after the supplier review.");
+            assert!(!text.contains("title:"));
+
+            let metadata = document.source_metadata.expect("source metadata");
+            assert_eq!(metadata["front_matter"]["title"], "Q3 planning");
+            assert_eq!(metadata["front_matter"]["tags"], serde_json::json!(["project-alpha", "#meeting"]));
+
+            assert_eq!(labels, vec!["meeting".to_string(), "project-alpha".to_string()]);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_markdown_is_indexed_verbatim_when_disabled() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let (document, labels) = process_note(ctx.state(), false).await?;
+
+            let text = document.ocr_text.unwrap_or_default();
+            assert!(text.starts_with("---\ntitle: Q3 planning"), "unexpected text: {}", text);
+            assert!(document
+                .source_metadata
+                .map_or(true, |metadata| metadata.get("front_matter").is_none()));
+            assert!(labels.is_empty(), "unexpected labels: {:?}", labels);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
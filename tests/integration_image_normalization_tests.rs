@@ -0,0 +1,147 @@
+//! Integration tests for converting the images a source syncs to one format.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use readur::ingestion::document_ingestion::{DocumentIngestionService, IngestionResult};
+    use readur::models::{CreateSource, Document, SourceType};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+    use uuid::Uuid;
+
+    fn local_folder_source(name: &str, image_normalization: serde_json::Value) -> CreateSource {
+        CreateSource {
+            name: name.to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": ["/scans"],
+                "file_extensions": ["bmp", "jpg"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false,
+                "image_normalization": image_normalization
+            }),
+        }
+    }
+
+    fn encode(format: image::ImageFormat) -> Vec<u8> {
+        let image = image::RgbImage::from_fn(40, 20, |x, y| image::Rgb([(x * 6) as u8, (y * 12) as u8, 90]));
+        let mut data = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut data), format).unwrap();
+        data
+    }
+
+    async fn ingest(
+        ctx: &TestContext,
+        user_id: Uuid,
+        source_id: Uuid,
+        filename: &str,
+        data: Vec<u8>,
+        mime_type: &str,
+    ) -> Result<Document> {
+        let state = ctx.state();
+        let ingestion = DocumentIngestionService::new(state.db.clone(), (*state.file_service).clone());
+        let result = ingestion
+            .ingest_from_source(filename, data, mime_type, user_id, source_id, "local_folder")
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        match result {
+            IngestionResult::Created(document) => Ok(document),
+            other => panic!("{} was not stored: {:?}", filename, other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bmp_is_stored_as_png_when_normalization_is_on() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let source = state
+                .db
+                .create_source(
+                    user_id,
+                    &local_folder_source("Scanner", json!({"enabled": true, "format": "png", "keep_original": true})),
+                )
+                .await?;
+
+            let bmp = encode(image::ImageFormat::Bmp);
+            let document = ingest(&ctx, user_id, source.id, "scan.bmp", bmp.clone(), "image/bmp").await?;
+
+            assert_eq!(document.mime_type, "image/png");
+            assert_eq!(document.filename, "scan.png");
+            assert_eq!(document.original_filename, "scan.bmp");
+            let stored = state.file_service.read_file(&document.file_path).await?;
+            assert_eq!(image::guess_format(&stored)?, image::ImageFormat::Png);
+            assert_eq!(document.file_size, stored.len() as i64);
+            // Syncing the same file again is recognized by the hash of what was synced
+            assert_eq!(document.file_hash, Some(format!("{:x}", Sha256::digest(&bmp))));
+
+            let source_metadata = document.source_metadata.expect("source metadata");
+            let metadata = &source_metadata["image_normalization"];
+            assert_eq!(metadata["original_format"], "bmp");
+            assert_eq!(metadata["original_mime_type"], "image/bmp");
+            assert_eq!(metadata["original_size"], bmp.len());
+            let original = state.file_service.read_file(metadata["original_path"].as_str().unwrap()).await?;
+            assert_eq!(original, bmp);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_images_are_stored_as_synced_when_kept_or_normalization_is_off() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+
+            // JPEG is kept by default
+            let normalized = state
+                .db
+                .create_source(user_id, &local_folder_source("Scanner", json!({"enabled": true})))
+                .await?;
+            let jpeg = encode(image::ImageFormat::Jpeg);
+            let document = ingest(&ctx, user_id, normalized.id, "photo.jpg", jpeg.clone(), "image/jpeg").await?;
+            assert_eq!(document.mime_type, "image/jpeg");
+            assert_eq!(document.filename, "photo.jpg");
+            assert_eq!(state.file_service.read_file(&document.file_path).await?, jpeg);
+
+            let unchanged = state
+                .db
+                .create_source(user_id, &local_folder_source("Archive", json!({"enabled": false})))
+                .await?;
+            let bmp = encode(image::ImageFormat::Bmp);
+            let document = ingest(&ctx, user_id, unchanged.id, "scan.bmp", bmp.clone(), "image/bmp").await?;
+            assert_eq!(document.mime_type, "image/bmp");
+            assert_eq!(state.file_service.read_file(&document.file_path).await?, bmp);
+            assert!(document
+                .source_metadata
+                .map_or(true, |metadata| metadata.get("image_normalization").is_none()));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -10,6 +10,7 @@ mod tests {
     use serde_json::json;
     use sqlx::Row;
     use std::collections::HashMap;
+    use tower::util::ServiceExt;
     use uuid::Uuid;
 
 
@@ -28,6 +29,7 @@ mod tests {
                 color: "#ff0000".to_string(),
                 background_color: None,
                 icon: Some("star".to_string()),
+                ocr_dpi: None,
             };
 
             let result = sqlx::query_scalar::<_, uuid::Uuid>(
@@ -156,6 +158,7 @@ mod tests {
                 color: Some("#00ff00".to_string()),
                 background_color: None,
                 icon: Some("edit".to_string()),
+                ocr_dpi: None,
             };
 
             let result = sqlx::query_as::<_, Label>(
@@ -969,4 +972,122 @@ mod tests {
 
         result.unwrap();
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_label_ocr_regions_are_saved_and_found_for_labeled_documents() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let label_id = sqlx::query_scalar::<_, Uuid>(
+                "INSERT INTO labels (user_id, name, color) VALUES ($1, 'Invoice', '#ff0000') RETURNING id",
+            )
+            .bind(user_id)
+            .fetch_one(&ctx.state.db.pool)
+            .await?;
+
+            let put_regions = |body: serde_json::Value| {
+                let app = ctx.app.clone();
+                let token = token.clone();
+                async move {
+                    app.oneshot(
+                        axum::http::Request::builder()
+                            .method("PUT")
+                            .uri(format!("/api/labels/{}/ocr-regions", label_id))
+                            .header("Authorization", format!("Bearer {}", token))
+                            .header("Content-Type", "application/json")
+                            .body(axum::body::Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap()
+                }
+            };
+
+            let response = put_regions(json!({
+                "regions": [
+                    { "name": "invoice_total", "region": { "x": 0.6, "y": 0.85, "width": 0.3, "height": 0.05 } },
+                    { "name": "invoice_number", "page": 2, "region": { "x": 0.6, "y": 0.1, "width": 0.3, "height": 0.04 } }
+                ]
+            }))
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            // Out of the page, and the same name twice, are rejected without touching the saved regions
+            let outside = put_regions(json!({
+                "regions": [{ "name": "total", "region": { "x": 0.8, "y": 0.0, "width": 0.3, "height": 0.1 } }]
+            }))
+            .await;
+            assert_eq!(outside.status(), StatusCode::BAD_REQUEST);
+            let repeated = put_regions(json!({
+                "regions": [
+                    { "name": "total", "region": { "x": 0.1, "y": 0.1, "width": 0.1, "height": 0.1 } },
+                    { "name": "total", "region": { "x": 0.5, "y": 0.5, "width": 0.1, "height": 0.1 } }
+                ]
+            }))
+            .await;
+            assert_eq!(repeated.status(), StatusCode::BAD_REQUEST);
+
+            let regions = ctx.state.db.get_label_ocr_regions(label_id).await?;
+            let names: Vec<(&str, usize)> = regions.iter().map(|r| (r.name.as_str(), r.page)).collect();
+            assert_eq!(names, vec![("invoice_number", 2), ("invoice_total", 1)]);
+
+            // Documents with the label get its regions, others none
+            let mut document_ids = Vec::new();
+            for filename in ["invoice.pdf", "letter.pdf"] {
+                let document_id = Uuid::new_v4();
+                sqlx::query(
+                    r#"
+                    INSERT INTO documents (id, user_id, filename, original_filename, file_path, file_size, mime_type, created_at, updated_at)
+                    VALUES ($1, $2, $3, $3, $4, 1024, 'application/pdf', NOW(), NOW())
+                    "#,
+                )
+                .bind(document_id)
+                .bind(user_id)
+                .bind(filename)
+                .bind(format!("/test/{}", filename))
+                .execute(&ctx.state.db.pool)
+                .await?;
+                document_ids.push(document_id);
+            }
+            sqlx::query("INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2)")
+                .bind(document_ids[0])
+                .bind(label_id)
+                .execute(&ctx.state.db.pool)
+                .await?;
+
+            let labeled = ctx.state.db.get_document_ocr_regions(document_ids[0]).await?;
+            assert_eq!(labeled.len(), 2);
+            assert!(labeled.iter().all(|(label, _)| label == "Invoice"));
+            assert!(ctx.state.db.get_document_ocr_regions(document_ids[1]).await?.is_empty());
+
+            // Fields are stored per document and replaced by the next run
+            assert_eq!(ctx.state.db.get_document_fields(document_ids[0]).await?, None);
+            let field = readur::ocr::region::ExtractedField {
+                name: "invoice_total".to_string(),
+                label: "Invoice".to_string(),
+                page: 1,
+                text: "Total: $1,240.00".to_string(),
+                confidence: 93.1,
+            };
+            ctx.state.db.set_document_fields(document_ids[0], &[field.clone()]).await?;
+            assert_eq!(ctx.state.db.get_document_fields(document_ids[0]).await?, Some(vec![field]));
+
+            // Deleting the label deletes its regions
+            sqlx::query("DELETE FROM labels WHERE id = $1").bind(label_id).execute(&ctx.state.db.pool).await?;
+            assert!(ctx.state.db.get_label_ocr_regions(label_id).await?.is_empty());
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,196 @@
+//! Integration tests for labeling documents with their detected language after OCR.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use chrono::Utc;
+    use readur::models::{CreateUser, Document, UpdateSettings, UserRole};
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::ocr::queue::OcrQueueItem;
+    use readur::test_utils::TestContext;
+    use readur::AppState;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    const GERMAN_TEXT: &str = "Die Rechnung ist bis zum Ende des Monats zu bezahlen. Der Betrag wird von \
+        dem Konto abgebucht, das Sie bei der Anmeldung angegeben haben. Wenn die Zahlung nicht \
+        rechtzeitig eingeht, wird eine Mahnung mit einer Gebühr an die Adresse des Kunden geschickt.";
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        CreateUser {
+            username: format!("language_label_{}", suffix),
+            email: format!("language_label_{}@example.com", suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_pending_text_document(user_id: Uuid, file_path: &str, file_size: i64) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: "rechnung.txt".to_string(),
+            original_filename: "rechnung.txt".to_string(),
+            file_path: file_path.to_string(),
+            file_size,
+            mime_type: "text/plain".to_string(),
+            content: None,
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("pending".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    /// Creates a user with language labeling switched on or off, runs OCR on a
+    /// German text document and returns the document's label names
+    async fn process_german_document(state: &Arc<AppState>, auto_label_language: bool) -> Result<Vec<String>> {
+        let user = state
+            .db
+            .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+            .await?;
+        let settings = UpdateSettings {
+            auto_label_language: Some(auto_label_language),
+            ..UpdateSettings::language_update(vec!["deu".to_string()], "deu".to_string(), "deu".to_string())
+        };
+        state.db.create_or_update_settings(user.id, &settings).await?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("rechnung.txt");
+        std::fs::write(&file_path, GERMAN_TEXT)?;
+
+        let document = state
+            .db
+            .create_document(create_pending_text_document(
+                user.id,
+                file_path.to_str().unwrap(),
+                GERMAN_TEXT.len() as i64,
+            ))
+            .await?;
+        state.queue_service.enqueue_document(document.id, 5, GERMAN_TEXT.len() as i64).await?;
+
+        // Fetch this document's job directly so jobs from concurrent tests are left alone
+        let item = sqlx::query_as::<_, OcrQueueItem>("SELECT * FROM ocr_queue WHERE document_id = $1")
+            .bind(document.id)
+            .fetch_one(state.db.get_pool())
+            .await?;
+
+        let ocr_service = EnhancedOcrService::new(
+            temp_dir.path().to_string_lossy().to_string(),
+            (*state.file_service).clone(),
+            100,
+            100,
+            300,
+        );
+        state.queue_service.process_item(item, &ocr_service).await?;
+
+        let stored = state
+            .db
+            .get_document_by_id(document.id, user.id, UserRole::User)
+            .await?
+            .expect("document exists");
+        assert_eq!(stored.ocr_status.as_deref(), Some("completed"), "error: {:?}", stored.ocr_error);
+        // The search language is recorded whether or not documents are labeled
+        assert_eq!(state.db.get_document_search_language(document.id).await?.as_deref(), Some("german"));
+
+        let labels = state.db.get_document_labels(document.id).await?;
+        for label in labels.iter().filter(|label| label.name.starts_with("lang:")) {
+            assert!(label.is_system, "language label should be a system label");
+            assert!(label.user_id.is_none(), "language label should not belong to a user");
+        }
+        Ok(labels.into_iter().map(|label| label.name).collect())
+    }
+
+    #[tokio::test]
+    async fn test_ocr_applies_detected_language_label_when_enabled() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let labels = process_german_document(ctx.state(), true).await?;
+            assert_eq!(labels, vec!["lang:de".to_string()]);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ocr_leaves_documents_unlabeled_when_disabled() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let labels = process_german_document(ctx.state(), false).await?;
+            assert!(labels.is_empty(), "unexpected labels: {:?}", labels);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_relabeling_replaces_previous_language_label() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user = state
+                .db
+                .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+                .await?;
+            let document = state
+                .db
+                .create_document(create_pending_text_document(user.id, "/tmp/relabel.txt", 1))
+                .await?;
+
+            let german = state.db.set_document_language_label(document.id, "lang:de").await?;
+            let french = state.db.set_document_language_label(document.id, "lang:fr").await?;
+            assert_ne!(german, french);
+
+            let labels = state.db.get_document_labels(document.id).await?;
+            let names: Vec<&str> = labels.iter().map(|label| label.name.as_str()).collect();
+            assert_eq!(names, vec!["lang:fr"]);
+
+            // Labeling again with the same language reuses the existing system label
+            let again = state.db.set_document_language_label(document.id, "lang:fr").await?;
+            assert_eq!(again, french);
+            assert_eq!(state.db.get_document_labels(document.id).await?.len(), 1);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -199,4 +199,93 @@ mod tests {
 
         result.unwrap();
     }
+
+    async fn error_body(response: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).expect("413 body should be JSON")
+    }
+
+    /// A single upload over MAX_UPLOAD_BYTES is refused up front with a structured 413
+    #[tokio::test]
+    async fn test_upload_over_request_limit_is_rejected_with_413() {
+        let config = TestConfigBuilder::default().with_max_upload_bytes(64 * 1024);
+        let ctx = TestContext::with_config(config).await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            // Still accepted under the limit
+            let (boundary, body) = create_multipart_body(&vec![b'a'; 16 * 1024], "small.txt", "text/plain");
+            let response = ctx.app.clone()
+                .oneshot(upload_request(&token, &boundary, body))
+                .await
+                .unwrap();
+            assert!(response.status().is_success(), "upload under the limit should succeed, got: {}", response.status());
+
+            let (boundary, body) = create_multipart_body(&vec![b'a'; 128 * 1024], "big.txt", "text/plain");
+            let body_len = body.len();
+            let mut request = upload_request(&token, &boundary, body);
+            request.headers_mut().insert("Content-Length", body_len.into());
+            let response = ctx.app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+            let error = error_body(response).await;
+            assert_eq!(error["error_code"], "REQUEST_TOO_LARGE");
+            assert_eq!(error["max_bytes"], 64 * 1024);
+            let message = error["error"].as_str().unwrap();
+            assert!(message.contains(&body_len.to_string()), "message should give the body size: {}", message);
+            assert!(message.contains("MAX_UPLOAD_BYTES"), "message should name the setting: {}", message);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    /// A chunked upload with no declared size is cut off at the limit and still gets a 413
+    #[tokio::test]
+    async fn test_chunked_upload_over_request_limit_is_rejected_with_413() {
+        let config = TestConfigBuilder::default().with_max_upload_bytes(64 * 1024);
+        let ctx = TestContext::with_config(config).await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let (boundary, body) = create_multipart_body(&vec![b'a'; 128 * 1024], "big.txt", "text/plain");
+            let chunks: Vec<Result<Vec<u8>, std::io::Error>> = body.chunks(8 * 1024).map(|chunk| Ok(chunk.to_vec())).collect();
+            let request = axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/documents")
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+                .body(axum::body::Body::from_stream(futures::stream::iter(chunks)))
+                .unwrap();
+
+            let response = ctx.app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+            let error = error_body(response).await;
+            assert!(
+                error["error"].as_str().unwrap().contains("MAX_UPLOAD_BYTES"),
+                "unexpected error: {}",
+                error
+            );
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
 }
@@ -0,0 +1,142 @@
+//! Integration tests for the library statistics endpoint.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::Document;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn document(user_id: Uuid, filename: &str, mime_type: &str, file_size: i64, ocr_status: &str, confidence: Option<f32>) -> Document {
+        let mut document = create_test_document(user_id);
+        document.filename = filename.to_string();
+        document.original_filename = filename.to_string();
+        document.mime_type = mime_type.to_string();
+        document.file_size = file_size;
+        document.ocr_status = Some(ocr_status.to_string());
+        document.ocr_confidence = confidence;
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    async fn get_library_stats(app: &axum::Router, token: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("GET")
+                    .uri("/api/metrics/library")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    fn facet_count(facets: &serde_json::Value, value: &str) -> i64 {
+        facets
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|facet| facet["value"] == value)
+            .map(|facet| facet["count"].as_i64().unwrap())
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn test_library_stats_are_scoped_to_the_user() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let other_user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+
+            db.create_document(document(user_id, "a.pdf", "application/pdf", 1000, "completed", Some(90.0))).await?;
+            db.create_document(document(user_id, "b.pdf", "application/pdf", 2000, "completed", Some(70.0))).await?;
+            db.create_document(document(user_id, "c.png", "image/png", 500, "failed", Some(10.0))).await?;
+            db.create_document(document(other_user.user_response.id, "d.png", "image/png", 9999, "completed", Some(50.0))).await?;
+
+            let (status, stats) = get_library_stats(&ctx.app, &token).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(stats["total_documents"], 3);
+            assert_eq!(stats["total_storage_bytes"], 3500);
+            assert_eq!(facet_count(&stats["by_mime_type"], "application/pdf"), 2);
+            assert_eq!(facet_count(&stats["by_mime_type"], "image/png"), 1);
+            assert_eq!(facet_count(&stats["by_ocr_status"], "completed"), 2);
+            assert_eq!(facet_count(&stats["by_ocr_status"], "failed"), 1);
+
+            // Only completed OCR counts towards the average confidence
+            let average = stats["average_ocr_confidence"].as_f64().unwrap();
+            assert!((average - 80.0).abs() < 0.01, "unexpected average {}", average);
+
+            let by_source = stats["by_source"].as_array().unwrap();
+            assert_eq!(by_source.len(), 1);
+            assert!(by_source[0]["source_id"].is_null(), "uploads have no source");
+            assert_eq!(by_source[0]["document_count"], 3);
+
+            let ingestion = stats["ingestion_last_30_days"].as_array().unwrap();
+            assert_eq!(ingestion.len(), 30);
+            assert_eq!(ingestion.last().unwrap()["count"], 3, "today's uploads are in the last bucket");
+            assert_eq!(ingestion.iter().map(|day| day["count"].as_i64().unwrap()).sum::<i64>(), 3);
+
+            // Results are cached briefly, so a new document doesn't show up right away
+            db.create_document(document(user_id, "e.pdf", "application/pdf", 100, "pending", None)).await?;
+            let (_, cached) = get_library_stats(&ctx.app, &token).await;
+            assert_eq!(cached["total_documents"], 3);
+            assert_eq!(cached["generated_at"], stats["generated_at"]);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_admin_library_stats_cover_all_users() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let admin = auth_helper.create_admin_user().await;
+            let token = auth_helper.login_user(&admin.username, &admin.password).await;
+            let first = auth_helper.create_test_user().await;
+            let second = auth_helper.create_test_user().await;
+
+            db.create_document(document(first.user_response.id, "a.pdf", "application/pdf", 1000, "completed", Some(90.0))).await?;
+            db.create_document(document(second.user_response.id, "b.pdf", "application/pdf", 2000, "completed", Some(90.0))).await?;
+
+            let (status, stats) = get_library_stats(&ctx.app, &token).await;
+            assert_eq!(status, StatusCode::OK);
+            // The test database may be shared with other tests, so only a lower bound holds
+            assert!(stats["total_documents"].as_i64().unwrap() >= 2);
+            assert!(stats["total_storage_bytes"].as_i64().unwrap() >= 3000);
+            assert!(facet_count(&stats["by_mime_type"], "application/pdf") >= 2);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
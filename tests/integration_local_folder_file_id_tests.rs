@@ -0,0 +1,102 @@
+//! Integration tests for recognizing renamed and moved files of a local folder
+//! source by their file id.
+
+#[cfg(all(test, unix))]
+mod tests {
+    use anyhow::Result;
+    use readur::models::{CreateSource, Document, SourceType};
+    use readur::scheduling::source_sync::SourceSyncService;
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use serde_json::json;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    fn local_folder_source(folder: &str, track_file_ids: bool) -> CreateSource {
+        CreateSource {
+            name: format!("Scans {}", Uuid::new_v4().simple()),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": [folder],
+                "file_extensions": ["txt"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false,
+                "track_file_ids": track_file_ids
+            }),
+        }
+    }
+
+    /// Syncs a folder holding one file, renames the file into a subfolder and
+    /// syncs again, returning the user's documents afterwards
+    async fn sync_rename_sync(ctx: &TestContext, track_file_ids: bool) -> Result<(TempDir, Vec<Document>)> {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let folder = TempDir::new()?;
+        let original = folder.path().join("invoice.txt");
+        std::fs::write(&original, format!("Invoice {}", Uuid::new_v4()))?;
+
+        let db = &ctx.state.db;
+        let source = db
+            .create_source(user_id, &local_folder_source(folder.path().to_str().unwrap(), track_file_ids))
+            .await?;
+        let sync_service = SourceSyncService::new(ctx.state.clone());
+        sync_service.sync_source(&source, false).await?;
+        assert_eq!(db.get_documents_by_user(user_id, 100, 0).await?.len(), 1);
+
+        std::fs::create_dir(folder.path().join("paid"))?;
+        std::fs::rename(&original, folder.path().join("paid").join("invoice-2026.txt"))?;
+        sync_service.sync_source(&source, false).await?;
+
+        let documents = db.get_documents_by_user(user_id, 100, 0).await?;
+        Ok((folder, documents))
+    }
+
+    #[tokio::test]
+    async fn test_renamed_file_keeps_its_document_and_updates_the_path() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let (folder, documents) = sync_rename_sync(&ctx, true).await?;
+            assert_eq!(documents.len(), 1, "the renamed file must not be stored again");
+
+            let moved = folder.path().join("paid").join("invoice-2026.txt");
+            let document = &documents[0];
+            assert_eq!(document.source_path.as_deref(), moved.to_str());
+            assert_eq!(document.original_filename, "invoice-2026.txt");
+            assert_eq!(document.filename, "invoice-2026.txt");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_without_file_ids_renamed_file_is_matched_by_content() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let (folder, documents) = sync_rename_sync(&ctx, false).await?;
+            assert_eq!(documents.len(), 1);
+
+            let original = folder.path().join("invoice.txt");
+            assert_eq!(documents[0].source_path.as_deref(), original.to_str());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
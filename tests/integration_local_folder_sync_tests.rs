@@ -31,6 +31,12 @@ fn create_test_local_config() -> LocalFolderSourceConfig {
         auto_sync: true,
         sync_interval_minutes: 30,
         file_extensions: vec![".pdf".to_string(), ".txt".to_string(), ".jpg".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        track_file_ids: false,
     }
 }
 
@@ -334,6 +340,11 @@ fn test_error_handling() {
         auto_sync: true,
         sync_interval_minutes: 30,
         file_extensions: vec![".txt".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        track_file_ids: false,
     };
     
     assert_eq!(non_existent_config.watch_folders[0], "/this/path/does/not/exist");
@@ -346,6 +357,11 @@ fn test_error_handling() {
         auto_sync: true,
         sync_interval_minutes: 30,
         file_extensions: vec![".txt".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        track_file_ids: false,
     };
     
     assert!(empty_paths_config.watch_folders.is_empty());
@@ -358,6 +374,11 @@ fn test_error_handling() {
         auto_sync: true,
         sync_interval_minutes: 0, // Invalid
         file_extensions: vec![".txt".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        track_file_ids: false,
     };
     
     assert_eq!(invalid_interval_config.sync_interval_minutes, 0);
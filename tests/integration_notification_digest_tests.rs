@@ -0,0 +1,126 @@
+//! Integration tests for digest delivery of notifications.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::models::{CreateNotification, CreateUser, UserRole};
+    use readur::services::notification_digest::NotificationDigestService;
+    use readur::test_utils::TestContext;
+    use uuid::Uuid;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        let test_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+            .to_string();
+        let unique_suffix = &test_id[test_id.len().saturating_sub(8)..];
+
+        CreateUser {
+            username: format!("testuser_{}_{}", suffix, unique_suffix),
+            email: format!("test_{}_{}@example.com", suffix, unique_suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn sync_completed(files_processed: u64) -> CreateNotification {
+        CreateNotification {
+            notification_type: "success".to_string(),
+            title: "Source Sync Completed".to_string(),
+            message: format!("Successfully processed {} files from Archive", files_processed),
+            action_url: Some("/documents".to_string()),
+            metadata: Some(serde_json::json!({ "files_processed": files_processed })),
+        }
+    }
+
+    async fn enable_digest(ctx: &TestContext, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO settings (user_id, notification_delivery_mode, notification_digest_interval_minutes)
+               VALUES ($1, 'digest', 5)
+               ON CONFLICT (user_id) DO UPDATE
+               SET notification_delivery_mode = 'digest', notification_digest_interval_minutes = 5"#
+        )
+        .bind(user_id)
+        .execute(ctx.state.db.get_pool())
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_immediate_mode_delivers_right_away() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("digest1")).await?;
+
+            let delivered = db.deliver_notification(user.id, &sync_completed(10)).await?;
+            assert!(delivered.is_some());
+            assert_eq!(db.count_user_notifications(user.id).await?, 1);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_digest_mode_coalesces_but_errors_are_immediate() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("digest2")).await?;
+            enable_digest(&ctx, user.id).await?;
+
+            assert!(db.deliver_notification(user.id, &sync_completed(1000)).await?.is_none());
+            assert!(db.deliver_notification(user.id, &sync_completed(240)).await?.is_none());
+
+            let error = CreateNotification {
+                notification_type: "error".to_string(),
+                title: "Source Sync Failed".to_string(),
+                message: "Sync failed for Archive: connection refused".to_string(),
+                action_url: Some("/sources".to_string()),
+                metadata: None,
+            };
+            assert!(db.deliver_notification(user.id, &error).await?.is_some());
+            assert_eq!(db.count_user_notifications(user.id).await?, 1);
+
+            // Not due until the digest interval has passed
+            let due = db.get_due_pending_notifications().await?;
+            assert!(due.iter().all(|n| n.user_id != user.id));
+
+            sqlx::query("UPDATE pending_notifications SET created_at = NOW() - INTERVAL '10 minutes' WHERE user_id = $1")
+                .bind(user.id)
+                .execute(db.get_pool())
+                .await?;
+
+            NotificationDigestService::new(db.clone()).flush_due().await?;
+
+            let notifications = db.get_user_notifications(user.id, 10, 0).await?;
+            assert_eq!(notifications.len(), 2);
+            let digest = notifications
+                .iter()
+                .find(|n| n.notification_type == "success")
+                .expect("Digest should have been delivered");
+            assert_eq!(digest.title, "Source Sync Completed (2)");
+            assert_eq!(digest.message, "Sync completed: 1,240 documents added");
+
+            let remaining = db.get_due_pending_notifications().await?;
+            assert!(remaining.iter().all(|n| n.user_id != user.id));
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+//! Integration tests for the Office extraction statistics endpoints.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::ocr::fallback_stats::{fallback_stats, FallbackStatsResponse};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use std::time::Duration;
+    use tower::util::ServiceExt;
+
+    async fn request(ctx: &TestContext, method: &str, uri: &str, token: &str) -> (StatusCode, Vec<u8>) {
+        let request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_admins_read_and_reset_extraction_statistics() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let admin = auth_helper.create_admin_user().await;
+            let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+            fallback_stats().record("antiword", false, Duration::from_millis(5));
+            fallback_stats().record("catdoc", true, Duration::from_millis(25));
+
+            let (status, body) = request(&ctx, "GET", "/api/ocr/fallback-stats", &token).await;
+            assert_eq!(status, StatusCode::OK);
+            let stats: FallbackStatsResponse = serde_json::from_slice(&body)?;
+            let catdoc = stats.methods.iter().find(|m| m.method == "catdoc").expect("catdoc was recorded");
+            assert!(catdoc.successes >= 1);
+            assert!(stats.methods.iter().any(|m| m.method == "antiword" && m.failures >= 1));
+
+            let (status, body) = request(&ctx, "POST", "/api/ocr/fallback-stats/reset", &token).await;
+            assert_eq!(status, StatusCode::OK);
+            let stats: FallbackStatsResponse = serde_json::from_slice(&body)?;
+            assert!(stats.methods.is_empty());
+
+            // The health endpoint reports the same statistics
+            let (status, body) = request(&ctx, "GET", "/api/ocr/health", &token).await;
+            assert_eq!(status, StatusCode::OK);
+            let health: serde_json::Value = serde_json::from_slice(&body)?;
+            assert!(health["fallback_stats"]["methods"].is_array());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_extraction_statistics_require_admin() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let (status, _) = request(&ctx, "GET", "/api/ocr/fallback-stats", &token).await;
+            assert_eq!(status, StatusCode::FORBIDDEN);
+            let (status, _) = request(&ctx, "POST", "/api/ocr/fallback-stats/reset", &token).await;
+            assert_eq!(status, StatusCode::FORBIDDEN);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+//! Integration tests for streaming the live OCR log of a document.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::ocr::live_log;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestConfigBuilder, TestContext};
+    use std::time::Duration;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn log_request(document_id: Uuid, token: &str) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::builder()
+            .method("GET")
+            .uri(format!("/api/documents/{}/ocr/log", document_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ocr_log_is_hidden_unless_enabled() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let admin = auth_helper.create_admin_user().await;
+            let token = auth_helper.login_user(&admin.username, "adminpass123").await;
+            let document = ctx.state.db.create_document(create_test_document(admin.user_response.id)).await?;
+
+            let response = ctx.app.clone().oneshot(log_request(document.id, &token)).await?;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ocr_log_requires_admin_and_a_running_job() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_ocr_debug_endpoints(true)).await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_token = auth_helper.login_user(&user.username, "password123").await;
+            let admin = auth_helper.create_admin_user().await;
+            let admin_token = auth_helper.login_user(&admin.username, "adminpass123").await;
+            let document = ctx.state.db.create_document(create_test_document(user.user_response.id)).await?;
+
+            let response = ctx.app.clone().oneshot(log_request(document.id, &user_token)).await?;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+            let response = ctx.app.clone().oneshot(log_request(Uuid::new_v4(), &admin_token)).await?;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+            // Nothing is OCRing the document
+            let response = ctx.app.clone().oneshot(log_request(document.id, &admin_token)).await?;
+            assert_eq!(response.status(), StatusCode::CONFLICT);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ocr_log_streams_until_the_job_finishes() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_ocr_debug_endpoints(true)).await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let admin = auth_helper.create_admin_user().await;
+            let token = auth_helper.login_user(&admin.username, "adminpass123").await;
+            let document = ctx.state.db.create_document(create_test_document(admin.user_response.id)).await?;
+
+            // Stands in for the OCR worker, which runs its jobs the same way
+            let (start, started) = tokio::sync::oneshot::channel::<()>();
+            let job = tokio::spawn(live_log::capture(document.id, async move {
+                let _ = started.await;
+                live_log::log_step("Deskewing page 1");
+                let _ = live_log::output(
+                    tokio::process::Command::new("sh").arg("-c").arg("echo 'page 1: 3 words' >&2"),
+                    "ocrmypdf",
+                )
+                .await;
+            }));
+            while live_log::subscribe(document.id).is_none() {
+                tokio::task::yield_now().await;
+            }
+
+            let response = ctx.app.clone().oneshot(log_request(document.id, &token)).await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+            start.send(()).unwrap();
+            let body = tokio::time::timeout(
+                Duration::from_secs(10),
+                axum::body::to_bytes(response.into_body(), usize::MAX),
+            )
+            .await
+            .expect("the stream ends with the job")?;
+            job.await?;
+
+            let body = String::from_utf8(body.to_vec())?;
+            assert!(body.contains("event: step"), "unexpected stream: {}", body);
+            assert!(body.contains("Deskewing page 1"));
+            assert!(body.contains("event: stderr"));
+            assert!(body.contains("ocrmypdf: page 1: 3 words"));
+            assert!(body.contains("event: finished"));
+            assert!(live_log::subscribe(document.id).is_none());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+//! Integration tests for previewing OCR settings on a sample image.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::ocr::health::OcrHealthChecker;
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+
+    enum Part<'a> {
+        File(&'a [u8], &'a str),
+        Text(&'a str, &'a str),
+    }
+
+    /// Builds a multipart/form-data body from the given parts, returning (boundary, body)
+    fn multipart_body(parts: &[Part]) -> (String, Vec<u8>) {
+        let boundary = format!("----boundary{}", uuid::Uuid::new_v4());
+        let mut body = Vec::new();
+        for part in parts {
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            match part {
+                Part::File(content, filename) => {
+                    body.extend_from_slice(
+                        format!(
+                            "Content-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+                             Content-Type: application/octet-stream\r\n\r\n",
+                            filename
+                        )
+                        .as_bytes(),
+                    );
+                    body.extend_from_slice(content);
+                }
+                Part::Text(name, value) => {
+                    body.extend_from_slice(
+                        format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n{}", name, value).as_bytes(),
+                    );
+                }
+            }
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+        (boundary, body)
+    }
+
+    async fn preview(app: &axum::Router, token: &str, parts: &[Part<'_>]) -> (StatusCode, serde_json::Value) {
+        let (boundary, body) = multipart_body(parts);
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/ocr/preview")
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+            .body(axum::body::Body::from(body))
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    fn preview_dirs() -> Vec<String> {
+        std::fs::read_dir(std::env::temp_dir())
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| name.starts_with("ocr_preview_"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_preview_returns_text_without_storing_anything() {
+        if OcrHealthChecker::new().validate_language("eng").is_err() {
+            println!("Skipping test: tesseract with English data is not installed");
+            return;
+        }
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let image = std::fs::read("test_files/landscape_300x200.png")?;
+            let dirs_before = preview_dirs();
+
+            let (status, body) = preview(
+                &ctx.app,
+                &token,
+                &[
+                    Part::File(&image, "sample.png"),
+                    Part::Text("settings", r#"{"ocr_page_segmentation_mode": 6, "enable_image_preprocessing": true}"#),
+                ],
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK, "unexpected response: {}", body);
+            assert!(body["text"].is_string());
+            assert!(body["confidence"].is_number());
+            assert!(body["preprocessing_applied"].is_array());
+            assert!(body["processed_image_png_base64"].is_null(), "processed image should only be sent on request");
+
+            // Previews leave no documents or scratch files behind
+            let documents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE user_id = $1")
+                .bind(user.user_response.id)
+                .fetch_one(ctx.state().db.get_pool())
+                .await?;
+            assert_eq!(documents, 0);
+            let leftover: Vec<String> = preview_dirs().into_iter().filter(|dir| !dirs_before.contains(dir)).collect();
+            assert!(leftover.is_empty(), "scratch directories left behind: {:?}", leftover);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_preview_rejects_bad_input() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let (status, body) = preview(&ctx.app, &token, &[Part::Text("include_processed_image", "true")]).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error_code"], "OCR_PREVIEW_BAD_REQUEST");
+
+            let image = std::fs::read("test_files/small_50x50.png")?;
+            let (status, body) = preview(
+                &ctx.app,
+                &token,
+                &[Part::File(&image, "sample.png"), Part::Text("settings", "{not json")],
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error_code"], "OCR_PREVIEW_INVALID_SETTINGS");
+
+            if OcrHealthChecker::new().validate_language("eng").is_ok() {
+                let (status, body) = preview(&ctx.app, &token, &[Part::File(b"plain text, not an image", "notes.png")]).await;
+                assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "unexpected response: {}", body);
+            }
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_preview_is_rate_limited_per_user() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            // Requests count against the limit even when they fail validation
+            for _ in 0..10 {
+                let (status, _) = preview(&ctx.app, &token, &[]).await;
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+            }
+            let (status, body) = preview(&ctx.app, &token, &[]).await;
+            assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(body["error_code"], "OCR_PREVIEW_RATE_LIMITED");
+
+            // Other users keep their own allowance
+            let other = auth_helper.create_test_user().await;
+            let other_token = auth_helper.login_user(&other.username, "password123").await;
+            let (status, _) = preview(&ctx.app, &other_token, &[]).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,174 @@
+//! Integration tests for fair OCR scheduling: users or sources take turns in
+//! the queue instead of one burst holding every worker.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::models::{CreateSource, Document, SourceType};
+    use readur::ocr::queue::{OcrFairnessPolicy, OcrQueueService, OcrSchedulingPolicy};
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn pending_document(user_id: Uuid, name: &str, source_id: Option<Uuid>) -> Document {
+        let mut document = create_test_document(user_id);
+        document.filename = name.to_string();
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document.ocr_status = Some("pending".to_string());
+        document.source_id = source_id;
+        document
+    }
+
+    fn local_folder_source(name: &str) -> CreateSource {
+        CreateSource {
+            name: name.to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": [format!("/data/{}", name)],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false
+            }),
+        }
+    }
+
+    fn queue_service(ctx: &TestContext, fairness: OcrFairnessPolicy) -> OcrQueueService {
+        let state = ctx.state();
+        OcrQueueService::new(
+            state.db.clone(),
+            state.db.get_pool().clone(),
+            2,
+            state.file_service.clone(),
+            100,
+            100,
+            60,
+        )
+        .with_scheduling(OcrSchedulingPolicy { fairness, ..OcrSchedulingPolicy::default() })
+    }
+
+    /// Queues `count` documents for `user_id` (in `source_id`, if any) and
+    /// returns their ids
+    async fn queue_burst(ctx: &TestContext, user_id: Uuid, source_id: Option<Uuid>, count: usize) -> Result<Vec<Uuid>> {
+        let state = ctx.state();
+        let mut ids = Vec::new();
+        for i in 0..count {
+            let document = state
+                .db
+                .create_document(pending_document(user_id, &format!("burst-{}.pdf", i), source_id))
+                .await?;
+            state.queue_service.enqueue_document(document.id, 5, document.file_size).await?;
+            ids.push(document.id);
+        }
+        Ok(ids)
+    }
+
+    /// Claims `count` items, returning which of `groups` each belonged to
+    async fn claim_order(queue: &OcrQueueService, groups: &[&[Uuid]], count: usize) -> Result<Vec<usize>> {
+        let mut order = Vec::new();
+        for _ in 0..count {
+            let item = queue.dequeue().await?.expect("pending queue item");
+            let group = groups
+                .iter()
+                .position(|ids| ids.contains(&item.document_id))
+                .expect("claimed item belongs to a burst");
+            order.push(group);
+        }
+        Ok(order)
+    }
+
+    #[test]
+    fn test_scheduling_policy_parses() {
+        assert_eq!("fifo".parse::<OcrFairnessPolicy>().unwrap(), OcrFairnessPolicy::Fifo);
+        assert_eq!("".parse::<OcrFairnessPolicy>().unwrap(), OcrFairnessPolicy::Fifo);
+        assert_eq!(" Fair_By_User ".parse::<OcrFairnessPolicy>().unwrap(), OcrFairnessPolicy::FairByUser);
+        assert_eq!("fair_by_source".parse::<OcrFairnessPolicy>().unwrap(), OcrFairnessPolicy::FairBySource);
+        assert!("round_robin".parse::<OcrFairnessPolicy>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fair_by_user_interleaves_two_bursts() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let first_user = auth_helper.create_test_user().await;
+            let second_user = auth_helper.create_test_user().await;
+
+            // A large backfill queued before a smaller burst from another user
+            let first = queue_burst(&ctx, first_user.user_response.id, None, 6).await?;
+            let second = queue_burst(&ctx, second_user.user_response.id, None, 3).await?;
+
+            let queue = queue_service(&ctx, OcrFairnessPolicy::FairByUser);
+            let order = claim_order(&queue, &[&first, &second], 9).await?;
+            assert_eq!(order, vec![0, 1, 0, 1, 0, 1, 0, 0, 0]);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fifo_drains_the_first_burst_first() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let first_user = auth_helper.create_test_user().await;
+            let second_user = auth_helper.create_test_user().await;
+
+            let first = queue_burst(&ctx, first_user.user_response.id, None, 4).await?;
+            let second = queue_burst(&ctx, second_user.user_response.id, None, 2).await?;
+
+            let queue = queue_service(&ctx, OcrFairnessPolicy::Fifo);
+            let order = claim_order(&queue, &[&first, &second], 6).await?;
+            assert_eq!(order, vec![0, 0, 0, 0, 1, 1]);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fair_by_source_interleaves_sources_of_one_user() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let db = &ctx.state().db;
+            let archive = db.create_source(user_id, &local_folder_source("archive")).await?;
+            let inbox = db.create_source(user_id, &local_folder_source("inbox")).await?;
+
+            // Uploads without a source take turns with the two sources
+            let archive_burst = queue_burst(&ctx, user_id, Some(archive.id), 4).await?;
+            let inbox_burst = queue_burst(&ctx, user_id, Some(inbox.id), 2).await?;
+            let uploads = queue_burst(&ctx, user_id, None, 1).await?;
+
+            let queue = queue_service(&ctx, OcrFairnessPolicy::FairBySource);
+            let order = claim_order(&queue, &[&archive_burst, &inbox_burst, &uploads], 7).await?;
+            assert_eq!(order, vec![0, 1, 2, 0, 1, 0, 0]);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
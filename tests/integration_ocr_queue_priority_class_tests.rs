@@ -0,0 +1,122 @@
+//! Integration tests for OCR queue scheduling classes: workers must drain
+//! interactive items before a backfill burst, regardless of numeric priority.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chrono::Utc;
+    use readur::models::{CreateUser, Document, UserRole};
+    use readur::ocr::queue::OcrPriorityClass;
+    use readur::test_utils::TestContext;
+    use uuid::Uuid;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        CreateUser {
+            username: format!("queue_class_test_{}", suffix),
+            email: format!("queue_class_{}@example.com", suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_pending_document(user_id: Uuid, name: &str) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: name.to_string(),
+            original_filename: name.to_string(),
+            file_path: format!("/path/to/{}", name),
+            file_size: 1024,
+            mime_type: "application/pdf".to_string(),
+            content: None,
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("pending".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interactive_item_is_picked_before_backfill_burst() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user = state
+                .db
+                .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+                .await?;
+
+            // A backfill burst with the highest numeric priority, queued first
+            let mut backfill = Vec::new();
+            for i in 0..20 {
+                let document = state
+                    .db
+                    .create_document(create_pending_document(user.id, &format!("backfill-{}.pdf", i)))
+                    .await?;
+                backfill.push((document.id, 10, document.file_size));
+            }
+            state
+                .queue_service
+                .enqueue_documents_batch_with_class(backfill, OcrPriorityClass::Backfill)
+                .await?;
+
+            let normal = state
+                .db
+                .create_document(create_pending_document(user.id, "normal.pdf"))
+                .await?;
+            state.queue_service.enqueue_document(normal.id, 10, normal.file_size).await?;
+
+            // Then an interactive upload with a low numeric priority
+            let interactive = state
+                .db
+                .create_document(create_pending_document(user.id, "upload.pdf"))
+                .await?;
+            state
+                .queue_service
+                .enqueue_document_with_class(interactive.id, OcrPriorityClass::Interactive, 1, interactive.file_size)
+                .await?;
+
+            let next = state.queue_service.dequeue().await?.expect("pending queue item");
+            assert_eq!(next.document_id, interactive.id, "interactive item should be picked next");
+
+            let next = state.queue_service.dequeue().await?.expect("pending queue item");
+            assert_eq!(next.document_id, normal.id, "normal items go before backfill");
+
+            let stored_class: i16 = sqlx::query_scalar(
+                "SELECT priority_class FROM ocr_queue WHERE document_id = $1",
+            )
+            .bind(normal.id)
+            .fetch_one(state.db.get_pool())
+            .await?;
+            assert_eq!(OcrPriorityClass::from_rank(stored_class), OcrPriorityClass::Normal);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+//! Integration tests for size-aware OCR scheduling: smaller files first within
+//! a priority, and a large-file lane that keeps small items flowing.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chrono::Utc;
+    use readur::models::{CreateUser, Document, UserRole};
+    use readur::ocr::queue::{OcrQueueService, OcrSchedulingPolicy};
+    use readur::test_utils::TestContext;
+    use uuid::Uuid;
+
+    const MB: i64 = 1024 * 1024;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        CreateUser {
+            username: format!("queue_size_test_{}", suffix),
+            email: format!("queue_size_{}@example.com", suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_pending_document(user_id: Uuid, name: &str, file_size: i64) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: name.to_string(),
+            original_filename: name.to_string(),
+            file_path: format!("/path/to/{}", name),
+            file_size,
+            mime_type: "application/pdf".to_string(),
+            content: None,
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("pending".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    fn queue_service(ctx: &TestContext, scheduling: OcrSchedulingPolicy) -> OcrQueueService {
+        let state = ctx.state();
+        OcrQueueService::new(
+            state.db.clone(),
+            state.db.get_pool().clone(),
+            2,
+            state.file_service.clone(),
+            100,
+            100,
+            60,
+        )
+        .with_scheduling(scheduling)
+    }
+
+    #[test]
+    fn test_only_known_sizes_at_the_threshold_are_large() {
+        let policy = OcrSchedulingPolicy {
+            large_file_threshold_bytes: Some(50 * MB),
+            ..OcrSchedulingPolicy::default()
+        };
+        assert!(policy.is_large(Some(50 * MB)));
+        assert!(!policy.is_large(Some(50 * MB - 1)));
+        assert!(!policy.is_large(None));
+        assert!(!OcrSchedulingPolicy::default().is_large(Some(i64::MAX)));
+    }
+
+    #[tokio::test]
+    async fn test_smaller_files_first_within_a_priority() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user = state
+                .db
+                .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+                .await?;
+
+            // A 500-page scan queued before two small documents of the same priority
+            let mut queued = Vec::new();
+            for (name, size) in [("scan.pdf", 200 * MB), ("receipt.pdf", 200 * 1024), ("letter.pdf", 50 * 1024)] {
+                let document = state.db.create_document(create_pending_document(user.id, name, size)).await?;
+                state.queue_service.enqueue_document(document.id, 5, document.file_size).await?;
+                queued.push(document.id);
+            }
+
+            let queue = queue_service(&ctx, OcrSchedulingPolicy { smaller_files_first: true, ..OcrSchedulingPolicy::default() });
+            let order: Vec<Uuid> = vec![
+                queue.dequeue().await?.expect("pending queue item").document_id,
+                queue.dequeue().await?.expect("pending queue item").document_id,
+                queue.dequeue().await?.expect("pending queue item").document_id,
+            ];
+            assert_eq!(order, vec![queued[2], queued[1], queued[0]]);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_full_large_file_lane_does_not_starve_small_items() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user = state
+                .db
+                .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+                .await?;
+
+            // Two large scans at the front of the queue, then small documents
+            let mut large = Vec::new();
+            for i in 0..2 {
+                let document = state
+                    .db
+                    .create_document(create_pending_document(user.id, &format!("archive-{}.pdf", i), 300 * MB))
+                    .await?;
+                state.queue_service.enqueue_document(document.id, 5, document.file_size).await?;
+                large.push(document.id);
+            }
+            let mut small = Vec::new();
+            for i in 0..3 {
+                let document = state
+                    .db
+                    .create_document(create_pending_document(user.id, &format!("note-{}.pdf", i), 100 * 1024))
+                    .await?;
+                state.queue_service.enqueue_document(document.id, 5, document.file_size).await?;
+                small.push(document.id);
+            }
+
+            let policy = OcrSchedulingPolicy {
+                large_file_threshold_bytes: Some(100 * MB),
+                large_file_concurrency: 1,
+                ..OcrSchedulingPolicy::default()
+            };
+            let queue = queue_service(&ctx, policy);
+
+            // The oldest large scan takes the only large-file slot
+            let first = queue.dequeue().await?.expect("pending queue item");
+            assert_eq!(first.document_id, large[0]);
+            assert!(policy.is_large(first.file_size));
+
+            // While it runs, the small documents are claimed and the second scan waits
+            for expected in &small {
+                let next = queue.dequeue_excluding_large().await?.expect("small item should be claimable");
+                assert_eq!(&next.document_id, expected);
+            }
+            assert!(queue.dequeue_excluding_large().await?.is_none());
+
+            // Once the lane frees up, the second scan is next
+            let next = queue.dequeue().await?.expect("pending queue item");
+            assert_eq!(next.document_id, large[1]);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+//! Integration tests for detecting OCR settings changes and reprocessing the
+//! documents processed under older settings.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use chrono::Utc;
+    use readur::models::Document;
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn create_processed_document(user_id: Uuid, name: &str) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: format!("{}.png", name),
+            original_filename: format!("{}.png", name),
+            file_path: format!("/path/to/{}.png", name),
+            file_size: 2048,
+            mime_type: "image/png".to_string(),
+            content: None,
+            ocr_text: Some(format!("OCR text of {}", name)),
+            ocr_confidence: Some(90.0),
+            ocr_word_count: Some(4),
+            ocr_processing_time_ms: Some(500),
+            ocr_status: Some("completed".to_string()),
+            ocr_error: None,
+            ocr_completed_at: Some(Utc::now()),
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    async fn send_json(
+        app: &axum::Router,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(axum::body::Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_ocr_settings_change_reports_affected_documents() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let first = db.create_document(create_processed_document(user_id, "first")).await?;
+            db.create_document(create_processed_document(user_id, "second")).await?;
+            let corrected = db.create_document(create_processed_document(user_id, "corrected")).await?;
+            sqlx::query("UPDATE documents SET ocr_manually_corrected = TRUE WHERE id = $1")
+                .bind(corrected.id)
+                .execute(db.get_pool())
+                .await?;
+
+            // A change that doesn't affect OCR output reports nothing
+            let (status, body) = send_json(&ctx.app, "PUT", "/api/settings", &token,
+                serde_json::json!({ "search_results_per_page": 50 })).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(body.get("ocr_reprocess").is_none());
+
+            let (status, body) = send_json(&ctx.app, "PUT", "/api/settings", &token,
+                serde_json::json!({ "ocr_language": "spa", "ocr_dpi": 400 })).await;
+            assert_eq!(status, StatusCode::OK);
+            let summary = &body["ocr_reprocess"];
+            assert_eq!(summary["changed_settings"], serde_json::json!(["ocr_language", "ocr_dpi"]));
+            assert_eq!(summary["affected_documents"], 2, "manually corrected documents are excluded");
+            assert_eq!(summary["queued_documents"], 0, "reprocessing is opt-in");
+
+            // Documents processed under the new version no longer count
+            let version = summary["settings_version"].as_i64().unwrap() as i32;
+            assert_eq!(db.get_ocr_settings_version(user_id).await?, version);
+            db.set_ocr_settings_version(first.id, version).await?;
+            assert_eq!(db.count_documents_for_ocr_reprocess(user_id, version).await?, 1);
+
+            // One-click reprocess queues the rest, after which they're no longer counted
+            let (status, body) = send_json(&ctx.app, "POST", "/api/settings/ocr/reprocess", &token,
+                serde_json::json!({})).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["queued_documents"], 1);
+            assert_eq!(db.count_documents_for_ocr_reprocess(user_id, version).await?, 0);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ocr_settings_change_queues_when_enabled() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let document = db.create_document(create_processed_document(user_id, "queued")).await?;
+
+            let (status, _) = send_json(&ctx.app, "PUT", "/api/settings", &token,
+                serde_json::json!({ "ocr_reprocess_on_settings_change": true })).await;
+            assert_eq!(status, StatusCode::OK);
+
+            let (status, body) = send_json(&ctx.app, "PUT", "/api/settings", &token,
+                serde_json::json!({ "enable_image_preprocessing": true })).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["ocr_reprocess"]["queued_documents"], 1);
+
+            let queued: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM ocr_queue WHERE document_id = $1 AND status = 'pending'",
+            )
+            .bind(document.id)
+            .fetch_one(db.get_pool())
+            .await?;
+            assert_eq!(queued, 1);
+
+            // Completed documents go back to pending so the worker stores the new results
+            let status: Option<String> = sqlx::query_scalar("SELECT ocr_status FROM documents WHERE id = $1")
+                .bind(document.id)
+                .fetch_one(db.get_pool())
+                .await?;
+            assert_eq!(status.as_deref(), Some("pending"));
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+//! Integration tests for manual OCR text correction.
+//!
+//! These tests verify that corrected text replaces the OCR text in search, that the
+//! original OCR output is preserved, and that corrections are protected from
+//! automatic reprocessing.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::test_utils::TestContext;
+    use readur::models::{CreateUser, Document, SearchRequest, UserRole};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        let test_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+            .to_string();
+        let unique_suffix = &test_id[test_id.len().saturating_sub(8)..];
+
+        CreateUser {
+            username: format!("testuser_{}_{}", suffix, unique_suffix),
+            email: format!("test_{}_{}@example.com", suffix, unique_suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_ocr_document(user_id: Uuid, ocr_text: &str, ocr_status: &str) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: "scan.png".to_string(),
+            original_filename: "scan.png".to_string(),
+            file_path: "/path/to/scan.png".to_string(),
+            file_size: 1024,
+            mime_type: "image/png".to_string(),
+            content: None,
+            ocr_text: Some(ocr_text.to_string()),
+            ocr_confidence: Some(72.0),
+            ocr_word_count: Some(ocr_text.split_whitespace().count() as i32),
+            ocr_processing_time_ms: Some(800),
+            ocr_status: Some(ocr_status.to_string()),
+            ocr_error: None,
+            ocr_completed_at: Some(Utc::now()),
+            tags: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    fn search_request(query: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            tags: None,
+            mime_types: None,
+            limit: Some(10),
+            offset: Some(0),
+            include_snippets: Some(true),
+            snippet_length: None,
+            search_mode: None,
+            include_shared: None,
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_reflects_corrected_text() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("correct1")).await?;
+            let document = db
+                .create_document(create_ocr_document(user.id, "Invoice from Acrne Corporatlon", "completed"))
+                .await?;
+
+            assert_eq!(db.search_documents(user.id, &search_request("Corporatlon")).await?.len(), 1);
+            assert!(db.search_documents(user.id, &search_request("Corporation")).await?.is_empty());
+
+            let correction = db
+                .correct_document_ocr_text(document.id, "Invoice from Acme Corporation")
+                .await?
+                .expect("Completed document should accept a correction");
+            assert!(correction.manually_corrected);
+            assert_eq!(correction.original_ocr_text.as_deref(), Some("Invoice from Acrne Corporatlon"));
+
+            let results = db.search_documents(user.id, &search_request("Corporation")).await?;
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].id, document.id);
+            assert!(db.search_documents(user.id, &search_request("Corporatlon")).await?.is_empty());
+
+            let enhanced = db.enhanced_search_documents(user.id, &search_request("Acme")).await?;
+            assert_eq!(enhanced.len(), 1);
+            assert!(enhanced[0].snippets.iter().any(|s| s.text.contains("Acme")));
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_original_ocr_text_survives_repeated_corrections() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("correct2")).await?;
+            let document = db
+                .create_document(create_ocr_document(user.id, "Tota1 due 1O0", "completed"))
+                .await?;
+
+            db.correct_document_ocr_text(document.id, "Total due 100").await?;
+            let correction = db
+                .correct_document_ocr_text(document.id, "Total due: 100 EUR")
+                .await?
+                .expect("Corrected document should accept another correction");
+            assert_eq!(correction.original_ocr_text.as_deref(), Some("Tota1 due 1O0"));
+
+            let stored = db.get_document_by_id(document.id, user.id, UserRole::User).await?.unwrap();
+            assert_eq!(stored.ocr_text.as_deref(), Some("Total due: 100 EUR"));
+            assert_eq!(stored.ocr_word_count, Some(4));
+
+            assert_eq!(db.get_manually_corrected_document_ids(&[document.id]).await?, vec![document.id]);
+
+            // A forced reprocess drops the correction state
+            db.clear_ocr_text_correction(document.id).await?;
+            assert!(db.get_manually_corrected_document_ids(&[document.id]).await?.is_empty());
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_correction_rejected_while_ocr_in_progress() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("correct3")).await?;
+            let document = db
+                .create_document(create_ocr_document(user.id, "partial", "processing"))
+                .await?;
+
+            assert!(db.correct_document_ocr_text(document.id, "corrected").await?.is_none());
+
+            let correction = db.get_ocr_text_correction(document.id).await?.unwrap();
+            assert!(!correction.manually_corrected);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
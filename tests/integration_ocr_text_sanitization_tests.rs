@@ -0,0 +1,121 @@
+//! Integration tests for sanitizing OCR text in the queue worker before it is stored.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use chrono::Utc;
+    use readur::models::{CreateUser, Document, UserRole};
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::test_utils::TestContext;
+    use uuid::Uuid;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        CreateUser {
+            username: format!("sanitize_test_{}", suffix),
+            email: format!("sanitize_{}@example.com", suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_pending_text_document(user_id: Uuid, file_path: &str, file_size: i64) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: "broken.txt".to_string(),
+            original_filename: "broken.txt".to_string(),
+            file_path: file_path.to_string(),
+            file_size,
+            mime_type: "text/plain".to_string(),
+            content: None,
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("pending".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ocr_text_with_nul_and_invalid_utf8_is_stored_sanitized() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let user = state
+                .db
+                .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+                .await?;
+
+            // NUL bytes, a bell character, an invalid UTF-8 byte and a decomposed accent
+            let mut content = b"Invoice\0 number 42\0 from Cafe\xCC\x81 Central\x07 total ".to_vec();
+            content.extend_from_slice(&[0xFF, b' ']);
+            content.extend_from_slice(b"paid in full");
+
+            let temp_dir = tempfile::tempdir()?;
+            let file_path = temp_dir.path().join("broken.txt");
+            std::fs::write(&file_path, &content)?;
+
+            let document = state
+                .db
+                .create_document(create_pending_text_document(
+                    user.id,
+                    file_path.to_str().unwrap(),
+                    content.len() as i64,
+                ))
+                .await?;
+
+            state.queue_service.enqueue_document(document.id, 5, content.len() as i64).await?;
+            let item = state.queue_service.dequeue().await?.expect("queued OCR job");
+
+            let ocr_service = EnhancedOcrService::new(
+                temp_dir.path().to_string_lossy().to_string(),
+                (*state.file_service).clone(),
+                100,
+                100,
+                300,
+            );
+            state.queue_service.process_item(item, &ocr_service).await?;
+
+            let stored = state
+                .db
+                .get_document_by_id(document.id, user.id, UserRole::User)
+                .await?
+                .expect("document exists");
+            assert_eq!(stored.ocr_status.as_deref(), Some("completed"), "error: {:?}", stored.ocr_error);
+
+            let text = stored.ocr_text.expect("OCR text stored");
+            assert!(!text.contains('\0'));
+            assert!(!text.contains('\u{07}'));
+            assert!(text.contains("Invoice number 42"));
+            assert!(text.contains("Caf\u{e9} Central"), "text should be NFC normalized: {:?}", text);
+            assert!(text.contains('\u{FFFD}'), "invalid UTF-8 should be replaced: {:?}", text);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
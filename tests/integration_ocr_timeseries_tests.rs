@@ -0,0 +1,172 @@
+//! Integration tests for OCR processing statistics over time.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use readur::ocr::queue::OcrStatsGranularity;
+    use readur::routes::metrics::OcrTimeseriesResponse;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    async fn get_timeseries(ctx: &TestContext, token: &str, query: &str) -> (StatusCode, Vec<u8>) {
+        let request = axum::http::Request::builder()
+            .method("GET")
+            .uri(format!("/api/metrics/ocr/timeseries?{}", query))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    /// Queues a document and records its job as finished with `status` at
+    /// `completed_at`
+    async fn finished_job(
+        ctx: &TestContext,
+        user_id: Uuid,
+        status: &str,
+        completed_at: DateTime<Utc>,
+        processing_time_ms: i32,
+        pages: i32,
+    ) -> Result<()> {
+        let state = ctx.state();
+        let mut document = create_test_document(user_id);
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        let document = state.db.create_document(document).await?;
+        let item_id = state.queue_service.enqueue_document(document.id, 5, document.file_size).await?;
+
+        sqlx::query(
+            "UPDATE ocr_queue SET status = $2, completed_at = $3, processing_time_ms = $4, progress_total = $5 WHERE id = $1",
+        )
+        .bind(item_id)
+        .bind(status)
+        .bind(completed_at)
+        .bind(processing_time_ms)
+        .bind(pages)
+        .execute(state.db.get_pool())
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timeseries_buckets_finished_jobs_by_hour() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let admin = auth_helper.create_admin_user().await;
+            let token = auth_helper.login_user(&admin.username, &admin.password).await;
+            let user_id = admin.user_response.id;
+
+            let from = Utc.with_ymd_and_hms(2026, 10, 15, 8, 0, 0).unwrap();
+            finished_job(&ctx, user_id, "completed", from + Duration::minutes(5), 1000, 3).await?;
+            finished_job(&ctx, user_id, "completed", from + Duration::minutes(50), 3000, 0).await?;
+            finished_job(&ctx, user_id, "failed", from + Duration::minutes(55), 0, 0).await?;
+            finished_job(&ctx, user_id, "completed", from + Duration::hours(2) + Duration::minutes(1), 2000, 10).await?;
+            // Outside the window
+            finished_job(&ctx, user_id, "completed", from + Duration::hours(3), 9000, 1).await?;
+
+            let (status, body) = get_timeseries(
+                &ctx,
+                &token,
+                "granularity=hour&from=2026-10-15T08:00:00Z&to=2026-10-15T11:00:00Z",
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            let response: OcrTimeseriesResponse = serde_json::from_slice(&body)?;
+            assert_eq!(response.granularity, OcrStatsGranularity::Hour);
+
+            let starts: Vec<DateTime<Utc>> = response.buckets.iter().map(|bucket| bucket.start).collect();
+            assert_eq!(starts, vec![from, from + Duration::hours(1), from + Duration::hours(2)]);
+
+            let first = &response.buckets[0];
+            assert_eq!(first.documents_completed, 2);
+            // A document without a page count counts as one page
+            assert_eq!(first.pages_completed, 4);
+            assert_eq!(first.documents_failed, 1);
+            assert!((first.failure_rate - 1.0 / 3.0).abs() < 1e-9);
+            assert_eq!(first.avg_processing_time_ms, Some(2000.0));
+            assert!(first.p95_processing_time_ms.unwrap() > 2800.0);
+
+            let empty = &response.buckets[1];
+            assert_eq!((empty.documents_completed, empty.documents_failed), (0, 0));
+            assert_eq!(empty.failure_rate, 0.0);
+            assert!(empty.avg_processing_time_ms.is_none());
+
+            assert_eq!(response.buckets[2].documents_completed, 1);
+            assert_eq!(response.buckets[2].pages_completed, 10);
+
+            let (status, body) = get_timeseries(
+                &ctx,
+                &token,
+                "granularity=day&from=2026-10-14T00:00:00Z&to=2026-10-16T00:00:00Z",
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            let response: OcrTimeseriesResponse = serde_json::from_slice(&body)?;
+            assert_eq!(response.buckets.len(), 2);
+            assert_eq!(response.buckets[0].documents_completed, 0);
+            assert_eq!(response.buckets[1].documents_completed, 4);
+            assert_eq!(response.buckets[1].documents_failed, 1);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_timeseries_requires_admin_and_a_bounded_window() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_token = auth_helper.login_user(&user.username, "password123").await;
+            let admin = auth_helper.create_admin_user().await;
+            let admin_token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+            let (status, _) = get_timeseries(&ctx, &user_token, "granularity=day").await;
+            assert_eq!(status, StatusCode::FORBIDDEN);
+
+            // Defaults to the last 24 hours
+            let (status, body) = get_timeseries(&ctx, &admin_token, "").await;
+            assert_eq!(status, StatusCode::OK);
+            let response: OcrTimeseriesResponse = serde_json::from_slice(&body)?;
+            assert!((24..=25).contains(&response.buckets.len()));
+
+            let (status, _) = get_timeseries(
+                &ctx,
+                &admin_token,
+                "from=2026-10-16T00:00:00Z&to=2026-10-15T00:00:00Z",
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            let (status, _) = get_timeseries(
+                &ctx,
+                &admin_token,
+                "granularity=hour&from=2020-01-01T00:00:00Z&to=2026-01-01T00:00:00Z",
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
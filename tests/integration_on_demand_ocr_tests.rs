@@ -0,0 +1,94 @@
+//! Integration tests for queueing OCR of a document on demand.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use serde_json::{json, Value};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    async fn request_ocr(app: &axum::Router, document_id: Uuid, token: &str, body: Value) -> (StatusCode, Value) {
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri(format!("/api/documents/{}/ocr", document_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_document_without_ocr_is_queued_once() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let mut document = create_test_document(user.user_response.id);
+            document.ocr_text = None;
+            document.ocr_status = Some("skipped".to_string());
+            document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+            let document = db.create_document(document).await?;
+
+            let (status, _) = request_ocr(&ctx.app, document.id, &token, json!({ "dpi": 20 })).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            let overrides = json!({ "page_segmentation_mode": 6, "dpi": 300 });
+            let (status, body) = request_ocr(&ctx.app, document.id, &token, overrides.clone()).await;
+            assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+            assert_eq!(body["document_id"], document.id.to_string());
+            let queue_item_id: Uuid = body["queue_item_id"].as_str().unwrap().parse()?;
+
+            let (document_id, queue_status, priority_class, stored_overrides): (Uuid, String, i16, Value) =
+                sqlx::query_as("SELECT document_id, status, priority_class, ocr_overrides FROM ocr_queue WHERE id = $1")
+                    .bind(queue_item_id)
+                    .fetch_one(db.get_pool())
+                    .await?;
+            assert_eq!(document_id, document.id);
+            assert_eq!(queue_status, "pending");
+            assert_eq!(priority_class, 2, "on-demand jobs are interactive");
+            assert_eq!(stored_overrides, overrides);
+
+            let ocr_status: Option<String> = sqlx::query_scalar("SELECT ocr_status FROM documents WHERE id = $1")
+                .bind(document.id)
+                .fetch_one(db.get_pool())
+                .await?;
+            assert_eq!(ocr_status.as_deref(), Some("pending"));
+
+            // A second request while the job is pending is refused
+            let (status, body) = request_ocr(&ctx.app, document.id, &token, json!({})).await;
+            assert_eq!(status, StatusCode::CONFLICT);
+            assert!(body["error"].as_str().unwrap().contains(&queue_item_id.to_string()));
+            let jobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ocr_queue WHERE document_id = $1")
+                .bind(document.id)
+                .fetch_one(db.get_pool())
+                .await?;
+            assert_eq!(jobs, 1);
+
+            // Other users can't queue the document
+            let other = auth_helper.create_test_user().await;
+            let other_token = auth_helper.login_user(&other.username, "password123").await;
+            let (status, _) = request_ocr(&ctx.app, document.id, &other_token, json!({})).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
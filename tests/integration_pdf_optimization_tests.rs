@@ -0,0 +1,194 @@
+//! Integration tests for optimizing bloated PDFs on ingest.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::ingestion::document_ingestion::{
+        DeduplicationPolicy, DocumentIngestionRequest, DocumentIngestionService, IngestionResult,
+    };
+    use readur::models::{Document, UpdateSettings};
+    use readur::services::pdf_optimizer::{PdfOptimizer, PdfOptimizerTool};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use sha2::{Digest, Sha256};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn ghostscript_available() -> bool {
+        std::process::Command::new("gs")
+            .arg("--version")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// A one-page PDF holding an uncompressed 1000x1000 grayscale scan, the way
+    /// some scanners write them
+    fn bloated_pdf() -> Vec<u8> {
+        let (width, height) = (1000usize, 1000usize);
+        let pixels: Vec<u8> = (0..width * height).map(|i| ((i % width + i / width) % 256) as u8).collect();
+
+        let mut image = format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 8 /Length {} >>\nstream\n",
+            width,
+            height,
+            pixels.len()
+        )
+        .into_bytes();
+        image.extend_from_slice(&pixels);
+        image.extend_from_slice(b"\nendstream");
+
+        let content = b"q 612 0 0 792 0 0 cm /Im1 Do Q";
+        let mut contents = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        contents.extend_from_slice(content);
+        contents.extend_from_slice(b"\nendstream");
+
+        let objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << /XObject << /Im1 5 0 R >> >> /Contents 4 0 R >>".to_vec(),
+            contents,
+            image,
+        ];
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+        let xref_offset = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref_offset).as_bytes(),
+        );
+        pdf
+    }
+
+    async fn ingest_pdf(ctx: &TestContext, user_id: Uuid, data: Vec<u8>) -> Result<Document> {
+        let state = ctx.state();
+        let optimizer = PdfOptimizer::new(PdfOptimizerTool::Ghostscript, 64 * 1024, Duration::from_secs(120));
+        let file_service = (*state.file_service).clone().with_pdf_optimizer(Some(optimizer));
+        let ingestion = DocumentIngestionService::new(state.db.clone(), file_service);
+
+        let result = ingestion
+            .ingest_document(DocumentIngestionRequest {
+                filename: "scan.pdf".to_string(),
+                original_filename: "scan.pdf".to_string(),
+                file_data: data,
+                mime_type: "application/pdf".to_string(),
+                user_id,
+                deduplication_policy: DeduplicationPolicy::Skip,
+                source_type: Some("web_upload".to_string()),
+                source_id: None,
+                original_created_at: None,
+                original_modified_at: None,
+                source_path: None,
+                file_permissions: None,
+                file_owner: None,
+                file_group: None,
+                source_metadata: None,
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        match result {
+            IngestionResult::Created(document) => Ok(document),
+            other => panic!("PDF was not stored: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bloated_pdf_is_stored_optimized_with_the_original_kept() {
+        if !ghostscript_available() {
+            eprintln!("Skipping PDF optimization test: Ghostscript (gs) is not installed");
+            return;
+        }
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+
+            let fixture = bloated_pdf();
+            let document = ingest_pdf(&ctx, user.user_response.id, fixture.clone()).await?;
+
+            let stored = state.file_service.read_file(&document.file_path).await?;
+            assert!(stored.starts_with(b"%PDF"));
+            assert_eq!(document.file_size, stored.len() as i64);
+            let reduction = 1.0 - stored.len() as f64 / fixture.len() as f64;
+            println!(
+                "Optimized bloated fixture from {} to {} bytes ({:.1}% smaller)",
+                fixture.len(),
+                stored.len(),
+                reduction * 100.0
+            );
+            assert!(reduction >= 0.5, "expected at least 50% reduction, got {:.1}%", reduction * 100.0);
+
+            let (original_size, original_path) = state
+                .db
+                .get_pdf_optimization(document.id)
+                .await?
+                .expect("optimization should be recorded");
+            assert_eq!(original_size, fixture.len() as i64);
+            let original = state.file_service.read_file(&original_path.expect("original should be kept")).await?;
+            assert_eq!(original, fixture);
+
+            // The hash is the original's, so the same scan is still recognized as a duplicate
+            let hash = format!("{:x}", Sha256::digest(&fixture));
+            let existing = state.db.get_document_by_user_and_hash(user.user_response.id, &hash).await?;
+            assert_eq!(existing.map(|doc| doc.id), Some(document.id));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_original_is_discarded_when_the_user_does_not_keep_it() {
+        if !ghostscript_available() {
+            eprintln!("Skipping PDF optimization test: Ghostscript (gs) is not installed");
+            return;
+        }
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+
+            let settings = UpdateSettings {
+                keep_original_pdf: Some(false),
+                ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+            };
+            state.db.create_or_update_settings(user_id, &settings).await?;
+
+            let fixture = bloated_pdf();
+            let document = ingest_pdf(&ctx, user_id, fixture.clone()).await?;
+            assert!(document.file_size < fixture.len() as i64);
+
+            let (original_size, original_path) = state.db.get_pdf_optimization(document.id).await?.unwrap();
+            assert_eq!(original_size, fixture.len() as i64);
+            assert!(original_path.is_none());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,217 @@
+//! Integration tests for the related documents lookup.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::db::documents::any_lexeme_tsquery;
+    use readur::test_utils::TestContext;
+    use readur::models::{CreateUser, Document, UserRole};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        let test_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+            .to_string();
+        let unique_suffix = &test_id[test_id.len().saturating_sub(8)..];
+
+        CreateUser {
+            username: format!("testuser_{}_{}", suffix, unique_suffix),
+            email: format!("test_{}_{}@example.com", suffix, unique_suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn create_document(user_id: Uuid, filename: &str, content: &str) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: filename.to_string(),
+            original_filename: filename.to_string(),
+            file_path: format!("/path/to/{}", filename),
+            file_size: 1024,
+            mime_type: "text/plain".to_string(),
+            content: Some(content.to_string()),
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("completed".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: None,
+            source_id: None,
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_related_documents_ranked_by_shared_terms() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("related1")).await?;
+
+            let source = db.create_document(create_document(
+                user.id,
+                "acme_january.txt",
+                "Acme invoice for consulting services. Invoice total due within thirty days. Acme consulting.",
+            )).await?;
+            let similar = db.create_document(create_document(
+                user.id,
+                "statement.txt",
+                "Acme consulting invoice. Consulting services invoice total due.",
+            )).await?;
+            let loosely = db.create_document(create_document(
+                user.id,
+                "notes.txt",
+                "Meeting notes mentioning consulting once.",
+            )).await?;
+            db.create_document(create_document(
+                user.id,
+                "recipe.txt",
+                "Bake the bread for forty minutes.",
+            )).await?;
+
+            let related = db.find_related_documents(source.id, 20, 0.3, 10).await?;
+            let ids: Vec<Uuid> = related.iter().map(|doc| doc.id).collect();
+
+            assert!(!ids.contains(&source.id), "the document itself must be excluded");
+            assert_eq!(ids.first(), Some(&similar.id));
+            assert!(ids.contains(&loosely.id));
+            assert_eq!(ids.len(), 2, "unrelated documents should not be returned");
+            assert!(related[0].score >= related[1].score);
+
+            let limited = db.find_related_documents(source.id, 20, 0.3, 1).await?;
+            assert_eq!(limited.len(), 1);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_related_documents_match_similar_filenames() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("related2")).await?;
+
+            let source = db.create_document(create_document(user.id, "bank_statement_2024_01.pdf", "")).await?;
+            let sibling = db.create_document(create_document(user.id, "bank_statement_2024_02.pdf", "")).await?;
+            db.create_document(create_document(user.id, "holiday_photo.jpg", "")).await?;
+
+            let related = db.find_related_documents(source.id, 20, 0.3, 10).await?;
+
+            assert_eq!(related.len(), 1);
+            assert_eq!(related[0].id, sibling.id);
+            assert_eq!(related[0].text_score, 0.0);
+            assert!(related[0].filename_similarity >= 0.3);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_related_documents_stay_within_owner_library() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let owner = db.create_user(create_test_user_data("related3")).await?;
+            let other = db.create_user(create_test_user_data("related4")).await?;
+
+            let source = db.create_document(create_document(
+                owner.id,
+                "contract.txt",
+                "Lease agreement for warehouse premises",
+            )).await?;
+            db.create_document(create_document(
+                other.id,
+                "contract.txt",
+                "Lease agreement for warehouse premises",
+            )).await?;
+
+            assert!(db.find_related_documents(source.id, 20, 0.3, 10).await?.is_empty());
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lexemes_with_backslashes_and_quotes_match_as_they_are() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+
+            for lexeme in [r"c:\finance\acme", "o'brien", r"back\'slash", "plain"] {
+                let matches: bool = sqlx::query_scalar("SELECT array_to_tsvector($1::text[]) @@ $2::tsquery")
+                    .bind(vec![lexeme.to_string()])
+                    .bind(any_lexeme_tsquery(&[lexeme.to_string()]))
+                    .fetch_one(db.get_pool())
+                    .await?;
+                assert!(matches, "{} must match itself", lexeme);
+            }
+
+            let user = db.create_user(create_test_user_data("related5")).await?;
+            let source = db.create_document(create_document(
+                user.id,
+                "transfer_log.txt",
+                r"Invoices exported to C:\Finance\Acme\2024 and \\fileserver\scans by O'Brien. Invoices reconciled.",
+            )).await?;
+            let similar = db.create_document(create_document(
+                user.id,
+                "reconciliation.txt",
+                r"O'Brien reconciled the exported invoices from C:\Finance\Acme\2024.",
+            )).await?;
+
+            let related = db.find_related_documents(source.id, 20, 0.3, 10).await?;
+            assert_eq!(related.len(), 1);
+            assert_eq!(related[0].id, similar.id);
+            assert!(related[0].text_score > 0.0);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
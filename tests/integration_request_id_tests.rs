@@ -0,0 +1,64 @@
+//! Integration tests for correlating work with the HTTP request that started it.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::logging::REQUEST_ID_HEADER;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_ocr_job_records_the_request_that_queued_it() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let mut document = create_test_document(user.user_response.id);
+            document.ocr_text = None;
+            document.ocr_status = Some("skipped".to_string());
+            document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+            let document = db.create_document(document).await?;
+
+            let request = axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/api/documents/{}/ocr", document.id))
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .header(REQUEST_ID_HEADER, "edge-req-1234")
+                .body(axum::body::Body::from("{}"))
+                .unwrap();
+            let response = ctx.app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+            assert_eq!(response.headers()[REQUEST_ID_HEADER], "edge-req-1234");
+
+            let request_id: Option<String> = sqlx::query_scalar("SELECT request_id FROM ocr_queue WHERE document_id = $1")
+                .bind(document.id)
+                .fetch_one(db.get_pool())
+                .await?;
+            assert_eq!(request_id.as_deref(), Some("edge-req-1234"));
+
+            // Jobs queued outside of a request have no id
+            let other = db.create_document(create_test_document(user.user_response.id)).await?;
+            ctx.state.queue_service.enqueue_document(other.id, 5, 1024).await?;
+            let request_id: Option<String> = sqlx::query_scalar("SELECT request_id FROM ocr_queue WHERE document_id = $1")
+                .bind(other.id)
+                .fetch_one(db.get_pool())
+                .await?;
+            assert_eq!(request_id, None);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+//! Integration tests for compression of API responses.
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use anyhow::Result;
+    use axum::http::{header, StatusCode};
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    async fn list_documents(ctx: &TestContext, token: &str, accept_encoding: Option<&str>) -> axum::response::Response {
+        let mut request = axum::http::Request::builder()
+            .uri("/api/documents")
+            .header("Authorization", format!("Bearer {}", token));
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+        ctx.app
+            .clone()
+            .oneshot(request.body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_large_document_list_is_compressed_when_requested() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            for i in 0..20 {
+                let mut document = create_test_document(user.user_response.id);
+                document.filename = format!("compressed-{}.pdf", i);
+                document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+                db.create_document(document).await?;
+            }
+
+            let plain = list_documents(&ctx, &token, None).await;
+            assert_eq!(plain.status(), StatusCode::OK);
+            assert!(plain.headers().get(header::CONTENT_ENCODING).is_none());
+            let plain_body = axum::body::to_bytes(plain.into_body(), usize::MAX).await?;
+            assert!(plain_body.len() > 1024);
+
+            let compressed = list_documents(&ctx, &token, Some("gzip")).await;
+            assert_eq!(compressed.status(), StatusCode::OK);
+            assert_eq!(compressed.headers()[header::CONTENT_ENCODING], "gzip");
+            let compressed_body = axum::body::to_bytes(compressed.into_body(), usize::MAX).await?;
+            assert!(compressed_body.len() < plain_body.len());
+
+            let mut decoded = Vec::new();
+            flate2::read::GzDecoder::new(compressed_body.as_ref()).read_to_end(&mut decoded)?;
+            let decoded: serde_json::Value = serde_json::from_slice(&decoded)?;
+            let plain: serde_json::Value = serde_json::from_slice(&plain_body)?;
+            assert_eq!(decoded, plain);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
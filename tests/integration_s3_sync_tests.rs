@@ -34,6 +34,17 @@ fn create_test_aws_s3_config() -> S3SourceConfig {
         auto_sync: true,
         sync_interval_minutes: 120,
         file_extensions: vec![".pdf".to_string(), ".txt".to_string(), ".docx".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     }
 }
 
@@ -51,6 +62,17 @@ fn create_test_minio_config() -> S3SourceConfig {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string(), ".jpg".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     }
 }
 
@@ -371,6 +393,16 @@ fn test_s3_error_handling_scenarios() {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
 
     assert!(invalid_bucket_config.bucket_name.contains('_'));
@@ -389,6 +421,16 @@ fn test_s3_error_handling_scenarios() {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
 
     assert!(empty_creds_config.access_key_id.is_empty());
@@ -407,6 +449,16 @@ fn test_s3_error_handling_scenarios() {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
     
     assert!(!is_valid_aws_region(&invalid_region_config.region));
@@ -0,0 +1,63 @@
+//! Integration tests for the startup schema verification.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::db::schema_check::SchemaCheckError;
+    use readur::test_utils::TestContext;
+
+    #[tokio::test]
+    async fn test_migrated_schema_passes_verification() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+
+            let issues = db.find_schema_issues().await?;
+            assert!(issues.is_empty(), "unexpected schema issues: {:?}", issues);
+            db.verify_schema(false).await?;
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_missing_column_is_rejected_then_auto_repaired() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+
+            sqlx::query("ALTER TABLE documents DROP COLUMN ocr_hybrid_extraction")
+                .execute(db.get_pool())
+                .await?;
+
+            match db.verify_schema(false).await {
+                Err(SchemaCheckError::Mismatch(issues)) => {
+                    assert_eq!(issues.len(), 1);
+                    assert_eq!(issues[0].to_string(), "missing column documents.ocr_hybrid_extraction");
+                }
+                other => panic!("expected a schema mismatch, got {:?}", other),
+            }
+
+            db.verify_schema(true).await?;
+            assert!(db.find_schema_issues().await?.is_empty());
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
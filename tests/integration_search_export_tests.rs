@@ -0,0 +1,116 @@
+//! Integration tests for exporting search results as CSV.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::Document;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn invoice(user_id: Uuid, filename: &str, confidence: f32) -> Document {
+        let mut document = create_test_document(user_id);
+        document.filename = filename.to_string();
+        document.original_filename = filename.to_string();
+        document.content = Some(format!("Invoice number {} for consulting services", filename.len()));
+        document.ocr_text = None;
+        document.ocr_status = Some("completed".to_string());
+        document.ocr_confidence = Some(confidence);
+        document.source_type = Some("web_upload".to_string());
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    async fn export(app: &axum::Router, uri: &str, token: &str) -> (StatusCode, Option<String>, String) {
+        let request = axum::http::Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_search_results_export_as_csv() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let plain = db.create_document(invoice(user_id, "march.pdf", 94.3)).await?;
+            let awkward = db.create_document(invoice(user_id, "Smith, \"J\" - invoice.pdf", 88.0)).await?;
+            let formula = db.create_document(invoice(user_id, "=HYPERLINK(1).pdf", 75.0)).await?;
+
+            // Another user's invoice must never be exported
+            let other = auth_helper.create_test_user().await;
+            db.create_document(invoice(other.user_response.id, "not-mine.pdf", 99.0)).await?;
+
+            for name in ["finance", "clients"] {
+                let label_id: Uuid = sqlx::query_scalar("INSERT INTO labels (user_id, name) VALUES ($1, $2) RETURNING id")
+                    .bind(user_id)
+                    .bind(name)
+                    .fetch_one(db.get_pool())
+                    .await?;
+                sqlx::query("INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2)")
+                    .bind(plain.id)
+                    .bind(label_id)
+                    .execute(db.get_pool())
+                    .await?;
+            }
+
+            let (status, content_type, csv) = export(
+                &ctx.app,
+                "/api/search/export?format=csv&query=invoice&columns=id,filename,labels,ocr_confidence,source",
+                &token,
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type.as_deref(), Some("text/csv; charset=utf-8"));
+
+            let lines: Vec<&str> = csv.split_terminator("\r\n").collect();
+            assert_eq!(lines[0], "id,filename,labels,ocr_confidence,source");
+            assert_eq!(lines.len(), 4, "header plus one row per own invoice: {:?}", lines);
+            assert!(lines.contains(&format!("{},march.pdf,clients; finance,94.3,web_upload", plain.id).as_str()));
+            assert!(lines.contains(&format!("{},\"Smith, \"\"J\"\" - invoice.pdf\",,88.0,web_upload", awkward.id).as_str()));
+            assert!(lines.contains(&format!("{},'=HYPERLINK(1).pdf,,75.0,web_upload", formula.id).as_str()));
+            assert!(!csv.contains("not-mine.pdf"));
+
+            // Filters narrow the export like they narrow search
+            let (status, _, csv) = export(&ctx.app, "/api/search/export?tags=finance&columns=filename", &token).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(csv, "filename\r\nmarch.pdf\r\n");
+
+            // Without columns, the default set is exported
+            let (_, _, csv) = export(&ctx.app, "/api/search/export?query=invoice", &token).await;
+            assert!(csv.starts_with("filename,created_at,document_date,labels,ocr_status,ocr_confidence,source\r\n"));
+
+            let (status, _, _) = export(&ctx.app, "/api/search/export?query=invoice&columns=filename,ocr_text", &token).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            let (status, _, _) = export(&ctx.app, "/api/search/export?query=invoice&format=xlsx", &token).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
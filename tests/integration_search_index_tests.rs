@@ -0,0 +1,101 @@
+//! Integration tests for the per-user search indexes and their health report.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::db::search_index::{per_user_search_indexes, SearchIndexStrategy};
+    use readur::models::{SearchRequest, UserRole};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use uuid::Uuid;
+
+    fn search(query: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            tags: None,
+            mime_types: None,
+            limit: None,
+            offset: None,
+            include_snippets: None,
+            snippet_length: None,
+            search_mode: None,
+            include_shared: None,
+            include_notes: None,
+            ignore_accents: None,
+            language: None,
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
+        }
+    }
+
+    /// Inserts `count` text documents for a user, every `every`th of which mentions `word`
+    async fn insert_documents(ctx: &TestContext, user_id: Uuid, count: i32, word: &str, every: i32) -> Result<()> {
+        sqlx::query(
+            r#"INSERT INTO documents (filename, original_filename, file_path, file_size, mime_type, content, ocr_status, user_id)
+               SELECT 'doc' || n || '.txt', 'doc' || n || '.txt', '/tmp/doc' || n || '.txt', 100, 'text/plain',
+                      CASE WHEN n % $3 = 0 THEN 'quarterly ' || $4 || ' number ' || n ELSE 'shipping report number ' || n END,
+                      'completed', $1
+               FROM generate_series(1, $2) AS n"#,
+        )
+        .bind(user_id)
+        .bind(count)
+        .bind(every)
+        .bind(word)
+        .execute(&ctx.state.db.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_searches_read_the_per_user_index() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let other = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            insert_documents(&ctx, user_id, 3000, "invoice", 300).await?;
+            insert_documents(&ctx, other.user_response.id, 3000, "invoice", 1).await?;
+
+            db.apply_search_index_strategy(SearchIndexStrategy::PerUser).await?;
+            sqlx::query("ANALYZE documents").execute(&db.pool).await?;
+
+            // The planner picks the index on its own; the user filter is part of the index scan
+            let plan = db.explain_search(user_id, UserRole::User, &search("invoice"), false).await?.join("\n");
+            assert!(plan.contains("idx_documents_user_content_search"), "unexpected plan:\n{}", plan);
+            assert!(
+                plan.lines().any(|line| line.contains("Index Cond") && line.contains("user_id =")),
+                "unexpected plan:\n{}",
+                plan
+            );
+            assert!(!plan.contains("Seq Scan"), "unexpected plan:\n{}", plan);
+
+            // Only the user's own matches are found
+            assert_eq!(db.count_search_documents(user_id, UserRole::User, &search("invoice")).await?, 10);
+
+            let health = db.search_index_health(SearchIndexStrategy::PerUser).await?;
+            assert!(health.missing_indexes.is_empty());
+            assert!(health.sample_plan_uses_user_index(), "{:?}", health.sample_plan);
+            for (name, _) in per_user_search_indexes() {
+                let index = health.indexes.iter().find(|index| index.name == name).expect("per-user index");
+                assert!(index.valid);
+                assert!(index.definition.contains("user_id"));
+            }
+
+            // Switching back drops them
+            db.apply_search_index_strategy(SearchIndexStrategy::Shared).await?;
+            let health = db.search_index_health(SearchIndexStrategy::Shared).await?;
+            assert!(!health.indexes.iter().any(|index| index.name.starts_with("idx_documents_user_content_search")));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -6,8 +6,10 @@
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
-    use readur::test_utils::TestContext;
-    use readur::models::{CreateUser, Document, SearchRequest, UserRole};
+    use readur::test_utils::{TestAuthHelper, TestConfigBuilder, TestContext};
+    use readur::models::{CreateUser, Document, SearchMatchCount, SearchRequest, SearchResponse, UserRole};
+    use axum::http::StatusCode;
+    use tower::util::ServiceExt;
     use chrono::Utc;
     use uuid::Uuid;
     use std::collections::HashSet;
@@ -107,6 +109,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
@@ -151,6 +160,13 @@ mod tests {
                     include_snippets: Some(false),
                     snippet_length: None,
                     search_mode: None,
+                    include_shared: None,
+                    include_notes: None,
+                    ignore_accents: None,
+                    language: None,
+                    document_date_from: None,
+                    document_date_to: None,
+                    collapse_duplicates: None,
                 };
                 let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
                 assert_eq!(count, 20, "Total should be consistent (20) at offset {}", offset);
@@ -194,6 +210,13 @@ mod tests {
                     include_snippets: Some(false),
                     snippet_length: None,
                     search_mode: None,
+                    include_shared: None,
+                    include_notes: None,
+                    ignore_accents: None,
+                    language: None,
+                    document_date_from: None,
+                    document_date_to: None,
+                    collapse_duplicates: None,
                 };
                 let results = db.search_documents(user.id, &request).await?;
 
@@ -244,6 +267,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
@@ -259,6 +289,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count_pdf = db.count_search_documents(user.id, UserRole::User, &request_pdf).await?;
@@ -301,6 +338,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
@@ -343,6 +387,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
@@ -394,6 +445,13 @@ mod tests {
                     include_snippets: Some(false),
                     snippet_length: None,
                     search_mode: None,
+                    include_shared: None,
+                    include_notes: None,
+                    ignore_accents: None,
+                    language: None,
+                    document_date_from: None,
+                    document_date_to: None,
+                    collapse_duplicates: None,
                 };
 
                 let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
@@ -442,6 +500,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             // User A should see only their 10 documents
@@ -496,6 +561,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count_apple = db.count_search_documents(user.id, UserRole::User, &request_apple).await?;
@@ -514,6 +586,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count_orange = db.count_search_documents(user.id, UserRole::User, &request_orange).await?;
@@ -581,6 +660,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
@@ -599,6 +685,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count_none = db.count_search_documents(user.id, UserRole::User, &request_none).await?;
@@ -641,6 +734,13 @@ mod tests {
                 include_snippets: Some(false),
                 snippet_length: None,
                 search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
             };
 
             let count = db.count_search_documents(user.id, UserRole::User, &request).await?;
@@ -655,4 +755,105 @@ mod tests {
 
         result.unwrap();
     }
+
+    /// Test that counting stops at the cap and estimates past it
+    #[tokio::test]
+    async fn test_capped_count_estimates_past_the_cap() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("capped1")).await?;
+
+            for i in 0..6 {
+                db.create_document(create_searchable_document(user.id, i, "text/plain")).await?;
+            }
+
+            let request = SearchRequest {
+                query: "searchable".to_string(),
+                tags: None,
+                mime_types: None,
+                limit: Some(5),
+                offset: Some(0),
+                include_snippets: Some(false),
+                snippet_length: None,
+                search_mode: None,
+                include_shared: None,
+                include_notes: None,
+                ignore_accents: None,
+                language: None,
+                document_date_from: None,
+                document_date_to: None,
+                collapse_duplicates: None,
+            };
+
+            let count = db.count_search_documents_capped(user.id, UserRole::User, &request, 6).await?;
+            assert_eq!(count, SearchMatchCount { total: 6, capped: false, total_is_estimate: false });
+
+            let count = db.count_search_documents_capped(user.id, UserRole::User, &request, 4).await?;
+            assert!(count.capped);
+            assert!(count.total_is_estimate);
+            assert!(count.total > 4, "estimate {} should exceed the cap", count.total);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    /// Test that the search endpoint flags capped results and refuses pages past the cap
+    #[tokio::test]
+    async fn test_search_endpoint_caps_results() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_search_max_results(3)).await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            for i in 0..5 {
+                db.create_document(create_searchable_document(user.user_response.id, i, "text/plain")).await?;
+            }
+
+            let search = |offset: i64| {
+                axum::http::Request::builder()
+                    .method("GET")
+                    .uri(format!("/api/search?query=searchable&limit=2&offset={}", offset))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(axum::body::Body::empty())
+                    .unwrap()
+            };
+
+            let response = ctx.app.clone().oneshot(search(0)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let page: SearchResponse = serde_json::from_slice(&body)?;
+            assert_eq!(page.documents.len(), 2);
+            assert!(page.capped);
+            assert!(page.total_is_estimate);
+
+            // The last page within the cap is cut off at the cap
+            let response = ctx.app.clone().oneshot(search(2)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let page: SearchResponse = serde_json::from_slice(&body)?;
+            assert_eq!(page.documents.len(), 1);
+
+            let response = ctx.app.clone().oneshot(search(3)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
 }
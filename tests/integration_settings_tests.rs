@@ -117,6 +117,35 @@ mod tests {
                 webdav_sync_interval_minutes: None,
                 office_extraction_timeout_seconds: None,
                 office_extraction_enable_detailed_logging: None,
+                ocr_auto_psm: None,
+                ocr_quick_preview_pages: None,
+                notification_delivery_mode: None,
+                notification_digest_interval_minutes: None,
+                ocr_max_pixels: None,
+                ocr_pdf_hybrid: None,
+                ocr_reprocess_on_settings_change: None,
+                auto_label_language: None,
+                extract_document_date: None,
+                document_date_priority: None,
+                document_date_rule: None,
+                document_date_order: None,
+                search_include_notes: None,
+                ocr_verify_orientation: None,
+                auto_split_on_separator: None,
+                auto_split_barcode: None,
+                expiry_reminder_days: None,
+                search_language: None,
+                search_ignore_accents: None,
+                extract_archives: None,
+                keep_original_pdf: None,
+                decode_barcodes: None,
+                search_collapse_duplicates: None,
+                ocr_incremental_pages: None,
+                parse_front_matter: None,
+                extract_tables: None,
+                doc_extraction_order: None,
+                pdf_extraction_order: None,
+                extraction_auto_order: None,
             };
 
             let response = ctx.app
@@ -242,6 +271,35 @@ mod tests {
                 webdav_sync_interval_minutes: None,
                 office_extraction_timeout_seconds: None,
                 office_extraction_enable_detailed_logging: None,
+                ocr_auto_psm: None,
+                ocr_quick_preview_pages: None,
+                notification_delivery_mode: None,
+                notification_digest_interval_minutes: None,
+                ocr_max_pixels: None,
+                ocr_pdf_hybrid: None,
+                ocr_reprocess_on_settings_change: None,
+                auto_label_language: None,
+                extract_document_date: None,
+                document_date_priority: None,
+                document_date_rule: None,
+                document_date_order: None,
+                search_include_notes: None,
+                ocr_verify_orientation: None,
+                auto_split_on_separator: None,
+                auto_split_barcode: None,
+                expiry_reminder_days: None,
+                search_language: None,
+                search_ignore_accents: None,
+                extract_archives: None,
+                keep_original_pdf: None,
+                decode_barcodes: None,
+                search_collapse_duplicates: None,
+                ocr_incremental_pages: None,
+                parse_front_matter: None,
+                extract_tables: None,
+                doc_extraction_order: None,
+                pdf_extraction_order: None,
+                extraction_auto_order: None,
             };
 
             let response = ctx.app
@@ -394,6 +452,35 @@ mod tests {
                 webdav_sync_interval_minutes: None,
                 office_extraction_timeout_seconds: None,
                 office_extraction_enable_detailed_logging: None,
+                ocr_auto_psm: None,
+                ocr_quick_preview_pages: None,
+                notification_delivery_mode: None,
+                notification_digest_interval_minutes: None,
+                ocr_max_pixels: None,
+                ocr_pdf_hybrid: None,
+                ocr_reprocess_on_settings_change: None,
+                auto_label_language: None,
+                extract_document_date: None,
+                document_date_priority: None,
+                document_date_rule: None,
+                document_date_order: None,
+                search_include_notes: None,
+                ocr_verify_orientation: None,
+                auto_split_on_separator: None,
+                auto_split_barcode: None,
+                expiry_reminder_days: None,
+                search_language: None,
+                search_ignore_accents: None,
+                extract_archives: None,
+                keep_original_pdf: None,
+                decode_barcodes: None,
+                search_collapse_duplicates: None,
+                ocr_incremental_pages: None,
+                parse_front_matter: None,
+                extract_tables: None,
+                doc_extraction_order: None,
+                pdf_extraction_order: None,
+                extraction_auto_order: None,
             };
 
             let response = ctx.app
@@ -615,6 +702,35 @@ mod tests {
                 webdav_sync_interval_minutes: None,
                 office_extraction_timeout_seconds: None,
                 office_extraction_enable_detailed_logging: None,
+                ocr_auto_psm: None,
+                ocr_quick_preview_pages: None,
+                notification_delivery_mode: None,
+                notification_digest_interval_minutes: None,
+                ocr_max_pixels: None,
+                ocr_pdf_hybrid: None,
+                ocr_reprocess_on_settings_change: None,
+                auto_label_language: None,
+                extract_document_date: None,
+                document_date_priority: None,
+                document_date_rule: None,
+                document_date_order: None,
+                search_include_notes: None,
+                ocr_verify_orientation: None,
+                auto_split_on_separator: None,
+                auto_split_barcode: None,
+                expiry_reminder_days: None,
+                search_language: None,
+                search_ignore_accents: None,
+                extract_archives: None,
+                keep_original_pdf: None,
+                decode_barcodes: None,
+                search_collapse_duplicates: None,
+                ocr_incremental_pages: None,
+                parse_front_matter: None,
+                extract_tables: None,
+                doc_extraction_order: None,
+                pdf_extraction_order: None,
+                extraction_auto_order: None,
             };
 
             // Update the settings
@@ -760,6 +876,35 @@ mod tests {
                 webdav_sync_interval_minutes: None,
                 office_extraction_timeout_seconds: None,
                 office_extraction_enable_detailed_logging: None,
+                ocr_auto_psm: None,
+                ocr_quick_preview_pages: None,
+                notification_delivery_mode: None,
+                notification_digest_interval_minutes: None,
+                ocr_max_pixels: None,
+                ocr_pdf_hybrid: None,
+                ocr_reprocess_on_settings_change: None,
+                auto_label_language: None,
+                extract_document_date: None,
+                document_date_priority: None,
+                document_date_rule: None,
+                document_date_order: None,
+                search_include_notes: None,
+                ocr_verify_orientation: None,
+                auto_split_on_separator: None,
+                auto_split_barcode: None,
+                expiry_reminder_days: None,
+                search_language: None,
+                search_ignore_accents: None,
+                extract_archives: None,
+                keep_original_pdf: None,
+                decode_barcodes: None,
+                search_collapse_duplicates: None,
+                ocr_incremental_pages: None,
+                parse_front_matter: None,
+                extract_tables: None,
+                doc_extraction_order: None,
+                pdf_extraction_order: None,
+                extraction_auto_order: None,
             };
 
             let response = ctx.app
@@ -789,4 +934,58 @@ mod tests {
         
         result.unwrap();
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_extraction_orders_are_validated_and_saved_as_method_names() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let db = &ctx.state().db;
+            let base = || UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string());
+
+            let saved = db
+                .create_or_update_settings(user_id, &UpdateSettings {
+                    doc_extraction_order: Some(Some("wvtext, Catdoc".to_string())),
+                    pdf_extraction_order: Some(Some("OCRmyPDF,pdftotext".to_string())),
+                    extraction_auto_order: Some(Some(true)),
+                    ..base()
+                })
+                .await?;
+            assert_eq!(saved.doc_extraction_order.as_deref(), Some("wvText,catdoc"));
+            assert_eq!(saved.pdf_extraction_order.as_deref(), Some("ocrmypdf,pdftotext"));
+            assert_eq!(saved.extraction_auto_order, Some(true));
+
+            // Methods of another strategy are rejected and the saved order is kept
+            let rejected = db
+                .create_or_update_settings(user_id, &UpdateSettings {
+                    pdf_extraction_order: Some(Some("pdftotext,antiword".to_string())),
+                    ..base()
+                })
+                .await;
+            assert!(rejected.is_err());
+            let kept = db.get_user_settings(user_id).await?.unwrap();
+            assert_eq!(kept.pdf_extraction_order.as_deref(), Some("ocrmypdf,pdftotext"));
+
+            // Clearing an order goes back to the server order
+            let cleared = db
+                .create_or_update_settings(user_id, &UpdateSettings {
+                    pdf_extraction_order: Some(None),
+                    ..base()
+                })
+                .await?;
+            assert_eq!(cleared.pdf_extraction_order, None);
+            assert_eq!(cleared.doc_extraction_order.as_deref(), Some("wvText,catdoc"));
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
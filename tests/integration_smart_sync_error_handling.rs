@@ -50,6 +50,9 @@ fn create_test_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     WebDAVService::new(config).expect("Failed to create WebDAV service")
@@ -96,6 +99,9 @@ async fn test_webdav_error_fallback() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 1, // Very short timeout to fail quickly
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let failing_webdav_service = WebDAVService::new(invalid_config)
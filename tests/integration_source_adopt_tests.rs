@@ -0,0 +1,179 @@
+//! Integration tests for adopting existing documents into a source.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::{AdoptDocumentsResponse, AdoptionRejection, CreateSource, SourceType};
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use serde_json::json;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn local_folder_source(name: &str) -> CreateSource {
+        CreateSource {
+            name: name.to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": ["/data/inbox"],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false
+            }),
+        }
+    }
+
+    fn new_document(user_id: Uuid, filename: &str, mime_type: &str) -> readur::models::Document {
+        let mut document = create_test_document(user_id);
+        document.filename = filename.to_string();
+        document.mime_type = mime_type.to_string();
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    async fn post_adopt(ctx: &TestContext, token: &str, source_id: Uuid, body: serde_json::Value) -> (StatusCode, Vec<u8>) {
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri(format!("/api/sources/{}/adopt", source_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_adopt_by_ids_rejects_foreign_and_owned_documents() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let other = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let source = db.create_source(user_id, &local_folder_source("Inbox")).await?;
+            let other_source = db.create_source(user_id, &local_folder_source("Archive")).await?;
+
+            let orphan = db.create_document(new_document(user_id, "orphan.pdf", "application/pdf")).await?;
+            let mut owned = new_document(user_id, "owned.pdf", "application/pdf");
+            owned.source_id = Some(other_source.id);
+            let owned = db.create_document(owned).await?;
+            let foreign = db.create_document(new_document(other.user_response.id, "foreign.pdf", "application/pdf")).await?;
+
+            let body = json!({
+                "document_ids": [orphan.id, owned.id, foreign.id],
+                "source_paths": { orphan.id.to_string(): "/data/inbox/orphan.pdf" }
+            });
+            let (status, body) = post_adopt(&ctx, &token, source.id, body).await;
+            assert_eq!(status, StatusCode::OK);
+            let response: AdoptDocumentsResponse = serde_json::from_slice(&body)?;
+            assert_eq!(response.adopted, vec![orphan.id]);
+            let rejected: Vec<(Uuid, AdoptionRejection)> =
+                response.rejected.iter().map(|rejection| (rejection.document_id, rejection.reason)).collect();
+            assert_eq!(
+                rejected,
+                vec![(owned.id, AdoptionRejection::OwnedByOtherSource), (foreign.id, AdoptionRejection::NotFound)]
+            );
+
+            let adopted = db.get_document_by_id(orphan.id, user_id, readur::models::UserRole::User).await?.unwrap();
+            assert_eq!(adopted.source_id, Some(source.id));
+            assert_eq!(adopted.source_path.as_deref(), Some("/data/inbox/orphan.pdf"));
+            let untouched = db.get_document_by_id(owned.id, user_id, readur::models::UserRole::User).await?.unwrap();
+            assert_eq!(untouched.source_id, Some(other_source.id));
+
+            // Adopting again is a no-op
+            let (status, body) = post_adopt(&ctx, &token, source.id, json!({ "document_ids": [orphan.id] })).await;
+            assert_eq!(status, StatusCode::OK);
+            let response: AdoptDocumentsResponse = serde_json::from_slice(&body)?;
+            assert_eq!(response.adopted, vec![orphan.id]);
+            assert!(response.rejected.is_empty());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_adopt_by_filter_only_takes_documents_without_a_source() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let source = db.create_source(user_id, &local_folder_source("Inbox")).await?;
+            let other_source = db.create_source(user_id, &local_folder_source("Archive")).await?;
+
+            let pdf = db.create_document(new_document(user_id, "scan.pdf", "application/pdf")).await?;
+            let image = db.create_document(new_document(user_id, "photo.png", "image/png")).await?;
+            let mut owned = new_document(user_id, "owned.pdf", "application/pdf");
+            owned.source_id = Some(other_source.id);
+            let owned = db.create_document(owned).await?;
+
+            let body = json!({ "filter": { "mime_types": ["application/pdf"] } });
+            let (status, body) = post_adopt(&ctx, &token, source.id, body).await;
+            assert_eq!(status, StatusCode::OK);
+            let response: AdoptDocumentsResponse = serde_json::from_slice(&body)?;
+            assert_eq!(response.adopted, vec![pdf.id]);
+
+            let role = readur::models::UserRole::User;
+            assert_eq!(db.get_document_by_id(image.id, user_id, role).await?.unwrap().source_id, None);
+            assert_eq!(db.get_document_by_id(owned.id, user_id, role).await?.unwrap().source_id, Some(other_source.id));
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_adopt_validates_request_and_source_ownership() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let other = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let source = db.create_source(user.user_response.id, &local_folder_source("Inbox")).await?;
+            let foreign_source = db.create_source(other.user_response.id, &local_folder_source("Theirs")).await?;
+
+            let (status, _) = post_adopt(&ctx, &token, source.id, json!({})).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            let (status, _) = post_adopt(&ctx, &token, foreign_source.id, json!({ "filter": {} })).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+//! Integration tests for confirmed source deletion and the undo window.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::{CreateSource, SourceDeletionPreview, SourceDeletionSummary, SourceType, UserRole};
+    use readur::services::source_deletion::SourcePurgeService;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestConfigBuilder, TestContext};
+    use serde_json::json;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn local_folder_source(name: &str) -> CreateSource {
+        CreateSource {
+            name: name.to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": ["/data/inbox"],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false
+            }),
+        }
+    }
+
+    async fn create_source_document(ctx: &TestContext, user_id: Uuid, source_id: Uuid) -> Result<Uuid> {
+        let mut document = create_test_document(user_id);
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document.source_id = Some(source_id);
+        Ok(ctx.state.db.create_document(document).await?.id)
+    }
+
+    async fn send(ctx: &TestContext, token: &str, method: &str, uri: String) -> (StatusCode, Vec<u8>) {
+        let request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    async fn preview(ctx: &TestContext, token: &str, source_id: Uuid) -> Result<SourceDeletionPreview> {
+        let (status, body) = send(ctx, token, "GET", format!("/api/sources/{}/delete-preview", source_id)).await;
+        assert_eq!(status, StatusCode::OK);
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_delete_removes_source_and_documents() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_source_deletion(true, 0)).await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let source = db.create_source(user_id, &local_folder_source("Inbox")).await?;
+            let mut documents = Vec::new();
+            for _ in 0..3 {
+                documents.push(create_source_document(&ctx, user_id, source.id).await?);
+            }
+
+            // Without the preview's token the source is kept
+            let (status, _) = send(&ctx, &token, "DELETE", format!("/api/sources/{}", source.id)).await;
+            assert_eq!(status, StatusCode::CONFLICT);
+            assert!(db.get_source(user_id, source.id).await?.is_some());
+
+            let preview = preview(&ctx, &token, source.id).await?;
+            assert_eq!(preview.document_count, 3);
+            assert!(preview.confirmation_required);
+            assert_eq!(preview.grace_period_minutes, 0);
+
+            let uri = format!(
+                "/api/sources/{}?delete_documents=true&confirmation_token={}",
+                source.id, preview.confirmation_token
+            );
+            let (status, body) = send(&ctx, &token, "DELETE", uri).await;
+            assert_eq!(status, StatusCode::OK);
+            let summary: SourceDeletionSummary = serde_json::from_slice(&body)?;
+            assert_eq!(summary.documents_deleted, 3);
+            assert_eq!(summary.documents_orphaned, 0);
+            assert_eq!(summary.purge_at, None);
+
+            assert!(db.get_source(user_id, source.id).await?.is_none());
+            for document_id in documents {
+                assert!(db.get_document_by_id(document_id, user_id, UserRole::User).await?.is_none());
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_token_goes_stale_when_documents_change() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_source_deletion(true, 0)).await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let source = db.create_source(user_id, &local_folder_source("Inbox")).await?;
+            create_source_document(&ctx, user_id, source.id).await?;
+            let preview = preview(&ctx, &token, source.id).await?;
+
+            // A sync added a document after the preview
+            create_source_document(&ctx, user_id, source.id).await?;
+
+            let uri = format!("/api/sources/{}?confirmation_token={}", source.id, preview.confirmation_token);
+            let (status, _) = send(&ctx, &token, "DELETE", uri).await;
+            assert_eq!(status, StatusCode::CONFLICT);
+            assert!(db.get_source(user_id, source.id).await?.is_some());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grace_period_allows_restore_before_purge() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_source_deletion(false, 60)).await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let user_id = user.user_response.id;
+
+            let source = db.create_source(user_id, &local_folder_source("Inbox")).await?;
+            let kept = create_source_document(&ctx, user_id, source.id).await?;
+
+            // Deleting only hides the source and its documents stay until the purge
+            let (status, body) = send(&ctx, &token, "DELETE", format!("/api/sources/{}?delete_documents=true", source.id)).await;
+            assert_eq!(status, StatusCode::OK);
+            let summary: SourceDeletionSummary = serde_json::from_slice(&body)?;
+            assert_eq!(summary.documents_deleted, 1);
+            assert!(summary.purge_at.is_some());
+
+            let (status, _) = send(&ctx, &token, "GET", format!("/api/sources/{}", source.id)).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert!(db.get_sources(user_id).await?.is_empty());
+            assert!(db.get_document_by_id(kept, user_id, UserRole::User).await?.is_some());
+
+            let (status, _) = send(&ctx, &token, "POST", format!("/api/sources/{}/restore", source.id)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(db.get_source(user_id, source.id).await?.is_some());
+
+            // Once the grace period is over the purge removes the source and its documents
+            let (status, _) = send(&ctx, &token, "DELETE", format!("/api/sources/{}?delete_documents=true", source.id)).await;
+            assert_eq!(status, StatusCode::OK);
+            let purge = SourcePurgeService::new(db.clone(), ctx.state.file_service.clone());
+            assert_eq!(purge.purge_due_sources().await?, 0);
+
+            sqlx::query("UPDATE sources SET purge_at = NOW() - INTERVAL '1 minute' WHERE id = $1")
+                .bind(source.id)
+                .execute(db.get_pool())
+                .await?;
+            assert_eq!(purge.purge_due_sources().await?, 1);
+            assert!(db.get_document_by_id(kept, user_id, UserRole::User).await?.is_none());
+
+            let (status, _) = send(&ctx, &token, "POST", format!("/api/sources/{}/restore", source.id)).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+//! Integration tests for turning OCR off on a source.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::models::{CreateSource, SourceType};
+    use readur::scheduling::source_sync::SourceSyncService;
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use serde_json::json;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    fn local_folder_source(folder: &str, extra: serde_json::Value) -> CreateSource {
+        let mut config = json!({
+            "watch_folders": [folder],
+            "file_extensions": ["txt"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "recursive": true,
+            "follow_symlinks": false,
+        });
+        config.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        CreateSource {
+            name: format!("Exports {}", Uuid::new_v4().simple()),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config,
+        }
+    }
+
+    /// Syncs a folder holding two files with background OCR on, returning the
+    /// OCR status of each stored document and how many of them were queued
+    async fn sync_folder(ctx: &TestContext, extra: serde_json::Value) -> Result<(Vec<String>, i64)> {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let folder = TempDir::new()?;
+        for name in ["report.txt", "ledger.txt"] {
+            std::fs::write(folder.path().join(name), format!("{} {}", name, Uuid::new_v4()))?;
+        }
+
+        let db = &ctx.state.db;
+        let source = db
+            .create_source(user_id, &local_folder_source(folder.path().to_str().unwrap(), extra))
+            .await?;
+        SourceSyncService::new(ctx.state.clone()).sync_source(&source, true).await?;
+
+        let documents = db.get_documents_by_user(user_id, 100, 0).await?;
+        assert_eq!(documents.len(), 2, "both files are stored");
+        let statuses = documents
+            .iter()
+            .map(|document| document.ocr_status.clone().unwrap_or_default())
+            .collect();
+
+        let document_ids: Vec<Uuid> = documents.iter().map(|document| document.id).collect();
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ocr_queue WHERE document_id = ANY($1)")
+            .bind(&document_ids)
+            .fetch_one(db.get_pool())
+            .await?;
+        Ok((statuses, queued))
+    }
+
+    #[tokio::test]
+    async fn test_source_with_ocr_disabled_stores_documents_without_queueing_them() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let (statuses, queued) = sync_folder(&ctx, json!({ "ocr_enabled": false })).await?;
+            assert_eq!(queued, 0);
+            assert_eq!(statuses, vec!["skipped", "skipped"]);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sources_queue_ocr_by_default_and_for_text_layers() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let (_, queued) = sync_folder(&ctx, json!({})).await?;
+            assert_eq!(queued, 2);
+
+            // Text layers are still read when OCR is off
+            let (_, queued) = sync_folder(&ctx, json!({ "ocr_enabled": false, "processing_mode": "text_only" })).await?;
+            assert_eq!(queued, 2);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
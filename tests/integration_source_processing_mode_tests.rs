@@ -0,0 +1,364 @@
+//! Integration tests for per-source processing modes in the OCR queue worker.
+//!
+//! Each mode is run against a native PDF (text layer, no images) and a scanned
+//! PDF (a single image, no text layer).
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use chrono::Utc;
+    use readur::models::{CreateSource, CreateUser, Document, OcrJobOverrides, SourceType, UserRole};
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::ocr::queue::{OcrQueueItem, OnDemandEnqueue};
+    use readur::test_utils::TestContext;
+    use readur::AppState;
+    use serde_json::json;
+    use std::path::Path;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    const NATIVE_TEXT: &str = "Quarterly report for the northern region sales team";
+
+    /// Assembles a PDF from its objects, computing the xref table
+    fn build_pdf(objects: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_offset
+            )
+            .as_bytes(),
+        );
+        pdf
+    }
+
+    fn stream_object(dictionary: &str, data: &[u8]) -> Vec<u8> {
+        let mut object = format!("<< {} /Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+        object.extend_from_slice(data);
+        object.extend_from_slice(b"\nendstream");
+        object
+    }
+
+    fn native_pdf() -> Vec<u8> {
+        let content = format!("BT\n/F1 12 Tf\n72 720 Td\n({}) Tj\nET", NATIVE_TEXT);
+        build_pdf(vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+            stream_object("", content.as_bytes()),
+        ])
+    }
+
+    fn scanned_pdf() -> Vec<u8> {
+        let pixels = vec![0x80u8; 16 * 16];
+        build_pdf(vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << /XObject << /Im1 4 0 R >> >> /Contents 5 0 R >>".to_vec(),
+            stream_object(
+                "/Type /XObject /Subtype /Image /Width 16 /Height 16 /ColorSpace /DeviceGray /BitsPerComponent 8",
+                &pixels,
+            ),
+            stream_object("", b"q 400 0 0 400 100 200 cm /Im1 Do Q"),
+        ])
+    }
+
+    fn pdftotext_available() -> bool {
+        std::process::Command::new("pdftotext").arg("-v").output().is_ok()
+    }
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        CreateUser {
+            username: format!("processing_mode_{}", suffix),
+            email: format!("processing_mode_{}@example.com", suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn local_folder_source(mode: &str) -> CreateSource {
+        CreateSource {
+            name: format!("{} source", mode),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": ["/tmp/processing-mode"],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false,
+                "processing_mode": mode,
+            }),
+        }
+    }
+
+    fn pending_pdf_document(user_id: Uuid, source_id: Uuid, file_path: &Path, file_size: i64) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            filename: "document.pdf".to_string(),
+            original_filename: "document.pdf".to_string(),
+            file_path: file_path.to_string_lossy().to_string(),
+            file_size,
+            mime_type: "application/pdf".to_string(),
+            content: None,
+            ocr_text: None,
+            ocr_confidence: None,
+            ocr_word_count: None,
+            ocr_processing_time_ms: None,
+            ocr_status: Some("pending".to_string()),
+            ocr_error: None,
+            ocr_completed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            user_id,
+            file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+            original_created_at: None,
+            original_modified_at: None,
+            source_path: None,
+            source_type: Some("local_folder".to_string()),
+            source_id: Some(source_id),
+            file_permissions: None,
+            file_owner: None,
+            file_group: None,
+            source_metadata: None,
+            ocr_retry_count: None,
+            ocr_failure_reason: None,
+        }
+    }
+
+    /// Stores `pdf` as a document of a source in `mode`. The returned directory
+    /// holds the file, so it must outlive the document's OCR jobs.
+    async fn store_with_mode(state: &Arc<AppState>, mode: &str, pdf: &[u8]) -> Result<(Document, tempfile::TempDir)> {
+        let user = state
+            .db
+            .create_user(create_test_user_data(&Uuid::new_v4().simple().to_string()))
+            .await?;
+        let source = state.db.create_source(user.id, &local_folder_source(mode)).await?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("document.pdf");
+        std::fs::write(&file_path, pdf)?;
+
+        let document = state
+            .db
+            .create_document(pending_pdf_document(user.id, source.id, &file_path, pdf.len() as i64))
+            .await?;
+        Ok((document, temp_dir))
+    }
+
+    /// Runs one OCR job and returns its document afterwards
+    async fn run_job(state: &Arc<AppState>, job_id: Uuid, document: &Document, temp_dir: &Path) -> Result<Document> {
+        // Fetch the job directly so jobs from concurrent tests are left alone
+        let item = sqlx::query_as::<_, OcrQueueItem>("SELECT * FROM ocr_queue WHERE id = $1")
+            .bind(job_id)
+            .fetch_one(state.db.get_pool())
+            .await?;
+
+        let ocr_service = EnhancedOcrService::new(
+            temp_dir.to_string_lossy().to_string(),
+            (*state.file_service).clone(),
+            100,
+            100,
+            300,
+        );
+        state.queue_service.process_item(item, &ocr_service).await?;
+
+        let stored = state
+            .db
+            .get_document_by_id(document.id, document.user_id, UserRole::User)
+            .await?
+            .expect("document exists");
+        Ok(stored)
+    }
+
+    /// Stores `pdf` as a document of a source in `mode`, runs its OCR job and
+    /// returns the document afterwards
+    async fn process_with_mode(state: &Arc<AppState>, mode: &str, pdf: &[u8]) -> Result<Document> {
+        let (document, temp_dir) = store_with_mode(state, mode, pdf).await?;
+        let job_id = state.queue_service.enqueue_document(document.id, 5, pdf.len() as i64).await?;
+        run_job(state, job_id, &document, temp_dir.path()).await
+    }
+
+    async fn queue_status(state: &Arc<AppState>, document_id: Uuid) -> Result<String> {
+        Ok(sqlx::query_scalar("SELECT status FROM ocr_queue WHERE document_id = $1")
+            .bind(document_id)
+            .fetch_one(state.db.get_pool())
+            .await?)
+    }
+
+    #[tokio::test]
+    async fn test_none_mode_stores_documents_without_text() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+
+            for pdf in [native_pdf(), scanned_pdf()] {
+                let document = process_with_mode(state, "none", &pdf).await?;
+                assert_eq!(document.ocr_status.as_deref(), Some("skipped"));
+                assert!(document.ocr_text.is_none());
+                assert!(document.ocr_failure_reason.is_none());
+                assert_eq!(queue_status(state, document.id).await?, "completed");
+            }
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_text_only_mode_uses_text_layer_of_native_pdf() {
+        if !pdftotext_available() {
+            println!("Skipping test: pdftotext is not installed");
+            return;
+        }
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let document = process_with_mode(ctx.state(), "text_only", &native_pdf()).await?;
+
+            assert_eq!(document.ocr_status.as_deref(), Some("completed"), "error: {:?}", document.ocr_error);
+            let text = document.ocr_text.expect("text layer stored");
+            assert!(text.contains(NATIVE_TEXT), "unexpected text: {:?}", text);
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_text_only_mode_skips_scanned_pdf_instead_of_failing() {
+        if !pdftotext_available() {
+            println!("Skipping test: pdftotext is not installed");
+            return;
+        }
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let document = process_with_mode(state, "text_only", &scanned_pdf()).await?;
+
+            assert_eq!(document.ocr_status.as_deref(), Some("skipped"), "error: {:?}", document.ocr_error);
+            assert!(document.ocr_text.is_none());
+            assert!(document.ocr_failure_reason.is_none());
+            assert_eq!(queue_status(state, document.id).await?, "completed");
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_full_mode_extracts_native_pdf_and_attempts_ocr_on_scans() {
+        if !pdftotext_available() {
+            println!("Skipping test: pdftotext is not installed");
+            return;
+        }
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+
+            let native = process_with_mode(state, "full", &native_pdf()).await?;
+            if native.ocr_status.as_deref() == Some("completed") {
+                assert!(native.ocr_text.unwrap_or_default().contains(NATIVE_TEXT));
+            } else {
+                // The full pipeline needs ocrmypdf, which may be missing in this environment
+                println!("Full pipeline unavailable: {:?}", native.ocr_error);
+            }
+
+            // A blank scan has nothing to read, but the full pipeline still tries OCR
+            // and reports the outcome rather than skipping the document
+            let scanned = process_with_mode(state, "full", &scanned_pdf()).await?;
+            assert_ne!(scanned.ocr_status.as_deref(), Some("skipped"));
+            assert_ne!(scanned.ocr_status.as_deref(), Some("pending"));
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_on_demand_ocr_runs_full_pipeline_on_none_mode_document() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let pdf = scanned_pdf();
+            let (document, temp_dir) = store_with_mode(state, "none", &pdf).await?;
+
+            let job_id = state.queue_service.enqueue_document(document.id, 5, pdf.len() as i64).await?;
+            let skipped = run_job(state, job_id, &document, temp_dir.path()).await?;
+            assert_eq!(skipped.ocr_status.as_deref(), Some("skipped"));
+
+            let overrides = OcrJobOverrides {
+                languages: Some(vec!["eng".to_string()]),
+                ..Default::default()
+            };
+            let job_id = match state.queue_service.enqueue_on_demand(document.id, pdf.len() as i64, &overrides).await? {
+                OnDemandEnqueue::Queued(id) => id,
+                other => panic!("document was not queued: {:?}", other),
+            };
+            // Asking again while the job is pending doesn't queue a second one
+            assert_eq!(
+                state.queue_service.enqueue_on_demand(document.id, pdf.len() as i64, &overrides).await?,
+                OnDemandEnqueue::AlreadyQueued(job_id)
+            );
+
+            // The source's mode is ignored: OCR is attempted and reports its outcome
+            let processed = run_job(state, job_id, &document, temp_dir.path()).await?;
+            assert_ne!(processed.ocr_status.as_deref(), Some("skipped"));
+            assert_ne!(processed.ocr_status.as_deref(), Some("pending"));
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
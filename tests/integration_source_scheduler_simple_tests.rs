@@ -229,6 +229,17 @@ fn test_auto_sync_configuration() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     assert!(webdav_config.auto_sync);
@@ -244,6 +255,17 @@ fn test_auto_sync_configuration() {
         auto_sync: false,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     assert!(!webdav_disabled.auto_sync);
@@ -305,6 +327,17 @@ fn test_source_configuration_sizes() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let serialized = serde_json::to_string(&webdav_config).unwrap();
@@ -180,9 +180,18 @@ async fn create_test_app_state() -> Arc<AppState> {
         file_stability_check_ms: Some(500),
         max_file_age_hours: None,
         ocr_language: "eng".to_string(),
+        ocr_auto_download_langs: false,
+        ocr_text_strip_control_chars: true,
+        ocr_text_normalize_unicode: true,
         concurrent_ocr_jobs: 2,
         ocr_timeout_seconds: 60,
+        ocr_model_cache_size: 2,
+        ocr_scheduling_policy: readur::ocr::queue::OcrFairnessPolicy::Fifo,
+        ocr_smaller_files_first: false,
+        ocr_large_file_threshold_mb: 0,
+        ocr_large_file_concurrency: 1,
         max_file_size_mb: 10,
+        max_upload_bytes: None,
         memory_limit_mb: 256,
         cpu_priority: "normal".to_string(),
         oidc_enabled: false,
@@ -194,9 +203,71 @@ async fn create_test_app_state() -> Arc<AppState> {
         allow_local_auth: None,
         s3_enabled: false,
         s3_config: None,
+        storage_compression: readur::storage::compression::StorageCompression::None,
+        storage_user_isolation: true,
+        webdav_stream_threshold_mb: 100,
+        webdav_discovery_cache_enabled: false,
+        webdav_discovery_cache_max_entries: 10000,
+        webdav_discovery_cache_ttl_minutes: 1440,
+        webdav_discovery_cache_file: None,
+        webdav_clock_skew_warning_seconds: 300,
+        webdav_clock_skew_tolerance_seconds: 0,
+        webdav_capability_ttl_minutes: 60,
+        webdav_max_downloads_per_host: 8,
+        ingestion_pipeline: readur::ingestion::pipeline::IngestionPipelineConfig::default(),
+        sync_failure_ratio_threshold: 0.5,
+        sync_history_retention_days: 90,
+        sync_transient_retries: 2,
+        sync_transient_retry_delay_seconds: 30,
+        source_delete_require_confirmation: false,
+        source_delete_grace_period_minutes: 0,
+        malware_scan_enabled: false,
+        malware_scan_endpoint: None,
+        malware_scan_command: None,
+        malware_scan_fail_open: false,
+        malware_scan_timeout_seconds: 60,
+        file_validation_mode: readur::services::file_validator::FileValidationMode::Lenient,
+        pdf_optimizer: readur::services::pdf_optimizer::PdfOptimizerTool::None,
+        pdf_optimize_min_size_kb: 1024,
+        pdf_optimize_timeout_seconds: 300,
+        handwriting_ocr_endpoint: None,
+        handwriting_ocr_api_key: None,
+        handwriting_ocr_timeout_seconds: 120,
+        ocr_confidence_calibration_file: None,
+        temp_file_max_age_hours: 24,
+        pagination_default_per_page: 25,
+        pagination_max_per_page: 100,
+        pagination_clamp_out_of_range: false,
+        search_max_results: 10000,
+        search_index_strategy: readur::db::search_index::SearchIndexStrategy::Shared,
+        response_compression_enabled: true,
+        response_compression_min_bytes: 1024,
             max_pdf_size_mb: 100,
             max_office_document_size_mb: 100,
+            office_doc_tool_order: readur::ocr::fallback_order::FallbackConfig::default().doc_tool_order,
+            pdf_extraction_order: readur::ocr::fallback_order::FallbackConfig::default().pdf_method_order,
+            extraction_auto_order: false,
+            archive_max_depth: 3,
+            archive_max_total_size_mb: 1024,
+            archive_max_entries: 1000,
         public_url: None,
+        schema_auto_repair: false,
+        backup_before_migrate: false,
+        migration_backup_dir: "./backups".to_string(),
+        migration_backup_include_data: false,
+        source_debug_endpoints: false,
+        ocr_debug_endpoints: false,
+        detect_document_language: true,
+        default_document_language: "english".to_string(),
+        thumbnail_size: 200,
+        thumbnail_auto_regenerate: false,
+        thumbnail_regeneration_concurrency: 2,
+        api_error_format: readur::error_envelope::ApiErrorFormat::Legacy,
+        api_error_expose_details: false,
+        credentials_master_keys: String::new(),
+        credentials_auto_rotate: false,
+        credentials_rotation_batch_size: 100,
+        mime_type_overrides: Default::default(),
     };
 
     // Use smaller connection pool for tests to avoid exhaustion  
@@ -652,6 +723,17 @@ async fn test_config_validation() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     assert!(!webdav_config.server_url.is_empty());
@@ -668,6 +750,11 @@ async fn test_config_validation() {
         auto_sync: true,
         sync_interval_minutes: 30,
         file_extensions: vec![".pdf".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        track_file_ids: false,
     };
     
     assert!(!local_config.watch_folders.is_empty());
@@ -686,6 +773,16 @@ async fn test_config_validation() {
         auto_sync: true,
         sync_interval_minutes: 120,
         file_extensions: vec![".pdf".to_string()],
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
     
     assert!(!s3_config.bucket_name.is_empty());
@@ -0,0 +1,192 @@
+//! Integration tests for a source's sync history.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use chrono::{DateTime, Duration, Utc};
+    use readur::models::{CreateSource, SourceSyncRun, SourceType, SyncFailureKind, SyncOutcome};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn webdav_source() -> CreateSource {
+        CreateSource {
+            name: "Archive".to_string(),
+            source_type: SourceType::WebDAV,
+            enabled: Some(true),
+            config: serde_json::json!({
+                "server_url": "https://cloud.example.com",
+                "username": "testuser",
+                "password": "testpass",
+                "watch_folders": ["/Documents"],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "server_type": "nextcloud"
+            }),
+        }
+    }
+
+    fn sync_run(source_id: Uuid, outcome: SyncOutcome, started_at: DateTime<Utc>, duration_seconds: i64) -> SourceSyncRun {
+        SourceSyncRun {
+            id: Uuid::new_v4(),
+            source_id,
+            outcome,
+            failure_kind: (outcome == SyncOutcome::Failed).then_some(SyncFailureKind::Transient),
+            retry_of_failed: false,
+            files_discovered: 10,
+            files_processed: 8,
+            files_failed: if outcome == SyncOutcome::Success { 0 } else { 2 },
+            files_added: 5,
+            files_updated: 3,
+            files_skipped: 2,
+            bytes_transferred: 4096,
+            failures: Vec::new(),
+            started_at,
+            completed_at: started_at + Duration::seconds(duration_seconds),
+        }
+    }
+
+    async fn get(app: &axum::Router, uri: &str, token: &str) -> (StatusCode, serde_json::Value) {
+        let request = axum::http::Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_sync_history_is_paginated_filtered_and_aggregated() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let source = db.create_source(user.user_response.id, &webdav_source()).await?;
+
+            let now = Utc::now();
+            let runs = [
+                sync_run(source.id, SyncOutcome::Success, now - Duration::days(3), 10),
+                sync_run(source.id, SyncOutcome::Partial, now - Duration::days(2), 20),
+                sync_run(source.id, SyncOutcome::Success, now - Duration::days(1), 30),
+                sync_run(source.id, SyncOutcome::Failed, now - Duration::hours(1), 60),
+            ];
+            for run in &runs {
+                db.insert_source_sync_run(run).await?;
+            }
+
+            let uri = format!("/api/sources/{}/sync-history?per_page=3", source.id);
+            let (status, body) = get(&ctx.app, &uri, &token).await;
+            assert_eq!(status, StatusCode::OK);
+            let page = body["runs"].as_array().unwrap();
+            assert_eq!(page.len(), 3);
+            assert_eq!(page[0]["id"], runs[3].id.to_string());
+            assert_eq!(page[0]["duration_ms"], 60_000);
+            assert_eq!(page[0]["failure_kind"], "transient");
+            assert!(page[1]["failure_kind"].is_null());
+            assert_eq!(page[0]["files_added"], 5);
+            assert_eq!(page[0]["files_updated"], 3);
+            assert_eq!(page[0]["files_skipped"], 2);
+            assert_eq!(page[0]["bytes_transferred"], 4096);
+            assert!(page[0].get("failures").is_none());
+            assert_eq!(body["pagination"]["total"], 4);
+            assert_eq!(body["pagination"]["total_pages"], 2);
+
+            // Stats cover every matching run, not just the page
+            assert_eq!(body["stats"]["total_runs"], 4);
+            assert_eq!(body["stats"]["successful_runs"], 2);
+            assert_eq!(body["stats"]["success_rate"], 0.5);
+            assert_eq!(body["stats"]["average_duration_ms"], 30_000);
+            assert_eq!(body["stats"]["files_added"], 20);
+            assert_eq!(body["stats"]["bytes_transferred"], 4 * 4096);
+
+            let uri = format!("/api/sources/{}/sync-history?status=success", source.id);
+            let (_, body) = get(&ctx.app, &uri, &token).await;
+            assert_eq!(body["runs"].as_array().unwrap().len(), 2);
+            assert_eq!(body["stats"]["success_rate"], 1.0);
+
+            let from = (now - Duration::days(2) - Duration::hours(1)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+            let to = (now - Duration::hours(2)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+            let uri = format!("/api/sources/{}/sync-history?from={}&to={}", source.id, from, to);
+            let (status, body) = get(&ctx.app, &uri, &token).await;
+            assert_eq!(status, StatusCode::OK);
+            let ids: Vec<String> = body["runs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|run| run["id"].as_str().unwrap().to_string())
+                .collect();
+            assert_eq!(ids, vec![runs[2].id.to_string(), runs[1].id.to_string()]);
+
+            let uri = format!("/api/sources/{}/sync-history?status=unknown", source.id);
+            let (status, _) = get(&ctx.app, &uri, &token).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            // Another user can't read the history
+            let other = auth_helper.create_test_user().await;
+            let other_token = auth_helper.login_user(&other.username, "password123").await;
+            let uri = format!("/api/sources/{}/sync-history", source.id);
+            let (status, _) = get(&ctx.app, &uri, &other_token).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sync_history_without_runs_and_pruning() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let source = db.create_source(user.user_response.id, &webdav_source()).await?;
+
+            let uri = format!("/api/sources/{}/sync-history", source.id);
+            let (status, body) = get(&ctx.app, &uri, &token).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(body["runs"].as_array().unwrap().is_empty());
+            assert_eq!(body["stats"]["total_runs"], 0);
+            assert!(body["stats"]["success_rate"].is_null());
+            assert!(body["stats"]["average_duration_ms"].is_null());
+
+            let now = Utc::now();
+            let old = sync_run(source.id, SyncOutcome::Success, now - Duration::days(100), 5);
+            let recent = sync_run(source.id, SyncOutcome::Success, now - Duration::days(1), 5);
+            db.insert_source_sync_run(&old).await?;
+            db.insert_source_sync_run(&recent).await?;
+
+            let pruned = db.prune_source_sync_runs(source.id, now - Duration::days(90)).await?;
+            assert_eq!(pruned, 1);
+            let (_, body) = get(&ctx.app, &uri, &token).await;
+            let runs = body["runs"].as_array().unwrap();
+            assert_eq!(runs.len(), 1);
+            assert_eq!(runs[0]["id"], recent.id.to_string());
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
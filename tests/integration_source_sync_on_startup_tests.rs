@@ -0,0 +1,101 @@
+//! Integration tests for sources that sync as soon as the scheduler starts.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use readur::models::{CreateSource, SourceType};
+    use readur::scheduling::source_scheduler::SourceScheduler;
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn local_folder_source(name: &str, watch_folder: &str, sync_on_startup: bool) -> CreateSource {
+        CreateSource {
+            name: name.to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: json!({
+                "watch_folders": [watch_folder],
+                "file_extensions": ["pdf"],
+                "auto_sync": true,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false,
+                "sync_on_startup": sync_on_startup
+            }),
+        }
+    }
+
+    /// Marks a source as synced just now, so its interval isn't due
+    async fn mark_synced(ctx: &TestContext, source_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE sources SET last_sync_at = NOW() WHERE id = $1")
+            .bind(source_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flagged_sources_are_synced_at_startup_only() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let watch_folder = tempfile::tempdir()?;
+            let watch_folder = watch_folder.path().to_str().unwrap();
+
+            let flagged = db.create_source(user_id, &local_folder_source("Scans", watch_folder, true)).await?;
+            let scheduled = db.create_source(user_id, &local_folder_source("Archive", watch_folder, false)).await?;
+            mark_synced(&ctx, flagged.id).await?;
+            mark_synced(&ctx, scheduled.id).await?;
+
+            let scheduler = SourceScheduler::new(ctx.state().clone());
+            let started = scheduler.check_and_sync_sources(true).await.map_err(|e| anyhow::anyhow!(e))?;
+            assert_eq!(started, vec![flagged.id]);
+
+            // Later checks wait for the interval again
+            let started = scheduler.check_and_sync_sources(false).await.map_err(|e| anyhow::anyhow!(e))?;
+            assert!(started.is_empty(), "unexpected syncs: {:?}", started);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_startup_sync_skips_disabled_sources() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let watch_folder = tempfile::tempdir()?;
+
+            let mut source = local_folder_source("Scans", watch_folder.path().to_str().unwrap(), true);
+            source.enabled = Some(false);
+            let source = db.create_source(user.user_response.id, &source).await?;
+            mark_synced(&ctx, source.id).await?;
+
+            let scheduler = SourceScheduler::new(ctx.state().clone());
+            let started = scheduler.check_and_sync_sources(true).await.map_err(|e| anyhow::anyhow!(e))?;
+            assert!(started.is_empty(), "unexpected syncs: {:?}", started);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,204 @@
+//! Integration tests for resetting a source's sync tracking state.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chrono::Utc;
+    use readur::models::{
+        CreateSource, CreateUser, CreateWebDAVDirectory, SourceType, UpdateSettings, UpdateWebDAVSyncState,
+        UserRole, WebDAVSourceConfig,
+    };
+    use readur::test_utils::TestContext;
+    use uuid::Uuid;
+
+    fn create_test_user_data(suffix: &str) -> CreateUser {
+        let test_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+            .to_string();
+        let unique_suffix = &test_id[test_id.len().saturating_sub(8)..];
+
+        CreateUser {
+            username: format!("testuser_{}_{}", suffix, unique_suffix),
+            email: format!("test_{}_{}@example.com", suffix, unique_suffix),
+            password: "password123".to_string(),
+            role: Some(UserRole::User),
+        }
+    }
+
+    fn webdav_source(watch_folders: Vec<String>) -> CreateSource {
+        let config = WebDAVSourceConfig {
+            server_url: "https://cloud.example.com".to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders,
+            file_extensions: vec!["pdf".to_string()],
+            auto_sync: false,
+            sync_interval_minutes: 60,
+            server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            propfind_depths: Default::default(),
+            download_verification: Default::default(),
+            max_directory_visits: 5,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            conditional_downloads: true,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+
+        CreateSource {
+            name: "Archive".to_string(),
+            source_type: SourceType::WebDAV,
+            enabled: Some(true),
+            config: serde_json::to_value(config).unwrap(),
+        }
+    }
+
+    fn directory(user_id: Uuid, path: &str) -> CreateWebDAVDirectory {
+        CreateWebDAVDirectory {
+            user_id,
+            directory_path: path.to_string(),
+            directory_etag: format!("etag-{}", path),
+            file_count: 3,
+            total_size_bytes: 1024,
+        }
+    }
+
+    /// Points the user's settings based WebDAV sync at the test server and gives
+    /// it a sync cursor
+    async fn start_settings_sync(ctx: &TestContext, user_id: Uuid, watch_folders: Vec<String>) -> Result<()> {
+        let db = &ctx.state.db;
+        let settings = UpdateSettings {
+            webdav_server_url: Some(Some("https://cloud.example.com/".to_string())),
+            webdav_watch_folders: Some(watch_folders),
+            ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+        };
+        db.create_or_update_settings(user_id, &settings).await?;
+        db.update_webdav_sync_state(user_id, &UpdateWebDAVSyncState {
+            last_sync_at: Some(Utc::now()),
+            sync_cursor: Some("cursor-42".to_string()),
+            is_running: false,
+            files_processed: 10,
+            files_remaining: 0,
+            current_folder: None,
+            errors: Vec::new(),
+        }).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reset_clears_tracking_under_watch_folders_only() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("reset1")).await?;
+            let source = db.create_source(user.id, &webdav_source(vec!["/Documents/".to_string()])).await?;
+            db.update_source_sync_stats(source.id, 10, 0, 4096).await?;
+
+            for path in ["/Documents", "/Documents/2024", "/Documents/2024/Taxes", "/Documents_old", "/Photos"] {
+                db.create_or_update_webdav_directory(&directory(user.id, path)).await?;
+            }
+            start_settings_sync(&ctx, user.id, vec!["/Documents/2024".to_string()]).await?;
+
+            let reset = db
+                .reset_source_sync_state(user.id, source.id, &["/Documents/".to_string()], Some("https://cloud.example.com"))
+                .await?
+                .expect("Source should exist");
+            assert_eq!(reset.directory_etags_cleared, 3);
+            assert!(reset.sync_cursor_cleared);
+            assert!(reset.previous_last_sync_at.is_some());
+
+            let remaining: Vec<String> = db
+                .list_webdav_directories(user.id)
+                .await?
+                .into_iter()
+                .map(|dir| dir.directory_path)
+                .collect();
+            assert_eq!(remaining.len(), 2);
+            assert!(remaining.contains(&"/Documents_old".to_string()));
+            assert!(remaining.contains(&"/Photos".to_string()));
+
+            let sync_state = db.get_webdav_sync_state(user.id).await?.unwrap();
+            assert!(sync_state.sync_cursor.is_none());
+            assert!(sync_state.last_sync_at.is_none());
+
+            let source = db.get_source(user.id, source.id).await?.unwrap();
+            assert!(source.last_sync_at.is_none());
+            assert_eq!(source.total_files_synced, 10);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reset_keeps_the_sync_cursor_of_other_folders_and_servers() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let user = db.create_user(create_test_user_data("reset4")).await?;
+            let source = db.create_source(user.id, &webdav_source(vec!["/Documents".to_string()])).await?;
+            start_settings_sync(&ctx, user.id, vec!["/Photos".to_string()]).await?;
+
+            let reset = db
+                .reset_source_sync_state(user.id, source.id, &["/Documents".to_string()], Some("https://cloud.example.com"))
+                .await?
+                .expect("Source should exist");
+            assert!(!reset.sync_cursor_cleared, "the cursor of a sync of other folders must be kept");
+
+            // The same folders on another server belong to another sync as well
+            start_settings_sync(&ctx, user.id, vec!["/Documents".to_string()]).await?;
+            let reset = db
+                .reset_source_sync_state(user.id, source.id, &["/Documents".to_string()], Some("https://files.example.org"))
+                .await?
+                .expect("Source should exist");
+            assert!(!reset.sync_cursor_cleared);
+
+            let sync_state = db.get_webdav_sync_state(user.id).await?.unwrap();
+            assert_eq!(sync_state.sync_cursor.as_deref(), Some("cursor-42"));
+            assert!(sync_state.last_sync_at.is_some());
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reset_unknown_source_returns_none() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let owner = db.create_user(create_test_user_data("reset2")).await?;
+            let other = db.create_user(create_test_user_data("reset3")).await?;
+            let source = db.create_source(owner.id, &webdav_source(vec!["/".to_string()])).await?;
+
+            assert!(db.reset_source_sync_state(other.id, source.id, &["/".to_string()], Some("https://cloud.example.com")).await?.is_none());
+            assert!(db.reset_source_sync_state(owner.id, Uuid::new_v4(), &[], None).await?.is_none());
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
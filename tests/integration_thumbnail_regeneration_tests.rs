@@ -0,0 +1,160 @@
+//! Integration tests for regenerating thumbnails after the thumbnail size changes.
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::Document;
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestConfigBuilder, TestContext};
+    use std::time::Duration;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    /// Stores a 400x300 PNG as a new document of `user_id` with a thumbnail made at 200 pixels
+    async fn create_image_document(ctx: &TestContext, user_id: Uuid) -> Result<Document> {
+        let image = image::RgbImage::from_fn(400, 300, |x, y| image::Rgb([(x % 256) as u8, (y % 256) as u8, 60]));
+        let mut png = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+
+        let mut document = create_test_document(user_id);
+        document.filename = "photo.png".to_string();
+        document.original_filename = "photo.png".to_string();
+        document.mime_type = "image/png".to_string();
+        document.file_size = png.len() as i64;
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document.file_path = ctx.state.file_service.save_document_file(user_id, document.id, "photo.png", &png).await?;
+
+        let document = ctx.state.db.create_document(document).await?;
+        let old_size = (*ctx.state.file_service).clone().with_thumbnail_size(200);
+        let thumbnail = old_size.get_or_generate_thumbnail(document.user_id, &document.file_path, &document.original_filename).await?;
+        assert_eq!(longest_side(&thumbnail), 200);
+        ctx.state.db.set_document_thumbnail_size(document.id, 200).await?;
+        Ok(document)
+    }
+
+    fn request(method: &str, uri: &str, token: &str) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    fn longest_side(thumbnail: &[u8]) -> u32 {
+        let thumbnail = image::load_from_memory(thumbnail).unwrap();
+        thumbnail.width().max(thumbnail.height())
+    }
+
+    #[tokio::test]
+    async fn test_thumbnail_at_an_old_size_is_regenerated_when_requested() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_thumbnail_size(64)).await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let document = create_image_document(&ctx, user.user_response.id).await?;
+
+            let response = ctx
+                .app
+                .clone()
+                .oneshot(request("GET", &format!("/api/documents/{}/thumbnail", document.id), &token))
+                .await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let thumbnail = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            assert_eq!(longest_side(&thumbnail), 64);
+            assert_eq!(ctx.state.db.get_document_thumbnail_size(document.id).await?, Some(64));
+
+            // The regenerated thumbnail is cached
+            let cached = ctx
+                .state
+                .file_service
+                .get_or_generate_thumbnail(document.user_id, &document.file_path, &document.original_filename)
+                .await?;
+            assert_eq!(longest_side(&cached), 64);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_admin_regenerates_thumbnails_in_the_background() {
+        let ctx = TestContext::with_config(TestConfigBuilder::default().with_thumbnail_size(64)).await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_token = auth_helper.login_user(&user.username, "password123").await;
+            let admin = auth_helper.create_admin_user().await;
+            let admin_token = auth_helper.login_user(&admin.username, "adminpass123").await;
+            let first = create_image_document(&ctx, user.user_response.id).await?;
+            let second = create_image_document(&ctx, user.user_response.id).await?;
+
+            let response = ctx
+                .app
+                .clone()
+                .oneshot(request("POST", "/api/admin/thumbnails/regenerate", &user_token))
+                .await?;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+            let response = ctx
+                .app
+                .clone()
+                .oneshot(request("POST", "/api/admin/thumbnails/regenerate", &admin_token))
+                .await?;
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            let started: serde_json::Value = serde_json::from_slice(&body)?;
+            assert_eq!(started["running"], true);
+            assert_eq!(started["thumbnail_size"], 64);
+            assert!(started["total"].as_i64().unwrap() >= 2);
+
+            let progress = tokio::time::timeout(Duration::from_secs(30), async {
+                loop {
+                    let response = ctx
+                        .app
+                        .clone()
+                        .oneshot(request("GET", "/api/admin/thumbnails/regenerate", &admin_token))
+                        .await
+                        .unwrap();
+                    assert_eq!(response.status(), StatusCode::OK);
+                    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                    let progress: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    if progress["running"] == false {
+                        break progress;
+                    }
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            })
+            .await
+            .expect("regeneration finishes");
+            assert!(progress["regenerated"].as_i64().unwrap() >= 2);
+            assert!(progress["finished_at"].is_string());
+
+            for document in [&first, &second] {
+                assert_eq!(ctx.state.db.get_document_thumbnail_size(document.id).await?, Some(64));
+                let thumbnail = ctx
+                    .state
+                    .file_service
+                    .get_or_generate_thumbnail(document.user_id, &document.file_path, &document.original_filename)
+                    .await?;
+                assert_eq!(longest_side(&thumbnail), 64);
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+//! Integration tests for accent-insensitive full-text search.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::http::StatusCode;
+    use readur::models::{Document, SearchMode, SearchRequest, UpdateSettings, UserRole};
+    use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+    use std::collections::HashSet;
+    use tower::util::ServiceExt;
+    use uuid::Uuid;
+
+    fn document_with_text(user_id: Uuid, content: &str) -> Document {
+        let mut document = create_test_document(user_id);
+        document.content = Some(content.to_string());
+        document.ocr_text = None;
+        document.ocr_status = Some("completed".to_string());
+        document.file_hash = Some(format!("{:x}", Uuid::new_v4().as_u128()));
+        document
+    }
+
+    fn search(query: &str, mode: SearchMode, ignore_accents: bool, language: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            tags: None,
+            mime_types: None,
+            limit: Some(50),
+            offset: Some(0),
+            include_snippets: Some(true),
+            snippet_length: Some(100),
+            search_mode: Some(mode),
+            include_shared: None,
+            include_notes: Some(false),
+            ignore_accents: Some(ignore_accents),
+            language: Some(language.to_string()),
+            document_date_from: None,
+            document_date_to: None,
+            collapse_duplicates: None,
+        }
+    }
+
+    async fn matching_ids(db: &readur::db::Database, user_id: Uuid, request: &SearchRequest) -> Result<HashSet<Uuid>> {
+        let results = db.enhanced_search_documents_with_role(user_id, UserRole::User, request).await?;
+        let total = db.count_search_documents(user_id, UserRole::User, request).await?;
+        assert_eq!(total as usize, results.len(), "count disagrees with results");
+        Ok(results.into_iter().map(|document| document.id).collect())
+    }
+
+    #[tokio::test]
+    async fn test_accents_are_ignored_at_index_and_query_time() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user_id = auth_helper.create_test_user().await.user_response.id;
+
+            let accented = db.create_document(document_with_text(user_id, "Receipt from the Café Müller in Zürich")).await?;
+            let plain = db.create_document(document_with_text(user_id, "Receipt from the cafe next door")).await?;
+            let both = HashSet::from([accented.id, plain.id]);
+
+            for query in ["cafe", "café", "CAFÉ"] {
+                let ids = matching_ids(db, user_id, &search(query, SearchMode::Simple, true, "english")).await?;
+                assert_eq!(ids, both, "'{}' should match with and without accents", query);
+            }
+            let ids = matching_ids(db, user_id, &search("zurich", SearchMode::Phrase, true, "german")).await?;
+            assert_eq!(ids, HashSet::from([accented.id]));
+
+            // With accents significant, "cafe" and "café" are different words
+            let ids = matching_ids(db, user_id, &search("cafe", SearchMode::Simple, false, "english")).await?;
+            assert_eq!(ids, HashSet::from([plain.id]));
+            let ids = matching_ids(db, user_id, &search("café", SearchMode::Simple, false, "english")).await?;
+            assert_eq!(ids, HashSet::from([accented.id]));
+
+            // Snippets highlight the accented spelling a plain query matched
+            let results = db
+                .enhanced_search_documents_with_role(user_id, UserRole::User, &search("muller", SearchMode::Simple, true, "simple"))
+                .await?;
+            let snippet = &results[0].snippets[0];
+            let range = &snippet.highlight_ranges[0];
+            assert_eq!(&snippet.text[range.start as usize..range.end as usize], "Müller");
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_endpoint_follows_accent_setting() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let db = &ctx.state.db;
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let user_id = user.user_response.id;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+            let accented = db.create_document(document_with_text(user_id, "Crème brûlée recipe")).await?;
+
+            let search = |uri: &str| {
+                let request = axum::http::Request::builder()
+                    .method("GET")
+                    .uri(uri)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(axum::body::Body::empty())
+                    .unwrap();
+                let app = ctx.app.clone();
+                async move {
+                    let response = app.oneshot(request).await.unwrap();
+                    let status = response.status();
+                    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                    (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap_or(serde_json::Value::Null))
+                }
+            };
+            let found = |response: &serde_json::Value| {
+                response["documents"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|document| document["id"] == accented.id.to_string())
+            };
+
+            // Accents are ignored by default
+            let (status, response) = search("/api/search/enhanced?query=creme%20brulee").await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(found(&response));
+
+            let settings = UpdateSettings {
+                search_ignore_accents: Some(false),
+                ..UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string())
+            };
+            db.create_or_update_settings(user_id, &settings).await?;
+            let (_, response) = search("/api/search/enhanced?query=creme%20brulee").await;
+            assert!(!found(&response));
+
+            // A search can still ask for it
+            let (_, response) = search("/api/search/enhanced?query=creme%20brulee&ignore_accents=true").await;
+            assert!(found(&response));
+
+            let (status, _) = search("/api/search?query=creme&language=klingon").await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
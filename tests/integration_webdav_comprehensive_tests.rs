@@ -33,6 +33,9 @@ async fn test_webdav_service_with_custom_retry() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let retry_config = RetryConfig {
@@ -42,6 +45,7 @@ async fn test_webdav_service_with_custom_retry() {
         backoff_multiplier: 1.5,
         timeout_seconds: 60,
         rate_limit_backoff_ms: 5000,
+        status_policies: RetryConfig::default_status_policies(),
     };
 
     let result = WebDAVService::new_with_retry(config, retry_config);
@@ -58,6 +62,9 @@ fn test_webdav_config_builder() {
         file_extensions: vec!["pdf".to_string(), "png".to_string(), "jpg".to_string()],
         timeout_seconds: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     // Test Nextcloud URL construction
@@ -251,6 +258,9 @@ fn test_webdav_url_construction() {
             file_extensions: vec!["pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some(server_type.to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
 
         let service = WebDAVService::new(config);
@@ -291,6 +301,9 @@ fn test_settings_webdav_integration() {
             file_extensions: settings.webdav_file_extensions.clone(),
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
 
         assert_eq!(webdav_config.server_url, "https://nextcloud.example.com");
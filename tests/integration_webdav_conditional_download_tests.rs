@@ -0,0 +1,148 @@
+//! Integration tests for conditional WebDAV downloads of files a sync already stored.
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        http::{header, HeaderMap, StatusCode},
+        response::IntoResponse,
+        Router,
+    };
+    use chrono::{TimeZone, Utc};
+    use readur::models::{FileIngestionInfo, SourceFileVersion};
+    use readur::services::webdav::{ConditionalDownload, WebDAVConfig, WebDAVService};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const CONTENT: &[u8] = b"%PDF-1.4 quarterly report";
+    const LAST_MODIFIED: &str = "Tue, 14 May 2024 09:30:00 GMT";
+
+    async fn start_server(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", address)
+    }
+
+    /// Honors `If-None-Match`, counting the full responses it sends
+    async fn start_conditional_server(full_responses: Arc<AtomicUsize>) -> String {
+        let app = Router::new().fallback(move |headers: HeaderMap| {
+            let full_responses = full_responses.clone();
+            async move {
+                if headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) == Some("\"v1\"") {
+                    return StatusCode::NOT_MODIFIED.into_response();
+                }
+                full_responses.fetch_add(1, Ordering::SeqCst);
+                ([(header::ETAG, "\"v1\"")], CONTENT).into_response()
+            }
+        });
+        start_server(app).await
+    }
+
+    /// Ignores conditional headers and always sends the file, optionally with an ETag
+    async fn start_unconditional_server(etag: Option<&'static str>) -> String {
+        let app = Router::new().fallback(move || async move {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::LAST_MODIFIED, LAST_MODIFIED.parse().unwrap());
+            if let Some(etag) = etag {
+                headers.insert(header::ETAG, etag.parse().unwrap());
+            }
+            (headers, CONTENT).into_response()
+        });
+        start_server(app).await
+    }
+
+    fn service(server_url: String) -> WebDAVService {
+        WebDAVService::new(WebDAVConfig {
+            server_url,
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        })
+        .expect("Failed to create WebDAV service")
+    }
+
+    #[allow(deprecated)]
+    fn file_info() -> FileIngestionInfo {
+        FileIngestionInfo {
+            relative_path: "/Documents/report.pdf".to_string(),
+            full_path: "/Documents/report.pdf".to_string(),
+            path: "/Documents/report.pdf".to_string(),
+            name: "report.pdf".to_string(),
+            size: CONTENT.len() as i64,
+            mime_type: "application/pdf".to_string(),
+            last_modified: Some(Utc.with_ymd_and_hms(2024, 5, 14, 9, 30, 0).unwrap()),
+            etag: "v1".to_string(),
+            is_directory: false,
+            created_at: None,
+            permissions: None,
+            owner: None,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    fn known(etag: &str, size: i64) -> SourceFileVersion {
+        SourceFileVersion {
+            etag: etag.to_string(),
+            size,
+            last_modified: Some(Utc.with_ymd_and_hms(2024, 5, 14, 9, 30, 0).unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_matching_etag_gets_304_and_is_skipped() {
+        let full_responses = Arc::new(AtomicUsize::new(0));
+        let service = service(start_conditional_server(full_responses.clone()).await);
+
+        let download = service.download_file_info(&file_info(), Some(&known("v1", CONTENT.len() as i64))).await.unwrap();
+        assert!(matches!(download, ConditionalDownload::Unchanged));
+        assert_eq!(full_responses.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_changed_etag_downloads_the_file() {
+        let full_responses = Arc::new(AtomicUsize::new(0));
+        let service = service(start_conditional_server(full_responses.clone()).await);
+
+        let download = service.download_file_info(&file_info(), Some(&known("v0", 10))).await.unwrap();
+        assert_eq!(download.into_modified().as_deref(), Some(CONTENT));
+        assert_eq!(full_responses.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_first_sync_downloads_unconditionally() {
+        let full_responses = Arc::new(AtomicUsize::new(0));
+        let service = service(start_conditional_server(full_responses.clone()).await);
+
+        let download = service.download_file_info(&file_info(), None).await.unwrap();
+        assert_eq!(download.into_modified().as_deref(), Some(CONTENT));
+    }
+
+    #[tokio::test]
+    async fn test_server_ignoring_if_none_match_is_judged_by_response_etag() {
+        let service = service(start_unconditional_server(Some("W/\"v1\"")).await);
+        let download = service.download_file_info(&file_info(), Some(&known("v1", 999))).await.unwrap();
+        assert!(matches!(download, ConditionalDownload::Unchanged));
+
+        let download = service.download_file_info(&file_info(), Some(&known("v0", CONTENT.len() as i64))).await.unwrap();
+        assert!(matches!(download, ConditionalDownload::Modified(_)));
+    }
+
+    #[tokio::test]
+    async fn test_server_without_etags_falls_back_to_size_and_mtime() {
+        let service = service(start_unconditional_server(None).await);
+        let download = service.download_file_info(&file_info(), Some(&known("v1", CONTENT.len() as i64))).await.unwrap();
+        assert!(matches!(download, ConditionalDownload::Unchanged));
+
+        let download = service.download_file_info(&file_info(), Some(&known("v1", 10))).await.unwrap();
+        assert_eq!(download.into_modified().as_deref(), Some(CONTENT));
+    }
+}
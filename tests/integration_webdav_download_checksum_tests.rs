@@ -0,0 +1,152 @@
+//! Integration tests for checking WebDAV downloads against the checksums the server sends.
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use readur::ingestion::spool::{DownloadedFile, SpoolPolicy};
+    use readur::models::{ChecksumVerification, DownloadVerification, FileIngestionInfo};
+    use readur::services::webdav::{ConditionalDownload, WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const CONTENT: &[u8] = b"%PDF-1.4 quarterly report";
+    /// CONTENT with one flipped byte, as a corrupted transfer delivers it
+    const CORRUPTED: &[u8] = b"%PDF-1.4 quarterly repurt";
+    const FILE_PATH: &str = "/Documents/report.pdf";
+
+    fn oc_checksum() -> String {
+        use sha1::{Digest, Sha1};
+        format!("SHA1:{:x}", Sha1::digest(CONTENT))
+    }
+
+    /// Serves `bodies` in turn, each with the checksum of CONTENT, then keeps
+    /// serving the last one
+    async fn start_server(bodies: &[&'static [u8]], checksum: Option<String>) -> MockServer {
+        let server = MockServer::start().await;
+        for (index, body) in bodies.iter().enumerate() {
+            let mut response = ResponseTemplate::new(200).set_body_bytes(*body);
+            if let Some(checksum) = &checksum {
+                response = response.insert_header("OC-Checksum", checksum.as_str());
+            }
+            let mock = Mock::given(method("GET")).and(path(FILE_PATH)).respond_with(response);
+            let mock = if index + 1 < bodies.len() { mock.up_to_n_times(1) } else { mock };
+            mock.mount(&server).await;
+        }
+        server
+    }
+
+    fn service(server_url: String, checksums: ChecksumVerification) -> WebDAVService {
+        WebDAVService::new(WebDAVConfig {
+            server_url,
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        })
+        .expect("Failed to create WebDAV service")
+        .with_download_verification(DownloadVerification { checksums, retries: 2 })
+    }
+
+    #[allow(deprecated)]
+    fn file_info() -> FileIngestionInfo {
+        FileIngestionInfo {
+            relative_path: FILE_PATH.to_string(),
+            full_path: FILE_PATH.to_string(),
+            path: FILE_PATH.to_string(),
+            name: "report.pdf".to_string(),
+            size: CONTENT.len() as i64,
+            mime_type: "application/pdf".to_string(),
+            last_modified: Some(Utc::now()),
+            etag: "v1".to_string(),
+            is_directory: false,
+            created_at: None,
+            permissions: None,
+            owner: None,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    async fn downloads(server: &MockServer) -> usize {
+        let requests = server.received_requests().await.unwrap_or_default();
+        requests.iter().filter(|request| request.method.as_str() == "GET").count()
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_download_is_detected_and_retried() {
+        let server = start_server(&[CORRUPTED, CONTENT], Some(oc_checksum())).await;
+        let service = service(server.uri(), ChecksumVerification::Verify);
+
+        let download = service.download_file_info(&file_info(), None).await.unwrap();
+
+        assert_eq!(download.into_modified().as_deref(), Some(CONTENT));
+        assert_eq!(downloads(&server).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_download_fails_when_every_retry_is_corrupted() {
+        let server = start_server(&[CORRUPTED], Some(oc_checksum())).await;
+        let service = service(server.uri(), ChecksumVerification::Verify);
+
+        let error = service.download_file_info(&file_info(), None).await.unwrap_err().to_string();
+
+        assert!(error.contains("Checksum mismatch downloading '/Documents/report.pdf'"), "{}", error);
+        assert!(error.contains("OC-Checksum"), "{}", error);
+        assert!(error.contains("giving up after 3 attempts"), "{}", error);
+        assert_eq!(downloads(&server).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_spooled_download_is_retried_without_leaving_the_corrupted_file() {
+        let server = start_server(&[CORRUPTED, CONTENT], Some(oc_checksum())).await;
+        let service = service(server.uri(), ChecksumVerification::Verify);
+        let spool_dir = tempfile::tempdir().unwrap();
+        let policy = SpoolPolicy {
+            threshold_bytes: 8,
+            directory: spool_dir.path().to_path_buf(),
+        };
+
+        let download = service.download_file_spooled_if_changed(FILE_PATH, None, &policy).await.unwrap();
+
+        let ConditionalDownload::Modified(DownloadedFile::Spooled(file)) = download else {
+            panic!("expected a spooled download");
+        };
+        assert_eq!(std::fs::read(file.path()).unwrap(), CONTENT);
+        assert_eq!(std::fs::read_dir(spool_dir.path()).unwrap().count(), 1);
+        assert_eq!(downloads(&server).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_strictness_settings() {
+        // Warn keeps a corrupted download without retrying
+        let server = start_server(&[CORRUPTED], Some(oc_checksum())).await;
+        let download = service(server.uri(), ChecksumVerification::Warn)
+            .download_file_info(&file_info(), None)
+            .await
+            .unwrap();
+        assert_eq!(download.into_modified().as_deref(), Some(CORRUPTED));
+        assert_eq!(downloads(&server).await, 1);
+
+        // Verify accepts a file without a checksum, require rejects it
+        let server = start_server(&[CONTENT], None).await;
+        let download = service(server.uri(), ChecksumVerification::Verify)
+            .download_file_info(&file_info(), None)
+            .await
+            .unwrap();
+        assert_eq!(download.into_modified().as_deref(), Some(CONTENT));
+
+        let error = service(server.uri(), ChecksumVerification::Require)
+            .download_file_info(&file_info(), None)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("sent no checksum"), "{}", error);
+    }
+}
@@ -0,0 +1,123 @@
+//! Integration tests for the limit on concurrent downloads shared by WebDAV sources on one host.
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use readur::services::webdav::host_limits::host_key;
+    use readur::services::webdav::{
+        ConcurrencyConfig, HostDownloadLimiter, RetryConfig, WebDAVConfig, WebDAVService,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const CONTENT: &[u8] = b"%PDF-1.4 shared server";
+
+    /// Downloads the server is answering right now, and the most it ever answered at once
+    #[derive(Default)]
+    struct Concurrency {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+        total: AtomicUsize,
+    }
+
+    /// Answers every request after a delay, recording how many overlap
+    async fn start_slow_server(concurrency: Arc<Concurrency>) -> String {
+        let app = Router::new().fallback(move || {
+            let concurrency = concurrency.clone();
+            async move {
+                let now = concurrency.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                concurrency.peak.fetch_max(now, Ordering::SeqCst);
+                concurrency.total.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(150)).await;
+                concurrency.in_flight.fetch_sub(1, Ordering::SeqCst);
+                CONTENT
+            }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", address)
+    }
+
+    /// A source of `username` on `server_url` allowed 4 downloads of its own at once
+    fn source(server_url: &str, username: &str, limiter: Arc<HostDownloadLimiter>) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: username.to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        };
+        let concurrency = ConcurrencyConfig {
+            max_concurrent_downloads: 4,
+            ..ConcurrencyConfig::default()
+        };
+        WebDAVService::new_with_configs(config, RetryConfig::default(), concurrency)
+            .expect("Failed to create WebDAV service")
+            .with_host_download_limiter(limiter)
+    }
+
+    /// Downloads 5 files with each source at the same time
+    async fn download_from_both(alice: &WebDAVService, bob: &WebDAVService) {
+        let alice_files: Vec<String> = (0..5).map(|i| format!("/Documents/alice-{}.pdf", i)).collect();
+        let bob_files: Vec<String> = (0..5).map(|i| format!("/Documents/bob-{}.pdf", i)).collect();
+        let downloads = alice_files
+            .iter()
+            .map(|path| alice.download_file(path))
+            .chain(bob_files.iter().map(|path| bob.download_file(path)));
+        for result in futures::future::join_all(downloads).await {
+            assert_eq!(result.expect("Download failed"), CONTENT);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sources_on_the_same_host_share_the_download_limit() {
+        let concurrency = Arc::new(Concurrency::default());
+        let server_url = start_slow_server(concurrency.clone()).await;
+        let limiter = Arc::new(HostDownloadLimiter::new(2));
+
+        let alice = source(&server_url, "alice", limiter.clone());
+        let bob = source(&format!("{}/", server_url), "bob", limiter.clone());
+        download_from_both(&alice, &bob).await;
+
+        assert_eq!(concurrency.total.load(Ordering::SeqCst), 10);
+        // Each source alone may download 4 files at once, both together only 2
+        assert_eq!(concurrency.peak.load(Ordering::SeqCst), 2);
+        assert_eq!(limiter.available_permits(&server_url), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_zero_disables_the_per_host_limit() {
+        let concurrency = Arc::new(Concurrency::default());
+        let server_url = start_slow_server(concurrency.clone()).await;
+        let limiter = Arc::new(HostDownloadLimiter::new(0));
+
+        let alice = source(&server_url, "alice", limiter.clone());
+        let bob = source(&server_url, "bob", limiter.clone());
+        download_from_both(&alice, &bob).await;
+
+        assert_eq!(concurrency.total.load(Ordering::SeqCst), 10);
+        assert!(concurrency.peak.load(Ordering::SeqCst) > 4);
+        assert_eq!(limiter.available_permits(&server_url), None);
+    }
+
+    #[test]
+    fn test_host_key_ignores_path_case_and_default_port() {
+        assert_eq!(
+            host_key("https://Cloud.Example.com/remote.php/dav/files/alice"),
+            host_key("https://cloud.example.com:443/remote.php/dav/files/bob/"),
+        );
+        assert_eq!(host_key("cloud.example.com"), "https://cloud.example.com:443");
+        assert_ne!(host_key("https://cloud.example.com"), host_key("https://cloud.example.com:8443"));
+        assert_ne!(host_key("http://cloud.example.com"), host_key("https://cloud.example.com"));
+        assert_ne!(host_key("https://cloud.example.com"), host_key("https://files.example.com"));
+    }
+}
@@ -76,6 +76,35 @@ fn create_empty_update_settings() -> UpdateSettings {
         // Office document extraction configuration
         office_extraction_timeout_seconds: None,
         office_extraction_enable_detailed_logging: None,
+        ocr_auto_psm: None,
+        ocr_quick_preview_pages: None,
+        notification_delivery_mode: None,
+        notification_digest_interval_minutes: None,
+        ocr_max_pixels: None,
+        ocr_pdf_hybrid: None,
+        ocr_reprocess_on_settings_change: None,
+        auto_label_language: None,
+        extract_document_date: None,
+        document_date_priority: None,
+        document_date_rule: None,
+        document_date_order: None,
+        search_include_notes: None,
+        ocr_verify_orientation: None,
+        auto_split_on_separator: None,
+        auto_split_barcode: None,
+        expiry_reminder_days: None,
+        search_language: None,
+        search_ignore_accents: None,
+        extract_archives: None,
+        keep_original_pdf: None,
+        decode_barcodes: None,
+        search_collapse_duplicates: None,
+        ocr_incremental_pages: None,
+        parse_front_matter: None,
+        extract_tables: None,
+        doc_extraction_order: None,
+        pdf_extraction_order: None,
+        extraction_auto_order: None,
     }
 }
 
@@ -204,6 +233,35 @@ async fn setup_webdav_settings(state: &AppState, user_id: Uuid) {
         // Office document extraction configuration
         office_extraction_timeout_seconds: None,
         office_extraction_enable_detailed_logging: None,
+        ocr_auto_psm: None,
+        ocr_quick_preview_pages: None,
+        notification_delivery_mode: None,
+        notification_digest_interval_minutes: None,
+        ocr_max_pixels: None,
+        ocr_pdf_hybrid: None,
+        ocr_reprocess_on_settings_change: None,
+        auto_label_language: None,
+        extract_document_date: None,
+        document_date_priority: None,
+        document_date_rule: None,
+        document_date_order: None,
+        search_include_notes: None,
+        ocr_verify_orientation: None,
+        auto_split_on_separator: None,
+        auto_split_barcode: None,
+        expiry_reminder_days: None,
+        search_language: None,
+        search_ignore_accents: None,
+        extract_archives: None,
+        keep_original_pdf: None,
+        decode_barcodes: None,
+        search_collapse_duplicates: None,
+        ocr_incremental_pages: None,
+        parse_front_matter: None,
+        extract_tables: None,
+        doc_extraction_order: None,
+        pdf_extraction_order: None,
+        extraction_auto_order: None,
     };
 
     state.db.create_or_update_settings(user_id, &update_settings).await
@@ -412,8 +470,9 @@ async fn test_notifications_endpoints() {
     assert_eq!(response.status(), StatusCode::OK);
 
     let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
-    let notifications: Vec<Value> = serde_json::from_slice(&body).unwrap();
-    assert!(notifications.len() >= 1);
+    let notifications: Value = serde_json::from_slice(&body).unwrap();
+    assert!(notifications["notifications"].as_array().unwrap().len() >= 1);
+    assert!(notifications["pagination"]["total"].as_i64().unwrap() >= 1);
 
     // Test GET /api/notifications/summary
     let request = Request::builder()
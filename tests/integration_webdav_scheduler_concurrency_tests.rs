@@ -38,6 +38,17 @@ async fn create_test_webdav_source(
         auto_sync,
         sync_interval_minutes: 1, // Fast interval for testing
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let create_source = CreateSource {
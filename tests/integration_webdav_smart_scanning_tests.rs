@@ -11,6 +11,9 @@ fn create_test_config() -> WebDAVConfig {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     }
 }
 
@@ -38,6 +41,9 @@ async fn test_recursive_etag_support_detection() {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let service = WebDAVService::new(config).expect("Failed to create WebDAV service");
@@ -99,6 +105,9 @@ async fn test_etag_support_detection_capabilities() {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let service = WebDAVService::new(config).expect("Failed to create WebDAV service");
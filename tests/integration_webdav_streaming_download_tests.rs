@@ -0,0 +1,183 @@
+//! Integration tests for streaming large WebDAV downloads to disk instead of memory.
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use axum::{body::{Body, Bytes}, http::header, Router};
+    use chrono::Utc;
+    use readur::ingestion::document_ingestion::{DeduplicationPolicy, DocumentIngestionService, IngestionResult};
+    use readur::ingestion::spool::{DownloadSink, DownloadedFile, SpoolPolicy};
+    use readur::models::FileIngestionInfo;
+    use readur::services::webdav::{WebDAVConfig, WebDAVService};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use sha2::{Digest, Sha256};
+
+    const CHUNK_SIZE: usize = 1024 * 1024;
+
+    /// Serves a file of `chunks` MiB for any path, generated on the fly so the
+    /// server itself doesn't hold the file in memory
+    async fn start_large_file_server(chunks: usize) -> String {
+        let chunk = Bytes::from(vec![0x5A; CHUNK_SIZE]);
+        let app = Router::new().fallback(move || {
+            let chunk = chunk.clone();
+            async move {
+                let body = futures::stream::iter((0..chunks).map(move |_| Ok::<_, std::io::Error>(chunk.clone())));
+                ([(header::CONTENT_LENGTH, (chunks * CHUNK_SIZE).to_string())], Body::from_stream(body))
+            }
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", address)
+    }
+
+    fn expected_sha256(chunks: usize) -> String {
+        let chunk = vec![0x5A; CHUNK_SIZE];
+        let mut hasher = Sha256::new();
+        for _ in 0..chunks {
+            hasher.update(&chunk);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Reads a `VmRSS`/`VmHWM` style line from /proc/self/status, in kB
+    #[cfg(target_os = "linux")]
+    fn proc_status_kb(field: &str) -> Option<u64> {
+        std::fs::read_to_string("/proc/self/status")
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix(field)?.trim().strip_suffix("kB")?.trim().parse().ok())
+    }
+
+    #[tokio::test]
+    async fn test_large_download_is_spooled_with_bounded_memory() {
+        const CHUNKS: usize = 256;
+        let server_url = start_large_file_server(CHUNKS).await;
+        let service = WebDAVService::new(WebDAVConfig {
+            server_url,
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 300,
+            server_type: Some("generic".to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
+        })
+        .expect("Failed to create WebDAV service");
+
+        let spool_dir = tempfile::tempdir().unwrap();
+        let policy = SpoolPolicy::new(8, spool_dir.path().to_path_buf());
+
+        // Reset the peak RSS so it only covers the download
+        #[cfg(target_os = "linux")]
+        let _ = std::fs::write("/proc/self/clear_refs", "5");
+        #[cfg(target_os = "linux")]
+        let rss_before = proc_status_kb("VmRSS:");
+
+        let download = service
+            .download_file_spooled("/Documents/scan-archive.pdf", &policy)
+            .await
+            .expect("Download failed");
+
+        #[cfg(target_os = "linux")]
+        if let (Some(before), Some(peak)) = (rss_before, proc_status_kb("VmHWM:")) {
+            let growth_mb = peak.saturating_sub(before) / 1024;
+            assert!(
+                growth_mb < 64,
+                "downloading a {} MiB file grew peak memory by {} MiB",
+                CHUNKS,
+                growth_mb
+            );
+        }
+
+        let file = match download {
+            DownloadedFile::Spooled(file) => file,
+            DownloadedFile::InMemory(data) => panic!("{} byte download was not spooled", data.len()),
+        };
+        assert_eq!(file.size(), (CHUNKS * CHUNK_SIZE) as u64);
+        assert_eq!(std::fs::metadata(file.path()).unwrap().len(), file.size());
+        assert_eq!(file.sha256(), expected_sha256(CHUNKS));
+
+        let spool_path = file.path().to_path_buf();
+        drop(file);
+        assert!(!spool_path.exists(), "spooled download should be removed on drop");
+    }
+
+    #[tokio::test]
+    async fn test_spooled_download_is_ingested_from_disk_and_cleaned_up() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let state = ctx.state();
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+
+            let spool_dir = tempfile::tempdir()?;
+            let policy = SpoolPolicy::new(1, spool_dir.path().to_path_buf());
+            let content: Vec<u8> = (0..3 * CHUNK_SIZE).map(|i| (i % 251) as u8).collect();
+            let mut sink = DownloadSink::new(&policy, None).await?;
+            for chunk in content.chunks(64 * 1024) {
+                sink.write(chunk).await?;
+            }
+            let download = sink.finish().await?;
+            let spool_path = match &download {
+                DownloadedFile::Spooled(file) => file.path().to_path_buf(),
+                DownloadedFile::InMemory(_) => panic!("download over the threshold was not spooled"),
+            };
+
+            #[allow(deprecated)]
+            let file_info = FileIngestionInfo {
+                relative_path: "/Documents/large.pdf".to_string(),
+                full_path: "/Documents/large.pdf".to_string(),
+                path: "/Documents/large.pdf".to_string(),
+                name: "large.pdf".to_string(),
+                size: content.len() as i64,
+                mime_type: "application/pdf".to_string(),
+                last_modified: Some(Utc::now()),
+                etag: "large-etag".to_string(),
+                is_directory: false,
+                created_at: None,
+                permissions: None,
+                owner: None,
+                group: None,
+                metadata: None,
+            };
+
+            let ingestion_service = DocumentIngestionService::new(state.db.clone(), (*state.file_service).clone());
+            let result = ingestion_service
+                .ingest_download_from_file_info(
+                    &file_info,
+                    download,
+                    user.user_response.id,
+                    DeduplicationPolicy::Skip,
+                    "webdav_sync",
+                    None,
+                )
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+            let document = match result {
+                IngestionResult::Created(document) => document,
+                other => panic!("expected a new document, got {:?}", other),
+            };
+            assert_eq!(document.file_size, content.len() as i64);
+            assert_eq!(document.file_hash.as_deref(), Some(format!("{:x}", Sha256::digest(&content)).as_str()));
+            assert_eq!(std::fs::read(&document.file_path)?, content);
+            assert!(!spool_path.exists(), "spooled download should be removed after ingestion");
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
@@ -30,6 +30,9 @@ fn create_test_webdav_config() -> WebDAVConfig {
         file_extensions: vec![".pdf".to_string(), ".txt".to_string(), ".jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     }
 }
 
@@ -44,6 +47,21 @@ fn create_test_source_config() -> WebDAVSourceConfig {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     }
 }
 
@@ -345,6 +363,9 @@ fn test_server_type_detection() {
             file_extensions: vec![".pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some(server_type.to_string()),
+            max_scan_depth: None,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         if is_supported {
@@ -368,6 +389,9 @@ fn test_error_handling_scenarios() {
         file_extensions: vec![".pdf".to_string()],
         timeout_seconds: 1, // Very short timeout
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     assert_eq!(timeout_config.timeout_seconds, 1);
@@ -381,6 +405,9 @@ fn test_error_handling_scenarios() {
         file_extensions: vec![".pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     assert_eq!(auth_config.username, "invalid_user");
@@ -395,6 +422,9 @@ fn test_error_handling_scenarios() {
         file_extensions: vec![".pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     assert_eq!(invalid_path_config.watch_folders[0], "/nonexistent_folder");
@@ -58,7 +58,7 @@ async fn test_thumbnail_for_s3_prefixed_path() {
     );
 
     let thumb = file_service
-        .get_or_generate_thumbnail("s3://documents/pic.png", "pic.png")
+        .get_or_generate_thumbnail(uuid::Uuid::new_v4(), "s3://documents/pic.png", "pic.png")
         .await
         .expect("thumbnail generation should work for s3:// paths");
     assert!(!thumb.is_empty());
@@ -27,6 +27,16 @@ async fn test_s3_service_new_validation() {
         file_extensions: vec![],
         auto_sync: false,
         sync_interval_minutes: 0,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
 
     let result = S3Service::new(config).await;
@@ -61,6 +71,16 @@ async fn test_s3_service_configuration() {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         auto_sync: false,
         sync_interval_minutes: 60,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
     
     // This test verifies the configuration structure is correct
@@ -35,6 +35,17 @@ fn test_webdav_config_serialization() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let json_value = serde_json::to_value(&config).unwrap();
@@ -55,6 +66,11 @@ fn test_local_folder_config_serialization() {
         sync_interval_minutes: 30,
         recursive: true,
         follow_symlinks: false,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        track_file_ids: false,
     };
     
     let json_value = serde_json::to_value(&config).unwrap();
@@ -80,6 +96,16 @@ fn test_s3_config_serialization() {
         file_extensions: vec![".pdf".to_string(), ".docx".to_string()],
         auto_sync: true,
         sync_interval_minutes: 120,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
     
     let json_value = serde_json::to_value(&config).unwrap();
@@ -107,6 +133,17 @@ fn test_auto_sync_validation() {
             auto_sync: true,
             sync_interval_minutes: interval,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            propfind_depths: Default::default(),
+            download_verification: Default::default(),
+            max_directory_visits: 5,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            conditional_downloads: true,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         assert!(webdav_config.auto_sync);
@@ -135,6 +172,17 @@ fn test_file_extension_validation() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     for ext in &config.file_extensions {
@@ -156,6 +204,11 @@ fn test_watch_folder_validation() {
             sync_interval_minutes: 30,
             recursive: true,
             follow_symlinks: false,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            track_file_ids: false,
         };
         
         assert_eq!(config.watch_folders[0], folder);
@@ -184,6 +237,17 @@ fn test_server_type_validation() {
             auto_sync: true,
             sync_interval_minutes: 60,
             server_type: server_type.clone(),
+            max_scan_depth: None,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            propfind_depths: Default::default(),
+            download_verification: Default::default(),
+            max_directory_visits: 5,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            conditional_downloads: true,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         assert_eq!(config.server_type, server_type);
@@ -219,6 +283,16 @@ fn test_s3_bucket_name_validation() {
             file_extensions: vec![".pdf".to_string()],
             auto_sync: true,
             sync_interval_minutes: 120,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            connect_timeout_seconds: 10,
+            read_timeout_seconds: 60,
+            operation_timeout_seconds: 300,
+            max_retries: 3,
+            create_bucket_if_missing: false,
+            download_verification: Default::default(),
         };
 
         assert_eq!(config.bucket_name, bucket_name);
@@ -243,6 +317,7 @@ fn test_endpoint_url_handling() {
         file_extensions: vec![".pdf".to_string()],
         auto_sync: true,
         sync_interval_minutes: 120,
+        sync_on_startup: false,
     };
     
     assert!(aws_config.endpoint_url.is_none());
@@ -260,6 +335,16 @@ fn test_endpoint_url_handling() {
         file_extensions: vec![".pdf".to_string()],
         auto_sync: true,
         sync_interval_minutes: 120,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        connect_timeout_seconds: 10,
+        read_timeout_seconds: 60,
+        operation_timeout_seconds: 300,
+        max_retries: 3,
+        create_bucket_if_missing: false,
+        download_verification: Default::default(),
     };
     
     assert!(minio_config.endpoint_url.is_some());
@@ -291,6 +376,17 @@ fn test_sync_interval_ranges() {
             auto_sync: true,
             sync_interval_minutes: interval,
             server_type: Some("nextcloud".to_string()),
+            max_scan_depth: None,
+            sync_newest_first: false,
+            sync_on_startup: false,
+            propfind_depths: Default::default(),
+            download_verification: Default::default(),
+            max_directory_visits: 5,
+            processing_mode: Default::default(),
+            ocr_enabled: true,
+            conditional_downloads: true,
+            detect_etag_aliases: false,
+            user_agent: None,
         };
         
         assert_eq!(config.sync_interval_minutes, interval);
@@ -320,6 +416,17 @@ fn test_configuration_size_limits() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     let serialized = serde_json::to_string(&large_webdav_config).unwrap();
@@ -346,6 +453,17 @@ fn test_concurrent_configuration_access() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     });
     
     let mut handles = vec![];
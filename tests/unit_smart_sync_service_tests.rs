@@ -320,6 +320,9 @@ fn create_real_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     WebDAVService::new(config).expect("Failed to create WebDAV service")
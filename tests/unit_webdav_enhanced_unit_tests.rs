@@ -114,6 +114,9 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string(), "png".to_string(), "jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     assert!(WebDAVService::new(valid_config).is_ok());
@@ -127,6 +130,9 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     // Should fail early with enhanced validation
@@ -141,6 +147,9 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     assert!(WebDAVService::new(invalid_scheme_config).is_err());
@@ -154,6 +163,9 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     assert!(WebDAVService::new(relative_url_config).is_err());
@@ -170,6 +182,9 @@ fn test_webdav_url_construction_comprehensive() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let service = WebDAVService::new(nextcloud_config).unwrap();
@@ -184,6 +199,9 @@ fn test_webdav_url_construction_comprehensive() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 60,
         server_type: Some("owncloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     assert!(WebDAVService::new(owncloud_config).is_ok());
@@ -197,6 +215,9 @@ fn test_webdav_url_construction_comprehensive() {
         file_extensions: vec!["txt".to_string()],
         timeout_seconds: 45,
         server_type: None, // No server type = generic
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     assert!(WebDAVService::new(generic_config).is_ok());
@@ -212,6 +233,9 @@ fn test_webdav_response_parsing_comprehensive() {
         file_extensions: vec!["pdf".to_string(), "png".to_string(), "jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let service = WebDAVService::new(config.clone()).unwrap();
@@ -267,6 +291,9 @@ fn test_empty_folder_parsing() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let service = WebDAVService::new(config).unwrap();
@@ -289,6 +316,9 @@ fn test_malformed_xml_handling() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let service = WebDAVService::new(config).unwrap();
@@ -309,6 +339,7 @@ fn test_retry_config_custom_values() {
         backoff_multiplier: 1.5,
         timeout_seconds: 90,
         rate_limit_backoff_ms: 10000,
+        status_policies: RetryConfig::default_status_policies(),
     };
 
     assert_eq!(custom_retry.max_retries, 5);
@@ -326,6 +357,9 @@ fn test_retry_config_custom_values() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     assert!(WebDAVService::new_with_retry(config, custom_retry).is_ok());
@@ -551,6 +585,7 @@ fn test_connection_result_variants() {
         message: "Connected successfully to Nextcloud 28.0.1".to_string(),
         server_version: Some("28.0.1".to_string()),
         server_type: Some("nextcloud".to_string()),
+        clock_skew_seconds: None,
     };
 
     assert!(success_result.success);
@@ -563,6 +598,7 @@ fn test_connection_result_variants() {
         message: "Authentication failed: 401 Unauthorized".to_string(),
         server_version: None,
         server_type: None,
+        clock_skew_seconds: None,
     };
 
     assert!(!failure_result.success);
@@ -60,6 +60,9 @@ fn test_webdav_config_creation() {
         file_extensions: vec!["pdf".to_string(), "png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     assert_eq!(config.server_url, "https://cloud.example.com");
@@ -81,6 +84,9 @@ fn test_webdav_service_creation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let result = WebDAVService::new(config);
@@ -97,6 +103,9 @@ fn test_webdav_response_parsing() {
         file_extensions: vec!["pdf".to_string(), "png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let service = WebDAVService::new(config).unwrap();
@@ -172,6 +181,7 @@ fn test_webdav_models() {
         message: "Connection successful".to_string(),
         server_version: Some("28.0.1".to_string()),
         server_type: Some("nextcloud".to_string()),
+        clock_skew_seconds: None,
     };
 
     assert!(result.success);
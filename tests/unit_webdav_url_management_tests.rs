@@ -11,6 +11,9 @@ fn test_nextcloud_directory_path_handling() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -55,6 +58,9 @@ fn test_nextcloud_file_path_handling() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -99,6 +105,9 @@ fn test_webdav_root_path_handling() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -139,6 +148,9 @@ fn test_url_construction_from_relative_path() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -164,6 +176,9 @@ fn test_owncloud_path_handling() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("owncloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -202,6 +217,9 @@ fn test_generic_webdav_path_handling() {
         file_extensions: vec!["txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -241,6 +259,9 @@ fn test_download_path_resolution() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -290,6 +311,9 @@ fn test_with_nextcloud_fixture_data() {
         file_extensions: vec!["jpg".to_string(), "jpeg".to_string(), "png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -47,6 +47,17 @@ async fn create_production_webdav_source(
         auto_sync,
         sync_interval_minutes: 5, // Realistic interval
         server_type: Some("nextcloud".to_string()),
+        max_scan_depth: None,
+        sync_newest_first: false,
+        sync_on_startup: false,
+        propfind_depths: Default::default(),
+        download_verification: Default::default(),
+        max_directory_visits: 5,
+        processing_mode: Default::default(),
+        ocr_enabled: true,
+        conditional_downloads: true,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
 
     let create_source = CreateSource {
@@ -196,12 +207,16 @@ impl ProductionMockWebDAVService {
             Ok(WebDAVDiscoveryResult {
                 files: files.clone(),
                 directories: vec![directory_info],
+                depth_limited_directories: Vec::new(),
+                etag_aliases: Vec::new(),
             })
         } else {
             // Unknown directory
             Ok(WebDAVDiscoveryResult {
                 files: vec![],
                 directories: vec![],
+                depth_limited_directories: Vec::new(),
+                etag_aliases: Vec::new(),
             })
         }
     }
@@ -114,6 +114,8 @@ impl MockWebDAVServiceForSmartSync {
                         metadata: None,
                     }
                 ],
+                depth_limited_directories: Vec::new(),
+                etag_aliases: Vec::new(),
             })
         }
     }
@@ -210,6 +212,8 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    depth_limited_directories: Vec::new(),
+                    etag_aliases: Vec::new(),
                 });
             }
             "changed" => {
@@ -234,6 +238,8 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    depth_limited_directories: Vec::new(),
+                    etag_aliases: Vec::new(),
                 });
             }
             "new_dirs" => {
@@ -258,6 +264,8 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    depth_limited_directories: Vec::new(),
+                    etag_aliases: Vec::new(),
                 });
             }
             "mixed" => {
@@ -298,6 +306,8 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    depth_limited_directories: Vec::new(),
+                    etag_aliases: Vec::new(),
                 });
             }
             _ => {} // Failed case doesn't need setup
@@ -845,6 +845,9 @@ fn create_stress_test_webdav_service(config: &StressTestConfig) -> Result<WebDAV
         timeout_seconds: config.scan_timeout_seconds,
         watch_folders: vec!["/".to_string()],
         file_extensions: vec![],
+        max_scan_depth: None,
+        detect_etag_aliases: false,
+        user_agent: None,
     };
     
     WebDAVService::new(webdav_config)